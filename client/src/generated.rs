@@ -0,0 +1,2663 @@
+// @generated by tests/codegen.rs from the server's OpenAPI document, do not edit.
+// Regenerate with `UPDATE_CLIENT=1 cargo test -p elidune-client --test codegen`.
+
+use reqwest::Method;
+
+use crate::{segment, Client, Result};
+
+impl Client {
+    /// `account_types` operations
+    pub fn account_types(&self) -> AccountTypesApi<'_> {
+        AccountTypesApi(self)
+    }
+
+    /// `admin` operations
+    pub fn admin(&self) -> AdminApi<'_> {
+        AdminApi(self)
+    }
+
+    /// `api_keys` operations
+    pub fn api_keys(&self) -> ApiKeysApi<'_> {
+        ApiKeysApi(self)
+    }
+
+    /// `artifacts` operations
+    pub fn artifacts(&self) -> ArtifactsApi<'_> {
+        ArtifactsApi(self)
+    }
+
+    /// `audit` operations
+    pub fn audit(&self) -> AuditApi<'_> {
+        AuditApi(self)
+    }
+
+    /// `auth` operations
+    pub fn auth(&self) -> AuthApi<'_> {
+        AuthApi(self)
+    }
+
+    /// `authors` operations
+    pub fn authors(&self) -> AuthorsApi<'_> {
+        AuthorsApi(self)
+    }
+
+    /// `backups` operations
+    pub fn backups(&self) -> BackupsApi<'_> {
+        BackupsApi(self)
+    }
+
+    /// `biblios` operations
+    pub fn biblios(&self) -> BibliosApi<'_> {
+        BibliosApi(self)
+    }
+
+    /// `campaigns` operations
+    pub fn campaigns(&self) -> CampaignsApi<'_> {
+        CampaignsApi(self)
+    }
+
+    /// `collections` operations
+    pub fn collections(&self) -> CollectionsApi<'_> {
+        CollectionsApi(self)
+    }
+
+    /// `covers` operations
+    pub fn covers(&self) -> CoversApi<'_> {
+        CoversApi(self)
+    }
+
+    /// `donations` operations
+    pub fn donations(&self) -> DonationsApi<'_> {
+        DonationsApi(self)
+    }
+
+    /// `email_templates` operations
+    pub fn email_templates(&self) -> EmailTemplatesApi<'_> {
+        EmailTemplatesApi(self)
+    }
+
+    /// `eod` operations
+    pub fn eod(&self) -> EodApi<'_> {
+        EodApi(self)
+    }
+
+    /// `equipment` operations
+    pub fn equipment(&self) -> EquipmentApi<'_> {
+        EquipmentApi(self)
+    }
+
+    /// `events` operations
+    pub fn events(&self) -> EventsApi<'_> {
+        EventsApi(self)
+    }
+
+    /// `fines` operations
+    pub fn fines(&self) -> FinesApi<'_> {
+        FinesApi(self)
+    }
+
+    /// `harvest` operations
+    pub fn harvest(&self) -> HarvestApi<'_> {
+        HarvestApi(self)
+    }
+
+    /// `headings` operations
+    pub fn headings(&self) -> HeadingsApi<'_> {
+        HeadingsApi(self)
+    }
+
+    /// `health` operations
+    pub fn health(&self) -> HealthApi<'_> {
+        HealthApi(self)
+    }
+
+    /// `holds` operations
+    pub fn holds(&self) -> HoldsApi<'_> {
+        HoldsApi(self)
+    }
+
+    /// `inventory` operations
+    pub fn inventory(&self) -> InventoryApi<'_> {
+        InventoryApi(self)
+    }
+
+    /// `item_states` operations
+    pub fn item_states(&self) -> ItemStatesApi<'_> {
+        ItemStatesApi(self)
+    }
+
+    /// `item_templates` operations
+    pub fn item_templates(&self) -> ItemTemplatesApi<'_> {
+        ItemTemplatesApi(self)
+    }
+
+    /// `items` operations
+    pub fn items(&self) -> ItemsApi<'_> {
+        ItemsApi(self)
+    }
+
+    /// `kiosks` operations
+    pub fn kiosks(&self) -> KiosksApi<'_> {
+        KiosksApi(self)
+    }
+
+    /// `labels` operations
+    pub fn labels(&self) -> LabelsApi<'_> {
+        LabelsApi(self)
+    }
+
+    /// `library_info` operations
+    pub fn library_info(&self) -> LibraryInfoApi<'_> {
+        LibraryInfoApi(self)
+    }
+
+    /// `loans` operations
+    pub fn loans(&self) -> LoansApi<'_> {
+        LoansApi(self)
+    }
+
+    /// `locations` operations
+    pub fn locations(&self) -> LocationsApi<'_> {
+        LocationsApi(self)
+    }
+
+    /// `maintenance` operations
+    pub fn maintenance(&self) -> MaintenanceApi<'_> {
+        MaintenanceApi(self)
+    }
+
+    /// `media_types` operations
+    pub fn media_types(&self) -> MediaTypesApi<'_> {
+        MediaTypesApi(self)
+    }
+
+    /// `notifications` operations
+    pub fn notifications(&self) -> NotificationsApi<'_> {
+        NotificationsApi(self)
+    }
+
+    /// `opac` operations
+    pub fn opac(&self) -> OpacApi<'_> {
+        OpacApi(self)
+    }
+
+    /// `payments` operations
+    pub fn payments(&self) -> PaymentsApi<'_> {
+        PaymentsApi(self)
+    }
+
+    /// `public_types` operations
+    pub fn public_types(&self) -> PublicTypesApi<'_> {
+        PublicTypesApi(self)
+    }
+
+    /// `purchase_suggestions` operations
+    pub fn purchase_suggestions(&self) -> PurchaseSuggestionsApi<'_> {
+        PurchaseSuggestionsApi(self)
+    }
+
+    /// `reading_programs` operations
+    pub fn reading_programs(&self) -> ReadingProgramsApi<'_> {
+        ReadingProgramsApi(self)
+    }
+
+    /// `reengagement` operations
+    pub fn reengagement(&self) -> ReengagementApi<'_> {
+        ReengagementApi(self)
+    }
+
+    /// `relators` operations
+    pub fn relators(&self) -> RelatorsApi<'_> {
+        RelatorsApi(self)
+    }
+
+    /// `schedules` operations
+    pub fn schedules(&self) -> SchedulesApi<'_> {
+        SchedulesApi(self)
+    }
+
+    /// `series` operations
+    pub fn series(&self) -> SeriesApi<'_> {
+        SeriesApi(self)
+    }
+
+    /// `snapshots` operations
+    pub fn snapshots(&self) -> SnapshotsApi<'_> {
+        SnapshotsApi(self)
+    }
+
+    /// `sources` operations
+    pub fn sources(&self) -> SourcesApi<'_> {
+        SourcesApi(self)
+    }
+
+    /// `sse` operations
+    pub fn sse(&self) -> SseApi<'_> {
+        SseApi(self)
+    }
+
+    /// `staffing` operations
+    pub fn staffing(&self) -> StaffingApi<'_> {
+        StaffingApi(self)
+    }
+
+    /// `stats` operations
+    pub fn stats(&self) -> StatsApi<'_> {
+        StatsApi(self)
+    }
+
+    /// `tasks` operations
+    pub fn tasks(&self) -> TasksApi<'_> {
+        TasksApi(self)
+    }
+
+    /// `users` operations
+    pub fn users(&self) -> UsersApi<'_> {
+        UsersApi(self)
+    }
+
+    /// `vendors` operations
+    pub fn vendors(&self) -> VendorsApi<'_> {
+        VendorsApi(self)
+    }
+
+    /// `visitor_counts` operations
+    pub fn visitor_counts(&self) -> VisitorCountsApi<'_> {
+        VisitorCountsApi(self)
+    }
+
+    /// `warehouse` operations
+    pub fn warehouse(&self) -> WarehouseApi<'_> {
+        WarehouseApi(self)
+    }
+
+    /// `z3950` operations
+    pub fn z3950(&self) -> Z3950Api<'_> {
+        Z3950Api(self)
+    }
+}
+
+/// `account_types` operations
+pub struct AccountTypesApi<'a>(&'a Client);
+
+impl AccountTypesApi<'_> {
+    /// `GET /account-types/{code}`: Get one account type by code (e.g. `librarian`, `admin`).
+    pub async fn get_account_type(&self, code: &str) -> Result<elidune_server::models::account_type::AccountTypeDefinition> {
+        self.0.json(self.0.request(Method::GET, &format!("/account-types/{}", segment(code)))).await
+    }
+
+    /// `GET /account-types`: List all account type definitions (`account_types` table).
+    pub async fn list_account_types(&self) -> Result<Vec<elidune_server::models::account_type::AccountTypeDefinition>> {
+        self.0.json(self.0.request(Method::GET, "/account-types")).await
+    }
+
+    /// `PUT /account-types/{code}`: Update display name and/or rights for an account type (admin only). `code` is immutable.
+    pub async fn update_account_type(&self, code: &str, body: &elidune_server::models::account_type::UpdateAccountTypeDefinition) -> Result<elidune_server::models::account_type::AccountTypeDefinition> {
+        self.0.json(self.0.request(Method::PUT, &format!("/account-types/{}", segment(code))).json(body)).await
+    }
+}
+
+/// `admin` operations
+pub struct AdminApi<'a>(&'a Client);
+
+impl AdminApi<'_> {
+    /// `GET /admin/config`: Get all overridable config sections (admin only)
+    pub async fn get_config(&self) -> Result<elidune_server::api::admin_config::ConfigResponse> {
+        self.0.json(self.0.request(Method::GET, "/admin/config")).await
+    }
+
+    /// `POST /admin/reindex-search`: Trigger a full reindex of the catalog into Meilisearch.
+    pub async fn reindex_search(&self) -> Result<elidune_server::api::admin_config::ReindexSearchResponse> {
+        self.0.json(self.0.request(Method::POST, "/admin/reindex-search")).await
+    }
+
+    /// `DELETE /admin/config/{section}`: Reset a config section to the file default (admin only). Removes DB override.
+    pub async fn reset_config_section(&self, section: &str) -> Result<elidune_server::api::admin_config::ConfigSectionInfo> {
+        self.0.json(self.0.request(Method::DELETE, &format!("/admin/config/{}", segment(section)))).await
+    }
+
+    /// `POST /admin/config/email/test`: Send a test email using the current live SMTP config (admin only)
+    pub async fn test_email(&self, body: &elidune_server::api::admin_config::TestEmailRequest) -> Result<reqwest::Response> {
+        self.0.send(self.0.request(Method::POST, "/admin/config/email/test").json(body)).await
+    }
+
+    /// `PUT /admin/config/{section}`: Update a config section (admin only). Validates, persists to DB, applies immediately.
+    pub async fn update_config_section(&self, section: &str, body: &elidune_server::api::admin_config::UpdateConfigSectionRequest) -> Result<elidune_server::api::admin_config::ConfigSectionInfo> {
+        self.0.json(self.0.request(Method::PUT, &format!("/admin/config/{}", segment(section))).json(body)).await
+    }
+}
+
+/// `api_keys` operations
+pub struct ApiKeysApi<'a>(&'a Client);
+
+impl ApiKeysApi<'_> {
+    /// `POST /settings/api-keys`: Register an API key (the key is returned only in this response)
+    pub async fn create_api_key(&self, body: &elidune_server::models::api_key::CreateApiKey) -> Result<elidune_server::models::api_key::ApiKeyWithToken> {
+        self.0.json(self.0.request(Method::POST, "/settings/api-keys").json(body)).await
+    }
+
+    /// `DELETE /settings/api-keys/{id}`: Delete an API key (and its usage history)
+    pub async fn delete_api_key(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/settings/api-keys/{}", id))).await
+    }
+
+    /// `GET /settings/api-keys/{id}`: Get an API key
+    pub async fn get_api_key(&self, id: i64) -> Result<elidune_server::models::api_key::ApiKey> {
+        self.0.json(self.0.request(Method::GET, &format!("/settings/api-keys/{}", id))).await
+    }
+
+    /// `GET /settings/api-keys/{id}/usage`: Daily requests made with an API key, per endpoint
+    pub async fn get_api_key_usage(&self, id: i64, query: &elidune_server::models::api_key::ApiKeyUsageQuery) -> Result<Vec<elidune_server::models::api_key::ApiKeyUsage>> {
+        self.0.json(self.0.request(Method::GET, &format!("/settings/api-keys/{}/usage", id)).query(query)).await
+    }
+
+    /// `GET /settings/api-keys`: List API keys
+    pub async fn list_api_keys(&self) -> Result<Vec<elidune_server::models::api_key::ApiKey>> {
+        self.0.json(self.0.request(Method::GET, "/settings/api-keys")).await
+    }
+
+    /// `POST /settings/api-keys/{id}/regenerate-token`: Issue a new key (the previous one stops working)
+    pub async fn regenerate_api_key_token(&self, id: i64) -> Result<elidune_server::models::api_key::ApiKeyWithToken> {
+        self.0.json(self.0.request(Method::POST, &format!("/settings/api-keys/{}/regenerate-token", id))).await
+    }
+
+    /// `PUT /settings/api-keys/{id}`: Update an API key (`isActive: false` revokes it)
+    pub async fn update_api_key(&self, id: i64, body: &elidune_server::models::api_key::UpdateApiKey) -> Result<elidune_server::models::api_key::ApiKey> {
+        self.0.json(self.0.request(Method::PUT, &format!("/settings/api-keys/{}", id)).json(body)).await
+    }
+}
+
+/// `artifacts` operations
+pub struct ArtifactsApi<'a>(&'a Client);
+
+impl ArtifactsApi<'_> {
+    /// `DELETE /artifacts/{id}`: Delete an artifact before it expires (owner or administrator)
+    pub async fn delete_artifact(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/artifacts/{}", id))).await
+    }
+
+    /// `GET /artifacts/{id}`: Download an artifact through its signed URL
+    pub async fn download_artifact(&self, id: i64, query: &elidune_server::models::artifact::ArtifactDownloadQuery) -> Result<reqwest::Response> {
+        self.0.send(self.0.request(Method::GET, &format!("/artifacts/{}", id)).query(query)).await
+    }
+
+    /// `GET /artifacts/{id}/link`: New signed download link for an artifact (owner or administrator)
+    pub async fn get_artifact_link(&self, id: i64) -> Result<elidune_server::models::artifact::ArtifactLink> {
+        self.0.json(self.0.request(Method::GET, &format!("/artifacts/{}/link", id))).await
+    }
+
+    /// `GET /artifacts`: The caller's unexpired artifacts with fresh download links (administrators see all)
+    pub async fn list_artifacts(&self) -> Result<Vec<elidune_server::models::artifact::ArtifactLink>> {
+        self.0.json(self.0.request(Method::GET, "/artifacts")).await
+    }
+}
+
+/// `audit` operations
+pub struct AuditApi<'a>(&'a Client);
+
+impl AuditApi<'_> {
+    /// `GET /audit/export`: Export audit log entries as JSON or CSV (admin only)
+    pub async fn export_audit_log(&self, query: &elidune_server::api::audit::AuditExportRequest) -> Result<reqwest::Response> {
+        self.0.send(self.0.request(Method::GET, "/audit/export").query(query)).await
+    }
+
+    /// `GET /audit`: Get paginated audit log entries (admin only)
+    pub async fn get_audit_log(&self, query: &elidune_server::api::audit::AuditQueryRequest) -> Result<elidune_server::models::audit::AuditLogPage> {
+        self.0.json(self.0.request(Method::GET, "/audit").query(query)).await
+    }
+}
+
+/// `auth` operations
+pub struct AuthApi<'a>(&'a Client);
+
+impl AuthApi<'_> {
+    /// `POST /auth/my-dashboard/widgets`: Add a widget after the last one
+    pub async fn add_my_dashboard_widget(&self, body: &elidune_server::models::dashboard::DashboardWidgetWrite) -> Result<elidune_server::models::dashboard::DashboardWidget> {
+        self.0.json(self.0.request(Method::POST, "/auth/my-dashboard/widgets").json(body)).await
+    }
+
+    /// `PUT /auth/pin`: Set or clear your own self-service PIN (confirmed with your password)
+    pub async fn change_own_pin(&self, body: &elidune_server::models::user::ChangeOwnPin) -> Result<()> {
+        self.0.empty(self.0.request(Method::PUT, "/auth/pin").json(body)).await
+    }
+
+    /// `POST /auth/change-password`: Change password using a scoped `change_password_only` token.
+    pub async fn change_password(&self, body: &elidune_server::api::auth::ChangePasswordRequest) -> Result<elidune_server::api::auth::Verify2FAResponse> {
+        self.0.json(self.0.request(Method::POST, "/auth/change-password").json(body)).await
+    }
+
+    /// `DELETE /auth/my-dashboard/widgets/{id}`: Remove one of the caller's widgets
+    pub async fn delete_my_dashboard_widget(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/auth/my-dashboard/widgets/{}", id))).await
+    }
+
+    /// `POST /auth/disable-2fa`: Disable 2FA endpoint
+    pub async fn disable_2fa(&self) -> Result<serde_json::Value> {
+        self.0.json(self.0.request(Method::POST, "/auth/disable-2fa")).await
+    }
+
+    /// `GET /auth/my-dashboard`: Get the caller's dashboard (widgets in display order, empty when never configured)
+    pub async fn get_my_dashboard(&self) -> Result<elidune_server::models::dashboard::Dashboard> {
+        self.0.json(self.0.request(Method::GET, "/auth/my-dashboard")).await
+    }
+
+    /// `GET /auth/my-dashboard/data`: Data of every widget of the caller's dashboard, in one call.
+    pub async fn get_my_dashboard_data(&self) -> Result<elidune_server::models::dashboard::DashboardData> {
+        self.0.json(self.0.request(Method::GET, "/auth/my-dashboard/data")).await
+    }
+
+    /// `POST /auth/login`: Login endpoint - authenticate and get JWT token
+    pub async fn login(&self, body: &elidune_server::api::auth::LoginRequest) -> Result<elidune_server::api::auth::LoginResponse> {
+        self.0.json(self.0.request(Method::POST, "/auth/login").json(body)).await
+    }
+
+    /// `POST /auth/login-barcode`: Patron login with card barcode + PIN (self-service kiosks).
+    pub async fn login_barcode(&self, body: &elidune_server::api::auth::BarcodeLoginRequest) -> Result<elidune_server::api::auth::BarcodeLoginResponse> {
+        self.0.json(self.0.request(Method::POST, "/auth/login-barcode").json(body)).await
+    }
+
+    /// `GET /auth/me`: Get current user profile
+    pub async fn me(&self) -> Result<elidune_server::api::auth::UserInfo> {
+        self.0.json(self.0.request(Method::GET, "/auth/me")).await
+    }
+
+    /// `POST /auth/recovery-codes`: Regenerate recovery codes endpoint (2FA must be enabled; confirmed with the password or a TOTP code)
+    pub async fn regenerate_recovery_codes(&self, body: &elidune_server::models::user::RegenerateRecoveryCodes) -> Result<elidune_server::api::auth::RecoveryCodesResponse> {
+        self.0.json(self.0.request(Method::POST, "/auth/recovery-codes").json(body)).await
+    }
+
+    /// `POST /auth/request-password-reset`: Request password reset email
+    pub async fn request_password_reset(&self, body: &elidune_server::api::auth::RequestPasswordResetRequest) -> Result<elidune_server::api::auth::RequestPasswordResetResponse> {
+        self.0.json(self.0.request(Method::POST, "/auth/request-password-reset").json(body)).await
+    }
+
+    /// `DELETE /auth/my-dashboard`: Remove every widget of the caller's dashboard
+    pub async fn reset_my_dashboard(&self) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, "/auth/my-dashboard")).await
+    }
+
+    /// `POST /auth/reset-password`: Reset password with token
+    pub async fn reset_password(&self, body: &elidune_server::api::auth::ResetPasswordRequest) -> Result<elidune_server::api::auth::ResetPasswordResponse> {
+        self.0.json(self.0.request(Method::POST, "/auth/reset-password").json(body)).await
+    }
+
+    /// `PUT /auth/my-dashboard`: Save the whole dashboard layout: replaces every widget, in the order of the list.
+    pub async fn save_my_dashboard(&self, body: &elidune_server::models::dashboard::SaveDashboard) -> Result<elidune_server::models::dashboard::Dashboard> {
+        self.0.json(self.0.request(Method::PUT, "/auth/my-dashboard").json(body)).await
+    }
+
+    /// `POST /auth/setup-2fa`: Setup 2FA endpoint
+    pub async fn setup_2fa(&self, body: &elidune_server::api::auth::Setup2FARequest) -> Result<elidune_server::api::auth::Setup2FAResponse> {
+        self.0.json(self.0.request(Method::POST, "/auth/setup-2fa").json(body)).await
+    }
+
+    /// `PUT /auth/my-dashboard/widgets/{id}`: Replace the definition of one of the caller's widgets (its position is kept)
+    pub async fn update_my_dashboard_widget(&self, id: i64, body: &elidune_server::models::dashboard::DashboardWidgetWrite) -> Result<elidune_server::models::dashboard::DashboardWidget> {
+        self.0.json(self.0.request(Method::PUT, &format!("/auth/my-dashboard/widgets/{}", id)).json(body)).await
+    }
+
+    /// `PUT /auth/profile`: Update own profile (name, password)
+    pub async fn update_my_profile(&self, body: &elidune_server::models::user::UpdateProfile) -> Result<elidune_server::models::user::User> {
+        self.0.json(self.0.request(Method::PUT, "/auth/profile").json(body)).await
+    }
+
+    /// `POST /auth/verify-2fa`: Verify 2FA code endpoint
+    pub async fn verify_2fa(&self, body: &elidune_server::api::auth::Verify2FARequest) -> Result<elidune_server::api::auth::Verify2FAResponse> {
+        self.0.json(self.0.request(Method::POST, "/auth/verify-2fa").json(body)).await
+    }
+
+    /// `POST /auth/verify-recovery`: Verify recovery code endpoint
+    pub async fn verify_recovery(&self, body: &elidune_server::api::auth::VerifyRecoveryRequest) -> Result<elidune_server::api::auth::Verify2FAResponse> {
+        self.0.json(self.0.request(Method::POST, "/auth/verify-recovery").json(body)).await
+    }
+}
+
+/// `authors` operations
+pub struct AuthorsApi<'a>(&'a Client);
+
+impl AuthorsApi<'_> {
+    /// `GET /authors/{id}`: Get an author
+    pub async fn get_author(&self, id: i64) -> Result<elidune_server::models::author::Author> {
+        self.0.json(self.0.request(Method::GET, &format!("/authors/{}", id))).await
+    }
+
+    /// `POST /authors/normalization`: Normalize the author names, in the background
+    pub async fn normalize_authors(&self, query: &elidune_server::models::author::AuthorNormalizationQuery) -> Result<elidune_server::api::tasks::TaskAcceptedResponse> {
+        self.0.json(self.0.request(Method::POST, "/authors/normalization").query(query)).await
+    }
+
+    /// `GET /authors/normalization`: Preview the normalization of the author names
+    pub async fn preview_author_normalization(&self, query: &elidune_server::models::author::AuthorNormalizationQuery) -> Result<elidune_server::models::author::AuthorNormalizationReport> {
+        self.0.json(self.0.request(Method::GET, "/authors/normalization").query(query)).await
+    }
+
+    /// `PUT /authors/{id}/display-name`: Set the preferred display form of an author ("J. R. R. Tolkien")
+    pub async fn set_author_display_name(&self, id: i64, body: &elidune_server::models::author::UpdateAuthorDisplayName) -> Result<elidune_server::models::author::Author> {
+        self.0.json(self.0.request(Method::PUT, &format!("/authors/{}/display-name", id)).json(body)).await
+    }
+}
+
+/// `backups` operations
+pub struct BackupsApi<'a>(&'a Client);
+
+impl BackupsApi<'_> {
+    /// `POST /admin/backup`: Back up the database now, in the background
+    pub async fn create_backup(&self) -> Result<elidune_server::api::backups::BackupStarted> {
+        self.0.json(self.0.request(Method::POST, "/admin/backup")).await
+    }
+
+    /// `GET /admin/backups`: Backup history (most recent first), with file sizes
+    pub async fn list_backups(&self, query: &elidune_server::models::backup::BackupsQuery) -> Result<Vec<elidune_server::models::backup::Backup>> {
+        self.0.json(self.0.request(Method::GET, "/admin/backups").query(query)).await
+    }
+}
+
+/// `biblios` operations
+pub struct BibliosApi<'a>(&'a Client);
+
+impl BibliosApi<'_> {
+    /// `POST /biblios/{id}/lock`: Take the edit lock of a record, or renew one's own (heartbeat)
+    pub async fn acquire_edit_lock(&self, id: i64) -> Result<elidune_server::models::biblio::EditLock> {
+        self.0.json(self.0.request(Method::POST, &format!("/biblios/{}/lock", id))).await
+    }
+
+    /// `POST /biblios/{id}/marc-diff/apply`: Update a biblio from a cached remote record, taking only the selected tags
+    pub async fn apply_marc_diff(&self, id: i64, body: &elidune_server::api::biblios::ApplyMarcDiffRequest) -> Result<elidune_server::models::biblio::Biblio> {
+        self.0.json(self.0.request(Method::POST, &format!("/biblios/{}/marc-diff/apply", id)).json(body)).await
+    }
+
+    /// `POST /biblios`: Create a new bibliographic record (with ISBN deduplication)
+    pub async fn create_biblio(&self, query: &elidune_server::api::biblios::CreateBiblioQuery, body: &elidune_server::models::biblio::Biblio) -> Result<elidune_server::api::biblios::CreateBiblioResponse> {
+        self.0.json(self.0.request(Method::POST, "/biblios").query(query).json(body)).await
+    }
+
+    /// `POST /biblios/{id}/items`: Create a new physical item for a bibliographic record
+    pub async fn create_item(&self, id: i64, body: &elidune_server::models::item::Item) -> Result<elidune_server::models::item::Item> {
+        self.0.json(self.0.request(Method::POST, &format!("/biblios/{}/items", id)).json(body)).await
+    }
+
+    /// `DELETE /biblios/{id}`: Delete a bibliographic record
+    pub async fn delete_biblio(&self, id: i64, query: &elidune_server::api::biblios::DeleteBiblioParams) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/biblios/{}", id)).query(query)).await
+    }
+
+    /// `GET /biblios/export.csv`: Export catalog as CSV
+    pub async fn export_biblios_csv(&self, query: &elidune_server::models::biblio::BiblioQuery) -> Result<reqwest::Response> {
+        self.0.send(self.0.request(Method::GET, "/biblios/export.csv").query(query)).await
+    }
+
+    /// `GET /biblios/{id}`: Get biblio details by ID
+    pub async fn get_biblio(&self, id: i64, query: &elidune_server::api::biblios::GetBiblioQuery) -> Result<elidune_server::models::biblio::Biblio> {
+        self.0.json(self.0.request(Method::GET, &format!("/biblios/{}", id)).query(query)).await
+    }
+
+    /// `GET /biblios/{id}/lock`: Current edit lock of a record
+    pub async fn get_edit_lock(&self, id: i64) -> Result<serde_json::Value> {
+        self.0.json(self.0.request(Method::GET, &format!("/biblios/{}/lock", id))).await
+    }
+
+    /// `GET /biblios/{id}/marc-diff`: Compare a biblio with a record from the Z39.50 search cache, tag by tag
+    pub async fn get_marc_diff(&self, id: i64, query: &elidune_server::api::biblios::MarcDiffQuery) -> Result<elidune_server::api::biblios::MarcDiffResponse> {
+        self.0.json(self.0.request(Method::GET, &format!("/biblios/{}/marc-diff", id)).query(query)).await
+    }
+
+    /// `POST /biblios/import-marc-batch`: Import cached MARC records from a batch into the catalog.
+    pub async fn import_marc_batch(&self, query: &elidune_server::api::biblios::ImportMarcBatchQuery) -> Result<elidune_server::api::tasks::TaskAcceptedResponse> {
+        self.0.json(self.0.request(Method::POST, "/biblios/import-marc-batch").query(query)).await
+    }
+
+    /// `GET /biblios`: List biblios with search and pagination
+    pub async fn list_biblios(&self, query: &elidune_server::models::biblio::BiblioQuery) -> Result<elidune_server::api::biblios::BiblioSearchPage> {
+        self.0.json(self.0.request(Method::GET, "/biblios").query(query)).await
+    }
+
+    /// `GET /biblios/{id}/items`: List physical items for a bibliographic record
+    pub async fn list_items(&self, id: i64) -> Result<Vec<elidune_server::models::item::Item>> {
+        self.0.json(self.0.request(Method::GET, &format!("/biblios/{}/items", id))).await
+    }
+
+    /// `GET /biblios/list-marc-batches`: List all MARC batches currently cached in Redis.
+    pub async fn list_marc_batches(&self) -> Result<Vec<elidune_server::services::marc::MarcBatchInfo>> {
+        self.0.json(self.0.request(Method::GET, "/biblios/list-marc-batches")).await
+    }
+
+    /// `POST /biblios/load-marc`: Upload a UNIMARC file and return parsed biblios with linked items (995/952).
+    pub async fn load_marc(&self) -> Result<elidune_server::services::marc::EnqueueResult> {
+        self.0.json(self.0.request(Method::POST, "/biblios/load-marc")).await
+    }
+
+    /// `GET /biblios/marc-batch/{batch_id}`: Reload a cached MARC batch from Redis by its batch ID.
+    pub async fn load_marc_batch(&self, batch_id: i64) -> Result<elidune_server::services::marc::EnqueueResult> {
+        self.0.json(self.0.request(Method::GET, &format!("/biblios/marc-batch/{}", batch_id))).await
+    }
+
+    /// `DELETE /biblios/{id}/lock`: Release the edit lock of a record
+    pub async fn release_edit_lock(&self, id: i64, query: &elidune_server::api::biblios::ReleaseEditLockQuery) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/biblios/{}/lock", id)).query(query)).await
+    }
+
+    /// `PUT /biblios/{id}`: Update an existing bibliographic record
+    pub async fn update_biblio(&self, id: i64, query: &elidune_server::api::biblios::UpdateBiblioQuery, body: &elidune_server::models::biblio::Biblio) -> Result<elidune_server::models::biblio::Biblio> {
+        self.0.json(self.0.request(Method::PUT, &format!("/biblios/{}", id)).query(query).json(body)).await
+    }
+}
+
+/// `campaigns` operations
+pub struct CampaignsApi<'a>(&'a Client);
+
+impl CampaignsApi<'_> {
+    /// `POST /campaigns`: Create a draft campaign
+    pub async fn create_campaign(&self, body: &elidune_server::models::campaign::CreateCampaign) -> Result<elidune_server::models::campaign::Campaign> {
+        self.0.json(self.0.request(Method::POST, "/campaigns").json(body)).await
+    }
+
+    /// `DELETE /campaigns/{id}`: Delete a draft or sent campaign with its delivery report
+    pub async fn delete_campaign(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/campaigns/{}", id))).await
+    }
+
+    /// `GET /campaigns/{id}`: Get a campaign with its delivery counters
+    pub async fn get_campaign(&self, id: i64) -> Result<elidune_server::models::campaign::Campaign> {
+        self.0.json(self.0.request(Method::GET, &format!("/campaigns/{}", id))).await
+    }
+
+    /// `GET /campaigns/{id}/recipients`: Queued recipients of a campaign with their delivery state
+    pub async fn list_campaign_recipients(&self, id: i64, query: &elidune_server::models::campaign::CampaignRecipientsQuery) -> Result<Vec<elidune_server::models::campaign::CampaignRecipient>> {
+        self.0.json(self.0.request(Method::GET, &format!("/campaigns/{}/recipients", id)).query(query)).await
+    }
+
+    /// `GET /campaigns`: List campaigns with their delivery counters (most recent first)
+    pub async fn list_campaigns(&self) -> Result<Vec<elidune_server::models::campaign::Campaign>> {
+        self.0.json(self.0.request(Method::GET, "/campaigns")).await
+    }
+
+    /// `POST /campaigns/preview`: Count the addresses a segment currently resolves to
+    pub async fn preview_campaign_segment(&self, body: &elidune_server::models::campaign::CampaignSegment) -> Result<elidune_server::models::campaign::CampaignSegmentPreview> {
+        self.0.json(self.0.request(Method::POST, "/campaigns/preview").json(body)).await
+    }
+
+    /// `POST /campaigns/{id}/bounces`: Record bounces reported by the mail provider (bounce notifications or provider export)
+    pub async fn record_campaign_bounces(&self, id: i64, body: &elidune_server::models::campaign::CampaignBounces) -> Result<elidune_server::models::campaign::CampaignBounceReport> {
+        self.0.json(self.0.request(Method::POST, &format!("/campaigns/{}/bounces", id)).json(body)).await
+    }
+
+    /// `POST /campaigns/{id}/send`: Send a draft campaign in the background, or resume a send interrupted by a restart.
+    pub async fn send_campaign(&self, id: i64) -> Result<elidune_server::api::campaigns::CampaignSendStarted> {
+        self.0.json(self.0.request(Method::POST, &format!("/campaigns/{}/send", id))).await
+    }
+
+    /// `PUT /campaigns/{id}`: Edit a draft campaign
+    pub async fn update_campaign(&self, id: i64, body: &elidune_server::models::campaign::UpdateCampaign) -> Result<elidune_server::models::campaign::Campaign> {
+        self.0.json(self.0.request(Method::PUT, &format!("/campaigns/{}", id)).json(body)).await
+    }
+}
+
+/// `collections` operations
+pub struct CollectionsApi<'a>(&'a Client);
+
+impl CollectionsApi<'_> {
+    /// `POST /collections`: Create a new collection.
+    pub async fn create_collection(&self, body: &elidune_server::models::biblio::CreateCollection) -> Result<elidune_server::models::biblio::Collection> {
+        self.0.json(self.0.request(Method::POST, "/collections").json(body)).await
+    }
+
+    /// `DELETE /collections/{id}`: Delete a collection (only if no biblios are linked).
+    pub async fn delete_collection(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/collections/{}", id))).await
+    }
+
+    /// `GET /collections/{id}`: Get a collection by ID.
+    pub async fn get_collection(&self, id: i64) -> Result<elidune_server::models::biblio::Collection> {
+        self.0.json(self.0.request(Method::GET, &format!("/collections/{}", id))).await
+    }
+
+    /// `GET /collections/{id}/biblios`: List biblios in a collection (ordered by volume number).
+    pub async fn get_collection_biblios(&self, id: i64) -> Result<Vec<elidune_server::models::biblio::BiblioShort>> {
+        self.0.json(self.0.request(Method::GET, &format!("/collections/{}/biblios", id))).await
+    }
+
+    /// `GET /collections`: List collections (paginated, optional name filter).
+    pub async fn list_collections(&self, query: &elidune_server::models::biblio::CollectionQuery) -> Result<elidune_server::api::collections::PaginatedCollections> {
+        self.0.json(self.0.request(Method::GET, "/collections").query(query)).await
+    }
+
+    /// `PUT /collections/{id}`: Update a collection.
+    pub async fn update_collection(&self, id: i64, body: &elidune_server::models::biblio::UpdateCollection) -> Result<elidune_server::models::biblio::Collection> {
+        self.0.json(self.0.request(Method::PUT, &format!("/collections/{}", id)).json(body)).await
+    }
+}
+
+/// `covers` operations
+pub struct CoversApi<'a>(&'a Client);
+
+impl CoversApi<'_> {
+    /// `GET /covers/isbn/{isbn}`: Proxy cover image from Open Library by ISBN
+    pub async fn get_cover_by_isbn(&self, isbn: &str, query: &elidune_server::api::covers::CoverQuery) -> Result<reqwest::Response> {
+        self.0.send(self.0.request(Method::GET, &format!("/covers/isbn/{}", segment(isbn))).query(query)).await
+    }
+}
+
+/// `donations` operations
+pub struct DonationsApi<'a>(&'a Client);
+
+impl DonationsApi<'_> {
+    /// `POST /donations/{id}/items`: Add a book to a donation
+    pub async fn add_donation_item(&self, id: i64, body: &elidune_server::models::donation::CreateDonationItem) -> Result<elidune_server::models::donation::DonationItem> {
+        self.0.json(self.0.request(Method::POST, &format!("/donations/{}/items", id)).json(body)).await
+    }
+
+    /// `POST /donations`: Record a donation and its books
+    pub async fn create_donation(&self, body: &elidune_server::models::donation::CreateDonation) -> Result<elidune_server::models::donation::Donation> {
+        self.0.json(self.0.request(Method::POST, "/donations").json(body)).await
+    }
+
+    /// `DELETE /donations/{id}`: Delete a donation (refused once a book has been added to the catalog)
+    pub async fn delete_donation(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/donations/{}", id))).await
+    }
+
+    /// `DELETE /donations/{id}/items/{line_id}`: Remove a book from a donation (not allowed once it was added to the catalog)
+    pub async fn delete_donation_item(&self, id: i64, line_id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/donations/{}/items/{}", id, line_id))).await
+    }
+
+    /// `GET /donations/{id}`: Get a donation with its books
+    pub async fn get_donation(&self, id: i64) -> Result<elidune_server::models::donation::Donation> {
+        self.0.json(self.0.request(Method::GET, &format!("/donations/{}", id))).await
+    }
+
+    /// `GET /donations/report/donors`: Annual donors report: donations, books per triage outcome and estimated value per donor
+    pub async fn get_donors_report(&self, query: &elidune_server::models::donation::DonorsReportQuery) -> Result<elidune_server::models::donation::DonorsReport> {
+        self.0.json(self.0.request(Method::GET, "/donations/report/donors").query(query)).await
+    }
+
+    /// `GET /donations`: List donations with their books
+    pub async fn list_donations(&self, query: &elidune_server::models::donation::DonationQuery) -> Result<Vec<elidune_server::models::donation::Donation>> {
+        self.0.json(self.0.request(Method::GET, "/donations").query(query)).await
+    }
+
+    /// `POST /donations/{id}/items/{line_id}/triage`: Triage a donated book: add to catalog, book sale, recycle (or back to pending)
+    pub async fn triage_donation_item(&self, id: i64, line_id: i64, body: &elidune_server::models::donation::TriageDonationItem) -> Result<elidune_server::models::donation::DonationItem> {
+        self.0.json(self.0.request(Method::POST, &format!("/donations/{}/items/{}/triage", id, line_id)).json(body)).await
+    }
+
+    /// `PUT /donations/{id}`: Update donor details, date, estimated value or notes
+    pub async fn update_donation(&self, id: i64, body: &elidune_server::models::donation::UpdateDonation) -> Result<elidune_server::models::donation::Donation> {
+        self.0.json(self.0.request(Method::PUT, &format!("/donations/{}", id)).json(body)).await
+    }
+}
+
+/// `email_templates` operations
+pub struct EmailTemplatesApi<'a>(&'a Client);
+
+impl EmailTemplatesApi<'_> {
+    /// `GET /settings/email-templates/{template_id}/{language}`: Get a single email template by id and language.
+    pub async fn get_email_template(&self, template_id: &str, language: &str) -> Result<elidune_server::api::email_templates::EmailTemplate> {
+        self.0.json(self.0.request(Method::GET, &format!("/settings/email-templates/{}/{}", segment(template_id), segment(language)))).await
+    }
+
+    /// `GET /settings/email-templates`: List all email templates (all (templateId, language) pairs).
+    pub async fn list_email_templates(&self) -> Result<Vec<elidune_server::api::email_templates::EmailTemplate>> {
+        self.0.json(self.0.request(Method::GET, "/settings/email-templates")).await
+    }
+
+    /// `PUT /settings/email-templates/{template_id}/{language}`: Insert or update a template (`UPSERT` on `(templateId, language)`).
+    pub async fn update_email_template(&self, template_id: &str, language: &str, body: &elidune_server::api::email_templates::UpdateEmailTemplateRequest) -> Result<elidune_server::api::email_templates::EmailTemplate> {
+        self.0.json(self.0.request(Method::PUT, &format!("/settings/email-templates/{}/{}", segment(template_id), segment(language))).json(body)).await
+    }
+}
+
+/// `eod` operations
+pub struct EodApi<'a>(&'a Client);
+
+impl EodApi<'_> {
+    /// `GET /admin/eod-runs/{id}`: Get an end-of-day run with the statistics snapshot of the day it closed
+    pub async fn get_eod_run(&self, id: i64) -> Result<elidune_server::models::eod::EodRunDetails> {
+        self.0.json(self.0.request(Method::GET, &format!("/admin/eod-runs/{}", id))).await
+    }
+
+    /// `GET /admin/eod-runs`: End-of-day run log (most recent first)
+    pub async fn list_eod_runs(&self, query: &elidune_server::models::eod::EodRunsQuery) -> Result<Vec<elidune_server::models::eod::EodRun>> {
+        self.0.json(self.0.request(Method::GET, "/admin/eod-runs").query(query)).await
+    }
+
+    /// `POST /admin/eod-runs`: Close the day now, in the background
+    pub async fn run_eod(&self) -> Result<elidune_server::api::eod::EodRunStarted> {
+        self.0.json(self.0.request(Method::POST, "/admin/eod-runs")).await
+    }
+}
+
+/// `equipment` operations
+pub struct EquipmentApi<'a>(&'a Client);
+
+impl EquipmentApi<'_> {
+    /// `POST /equipment`: Create equipment
+    pub async fn create_equipment(&self, body: &elidune_server::models::equipment::CreateEquipment) -> Result<elidune_server::models::equipment::Equipment> {
+        self.0.json(self.0.request(Method::POST, "/equipment").json(body)).await
+    }
+
+    /// `DELETE /equipment/{id}`: Delete equipment
+    pub async fn delete_equipment(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/equipment/{}", id))).await
+    }
+
+    /// `GET /equipment/{id}`: Get equipment by ID
+    pub async fn get_equipment(&self, id: i64) -> Result<elidune_server::models::equipment::Equipment> {
+        self.0.json(self.0.request(Method::GET, &format!("/equipment/{}", id))).await
+    }
+
+    /// `GET /equipment`: List all equipment
+    pub async fn list_equipment(&self) -> Result<Vec<elidune_server::models::equipment::Equipment>> {
+        self.0.json(self.0.request(Method::GET, "/equipment")).await
+    }
+
+    /// `PUT /equipment/{id}`: Update equipment
+    pub async fn update_equipment(&self, id: i64, body: &elidune_server::models::equipment::UpdateEquipment) -> Result<elidune_server::models::equipment::Equipment> {
+        self.0.json(self.0.request(Method::PUT, &format!("/equipment/{}", id)).json(body)).await
+    }
+}
+
+/// `events` operations
+pub struct EventsApi<'a>(&'a Client);
+
+impl EventsApi<'_> {
+    /// `POST /events/{id}/cancel`: Cancel a draft or published event and email registered attendees (unless `notify` is `false`)
+    pub async fn cancel_event(&self, id: i64, body: &elidune_server::models::event::CancelEvent) -> Result<elidune_server::services::events::CancelEventResponse> {
+        self.0.json(self.0.request(Method::POST, &format!("/events/{}/cancel", id)).json(body)).await
+    }
+
+    /// `POST /events`: Create an event
+    pub async fn create_event(&self, body: &elidune_server::models::event::CreateEvent) -> Result<elidune_server::models::event::Event> {
+        self.0.json(self.0.request(Method::POST, "/events").json(body)).await
+    }
+
+    /// `DELETE /events/{id}`: Delete an event
+    pub async fn delete_event(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/events/{}", id))).await
+    }
+
+    /// `GET /events/feed.atom`: Atom feed of library events (public)
+    pub async fn events_feed(&self) -> Result<String> {
+        self.0.text(self.0.request(Method::GET, "/events/feed.atom")).await
+    }
+
+    /// `GET /events/{id}`: Get event by ID (includes `attachmentDataBase64` when an attachment exists).
+    pub async fn get_event(&self, id: i64) -> Result<elidune_server::models::event::Event> {
+        self.0.json(self.0.request(Method::GET, &format!("/events/{}", id))).await
+    }
+
+    /// `GET /events/conflicts`: Planning conflicts between two days: events on closure days and overlapping events in the same room
+    pub async fn list_event_conflicts(&self, query: &elidune_server::models::event::EventConflictQuery) -> Result<Vec<elidune_server::models::event::EventConflict>> {
+        self.0.json(self.0.request(Method::GET, "/events/conflicts").query(query)).await
+    }
+
+    /// `GET /events/{id}/registrations`: List attendees registered to an event
+    pub async fn list_event_registrations(&self, id: i64) -> Result<Vec<elidune_server::models::event::EventRegistration>> {
+        self.0.json(self.0.request(Method::GET, &format!("/events/{}/registrations", id))).await
+    }
+
+    /// `GET /events`: List events with filters and pagination. Without events rights (OPAC), only published
+    pub async fn list_events(&self, query: &elidune_server::models::event::EventQuery) -> Result<elidune_server::api::events::EventsListResponse> {
+        self.0.json(self.0.request(Method::GET, "/events").query(query)).await
+    }
+
+    /// `POST /events/{id}/publish`: Publish a draft event (makes it visible on the OPAC and in the Atom feed)
+    pub async fn publish_event(&self, id: i64) -> Result<elidune_server::models::event::Event> {
+        self.0.json(self.0.request(Method::POST, &format!("/events/{}/publish", id))).await
+    }
+
+    /// `POST /events/{id}/registrations`: Register to a published event (the caller, or `userId` with events write rights)
+    pub async fn register_to_event(&self, id: i64, body: &elidune_server::models::event::RegisterEvent) -> Result<()> {
+        self.0.empty(self.0.request(Method::POST, &format!("/events/{}/registrations", id)).json(body)).await
+    }
+
+    /// `POST /events/{id}/send-announcement`: Send an announcement email for an event to all users whose `users.public_type` id
+    pub async fn send_event_announcement(&self, id: i64, body: &elidune_server::services::events::SendAnnouncementRequest) -> Result<elidune_server::services::events::AnnouncementReport> {
+        self.0.json(self.0.request(Method::POST, &format!("/events/{}/send-announcement", id)).json(body)).await
+    }
+
+    /// `DELETE /events/{id}/registrations/{user_id}`: Remove a registration (one's own, or anyone's with events write rights)
+    pub async fn unregister_from_event(&self, id: i64, user_id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/events/{}/registrations/{}", id, user_id))).await
+    }
+
+    /// `PUT /events/{id}`: Update an event (optional `attachment` / `removeAttachment` same as create semantics)
+    pub async fn update_event(&self, id: i64, body: &elidune_server::models::event::UpdateEvent) -> Result<elidune_server::models::event::Event> {
+        self.0.json(self.0.request(Method::PUT, &format!("/events/{}", id)).json(body)).await
+    }
+}
+
+/// `fines` operations
+pub struct FinesApi<'a>(&'a Client);
+
+impl FinesApi<'_> {
+    /// `POST /fines/amnesty`: Amnesty campaign: waive every unpaid fine issued between two days (admin only)
+    pub async fn fine_amnesty(&self, body: &elidune_server::models::fine::FineAmnestyRequest) -> Result<elidune_server::models::fine::FineAmnestyReport> {
+        self.0.json(self.0.request(Method::POST, "/fines/amnesty").json(body)).await
+    }
+
+    /// `GET /fines/rules`: List fine rules
+    pub async fn list_fine_rules(&self) -> Result<Vec<elidune_server::models::fine::FineRule>> {
+        self.0.json(self.0.request(Method::GET, "/fines/rules")).await
+    }
+
+    /// `GET /users/{id}/fines`: List all fines for a user
+    pub async fn list_user_fines(&self, id: i64) -> Result<elidune_server::api::fines::UnpaidFinesSummary> {
+        self.0.json(self.0.request(Method::GET, &format!("/users/{}/fines", id))).await
+    }
+
+    /// `POST /fines/{id}/pay`: Pay a fine (recorded in the cash register)
+    pub async fn pay_fine(&self, id: i64, body: &elidune_server::models::fine::PayFineRequest) -> Result<elidune_server::models::fine::Fine> {
+        self.0.json(self.0.request(Method::POST, &format!("/fines/{}/pay", id)).json(body)).await
+    }
+
+    /// `PUT /fines/rules`: Upsert a fine rule (admin only)
+    pub async fn upsert_fine_rule(&self, body: &elidune_server::api::fines::UpsertFineRuleRequest) -> Result<elidune_server::models::fine::FineRule> {
+        self.0.json(self.0.request(Method::PUT, "/fines/rules").json(body)).await
+    }
+
+    /// `POST /fines/{id}/waive`: Waive a fine
+    pub async fn waive_fine(&self, id: i64, body: &elidune_server::models::fine::WaiveFineRequest) -> Result<elidune_server::models::fine::Fine> {
+        self.0.json(self.0.request(Method::POST, &format!("/fines/{}/waive", id)).json(body)).await
+    }
+
+    /// `GET /fines/waivers/summary`: Waived fine totals by reason (staff, manual waivers, amnesty campaigns and weeks)
+    pub async fn waived_fines_summary(&self, query: &elidune_server::models::fine::WaivedFinesQuery) -> Result<elidune_server::models::fine::WaivedFinesSummary> {
+        self.0.json(self.0.request(Method::GET, "/fines/waivers/summary").query(query)).await
+    }
+}
+
+/// `harvest` operations
+pub struct HarvestApi<'a>(&'a Client);
+
+impl HarvestApi<'_> {
+    /// `POST /harvest/sources`: Create a harvest source
+    pub async fn create_harvest_source(&self, body: &elidune_server::models::harvest::CreateHarvestSource) -> Result<elidune_server::models::harvest::HarvestSource> {
+        self.0.json(self.0.request(Method::POST, "/harvest/sources").json(body)).await
+    }
+
+    /// `DELETE /harvest/sources/{id}`: Delete a harvest source and its run history (harvested records stay in the catalog)
+    pub async fn delete_harvest_source(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/harvest/sources/{}", id))).await
+    }
+
+    /// `GET /harvest/runs/{id}`: Get a harvest run report
+    pub async fn get_harvest_run(&self, id: i64) -> Result<elidune_server::models::harvest::HarvestRun> {
+        self.0.json(self.0.request(Method::GET, &format!("/harvest/runs/{}", id))).await
+    }
+
+    /// `GET /harvest/sources/{id}`: Get a harvest source
+    pub async fn get_harvest_source(&self, id: i64) -> Result<elidune_server::models::harvest::HarvestSource> {
+        self.0.json(self.0.request(Method::GET, &format!("/harvest/sources/{}", id))).await
+    }
+
+    /// `GET /harvest/sources/{id}/runs`: Run history of a harvest source (most recent first)
+    pub async fn list_harvest_runs(&self, id: i64, query: &elidune_server::models::harvest::HarvestRunsQuery) -> Result<Vec<elidune_server::models::harvest::HarvestRun>> {
+        self.0.json(self.0.request(Method::GET, &format!("/harvest/sources/{}/runs", id)).query(query)).await
+    }
+
+    /// `GET /harvest/sources`: List harvest sources
+    pub async fn list_harvest_sources(&self) -> Result<Vec<elidune_server::models::harvest::HarvestSource>> {
+        self.0.json(self.0.request(Method::GET, "/harvest/sources")).await
+    }
+
+    /// `POST /harvest/sources/{id}/run`: Run a harvest now, in the background
+    pub async fn run_harvest_source(&self, id: i64) -> Result<elidune_server::api::harvest::HarvestRunStarted> {
+        self.0.json(self.0.request(Method::POST, &format!("/harvest/sources/{}/run", id))).await
+    }
+
+    /// `PUT /harvest/sources/{id}`: Update a harvest source (an empty `password` removes it, `resetCursor` re-applies all files)
+    pub async fn update_harvest_source(&self, id: i64, body: &elidune_server::models::harvest::UpdateHarvestSource) -> Result<elidune_server::models::harvest::HarvestSource> {
+        self.0.json(self.0.request(Method::PUT, &format!("/harvest/sources/{}", id)).json(body)).await
+    }
+}
+
+/// `headings` operations
+pub struct HeadingsApi<'a>(&'a Client);
+
+impl HeadingsApi<'_> {
+    /// `POST /settings/genres`: Create a genre (`sortOrder` defaults to after the last one)
+    pub async fn create_genre(&self, body: &elidune_server::models::heading::CreateGenreHeading) -> Result<elidune_server::models::heading::GenreHeading> {
+        self.0.json(self.0.request(Method::POST, "/settings/genres").json(body)).await
+    }
+
+    /// `POST /settings/subjects`: Create a subject heading, optionally under a broader term
+    pub async fn create_subject(&self, body: &elidune_server::models::heading::CreateSubjectHeading) -> Result<elidune_server::models::heading::SubjectHeading> {
+        self.0.json(self.0.request(Method::POST, "/settings/subjects").json(body)).await
+    }
+
+    /// `DELETE /settings/genres/{id}`: Delete a genre (fails while records use it; merge it instead)
+    pub async fn delete_genre(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/settings/genres/{}", id))).await
+    }
+
+    /// `DELETE /settings/subjects/{id}`: Delete a subject heading (fails while records use it or it has narrower terms)
+    pub async fn delete_subject(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/settings/subjects/{}", id))).await
+    }
+
+    /// `GET /biblios/{id}/genres`: Genres of a bibliographic record
+    pub async fn get_biblio_genres(&self, id: i64) -> Result<Vec<elidune_server::models::heading::GenreHeading>> {
+        self.0.json(self.0.request(Method::GET, &format!("/biblios/{}/genres", id))).await
+    }
+
+    /// `GET /biblios/{id}/subjects`: Subject headings of a bibliographic record
+    pub async fn get_biblio_subjects(&self, id: i64) -> Result<Vec<elidune_server::models::heading::SubjectHeading>> {
+        self.0.json(self.0.request(Method::GET, &format!("/biblios/{}/subjects", id))).await
+    }
+
+    /// `GET /settings/genres/{id}`: Get a genre
+    pub async fn get_genre(&self, id: i64) -> Result<elidune_server::models::heading::GenreHeading> {
+        self.0.json(self.0.request(Method::GET, &format!("/settings/genres/{}", id))).await
+    }
+
+    /// `GET /settings/subjects/{id}`: Get a subject heading
+    pub async fn get_subject(&self, id: i64) -> Result<elidune_server::models::heading::SubjectHeading> {
+        self.0.json(self.0.request(Method::GET, &format!("/settings/subjects/{}", id))).await
+    }
+
+    /// `GET /settings/genres`: List genres (by sort order)
+    pub async fn list_genres(&self) -> Result<Vec<elidune_server::models::heading::GenreHeading>> {
+        self.0.json(self.0.request(Method::GET, "/settings/genres")).await
+    }
+
+    /// `GET /settings/subjects`: List subject headings (paginated, by heading)
+    pub async fn list_subjects(&self, query: &elidune_server::models::heading::SubjectQuery) -> Result<elidune_server::api::headings::PaginatedSubjects> {
+        self.0.json(self.0.request(Method::GET, "/settings/subjects").query(query)).await
+    }
+
+    /// `POST /settings/genres/{id}/merge`: Merge duplicate genres into this one: their records are re-mapped, then they are deleted
+    pub async fn merge_genres(&self, id: i64, body: &elidune_server::models::heading::MergeHeadings) -> Result<elidune_server::models::heading::MergeHeadingsReport> {
+        self.0.json(self.0.request(Method::POST, &format!("/settings/genres/{}/merge", id)).json(body)).await
+    }
+
+    /// `POST /settings/subjects/{id}/merge`: Merge duplicate subject headings into this one: their records are re-mapped, their narrower
+    pub async fn merge_subjects(&self, id: i64, body: &elidune_server::models::heading::MergeHeadings) -> Result<elidune_server::models::heading::MergeHeadingsReport> {
+        self.0.json(self.0.request(Method::POST, &format!("/settings/subjects/{}/merge", id)).json(body)).await
+    }
+
+    /// `PUT /biblios/{id}/genres`: Replace the genres of a bibliographic record
+    pub async fn set_biblio_genres(&self, id: i64, body: &elidune_server::models::heading::SetBiblioHeadings) -> Result<Vec<elidune_server::models::heading::GenreHeading>> {
+        self.0.json(self.0.request(Method::PUT, &format!("/biblios/{}/genres", id)).json(body)).await
+    }
+
+    /// `PUT /biblios/{id}/subjects`: Replace the subject headings of a bibliographic record
+    pub async fn set_biblio_subjects(&self, id: i64, body: &elidune_server::models::heading::SetBiblioHeadings) -> Result<Vec<elidune_server::models::heading::SubjectHeading>> {
+        self.0.json(self.0.request(Method::PUT, &format!("/biblios/{}/subjects", id)).json(body)).await
+    }
+
+    /// `PUT /settings/genres/{id}`: Update a genre
+    pub async fn update_genre(&self, id: i64, body: &elidune_server::models::heading::UpdateGenreHeading) -> Result<elidune_server::models::heading::GenreHeading> {
+        self.0.json(self.0.request(Method::PUT, &format!("/settings/genres/{}", id)).json(body)).await
+    }
+
+    /// `PUT /settings/subjects/{id}`: Update a subject heading (rename, move under another broader term, or detach with `topLevel`)
+    pub async fn update_subject(&self, id: i64, body: &elidune_server::models::heading::UpdateSubjectHeading) -> Result<elidune_server::models::heading::SubjectHeading> {
+        self.0.json(self.0.request(Method::PUT, &format!("/settings/subjects/{}", id)).json(body)).await
+    }
+}
+
+/// `health` operations
+pub struct HealthApi<'a>(&'a Client);
+
+impl HealthApi<'_> {
+    /// `GET /health`: Health check — process is up; includes DB/setup snapshot when the database is reachable.
+    pub async fn health_check(&self) -> Result<elidune_server::api::health::HealthResponse> {
+        self.0.json(self.0.request(Method::GET, "/health")).await
+    }
+
+    /// `POST /first_setup`: Single-shot bootstrap: create admin user, library row, optional email override.
+    pub async fn post_first_setup(&self, body: &elidune_server::api::first_setup::FirstSetupRequest) -> Result<elidune_server::api::first_setup::FirstSetupResponse> {
+        self.0.json(self.0.request(Method::POST, "/first_setup").json(body)).await
+    }
+
+    /// `GET /ready`: Readiness — database must be reachable; HTTP 503 when not.
+    pub async fn readiness_check(&self) -> Result<elidune_server::api::health::HealthResponse> {
+        self.0.json(self.0.request(Method::GET, "/ready")).await
+    }
+
+    /// `GET /version`: Server version endpoint
+    pub async fn version(&self) -> Result<elidune_server::api::health::VersionResponse> {
+        self.0.json(self.0.request(Method::GET, "/version")).await
+    }
+}
+
+/// `holds` operations
+pub struct HoldsApi<'a>(&'a Client);
+
+impl HoldsApi<'_> {
+    /// `POST /holds/{id}/locker`: Place a ready hold's copy in a pickup locker
+    pub async fn assign_hold_locker(&self, id: i64, body: &elidune_server::models::hold::AssignHoldLocker) -> Result<elidune_server::models::hold::Hold> {
+        self.0.json(self.0.request(Method::POST, &format!("/holds/{}/locker", id)).json(body)).await
+    }
+
+    /// `DELETE /holds/{id}`
+    pub async fn cancel_hold(&self, id: i64) -> Result<elidune_server::models::hold::Hold> {
+        self.0.json(self.0.request(Method::DELETE, &format!("/holds/{}", id))).await
+    }
+
+    /// `POST /holds`
+    pub async fn create_hold(&self, body: &elidune_server::api::holds::CreateHoldRequest) -> Result<elidune_server::models::hold::Hold> {
+        self.0.json(self.0.request(Method::POST, "/holds").json(body)).await
+    }
+
+    /// `GET /holds/expired`: Copies to take off the hold shelf and reshelve: holds whose pickup deadline passed since
+    pub async fn get_expired_holds(&self, query: &elidune_server::models::hold::ExpiredHoldsQuery) -> Result<Vec<elidune_server::models::hold::HoldShelfGroup>> {
+        self.0.json(self.0.request(Method::GET, "/holds/expired").query(query)).await
+    }
+
+    /// `GET /holds/pull-list`: Daily pull list: copies on the shelves to set aside for the first hold of their queue,
+    pub async fn get_pull_list(&self, query: &elidune_server::models::hold::HoldPullListQuery) -> Result<Vec<elidune_server::models::hold::HoldShelfGroup>> {
+        self.0.json(self.0.request(Method::GET, "/holds/pull-list").query(query)).await
+    }
+
+    /// `GET /holds`: Paginated list of holds: staff (`holds_rights` read/write) sees all rows; `o` sees only their holds.
+    pub async fn list_holds(&self, query: &elidune_server::api::holds::ListHoldsQuery) -> Result<elidune_server::api::biblios::PaginatedHolds> {
+        self.0.json(self.0.request(Method::GET, "/holds").query(query)).await
+    }
+
+    /// `GET /items/{id}/holds`
+    pub async fn list_holds_for_item(&self, id: i64) -> Result<Vec<elidune_server::models::hold::Hold>> {
+        self.0.json(self.0.request(Method::GET, &format!("/items/{}/holds", id))).await
+    }
+
+    /// `GET /users/{id}/holds`
+    pub async fn list_holds_for_user(&self, id: i64) -> Result<Vec<elidune_server::models::hold::HoldDetails>> {
+        self.0.json(self.0.request(Method::GET, &format!("/users/{}/holds", id))).await
+    }
+
+    /// `POST /lockers/webhook`: Locker vendor webhook (compartment opened, pickup window lapsed)
+    pub async fn locker_webhook(&self, body: &elidune_server::models::hold::LockerWebhookEvent) -> Result<elidune_server::models::hold::LockerEventOutcome> {
+        self.0.json(self.0.request(Method::POST, "/lockers/webhook").json(body)).await
+    }
+}
+
+/// `inventory` operations
+pub struct InventoryApi<'a>(&'a Client);
+
+impl InventoryApi<'_> {
+    /// `POST /inventory/sessions/{id}/scans/batch`: Batch scan barcodes (open session only, max `INVENTORY_BATCH_MAX_BARCODES`).
+    pub async fn batch_scan(&self, id: i64, body: &elidune_server::models::inventory::BatchScanBarcodes) -> Result<elidune_server::api::tasks::TaskAcceptedResponse> {
+        self.0.json(self.0.request(Method::POST, &format!("/inventory/sessions/{}/scans/batch", id)).json(body)).await
+    }
+
+    /// `POST /inventory/sessions/{id}/close`: Close a session (no more scans accepted)
+    pub async fn close_session(&self, id: i64) -> Result<elidune_server::models::inventory::InventorySession> {
+        self.0.json(self.0.request(Method::POST, &format!("/inventory/sessions/{}/close", id))).await
+    }
+
+    /// `POST /inventory/sessions`: Create a new inventory session
+    pub async fn create_session(&self, body: &elidune_server::models::inventory::CreateInventorySession) -> Result<elidune_server::models::inventory::InventorySession> {
+        self.0.json(self.0.request(Method::POST, "/inventory/sessions").json(body)).await
+    }
+
+    /// `GET /inventory/sessions/{id}/report`: Get discrepancy report for a session
+    pub async fn get_report(&self, id: i64) -> Result<elidune_server::models::inventory::InventoryReport> {
+        self.0.json(self.0.request(Method::GET, &format!("/inventory/sessions/{}/report", id))).await
+    }
+
+    /// `GET /inventory/sessions/{id}`: Get session details
+    pub async fn get_session(&self, id: i64) -> Result<elidune_server::models::inventory::InventorySession> {
+        self.0.json(self.0.request(Method::GET, &format!("/inventory/sessions/{}", id))).await
+    }
+
+    /// `GET /inventory/sessions/{id}/missing`: Missing items in session scope (never appeared as `itemId` on a scan).
+    pub async fn list_missing(&self, id: i64, query: &elidune_server::api::inventory::ListInventoryPageQuery) -> Result<elidune_server::api::biblios::PaginatedInventoryMissing> {
+        self.0.json(self.0.request(Method::GET, &format!("/inventory/sessions/{}/missing", id)).query(query)).await
+    }
+
+    /// `GET /inventory/sessions/{id}/scans`: Get scans for a session (paginated, oldest first).
+    pub async fn list_scans(&self, id: i64, query: &elidune_server::api::inventory::ListInventoryPageQuery) -> Result<elidune_server::api::biblios::PaginatedInventoryScans> {
+        self.0.json(self.0.request(Method::GET, &format!("/inventory/sessions/{}/scans", id)).query(query)).await
+    }
+
+    /// `GET /inventory/sessions`: List inventory sessions (paginated).
+    pub async fn list_sessions(&self, query: &elidune_server::api::inventory::ListInventorySessionsQuery) -> Result<elidune_server::api::biblios::PaginatedInventorySessions> {
+        self.0.json(self.0.request(Method::GET, "/inventory/sessions").query(query)).await
+    }
+
+    /// `POST /inventory/sessions/{id}/scan`: Scan a barcode in an open session
+    pub async fn scan_barcode(&self, id: i64, body: &elidune_server::models::inventory::ScanBarcode) -> Result<elidune_server::models::inventory::InventoryScan> {
+        self.0.json(self.0.request(Method::POST, &format!("/inventory/sessions/{}/scan", id)).json(body)).await
+    }
+}
+
+/// `item_states` operations
+pub struct ItemStatesApi<'a>(&'a Client);
+
+impl ItemStatesApi<'_> {
+    /// `POST /settings/item-states`: Create an item state
+    pub async fn create_item_state(&self, body: &elidune_server::models::item_state::CreateItemState) -> Result<elidune_server::models::item_state::ItemState> {
+        self.0.json(self.0.request(Method::POST, "/settings/item-states").json(body)).await
+    }
+
+    /// `DELETE /settings/item-states/{code}`: Delete an item state (fails while copies are in this state)
+    pub async fn delete_item_state(&self, code: i32) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/settings/item-states/{}", code))).await
+    }
+
+    /// `GET /settings/item-states/{code}`: Get an item state by code
+    pub async fn get_item_state(&self, code: i32) -> Result<elidune_server::models::item_state::ItemState> {
+        self.0.json(self.0.request(Method::GET, &format!("/settings/item-states/{}", code))).await
+    }
+
+    /// `GET /settings/item-states`: List item states (values allowed in an item's `circulationStatus`)
+    pub async fn list_item_states(&self) -> Result<Vec<elidune_server::models::item_state::ItemState>> {
+        self.0.json(self.0.request(Method::GET, "/settings/item-states")).await
+    }
+
+    /// `PUT /settings/item-states/{code}`: Update an item state (an empty `color` clears it)
+    pub async fn update_item_state(&self, code: i32, body: &elidune_server::models::item_state::UpdateItemState) -> Result<elidune_server::models::item_state::ItemState> {
+        self.0.json(self.0.request(Method::PUT, &format!("/settings/item-states/{}", code)).json(body)).await
+    }
+}
+
+/// `item_templates` operations
+pub struct ItemTemplatesApi<'a>(&'a Client);
+
+impl ItemTemplatesApi<'_> {
+    /// `POST /settings/item-templates`: Create a cataloging template
+    pub async fn create_item_template(&self, body: &elidune_server::models::item_template::CreateItemTemplate) -> Result<elidune_server::models::item_template::ItemTemplate> {
+        self.0.json(self.0.request(Method::POST, "/settings/item-templates").json(body)).await
+    }
+
+    /// `DELETE /settings/item-templates/{code}`: Delete a cataloging template
+    pub async fn delete_item_template(&self, code: &str) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/settings/item-templates/{}", segment(code)))).await
+    }
+
+    /// `GET /settings/item-templates/{code}`: Get a cataloging template by code
+    pub async fn get_item_template(&self, code: &str) -> Result<elidune_server::models::item_template::ItemTemplate> {
+        self.0.json(self.0.request(Method::GET, &format!("/settings/item-templates/{}", segment(code)))).await
+    }
+
+    /// `GET /settings/item-templates`: List cataloging templates (used with `POST /items?template=`)
+    pub async fn list_item_templates(&self) -> Result<Vec<elidune_server::models::item_template::ItemTemplate>> {
+        self.0.json(self.0.request(Method::GET, "/settings/item-templates")).await
+    }
+
+    /// `PUT /settings/item-templates/{code}`: Replace a cataloging template (omitted fields are cleared)
+    pub async fn update_item_template(&self, code: &str, body: &elidune_server::models::item_template::ItemTemplateFields) -> Result<elidune_server::models::item_template::ItemTemplate> {
+        self.0.json(self.0.request(Method::PUT, &format!("/settings/item-templates/{}", segment(code))).json(body)).await
+    }
+}
+
+/// `items` operations
+pub struct ItemsApi<'a>(&'a Client);
+
+impl ItemsApi<'_> {
+    /// `GET /items/{id}/access`: Follow the digital resource link of a copy (e-book, streaming…).
+    pub async fn access_item(&self, id: i64) -> Result<reqwest::Response> {
+        self.0.send(self.0.request(Method::GET, &format!("/items/{}/access", id))).await
+    }
+
+    /// `POST /deposits/{id}/close`: End a deposit: copies on the shelf are archived, copies on loan are listed for follow-up
+    pub async fn close_deposit(&self, id: i64) -> Result<elidune_server::models::deposit::DepositCloseReport> {
+        self.0.json(self.0.request(Method::POST, &format!("/deposits/{}/close", id))).await
+    }
+
+    /// `POST /bundles`: Create a bundle from copy barcodes
+    pub async fn create_bundle(&self, body: &elidune_server::models::bundle::CreateItemBundle) -> Result<elidune_server::models::bundle::ItemBundle> {
+        self.0.json(self.0.request(Method::POST, "/bundles").json(body)).await
+    }
+
+    /// `POST /deposits`: Register a deposit batch with its owner and return deadline
+    pub async fn create_deposit(&self, body: &elidune_server::models::deposit::CreateDeposit) -> Result<elidune_server::models::deposit::Deposit> {
+        self.0.json(self.0.request(Method::POST, "/deposits").json(body)).await
+    }
+
+    /// `DELETE /bundles/{id}`: Delete a bundle; its copies circulate on their own again
+    pub async fn delete_bundle(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/bundles/{}", id))).await
+    }
+
+    /// `DELETE /deposits/{id}`: Delete a deposit registered by mistake (only while it has no copies)
+    pub async fn delete_deposit(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/deposits/{}", id))).await
+    }
+
+    /// `DELETE /items/{id}`: Delete a physical item (soft delete unless `force` when borrowed).
+    pub async fn delete_item(&self, id: i64, query: &elidune_server::api::items::DeleteItemParams) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/items/{}", id)).query(query)).await
+    }
+
+    /// `GET /items/export`: Export the whole catalog, streamed page by page.
+    pub async fn export_items(&self, query: &elidune_server::api::items::ItemExportQuery) -> Result<reqwest::Response> {
+        self.0.send(self.0.request(Method::GET, "/items/export").query(query)).await
+    }
+
+    /// `GET /items/barcode/{barcode}`: Get the bibliographic record for a physical copy identified by barcode.
+    pub async fn get_biblio_by_barcode(&self, barcode: &str) -> Result<elidune_server::models::biblio::Biblio> {
+        self.0.json(self.0.request(Method::GET, &format!("/items/barcode/{}", segment(barcode)))).await
+    }
+
+    /// `GET /items/{id}`: Get the bibliographic record for a physical copy.
+    pub async fn get_biblio_by_item(&self, id: i64) -> Result<elidune_server::models::biblio::Biblio> {
+        self.0.json(self.0.request(Method::GET, &format!("/items/{}", id))).await
+    }
+
+    /// `GET /bundles/{id}`: Get a bundle with its parts
+    pub async fn get_bundle(&self, id: i64) -> Result<elidune_server::models::bundle::ItemBundle> {
+        self.0.json(self.0.request(Method::GET, &format!("/bundles/{}", id))).await
+    }
+
+    /// `GET /deposits/{id}`: Get a deposit
+    pub async fn get_deposit(&self, id: i64) -> Result<elidune_server::models::deposit::Deposit> {
+        self.0.json(self.0.request(Method::GET, &format!("/deposits/{}", id))).await
+    }
+
+    /// `GET /deposits/{id}/manifest`: Return manifest of a deposit: every copy still in the catalog, including copies on loan
+    pub async fn get_deposit_manifest(&self, id: i64) -> Result<reqwest::Response> {
+        self.0.send(self.0.request(Method::GET, &format!("/deposits/{}/manifest", id))).await
+    }
+
+    /// `POST /deposits/{id}/import-marc-batch`: Import a cached MARC batch as copies of the deposit.
+    pub async fn import_deposit_batch(&self, id: i64, query: &elidune_server::models::deposit::ImportDepositQuery) -> Result<elidune_server::api::tasks::TaskAcceptedResponse> {
+        self.0.json(self.0.request(Method::POST, &format!("/deposits/{}/import-marc-batch", id)).query(query)).await
+    }
+
+    /// `GET /items/{id}/citation`: Citation of the bibliographic record of a physical copy.
+    pub async fn item_citation(&self, id: i64, query: &elidune_server::models::citation::CitationQuery) -> Result<elidune_server::models::citation::Citation> {
+        self.0.json(self.0.request(Method::GET, &format!("/items/{}/citation", id)).query(query)).await
+    }
+
+    /// `GET /bundles`: List item bundles
+    pub async fn list_bundles(&self) -> Result<Vec<elidune_server::models::bundle::ItemBundle>> {
+        self.0.json(self.0.request(Method::GET, "/bundles")).await
+    }
+
+    /// `GET /deposits`: List deposits (active first)
+    pub async fn list_deposits(&self, query: &elidune_server::models::deposit::DepositQuery) -> Result<Vec<elidune_server::models::deposit::Deposit>> {
+        self.0.json(self.0.request(Method::GET, "/deposits").query(query)).await
+    }
+
+    /// `GET /items/new/feed.atom`: Atom feed of new acquisitions (public)
+    pub async fn new_items_feed(&self) -> Result<String> {
+        self.0.text(self.0.request(Method::GET, "/items/new/feed.atom")).await
+    }
+
+    /// `GET /items/labels`: Print spine labels (PDF) for the copies of a shelving location or created in a date range.
+    pub async fn print_spine_labels(&self, query: &elidune_server::models::item::SpineLabelQuery) -> Result<reqwest::Response> {
+        self.0.send(self.0.request(Method::GET, "/items/labels").query(query)).await
+    }
+
+    /// `POST /items`: Quick cataloging: create a record with its copies, optionally from a template.
+    pub async fn quick_create_item(&self, query: &elidune_server::models::item_template::QuickCreateQuery, body: &elidune_server::models::biblio::Biblio) -> Result<elidune_server::api::biblios::CreateBiblioResponse> {
+        self.0.json(self.0.request(Method::POST, "/items").query(query).json(body)).await
+    }
+
+    /// `POST /items/recalculate-call-numbers`: Recalculate call numbers in bulk (prefix rewrites, Dewey truncation, audience prefixes).
+    pub async fn recalculate_call_numbers(&self, body: &elidune_server::models::item::RecalculateCallNumbers) -> Result<elidune_server::models::item::CallNumberRecalculationReport> {
+        self.0.json(self.0.request(Method::POST, "/items/recalculate-call-numbers").json(body)).await
+    }
+
+    /// `POST /items/{id}/receive`: Receive a copy in transit at its home place (`transitPlace` is cleared).
+    pub async fn receive_item(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::POST, &format!("/items/{}/receive", id))).await
+    }
+
+    /// `POST /items/{id}/routing`: Answer the routing prompt of a return (`routing.promptOverride`).
+    pub async fn route_item(&self, id: i64, body: &elidune_server::api::items::RouteItemRequest) -> Result<elidune_server::models::loan::ReturnRouting> {
+        self.0.json(self.0.request(Method::POST, &format!("/items/{}/routing", id)).json(body)).await
+    }
+
+    /// `PUT /bundles/{id}`: Update a bundle (parts can only change while no part is on loan)
+    pub async fn update_bundle(&self, id: i64, body: &elidune_server::models::bundle::UpdateItemBundle) -> Result<elidune_server::models::bundle::ItemBundle> {
+        self.0.json(self.0.request(Method::PUT, &format!("/bundles/{}", id)).json(body)).await
+    }
+
+    /// `PUT /deposits/{id}`: Update an active deposit (e.g. extend the return deadline)
+    pub async fn update_deposit(&self, id: i64, body: &elidune_server::models::deposit::UpdateDeposit) -> Result<elidune_server::models::deposit::Deposit> {
+        self.0.json(self.0.request(Method::PUT, &format!("/deposits/{}", id)).json(body)).await
+    }
+
+    /// `PUT /items/{id}`: Update a physical item. The path id is authoritative.
+    pub async fn update_item(&self, id: i64, body: &elidune_server::models::item::Item) -> Result<elidune_server::models::item::Item> {
+        self.0.json(self.0.request(Method::PUT, &format!("/items/{}", id)).json(body)).await
+    }
+}
+
+/// `kiosks` operations
+pub struct KiosksApi<'a>(&'a Client);
+
+impl KiosksApi<'_> {
+    /// `POST /settings/kiosks`: Register a kiosk (the token is returned only in this response)
+    pub async fn create_kiosk(&self, body: &elidune_server::models::kiosk::CreateKiosk) -> Result<elidune_server::models::kiosk::KioskWithToken> {
+        self.0.json(self.0.request(Method::POST, "/settings/kiosks").json(body)).await
+    }
+
+    /// `DELETE /settings/kiosks/{id}`: Delete a kiosk
+    pub async fn delete_kiosk(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/settings/kiosks/{}", id))).await
+    }
+
+    /// `GET /settings/kiosks/{id}`: Get a kiosk
+    pub async fn get_kiosk(&self, id: i64) -> Result<elidune_server::models::kiosk::Kiosk> {
+        self.0.json(self.0.request(Method::GET, &format!("/settings/kiosks/{}", id))).await
+    }
+
+    /// `GET /kiosk/session`: Identity of the calling terminal (lets a kiosk check its token at startup)
+    pub async fn kiosk_session(&self) -> Result<elidune_server::models::kiosk::KioskSession> {
+        self.0.json(self.0.request(Method::GET, "/kiosk/session")).await
+    }
+
+    /// `GET /settings/kiosks`: List kiosks
+    pub async fn list_kiosks(&self) -> Result<Vec<elidune_server::models::kiosk::Kiosk>> {
+        self.0.json(self.0.request(Method::GET, "/settings/kiosks")).await
+    }
+
+    /// `POST /settings/kiosks/{id}/regenerate-token`: Issue a new token for a kiosk (the previous token stops working)
+    pub async fn regenerate_kiosk_token(&self, id: i64) -> Result<elidune_server::models::kiosk::KioskWithToken> {
+        self.0.json(self.0.request(Method::POST, &format!("/settings/kiosks/{}/regenerate-token", id))).await
+    }
+
+    /// `PUT /settings/kiosks/{id}`: Update a kiosk (`isActive: false` revokes its token)
+    pub async fn update_kiosk(&self, id: i64, body: &elidune_server::models::kiosk::UpdateKiosk) -> Result<elidune_server::models::kiosk::Kiosk> {
+        self.0.json(self.0.request(Method::PUT, &format!("/settings/kiosks/{}", id)).json(body)).await
+    }
+}
+
+/// `labels` operations
+pub struct LabelsApi<'a>(&'a Client);
+
+impl LabelsApi<'_> {
+    /// `DELETE /labels/{kind}/{code}/{lang}`: Delete a translation (the code then falls back to its raw value)
+    pub async fn delete_label(&self, kind: &str, code: &str, lang: &str) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/labels/{}/{}/{}", segment(kind), segment(code), segment(lang)))).await
+    }
+
+    /// `GET /labels`: Translated labels of media types, audiences, patron categories, genres and account types
+    pub async fn list_labels(&self, query: &elidune_server::models::label::LabelsQuery) -> Result<elidune_server::models::label::LabelSet> {
+        self.0.json(self.0.request(Method::GET, "/labels").query(query)).await
+    }
+
+    /// `PUT /labels/{kind}/{code}/{lang}`: Create or replace the translation of a code
+    pub async fn upsert_label(&self, kind: &str, code: &str, lang: &str, body: &elidune_server::models::label::UpsertLabel) -> Result<elidune_server::models::label::Label> {
+        self.0.json(self.0.request(Method::PUT, &format!("/labels/{}/{}/{}", segment(kind), segment(code), segment(lang))).json(body)).await
+    }
+}
+
+/// `library_info` operations
+pub struct LibraryInfoApi<'a>(&'a Client);
+
+impl LibraryInfoApi<'_> {
+    /// `GET /library-info`: Get library information (public)
+    pub async fn get_library_info(&self) -> Result<elidune_server::api::library_info::LibraryInfo> {
+        self.0.json(self.0.request(Method::GET, "/library-info")).await
+    }
+
+    /// `PUT /library-info`: Update library information (requires write settings permission)
+    pub async fn update_library_info(&self, body: &elidune_server::api::library_info::UpdateLibraryInfoRequest) -> Result<elidune_server::api::library_info::LibraryInfo> {
+        self.0.json(self.0.request(Method::PUT, "/library-info").json(body)).await
+    }
+}
+
+/// `loans` operations
+pub struct LoansApi<'a>(&'a Client);
+
+impl LoansApi<'_> {
+    /// `POST /loans/batch-create`: Batch create loans — check out multiple items for one patron at once
+    pub async fn batch_create_loans(&self, body: &elidune_server::api::batch::BatchCreateLoansRequest) -> Result<elidune_server::api::batch::BatchCreateLoansResponse> {
+        self.0.json(self.0.request(Method::POST, "/loans/batch-create").json(body)).await
+    }
+
+    /// `POST /loans/batch-return`: Batch return by barcodes — for scanner return stations
+    pub async fn batch_return(&self, body: &elidune_server::api::batch::BatchReturnRequest) -> Result<elidune_server::api::batch::BatchReturnResponse> {
+        self.0.json(self.0.request(Method::POST, "/loans/batch-return").json(body)).await
+    }
+
+    /// `POST /loans`: Create a new loan (borrow an item)
+    pub async fn create_loan(&self, body: &elidune_server::api::loans::CreateLoanRequest) -> Result<elidune_server::api::loans::LoanResponse> {
+        self.0.json(self.0.request(Method::POST, "/loans").json(body)).await
+    }
+
+    /// `POST /loan-batches`: Check out copies to a group account with one shared due date (all or nothing)
+    pub async fn create_loan_batch(&self, body: &elidune_server::models::loan_batch::CreateLoanBatch) -> Result<elidune_server::models::loan_batch::LoanBatch> {
+        self.0.json(self.0.request(Method::POST, "/loan-batches").json(body)).await
+    }
+
+    /// `GET /users/{id}/loans/export`: Download all loans for a user as one MARC file (`Content-Disposition: attachment`).
+    pub async fn export_user_loans_marc(&self, id: i64, query: &elidune_server::api::loans::ExportUserLoansMarcQuery) -> Result<reqwest::Response> {
+        self.0.send(self.0.request(Method::GET, &format!("/users/{}/loans/export", id)).query(query)).await
+    }
+
+    /// `POST /loan-batches/{id}/extend`: Move the shared due date of every copy still out
+    pub async fn extend_loan_batch(&self, id: i64, body: &elidune_server::models::loan_batch::ExtendLoanBatch) -> Result<elidune_server::models::loan_batch::LoanBatch> {
+        self.0.json(self.0.request(Method::POST, &format!("/loan-batches/{}/extend", id)).json(body)).await
+    }
+
+    /// `GET /users/{id}/checkout-blocks`: Reasons a patron cannot borrow right now (empty when checkout is allowed)
+    pub async fn get_checkout_blocks(&self, id: i64) -> Result<Vec<elidune_server::models::loan::CheckoutBlock>> {
+        self.0.json(self.0.request(Method::GET, &format!("/users/{}/checkout-blocks", id))).await
+    }
+
+    /// `GET /loan-batches/{id}`: Get a group loan batch with its copies
+    pub async fn get_loan_batch(&self, id: i64) -> Result<elidune_server::models::loan_batch::LoanBatch> {
+        self.0.json(self.0.request(Method::GET, &format!("/loan-batches/{}", id))).await
+    }
+
+    /// `GET /loans/{id}/renewability`: Whether a loan can be renewed now, with the reason codes when it cannot
+    pub async fn get_loan_renewability(&self, id: i64) -> Result<elidune_server::models::loan::LoanRenewability> {
+        self.0.json(self.0.request(Method::GET, &format!("/loans/{}/renewability", id))).await
+    }
+
+    /// `GET /loans/settings`: Get global loan rules per media type (`loans_settings`).
+    pub async fn get_loan_settings(&self) -> Result<Vec<elidune_server::api::loans::LoanSettings>> {
+        self.0.json(self.0.request(Method::GET, "/loans/settings")).await
+    }
+
+    /// `GET /loans/overdue`: Get all overdue loans (admin dashboard)
+    pub async fn get_overdue_loans(&self, query: &elidune_server::api::loans::OverdueLoansQuery) -> Result<elidune_server::services::reminders::OverdueLoansPage> {
+        self.0.json(self.0.request(Method::GET, "/loans/overdue").query(query)).await
+    }
+
+    /// `GET /users/{id}/loans`: Get loans for a specific user (paginated).
+    pub async fn get_user_loans(&self, id: i64, query: &elidune_server::api::loans::GetUserLoansQuery) -> Result<elidune_server::api::biblios::PaginatedLoans> {
+        self.0.json(self.0.request(Method::GET, &format!("/users/{}/loans", id)).query(query)).await
+    }
+
+    /// `GET /loan-batches`: List group loan batches
+    pub async fn list_loan_batches(&self, query: &elidune_server::models::loan_batch::LoanBatchQuery) -> Result<Vec<elidune_server::models::loan_batch::LoanBatch>> {
+        self.0.json(self.0.request(Method::GET, "/loan-batches").query(query)).await
+    }
+
+    /// `POST /loans/{id}/renew`: Renew a loan
+    pub async fn renew_loan(&self, id: i64) -> Result<elidune_server::api::loans::LoanResponse> {
+        self.0.json(self.0.request(Method::POST, &format!("/loans/{}/renew", id))).await
+    }
+
+    /// `POST /loans/items/{item_id}/renew`: Renew a loan by item identification (barcode or call number)
+    pub async fn renew_loan_by_item(&self, item_id: &str) -> Result<elidune_server::api::loans::LoanResponse> {
+        self.0.json(self.0.request(Method::POST, &format!("/loans/items/{}/renew", segment(item_id)))).await
+    }
+
+    /// `POST /bundles/{id}/return`: Return the scanned parts of a bundle and report the parts still missing
+    pub async fn return_bundle(&self, id: i64, body: &elidune_server::models::bundle::ReturnItemBundle) -> Result<elidune_server::models::bundle::BundleReturnReport> {
+        self.0.json(self.0.request(Method::POST, &format!("/bundles/{}/return", id)).json(body)).await
+    }
+
+    /// `POST /loans/{id}/return`: Return a borrowed item
+    pub async fn return_loan(&self, id: i64, query: &elidune_server::api::loans::ReturnQuery) -> Result<elidune_server::api::loans::ReturnResponse> {
+        self.0.json(self.0.request(Method::POST, &format!("/loans/{}/return", id)).query(query)).await
+    }
+
+    /// `POST /loan-batches/{id}/return`: Return every copy of the batch still out
+    pub async fn return_loan_batch(&self, id: i64) -> Result<elidune_server::models::loan_batch::LoanBatchReturnReport> {
+        self.0.json(self.0.request(Method::POST, &format!("/loan-batches/{}/return", id))).await
+    }
+
+    /// `POST /loans/items/{item_id}/return`: Return a borrowed item by item identification (barcode or call number)
+    pub async fn return_loan_by_item(&self, item_id: &str, query: &elidune_server::api::loans::ReturnQuery) -> Result<elidune_server::api::loans::ReturnResponse> {
+        self.0.json(self.0.request(Method::POST, &format!("/loans/items/{}/return", segment(item_id))).query(query)).await
+    }
+
+    /// `POST /loans/send-due-soon-reminders`: Trigger the due-soon digest (admin only): one email per patron listing the loans due in
+    pub async fn send_due_soon_reminders(&self, query: &elidune_server::api::loans::SendRemindersQuery) -> Result<elidune_server::services::reminders::ReminderReport> {
+        self.0.json(self.0.request(Method::POST, "/loans/send-due-soon-reminders").query(query)).await
+    }
+
+    /// `POST /loans/send-overdue-reminders`: Trigger overdue reminder emails (admin only)
+    pub async fn send_overdue_reminders(&self, query: &elidune_server::api::loans::SendRemindersQuery) -> Result<elidune_server::services::reminders::ReminderReport> {
+        self.0.json(self.0.request(Method::POST, "/loans/send-overdue-reminders").query(query)).await
+    }
+
+    /// `PUT /loans/settings`: Update global loan rules per media type.
+    pub async fn update_loan_settings(&self, body: &elidune_server::api::loans::UpdateLoanSettingsRequest) -> Result<Vec<elidune_server::api::loans::LoanSettings>> {
+        self.0.json(self.0.request(Method::PUT, "/loans/settings").json(body)).await
+    }
+}
+
+/// `locations` operations
+pub struct LocationsApi<'a>(&'a Client);
+
+impl LocationsApi<'_> {
+    /// `POST /settings/locations/mapping`: Give each group of copies its location (and the place of the location's site)
+    pub async fn apply_location_mapping(&self, body: &elidune_server::models::location::ApplyLocationMappings) -> Result<elidune_server::models::location::LocationMappingReport> {
+        self.0.json(self.0.request(Method::POST, "/settings/locations/mapping").json(body)).await
+    }
+
+    /// `POST /settings/locations`: Create a site (one per place), a room under a site or a shelf range under a room
+    pub async fn create_location(&self, body: &elidune_server::models::location::CreateLocation) -> Result<elidune_server::models::location::ShelvingLocation> {
+        self.0.json(self.0.request(Method::POST, "/settings/locations").json(body)).await
+    }
+
+    /// `DELETE /settings/locations/{id}`: Delete a shelving location without child locations nor copies
+    pub async fn delete_location(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/settings/locations/{}", id))).await
+    }
+
+    /// `GET /settings/locations/{id}`: Get a shelving location by id
+    pub async fn get_location(&self, id: i64) -> Result<elidune_server::models::location::ShelvingLocation> {
+        self.0.json(self.0.request(Method::GET, &format!("/settings/locations/{}", id))).await
+    }
+
+    /// `GET /settings/locations/mapping`: Mapping assistant: copies without a location, by place and call number prefix
+    pub async fn get_location_mapping(&self) -> Result<Vec<elidune_server::models::location::LocationMappingRow>> {
+        self.0.json(self.0.request(Method::GET, "/settings/locations/mapping")).await
+    }
+
+    /// `GET /settings/locations/tree`: Shelving map as a tree: sites with their rooms, rooms with their shelf ranges
+    pub async fn get_location_tree(&self, query: &elidune_server::models::location::LocationQuery) -> Result<Vec<elidune_server::models::location::LocationNode>> {
+        self.0.json(self.0.request(Method::GET, "/settings/locations/tree").query(query)).await
+    }
+
+    /// `GET /settings/locations`: List shelving locations in map order (each site followed by its rooms and shelf ranges)
+    pub async fn list_locations(&self, query: &elidune_server::models::location::LocationQuery) -> Result<Vec<elidune_server::models::location::ShelvingLocation>> {
+        self.0.json(self.0.request(Method::GET, "/settings/locations").query(query)).await
+    }
+
+    /// `PUT /settings/locations/{id}`: Rename, recode or reorder a shelving location
+    pub async fn update_location(&self, id: i64, body: &elidune_server::models::location::UpdateLocation) -> Result<elidune_server::models::location::ShelvingLocation> {
+        self.0.json(self.0.request(Method::PUT, &format!("/settings/locations/{}", id)).json(body)).await
+    }
+}
+
+/// `maintenance` operations
+pub struct MaintenanceApi<'a>(&'a Client);
+
+impl MaintenanceApi<'_> {
+    /// `GET /maintenance/database/dump`: Download a full PostgreSQL plain-SQL dump (admin only). The file is produced with `pg_dump` then streamed to the client.
+    pub async fn dump_database(&self) -> Result<reqwest::Response> {
+        self.0.send(self.0.request(Method::GET, "/maintenance/database/dump")).await
+    }
+
+    /// `POST /maintenance/database/restore`: Apply a plain SQL script with `psql` (admin only). Use the same format as [`dump_database`] (plain `pg_dump`).
+    pub async fn restore_database(&self) -> Result<()> {
+        self.0.empty(self.0.request(Method::POST, "/maintenance/database/restore")).await
+    }
+
+    /// `POST /maintenance`: Run one or more maintenance actions (admin only).
+    pub async fn run_maintenance(&self, body: &elidune_server::api::maintenance::MaintenanceRequest) -> Result<elidune_server::api::tasks::TaskAcceptedResponse> {
+        self.0.json(self.0.request(Method::POST, "/maintenance").json(body)).await
+    }
+}
+
+/// `media_types` operations
+pub struct MediaTypesApi<'a>(&'a Client);
+
+impl MediaTypesApi<'_> {
+    /// `POST /settings/media-types`: Create a media type
+    pub async fn create_media_type(&self, body: &elidune_server::models::media_type::CreateMediaType) -> Result<elidune_server::models::media_type::MediaTypeDefinition> {
+        self.0.json(self.0.request(Method::POST, "/settings/media-types").json(body)).await
+    }
+
+    /// `DELETE /settings/media-types/{code}`: Delete a media type (built-in codes and codes used by biblios cannot be deleted)
+    pub async fn delete_media_type(&self, code: &str) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/settings/media-types/{}", segment(code)))).await
+    }
+
+    /// `GET /settings/media-types/{code}`: Get a media type by code
+    pub async fn get_media_type(&self, code: &str) -> Result<elidune_server::models::media_type::MediaTypeDefinition> {
+        self.0.json(self.0.request(Method::GET, &format!("/settings/media-types/{}", segment(code)))).await
+    }
+
+    /// `GET /settings/media-types`: List media types (values allowed in a biblio's `mediaType`)
+    pub async fn list_media_types(&self) -> Result<Vec<elidune_server::models::media_type::MediaTypeDefinition>> {
+        self.0.json(self.0.request(Method::GET, "/settings/media-types")).await
+    }
+
+    /// `PUT /settings/media-types/{code}`: Update a media type (label, icon, default loan rules, MARC leader codes)
+    pub async fn update_media_type(&self, code: &str, body: &elidune_server::models::media_type::UpdateMediaType) -> Result<elidune_server::models::media_type::MediaTypeDefinition> {
+        self.0.json(self.0.request(Method::PUT, &format!("/settings/media-types/{}", segment(code))).json(body)).await
+    }
+}
+
+/// `notifications` operations
+pub struct NotificationsApi<'a>(&'a Client);
+
+impl NotificationsApi<'_> {
+    /// `GET /notifications/unread-count`: Number of unread notifications of the caller (inbox badge)
+    pub async fn get_unread_count(&self) -> Result<elidune_server::models::notification::UnreadNotificationCount> {
+        self.0.json(self.0.request(Method::GET, "/notifications/unread-count")).await
+    }
+
+    /// `GET /notifications`: The caller's notifications, newest first.
+    pub async fn list_notifications(&self, query: &elidune_server::models::notification::NotificationQuery) -> Result<elidune_server::models::notification::NotificationsPage> {
+        self.0.json(self.0.request(Method::GET, "/notifications").query(query)).await
+    }
+
+    /// `POST /notifications/read-all`: Mark every unread notification of the caller as read
+    pub async fn mark_all_notifications_read(&self) -> Result<elidune_server::models::notification::NotificationsMarkedRead> {
+        self.0.json(self.0.request(Method::POST, "/notifications/read-all")).await
+    }
+
+    /// `POST /notifications/{id}/read`: Mark one of the caller's notifications as read
+    pub async fn mark_notification_read(&self, id: i64) -> Result<elidune_server::models::notification::Notification> {
+        self.0.json(self.0.request(Method::POST, &format!("/notifications/{}/read", id))).await
+    }
+}
+
+/// `opac` operations
+pub struct OpacApi<'a>(&'a Client);
+
+impl OpacApi<'_> {
+    /// `POST /admin/sitemap`: Regenerate the sitemap now, in the background
+    pub async fn generate_sitemap(&self) -> Result<elidune_server::api::tasks::TaskAcceptedResponse> {
+        self.0.json(self.0.request(Method::POST, "/admin/sitemap")).await
+    }
+
+    /// `GET /sitemap.xml`: Sitemap of the OPAC record pages (public)
+    pub async fn get_sitemap(&self) -> Result<String> {
+        self.0.text(self.0.request(Method::GET, "/sitemap.xml")).await
+    }
+
+    /// `GET /sitemaps/{file}`: Shard of a sharded sitemap (public)
+    pub async fn get_sitemap_shard(&self, file: &str) -> Result<String> {
+        self.0.text(self.0.request(Method::GET, &format!("/sitemaps/{}", file))).await
+    }
+
+    /// `GET /opac/biblios/{id}/availability`: Get availability for a bibliographic record (how many physical copies are available)
+    pub async fn opac_availability(&self, id: i64) -> Result<serde_json::Value> {
+        self.0.json(self.0.request(Method::GET, &format!("/opac/biblios/{}/availability", id))).await
+    }
+
+    /// `GET /opac/availability`: Availability of up to 100 ISBNs at once, for partner applications holding an API key
+    pub async fn opac_availability_batch(&self, query: &elidune_server::api::opac::AvailabilityBatchQuery) -> Result<elidune_server::models::biblio::AvailabilityBatch> {
+        self.0.json(self.0.request(Method::GET, "/opac/availability").query(query)).await
+    }
+
+    /// `GET /opac/biblios/{id}/citation`: Citation of a bibliographic record — public
+    pub async fn opac_citation(&self, id: i64, query: &elidune_server::models::citation::CitationQuery) -> Result<elidune_server::models::citation::Citation> {
+        self.0.json(self.0.request(Method::GET, &format!("/opac/biblios/{}/citation", id)).query(query)).await
+    }
+
+    /// `GET /opac/biblios/{id}`: Get a single bibliographic record by ID — public
+    pub async fn opac_get_biblio(&self, id: i64) -> Result<elidune_server::models::biblio::Biblio> {
+        self.0.json(self.0.request(Method::GET, &format!("/opac/biblios/{}", id))).await
+    }
+
+    /// `GET /opac/biblios`: Public catalog search — no auth required
+    pub async fn opac_search(&self, query: &elidune_server::models::biblio::BiblioQuery) -> Result<elidune_server::api::biblios::BiblioSearchPage> {
+        self.0.json(self.0.request(Method::GET, "/opac/biblios").query(query)).await
+    }
+
+    /// `GET /opac/widget/{isbn}`: Availability widget for third-party sites — no auth, CORS-open, cacheable
+    pub async fn opac_widget(&self, isbn: &str, query: &elidune_server::api::opac::WidgetQuery) -> Result<elidune_server::models::biblio::BiblioAvailability> {
+        self.0.json(self.0.request(Method::GET, &format!("/opac/widget/{}", segment(isbn))).query(query)).await
+    }
+
+    /// `GET /authors/suggest`: Author completions for the search box — public
+    pub async fn suggest_authors(&self, query: &elidune_server::models::biblio::SuggestQuery) -> Result<Vec<elidune_server::models::biblio::Suggestion>> {
+        self.0.json(self.0.request(Method::GET, "/authors/suggest").query(query)).await
+    }
+
+    /// `GET /items/suggest`: Title completions for the search box — public
+    pub async fn suggest_titles(&self, query: &elidune_server::models::biblio::SuggestQuery) -> Result<Vec<elidune_server::models::biblio::Suggestion>> {
+        self.0.json(self.0.request(Method::GET, "/items/suggest").query(query)).await
+    }
+}
+
+/// `payments` operations
+pub struct PaymentsApi<'a>(&'a Client);
+
+impl PaymentsApi<'_> {
+    /// `GET /payments/export`: Accounting export of a finished month (CSV for the municipal accounting software).
+    pub async fn export_payments(&self, query: &elidune_server::models::payment::PaymentExportQuery) -> Result<reqwest::Response> {
+        self.0.send(self.0.request(Method::GET, "/payments/export").query(query)).await
+    }
+
+    /// `GET /payments/daily-summary`: Daily cash-up: totals received and refunded by method, category and operator
+    pub async fn get_daily_summary(&self, query: &elidune_server::models::payment::DailySummaryQuery) -> Result<elidune_server::models::payment::DailyCashSummary> {
+        self.0.json(self.0.request(Method::GET, "/payments/daily-summary").query(query)).await
+    }
+
+    /// `GET /payments/{id}`: Get a payment
+    pub async fn get_payment(&self, id: i64) -> Result<elidune_server::models::payment::Payment> {
+        self.0.json(self.0.request(Method::GET, &format!("/payments/{}", id))).await
+    }
+
+    /// `GET /payments/receipt-gaps`: Receipt numbers of a year missing from the sequence
+    pub async fn get_receipt_gaps(&self, query: &elidune_server::models::payment::ReceiptGapsQuery) -> Result<elidune_server::models::payment::ReceiptGapReport> {
+        self.0.json(self.0.request(Method::GET, "/payments/receipt-gaps").query(query)).await
+    }
+
+    /// `GET /payments`: List payments and refunds, newest first
+    pub async fn list_payments(&self, query: &elidune_server::models::payment::PaymentQuery) -> Result<elidune_server::models::payment::PaymentsPage> {
+        self.0.json(self.0.request(Method::GET, "/payments").query(query)).await
+    }
+
+    /// `GET /payments/period-locks`: Months exported to accounting, newest first
+    pub async fn list_period_locks(&self) -> Result<Vec<elidune_server::models::payment::PaymentPeriodLock>> {
+        self.0.json(self.0.request(Method::GET, "/payments/period-locks")).await
+    }
+
+    /// `POST /payments`: Record a payment taken at the desk (fine, membership renewal, printing...)
+    pub async fn record_payment(&self, body: &elidune_server::models::payment::RecordPayment) -> Result<elidune_server::models::payment::Payment> {
+        self.0.json(self.0.request(Method::POST, "/payments").json(body)).await
+    }
+
+    /// `POST /payments/{id}/refund`: Refund all or part of a payment (a refunded fine payment is owed again)
+    pub async fn refund_payment(&self, id: i64, body: &elidune_server::models::payment::RefundPayment) -> Result<elidune_server::models::payment::Payment> {
+        self.0.json(self.0.request(Method::POST, &format!("/payments/{}/refund", id)).json(body)).await
+    }
+
+    /// `DELETE /payments/period-locks/{month}`: Reopen an exported month, e.g. after an export rejected by accounting (admin only)
+    pub async fn unlock_period(&self, month: &str) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/payments/period-locks/{}", segment(month)))).await
+    }
+}
+
+/// `public_types` operations
+pub struct PublicTypesApi<'a>(&'a Client);
+
+impl PublicTypesApi<'_> {
+    /// `POST /public-types`: Create a new public type
+    pub async fn create_public_type(&self, body: &elidune_server::models::public_type::CreatePublicType) -> Result<elidune_server::models::public_type::PublicType> {
+        self.0.json(self.0.request(Method::POST, "/public-types").json(body)).await
+    }
+
+    /// `DELETE /public-types/{id}`: Delete a public type
+    pub async fn delete_public_type(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/public-types/{}", id))).await
+    }
+
+    /// `GET /public-types/{id}`: Get public type by ID with loan settings
+    pub async fn get_public_type(&self, id: i64) -> Result<serde_json::Value> {
+        self.0.json(self.0.request(Method::GET, &format!("/public-types/{}", id))).await
+    }
+
+    /// `GET /public-types`: List all public types
+    pub async fn list_public_types(&self) -> Result<Vec<elidune_server::models::public_type::PublicType>> {
+        self.0.json(self.0.request(Method::GET, "/public-types")).await
+    }
+
+    /// `PUT /public-types/{id}/loan-settings`: Replace all loan settings for a public type (full list). Rows are deleted and re-inserted; response is the new list (same order as GET).
+    pub async fn update_loan_settings(&self, id: i64, body: &elidune_server::models::public_type::ReplacePublicTypeLoanSettingsRequest) -> Result<Vec<elidune_server::models::public_type::PublicTypeLoanSettings>> {
+        self.0.json(self.0.request(Method::PUT, &format!("/public-types/{}/loan-settings", id)).json(body)).await
+    }
+
+    /// `PUT /public-types/{id}`: Update a public type
+    pub async fn update_public_type(&self, id: i64, body: &elidune_server::models::public_type::UpdatePublicType) -> Result<elidune_server::models::public_type::PublicType> {
+        self.0.json(self.0.request(Method::PUT, &format!("/public-types/{}", id)).json(body)).await
+    }
+}
+
+/// `purchase_suggestions` operations
+pub struct PurchaseSuggestionsApi<'a>(&'a Client);
+
+impl PurchaseSuggestionsApi<'_> {
+    /// `POST /opac/suggestions`: Suggest a title the library does not own
+    pub async fn create_suggestion(&self, body: &elidune_server::models::purchase_suggestion::CreatePurchaseSuggestion) -> Result<elidune_server::models::purchase_suggestion::PurchaseSuggestion> {
+        self.0.json(self.0.request(Method::POST, "/opac/suggestions").json(body)).await
+    }
+
+    /// `GET /suggestions/{id}`: Get a purchase suggestion
+    pub async fn get_suggestion(&self, id: i64) -> Result<elidune_server::models::purchase_suggestion::PurchaseSuggestion> {
+        self.0.json(self.0.request(Method::GET, &format!("/suggestions/{}", id))).await
+    }
+
+    /// `GET /opac/suggestions`: List the current patron's suggestions with their status
+    pub async fn list_my_suggestions(&self) -> Result<Vec<elidune_server::models::purchase_suggestion::PurchaseSuggestion>> {
+        self.0.json(self.0.request(Method::GET, "/opac/suggestions")).await
+    }
+
+    /// `GET /suggestions`: List purchase suggestions for triage
+    pub async fn list_suggestions(&self, query: &elidune_server::models::purchase_suggestion::PurchaseSuggestionQuery) -> Result<Vec<elidune_server::models::purchase_suggestion::PurchaseSuggestion>> {
+        self.0.json(self.0.request(Method::GET, "/suggestions").query(query)).await
+    }
+
+    /// `POST /suggestions/{id}/triage`: Triage a suggestion: ordered (optionally on a purchase order), rejected with a reason, added
+    pub async fn triage_suggestion(&self, id: i64, body: &elidune_server::models::purchase_suggestion::TriagePurchaseSuggestion) -> Result<elidune_server::models::purchase_suggestion::PurchaseSuggestion> {
+        self.0.json(self.0.request(Method::POST, &format!("/suggestions/{}/triage", id)).json(body)).await
+    }
+}
+
+/// `reading_programs` operations
+pub struct ReadingProgramsApi<'a>(&'a Client);
+
+impl ReadingProgramsApi<'_> {
+    /// `POST /reading-programs/{id}/enrollments/{enrollment_id}/entries`: Log a book read by an enrolled patron (free title, catalog record or returned loan)
+    pub async fn add_reading_entry(&self, id: i64, enrollment_id: i64, body: &elidune_server::models::reading_program::CreateReadingEntry) -> Result<elidune_server::models::reading_program::ReadingEntry> {
+        self.0.json(self.0.request(Method::POST, &format!("/reading-programs/{}/enrollments/{}/entries", id, enrollment_id)).json(body)).await
+    }
+
+    /// `POST /reading-programs`: Create a reading program
+    pub async fn create_reading_program(&self, body: &elidune_server::models::reading_program::CreateReadingProgram) -> Result<elidune_server::models::reading_program::ReadingProgram> {
+        self.0.json(self.0.request(Method::POST, "/reading-programs").json(body)).await
+    }
+
+    /// `DELETE /reading-programs/{id}/enrollments/{enrollment_id}/entries/{entry_id}`: Delete a reading entry
+    pub async fn delete_reading_entry(&self, id: i64, enrollment_id: i64, entry_id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/reading-programs/{}/enrollments/{}/entries/{}", id, enrollment_id, entry_id))).await
+    }
+
+    /// `DELETE /reading-programs/{id}`: Delete a reading program with its enrollments and reading log
+    pub async fn delete_reading_program(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/reading-programs/{}", id))).await
+    }
+
+    /// `POST /reading-programs/{id}/enrollments`: Enroll a patron
+    pub async fn enroll_reader(&self, id: i64, body: &elidune_server::models::reading_program::EnrollReader) -> Result<elidune_server::models::reading_program::ReadingEnrollment> {
+        self.0.json(self.0.request(Method::POST, &format!("/reading-programs/{}/enrollments", id)).json(body)).await
+    }
+
+    /// `GET /reading-programs/{id}/enrollments/{enrollment_id}`: Get an enrollment with its progress
+    pub async fn get_enrollment(&self, id: i64, enrollment_id: i64) -> Result<elidune_server::models::reading_program::ReadingEnrollment> {
+        self.0.json(self.0.request(Method::GET, &format!("/reading-programs/{}/enrollments/{}", id, enrollment_id))).await
+    }
+
+    /// `GET /reading-programs/{id}`: Get a reading program
+    pub async fn get_reading_program(&self, id: i64) -> Result<elidune_server::models::reading_program::ReadingProgram> {
+        self.0.json(self.0.request(Method::GET, &format!("/reading-programs/{}", id))).await
+    }
+
+    /// `GET /reading-programs/{id}/stats`: Participation statistics by age bracket (JSON, or CSV with `format=csv`)
+    pub async fn get_reading_program_stats(&self, id: i64, query: &elidune_server::models::reading_program::ReadingProgramStatsQuery) -> Result<elidune_server::models::reading_program::ReadingProgramStats> {
+        self.0.json(self.0.request(Method::GET, &format!("/reading-programs/{}/stats", id)).query(query)).await
+    }
+
+    /// `GET /reading-programs/{id}/enrollments`: List enrolled patrons with their progress
+    pub async fn list_enrollments(&self, id: i64) -> Result<Vec<elidune_server::models::reading_program::ReadingEnrollment>> {
+        self.0.json(self.0.request(Method::GET, &format!("/reading-programs/{}/enrollments", id))).await
+    }
+
+    /// `GET /reading-programs/{id}/enrollments/{enrollment_id}/entries`: Reading log of an enrollment
+    pub async fn list_reading_entries(&self, id: i64, enrollment_id: i64) -> Result<Vec<elidune_server::models::reading_program::ReadingEntry>> {
+        self.0.json(self.0.request(Method::GET, &format!("/reading-programs/{}/enrollments/{}/entries", id, enrollment_id))).await
+    }
+
+    /// `GET /reading-programs`: List reading programs
+    pub async fn list_reading_programs(&self) -> Result<Vec<elidune_server::models::reading_program::ReadingProgram>> {
+        self.0.json(self.0.request(Method::GET, "/reading-programs")).await
+    }
+
+    /// `POST /reading-programs/{id}/sync-loans`: Log the loans enrolled patrons returned during the program (already logged loans are skipped)
+    pub async fn sync_reading_loans(&self, id: i64) -> Result<elidune_server::models::reading_program::ReadingLoansSyncReport> {
+        self.0.json(self.0.request(Method::POST, &format!("/reading-programs/{}/sync-loans", id))).await
+    }
+
+    /// `DELETE /reading-programs/{id}/enrollments/{enrollment_id}`: Remove a patron from a program (its reading log is deleted)
+    pub async fn unenroll_reader(&self, id: i64, enrollment_id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/reading-programs/{}/enrollments/{}", id, enrollment_id))).await
+    }
+
+    /// `PUT /reading-programs/{id}`: Update a reading program (completion is re-evaluated for every enrollment)
+    pub async fn update_reading_program(&self, id: i64, body: &elidune_server::models::reading_program::UpdateReadingProgram) -> Result<elidune_server::models::reading_program::ReadingProgram> {
+        self.0.json(self.0.request(Method::PUT, &format!("/reading-programs/{}", id)).json(body)).await
+    }
+}
+
+/// `reengagement` operations
+pub struct ReengagementApi<'a>(&'a Client);
+
+impl ReengagementApi<'_> {
+    /// `GET /reengagement/preview`: Members the next run would email
+    pub async fn preview_reengagement(&self) -> Result<elidune_server::models::reengagement::ReengagementPreview> {
+        self.0.json(self.0.request(Method::GET, "/reengagement/preview")).await
+    }
+
+    /// `POST /reengagement/run`: Send the re-engagement emails now, in the background
+    pub async fn run_reengagement(&self) -> Result<elidune_server::api::tasks::TaskAcceptedResponse> {
+        self.0.json(self.0.request(Method::POST, "/reengagement/run")).await
+    }
+}
+
+/// `relators` operations
+pub struct RelatorsApi<'a>(&'a Client);
+
+impl RelatorsApi<'_> {
+    /// `POST /settings/relators`: Create a relator
+    pub async fn create_relator(&self, body: &elidune_server::models::relator::CreateRelator) -> Result<elidune_server::models::relator::RelatorDefinition> {
+        self.0.json(self.0.request(Method::POST, "/settings/relators").json(body)).await
+    }
+
+    /// `DELETE /settings/relators/{code}`: Delete a relator (built-in codes and codes used by author credits cannot be deleted)
+    pub async fn delete_relator(&self, code: &str) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/settings/relators/{}", segment(code)))).await
+    }
+
+    /// `GET /settings/relators/{code}`: Get a relator by code
+    pub async fn get_relator(&self, code: &str) -> Result<elidune_server::models::relator::RelatorDefinition> {
+        self.0.json(self.0.request(Method::GET, &format!("/settings/relators/{}", segment(code)))).await
+    }
+
+    /// `GET /settings/relators`: List relators (values allowed in an author's `function`)
+    pub async fn list_relators(&self) -> Result<Vec<elidune_server::models::relator::RelatorDefinition>> {
+        self.0.json(self.0.request(Method::GET, "/settings/relators")).await
+    }
+
+    /// `PUT /settings/relators/{code}`: Update a relator (MARC codes, label, aliases)
+    pub async fn update_relator(&self, code: &str, body: &elidune_server::models::relator::UpdateRelator) -> Result<elidune_server::models::relator::RelatorDefinition> {
+        self.0.json(self.0.request(Method::PUT, &format!("/settings/relators/{}", segment(code))).json(body)).await
+    }
+}
+
+/// `schedules` operations
+pub struct SchedulesApi<'a>(&'a Client);
+
+impl SchedulesApi<'_> {
+    /// `POST /schedules/closures`: Create a closure
+    pub async fn create_closure(&self, body: &elidune_server::models::schedule::CreateScheduleClosure) -> Result<elidune_server::models::schedule::ScheduleClosureCreated> {
+        self.0.json(self.0.request(Method::POST, "/schedules/closures").json(body)).await
+    }
+
+    /// `POST /schedules/periods`: Create a schedule period
+    pub async fn create_period(&self, body: &elidune_server::models::schedule::CreateSchedulePeriod) -> Result<elidune_server::models::schedule::SchedulePeriod> {
+        self.0.json(self.0.request(Method::POST, "/schedules/periods").json(body)).await
+    }
+
+    /// `POST /schedules/periods/{id}/slots`: Create a slot for a period
+    pub async fn create_slot(&self, id: i64, body: &elidune_server::models::schedule::CreateScheduleSlot) -> Result<elidune_server::models::schedule::ScheduleSlot> {
+        self.0.json(self.0.request(Method::POST, &format!("/schedules/periods/{}/slots", id)).json(body)).await
+    }
+
+    /// `DELETE /schedules/closures/{id}`: Delete a closure
+    pub async fn delete_closure(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/schedules/closures/{}", id))).await
+    }
+
+    /// `DELETE /schedules/periods/{id}`: Delete a schedule period
+    pub async fn delete_period(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/schedules/periods/{}", id))).await
+    }
+
+    /// `DELETE /schedules/slots/{id}`: Delete a slot
+    pub async fn delete_slot(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/schedules/slots/{}", id))).await
+    }
+
+    /// `GET /schedules/status`: Current opening status and next opening time
+    pub async fn get_status(&self) -> Result<elidune_server::models::schedule::ScheduleStatus> {
+        self.0.json(self.0.request(Method::GET, "/schedules/status")).await
+    }
+
+    /// `GET /schedules/week`: Weekly timetable with closures applied
+    pub async fn get_week(&self, query: &elidune_server::models::schedule::WeekScheduleQuery) -> Result<elidune_server::models::schedule::WeekSchedule> {
+        self.0.json(self.0.request(Method::GET, "/schedules/week").query(query)).await
+    }
+
+    /// `GET /schedules/closures`: List schedule closures
+    pub async fn list_closures(&self, query: &elidune_server::models::schedule::ScheduleClosureQuery) -> Result<Vec<elidune_server::models::schedule::ScheduleClosure>> {
+        self.0.json(self.0.request(Method::GET, "/schedules/closures").query(query)).await
+    }
+
+    /// `GET /schedules/periods`: List schedule periods
+    pub async fn list_periods(&self) -> Result<Vec<elidune_server::models::schedule::SchedulePeriod>> {
+        self.0.json(self.0.request(Method::GET, "/schedules/periods")).await
+    }
+
+    /// `GET /schedules/periods/{id}/slots`: List slots for a period
+    pub async fn list_slots(&self, id: i64) -> Result<Vec<elidune_server::models::schedule::ScheduleSlot>> {
+        self.0.json(self.0.request(Method::GET, &format!("/schedules/periods/{}/slots", id))).await
+    }
+
+    /// `PUT /schedules/periods/{id}`: Update a schedule period
+    pub async fn update_period(&self, id: i64, body: &elidune_server::models::schedule::UpdateSchedulePeriod) -> Result<elidune_server::models::schedule::SchedulePeriod> {
+        self.0.json(self.0.request(Method::PUT, &format!("/schedules/periods/{}", id)).json(body)).await
+    }
+}
+
+/// `series` operations
+pub struct SeriesApi<'a>(&'a Client);
+
+impl SeriesApi<'_> {
+    /// `POST /series`: Create a new series.
+    pub async fn create_serie(&self, body: &elidune_server::models::biblio::CreateSerie) -> Result<elidune_server::models::biblio::Serie> {
+        self.0.json(self.0.request(Method::POST, "/series").json(body)).await
+    }
+
+    /// `DELETE /series/{id}`: Delete a series (only if no biblios are linked).
+    pub async fn delete_serie(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/series/{}", id))).await
+    }
+
+    /// `GET /series/{id}`: Get a series by ID.
+    pub async fn get_serie(&self, id: i64) -> Result<elidune_server::models::biblio::Serie> {
+        self.0.json(self.0.request(Method::GET, &format!("/series/{}", id))).await
+    }
+
+    /// `GET /series/{id}/biblios`: List biblios in a series (ordered by volume number).
+    pub async fn get_serie_biblios(&self, id: i64) -> Result<Vec<elidune_server::models::biblio::BiblioShort>> {
+        self.0.json(self.0.request(Method::GET, &format!("/series/{}/biblios", id))).await
+    }
+
+    /// `GET /series`: List series (paginated, optional name filter).
+    pub async fn list_series(&self, query: &elidune_server::models::biblio::SerieQuery) -> Result<elidune_server::api::series::PaginatedSeries> {
+        self.0.json(self.0.request(Method::GET, "/series").query(query)).await
+    }
+
+    /// `PUT /series/{id}`: Update a series.
+    pub async fn update_serie(&self, id: i64, body: &elidune_server::models::biblio::UpdateSerie) -> Result<elidune_server::models::biblio::Serie> {
+        self.0.json(self.0.request(Method::PUT, &format!("/series/{}", id)).json(body)).await
+    }
+}
+
+/// `snapshots` operations
+pub struct SnapshotsApi<'a>(&'a Client);
+
+impl SnapshotsApi<'_> {
+    /// `GET /admin/snapshots/{id}`: Get a catalog snapshot
+    pub async fn get_snapshot(&self, id: i64) -> Result<elidune_server::models::snapshot::CatalogSnapshot> {
+        self.0.json(self.0.request(Method::GET, &format!("/admin/snapshots/{}", id))).await
+    }
+
+    /// `GET /admin/snapshots`: List catalog snapshots, newest first
+    pub async fn list_snapshots(&self) -> Result<Vec<elidune_server::models::snapshot::CatalogSnapshot>> {
+        self.0.json(self.0.request(Method::GET, "/admin/snapshots")).await
+    }
+
+    /// `POST /admin/snapshots/{id}/restore`: Restore the copies of a snapshot as they were before the bulk edit
+    pub async fn restore_snapshot(&self, id: i64) -> Result<elidune_server::models::snapshot::SnapshotRestoreReport> {
+        self.0.json(self.0.request(Method::POST, &format!("/admin/snapshots/{}/restore", id))).await
+    }
+}
+
+/// `sources` operations
+pub struct SourcesApi<'a>(&'a Client);
+
+impl SourcesApi<'_> {
+    /// `POST /sources/{id}/archive`: Archive a source (fails if non-archived items are still linked)
+    pub async fn archive_source(&self, id: i64) -> Result<elidune_server::models::source::Source> {
+        self.0.json(self.0.request(Method::POST, &format!("/sources/{}/archive", id))).await
+    }
+
+    /// `POST /sources`: Create a source
+    pub async fn create_source(&self, body: &elidune_server::models::source::CreateSource) -> Result<elidune_server::models::source::Source> {
+        self.0.json(self.0.request(Method::POST, "/sources").json(body)).await
+    }
+
+    /// `DELETE /sources/{id}`: Delete a source, reassigning its items and biblios to a target source
+    pub async fn delete_source(&self, id: i64, query: &elidune_server::models::source::DeleteSourceQuery) -> Result<elidune_server::models::source::SourceDeletionReport> {
+        self.0.json(self.0.request(Method::DELETE, &format!("/sources/{}", id)).query(query)).await
+    }
+
+    /// `GET /sources/{id}`: Get source by ID
+    pub async fn get_source(&self, id: i64) -> Result<elidune_server::models::source::Source> {
+        self.0.json(self.0.request(Method::GET, &format!("/sources/{}", id))).await
+    }
+
+    /// `GET /sources/{id}/stats`: Usage statistics for a source (items, biblios, loans by year)
+    pub async fn get_source_stats(&self, id: i64) -> Result<elidune_server::models::source::SourceStats> {
+        self.0.json(self.0.request(Method::GET, &format!("/sources/{}/stats", id))).await
+    }
+
+    /// `GET /sources`: List all sources
+    pub async fn list_sources(&self, query: &elidune_server::api::sources::SourcesQuery) -> Result<Vec<elidune_server::models::source::Source>> {
+        self.0.json(self.0.request(Method::GET, "/sources").query(query)).await
+    }
+
+    /// `POST /sources/merge`: Merge multiple sources into a new one
+    pub async fn merge_sources(&self, body: &elidune_server::models::source::MergeSources) -> Result<elidune_server::models::source::Source> {
+        self.0.json(self.0.request(Method::POST, "/sources/merge").json(body)).await
+    }
+
+    /// `PUT /sources/{id}/vendor`: Link a source to a vendor, or clear the link
+    pub async fn set_source_vendor(&self, id: i64, body: &elidune_server::models::source::SetSourceVendor) -> Result<elidune_server::models::source::Source> {
+        self.0.json(self.0.request(Method::PUT, &format!("/sources/{}/vendor", id)).json(body)).await
+    }
+
+    /// `PUT /sources/{id}`: Update a source (name and/or default status)
+    pub async fn update_source(&self, id: i64, body: &elidune_server::models::source::UpdateSource) -> Result<elidune_server::models::source::Source> {
+        self.0.json(self.0.request(Method::PUT, &format!("/sources/{}", id)).json(body)).await
+    }
+}
+
+/// `sse` operations
+pub struct SseApi<'a>(&'a Client);
+
+impl SseApi<'_> {
+    /// `GET /events/stream`: Subscribe to real-time library events
+    pub async fn sse_stream(&self) -> Result<reqwest::Response> {
+        self.0.send(self.0.request(Method::GET, "/events/stream")).await
+    }
+}
+
+/// `staffing` operations
+pub struct StaffingApi<'a>(&'a Client);
+
+impl StaffingApi<'_> {
+    /// `POST /staffing/absences`: Record an absence for oneself (or anyone, with settings write rights)
+    pub async fn create_absence(&self, body: &elidune_server::models::staffing::CreateStaffAbsence) -> Result<elidune_server::models::staffing::StaffAbsenceCreated> {
+        self.0.json(self.0.request(Method::POST, "/staffing/absences").json(body)).await
+    }
+
+    /// `POST /staffing/assignments`: Assign a user to a shift
+    pub async fn create_assignment(&self, body: &elidune_server::models::staffing::CreateShiftAssignment) -> Result<elidune_server::models::staffing::ShiftAssignment> {
+        self.0.json(self.0.request(Method::POST, "/staffing/assignments").json(body)).await
+    }
+
+    /// `POST /staffing/shift-templates`: Create a shift template on an opening slot
+    pub async fn create_shift_template(&self, body: &elidune_server::models::staffing::ShiftTemplateFields) -> Result<elidune_server::models::staffing::ShiftTemplate> {
+        self.0.json(self.0.request(Method::POST, "/staffing/shift-templates").json(body)).await
+    }
+
+    /// `DELETE /staffing/absences/{id}`: Delete an absence (one's own, or anyone's with settings write rights)
+    pub async fn delete_absence(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/staffing/absences/{}", id))).await
+    }
+
+    /// `DELETE /staffing/assignments/{id}`: Remove a user from a shift
+    pub async fn delete_assignment(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/staffing/assignments/{}", id))).await
+    }
+
+    /// `DELETE /staffing/shift-templates/{id}`: Delete a shift template and its assignments
+    pub async fn delete_shift_template(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/staffing/shift-templates/{}", id))).await
+    }
+
+    /// `GET /staffing/calendar/{token}`: iCal feed of a person's shifts (the `.ics` suffix is optional)
+    pub async fn get_calendar(&self, token: &str) -> Result<reqwest::Response> {
+        self.0.send(self.0.request(Method::GET, &format!("/staffing/calendar/{}", segment(token)))).await
+    }
+
+    /// `GET /staffing/roster`: Roster: every shift of the range with its assignees and understaffing flag
+    pub async fn get_roster(&self, query: &elidune_server::models::staffing::StaffingRangeQuery) -> Result<Vec<elidune_server::models::staffing::RosterShift>> {
+        self.0.json(self.0.request(Method::GET, "/staffing/roster").query(query)).await
+    }
+
+    /// `GET /staffing/shift-templates/{id}`: Get a shift template
+    pub async fn get_shift_template(&self, id: i64) -> Result<elidune_server::models::staffing::ShiftTemplate> {
+        self.0.json(self.0.request(Method::GET, &format!("/staffing/shift-templates/{}", id))).await
+    }
+
+    /// `GET /staffing/absences`: List absences overlapping a range (staff, or one's own with `userId`)
+    pub async fn list_absences(&self, query: &elidune_server::models::staffing::StaffAbsenceQuery) -> Result<Vec<elidune_server::models::staffing::StaffAbsence>> {
+        self.0.json(self.0.request(Method::GET, "/staffing/absences").query(query)).await
+    }
+
+    /// `GET /staffing/shift-templates`: List shift templates
+    pub async fn list_shift_templates(&self, query: &elidune_server::models::staffing::ShiftTemplateQuery) -> Result<Vec<elidune_server::models::staffing::ShiftTemplate>> {
+        self.0.json(self.0.request(Method::GET, "/staffing/shift-templates").query(query)).await
+    }
+
+    /// `GET /staffing/me/shifts`: Shifts of the current user in a range
+    pub async fn my_shifts(&self, query: &elidune_server::models::staffing::StaffingRangeQuery) -> Result<Vec<elidune_server::models::staffing::ShiftAssignment>> {
+        self.0.json(self.0.request(Method::GET, "/staffing/me/shifts").query(query)).await
+    }
+
+    /// `POST /staffing/me/calendar-token`: Create (or replace) the secret URL of the caller's iCal feed
+    pub async fn regenerate_calendar_token(&self) -> Result<elidune_server::models::staffing::StaffCalendarToken> {
+        self.0.json(self.0.request(Method::POST, "/staffing/me/calendar-token")).await
+    }
+
+    /// `PUT /staffing/shift-templates/{id}`: Replace a shift template (omitted fields are cleared)
+    pub async fn update_shift_template(&self, id: i64, body: &elidune_server::models::staffing::ShiftTemplateFields) -> Result<elidune_server::models::staffing::ShiftTemplate> {
+        self.0.json(self.0.request(Method::PUT, &format!("/staffing/shift-templates/{}", id)).json(body)).await
+    }
+}
+
+/// `stats` operations
+pub struct StatsApi<'a>(&'a Client);
+
+impl StatsApi<'_> {
+    /// `POST /stats/saved`: Save a stats query for reuse.
+    pub async fn create_saved_query(&self, body: &elidune_server::models::stats_builder::SavedStatsQueryWrite) -> Result<elidune_server::models::stats_builder::SavedStatsQuery> {
+        self.0.json(self.0.request(Method::POST, "/stats/saved").json(body)).await
+    }
+
+    /// `POST /stats/reports`: Save a report definition for reuse.
+    pub async fn create_saved_report(&self, body: &elidune_server::models::stats_builder::SavedReportWrite) -> Result<elidune_server::models::stats_builder::SavedReport> {
+        self.0.json(self.0.request(Method::POST, "/stats/reports").json(body)).await
+    }
+
+    /// `DELETE /stats/saved/{id}`: Delete a saved query (owner or admin).
+    pub async fn delete_saved_query(&self, id: i64) -> Result<serde_json::Value> {
+        self.0.json(self.0.request(Method::DELETE, &format!("/stats/saved/{}", id))).await
+    }
+
+    /// `DELETE /stats/reports/{id}`: Delete a saved report (owner or admin).
+    pub async fn delete_saved_report(&self, id: i64) -> Result<serde_json::Value> {
+        self.0.json(self.0.request(Method::DELETE, &format!("/stats/reports/{}", id))).await
+    }
+
+    /// `GET /stats/acquisitions`: Value of the copies acquired in a period, per media type.
+    pub async fn get_acquisition_stats(&self, query: &elidune_server::api::stats::AcquisitionStatsQuery) -> Result<elidune_server::api::stats::AcquisitionStatsResponse> {
+        self.0.json(self.0.request(Method::GET, "/stats/acquisitions").query(query)).await
+    }
+
+    /// `GET /stats/catalog-quality`: Completeness of the catalog per source, to prioritize enrichment work.
+    pub async fn get_catalog_quality_stats(&self) -> Result<elidune_server::api::stats::CatalogQualityResponse> {
+        self.0.json(self.0.request(Method::GET, "/stats/catalog-quality")).await
+    }
+
+    /// `GET /stats/catalog`: Get catalog statistics (items/physical copies: active, entered, archived) with optional breakdowns.
+    pub async fn get_catalog_stats(&self, query: &elidune_server::api::stats::CatalogStatsQuery) -> Result<elidune_server::api::stats::CatalogStatsResponse> {
+        self.0.json(self.0.request(Method::GET, "/stats/catalog").query(query)).await
+    }
+
+    /// `GET /stats/loans`: Get advanced loan statistics.
+    pub async fn get_loan_stats(&self, query: &elidune_server::api::stats::LoanStatsQuery) -> Result<elidune_server::api::stats::LoanStatsResponse> {
+        self.0.json(self.0.request(Method::GET, "/stats/loans").query(query)).await
+    }
+
+    /// `GET /stats/reengagement`: Effectiveness of the re-engagement emails sent to inactive members.
+    pub async fn get_reengagement_stats(&self, query: &elidune_server::models::reengagement::ReengagementStatsQuery) -> Result<elidune_server::models::reengagement::ReengagementStats> {
+        self.0.json(self.0.request(Method::GET, "/stats/reengagement").query(query)).await
+    }
+
+    /// `GET /stats/reports/schema`: Dimensions, measures and output formats accepted by the report builder.
+    pub async fn get_report_schema(&self) -> Result<serde_json::Value> {
+        self.0.json(self.0.request(Method::GET, "/stats/reports/schema")).await
+    }
+
+    /// `GET /stats`: Get library statistics
+    pub async fn get_stats(&self, query: &elidune_server::api::stats::StatsQuery) -> Result<elidune_server::api::stats::StatsResponse> {
+        self.0.json(self.0.request(Method::GET, "/stats").query(query)).await
+    }
+
+    /// `GET /stats/schema`: Discovery document for the visual query builder (`entities`, `operators`, …).
+    pub async fn get_stats_schema(&self) -> Result<serde_json::Value> {
+        self.0.json(self.0.request(Method::GET, "/stats/schema")).await
+    }
+
+    /// `GET /stats/users`: Get user loan statistics (leaderboard-style)
+    pub async fn get_user_stats(&self, query: &elidune_server::api::stats::UserStatsQuery) -> Result<elidune_server::api::stats::UserStatsResponse> {
+        self.0.json(self.0.request(Method::GET, "/stats/users").query(query)).await
+    }
+
+    /// `GET /stats/visitors`: Attendance statistics: visitor counts compared with the opening hours.
+    pub async fn get_visitor_stats(&self, query: &elidune_server::models::visitor_count::VisitorStatsQuery) -> Result<elidune_server::models::visitor_count::VisitorStats> {
+        self.0.json(self.0.request(Method::GET, "/stats/visitors").query(query)).await
+    }
+
+    /// `GET /stats/saved`: List saved stats queries (own + shared; admins see all).
+    pub async fn list_saved_queries(&self) -> Result<Vec<elidune_server::models::stats_builder::SavedStatsQuery>> {
+        self.0.json(self.0.request(Method::GET, "/stats/saved")).await
+    }
+
+    /// `GET /stats/reports`: List saved reports (own + shared; admins see all).
+    pub async fn list_saved_reports(&self) -> Result<Vec<elidune_server::models::stats_builder::SavedReport>> {
+        self.0.json(self.0.request(Method::GET, "/stats/reports")).await
+    }
+
+    /// `POST /stats/reports/run`: Run an unsaved report definition (results cached like `/stats/saved/{id}/run`).
+    pub async fn post_run_report(&self, query: &elidune_server::api::stats::ReportRunQuery, body: &elidune_server::models::stats_builder::ReportDefinition) -> Result<elidune_server::models::stats_builder::ReportTable> {
+        self.0.json(self.0.request(Method::POST, "/stats/reports/run").query(query).json(body)).await
+    }
+
+    /// `POST /stats/query`: Run a declarative stats query (tabular result, paginated).
+    pub async fn post_stats_query(&self, body: &elidune_server::models::stats_builder::StatsBuilderBody) -> Result<elidune_server::models::stats_builder::StatsTableResponse> {
+        self.0.json(self.0.request(Method::POST, "/stats/query").json(body)).await
+    }
+
+    /// `GET /stats/saved/{id}/run`: Execute a saved query by id (same body as `POST /stats/query` would use).
+    pub async fn run_saved_query(&self, id: i64) -> Result<elidune_server::models::stats_builder::StatsTableResponse> {
+        self.0.json(self.0.request(Method::GET, &format!("/stats/saved/{}/run", id))).await
+    }
+
+    /// `GET /stats/reports/{id}/run`: Run a saved report by id.
+    pub async fn run_saved_report(&self, id: i64, query: &elidune_server::api::stats::ReportRunQuery) -> Result<elidune_server::models::stats_builder::ReportTable> {
+        self.0.json(self.0.request(Method::GET, &format!("/stats/reports/{}/run", id)).query(query)).await
+    }
+
+    /// `PUT /stats/saved/{id}`: Update a saved query (owner or admin).
+    pub async fn update_saved_query(&self, id: i64, body: &elidune_server::models::stats_builder::SavedStatsQueryWrite) -> Result<elidune_server::models::stats_builder::SavedStatsQuery> {
+        self.0.json(self.0.request(Method::PUT, &format!("/stats/saved/{}", id)).json(body)).await
+    }
+
+    /// `PUT /stats/reports/{id}`: Update a saved report (owner or admin).
+    pub async fn update_saved_report(&self, id: i64, body: &elidune_server::models::stats_builder::SavedReportWrite) -> Result<elidune_server::models::stats_builder::SavedReport> {
+        self.0.json(self.0.request(Method::PUT, &format!("/stats/reports/{}", id)).json(body)).await
+    }
+}
+
+/// `tasks` operations
+pub struct TasksApi<'a>(&'a Client);
+
+impl TasksApi<'_> {
+    /// `GET /tasks/{id}`: Get the current state of a background task.
+    pub async fn get_task(&self, id: i64) -> Result<elidune_server::models::task::BackgroundTask> {
+        self.0.json(self.0.request(Method::GET, &format!("/tasks/{}", id))).await
+    }
+
+    /// `GET /tasks`: List background tasks for the current user.
+    pub async fn list_tasks(&self) -> Result<Vec<elidune_server::models::task::BackgroundTask>> {
+        self.0.json(self.0.request(Method::GET, "/tasks")).await
+    }
+}
+
+/// `users` operations
+pub struct UsersApi<'a>(&'a Client);
+
+impl UsersApi<'_> {
+    /// `POST /users/{id}/messages/{message_id}/acknowledge`: Acknowledge a message (idempotent).
+    pub async fn acknowledge_user_message(&self, id: i64, message_id: i64) -> Result<elidune_server::models::user_message::UserMessage> {
+        self.0.json(self.0.request(Method::POST, &format!("/users/{}/messages/{}/acknowledge", id, message_id))).await
+    }
+
+    /// `POST /users`: Create a new user
+    pub async fn create_user(&self, body: &elidune_server::models::user::UserPayload) -> Result<elidune_server::models::user::User> {
+        self.0.json(self.0.request(Method::POST, "/users").json(body)).await
+    }
+
+    /// `POST /users/{id}/messages`: Add a message to a patron account.
+    pub async fn create_user_message(&self, id: i64, body: &elidune_server::models::user_message::CreateUserMessage) -> Result<elidune_server::models::user_message::UserMessage> {
+        self.0.json(self.0.request(Method::POST, &format!("/users/{}/messages", id)).json(body)).await
+    }
+
+    /// `DELETE /users/{id}`: Delete a user.
+    pub async fn delete_user(&self, id: i64, query: &elidune_server::api::users::DeleteUserParams) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/users/{}", id)).query(query)).await
+    }
+
+    /// `DELETE /users/{id}/messages/{message_id}`: Delete a message from a patron account
+    pub async fn delete_user_message(&self, id: i64, message_id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/users/{}/messages/{}", id, message_id))).await
+    }
+
+    /// `POST /email/webhook`: Mail provider webhook (bounces and spam complaints)
+    pub async fn email_webhook(&self, body: &elidune_server::models::email_health::EmailFeedbackBatch) -> Result<elidune_server::models::email_health::EmailFeedbackReport> {
+        self.0.json(self.0.request(Method::POST, "/email/webhook").json(body)).await
+    }
+
+    /// `PUT /users/{id}/force-password-change`: Force the user to change their password on next login (admin only).
+    pub async fn force_password_change(&self, id: i64) -> Result<serde_json::Value> {
+        self.0.json(self.0.request(Method::PUT, &format!("/users/{}/force-password-change", id))).await
+    }
+
+    /// `GET /users/{id}`: Get user details by ID
+    pub async fn get_user(&self, id: i64) -> Result<elidune_server::models::user::User> {
+        self.0.json(self.0.request(Method::GET, &format!("/users/{}", id))).await
+    }
+
+    /// `GET /users/{id}/activity`: Activity timeline of a user
+    pub async fn get_user_activity(&self, id: i64, query: &elidune_server::models::user::UserActivityQuery) -> Result<elidune_server::api::biblios::PaginatedUserActivity> {
+        self.0.json(self.0.request(Method::GET, &format!("/users/{}/activity", id)).query(query)).await
+    }
+
+    /// `POST /users/{id}/impersonate`: See what a patron sees: issue a short-lived token acting as them (admin only).
+    pub async fn impersonate_user(&self, id: i64, body: &elidune_server::api::users::ImpersonateRequest) -> Result<elidune_server::api::users::ImpersonationResponse> {
+        self.0.json(self.0.request(Method::POST, &format!("/users/{}/impersonate", id)).json(body)).await
+    }
+
+    /// `GET /users/{id}/messages`: List the messages of a patron (pending ones unless `includeAcknowledged=true`).
+    pub async fn list_user_messages(&self, id: i64, query: &elidune_server::models::user_message::UserMessageQuery) -> Result<Vec<elidune_server::models::user_message::UserMessage>> {
+        self.0.json(self.0.request(Method::GET, &format!("/users/{}/messages", id)).query(query)).await
+    }
+
+    /// `GET /users`: List users with search and pagination
+    pub async fn list_users(&self, query: &elidune_server::models::user::UserQuery) -> Result<elidune_server::api::biblios::PaginatedUsers> {
+        self.0.json(self.0.request(Method::GET, "/users").query(query)).await
+    }
+
+    /// `POST /users/merge`: Merge a duplicate patron account into the surviving one
+    pub async fn merge_users(&self, body: &elidune_server::models::user::MergeUsers) -> Result<elidune_server::models::user::UserMergeReport> {
+        self.0.json(self.0.request(Method::POST, "/users/merge").json(body)).await
+    }
+
+    /// `DELETE /users/{id}/email-health`: Clear the bounces and complaints recorded for a user's email address
+    pub async fn reset_user_email_health(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/users/{}/email-health", id))).await
+    }
+
+    /// `POST /users/{id}/restore`: Restore a user pending deletion (back to the status it had before the delete)
+    pub async fn restore_user(&self, id: i64) -> Result<elidune_server::models::user::User> {
+        self.0.json(self.0.request(Method::POST, &format!("/users/{}/restore", id))).await
+    }
+
+    /// `POST /users/expiry-campaign`: Start a membership expiry campaign
+    pub async fn run_expiry_campaign(&self, body: &elidune_server::models::user::UserExpiryCampaign) -> Result<elidune_server::api::tasks::TaskAcceptedResponse> {
+        self.0.json(self.0.request(Method::POST, "/users/expiry-campaign").json(body)).await
+    }
+
+    /// `PUT /users/{id}/pin`: Set or clear the self-service PIN of a patron (used with `POST /auth/login-barcode`).
+    pub async fn set_user_pin(&self, id: i64, body: &elidune_server::models::user::SetUserPin) -> Result<()> {
+        self.0.empty(self.0.request(Method::PUT, &format!("/users/{}/pin", id)).json(body)).await
+    }
+
+    /// `PUT /users/{id}/account-type`: Update user's account type (admin only)
+    pub async fn update_account_type(&self, id: i64, body: &elidune_server::models::user::UpdateAccountType) -> Result<elidune_server::models::user::User> {
+        self.0.json(self.0.request(Method::PUT, &format!("/users/{}/account-type", id)).json(body)).await
+    }
+
+    /// `PUT /users/{id}/campaigns-opt-in`: Opt in to (or out of) email campaigns.
+    pub async fn update_campaigns_opt_in(&self, id: i64, body: &elidune_server::models::campaign::CampaignsOptIn) -> Result<elidune_server::models::campaign::CampaignsOptIn> {
+        self.0.json(self.0.request(Method::PUT, &format!("/users/{}/campaigns-opt-in", id)).json(body)).await
+    }
+
+    /// `PUT /users/{id}`: Update an existing user
+    pub async fn update_user(&self, id: i64, body: &elidune_server::models::user::UserPayload) -> Result<elidune_server::models::user::User> {
+        self.0.json(self.0.request(Method::PUT, &format!("/users/{}", id)).json(body)).await
+    }
+}
+
+/// `vendors` operations
+pub struct VendorsApi<'a>(&'a Client);
+
+impl VendorsApi<'_> {
+    /// `POST /purchase-orders`: Create a purchase order
+    pub async fn create_purchase_order(&self, body: &elidune_server::models::vendor::CreatePurchaseOrder) -> Result<elidune_server::models::vendor::PurchaseOrder> {
+        self.0.json(self.0.request(Method::POST, "/purchase-orders").json(body)).await
+    }
+
+    /// `POST /vendors`: Create a vendor
+    pub async fn create_vendor(&self, body: &elidune_server::models::vendor::CreateVendor) -> Result<elidune_server::models::vendor::Vendor> {
+        self.0.json(self.0.request(Method::POST, "/vendors").json(body)).await
+    }
+
+    /// `DELETE /purchase-orders/{id}`: Delete a purchase order
+    pub async fn delete_purchase_order(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/purchase-orders/{}", id))).await
+    }
+
+    /// `DELETE /vendors/{id}`: Delete a vendor (fails while purchase orders reference it; linked sources are unlinked)
+    pub async fn delete_vendor(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/vendors/{}", id))).await
+    }
+
+    /// `GET /purchase-orders/{id}`: Get a purchase order by ID
+    pub async fn get_purchase_order(&self, id: i64) -> Result<elidune_server::models::vendor::PurchaseOrder> {
+        self.0.json(self.0.request(Method::GET, &format!("/purchase-orders/{}", id))).await
+    }
+
+    /// `GET /vendors/{id}`: Get a vendor by ID
+    pub async fn get_vendor(&self, id: i64) -> Result<elidune_server::models::vendor::Vendor> {
+        self.0.json(self.0.request(Method::GET, &format!("/vendors/{}", id))).await
+    }
+
+    /// `GET /vendors/spend`: Annual spend per vendor (orders count, ordered and received amounts)
+    pub async fn get_vendor_spend(&self, query: &elidune_server::models::vendor::VendorSpendQuery) -> Result<Vec<elidune_server::models::vendor::VendorAnnualSpend>> {
+        self.0.json(self.0.request(Method::GET, "/vendors/spend").query(query)).await
+    }
+
+    /// `GET /purchase-orders`: List purchase orders
+    pub async fn list_purchase_orders(&self, query: &elidune_server::models::vendor::PurchaseOrderQuery) -> Result<Vec<elidune_server::models::vendor::PurchaseOrder>> {
+        self.0.json(self.0.request(Method::GET, "/purchase-orders").query(query)).await
+    }
+
+    /// `GET /vendors`: List vendors
+    pub async fn list_vendors(&self, query: &elidune_server::api::vendors::VendorsQuery) -> Result<Vec<elidune_server::models::vendor::Vendor>> {
+        self.0.json(self.0.request(Method::GET, "/vendors").query(query)).await
+    }
+
+    /// `PUT /purchase-orders/{id}`: Update a purchase order
+    pub async fn update_purchase_order(&self, id: i64, body: &elidune_server::models::vendor::UpdatePurchaseOrder) -> Result<elidune_server::models::vendor::PurchaseOrder> {
+        self.0.json(self.0.request(Method::PUT, &format!("/purchase-orders/{}", id)).json(body)).await
+    }
+
+    /// `PUT /vendors/{id}`: Update a vendor
+    pub async fn update_vendor(&self, id: i64, body: &elidune_server::models::vendor::UpdateVendor) -> Result<elidune_server::models::vendor::Vendor> {
+        self.0.json(self.0.request(Method::PUT, &format!("/vendors/{}", id)).json(body)).await
+    }
+}
+
+/// `visitor_counts` operations
+pub struct VisitorCountsApi<'a>(&'a Client);
+
+impl VisitorCountsApi<'_> {
+    /// `POST /visitor-counts`: Create a visitor count record
+    pub async fn create_visitor_count(&self, body: &elidune_server::models::visitor_count::CreateVisitorCount) -> Result<elidune_server::models::visitor_count::VisitorCount> {
+        self.0.json(self.0.request(Method::POST, "/visitor-counts").json(body)).await
+    }
+
+    /// `DELETE /visitor-counts/{id}`: Delete a visitor count record
+    pub async fn delete_visitor_count(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/visitor-counts/{}", id))).await
+    }
+
+    /// `GET /visitor-counts`: List visitor counts
+    pub async fn list_visitor_counts(&self, query: &elidune_server::models::visitor_count::VisitorCountQuery) -> Result<Vec<elidune_server::models::visitor_count::VisitorCount>> {
+        self.0.json(self.0.request(Method::GET, "/visitor-counts").query(query)).await
+    }
+}
+
+/// `warehouse` operations
+pub struct WarehouseApi<'a>(&'a Client);
+
+impl WarehouseApi<'_> {
+    /// `POST /warehouse/targets`: Create a warehouse export target
+    pub async fn create_warehouse_target(&self, body: &elidune_server::models::warehouse::CreateWarehouseTarget) -> Result<elidune_server::models::warehouse::WarehouseTarget> {
+        self.0.json(self.0.request(Method::POST, "/warehouse/targets").json(body)).await
+    }
+
+    /// `DELETE /warehouse/targets/{id}`: Delete a warehouse export target and its run history (exported files stay on the remote side)
+    pub async fn delete_warehouse_target(&self, id: i64) -> Result<()> {
+        self.0.empty(self.0.request(Method::DELETE, &format!("/warehouse/targets/{}", id))).await
+    }
+
+    /// `GET /warehouse/runs/{id}`: Get a warehouse export run with its manifest
+    pub async fn get_warehouse_run(&self, id: i64) -> Result<elidune_server::models::warehouse::WarehouseRun> {
+        self.0.json(self.0.request(Method::GET, &format!("/warehouse/runs/{}", id))).await
+    }
+
+    /// `GET /warehouse/targets/{id}`: Get a warehouse export target
+    pub async fn get_warehouse_target(&self, id: i64) -> Result<elidune_server::models::warehouse::WarehouseTarget> {
+        self.0.json(self.0.request(Method::GET, &format!("/warehouse/targets/{}", id))).await
+    }
+
+    /// `GET /warehouse/targets/{id}/runs`: Run history of a warehouse export target (most recent first)
+    pub async fn list_warehouse_runs(&self, id: i64, query: &elidune_server::models::warehouse::WarehouseRunsQuery) -> Result<Vec<elidune_server::models::warehouse::WarehouseRun>> {
+        self.0.json(self.0.request(Method::GET, &format!("/warehouse/targets/{}/runs", id)).query(query)).await
+    }
+
+    /// `GET /warehouse/targets`: List warehouse export targets
+    pub async fn list_warehouse_targets(&self) -> Result<Vec<elidune_server::models::warehouse::WarehouseTarget>> {
+        self.0.json(self.0.request(Method::GET, "/warehouse/targets")).await
+    }
+
+    /// `POST /warehouse/targets/{id}/run`: Run an export now, in the background
+    pub async fn run_warehouse_target(&self, id: i64) -> Result<elidune_server::api::warehouse::WarehouseRunStarted> {
+        self.0.json(self.0.request(Method::POST, &format!("/warehouse/targets/{}/run", id))).await
+    }
+
+    /// `PUT /warehouse/targets/{id}`: Update a warehouse export target (an empty `password` removes it, `resetWatermarks` sends the full history again)
+    pub async fn update_warehouse_target(&self, id: i64, body: &elidune_server::models::warehouse::UpdateWarehouseTarget) -> Result<elidune_server::models::warehouse::WarehouseTarget> {
+        self.0.json(self.0.request(Method::PUT, &format!("/warehouse/targets/{}", id)).json(body)).await
+    }
+}
+
+/// `z3950` operations
+pub struct Z3950Api<'a>(&'a Client);
+
+impl Z3950Api<'_> {
+    /// `GET /z3950/servers`: List Z39.50 server definitions (staff).
+    pub async fn get_z3950_servers(&self) -> Result<Vec<elidune_server::api::z3950::Z3950ServerConfig>> {
+        self.0.json(self.0.request(Method::GET, "/z3950/servers")).await
+    }
+
+    /// `POST /z3950/import`: Import a record from Z39.50 search results into local catalog.
+    pub async fn import_record(&self, body: &elidune_server::api::z3950::Z3950ImportRequest) -> Result<elidune_server::api::z3950::Z3950ImportResponse> {
+        self.0.json(self.0.request(Method::POST, "/z3950/import").json(body)).await
+    }
+
+    /// `GET /z3950/search`: Search remote catalogs via Z39.50
+    pub async fn search(&self, query: &elidune_server::api::z3950::Z3950SearchQuery) -> Result<elidune_server::api::z3950::Z3950SearchResponse> {
+        self.0.json(self.0.request(Method::GET, "/z3950/search").query(query)).await
+    }
+
+    /// `PUT /z3950/servers`: Update Z39.50 server definitions (staff).
+    pub async fn update_z3950_servers(&self, body: &elidune_server::api::z3950::UpdateZ3950ServersRequest) -> Result<Vec<elidune_server::api::z3950::Z3950ServerConfig>> {
+        self.0.json(self.0.request(Method::PUT, "/z3950/servers").json(body)).await
+    }
+}
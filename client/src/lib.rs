@@ -0,0 +1,216 @@
+//! Typed async client for the Elidune REST API.
+//!
+//! Requests and responses are the server's own models, re-exported as [`api`] and [`models`].
+//! One method per documented operation is generated from the OpenAPI document (see
+//! `tests/codegen.rs`) and grouped by tag:
+//!
+//! ```no_run
+//! # async fn run() -> elidune_client::Result<()> {
+//! let client = elidune_client::Client::new("https://library.example.org/api/v2");
+//! client.login("librarian", "secret").await?;
+//! let me = client.auth().me().await?;
+//! let loans = client.loans().get_user_loans(me.id, &Default::default()).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::{Arc, RwLock};
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+
+pub use elidune_server::{api, models};
+
+use elidune_server::{
+    api::auth::{
+        BarcodeLoginRequest, BarcodeLoginResponse, LoginRequest, LoginResponse, Verify2FARequest,
+        Verify2FAResponse,
+    },
+    error::ErrorResponse,
+};
+
+mod generated;
+
+pub use generated::*;
+
+/// Header carrying the kiosk token of a catalog terminal
+const KIOSK_TOKEN_HEADER: &str = "X-Kiosk-Token";
+
+/// Characters escaped in path parameters (everything but unreserved characters)
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Client errors
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Transport failure, or a body that does not decode into the expected type
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// Error response from the server
+    #[error("{status} {code}: {message}")]
+    Api {
+        status: StatusCode,
+        /// Machine-readable error code (e.g. `"not_found"`), empty if the body was not an error document
+        code: String,
+        message: String,
+    },
+}
+
+impl Error {
+    /// HTTP status of a server error response
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::Http(e) => e.status(),
+            Error::Api { status, .. } => Some(*status),
+        }
+    }
+}
+
+/// Elidune API client.
+///
+/// Cheap to clone: clones share the connection pool and the access token, so a token obtained by
+/// [`Client::login`] is used by every clone.
+#[derive(Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    token: Arc<RwLock<Option<String>>>,
+    kiosk_token: Option<String>,
+}
+
+impl Client {
+    /// Client for the API rooted at `base_url` (e.g. `https://library.example.org/api/v2`)
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(reqwest::Client::new(), base_url)
+    }
+
+    /// Same as [`Client::new`], with a preconfigured HTTP client (timeouts, proxies, certificates)
+    pub fn with_http_client(http: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: Arc::new(RwLock::new(None)),
+            kiosk_token: None,
+        }
+    }
+
+    /// Use an existing access token (staff, self-service or impersonation)
+    #[must_use]
+    pub fn with_token(self, token: impl Into<String>) -> Self {
+        self.set_token(Some(token.into()));
+        self
+    }
+
+    /// Send the kiosk token of a catalog terminal with every request
+    #[must_use]
+    pub fn with_kiosk_token(mut self, token: impl Into<String>) -> Self {
+        self.kiosk_token = Some(token.into());
+        self
+    }
+
+    /// Current access token
+    pub fn token(&self) -> Option<String> {
+        self.token.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace (or clear with `None`) the access token
+    pub fn set_token(&self, token: Option<String>) {
+        *self.token.write().unwrap_or_else(|e| e.into_inner()) = token;
+    }
+
+    /// Staff/patron login. The returned token is kept for later calls; when the account uses 2FA
+    /// (`requires_2fa`), finish with [`Client::verify_2fa`].
+    pub async fn login(&self, username: &str, password: &str) -> Result<LoginResponse> {
+        let response = self
+            .auth()
+            .login(&LoginRequest {
+                username: username.to_string(),
+                password: password.to_string(),
+                device_id: None,
+            })
+            .await?;
+        if let Some(token) = &response.token {
+            self.set_token(Some(token.clone()));
+        }
+        Ok(response)
+    }
+
+    /// Second login step for 2FA accounts; the returned token is kept for later calls
+    pub async fn verify_2fa(&self, request: &Verify2FARequest) -> Result<Verify2FAResponse> {
+        let response = self.auth().verify_2fa(request).await?;
+        self.set_token(Some(response.token.clone()));
+        Ok(response)
+    }
+
+    /// Patron card barcode + PIN login; the self-service token is kept for later calls
+    pub async fn login_barcode(&self, barcode: &str, pin: &str) -> Result<BarcodeLoginResponse> {
+        let response = self
+            .auth()
+            .login_barcode(&BarcodeLoginRequest {
+                barcode: barcode.to_string(),
+                pin: pin.to_string(),
+            })
+            .await?;
+        self.set_token(Some(response.token.clone()));
+        Ok(response)
+    }
+
+    /// Forget the access token (tokens are stateless JWTs, there is nothing to revoke server-side)
+    pub fn logout(&self) {
+        self.set_token(None);
+    }
+
+    /// Request on `path` (relative to the API root) with the credentials attached
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let mut request = self.http.request(method, format!("{}{}", self.base_url, path));
+        if let Some(token) = self.token() {
+            request = request.bearer_auth(token);
+        }
+        if let Some(kiosk_token) = &self.kiosk_token {
+            request = request.header(KIOSK_TOKEN_HEADER, kiosk_token);
+        }
+        request
+    }
+
+    /// Send the request, turning error statuses into [`Error::Api`]
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(match serde_json::from_str::<ErrorResponse>(&body) {
+            Ok(error) => Error::Api {
+                status,
+                code: error.code,
+                message: error.message,
+            },
+            Err(_) => Error::Api {
+                status,
+                code: String::new(),
+                message: body,
+            },
+        })
+    }
+
+    async fn json<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        Ok(self.send(request).await?.json().await?)
+    }
+
+    async fn text(&self, request: RequestBuilder) -> Result<String> {
+        Ok(self.send(request).await?.text().await?)
+    }
+
+    async fn empty(&self, request: RequestBuilder) -> Result<()> {
+        self.send(request).await.map(drop)
+    }
+}
+
+/// Percent-encoded path parameter
+fn segment(value: &str) -> String {
+    utf8_percent_encode(value, PATH_SEGMENT).to_string()
+}
@@ -0,0 +1,398 @@
+//! Generates `src/generated.rs` from the server's OpenAPI document.
+//!
+//! Every documented operation becomes a method of the API group named after its tag. Body and
+//! response schemas resolve to the server types deriving them, and query parameters to the
+//! handler's `Query<...>` struct, so the client breaks at compile time when the server changes.
+//! The test fails when the checked-in file is stale; regenerate it with:
+//!
+//! ```sh
+//! UPDATE_CLIENT=1 cargo test -p elidune-client --test codegen
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use elidune_server::api::openapi::ApiDoc;
+use regex::Regex;
+use serde_json::Value;
+use utoipa::OpenApi;
+
+const SERVER_SRC: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../src");
+const GENERATED: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/generated.rs");
+const METHODS: &[&str] = &["get", "post", "put", "patch", "delete"];
+
+/// A public type of the server crate
+struct Definition {
+    /// Full path, e.g. `elidune_server::models::loan::LoanDetails`
+    path: String,
+    file: PathBuf,
+    /// Attributes above the definition (derives, aliases)
+    attributes: String,
+}
+
+/// Public types of the server crate, by name
+struct TypeIndex(BTreeMap<String, Vec<Definition>>);
+
+impl TypeIndex {
+    fn build() -> Self {
+        let definition = Regex::new(r"^pub (?:struct|enum|type) (\w+)").unwrap();
+        let alias = Regex::new(r"(\w+)\s*=\s*\w+<").unwrap();
+        let mut index = BTreeMap::<String, Vec<Definition>>::new();
+        for file in rust_files(Path::new(SERVER_SRC)) {
+            let Some(module) = public_module(&file) else {
+                continue;
+            };
+            let source = non_test_source(&file);
+            let lines: Vec<&str> = source.lines().collect();
+            for (i, line) in lines.iter().enumerate() {
+                let Some(name) = definition.captures(line).map(|c| c[1].to_string()) else {
+                    continue;
+                };
+                let attributes = attributes_above(&lines, i);
+                let mut names = vec![name];
+                // `#[aliases(PaginatedX = PaginatedResponse<X>)]` declares public type aliases
+                if let Some(aliases) = attributes.split("#[aliases(").nth(1) {
+                    names.extend(alias.captures_iter(aliases).map(|c| c[1].to_string()));
+                }
+                for name in names {
+                    index.entry(name.clone()).or_default().push(Definition {
+                        path: format!("{}::{}", module, name),
+                        file: file.clone(),
+                        attributes: attributes.clone(),
+                    });
+                }
+            }
+        }
+        Self(index)
+    }
+
+    /// Path of the type `name`, preferring a definition in `file`, then one deriving `derive`
+    fn resolve(&self, name: &str, file: Option<&Path>, derive: &str) -> String {
+        let candidates = self
+            .0
+            .get(name)
+            .unwrap_or_else(|| panic!("no public server type named `{}`", name));
+        if let [only] = candidates.as_slice() {
+            return only.path.clone();
+        }
+        let local: Vec<_> = candidates.iter().filter(|d| Some(d.file.as_path()) == file).collect();
+        if let [only] = local.as_slice() {
+            return only.path.clone();
+        }
+        let derived: Vec<_> = candidates.iter().filter(|d| d.attributes.contains(derive)).collect();
+        match derived.as_slice() {
+            [only] => only.path.clone(),
+            _ => panic!(
+                "ambiguous type `{}`: {}",
+                name,
+                candidates.iter().map(|d| d.path.as_str()).collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+
+    /// Rust type of a body or response schema
+    fn schema_type(&self, schema: &Value) -> String {
+        if let Some(reference) = schema["$ref"].as_str() {
+            let name = reference.rsplit('/').next().unwrap_or_default();
+            return self.resolve(name, None, "ToSchema");
+        }
+        match schema["type"].as_str() {
+            Some("array") => format!("Vec<{}>", self.schema_type(&schema["items"])),
+            _ => "serde_json::Value".to_string(),
+        }
+    }
+}
+
+/// `.rs` files under `dir`, sorted
+fn rust_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(rust_files(&path));
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Source without the `#[cfg(test)]` tail
+fn non_test_source(file: &Path) -> String {
+    let source = fs::read_to_string(file).unwrap();
+    match source.find("#[cfg(test)]") {
+        Some(end) => source[..end].to_string(),
+        None => source,
+    }
+}
+
+/// Module path of `file` (`elidune_server::models::loan`), if every module on the way is `pub`
+fn public_module(file: &Path) -> Option<String> {
+    let src = Path::new(SERVER_SRC);
+    let relative = file.strip_prefix(src).ok()?.with_extension("");
+    let mut segments: Vec<String> = relative.iter().map(|s| s.to_string_lossy().into_owned()).collect();
+    match segments.last().map(String::as_str) {
+        Some("main") => return None,
+        Some("lib") | Some("mod") => {
+            segments.pop();
+        }
+        _ => {}
+    }
+    let mut parent = src.to_path_buf();
+    for (depth, segment) in segments.iter().enumerate() {
+        let parent_file = if depth == 0 {
+            parent.join("lib.rs")
+        } else if parent.join("mod.rs").exists() {
+            parent.join("mod.rs")
+        } else {
+            parent.with_extension("rs")
+        };
+        let declared = format!("pub mod {};", segment);
+        if !fs::read_to_string(parent_file).ok()?.lines().any(|l| l.trim() == declared) {
+            return None;
+        }
+        parent = parent.join(segment);
+    }
+    Some(
+        std::iter::once("elidune_server".to_string())
+            .chain(segments)
+            .collect::<Vec<_>>()
+            .join("::"),
+    )
+}
+
+/// Attribute and doc lines directly above line `i`
+fn attributes_above(lines: &[&str], i: usize) -> String {
+    let start = lines[..i]
+        .iter()
+        .rposition(|l| {
+            let l = l.trim();
+            l.is_empty() || l.ends_with('}') || l.ends_with(';')
+        })
+        .map_or(0, |p| p + 1);
+    lines[start..i].join("\n")
+}
+
+/// Handler of a documented operation
+struct Handler {
+    file: PathBuf,
+    /// Type inside `Query<...>`, if the handler reads the query string
+    query: Option<String>,
+}
+
+/// Handlers in `src/api`, by (method, path) of their `#[utoipa::path]`
+fn handlers() -> BTreeMap<(String, String), Handler> {
+    let annotation =
+        Regex::new(r#"#\[utoipa::path\(\s*(get|post|put|patch|delete)\s*,\s*path\s*=\s*"([^"]+)""#).unwrap();
+    let function = Regex::new(r"fn \w+").unwrap();
+    let mut handlers = BTreeMap::new();
+    for file in rust_files(&Path::new(SERVER_SRC).join("api")) {
+        let source = non_test_source(&file);
+        for captures in annotation.captures_iter(&source) {
+            let rest = &source[captures.get(0).unwrap().end()..];
+            let start = function.find(rest).expect("handler after #[utoipa::path]").start();
+            let signature = &rest[start..rest[start..].find(" {\n").map_or(rest.len(), |e| start + e)];
+            let query = signature.split_once("Query<").map(|(_, tail)| generic_argument(tail));
+            handlers.insert(
+                (captures[1].to_string(), captures[2].to_string()),
+                Handler { file: file.clone(), query },
+            );
+        }
+    }
+    handlers
+}
+
+/// Text up to the `>` closing an already opened `<`
+fn generic_argument(tail: &str) -> String {
+    let mut depth = 1;
+    for (i, c) in tail.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return tail[..i].trim().to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+    panic!("unbalanced generic argument: {}", tail);
+}
+
+/// `account_types` → `AccountTypes`
+fn pascal_case(snake: &str) -> String {
+    snake
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |c| c.to_uppercase().chain(chars).collect())
+        })
+        .collect()
+}
+
+/// How a method reads the successful response
+enum Output {
+    Json(String),
+    Text,
+    Empty,
+    Raw,
+}
+
+impl Output {
+    fn of(types: &TypeIndex, operation: &Value) -> Self {
+        let responses = operation["responses"].as_object().unwrap();
+        // Redirect-only operations (digital resource access) hand back the followed response
+        let Some((status, response)) = responses.iter().find(|(status, _)| status.starts_with('2')) else {
+            return Output::Raw;
+        };
+        match response["content"].as_object() {
+            Some(content) => match content.get("application/json") {
+                Some(json) => Output::Json(types.schema_type(&json["schema"])),
+                None => Output::Text,
+            },
+            None if status == "204" => Output::Empty,
+            None => Output::Raw,
+        }
+    }
+
+    fn rust_type(&self) -> &str {
+        match self {
+            Output::Json(rust_type) => rust_type,
+            Output::Text => "String",
+            Output::Empty => "()",
+            Output::Raw => "reqwest::Response",
+        }
+    }
+}
+
+fn method(types: &TypeIndex, handlers: &BTreeMap<(String, String), Handler>, http_method: &str, path: &str, operation: &Value) -> String {
+    let handler = handlers
+        .get(&(http_method.to_string(), path.to_string()))
+        .unwrap_or_else(|| panic!("no handler annotated with {} {}", http_method, path));
+
+    let mut arguments = vec!["&self".to_string()];
+    let mut url_arguments = Vec::new();
+    for parameter in operation["parameters"].as_array().into_iter().flatten() {
+        if parameter["in"] != "path" {
+            continue;
+        }
+        let name = parameter["name"].as_str().unwrap();
+        let schema = &parameter["schema"];
+        let (rust_type, value) = match (schema["type"].as_str(), schema["format"].as_str()) {
+            (Some("integer"), Some("int32")) => ("i32", name.to_string()),
+            (Some("integer"), _) => ("i64", name.to_string()),
+            _ => ("&str", format!("segment({})", name)),
+        };
+        arguments.push(format!("{}: {}", name, rust_type));
+        url_arguments.push(value);
+    }
+    if let Some(query) = &handler.query {
+        arguments.push(format!("query: &{}", types.resolve(query, Some(&handler.file), "IntoParams")));
+    }
+    let body = &operation["requestBody"]["content"]["application/json"]["schema"];
+    if !body.is_null() {
+        arguments.push(format!("body: &{}", types.schema_type(body)));
+    }
+
+    let url = if url_arguments.is_empty() {
+        format!("\"{}\"", path)
+    } else {
+        let template = Regex::new(r"\{\w+\}").unwrap().replace_all(path, "{}");
+        format!("&format!(\"{}\", {})", template, url_arguments.join(", "))
+    };
+    let mut request = format!("self.0.request(Method::{}, {})", http_method.to_uppercase(), url);
+    if handler.query.is_some() {
+        request.push_str(".query(query)");
+    }
+    if !body.is_null() {
+        request.push_str(".json(body)");
+    }
+    let output = Output::of(types, operation);
+    let call = match output {
+        Output::Json(_) => format!("self.0.json({})", request),
+        Output::Text => format!("self.0.text({})", request),
+        Output::Empty => format!("self.0.empty({})", request),
+        Output::Raw => format!("self.0.send({})", request),
+    };
+
+    let mut doc = format!("`{} {}`", http_method.to_uppercase(), path);
+    if let Some(summary) = operation["summary"].as_str() {
+        write!(doc, ": {}", summary).unwrap();
+    }
+    format!(
+        "    /// {}\n    pub async fn {}({}) -> Result<{}> {{\n        {}.await\n    }}\n",
+        doc,
+        operation["operationId"].as_str().unwrap(),
+        arguments.join(", "),
+        output.rust_type(),
+        call
+    )
+}
+
+fn generate() -> String {
+    let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let types = TypeIndex::build();
+    let handlers = handlers();
+
+    let mut groups = BTreeMap::<String, BTreeMap<String, String>>::new();
+    for (path, item) in doc["paths"].as_object().unwrap() {
+        for http_method in METHODS {
+            let Some(operation) = item.get(*http_method) else {
+                continue;
+            };
+            let tag = operation["tags"][0].as_str().expect("operation without a tag");
+            let name = operation["operationId"].as_str().unwrap().to_string();
+            let previous = groups
+                .entry(tag.to_string())
+                .or_default()
+                .insert(name.clone(), method(&types, &handlers, http_method, path, operation));
+            assert!(previous.is_none(), "duplicate operation `{}` in tag `{}`", name, tag);
+        }
+    }
+
+    let mut out = String::from(
+        "// @generated by tests/codegen.rs from the server's OpenAPI document, do not edit.\n\
+         // Regenerate with `UPDATE_CLIENT=1 cargo test -p elidune-client --test codegen`.\n\n\
+         use reqwest::Method;\n\n\
+         use crate::{segment, Client, Result};\n\n\
+         impl Client {\n",
+    );
+    for tag in groups.keys() {
+        write!(
+            out,
+            "    /// `{tag}` operations\n    pub fn {tag}(&self) -> {api}<'_> {{\n        {api}(self)\n    }}\n\n",
+            tag = tag,
+            api = format!("{}Api", pascal_case(tag))
+        )
+        .unwrap();
+    }
+    out.truncate(out.len() - 1);
+    out.push_str("}\n");
+    for (tag, methods) in &groups {
+        let api = format!("{}Api", pascal_case(tag));
+        write!(out, "\n/// `{}` operations\npub struct {}<'a>(&'a Client);\n\nimpl {}<'_> {{\n", tag, api, api).unwrap();
+        out.push_str(&methods.values().cloned().collect::<Vec<_>>().join("\n"));
+        out.push_str("}\n");
+    }
+    out
+}
+
+#[test]
+fn generated_client_is_up_to_date() {
+    let generated = generate();
+    if std::env::var_os("UPDATE_CLIENT").is_some() {
+        fs::write(GENERATED, generated).unwrap();
+        return;
+    }
+    let current = fs::read_to_string(GENERATED).unwrap_or_default();
+    assert!(
+        current == generated,
+        "src/generated.rs is stale, regenerate it with: UPDATE_CLIENT=1 cargo test -p elidune-client --test codegen"
+    );
+}
@@ -0,0 +1,88 @@
+//! The client against the full router on a throwaway database (see `tests/repository` in the
+//! server crate). Ignored by default:
+//!
+//! ```sh
+//! cargo test -p elidune-client --test live -- --ignored
+//! ```
+
+#[allow(dead_code)]
+#[path = "../../tests/repository/fixtures.rs"]
+mod fixtures;
+#[allow(dead_code)]
+#[path = "../../tests/repository/harness.rs"]
+mod harness;
+#[allow(dead_code)]
+#[path = "../../tests/contract/server.rs"]
+mod server;
+
+use elidune_client::{
+    api::{auth::BarcodeLoginRequest, biblios::GetBiblioQuery, loans::GetUserLoansQuery},
+    Client, Error,
+};
+use reqwest::StatusCode;
+
+/// Error of a call expected to fail (response types do not implement `Debug`)
+fn refused<T>(result: elidune_client::Result<T>) -> Error {
+    match result {
+        Ok(_) => panic!("the call should have failed"),
+        Err(e) => e,
+    }
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test -p elidune-client --test live -- --ignored
+async fn login_keeps_the_token_for_typed_calls() {
+    let server = server::boot().await;
+    let client = Client::new(&server.base_url);
+
+    let anonymous = refused(client.auth().me().await);
+    assert_eq!(anonymous.status(), Some(StatusCode::UNAUTHORIZED));
+
+    let login = client.login(server::ADMIN_LOGIN, server::ADMIN_PASSWORD).await.unwrap();
+    assert!(!login.requires_2fa);
+    assert_eq!(client.token(), login.token);
+
+    let me = client.auth().me().await.unwrap();
+    assert_eq!(me.login, server::ADMIN_LOGIN);
+
+    let loans = client
+        .loans()
+        .get_user_loans(me.id, &GetUserLoansQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(loans.items.len(), 1);
+    assert_eq!(loans.items[0].item_identification.as_deref(), Some(server::BARCODE));
+
+    let biblio = client.items().get_biblio_by_barcode(server::BARCODE).await.unwrap();
+    assert_eq!(biblio.isbn.as_ref().map(AsRef::as_ref), Some(server::ISBN));
+
+    // Clones share the token
+    client.clone().logout();
+    assert!(client.token().is_none());
+}
+
+#[tokio::test]
+#[ignore]
+async fn error_responses_carry_the_server_error_code() {
+    let server = server::boot().await;
+    let client = Client::new(&server.base_url).with_token(&server.token);
+
+    match refused(client.biblios().get_biblio(i64::MAX, &GetBiblioQuery::default()).await) {
+        Error::Api { status, code, .. } => {
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert_eq!(code, "not_found");
+        }
+        other => panic!("expected an API error, got {:?}", other),
+    }
+
+    let unknown = refused(
+        client
+            .auth()
+            .login_barcode(&BarcodeLoginRequest {
+                barcode: "unknown".to_string(),
+                pin: "0000".to_string(),
+            })
+            .await,
+    );
+    assert_eq!(unknown.status(), Some(StatusCode::UNAUTHORIZED));
+}
@@ -0,0 +1,32 @@
+-- Equipment circulation: laptops, e-readers, ... can be checked out through the loans flow.
+-- A loan row references either a physical copy (`item_id`) or a piece of equipment (`equipment_id`).
+
+ALTER TABLE equipment
+    ADD COLUMN IF NOT EXISTS barcode            VARCHAR(100),
+    ADD COLUMN IF NOT EXISTS loan_duration_days SMALLINT NOT NULL DEFAULT 7,
+    ADD COLUMN IF NOT EXISTS max_renewals       SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS requires_deposit   BOOLEAN  NOT NULL DEFAULT FALSE;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_equipment_barcode_unique
+    ON equipment (barcode) WHERE barcode IS NOT NULL;
+
+ALTER TABLE loans
+    ADD COLUMN IF NOT EXISTS equipment_id     BIGINT REFERENCES equipment(id),
+    ADD COLUMN IF NOT EXISTS deposit_received BOOLEAN;
+
+ALTER TABLE loans DROP CONSTRAINT IF EXISTS loans_item_or_equipment_chk;
+ALTER TABLE loans
+    ADD CONSTRAINT loans_item_or_equipment_chk
+    CHECK ((item_id IS NULL) <> (equipment_id IS NULL)) NOT VALID;
+
+CREATE INDEX IF NOT EXISTS idx_loans_equipment_id ON loans(equipment_id);
+
+ALTER TABLE loans_archives
+    ADD COLUMN IF NOT EXISTS equipment_id BIGINT REFERENCES equipment(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_loans_archives_equipment_id ON loans_archives(equipment_id);
+
+COMMENT ON COLUMN equipment.loan_duration_days IS
+    'Loan period for this equipment (equipment loans ignore loans_settings).';
+COMMENT ON COLUMN equipment.requires_deposit IS
+    'When true, checkout requires staff to confirm that a deposit was received.';
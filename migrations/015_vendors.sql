@@ -0,0 +1,45 @@
+-- Supplier directory and purchase orders.
+-- A source linked to a vendor is a commercial supplier; a source without vendor is a donation /
+-- non-commercial origin.
+
+CREATE TABLE IF NOT EXISTS vendors (
+    id              BIGSERIAL     PRIMARY KEY,
+    name            VARCHAR(255)  NOT NULL,
+    contact_name    VARCHAR(255),
+    email           VARCHAR(255),
+    phone           VARCHAR(50),
+    address         TEXT,
+    website         VARCHAR(255),
+    customer_number VARCHAR(100),
+    discount_rate   NUMERIC(5,2)  NOT NULL DEFAULT 0 CHECK (discount_rate >= 0 AND discount_rate <= 100),
+    is_active       BOOLEAN       NOT NULL DEFAULT TRUE,
+    notes           TEXT,
+    created_at      TIMESTAMPTZ   DEFAULT NOW(),
+    update_at       TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_vendors_name_unique ON vendors (LOWER(name));
+
+ALTER TABLE sources
+    ADD COLUMN IF NOT EXISTS vendor_id BIGINT REFERENCES vendors(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_sources_vendor_id ON sources(vendor_id);
+
+CREATE TABLE IF NOT EXISTS purchase_orders (
+    id              BIGSERIAL     PRIMARY KEY,
+    vendor_id       BIGINT        NOT NULL REFERENCES vendors(id) ON DELETE RESTRICT,
+    source_id       BIGINT        REFERENCES sources(id) ON DELETE SET NULL,
+    reference       VARCHAR(100),
+    order_date      DATE          NOT NULL DEFAULT CURRENT_DATE,
+    status          VARCHAR(20)   NOT NULL DEFAULT 'ordered'
+                                  CHECK (status IN ('ordered', 'received', 'cancelled')),
+    total_amount    NUMERIC(12,2) NOT NULL DEFAULT 0 CHECK (total_amount >= 0),
+    notes           TEXT,
+    created_at      TIMESTAMPTZ   DEFAULT NOW(),
+    update_at       TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_purchase_orders_vendor ON purchase_orders(vendor_id, order_date);
+
+COMMENT ON COLUMN vendors.discount_rate IS 'Negotiated discount in percent (0-100).';
+COMMENT ON COLUMN purchase_orders.total_amount IS 'Net amount actually spent (after discount).';
@@ -0,0 +1,36 @@
+-- Managed taxonomy for items.circulation_status (previously an opaque smallint).
+-- Each state has a label, an optional display color, and may block circulation (checkout refused unless forced).
+
+CREATE TABLE IF NOT EXISTS item_states (
+    code               SMALLINT PRIMARY KEY,
+    label              VARCHAR(100) NOT NULL,
+    blocks_circulation BOOLEAN NOT NULL DEFAULT FALSE,
+    color              VARCHAR(7),
+    sort_order         SMALLINT NOT NULL DEFAULT 0,
+    created_at         TIMESTAMPTZ DEFAULT NOW(),
+    update_at          TIMESTAMPTZ,
+    CONSTRAINT item_states_color_chk CHECK (color IS NULL OR color ~ '^#[0-9A-Fa-f]{6}$')
+);
+
+INSERT INTO item_states (code, label, blocks_circulation, color, sort_order) VALUES
+    (0, 'Available',  FALSE, '#2E7D32', 0),
+    (1, 'In repair',  TRUE,  '#F9A825', 1),
+    (2, 'Lost',       TRUE,  '#C62828', 2),
+    (3, 'Missing',    TRUE,  '#6A1B9A', 3),
+    (4, 'Withdrawn',  TRUE,  '#616161', 4)
+ON CONFLICT (code) DO NOTHING;
+
+-- Keep legacy codes valid: register any value already present on items.
+INSERT INTO item_states (code, label, sort_order)
+SELECT DISTINCT circulation_status, 'State ' || circulation_status, 100
+FROM items
+WHERE circulation_status IS NOT NULL
+ON CONFLICT (code) DO NOTHING;
+
+ALTER TABLE items DROP CONSTRAINT IF EXISTS items_circulation_status_fkey;
+ALTER TABLE items
+    ADD CONSTRAINT items_circulation_status_fkey
+    FOREIGN KEY (circulation_status) REFERENCES item_states(code);
+
+COMMENT ON COLUMN item_states.blocks_circulation IS
+    'When true, copies in this state cannot be checked out unless the loan is forced.';
@@ -0,0 +1,36 @@
+-- Donations intake: a donation groups the books given by one donor on one date.
+-- Each line is triaged: added to the catalog (a copy is created with source "donation"),
+-- set aside for the book sale, or recycled.
+
+CREATE TABLE IF NOT EXISTS donations (
+    id              BIGSERIAL     PRIMARY KEY,
+    donor_name      VARCHAR(255)  NOT NULL,
+    donor_email     VARCHAR(255),
+    donor_phone     VARCHAR(50),
+    -- Set when the donor is a registered patron
+    donor_user_id   BIGINT        REFERENCES users(id) ON DELETE SET NULL,
+    received_at     DATE          NOT NULL DEFAULT CURRENT_DATE,
+    estimated_value NUMERIC(10,2) CHECK (estimated_value IS NULL OR estimated_value >= 0),
+    notes           TEXT,
+    created_at      TIMESTAMPTZ   DEFAULT NOW(),
+    update_at       TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_donations_received_at ON donations(received_at);
+
+CREATE TABLE IF NOT EXISTS donation_items (
+    id          BIGSERIAL    PRIMARY KEY,
+    donation_id BIGINT       NOT NULL REFERENCES donations(id) ON DELETE CASCADE,
+    title       VARCHAR(500) NOT NULL,
+    authors     VARCHAR(500),
+    isbn        VARCHAR(20),
+    triage      VARCHAR(20)  NOT NULL DEFAULT 'pending'
+                CHECK (triage IN ('pending', 'catalog', 'sale', 'recycle')),
+    -- Copy created when the line was added to the catalog
+    biblio_id   BIGINT       REFERENCES biblios(id) ON DELETE SET NULL,
+    item_id     BIGINT       REFERENCES items(id) ON DELETE SET NULL,
+    triaged_at  TIMESTAMPTZ,
+    notes       TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_donation_items_donation_id ON donation_items(donation_id);
@@ -0,0 +1,41 @@
+-- Reading programs (e.g. summer reading challenge): patrons enroll, log the books they read,
+-- and complete the program once `goal_books` entries fall within the program dates.
+
+CREATE TABLE IF NOT EXISTS reading_programs (
+    id          BIGSERIAL    PRIMARY KEY,
+    name        VARCHAR(255) NOT NULL,
+    description TEXT,
+    start_date  DATE         NOT NULL,
+    end_date    DATE         NOT NULL,
+    goal_books  SMALLINT     NOT NULL CHECK (goal_books > 0),
+    -- When true, only entries tied to a returned loan count towards the goal
+    loans_only  BOOLEAN      NOT NULL DEFAULT FALSE,
+    created_at  TIMESTAMPTZ  DEFAULT NOW(),
+    update_at   TIMESTAMPTZ,
+    CONSTRAINT reading_programs_dates_chk CHECK (end_date >= start_date)
+);
+
+CREATE TABLE IF NOT EXISTS reading_program_enrollments (
+    id           BIGSERIAL   PRIMARY KEY,
+    program_id   BIGINT      NOT NULL REFERENCES reading_programs(id) ON DELETE CASCADE,
+    user_id      BIGINT      NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    enrolled_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    CONSTRAINT reading_program_enrollments_unique UNIQUE (program_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS reading_entries (
+    id              BIGSERIAL    PRIMARY KEY,
+    enrollment_id   BIGINT       NOT NULL REFERENCES reading_program_enrollments(id) ON DELETE CASCADE,
+    read_at         DATE         NOT NULL DEFAULT CURRENT_DATE,
+    title           VARCHAR(500),
+    biblio_id       BIGINT       REFERENCES biblios(id) ON DELETE SET NULL,
+    -- Returned loan this entry comes from (`loans_archives.id`)
+    loan_archive_id BIGINT       REFERENCES loans_archives(id) ON DELETE SET NULL,
+    notes           TEXT,
+    created_at      TIMESTAMPTZ  DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_reading_entries_enrollment_id ON reading_entries(enrollment_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_reading_entries_loan_unique
+    ON reading_entries (enrollment_id, loan_archive_id) WHERE loan_archive_id IS NOT NULL;
@@ -0,0 +1,37 @@
+-- Group loans: a batch of copies checked out at once to a group account (e.g. a class visit)
+-- with one shared due date, extended and returned as a whole.
+
+-- Limits per account type; NULL `group_loans_max_items` = group loans not allowed for this type.
+ALTER TABLE account_types
+    ADD COLUMN IF NOT EXISTS group_loans_max_items SMALLINT CHECK (group_loans_max_items > 0),
+    ADD COLUMN IF NOT EXISTS group_loans_max_days  SMALLINT CHECK (group_loans_max_days > 0);
+
+COMMENT ON COLUMN account_types.group_loans_max_items IS
+    'Maximum copies in one group loan batch; NULL = group loans not allowed';
+COMMENT ON COLUMN account_types.group_loans_max_days IS
+    'Maximum days between today and the shared due date of a batch; NULL = 42';
+
+UPDATE account_types
+SET group_loans_max_items = 40, group_loans_max_days = 42
+WHERE code = 'group' AND group_loans_max_items IS NULL;
+
+CREATE TABLE IF NOT EXISTS loan_batches (
+    id          BIGSERIAL    PRIMARY KEY,
+    user_id     BIGINT       NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    label       VARCHAR(255),
+    due_at      TIMESTAMPTZ  NOT NULL,
+    notes       TEXT,
+    created_by  BIGINT       REFERENCES users(id) ON DELETE SET NULL,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    update_at   TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_loan_batches_user_id ON loan_batches(user_id);
+
+ALTER TABLE loans
+    ADD COLUMN IF NOT EXISTS batch_id BIGINT REFERENCES loan_batches(id) ON DELETE SET NULL;
+ALTER TABLE loans_archives
+    ADD COLUMN IF NOT EXISTS batch_id BIGINT REFERENCES loan_batches(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_loans_batch_id ON loans(batch_id) WHERE batch_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_loans_archives_batch_id ON loans_archives(batch_id) WHERE batch_id IS NOT NULL;
@@ -0,0 +1,21 @@
+-- Digital resources: a copy may point to an online resource (e-book, streaming, database).
+-- `access_type` tells whether anyone may follow the link (open) or only authenticated users (restricted).
+-- Each click-through on GET /items/:id/access is recorded in item_accesses for usage reporting.
+
+ALTER TABLE items ADD COLUMN IF NOT EXISTS access_url TEXT;
+ALTER TABLE items ADD COLUMN IF NOT EXISTS access_type VARCHAR(20);
+
+ALTER TABLE items DROP CONSTRAINT IF EXISTS items_access_type_chk;
+ALTER TABLE items ADD CONSTRAINT items_access_type_chk
+    CHECK (access_type IS NULL OR access_type IN ('open', 'restricted'));
+
+CREATE TABLE IF NOT EXISTS item_accesses (
+    id          BIGSERIAL   PRIMARY KEY,
+    item_id     BIGINT      NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    -- NULL for anonymous access to open resources
+    user_id     BIGINT      REFERENCES users(id) ON DELETE SET NULL,
+    accessed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_item_accesses_item_id ON item_accesses(item_id);
+CREATE INDEX IF NOT EXISTS idx_item_accesses_accessed_at ON item_accesses(accessed_at);
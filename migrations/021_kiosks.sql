@@ -0,0 +1,25 @@
+-- Kiosk tokens: long-lived credentials for in-library catalog terminals (OPAC read and patron
+-- self-service login only), so public terminals never hold a staff JWT.
+
+CREATE TABLE IF NOT EXISTS kiosks (
+    id            BIGSERIAL     PRIMARY KEY,
+    name          VARCHAR(100)  NOT NULL,
+    location      VARCHAR(255),
+    -- SHA-256 (hex) of the token; the token itself is only shown once
+    token_hash    VARCHAR(64)   NOT NULL UNIQUE,
+    -- First characters of the token, to recognise it in the admin list
+    token_prefix  VARCHAR(16)   NOT NULL,
+    -- Addresses or CIDR ranges the terminal may connect from
+    allowed_ips   TEXT[]        NOT NULL,
+    is_active     BOOLEAN       NOT NULL DEFAULT TRUE,
+    expires_at    TIMESTAMPTZ,
+    last_used_at  TIMESTAMPTZ,
+    last_used_ip  VARCHAR(64),
+    created_by    BIGINT        REFERENCES users(id) ON DELETE SET NULL,
+    created_at    TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    update_at     TIMESTAMPTZ,
+    CONSTRAINT kiosks_allowed_ips_not_empty CHECK (cardinality(allowed_ips) > 0)
+);
+
+COMMENT ON COLUMN kiosks.allowed_ips IS
+    'Client addresses (e.g. 192.168.10.21) or CIDR ranges (e.g. 192.168.10.0/24) allowed to use the token';
@@ -0,0 +1,11 @@
+-- Self-service PIN: patrons sign in at kiosks with card barcode + PIN instead of login/password.
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS pin_hash            VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS pin_failed_attempts SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS pin_locked_until    TIMESTAMPTZ;
+
+COMMENT ON COLUMN users.pin_hash IS 'Argon2 hash of the self-service PIN; NULL = barcode login disabled';
+COMMENT ON COLUMN users.pin_locked_until IS 'Barcode login refused until this time after repeated wrong PINs';
+
+CREATE INDEX IF NOT EXISTS idx_users_barcode ON users(barcode) WHERE barcode IS NOT NULL;
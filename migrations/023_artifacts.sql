@@ -0,0 +1,23 @@
+-- Generated files (catalog / audit / loan exports, import reports): content lives in the blob
+-- store, this table keeps the metadata. Rows and blobs are removed once `expires_at` is past.
+
+CREATE TABLE IF NOT EXISTS artifacts (
+    id                  BIGSERIAL     PRIMARY KEY,
+    kind                VARCHAR(50)   NOT NULL,
+    filename            VARCHAR(255)  NOT NULL,
+    content_type        VARCHAR(100)  NOT NULL,
+    size_bytes          BIGINT        NOT NULL DEFAULT 0,
+    -- Key of the content in the blob store (backend-specific)
+    storage_key         VARCHAR(255)  NOT NULL UNIQUE,
+    created_by          BIGINT        REFERENCES users(id) ON DELETE SET NULL,
+    created_at          TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    expires_at          TIMESTAMPTZ   NOT NULL,
+    download_count      INTEGER       NOT NULL DEFAULT 0,
+    last_downloaded_at  TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_artifacts_expires_at ON artifacts (expires_at);
+CREATE INDEX IF NOT EXISTS idx_artifacts_created_by ON artifacts (created_by, created_at DESC);
+
+COMMENT ON COLUMN artifacts.kind IS
+    'catalog_export | audit_export | loans_export | import_report';
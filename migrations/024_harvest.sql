@@ -0,0 +1,67 @@
+-- Scheduled harvest of delta files published by a union catalog (HTTP, FTP or SFTP): source
+-- configuration, per-run reports, and the link between remote record ids and local biblios.
+
+CREATE TABLE IF NOT EXISTS harvest_sources (
+    id                    BIGSERIAL     PRIMARY KEY,
+    name                  VARCHAR(100)  NOT NULL,
+    -- https://host/path/delta.mrc, ftp://host/dir/ or sftp://host:22/dir/
+    url                   TEXT          NOT NULL,
+    username              VARCHAR(255),
+    -- Needed in clear to log in to the remote server; never returned by the API
+    password              TEXT,
+    -- Expected SSH host key (`SHA256:…`, as printed by `ssh-keygen -lf`), required for SFTP
+    host_key_fingerprint  VARCHAR(100),
+    -- Glob selecting the delta files of an FTP/SFTP directory (e.g. `delta_*.mrc`)
+    file_pattern          VARCHAR(100)  NOT NULL DEFAULT '*',
+    -- iso2709 | marcxml
+    marc_format           VARCHAR(20)   NOT NULL DEFAULT 'iso2709',
+    -- How records are identified and applied (see HarvestMappingProfile)
+    mapping_profile       JSONB         NOT NULL DEFAULT '{}',
+    -- Catalog source given to the copies created from harvested records
+    catalog_source_id     BIGINT        REFERENCES sources(id) ON DELETE SET NULL,
+    -- Local time of the nightly run (HH:MM)
+    schedule_time         VARCHAR(5)    NOT NULL DEFAULT '02:30',
+    is_active             BOOLEAN       NOT NULL DEFAULT TRUE,
+    -- Addresses notified when a run fails or rejects records
+    alert_emails          TEXT[]        NOT NULL DEFAULT '{}',
+    -- Last applied file name (FTP/SFTP) or validator of the last fetched file (HTTP)
+    last_cursor           TEXT,
+    last_run_at           TIMESTAMPTZ,
+    created_at            TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    update_at             TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS harvest_runs (
+    id              BIGSERIAL     PRIMARY KEY,
+    source_id       BIGINT        NOT NULL REFERENCES harvest_sources(id) ON DELETE CASCADE,
+    -- schedule | manual
+    trigger         VARCHAR(20)   NOT NULL,
+    -- running | succeeded | partial | failed
+    status          VARCHAR(20)   NOT NULL DEFAULT 'running',
+    started_by      BIGINT        REFERENCES users(id) ON DELETE SET NULL,
+    started_at      TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    finished_at     TIMESTAMPTZ,
+    files           TEXT[]        NOT NULL DEFAULT '{}',
+    created_count   INTEGER       NOT NULL DEFAULT 0,
+    updated_count   INTEGER       NOT NULL DEFAULT 0,
+    deleted_count   INTEGER       NOT NULL DEFAULT 0,
+    skipped_count   INTEGER       NOT NULL DEFAULT 0,
+    failed_count    INTEGER       NOT NULL DEFAULT 0,
+    -- Rejected records: [{ "file", "recordId", "error" }]
+    record_errors   JSONB         NOT NULL DEFAULT '[]',
+    -- Why the run stopped (fetch or parse failure)
+    error           TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_harvest_runs_source ON harvest_runs (source_id, started_at DESC);
+
+CREATE TABLE IF NOT EXISTS harvest_records (
+    source_id     BIGINT        NOT NULL REFERENCES harvest_sources(id) ON DELETE CASCADE,
+    -- Record id in the union catalog (001 or 035)
+    remote_id     VARCHAR(100)  NOT NULL,
+    biblio_id     BIGINT        NOT NULL REFERENCES biblios(id) ON DELETE CASCADE,
+    harvested_at  TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (source_id, remote_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_harvest_records_biblio ON harvest_records (biblio_id);
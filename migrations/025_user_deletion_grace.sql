@@ -0,0 +1,11 @@
+-- Two-phase user deletion: `DELETE /users/:id` first moves the account to `pending_deletion`;
+-- the nightly retention job anonymizes it once `deletion_scheduled_at` has passed. Until then the
+-- account can be restored (to the status it had before, e.g. `blocked`) and archived loans keep
+-- their borrower attributes for statistics.
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deletion_scheduled_at TIMESTAMPTZ;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS status_before_deletion VARCHAR(32);
+
+CREATE INDEX IF NOT EXISTS idx_users_deletion_scheduled
+    ON users (deletion_scheduled_at)
+    WHERE status = 'pending_deletion';
@@ -0,0 +1,6 @@
+-- Duplicate patron accounts: `POST /users/merge` moves loans, holds, fines and enrollments of the
+-- duplicate to the surviving account, then archives the duplicate with a pointer to the survivor.
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS merged_into BIGINT REFERENCES users(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_users_merged_into ON users (merged_into) WHERE merged_into IS NOT NULL;
@@ -0,0 +1,16 @@
+-- Hold pickup lockers: a `ready` hold whose copy is placed in a vendor compartment becomes
+-- `in_locker`; the vendor webhook reports the compartment being opened (`picked_up`, the loan is
+-- created then) or its pickup window lapsing (`expired`).
+
+ALTER TABLE holds
+    ADD COLUMN IF NOT EXISTS pickup_locker      BOOLEAN     NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS locker_id          VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS locker_compartment VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS locker_pickup_code VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS locker_assigned_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS picked_up_at       TIMESTAMPTZ;
+
+COMMENT ON COLUMN holds.pickup_locker IS 'Patron asked to collect the copy from a pickup locker';
+COMMENT ON COLUMN holds.locker_pickup_code IS 'Code the patron types at the locker (issued by the vendor)';
+
+CREATE INDEX IF NOT EXISTS idx_holds_in_locker ON holds (expires_at) WHERE status = 'in_locker';
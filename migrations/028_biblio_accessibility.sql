@@ -0,0 +1,38 @@
+-- Accessible formats of a bibliographic record (large print, braille, audiobook,
+-- dyslexia-friendly typesetting), set from MARC on import and editable by staff. Used as a
+-- search filter and facet in the staff catalog and the OPAC.
+
+ALTER TABLE biblios
+    ADD COLUMN IF NOT EXISTS accessibility TEXT[] NOT NULL DEFAULT '{}';
+
+ALTER TABLE biblios DROP CONSTRAINT IF EXISTS biblios_accessibility_check;
+ALTER TABLE biblios ADD CONSTRAINT biblios_accessibility_check
+    CHECK (accessibility <@ ARRAY['largePrint', 'braille', 'audiobook', 'dyslexiaFriendly']::TEXT[]);
+
+COMMENT ON COLUMN biblios.accessibility IS 'Accessible formats: largePrint, braille, audiobook, dyslexiaFriendly';
+
+CREATE INDEX IF NOT EXISTS idx_biblios_accessibility ON biblios USING GIN (accessibility);
+
+-- Backfill from the data already in the catalog (same wording as the MARC translator)
+WITH detected AS (
+    SELECT b.id,
+           ARRAY_REMOVE(ARRAY[
+               CASE WHEN t.txt ~ '(large print|large type|gros caract|grands caract)' THEN 'largePrint' END,
+               CASE WHEN t.txt LIKE '%braille%' THEN 'braille' END,
+               CASE WHEN b.media_type IN ('audio', 'audioNonMusic', 'audioNonMusicTape', 'audioNonMusicCd')
+                      OR t.txt ~ '(audiobook|audio book|livres? audio|livre-audio)'
+                    THEN 'audiobook' END,
+               CASE WHEN t.txt LIKE '%dyslexi%' THEN 'dyslexiaFriendly' END
+           ], NULL) AS features
+    FROM biblios b
+    CROSS JOIN LATERAL (
+        SELECT lower(concat_ws(' ', b.format, b.page_extent, b.accompanying_material, b.notes,
+                               b.subject, array_to_string(b.keywords, ' '))) AS txt
+    ) t
+)
+UPDATE biblios b
+SET accessibility = d.features
+FROM detected d
+WHERE d.id = b.id
+  AND cardinality(d.features) > 0
+  AND b.accessibility = '{}';
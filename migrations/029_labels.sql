@@ -0,0 +1,164 @@
+-- Translated display labels for the codes returned by the API (media types, audiences, patron
+-- categories, genres, account types). One row per (kind, code, language); `lang` is an ISO 639-1
+-- code. Clients and stats responses pick the label of the caller's language (user preference,
+-- then Accept-Language) and fall back to the raw code.
+
+CREATE TABLE IF NOT EXISTS labels (
+    kind       VARCHAR(32)  NOT NULL,
+    code       VARCHAR(64)  NOT NULL,
+    lang       VARCHAR(8)   NOT NULL,
+    label      VARCHAR(200) NOT NULL,
+    update_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (kind, code, lang),
+    CONSTRAINT labels_kind_check
+        CHECK (kind IN ('mediaType', 'audienceType', 'publicType', 'genre', 'accountType')),
+    CONSTRAINT labels_lang_check CHECK (lang ~ '^[a-z]{2}$')
+);
+
+CREATE INDEX IF NOT EXISTS idx_labels_lang ON labels (lang);
+
+INSERT INTO labels (kind, code, lang, label) VALUES
+    -- Media types (biblios.media_type)
+    ('mediaType', 'unknown',           'en', 'Unknown'),
+    ('mediaType', 'unknown',           'fr', 'Inconnu'),
+    ('mediaType', 'printedText',       'en', 'Book'),
+    ('mediaType', 'printedText',       'fr', 'Livre'),
+    ('mediaType', 'multimedia',        'en', 'Multimedia'),
+    ('mediaType', 'multimedia',        'fr', 'Multimédia'),
+    ('mediaType', 'comics',            'en', 'Comics'),
+    ('mediaType', 'comics',            'fr', 'Bande dessinée'),
+    ('mediaType', 'periodic',          'en', 'Periodical'),
+    ('mediaType', 'periodic',          'fr', 'Périodique'),
+    ('mediaType', 'video',             'en', 'Video'),
+    ('mediaType', 'video',             'fr', 'Vidéo'),
+    ('mediaType', 'videoTape',         'en', 'Video tape'),
+    ('mediaType', 'videoTape',         'fr', 'Cassette vidéo'),
+    ('mediaType', 'videoDvd',          'en', 'DVD'),
+    ('mediaType', 'videoDvd',          'fr', 'DVD'),
+    ('mediaType', 'audio',             'en', 'Audio'),
+    ('mediaType', 'audio',             'fr', 'Audio'),
+    ('mediaType', 'audioMusic',        'en', 'Music'),
+    ('mediaType', 'audioMusic',        'fr', 'Musique'),
+    ('mediaType', 'audioMusicTape',    'en', 'Music tape'),
+    ('mediaType', 'audioMusicTape',    'fr', 'Cassette audio musicale'),
+    ('mediaType', 'audioMusicCd',      'en', 'Music CD'),
+    ('mediaType', 'audioMusicCd',      'fr', 'CD musical'),
+    ('mediaType', 'audioNonMusic',     'en', 'Spoken audio'),
+    ('mediaType', 'audioNonMusic',     'fr', 'Audio parlé'),
+    ('mediaType', 'audioNonMusicTape', 'en', 'Spoken audio tape'),
+    ('mediaType', 'audioNonMusicTape', 'fr', 'Cassette audio parlée'),
+    ('mediaType', 'audioNonMusicCd',   'en', 'Spoken audio CD'),
+    ('mediaType', 'audioNonMusicCd',   'fr', 'CD parlé'),
+    ('mediaType', 'cdRom',             'en', 'CD-ROM'),
+    ('mediaType', 'cdRom',             'fr', 'CD-ROM'),
+    ('mediaType', 'images',            'en', 'Images'),
+    ('mediaType', 'images',            'fr', 'Images'),
+    -- Intended audiences (biblios.audience_type)
+    ('audienceType', 'juvenile',     'en', 'Juvenile'),
+    ('audienceType', 'juvenile',     'fr', 'Jeunesse'),
+    ('audienceType', 'preschool',    'en', 'Preschool'),
+    ('audienceType', 'preschool',    'fr', 'Préscolaire'),
+    ('audienceType', 'primary',      'en', 'Primary school'),
+    ('audienceType', 'primary',      'fr', 'Primaire'),
+    ('audienceType', 'children',     'en', 'Children'),
+    ('audienceType', 'children',     'fr', 'Enfants'),
+    ('audienceType', 'youngAdult',   'en', 'Young adult'),
+    ('audienceType', 'youngAdult',   'fr', 'Adolescents'),
+    ('audienceType', 'adultSerious', 'en', 'Adult (serious)'),
+    ('audienceType', 'adultSerious', 'fr', 'Adultes (spécialisé)'),
+    ('audienceType', 'adult',        'en', 'Adult'),
+    ('audienceType', 'adult',        'fr', 'Adultes'),
+    ('audienceType', 'general',      'en', 'General'),
+    ('audienceType', 'general',      'fr', 'Tout public'),
+    ('audienceType', 'specialized',  'en', 'Specialized'),
+    ('audienceType', 'specialized',  'fr', 'Spécialisé'),
+    ('audienceType', 'unknown',      'en', 'Unknown'),
+    ('audienceType', 'unknown',      'fr', 'Inconnu'),
+    -- Patron categories (public_types.name, default set)
+    ('publicType', 'child',   'en', 'Child'),
+    ('publicType', 'child',   'fr', 'Enfant'),
+    ('publicType', 'adult',   'en', 'Adult'),
+    ('publicType', 'adult',   'fr', 'Adulte'),
+    ('publicType', 'school',  'en', 'School'),
+    ('publicType', 'school',  'fr', 'Scolaire'),
+    ('publicType', 'staff',   'en', 'Staff'),
+    ('publicType', 'staff',   'fr', 'Personnel'),
+    ('publicType', 'senior',  'en', 'Senior'),
+    ('publicType', 'senior',  'fr', 'Senior'),
+    ('publicType', 'unknown', 'en', 'Unknown'),
+    ('publicType', 'unknown', 'fr', 'Inconnu'),
+    -- Genres (Genre enum names)
+    ('genre', 'Unknown',                   'en', 'Unknown'),
+    ('genre', 'Unknown',                   'fr', 'Inconnu'),
+    ('genre', 'LitteratureGeneral',        'en', 'General works'),
+    ('genre', 'LitteratureGeneral',        'fr', 'Généralités'),
+    ('genre', 'LitteratureFiction',        'en', 'Fiction'),
+    ('genre', 'LitteratureFiction',        'fr', 'Roman'),
+    ('genre', 'LitteratureComic',          'en', 'Comics'),
+    ('genre', 'LitteratureComic',          'fr', 'Bande dessinée'),
+    ('genre', 'LitteratureTheatre',        'en', 'Drama'),
+    ('genre', 'LitteratureTheatre',        'fr', 'Théâtre'),
+    ('genre', 'LitteraturePoem',           'en', 'Poetry'),
+    ('genre', 'LitteraturePoem',           'fr', 'Poésie'),
+    ('genre', 'LitteraturePhilosophy',     'en', 'Philosophy'),
+    ('genre', 'LitteraturePhilosophy',     'fr', 'Philosophie'),
+    ('genre', 'LitteratureReligion',       'en', 'Religion'),
+    ('genre', 'LitteratureReligion',       'fr', 'Religion'),
+    ('genre', 'LitteratureSocialSciences', 'en', 'Social sciences'),
+    ('genre', 'LitteratureSocialSciences', 'fr', 'Sciences sociales'),
+    ('genre', 'LitteratureLanguages',      'en', 'Languages'),
+    ('genre', 'LitteratureLanguages',      'fr', 'Langues'),
+    ('genre', 'LitteratureSciences',       'en', 'Sciences'),
+    ('genre', 'LitteratureSciences',       'fr', 'Sciences'),
+    ('genre', 'LitteratureTechnical',      'en', 'Technology'),
+    ('genre', 'LitteratureTechnical',      'fr', 'Techniques'),
+    ('genre', 'LitteratureArt',            'en', 'Arts'),
+    ('genre', 'LitteratureArt',            'fr', 'Arts'),
+    ('genre', 'LitteratureSport',          'en', 'Sports and leisure'),
+    ('genre', 'LitteratureSport',          'fr', 'Sports et loisirs'),
+    ('genre', 'LitteratureLitterature',    'en', 'Literature'),
+    ('genre', 'LitteratureLitterature',    'fr', 'Littérature'),
+    ('genre', 'LitteratureHistory',        'en', 'History'),
+    ('genre', 'LitteratureHistory',        'fr', 'Histoire'),
+    ('genre', 'LitteratureGeography',      'en', 'Geography'),
+    ('genre', 'LitteratureGeography',      'fr', 'Géographie'),
+    ('genre', 'LitteratureOther',          'en', 'Other'),
+    ('genre', 'LitteratureOther',          'fr', 'Autre'),
+    ('genre', 'AudioUnknown',              'en', 'Music (unspecified)'),
+    ('genre', 'AudioUnknown',              'fr', 'Musique (non précisé)'),
+    ('genre', 'AudioJazz',                 'en', 'Jazz'),
+    ('genre', 'AudioJazz',                 'fr', 'Jazz'),
+    ('genre', 'AudioBlues',                'en', 'Blues'),
+    ('genre', 'AudioBlues',                'fr', 'Blues'),
+    ('genre', 'AudioRock',                 'en', 'Rock'),
+    ('genre', 'AudioRock',                 'fr', 'Rock'),
+    ('genre', 'AudioWorld',                'en', 'World music'),
+    ('genre', 'AudioWorld',                'fr', 'Musiques du monde'),
+    ('genre', 'AudioClassical',            'en', 'Classical'),
+    ('genre', 'AudioClassical',            'fr', 'Classique'),
+    ('genre', 'VideoUnknown',              'en', 'Video (unspecified)'),
+    ('genre', 'VideoUnknown',              'fr', 'Vidéo (non précisé)'),
+    ('genre', 'VideoFiction',              'en', 'Feature film'),
+    ('genre', 'VideoFiction',              'fr', 'Fiction'),
+    ('genre', 'VideoHistory',              'en', 'History'),
+    ('genre', 'VideoHistory',              'fr', 'Histoire'),
+    ('genre', 'VideoArt',                  'en', 'Arts'),
+    ('genre', 'VideoArt',                  'fr', 'Arts'),
+    ('genre', 'VideoDocumentary',          'en', 'Documentary'),
+    ('genre', 'VideoDocumentary',          'fr', 'Documentaire'),
+    ('genre', 'VideoMusical',              'en', 'Musical'),
+    ('genre', 'VideoMusical',              'fr', 'Comédie musicale'),
+    -- Account types (account_types.code)
+    ('accountType', 'guest',     'en', 'Guest'),
+    ('accountType', 'guest',     'fr', 'Invité'),
+    ('accountType', 'reader',    'en', 'Reader'),
+    ('accountType', 'reader',    'fr', 'Lecteur'),
+    ('accountType', 'librarian', 'en', 'Librarian'),
+    ('accountType', 'librarian', 'fr', 'Bibliothécaire'),
+    ('accountType', 'admin',     'en', 'Administrator'),
+    ('accountType', 'admin',     'fr', 'Administrateur'),
+    ('accountType', 'group',     'en', 'Group'),
+    ('accountType', 'group',     'fr', 'Collectivité'),
+    ('accountType', 'unknown',   'en', 'Unknown'),
+    ('accountType', 'unknown',   'fr', 'Inconnu')
+ON CONFLICT (kind, code, lang) DO NOTHING;
@@ -0,0 +1,74 @@
+-- Managed genre and subject heading vocabularies, assigned to bibliographic records.
+-- Subjects form a hierarchy (broader / narrower terms); merging a heading into another re-maps
+-- every record that used it. Genre codes are also the `genre` codes of the `labels` table.
+
+CREATE TABLE IF NOT EXISTS genres (
+    id          BIGSERIAL     PRIMARY KEY,
+    code        VARCHAR(64)   NOT NULL UNIQUE,
+    label       VARCHAR(200)  NOT NULL,
+    sort_order  SMALLINT      NOT NULL DEFAULT 0,
+    created_at  TIMESTAMPTZ   DEFAULT NOW(),
+    update_at   TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS subjects (
+    id          BIGSERIAL     PRIMARY KEY,
+    heading     VARCHAR(255)  NOT NULL,
+    broader_id  BIGINT        REFERENCES subjects(id) ON DELETE RESTRICT,
+    scope_note  TEXT,
+    created_at  TIMESTAMPTZ   DEFAULT NOW(),
+    update_at   TIMESTAMPTZ,
+    CONSTRAINT subjects_broader_not_self CHECK (broader_id IS NULL OR broader_id <> id)
+);
+
+COMMENT ON COLUMN subjects.broader_id IS 'Broader term (NULL = top-level heading)';
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_subjects_heading ON subjects (lower(heading));
+CREATE INDEX IF NOT EXISTS idx_subjects_broader ON subjects (broader_id);
+
+CREATE TABLE IF NOT EXISTS biblio_genres (
+    biblio_id  BIGINT NOT NULL REFERENCES biblios(id) ON DELETE CASCADE,
+    genre_id   BIGINT NOT NULL REFERENCES genres(id) ON DELETE CASCADE,
+    PRIMARY KEY (biblio_id, genre_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_biblio_genres_genre ON biblio_genres (genre_id);
+
+CREATE TABLE IF NOT EXISTS biblio_subjects (
+    biblio_id   BIGINT NOT NULL REFERENCES biblios(id) ON DELETE CASCADE,
+    subject_id  BIGINT NOT NULL REFERENCES subjects(id) ON DELETE CASCADE,
+    PRIMARY KEY (biblio_id, subject_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_biblio_subjects_subject ON biblio_subjects (subject_id);
+
+-- Default genres: the former fixed genre codes
+INSERT INTO genres (code, label, sort_order) VALUES
+    ('LitteratureGeneral',        'General works',      1),
+    ('LitteratureFiction',        'Fiction',            2),
+    ('LitteratureComic',          'Comics',             3),
+    ('LitteratureTheatre',        'Drama',              4),
+    ('LitteraturePoem',           'Poetry',             5),
+    ('LitteraturePhilosophy',     'Philosophy',         6),
+    ('LitteratureReligion',       'Religion',           7),
+    ('LitteratureSocialSciences', 'Social sciences',    8),
+    ('LitteratureLanguages',      'Languages',          9),
+    ('LitteratureSciences',       'Sciences',           10),
+    ('LitteratureTechnical',      'Technology',         11),
+    ('LitteratureArt',            'Arts',               12),
+    ('LitteratureSport',          'Sports and leisure', 13),
+    ('LitteratureLitterature',    'Literature',         14),
+    ('LitteratureHistory',        'History',            15),
+    ('LitteratureGeography',      'Geography',          16),
+    ('LitteratureOther',          'Other',              17),
+    ('AudioJazz',                 'Jazz',               101),
+    ('AudioBlues',                'Blues',              102),
+    ('AudioRock',                 'Rock',               103),
+    ('AudioWorld',                'World music',        104),
+    ('AudioClassical',            'Classical',          105),
+    ('VideoFiction',              'Feature film',       201),
+    ('VideoHistory',              'History',            202),
+    ('VideoArt',                  'Arts',               203),
+    ('VideoDocumentary',          'Documentary',        204),
+    ('VideoMusical',              'Musical',            205)
+ON CONFLICT (code) DO NOTHING;
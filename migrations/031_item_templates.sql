@@ -0,0 +1,30 @@
+-- Cataloging templates: predefined field sets merged into quick record creation (`POST /items?template=`).
+-- Fields left NULL are not part of the template; submitted fields always win over template ones.
+
+CREATE TABLE IF NOT EXISTS item_templates (
+    id                  BIGSERIAL     PRIMARY KEY,
+    code                VARCHAR(64)   NOT NULL UNIQUE,
+    name                VARCHAR(200)  NOT NULL,
+    description         TEXT,
+    -- Record defaults
+    media_type          VARCHAR(50),
+    audience_type       VARCHAR(50),
+    genre_id            BIGINT        REFERENCES genres(id) ON DELETE SET NULL,
+    -- Copy defaults
+    place               SMALLINT,
+    borrowable          BOOLEAN,
+    circulation_status  SMALLINT      REFERENCES item_states(code) ON DELETE SET NULL,
+    created_at          TIMESTAMPTZ   DEFAULT NOW(),
+    update_at           TIMESTAMPTZ
+);
+
+COMMENT ON COLUMN item_templates.place IS 'Default shelving location of new copies (items.place)';
+
+INSERT INTO item_templates (code, name, media_type, audience_type, genre_id, borrowable)
+VALUES
+    ('novel', 'Novel', 'printedText', 'adult',
+        (SELECT id FROM genres WHERE code = 'LitteratureFiction'), TRUE),
+    ('dvd', 'DVD', 'videoDvd', 'general',
+        (SELECT id FROM genres WHERE code = 'VideoFiction'), TRUE),
+    ('magazine', 'Magazine issue', 'periodic', 'general', NULL, TRUE)
+ON CONFLICT (code) DO NOTHING;
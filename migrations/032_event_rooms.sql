@@ -0,0 +1,7 @@
+-- Room (or location) of cultural events, checked for double bookings and closures
+
+ALTER TABLE events ADD COLUMN IF NOT EXISTS room VARCHAR(100);
+
+CREATE INDEX IF NOT EXISTS idx_events_room_date ON events (lower(room), event_date) WHERE room IS NOT NULL;
+
+COMMENT ON COLUMN events.room IS 'Room or location; events on the same day in the same room (case-insensitive) must not overlap';
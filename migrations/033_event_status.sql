@@ -0,0 +1,24 @@
+-- Publishing workflow of events (draft → published → cancelled) and attendee registrations
+
+-- Existing events were already public: they become published, new ones start as drafts
+ALTER TABLE events ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'published'
+    CHECK (status IN ('draft', 'published', 'cancelled'));
+ALTER TABLE events ALTER COLUMN status SET DEFAULT 'draft';
+ALTER TABLE events ADD COLUMN IF NOT EXISTS published_at TIMESTAMPTZ;
+ALTER TABLE events ADD COLUMN IF NOT EXISTS cancelled_at TIMESTAMPTZ;
+ALTER TABLE events ADD COLUMN IF NOT EXISTS cancellation_reason TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_events_status_date ON events (status, event_date);
+
+COMMENT ON COLUMN events.status IS 'draft (staff only), published (visible on the OPAC and feed) or cancelled';
+
+CREATE TABLE IF NOT EXISTS event_registrations (
+    event_id BIGINT NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (event_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_registrations_user ON event_registrations (user_id);
+
+COMMENT ON TABLE event_registrations IS 'Patrons registered to an event; notified by email when it is cancelled';
@@ -0,0 +1,53 @@
+-- Volunteer and staff scheduling on top of the opening hours (schedule_periods / schedule_slots)
+
+-- Shift template: a position to fill during an opening slot (e.g. "Front desk", 2 people)
+CREATE TABLE IF NOT EXISTS staff_shift_templates (
+    id            BIGSERIAL    PRIMARY KEY,
+    slot_id       BIGINT       NOT NULL REFERENCES schedule_slots(id) ON DELETE CASCADE,
+    name          VARCHAR(100) NOT NULL,
+    -- NULL = opening / closing time of the slot
+    start_time    TIME,
+    end_time      TIME,
+    staff_needed  SMALLINT     NOT NULL DEFAULT 1 CHECK (staff_needed > 0),
+    notes         TEXT,
+    created_at    TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    update_at     TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_staff_shift_templates_slot ON staff_shift_templates (slot_id);
+
+-- A user (staff or volunteer) working a template on a given day
+CREATE TABLE IF NOT EXISTS staff_shift_assignments (
+    id           BIGSERIAL    PRIMARY KEY,
+    template_id  BIGINT       NOT NULL REFERENCES staff_shift_templates(id) ON DELETE CASCADE,
+    shift_date   DATE         NOT NULL,
+    user_id      BIGINT       NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    notes        TEXT,
+    created_by   BIGINT       REFERENCES users(id) ON DELETE SET NULL,
+    created_at   TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    UNIQUE (template_id, shift_date, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_staff_shift_assignments_user_date ON staff_shift_assignments (user_id, shift_date);
+CREATE INDEX IF NOT EXISTS idx_staff_shift_assignments_date ON staff_shift_assignments (shift_date);
+
+-- Days a user cannot work (both days included); assignments in the range stay but show as absent
+CREATE TABLE IF NOT EXISTS staff_absences (
+    id          BIGSERIAL    PRIMARY KEY,
+    user_id     BIGINT       NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    start_date  DATE         NOT NULL,
+    end_date    DATE         NOT NULL,
+    reason      VARCHAR(255),
+    created_by  BIGINT       REFERENCES users(id) ON DELETE SET NULL,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    CHECK (end_date >= start_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_staff_absences_user ON staff_absences (user_id, start_date, end_date);
+
+-- Secret of each user's iCal feed (only the SHA-256 is stored; regenerating revokes the old URL)
+CREATE TABLE IF NOT EXISTS staff_calendar_tokens (
+    user_id     BIGINT       PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    token_hash  VARCHAR(64)  NOT NULL UNIQUE,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
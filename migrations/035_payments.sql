@@ -0,0 +1,29 @@
+-- Cash register: payments taken at the front desk (fines, memberships, printing...) and refunds
+
+-- Refunds are rows with a negative amount pointing at the payment they reverse.
+-- `fine_id` has no foreign key: the `fines` table is not created on every deployment.
+CREATE TABLE IF NOT EXISTS payments (
+    id                    BIGSERIAL     PRIMARY KEY,
+    user_id               BIGINT        REFERENCES users(id) ON DELETE SET NULL,
+    amount                NUMERIC(10,2) NOT NULL CHECK (amount <> 0),
+    method                VARCHAR(20)   NOT NULL
+        CHECK (method IN ('cash', 'card', 'cheque', 'transfer', 'other')),
+    category              VARCHAR(20)   NOT NULL
+        CHECK (category IN ('fine', 'membership', 'printing', 'other')),
+    fine_id               BIGINT,
+    -- New membership expiry set by a renewal payment
+    membership_expiry_at  TIMESTAMPTZ,
+    refund_of             BIGINT        REFERENCES payments(id) ON DELETE RESTRICT,
+    notes                 TEXT,
+    operator_id           BIGINT        REFERENCES users(id) ON DELETE SET NULL,
+    created_at            TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    CHECK ((refund_of IS NULL) = (amount > 0)),
+    CHECK (category <> 'fine' OR fine_id IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_payments_created_at ON payments (created_at);
+CREATE INDEX IF NOT EXISTS idx_payments_user ON payments (user_id) WHERE user_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_payments_fine ON payments (fine_id) WHERE fine_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_payments_refund_of ON payments (refund_of) WHERE refund_of IS NOT NULL;
+
+COMMENT ON COLUMN payments.amount IS 'Positive for payments, negative for refunds (refund_of set)';
@@ -0,0 +1,86 @@
+-- Cash register: per-year sequential receipt numbers and locking of periods exported to accounting
+
+-- Last receipt number issued per year; incremented in the transaction recording the payment,
+-- so a rolled back payment does not consume a number
+CREATE TABLE IF NOT EXISTS payment_receipt_counters (
+    year      SMALLINT PRIMARY KEY,
+    last_seq  INTEGER  NOT NULL
+);
+
+ALTER TABLE payments ADD COLUMN IF NOT EXISTS receipt_year SMALLINT;
+ALTER TABLE payments ADD COLUMN IF NOT EXISTS receipt_seq INTEGER;
+
+-- Number payments recorded before this migration in chronological order
+WITH numbered AS (
+    SELECT id,
+           EXTRACT(YEAR FROM created_at)::smallint AS year,
+           ROW_NUMBER() OVER (PARTITION BY EXTRACT(YEAR FROM created_at) ORDER BY created_at, id) AS seq
+    FROM payments
+    WHERE receipt_seq IS NULL
+)
+UPDATE payments p SET receipt_year = n.year, receipt_seq = n.seq
+FROM numbered n
+WHERE n.id = p.id;
+
+INSERT INTO payment_receipt_counters (year, last_seq)
+SELECT receipt_year, MAX(receipt_seq) FROM payments GROUP BY receipt_year
+ON CONFLICT (year) DO UPDATE SET last_seq = GREATEST(payment_receipt_counters.last_seq, EXCLUDED.last_seq);
+
+ALTER TABLE payments ALTER COLUMN receipt_year SET NOT NULL;
+ALTER TABLE payments ALTER COLUMN receipt_seq SET NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_payments_receipt ON payments (receipt_year, receipt_seq);
+
+-- Months exported to the accounting software; `[starts_at, ends_at)` is the month in library local time
+CREATE TABLE IF NOT EXISTS payment_period_locks (
+    period          DATE          PRIMARY KEY,
+    starts_at       TIMESTAMPTZ   NOT NULL,
+    ends_at         TIMESTAMPTZ   NOT NULL,
+    payments_count  INTEGER       NOT NULL,
+    total           NUMERIC(12,2) NOT NULL,
+    locked_by       BIGINT        REFERENCES users(id) ON DELETE SET NULL,
+    locked_at       TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    CHECK (ends_at > starts_at)
+);
+
+-- Payments of an exported month cannot be added, deleted or have their accounting fields changed.
+-- Patron and operator links may still change (user deletion, account merge).
+CREATE OR REPLACE FUNCTION payments_guard_locked_period() RETURNS trigger AS $$
+DECLARE
+    row_created_at TIMESTAMPTZ;
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        row_created_at := NEW.created_at;
+    ELSE
+        row_created_at := OLD.created_at;
+    END IF;
+
+    IF TG_OP = 'UPDATE'
+       AND (NEW.amount, NEW.method, NEW.category, NEW.fine_id, NEW.refund_of, NEW.created_at,
+            NEW.receipt_year, NEW.receipt_seq)
+           IS NOT DISTINCT FROM
+           (OLD.amount, OLD.method, OLD.category, OLD.fine_id, OLD.refund_of, OLD.created_at,
+            OLD.receipt_year, OLD.receipt_seq)
+    THEN
+        RETURN NEW;
+    END IF;
+
+    IF EXISTS (
+        SELECT 1 FROM payment_period_locks l
+        WHERE row_created_at >= l.starts_at AND row_created_at < l.ends_at
+           OR (TG_OP = 'UPDATE' AND NEW.created_at >= l.starts_at AND NEW.created_at < l.ends_at)
+    ) THEN
+        RAISE EXCEPTION 'payment % belongs to a period exported to accounting', COALESCE(OLD.id, NEW.id)
+            USING ERRCODE = 'check_violation';
+    END IF;
+
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_payments_locked_period ON payments;
+CREATE TRIGGER trg_payments_locked_period
+    BEFORE INSERT OR UPDATE OR DELETE ON payments
+    FOR EACH ROW EXECUTE FUNCTION payments_guard_locked_period();
@@ -0,0 +1,46 @@
+-- Reading level (recommended reading age) of children's and teen biblios, finer than
+-- audience_type. Set from the MARC target audience on import and editable by staff. Used as a
+-- search filter and a stats breakdown.
+
+ALTER TABLE biblios
+    ADD COLUMN IF NOT EXISTS reading_level VARCHAR(20);
+
+ALTER TABLE biblios DROP CONSTRAINT IF EXISTS biblios_reading_level_check;
+ALTER TABLE biblios ADD CONSTRAINT biblios_reading_level_check
+    CHECK (reading_level IN ('ages0To3', 'ages4To6', 'ages7To9', 'ages10To12', 'teen'));
+
+COMMENT ON COLUMN biblios.reading_level IS 'Reading level: ages0To3, ages4To6, ages7To9, ages10To12, teen';
+
+CREATE INDEX IF NOT EXISTS idx_biblios_reading_level ON biblios (reading_level)
+    WHERE reading_level IS NOT NULL;
+
+-- Backfill from the age-banded audience codes (same mapping as the MARC translator)
+UPDATE biblios
+SET reading_level = CASE audience_type
+        WHEN 'preschool'  THEN 'ages0To3'
+        WHEN 'primary'    THEN 'ages7To9'
+        WHEN 'children'   THEN 'ages10To12'
+        WHEN 'youngAdult' THEN 'teen'
+    END
+WHERE reading_level IS NULL
+  AND audience_type IN ('preschool', 'primary', 'children', 'youngAdult');
+
+-- Display labels for the stats breakdown
+ALTER TABLE labels DROP CONSTRAINT IF EXISTS labels_kind_check;
+ALTER TABLE labels ADD CONSTRAINT labels_kind_check
+    CHECK (kind IN ('mediaType', 'audienceType', 'readingLevel', 'publicType', 'genre', 'accountType'));
+
+INSERT INTO labels (kind, code, lang, label) VALUES
+    ('readingLevel', 'ages0To3',   'en', '0–3 years'),
+    ('readingLevel', 'ages0To3',   'fr', '0–3 ans'),
+    ('readingLevel', 'ages4To6',   'en', '4–6 years'),
+    ('readingLevel', 'ages4To6',   'fr', '4–6 ans'),
+    ('readingLevel', 'ages7To9',   'en', '7–9 years'),
+    ('readingLevel', 'ages7To9',   'fr', '7–9 ans'),
+    ('readingLevel', 'ages10To12', 'en', '10–12 years'),
+    ('readingLevel', 'ages10To12', 'fr', '10–12 ans'),
+    ('readingLevel', 'teen',       'en', 'Teen'),
+    ('readingLevel', 'teen',       'fr', 'Adolescents'),
+    ('readingLevel', 'unknown',    'en', 'Not set'),
+    ('readingLevel', 'unknown',    'fr', 'Non renseigné')
+ON CONFLICT (kind, code, lang) DO NOTHING;
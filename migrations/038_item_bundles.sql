@@ -0,0 +1,29 @@
+-- Item bundles: box sets and multi-volume works whose copies circulate as one unit.
+-- Checking out any part (or the bundle barcode) checks out every part with one due date;
+-- returns report the parts still missing.
+
+CREATE TABLE IF NOT EXISTS item_bundles (
+    id          BIGSERIAL     PRIMARY KEY,
+    name        VARCHAR(255)  NOT NULL,
+    -- Optional label on the box; scanned at checkout like a copy barcode
+    barcode     VARCHAR(100),
+    notes       TEXT,
+    created_at  TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    update_at   TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_item_bundles_barcode_unique
+    ON item_bundles (barcode) WHERE barcode IS NOT NULL;
+
+ALTER TABLE items
+    ADD COLUMN IF NOT EXISTS bundle_id BIGINT REFERENCES item_bundles(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_items_bundle_id ON items(bundle_id) WHERE bundle_id IS NOT NULL;
+
+ALTER TABLE loans
+    ADD COLUMN IF NOT EXISTS bundle_id BIGINT REFERENCES item_bundles(id) ON DELETE SET NULL;
+ALTER TABLE loans_archives
+    ADD COLUMN IF NOT EXISTS bundle_id BIGINT REFERENCES item_bundles(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_loans_bundle_id ON loans(bundle_id) WHERE bundle_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_loans_archives_bundle_id ON loans_archives(bundle_id) WHERE bundle_id IS NOT NULL;
@@ -0,0 +1,29 @@
+-- Deposit collections: rotating deposits lent by another library (e.g. the departmental lending
+-- library), catalogued as deposit copies, protected from weeding and sent back when the deposit ends.
+
+CREATE TABLE IF NOT EXISTS deposits (
+    id           BIGSERIAL     PRIMARY KEY,
+    -- Lending institution
+    owner        VARCHAR(255)  NOT NULL,
+    label        VARCHAR(255),
+    received_at  DATE          NOT NULL DEFAULT CURRENT_DATE,
+    return_due   DATE          NOT NULL,
+    status       VARCHAR(16)   NOT NULL DEFAULT 'active',
+    notes        TEXT,
+    created_by   BIGINT        REFERENCES users(id) ON DELETE SET NULL,
+    created_at   TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    update_at    TIMESTAMPTZ,
+    closed_at    TIMESTAMPTZ,
+    closed_by    BIGINT        REFERENCES users(id) ON DELETE SET NULL,
+    CONSTRAINT deposits_status_chk CHECK (status IN ('active', 'closed')),
+    CHECK (return_due >= received_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_deposits_status ON deposits (status);
+
+COMMENT ON COLUMN deposits.status IS 'active = copies on the shelves; closed = deposit sent back to its owner';
+
+ALTER TABLE items
+    ADD COLUMN IF NOT EXISTS deposit_id BIGINT REFERENCES deposits(id);
+
+CREATE INDEX IF NOT EXISTS idx_items_deposit_id ON items(deposit_id) WHERE deposit_id IS NOT NULL;
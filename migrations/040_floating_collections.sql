@@ -0,0 +1,15 @@
+-- Floating collections (`circulation.floating_rules`): a copy checked in at another place than its
+-- own either floats (stays there) or travels back home. The routing state lives on the copy.
+
+ALTER TABLE items ADD COLUMN IF NOT EXISTS transit_place      SMALLINT;
+ALTER TABLE items ADD COLUMN IF NOT EXISTS routing_place      SMALLINT;
+ALTER TABLE items ADD COLUMN IF NOT EXISTS floated_from_place SMALLINT;
+
+CREATE INDEX IF NOT EXISTS idx_items_transit_place ON items (transit_place) WHERE transit_place IS NOT NULL;
+
+COMMENT ON COLUMN items.transit_place IS
+    'Home place the copy is travelling back to (set at check-in elsewhere, cleared when received)';
+COMMENT ON COLUMN items.routing_place IS
+    'Place the copy was checked in at, waiting for the staff to confirm its floating rule';
+COMMENT ON COLUMN items.floated_from_place IS
+    'Place the copy was shelved at before it last floated, to reshelve it there';
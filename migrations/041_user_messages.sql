@@ -0,0 +1,21 @@
+-- Patron messages: staff notes attached to a patron account ("bring back the DVD case").
+-- `blocking` notes stop checkouts until they are acknowledged (or the checkout is forced);
+-- patron-visible notes are also shown in the self-service account.
+
+CREATE TABLE IF NOT EXISTS user_messages (
+    id               BIGSERIAL    PRIMARY KEY,
+    user_id          BIGINT       NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    body             TEXT         NOT NULL,
+    severity         VARCHAR(16)  NOT NULL DEFAULT 'info',
+    patron_visible   BOOLEAN      NOT NULL DEFAULT FALSE,
+    -- Hidden after this instant, acknowledged or not
+    expires_at       TIMESTAMPTZ,
+    created_by       BIGINT       REFERENCES users(id) ON DELETE SET NULL,
+    created_at       TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    acknowledged_at  TIMESTAMPTZ,
+    acknowledged_by  BIGINT       REFERENCES users(id) ON DELETE SET NULL,
+    CONSTRAINT user_messages_severity_chk CHECK (severity IN ('info', 'blocking'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_messages_pending
+    ON user_messages (user_id) WHERE acknowledged_at IS NULL;
@@ -0,0 +1,15 @@
+-- Per-account-type right to check out despite checkout blocks (expired membership, overdues, fines, blocking messages).
+
+ALTER TABLE account_types
+    ADD COLUMN IF NOT EXISTS circulation_override_rights VARCHAR(1);
+
+UPDATE account_types
+SET circulation_override_rights = 'w'
+WHERE code IN ('librarian', 'admin');
+
+UPDATE account_types
+SET circulation_override_rights = 'n'
+WHERE circulation_override_rights IS NULL;
+
+COMMENT ON COLUMN account_types.circulation_override_rights IS
+    'n/w: whether force=true on a checkout overrides the patron''s checkout blocks';
@@ -0,0 +1,42 @@
+-- Email campaigns: closure and event announcements sent to a patron segment.
+-- Recipients are resolved when the campaign is sent and queued in `campaign_recipients`,
+-- which the background sender drains at the SMTP throttle rate. Bounces reported by the
+-- mail provider are recorded against the queued addresses.
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS campaigns_opt_in BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS campaigns (
+    id            BIGSERIAL    PRIMARY KEY,
+    name          VARCHAR(255) NOT NULL,
+    subject       VARCHAR(255) NOT NULL,
+    -- Plain text, inserted into the `campaign` email template
+    body          TEXT         NOT NULL,
+    -- {"accountTypes": [...], "minAge": n, "maxAge": n, "activeWithinDays": n, "optedInOnly": bool}
+    segment       JSONB        NOT NULL DEFAULT '{}'::jsonb,
+    status        VARCHAR(16)  NOT NULL DEFAULT 'draft',
+    created_by    BIGINT       REFERENCES users(id) ON DELETE SET NULL,
+    created_at    TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    update_at     TIMESTAMPTZ,
+    started_at    TIMESTAMPTZ,
+    completed_at  TIMESTAMPTZ,
+    CONSTRAINT campaigns_status_chk CHECK (status IN ('draft', 'sending', 'sent'))
+);
+
+-- Outbound queue; one row per address (members of a family sharing an address get one email)
+CREATE TABLE IF NOT EXISTS campaign_recipients (
+    campaign_id   BIGINT       NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    email         VARCHAR(255) NOT NULL,
+    user_id       BIGINT       REFERENCES users(id) ON DELETE SET NULL,
+    status        VARCHAR(16)  NOT NULL DEFAULT 'pending',
+    error         TEXT,
+    -- Claimed by a sender (status `sending`), then sent or failed
+    attempted_at  TIMESTAMPTZ,
+    sent_at       TIMESTAMPTZ,
+    bounced_at    TIMESTAMPTZ,
+    PRIMARY KEY (campaign_id, email),
+    CONSTRAINT campaign_recipients_status_chk
+        CHECK (status IN ('pending', 'sending', 'sent', 'failed', 'bounced'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_campaign_recipients_pending
+    ON campaign_recipients (campaign_id) WHERE status = 'pending';
@@ -0,0 +1,32 @@
+-- Outbound email deliverability. The mail provider reports bounces and spam complaints through
+-- a signed webhook; each report is kept in `email_feedback` and summed per address in
+-- `email_health`. Addresses are stored trimmed and lowercased.
+
+CREATE TABLE IF NOT EXISTS email_feedback (
+    id                 BIGSERIAL    PRIMARY KEY,
+    email              VARCHAR(255) NOT NULL,
+    kind               VARCHAR(16)  NOT NULL,
+    reason             TEXT,
+    -- Provider event id: webhook retries are recorded once
+    provider_event_id  VARCHAR(255),
+    occurred_at        TIMESTAMPTZ  NOT NULL,
+    received_at        TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    CONSTRAINT email_feedback_kind_chk CHECK (kind IN ('hard_bounce', 'soft_bounce', 'complaint'))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_email_feedback_provider_event
+    ON email_feedback (provider_event_id) WHERE provider_event_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_email_feedback_email ON email_feedback (email, occurred_at DESC);
+
+-- Counters since the last reset by staff. `undeliverable_at` is set once hard bounces reach
+-- `email.hard_bounce_threshold`; no email is sent to the address until staff reset it.
+CREATE TABLE IF NOT EXISTS email_health (
+    email             VARCHAR(255) PRIMARY KEY,
+    hard_bounces      INTEGER      NOT NULL DEFAULT 0,
+    soft_bounces      INTEGER      NOT NULL DEFAULT 0,
+    complaints        INTEGER      NOT NULL DEFAULT 0,
+    last_feedback_at  TIMESTAMPTZ,
+    last_reason       TEXT,
+    undeliverable_at  TIMESTAMPTZ,
+    update_at         TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,6 @@
+-- Hourly visitor counts: people counters report one bucket per hour ([hour, hour + 1), library
+-- local time). NULL keeps the existing meaning of a daily total. Attendance statistics compare
+-- the buckets with the opening hours to spot counts registered while the library was closed.
+
+ALTER TABLE visitor_counts ADD COLUMN IF NOT EXISTS count_hour SMALLINT
+    CHECK (count_hour IS NULL OR count_hour BETWEEN 0 AND 23);
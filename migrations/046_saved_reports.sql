@@ -0,0 +1,18 @@
+-- Report builder: saved report definitions (dimensions × measures over a period).
+-- Unlike `saved_queries`, a definition only names whitelisted dimensions and measures; the
+-- server compiles it into stats builder queries, so no SQL or table name is ever stored.
+
+CREATE TABLE IF NOT EXISTS saved_reports (
+    id              BIGSERIAL    PRIMARY KEY,
+    name            VARCHAR(200) NOT NULL,
+    description     TEXT,
+    -- {"dimensions": [...], "measures": [...], "granularity": "...", "from": "...", "to": "..."}
+    definition      JSONB        NOT NULL,
+    user_id         BIGINT       NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    is_shared       BOOLEAN      NOT NULL DEFAULT FALSE,
+    created_at      TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_saved_reports_user_id ON saved_reports(user_id);
+CREATE INDEX IF NOT EXISTS idx_saved_reports_shared ON saved_reports(is_shared) WHERE is_shared = TRUE;
@@ -0,0 +1,49 @@
+-- Data warehouse exports: anonymized fact tables (loans, acquisitions, visits) pushed every night
+-- as CSV or Parquet files to an S3 bucket or an SFTP directory, with a manifest per run.
+-- Each fact only sends the rows changed since its watermark (upper bound of the last successful run).
+
+CREATE TABLE IF NOT EXISTS warehouse_targets (
+    id                    BIGSERIAL     PRIMARY KEY,
+    name                  VARCHAR(100)  NOT NULL,
+    -- s3://bucket/prefix/ or sftp://host[:port]/dir/
+    url                   TEXT          NOT NULL,
+    -- S3 access key id, or SFTP user
+    username              VARCHAR(255),
+    -- S3 secret access key, or SFTP password; never returned by the API
+    password              TEXT,
+    -- Expected SSH host key (`SHA256:…`), required for SFTP
+    host_key_fingerprint  VARCHAR(100),
+    -- S3 region (signature scope) and optional endpoint of an S3-compatible store (path-style URLs)
+    region                VARCHAR(50)   NOT NULL DEFAULT 'us-east-1',
+    endpoint              TEXT,
+    -- csv | parquet
+    format                VARCHAR(20)   NOT NULL DEFAULT 'parquet',
+    -- loans | acquisitions | visits
+    facts                 TEXT[]        NOT NULL DEFAULT '{loans,acquisitions,visits}',
+    -- Local time of the nightly run (HH:MM)
+    schedule_time         VARCHAR(5)    NOT NULL DEFAULT '01:30',
+    is_active             BOOLEAN       NOT NULL DEFAULT TRUE,
+    -- {"loans": "2026-10-17T01:30:00Z", ...}: rows changed after these instants are sent next time
+    watermarks            JSONB         NOT NULL DEFAULT '{}',
+    last_run_at           TIMESTAMPTZ,
+    created_at            TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    update_at             TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS warehouse_runs (
+    id            BIGSERIAL     PRIMARY KEY,
+    target_id     BIGINT        NOT NULL REFERENCES warehouse_targets(id) ON DELETE CASCADE,
+    -- schedule | manual
+    trigger       VARCHAR(20)   NOT NULL,
+    -- running | succeeded | failed
+    status        VARCHAR(20)   NOT NULL DEFAULT 'running',
+    started_by    BIGINT        REFERENCES users(id) ON DELETE SET NULL,
+    started_at    TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    finished_at   TIMESTAMPTZ,
+    -- Manifest uploaded with the files (null until the upload succeeded)
+    manifest      JSONB,
+    error         TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_warehouse_runs_target ON warehouse_runs (target_id, started_at DESC);
+
@@ -0,0 +1,30 @@
+-- Case- and accent-insensitive search: lowercased, unaccented copies of the searched names
+-- (biblio titles, author names, user names), kept up to date by PostgreSQL and indexed with
+-- trigrams so `LIKE '%eleve%'` finds "Élève" without scanning the table.
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- unaccent() is only STABLE (its dictionary could change); naming the dictionary explicitly makes
+-- the wrapper safe to declare IMMUTABLE, as generated columns and indexes require.
+CREATE OR REPLACE FUNCTION normalize_search(value TEXT) RETURNS TEXT
+    LANGUAGE sql IMMUTABLE PARALLEL SAFE STRICT
+    AS $$ SELECT lower(public.unaccent('public.unaccent'::regdictionary, value)) $$;
+
+ALTER TABLE biblios ADD COLUMN IF NOT EXISTS title_normalized TEXT
+    GENERATED ALWAYS AS (normalize_search(title)) STORED;
+
+ALTER TABLE authors ADD COLUMN IF NOT EXISTS lastname_normalized TEXT
+    GENERATED ALWAYS AS (normalize_search(lastname)) STORED;
+ALTER TABLE authors ADD COLUMN IF NOT EXISTS firstname_normalized TEXT
+    GENERATED ALWAYS AS (normalize_search(firstname)) STORED;
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS lastname_normalized TEXT
+    GENERATED ALWAYS AS (normalize_search(lastname)) STORED;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS firstname_normalized TEXT
+    GENERATED ALWAYS AS (normalize_search(firstname)) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_biblios_title_normalized ON biblios USING gin (title_normalized gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_authors_lastname_normalized ON authors USING gin (lastname_normalized gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_authors_firstname_normalized ON authors USING gin (firstname_normalized gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_users_lastname_normalized ON users USING gin (lastname_normalized gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_users_firstname_normalized ON users USING gin (firstname_normalized gin_trgm_ops);
@@ -0,0 +1,33 @@
+-- API keys: credentials for partner applications (e.g. the city's mobile app) calling public
+-- endpoints at a higher volume than anonymous clients. Each key is limited to its scopes, rate
+-- limited on its own, and its requests are counted per day and endpoint.
+
+CREATE TABLE IF NOT EXISTS api_keys (
+    id            BIGSERIAL     PRIMARY KEY,
+    name          VARCHAR(100)  NOT NULL,
+    -- SHA-256 (hex) of the key; the key itself is only shown once
+    token_hash    VARCHAR(64)   NOT NULL UNIQUE,
+    -- First characters of the key, to recognise it in the admin list
+    token_prefix  VARCHAR(16)   NOT NULL,
+    scopes        TEXT[]        NOT NULL,
+    is_active     BOOLEAN       NOT NULL DEFAULT TRUE,
+    expires_at    TIMESTAMPTZ,
+    last_used_at  TIMESTAMPTZ,
+    created_by    BIGINT        REFERENCES users(id) ON DELETE SET NULL,
+    created_at    TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    update_at     TIMESTAMPTZ,
+    CONSTRAINT api_keys_scopes_not_empty CHECK (cardinality(scopes) > 0)
+);
+
+CREATE TABLE IF NOT EXISTS api_key_usage (
+    api_key_id     BIGINT       NOT NULL REFERENCES api_keys(id) ON DELETE CASCADE,
+    day            DATE         NOT NULL,
+    -- Scope of the endpoint called (e.g. `opac.availability`)
+    endpoint       VARCHAR(64)  NOT NULL,
+    request_count  BIGINT       NOT NULL DEFAULT 0,
+    -- Records requested through the key (ISBNs for availability)
+    record_count   BIGINT       NOT NULL DEFAULT 0,
+    PRIMARY KEY (api_key_id, day, endpoint)
+);
+
+COMMENT ON COLUMN api_keys.scopes IS 'Endpoints the key may call (currently only opac.availability)';
@@ -0,0 +1,7 @@
+-- Due-soon reminders: patrons get one digest listing every loan due in N days
+-- (`reminders.due_soon_days` lead times). The stamp keeps a loan out of a second digest the same day.
+
+ALTER TABLE loans
+    ADD COLUMN IF NOT EXISTS due_soon_notified_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN loans.due_soon_notified_at IS 'Last due-soon digest listing this loan';
@@ -0,0 +1,67 @@
+-- Managed taxonomy for biblios.media_type (previously a fixed list of codes in the server, some
+-- rows still holding the terse legacy codes 'b', 'bc', 'v', ...). Each media type has a default
+-- label (translations stay in `labels`), an icon, default loan rules used when `loans_settings`
+-- has no row for it, and the MARC leader codes written for, and recognised on, its records.
+
+CREATE TABLE IF NOT EXISTS media_types (
+    code                      VARCHAR(30)  PRIMARY KEY,
+    -- Terse code used by older versions, still accepted as input
+    legacy_code               VARCHAR(5)   UNIQUE,
+    label                     VARCHAR(100) NOT NULL,
+    -- Icon name for clients (e.g. `book`, `disc`)
+    icon                      VARCHAR(64),
+    loan_duration             SMALLINT,
+    loan_nb_max               SMALLINT,
+    loan_nb_renews            SMALLINT,
+    -- MARC leader/06 (type of record) and leader/07 (bibliographic level)
+    marc_record_type          CHAR(1)      NOT NULL DEFAULT 'a',
+    marc_bibliographic_level  CHAR(1)      NOT NULL DEFAULT 'm',
+    sort_order                SMALLINT     NOT NULL DEFAULT 0,
+    created_at                TIMESTAMPTZ  DEFAULT NOW(),
+    update_at                 TIMESTAMPTZ,
+    CONSTRAINT media_types_marc_chk
+        CHECK (marc_record_type ~ '^[a-z]$' AND marc_bibliographic_level ~ '^[a-z]$')
+);
+
+INSERT INTO media_types (code, legacy_code, label, icon, marc_record_type, marc_bibliographic_level, sort_order) VALUES
+    ('printedText',       'b',   'Book',              'book',       'a', 'm', 0),
+    ('comics',            'bc',  'Comics',            'comics',     'a', 'm', 1),
+    ('periodic',          'p',   'Periodical',        'newspaper',  'a', 's', 2),
+    ('video',             'v',   'Video',             'movie',      'g', 'm', 3),
+    ('videoTape',         'vt',  'Video tape',        'videotape',  'g', 'm', 4),
+    ('videoDvd',          'vd',  'DVD',               'disc',       'g', 'm', 5),
+    ('audio',             'a',   'Audio',             'headphones', 'i', 'm', 6),
+    ('audioMusic',        'am',  'Music',             'music',      'j', 'm', 7),
+    ('audioMusicTape',    'amt', 'Music tape',        'cassette',   'j', 'm', 8),
+    ('audioMusicCd',      'amc', 'Music CD',          'disc',       'j', 'm', 9),
+    ('audioNonMusic',     'an',  'Spoken audio',      'headphones', 'i', 'm', 10),
+    ('audioNonMusicTape', 'ant', 'Spoken audio tape', 'cassette',   'i', 'm', 11),
+    ('audioNonMusicCd',   'anc', 'Spoken audio CD',   'disc',       'i', 'm', 12),
+    ('multimedia',        'm',   'Multimedia',        'package',    'o', 'm', 13),
+    ('cdRom',             'c',   'CD-ROM',            'disc',       'm', 'm', 14),
+    ('images',            'i',   'Images',            'image',      'k', 'm', 15),
+    ('unknown',           'u',   'Unknown',           NULL,         'a', 'm', 99)
+ON CONFLICT (code) DO NOTHING;
+
+-- Rewrite legacy codes to the canonical ones
+UPDATE biblios b SET media_type = mt.code FROM media_types mt WHERE b.media_type = mt.legacy_code;
+UPDATE loans_settings s SET media_type = mt.code FROM media_types mt
+WHERE s.media_type = mt.legacy_code
+  AND NOT EXISTS (SELECT 1 FROM loans_settings x WHERE x.media_type = mt.code);
+UPDATE public_type_loan_settings s SET media_type = mt.code FROM media_types mt
+WHERE s.media_type = mt.legacy_code
+  AND NOT EXISTS (
+      SELECT 1 FROM public_type_loan_settings x
+      WHERE x.public_type_id = s.public_type_id AND x.media_type = mt.code
+  );
+UPDATE item_templates t SET media_type = mt.code FROM media_types mt WHERE t.media_type = mt.legacy_code;
+
+-- Keep other values valid: register any code already present on biblios
+INSERT INTO media_types (code, label, sort_order)
+SELECT DISTINCT media_type, media_type, 100
+FROM biblios
+WHERE media_type <> ''
+ON CONFLICT (code) DO NOTHING;
+
+COMMENT ON COLUMN media_types.loan_duration IS
+    'Loan duration (days) for this media type when loans_settings has no row for it; same for loan_nb_max and loan_nb_renews.';
@@ -0,0 +1,11 @@
+-- Search-as-you-type for the OPAC search box (GET /items/suggest, GET /authors/suggest): trigram
+-- indexes limited to what may be suggested, and a normalized full name so that "victor hu" completes
+-- to "Victor Hugo". They serve both the prefix (`LIKE 'victor hu%'`) and the fuzzy (`<%`) matches.
+
+ALTER TABLE authors ADD COLUMN IF NOT EXISTS name_normalized TEXT
+    GENERATED ALWAYS AS (normalize_search(btrim(coalesce(firstname, '') || ' ' || coalesce(lastname, '')))) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_biblios_title_suggest
+    ON biblios USING gin (title_normalized gin_trgm_ops) WHERE archived_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_authors_name_suggest
+    ON authors USING gin (name_normalized gin_trgm_ops);
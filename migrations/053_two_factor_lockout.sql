@@ -0,0 +1,9 @@
+-- Brute-force protection on 2FA and recovery codes: consecutive wrong codes lock verification
+-- for an exponentially growing time.
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS two_factor_failed_attempts SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS two_factor_locked_until    TIMESTAMPTZ;
+
+COMMENT ON COLUMN users.two_factor_failed_attempts IS 'Wrong 2FA or recovery codes in a row (reset by a valid code)';
+COMMENT ON COLUMN users.two_factor_locked_until IS '2FA and recovery code verification refused until this time';
@@ -0,0 +1,23 @@
+-- Catalog snapshots: copies (items) captured before a bulk edit, so that an accidental call-number
+-- rewrite or source merge can be reverted. Rows are kept whole as JSON (`to_jsonb(items.*)`);
+-- snapshots older than the retention period or beyond the configured count are deleted.
+
+CREATE TABLE IF NOT EXISTS catalog_snapshots (
+    id           BIGSERIAL    PRIMARY KEY,
+    -- Bulk operation that took the snapshot (e.g. `call_numbers.recalculate`, `sources.merge`)
+    operation    VARCHAR(64)  NOT NULL,
+    item_count   INTEGER      NOT NULL,
+    created_by   BIGINT       REFERENCES users(id) ON DELETE SET NULL,
+    created_at   TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    restored_at  TIMESTAMPTZ,
+    restored_by  BIGINT       REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_catalog_snapshots_created_at ON catalog_snapshots (created_at DESC);
+
+CREATE TABLE IF NOT EXISTS catalog_snapshot_items (
+    snapshot_id  BIGINT  NOT NULL REFERENCES catalog_snapshots(id) ON DELETE CASCADE,
+    item_id      BIGINT  NOT NULL,
+    data         JSONB   NOT NULL,
+    PRIMARY KEY (snapshot_id, item_id)
+);
@@ -0,0 +1,19 @@
+-- Fine waivers: one row per fine written off (by staff, by an amnesty campaign over a date range,
+-- or accrued during an amnesty week), so that waived totals can be reported by reason.
+-- `fine_id` has no foreign key: the `fines` table is not created on every deployment.
+
+CREATE TABLE IF NOT EXISTS fine_waivers (
+    id         BIGSERIAL      PRIMARY KEY,
+    fine_id    BIGINT         NOT NULL,
+    user_id    BIGINT         NOT NULL,
+    -- Outstanding balance written off
+    amount     NUMERIC(10,2)  NOT NULL,
+    -- `manual` | `amnesty` | `amnestyWeek`
+    reason     VARCHAR(16)    NOT NULL,
+    -- Campaign or amnesty week name (amnesty reasons only)
+    amnesty    VARCHAR(255),
+    waived_by  BIGINT         REFERENCES users(id) ON DELETE SET NULL,
+    waived_at  TIMESTAMPTZ    NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_fine_waivers_waived_at ON fine_waivers (waived_at);
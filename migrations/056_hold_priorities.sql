@@ -0,0 +1,22 @@
+-- Hold priorities: teachers preparing classes and staff needs can be served ahead of the regular queue.
+-- A priority hold enters the queue of the copy according to `holds.priority_order`; placing one
+-- needs the per-account-type `priority_holds_rights`.
+
+ALTER TABLE holds
+    ADD COLUMN IF NOT EXISTS priority VARCHAR(16) NOT NULL DEFAULT 'normal';
+
+COMMENT ON COLUMN holds.priority IS 'normal / staff / teaching';
+
+ALTER TABLE account_types
+    ADD COLUMN IF NOT EXISTS priority_holds_rights VARCHAR(1);
+
+UPDATE account_types
+SET priority_holds_rights = 'w'
+WHERE code IN ('librarian', 'admin');
+
+UPDATE account_types
+SET priority_holds_rights = 'n'
+WHERE priority_holds_rights IS NULL;
+
+COMMENT ON COLUMN account_types.priority_holds_rights IS
+    'n/w: whether holds can be placed with a staff or teaching priority';
@@ -0,0 +1,27 @@
+-- Purchase suggestions: patrons suggest titles the library does not own. Staff triage each
+-- suggestion (ordered, rejected with a reason, added); once the title is in the catalog with a
+-- copy, the patron is notified by email and `notified_at` is stamped.
+
+CREATE TABLE IF NOT EXISTS purchase_suggestions (
+    id                BIGSERIAL     PRIMARY KEY,
+    user_id           BIGINT        NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Normalized ISBN (digits and X only)
+    isbn              VARCHAR(20),
+    title             VARCHAR(500)  NOT NULL,
+    author            VARCHAR(500),
+    notes             TEXT,
+    status            VARCHAR(20)   NOT NULL DEFAULT 'pending'
+                      CHECK (status IN ('pending', 'ordered', 'rejected', 'added')),
+    rejection_reason  TEXT,
+    -- Acquisitions order the title was placed on
+    purchase_order_id BIGINT        REFERENCES purchase_orders(id) ON DELETE SET NULL,
+    -- Catalog record of the title once acquired
+    biblio_id         BIGINT        REFERENCES biblios(id) ON DELETE SET NULL,
+    triaged_by        BIGINT        REFERENCES users(id) ON DELETE SET NULL,
+    triaged_at        TIMESTAMPTZ,
+    notified_at       TIMESTAMPTZ,
+    created_at        TIMESTAMPTZ   DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_purchase_suggestions_user_id ON purchase_suggestions(user_id);
+CREATE INDEX IF NOT EXISTS idx_purchase_suggestions_status ON purchase_suggestions(status);
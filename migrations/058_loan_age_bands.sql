@@ -0,0 +1,43 @@
+-- Borrower age band captured on each loan at checkout, so loan statistics by age keep working
+-- once the borrower is anonymized (birthdate erased)
+
+CREATE OR REPLACE FUNCTION loan_age_band(birthdate DATE, at TIMESTAMPTZ) RETURNS VARCHAR AS $$
+    SELECT CASE
+        WHEN birthdate IS NULL OR at IS NULL THEN NULL
+        WHEN EXTRACT(YEAR FROM AGE(at::date, birthdate)) < 15 THEN '0-14'
+        WHEN EXTRACT(YEAR FROM AGE(at::date, birthdate)) < 25 THEN '15-24'
+        WHEN EXTRACT(YEAR FROM AGE(at::date, birthdate)) < 45 THEN '25-44'
+        WHEN EXTRACT(YEAR FROM AGE(at::date, birthdate)) < 65 THEN '45-64'
+        ELSE '65+'
+    END
+$$ LANGUAGE sql STABLE;
+
+ALTER TABLE loans ADD COLUMN IF NOT EXISTS borrower_age_band VARCHAR(8);
+ALTER TABLE loans_archives ADD COLUMN IF NOT EXISTS borrower_age_band VARCHAR(8);
+
+COMMENT ON COLUMN loans.borrower_age_band IS 'Borrower age band at loan time (0-14, 15-24, 25-44, 45-64, 65+)';
+COMMENT ON COLUMN loans_archives.borrower_age_band IS 'Borrower age band at loan time, copied from the loan';
+
+-- Loans recorded before this migration, while the birthdates are still known
+UPDATE loans l SET borrower_age_band = loan_age_band(u.birthdate, l.date)
+FROM users u
+WHERE u.id = l.user_id AND l.borrower_age_band IS NULL;
+
+UPDATE loans_archives a SET borrower_age_band = loan_age_band(u.birthdate, a.date)
+FROM users u
+WHERE u.id = a.user_id AND a.borrower_age_band IS NULL;
+
+-- Every checkout path (desk, bundles, batches, kiosks) inserts into loans
+CREATE OR REPLACE FUNCTION loans_capture_age_band() RETURNS trigger AS $$
+BEGIN
+    IF NEW.borrower_age_band IS NULL THEN
+        NEW.borrower_age_band := (SELECT loan_age_band(u.birthdate, NEW.date) FROM users u WHERE u.id = NEW.user_id);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_loans_age_band ON loans;
+CREATE TRIGGER trg_loans_age_band
+    BEFORE INSERT ON loans
+    FOR EACH ROW EXECUTE FUNCTION loans_capture_age_band();
@@ -0,0 +1,9 @@
+-- Per-server trust weight used to rank merged Z39.50 search results
+-- (1 = neutral, higher ranks the server's records first, 0 sends them last)
+
+ALTER TABLE z3950servers ADD COLUMN IF NOT EXISTS trust_weight DOUBLE PRECISION NOT NULL DEFAULT 1.0;
+
+ALTER TABLE z3950servers DROP CONSTRAINT IF EXISTS z3950servers_trust_weight_check;
+ALTER TABLE z3950servers ADD CONSTRAINT z3950servers_trust_weight_check CHECK (trust_weight >= 0);
+
+COMMENT ON COLUMN z3950servers.trust_weight IS 'Ranking weight of this server''s records in merged search results (default 1)';
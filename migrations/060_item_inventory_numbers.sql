@@ -0,0 +1,35 @@
+-- Inventory register: per-year sequential inventory numbers of copies (registre d'inventaire)
+
+-- Last inventory number issued per year; incremented in the transaction creating the copy,
+-- so a rolled back creation does not consume a number
+CREATE TABLE IF NOT EXISTS item_inventory_counters (
+    year      SMALLINT PRIMARY KEY,
+    last_seq  INTEGER  NOT NULL
+);
+
+ALTER TABLE items ADD COLUMN IF NOT EXISTS inventory_year SMALLINT;
+ALTER TABLE items ADD COLUMN IF NOT EXISTS inventory_seq INTEGER;
+
+-- Number copies created before this migration in chronological order
+WITH numbered AS (
+    SELECT id,
+           EXTRACT(YEAR FROM COALESCE(created_at, NOW()))::smallint AS year,
+           ROW_NUMBER() OVER (PARTITION BY EXTRACT(YEAR FROM COALESCE(created_at, NOW())) ORDER BY created_at, id) AS seq
+    FROM items
+    WHERE inventory_seq IS NULL
+)
+UPDATE items i SET inventory_year = n.year, inventory_seq = n.seq
+FROM numbered n
+WHERE n.id = i.id;
+
+INSERT INTO item_inventory_counters (year, last_seq)
+SELECT inventory_year, MAX(inventory_seq) FROM items WHERE inventory_year IS NOT NULL GROUP BY inventory_year
+ON CONFLICT (year) DO UPDATE SET last_seq = GREATEST(item_inventory_counters.last_seq, EXCLUDED.last_seq);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_items_inventory ON items (inventory_year, inventory_seq);
+
+-- Number as printed in the register and on slips and labels (`2026-000042`)
+ALTER TABLE items ADD COLUMN IF NOT EXISTS inventory_number TEXT GENERATED ALWAYS AS (
+    inventory_year::text || '-' || lpad(inventory_seq::text, GREATEST(6, length(inventory_seq::text)), '0')
+) STORED;
+CREATE INDEX IF NOT EXISTS idx_items_inventory_number ON items (inventory_number);
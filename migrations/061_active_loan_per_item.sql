@@ -0,0 +1,17 @@
+-- At most one active loan per copy: two desks checking out the same copy at the same time
+-- can no longer both succeed (the loan transaction also locks the copy row)
+
+-- Close duplicate active loans left by earlier races, keeping the most recent checkout
+UPDATE loans l
+SET returned_at = d.next_date
+FROM (
+    SELECT id,
+           LEAD(date) OVER (PARTITION BY item_id ORDER BY date, id) AS next_date,
+           ROW_NUMBER() OVER (PARTITION BY item_id ORDER BY date DESC, id DESC) AS rn
+    FROM loans
+    WHERE item_id IS NOT NULL AND returned_at IS NULL
+) d
+WHERE l.id = d.id AND d.rn > 1;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_loans_active_item
+    ON loans(item_id) WHERE returned_at IS NULL AND item_id IS NOT NULL;
@@ -0,0 +1,20 @@
+-- Personal dashboards: each user's homepage widgets, each one showing the result of a
+-- statistics endpoint (or a saved query / report) with stored parameters
+
+CREATE TABLE IF NOT EXISTS dashboard_widgets (
+    id           BIGSERIAL     PRIMARY KEY,
+    user_id      BIGINT        NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Display order on the dashboard
+    position     INTEGER       NOT NULL,
+    -- `stats` | `loanStats` | `userStats` | `catalogStats` | `visitorStats` | `savedQuery` | `savedReport`
+    widget_type  VARCHAR(32)   NOT NULL,
+    title        VARCHAR(200),
+    -- Query parameters of the statistics endpoint (`{ "id": ... }` for saved queries and reports)
+    params       JSONB         NOT NULL DEFAULT '{}'::jsonb,
+    -- Placement on the client grid, not interpreted by the server
+    layout       JSONB,
+    created_at   TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    update_at    TIMESTAMPTZ   NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_dashboard_widgets_user ON dashboard_widgets(user_id, position);
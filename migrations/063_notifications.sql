@@ -0,0 +1,21 @@
+-- In-app notifications inbox: one row per recipient, written by the same events that send
+-- emails (background job finished, import report ready, overdue escalation)
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id           BIGSERIAL     PRIMARY KEY,
+    user_id      BIGINT        NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- `taskCompleted` | `taskFailed` | `importReportReady` | `overdueEscalation`
+    kind         VARCHAR(32)   NOT NULL,
+    title        VARCHAR(300)  NOT NULL,
+    body         TEXT,
+    -- What the notification links to (`task`, `artifact`, `loan`, …) and its id
+    entity_type  VARCHAR(32),
+    entity_id    BIGINT,
+    -- Kind-specific details for the client (counts, file name, loan ids, …)
+    data         JSONB,
+    created_at   TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    read_at      TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user ON notifications(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_notifications_unread ON notifications(user_id) WHERE read_at IS NULL;
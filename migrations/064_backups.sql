@@ -0,0 +1,33 @@
+-- Logical database backups started from the admin API (`POST /admin/backup`). The file itself
+-- lives in the artifacts store or in an S3 bucket; this table keeps the history and sizes.
+
+CREATE TABLE IF NOT EXISTS backups (
+    id              BIGSERIAL     PRIMARY KEY,
+    -- running | succeeded | failed
+    status          VARCHAR(20)   NOT NULL DEFAULT 'running',
+    -- artifacts | s3
+    destination     VARCHAR(20)   NOT NULL,
+    filename        VARCHAR(255)  NOT NULL,
+    -- Artifact holding the file (destination `artifacts`), cleared when the artifact expires
+    artifact_id     BIGINT        REFERENCES artifacts(id) ON DELETE SET NULL,
+    -- `s3://bucket/key` of the uploaded file (destination `s3`)
+    location        TEXT,
+    size_bytes      BIGINT,
+    -- SHA-256 of the compressed file
+    sha256          VARCHAR(64),
+    -- Last migration applied when the backup was taken
+    schema_version  BIGINT,
+    table_count     INTEGER,
+    row_count       BIGINT,
+    -- Redis key counts per prefix at backup time (Redis itself is not backed up)
+    redis_notes     JSONB,
+    created_by      BIGINT        REFERENCES users(id) ON DELETE SET NULL,
+    started_at      TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    finished_at     TIMESTAMPTZ,
+    error           TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_backups_started_at ON backups (started_at DESC);
+
+COMMENT ON COLUMN artifacts.kind IS
+    'catalog_export | audit_export | loans_export | import_report | processing_slip | backup';
@@ -0,0 +1,18 @@
+-- Currency handling of copy prices (`[currency]` in the configuration).
+
+-- VAT rate (percent) included in the prices of this media type, for acquisition reporting
+ALTER TABLE media_types ADD COLUMN IF NOT EXISTS vat_rate NUMERIC(5, 2);
+ALTER TABLE media_types DROP CONSTRAINT IF EXISTS media_types_vat_rate_chk;
+ALTER TABLE media_types ADD CONSTRAINT media_types_vat_rate_chk
+    CHECK (vat_rate IS NULL OR (vat_rate >= 0 AND vat_rate < 100));
+
+-- Prices are now stored as plain decimals (`12.50`). Rewrite the simple legacy values
+-- (`12,50`, `12.5 €`, `15 EUR`); free text (`env. 15`, `gratuit`) is kept as it is.
+UPDATE items
+SET price = replace(regexp_replace(price, '\s|€|EUR|eur', '', 'g'), ',', '.')
+WHERE price ~ '^\s*[0-9]+([.,][0-9]{1,2})?\s*(€|EUR|eur)?\s*$';
+
+UPDATE items SET price = NULL WHERE btrim(price) = '';
+
+COMMENT ON COLUMN items.price IS
+    'Price including VAT, canonical decimal (12.50); older free-text values are kept as entered';
@@ -0,0 +1,57 @@
+-- Shelving map: managed locations site → room → shelf range (`/settings/locations`).
+-- A site stands for a place of the library (`items.place`); rooms and shelf ranges share the
+-- place of their site, so floating rules, transits and per-place reports keep working on `place`.
+
+CREATE TABLE IF NOT EXISTS shelving_locations (
+    id          BIGSERIAL     PRIMARY KEY,
+    parent_id   BIGINT        REFERENCES shelving_locations(id),
+    -- site | room | shelfRange
+    kind        VARCHAR(20)   NOT NULL,
+    -- Matched against the first word of call numbers by the mapping assistant
+    code        VARCHAR(30),
+    name        VARCHAR(100)  NOT NULL,
+    place       SMALLINT      NOT NULL,
+    sort_order  SMALLINT      NOT NULL DEFAULT 50,
+    created_at  TIMESTAMPTZ   DEFAULT NOW(),
+    update_at   TIMESTAMPTZ,
+    CONSTRAINT shelving_locations_kind_chk CHECK (kind IN ('site', 'room', 'shelfRange')),
+    CONSTRAINT shelving_locations_parent_chk CHECK ((kind = 'site') = (parent_id IS NULL))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_shelving_locations_site_place
+    ON shelving_locations (place) WHERE kind = 'site';
+CREATE INDEX IF NOT EXISTS idx_shelving_locations_parent ON shelving_locations (parent_id);
+
+-- Every location with its ancestors (site first) and its display path. Three levels at most,
+-- so the recursion stays cheap enough to be joined by search, pull lists and inventory.
+CREATE OR REPLACE VIEW shelving_location_paths AS
+WITH RECURSIVE tree AS (
+    SELECT l.id, ARRAY[l.id] AS ancestor_ids, l.name::text AS path,
+           ARRAY[l.sort_order::bigint, l.id] AS sort_key
+    FROM shelving_locations l
+    WHERE l.parent_id IS NULL
+    UNION ALL
+    SELECT l.id, t.ancestor_ids || l.id, t.path || ' › ' || l.name,
+           t.sort_key || ARRAY[l.sort_order::bigint, l.id]
+    FROM shelving_locations l
+    JOIN tree t ON l.parent_id = t.id
+)
+SELECT id, ancestor_ids, path, sort_key FROM tree;
+
+ALTER TABLE items ADD COLUMN IF NOT EXISTS location_id BIGINT REFERENCES shelving_locations(id);
+CREATE INDEX IF NOT EXISTS idx_items_location_id ON items (location_id) WHERE location_id IS NOT NULL;
+
+COMMENT ON COLUMN items.location_id IS
+    'Shelving location (shelving_locations); items.place is kept equal to the place of its site';
+
+-- Inventory sessions limited to a part of the shelving map (the location and everything under it)
+ALTER TABLE inventory_sessions ADD COLUMN IF NOT EXISTS scope_location_id BIGINT
+    REFERENCES shelving_locations(id) ON DELETE SET NULL;
+
+-- One site per place already used by copies, so that the mapping assistant has somewhere to
+-- start from; staff rename them and add rooms and shelf ranges.
+INSERT INTO shelving_locations (kind, name, place)
+SELECT DISTINCT 'site', 'Place ' || place, place
+FROM items
+WHERE place IS NOT NULL
+ON CONFLICT DO NOTHING;
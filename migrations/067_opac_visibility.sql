@@ -0,0 +1,32 @@
+-- OPAC visibility derived from item states: copies in a state that is not `opac_visible`
+-- (on order, in processing...) stay in staff search but are hidden from the public catalog,
+-- public availability, the Z39.50 target and the new acquisitions feed. A record whose active
+-- copies are all hidden is hidden from the OPAC altogether.
+
+ALTER TABLE item_states ADD COLUMN IF NOT EXISTS opac_visible BOOLEAN NOT NULL DEFAULT TRUE;
+
+INSERT INTO item_states (code, label, blocks_circulation, color, sort_order, opac_visible) VALUES
+    (5, 'On order',      TRUE, '#1565C0', 5, FALSE),
+    (6, 'In processing', TRUE, '#00838F', 6, FALSE)
+ON CONFLICT (code) DO NOTHING;
+
+COMMENT ON COLUMN item_states.opac_visible IS
+    'When false, copies in this state are hidden from the OPAC (search, record, availability, Z39.50).';
+
+-- Copies without a state are visible.
+CREATE OR REPLACE FUNCTION item_opac_visible(status SMALLINT) RETURNS BOOLEAN
+    LANGUAGE sql STABLE PARALLEL SAFE
+    AS $$ SELECT status IS NULL OR COALESCE((SELECT opac_visible FROM item_states WHERE code = status), TRUE) $$;
+
+-- A record is hidden when it has active copies and none of them is visible.
+-- Records without active copies (electronic resources, bare notices) are left to the other filters.
+CREATE OR REPLACE FUNCTION biblio_opac_hidden(biblio BIGINT) RETURNS BOOLEAN
+    LANGUAGE sql STABLE PARALLEL SAFE
+    AS $$
+        SELECT EXISTS (SELECT 1 FROM items i WHERE i.biblio_id = biblio AND i.archived_at IS NULL)
+           AND NOT EXISTS (
+               SELECT 1 FROM items i
+               WHERE i.biblio_id = biblio AND i.archived_at IS NULL
+                 AND item_opac_visible(i.circulation_status)
+           )
+    $$;
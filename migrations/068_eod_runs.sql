@@ -0,0 +1,39 @@
+-- End-of-day job (`[eod]`, `/admin/eod-runs`): closes the circulation day after opening hours.
+-- Each run archives returned loans left in `loans`, finalizes the day's statistics snapshot,
+-- prepares the hold pull list for the next morning and emails a digest to the staff.
+
+-- One row per business day, written (and rewritten by a new run) by the end-of-day job
+CREATE TABLE IF NOT EXISTS daily_stats (
+    day              DATE          PRIMARY KEY,
+    loans            INTEGER       NOT NULL DEFAULT 0,
+    returns          INTEGER       NOT NULL DEFAULT 0,
+    new_users        INTEGER       NOT NULL DEFAULT 0,
+    holds_placed     INTEGER       NOT NULL DEFAULT 0,
+    -- Circulation state at closing time
+    active_loans     INTEGER       NOT NULL DEFAULT 0,
+    overdue_loans    INTEGER       NOT NULL DEFAULT 0,
+    computed_at      TIMESTAMPTZ   NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS eod_runs (
+    id                     BIGSERIAL     PRIMARY KEY,
+    business_day           DATE          NOT NULL,
+    -- running | succeeded | partial | failed
+    status                 VARCHAR(20)   NOT NULL DEFAULT 'running',
+    -- scheduled | manual
+    trigger                VARCHAR(20)   NOT NULL,
+    triggered_by           BIGINT        REFERENCES users(id) ON DELETE SET NULL,
+    -- Outcome of each step, in order: [{ "step", "status", "count", "message" }]
+    steps                  JSONB         NOT NULL DEFAULT '[]'::jsonb,
+    archived_loans         INTEGER,
+    pull_list_entries      INTEGER,
+    pull_list_artifact_id  BIGINT        REFERENCES artifacts(id) ON DELETE SET NULL,
+    digest_recipients      INTEGER,
+    started_at             TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    finished_at            TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_eod_runs_started_at ON eod_runs (started_at DESC);
+
+COMMENT ON COLUMN artifacts.kind IS
+    'catalog_export | audit_export | loans_export | import_report | processing_slip | backup | pull_list';
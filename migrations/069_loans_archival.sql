@@ -0,0 +1,36 @@
+-- Loans archival policy: a returned loan moves from `loans` to `loans_archives` on return, or
+-- `circulation.archive_returned_after_days` days later through the nightly archival job. Until
+-- then it stays in `loans` with its `returned_at` set.
+--
+-- `loans_all` is every loan, active, returned or archived, with the same columns whichever table
+-- holds it. History, statistics and exports read it instead of stacking both tables by hand.
+-- Borrower columns of rows still in `loans` are the borrower's current ones, as archival copies.
+-- `id` is only unique together with `archived` (each table has its own sequence).
+
+CREATE OR REPLACE VIEW loans_all AS
+    SELECT l.id, FALSE AS archived, l.user_id, l.item_id, l.equipment_id,
+           l.date, l.renew_at, l.nb_renews, l.expiry_at, l.returned_at, l.notes,
+           l.batch_id, l.bundle_id, l.borrower_age_band,
+           u.public_type AS borrower_public_type, u.addr_city, u.account_type
+    FROM loans l
+    LEFT JOIN users u ON u.id = l.user_id
+    UNION ALL
+    SELECT a.id, TRUE AS archived, a.user_id, a.item_id, a.equipment_id,
+           a.date, NULL::timestamptz AS renew_at, a.nb_renews, a.expiry_at, a.returned_at, a.notes,
+           a.batch_id, a.bundle_id, a.borrower_age_band,
+           a.borrower_public_type, a.addr_city, a.account_type
+    FROM loans_archives a;
+
+COMMENT ON VIEW loans_all IS
+    'Active, returned and archived loans (loans UNION ALL loans_archives); key (archived, id).';
+
+-- Archival job: returned loans still in `loans`
+CREATE INDEX IF NOT EXISTS idx_loans_returned ON loans(returned_at) WHERE returned_at IS NOT NULL;
+
+-- Statistics over periods: loans started, returns, active borrowers
+CREATE INDEX IF NOT EXISTS idx_loans_date                  ON loans(date);
+CREATE INDEX IF NOT EXISTS idx_loans_archives_date         ON loans_archives(date);
+CREATE INDEX IF NOT EXISTS idx_loans_archives_returned_at  ON loans_archives(returned_at);
+CREATE INDEX IF NOT EXISTS idx_loans_archives_user_date    ON loans_archives(user_id, date);
+CREATE INDEX IF NOT EXISTS idx_loans_archives_public_type  ON loans_archives(borrower_public_type)
+    WHERE borrower_public_type IS NOT NULL;
@@ -0,0 +1,23 @@
+-- Re-engagement emails: members who stopped borrowing (`reengagement.inactive_days`) and opted in
+-- to campaigns receive the new acquisitions in the media types they borrowed most. One row per
+-- email; the loans a member makes afterwards measure the effectiveness (`/stats/reengagement`).
+
+CREATE TABLE IF NOT EXISTS reengagement_emails (
+    id            BIGSERIAL    PRIMARY KEY,
+    user_id       BIGINT       REFERENCES users(id) ON DELETE SET NULL,
+    -- Last loan of the member when the email was composed
+    last_loan_at  TIMESTAMPTZ,
+    -- Media type codes the suggestions were picked from, most borrowed first
+    media_types   TEXT[]       NOT NULL DEFAULT '{}',
+    -- Suggested biblios, in the order of the email
+    biblio_ids    BIGINT[]     NOT NULL DEFAULT '{}',
+    -- `sending` while claimed by a run, then `sent` or `failed` (SMTP error)
+    status        VARCHAR(16)  NOT NULL DEFAULT 'sending',
+    error         TEXT,
+    created_at    TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    sent_at       TIMESTAMPTZ,
+    CONSTRAINT reengagement_emails_status_chk CHECK (status IN ('sending', 'sent', 'failed'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_reengagement_emails_user ON reengagement_emails (user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_reengagement_emails_sent ON reengagement_emails (sent_at) WHERE status = 'sent';
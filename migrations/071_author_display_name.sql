@@ -0,0 +1,5 @@
+-- Preferred display form of an author ("J. R. R. Tolkien"), set by cataloguers. Lists, exports
+-- and emails show it instead of the "lastname firstname" composition when set; search keeps
+-- matching on lastname / firstname.
+
+ALTER TABLE authors ADD COLUMN IF NOT EXISTS display_name VARCHAR(255);
@@ -0,0 +1,126 @@
+-- Managed vocabulary for biblio_authors.function (previously whatever the MARC translator made
+-- of the relator: unknown terms such as "director" or "illustrateur" fell back to 'author').
+-- Each relator has a camelCase code stored on biblio_authors, its MARC 21 and UNIMARC relator
+-- codes, a default label (translations stay in `labels`) and free-text aliases recognised on
+-- import ($e terms, cataloguers' spellings).
+
+CREATE TABLE IF NOT EXISTS relators (
+    code          VARCHAR(50)  PRIMARY KEY,
+    -- MARC 21 relator code ($4)
+    marc_code     CHAR(3)      NOT NULL UNIQUE,
+    -- UNIMARC relator code ($4 of 7XX)
+    unimarc_code  CHAR(3)      UNIQUE,
+    label         VARCHAR(100) NOT NULL,
+    -- Other terms resolving to this relator, compared case- and accent-insensitively
+    aliases       TEXT[]       NOT NULL DEFAULT '{}',
+    sort_order    SMALLINT     NOT NULL DEFAULT 0,
+    created_at    TIMESTAMPTZ  DEFAULT NOW(),
+    update_at     TIMESTAMPTZ,
+    CONSTRAINT relators_marc_code_chk CHECK (marc_code ~ '^[a-z]{3}$'),
+    CONSTRAINT relators_unimarc_code_chk CHECK (unimarc_code ~ '^[0-9]{3}$')
+);
+
+INSERT INTO relators (code, marc_code, unimarc_code, label, aliases, sort_order) VALUES
+    ('author',             'aut', '070', 'Author',              '{auteur,writer,ecrivain,"auteur du texte"}', 0),
+    ('illustrator',        'ill', '440', 'Illustrator',         '{illustrateur,illustratrice,illustrations}', 1),
+    ('translator',         'trl', '730', 'Translator',          '{traducteur,traductrice,traduction}', 2),
+    ('scientificAdvisor',  'edt', '340', 'Editor',              '{editor,"editeur scientifique","scientific advisor","directeur de publication scientifique"}', 3),
+    ('prefaceWriter',      'aui', '080', 'Preface writer',      '{aft,wpr,"author of introduction","author of afterword",prefacier,preface,postface}', 4),
+    ('photographer',       'pht', '600', 'Photographer',        '{photographe,photographies}', 5),
+    ('publishingDirector', 'pbd', '651', 'Publishing director', '{publisher,pbl,"directeur de publication","directrice de publication"}', 6),
+    ('composer',           'cmp', '230', 'Composer',            '{compositeur,compositrice,musique}', 7),
+    ('director',           'drt', '300', 'Director',            '{"film director",realisateur,realisatrice,realisation,"metteur en scene"}', 8),
+    ('screenwriter',       'aus', '690', 'Screenwriter',        '{scenariste,scenario,"author of screenplay"}', 9),
+    ('actor',              'act', '005', 'Actor',               '{acteur,actrice,interprete}', 10),
+    ('narrator',           'nrt', '550', 'Narrator',            '{narrateur,narratrice,lecteur,lectrice,"read by",voix}', 11),
+    ('performer',          'prf', '590', 'Performer',           '{musician,musicien,musicienne,chanteur,chanteuse,singer}', 12),
+    ('lyricist',           'lyr', '520', 'Lyricist',            '{parolier,paroliere,paroles}', 13),
+    ('adapter',            'adp', '010', 'Adapter',             '{adaptateur,adaptatrice,adaptation}', 14),
+    ('colorist',           'clr', NULL,  'Colorist',            '{coloriste,couleurs,colourist}', 15),
+    ('compiler',           'com', '220', 'Compiler',            '{compilateur,compilatrice,anthologiste}', 16),
+    ('commentator',        'cwt', '210', 'Commentator',         '{commentateur,commentatrice,annotateur}', 17),
+    ('producer',           'pro', '630', 'Producer',            '{producteur,productrice,production}', 18),
+    ('contributor',        'ctb', '205', 'Contributor',         '{collaborateur,collaboratrice,contribution}', 19)
+ON CONFLICT (code) DO NOTHING;
+
+COMMENT ON COLUMN relators.code IS 'Value stored in biblio_authors.function (camelCase)';
+
+-- Code of the relator a term designates: its code, MARC 21 or UNIMARC code, label or an alias
+-- (ignoring case, accents and the punctuation ending $e terms). NULL when none matches.
+CREATE OR REPLACE FUNCTION resolve_relator(term TEXT) RETURNS VARCHAR
+    LANGUAGE sql STABLE PARALLEL SAFE STRICT
+    AS $$
+        WITH t AS (SELECT normalize_search(btrim(term, ' .,;:()[]')) AS value)
+        SELECT r.code
+        FROM relators r, t
+        WHERE lower(r.code) = t.value
+           OR r.marc_code = t.value
+           OR r.unimarc_code = t.value
+           OR normalize_search(r.label) = t.value
+           OR EXISTS (SELECT 1 FROM unnest(r.aliases) a WHERE normalize_search(a) = t.value)
+        ORDER BY (lower(r.code) = t.value) DESC, (r.marc_code = t.value) DESC, r.sort_order, r.code
+        LIMIT 1
+    $$;
+
+-- Rewrite existing functions to the canonical codes (unknown terms were already stored as
+-- 'author' by the translator), skipping rows whose canonical pair already exists
+UPDATE biblio_authors ba SET function = resolve_relator(ba.function)
+WHERE ba.function IS NOT NULL
+  AND resolve_relator(ba.function) IS NOT NULL
+  AND resolve_relator(ba.function) <> ba.function
+  AND NOT EXISTS (
+      SELECT 1 FROM biblio_authors x
+      WHERE x.biblio_id = ba.biblio_id AND x.author_id = ba.author_id
+        AND x.function = resolve_relator(ba.function)
+  );
+
+CREATE INDEX IF NOT EXISTS idx_biblio_authors_function ON biblio_authors (function)
+    WHERE function IS NOT NULL;
+
+-- Display labels
+ALTER TABLE labels DROP CONSTRAINT IF EXISTS labels_kind_check;
+ALTER TABLE labels ADD CONSTRAINT labels_kind_check
+    CHECK (kind IN ('mediaType', 'audienceType', 'readingLevel', 'publicType', 'genre', 'accountType', 'relator'));
+
+INSERT INTO labels (kind, code, lang, label) VALUES
+    ('relator', 'author',             'en', 'Author'),
+    ('relator', 'author',             'fr', 'Auteur'),
+    ('relator', 'illustrator',        'en', 'Illustrator'),
+    ('relator', 'illustrator',        'fr', 'Illustrateur'),
+    ('relator', 'translator',         'en', 'Translator'),
+    ('relator', 'translator',         'fr', 'Traducteur'),
+    ('relator', 'scientificAdvisor',  'en', 'Editor'),
+    ('relator', 'scientificAdvisor',  'fr', 'Éditeur scientifique'),
+    ('relator', 'prefaceWriter',      'en', 'Preface writer'),
+    ('relator', 'prefaceWriter',      'fr', 'Préfacier'),
+    ('relator', 'photographer',       'en', 'Photographer'),
+    ('relator', 'photographer',       'fr', 'Photographe'),
+    ('relator', 'publishingDirector', 'en', 'Publishing director'),
+    ('relator', 'publishingDirector', 'fr', 'Directeur de publication'),
+    ('relator', 'composer',           'en', 'Composer'),
+    ('relator', 'composer',           'fr', 'Compositeur'),
+    ('relator', 'director',           'en', 'Director'),
+    ('relator', 'director',           'fr', 'Réalisateur'),
+    ('relator', 'screenwriter',       'en', 'Screenwriter'),
+    ('relator', 'screenwriter',       'fr', 'Scénariste'),
+    ('relator', 'actor',              'en', 'Actor'),
+    ('relator', 'actor',              'fr', 'Acteur'),
+    ('relator', 'narrator',           'en', 'Narrator'),
+    ('relator', 'narrator',           'fr', 'Narrateur'),
+    ('relator', 'performer',          'en', 'Performer'),
+    ('relator', 'performer',          'fr', 'Interprète'),
+    ('relator', 'lyricist',           'en', 'Lyricist'),
+    ('relator', 'lyricist',           'fr', 'Parolier'),
+    ('relator', 'adapter',            'en', 'Adapter'),
+    ('relator', 'adapter',            'fr', 'Adaptateur'),
+    ('relator', 'colorist',           'en', 'Colorist'),
+    ('relator', 'colorist',           'fr', 'Coloriste'),
+    ('relator', 'compiler',           'en', 'Compiler'),
+    ('relator', 'compiler',           'fr', 'Compilateur'),
+    ('relator', 'commentator',        'en', 'Commentator'),
+    ('relator', 'commentator',        'fr', 'Commentateur'),
+    ('relator', 'producer',           'en', 'Producer'),
+    ('relator', 'producer',           'fr', 'Producteur'),
+    ('relator', 'contributor',        'en', 'Contributor'),
+    ('relator', 'contributor',        'fr', 'Collaborateur')
+ON CONFLICT (kind, code, lang) DO NOTHING;
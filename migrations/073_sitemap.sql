@@ -0,0 +1,19 @@
+-- OPAC sitemap (`GET /sitemap.xml`): generated by a background job (`[sitemap]`) and stored here so
+-- every instance serves the same files. A run writes its files under a new generation and
+-- publishes them at once; the files of the previous generation are then deleted.
+
+CREATE SEQUENCE IF NOT EXISTS sitemap_generation_seq;
+
+CREATE TABLE IF NOT EXISTS sitemap_files (
+    generation    BIGINT       NOT NULL,
+    -- `sitemap.xml` (single file or index), `sitemap-1.xml`... (shards of a large catalog)
+    name          VARCHAR(50)  NOT NULL,
+    content       TEXT         NOT NULL,
+    -- Record URLs in the file (shards referenced by an index for `sitemap.xml` when sharded)
+    url_count     INTEGER      NOT NULL,
+    published     BOOLEAN      NOT NULL DEFAULT FALSE,
+    generated_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (generation, name)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_sitemap_files_published ON sitemap_files (name) WHERE published;
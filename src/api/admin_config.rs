@@ -11,6 +11,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use utoipa::ToSchema;
+use validator::Validate;
 
 use crate::{
     error::{AppError, AppResult},
@@ -18,13 +19,13 @@ use crate::{
     AppState,
 };
 
-use super::{AuthenticatedUser, ClientIp};
+use super::{AuthenticatedUser, ClientIp, ValidatedJson};
 
 /// A single config section with its current value and override status
 #[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfigSectionInfo {
-    /// Section key (e.g. "email", "logging", "reminders", "audit", "holds")
+    /// Section key (e.g. "email", "logging", "reminders", "audit", "holds", "lockers")
     pub key: String,
     /// Current effective value (merged file + DB override)
     pub value: Value,
@@ -35,22 +36,23 @@ pub struct ConfigSectionInfo {
 }
 
 /// Response for GET /admin/config
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct ConfigResponse {
     pub sections: Vec<ConfigSectionInfo>,
 }
 
 /// Request body for PUT /admin/config/:section
-#[derive(Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateConfigSectionRequest {
     /// The new JSON value for the section
     pub value: Value,
 }
 
 /// Request body for POST /admin/config/email/test
-#[derive(Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
 pub struct TestEmailRequest {
     /// Recipient email address for the test
+    #[validate(email(message = "Invalid email format"))]
     pub to: String,
 }
 
@@ -109,7 +111,8 @@ pub async fn get_config(
         (status = 200, description = "Updated config section", body = ConfigSectionInfo),
         (status = 400, description = "Validation error"),
         (status = 403, description = "Admin privileges required"),
-        (status = 404, description = "Unknown section")
+        (status = 404, description = "Unknown section"),
+        (status = 422, description = "Missing `value`", body = InvalidFieldsResponse)
     )
 )]
 pub async fn update_config_section(
@@ -117,7 +120,7 @@ pub async fn update_config_section(
     AuthenticatedUser(claims): AuthenticatedUser,
     ClientIp(ip): ClientIp,
     Path(section): Path<String>,
-    Json(body): Json<UpdateConfigSectionRequest>,
+    ValidatedJson(body): ValidatedJson<UpdateConfigSectionRequest>,
 ) -> AppResult<Json<ConfigSectionInfo>> {
     claims.require_admin()?;
 
@@ -242,14 +245,15 @@ pub async fn reset_config_section(
     responses(
         (status = 200, description = "Test email sent"),
         (status = 400, description = "Invalid request or SMTP error"),
-        (status = 403, description = "Admin privileges required")
+        (status = 403, description = "Admin privileges required"),
+        (status = 422, description = "Invalid recipient address", body = InvalidFieldsResponse)
     )
 )]
 pub async fn test_email(
     State(state): State<AppState>,
     AuthenticatedUser(claims): AuthenticatedUser,
     ClientIp(ip): ClientIp,
-    Json(body): Json<TestEmailRequest>,
+    ValidatedJson(body): ValidatedJson<TestEmailRequest>,
 ) -> AppResult<StatusCode> {
     claims.require_admin()?;
 
@@ -274,7 +278,7 @@ pub async fn test_email(
 /// Returns immediately with the number of items queued; Meilisearch processes them asynchronously.
 #[utoipa::path(
     post,
-    path = "/api/v1/admin/reindex-search",
+    path = "/admin/reindex-search",
     tag = "admin",
     security(("bearer_auth" = [])),
     responses(
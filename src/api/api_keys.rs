@@ -0,0 +1,206 @@
+//! API key endpoints (`/settings/api-keys`)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::api_key::{ApiKey, ApiKeyUsage, ApiKeyUsageQuery, ApiKeyWithToken, CreateApiKey, UpdateApiKey},
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Build the `/settings/api-keys*` routes (administrators).
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post};
+    axum::Router::new()
+        .route("/settings/api-keys", get(list_api_keys).post(create_api_key))
+        .route(
+            "/settings/api-keys/:id",
+            get(get_api_key).put(update_api_key).delete(delete_api_key),
+        )
+        .route("/settings/api-keys/:id/regenerate-token", post(regenerate_api_key_token))
+        .route("/settings/api-keys/:id/usage", get(get_api_key_usage))
+}
+
+/// List API keys
+#[utoipa::path(
+    get,
+    path = "/settings/api-keys",
+    tag = "api_keys",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "API keys", body = Vec<ApiKey>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+    )
+)]
+pub async fn list_api_keys(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<ApiKey>>> {
+    claims.require_admin()?;
+    let api_keys = state.services.api_keys.list().await?;
+    Ok(Json(api_keys))
+}
+
+/// Get an API key
+#[utoipa::path(
+    get,
+    path = "/settings/api-keys/{id}",
+    tag = "api_keys",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "API key ID")),
+    responses(
+        (status = 200, description = "API key", body = ApiKey),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_api_key(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<ApiKey>> {
+    claims.require_admin()?;
+    let api_key = state.services.api_keys.get(id).await?;
+    Ok(Json(api_key))
+}
+
+/// Register an API key (the key is returned only in this response)
+#[utoipa::path(
+    post,
+    path = "/settings/api-keys",
+    tag = "api_keys",
+    security(("bearer_auth" = [])),
+    request_body = CreateApiKey,
+    responses(
+        (status = 201, description = "API key created", body = ApiKeyWithToken),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+    )
+)]
+pub async fn create_api_key(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateApiKey>,
+) -> AppResult<(StatusCode, Json<ApiKeyWithToken>)> {
+    claims.require_admin()?;
+    let created = state.services.api_keys.create(&data, claims.user_id).await?;
+    state.services.audit.log(audit::event::API_KEY_CREATED, Some(claims.user_id), Some("api_key"), Some(created.api_key.id), ip, Some(&created.api_key), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Update an API key (`isActive: false` revokes it)
+#[utoipa::path(
+    put,
+    path = "/settings/api-keys/{id}",
+    tag = "api_keys",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "API key ID")),
+    request_body = UpdateApiKey,
+    responses(
+        (status = 200, description = "API key updated", body = ApiKey),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_api_key(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdateApiKey>,
+) -> AppResult<Json<ApiKey>> {
+    claims.require_admin()?;
+    let api_key = state.services.api_keys.update(id, &data).await?;
+    state.services.audit.log(audit::event::API_KEY_UPDATED, Some(claims.user_id), Some("api_key"), Some(id), ip, Some((&data, &api_key)), audit::AuditLogMeta::success());
+    Ok(Json(api_key))
+}
+
+/// Issue a new key (the previous one stops working)
+#[utoipa::path(
+    post,
+    path = "/settings/api-keys/{id}/regenerate-token",
+    tag = "api_keys",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "API key ID")),
+    responses(
+        (status = 200, description = "New key", body = ApiKeyWithToken),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn regenerate_api_key_token(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<Json<ApiKeyWithToken>> {
+    claims.require_admin()?;
+    let regenerated = state.services.api_keys.regenerate_token(id).await?;
+    state.services.audit.log(audit::event::API_KEY_TOKEN_REGENERATED, Some(claims.user_id), Some("api_key"), Some(id), ip, Some(serde_json::json!({ "tokenPrefix": regenerated.api_key.token_prefix })), audit::AuditLogMeta::success());
+    Ok(Json(regenerated))
+}
+
+/// Delete an API key (and its usage history)
+#[utoipa::path(
+    delete,
+    path = "/settings/api-keys/{id}",
+    tag = "api_keys",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "API key ID")),
+    responses(
+        (status = 204, description = "API key deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_api_key(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_admin()?;
+    state.services.api_keys.delete(id).await?;
+    state.services.audit.log(audit::event::API_KEY_DELETED, Some(claims.user_id), Some("api_key"), Some(id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Daily requests made with an API key, per endpoint
+#[utoipa::path(
+    get,
+    path = "/settings/api-keys/{id}/usage",
+    tag = "api_keys",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "API key ID"), ApiKeyUsageQuery),
+    responses(
+        (status = 200, description = "Usage per day and endpoint, newest first", body = Vec<ApiKeyUsage>),
+        (status = 400, description = "Invalid range", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_api_key_usage(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+    Query(query): Query<ApiKeyUsageQuery>,
+) -> AppResult<Json<Vec<ApiKeyUsage>>> {
+    claims.require_admin()?;
+    let usage = state.services.api_keys.usage(id, query.from, query.to).await?;
+    Ok(Json(usage))
+}
@@ -0,0 +1,222 @@
+//! Generated file (artifact) endpoints (`/artifacts`)
+
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use tokio_stream::StreamExt;
+use tokio_util::io::ReaderStream;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::artifact::{Artifact, ArtifactDownloadQuery, ArtifactKind, ArtifactLink, ExportDelivery},
+    services::{artifacts::BlobReader, exports::ExportStream},
+};
+
+use super::{versioning::ApiVersion, AuthenticatedUser};
+
+/// Build the `/artifacts` routes (JWT).
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{delete, get};
+    axum::Router::new()
+        .route("/artifacts", get(list_artifacts))
+        .route("/artifacts/:id/link", get(get_artifact_link))
+        .route("/artifacts/:id", delete(delete_artifact))
+}
+
+/// Signed download route (no bearer token; mounted with the public rate limiter).
+pub fn router_download() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new().route("/artifacts/:id", get(download_artifact))
+}
+
+/// Signed link for an artifact, absolute when `server.public_base_url` is set
+pub(crate) fn artifact_link(state: &crate::AppState, artifact: Artifact) -> ArtifactLink {
+    let api_root = format!(
+        "{}{}",
+        state.config.server.public_base_url.as_deref().unwrap_or("").trim_end_matches('/'),
+        ApiVersion::LATEST.prefix()
+    );
+    state.services.artifacts.link(artifact, &api_root)
+}
+
+/// Stream an artifact as a file attachment
+pub(crate) fn file_response(artifact: &Artifact, reader: BlobReader) -> AppResult<Response> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, artifact.content_type.as_str())
+        .header(header::CONTENT_LENGTH, artifact.size_bytes)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!(r#"attachment; filename="{}""#, artifact.filename.replace('"', "")),
+        )
+        .body(Body::from_stream(ReaderStream::new(reader)))
+        .map_err(|e| AppError::Internal(format!("artifact response: {}", e)))
+}
+
+/// Answer an export request: the file itself, or `201` with a signed link
+pub(crate) async fn deliver(
+    state: &crate::AppState,
+    artifact: Artifact,
+    delivery: ExportDelivery,
+) -> AppResult<Response> {
+    match delivery {
+        ExportDelivery::Link => {
+            Ok((StatusCode::CREATED, Json(artifact_link(state, artifact))).into_response())
+        }
+        ExportDelivery::Inline => {
+            let reader = state.services.artifacts.open(&artifact).await?;
+            file_response(&artifact, reader)
+        }
+    }
+}
+
+/// Answer a streamed export: chunks go straight to the client, or into an artifact for `link`
+pub(crate) async fn deliver_stream(
+    state: &crate::AppState,
+    export: ExportStream,
+    kind: ArtifactKind,
+    created_by: i64,
+    delivery: ExportDelivery,
+) -> AppResult<Response> {
+    match delivery {
+        ExportDelivery::Inline => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, export.content_type)
+            .header(
+                header::CONTENT_DISPOSITION,
+                format!(r#"attachment; filename="{}""#, export.filename),
+            )
+            .body(Body::from_stream(export.chunks))
+            .map_err(|e| AppError::Internal(format!("export response: {}", e))),
+        ExportDelivery::Link => {
+            let mut writer = state
+                .services
+                .artifacts
+                .writer(kind, export.filename, export.content_type, Some(created_by))
+                .await?;
+            let mut chunks = export.chunks;
+            while let Some(chunk) = chunks.next().await {
+                let chunk = chunk.map_err(|e| AppError::Internal(format!("Export failed: {}", e)))?;
+                writer.write(&chunk).await?;
+            }
+            let artifact = writer.finish().await?;
+            Ok((StatusCode::CREATED, Json(artifact_link(state, artifact))).into_response())
+        }
+    }
+}
+
+/// The caller's unexpired artifacts with fresh download links (administrators see all)
+#[utoipa::path(
+    get,
+    path = "/artifacts",
+    tag = "artifacts",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Artifacts, newest first", body = Vec<ArtifactLink>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+    )
+)]
+pub async fn list_artifacts(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<ArtifactLink>>> {
+    let owner = (!claims.is_admin()).then_some(claims.user_id);
+    let artifacts = state.services.artifacts.list(owner).await?;
+    Ok(Json(
+        artifacts
+            .into_iter()
+            .map(|artifact| artifact_link(&state, artifact))
+            .collect(),
+    ))
+}
+
+/// New signed download link for an artifact (owner or administrator)
+#[utoipa::path(
+    get,
+    path = "/artifacts/{id}/link",
+    tag = "artifacts",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Artifact ID")),
+    responses(
+        (status = 200, description = "Artifact with download link", body = ArtifactLink),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not the owner", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 410, description = "Artifact expired", body = ErrorResponse),
+    )
+)]
+pub async fn get_artifact_link(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<ArtifactLink>> {
+    let artifact = state.services.artifacts.get(id).await?;
+    require_owner_or_admin(&claims, &artifact)?;
+    if artifact.expires_at <= chrono::Utc::now() {
+        return Err(AppError::Gone(format!("Artifact {} has expired", id)));
+    }
+    Ok(Json(artifact_link(&state, artifact)))
+}
+
+/// Download an artifact through its signed URL
+#[utoipa::path(
+    get,
+    path = "/artifacts/{id}",
+    tag = "artifacts",
+    params(("id" = i64, Path, description = "Artifact ID"), ArtifactDownloadQuery),
+    responses(
+        (status = 200, description = "File attachment"),
+        (status = 403, description = "Invalid signature", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 410, description = "Link or artifact expired", body = ErrorResponse),
+    )
+)]
+pub async fn download_artifact(
+    State(state): State<crate::AppState>,
+    Path(id): Path<i64>,
+    Query(query): Query<ArtifactDownloadQuery>,
+) -> AppResult<Response> {
+    let (artifact, reader) = state
+        .services
+        .artifacts
+        .open_signed(id, query.expires, &query.signature)
+        .await?;
+    file_response(&artifact, reader)
+}
+
+/// Delete an artifact before it expires (owner or administrator)
+#[utoipa::path(
+    delete,
+    path = "/artifacts/{id}",
+    tag = "artifacts",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Artifact ID")),
+    responses(
+        (status = 204, description = "Artifact deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not the owner", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_artifact(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    let artifact = state.services.artifacts.get(id).await?;
+    require_owner_or_admin(&claims, &artifact)?;
+    state.services.artifacts.delete(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn require_owner_or_admin(claims: &crate::models::user::UserClaims, artifact: &Artifact) -> AppResult<()> {
+    if claims.is_admin() || artifact.created_by == Some(claims.user_id) {
+        Ok(())
+    } else {
+        Err(AppError::Authorization("Artifact belongs to another user".to_string()))
+    }
+}
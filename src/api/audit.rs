@@ -2,23 +2,27 @@
 
 use axum::{
     extract::{Query, State},
-    response::{IntoResponse, Response},
+    response::Response,
     Json,
 };
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
 use crate::{
-    error::AppResult,
-    models::audit::{AuditLogPage, AuditQueryParams},
+    error::{AppError, AppResult},
+    models::{
+        artifact::{ArtifactKind, ExportDeliveryQuery},
+        audit::{AuditLogPage, AuditQueryParams},
+        event_envelope::{EventEnvelope, AUDIT_ENTRY_VERSION},
+    },
     AppState,
 };
 
 use super::AuthenticatedUser;
 
 /// Query parameters for audit log
-#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
 #[serde(rename_all = "camelCase")]
 pub struct AuditQueryRequest {
     pub event_type: Option<String>,
@@ -35,9 +39,10 @@ pub struct AuditQueryRequest {
 }
 
 /// Query parameters for audit log export
-#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
 #[serde(rename_all = "camelCase")]
 pub struct AuditExportRequest {
+    /// `json` (default), `csv`, or `events` (JSON array of versioned event envelopes)
     pub format: Option<String>,
     pub event_type: Option<String>,
     pub from_date: Option<DateTime<Utc>>,
@@ -81,14 +86,18 @@ pub async fn get_audit_log(
 }
 
 /// Export audit log entries as JSON or CSV (admin only)
+///
+/// `format=events` wraps each entry in the versioned event envelope shared with the SSE stream.
+/// The file is stored as an artifact; `delivery=link` returns a signed download URL instead.
 #[utoipa::path(
     get,
     path = "/audit/export",
     tag = "audit",
     security(("bearer_auth" = [])),
-    params(AuditExportRequest),
+    params(AuditExportRequest, ExportDeliveryQuery),
     responses(
         (status = 200, description = "Audit log export (JSON array or CSV)"),
+        (status = 201, description = "Export stored (`delivery=link`)", body = ArtifactLink),
         (status = 403, description = "Insufficient permissions")
     )
 )]
@@ -96,6 +105,7 @@ pub async fn export_audit_log(
     State(state): State<AppState>,
     AuthenticatedUser(claims): AuthenticatedUser,
     Query(query): Query<AuditExportRequest>,
+    Query(delivery): Query<ExportDeliveryQuery>,
 ) -> AppResult<Response> {
     claims.require_admin()?;
 
@@ -107,10 +117,17 @@ pub async fn export_audit_log(
 
     let format = query.format.as_deref().unwrap_or("json");
 
-    if format == "csv" {
-        let mut csv = String::from(
-            "id,event_type,outcome,user_id,entity_type,entity_id,ip_address,http_status,error_code,error_message,payload,created_at\n",
-        );
+    let artifact = if format == "csv" {
+        let mut writer = state
+            .services
+            .artifacts
+            .writer(ArtifactKind::AuditExport, "audit_log.csv", "text/csv", Some(claims.user_id))
+            .await?;
+        writer
+            .write_str(
+                "id,event_type,outcome,user_id,entity_type,entity_id,ip_address,http_status,error_code,error_message,payload,created_at\n",
+            )
+            .await?;
         for e in &entries {
             let payload_str = e
                 .payload
@@ -122,33 +139,50 @@ pub async fn export_audit_log(
                 .as_deref()
                 .unwrap_or("")
                 .replace('"', "\"\"");
-            csv.push_str(&format!(
-                "{},{},{},{},{},{},{},{},{},\"{}\",\"{}\",{}\n",
-                e.id,
-                e.event_type,
-                e.outcome,
-                e.user_id.map(|v| v.to_string()).unwrap_or_default(),
-                e.entity_type.as_deref().unwrap_or(""),
-                e.entity_id.map(|v| v.to_string()).unwrap_or_default(),
-                e.ip_address.as_deref().unwrap_or(""),
-                e.http_status.map(|v| v.to_string()).unwrap_or_default(),
-                e.error_code.as_deref().unwrap_or(""),
-                esc_msg,
-                payload_str,
-                e.created_at.to_rfc3339(),
-            ));
+            writer
+                .write_str(&format!(
+                    "{},{},{},{},{},{},{},{},{},\"{}\",\"{}\",{}\n",
+                    e.id,
+                    e.event_type,
+                    e.outcome,
+                    e.user_id.map(|v| v.to_string()).unwrap_or_default(),
+                    e.entity_type.as_deref().unwrap_or(""),
+                    e.entity_id.map(|v| v.to_string()).unwrap_or_default(),
+                    e.ip_address.as_deref().unwrap_or(""),
+                    e.http_status.map(|v| v.to_string()).unwrap_or_default(),
+                    e.error_code.as_deref().unwrap_or(""),
+                    esc_msg,
+                    payload_str,
+                    e.created_at.to_rfc3339(),
+                ))
+                .await?;
         }
-        Ok((
-            [
-                ("content-type", "text/csv"),
-                ("content-disposition", "attachment; filename=\"audit_log.csv\""),
-            ],
-            csv,
-        )
-            .into_response())
+        writer.finish().await?
     } else {
-        Ok(Json(entries).into_response())
-    }
+        let json = if format == "events" {
+            let events: Vec<_> = entries
+                .into_iter()
+                .map(|e| EventEnvelope::new(e.event_type.clone(), AUDIT_ENTRY_VERSION, e.created_at, e))
+                .collect();
+            serde_json::to_vec(&events)
+        } else {
+            serde_json::to_vec(&entries)
+        }
+        .map_err(|e| AppError::Internal(format!("Audit export serialization: {}", e)))?;
+        state
+            .services
+            .artifacts
+            .store_bytes(
+                ArtifactKind::AuditExport,
+                "audit_log.json",
+                "application/json",
+                Some(claims.user_id),
+                &json,
+            )
+            .await?
+    };
+
+    super::artifacts::deliver(&state, artifact, delivery.delivery).await
 }
 
 /// Build the audit routes for this domain.
@@ -1,20 +1,24 @@
 //! Authentication endpoints
 
-use axum::{extract::State, Json};
+use axum::{extract::State, http::StatusCode, Json};
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use utoipa::ToSchema;
 
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
 #[allow(unused_imports)] // Used in utoipa macros
 use crate::error::ErrorResponse;
-use crate::models::Language;
+use crate::models::{
+    user::{AccountTypeSlug, ChangeOwnPin, RegenerateRecoveryCodes, User, SCOPE_SELF_SERVICE},
+    Language,
+};
 use crate::services::audit;
+use crate::services::users::SELF_SERVICE_TOKEN_SECONDS;
 
 use super::ClientIp;
 
-use super::{AuthenticatedUser, PasswordChangeUser};
+use super::{AuthenticatedUser, MaybeKiosk, PasswordChangeUser, SelfServiceUser};
 
 
 /// Build the auth routes for this domain.
@@ -31,11 +35,30 @@ pub fn router() -> axum::Router<crate::AppState> {
         .route("/auth/change-password", post(change_password))
         .route("/auth/setup-2fa", post(setup_2fa))
         .route("/auth/disable-2fa", post(disable_2fa))
+        .route("/auth/recovery-codes", post(regenerate_recovery_codes))
+        .route("/auth/pin", put(change_own_pin))
+}
+
+/// Barcode + PIN login — mounted separately so it gets its own rate limiter.
+pub fn router_barcode() -> axum::Router<crate::AppState> {
+    use axum::routing::post;
+    axum::Router::new().route("/auth/login-barcode", post(login_barcode))
 }
 
 #[derive(Serialize)]
 struct LoginIdentifierAudit<'a> {
     login: &'a str,
+    /// Terminal the login went through (kiosk passthrough)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kiosk_id: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct BarcodeLoginAudit<'a> {
+    barcode: &'a str,
+    /// Terminal the login went through
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kiosk_id: Option<i64>,
 }
 
 #[derive(Clone, Copy, Serialize)]
@@ -55,7 +78,7 @@ struct UserIdAudit {
 }
 
 /// Login request body
-#[derive(Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginRequest {
     /// Username or login
@@ -67,7 +90,7 @@ pub struct LoginRequest {
 }
 
 /// Login response with JWT token
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginResponse {
     /// JWT access token (None if 2FA is required)
@@ -82,6 +105,9 @@ pub struct LoginResponse {
     pub requires_2fa: bool,
     /// 2FA method if required ("totp" or "email")
     pub two_factor_method: Option<String>,
+    /// Email 2FA challenge the code was sent for (pass it to `/auth/verify-2fa`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub challenge_id: Option<String>,
     /// Device ID generated by server (only present if 2FA is required, client should store and reuse it)
     pub device_id: Option<String>,
     /// When true the user must change their password before continuing
@@ -90,7 +116,7 @@ pub struct LoginResponse {
 
 /// User information returned after login
 #[serde_as]
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserInfo {
     /// User ID
@@ -123,28 +149,64 @@ pub struct UserInfo {
 
 
 
+impl From<User> for UserInfo {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            login: user.login.unwrap_or_default(),
+            email: user.email,
+            firstname: user.firstname,
+            lastname: user.lastname,
+            addr_street: user.addr_street,
+            addr_zip_code: user.addr_zip_code,
+            addr_city: user.addr_city,
+            phone: user.phone,
+            birthdate: user.birthdate,
+            account_type: user.account_type.to_string(),
+            language: user.language.unwrap_or(Language::French),
+        }
+    }
+}
+
 /// Login endpoint - authenticate and get JWT token
+///
+/// From a catalog terminal, send the kiosk token in `X-Kiosk-Token`: only patron accounts
+/// may then sign in, and the login is recorded under the kiosk identity.
 #[utoipa::path(
     post,
     path = "/auth/login",
     tag = "auth",
     request_body = LoginRequest,
+    params(("X-Kiosk-Token" = Option<String>, Header, description = "Kiosk token (patron self-service login on a catalog terminal)")),
     responses(
         (status = 200, description = "Login successful", body = LoginResponse),
-        (status = 401, description = "Invalid credentials", body = ErrorResponse)
+        (status = 401, description = "Invalid credentials or kiosk token", body = ErrorResponse),
+        (status = 403, description = "Staff account used on a kiosk, or kiosk used from a non-allowed address", body = ErrorResponse)
     )
 )]
 pub async fn login(
     State(state): State<crate::AppState>,
     ClientIp(ip): ClientIp,
+    MaybeKiosk(kiosk): MaybeKiosk,
     Json(request): Json<LoginRequest>,
 ) -> AppResult<Json<LoginResponse>> {
-    let login_result = state
+    let mut login_result = state
         .services
         .users
         .authenticate(&request.username, &request.password, request.device_id.as_deref())
         .await;
 
+    // Public terminals never carry staff sessions
+    if let (Some(kiosk), Ok((_, user))) = (&kiosk, &login_result) {
+        if matches!(user.account_type, AccountTypeSlug::Librarian | AccountTypeSlug::Admin) {
+            login_result = Err(AppError::Authorization(format!(
+                "Staff accounts cannot sign in from kiosk '{}'",
+                kiosk.name
+            )));
+        }
+    }
+    let kiosk_id = kiosk.as_ref().map(|k| k.id);
+
     match &login_result {
         Ok((_, user)) => {
             state.services.audit.log(
@@ -155,19 +217,32 @@ pub async fn login(
                 ip.clone(),
                 Some(LoginIdentifierAudit {
                     login: request.username.as_str(),
+                    kiosk_id,
                 }),
                 audit::AuditLogMeta::success(),
             );
+            if let Some(kiosk) = &kiosk {
+                state.services.audit.log(
+                    audit::event::KIOSK_PATRON_LOGIN,
+                    Some(user.id),
+                    Some("kiosk"),
+                    Some(kiosk.id),
+                    ip.clone(),
+                    Some(serde_json::json!({ "kiosk": kiosk.name })),
+                    audit::AuditLogMeta::success(),
+                );
+            }
         }
         Err(e) => {
             state.services.audit.log(
                 audit::event::AUTH_LOGIN_FAILED,
                 None,
-                None,
-                None,
+                kiosk_id.map(|_| "kiosk"),
+                kiosk_id,
                 ip.clone(),
                 Some(LoginIdentifierAudit {
                     login: request.username.as_str(),
+                    kiosk_id,
                 }),
                 audit::AuditLogMeta::from_app_error(e),
             );
@@ -197,20 +272,11 @@ pub async fn login(
         }
     });
 
-    // If 2FA is enabled and method is email, send code
+    // Email 2FA: open a challenge and send its code
+    let mut challenge_id = None;
     if requires_2fa && two_factor_method.as_deref() == Some("email") {
         if let Some(ref email) = user.email {
-            use rand::Rng;
-            // Generate code before await to avoid Send issues
-            let code = {
-                let mut rng = rand::thread_rng();
-                format!("{:06}", rng.gen_range(100000..999999))
-            };
-            
-            // Store code in Redis with 10 minutes expiration
-            state.services.redis.store_2fa_code(user.id, &code, 600).await?;
-            
-            // Send code via email
+            let (id, code) = state.services.users.start_email_2fa(user.id).await?;
             match state
                 .services
                 .email
@@ -241,6 +307,7 @@ pub async fn login(
                     return Err(e);
                 }
             }
+            challenge_id = Some(id);
         }
     }
 
@@ -250,27 +317,125 @@ pub async fn login(
         token,
         token_type: "Bearer".to_string(),
         expires_in: (state.config.users.jwt_expiration_hours * 3600) as i64,
-        user: UserInfo {
-            id: user.id,
-            login: user.login.unwrap_or_default(),
-            email: user.email,
-            firstname: user.firstname,
-            lastname: user.lastname,
-            addr_street: user.addr_street,
-            addr_zip_code: user.addr_zip_code,
-            addr_city: user.addr_city,
-            phone: user.phone,
-            birthdate: user.birthdate,
-            account_type: user.account_type.to_string(),
-            language: user.language.unwrap_or(Language::French),
-        },
+        user: UserInfo::from(user),
         requires_2fa,
         two_factor_method,
+        challenge_id,
         device_id,
         must_change_password,
     }))
 }
 
+/// Barcode + PIN login request body
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BarcodeLoginRequest {
+    /// Patron card barcode
+    pub barcode: String,
+    /// Self-service PIN
+    pub pin: String,
+}
+
+/// Barcode + PIN login response (self-service token)
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BarcodeLoginResponse {
+    /// JWT access token, only accepted by self-service endpoints
+    pub token: String,
+    /// Token type (always "Bearer")
+    pub token_type: String,
+    /// Token expiration time in seconds
+    pub expires_in: i64,
+    /// Token scope (always "self_service")
+    pub scope: String,
+    pub user: UserInfo,
+}
+
+/// Patron login with card barcode + PIN (self-service kiosks).
+///
+/// Returns a short-lived token limited to self-service endpoints (own profile, loans,
+/// renewals, holds). Staff accounts are refused; repeated wrong PINs lock barcode login.
+#[utoipa::path(
+    post,
+    path = "/auth/login-barcode",
+    tag = "auth",
+    request_body = BarcodeLoginRequest,
+    params(("X-Kiosk-Token" = Option<String>, Header, description = "Kiosk token of the terminal, if any")),
+    responses(
+        (status = 200, description = "Login successful", body = BarcodeLoginResponse),
+        (status = 401, description = "Invalid card or PIN, or barcode login locked", body = ErrorResponse),
+        (status = 403, description = "Staff account", body = ErrorResponse),
+        (status = 429, description = "Too many requests")
+    )
+)]
+pub async fn login_barcode(
+    State(state): State<crate::AppState>,
+    ClientIp(ip): ClientIp,
+    MaybeKiosk(kiosk): MaybeKiosk,
+    Json(request): Json<BarcodeLoginRequest>,
+) -> AppResult<Json<BarcodeLoginResponse>> {
+    let result = state
+        .services
+        .users
+        .authenticate_barcode(&request.barcode, &request.pin)
+        .await;
+    let kiosk_id = kiosk.as_ref().map(|k| k.id);
+    let audit_payload = BarcodeLoginAudit {
+        barcode: request.barcode.trim(),
+        kiosk_id,
+    };
+
+    let (token, user) = match result {
+        Ok(ok) => ok,
+        Err(e) => {
+            state.services.audit.log(audit::event::AUTH_BARCODE_LOGIN_FAILED, None, kiosk_id.map(|_| "kiosk"), kiosk_id, ip, Some(&audit_payload), audit::AuditLogMeta::from_app_error(&e));
+            return Err(e);
+        }
+    };
+    state.services.audit.log(audit::event::AUTH_BARCODE_LOGIN_SUCCESS, Some(user.id), Some("user"), Some(user.id), ip.clone(), Some(&audit_payload), audit::AuditLogMeta::success());
+    if let Some(kiosk) = &kiosk {
+        state.services.audit.log(audit::event::KIOSK_PATRON_LOGIN, Some(user.id), Some("kiosk"), Some(kiosk.id), ip, Some(serde_json::json!({ "kiosk": kiosk.name, "method": "barcode" })), audit::AuditLogMeta::success());
+    }
+
+    Ok(Json(BarcodeLoginResponse {
+        token,
+        token_type: "Bearer".to_string(),
+        expires_in: SELF_SERVICE_TOKEN_SECONDS,
+        scope: SCOPE_SELF_SERVICE.to_string(),
+        user: UserInfo::from(user),
+    }))
+}
+
+/// Set or clear your own self-service PIN (confirmed with your password)
+#[utoipa::path(
+    put,
+    path = "/auth/pin",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    request_body = ChangeOwnPin,
+    responses(
+        (status = 204, description = "PIN updated"),
+        (status = 401, description = "Not authenticated or wrong password", body = ErrorResponse),
+        (status = 403, description = "Impersonation token", body = ErrorResponse),
+        (status = 422, description = "PIN is not 4 to 8 digits (`invalid_fields`) or staff account", body = ErrorResponse)
+    )
+)]
+pub async fn change_own_pin(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(request): Json<ChangeOwnPin>,
+) -> AppResult<StatusCode> {
+    claims.require_not_impersonating()?;
+    state
+        .services
+        .users
+        .change_own_pin(claims.user_id, &request.current_password, request.pin.as_deref())
+        .await?;
+    state.services.audit.log(audit::event::AUTH_PIN_CHANGED, Some(claims.user_id), Some("user"), Some(claims.user_id), ip, Some(serde_json::json!({ "enabled": request.pin.is_some() })), audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
 /// Get current user profile
 #[utoipa::path(
     get,
@@ -284,29 +449,16 @@ pub async fn login(
 )]
 pub async fn me(
     State(state): State<crate::AppState>,
-    AuthenticatedUser(claims): AuthenticatedUser,
+    SelfServiceUser(claims): SelfServiceUser,
 ) -> AppResult<Json<UserInfo>> {
     let user = state.services.users.get_by_id(claims.user_id).await?;
 
-    Ok(Json(UserInfo {
-        id: user.id,
-        login: user.login.unwrap_or_default(),
-        email: user.email,
-        firstname: user.firstname,
-        lastname: user.lastname,
-        addr_street: user.addr_street,
-        addr_zip_code: user.addr_zip_code,
-        addr_city: user.addr_city,
-        phone: user.phone,
-        birthdate: user.birthdate,
-        account_type: user.account_type.to_string(),
-        language: user.language.unwrap_or(Language::French),
-    }))
+    Ok(Json(UserInfo::from(user)))
 }
 
 /// Verify 2FA code request
 #[serde_as]
-#[derive(Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Verify2FARequest {
     /// User ID (from login response)
@@ -315,6 +467,8 @@ pub struct Verify2FARequest {
     pub user_id: i64,
     /// 2FA code (TOTP or email code)
     pub code: String,
+    /// Email 2FA challenge from the login response (default: the user's latest challenge)
+    pub challenge_id: Option<String>,
     /// Device ID for trusted device feature (optional)
     pub device_id: Option<String>,
     /// Whether to trust this device for 90 days (optional, default: false)
@@ -322,7 +476,7 @@ pub struct Verify2FARequest {
 }
 
 /// Verify 2FA code response
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Verify2FAResponse {
     /// JWT access token
@@ -357,7 +511,13 @@ pub async fn verify_2fa(
     let result = state
         .services
         .users
-        .verify_2fa(request.user_id, &request.code, request.device_id.as_deref(), trust_device)
+        .verify_2fa(
+            request.user_id,
+            request.challenge_id.as_deref(),
+            &request.code,
+            request.device_id.as_deref(),
+            trust_device,
+        )
         .await;
 
     match &result {
@@ -393,7 +553,7 @@ pub async fn verify_2fa(
 
 /// Verify recovery code request
 #[serde_as]
-#[derive(Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct VerifyRecoveryRequest {
     /// User ID (from login response)
@@ -405,7 +565,7 @@ pub struct VerifyRecoveryRequest {
 }
 
 /// Password reset request
-#[derive(Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RequestPasswordResetRequest {
     /// Login or email address for the account.
@@ -416,19 +576,19 @@ pub struct RequestPasswordResetRequest {
 }
 
 /// Response after a password-reset email was queued (public: no token in body).
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RequestPasswordResetResponse {
     pub message: String,
 }
 
 /// Response after password was reset with a valid token.
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ResetPasswordResponse {
     pub message: String,
 }
-#[derive(Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ResetPasswordRequest {
     /// Reset token received by email
@@ -570,13 +730,6 @@ pub async fn reset_password(
     ClientIp(ip): ClientIp,
     Json(request): Json<ResetPasswordRequest>,
 ) -> AppResult<Json<ResetPasswordResponse>> {
-
-    if request.new_password.len() < 4 {
-        return Err(crate::error::AppError::Validation(
-            "Password must be at least 4 characters".to_string(),
-        ));
-    }
-
     state
         .services
         .users
@@ -600,14 +753,14 @@ pub async fn reset_password(
 }
 
 /// Setup 2FA request
-#[derive(Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct Setup2FARequest {
     /// 2FA method: "totp" or "email"
     pub method: String,
 }
 
 /// Setup 2FA response
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Setup2FAResponse {
     /// Provisioning URI for TOTP method (to generate QR code on client)
@@ -625,7 +778,8 @@ pub struct Setup2FAResponse {
     request_body = Setup2FARequest,
     responses(
         (status = 200, description = "2FA setup successful", body = Setup2FAResponse),
-        (status = 400, description = "Invalid request", body = ErrorResponse)
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 403, description = "Impersonation token", body = ErrorResponse)
     )
 )]
 pub async fn setup_2fa(
@@ -634,6 +788,7 @@ pub async fn setup_2fa(
     ClientIp(ip): ClientIp,
     Json(request): Json<Setup2FARequest>,
 ) -> AppResult<Json<Setup2FAResponse>> {
+    claims.require_not_impersonating()?;
     let user = state.services.users.get_by_id(claims.user_id).await?;
 
     let (totp_secret, provisioning_uri) = if request.method == "totp" {
@@ -668,8 +823,9 @@ pub async fn setup_2fa(
     tag = "auth",
     security(("bearer_auth" = [])),
     responses(
-        (status = 200, description = "2FA disabled successfully"),
-        (status = 401, description = "Not authenticated", body = ErrorResponse)
+        (status = 200, description = "2FA disabled successfully", body = serde_json::Value),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Impersonation token", body = ErrorResponse)
     )
 )]
 pub async fn disable_2fa(
@@ -677,6 +833,7 @@ pub async fn disable_2fa(
     AuthenticatedUser(claims): AuthenticatedUser,
     ClientIp(ip): ClientIp,
 ) -> AppResult<Json<serde_json::Value>> {
+    claims.require_not_impersonating()?;
     state.services.users.disable_2fa(claims.user_id).await?;
 
     state.services.audit.log(
@@ -693,8 +850,58 @@ pub async fn disable_2fa(
     Ok(Json(serde_json::json!({"message": "2FA disabled successfully"})))
 }
 
+/// New recovery codes
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCodesResponse {
+    /// Recovery codes (save these securely!); the previous ones no longer work
+    pub recovery_codes: Vec<String>,
+}
+
+/// Regenerate recovery codes endpoint (2FA must be enabled; confirmed with the password or a TOTP code)
+#[utoipa::path(
+    post,
+    path = "/auth/recovery-codes",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    request_body = RegenerateRecoveryCodes,
+    responses(
+        (status = 200, description = "New recovery codes", body = RecoveryCodesResponse),
+        (status = 400, description = "2FA is not enabled, or no confirmation", body = ErrorResponse),
+        (status = 401, description = "Not authenticated, wrong password or 2FA code", body = ErrorResponse),
+        (status = 403, description = "Impersonation token", body = ErrorResponse)
+    )
+)]
+pub async fn regenerate_recovery_codes(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(request): Json<RegenerateRecoveryCodes>,
+) -> AppResult<Json<RecoveryCodesResponse>> {
+    claims.require_not_impersonating()?;
+    let recovery_codes = state
+        .services
+        .users
+        .regenerate_recovery_codes(claims.user_id, &request)
+        .await?;
+
+    state.services.audit.log(
+        audit::event::AUTH_RECOVERY_CODES_REGENERATED,
+        Some(claims.user_id),
+        Some("user"),
+        Some(claims.user_id),
+        ip,
+        Some(UserIdAudit {
+            user_id: claims.user_id,
+        }),
+        audit::AuditLogMeta::success(),
+    );
+
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
 /// First-login password change request
-#[derive(Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ChangePasswordRequest {
     /// New password (min 4 chars)
@@ -0,0 +1,149 @@
+//! Author name forms (`/authors`): preferred display form and batch normalization of the names
+//! (the OPAC suggestions live in `opac`, `GET /authors/suggest`)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::{
+        author::{Author, AuthorNormalizationQuery, AuthorNormalizationReport, UpdateAuthorDisplayName},
+        task::TaskKind,
+    },
+    services::audit,
+};
+
+use super::{tasks::TaskAcceptedResponse, AuthenticatedUser, ClientIp};
+
+/// Build the `/authors` routes.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, put};
+    axum::Router::new()
+        .route("/authors/normalization", get(preview_author_normalization).post(normalize_authors))
+        .route("/authors/:id", get(get_author))
+        .route("/authors/:id/display-name", put(set_author_display_name))
+}
+
+/// Get an author
+#[utoipa::path(
+    get,
+    path = "/authors/{id}",
+    tag = "authors",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Author ID")),
+    responses(
+        (status = 200, description = "Author", body = Author),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Author not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_author(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Author>> {
+    claims.require_read_items()?;
+    Ok(Json(state.services.catalog.get_author(id).await?))
+}
+
+/// Set the preferred display form of an author ("J. R. R. Tolkien")
+///
+/// Lists, exports and emails show it instead of the lastname / firstname composition; an empty
+/// or null `displayName` goes back to the composition. Search still matches lastname / firstname.
+#[utoipa::path(
+    put,
+    path = "/authors/{id}/display-name",
+    tag = "authors",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Author ID")),
+    request_body = UpdateAuthorDisplayName,
+    responses(
+        (status = 200, description = "Author updated", body = Author),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Author not found", body = ErrorResponse),
+    )
+)]
+pub async fn set_author_display_name(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdateAuthorDisplayName>,
+) -> AppResult<Json<Author>> {
+    claims.require_write_items()?;
+    let author = state.services.catalog.set_author_display_name(id, data.display_name).await?;
+    state.services.audit.log(audit::event::AUTHOR_UPDATED, Some(claims.user_id), Some("author"), Some(id), ip, Some(&author), audit::AuditLogMeta::success());
+    Ok(Json(author))
+}
+
+/// Preview the normalization of the author names
+///
+/// Same rules as on ingest: typographic punctuation mapped to ASCII, stray punctuation and
+/// spaces removed, "Tolkien, J.R.R." split into lastname / firstname, initials spaced, names
+/// typed in capitals or lowercase title-cased. Authors whose names become identical are merged
+/// into the oldest one. Nothing is written.
+#[utoipa::path(
+    get,
+    path = "/authors/normalization",
+    tag = "authors",
+    security(("bearer_auth" = [])),
+    params(AuthorNormalizationQuery),
+    responses(
+        (status = 200, description = "Changes the normalization would make", body = AuthorNormalizationReport),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn preview_author_normalization(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<AuthorNormalizationQuery>,
+) -> AppResult<Json<AuthorNormalizationReport>> {
+    claims.require_read_items()?;
+    Ok(Json(state.services.catalog.normalize_authors(false, query.limit).await?))
+}
+
+/// Normalize the author names, in the background
+///
+/// Applies the changes of `GET /authors/normalization` and reindexes the records concerned.
+/// Poll `GET /tasks/{taskId}` for the `AuthorNormalizationReport`.
+#[utoipa::path(
+    post,
+    path = "/authors/normalization",
+    tag = "authors",
+    security(("bearer_auth" = [])),
+    params(AuthorNormalizationQuery),
+    responses(
+        (status = 202, description = "Normalization started; poll GET /tasks/:id", body = TaskAcceptedResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn normalize_authors(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Query(query): Query<AuthorNormalizationQuery>,
+) -> AppResult<(StatusCode, Json<TaskAcceptedResponse>)> {
+    claims.require_write_settings()?;
+    let catalog = state.services.catalog.clone();
+    let audit_svc = state.services.audit.clone();
+    let user_id = claims.user_id;
+    let task_id = state.services.tasks.spawn_task(TaskKind::AuthorNormalization, user_id, move |handle| async move {
+        match catalog.normalize_authors(true, query.limit).await {
+            Ok(report) => {
+                audit_svc.log(audit::event::AUTHORS_NORMALIZED, Some(user_id), Some("author"), None, ip, Some(&report), audit::AuditLogMeta::success());
+                handle.complete(serde_json::to_value(&report).unwrap_or_default()).await
+            }
+            Err(e) => handle.fail(e.to_string()).await,
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(TaskAcceptedResponse { task_id })))
+}
@@ -0,0 +1,103 @@
+//! Database backup endpoints (`/admin/backup`, `/admin/backups`)
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use utoipa::ToSchema;
+
+use crate::{
+    error::AppResult,
+    models::{
+        backup::{Backup, BackupStatus, BackupsQuery},
+        task::TaskKind,
+    },
+};
+
+use super::AuthenticatedUser;
+
+/// Backup accepted: the history entry is updated when the background task ends
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupStarted {
+    /// Background task id (`GET /tasks/:id`)
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub task_id: i64,
+    pub backup: Backup,
+}
+
+/// Build the `/admin/backup*` routes (administrators).
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post};
+    axum::Router::new()
+        .route("/admin/backup", post(create_backup))
+        .route("/admin/backups", get(list_backups))
+}
+
+/// Back up the database now, in the background
+///
+/// Consistent logical export of every table (no PostgreSQL client tools needed), gzip-compressed
+/// and written to the artifacts store or to S3 (`[backup]` configuration). Redis is described in
+/// the file, not backed up. Restore with `elidune-server restore <file>`.
+#[utoipa::path(
+    post,
+    path = "/admin/backup",
+    tag = "backups",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 202, description = "Backup started", body = BackupStarted),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required, or demo mode", body = ErrorResponse),
+        (status = 409, description = "A backup is already running", body = ErrorResponse),
+    )
+)]
+pub async fn create_backup(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<(StatusCode, Json<BackupStarted>)> {
+    claims.require_admin()?;
+    super::demo::refuse_in_demo_mode(&state, "Backups")?;
+    let service = state.services.backup.clone();
+    let backup = service.start(claims.user_id).await?;
+
+    let started = backup.clone();
+    let task_id = state.services.tasks.spawn_task(TaskKind::DatabaseBackup, claims.user_id, move |handle| async move {
+        match service.execute(backup).await {
+            Ok(backup) if backup.status == BackupStatus::Failed => {
+                handle.fail(backup.error.unwrap_or_else(|| "Backup failed".to_string())).await;
+            }
+            Ok(backup) => handle.complete(serde_json::to_value(&backup).unwrap_or_default()).await,
+            Err(e) => handle.fail(e.to_string()).await,
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(BackupStarted { task_id, backup: started })))
+}
+
+/// Backup history (most recent first), with file sizes
+#[utoipa::path(
+    get,
+    path = "/admin/backups",
+    tag = "backups",
+    security(("bearer_auth" = [])),
+    params(BackupsQuery),
+    responses(
+        (status = 200, description = "Backups", body = Vec<Backup>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+    )
+)]
+pub async fn list_backups(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<BackupsQuery>,
+) -> AppResult<Json<Vec<Backup>>> {
+    claims.require_admin()?;
+    let backups = state.services.backup.list(query.limit).await?;
+    Ok(Json(backups))
+}
@@ -10,7 +10,10 @@ use crate::{
     services::audit,
 };
 
-use super::{AuthenticatedUser, ClientIp};
+use super::{
+    sse::{self, SsePayload},
+    AuthenticatedUser, ClientIp,
+};
 
 
 pub fn router() -> axum::Router<crate::AppState> {
@@ -22,7 +25,7 @@ pub fn router() -> axum::Router<crate::AppState> {
 
 
 /// Batch return request — list of barcodes to return
-#[derive(Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchReturnRequest {
     /// List of specimen barcodes to return
@@ -30,7 +33,7 @@ pub struct BatchReturnRequest {
 }
 
 /// Result for a single barcode in a batch operation
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchReturnItemResult {
     pub barcode: String,
@@ -42,7 +45,7 @@ pub struct BatchReturnItemResult {
 }
 
 /// Batch return response
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchReturnResponse {
     pub returned: u32,
@@ -62,8 +65,8 @@ pub struct BatchReturnResponse {
     request_body = BatchReturnRequest,
     responses(
         (status = 200, description = "Batch return results (partial success possible)", body = BatchReturnResponse),
-        (status = 400, description = "Empty list or invalid input", body = crate::error::ErrorResponse),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse)
+        (status = 400, description = "Empty list or invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse)
     )
 )]
 pub async fn batch_return(
@@ -85,8 +88,10 @@ pub async fn batch_return(
     let mut errors = 0u32;
 
     for barcode in &req.barcodes {
-        match state.services.loans.return_loan_by_item(barcode).await {
-            Ok(loan) => {
+        match state.services.loans.return_loan_by_item(barcode, None, None).await {
+            Ok(outcome) => {
+                super::loans::publish_return(&state, &outcome);
+                let loan = outcome.details;
                 state.services.audit.log(
                     audit::event::LOAN_RETURNED,
                     Some(claims.user_id),
@@ -125,6 +130,9 @@ pub async fn batch_return(
         }
     }
 
+    if returned > 0 {
+        sse::publish_counters(&state);
+    }
     Ok(Json(BatchReturnResponse { returned, errors, results }))
 }
 
@@ -136,7 +144,7 @@ pub struct BatchCreateLoanItem {
 }
 
 /// Batch create loans request — assign multiple items to the same user
-#[derive(Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchCreateLoansRequest {
     pub user_id: String,
@@ -146,7 +154,7 @@ pub struct BatchCreateLoansRequest {
 }
 
 /// Batch create response
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchCreateLoansResponse {
     pub created: u32,
@@ -154,7 +162,7 @@ pub struct BatchCreateLoansResponse {
     pub results: Vec<BatchCreateLoanItemResult>,
 }
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchCreateLoanItemResult {
     pub barcode: String,
@@ -174,8 +182,8 @@ pub struct BatchCreateLoanItemResult {
     request_body = BatchCreateLoansRequest,
     responses(
         (status = 200, description = "Batch loan results (partial success possible)", body = BatchCreateLoansResponse),
-        (status = 400, description = "Invalid input", body = crate::error::ErrorResponse),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse)
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse)
     )
 )]
 pub async fn batch_create_loans(
@@ -204,11 +212,17 @@ pub async fn batch_create_loans(
         let loan_data = crate::models::loan::CreateLoan {
             user_id,
             item_id: None,
+            equipment_id: None,
             item_identification: Some(barcode.clone()),
             force: req.force,
+            deposit_received: false,
+            override_blocks: req.force && claims.can_override_checkout_blocks(),
         };
         match state.services.loans.create_loan(loan_data).await {
-            Ok((loan_id, expiry_at)) => {
+            Ok(outcome) => {
+                let (loan_id, expiry_at) = (outcome.loan_id, outcome.expiry_at);
+                super::loans::audit_blocks_overridden(&state, &claims, ip.clone(), user_id, &outcome);
+                sse::publish(&state, SsePayload::loan("loan.created", loan_id, Some(user_id), None));
                 state.services.audit.log(
                     audit::event::LOAN_CREATED,
                     Some(claims.user_id),
@@ -247,6 +261,9 @@ pub async fn batch_create_loans(
         }
     }
 
+    if created > 0 {
+        sse::publish_counters(&state);
+    }
     Ok(Json(BatchCreateLoansResponse { created, errors, results }))
 }
 
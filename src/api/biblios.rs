@@ -3,24 +3,31 @@
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
     Json,
 };
 use axum_extra::extract::Multipart;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
     error::{AppError, AppResult},
     models::{
-        biblio::{Biblio, BiblioQuery, BiblioShort},
+        artifact::{ArtifactKind, ExportDeliveryQuery},
+        biblio::{Biblio, BiblioFacets, BiblioQuery, BiblioShort, EditLock},
+        hold::HoldDetails,
         import_report::ImportReport,
+        inventory::{InventoryMissingRow, InventoryScan, InventorySession},
         item::Item,
+        loan::LoanDetails,
+        notification::{NewNotification, NotificationKind},
+        user::{UserActivity, UserShort},
     },
     models::task::TaskKind,
+    marc::diff::{MarcDiffFormat, MarcFieldDiff},
     services::{
         audit::{self},
+        exports::escape_csv,
         marc::{EnqueueResult, MarcBatchInfo},
     },
 };
@@ -35,6 +42,12 @@ pub fn router() -> axum::Router<crate::AppState> {
         .route("/biblios", get(list_biblios).post(create_biblio))
         .route("/biblios/:id", get(get_biblio).put(update_biblio).delete(delete_biblio))
         .route("/biblios/:id/items", get(list_items).post(create_item))
+        .route(
+            "/biblios/:id/lock",
+            get(get_edit_lock).post(acquire_edit_lock).delete(release_edit_lock),
+        )
+        .route("/biblios/:id/marc-diff", get(get_marc_diff))
+        .route("/biblios/:id/marc-diff/apply", post(apply_marc_diff))
         .route("/biblios/export.csv", get(export_biblios_csv))
         .route("/biblios/load-marc", post(load_marc))
         .route("/biblios/import-marc-batch", post(import_marc_batch))
@@ -42,7 +55,7 @@ pub fn router() -> axum::Router<crate::AppState> {
         .route("/biblios/marc-batch/:batch_id", get(load_marc_batch))
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GetBiblioQuery {
     /// If true, include the full MARC record (marc_record JSONB) in the response
@@ -54,8 +67,18 @@ pub struct GetBiblioQuery {
 ///
 /// All list endpoints return this envelope so clients have a consistent way
 /// to read pagination metadata without inspecting headers.
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
+#[aliases(
+    PaginatedBiblios = PaginatedResponse<BiblioShort>,
+    PaginatedUsers = PaginatedResponse<UserShort>,
+    PaginatedUserActivity = PaginatedResponse<UserActivity>,
+    PaginatedLoans = PaginatedResponse<LoanDetails>,
+    PaginatedHolds = PaginatedResponse<HoldDetails>,
+    PaginatedInventorySessions = PaginatedResponse<InventorySession>,
+    PaginatedInventoryScans = PaginatedResponse<InventoryScan>,
+    PaginatedInventoryMissing = PaginatedResponse<InventoryMissingRow>,
+)]
 pub struct PaginatedResponse<T>
 where
     T: for<'a> ToSchema<'a>,
@@ -84,6 +107,38 @@ impl<T: for<'a> ToSchema<'a>> PaginatedResponse<T> {
     }
 }
 
+/// Catalog search page: the [`PaginatedResponse`] fields plus facet counts for filter sidebars.
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BiblioSearchPage {
+    /// Page contents
+    pub items: Vec<BiblioShort>,
+    /// Total number of matching records across all pages
+    pub total: i64,
+    /// Current 1-based page number
+    pub page: i64,
+    /// Maximum records per page
+    pub per_page: i64,
+    /// Total number of pages (`ceil(total / per_page)`)
+    pub page_count: i64,
+    /// Counts over all matching records (not only this page)
+    pub facets: BiblioFacets,
+}
+
+impl BiblioSearchPage {
+    pub fn new(items: Vec<BiblioShort>, total: i64, page: i64, per_page: i64, facets: BiblioFacets) -> Self {
+        let paginated = PaginatedResponse::new(items, total, page, per_page);
+        Self {
+            items: paginated.items,
+            total: paginated.total,
+            page: paginated.page,
+            per_page: paginated.per_page,
+            page_count: paginated.page_count,
+            facets,
+        }
+    }
+}
+
 /// List biblios with search and pagination
 #[utoipa::path(
     get,
@@ -100,12 +155,14 @@ impl<T: for<'a> ToSchema<'a>> PaginatedResponse<T> {
         ("serieId" = Option<i64>, Query, description = "Filter by series ID (exact match)"),
         ("collection" = Option<String>, Query, description = "Filter by collection name (substring)"),
         ("collectionId" = Option<i64>, Query, description = "Filter by collection ID (exact match)"),
+        ("readingLevel" = Option<String>, Query, description = "Comma-separated reading levels, any of them (ages0To3, ages4To6, ages7To9, ages10To12, teen)"),
+        ("accessibility" = Option<String>, Query, description = "Comma-separated accessible formats, all required (largePrint, braille, audiobook, dyslexiaFriendly)"),
         ("includeWithoutActiveItems" = Option<bool>, Query, description = "If true, include biblios with no active (non-archived) items; default excludes them"),
         ("page" = Option<i64>, Query, description = "Page number (default: 1)"),
         ("perPage" = Option<i64>, Query, description = "Items per page (default: 20)")
     ),
     responses(
-        (status = 200, description = "List of bibliographic records", body = PaginatedResponse<BiblioShort>),
+        (status = 200, description = "List of bibliographic records with facet counts", body = BiblioSearchPage),
         (status = 401, description = "Not authenticated")
     )
 )]
@@ -113,14 +170,14 @@ pub async fn list_biblios(
     State(state): State<crate::AppState>,
     AuthenticatedUser(claims): AuthenticatedUser,
     Query(query): Query<BiblioQuery>,
-) -> AppResult<Json<PaginatedResponse<BiblioShort>>> {
+) -> AppResult<Json<BiblioSearchPage>> {
     claims.require_read_items()?;
 
-    let (biblios, total) = state.services.catalog.search_biblios(&query).await?;
+    let (biblios, total, facets) = state.services.catalog.search_biblios_with_facets(&query).await?;
     let page = query.page.unwrap_or(1);
     let per_page = query.per_page.unwrap_or(20);
 
-    Ok(Json(PaginatedResponse::new(biblios, total, page, per_page)))
+    Ok(Json(BiblioSearchPage::new(biblios, total, page, per_page, facets)))
 }
 
 /// Get biblio details by ID
@@ -134,7 +191,7 @@ pub async fn list_biblios(
         ("full_record" = Option<bool>, Query, description = "If true, include full MARC record data")
     ),
     responses(
-        (status = 200, description = "Bibliographic record details", body = Biblio),
+        (status = 200, description = "Bibliographic record details (with `editLock` while a cataloger edits it)", body = Biblio),
         (status = 404, description = "Biblio not found")
     )
 )]
@@ -146,13 +203,15 @@ pub async fn get_biblio(
 ) -> AppResult<Json<Biblio>> {
     claims.require_read_items()?;
 
-    let biblio = state.services.catalog.get_biblio(id).await?;
+    let mut biblio = state.services.catalog.get_biblio(id).await?;
+    // Informative only: an unreachable Redis must not hide the record
+    biblio.edit_lock = state.services.edit_locks.get(id).await.ok().flatten();
     Ok(Json(biblio))
 }
 
 /// Query params for create biblio
 #[serde_as]
-#[derive(Debug, Deserialize, Default, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateBiblioQuery {
     /// If true, allow creating a biblio even when another has the same ISBN
@@ -163,7 +222,7 @@ pub struct CreateBiblioQuery {
 }
 
 /// Response body for biblio creation (biblio + optional dedup report)
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateBiblioResponse {
     pub biblio: Biblio,
@@ -194,6 +253,9 @@ pub struct ImportMarcBatchQuery {
     pub allow_duplicate_isbn: bool,
     /// Set to the existing biblio ID to confirm replacement of a duplicate
     pub confirm_replace_existing_id: Option<i64>,
+    /// If true, store a PDF processing slip artifact per created copy
+    #[serde(default)]
+    pub processing_slips: bool,
 }
 
 /// Create a new bibliographic record (with ISBN deduplication)
@@ -210,7 +272,7 @@ pub struct ImportMarcBatchQuery {
     responses(
         (status = 201, description = "Biblio created or merged", body = CreateBiblioResponse),
         (status = 400, description = "Invalid input"),
-        (status = 409, description = "Duplicate ISBN requires confirmation", body = crate::models::import_report::DuplicateConfirmationRequired)
+        (status = 409, description = "Duplicate ISBN requires confirmation", body = DuplicateConfirmationRequired)
     )
 )]
 pub async fn create_biblio(
@@ -294,7 +356,9 @@ pub async fn load_marc(
 ///
 /// Returns `202 Accepted` immediately with a `taskId`.  Poll `GET /tasks/:id`
 /// until `status` is `completed` or `failed`.  The `result` field of the
-/// completed task contains a `MarcBatchImportReport`.
+/// completed task contains a `MarcBatchImportReport`, plus `reportArtifactId`: the
+/// same report stored as an artifact (see `GET /artifacts/:id/link`). With
+/// `processingSlips=true`, `slipArtifactIds` lists one PDF processing slip per created copy.
 #[utoipa::path(
     post,
     path = "/biblios/import-marc-batch",
@@ -305,7 +369,8 @@ pub async fn load_marc(
         ("source_id" = String, Query, description = "Source ID to attach to imported biblios"),
         ("record_id" = Option<usize>, Query, description = "Optional single record index; if omitted, all records in the batch are imported"),
         ("allow_duplicate_isbn" = Option<bool>, Query, description = "Allow creating a biblio even when another has the same ISBN (default: false)"),
-        ("confirm_replace_existing_id" = Option<i64>, Query, description = "Confirm replacement of an existing biblio by its ID")
+        ("confirm_replace_existing_id" = Option<i64>, Query, description = "Confirm replacement of an existing biblio by its ID"),
+        ("processingSlips" = Option<bool>, Query, description = "Store a PDF processing slip per created copy (default: false)")
     ),
     responses(
         (status = 202, description = "Import task accepted", body = TaskAcceptedResponse),
@@ -319,14 +384,31 @@ pub async fn import_marc_batch(
     Query(params): Query<ImportMarcBatchQuery>,
 ) -> AppResult<(StatusCode, Json<TaskAcceptedResponse>)> {
     claims.require_write_items()?;
+    let task_id = spawn_marc_batch_import(&state, claims.user_id, ip, params, None);
+    Ok((StatusCode::ACCEPTED, Json(TaskAcceptedResponse { task_id })))
+}
 
+/// Spawn the background import of a cached MARC batch; returns the task id.
+///
+/// Copies are flagged as deposit copies when `deposit_id` is set (see `POST /deposits/:id/import-marc-batch`).
+pub(crate) fn spawn_marc_batch_import(
+    state: &crate::AppState,
+    user_id: i64,
+    ip: Option<String>,
+    params: ImportMarcBatchQuery,
+    deposit_id: Option<i64>,
+) -> i64 {
     let marc = state.services.marc.clone();
     let audit = state.services.audit.clone();
-    let p = params.clone();
+    let artifacts = state.services.artifacts.clone();
+    let exports = state.services.exports.clone();
+    let notifications = state.services.notifications.clone();
+    let p = params;
+    let entity = deposit_id.map(|_| "deposit");
 
-    let task_id = state.services.tasks.spawn_task(
+    state.services.tasks.spawn_task(
         TaskKind::MarcBatchImport,
-        claims.user_id,
+        user_id,
         move |handle| async move {
             match marc
                 .import_from_batch(
@@ -335,6 +417,7 @@ pub async fn import_marc_batch(
                     p.record_id,
                     p.allow_duplicate_isbn,
                     p.confirm_replace_existing_id,
+                    deposit_id,
                     Some(handle.clone()),
                 )
                 .await
@@ -342,22 +425,36 @@ pub async fn import_marc_batch(
                 Ok(report) => {
                     audit.log(
                         audit::event::IMPORT_MARC_BATCH,
-                        Some(claims.user_id),
-                        None,
-                        None,
+                        Some(user_id),
+                        entity,
+                        deposit_id,
                         ip.clone(),
                         Some(&p),
                         audit::AuditLogMeta::success(),
                     );
-                    let result = serde_json::to_value(&report).unwrap_or_default();
+                    let mut result = serde_json::to_value(&report).unwrap_or_default();
+                    // Keep the full report downloadable after the task result is gone
+                    match store_import_report(&artifacts, p.batch_id, user_id, &result).await {
+                        Ok(artifact) => {
+                            result["reportArtifactId"] = serde_json::json!(artifact.id.to_string());
+                            notifications.notify(user_id, &import_report_notification(p.batch_id, &artifact)).await;
+                        }
+                        Err(e) => tracing::warn!("Failed to store import report of batch {}: {}", p.batch_id, e),
+                    }
+                    if p.processing_slips {
+                        match store_processing_slips(&exports, &artifacts, &report.created_item_ids, user_id).await {
+                            Ok(ids) => result["slipArtifactIds"] = serde_json::json!(ids),
+                            Err(e) => tracing::warn!("Failed to store processing slips of batch {}: {}", p.batch_id, e),
+                        }
+                    }
                     handle.complete(result).await;
                 }
                 Err(e) => {
                     audit.log(
                         audit::event::IMPORT_MARC_BATCH,
-                        Some(claims.user_id),
-                        None,
-                        None,
+                        Some(user_id),
+                        entity,
+                        deposit_id,
                         ip,
                         Some(&p),
                         audit::AuditLogMeta::from_app_error(&e),
@@ -366,9 +463,64 @@ pub async fn import_marc_batch(
                 }
             }
         },
-    );
+    )
+}
 
-    Ok((StatusCode::ACCEPTED, Json(TaskAcceptedResponse { task_id })))
+async fn store_import_report(
+    artifacts: &crate::services::artifacts::ArtifactsService,
+    batch_id: i64,
+    user_id: i64,
+    report: &serde_json::Value,
+) -> AppResult<crate::models::artifact::Artifact> {
+    let json = serde_json::to_vec_pretty(report)
+        .map_err(|e| AppError::Internal(format!("Import report serialization: {}", e)))?;
+    artifacts
+        .store_bytes(
+            ArtifactKind::ImportReport,
+            &format!("import_report_{}.json", batch_id),
+            "application/json",
+            Some(user_id),
+            &json,
+        )
+        .await
+}
+
+/// In-app notification pointing to a stored import report
+fn import_report_notification(batch_id: i64, artifact: &crate::models::artifact::Artifact) -> NewNotification {
+    NewNotification {
+        kind: NotificationKind::ImportReportReady,
+        title: format!("Import report of batch {} is ready", batch_id),
+        body: None,
+        entity_type: Some("artifact".to_string()),
+        entity_id: Some(artifact.id),
+        data: Some(serde_json::json!({
+            "batchId": batch_id.to_string(),
+            "filename": artifact.filename,
+        })),
+    }
+}
+
+/// Store one PDF processing slip artifact per copy; returns the artifact ids (as strings).
+pub(crate) async fn store_processing_slips(
+    exports: &crate::services::exports::ExportsService,
+    artifacts: &crate::services::artifacts::ArtifactsService,
+    item_ids: &[i64],
+    user_id: i64,
+) -> AppResult<Vec<String>> {
+    let mut ids = Vec::with_capacity(item_ids.len());
+    for (item_id, pdf) in exports.processing_slips(item_ids).await? {
+        let artifact = artifacts
+            .store_bytes(
+                ArtifactKind::ProcessingSlip,
+                &format!("processing_slip_{}.pdf", item_id),
+                "application/pdf",
+                Some(user_id),
+                &pdf,
+            )
+            .await?;
+        ids.push(artifact.id.to_string());
+    }
+    Ok(ids)
 }
 
 /// Update an existing bibliographic record
@@ -411,7 +563,7 @@ pub async fn update_biblio(
     Ok(Json(updated))
 }
 
-#[derive(Debug, Deserialize, Default, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateBiblioQuery {
     #[serde(default)]
@@ -460,12 +612,228 @@ pub async fn delete_biblio(
     Ok(StatusCode::NO_CONTENT)
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteBiblioParams {
     pub force: Option<bool>,
 }
 
+/// Current edit lock of a record
+#[utoipa::path(
+    get,
+    path = "/biblios/{id}/lock",
+    tag = "biblios",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Biblio ID")),
+    responses(
+        (status = 200, description = "Lock holder, or null when nobody edits the record", body = Option<EditLock>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn get_edit_lock(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Option<EditLock>>> {
+    claims.require_read_items()?;
+    let lock = state.services.edit_locks.get(id).await?;
+    Ok(Json(lock))
+}
+
+/// Take the edit lock of a record, or renew one's own (heartbeat)
+///
+/// The lock is advisory: it warns other catalogers but does not block updates. It lapses after
+/// two minutes unless this endpoint is called again.
+#[utoipa::path(
+    post,
+    path = "/biblios/{id}/lock",
+    tag = "biblios",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Biblio ID")),
+    responses(
+        (status = 200, description = "Lock taken or renewed", body = EditLock),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Biblio not found", body = ErrorResponse),
+        (status = 409, description = "Another user holds the lock", body = ErrorResponse),
+    )
+)]
+pub async fn acquire_edit_lock(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<EditLock>> {
+    claims.require_write_items()?;
+    state.services.catalog.get_biblio_short(id).await?;
+    let lock = state.services.edit_locks.acquire(id, claims.user_id, &claims.sub).await?;
+    Ok(Json(lock))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ReleaseEditLockQuery {
+    /// Remove another user's lock (administrators)
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Release the edit lock of a record
+#[utoipa::path(
+    delete,
+    path = "/biblios/{id}/lock",
+    tag = "biblios",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Biblio ID"), ReleaseEditLockQuery),
+    responses(
+        (status = 204, description = "Lock released (or the record was not locked)"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions (force requires an administrator)", body = ErrorResponse),
+        (status = 409, description = "Another user holds the lock", body = ErrorResponse),
+    )
+)]
+pub async fn release_edit_lock(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Query(query): Query<ReleaseEditLockQuery>,
+) -> AppResult<StatusCode> {
+    claims.require_write_items()?;
+    if query.force {
+        claims.require_admin()?;
+    }
+    let previous = state.services.edit_locks.release(id, claims.user_id, query.force).await?;
+    if let Some(lock) = previous.filter(|l| l.user_id != claims.user_id) {
+        state.services.audit.log(
+            audit::event::BIBLIO_LOCK_FORCE_RELEASED,
+            Some(claims.user_id),
+            Some("biblio"),
+            Some(id),
+            ip,
+            Some(&lock),
+            audit::AuditLogMeta::success(),
+        );
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct MarcDiffQuery {
+    /// Z39.50 search result to compare with (`id` of a `/z3950/search` biblio)
+    #[serde_as(as = "DisplayFromStr")]
+    #[param(value_type = String)]
+    pub remote_id: i64,
+    /// MARC flavour used for tags (default: unimarc)
+    #[serde(default)]
+    pub format: MarcDiffFormat,
+}
+
+/// Field-level differences between a local biblio and a remote record
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarcDiffResponse {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub biblio_id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub remote_id: i64,
+    pub format: MarcDiffFormat,
+    /// One entry per tag found on either side, in tag order
+    pub fields: Vec<MarcFieldDiff>,
+}
+
+/// Selective re-import of a remote record
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyMarcDiffRequest {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub remote_id: i64,
+    /// Tags taken from the remote record; every other local field is kept
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub format: MarcDiffFormat,
+}
+
+#[derive(Serialize)]
+struct MarcDiffAppliedAudit<'a> {
+    remote_id: i64,
+    tags: &'a [String],
+}
+
+/// Compare a biblio with a record from the Z39.50 search cache, tag by tag
+#[utoipa::path(
+    get,
+    path = "/biblios/{id}/marc-diff",
+    tag = "biblios",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Biblio ID"), MarcDiffQuery),
+    responses(
+        (status = 200, description = "Field-level differences (local vs remote)", body = MarcDiffResponse),
+        (status = 404, description = "Biblio not found or remote record no longer cached")
+    )
+)]
+pub async fn get_marc_diff(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+    Query(query): Query<MarcDiffQuery>,
+) -> AppResult<Json<MarcDiffResponse>> {
+    claims.require_read_items()?;
+    let fields = state.services.z3950.marc_diff(id, query.remote_id, query.format).await?;
+    Ok(Json(MarcDiffResponse { biblio_id: id, remote_id: query.remote_id, format: query.format, fields }))
+}
+
+/// Update a biblio from a cached remote record, taking only the selected tags
+#[utoipa::path(
+    post,
+    path = "/biblios/{id}/marc-diff/apply",
+    tag = "biblios",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Biblio ID")),
+    request_body = ApplyMarcDiffRequest,
+    responses(
+        (status = 200, description = "Biblio updated; copies and non-selected fields are kept", body = Biblio),
+        (status = 400, description = "No or invalid tags"),
+        (status = 404, description = "Biblio not found or remote record no longer cached"),
+        (status = 409, description = "The remote ISBN belongs to another biblio")
+    )
+)]
+pub async fn apply_marc_diff(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(request): Json<ApplyMarcDiffRequest>,
+) -> AppResult<Json<Biblio>> {
+    claims.require_write_items()?;
+    let updated = state
+        .services
+        .z3950
+        .apply_marc_diff(id, request.remote_id, &request.tags, request.format)
+        .await?;
+
+    state.services.audit.log(
+        audit::event::BIBLIO_UPDATED,
+        Some(claims.user_id),
+        Some("biblio"),
+        Some(id),
+        ip,
+        Some(MarcDiffAppliedAudit { remote_id: request.remote_id, tags: &request.tags }),
+        audit::AuditLogMeta::success(),
+    );
+
+    Ok(Json(updated))
+}
+
 /// List physical items for a bibliographic record
 #[utoipa::path(
     get,
@@ -504,7 +872,8 @@ pub async fn list_items(
     responses(
         (status = 201, description = "Physical item created", body = Item),
         (status = 404, description = "Biblio not found"),
-        (status = 409, description = "An item with this barcode already exists", body = crate::models::import_report::DuplicateItemBarcodeRequired)
+        (status = 409, description = "An item with this barcode already exists", body = DuplicateItemBarcodeRequired),
+        (status = 422, description = "Invalid fields", body = InvalidFieldsResponse)
     )
 )]
 pub async fn create_item(
@@ -582,6 +951,7 @@ pub async fn load_marc_batch(
 ///
 /// Returns a UTF-8 CSV file with all bibliographic records matching the query.
 /// Streams all pages — does not paginate. Use the same query params as `GET /biblios`.
+/// The file is stored as an artifact; `delivery=link` returns a signed download URL instead.
 #[utoipa::path(
     get,
     path = "/biblios/export.csv",
@@ -590,17 +960,20 @@ pub async fn load_marc_batch(
     params(
         ("title" = Option<String>, Query, description = "Filter by title"),
         ("author" = Option<String>, Query, description = "Filter by author"),
-        ("media_type" = Option<String>, Query, description = "Filter by media type")
+        ("media_type" = Option<String>, Query, description = "Filter by media type"),
+        ExportDeliveryQuery
     ),
     responses(
         (status = 200, description = "CSV file", content_type = "text/csv"),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse)
+        (status = 201, description = "Export stored (`delivery=link`)", body = ArtifactLink),
+        (status = 401, description = "Not authenticated", body = ErrorResponse)
     )
 )]
 pub async fn export_biblios_csv(
     State(state): State<crate::AppState>,
     AuthenticatedUser(claims): AuthenticatedUser,
     Query(mut query): Query<BiblioQuery>,
+    Query(delivery): Query<ExportDeliveryQuery>,
 ) -> AppResult<axum::response::Response> {
     claims.require_read_catalog()?;
 
@@ -609,7 +982,17 @@ pub async fn export_biblios_csv(
 
     let (biblios, _) = state.services.catalog.search_biblios(&query).await?;
 
-    let mut csv = String::from("id,isbn,title,author,media_type,date,items\n");
+    let mut writer = state
+        .services
+        .artifacts
+        .writer(
+            ArtifactKind::CatalogExport,
+            "catalog.csv",
+            "text/csv; charset=utf-8",
+            Some(claims.user_id),
+        )
+        .await?;
+    writer.write_str("id,isbn,title,author,media_type,date,items\n").await?;
     for biblio in &biblios {
         let author_name = biblio
             .author
@@ -624,38 +1007,20 @@ pub async fn export_biblios_csv(
                 .to_string()
             })
             .unwrap_or_default();
-        csv.push_str(&format!(
-            "{},{},{},{},{},{},{}\n",
-            biblio.id,
-            escape_csv(biblio.isbn.as_ref().map(|i| i.as_str()).unwrap_or("")),
-            escape_csv(biblio.title.as_deref().unwrap_or("")),
-            escape_csv(&author_name),
-            escape_csv(biblio.media_type.as_db_str()),
-            escape_csv(biblio.date.as_deref().unwrap_or("")),
-            biblio.items.len(),
-        ));
+        writer
+            .write_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                biblio.id,
+                escape_csv(biblio.isbn.as_ref().map(|i| i.as_str()).unwrap_or("")),
+                escape_csv(biblio.title.as_deref().unwrap_or("")),
+                escape_csv(&author_name),
+                escape_csv(biblio.media_type.as_db_str()),
+                escape_csv(biblio.date.as_deref().unwrap_or("")),
+                biblio.items.len(),
+            ))
+            .await?;
     }
+    let artifact = writer.finish().await?;
 
-    use axum::http::header;
-    Ok((
-        [
-            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
-            (
-                header::CONTENT_DISPOSITION,
-                "attachment; filename=\"catalog.csv\"",
-            ),
-        ],
-        csv,
-    )
-        .into_response())
+    super::artifacts::deliver(&state, artifact, delivery.delivery).await
 }
-
-fn escape_csv(s: &str) -> String {
-    if s.contains([',', '"', '\n']) {
-        format!("\"{}\"", s.replace('"', "\"\""))
-    } else {
-        s.to_string()
-    }
-}
-
-
@@ -0,0 +1,185 @@
+//! Item bundle API endpoints (box sets and multi-volume works circulating as one unit)
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::bundle::{BundleReturnReport, CreateItemBundle, ItemBundle, ReturnItemBundle, UpdateItemBundle},
+    services::audit,
+};
+
+use super::{sse, AuthenticatedUser, ClientIp};
+
+/// Build the item bundle routes for this domain.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post};
+    axum::Router::new()
+        .route("/bundles", get(list_bundles).post(create_bundle))
+        .route("/bundles/:id", get(get_bundle).put(update_bundle).delete(delete_bundle))
+        .route("/bundles/:id/return", post(return_bundle))
+}
+
+/// List item bundles
+#[utoipa::path(
+    get,
+    path = "/bundles",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Bundles with part counters", body = Vec<ItemBundle>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_bundles(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<ItemBundle>>> {
+    claims.require_read_items()?;
+    Ok(Json(state.services.bundles.list().await?))
+}
+
+/// Get a bundle with its parts
+#[utoipa::path(
+    get,
+    path = "/bundles/{id}",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Bundle ID")),
+    responses(
+        (status = 200, description = "Bundle", body = ItemBundle),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_bundle(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<ItemBundle>> {
+    claims.require_read_items()?;
+    Ok(Json(state.services.bundles.get(id).await?))
+}
+
+/// Create a bundle from copy barcodes
+#[utoipa::path(
+    post,
+    path = "/bundles",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    request_body = CreateItemBundle,
+    responses(
+        (status = 201, description = "Bundle created", body = ItemBundle),
+        (status = 400, description = "Invalid name or part list", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Copy not found", body = ErrorResponse),
+        (status = 409, description = "Barcode already in use", body = ErrorResponse),
+        (status = 422, description = "A copy already belongs to another bundle", body = ErrorResponse),
+    )
+)]
+pub async fn create_bundle(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateItemBundle>,
+) -> AppResult<(StatusCode, Json<ItemBundle>)> {
+    claims.require_write_items()?;
+    let bundle = state.services.bundles.create(&data).await?;
+    state.services.audit.log(audit::event::BUNDLE_CREATED, Some(claims.user_id), Some("item_bundle"), Some(bundle.id), ip, Some(&data), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(bundle)))
+}
+
+/// Update a bundle (parts can only change while no part is on loan)
+#[utoipa::path(
+    put,
+    path = "/bundles/{id}",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Bundle ID")),
+    request_body = UpdateItemBundle,
+    responses(
+        (status = 200, description = "Bundle updated", body = ItemBundle),
+        (status = 400, description = "Invalid name or part list", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Bundle or copy not found", body = ErrorResponse),
+        (status = 409, description = "Barcode already in use", body = ErrorResponse),
+        (status = 422, description = "Parts on loan, or a copy belongs to another bundle", body = ErrorResponse),
+    )
+)]
+pub async fn update_bundle(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdateItemBundle>,
+) -> AppResult<Json<ItemBundle>> {
+    claims.require_write_items()?;
+    let bundle = state.services.bundles.update(id, &data).await?;
+    state.services.audit.log(audit::event::BUNDLE_UPDATED, Some(claims.user_id), Some("item_bundle"), Some(id), ip, Some(&data), audit::AuditLogMeta::success());
+    Ok(Json(bundle))
+}
+
+/// Delete a bundle; its copies circulate on their own again
+#[utoipa::path(
+    delete,
+    path = "/bundles/{id}",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Bundle ID")),
+    responses(
+        (status = 204, description = "Bundle deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 422, description = "Parts on loan", body = ErrorResponse),
+    )
+)]
+pub async fn delete_bundle(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_items()?;
+    state.services.bundles.delete(id).await?;
+    state.services.audit.log(audit::event::BUNDLE_DELETED, Some(claims.user_id), Some("item_bundle"), Some(id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Return the scanned parts of a bundle and report the parts still missing
+#[utoipa::path(
+    post,
+    path = "/bundles/{id}/return",
+    tag = "loans",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Bundle ID")),
+    request_body = ReturnItemBundle,
+    responses(
+        (status = 200, description = "Parts returned; `missing` lists the parts still on loan", body = BundleReturnReport),
+        (status = 400, description = "Invalid barcode list", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 422, description = "A barcode is not a part of the bundle, or no scanned part is on loan", body = ErrorResponse),
+    )
+)]
+pub async fn return_bundle(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<ReturnItemBundle>,
+) -> AppResult<Json<BundleReturnReport>> {
+    claims.require_write_loans()?;
+    let report = state.services.bundles.return_parts(id, &data).await?;
+    sse::publish_counters(&state);
+    state.services.audit.log(audit::event::BUNDLE_RETURNED, Some(claims.user_id), Some("item_bundle"), Some(id), ip, Some(&report), audit::AuditLogMeta::success());
+    Ok(Json(report))
+}
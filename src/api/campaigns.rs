@@ -0,0 +1,334 @@
+//! Email campaign API endpoints (`/campaigns`) and the patron campaign opt-in
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use utoipa::ToSchema;
+
+use crate::{
+    error::AppResult,
+    models::{
+        campaign::{
+            Campaign, CampaignBounceReport, CampaignBounces, CampaignRecipient, CampaignRecipientsQuery,
+            CampaignSegment, CampaignSegmentPreview, CampaignsOptIn, CreateCampaign, UpdateCampaign,
+        },
+        task::TaskKind,
+    },
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp, SelfServiceUser};
+
+/// Send accepted: the counters are updated while the background task drains the queue
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignSendStarted {
+    /// Background task id (`GET /tasks/:id`)
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub task_id: i64,
+    pub campaign: Campaign,
+}
+
+/// Build the `/campaigns` routes.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post, put};
+    axum::Router::new()
+        .route("/campaigns", get(list_campaigns).post(create_campaign))
+        .route("/campaigns/preview", post(preview_campaign_segment))
+        .route(
+            "/campaigns/:id",
+            get(get_campaign).put(update_campaign).delete(delete_campaign),
+        )
+        .route("/campaigns/:id/send", post(send_campaign))
+        .route("/campaigns/:id/recipients", get(list_campaign_recipients))
+        .route("/campaigns/:id/bounces", post(record_campaign_bounces))
+        .route("/users/:id/campaigns-opt-in", put(update_campaigns_opt_in))
+}
+
+/// List campaigns with their delivery counters (most recent first)
+#[utoipa::path(
+    get,
+    path = "/campaigns",
+    tag = "campaigns",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Campaigns", body = Vec<Campaign>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+    )
+)]
+pub async fn list_campaigns(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<Campaign>>> {
+    claims.require_read_users()?;
+    Ok(Json(state.services.campaigns.list().await?))
+}
+
+/// Create a draft campaign
+#[utoipa::path(
+    post,
+    path = "/campaigns",
+    tag = "campaigns",
+    security(("bearer_auth" = [])),
+    request_body = CreateCampaign,
+    responses(
+        (status = 201, description = "Draft created", body = Campaign),
+        (status = 400, description = "Invalid text or segment", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+    )
+)]
+pub async fn create_campaign(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateCampaign>,
+) -> AppResult<(StatusCode, Json<Campaign>)> {
+    claims.require_write_users()?;
+    let campaign = state.services.campaigns.create(&data, claims.user_id).await?;
+    state.services.audit.log(audit::event::CAMPAIGN_CREATED, Some(claims.user_id), Some("campaign"), Some(campaign.id), ip, Some(&campaign), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(campaign)))
+}
+
+/// Count the addresses a segment currently resolves to
+#[utoipa::path(
+    post,
+    path = "/campaigns/preview",
+    tag = "campaigns",
+    security(("bearer_auth" = [])),
+    request_body = CampaignSegment,
+    responses(
+        (status = 200, description = "Recipient count", body = CampaignSegmentPreview),
+        (status = 400, description = "Invalid segment", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+    )
+)]
+pub async fn preview_campaign_segment(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Json(segment): Json<CampaignSegment>,
+) -> AppResult<Json<CampaignSegmentPreview>> {
+    claims.require_read_users()?;
+    Ok(Json(state.services.campaigns.preview(&segment).await?))
+}
+
+/// Get a campaign with its delivery counters
+#[utoipa::path(
+    get,
+    path = "/campaigns/{id}",
+    tag = "campaigns",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Campaign ID")),
+    responses(
+        (status = 200, description = "Campaign", body = Campaign),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_campaign(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Campaign>> {
+    claims.require_read_users()?;
+    Ok(Json(state.services.campaigns.get(id).await?))
+}
+
+/// Edit a draft campaign
+#[utoipa::path(
+    put,
+    path = "/campaigns/{id}",
+    tag = "campaigns",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Campaign ID")),
+    request_body = UpdateCampaign,
+    responses(
+        (status = 200, description = "Campaign updated", body = Campaign),
+        (status = 400, description = "Invalid text or segment", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "The campaign is no longer a draft", body = ErrorResponse),
+    )
+)]
+pub async fn update_campaign(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdateCampaign>,
+) -> AppResult<Json<Campaign>> {
+    claims.require_write_users()?;
+    let campaign = state.services.campaigns.update(id, &data).await?;
+    state.services.audit.log(audit::event::CAMPAIGN_UPDATED, Some(claims.user_id), Some("campaign"), Some(id), ip, Some(&data), audit::AuditLogMeta::success());
+    Ok(Json(campaign))
+}
+
+/// Delete a draft or sent campaign with its delivery report
+#[utoipa::path(
+    delete,
+    path = "/campaigns/{id}",
+    tag = "campaigns",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Campaign ID")),
+    responses(
+        (status = 204, description = "Campaign deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "The campaign is being sent", body = ErrorResponse),
+    )
+)]
+pub async fn delete_campaign(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_users()?;
+    state.services.campaigns.delete(id).await?;
+    state.services.audit.log(audit::event::CAMPAIGN_DELETED, Some(claims.user_id), Some("campaign"), Some(id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Send a draft campaign in the background, or resume a send interrupted by a restart.
+///
+/// The segment is resolved once, when the draft is queued; the queue is then drained at the
+/// `reminders.smtpThrottleMs` rate.
+#[utoipa::path(
+    post,
+    path = "/campaigns/{id}/send",
+    tag = "campaigns",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Campaign ID")),
+    responses(
+        (status = 202, description = "Send started", body = CampaignSendStarted),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "The campaign was already sent", body = ErrorResponse),
+    )
+)]
+pub async fn send_campaign(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<(StatusCode, Json<CampaignSendStarted>)> {
+    claims.require_write_users()?;
+    let campaigns = state.services.campaigns.clone();
+    let campaign = campaigns.start_send(id).await?;
+    state.services.audit.log(
+        audit::event::CAMPAIGN_SEND_STARTED,
+        Some(claims.user_id),
+        Some("campaign"),
+        Some(id),
+        ip,
+        Some(serde_json::json!({ "recipients": campaign.recipients_count, "pending": campaign.pending_count })),
+        audit::AuditLogMeta::success(),
+    );
+
+    let task_id = state.services.tasks.spawn_task(TaskKind::CampaignSend, claims.user_id, move |handle| async move {
+        match campaigns.execute(id).await {
+            Ok(campaign) => handle.complete(serde_json::to_value(&campaign).unwrap_or_default()).await,
+            Err(e) => handle.fail(e.to_string()).await,
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(CampaignSendStarted { task_id, campaign })))
+}
+
+/// Queued recipients of a campaign with their delivery state
+#[utoipa::path(
+    get,
+    path = "/campaigns/{id}/recipients",
+    tag = "campaigns",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Campaign ID"), CampaignRecipientsQuery),
+    responses(
+        (status = 200, description = "Recipients by address", body = Vec<CampaignRecipient>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn list_campaign_recipients(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+    Query(query): Query<CampaignRecipientsQuery>,
+) -> AppResult<Json<Vec<CampaignRecipient>>> {
+    claims.require_read_users()?;
+    Ok(Json(state.services.campaigns.recipients(id, query.status).await?))
+}
+
+/// Record bounces reported by the mail provider (bounce notifications or provider export)
+#[utoipa::path(
+    post,
+    path = "/campaigns/{id}/bounces",
+    tag = "campaigns",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Campaign ID")),
+    request_body = CampaignBounces,
+    responses(
+        (status = 200, description = "Bounces recorded", body = CampaignBounceReport),
+        (status = 400, description = "Empty or too many addresses", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn record_campaign_bounces(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<CampaignBounces>,
+) -> AppResult<Json<CampaignBounceReport>> {
+    claims.require_write_users()?;
+    let report = state.services.campaigns.record_bounces(id, &data.emails).await?;
+    state.services.audit.log(audit::event::CAMPAIGN_BOUNCES_RECORDED, Some(claims.user_id), Some("campaign"), Some(id), ip, Some(&report), audit::AuditLogMeta::success());
+    Ok(Json(report))
+}
+
+/// Opt in to (or out of) email campaigns.
+///
+/// Patrons can change their own consent, self-service tokens included; staff need users write
+/// rights.
+#[utoipa::path(
+    put,
+    path = "/users/{id}/campaigns-opt-in",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "User ID")),
+    request_body = CampaignsOptIn,
+    responses(
+        (status = 200, description = "Consent recorded", body = CampaignsOptIn),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_campaigns_opt_in(
+    State(state): State<crate::AppState>,
+    SelfServiceUser(claims): SelfServiceUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<CampaignsOptIn>,
+) -> AppResult<Json<CampaignsOptIn>> {
+    if claims.user_id != id {
+        claims.require_write_users()?;
+    }
+    state.services.campaigns.set_opt_in(id, data.opt_in).await?;
+    state.services.audit.log(audit::event::USER_CAMPAIGNS_OPT_IN_UPDATED, Some(claims.user_id), Some("user"), Some(id), ip, Some(&data), audit::AuditLogMeta::success());
+    Ok(Json(data))
+}
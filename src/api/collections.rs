@@ -7,7 +7,7 @@ use axum::{
     Json, Router,
 };
 use axum::routing::get;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::{
@@ -18,7 +18,7 @@ use crate::{
 use super::AuthenticatedUser;
 
 /// Paginated list of collections.
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PaginatedCollections {
     pub items: Vec<Collection>,
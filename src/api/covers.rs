@@ -9,10 +9,10 @@ use axum::{
     http::{header, StatusCode},
     response::Response,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Cover image size
-#[derive(Debug, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum CoverSize {
     S,
@@ -37,7 +37,7 @@ impl Default for CoverSize {
 }
 
 /// Cover image query params
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct CoverQuery {
     #[serde(default)]
     pub size: CoverSize,
@@ -57,7 +57,8 @@ pub struct CoverQuery {
     ),
     responses(
         (status = 200, description = "Cover image (JPEG)", content_type = "image/jpeg"),
-        (status = 404, description = "No cover available for this ISBN")
+        (status = 404, description = "No cover available for this ISBN"),
+        (status = 502, description = "Cover service unreachable")
     )
 )]
 pub async fn get_cover_by_isbn(
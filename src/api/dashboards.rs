@@ -0,0 +1,279 @@
+//! Personal dashboard endpoints (`/auth/my-dashboard`): the caller's widgets and their data
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{
+        dashboard::{
+            widget_params, Dashboard, DashboardData, DashboardWidget, DashboardWidgetData,
+            DashboardWidgetType, DashboardWidgetWrite, SaveDashboard, SavedStatsRef,
+        },
+        user::UserClaims,
+    },
+    repository::stats::{saved_queries, saved_reports},
+    services::stats::{run_report, run_stats_query},
+};
+
+use super::{stats, AuthenticatedUser, Locale};
+
+/// Build the personal dashboard routes for this domain.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post, put};
+    axum::Router::new()
+        .route(
+            "/auth/my-dashboard",
+            get(get_my_dashboard).put(save_my_dashboard).delete(reset_my_dashboard),
+        )
+        .route("/auth/my-dashboard/data", get(get_my_dashboard_data))
+        .route("/auth/my-dashboard/widgets", post(add_my_dashboard_widget))
+        .route(
+            "/auth/my-dashboard/widgets/:id",
+            put(update_my_dashboard_widget).delete(delete_my_dashboard_widget),
+        )
+}
+
+/// Get the caller's dashboard (widgets in display order, empty when never configured)
+#[utoipa::path(
+    get,
+    path = "/auth/my-dashboard",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Dashboard widgets", body = Dashboard),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+    )
+)]
+pub async fn get_my_dashboard(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Dashboard>> {
+    Ok(Json(state.services.dashboards.get(claims.user_id).await?))
+}
+
+/// Save the whole dashboard layout: replaces every widget, in the order of the list.
+///
+/// Widgets get new ids; use the widget endpoints to change one widget and keep the others.
+#[utoipa::path(
+    put,
+    path = "/auth/my-dashboard",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    request_body = SaveDashboard,
+    responses(
+        (status = 200, description = "Saved dashboard", body = Dashboard),
+        (status = 400, description = "Too many widgets, title too long or invalid params", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+    )
+)]
+pub async fn save_my_dashboard(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Json(data): Json<SaveDashboard>,
+) -> AppResult<Json<Dashboard>> {
+    Ok(Json(state.services.dashboards.save(claims.user_id, &data.widgets).await?))
+}
+
+/// Remove every widget of the caller's dashboard
+#[utoipa::path(
+    delete,
+    path = "/auth/my-dashboard",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Dashboard reset"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+    )
+)]
+pub async fn reset_my_dashboard(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<StatusCode> {
+    state.services.dashboards.reset(claims.user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Add a widget after the last one
+#[utoipa::path(
+    post,
+    path = "/auth/my-dashboard/widgets",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    request_body = DashboardWidgetWrite,
+    responses(
+        (status = 201, description = "Widget added", body = DashboardWidget),
+        (status = 400, description = "Dashboard full, title too long or invalid params", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+    )
+)]
+pub async fn add_my_dashboard_widget(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Json(data): Json<DashboardWidgetWrite>,
+) -> AppResult<(StatusCode, Json<DashboardWidget>)> {
+    let widget = state.services.dashboards.add_widget(claims.user_id, &data).await?;
+    Ok((StatusCode::CREATED, Json(widget)))
+}
+
+/// Replace the definition of one of the caller's widgets (its position is kept)
+#[utoipa::path(
+    put,
+    path = "/auth/my-dashboard/widgets/{id}",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Widget ID")),
+    request_body = DashboardWidgetWrite,
+    responses(
+        (status = 200, description = "Widget updated", body = DashboardWidget),
+        (status = 400, description = "Title too long or invalid params", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Not one of the caller's widgets", body = ErrorResponse),
+    )
+)]
+pub async fn update_my_dashboard_widget(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+    Json(data): Json<DashboardWidgetWrite>,
+) -> AppResult<Json<DashboardWidget>> {
+    Ok(Json(state.services.dashboards.update_widget(claims.user_id, id, &data).await?))
+}
+
+/// Remove one of the caller's widgets
+#[utoipa::path(
+    delete,
+    path = "/auth/my-dashboard/widgets/{id}",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Widget ID")),
+    responses(
+        (status = 204, description = "Widget removed"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Not one of the caller's widgets", body = ErrorResponse),
+    )
+)]
+pub async fn delete_my_dashboard_widget(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    state.services.dashboards.delete_widget(claims.user_id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Data of every widget of the caller's dashboard, in one call.
+///
+/// Each widget is resolved like a call to its endpoint with the stored parameters, with the
+/// caller's rights and language. A widget that fails (missing rights, saved query deleted, …)
+/// carries `error` and `errorCode` instead of `data`; the other widgets are still returned.
+#[utoipa::path(
+    get,
+    path = "/auth/my-dashboard/data",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Widget data, in display order", body = DashboardData),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+    )
+)]
+pub async fn get_my_dashboard_data(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Locale(lang): Locale,
+) -> AppResult<Json<DashboardData>> {
+    let dashboard = state.services.dashboards.get(claims.user_id).await?;
+    let mut widgets = Vec::with_capacity(dashboard.widgets.len());
+    for widget in &dashboard.widgets {
+        let (data, error, error_code) = match resolve_widget(&state, &claims, &lang, widget).await {
+            Ok(data) => (Some(data), None, None),
+            Err(e) => {
+                let (_, code, message) = e.audit_http_fields();
+                (None, Some(message), Some(code.to_string()))
+            }
+        };
+        widgets.push(DashboardWidgetData {
+            widget_id: widget.id,
+            widget_type: widget.widget_type,
+            data,
+            error,
+            error_code,
+        });
+    }
+    Ok(Json(DashboardData {
+        widgets,
+        generated_at: Utc::now(),
+    }))
+}
+
+/// Response body of the widget's endpoint called with its stored parameters
+async fn resolve_widget(
+    state: &crate::AppState,
+    claims: &UserClaims,
+    lang: &str,
+    widget: &DashboardWidget,
+) -> AppResult<serde_json::Value> {
+    let user = || AuthenticatedUser(claims.clone());
+    let locale = || Locale(lang.to_string());
+    match widget.widget_type {
+        DashboardWidgetType::Stats => {
+            let Json(body) = stats::get_stats(State(state.clone()), user(), locale(), Query(params(widget)?)).await?;
+            to_data(body)
+        }
+        DashboardWidgetType::LoanStats => {
+            let Json(body) = stats::get_loan_stats(State(state.clone()), user(), locale(), Query(params(widget)?)).await?;
+            to_data(body)
+        }
+        DashboardWidgetType::UserStats => {
+            let Json(body) = stats::get_user_stats(State(state.clone()), user(), locale(), Query(params(widget)?)).await?;
+            to_data(body)
+        }
+        DashboardWidgetType::CatalogStats => {
+            let Json(body) = stats::get_catalog_stats(State(state.clone()), user(), locale(), Query(params(widget)?)).await?;
+            to_data(body)
+        }
+        DashboardWidgetType::VisitorStats => {
+            let Json(body) = stats::get_visitor_stats(State(state.clone()), user(), Query(params(widget)?)).await?;
+            to_data(body)
+        }
+        DashboardWidgetType::SavedQuery => {
+            require_staff(claims)?;
+            let SavedStatsRef { id } = params(widget)?;
+            let pool = state.services.repository_pool();
+            let saved = saved_queries::get_by_id(pool, id, claims.user_id, claims.is_admin())
+                .await?
+                .ok_or_else(|| AppError::NotFound("Saved query not found".into()))?;
+            to_data(run_stats_query(pool, Some(&state.services.redis), &saved.query).await?)
+        }
+        DashboardWidgetType::SavedReport => {
+            require_staff(claims)?;
+            let SavedStatsRef { id } = params(widget)?;
+            let pool = state.services.repository_pool();
+            let saved = saved_reports::get_by_id(pool, id, claims.user_id, claims.is_admin())
+                .await?
+                .ok_or_else(|| AppError::NotFound("Saved report not found".into()))?;
+            to_data(run_report(pool, Some(&state.services.redis), &saved.definition).await?)
+        }
+    }
+}
+
+/// Saved queries and reports are staff-only, as their `/stats` endpoints (`StaffUser`)
+fn require_staff(claims: &UserClaims) -> AppResult<()> {
+    if !claims.is_admin() && !claims.is_librarian() {
+        return Err(AppError::Authorization("Staff access required".to_string()));
+    }
+    Ok(())
+}
+
+fn params<T: serde::de::DeserializeOwned>(widget: &DashboardWidget) -> AppResult<T> {
+    widget_params(&widget.params).map_err(|e| AppError::Validation(format!("Invalid widget params: {}", e)))
+}
+
+fn to_data(body: impl Serialize) -> AppResult<serde_json::Value> {
+    serde_json::to_value(body).map_err(|e| AppError::Internal(format!("Serialize widget data: {}", e)))
+}
@@ -0,0 +1,279 @@
+//! Demo mode (`demo.enabled`): patron PII masked in every API response.
+//!
+//! For training sessions on a copy of production. The masking happens on the way out, on the
+//! serialized JSON bodies, so stored data is never altered and no handler can forget it: values of
+//! patron fields (names, emails, phones, addresses, logins, birth dates) are replaced with fakes
+//! derived from the real value, the same on every response. Author objects keep their names.
+//!
+//! Other bodies cannot be masked, so they are refused with 403 (file exports, backups, warehouse
+//! files, event streams...) unless their type only ever carries catalog data (covers, Atom feeds,
+//! the OPAC widget, citations). Backups and warehouse exports are not even started. Every response
+//! carries `X-Demo-Mode: true` for the client banner.
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::error::{AppError, AppResult};
+
+/// Response header set on every response in demo mode
+pub const DEMO_MODE_HEADER: &str = "x-demo-mode";
+
+/// Content types passed through unmasked in demo mode: they only carry catalog data.
+/// Every other non-JSON body is refused.
+const CATALOG_CONTENT_TYPES: &[&str] = &[
+    "image/",
+    "application/atom+xml",
+    "text/html",
+    "application/javascript",
+    "application/x-bibtex",
+    "application/x-research-info-systems",
+];
+
+const FIRST_NAMES: &[&str] = &[
+    "Alice", "Bruno", "Camille", "David", "Emma", "Félix", "Gabrielle", "Hugo", "Inès", "Jules",
+    "Karima", "Louis", "Manon", "Nathan", "Océane", "Paul", "Quentin", "Rose", "Samuel", "Théa",
+    "Ugo", "Victoire", "William", "Yasmine", "Zoé", "Adrien", "Blanche", "Cyril", "Diane", "Étienne",
+    "Flora", "Gaspard",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Arnaud", "Bertin", "Carpentier", "Delmas", "Estève", "Fabre", "Gautier", "Hamon", "Imbert",
+    "Jacquet", "Kieffer", "Lemaire", "Marchal", "Noël", "Olivier", "Perrin", "Quéré", "Renaud",
+    "Sauvage", "Tessier", "Urvoy", "Vasseur", "Weber", "Yvon", "Zimmer", "Aubert", "Barbier",
+    "Chauvin", "Dumont", "Égal", "Ferrand", "Guillot",
+];
+
+/// Patron field masked in demo mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PiiField {
+    FirstName,
+    LastName,
+    FullName,
+    Email,
+    Phone,
+    Street,
+    City,
+    ZipCode,
+    BirthDate,
+    Login,
+}
+
+/// Patron field named `key`, in camelCase or snake_case (`user_email`, `addrZipCode`, …)
+fn pii_field(key: &str) -> Option<PiiField> {
+    let key: String = key.chars().filter(|c| *c != '_').flat_map(char::to_lowercase).collect();
+    Some(match key.as_str() {
+        "firstname" | "userfirstname" => PiiField::FirstName,
+        "lastname" | "userlastname" => PiiField::LastName,
+        "username" | "donorname" | "contactname" | "fullname" | "patronname" | "borrowername" => PiiField::FullName,
+        "email" | "useremail" | "donoremail" | "contactemail" => PiiField::Email,
+        "phone" | "userphone" | "donorphone" | "contactphone" => PiiField::Phone,
+        "addrstreet" => PiiField::Street,
+        "addrcity" => PiiField::City,
+        "addrzipcode" => PiiField::ZipCode,
+        "birthdate" => PiiField::BirthDate,
+        "login" => PiiField::Login,
+        _ => return None,
+    })
+}
+
+/// Subtrees left as is: author names are catalog data, not patron data
+fn is_catalog_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    key.ends_with("author") || key.ends_with("authors")
+}
+
+/// Stable number derived from a real value (same fake on every response)
+fn seed(value: &str) -> u64 {
+    let digest = Sha256::digest(value.as_bytes());
+    u64::from_be_bytes(digest[..8].try_into().expect("8 bytes"))
+}
+
+fn fake(field: PiiField, value: &Value) -> Value {
+    let text = match value {
+        Value::String(s) if !s.is_empty() => s.clone(),
+        Value::Number(n) => n.to_string(),
+        _ => return value.clone(),
+    };
+    let h = seed(&text);
+    let first = FIRST_NAMES[(h % FIRST_NAMES.len() as u64) as usize];
+    let last = LAST_NAMES[((h >> 16) % LAST_NAMES.len() as u64) as usize];
+    match field {
+        PiiField::FirstName => Value::from(first),
+        PiiField::LastName => Value::from(last),
+        PiiField::FullName => Value::from(format!("{} {}", first, last)),
+        PiiField::Email => Value::from(format!("demo-{:08x}@example.org", h as u32)),
+        PiiField::Phone => Value::from(format!("555-01{:02}-{:04}", (h >> 8) % 100, h % 10_000)),
+        PiiField::Street => Value::from(format!("{} Demo Street", h % 200 + 1)),
+        PiiField::City => Value::from("Demoville"),
+        PiiField::ZipCode => {
+            let zip = 10_000 + h % 90_000;
+            if value.is_number() { Value::from(zip) } else { Value::from(zip.to_string()) }
+        }
+        // Keep the year (age statistics), not the day
+        PiiField::BirthDate => match text.get(..4).filter(|y| y.chars().all(|c| c.is_ascii_digit())) {
+            Some(year) => Value::from(format!("{}-01-01", year)),
+            None => Value::Null,
+        },
+        PiiField::Login => Value::from(format!("demo{:05}", h % 100_000)),
+    }
+}
+
+/// Replace the patron fields of a JSON document with fakes, in place
+pub fn mask_pii(value: &mut Value) {
+    match value {
+        Value::Object(map) => mask_object(map),
+        Value::Array(items) => items.iter_mut().for_each(mask_pii),
+        _ => {}
+    }
+}
+
+fn mask_object(map: &mut Map<String, Value>) {
+    for (key, value) in map.iter_mut() {
+        if is_catalog_key(key) {
+            continue;
+        }
+        match pii_field(key) {
+            Some(field) if !value.is_object() && !value.is_array() => *value = fake(field, value),
+            _ => mask_pii(value),
+        }
+    }
+}
+
+fn content_type(response: &Response) -> String {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Whether a response body passes through demo mode without masking
+fn passes_unmasked(response: &Response, content_type: &str) -> bool {
+    // No body (204, redirects), or an error message
+    content_type.is_empty()
+        || !response.status().is_success()
+        || CATALOG_CONTENT_TYPES.iter().any(|t| content_type.starts_with(t))
+}
+
+/// Refuse an operation copying the database out of the server (backups, warehouse exports)
+pub fn refuse_in_demo_mode(state: &crate::AppState, operation: &str) -> AppResult<()> {
+    if state.config.demo.enabled {
+        return Err(AppError::Authorization(format!("{} are disabled in demo mode", operation)));
+    }
+    Ok(())
+}
+
+/// Middleware of demo mode: masks JSON bodies, refuses other data bodies, flags every response
+pub async fn demo_mode(request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+    let content_type = content_type(&response);
+    let mut response = if content_type.starts_with("application/json") || content_type.contains("+json") {
+        let (mut parts, body) = response.into_parts();
+        let masked = match axum::body::to_bytes(body, usize::MAX).await {
+            Ok(bytes) => match serde_json::from_slice::<Value>(&bytes) {
+                Ok(mut json) => {
+                    mask_pii(&mut json);
+                    serde_json::to_vec(&json).unwrap_or_default()
+                }
+                // Not JSON after all: nothing recognisable to mask
+                Err(_) => bytes.to_vec(),
+            },
+            Err(e) => {
+                tracing::warn!("Demo mode could not read a response body: {}", e);
+                return AppError::Internal("Unreadable response body".to_string()).into_response();
+            }
+        };
+        parts.headers.remove(header::CONTENT_LENGTH);
+        Response::from_parts(parts, Body::from(masked))
+    } else if passes_unmasked(&response, &content_type) {
+        response
+    } else {
+        AppError::Authorization("File exports are disabled in demo mode".to_string()).into_response()
+    };
+    response
+        .headers_mut()
+        .insert(DEMO_MODE_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn patron_fields_get_stable_fakes() {
+        let mut loans = json!({
+            "items": [{
+                "id": "1",
+                "user": {
+                    "firstname": "Jeanne",
+                    "lastname": "Durand",
+                    "email": "jeanne.durand@mail.fr",
+                    "addrZipCode": 75011,
+                    "birthdate": "1984-06-02",
+                    "phone": null
+                },
+                "biblio": { "title": "Dune", "authors": [{ "lastname": "Herbert", "firstname": "Frank" }] }
+            }],
+            "total": 1
+        });
+        let mut again = loans.clone();
+        mask_pii(&mut loans);
+        mask_pii(&mut again);
+        assert_eq!(loans, again, "fakes are deterministic");
+
+        let user = &loans["items"][0]["user"];
+        assert_ne!(user["firstname"], "Jeanne");
+        assert_ne!(user["lastname"], "Durand");
+        assert!(user["email"].as_str().unwrap().ends_with("@example.org"));
+        assert!(user["addrZipCode"].is_number());
+        assert_eq!(user["birthdate"], "1984-01-01");
+        assert!(user["phone"].is_null());
+        assert_eq!(loans["items"][0]["biblio"]["authors"][0]["lastname"], "Herbert");
+        assert_eq!(loans["items"][0]["biblio"]["title"], "Dune");
+    }
+
+    #[test]
+    fn snake_case_and_composite_names_are_masked() {
+        let mut row = json!({ "user_email": "a@b.c", "userName": "Jeanne Durand", "donor_name": "Paul Roux" });
+        mask_pii(&mut row);
+        assert!(row["user_email"].as_str().unwrap().starts_with("demo-"));
+        assert_eq!(row["userName"].as_str().unwrap().split(' ').count(), 2);
+        assert_ne!(row["donor_name"], "Paul Roux");
+    }
+
+    #[test]
+    fn only_catalog_bodies_pass_unmasked() {
+        let ok = |ty: &str| {
+            let response = ([(header::CONTENT_TYPE, ty.to_string())], "body").into_response();
+            passes_unmasked(&response, &content_type(&response))
+        };
+        assert!(ok("image/jpeg"));
+        assert!(ok("application/atom+xml; charset=utf-8"));
+        assert!(ok("application/x-bibtex; charset=utf-8"));
+        for refused in [
+            "application/gzip",
+            "application/vnd.apache.parquet",
+            "application/marc",
+            "application/octet-stream",
+            "text/event-stream",
+            "text/csv; charset=utf-8",
+            "application/xml",
+            "text/plain; charset=utf-8",
+        ] {
+            assert!(!ok(refused), "{} must be refused", refused);
+        }
+
+        let empty = axum::http::StatusCode::NO_CONTENT.into_response();
+        assert!(passes_unmasked(&empty, ""));
+    }
+}
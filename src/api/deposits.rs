@@ -0,0 +1,259 @@
+//! Deposit collection API endpoints (rotating deposits lent by another library)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::deposit::{CreateDeposit, Deposit, DepositCloseReport, DepositQuery, ImportDepositQuery, UpdateDeposit},
+    services::audit,
+};
+
+use super::{
+    biblios::{spawn_marc_batch_import, ImportMarcBatchQuery},
+    tasks::TaskAcceptedResponse,
+    AuthenticatedUser, ClientIp,
+};
+
+/// Build the deposit routes for this domain.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post};
+    axum::Router::new()
+        .route("/deposits", get(list_deposits).post(create_deposit))
+        .route("/deposits/:id", get(get_deposit).put(update_deposit).delete(delete_deposit))
+        .route("/deposits/:id/import-marc-batch", post(import_deposit_batch))
+        .route("/deposits/:id/manifest", get(get_deposit_manifest))
+        .route("/deposits/:id/close", post(close_deposit))
+}
+
+/// List deposits (active first)
+#[utoipa::path(
+    get,
+    path = "/deposits",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(DepositQuery),
+    responses(
+        (status = 200, description = "Deposits with copy counters", body = Vec<Deposit>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_deposits(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<DepositQuery>,
+) -> AppResult<Json<Vec<Deposit>>> {
+    claims.require_read_items()?;
+    Ok(Json(state.services.deposits.list(&query).await?))
+}
+
+/// Get a deposit
+#[utoipa::path(
+    get,
+    path = "/deposits/{id}",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Deposit ID")),
+    responses(
+        (status = 200, description = "Deposit", body = Deposit),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_deposit(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Deposit>> {
+    claims.require_read_items()?;
+    Ok(Json(state.services.deposits.get(id).await?))
+}
+
+/// Register a deposit batch with its owner and return deadline
+#[utoipa::path(
+    post,
+    path = "/deposits",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    request_body = CreateDeposit,
+    responses(
+        (status = 201, description = "Deposit registered", body = Deposit),
+        (status = 400, description = "Empty owner or return date before reception", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn create_deposit(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateDeposit>,
+) -> AppResult<(StatusCode, Json<Deposit>)> {
+    claims.require_write_items()?;
+    let deposit = state.services.deposits.create(&data, claims.user_id).await?;
+    state.services.audit.log(audit::event::DEPOSIT_CREATED, Some(claims.user_id), Some("deposit"), Some(deposit.id), ip, Some(&data), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(deposit)))
+}
+
+/// Update an active deposit (e.g. extend the return deadline)
+#[utoipa::path(
+    put,
+    path = "/deposits/{id}",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Deposit ID")),
+    request_body = UpdateDeposit,
+    responses(
+        (status = 200, description = "Deposit updated", body = Deposit),
+        (status = 400, description = "Empty owner or return date before reception", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 422, description = "Deposit closed", body = ErrorResponse),
+    )
+)]
+pub async fn update_deposit(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdateDeposit>,
+) -> AppResult<Json<Deposit>> {
+    claims.require_write_items()?;
+    let deposit = state.services.deposits.update(id, &data).await?;
+    state.services.audit.log(audit::event::DEPOSIT_UPDATED, Some(claims.user_id), Some("deposit"), Some(id), ip, Some(&data), audit::AuditLogMeta::success());
+    Ok(Json(deposit))
+}
+
+/// Delete a deposit registered by mistake (only while it has no copies)
+#[utoipa::path(
+    delete,
+    path = "/deposits/{id}",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Deposit ID")),
+    responses(
+        (status = 204, description = "Deposit deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 422, description = "Deposit has copies", body = ErrorResponse),
+    )
+)]
+pub async fn delete_deposit(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_items()?;
+    state.services.deposits.delete(id).await?;
+    state.services.audit.log(audit::event::DEPOSIT_DELETED, Some(claims.user_id), Some("deposit"), Some(id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Import a cached MARC batch as copies of the deposit.
+///
+/// Same task as `POST /biblios/import-marc-batch`: returns `202 Accepted` with a `taskId`;
+/// every imported copy is flagged with the deposit and cannot be weeded while it is active.
+#[utoipa::path(
+    post,
+    path = "/deposits/{id}/import-marc-batch",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Deposit ID"), ImportDepositQuery),
+    responses(
+        (status = 202, description = "Import task accepted", body = TaskAcceptedResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 422, description = "Deposit closed", body = ErrorResponse),
+    )
+)]
+pub async fn import_deposit_batch(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Query(query): Query<ImportDepositQuery>,
+) -> AppResult<(StatusCode, Json<TaskAcceptedResponse>)> {
+    claims.require_write_items()?;
+    state.services.deposits.active(id).await?;
+    let params = ImportMarcBatchQuery {
+        source_id: query.source_id,
+        batch_id: query.batch_id,
+        record_id: None,
+        allow_duplicate_isbn: query.allow_duplicate_isbn,
+        confirm_replace_existing_id: None,
+        processing_slips: query.processing_slips,
+    };
+    let task_id = spawn_marc_batch_import(&state, claims.user_id, ip, params, Some(id));
+    Ok((StatusCode::ACCEPTED, Json(TaskAcceptedResponse { task_id })))
+}
+
+/// Return manifest of a deposit: every copy still in the catalog, including copies on loan
+#[utoipa::path(
+    get,
+    path = "/deposits/{id}/manifest",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Deposit ID")),
+    responses(
+        (status = 200, description = "`;`-separated CSV file, one line per copy", content_type = "text/csv"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_deposit_manifest(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Response> {
+    claims.require_read_items()?;
+    let (deposit, csv) = state.services.deposits.manifest(id).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"deposit_{}_manifest.csv\"", deposit.id),
+            ),
+        ],
+        csv,
+    )
+        .into_response())
+}
+
+/// End a deposit: copies on the shelf are archived, copies on loan are listed for follow-up
+#[utoipa::path(
+    post,
+    path = "/deposits/{id}/close",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Deposit ID")),
+    responses(
+        (status = 200, description = "Deposit closed; `onLoan` lists the copies still to recall", body = DepositCloseReport),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 422, description = "Deposit already closed", body = ErrorResponse),
+    )
+)]
+pub async fn close_deposit(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<Json<DepositCloseReport>> {
+    claims.require_write_items()?;
+    let report = state.services.deposits.close(id, claims.user_id).await?;
+    state.services.audit.log(audit::event::DEPOSIT_CLOSED, Some(claims.user_id), Some("deposit"), Some(id), ip, Some(&report), audit::AuditLogMeta::success());
+    Ok(Json(report))
+}
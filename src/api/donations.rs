@@ -0,0 +1,280 @@
+//! Donations intake API endpoints
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::donation::{
+        CreateDonation, CreateDonationItem, Donation, DonationItem, DonationQuery, DonorsReport,
+        DonorsReportQuery, TriageDonationItem, UpdateDonation,
+    },
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Build the donations routes for this domain.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{delete, get, post};
+    axum::Router::new()
+        .route("/donations", get(list_donations).post(create_donation))
+        .route("/donations/report/donors", get(get_donors_report))
+        .route("/donations/:id", get(get_donation).put(update_donation).delete(delete_donation))
+        .route("/donations/:id/items", post(add_donation_item))
+        .route("/donations/:id/items/:line_id", delete(delete_donation_item))
+        .route("/donations/:id/items/:line_id/triage", post(triage_donation_item))
+}
+
+/// List donations with their books
+#[utoipa::path(
+    get,
+    path = "/donations",
+    tag = "donations",
+    security(("bearer_auth" = [])),
+    params(DonationQuery),
+    responses(
+        (status = 200, description = "Donations", body = Vec<Donation>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_donations(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<DonationQuery>,
+) -> AppResult<Json<Vec<Donation>>> {
+    claims.require_read_items()?;
+    let donations = state.services.donations.list(&query).await?;
+    Ok(Json(donations))
+}
+
+/// Get a donation with its books
+#[utoipa::path(
+    get,
+    path = "/donations/{id}",
+    tag = "donations",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Donation ID")),
+    responses(
+        (status = 200, description = "Donation", body = Donation),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_donation(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Donation>> {
+    claims.require_read_items()?;
+    let donation = state.services.donations.get_by_id(id).await?;
+    Ok(Json(donation))
+}
+
+/// Record a donation and its books
+#[utoipa::path(
+    post,
+    path = "/donations",
+    tag = "donations",
+    security(("bearer_auth" = [])),
+    request_body = CreateDonation,
+    responses(
+        (status = 201, description = "Donation recorded", body = Donation),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Donor user not found", body = ErrorResponse),
+    )
+)]
+pub async fn create_donation(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateDonation>,
+) -> AppResult<(StatusCode, Json<Donation>)> {
+    claims.require_write_items()?;
+    let donation = state.services.donations.create(&data).await?;
+    state.services.audit.log(audit::event::DONATION_CREATED, Some(claims.user_id), Some("donation"), Some(donation.id), ip, Some(&donation), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(donation)))
+}
+
+/// Update donor details, date, estimated value or notes
+#[utoipa::path(
+    put,
+    path = "/donations/{id}",
+    tag = "donations",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Donation ID")),
+    request_body = UpdateDonation,
+    responses(
+        (status = 200, description = "Donation updated", body = Donation),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_donation(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdateDonation>,
+) -> AppResult<Json<Donation>> {
+    claims.require_write_items()?;
+    let donation = state.services.donations.update(id, &data).await?;
+    state.services.audit.log(audit::event::DONATION_UPDATED, Some(claims.user_id), Some("donation"), Some(id), ip, Some(&data), audit::AuditLogMeta::success());
+    Ok(Json(donation))
+}
+
+/// Delete a donation (refused once a book has been added to the catalog)
+#[utoipa::path(
+    delete,
+    path = "/donations/{id}",
+    tag = "donations",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Donation ID")),
+    responses(
+        (status = 204, description = "Donation deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Donation has cataloged books", body = ErrorResponse),
+    )
+)]
+pub async fn delete_donation(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_items()?;
+    state.services.donations.delete(id).await?;
+    state.services.audit.log(audit::event::DONATION_DELETED, Some(claims.user_id), Some("donation"), Some(id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Add a book to a donation
+#[utoipa::path(
+    post,
+    path = "/donations/{id}/items",
+    tag = "donations",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Donation ID")),
+    request_body = CreateDonationItem,
+    responses(
+        (status = 201, description = "Book added", body = DonationItem),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Donation not found", body = ErrorResponse),
+    )
+)]
+pub async fn add_donation_item(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<CreateDonationItem>,
+) -> AppResult<(StatusCode, Json<DonationItem>)> {
+    claims.require_write_items()?;
+    let line = state.services.donations.add_item(id, &data).await?;
+    state.services.audit.log(audit::event::DONATION_UPDATED, Some(claims.user_id), Some("donation"), Some(id), ip, Some(&line), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(line)))
+}
+
+/// Remove a book from a donation (not allowed once it was added to the catalog)
+#[utoipa::path(
+    delete,
+    path = "/donations/{id}/items/{line_id}",
+    tag = "donations",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i32, Path, description = "Donation ID"),
+        ("line_id" = i32, Path, description = "Donated book ID"),
+    ),
+    responses(
+        (status = 204, description = "Book removed"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Book was added to the catalog", body = ErrorResponse),
+    )
+)]
+pub async fn delete_donation_item(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path((id, line_id)): Path<(i64, i64)>,
+) -> AppResult<StatusCode> {
+    claims.require_write_items()?;
+    state.services.donations.delete_item(id, line_id).await?;
+    state.services.audit.log(audit::event::DONATION_UPDATED, Some(claims.user_id), Some("donation"), Some(id), ip, Some(serde_json::json!({ "removedItemId": line_id.to_string() })), audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Triage a donated book: add to catalog, book sale, recycle (or back to pending)
+///
+/// `catalog` creates a copy on `biblioId` with source `donation`.
+#[utoipa::path(
+    post,
+    path = "/donations/{id}/items/{line_id}/triage",
+    tag = "donations",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i32, Path, description = "Donation ID"),
+        ("line_id" = i32, Path, description = "Donated book ID"),
+    ),
+    request_body = TriageDonationItem,
+    responses(
+        (status = 200, description = "Triage recorded", body = DonationItem),
+        (status = 400, description = "Missing biblioId for catalog", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Donation, book or biblio not found", body = ErrorResponse),
+        (status = 409, description = "Already cataloged, or barcode in use", body = ErrorResponse),
+    )
+)]
+pub async fn triage_donation_item(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path((id, line_id)): Path<(i64, i64)>,
+    Json(data): Json<TriageDonationItem>,
+) -> AppResult<Json<DonationItem>> {
+    claims.require_write_items()?;
+    let (line, created) = state.services.donations.triage(id, line_id, &data).await?;
+    if let Some(ref item) = created {
+        state.services.audit.log(audit::event::ITEM_CREATED, Some(claims.user_id), Some("item"), item.id, ip.clone(), Some((line.biblio_id, item)), audit::AuditLogMeta::success());
+    }
+    state.services.audit.log(audit::event::DONATION_ITEM_TRIAGED, Some(claims.user_id), Some("donation"), Some(id), ip, Some(&line), audit::AuditLogMeta::success());
+    Ok(Json(line))
+}
+
+/// Annual donors report: donations, books per triage outcome and estimated value per donor
+#[utoipa::path(
+    get,
+    path = "/donations/report/donors",
+    tag = "donations",
+    security(("bearer_auth" = [])),
+    params(DonorsReportQuery),
+    responses(
+        (status = 200, description = "Donors report", body = DonorsReport),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn get_donors_report(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<DonorsReportQuery>,
+) -> AppResult<Json<DonorsReport>> {
+    claims.require_read_items()?;
+    let report = state.services.donations.donors_report(query.year).await?;
+    Ok(Json(report))
+}
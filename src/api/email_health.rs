@@ -0,0 +1,117 @@
+//! Email deliverability endpoints (mail provider webhook `/email/webhook`, `/users/:id/email-health`)
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+
+use crate::{
+    error::{AppError, AppResult},
+    models::email_health::{EmailFeedbackBatch, EmailFeedbackReport},
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Header carrying `sha256=<hex HMAC-SHA256 of the body>` on webhook calls
+const SIGNATURE_HEADER: &str = "x-email-signature";
+
+/// Build the staff email health routes.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::delete;
+    axum::Router::new().route("/users/:id/email-health", delete(reset_user_email_health))
+}
+
+/// Mail provider webhook route (no bearer token; mounted with the public rate limiter).
+pub fn router_webhook() -> axum::Router<crate::AppState> {
+    use axum::routing::post;
+    axum::Router::new().route("/email/webhook", post(email_webhook))
+}
+
+/// Mail provider webhook (bounces and spam complaints)
+///
+/// Authenticated by `X-Email-Signature: sha256=<hex HMAC-SHA256 of the raw body>` keyed with
+/// `email.bounce_webhook_secret`. Events already recorded under the same `eventId` are counted
+/// as duplicates.
+#[utoipa::path(
+    post,
+    path = "/email/webhook",
+    tag = "users",
+    request_body = EmailFeedbackBatch,
+    params(("X-Email-Signature" = String, Header, description = "sha256=<hex HMAC-SHA256 of the body>")),
+    responses(
+        (status = 200, description = "Events recorded", body = EmailFeedbackReport),
+        (status = 400, description = "Invalid body, address, or too many events", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid signature", body = ErrorResponse),
+    )
+)]
+pub async fn email_webhook(
+    State(state): State<crate::AppState>,
+    ClientIp(ip): ClientIp,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<Json<EmailFeedbackReport>> {
+    let signature = headers.get(SIGNATURE_HEADER).and_then(|v| v.to_str().ok());
+    state.services.email_health.verify_webhook(&body, signature)?;
+    let batch: EmailFeedbackBatch = serde_json::from_slice(&body)
+        .map_err(|e| AppError::BadRequest(format!("Invalid email feedback: {}", e)))?;
+
+    let report = state.services.email_health.record(&batch).await?;
+    if !report.undeliverable.is_empty() {
+        state.services.audit.log(
+            audit::event::EMAIL_MARKED_UNDELIVERABLE,
+            None,
+            None,
+            None,
+            ip,
+            Some(serde_json::json!({ "emails": report.undeliverable })),
+            audit::AuditLogMeta::success(),
+        );
+    }
+    Ok(Json(report))
+}
+
+/// Clear the bounces and complaints recorded for a user's email address
+///
+/// Emails are sent to the address again (e.g. once the patron fixed their mailbox). Campaign
+/// consent withdrawn by a complaint is not restored.
+#[utoipa::path(
+    delete,
+    path = "/users/{id}/email-health",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "User ID")),
+    responses(
+        (status = 204, description = "Counters cleared"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "User not found, or nothing recorded for their address", body = ErrorResponse),
+        (status = 422, description = "The user has no email address", body = ErrorResponse),
+    )
+)]
+pub async fn reset_user_email_health(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_users()?;
+    let user = state.services.users.get_by_id(id).await?;
+    let email = user
+        .email
+        .filter(|e| !e.trim().is_empty())
+        .ok_or_else(|| AppError::BusinessRule("User has no email address".to_string()))?;
+    state.services.email_health.reset(&email).await?;
+    state.services.audit.log(
+        audit::event::USER_EMAIL_HEALTH_RESET,
+        Some(claims.user_id),
+        Some("user"),
+        Some(id),
+        ip,
+        Some(serde_json::json!({ "email": email })),
+        audit::AuditLogMeta::success(),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
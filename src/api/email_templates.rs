@@ -58,8 +58,8 @@ impl From<EmailTemplateRow> for EmailTemplate {
     }
 }
 
-/// Body for `PUT /settings/email-templates/{templateId}/{language}`.
-#[derive(Debug, Deserialize, ToSchema)]
+/// Body for `PUT /settings/email-templates/{template_id}/{language}`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateEmailTemplateRequest {
     pub subject: String,
@@ -95,11 +95,11 @@ pub async fn list_email_templates(
 /// Get a single email template by id and language.
 #[utoipa::path(
     get,
-    path = "/settings/email-templates/{templateId}/{language}",
+    path = "/settings/email-templates/{template_id}/{language}",
     tag = "email_templates",
     security(("bearer_auth" = [])),
     params(
-        ("templateId" = String, Path, description = "Template id (e.g. password_reset)"),
+        ("template_id" = String, Path, description = "Template id (e.g. password_reset)"),
         ("language" = String, Path, description = "Language: english | french")
     ),
     responses(
@@ -134,11 +134,11 @@ pub async fn get_email_template(
 /// Insert or update a template (`UPSERT` on `(templateId, language)`).
 #[utoipa::path(
     put,
-    path = "/settings/email-templates/{templateId}/{language}",
+    path = "/settings/email-templates/{template_id}/{language}",
     tag = "email_templates",
     security(("bearer_auth" = [])),
     params(
-        ("templateId" = String, Path, description = "Template id"),
+        ("template_id" = String, Path, description = "Template id"),
         ("language" = String, Path, description = "Language: english | french")
     ),
     request_body = UpdateEmailTemplateRequest,
@@ -0,0 +1,125 @@
+//! End-of-day job endpoints (`/admin/eod-runs`)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use utoipa::ToSchema;
+
+use crate::{
+    error::AppResult,
+    models::{
+        eod::{EodRun, EodRunDetails, EodRunStatus, EodRunsQuery, EodTrigger},
+        task::TaskKind,
+    },
+};
+
+use super::AuthenticatedUser;
+
+/// Run accepted: the log entry is updated when the background task ends
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EodRunStarted {
+    /// Background task id (`GET /tasks/:id`)
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub task_id: i64,
+    pub run: EodRun,
+}
+
+/// Build the `/admin/eod-runs` routes (administrators).
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new()
+        .route("/admin/eod-runs", get(list_eod_runs).post(run_eod))
+        .route("/admin/eod-runs/:id", get(get_eod_run))
+}
+
+/// End-of-day run log (most recent first)
+#[utoipa::path(
+    get,
+    path = "/admin/eod-runs",
+    tag = "eod",
+    security(("bearer_auth" = [])),
+    params(EodRunsQuery),
+    responses(
+        (status = 200, description = "End-of-day runs", body = Vec<EodRun>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+    )
+)]
+pub async fn list_eod_runs(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<EodRunsQuery>,
+) -> AppResult<Json<Vec<EodRun>>> {
+    claims.require_admin()?;
+    let runs = state.services.eod.list(query.limit).await?;
+    Ok(Json(runs))
+}
+
+/// Get an end-of-day run with the statistics snapshot of the day it closed
+#[utoipa::path(
+    get,
+    path = "/admin/eod-runs/{id}",
+    tag = "eod",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "End-of-day run ID")),
+    responses(
+        (status = 200, description = "End-of-day run", body = EodRunDetails),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_eod_run(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<EodRunDetails>> {
+    claims.require_admin()?;
+    let run = state.services.eod.get(id).await?;
+    Ok(Json(run))
+}
+
+/// Close the day now, in the background
+///
+/// Runs the same steps as the scheduled job (`[eod]` configuration) for today: archive returned
+/// loans, daily statistics, pull list for tomorrow, staff digest.
+#[utoipa::path(
+    post,
+    path = "/admin/eod-runs",
+    tag = "eod",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 202, description = "Run started", body = EodRunStarted),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 409, description = "An end-of-day run is in progress", body = ErrorResponse),
+    )
+)]
+pub async fn run_eod(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<(StatusCode, Json<EodRunStarted>)> {
+    claims.require_admin()?;
+    let service = state.services.eod.clone();
+    let run = service.start(EodTrigger::Manual, Some(claims.user_id)).await?;
+
+    let started = run.clone();
+    let task_id = state.services.tasks.spawn_task(TaskKind::EndOfDay, claims.user_id, move |handle| async move {
+        match service.execute(run).await {
+            Ok(run) if run.status == EodRunStatus::Failed => {
+                handle.fail("Every end-of-day step failed".to_string()).await;
+            }
+            Ok(run) => handle.complete(serde_json::to_value(&run).unwrap_or_default()).await,
+            Err(e) => handle.fail(e.to_string()).await,
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(EodRunStarted { task_id, run: started })))
+}
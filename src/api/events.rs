@@ -5,19 +5,22 @@ use axum::{
     http::StatusCode,
     Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::{
-    error::AppResult,
-    models::event::{CreateEvent, Event, EventQuery, UpdateEvent},
+    error::{AppError, AppResult},
+    models::event::{
+        CancelEvent, CreateEvent, Event, EventConflict, EventConflictQuery, EventQuery, EventRegistration,
+        EventStatus, RegisterEvent, UpdateEvent,
+    },
     services::{
         audit,
-        events::{AnnouncementReport, SendAnnouncementRequest},
+        events::{AnnouncementReport, CancelEventResponse, SendAnnouncementRequest},
     },
 };
 
-use super::{AuthenticatedUser, ClientIp};
+use super::{AuthenticatedUser, ClientIp, ValidatedJson};
 
 
 /// Build the events routes for this domain.
@@ -25,19 +28,30 @@ pub fn router() -> axum::Router<crate::AppState> {
     use axum::routing::{get, post};
     axum::Router::new()
         .route("/events", get(list_events).post(create_event))
+        .route("/events/conflicts", get(list_event_conflicts))
         .route("/events/:id", get(get_event).put(update_event).delete(delete_event))
         .route("/events/:id/send-announcement", post(send_event_announcement))
+        .route("/events/:id/publish", post(publish_event))
+        .route("/events/:id/cancel", post(cancel_event))
+        .route("/events/:id/registrations", get(list_event_registrations).post(register_to_event))
+        .route("/events/:id/registrations/:user_id", axum::routing::delete(unregister_from_event))
+}
+
+/// Staff (events read rights) see every event; everyone else only published ones
+fn can_see_unpublished(user: &Option<AuthenticatedUser>) -> bool {
+    user.as_ref().is_some_and(|AuthenticatedUser(claims)| claims.require_read_events().is_ok())
 }
 
 /// Paginated events response
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EventsListResponse {
     pub events: Vec<Event>,
     pub total: i64,
 }
 
-/// List events with filters and pagination
+/// List events with filters and pagination. Without events rights (OPAC), only published
+/// events are listed whatever `status` says.
 #[utoipa::path(
     get,
     path = "/events",
@@ -54,13 +68,18 @@ pub struct EventsListResponse {
 )]
 pub async fn list_events(
     State(state): State<crate::AppState>,
-    Query(query): Query<EventQuery>,
+    user: Option<AuthenticatedUser>,
+    Query(mut query): Query<EventQuery>,
 ) -> AppResult<Json<EventsListResponse>> {
+    if !can_see_unpublished(&user) {
+        query.status = Some(EventStatus::Published);
+    }
     let (events, total) = state.services.events.list(&query).await?;
     Ok(Json(EventsListResponse { events, total }))
 }
 
-/// Get event by ID (includes `attachmentDataBase64` when an attachment exists)
+/// Get event by ID (includes `attachmentDataBase64` when an attachment exists).
+/// Drafts and cancelled events are not found without events rights.
 #[utoipa::path(
     get,
     path = "/events/{id}",
@@ -77,12 +96,40 @@ pub async fn list_events(
 )]
 pub async fn get_event(
     State(state): State<crate::AppState>,
+    user: Option<AuthenticatedUser>,
     Path(id): Path<i64>,
 ) -> AppResult<Json<Event>> {
     let event = state.services.events.get_by_id_with_attachment(id).await?;
+    if event.status != EventStatus::Published && !can_see_unpublished(&user) {
+        return Err(AppError::NotFound(format!("Event {} not found", id)));
+    }
     Ok(Json(event))
 }
 
+/// Planning conflicts between two days: events on closure days and overlapping events in the same room
+#[utoipa::path(
+    get,
+    path = "/events/conflicts",
+    tag = "events",
+    security(("bearer_auth" = [])),
+    params(EventConflictQuery),
+    responses(
+        (status = 200, description = "Conflicts by day", body = Vec<EventConflict>),
+        (status = 400, description = "Invalid range", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_event_conflicts(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<EventConflictQuery>,
+) -> AppResult<Json<Vec<EventConflict>>> {
+    claims.require_read_events()?;
+    let conflicts = state.services.events.conflicts(query.start, query.end).await?;
+    Ok(Json(conflicts))
+}
+
 /// Create an event
 #[utoipa::path(
     post,
@@ -96,13 +143,15 @@ pub async fn get_event(
         (status = 401, description = "Not authenticated", body = ErrorResponse),
         (status = 403, description = "Insufficient permissions", body = ErrorResponse),
         (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Closure day or room already booked", body = ErrorResponse),
+        (status = 422, description = "Invalid fields", body = InvalidFieldsResponse),
     )
 )]
 pub async fn create_event(
     State(state): State<crate::AppState>,
     AuthenticatedUser(claims): AuthenticatedUser,
     ClientIp(ip): ClientIp,
-    Json(data): Json<CreateEvent>,
+    ValidatedJson(data): ValidatedJson<CreateEvent>,
 ) -> AppResult<(StatusCode, Json<Event>)> {
     claims.require_write_events()?;
     match state.services.events.create(&data).await {
@@ -147,6 +196,8 @@ pub async fn create_event(
         (status = 401, description = "Not authenticated", body = ErrorResponse),
         (status = 403, description = "Insufficient permissions", body = ErrorResponse),
         (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Closure day or room already booked", body = ErrorResponse),
+        (status = 422, description = "Invalid fields", body = InvalidFieldsResponse),
     )
 )]
 pub async fn update_event(
@@ -154,7 +205,7 @@ pub async fn update_event(
     AuthenticatedUser(claims): AuthenticatedUser,
     ClientIp(ip): ClientIp,
     Path(id): Path<i64>,
-    Json(data): Json<UpdateEvent>,
+    ValidatedJson(data): ValidatedJson<UpdateEvent>,
 ) -> AppResult<Json<Event>> {
     claims.require_write_events()?;
     match state.services.events.update(id, &data).await {
@@ -254,6 +305,8 @@ pub async fn delete_event(
         (status = 401, description = "Not authenticated", body = ErrorResponse),
         (status = 403, description = "Insufficient permissions", body = ErrorResponse),
         (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Event is not published", body = ErrorResponse),
+        (status = 422, description = "Invalid fields", body = InvalidFieldsResponse),
     )
 )]
 pub async fn send_event_announcement(
@@ -261,7 +314,7 @@ pub async fn send_event_announcement(
     AuthenticatedUser(claims): AuthenticatedUser,
     ClientIp(ip): ClientIp,
     Path(id): Path<i64>,
-    Json(payload): Json<SendAnnouncementRequest>,
+    ValidatedJson(payload): ValidatedJson<SendAnnouncementRequest>,
 ) -> AppResult<Json<AnnouncementReport>> {
     claims.require_write_events()?;
     let report = state
@@ -271,3 +324,161 @@ pub async fn send_event_announcement(
         .await?;
     Ok(Json(report))
 }
+
+/// Publish a draft event (makes it visible on the OPAC and in the Atom feed)
+#[utoipa::path(
+    post,
+    path = "/events/{id}/publish",
+    tag = "events",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Event ID")),
+    responses(
+        (status = 200, description = "Event published", body = Event),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Event is not a draft", body = ErrorResponse),
+    )
+)]
+pub async fn publish_event(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Event>> {
+    claims.require_write_events()?;
+    let event = state.services.events.publish(id).await?;
+    state.services.audit.log(audit::event::EVENT_PUBLISHED, Some(claims.user_id), Some("event"), Some(id), ip, Some(serde_json::json!({ "name": event.name })), audit::AuditLogMeta::success());
+    Ok(Json(event))
+}
+
+/// Cancel a draft or published event and email registered attendees (unless `notify` is `false`)
+#[utoipa::path(
+    post,
+    path = "/events/{id}/cancel",
+    tag = "events",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Event ID")),
+    request_body = CancelEvent,
+    responses(
+        (status = 200, description = "Event cancelled, with the notification report", body = CancelEventResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Event already cancelled", body = ErrorResponse),
+        (status = 422, description = "Invalid fields", body = InvalidFieldsResponse),
+    )
+)]
+pub async fn cancel_event(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    ValidatedJson(data): ValidatedJson<CancelEvent>,
+) -> AppResult<Json<CancelEventResponse>> {
+    claims.require_write_events()?;
+    let response = state.services.events.cancel(id, &data).await?;
+    state.services.audit.log(
+        audit::event::EVENT_CANCELLED,
+        Some(claims.user_id),
+        Some("event"),
+        Some(id),
+        ip,
+        Some(serde_json::json!({
+            "name": response.event.name,
+            "reason": response.event.cancellation_reason,
+            "emails_sent": response.notifications.emails_sent,
+            "errors": response.notifications.errors.len(),
+        })),
+        audit::AuditLogMeta::success(),
+    );
+    Ok(Json(response))
+}
+
+/// List attendees registered to an event
+#[utoipa::path(
+    get,
+    path = "/events/{id}/registrations",
+    tag = "events",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Event ID")),
+    responses(
+        (status = 200, description = "Registered attendees", body = Vec<EventRegistration>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn list_event_registrations(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Vec<EventRegistration>>> {
+    claims.require_read_events()?;
+    let registrations = state.services.events.registrations(id).await?;
+    Ok(Json(registrations))
+}
+
+/// Register to a published event (the caller, or `userId` with events write rights)
+#[utoipa::path(
+    post,
+    path = "/events/{id}/registrations",
+    tag = "events",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Event ID")),
+    request_body = RegisterEvent,
+    responses(
+        (status = 204, description = "Registered (or already registered)"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Event or user not found", body = ErrorResponse),
+        (status = 409, description = "Event is not published", body = ErrorResponse),
+        (status = 422, description = "Invalid fields", body = InvalidFieldsResponse),
+    )
+)]
+pub async fn register_to_event(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    ValidatedJson(data): ValidatedJson<RegisterEvent>,
+) -> AppResult<StatusCode> {
+    let user_id = data.user_id.unwrap_or(claims.user_id);
+    if user_id != claims.user_id {
+        claims.require_write_events()?;
+    }
+    state.services.events.register(id, user_id).await?;
+    state.services.audit.log(audit::event::EVENT_REGISTERED, Some(claims.user_id), Some("event"), Some(id), ip, Some(serde_json::json!({ "user_id": user_id })), audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Remove a registration (one's own, or anyone's with events write rights)
+#[utoipa::path(
+    delete,
+    path = "/events/{id}/registrations/{user_id}",
+    tag = "events",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "Event ID"),
+        ("user_id" = i64, Path, description = "Registered user ID"),
+    ),
+    responses(
+        (status = 204, description = "Registration removed"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not registered", body = ErrorResponse),
+    )
+)]
+pub async fn unregister_from_event(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path((id, user_id)): Path<(i64, i64)>,
+) -> AppResult<StatusCode> {
+    if user_id != claims.user_id {
+        claims.require_write_events()?;
+    }
+    state.services.events.unregister(id, user_id).await?;
+    state.services.audit.log(audit::event::EVENT_UNREGISTERED, Some(claims.user_id), Some("event"), Some(id), ip, Some(serde_json::json!({ "user_id": user_id })), audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
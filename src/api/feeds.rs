@@ -0,0 +1,332 @@
+//! Public Atom feeds (events, new acquisitions) for news aggregators and the city portal.
+//!
+//! Entry IDs are URNs derived from database IDs so they never change when the public base URL
+//! or the record content changes; `updated` tracks the last modification of each record.
+
+use axum::{
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, NaiveDate, SecondsFormat, Utc};
+
+use crate::{
+    api::versioning::ApiVersion,
+    error::AppResult,
+    models::{biblio::NewAcquisition, event::{Event, EventQuery, EventStatus}},
+    utils::escape_html,
+};
+
+const ATOM_CONTENT_TYPE: &str = "application/atom+xml; charset=utf-8";
+const FEED_CACHE_CONTROL: &str = "public, max-age=900";
+/// Maximum number of entries per feed.
+const FEED_MAX_ENTRIES: i64 = 50;
+/// Events older than this are dropped from the events feed.
+const EVENTS_LOOKBACK_DAYS: i64 = 90;
+/// Look-back window for the new acquisitions feed.
+const NEW_ITEMS_LOOKBACK_DAYS: i64 = 60;
+/// Event type excluded from public feeds (school visits carry school/class details).
+const EVENT_TYPE_SCHOOL_VISIT: i16 = 1;
+
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new()
+        .route("/events/feed.atom", get(events_feed))
+        .route("/items/new/feed.atom", get(new_items_feed))
+}
+
+/// Atom feed of library events (public)
+#[utoipa::path(
+    get,
+    path = "/events/feed.atom",
+    tag = "events",
+    responses(
+        (status = 200, description = "Atom 1.0 feed", content_type = "application/atom+xml", body = String)
+    )
+)]
+pub async fn events_feed(State(state): State<crate::AppState>) -> AppResult<Response> {
+    let since = Utc::now().date_naive() - Duration::days(EVENTS_LOOKBACK_DAYS);
+    let query = EventQuery {
+        start_date: Some(since.format("%Y-%m-%d").to_string()),
+        end_date: None,
+        event_type: None,
+        status: Some(EventStatus::Published),
+        page: Some(1),
+        per_page: Some(FEED_MAX_ENTRIES),
+    };
+    let ((events, _), library) = tokio::try_join!(
+        state.services.events.list(&query),
+        state.services.library_info.get(),
+    )?;
+
+    let base_url = public_base_url(&state);
+    let library_name = library.name.unwrap_or_else(|| "Library".to_string());
+    let entries = events
+        .iter()
+        .filter(|e| e.event_type != EVENT_TYPE_SCHOOL_VISIT)
+        .map(event_entry)
+        .collect();
+
+    let feed = AtomFeed {
+        id: "urn:elidune:feed:events",
+        title: format!("{} — events", library_name),
+        author: library_name,
+        self_url: format!("{}{}/events/feed.atom", base_url, ApiVersion::LATEST.prefix()),
+        entries,
+    };
+    Ok(atom_response(feed.render()))
+}
+
+/// Atom feed of new acquisitions (public)
+#[utoipa::path(
+    get,
+    path = "/items/new/feed.atom",
+    tag = "items",
+    responses(
+        (status = 200, description = "Atom 1.0 feed", content_type = "application/atom+xml", body = String)
+    )
+)]
+pub async fn new_items_feed(State(state): State<crate::AppState>) -> AppResult<Response> {
+    let (acquisitions, library) = tokio::try_join!(
+        state
+            .services
+            .catalog
+            .list_new_acquisitions(NEW_ITEMS_LOOKBACK_DAYS, FEED_MAX_ENTRIES),
+        state.services.library_info.get(),
+    )?;
+
+    let base_url = public_base_url(&state);
+    let library_name = library.name.unwrap_or_else(|| "Library".to_string());
+    let entries = acquisitions
+        .iter()
+        .map(|a| acquisition_entry(a, &base_url))
+        .collect();
+
+    let feed = AtomFeed {
+        id: "urn:elidune:feed:new-items",
+        title: format!("{} — new acquisitions", library_name),
+        author: library_name,
+        self_url: format!("{}{}/items/new/feed.atom", base_url, ApiVersion::LATEST.prefix()),
+        entries,
+    };
+    Ok(atom_response(feed.render()))
+}
+
+fn public_base_url(state: &crate::AppState) -> String {
+    state
+        .config
+        .server
+        .public_base_url
+        .as_deref()
+        .unwrap_or("")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn atom_response(body: String) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, ATOM_CONTENT_TYPE),
+            (header::CACHE_CONTROL, FEED_CACHE_CONTROL),
+            (header::ACCESS_CONTROL_ALLOW_ORIGIN, "*"),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+fn event_type_label(event_type: i16) -> &'static str {
+    match event_type {
+        0 => "Animation",
+        1 => "School visit",
+        2 => "Exhibition",
+        3 => "Conference",
+        4 => "Workshop",
+        5 => "Show",
+        _ => "Event",
+    }
+}
+
+fn event_entry(event: &Event) -> AtomEntry {
+    let day_start = |d: NaiveDate| d.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc()).unwrap_or_default();
+    let updated = event
+        .update_at
+        .or(event.created_at)
+        .unwrap_or_else(|| day_start(event.event_date));
+
+    let mut when = event.event_date.format("%Y-%m-%d").to_string();
+    if let Some(start) = event.start_time {
+        when.push_str(&format!(" {}", start.format("%H:%M")));
+        if let Some(end) = event.end_time {
+            when.push_str(&format!("–{}", end.format("%H:%M")));
+        }
+    }
+    let mut content = format!("{} — {}", event_type_label(event.event_type), when);
+    if let Some(description) = event.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        content.push_str("\n\n");
+        content.push_str(description);
+    }
+
+    AtomEntry {
+        id: format!("urn:elidune:event:{}", event.id),
+        title: event.name.clone(),
+        updated,
+        published: event.created_at,
+        author: None,
+        link: None,
+        content,
+    }
+}
+
+fn acquisition_entry(acquisition: &NewAcquisition, base_url: &str) -> AtomEntry {
+    let title = acquisition
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("Untitled ({})", acquisition.media_type));
+    let mut content = acquisition.media_type.to_string();
+    if let Some(isbn) = acquisition.isbn.as_ref().filter(|i| !i.is_empty()) {
+        content.push_str(&format!(" — ISBN {}", isbn));
+    }
+    if let Some(abstract_) = acquisition.abstract_.as_deref().filter(|a| !a.trim().is_empty()) {
+        content.push_str("\n\n");
+        content.push_str(abstract_);
+    }
+
+    AtomEntry {
+        id: format!("urn:elidune:biblio:{}", acquisition.biblio_id),
+        title,
+        updated: acquisition.updated_at,
+        published: Some(acquisition.acquired_at),
+        author: acquisition.author.clone(),
+        link: Some(format!("{}{}/opac/biblios/{}", base_url, ApiVersion::LATEST.prefix(), acquisition.biblio_id)),
+        content,
+    }
+}
+
+fn atom_date(dt: DateTime<Utc>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+struct AtomEntry {
+    id: String,
+    title: String,
+    updated: DateTime<Utc>,
+    published: Option<DateTime<Utc>>,
+    author: Option<String>,
+    link: Option<String>,
+    content: String,
+}
+
+struct AtomFeed {
+    id: &'static str,
+    title: String,
+    author: String,
+    self_url: String,
+    entries: Vec<AtomEntry>,
+}
+
+impl AtomFeed {
+    /// Serialize as an Atom 1.0 document. Feed `updated` is the newest entry's `updated`
+    /// (Unix epoch for an empty feed) so it only moves when content actually changes.
+    fn render(&self) -> String {
+        let updated = self
+            .entries
+            .iter()
+            .map(|e| e.updated)
+            .max()
+            .unwrap_or_default();
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        xml.push_str(&format!("  <id>{}</id>\n", escape_html(self.id)));
+        xml.push_str(&format!("  <title>{}</title>\n", escape_html(&self.title)));
+        xml.push_str(&format!("  <updated>{}</updated>\n", atom_date(updated)));
+        xml.push_str(&format!(
+            "  <author><name>{}</name></author>\n",
+            escape_html(&self.author)
+        ));
+        xml.push_str(&format!(
+            "  <link rel=\"self\" type=\"application/atom+xml\" href=\"{}\"/>\n",
+            escape_html(&self.self_url)
+        ));
+        xml.push_str("  <generator>Elidune</generator>\n");
+
+        for entry in &self.entries {
+            xml.push_str("  <entry>\n");
+            xml.push_str(&format!("    <id>{}</id>\n", escape_html(&entry.id)));
+            xml.push_str(&format!("    <title>{}</title>\n", escape_html(&entry.title)));
+            xml.push_str(&format!("    <updated>{}</updated>\n", atom_date(entry.updated)));
+            if let Some(published) = entry.published {
+                xml.push_str(&format!("    <published>{}</published>\n", atom_date(published)));
+            }
+            if let Some(author) = &entry.author {
+                xml.push_str(&format!(
+                    "    <author><name>{}</name></author>\n",
+                    escape_html(author)
+                ));
+            }
+            if let Some(link) = &entry.link {
+                xml.push_str(&format!(
+                    "    <link rel=\"alternate\" href=\"{}\"/>\n",
+                    escape_html(link)
+                ));
+            }
+            xml.push_str(&format!(
+                "    <content type=\"text\">{}</content>\n",
+                escape_html(&entry.content)
+            ));
+            xml.push_str("  </entry>\n");
+        }
+
+        xml.push_str("</feed>\n");
+        xml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(id: i64, updated: DateTime<Utc>) -> AtomEntry {
+        AtomEntry {
+            id: format!("urn:elidune:event:{}", id),
+            title: "Tom & Jerry <live>".to_string(),
+            updated,
+            published: None,
+            author: None,
+            link: None,
+            content: "Show".to_string(),
+        }
+    }
+
+    #[test]
+    fn feed_updated_is_newest_entry_and_text_is_escaped() {
+        let older = Utc.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap();
+        let newer = Utc.with_ymd_and_hms(2026, 3, 5, 18, 30, 0).unwrap();
+        let feed = AtomFeed {
+            id: "urn:elidune:feed:events",
+            title: "Library — events".to_string(),
+            author: "Library".to_string(),
+            self_url: "/api/v1/events/feed.atom".to_string(),
+            entries: vec![entry(1, older), entry(2, newer)],
+        };
+        let xml = feed.render();
+        assert!(xml.contains("<feed xmlns=\"http://www.w3.org/2005/Atom\">"));
+        assert!(xml.contains("  <updated>2026-03-05T18:30:00Z</updated>"));
+        assert!(xml.contains("<id>urn:elidune:event:1</id>"));
+        assert!(xml.contains("<title>Tom &amp; Jerry &lt;live&gt;</title>"));
+    }
+
+    #[test]
+    fn empty_feed_has_stable_updated() {
+        let feed = AtomFeed {
+            id: "urn:elidune:feed:new-items",
+            title: "Library — new acquisitions".to_string(),
+            author: "Library".to_string(),
+            self_url: String::new(),
+            entries: Vec::new(),
+        };
+        assert!(feed.render().contains("<updated>1970-01-01T00:00:00Z</updated>"));
+    }
+}
@@ -1,7 +1,7 @@
 //! Fine / penalty endpoints
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
 use rust_decimal::Decimal;
@@ -10,27 +10,36 @@ use utoipa::ToSchema;
 
 use crate::{
     error::AppResult,
-    models::fine::{Fine, FineRule, PayFineRequest, WaiveFineRequest},
+    models::{
+        fine::{
+            Fine, FineAmnestyReport, FineAmnestyRequest, FineRule, PayFineRequest, WaiveFineRequest,
+            WaivedFinesQuery, WaivedFinesSummary,
+        },
+        payment::{PaymentCategory, PaymentMethod, RecordPayment},
+    },
     services::audit,
 };
 
 use super::{AuthenticatedUser, ClientIp, StaffUser};
 
 /// Upsert fine rule request
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpsertFineRuleRequest {
     pub media_type: Option<String>,
+    #[schema(value_type = String, example = "0.10")]
     pub daily_rate: Decimal,
+    #[schema(value_type = Option<String>, example = "5.00")]
     pub max_amount: Option<Decimal>,
     #[serde(default)]
     pub grace_days: i32,
 }
 
 /// Unpaid fine summary for a user
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UnpaidFinesSummary {
+    #[schema(value_type = String, example = "3.20")]
     pub total_unpaid: Decimal,
     pub fines: Vec<Fine>,
 }
@@ -44,8 +53,8 @@ pub struct UnpaidFinesSummary {
     params(("id" = i64, Path, description = "User ID")),
     responses(
         (status = 200, description = "User fines with total unpaid", body = UnpaidFinesSummary),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 404, description = "User not found", body = crate::error::ErrorResponse)
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
     )
 )]
 pub async fn list_user_fines(
@@ -59,7 +68,7 @@ pub async fn list_user_fines(
     Ok(Json(UnpaidFinesSummary { total_unpaid, fines }))
 }
 
-/// Pay a fine (record payment)
+/// Pay a fine (recorded in the cash register)
 #[utoipa::path(
     post,
     path = "/fines/{id}/pay",
@@ -69,9 +78,10 @@ pub async fn list_user_fines(
     request_body = PayFineRequest,
     responses(
         (status = 200, description = "Fine updated with payment", body = Fine),
-        (status = 400, description = "Invalid amount", body = crate::error::ErrorResponse),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 404, description = "Fine not found", body = crate::error::ErrorResponse)
+        (status = 400, description = "Invalid amount or above the outstanding balance", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Fine not found", body = ErrorResponse),
+        (status = 422, description = "Fine already paid or waived", body = ErrorResponse)
     )
 )]
 pub async fn pay_fine(
@@ -81,7 +91,16 @@ pub async fn pay_fine(
     Path(id): Path<i64>,
     Json(req): Json<PayFineRequest>,
 ) -> AppResult<Json<Fine>> {
-    let fine = state.services.fines.pay(id, req.amount, req.notes.as_deref()).await?;
+    let payment = RecordPayment {
+        user_id: None,
+        amount: Some(req.amount),
+        method: req.method.unwrap_or(PaymentMethod::Cash),
+        category: PaymentCategory::Fine,
+        fine_id: Some(id),
+        notes: req.notes,
+    };
+    let payment = state.services.payments.record(&payment, claims.user_id).await?;
+    let fine = state.services.fines.get(id).await?;
 
     state.services.audit.log(
         audit::event::FINE_PAID,
@@ -89,7 +108,7 @@ pub async fn pay_fine(
         Some("fine"),
         Some(id),
         ip,
-        Some(serde_json::json!({ "amount": req.amount })),
+        Some(serde_json::json!({ "amount": req.amount, "paymentId": payment.id })),
      audit::AuditLogMeta::success());
 
     Ok(Json(fine))
@@ -105,9 +124,10 @@ pub async fn pay_fine(
     request_body = WaiveFineRequest,
     responses(
         (status = 200, description = "Fine waived", body = Fine),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 403, description = "Staff access required", body = crate::error::ErrorResponse),
-        (status = 404, description = "Fine not found", body = crate::error::ErrorResponse)
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Staff access required", body = ErrorResponse),
+        (status = 404, description = "Fine not found", body = ErrorResponse),
+        (status = 422, description = "Fine already paid or waived", body = ErrorResponse)
     )
 )]
 pub async fn waive_fine(
@@ -117,7 +137,7 @@ pub async fn waive_fine(
     Path(id): Path<i64>,
     Json(req): Json<WaiveFineRequest>,
 ) -> AppResult<Json<Fine>> {
-    let fine = state.services.fines.waive(id, req.notes.as_deref()).await?;
+    let fine = state.services.fines.waive(id, req.notes.as_deref(), claims.user_id).await?;
 
     state.services.audit.log(
         audit::event::FINE_WAIVED,
@@ -131,6 +151,64 @@ pub async fn waive_fine(
     Ok(Json(fine))
 }
 
+/// Amnesty campaign: waive every unpaid fine issued between two days (admin only)
+#[utoipa::path(
+    post,
+    path = "/fines/amnesty",
+    tag = "fines",
+    security(("bearer_auth" = [])),
+    request_body = FineAmnestyRequest,
+    responses(
+        (status = 200, description = "Fines waived by the campaign", body = FineAmnestyReport),
+        (status = 400, description = "Missing name or invalid date range", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Administrator privileges required", body = ErrorResponse)
+    )
+)]
+pub async fn fine_amnesty(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(req): Json<FineAmnestyRequest>,
+) -> AppResult<Json<FineAmnestyReport>> {
+    claims.require_admin()?;
+    let report = state.services.fines.amnesty(&req, claims.user_id).await?;
+
+    state.services.audit.log(
+        audit::event::FINES_AMNESTY,
+        Some(claims.user_id),
+        Some("fine"),
+        None,
+        ip,
+        Some(&report),
+        audit::AuditLogMeta::success(),
+    );
+
+    Ok(Json(report))
+}
+
+/// Waived fine totals by reason (staff, manual waivers, amnesty campaigns and weeks)
+#[utoipa::path(
+    get,
+    path = "/fines/waivers/summary",
+    tag = "fines",
+    security(("bearer_auth" = [])),
+    params(WaivedFinesQuery),
+    responses(
+        (status = 200, description = "Waived totals over the period", body = WaivedFinesSummary),
+        (status = 400, description = "Invalid date range", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Staff access required", body = ErrorResponse)
+    )
+)]
+pub async fn waived_fines_summary(
+    State(state): State<crate::AppState>,
+    StaffUser(_staff): StaffUser,
+    Query(query): Query<WaivedFinesQuery>,
+) -> AppResult<Json<WaivedFinesSummary>> {
+    Ok(Json(state.services.fines.waived_summary(&query).await?))
+}
+
 /// List fine rules
 #[utoipa::path(
     get,
@@ -139,8 +217,8 @@ pub async fn waive_fine(
     security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "Fine rules per media type", body = Vec<FineRule>),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 403, description = "Insufficient permissions", body = crate::error::ErrorResponse)
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse)
     )
 )]
 pub async fn list_fine_rules(
@@ -160,8 +238,8 @@ pub async fn list_fine_rules(
     request_body = UpsertFineRuleRequest,
     responses(
         (status = 200, description = "Fine rule saved", body = FineRule),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 403, description = "Staff access required", body = crate::error::ErrorResponse)
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Staff access required", body = ErrorResponse)
     )
 )]
 pub async fn upsert_fine_rule(
@@ -189,4 +267,6 @@ pub fn router() -> axum::Router<crate::AppState> {
         .route("/fines/rules", get(list_fine_rules).put(upsert_fine_rule))
         .route("/fines/:id/pay", post(pay_fine))
         .route("/fines/:id/waive", post(waive_fine))
+        .route("/fines/amnesty", post(fine_amnesty))
+        .route("/fines/waivers/summary", get(waived_fines_summary))
 }
@@ -51,6 +51,8 @@ impl FirstSetupEmailBody {
             templates_dir: self
                 .templates_dir
                 .unwrap_or_else(|| file_defaults.templates_dir.clone()),
+            bounce_webhook_secret: file_defaults.bounce_webhook_secret.clone(),
+            hard_bounce_threshold: file_defaults.hard_bounce_threshold,
             overridable: file_defaults.overridable,
         }
     }
@@ -58,7 +60,7 @@ impl FirstSetupEmailBody {
 
 /// Admin account fields for bootstrap (required patron fields are enforced).
 #[serde_as]
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FirstSetupAdminBody {
     pub login: String,
@@ -91,7 +93,7 @@ fn profile_language_only(lang: Language) -> UpdateProfile {
 }
 
 /// Full first-setup payload: admin user, library details, optional runtime email override.
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FirstSetupRequest {
     pub admin: FirstSetupAdminBody,
@@ -101,7 +103,7 @@ pub struct FirstSetupRequest {
 }
 
 /// Response mirrors login success so the client can store the JWT immediately.
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FirstSetupResponse {
     pub token: String,
@@ -119,8 +121,8 @@ pub struct FirstSetupResponse {
     request_body = FirstSetupRequest,
     responses(
         (status = 201, description = "Setup completed; returns JWT like login", body = FirstSetupResponse),
-        (status = 400, description = "Validation error", body = crate::error::ErrorResponse),
-        (status = 409, description = "Setup already done or preconditions failed", body = crate::error::ErrorResponse),
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 409, description = "Setup already done or preconditions failed", body = ErrorResponse),
     )
 )]
 pub async fn post_first_setup(
@@ -0,0 +1,41 @@
+//! GraphQL endpoint (`graphql` feature): queries on `POST /graphql`, GraphiQL on `GET /graphql`
+
+use std::sync::Arc;
+
+use async_graphql::http::GraphiQLSource;
+use axum::{
+    extract::State,
+    response::Html,
+    routing::get,
+    Extension, Json, Router,
+};
+
+use crate::{
+    graphql::{self, EliduneSchema},
+    services::Services,
+};
+
+use super::{versioning::ApiVersion, AuthenticatedUser};
+
+/// GraphQL routes; the schema is built once and shared by every request
+pub fn router(services: Arc<Services>) -> Router<crate::AppState> {
+    Router::new()
+        .route("/graphql", get(graphiql).post(execute))
+        .layer(Extension(graphql::build_schema(services)))
+}
+
+/// Run a query as the authenticated user (rights are checked per field)
+async fn execute(
+    State(state): State<crate::AppState>,
+    Extension(schema): Extension<EliduneSchema>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    let request = graphql::request_data(request, &state.services, claims);
+    Json(schema.execute(request).await)
+}
+
+/// GraphiQL explorer (set the `Authorization: Bearer …` header in its headers tab)
+async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint(&format!("{}/graphql", ApiVersion::LATEST.prefix())).finish())
+}
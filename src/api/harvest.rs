@@ -0,0 +1,260 @@
+//! Union catalog harvest API endpoints (`/harvest/sources`, `/harvest/runs`)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use utoipa::ToSchema;
+
+use crate::{
+    error::AppResult,
+    models::{
+        harvest::{CreateHarvestSource, HarvestRun, HarvestRunStatus, HarvestRunsQuery, HarvestSource, UpdateHarvestSource},
+        task::TaskKind,
+    },
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Manual run accepted: the run report is updated when the background task ends
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HarvestRunStarted {
+    /// Background task id (`GET /tasks/:id`)
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub task_id: i64,
+    pub run: HarvestRun,
+}
+
+/// Build the `/harvest/*` routes.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post};
+    axum::Router::new()
+        .route("/harvest/sources", get(list_harvest_sources).post(create_harvest_source))
+        .route(
+            "/harvest/sources/:id",
+            get(get_harvest_source).put(update_harvest_source).delete(delete_harvest_source),
+        )
+        .route("/harvest/sources/:id/run", post(run_harvest_source))
+        .route("/harvest/sources/:id/runs", get(list_harvest_runs))
+        .route("/harvest/runs/:id", get(get_harvest_run))
+}
+
+/// List harvest sources
+#[utoipa::path(
+    get,
+    path = "/harvest/sources",
+    tag = "harvest",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Harvest sources", body = Vec<HarvestSource>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+    )
+)]
+pub async fn list_harvest_sources(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<HarvestSource>>> {
+    claims.require_read_settings()?;
+    let sources = state.services.harvest.list_sources().await?;
+    Ok(Json(sources))
+}
+
+/// Get a harvest source
+#[utoipa::path(
+    get,
+    path = "/harvest/sources/{id}",
+    tag = "harvest",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Harvest source ID")),
+    responses(
+        (status = 200, description = "Harvest source", body = HarvestSource),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_harvest_source(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<HarvestSource>> {
+    claims.require_read_settings()?;
+    let source = state.services.harvest.get_source(id).await?;
+    Ok(Json(source))
+}
+
+/// Create a harvest source
+#[utoipa::path(
+    post,
+    path = "/harvest/sources",
+    tag = "harvest",
+    security(("bearer_auth" = [])),
+    request_body = CreateHarvestSource,
+    responses(
+        (status = 201, description = "Harvest source created", body = HarvestSource),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+    )
+)]
+pub async fn create_harvest_source(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateHarvestSource>,
+) -> AppResult<(StatusCode, Json<HarvestSource>)> {
+    claims.require_write_settings()?;
+    let source = state.services.harvest.create_source(&data).await?;
+    state.services.audit.log(audit::event::HARVEST_SOURCE_CREATED, Some(claims.user_id), Some("harvest_source"), Some(source.id), ip, Some(&source), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(source)))
+}
+
+/// Update a harvest source (an empty `password` removes it, `resetCursor` re-applies all files)
+#[utoipa::path(
+    put,
+    path = "/harvest/sources/{id}",
+    tag = "harvest",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Harvest source ID")),
+    request_body = UpdateHarvestSource,
+    responses(
+        (status = 200, description = "Harvest source updated", body = HarvestSource),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_harvest_source(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdateHarvestSource>,
+) -> AppResult<Json<HarvestSource>> {
+    claims.require_write_settings()?;
+    let source = state.services.harvest.update_source(id, &data).await?;
+    state.services.audit.log(audit::event::HARVEST_SOURCE_UPDATED, Some(claims.user_id), Some("harvest_source"), Some(id), ip, Some(&source), audit::AuditLogMeta::success());
+    Ok(Json(source))
+}
+
+/// Delete a harvest source and its run history (harvested records stay in the catalog)
+#[utoipa::path(
+    delete,
+    path = "/harvest/sources/{id}",
+    tag = "harvest",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Harvest source ID")),
+    responses(
+        (status = 204, description = "Harvest source deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_harvest_source(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_settings()?;
+    state.services.harvest.delete_source(id).await?;
+    state.services.audit.log(audit::event::HARVEST_SOURCE_DELETED, Some(claims.user_id), Some("harvest_source"), Some(id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Run a harvest now, in the background
+#[utoipa::path(
+    post,
+    path = "/harvest/sources/{id}/run",
+    tag = "harvest",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Harvest source ID")),
+    responses(
+        (status = 202, description = "Run started", body = HarvestRunStarted),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "A run of this source is in progress", body = ErrorResponse),
+    )
+)]
+pub async fn run_harvest_source(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<(StatusCode, Json<HarvestRunStarted>)> {
+    claims.require_write_settings()?;
+    let harvest = state.services.harvest.clone();
+    let (source, run) = harvest.start_run(id, claims.user_id).await?;
+
+    let started = run.clone();
+    let task_id = state.services.tasks.spawn_task(TaskKind::HarvestRun, claims.user_id, move |handle| async move {
+        match harvest.execute(source, run).await {
+            Ok(run) if run.status == HarvestRunStatus::Failed => {
+                handle.fail(run.error.unwrap_or_else(|| "Harvest failed".to_string())).await;
+            }
+            Ok(run) => handle.complete(serde_json::to_value(&run).unwrap_or_default()).await,
+            Err(e) => handle.fail(e.to_string()).await,
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(HarvestRunStarted { task_id, run: started })))
+}
+
+/// Run history of a harvest source (most recent first)
+#[utoipa::path(
+    get,
+    path = "/harvest/sources/{id}/runs",
+    tag = "harvest",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Harvest source ID"), HarvestRunsQuery),
+    responses(
+        (status = 200, description = "Harvest runs", body = Vec<HarvestRun>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn list_harvest_runs(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+    Query(query): Query<HarvestRunsQuery>,
+) -> AppResult<Json<Vec<HarvestRun>>> {
+    claims.require_read_settings()?;
+    let runs = state.services.harvest.runs(id, query.limit).await?;
+    Ok(Json(runs))
+}
+
+/// Get a harvest run report
+#[utoipa::path(
+    get,
+    path = "/harvest/runs/{id}",
+    tag = "harvest",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Harvest run ID")),
+    responses(
+        (status = 200, description = "Harvest run", body = HarvestRun),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_harvest_run(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<HarvestRun>> {
+    claims.require_read_settings()?;
+    let run = state.services.harvest.run(id).await?;
+    Ok(Json(run))
+}
@@ -0,0 +1,476 @@
+//! Genre and subject heading vocabularies (`/settings/genres`, `/settings/subjects`) and their
+//! assignment to bibliographic records (`/biblios/:id/genres`, `/biblios/:id/subjects`)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    error::AppResult,
+    models::heading::{
+        CreateGenreHeading, CreateSubjectHeading, GenreHeading, MergeHeadings, MergeHeadingsReport,
+        SetBiblioHeadings, SubjectHeading, SubjectQuery, UpdateGenreHeading, UpdateSubjectHeading,
+    },
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Build the heading routes.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post};
+    axum::Router::new()
+        .route("/settings/genres", get(list_genres).post(create_genre))
+        .route("/settings/genres/:id", get(get_genre).put(update_genre).delete(delete_genre))
+        .route("/settings/genres/:id/merge", post(merge_genres))
+        .route("/settings/subjects", get(list_subjects).post(create_subject))
+        .route("/settings/subjects/:id", get(get_subject).put(update_subject).delete(delete_subject))
+        .route("/settings/subjects/:id/merge", post(merge_subjects))
+        .route("/biblios/:id/genres", get(get_biblio_genres).put(set_biblio_genres))
+        .route("/biblios/:id/subjects", get(get_biblio_subjects).put(set_biblio_subjects))
+}
+
+/// Paginated list of subject headings.
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedSubjects {
+    pub items: Vec<SubjectHeading>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub page_count: i64,
+}
+
+// ── Genres ────────────────────────────────────────────────────────────────────
+
+/// List genres (by sort order)
+#[utoipa::path(
+    get,
+    path = "/settings/genres",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Genres", body = Vec<GenreHeading>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_genres(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<GenreHeading>>> {
+    claims.require_read_items()?;
+    Ok(Json(state.services.catalog.list_genres().await?))
+}
+
+/// Get a genre
+#[utoipa::path(
+    get,
+    path = "/settings/genres/{id}",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Genre ID")),
+    responses(
+        (status = 200, description = "Genre", body = GenreHeading),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_genre(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<GenreHeading>> {
+    claims.require_read_items()?;
+    Ok(Json(state.services.catalog.get_genre(id).await?))
+}
+
+/// Create a genre (`sortOrder` defaults to after the last one)
+#[utoipa::path(
+    post,
+    path = "/settings/genres",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    request_body = CreateGenreHeading,
+    responses(
+        (status = 201, description = "Genre created", body = GenreHeading),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 409, description = "Code already exists", body = ErrorResponse),
+    )
+)]
+pub async fn create_genre(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateGenreHeading>,
+) -> AppResult<(StatusCode, Json<GenreHeading>)> {
+    claims.require_write_settings()?;
+    let genre = state.services.catalog.create_genre(&data).await?;
+    state.services.audit.log(audit::event::GENRE_CREATED, Some(claims.user_id), Some("genre"), Some(genre.id), ip, Some(&genre), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(genre)))
+}
+
+/// Update a genre
+#[utoipa::path(
+    put,
+    path = "/settings/genres/{id}",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Genre ID")),
+    request_body = UpdateGenreHeading,
+    responses(
+        (status = 200, description = "Genre updated", body = GenreHeading),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Code already exists", body = ErrorResponse),
+    )
+)]
+pub async fn update_genre(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdateGenreHeading>,
+) -> AppResult<Json<GenreHeading>> {
+    claims.require_write_settings()?;
+    let genre = state.services.catalog.update_genre(id, &data).await?;
+    state.services.audit.log(audit::event::GENRE_UPDATED, Some(claims.user_id), Some("genre"), Some(id), ip, Some((&data, &genre)), audit::AuditLogMeta::success());
+    Ok(Json(genre))
+}
+
+/// Delete a genre (fails while records use it; merge it instead)
+#[utoipa::path(
+    delete,
+    path = "/settings/genres/{id}",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Genre ID")),
+    responses(
+        (status = 204, description = "Genre deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Genre in use", body = ErrorResponse),
+    )
+)]
+pub async fn delete_genre(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_settings()?;
+    state.services.catalog.delete_genre(id).await?;
+    state.services.audit.log(audit::event::GENRE_DELETED, Some(claims.user_id), Some("genre"), Some(id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Merge duplicate genres into this one: their records are re-mapped, then they are deleted
+#[utoipa::path(
+    post,
+    path = "/settings/genres/{id}/merge",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Genre kept")),
+    request_body = MergeHeadings,
+    responses(
+        (status = 200, description = "Genres merged", body = MergeHeadingsReport),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Genre not found", body = ErrorResponse),
+    )
+)]
+pub async fn merge_genres(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<MergeHeadings>,
+) -> AppResult<Json<MergeHeadingsReport>> {
+    claims.require_write_settings()?;
+    let report = state.services.catalog.merge_genres(id, &data.source_ids).await?;
+    state.services.audit.log(audit::event::GENRE_MERGED, Some(claims.user_id), Some("genre"), Some(id), ip, Some(&report), audit::AuditLogMeta::success());
+    Ok(Json(report))
+}
+
+// ── Subjects ──────────────────────────────────────────────────────────────────
+
+/// List subject headings (paginated, by heading)
+#[utoipa::path(
+    get,
+    path = "/settings/subjects",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    params(SubjectQuery),
+    responses(
+        (status = 200, description = "Subject headings", body = PaginatedSubjects),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_subjects(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<SubjectQuery>,
+) -> AppResult<Json<PaginatedSubjects>> {
+    claims.require_read_items()?;
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(50).clamp(1, 200);
+    let (items, total) = state.services.catalog.list_subjects(&query).await?;
+    let page_count = (total + per_page - 1) / per_page;
+    Ok(Json(PaginatedSubjects { items, total, page, per_page, page_count }))
+}
+
+/// Get a subject heading
+#[utoipa::path(
+    get,
+    path = "/settings/subjects/{id}",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Subject ID")),
+    responses(
+        (status = 200, description = "Subject heading", body = SubjectHeading),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_subject(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<SubjectHeading>> {
+    claims.require_read_items()?;
+    Ok(Json(state.services.catalog.get_subject(id).await?))
+}
+
+/// Create a subject heading, optionally under a broader term
+#[utoipa::path(
+    post,
+    path = "/settings/subjects",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    request_body = CreateSubjectHeading,
+    responses(
+        (status = 201, description = "Subject heading created", body = SubjectHeading),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Broader term not found", body = ErrorResponse),
+        (status = 409, description = "Heading already exists", body = ErrorResponse),
+    )
+)]
+pub async fn create_subject(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateSubjectHeading>,
+) -> AppResult<(StatusCode, Json<SubjectHeading>)> {
+    claims.require_write_settings()?;
+    let subject = state.services.catalog.create_subject(&data).await?;
+    state.services.audit.log(audit::event::SUBJECT_CREATED, Some(claims.user_id), Some("subject"), Some(subject.id), ip, Some(&subject), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(subject)))
+}
+
+/// Update a subject heading (rename, move under another broader term, or detach with `topLevel`)
+#[utoipa::path(
+    put,
+    path = "/settings/subjects/{id}",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Subject ID")),
+    request_body = UpdateSubjectHeading,
+    responses(
+        (status = 200, description = "Subject heading updated", body = SubjectHeading),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Heading already exists", body = ErrorResponse),
+        (status = 422, description = "The hierarchy would loop", body = ErrorResponse),
+    )
+)]
+pub async fn update_subject(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdateSubjectHeading>,
+) -> AppResult<Json<SubjectHeading>> {
+    claims.require_write_settings()?;
+    let subject = state.services.catalog.update_subject(id, &data).await?;
+    state.services.audit.log(audit::event::SUBJECT_UPDATED, Some(claims.user_id), Some("subject"), Some(id), ip, Some((&data, &subject)), audit::AuditLogMeta::success());
+    Ok(Json(subject))
+}
+
+/// Delete a subject heading (fails while records use it or it has narrower terms)
+#[utoipa::path(
+    delete,
+    path = "/settings/subjects/{id}",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Subject ID")),
+    responses(
+        (status = 204, description = "Subject heading deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Heading in use or has narrower terms", body = ErrorResponse),
+    )
+)]
+pub async fn delete_subject(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_settings()?;
+    state.services.catalog.delete_subject(id).await?;
+    state.services.audit.log(audit::event::SUBJECT_DELETED, Some(claims.user_id), Some("subject"), Some(id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Merge duplicate subject headings into this one: their records are re-mapped, their narrower
+/// terms moved under it, then they are deleted
+#[utoipa::path(
+    post,
+    path = "/settings/subjects/{id}/merge",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Subject heading kept")),
+    request_body = MergeHeadings,
+    responses(
+        (status = 200, description = "Subject headings merged", body = MergeHeadingsReport),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Subject heading not found", body = ErrorResponse),
+        (status = 422, description = "The kept heading is narrower than a merged one", body = ErrorResponse),
+    )
+)]
+pub async fn merge_subjects(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<MergeHeadings>,
+) -> AppResult<Json<MergeHeadingsReport>> {
+    claims.require_write_settings()?;
+    let report = state.services.catalog.merge_subjects(id, &data.source_ids).await?;
+    state.services.audit.log(audit::event::SUBJECT_MERGED, Some(claims.user_id), Some("subject"), Some(id), ip, Some(&report), audit::AuditLogMeta::success());
+    Ok(Json(report))
+}
+
+// ── Assignment ────────────────────────────────────────────────────────────────
+
+/// Genres of a bibliographic record
+#[utoipa::path(
+    get,
+    path = "/biblios/{id}/genres",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Biblio ID")),
+    responses(
+        (status = 200, description = "Genres of the record", body = Vec<GenreHeading>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Biblio not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_biblio_genres(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Vec<GenreHeading>>> {
+    claims.require_read_items()?;
+    Ok(Json(state.services.catalog.get_biblio_genres(id).await?))
+}
+
+/// Replace the genres of a bibliographic record
+#[utoipa::path(
+    put,
+    path = "/biblios/{id}/genres",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Biblio ID")),
+    request_body = SetBiblioHeadings,
+    responses(
+        (status = 200, description = "Genres of the record", body = Vec<GenreHeading>),
+        (status = 400, description = "Unknown genre", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Biblio not found", body = ErrorResponse),
+    )
+)]
+pub async fn set_biblio_genres(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<SetBiblioHeadings>,
+) -> AppResult<Json<Vec<GenreHeading>>> {
+    claims.require_write_items()?;
+    let genres = state.services.catalog.set_biblio_genres(id, &data.ids).await?;
+    state.services.audit.log(audit::event::BIBLIO_HEADINGS_UPDATED, Some(claims.user_id), Some("biblio"), Some(id), ip, Some(serde_json::json!({ "genreIds": &data.ids })), audit::AuditLogMeta::success());
+    Ok(Json(genres))
+}
+
+/// Subject headings of a bibliographic record
+#[utoipa::path(
+    get,
+    path = "/biblios/{id}/subjects",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Biblio ID")),
+    responses(
+        (status = 200, description = "Subject headings of the record", body = Vec<SubjectHeading>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Biblio not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_biblio_subjects(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Vec<SubjectHeading>>> {
+    claims.require_read_items()?;
+    Ok(Json(state.services.catalog.get_biblio_subjects(id).await?))
+}
+
+/// Replace the subject headings of a bibliographic record
+#[utoipa::path(
+    put,
+    path = "/biblios/{id}/subjects",
+    tag = "headings",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Biblio ID")),
+    request_body = SetBiblioHeadings,
+    responses(
+        (status = 200, description = "Subject headings of the record", body = Vec<SubjectHeading>),
+        (status = 400, description = "Unknown subject", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Biblio not found", body = ErrorResponse),
+    )
+)]
+pub async fn set_biblio_subjects(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<SetBiblioHeadings>,
+) -> AppResult<Json<Vec<SubjectHeading>>> {
+    claims.require_write_items()?;
+    let subjects = state.services.catalog.set_biblio_subjects(id, &data.ids).await?;
+    state.services.audit.log(audit::event::BIBLIO_HEADINGS_UPDATED, Some(claims.user_id), Some("biblio"), Some(id), ip, Some(serde_json::json!({ "subjectIds": &data.ids })), audit::AuditLogMeta::success());
+    Ok(Json(subjects))
+}
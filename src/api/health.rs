@@ -6,16 +6,16 @@ use axum::{
     response::IntoResponse,
     Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct HealthDatabaseStatus {
     pub connected: bool,
 }
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct HealthSetupStatus {
     /// True when there are no users and no `settings` rows (initial wizard required).
@@ -24,7 +24,7 @@ pub struct HealthSetupStatus {
     pub settings_empty: bool,
 }
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct HealthResponse {
     /// `healthy`, `need_first_setup`, or `degraded` (database unreachable).
@@ -158,7 +158,7 @@ pub async fn readiness_check(State(state): State<crate::AppState>) -> impl IntoR
         .into_response()
 }
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct VersionResponse {
     /// Server version (from Cargo.toml)
@@ -186,4 +186,5 @@ pub fn router() -> axum::Router<crate::AppState> {
     axum::Router::new()
         .route("/health", get(health_check))
         .route("/ready", get(readiness_check))
+        .route("/version", get(version))
 }
@@ -2,10 +2,11 @@
 
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 use utoipa::{IntoParams, ToSchema};
@@ -13,25 +14,34 @@ use utoipa::{IntoParams, ToSchema};
 use crate::{
     error::{AppError, AppResult},
     models::{
-        hold::{CreateHold, Hold, HoldDetails},
+        hold::{
+            CreateHold, ExpiredHoldsQuery, Hold, HoldDetails, HoldListFormat, HoldPriority, HoldPullListQuery,
+            HoldShelfGroup,
+        },
         user::Rights,
     },
     services::audit,
 };
 
-use super::{biblios::PaginatedResponse, AuthenticatedUser, ClientIp};
+use super::{
+    biblios::PaginatedResponse,
+    sse::{self, SsePayload},
+    AuthenticatedUser, ClientIp, SelfServiceUser,
+};
 
 pub fn router() -> axum::Router<crate::AppState> {
     use axum::routing::{delete, get};
     axum::Router::new()
         .route("/holds", get(list_holds).post(create_hold))
+        .route("/holds/pull-list", get(get_pull_list))
+        .route("/holds/expired", get(get_expired_holds))
         .route("/holds/:id", delete(cancel_hold))
         .route("/items/:id/holds", get(list_holds_for_item))
         .route("/users/:id/holds", get(list_holds_for_user))
 }
 
 /// Query parameters for `GET /holds` (global list).
-#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
 #[serde(rename_all = "camelCase")]
 pub struct ListHoldsQuery {
     /// Page number (1-based, default 1)
@@ -50,13 +60,13 @@ pub struct ListHoldsQuery {
     security(("bearer_auth" = [])),
     params(ListHoldsQuery),
     responses(
-        (status = 200, description = "All holds", body = PaginatedResponse<HoldDetails>),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse)
+        (status = 200, description = "All holds", body = PaginatedHolds),
+        (status = 401, description = "Not authenticated", body = ErrorResponse)
     )
 )]
 pub async fn list_holds(
     State(state): State<crate::AppState>,
-    AuthenticatedUser(claims): AuthenticatedUser,
+    SelfServiceUser(claims): SelfServiceUser,
     Query(query): Query<ListHoldsQuery>,
 ) -> AppResult<Json<PaginatedResponse<HoldDetails>>> {
     claims.require_list_holds()?;
@@ -78,7 +88,7 @@ pub async fn list_holds(
 }
 
 #[serde_as]
-#[derive(Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateHoldRequest {
     #[serde_as(as = "DisplayFromStr")]
@@ -88,6 +98,12 @@ pub struct CreateHoldRequest {
     #[schema(value_type = String)]
     pub item_id: i64,
     pub notes: Option<String>,
+    /// Collect the copy from a pickup locker (see the `lockers` settings section)
+    #[serde(default)]
+    pub pickup_locker: bool,
+    /// `staff` / `teaching` need `priority_holds_rights` (default `normal`)
+    #[serde(default)]
+    pub priority: HoldPriority,
 }
 
 #[utoipa::path(
@@ -98,14 +114,15 @@ pub struct CreateHoldRequest {
     request_body = CreateHoldRequest,
     responses(
         (status = 201, description = "Hold created", body = Hold),
-        (status = 400, description = "Invalid request", body = crate::error::ErrorResponse),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 409, description = "User already has a hold for this item", body = crate::error::ErrorResponse)
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights (hold for another user, or priority hold)", body = ErrorResponse),
+        (status = 409, description = "User already has a hold for this item", body = ErrorResponse)
     )
 )]
 pub async fn create_hold(
     State(state): State<crate::AppState>,
-    AuthenticatedUser(claims): AuthenticatedUser,
+    SelfServiceUser(claims): SelfServiceUser,
     ClientIp(ip): ClientIp,
     Json(req): Json<CreateHoldRequest>,
 ) -> AppResult<(StatusCode, Json<Hold>)> {
@@ -115,12 +132,20 @@ pub async fn create_hold(
             "Insufficient rights to place a hold for another user".into(),
         ));
     }
+    if req.priority != HoldPriority::Normal && !claims.can_place_priority_holds() {
+        return Err(AppError::Authorization(
+            "Insufficient rights to place a priority hold".into(),
+        ));
+    }
     let data = CreateHold {
         user_id: req.user_id,
         item_id: req.item_id,
         notes: req.notes,
+        pickup_locker: req.pickup_locker,
+        priority: req.priority,
     };
     let hold = state.services.holds.place_hold(data).await?;
+    sse::publish(&state, SsePayload::hold("hold.created", &hold));
 
     state.services.audit.log(
         audit::event::HOLD_CREATED,
@@ -128,7 +153,7 @@ pub async fn create_hold(
         Some("hold"),
         Some(hold.id),
         ip,
-        None::<()>,
+        (hold.priority != HoldPriority::Normal).then(|| serde_json::json!({ "priority": hold.priority })),
      audit::AuditLogMeta::success());
 
     Ok((StatusCode::CREATED, Json(hold)))
@@ -142,8 +167,8 @@ pub async fn create_hold(
     params(("id" = i64, Path, description = "Item ID")),
     responses(
         (status = 200, description = "Hold queue for this item", body = Vec<Hold>),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 404, description = "Item not found", body = crate::error::ErrorResponse)
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse)
     )
 )]
 pub async fn list_holds_for_item(
@@ -164,8 +189,8 @@ pub async fn list_holds_for_item(
     params(("id" = i64, Path, description = "User ID")),
     responses(
         (status = 200, description = "User's holds", body = Vec<HoldDetails>),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 404, description = "User not found", body = crate::error::ErrorResponse)
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
     )
 )]
 pub async fn list_holds_for_user(
@@ -187,14 +212,14 @@ pub async fn list_holds_for_user(
     params(("id" = i64, Path, description = "Hold ID")),
     responses(
         (status = 200, description = "Hold cancelled", body = Hold),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 403, description = "Cannot cancel another user's hold", body = crate::error::ErrorResponse),
-        (status = 404, description = "Hold not found", body = crate::error::ErrorResponse)
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Cannot cancel another user's hold", body = ErrorResponse),
+        (status = 404, description = "Hold not found", body = ErrorResponse)
     )
 )]
 pub async fn cancel_hold(
     State(state): State<crate::AppState>,
-    AuthenticatedUser(claims): AuthenticatedUser,
+    SelfServiceUser(claims): SelfServiceUser,
     ClientIp(ip): ClientIp,
     Path(id): Path<i64>,
 ) -> AppResult<Json<Hold>> {
@@ -205,6 +230,10 @@ pub async fn cancel_hold(
         .holds
         .cancel(id, claims.user_id, can_manage_others)
         .await?;
+    if hold.locker_assigned_at.is_some() && hold.picked_up_at.is_none() {
+        state.services.lockers.release(&hold).await;
+    }
+    sse::publish(&state, SsePayload::hold("hold.cancelled", &hold));
 
     state.services.audit.log(
         audit::event::HOLD_CANCELLED,
@@ -217,3 +246,75 @@ pub async fn cancel_hold(
 
     Ok(Json(hold))
 }
+
+/// Daily pull list: copies on the shelves to set aside for the first hold of their queue,
+/// grouped by shelving location and sorted by call number. `format=pdf` returns a printable sheet.
+#[utoipa::path(
+    get,
+    path = "/holds/pull-list",
+    tag = "holds",
+    security(("bearer_auth" = [])),
+    params(HoldPullListQuery),
+    responses(
+        (status = 200, description = "Copies to pull, by location (JSON, or a PDF sheet with `format=pdf`)", body = Vec<HoldShelfGroup>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse)
+    )
+)]
+pub async fn get_pull_list(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<HoldPullListQuery>,
+) -> AppResult<Response> {
+    claims.require_read_holds_staff()?;
+    let groups = state.services.holds.pull_list().await?;
+    hold_shelf_response(&state, query.format, "Hold pull list", "hold-pull-list.pdf", groups)
+}
+
+/// Copies to take off the hold shelf and reshelve: holds whose pickup deadline passed since
+/// `since` (default: the last 7 days), unless the copy went out since or another hold is queued on it.
+#[utoipa::path(
+    get,
+    path = "/holds/expired",
+    tag = "holds",
+    security(("bearer_auth" = [])),
+    params(ExpiredHoldsQuery),
+    responses(
+        (status = 200, description = "Copies to reshelve, by location (JSON, or a PDF sheet with `format=pdf`)", body = Vec<HoldShelfGroup>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse)
+    )
+)]
+pub async fn get_expired_holds(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<ExpiredHoldsQuery>,
+) -> AppResult<Response> {
+    claims.require_read_holds_staff()?;
+    let groups = state.services.holds.expired_to_reshelve(query.since).await?;
+    hold_shelf_response(&state, query.format, "Expired holds to reshelve", "expired-holds.pdf", groups)
+}
+
+fn hold_shelf_response(
+    state: &crate::AppState,
+    format: HoldListFormat,
+    title: &str,
+    filename: &str,
+    groups: Vec<HoldShelfGroup>,
+) -> AppResult<Response> {
+    match format {
+        HoldListFormat::Json => Ok(Json(groups).into_response()),
+        HoldListFormat::Pdf => {
+            let title = format!("{} - {}", title, chrono::Local::now().format("%d/%m/%Y"));
+            let pdf = state.services.exports.hold_shelf_list(&title, &groups)?;
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "application/pdf".to_string()),
+                    (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+                ],
+                pdf,
+            )
+                .into_response())
+        }
+    }
+}
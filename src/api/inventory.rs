@@ -6,7 +6,7 @@ use axum::{
     Json,
 };
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
 use crate::{
@@ -37,7 +37,7 @@ pub fn router() -> axum::Router<crate::AppState> {
 }
 
 /// Query for `GET /inventory/sessions`.
-#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
 #[serde(rename_all = "camelCase")]
 pub struct ListInventorySessionsQuery {
     /// Page number (1-based, default 1)
@@ -49,7 +49,7 @@ pub struct ListInventorySessionsQuery {
 }
 
 /// Query for paginated scan / missing lists.
-#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
 #[serde(rename_all = "camelCase")]
 pub struct ListInventoryPageQuery {
     pub page: Option<i64>,
@@ -64,9 +64,9 @@ pub struct ListInventoryPageQuery {
     security(("bearer_auth" = [])),
     params(ListInventorySessionsQuery),
     responses(
-        (status = 200, description = "Paginated sessions", body = PaginatedResponse<InventorySession>),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 403, description = "Staff access required", body = crate::error::ErrorResponse)
+        (status = 200, description = "Paginated sessions", body = PaginatedInventorySessions),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Staff access required", body = ErrorResponse)
     )
 )]
 pub async fn list_sessions(
@@ -93,8 +93,8 @@ pub async fn list_sessions(
     request_body = CreateInventorySession,
     responses(
         (status = 201, description = "Session created", body = InventorySession),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 403, description = "Staff access required", body = crate::error::ErrorResponse)
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Staff access required", body = ErrorResponse)
     )
 )]
 pub async fn create_session(
@@ -110,6 +110,7 @@ pub async fn create_session(
             req.location_filter.as_deref(),
             req.notes.as_deref(),
             req.scope_place,
+            req.scope_location_id,
             Some(claims.user_id),
         )
         .await?;
@@ -133,8 +134,8 @@ pub async fn create_session(
     params(("id" = i64, Path, description = "Session ID")),
     responses(
         (status = 200, description = "Session details", body = InventorySession),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 404, description = "Session not found", body = crate::error::ErrorResponse)
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse)
     )
 )]
 pub async fn get_session(
@@ -154,9 +155,9 @@ pub async fn get_session(
     params(("id" = i64, Path, description = "Session ID")),
     responses(
         (status = 200, description = "Session closed", body = InventorySession),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 403, description = "Staff access required", body = crate::error::ErrorResponse),
-        (status = 404, description = "Open session not found", body = crate::error::ErrorResponse)
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Staff access required", body = ErrorResponse),
+        (status = 404, description = "Open session not found", body = ErrorResponse)
     )
 )]
 pub async fn close_session(
@@ -186,9 +187,9 @@ pub async fn close_session(
     request_body = ScanBarcode,
     responses(
         (status = 201, description = "Barcode recorded", body = InventoryScan),
-        (status = 400, description = "Session is closed", body = crate::error::ErrorResponse),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 404, description = "Session not found", body = crate::error::ErrorResponse)
+        (status = 400, description = "Session is closed", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse)
     )
 )]
 pub async fn scan_barcode(
@@ -224,9 +225,9 @@ pub async fn scan_barcode(
     request_body = BatchScanBarcodes,
     responses(
         (status = 202, description = "Batch accepted; poll GET /tasks/:id", body = TaskAcceptedResponse),
-        (status = 400, description = "Session closed or batch too large", body = crate::error::ErrorResponse),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 404, description = "Session not found", body = crate::error::ErrorResponse)
+        (status = 400, description = "Session closed or batch too large", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse)
     )
 )]
 pub async fn batch_scan(
@@ -291,9 +292,9 @@ pub async fn batch_scan(
         ListInventoryPageQuery
     ),
     responses(
-        (status = 200, description = "Paginated scans", body = PaginatedResponse<InventoryScan>),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 404, description = "Session not found", body = crate::error::ErrorResponse)
+        (status = 200, description = "Paginated scans", body = PaginatedInventoryScans),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse)
     )
 )]
 pub async fn list_scans(
@@ -323,9 +324,9 @@ pub async fn list_scans(
         ListInventoryPageQuery
     ),
     responses(
-        (status = 200, description = "Paginated missing copies", body = PaginatedResponse<InventoryMissingRow>),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 404, description = "Session not found", body = crate::error::ErrorResponse)
+        (status = 200, description = "Paginated missing copies", body = PaginatedInventoryMissing),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse)
     )
 )]
 pub async fn list_missing(
@@ -353,8 +354,8 @@ pub async fn list_missing(
     params(("id" = i64, Path, description = "Session ID")),
     responses(
         (status = 200, description = "Discrepancy report", body = InventoryReport),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 404, description = "Session not found", body = crate::error::ErrorResponse)
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse)
     )
 )]
 pub async fn get_report(
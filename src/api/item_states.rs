@@ -0,0 +1,154 @@
+//! Item state taxonomy API endpoints (`/settings/item-states`)
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::item_state::{CreateItemState, ItemState, UpdateItemState},
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Build the `/settings/item-states*` routes.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new()
+        .route("/settings/item-states", get(list_item_states).post(create_item_state))
+        .route(
+            "/settings/item-states/:code",
+            get(get_item_state).put(update_item_state).delete(delete_item_state),
+        )
+}
+
+/// List item states (values allowed in an item's `circulationStatus`)
+#[utoipa::path(
+    get,
+    path = "/settings/item-states",
+    tag = "item_states",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Item states", body = Vec<ItemState>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_item_states(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<ItemState>>> {
+    claims.require_read_items()?;
+    let states = state.services.item_states.list().await?;
+    Ok(Json(states))
+}
+
+/// Get an item state by code
+#[utoipa::path(
+    get,
+    path = "/settings/item-states/{code}",
+    tag = "item_states",
+    security(("bearer_auth" = [])),
+    params(("code" = i16, Path, description = "Item state code")),
+    responses(
+        (status = 200, description = "Item state", body = ItemState),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_item_state(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(code): Path<i16>,
+) -> AppResult<Json<ItemState>> {
+    claims.require_read_items()?;
+    let item_state = state.services.item_states.get(code).await?;
+    Ok(Json(item_state))
+}
+
+/// Create an item state
+#[utoipa::path(
+    post,
+    path = "/settings/item-states",
+    tag = "item_states",
+    security(("bearer_auth" = [])),
+    request_body = CreateItemState,
+    responses(
+        (status = 201, description = "Item state created", body = ItemState),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 409, description = "Code already exists", body = ErrorResponse),
+    )
+)]
+pub async fn create_item_state(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateItemState>,
+) -> AppResult<(StatusCode, Json<ItemState>)> {
+    claims.require_write_settings()?;
+    let item_state = state.services.item_states.create(&data).await?;
+    state.services.audit.log(audit::event::ITEM_STATE_CREATED, Some(claims.user_id), Some("item_state"), Some(item_state.code as i64), ip, Some(&item_state), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(item_state)))
+}
+
+/// Update an item state (an empty `color` clears it)
+#[utoipa::path(
+    put,
+    path = "/settings/item-states/{code}",
+    tag = "item_states",
+    security(("bearer_auth" = [])),
+    params(("code" = i16, Path, description = "Item state code")),
+    request_body = UpdateItemState,
+    responses(
+        (status = 200, description = "Item state updated", body = ItemState),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_item_state(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(code): Path<i16>,
+    Json(data): Json<UpdateItemState>,
+) -> AppResult<Json<ItemState>> {
+    claims.require_write_settings()?;
+    let item_state = state.services.item_states.update(code, &data).await?;
+    state.services.audit.log(audit::event::ITEM_STATE_UPDATED, Some(claims.user_id), Some("item_state"), Some(code as i64), ip, Some((&data, &item_state)), audit::AuditLogMeta::success());
+    Ok(Json(item_state))
+}
+
+/// Delete an item state (fails while copies are in this state)
+#[utoipa::path(
+    delete,
+    path = "/settings/item-states/{code}",
+    tag = "item_states",
+    security(("bearer_auth" = [])),
+    params(("code" = i16, Path, description = "Item state code")),
+    responses(
+        (status = 204, description = "Item state deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "State is in use", body = ErrorResponse),
+    )
+)]
+pub async fn delete_item_state(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(code): Path<i16>,
+) -> AppResult<StatusCode> {
+    claims.require_write_settings()?;
+    state.services.item_states.delete(code).await?;
+    state.services.audit.log(audit::event::ITEM_STATE_DELETED, Some(claims.user_id), Some("item_state"), Some(code as i64), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
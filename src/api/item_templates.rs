@@ -0,0 +1,154 @@
+//! Cataloging templates API endpoints (`/settings/item-templates`)
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::item_template::{CreateItemTemplate, ItemTemplate, ItemTemplateFields},
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Build the `/settings/item-templates*` routes.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new()
+        .route("/settings/item-templates", get(list_item_templates).post(create_item_template))
+        .route(
+            "/settings/item-templates/:code",
+            get(get_item_template).put(update_item_template).delete(delete_item_template),
+        )
+}
+
+/// List cataloging templates (used with `POST /items?template=`)
+#[utoipa::path(
+    get,
+    path = "/settings/item-templates",
+    tag = "item_templates",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Cataloging templates", body = Vec<ItemTemplate>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_item_templates(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<ItemTemplate>>> {
+    claims.require_read_items()?;
+    let templates = state.services.item_templates.list().await?;
+    Ok(Json(templates))
+}
+
+/// Get a cataloging template by code
+#[utoipa::path(
+    get,
+    path = "/settings/item-templates/{code}",
+    tag = "item_templates",
+    security(("bearer_auth" = [])),
+    params(("code" = String, Path, description = "Template code")),
+    responses(
+        (status = 200, description = "Cataloging template", body = ItemTemplate),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_item_template(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(code): Path<String>,
+) -> AppResult<Json<ItemTemplate>> {
+    claims.require_read_items()?;
+    let template = state.services.item_templates.get(&code).await?;
+    Ok(Json(template))
+}
+
+/// Create a cataloging template
+#[utoipa::path(
+    post,
+    path = "/settings/item-templates",
+    tag = "item_templates",
+    security(("bearer_auth" = [])),
+    request_body = CreateItemTemplate,
+    responses(
+        (status = 201, description = "Template created", body = ItemTemplate),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 409, description = "Code already exists", body = ErrorResponse),
+    )
+)]
+pub async fn create_item_template(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateItemTemplate>,
+) -> AppResult<(StatusCode, Json<ItemTemplate>)> {
+    claims.require_write_settings()?;
+    let template = state.services.item_templates.create(&data).await?;
+    state.services.audit.log(audit::event::ITEM_TEMPLATE_CREATED, Some(claims.user_id), Some("item_template"), Some(template.id), ip, Some(&template), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(template)))
+}
+
+/// Replace a cataloging template (omitted fields are cleared)
+#[utoipa::path(
+    put,
+    path = "/settings/item-templates/{code}",
+    tag = "item_templates",
+    security(("bearer_auth" = [])),
+    params(("code" = String, Path, description = "Template code")),
+    request_body = ItemTemplateFields,
+    responses(
+        (status = 200, description = "Template updated", body = ItemTemplate),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_item_template(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(code): Path<String>,
+    Json(data): Json<ItemTemplateFields>,
+) -> AppResult<Json<ItemTemplate>> {
+    claims.require_write_settings()?;
+    let template = state.services.item_templates.update(&code, &data).await?;
+    state.services.audit.log(audit::event::ITEM_TEMPLATE_UPDATED, Some(claims.user_id), Some("item_template"), Some(template.id), ip, Some(&template), audit::AuditLogMeta::success());
+    Ok(Json(template))
+}
+
+/// Delete a cataloging template
+#[utoipa::path(
+    delete,
+    path = "/settings/item-templates/{code}",
+    tag = "item_templates",
+    security(("bearer_auth" = [])),
+    params(("code" = String, Path, description = "Template code")),
+    responses(
+        (status = 204, description = "Template deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_item_template(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(code): Path<String>,
+) -> AppResult<StatusCode> {
+    claims.require_write_settings()?;
+    let template = state.services.item_templates.get(&code).await?;
+    state.services.item_templates.delete(&code).await?;
+    state.services.audit.log(audit::event::ITEM_TEMPLATE_DELETED, Some(claims.user_id), Some("item_template"), Some(template.id), ip, Some(&template), audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
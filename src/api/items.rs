@@ -2,23 +2,33 @@
 
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::{IntoResponse, Redirect, Response},
     Json,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
     error::AppResult,
+    models::artifact::{ArtifactKind, ExportDeliveryQuery},
     models::biblio::Biblio,
-    models::item::Item,
+    models::citation::{Citation, CitationQuery},
+    models::item::{CallNumberRecalculationReport, Item, ItemExportFormat, RecalculateCallNumbers, SpineLabelQuery},
+    models::item_template::QuickCreateQuery,
+    models::loan::{LoanMarcExportEncoding, ReturnRouting},
     services::audit::{self},
 };
 
-use super::{AuthenticatedUser, ClientIp, ValidatedJson};
+use super::{biblios::CreateBiblioResponse, AuthenticatedUser, ClientIp, ValidatedJson};
 
 pub fn router() -> axum::Router<crate::AppState> {
-    use axum::routing::get;
+    use axum::routing::{get, post};
     axum::Router::new()
+        .route("/items", post(quick_create_item))
+        .route("/items/recalculate-call-numbers", post(recalculate_call_numbers))
+        .route("/items/export", get(export_items))
+        .route("/items/labels", get(print_spine_labels))
         .route(
             "/items/barcode/:barcode",
             get(get_biblio_by_barcode),
@@ -27,6 +37,68 @@ pub fn router() -> axum::Router<crate::AppState> {
             "/items/:id",
             get(get_biblio_by_item).put(update_item).delete(delete_item),
         )
+        .route("/items/:id/access", get(access_item))
+        .route("/items/:id/citation", get(item_citation))
+        .route("/items/:id/routing", post(route_item))
+        .route("/items/:id/receive", post(receive_item))
+}
+
+/// Quick cataloging: create a record with its copies, optionally from a template.
+///
+/// The body has the shape of [`Biblio`]; with `template`, the template fields fill the ones left
+/// absent or `null` (media type and audience on the record; location, borrowable and state on
+/// each copy). The template genre is assigned when the resulting record has no genre yet.
+#[utoipa::path(
+    post,
+    path = "/items",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(QuickCreateQuery),
+    request_body = Biblio,
+    responses(
+        (status = 201, description = "Record created or merged", body = CreateBiblioResponse),
+        (status = 400, description = "Invalid record or unknown template", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 409, description = "Duplicate ISBN requires confirmation", body = DuplicateConfirmationRequired)
+    )
+)]
+pub async fn quick_create_item(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Query(query): Query<QuickCreateQuery>,
+    Json(body): Json<serde_json::Value>,
+) -> AppResult<(StatusCode, Json<CreateBiblioResponse>)> {
+    claims.require_write_items()?;
+
+    let (biblio, genre_id) = state
+        .services
+        .item_templates
+        .prepare_biblio(query.template.as_deref(), body)
+        .await?;
+    let (biblio, import_report) = state
+        .services
+        .catalog
+        .create_biblio(biblio, query.allow_duplicate_isbn, None)
+        .await?;
+
+    if let (Some(genre_id), Some(biblio_id)) = (genre_id, biblio.id) {
+        if state.services.catalog.get_biblio_genres(biblio_id).await?.is_empty() {
+            state.services.catalog.set_biblio_genres(biblio_id, &[genre_id]).await?;
+        }
+    }
+
+    state.services.audit.log(
+        audit::event::BIBLIO_CREATED,
+        Some(claims.user_id),
+        Some("biblio"),
+        biblio.id,
+        ip,
+        Some(&biblio),
+        audit::AuditLogMeta::success());
+
+    Ok((StatusCode::CREATED, Json(CreateBiblioResponse { biblio, import_report })))
 }
 
 /// Get the bibliographic record for a physical copy.
@@ -42,9 +114,9 @@ pub fn router() -> axum::Router<crate::AppState> {
     ),
     responses(
         (status = 200, description = "Biblio with a single item entry", body = Biblio),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 404, description = "Item not found or archived", body = crate::error::ErrorResponse),
-        (status = 410, description = "Bibliographic record is archived", body = crate::error::ErrorResponse)
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Item not found or archived", body = ErrorResponse),
+        (status = 410, description = "Bibliographic record is archived", body = ErrorResponse)
     )
 )]
 pub async fn get_biblio_by_item(
@@ -57,6 +129,38 @@ pub async fn get_biblio_by_item(
     Ok(Json(biblio))
 }
 
+/// Citation of the bibliographic record of a physical copy.
+///
+/// `style=apa` (default), `mla` or `iso690` return the citation as text and HTML;
+/// `style=bibtex` and `style=ris` download a file for reference managers. The OPAC uses
+/// `GET /opac/biblios/{id}/citation`.
+#[utoipa::path(
+    get,
+    path = "/items/{id}/citation",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "Physical copy (item) ID"),
+        CitationQuery
+    ),
+    responses(
+        (status = 200, description = "Citation (JSON), or BibTeX / RIS file", body = Citation),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Item not found or archived", body = ErrorResponse),
+        (status = 410, description = "Bibliographic record is archived", body = ErrorResponse)
+    )
+)]
+pub async fn item_citation(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(item_id): Path<i64>,
+    Query(query): Query<CitationQuery>,
+) -> AppResult<Response> {
+    claims.require_read_items()?;
+    let biblio = state.services.catalog.get_biblio_for_item(item_id).await?;
+    Ok(super::opac::citation_response(&biblio, query.style.unwrap_or_default()))
+}
+
 /// Get the bibliographic record for a physical copy identified by barcode.
 ///
 /// Response is a full [`Biblio`]; `items` contains **only** the matching copy.
@@ -70,9 +174,9 @@ pub async fn get_biblio_by_item(
     ),
     responses(
         (status = 200, description = "Biblio with a single item entry", body = Biblio),
-        (status = 401, description = "Not authenticated", body = crate::error::ErrorResponse),
-        (status = 404, description = "No active item with this barcode", body = crate::error::ErrorResponse),
-        (status = 410, description = "Bibliographic record is archived", body = crate::error::ErrorResponse)
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "No active item with this barcode", body = ErrorResponse),
+        (status = 410, description = "Bibliographic record is archived", body = ErrorResponse)
     )
 )]
 pub async fn get_biblio_by_barcode(
@@ -89,6 +193,33 @@ pub async fn get_biblio_by_barcode(
     Ok(Json(biblio))
 }
 
+/// Follow the digital resource link of a copy (e-book, streaming…).
+///
+/// Records the click-through for usage statistics and redirects (307) to `accessUrl`.
+/// Open resources may be accessed anonymously; restricted ones need a valid token.
+#[utoipa::path(
+    get,
+    path = "/items/{id}/access",
+    tag = "items",
+    params(
+        ("id" = i64, Path, description = "Physical copy (item) ID")
+    ),
+    responses(
+        (status = 307, description = "Redirect to the digital resource"),
+        (status = 401, description = "Restricted resource and not authenticated", body = ErrorResponse),
+        (status = 404, description = "Item not found, archived or without digital resource", body = ErrorResponse)
+    )
+)]
+pub async fn access_item(
+    State(state): State<crate::AppState>,
+    user: Option<AuthenticatedUser>,
+    Path(item_id): Path<i64>,
+) -> AppResult<Redirect> {
+    let user_id = user.map(|AuthenticatedUser(claims)| claims.user_id);
+    let url = state.services.catalog.access_item(item_id, user_id).await?;
+    Ok(Redirect::temporary(&url))
+}
+
 /// Update a physical item. The path id is authoritative.
 #[utoipa::path(
     put,
@@ -101,9 +232,10 @@ pub async fn get_biblio_by_barcode(
     request_body = Item,
     responses(
         (status = 200, description = "Physical item updated", body = Item),
-        (status = 400, description = "Validation error", body = crate::error::ErrorResponse),
-        (status = 404, description = "Biblio or item not found", body = crate::error::ErrorResponse),
-        (status = 409, description = "An item with this barcode already exists")
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 404, description = "Biblio or item not found", body = ErrorResponse),
+        (status = 409, description = "An item with this barcode already exists"),
+        (status = 422, description = "Invalid fields", body = InvalidFieldsResponse)
     )
 )]
 pub async fn update_item(
@@ -114,12 +246,24 @@ pub async fn update_item(
     ValidatedJson(mut item): ValidatedJson<Item>,
 ) -> AppResult<Json<Item>> {
     claims.require_write_items()?;
-    let (biblio_id, _) = state
+    let (biblio_id, previous_state, _) = state
         .services
         .catalog
         .update_item(item_id, &mut item)
         .await?;
 
+    if let Some(new_state) = item.circulation_status.filter(|s| Some(*s) != previous_state) {
+        state.services.audit.log(
+            audit::event::ITEM_STATE_CHANGED,
+            Some(claims.user_id),
+            Some("item"),
+            Some(item_id),
+            ip.clone(),
+            Some(serde_json::json!({ "from": previous_state, "to": new_state })),
+            audit::AuditLogMeta::success(),
+        );
+    }
+
     state.services.audit.log(
         audit::event::ITEM_UPDATED,
         Some(claims.user_id),
@@ -175,8 +319,222 @@ pub async fn delete_item(
     Ok(StatusCode::NO_CONTENT)
 }
 
-#[derive(Deserialize)]
+/// Staff answer to the routing prompt of a return
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteItemRequest {
+    /// Place the copy was checked in at (`routing.returnPlace` of the return)
+    pub place: i16,
+    /// Stay at the place (`true`) or go back home (`false`)
+    pub floating: bool,
+}
+
+/// Answer the routing prompt of a return (`routing.promptOverride`).
+///
+/// A floating copy takes the return place, a copy sent home is in transit until received there.
+#[utoipa::path(
+    post,
+    path = "/items/{id}/routing",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "Physical copy (item) ID")
+    ),
+    request_body = RouteItemRequest,
+    responses(
+        (status = 200, description = "Copy routed", body = ReturnRouting),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 409, description = "The copy is on loan", body = ErrorResponse),
+        (status = 422, description = "No return of the copy at this place waits for an answer", body = ErrorResponse)
+    )
+)]
+pub async fn route_item(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(item_id): Path<i64>,
+    Json(request): Json<RouteItemRequest>,
+) -> AppResult<Json<ReturnRouting>> {
+    claims.require_write_loans()?;
+    let routing = state
+        .services
+        .loans
+        .answer_routing(item_id, request.place, request.floating)
+        .await?;
+    super::loans::log_routing(&state, claims.user_id, ip, item_id, &routing);
+    Ok(Json(routing))
+}
+
+/// Receive a copy in transit at its home place (`transitPlace` is cleared).
+#[utoipa::path(
+    post,
+    path = "/items/{id}/receive",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "Physical copy (item) ID")
+    ),
+    responses(
+        (status = 204, description = "Copy received"),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 422, description = "The copy is not in transit", body = ErrorResponse)
+    )
+)]
+pub async fn receive_item(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(item_id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_loans()?;
+    let place = state.services.loans.receive_item(item_id).await?;
+
+    state.services.audit.log(
+        audit::event::ITEM_RECEIVED,
+        Some(claims.user_id),
+        Some("item"),
+        Some(item_id),
+        ip,
+        Some(serde_json::json!({ "place": place })),
+        audit::AuditLogMeta::success(),
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteItemParams {
     pub force: Option<bool>,
 }
+
+/// Query of `GET /items/export`
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ItemExportQuery {
+    /// `csv` (default), `xlsx`, `json`, `marc21`, `unimarc` or `marcxml`
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    pub format: ItemExportFormat,
+    /// ISO2709 character encoding (`utf8` default, `marc8`); ignored by other formats
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    pub encoding: LoanMarcExportEncoding,
+}
+
+/// Export the whole catalog, streamed page by page.
+///
+/// Row formats (`csv`, `xlsx`, `json`) have one line per active copy; MARC formats have one record
+/// per biblio with its active copies as holdings. The body is produced while it is sent, so the
+/// size of the catalog does not matter; a failure midway aborts the transfer. `delivery=link`
+/// stores the file as an artifact and returns a signed download URL instead.
+#[utoipa::path(
+    get,
+    path = "/items/export",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(ItemExportQuery, ExportDeliveryQuery),
+    responses(
+        (status = 200, description = "File attachment, streamed"),
+        (status = 201, description = "Export stored (`delivery=link`)", body = ArtifactLink),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse)
+    )
+)]
+pub async fn export_items(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<ItemExportQuery>,
+    Query(delivery): Query<ExportDeliveryQuery>,
+) -> AppResult<Response> {
+    claims.require_read_catalog()?;
+    let export = state.services.exports.items(query.format, query.encoding);
+    super::artifacts::deliver_stream(
+        &state,
+        export,
+        ArtifactKind::CatalogExport,
+        claims.user_id,
+        delivery.delivery,
+    )
+    .await
+}
+
+/// Print spine labels (PDF) for the copies of a shelving location or created in a date range.
+///
+/// One label per active copy with a call number, in call number order, the call number split one
+/// word per line. `stock` selects the Avery sheet layout; `skip` starts after the positions already
+/// used on a partly used sheet. At most 2000 labels per request.
+#[utoipa::path(
+    get,
+    path = "/items/labels",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    params(SpineLabelQuery),
+    responses(
+        (status = 200, description = "PDF label sheets", content_type = "application/pdf"),
+        (status = 400, description = "Missing filter, invalid layout option or too many copies", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "No copy matches the filter", body = ErrorResponse)
+    )
+)]
+pub async fn print_spine_labels(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<SpineLabelQuery>,
+) -> AppResult<Response> {
+    claims.require_read_items()?;
+    let pdf = state.services.exports.spine_labels(&query).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf"),
+            (header::CONTENT_DISPOSITION, r#"attachment; filename="spine-labels.pdf""#),
+        ],
+        pdf,
+    )
+        .into_response())
+}
+
+/// Recalculate call numbers in bulk (prefix rewrites, Dewey truncation, audience prefixes).
+///
+/// `dryRun` defaults to true and only returns the diff. When applied, each changed copy gets an
+/// `item.call_number_recalculated` audit entry.
+#[utoipa::path(
+    post,
+    path = "/items/recalculate-call-numbers",
+    tag = "items",
+    security(("bearer_auth" = [])),
+    request_body = RecalculateCallNumbers,
+    responses(
+        (status = 200, description = "Changed (or would-be changed) call numbers", body = CallNumberRecalculationReport),
+        (status = 400, description = "Invalid rule set", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 422, description = "Invalid fields", body = InvalidFieldsResponse)
+    )
+)]
+pub async fn recalculate_call_numbers(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    ValidatedJson(request): ValidatedJson<RecalculateCallNumbers>,
+) -> AppResult<Json<CallNumberRecalculationReport>> {
+    claims.require_write_items()?;
+    let report = state.services.catalog.recalculate_call_numbers(&request, claims.user_id).await?;
+
+    if !report.dry_run {
+        for change in &report.changes {
+            state.services.audit.log(
+                audit::event::ITEM_CALL_NUMBER_RECALCULATED,
+                Some(claims.user_id),
+                Some("item"),
+                Some(change.item_id),
+                ip.clone(),
+                Some(serde_json::json!({ "before": change.before, "after": change.after })),
+                audit::AuditLogMeta::success(),
+            );
+        }
+    }
+
+    Ok(Json(report))
+}
@@ -0,0 +1,238 @@
+//! Kiosk token API endpoints (`/settings/kiosks`, `/kiosk/session`)
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::kiosk::{CreateKiosk, Kiosk, KioskSession, KioskWithToken, UpdateKiosk},
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp, KioskClient};
+
+/// Build the `/settings/kiosks*` routes (administrators).
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post};
+    axum::Router::new()
+        .route("/settings/kiosks", get(list_kiosks).post(create_kiosk))
+        .route(
+            "/settings/kiosks/:id",
+            get(get_kiosk).put(update_kiosk).delete(delete_kiosk),
+        )
+        .route("/settings/kiosks/:id/regenerate-token", post(regenerate_kiosk_token))
+}
+
+/// Routes called by the terminals themselves (mounted with the public rate limiter).
+pub fn router_kiosk() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new().route("/kiosk/session", get(kiosk_session))
+}
+
+/// List kiosks
+#[utoipa::path(
+    get,
+    path = "/settings/kiosks",
+    tag = "kiosks",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Kiosks", body = Vec<Kiosk>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+    )
+)]
+pub async fn list_kiosks(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<Kiosk>>> {
+    claims.require_admin()?;
+    let kiosks = state.services.kiosks.list().await?;
+    Ok(Json(kiosks))
+}
+
+/// Get a kiosk
+#[utoipa::path(
+    get,
+    path = "/settings/kiosks/{id}",
+    tag = "kiosks",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Kiosk ID")),
+    responses(
+        (status = 200, description = "Kiosk", body = Kiosk),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_kiosk(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Kiosk>> {
+    claims.require_admin()?;
+    let kiosk = state.services.kiosks.get(id).await?;
+    Ok(Json(kiosk))
+}
+
+/// Register a kiosk (the token is returned only in this response)
+#[utoipa::path(
+    post,
+    path = "/settings/kiosks",
+    tag = "kiosks",
+    security(("bearer_auth" = [])),
+    request_body = CreateKiosk,
+    responses(
+        (status = 201, description = "Kiosk created", body = KioskWithToken),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+    )
+)]
+pub async fn create_kiosk(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateKiosk>,
+) -> AppResult<(StatusCode, Json<KioskWithToken>)> {
+    claims.require_admin()?;
+    let created = state.services.kiosks.create(&data, claims.user_id).await?;
+    state.services.audit.log(
+        audit::event::KIOSK_CREATED,
+        Some(claims.user_id),
+        Some("kiosk"),
+        Some(created.kiosk.id),
+        ip,
+        Some(&created.kiosk),
+        audit::AuditLogMeta::success(),
+    );
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Update a kiosk (`isActive: false` revokes its token)
+#[utoipa::path(
+    put,
+    path = "/settings/kiosks/{id}",
+    tag = "kiosks",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Kiosk ID")),
+    request_body = UpdateKiosk,
+    responses(
+        (status = 200, description = "Kiosk updated", body = Kiosk),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_kiosk(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdateKiosk>,
+) -> AppResult<Json<Kiosk>> {
+    claims.require_admin()?;
+    let kiosk = state.services.kiosks.update(id, &data).await?;
+    state.services.audit.log(
+        audit::event::KIOSK_UPDATED,
+        Some(claims.user_id),
+        Some("kiosk"),
+        Some(id),
+        ip,
+        Some((&data, &kiosk)),
+        audit::AuditLogMeta::success(),
+    );
+    Ok(Json(kiosk))
+}
+
+/// Issue a new token for a kiosk (the previous token stops working)
+#[utoipa::path(
+    post,
+    path = "/settings/kiosks/{id}/regenerate-token",
+    tag = "kiosks",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Kiosk ID")),
+    responses(
+        (status = 200, description = "New token", body = KioskWithToken),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn regenerate_kiosk_token(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<Json<KioskWithToken>> {
+    claims.require_admin()?;
+    let regenerated = state.services.kiosks.regenerate_token(id).await?;
+    state.services.audit.log(
+        audit::event::KIOSK_TOKEN_REGENERATED,
+        Some(claims.user_id),
+        Some("kiosk"),
+        Some(id),
+        ip,
+        Some(serde_json::json!({ "tokenPrefix": regenerated.kiosk.token_prefix })),
+        audit::AuditLogMeta::success(),
+    );
+    Ok(Json(regenerated))
+}
+
+/// Delete a kiosk
+#[utoipa::path(
+    delete,
+    path = "/settings/kiosks/{id}",
+    tag = "kiosks",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Kiosk ID")),
+    responses(
+        (status = 204, description = "Kiosk deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_kiosk(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_admin()?;
+    state.services.kiosks.delete(id).await?;
+    state.services.audit.log(
+        audit::event::KIOSK_DELETED,
+        Some(claims.user_id),
+        Some("kiosk"),
+        Some(id),
+        ip,
+        None::<serde_json::Value>,
+        audit::AuditLogMeta::success(),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Identity of the calling terminal (lets a kiosk check its token at startup)
+#[utoipa::path(
+    get,
+    path = "/kiosk/session",
+    tag = "kiosks",
+    security(("kiosk_token" = [])),
+    responses(
+        (status = 200, description = "Kiosk identity", body = KioskSession),
+        (status = 401, description = "Missing, invalid, revoked or expired token", body = ErrorResponse),
+        (status = 403, description = "Token used from a non-allowed address", body = ErrorResponse),
+    )
+)]
+pub async fn kiosk_session(KioskClient(kiosk): KioskClient) -> AppResult<Json<KioskSession>> {
+    Ok(Json(KioskSession {
+        kiosk_id: kiosk.id,
+        name: kiosk.name,
+        location: kiosk.location,
+        expires_at: kiosk.expires_at,
+    }))
+}
@@ -0,0 +1,128 @@
+//! Translated display labels endpoints (`/labels`)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::label::{Label, LabelKind, LabelSet, LabelsQuery, UpsertLabel},
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp, Locale};
+
+/// Public `GET /labels` (OPAC and staff UI both need it; mounted with the public rate limiter).
+pub fn router_public() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new().route("/labels", get(list_labels))
+}
+
+/// Staff routes editing translations.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::put;
+    axum::Router::new().route(
+        "/labels/:kind/:code/:lang",
+        put(upsert_label).delete(delete_label),
+    )
+}
+
+/// Translated labels of media types, audiences, patron categories, genres and account types
+///
+/// Without `lang`, answers in the caller's language: the authenticated user's preferred
+/// language, then `Accept-Language`, then `en`. Codes without a translation are absent.
+#[utoipa::path(
+    get,
+    path = "/labels",
+    tag = "labels",
+    params(
+        LabelsQuery,
+        ("Accept-Language" = Option<String>, Header, description = "Preferred languages (e.g. fr-FR,fr;q=0.9,en;q=0.8)")
+    ),
+    responses(
+        (status = 200, description = "Labels grouped by kind then code", body = LabelSet),
+        (status = 400, description = "Invalid language code", body = ErrorResponse),
+    )
+)]
+pub async fn list_labels(
+    State(state): State<crate::AppState>,
+    Locale(locale): Locale,
+    Query(query): Query<LabelsQuery>,
+) -> AppResult<Json<LabelSet>> {
+    let lang = query.lang.unwrap_or(locale);
+    let mut labels = state.services.labels.label_set(&lang, query.kind).await?;
+    labels.languages = state.services.labels.languages().await?;
+    Ok(Json(labels))
+}
+
+/// Create or replace the translation of a code
+#[utoipa::path(
+    put,
+    path = "/labels/{kind}/{code}/{lang}",
+    tag = "labels",
+    security(("bearer_auth" = [])),
+    params(
+        ("kind" = LabelKind, Path, description = "Kind of code"),
+        ("code" = String, Path, description = "Code as returned by the API (e.g. printedText)"),
+        ("lang" = String, Path, description = "ISO 639-1 language code")
+    ),
+    request_body = UpsertLabel,
+    responses(
+        (status = 200, description = "Label saved", body = Label),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn upsert_label(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path((kind, code, lang)): Path<(LabelKind, String, String)>,
+    Json(data): Json<UpsertLabel>,
+) -> AppResult<Json<Label>> {
+    claims.require_write_settings()?;
+    let label = state.services.labels.upsert(kind, &code, &lang, &data.label).await?;
+    state.services.audit.log(audit::event::LABEL_UPDATED, Some(claims.user_id), Some("label"), None, ip, Some(&label), audit::AuditLogMeta::success());
+    Ok(Json(label))
+}
+
+/// Delete a translation (the code then falls back to its raw value)
+#[utoipa::path(
+    delete,
+    path = "/labels/{kind}/{code}/{lang}",
+    tag = "labels",
+    security(("bearer_auth" = [])),
+    params(
+        ("kind" = LabelKind, Path, description = "Kind of code"),
+        ("code" = String, Path, description = "Code as returned by the API"),
+        ("lang" = String, Path, description = "ISO 639-1 language code")
+    ),
+    responses(
+        (status = 204, description = "Label deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_label(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path((kind, code, lang)): Path<(LabelKind, String, String)>,
+) -> AppResult<StatusCode> {
+    claims.require_write_settings()?;
+    state.services.labels.delete(kind, &code, &lang).await?;
+    state.services.audit.log(
+        audit::event::LABEL_DELETED,
+        Some(claims.user_id),
+        Some("label"),
+        None,
+        ip,
+        Some(serde_json::json!({ "kind": kind, "code": code, "lang": lang })),
+        audit::AuditLogMeta::success(),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -4,11 +4,12 @@ use axum::{extract::State, Json};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use validator::Validate;
 
 use crate::error::AppResult;
 use crate::services::audit;
 
-use super::{AuthenticatedUser, ClientIp};
+use super::{AuthenticatedUser, ClientIp, ValidatedJson};
 
 
 /// Public GET only — merged under the public API rate limiter in `main.rs`.
@@ -49,24 +50,30 @@ pub struct LibraryInfo {
 }
 
 /// Update library information request
-#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateLibraryInfoRequest {
     /// Library name
     pub name: Option<String>,
     /// Street address (number + street)
+    #[validate(length(max = 100, message = "Address must be at most 100 characters"))]
     pub addr_line1: Option<String>,
     /// Address complement (building, apt, etc.)
+    #[validate(length(max = 100, message = "Address must be at most 100 characters"))]
     pub addr_line2: Option<String>,
     /// Postal code
+    #[validate(length(max = 10, message = "Postal code must be at most 10 characters"))]
     pub addr_postcode: Option<String>,
     /// City
+    #[validate(length(max = 100, message = "City must be at most 100 characters"))]
     pub addr_city: Option<String>,
     /// Country
+    #[validate(length(max = 50, message = "Country must be at most 50 characters"))]
     pub addr_country: Option<String>,
     /// Phone numbers (replaces existing list)
     pub phones: Option<Vec<String>>,
     /// Contact email
+    #[validate(email(message = "Invalid email format"))]
     pub email: Option<String>,
 }
 
@@ -99,14 +106,15 @@ pub async fn get_library_info(
     request_body = UpdateLibraryInfoRequest,
     responses(
         (status = 200, description = "Library information updated", body = LibraryInfo),
-        (status = 403, description = "Insufficient permissions")
+        (status = 403, description = "Insufficient permissions"),
+        (status = 422, description = "Invalid fields", body = InvalidFieldsResponse)
     )
 )]
 pub async fn update_library_info(
     State(state): State<crate::AppState>,
     AuthenticatedUser(claims): AuthenticatedUser,
     ClientIp(ip): ClientIp,
-    Json(request): Json<UpdateLibraryInfoRequest>,
+    ValidatedJson(request): ValidatedJson<UpdateLibraryInfoRequest>,
 ) -> AppResult<Json<LibraryInfo>> {
     claims.require_write_settings()?;
 
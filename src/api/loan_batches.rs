@@ -0,0 +1,159 @@
+//! Group loans API endpoints (class visits: batches with one shared due date)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::loan_batch::{
+        CreateLoanBatch, ExtendLoanBatch, LoanBatch, LoanBatchQuery, LoanBatchReturnReport,
+    },
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Build the group loans routes for this domain.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post};
+    axum::Router::new()
+        .route("/loan-batches", get(list_loan_batches).post(create_loan_batch))
+        .route("/loan-batches/:id", get(get_loan_batch))
+        .route("/loan-batches/:id/extend", post(extend_loan_batch))
+        .route("/loan-batches/:id/return", post(return_loan_batch))
+}
+
+/// List group loan batches
+#[utoipa::path(
+    get,
+    path = "/loan-batches",
+    tag = "loans",
+    security(("bearer_auth" = [])),
+    params(LoanBatchQuery),
+    responses(
+        (status = 200, description = "Loan batches", body = Vec<LoanBatch>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_loan_batches(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<LoanBatchQuery>,
+) -> AppResult<Json<Vec<LoanBatch>>> {
+    claims.require_read_loans()?;
+    let batches = state.services.loan_batches.list(&query).await?;
+    Ok(Json(batches))
+}
+
+/// Get a group loan batch with its copies
+#[utoipa::path(
+    get,
+    path = "/loan-batches/{id}",
+    tag = "loans",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Loan batch ID")),
+    responses(
+        (status = 200, description = "Loan batch", body = LoanBatch),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_loan_batch(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<LoanBatch>> {
+    claims.require_read_loans()?;
+    let batch = state.services.loan_batches.get(id).await?;
+    Ok(Json(batch))
+}
+
+/// Check out copies to a group account with one shared due date (all or nothing)
+#[utoipa::path(
+    post,
+    path = "/loan-batches",
+    tag = "loans",
+    security(("bearer_auth" = [])),
+    request_body = CreateLoanBatch,
+    responses(
+        (status = 201, description = "Copies checked out", body = LoanBatch),
+        (status = 400, description = "Invalid barcodes or due date", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "User or copy not found", body = ErrorResponse),
+        (status = 422, description = "Group loans not allowed, too many copies, or a copy cannot be lent", body = ErrorResponse),
+    )
+)]
+pub async fn create_loan_batch(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateLoanBatch>,
+) -> AppResult<(StatusCode, Json<LoanBatch>)> {
+    claims.require_write_loans()?;
+    let batch = state.services.loan_batches.create(&data, claims.user_id).await?;
+    state.services.audit.log(audit::event::LOAN_BATCH_CREATED, Some(claims.user_id), Some("loan_batch"), Some(batch.id), ip, Some(serde_json::json!({ "userId": data.user_id.to_string(), "dueAt": batch.due_at, "barcodes": data.barcodes, "force": data.force })), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(batch)))
+}
+
+/// Move the shared due date of every copy still out
+#[utoipa::path(
+    post,
+    path = "/loan-batches/{id}/extend",
+    tag = "loans",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Loan batch ID")),
+    request_body = ExtendLoanBatch,
+    responses(
+        (status = 200, description = "Due date extended", body = LoanBatch),
+        (status = 400, description = "Invalid due date", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 422, description = "Every copy was returned", body = ErrorResponse),
+    )
+)]
+pub async fn extend_loan_batch(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<ExtendLoanBatch>,
+) -> AppResult<Json<LoanBatch>> {
+    claims.require_write_loans()?;
+    let batch = state.services.loan_batches.extend(id, &data).await?;
+    state.services.audit.log(audit::event::LOAN_BATCH_EXTENDED, Some(claims.user_id), Some("loan_batch"), Some(id), ip, Some(&data), audit::AuditLogMeta::success());
+    Ok(Json(batch))
+}
+
+/// Return every copy of the batch still out
+#[utoipa::path(
+    post,
+    path = "/loan-batches/{id}/return",
+    tag = "loans",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Loan batch ID")),
+    responses(
+        (status = 200, description = "Copies returned", body = LoanBatchReturnReport),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 422, description = "Every copy was already returned", body = ErrorResponse),
+    )
+)]
+pub async fn return_loan_batch(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<Json<LoanBatchReturnReport>> {
+    claims.require_write_loans()?;
+    let report = state.services.loan_batches.return_all(id).await?;
+    state.services.audit.log(audit::event::LOAN_BATCH_RETURNED, Some(claims.user_id), Some("loan_batch"), Some(id), ip, Some(&report), audit::AuditLogMeta::success());
+    Ok(Json(report))
+}
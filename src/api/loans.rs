@@ -1,12 +1,8 @@
 //! Loan management endpoints
 
 use axum::{
-    body::Body,
     extract::{Path, Query, State},
-    http::{
-        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
-        StatusCode,
-    },
+    http::StatusCode,
     response::Response,
     Json,
 };
@@ -14,15 +10,19 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
 
 use crate::{
     error::{AppError, AppResult},
     models::{
+        artifact::{ArtifactKind, ExportDeliveryQuery},
         biblio::MediaType,
+        bundle::BundlePart,
         loan::{
-            CreateLoan, LoanDetails, LoanMarcExportEncoding, LoanMarcExportFormat,
-            LoanSettingsRenewAt,
-        }, user::Rights,
+            CheckoutBlock, CreateLoan, LoanCheckoutOutcome, LoanDetails,
+            LoanMarcExportEncoding, LoanMarcExportFormat, LoanRenewability, LoanReturnOutcome,
+            LoanSettingsRenewAt, ReturnRouting,
+        }, user::{Rights, UserClaims}, user_message::UserMessage,
     },
     services::{
         audit::{self},
@@ -30,17 +30,24 @@ use crate::{
     },
 };
 
-use super::{biblios::PaginatedResponse, AuthenticatedUser, ClientIp};
+use super::{
+    biblios::PaginatedResponse,
+    sse::{self, SsePayload},
+    AuthenticatedUser, ClientIp, SelfServiceUser, ValidatedJson,
+};
 
 /// Loan rules (`loans_settings`): per-document-type overrides plus one global default row (`mediaType` JSON `null`).
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LoanSettings {
     /// `null` = global default row (`media_type` IS NULL in DB). On that row, `maxLoans` is the cap **across all media** for a patron.
     pub media_type: Option<MediaType>,
     /// Per-media cap when `mediaType` is set; **total** active loans cap when `mediaType` is null (default row).
+    #[validate(range(min = 0, message = "Maximum loans must not be negative"))]
     pub max_loans: i16,
+    #[validate(range(min = 0, message = "Maximum renewals must not be negative"))]
     pub max_renewals: i16,
+    #[validate(range(min = 1, message = "Loan duration must be at least one day"))]
     pub duration_days: i16,
     /// How the new due date is computed on renew: from renewal time (`now`) or current due date (`at_due_date`).
     #[serde(default)]
@@ -48,9 +55,10 @@ pub struct LoanSettings {
 }
 
 /// Partial update of global loan rules.
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateLoanSettingsRequest {
+    #[validate(nested)]
     pub loan_settings: Option<Vec<LoanSettings>>,
 }
 
@@ -63,8 +71,10 @@ pub fn router() -> axum::Router<crate::AppState> {
         .route("/loans/settings", get(get_loan_settings).put(update_loan_settings))
         .route("/loans/overdue", get(get_overdue_loans))
         .route("/loans/send-overdue-reminders", post(send_overdue_reminders))
+        .route("/loans/send-due-soon-reminders", post(send_due_soon_reminders))
         .route("/loans/:id/return", post(return_loan))
         .route("/loans/:id/renew", post(renew_loan))
+        .route("/loans/:id/renewability", get(get_loan_renewability))
         .route("/loans/items/:item_id/return", post(return_loan_by_item))
         .route("/loans/items/:item_id/renew", post(renew_loan_by_item))
 }
@@ -73,7 +83,7 @@ pub fn router() -> axum::Router<crate::AppState> {
 
 /// Create loan request
 #[serde_as]
-#[derive(Serialize, Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateLoanRequest {
     #[serde_as(as = "DisplayFromStr")]
@@ -82,20 +92,38 @@ pub struct CreateLoanRequest {
     #[serde_as(as = "Option<DisplayFromStr>")]
     #[schema(value_type = Option<String>)]
     pub item_id: Option<i64>,
+    /// Equipment to check out (alternative to `itemId`; equipment barcodes also work in `itemIdentification`).
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    #[serde(default)]
+    pub equipment_id: Option<i64>,
+    /// Barcode of a copy or of a piece of equipment.
+    #[validate(length(max = 100, message = "Barcode must be at most 100 characters"))]
     pub item_identification: Option<String>,
-    /// When true, bypasses patron/subscription/limits checks and hold-queue rules; active holds on the copy are cancelled.
+    /// When true, bypasses hold-queue rules (active holds on the copy are cancelled) and, for callers
+    /// with `circulation_override_rights`, the patron's checkout blocks.
     pub force: Option<bool>,
+    /// Staff confirms the deposit was received (equipment with `requiresDeposit`).
+    pub deposit_received: Option<bool>,
 }
 
 #[derive(Serialize)]
 struct LoanCreatedAudit {
     user_id: i64,
     item_id: Option<i64>,
+    equipment_id: Option<i64>,
     item_identification: Option<String>,
     force: bool,
+    deposit_received: bool,
     expiry_at: DateTime<Utc>,
 }
 
+#[derive(Serialize)]
+struct CheckoutBlocksOverriddenAudit<'a> {
+    user_id: i64,
+    blocks: &'a [CheckoutBlock],
+}
+
 #[derive(Serialize)]
 struct RenewLoanAudit {
     new_expiry_at: DateTime<Utc>,
@@ -119,7 +147,7 @@ struct ReminderBatchManualAudit {
 
 /// Loan response with calculated dates
 #[serde_as]
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LoanResponse {
     #[serde_as(as = "DisplayFromStr")]
@@ -127,17 +155,62 @@ pub struct LoanResponse {
     pub id: i64,
     pub expiry_at: DateTime<Utc>,
     pub message: String,
+    /// Pending messages on the patron account, to show the librarian at checkout
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<UserMessage>,
 }
 
 /// Return response with loan details
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct ReturnResponse {
     pub status: String,
     pub loan: LoanDetails,
+    /// Bundle parts still on loan: the set came back incomplete
+    #[serde(rename = "missingBundleParts", default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_bundle_parts: Vec<BundlePart>,
+    /// Copy checked in at another place than its own: stays there or travels home
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<ReturnRouting>,
+    /// The loan is returned but the copy could not be routed: route it by hand
+    #[serde(rename = "routingFailed", default, skip_serializing_if = "std::ops::Not::not")]
+    pub routing_failed: bool,
+}
+
+/// Check-in place of a return (libraries with several places)
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ReturnQuery {
+    /// Place (`items.place` code) the copy is checked in at; a copy of another place is routed by
+    /// `circulation.floating_rules`
+    #[serde(default)]
+    pub place: Option<i16>,
+    /// Staff choice overriding the floating rule: stay at the place (`true`) or go home (`false`)
+    #[serde(default)]
+    pub floating: Option<bool>,
+}
+
+/// Audit the routing of a returned copy (or the staff answer to its prompt)
+pub(crate) fn log_routing(
+    state: &crate::AppState,
+    user_id: i64,
+    ip: Option<String>,
+    item_id: i64,
+    routing: &ReturnRouting,
+) {
+    state.services.audit.log(
+        audit::event::ITEM_ROUTED,
+        Some(user_id),
+        Some("item"),
+        Some(item_id),
+        ip,
+        Some(routing),
+        audit::AuditLogMeta::success(),
+    );
 }
 
 /// Query parameters for overdue loans list
-#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
 #[serde(rename_all = "camelCase")]
 pub struct OverdueLoansQuery {
     pub page: Option<i64>,
@@ -145,7 +218,7 @@ pub struct OverdueLoansQuery {
 }
 
 /// Query parameters for sending reminders
-#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
 #[serde(rename_all = "camelCase")]
 pub struct SendRemindersQuery {
     /// If true, no emails are sent; only shows what would be sent
@@ -181,14 +254,15 @@ pub async fn get_loan_settings(
     request_body = UpdateLoanSettingsRequest,
     responses(
         (status = 200, description = "Updated global loan rules", body = Vec<LoanSettings>),
-        (status = 403, description = "Insufficient permissions")
+        (status = 403, description = "Insufficient permissions"),
+        (status = 422, description = "Invalid fields", body = InvalidFieldsResponse)
     )
 )]
 pub async fn update_loan_settings(
     State(state): State<crate::AppState>,
     AuthenticatedUser(claims): AuthenticatedUser,
     ClientIp(ip): ClientIp,
-    Json(body): Json<UpdateLoanSettingsRequest>,
+    ValidatedJson(body): ValidatedJson<UpdateLoanSettingsRequest>,
 ) -> AppResult<Json<Vec<LoanSettings>>> {
     claims.require_write_settings()?;
     let rows = state.services.loans.update_global_loan_settings(body).await?;
@@ -205,6 +279,51 @@ pub async fn update_loan_settings(
     Ok(Json(rows))
 }
 
+/// Audit a checkout that went through checkout blocks
+pub(crate) fn audit_blocks_overridden(
+    state: &crate::AppState,
+    claims: &UserClaims,
+    ip: Option<String>,
+    user_id: i64,
+    outcome: &LoanCheckoutOutcome,
+) {
+    if outcome.overridden_blocks.is_empty() {
+        return;
+    }
+    state.services.audit.log(
+        audit::event::LOAN_BLOCKS_OVERRIDDEN,
+        Some(claims.user_id),
+        Some("loan"),
+        Some(outcome.loan_id),
+        ip,
+        Some(CheckoutBlocksOverriddenAudit { user_id, blocks: &outcome.overridden_blocks }),
+        audit::AuditLogMeta::success(),
+    );
+}
+
+/// Reasons a patron cannot borrow right now (empty when checkout is allowed)
+#[utoipa::path(
+    get,
+    path = "/users/{id}/checkout-blocks",
+    tag = "loans",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Checkout blocks, in evaluation order", body = Vec<CheckoutBlock>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_checkout_blocks(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(user_id): Path<i64>,
+) -> AppResult<Json<Vec<CheckoutBlock>>> {
+    claims.require_read_users()?;
+    Ok(Json(state.services.loans.checkout_blocks(user_id).await?))
+}
+
 /// Get loans for a specific user (paginated).
 #[utoipa::path(
     get,
@@ -216,13 +335,13 @@ pub async fn update_loan_settings(
         GetUserLoansQuery
     ),
     responses(
-        (status = 200, description = "User's loans", body = PaginatedResponse<LoanDetails>),
+        (status = 200, description = "User's loans", body = PaginatedLoans),
         (status = 404, description = "User not found")
     )
 )]
 pub async fn get_user_loans(
     State(state): State<crate::AppState>,
-    AuthenticatedUser(claims): AuthenticatedUser,
+    SelfServiceUser(claims): SelfServiceUser,
     Path(user_id): Path<i64>,
     Query(query): Query<GetUserLoansQuery>,
 ) -> AppResult<Json<PaginatedResponse<LoanDetails>>> {
@@ -237,7 +356,10 @@ pub async fn get_user_loans(
     let page = query.page.unwrap_or(1).max(1);
     let per_page = query.per_page.unwrap_or(20).clamp(1, 200);
 
-    let (items, total) = if query.archived.unwrap_or(false) {
+    let search = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    let (items, total) = if let Some(q) = search {
+        state.services.loans.search_user_loans(user_id, q, page, per_page).await?
+    } else if query.archived.unwrap_or(false) {
         state
             .services
             .loans
@@ -251,7 +373,7 @@ pub async fn get_user_loans(
 }
 
 /// Query for MARC export download (no pagination; full list in one file).
-#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportUserLoansMarcQuery {
     /// If true, export archived (returned) loans instead of active loans.
@@ -265,6 +387,8 @@ pub struct ExportUserLoansMarcQuery {
 }
 
 /// Download all loans for a user as one MARC file (`Content-Disposition: attachment`).
+///
+/// The file is stored as an artifact; `delivery=link` returns a signed download URL instead.
 #[utoipa::path(
     get,
     path = "/users/{id}/loans/export",
@@ -272,10 +396,12 @@ pub struct ExportUserLoansMarcQuery {
     security(("bearer_auth" = [])),
     params(
         ("id" = i64, Path, description = "User ID"),
-        ExportUserLoansMarcQuery
+        ExportUserLoansMarcQuery,
+        ExportDeliveryQuery
     ),
     responses(
         (status = 200, description = "File attachment (JSON array of marc-rs records, or ISO2709, or MARC-XML collection)"),
+        (status = 201, description = "Export stored (`delivery=link`)", body = ArtifactLink),
         (status = 400, description = "Too many loans to export"),
         (status = 403, description = "Access denied"),
         (status = 404, description = "User not found")
@@ -286,6 +412,7 @@ pub async fn export_user_loans_marc(
     AuthenticatedUser(claims): AuthenticatedUser,
     Path(user_id): Path<i64>,
     Query(query): Query<ExportUserLoansMarcQuery>,
+    Query(delivery): Query<ExportDeliveryQuery>,
 ) -> AppResult<Response> {
     claims.require_self_or_staff(user_id)?;
     let archived = query.archived.unwrap_or(false);
@@ -294,20 +421,22 @@ pub async fn export_user_loans_marc(
         .loans
         .export_user_loans_marc_file(user_id, archived, query.format, query.encoding)
         .await?;
-    let disposition = format!(r#"attachment; filename="{}""#, filename);
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(CONTENT_TYPE, content_type)
-        .header(CONTENT_DISPOSITION, disposition)
-        .body(Body::from(bytes))
-        .map_err(|e| AppError::Internal(format!("export response: {}", e)))
+    let artifact = state
+        .services
+        .artifacts
+        .store_bytes(ArtifactKind::LoansExport, filename, content_type, Some(claims.user_id), &bytes)
+        .await?;
+    super::artifacts::deliver(&state, artifact, delivery.delivery).await
 }
 
-#[derive(Debug, Deserialize, Default, ToSchema, IntoParams)]
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema, IntoParams)]
 #[serde(rename_all = "camelCase")]
 pub struct GetUserLoansQuery {
     /// If true, return past (returned) loans from the archive table
     pub archived: Option<bool>,
+    /// Search title, author and barcode across active and archived loans (`archived` is then ignored);
+    /// each result carries the matched field in `matched`
+    pub q: Option<String>,
     /// Page number (1-based, default 1)
     pub page: Option<i64>,
     /// Page size (default 20, max 200)
@@ -315,6 +444,12 @@ pub struct GetUserLoansQuery {
 }
 
 /// Create a new loan (borrow an item)
+///
+/// Checkout blocks (blocked account, expired membership, overdues or unpaid fines above the
+/// `circulation` thresholds, blocking messages) reject the checkout with a `checkout_blocked` body
+/// listing them all. `force=true` overrides them for callers with `circulation_override_rights`;
+/// the override is audited as `loan.blocks_overridden`. The patron's pending messages are returned
+/// in `messages` for the librarian to read.
 #[utoipa::path(
     post,
     path = "/loans",
@@ -325,24 +460,36 @@ pub struct GetUserLoansQuery {
         (status = 201, description = "Loan created", body = LoanResponse),
         (status = 400, description = "Invalid request"),
         (status = 404, description = "User or specimen not found"),
-        (status = 409, description = "Specimen already borrowed or max loans reached")
+        (status = 409, description = "Specimen already borrowed or max loans reached"),
+        (status = 422, description = "Checkout blocked (`checkout_blocked`) or invalid fields (`invalid_fields`)", body = CheckoutBlockedResponse)
     )
 )]
 pub async fn create_loan(
     State(state): State<crate::AppState>,
     AuthenticatedUser(claims): AuthenticatedUser,
     ClientIp(ip): ClientIp,
-    Json(request): Json<CreateLoanRequest>,
+    ValidatedJson(request): ValidatedJson<CreateLoanRequest>,
 ) -> AppResult<(StatusCode, Json<LoanResponse>)> {
     claims.require_write_loans()?;
     let loan = CreateLoan {
         user_id: request.user_id,
         item_id: request.item_id,
+        equipment_id: request.equipment_id,
         item_identification: request.item_identification.clone(),
         force: request.force.unwrap_or(false),
+        deposit_received: request.deposit_received.unwrap_or(false),
+        override_blocks: request.force.unwrap_or(false) && claims.can_override_checkout_blocks(),
     };
 
-    let (loan_id, expiry_at) = state.services.loans.create_loan(loan).await?;
+    let outcome = state.services.loans.create_loan(loan).await?;
+    let (loan_id, expiry_at) = (outcome.loan_id, outcome.expiry_at);
+    audit_blocks_overridden(&state, &claims, ip.clone(), request.user_id, &outcome);
+    let messages = state.services.user_messages.pending(request.user_id).await?;
+    sse::publish(
+        &state,
+        SsePayload::loan("loan.created", loan_id, Some(request.user_id), request.item_id),
+    );
+    sse::publish_counters(&state);
 
     state.services.audit.log(
         audit::event::LOAN_CREATED,
@@ -353,8 +500,10 @@ pub async fn create_loan(
         Some(LoanCreatedAudit {
             user_id: request.user_id,
             item_id: request.item_id,
+            equipment_id: request.equipment_id,
             item_identification: request.item_identification.clone(),
             force: request.force.unwrap_or(false),
+            deposit_received: request.deposit_received.unwrap_or(false),
             expiry_at,
         }),
      audit::AuditLogMeta::success());
@@ -365,17 +514,26 @@ pub async fn create_loan(
             id: loan_id,
             expiry_at,
             message: "Item borrowed successfully".to_string(),
+            messages,
         }),
     ))
 }
 
+/// SSE events of a return: the loan, and the hold it made ready
+pub(crate) fn publish_return(state: &crate::AppState, outcome: &LoanReturnOutcome) {
+    sse::publish(state, SsePayload::loan_returned(&outcome.details));
+    if let Some(hold) = &outcome.readied_hold {
+        sse::publish(state, SsePayload::hold("hold.ready", hold));
+    }
+}
+
 /// Return a borrowed item
 #[utoipa::path(
     post,
     path = "/loans/{id}/return",
     tag = "loans",
     security(("bearer_auth" = [])),
-    params(("id" = i32, Path, description = "Loan ID")),
+    params(("id" = i32, Path, description = "Loan ID"), ReturnQuery),
     responses(
         (status = 200, description = "Item returned", body = ReturnResponse),
         (status = 404, description = "Loan not found"),
@@ -387,9 +545,23 @@ pub async fn return_loan(
     AuthenticatedUser(claims): AuthenticatedUser,
     ClientIp(ip): ClientIp,
     Path(loan_id): Path<i64>,
+    Query(query): Query<ReturnQuery>,
 ) -> AppResult<Json<ReturnResponse>> {
     claims.require_write_loans()?;
-    let loan = state.services.loans.return_loan(loan_id).await?;
+    let outcome = state
+        .services
+        .loans
+        .return_loan(loan_id, query.place, query.floating)
+        .await?;
+    publish_return(&state, &outcome);
+    sse::publish_counters(&state);
+    let missing_bundle_parts = outcome.missing_bundle_parts;
+    let routing = outcome.routing;
+    let routing_failed = outcome.routing_failed;
+    let loan = outcome.details;
+    if let (Some(routing), Some(item_id)) = (&routing, loan.item_id) {
+        log_routing(&state, claims.user_id, ip.clone(), item_id, routing);
+    }
 
     state.services.audit.log(
         audit::event::LOAN_RETURNED,
@@ -400,7 +572,13 @@ pub async fn return_loan(
         Some(&loan),
      audit::AuditLogMeta::success());
 
-    Ok(Json(ReturnResponse { status: "returned".to_string(), loan }))
+    Ok(Json(ReturnResponse {
+        status: "returned".to_string(),
+        loan,
+        missing_bundle_parts,
+        routing,
+        routing_failed,
+    }))
 }
 
 /// Renew a loan
@@ -418,7 +596,7 @@ pub async fn return_loan(
 )]
 pub async fn renew_loan(
     State(state): State<crate::AppState>,
-    AuthenticatedUser(claims): AuthenticatedUser,
+    SelfServiceUser(claims): SelfServiceUser,
     ClientIp(ip): ClientIp,
     Path(loan_id): Path<i64>,
 ) -> AppResult<Json<LoanResponse>> {
@@ -435,6 +613,8 @@ pub async fn renew_loan(
 
 
     let (new_expiry_date, renew_count) = state.services.loans.renew_loan(loan_id).await?;
+    sse::publish(&state, SsePayload::loan("loan.renewed", loan_id, Some(user_id), loan.item_id));
+    sse::publish_counters(&state);
 
     state.services.audit.log(
         audit::event::LOAN_RENEWED,
@@ -452,16 +632,47 @@ pub async fn renew_loan(
         id: loan_id,
         expiry_at: new_expiry_date,
         message: format!("Loan renewed ({} renewals)", renew_count),
+        messages: vec![],
     }))
 }
 
+/// Whether a loan can be renewed now, with the reason codes when it cannot
+#[utoipa::path(
+    get,
+    path = "/loans/{id}/renewability",
+    tag = "loans",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Loan ID")),
+    responses(
+        (status = 200, description = "Renewal rules applied to the loan", body = LoanRenewability),
+        (status = 403, description = "Loan of another user without loan read rights", body = ErrorResponse),
+        (status = 404, description = "Loan not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_loan_renewability(
+    State(state): State<crate::AppState>,
+    SelfServiceUser(claims): SelfServiceUser,
+    Path(loan_id): Path<i64>,
+) -> AppResult<Json<LoanRenewability>> {
+    let loan = state.services.loans.get_loan(loan_id).await?;
+
+    if claims.rights.loans_rights.rank() < Rights::Read.rank() && loan.user_id != claims.user_id {
+        return Err(AppError::Authorization(
+            "Insufficient rights to read loans for another user".into(),
+        ));
+    }
+
+    let renewability = state.services.loans.renewability(loan_id).await?;
+    Ok(Json(renewability))
+}
+
 /// Return a borrowed item by item identification (barcode or call number)
 #[utoipa::path(
     post,
     path = "/loans/items/{item_id}/return",
     tag = "loans",
     security(("bearer_auth" = [])),
-    params(("item_id" = String, Path, description = "Item barcode or call number")),
+    params(("item_id" = String, Path, description = "Item barcode or call number"), ReturnQuery),
     responses(
         (status = 200, description = "Item returned", body = ReturnResponse),
         (status = 404, description = "Item or active loan not found"),
@@ -473,10 +684,24 @@ pub async fn return_loan_by_item(
     AuthenticatedUser(claims): AuthenticatedUser,
     ClientIp(ip): ClientIp,
     Path(item_id): Path<String>,
+    Query(query): Query<ReturnQuery>,
 ) -> AppResult<Json<ReturnResponse>> {
     claims.require_write_loans()?;
-    let loan = state.services.loans.return_loan_by_item(&item_id).await?;
+    let outcome = state
+        .services
+        .loans
+        .return_loan_by_item(&item_id, query.place, query.floating)
+        .await?;
+    publish_return(&state, &outcome);
+    sse::publish_counters(&state);
+    let missing_bundle_parts = outcome.missing_bundle_parts;
+    let routing = outcome.routing;
+    let routing_failed = outcome.routing_failed;
+    let loan = outcome.details;
     let loan_id = loan.id;
+    if let (Some(routing), Some(copy_id)) = (&routing, loan.item_id) {
+        log_routing(&state, claims.user_id, ip.clone(), copy_id, routing);
+    }
 
     state.services.audit.log(
         audit::event::LOAN_RETURNED,
@@ -487,7 +712,13 @@ pub async fn return_loan_by_item(
         Some((item_id.as_str(), &loan)),
      audit::AuditLogMeta::success());
 
-    Ok(Json(ReturnResponse { status: "returned".to_string(), loan }))
+    Ok(Json(ReturnResponse {
+        status: "returned".to_string(),
+        loan,
+        missing_bundle_parts,
+        routing,
+        routing_failed,
+    }))
 }
 
 /// Renew a loan by item identification (barcode or call number)
@@ -515,6 +746,8 @@ pub async fn renew_loan_by_item(
         .loans
         .renew_loan_by_item(&item_id)
         .await?;
+    sse::publish(&state, SsePayload::loan("loan.renewed", loan_id, None, None));
+    sse::publish_counters(&state);
 
     state.services.audit.log(
         audit::event::LOAN_RENEWED,
@@ -533,6 +766,7 @@ pub async fn renew_loan_by_item(
         id: loan_id,
         expiry_at: new_expiry_date,
         message: format!("Loan renewed ({} renewals)", renew_count),
+        messages: vec![],
     }))
 }
 
@@ -614,3 +848,51 @@ pub async fn send_overdue_reminders(
     Ok(Json(report))
 }
 
+/// Trigger the due-soon digest (admin only): one email per patron listing the loans due in
+/// one of the `reminders.due_soon_days` lead times
+#[utoipa::path(
+    post,
+    path = "/loans/send-due-soon-reminders",
+    tag = "loans",
+    security(("bearer_auth" = [])),
+    params(SendRemindersQuery),
+    responses(
+        (status = 200, description = "Reminder report", body = ReminderReport),
+        (status = 403, description = "Insufficient permissions")
+    )
+)]
+pub async fn send_due_soon_reminders(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Query(query): Query<SendRemindersQuery>,
+) -> AppResult<Json<ReminderReport>> {
+    claims.require_admin()?;
+
+    let dry_run = query.dry_run.unwrap_or(false);
+
+    let report = state
+        .services
+        .reminders
+        .send_due_soon_reminders(dry_run, Some(claims.user_id), ip.clone())
+        .await?;
+
+    if !dry_run {
+        state.services.audit.log(
+            audit::event::SYSTEM_DUE_SOON_BATCH_COMPLETED,
+            Some(claims.user_id),
+            None,
+            None,
+            ip,
+            Some(ReminderBatchManualAudit {
+                triggered_by: "manual",
+                emails_sent: report.emails_sent,
+                loans_reminded: report.loans_reminded,
+                errors: report.errors.len(),
+            }),
+         audit::AuditLogMeta::success());
+    }
+
+    Ok(Json(report))
+}
+
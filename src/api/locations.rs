@@ -0,0 +1,235 @@
+//! Shelving map API endpoints (`/settings/locations`)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::location::{
+        ApplyLocationMappings, CreateLocation, LocationMappingReport, LocationMappingRow, LocationNode,
+        LocationQuery, ShelvingLocation, UpdateLocation,
+    },
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Build the `/settings/locations*` routes.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new()
+        .route("/settings/locations", get(list_locations).post(create_location))
+        .route("/settings/locations/tree", get(get_location_tree))
+        .route("/settings/locations/mapping", get(get_location_mapping).post(apply_location_mapping))
+        .route(
+            "/settings/locations/:id",
+            get(get_location).put(update_location).delete(delete_location),
+        )
+}
+
+/// List shelving locations in map order (each site followed by its rooms and shelf ranges)
+#[utoipa::path(
+    get,
+    path = "/settings/locations",
+    tag = "locations",
+    security(("bearer_auth" = [])),
+    params(LocationQuery),
+    responses(
+        (status = 200, description = "Shelving locations", body = Vec<ShelvingLocation>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_locations(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<LocationQuery>,
+) -> AppResult<Json<Vec<ShelvingLocation>>> {
+    claims.require_read_items()?;
+    let locations = state.services.locations.list(query.place).await?;
+    Ok(Json(locations))
+}
+
+/// Shelving map as a tree: sites with their rooms, rooms with their shelf ranges
+#[utoipa::path(
+    get,
+    path = "/settings/locations/tree",
+    tag = "locations",
+    security(("bearer_auth" = [])),
+    params(LocationQuery),
+    responses(
+        (status = 200, description = "Sites with their child locations", body = Vec<LocationNode>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn get_location_tree(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<LocationQuery>,
+) -> AppResult<Json<Vec<LocationNode>>> {
+    claims.require_read_items()?;
+    let tree = state.services.locations.tree(query.place).await?;
+    Ok(Json(tree))
+}
+
+/// Get a shelving location by id
+#[utoipa::path(
+    get,
+    path = "/settings/locations/{id}",
+    tag = "locations",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Location ID")),
+    responses(
+        (status = 200, description = "Shelving location", body = ShelvingLocation),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_location(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<ShelvingLocation>> {
+    claims.require_read_items()?;
+    let location = state.services.locations.get(id).await?;
+    Ok(Json(location))
+}
+
+/// Create a site (one per place), a room under a site or a shelf range under a room
+#[utoipa::path(
+    post,
+    path = "/settings/locations",
+    tag = "locations",
+    security(("bearer_auth" = [])),
+    request_body = CreateLocation,
+    responses(
+        (status = 201, description = "Location created", body = ShelvingLocation),
+        (status = 400, description = "Invalid input or level", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Parent not found", body = ErrorResponse),
+        (status = 409, description = "Place already has a site", body = ErrorResponse),
+    )
+)]
+pub async fn create_location(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateLocation>,
+) -> AppResult<(StatusCode, Json<ShelvingLocation>)> {
+    claims.require_write_settings()?;
+    let location = state.services.locations.create(&data).await?;
+    state.services.audit.log(audit::event::LOCATION_CREATED, Some(claims.user_id), Some("location"), Some(location.id), ip, Some(&location), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(location)))
+}
+
+/// Rename, recode or reorder a shelving location
+#[utoipa::path(
+    put,
+    path = "/settings/locations/{id}",
+    tag = "locations",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Location ID")),
+    request_body = UpdateLocation,
+    responses(
+        (status = 200, description = "Location updated", body = ShelvingLocation),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_location(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdateLocation>,
+) -> AppResult<Json<ShelvingLocation>> {
+    claims.require_write_settings()?;
+    let location = state.services.locations.update(id, &data).await?;
+    state.services.audit.log(audit::event::LOCATION_UPDATED, Some(claims.user_id), Some("location"), Some(id), ip, Some((&data, &location)), audit::AuditLogMeta::success());
+    Ok(Json(location))
+}
+
+/// Delete a shelving location without child locations nor copies
+#[utoipa::path(
+    delete,
+    path = "/settings/locations/{id}",
+    tag = "locations",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Location ID")),
+    responses(
+        (status = 204, description = "Location deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Location still holds locations or copies", body = ErrorResponse),
+    )
+)]
+pub async fn delete_location(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_settings()?;
+    state.services.locations.delete(id).await?;
+    state.services.audit.log(audit::event::LOCATION_DELETED, Some(claims.user_id), Some("location"), Some(id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Mapping assistant: copies without a location, by place and call number prefix
+///
+/// Each group comes with a suggested location (same place, `code` equal to the prefix).
+#[utoipa::path(
+    get,
+    path = "/settings/locations/mapping",
+    tag = "locations",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Groups of copies to locate", body = Vec<LocationMappingRow>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn get_location_mapping(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<LocationMappingRow>>> {
+    claims.require_write_settings()?;
+    let rows = state.services.locations.mapping_candidates().await?;
+    Ok(Json(rows))
+}
+
+/// Give each group of copies its location (and the place of the location's site)
+#[utoipa::path(
+    post,
+    path = "/settings/locations/mapping",
+    tag = "locations",
+    security(("bearer_auth" = [])),
+    request_body = ApplyLocationMappings,
+    responses(
+        (status = 200, description = "Copies located", body = LocationMappingReport),
+        (status = 400, description = "Location not in the place of its group", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Location not found", body = ErrorResponse),
+    )
+)]
+pub async fn apply_location_mapping(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<ApplyLocationMappings>,
+) -> AppResult<Json<LocationMappingReport>> {
+    claims.require_write_settings()?;
+    let report = state.services.locations.apply_mappings(&data).await?;
+    state.services.audit.log(audit::event::LOCATIONS_MAPPED, Some(claims.user_id), Some("location"), None, ip, Some((&data, &report)), audit::AuditLogMeta::success());
+    Ok(Json(report))
+}
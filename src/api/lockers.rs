@@ -0,0 +1,139 @@
+//! Hold pickup locker endpoints (`/holds/:id/locker`, vendor webhook `/lockers/webhook`)
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+
+use crate::{
+    error::{AppError, AppResult},
+    models::hold::{AssignHoldLocker, Hold, HoldStatus, LockerEventOutcome, LockerWebhookEvent},
+    services::audit,
+};
+
+use super::{
+    sse::{self, SsePayload},
+    AuthenticatedUser, ClientIp,
+};
+
+/// Header carrying `sha256=<hex HMAC-SHA256 of the body>` on webhook calls
+const SIGNATURE_HEADER: &str = "x-locker-signature";
+
+/// Build the staff locker routes.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::post;
+    axum::Router::new().route("/holds/:id/locker", post(assign_hold_locker))
+}
+
+/// Vendor webhook route (no bearer token; mounted with the public rate limiter).
+pub fn router_webhook() -> axum::Router<crate::AppState> {
+    use axum::routing::post;
+    axum::Router::new().route("/lockers/webhook", post(locker_webhook))
+}
+
+/// Place a ready hold's copy in a pickup locker
+///
+/// Reserves a compartment through the vendor API; the hold becomes `inLocker` until the patron
+/// opens the compartment or `lockers.pickup_days` lapse. The patron is emailed the pickup code.
+#[utoipa::path(
+    post,
+    path = "/holds/{id}/locker",
+    tag = "holds",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Hold ID")),
+    request_body = AssignHoldLocker,
+    responses(
+        (status = 200, description = "Hold placed in a locker", body = Hold),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Hold not found", body = ErrorResponse),
+        (status = 409, description = "No free compartment, or the hold changed meanwhile", body = ErrorResponse),
+        (status = 422, description = "Lockers disabled, or the hold is not ready", body = ErrorResponse)
+    )
+)]
+pub async fn assign_hold_locker(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(request): Json<AssignHoldLocker>,
+) -> AppResult<Json<Hold>> {
+    claims.require_write_holds()?;
+    let hold = state.services.lockers.assign(id, &request).await?;
+    sse::publish(&state, SsePayload::hold("hold.inLocker", &hold));
+
+    state.services.audit.log(
+        audit::event::HOLD_LOCKER_ASSIGNED,
+        Some(claims.user_id),
+        Some("hold"),
+        Some(id),
+        ip,
+        Some(serde_json::json!({
+            "lockerId": hold.locker_id,
+            "compartment": hold.locker_compartment,
+        })),
+        audit::AuditLogMeta::success(),
+    );
+
+    Ok(Json(hold))
+}
+
+/// Locker vendor webhook (compartment opened, pickup window lapsed)
+///
+/// Authenticated by `X-Locker-Signature: sha256=<hex HMAC-SHA256 of the raw body>` keyed with
+/// `lockers.webhook_secret`. Repeated deliveries of an applied event are acknowledged unchanged.
+#[utoipa::path(
+    post,
+    path = "/lockers/webhook",
+    tag = "holds",
+    request_body = LockerWebhookEvent,
+    params(("X-Locker-Signature" = String, Header, description = "sha256=<hex HMAC-SHA256 of the body>")),
+    responses(
+        (status = 200, description = "Event applied", body = LockerEventOutcome),
+        (status = 400, description = "Invalid body or hold reference", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid signature", body = ErrorResponse),
+        (status = 404, description = "Hold not found", body = ErrorResponse),
+        (status = 409, description = "Hold is not in a pickup locker", body = ErrorResponse),
+        (status = 422, description = "Lockers disabled", body = ErrorResponse)
+    )
+)]
+pub async fn locker_webhook(
+    State(state): State<crate::AppState>,
+    ClientIp(ip): ClientIp,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<Json<LockerEventOutcome>> {
+    let signature = headers.get(SIGNATURE_HEADER).and_then(|v| v.to_str().ok());
+    state.services.lockers.verify_webhook(&body, signature)?;
+    let event: LockerWebhookEvent = serde_json::from_slice(&body)
+        .map_err(|e| AppError::BadRequest(format!("Invalid locker event: {}", e)))?;
+
+    let outcome = state.services.lockers.handle_event(&event).await?;
+    let hold = &outcome.hold;
+    let (sse_event, audit_event) = match hold.status {
+        HoldStatus::PickedUp => ("hold.pickedUp", audit::event::HOLD_PICKED_UP),
+        _ => ("hold.expired", audit::event::HOLD_LOCKER_EXPIRED),
+    };
+    sse::publish(&state, SsePayload::hold(sse_event, hold));
+    if outcome.loan_id.is_some() {
+        sse::publish_counters(&state);
+    }
+
+    state.services.audit.log(
+        audit_event,
+        None,
+        Some("hold"),
+        Some(hold.id),
+        ip,
+        Some(serde_json::json!({
+            "event": event,
+            "loanId": outcome.loan_id.map(|id| id.to_string()),
+            "loanError": outcome.loan_error,
+        })),
+        audit::AuditLogMeta::success(),
+    );
+
+    Ok(Json(outcome))
+}
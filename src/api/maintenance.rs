@@ -823,7 +823,7 @@ async fn run_z3950_refresh_action(
         };
 
         let remote = match Z3950Service::query(&mut client, &server, &search_query).await {
-            Ok(mut recs) => recs.pop(),
+            Ok(mut presented) => presented.records.pop(),
             Err(e) => {
                 failed += 1;
                 let prog = make_progress(CatalogZ3950RefreshProgress {
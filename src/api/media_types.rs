@@ -0,0 +1,162 @@
+//! Media type taxonomy API endpoints (`/settings/media-types`)
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::media_type::{CreateMediaType, MediaTypeDefinition, UpdateMediaType},
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Build the `/settings/media-types*` routes.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new()
+        .route("/settings/media-types", get(list_media_types).post(create_media_type))
+        .route(
+            "/settings/media-types/:code",
+            get(get_media_type).put(update_media_type).delete(delete_media_type),
+        )
+}
+
+/// List media types (values allowed in a biblio's `mediaType`)
+#[utoipa::path(
+    get,
+    path = "/settings/media-types",
+    tag = "media_types",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Media types", body = Vec<MediaTypeDefinition>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_media_types(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<MediaTypeDefinition>>> {
+    claims.require_read_items()?;
+    let media_types = state.services.media_types.list().await?;
+    Ok(Json(media_types))
+}
+
+/// Get a media type by code
+#[utoipa::path(
+    get,
+    path = "/settings/media-types/{code}",
+    tag = "media_types",
+    security(("bearer_auth" = [])),
+    params(("code" = String, Path, description = "Media type code")),
+    responses(
+        (status = 200, description = "Media type", body = MediaTypeDefinition),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_media_type(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(code): Path<String>,
+) -> AppResult<Json<MediaTypeDefinition>> {
+    claims.require_read_items()?;
+    let media_type = state.services.media_types.get(&code).await?;
+    Ok(Json(media_type))
+}
+
+/// Create a media type
+#[utoipa::path(
+    post,
+    path = "/settings/media-types",
+    tag = "media_types",
+    security(("bearer_auth" = [])),
+    request_body = CreateMediaType,
+    responses(
+        (status = 201, description = "Media type created", body = MediaTypeDefinition),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 409, description = "Code already exists", body = ErrorResponse),
+    )
+)]
+pub async fn create_media_type(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateMediaType>,
+) -> AppResult<(StatusCode, Json<MediaTypeDefinition>)> {
+    claims.require_write_settings()?;
+    let media_type = state.services.media_types.create(&data).await?;
+    state.services.audit.log(audit::event::MEDIA_TYPE_CREATED, Some(claims.user_id), Some("media_type"), None, ip, Some(&media_type), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(media_type)))
+}
+
+/// Update a media type (label, icon, default loan rules, MARC leader codes)
+#[utoipa::path(
+    put,
+    path = "/settings/media-types/{code}",
+    tag = "media_types",
+    security(("bearer_auth" = [])),
+    params(("code" = String, Path, description = "Media type code")),
+    request_body = UpdateMediaType,
+    responses(
+        (status = 200, description = "Media type updated", body = MediaTypeDefinition),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_media_type(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(code): Path<String>,
+    Json(data): Json<UpdateMediaType>,
+) -> AppResult<Json<MediaTypeDefinition>> {
+    claims.require_write_settings()?;
+    let media_type = state.services.media_types.update(&code, &data).await?;
+    state.services.audit.log(audit::event::MEDIA_TYPE_UPDATED, Some(claims.user_id), Some("media_type"), None, ip, Some((&data, &media_type)), audit::AuditLogMeta::success());
+    Ok(Json(media_type))
+}
+
+/// Delete a media type (built-in codes and codes used by biblios cannot be deleted)
+#[utoipa::path(
+    delete,
+    path = "/settings/media-types/{code}",
+    tag = "media_types",
+    security(("bearer_auth" = [])),
+    params(("code" = String, Path, description = "Media type code")),
+    responses(
+        (status = 204, description = "Media type deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Built-in or in use", body = ErrorResponse),
+    )
+)]
+pub async fn delete_media_type(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(code): Path<String>,
+) -> AppResult<StatusCode> {
+    claims.require_write_settings()?;
+    state.services.media_types.delete(&code).await?;
+    state.services.audit.log(
+        audit::event::MEDIA_TYPE_DELETED,
+        Some(claims.user_id),
+        Some("media_type"),
+        None,
+        ip,
+        Some(serde_json::json!({ "code": code })),
+        audit::AuditLogMeta::success(),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -2,35 +2,75 @@
 
 pub mod account_types;
 pub mod admin_config;
+pub mod api_keys;
+pub mod artifacts;
 pub mod audit;
 pub mod auth;
+pub mod authors;
+pub mod backups;
 pub mod batch;
 pub mod biblios;
+pub mod bundles;
+pub mod campaigns;
 pub mod collections;
 pub mod covers;
+pub mod dashboards;
+pub mod demo;
+pub mod deposits;
+pub mod donations;
+pub mod email_health;
+pub mod eod;
 pub mod email_templates;
 pub mod equipment;
 pub mod events;
+pub mod feeds;
 pub mod fines;
 pub mod first_setup;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod harvest;
+pub mod headings;
 pub mod health;
 pub mod inventory;
+pub mod item_states;
+pub mod item_templates;
+pub mod kiosks;
 pub mod items;
+pub mod labels;
 pub mod library_info;
+pub mod loan_batches;
 pub mod loans;
+pub mod locations;
+pub mod lockers;
 pub mod maintenance;
+pub mod media_types;
+pub mod notifications;
 pub mod openapi;
 pub mod opac;
+pub mod payments;
+pub mod postman;
 pub mod public_types;
+pub mod purchase_suggestions;
+pub mod reading_programs;
+pub mod reengagement;
+pub mod relators;
+pub mod router;
 pub mod holds;
 pub mod schedules;
 pub mod series;
+pub mod sitemap;
+pub mod snapshots;
 pub mod sources;
 pub mod sse;
+pub mod staffing;
 pub mod stats;
 pub mod tasks;
+pub mod user_messages;
 pub mod users;
+pub mod vendors;
+pub mod versioning;
 pub mod visitor_counts;
+pub mod warehouse;
 pub mod z3950;
 
 use std::net::SocketAddr;
@@ -38,12 +78,20 @@ use std::net::SocketAddr;
 use axum::{
     async_trait,
     extract::{ConnectInfo, FromRequest, FromRequestParts, Request},
-    http::{header::AUTHORIZATION, request::Parts},
+    http::{header::{ACCEPT_LANGUAGE, AUTHORIZATION}, request::Parts},
 };
 use serde::de::DeserializeOwned;
 use validator::Validate;
 
-use crate::{error::AppError, models::user::{UserClaims, SCOPE_CHANGE_PASSWORD}, AppState};
+use crate::{
+    error::AppError,
+    models::{
+        api_key::API_KEY_HEADER,
+        kiosk::{Kiosk, KIOSK_TOKEN_HEADER},
+        user::{UserClaims, SCOPE_CHANGE_PASSWORD},
+    },
+    AppState,
+};
 
 /// Resolved client IP for audit: proxy headers first, then `ConnectInfo` peer address.
 pub struct ClientIp(pub Option<String>);
@@ -72,7 +120,10 @@ where
 // ============================================================================
 
 /// Axum extractor that parses a JSON body **and** runs `validator::Validate`
-/// on the resulting value, returning `400 Validation` on failure.
+/// on the resulting value.
+///
+/// Malformed JSON is a `400 validation_error`. Fields of the wrong type, missing fields and failed
+/// checks are a `422 invalid_fields` listing the errors per field path.
 pub struct ValidatedJson<T>(pub T);
 
 #[async_trait]
@@ -88,17 +139,39 @@ where
             .await
             .map_err(|e| AppError::BadRequest(e.to_string()))?;
 
-        let value: T = serde_json::from_slice(&bytes)
-            .map_err(|e| AppError::Validation(format!("Invalid JSON body: {e}")))?;
-
-        value
-            .validate()
-            .map_err(|e| AppError::Validation(e.to_string()))?;
+        let value: T = parse_json_body(&bytes)?;
+        value.validate()?;
 
         Ok(Self(value))
     }
 }
 
+/// Decode a JSON body; data errors (wrong type, missing field) are reported on their field path
+fn parse_json_body<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, AppError> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    serde_path_to_error::deserialize(&mut deserializer).map_err(|e| {
+        let inner = e.inner();
+        if inner.classify() != serde_json::error::Category::Data {
+            return AppError::Validation(format!("Invalid JSON body: {inner}"));
+        }
+        let path = e.path().to_string();
+        let parent = if path == "." { String::new() } else { path };
+        let message = inner.to_string();
+        // `missing field `x` at line 1 column 2`: the path stops at the enclosing object
+        let missing = message
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.split_once('`'))
+            .map(|(field, _)| field.to_string());
+        let message = message.split(" at line ").next().unwrap_or_default().to_string();
+        match missing {
+            Some(field) if parent.is_empty() => AppError::invalid_field(field, "required", message),
+            Some(field) => AppError::invalid_field(format!("{parent}.{field}"), "required", message),
+            None if parent.is_empty() => AppError::invalid_field("body", "type", message),
+            None => AppError::invalid_field(parent, "type", message),
+        }
+    })
+}
+
 // ============================================================================
 // RBAC typed extractors
 // ============================================================================
@@ -160,13 +233,40 @@ fn extract_claims(parts: &Parts, secret: &str) -> Result<UserClaims, AppError> {
 /// Extractor for authenticated user from JWT token.
 ///
 /// Rejects tokens with scope `change_password_only` — those are only accepted
-/// by the dedicated `POST /auth/change-password` endpoint via [`PasswordChangeUser`].
+/// by the dedicated `POST /auth/change-password` endpoint via [`PasswordChangeUser`] —
+/// and `self_service` tokens (barcode + PIN login), only accepted via [`SelfServiceUser`].
 pub struct AuthenticatedUser(pub UserClaims);
 
 #[async_trait]
 impl FromRequestParts<AppState> for AuthenticatedUser {
     type Rejection = AppError;
 
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let SelfServiceUser(claims) = SelfServiceUser::from_request_parts(parts, state).await?;
+
+        if claims.is_self_service_scope() {
+            return Err(AppError::Authorization(
+                "Barcode login only gives access to self-service endpoints".to_string(),
+            ));
+        }
+
+        Ok(AuthenticatedUser(claims))
+    }
+}
+
+/// Extractor for patron self-service endpoints (own profile, loans, renewals, holds).
+///
+/// Accepts full tokens and `self_service` tokens from `POST /auth/login-barcode`;
+/// rejects password-change tokens like [`AuthenticatedUser`].
+///
+/// Every JWT-authenticated extractor goes through this one, so requests made with an
+/// impersonation token are audited here (`user.impersonated_request`, both identities).
+pub struct SelfServiceUser(pub UserClaims);
+
+#[async_trait]
+impl FromRequestParts<AppState> for SelfServiceUser {
+    type Rejection = AppError;
+
     async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
         let claims = extract_claims(parts, &state.config.users.jwt_secret)?;
 
@@ -176,7 +276,12 @@ impl FromRequestParts<AppState> for AuthenticatedUser {
             ));
         }
 
-        Ok(AuthenticatedUser(claims))
+        if let Some(admin_id) = claims.impersonated_by {
+            let Ok(ClientIp(ip)) = ClientIp::from_request_parts(parts, state).await;
+            state.services.audit.log(crate::services::audit::event::USER_IMPERSONATED_REQUEST, Some(admin_id), Some("user"), Some(claims.user_id), ip, Some(serde_json::json!({ "impersonatorId": admin_id, "impersonatedUserId": claims.user_id, "method": parts.method.as_str(), "path": parts.uri.path() })), crate::services::audit::AuditLogMeta::success());
+        }
+
+        Ok(SelfServiceUser(claims))
     }
 }
 
@@ -202,3 +307,150 @@ impl FromRequestParts<AppState> for PasswordChangeUser {
     }
 }
 
+
+// ============================================================================
+// Kiosk extractors
+// ============================================================================
+
+/// Extractor for catalog terminals: requires a valid `X-Kiosk-Token` used from an allowed address.
+///
+/// Kiosk tokens are opaque (not JWTs), so every other extractor rejects them.
+pub struct KioskClient(pub Kiosk);
+
+#[async_trait]
+impl FromRequestParts<AppState> for KioskClient {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        match MaybeKiosk::from_request_parts(parts, state).await? {
+            MaybeKiosk(Some(kiosk)) => Ok(KioskClient(kiosk)),
+            MaybeKiosk(None) => Err(AppError::Authentication("Missing kiosk token".to_string())),
+        }
+    }
+}
+
+/// Like [`KioskClient`] but optional: `None` without the header, an error when the token is invalid.
+pub struct MaybeKiosk(pub Option<Kiosk>);
+
+#[async_trait]
+impl FromRequestParts<AppState> for MaybeKiosk {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let Some(header) = parts.headers.get(KIOSK_TOKEN_HEADER) else {
+            return Ok(MaybeKiosk(None));
+        };
+        let token = header
+            .to_str()
+            .map_err(|_| AppError::Authentication("Invalid kiosk token".to_string()))?
+            .trim()
+            .to_string();
+        // Not `ClientIp`: forwarded headers only count when a trusted proxy sent them
+        let peer = parts.extensions.get::<ConnectInfo<SocketAddr>>().map(|c| c.0);
+        let ip =
+            crate::services::kiosks::kiosk_client_ip(&parts.headers, peer, &state.config.server.trusted_proxies);
+        let kiosk = state.services.kiosks.authenticate(&token, ip).await?;
+        Ok(MaybeKiosk(Some(kiosk)))
+    }
+}
+
+
+// ============================================================================
+// API key extractor
+// ============================================================================
+
+/// `X-Api-Key` value sent by a partner application, not yet checked.
+///
+/// The handler resolves it with [`crate::services::api_keys::ApiKeysService::authenticate`] for its
+/// own scope, so the request is counted against the right endpoint.
+pub struct ApiKeyToken(pub String);
+
+#[async_trait]
+impl FromRequestParts<AppState> for ApiKeyToken {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &AppState) -> Result<Self, Self::Rejection> {
+        let header = parts
+            .headers
+            .get(API_KEY_HEADER)
+            .ok_or_else(|| AppError::Authentication("Missing API key".to_string()))?;
+        let token = header
+            .to_str()
+            .map_err(|_| AppError::Authentication("Invalid API key".to_string()))?
+            .trim();
+        if token.is_empty() {
+            return Err(AppError::Authentication("Missing API key".to_string()));
+        }
+        Ok(ApiKeyToken(token.to_string()))
+    }
+}
+
+
+// ============================================================================
+// Localization
+// ============================================================================
+
+/// Language (ISO 639-1) to localize labels in: the authenticated user's preferred language,
+/// then `Accept-Language`, falling back to [`crate::models::label::DEFAULT_LANG`].
+///
+/// Never rejects a request for a missing or invalid token; anonymous callers only get the header.
+pub struct Locale(pub String);
+
+#[async_trait]
+impl FromRequestParts<AppState> for Locale {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let user_id = extract_claims(parts, &state.config.users.jwt_secret)
+            .ok()
+            .filter(|claims| !claims.is_password_change_scope())
+            .map(|claims| claims.user_id);
+        let accept_language = parts.headers.get(ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok());
+        let lang = state.services.labels.resolve_lang(user_id, accept_language).await?;
+        Ok(Locale(lang))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FieldErrors;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    #[allow(dead_code)]
+    struct Row {
+        duration_days: i16,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    #[allow(dead_code)]
+    struct Body {
+        name: String,
+        rows: Vec<Row>,
+    }
+
+    fn fields(result: Result<Body, AppError>) -> FieldErrors {
+        match result {
+            Err(AppError::InvalidFields(fields)) => fields,
+            other => panic!("expected invalid fields, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn json_data_errors_name_the_field() {
+        let missing = fields(parse_json_body(br#"{"rows":[]}"#));
+        assert_eq!(missing["name"][0].code, "required");
+        assert_eq!(missing["name"][0].message, "missing field `name`");
+
+        let nested = fields(parse_json_body(br#"{"name":"a","rows":[{"durationDays":1},{}]}"#));
+        assert!(nested.contains_key("rows[1].durationDays"));
+
+        let wrong_type = fields(parse_json_body(br#"{"name":"a","rows":[{"durationDays":"x"}]}"#));
+        assert_eq!(wrong_type["rows[0].durationDays"][0].code, "type");
+
+        assert!(matches!(parse_json_body::<Body>(b"{\"name\":"), Err(AppError::Validation(_))));
+    }
+}
@@ -0,0 +1,105 @@
+//! In-app notifications endpoints (`/notifications`): the caller's inbox
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::notification::{
+        Notification, NotificationQuery, NotificationsMarkedRead, NotificationsPage, UnreadNotificationCount,
+    },
+};
+
+use super::AuthenticatedUser;
+
+/// Build the notification routes for this domain.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post};
+    axum::Router::new()
+        .route("/notifications", get(list_notifications))
+        .route("/notifications/unread-count", get(get_unread_count))
+        .route("/notifications/read-all", post(mark_all_notifications_read))
+        .route("/notifications/:id/read", post(mark_notification_read))
+}
+
+/// The caller's notifications, newest first.
+///
+/// New notifications are also pushed on the SSE stream (`notification.created`).
+#[utoipa::path(
+    get,
+    path = "/notifications",
+    tag = "notifications",
+    security(("bearer_auth" = [])),
+    params(NotificationQuery),
+    responses(
+        (status = 200, description = "Notifications", body = NotificationsPage),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+    )
+)]
+pub async fn list_notifications(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<NotificationQuery>,
+) -> AppResult<Json<NotificationsPage>> {
+    Ok(Json(state.services.notifications.list(claims.user_id, &query).await?))
+}
+
+/// Number of unread notifications of the caller (inbox badge)
+#[utoipa::path(
+    get,
+    path = "/notifications/unread-count",
+    tag = "notifications",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Unread count", body = UnreadNotificationCount),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+    )
+)]
+pub async fn get_unread_count(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<UnreadNotificationCount>> {
+    let unread = state.services.notifications.unread_count(claims.user_id).await?;
+    Ok(Json(UnreadNotificationCount { unread }))
+}
+
+/// Mark one of the caller's notifications as read
+#[utoipa::path(
+    post,
+    path = "/notifications/{id}/read",
+    tag = "notifications",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Notification ID")),
+    responses(
+        (status = 200, description = "Notification marked as read", body = Notification),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "Not one of the caller's notifications", body = ErrorResponse),
+    )
+)]
+pub async fn mark_notification_read(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Notification>> {
+    Ok(Json(state.services.notifications.mark_read(claims.user_id, id).await?))
+}
+
+/// Mark every unread notification of the caller as read
+#[utoipa::path(
+    post,
+    path = "/notifications/read-all",
+    tag = "notifications",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Notifications marked as read", body = NotificationsMarkedRead),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+    )
+)]
+pub async fn mark_all_notifications_read(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<NotificationsMarkedRead>> {
+    Ok(Json(state.services.notifications.mark_all_read(claims.user_id).await?))
+}
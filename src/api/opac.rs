@@ -5,21 +5,53 @@
 
 use axum::{
     extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
     Json,
 };
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
-    api::biblios::PaginatedResponse,
-    error::AppResult,
-    models::biblio::{BiblioQuery, BiblioShort},
+    api::{biblios::BiblioSearchPage, versioning::ApiVersion, ApiKeyToken, ClientIp},
+    error::{AppError, AppResult},
+    models::{
+        api_key::SCOPE_OPAC_AVAILABILITY,
+        biblio::{AvailabilityBatch, Biblio, BiblioAvailability, BiblioQuery, Isbn, SuggestQuery, Suggestion},
+        citation::{Citation, CitationQuery, CitationStyle},
+    },
+    services::citations,
+    utils::escape_html,
 };
 
+/// Cache lifetime for widget responses: short enough for availability to stay meaningful,
+/// long enough for the city website and intermediate proxies to absorb traffic.
+const WIDGET_CACHE_CONTROL: &str = "public, max-age=300, stale-while-revalidate=600";
+
+/// Most ISBNs accepted by one `GET /opac/availability` call
+const MAX_BATCH_ISBNS: usize = 100;
+
 pub fn router() -> axum::Router<crate::AppState> {
     use axum::routing::get;
     axum::Router::new()
         .route("/opac/biblios", get(opac_search))
         .route("/opac/biblios/:id", get(opac_get_biblio))
         .route("/opac/biblios/:id/availability", get(opac_availability))
+        .route("/opac/biblios/:id/citation", get(opac_citation))
+        .route("/items/suggest", get(suggest_titles))
+        .route("/authors/suggest", get(suggest_authors))
+}
+
+/// Embeddable availability widget — mounted separately so it gets its own rate limiter.
+pub fn router_widget() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new().route("/opac/widget/:isbn", get(opac_widget))
+}
+
+/// Bulk availability for partner apps — mounted separately, rate limited per API key.
+pub fn router_partner() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new().route("/opac/availability", get(opac_availability_batch))
 }
 
 
@@ -38,47 +70,135 @@ pub fn router() -> axum::Router<crate::AppState> {
         ("serie_id" = Option<i64>, Query, description = "Filter by series ID (exact match)"),
         ("collection" = Option<String>, Query, description = "Filter by collection name (substring)"),
         ("collection_id" = Option<i64>, Query, description = "Filter by collection ID (exact match)"),
+        ("readingLevel" = Option<String>, Query, description = "Comma-separated reading levels, any of them (ages0To3, ages4To6, ages7To9, ages10To12, teen)"),
+        ("accessibility" = Option<String>, Query, description = "Comma-separated accessible formats, all required (largePrint, braille, audiobook, dyslexiaFriendly)"),
         ("include_without_active_items" = Option<bool>, Query, description = "If true, include biblios with no active items; default excludes them (patron catalogue)"),
         ("page" = Option<i64>, Query, description = "Page number (default 1)"),
         ("per_page" = Option<i64>, Query, description = "Items per page (default 20, max 50)")
     ),
     responses(
-        (status = 200, description = "Catalog search results", body = PaginatedResponse<BiblioShort>)
+        (status = 200, description = "Catalog search results with facet counts", body = BiblioSearchPage)
     )
 )]
 pub async fn opac_search(
     State(state): State<crate::AppState>,
     Query(mut query): Query<BiblioQuery>,
-) -> AppResult<Json<PaginatedResponse<BiblioShort>>> {
+) -> AppResult<Json<BiblioSearchPage>> {
     // Cap per_page to prevent abuse on public endpoint
     let per_page = query.per_page.unwrap_or(20).min(50);
     let page = query.page.unwrap_or(1).max(1);
     query.per_page = Some(per_page);
     query.page = Some(page);
+    query.opac = true;
+
+    let (biblios, total, facets) = state.services.catalog.search_biblios_with_facets(&query).await?;
+    Ok(Json(BiblioSearchPage::new(biblios, total, page, per_page, facets)))
+}
+
+/// Title completions for the search box — public
+#[utoipa::path(
+    get,
+    path = "/items/suggest",
+    tag = "opac",
+    params(SuggestQuery),
+    responses(
+        (status = 200, description = "Titles of non-archived records, titles starting with `q` first", body = Vec<Suggestion>)
+    )
+)]
+pub async fn suggest_titles(
+    State(state): State<crate::AppState>,
+    Query(query): Query<SuggestQuery>,
+) -> AppResult<Json<Vec<Suggestion>>> {
+    let suggestions = state.services.catalog.suggest_titles(&query).await?;
+    Ok(Json(suggestions))
+}
 
-    let (biblios, total) = state.services.catalog.search_biblios(&query).await?;
-    Ok(Json(PaginatedResponse::new(biblios, total, page, per_page)))
+/// Author completions for the search box — public
+#[utoipa::path(
+    get,
+    path = "/authors/suggest",
+    tag = "opac",
+    params(SuggestQuery),
+    responses(
+        (status = 200, description = "Authors of non-archived records, last names starting with `q` first", body = Vec<Suggestion>)
+    )
+)]
+pub async fn suggest_authors(
+    State(state): State<crate::AppState>,
+    Query(query): Query<SuggestQuery>,
+) -> AppResult<Json<Vec<Suggestion>>> {
+    let suggestions = state.services.catalog.suggest_authors(&query).await?;
+    Ok(Json(suggestions))
 }
 
 /// Get a single bibliographic record by ID — public
+///
+/// Copies in a state hidden from the OPAC (on order, in processing...) are left out.
 #[utoipa::path(
     get,
     path = "/opac/biblios/{id}",
     tag = "opac",
     params(("id" = i64, Path, description = "Biblio ID")),
     responses(
-        (status = 200, description = "Bibliographic record details", body = crate::models::biblio::Biblio),
-        (status = 404, description = "Biblio not found", body = crate::error::ErrorResponse)
+        (status = 200, description = "Bibliographic record details", body = Biblio),
+        (status = 404, description = "Biblio not found or all its copies hidden from the OPAC", body = ErrorResponse)
     )
 )]
 pub async fn opac_get_biblio(
     State(state): State<crate::AppState>,
     Path(biblio_id): Path<i64>,
 ) -> AppResult<Json<crate::models::biblio::Biblio>> {
-    let biblio = state.services.catalog.get_biblio(biblio_id).await?;
+    let biblio = state.services.catalog.get_opac_biblio(biblio_id).await?;
     Ok(Json(biblio))
 }
 
+/// Citation of a bibliographic record — public
+///
+/// `style=apa` (default), `mla` or `iso690` return the citation as text and HTML;
+/// `style=bibtex` and `style=ris` download a file for reference managers.
+#[utoipa::path(
+    get,
+    path = "/opac/biblios/{id}/citation",
+    tag = "opac",
+    params(("id" = i64, Path, description = "Biblio ID"), CitationQuery),
+    responses(
+        (status = 200, description = "Citation (JSON), or BibTeX / RIS file", body = Citation),
+        (status = 404, description = "Biblio not found or all its copies hidden from the OPAC", body = ErrorResponse)
+    )
+)]
+pub async fn opac_citation(
+    State(state): State<crate::AppState>,
+    Path(biblio_id): Path<i64>,
+    Query(query): Query<CitationQuery>,
+) -> AppResult<Response> {
+    let biblio = state.services.catalog.get_opac_biblio(biblio_id).await?;
+    Ok(citation_response(&biblio, query.style.unwrap_or_default()))
+}
+
+/// Citation as JSON, or the BibTeX / RIS download (`GET /opac/biblios/:id/citation`, `GET /items/:id/citation`)
+pub(crate) fn citation_response(biblio: &Biblio, style: CitationStyle) -> Response {
+    let id = biblio.id.unwrap_or_default();
+    match style {
+        CitationStyle::Bibtex => (
+            [
+                (header::CONTENT_TYPE, "application/x-bibtex; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.bib\"", id)),
+            ],
+            citations::bibtex(biblio),
+        )
+            .into_response(),
+        CitationStyle::Ris => (
+            [
+                (header::CONTENT_TYPE, "application/x-research-info-systems; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.ris\"", id)),
+            ],
+            citations::ris(biblio),
+        )
+            .into_response(),
+        style => Json(citations::citation(biblio, style)).into_response(),
+    }
+}
+
 /// Get availability for a bibliographic record (how many physical copies are available)
 #[utoipa::path(
     get,
@@ -86,13 +206,15 @@ pub async fn opac_get_biblio(
     tag = "opac",
     params(("id" = i64, Path, description = "Biblio ID")),
     responses(
-        (status = 200, description = "Availability count", body = serde_json::Value)
+        (status = 200, description = "Availability count (copies shown in the OPAC)", body = serde_json::Value),
+        (status = 404, description = "Biblio not found or all its copies hidden from the OPAC", body = ErrorResponse)
     )
 )]
 pub async fn opac_availability(
     State(state): State<crate::AppState>,
     Path(biblio_id): Path<i64>,
 ) -> AppResult<Json<serde_json::Value>> {
+    state.services.catalog.get_opac_biblio(biblio_id).await?;
     let active_loans = state.services.loans.count_active_for_biblio(biblio_id).await?;
     let hold_count = state.services.holds.count_active_for_biblio(biblio_id).await?;
     Ok(Json(serde_json::json!({
@@ -102,3 +224,173 @@ pub async fn opac_availability(
     })))
 }
 
+
+/// Output format of the availability widget
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WidgetFormat {
+    /// JSON body (or JSONP when `callback` is set)
+    #[default]
+    Json,
+    /// Self-contained HTML fragment
+    Html,
+}
+
+/// Query parameters for `GET /opac/widget/{isbn}`
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+pub struct WidgetQuery {
+    #[serde(default)]
+    pub format: WidgetFormat,
+    /// JSONP callback name (JSON format only; letters, digits, `_`, `$` and `.`)
+    pub callback: Option<String>,
+}
+
+/// Availability widget for third-party sites — no auth, CORS-open, cacheable
+#[utoipa::path(
+    get,
+    path = "/opac/widget/{isbn}",
+    tag = "opac",
+    params(
+        ("isbn" = String, Path, description = "ISBN-10 or ISBN-13 (hyphens allowed)"),
+        WidgetQuery
+    ),
+    responses(
+        (status = 200, description = "Availability summary (JSON, JSONP or HTML fragment)", body = BiblioAvailability),
+        (status = 400, description = "Invalid ISBN or callback", body = ErrorResponse),
+        (status = 404, description = "No biblio with this ISBN", body = ErrorResponse)
+    )
+)]
+pub async fn opac_widget(
+    State(state): State<crate::AppState>,
+    Path(isbn): Path<String>,
+    Query(query): Query<WidgetQuery>,
+) -> AppResult<Response> {
+    let isbn = Isbn::new(isbn);
+    let mut availability = state.services.catalog.get_availability_by_isbn(&isbn).await?;
+    let base_url = state
+        .config
+        .server
+        .public_base_url
+        .as_deref()
+        .unwrap_or("")
+        .trim_end_matches('/');
+    availability.cover_url = Some(format!("{}{}/covers/isbn/{}?size=S", base_url, ApiVersion::LATEST.prefix(), isbn));
+
+    let (content_type, body) = match (query.format, query.callback.as_deref()) {
+        (WidgetFormat::Html, _) => ("text/html; charset=utf-8", render_widget_html(&availability)),
+        (WidgetFormat::Json, Some(callback)) => {
+            if !is_valid_jsonp_callback(callback) {
+                return Err(AppError::Validation("Invalid JSONP callback name".to_string()));
+            }
+            let json = serde_json::to_string(&availability)
+                .map_err(|e| AppError::Internal(format!("Widget serialization: {}", e)))?;
+            ("application/javascript; charset=utf-8", format!("/**/{}({});", callback, json))
+        }
+        (WidgetFormat::Json, None) => {
+            let json = serde_json::to_string(&availability)
+                .map_err(|e| AppError::Internal(format!("Widget serialization: {}", e)))?;
+            ("application/json", json)
+        }
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CACHE_CONTROL, WIDGET_CACHE_CONTROL),
+            (header::ACCESS_CONTROL_ALLOW_ORIGIN, "*"),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Query parameters for `GET /opac/availability`
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+pub struct AvailabilityBatchQuery {
+    /// Comma-separated ISBN-10 or ISBN-13 (hyphens allowed), at most 100
+    pub isbns: String,
+}
+
+/// Availability of up to 100 ISBNs at once, for partner applications holding an API key
+#[utoipa::path(
+    get,
+    path = "/opac/availability",
+    tag = "opac",
+    security(("api_key" = [])),
+    params(AvailabilityBatchQuery),
+    responses(
+        (status = 200, description = "Availability per ISBN, in request order (`availability` is null for unknown ISBNs)", body = AvailabilityBatch),
+        (status = 400, description = "No ISBN or more than 100", body = ErrorResponse),
+        (status = 401, description = "Missing, invalid, revoked or expired API key", body = ErrorResponse),
+        (status = 403, description = "API key lacks the opac.availability scope", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded for this API key", body = ErrorResponse)
+    )
+)]
+pub async fn opac_availability_batch(
+    State(state): State<crate::AppState>,
+    ApiKeyToken(token): ApiKeyToken,
+    ClientIp(ip): ClientIp,
+    Query(query): Query<AvailabilityBatchQuery>,
+) -> AppResult<Json<AvailabilityBatch>> {
+    let isbns = parse_isbn_list(&query.isbns)?;
+    state
+        .services
+        .api_keys
+        .authenticate(&token, SCOPE_OPAC_AVAILABILITY, isbns.len() as i64, ip)
+        .await?;
+    let results = state
+        .services
+        .catalog
+        .get_availability_by_isbns(Some(&state.services.redis), &isbns)
+        .await?;
+    Ok(Json(AvailabilityBatch { results }))
+}
+
+/// Split, normalize and deduplicate a comma-separated ISBN list (first occurrence order kept).
+fn parse_isbn_list(raw: &str) -> AppResult<Vec<Isbn>> {
+    let mut isbns: Vec<Isbn> = Vec::new();
+    for isbn in raw.split(',').map(Isbn::new).filter(|isbn| !isbn.is_empty()) {
+        if !isbns.contains(&isbn) {
+            isbns.push(isbn);
+        }
+    }
+    if isbns.is_empty() {
+        return Err(AppError::Validation("isbns must list at least one ISBN".to_string()));
+    }
+    if isbns.len() > MAX_BATCH_ISBNS {
+        return Err(AppError::Validation(format!(
+            "isbns is limited to {} ISBNs per request",
+            MAX_BATCH_ISBNS
+        )));
+    }
+    Ok(isbns)
+}
+
+/// JSONP callbacks are echoed into executable JavaScript, so only plain identifiers are allowed.
+fn is_valid_jsonp_callback(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$' || c == '.')
+}
+
+fn render_widget_html(availability: &BiblioAvailability) -> String {
+    let title = escape_html(availability.title.as_deref().unwrap_or(""));
+    let cover = escape_html(availability.cover_url.as_deref().unwrap_or(""));
+    let (class, label) = if availability.is_available() {
+        ("available", format!("Available ({}/{})", availability.available_items, availability.total_items))
+    } else if availability.total_items > 0 {
+        ("on-loan", "On loan".to_string())
+    } else {
+        ("unavailable", "Not available for loan".to_string())
+    };
+    format!(
+        "<div class=\"elidune-widget elidune-widget--{class}\">\
+<img class=\"elidune-widget__cover\" src=\"{cover}\" alt=\"\" loading=\"lazy\">\
+<span class=\"elidune-widget__title\">{title}</span>\
+<span class=\"elidune-widget__status\">{label}</span>\
+</div>"
+    )
+}
@@ -1,11 +1,11 @@
 //! OpenAPI documentation
 
-use axum::Router;
-use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use axum::{routing::get, Router};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
 use utoipa::{Modify, OpenApi};
 use utoipa_swagger_ui::SwaggerUi;
 
-use crate::api::{account_types, admin_config, audit, auth, biblios, collections, email_templates, equipment, events, first_setup, health, holds, inventory, items, library_info, loans, maintenance, opac, public_types, schedules, series, sources, stats, tasks, users, visitor_counts, z3950};
+use crate::api::{account_types, admin_config, api_keys, artifacts, audit, auth, authors, backups, batch, biblios, bundles, campaigns, collections, covers, dashboards, deposits, donations, email_health, email_templates, eod, equipment, events, feeds, fines, first_setup, harvest, headings, health, holds, inventory, item_states, item_templates, items, kiosks, labels, library_info, loan_batches, loans, locations, lockers, maintenance, media_types, notifications, opac, payments, public_types, purchase_suggestions, reading_programs, reengagement, relators, schedules, series, sitemap, snapshots, sources, sse, staffing, stats, tasks, user_messages, users, vendors, visitor_counts, warehouse, z3950};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -17,7 +17,8 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
         contact(name = "Elidune Team", email = "contact@elidune.org")
     ),
     servers(
-        (url = "/api/v1", description = "API v1")
+        (url = "/api/v2", description = "API v2"),
+        (url = "/api/v1", description = "API v1 (same operations; see the `api` config section for its retirement)")
     ),
     paths(
         // Health
@@ -34,8 +35,25 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
         auth::reset_password,
         auth::setup_2fa,
         auth::disable_2fa,
+        auth::regenerate_recovery_codes,
+        auth::login_barcode,
+        auth::change_own_pin,
+        auth::change_password,
+        dashboards::get_my_dashboard,
+        dashboards::save_my_dashboard,
+        dashboards::reset_my_dashboard,
+        dashboards::get_my_dashboard_data,
+        dashboards::add_my_dashboard_widget,
+        dashboards::update_my_dashboard_widget,
+        dashboards::delete_my_dashboard_widget,
+        // Notifications
+        notifications::list_notifications,
+        notifications::get_unread_count,
+        notifications::mark_notification_read,
+        notifications::mark_all_notifications_read,
         // Biblios and physical items
         biblios::list_biblios,
+        biblios::export_biblios_csv,
         biblios::get_biblio,
         biblios::create_biblio,
         biblios::load_marc,
@@ -44,38 +62,108 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
         biblios::load_marc_batch,
         biblios::update_biblio,
         biblios::delete_biblio,
+        biblios::get_edit_lock,
+        biblios::acquire_edit_lock,
+        biblios::release_edit_lock,
+        biblios::get_marc_diff,
+        biblios::apply_marc_diff,
         biblios::list_items,
         biblios::create_item,
+        items::quick_create_item,
         items::get_biblio_by_item,
+        items::item_citation,
         items::get_biblio_by_barcode,
         items::update_item,
         items::delete_item,
+        items::recalculate_call_numbers,
+        items::export_items,
+        items::print_spine_labels,
+        items::access_item,
+        items::route_item,
+        items::receive_item,
         // Users
         users::list_users,
         users::get_user,
         users::create_user,
         users::update_user,
         users::delete_user,
+        users::restore_user,
+        users::merge_users,
+        users::run_expiry_campaign,
         users::update_my_profile,
         users::update_account_type,
+        users::set_user_pin,
+        users::impersonate_user,
+        users::get_user_activity,
+        users::force_password_change,
+        user_messages::list_user_messages,
+        user_messages::create_user_message,
+        user_messages::delete_user_message,
+        user_messages::acknowledge_user_message,
         // Loans
         loans::get_user_loans,
+        loans::get_checkout_blocks,
         loans::export_user_loans_marc,
         loans::create_loan,
         loans::return_loan,
         loans::renew_loan,
+        loans::get_loan_renewability,
         loans::return_loan_by_item,
         loans::renew_loan_by_item,
         loans::get_overdue_loans,
         loans::send_overdue_reminders,
+        loans::send_due_soon_reminders,
         loans::get_loan_settings,
         loans::update_loan_settings,
+        batch::batch_return,
+        batch::batch_create_loans,
+        loan_batches::list_loan_batches,
+        loan_batches::get_loan_batch,
+        loan_batches::create_loan_batch,
+        loan_batches::extend_loan_batch,
+        loan_batches::return_loan_batch,
+        bundles::list_bundles,
+        bundles::get_bundle,
+        bundles::create_bundle,
+        bundles::update_bundle,
+        bundles::delete_bundle,
+        bundles::return_bundle,
+        deposits::list_deposits,
+        deposits::get_deposit,
+        deposits::create_deposit,
+        deposits::update_deposit,
+        deposits::delete_deposit,
+        deposits::import_deposit_batch,
+        deposits::get_deposit_manifest,
+        deposits::close_deposit,
         // Holds
         holds::list_holds,
         holds::create_hold,
         holds::list_holds_for_item,
         holds::list_holds_for_user,
         holds::cancel_hold,
+        holds::get_pull_list,
+        holds::get_expired_holds,
+        lockers::assign_hold_locker,
+        lockers::locker_webhook,
+        // Fines
+        fines::list_user_fines,
+        fines::pay_fine,
+        fines::waive_fine,
+        fines::fine_amnesty,
+        fines::waived_fines_summary,
+        fines::list_fine_rules,
+        fines::upsert_fine_rule,
+        // Cash register
+        payments::list_payments,
+        payments::get_payment,
+        payments::record_payment,
+        payments::refund_payment,
+        payments::get_daily_summary,
+        payments::export_payments,
+        payments::list_period_locks,
+        payments::unlock_period,
+        payments::get_receipt_gaps,
         // Inventory (stocktaking)
         inventory::list_sessions,
         inventory::create_session,
@@ -96,6 +184,10 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
         stats::get_loan_stats,
         stats::get_user_stats,
         stats::get_catalog_stats,
+        stats::get_visitor_stats,
+        stats::get_acquisition_stats,
+        stats::get_catalog_quality_stats,
+        stats::get_reengagement_stats,
         stats::get_stats_schema,
         stats::post_stats_query,
         stats::list_saved_queries,
@@ -103,6 +195,13 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
         stats::update_saved_query,
         stats::delete_saved_query,
         stats::run_saved_query,
+        stats::get_report_schema,
+        stats::post_run_report,
+        stats::list_saved_reports,
+        stats::create_saved_report,
+        stats::update_saved_report,
+        stats::delete_saved_report,
+        stats::run_saved_report,
         // Library info
         library_info::get_library_info,
         library_info::update_library_info,
@@ -125,6 +224,23 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
         schedules::list_closures,
         schedules::create_closure,
         schedules::delete_closure,
+        schedules::get_status,
+        schedules::get_week,
+        // Staffing
+        staffing::list_shift_templates,
+        staffing::get_shift_template,
+        staffing::create_shift_template,
+        staffing::update_shift_template,
+        staffing::delete_shift_template,
+        staffing::get_roster,
+        staffing::create_assignment,
+        staffing::delete_assignment,
+        staffing::list_absences,
+        staffing::create_absence,
+        staffing::delete_absence,
+        staffing::my_shifts,
+        staffing::regenerate_calendar_token,
+        staffing::get_calendar,
         // Series
         series::list_series,
         series::get_serie,
@@ -146,6 +262,161 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
         sources::update_source,
         sources::archive_source,
         sources::merge_sources,
+        sources::get_source_stats,
+        sources::delete_source,
+        sources::set_source_vendor,
+        // Vendors
+        vendors::list_vendors,
+        vendors::get_vendor,
+        vendors::create_vendor,
+        vendors::update_vendor,
+        vendors::delete_vendor,
+        vendors::get_vendor_spend,
+        vendors::list_purchase_orders,
+        vendors::get_purchase_order,
+        vendors::create_purchase_order,
+        vendors::update_purchase_order,
+        vendors::delete_purchase_order,
+        // Donations
+        donations::list_donations,
+        donations::get_donation,
+        donations::create_donation,
+        donations::update_donation,
+        donations::delete_donation,
+        donations::add_donation_item,
+        donations::delete_donation_item,
+        donations::triage_donation_item,
+        donations::get_donors_report,
+        // Purchase suggestions
+        purchase_suggestions::create_suggestion,
+        purchase_suggestions::list_my_suggestions,
+        purchase_suggestions::list_suggestions,
+        purchase_suggestions::get_suggestion,
+        purchase_suggestions::triage_suggestion,
+        // Reading programs
+        reading_programs::list_reading_programs,
+        reading_programs::get_reading_program,
+        reading_programs::create_reading_program,
+        reading_programs::update_reading_program,
+        reading_programs::delete_reading_program,
+        reading_programs::list_enrollments,
+        reading_programs::get_enrollment,
+        reading_programs::enroll_reader,
+        reading_programs::unenroll_reader,
+        reading_programs::list_reading_entries,
+        reading_programs::add_reading_entry,
+        reading_programs::delete_reading_entry,
+        reading_programs::sync_reading_loans,
+        reading_programs::get_reading_program_stats,
+        // Item states
+        item_states::list_item_states,
+        item_states::get_item_state,
+        item_states::create_item_state,
+        item_states::update_item_state,
+        item_states::delete_item_state,
+        locations::list_locations,
+        locations::get_location_tree,
+        locations::get_location,
+        locations::create_location,
+        locations::update_location,
+        locations::delete_location,
+        locations::get_location_mapping,
+        locations::apply_location_mapping,
+        media_types::list_media_types,
+        media_types::get_media_type,
+        media_types::create_media_type,
+        media_types::update_media_type,
+        media_types::delete_media_type,
+        relators::list_relators,
+        relators::get_relator,
+        relators::create_relator,
+        relators::update_relator,
+        relators::delete_relator,
+        item_templates::list_item_templates,
+        item_templates::get_item_template,
+        item_templates::create_item_template,
+        item_templates::update_item_template,
+        item_templates::delete_item_template,
+        labels::list_labels,
+        labels::upsert_label,
+        labels::delete_label,
+        headings::list_genres,
+        headings::get_genre,
+        headings::create_genre,
+        headings::update_genre,
+        headings::delete_genre,
+        headings::merge_genres,
+        headings::list_subjects,
+        headings::get_subject,
+        headings::create_subject,
+        headings::update_subject,
+        headings::delete_subject,
+        headings::merge_subjects,
+        headings::get_biblio_genres,
+        headings::set_biblio_genres,
+        headings::get_biblio_subjects,
+        headings::set_biblio_subjects,
+        authors::get_author,
+        authors::set_author_display_name,
+        authors::preview_author_normalization,
+        authors::normalize_authors,
+        kiosks::list_kiosks,
+        kiosks::get_kiosk,
+        kiosks::create_kiosk,
+        kiosks::update_kiosk,
+        kiosks::regenerate_kiosk_token,
+        kiosks::delete_kiosk,
+        kiosks::kiosk_session,
+        api_keys::list_api_keys,
+        api_keys::get_api_key,
+        api_keys::create_api_key,
+        api_keys::update_api_key,
+        api_keys::regenerate_api_key_token,
+        api_keys::delete_api_key,
+        api_keys::get_api_key_usage,
+        snapshots::list_snapshots,
+        snapshots::get_snapshot,
+        snapshots::restore_snapshot,
+        backups::create_backup,
+        backups::list_backups,
+        eod::list_eod_runs,
+        eod::get_eod_run,
+        eod::run_eod,
+        reengagement::preview_reengagement,
+        reengagement::run_reengagement,
+        harvest::list_harvest_sources,
+        harvest::get_harvest_source,
+        harvest::create_harvest_source,
+        harvest::update_harvest_source,
+        harvest::delete_harvest_source,
+        harvest::run_harvest_source,
+        harvest::list_harvest_runs,
+        harvest::get_harvest_run,
+        warehouse::list_warehouse_targets,
+        warehouse::get_warehouse_target,
+        warehouse::create_warehouse_target,
+        warehouse::update_warehouse_target,
+        warehouse::delete_warehouse_target,
+        warehouse::run_warehouse_target,
+        warehouse::list_warehouse_runs,
+        warehouse::get_warehouse_run,
+        campaigns::list_campaigns,
+        campaigns::create_campaign,
+        campaigns::preview_campaign_segment,
+        campaigns::get_campaign,
+        campaigns::update_campaign,
+        campaigns::delete_campaign,
+        campaigns::send_campaign,
+        campaigns::list_campaign_recipients,
+        campaigns::record_campaign_bounces,
+        campaigns::update_campaigns_opt_in,
+        email_health::email_webhook,
+        email_health::reset_user_email_health,
+        // Artifacts
+        artifacts::list_artifacts,
+        artifacts::get_artifact_link,
+        artifacts::download_artifact,
+        artifacts::delete_artifact,
         // Equipment
         equipment::list_equipment,
         equipment::get_equipment,
@@ -154,11 +425,19 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
         equipment::delete_equipment,
         // Events
         events::list_events,
+        events::list_event_conflicts,
         events::get_event,
         events::create_event,
         events::update_event,
         events::delete_event,
         events::send_event_announcement,
+        events::publish_event,
+        events::cancel_event,
+        events::list_event_registrations,
+        events::register_to_event,
+        events::unregister_from_event,
+        feeds::events_feed,
+        feeds::new_items_feed,
         // Library account types (roles / rights)
         account_types::list_account_types,
         account_types::get_account_type,
@@ -168,6 +447,7 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
         admin_config::update_config_section,
         admin_config::reset_config_section,
         admin_config::test_email,
+        admin_config::reindex_search,
         // Maintenance
         maintenance::run_maintenance,
         maintenance::dump_database,
@@ -189,6 +469,17 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
         opac::opac_search,
         opac::opac_get_biblio,
         opac::opac_availability,
+        opac::opac_citation,
+        opac::opac_widget,
+        opac::opac_availability_batch,
+        opac::suggest_titles,
+        opac::suggest_authors,
+        sitemap::get_sitemap,
+        sitemap::get_sitemap_shard,
+        sitemap::generate_sitemap,
+        covers::get_cover_by_isbn,
+        // Real-time events
+        sse::sse_stream,
     ),
     components(
         schemas(
@@ -205,10 +496,53 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
             auth::ResetPasswordResponse,
             auth::Setup2FARequest,
             auth::Setup2FAResponse,
+            auth::RecoveryCodesResponse,
+            auth::BarcodeLoginRequest,
+            auth::BarcodeLoginResponse,
+            auth::ChangePasswordRequest,
+            // Personal dashboards
+            crate::models::dashboard::Dashboard,
+            crate::models::dashboard::DashboardWidget,
+            crate::models::dashboard::DashboardWidgetType,
+            crate::models::dashboard::DashboardWidgetWrite,
+            crate::models::dashboard::SaveDashboard,
+            crate::models::dashboard::DashboardData,
+            crate::models::dashboard::DashboardWidgetData,
+            // Notifications
+            crate::models::notification::Notification,
+            crate::models::notification::NotificationKind,
+            crate::models::notification::NotificationsPage,
+            crate::models::notification::UnreadNotificationCount,
+            crate::models::notification::NotificationsMarkedRead,
             // Biblios (bibliographic records)
             crate::models::biblio::Biblio,
+            crate::models::biblio::EditLock,
             crate::models::biblio::BiblioShort,
             crate::models::biblio::BiblioQuery,
+            crate::models::biblio::Isbn,
+            crate::models::biblio::MediaType,
+            crate::models::biblio::AudienceType,
+            crate::models::biblio::AccessibilityFeature,
+            crate::models::biblio::ReadingLevel,
+            crate::models::biblio::BiblioFacets,
+            crate::models::biblio::FacetCount,
+            crate::models::biblio::Suggestion,
+            crate::models::biblio::SuggestQuery,
+            biblios::BiblioSearchPage,
+            crate::models::author::Author,
+            crate::models::author::Function,
+            crate::models::author::UpdateAuthorDisplayName,
+            crate::models::author::AuthorNormalizationChange,
+            crate::models::author::AuthorNormalizationReport,
+            crate::models::Language,
+            crate::services::marc::EnqueueResult,
+            crate::services::marc::MarcBatchInfo,
+            crate::marc::MarcImportPreview,
+            biblios::MarcDiffResponse,
+            biblios::ApplyMarcDiffRequest,
+            crate::marc::diff::MarcDiffFormat,
+            crate::marc::diff::MarcFieldChange,
+            crate::marc::diff::MarcFieldDiff,
             crate::models::biblio::Serie,
             crate::models::biblio::Collection,
             crate::models::biblio::Edition,
@@ -223,33 +557,102 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
             // Items (physical copies)
             crate::models::item::Item,
             crate::models::item::ItemShort,
+            crate::models::item::ItemAccessType,
+            crate::models::item::CallNumberPrefixRewrite,
+            crate::models::item::CallNumberRules,
+            crate::models::item::RecalculateCallNumbers,
+            crate::models::item::CallNumberChange,
+            crate::models::item::CallNumberRecalculationReport,
             // Pagination
-            biblios::PaginatedResponse<crate::models::biblio::BiblioShort>,
-            biblios::PaginatedResponse<crate::models::user::UserShort>,
-            biblios::PaginatedResponse<crate::models::loan::LoanDetails>,
+            biblios::PaginatedBiblios,
+            biblios::PaginatedUsers,
+            biblios::PaginatedUserActivity,
+            biblios::PaginatedLoans,
+            biblios::PaginatedHolds,
+            biblios::PaginatedInventorySessions,
+            biblios::PaginatedInventoryScans,
+            biblios::PaginatedInventoryMissing,
             // Users
             crate::models::user::User,
             crate::models::user::UserShort,
+            crate::models::user::UserActivity,
+            crate::models::user::UserActivityKind,
+            crate::models::user::AccountTypeSlug,
+            crate::models::user::FeeSlug,
+            crate::models::user::UserStatus,
+            crate::models::enums::Sex,
             crate::models::user::UserQuery,
             crate::models::user::UserPayload,
             crate::models::user::UpdateProfile,
             crate::models::user::UpdateAccountType,
+            crate::models::user::SetUserPin,
+            crate::models::user::MergeUsers,
+            crate::models::user::UserMergeReport,
+            crate::models::user::UserExpiryCampaign,
+            crate::models::user::UserExpiryCampaignReport,
+            crate::models::user::UserExpiryOutcome,
+            crate::models::user::RenewalInvitationStatus,
+            crate::models::user::UserMergeCounts,
+            crate::models::user::UserMergeConflict,
+            crate::models::user::ChangeOwnPin,
+            crate::models::user::RegenerateRecoveryCodes,
+            users::ImpersonateRequest,
+            users::ImpersonationResponse,
             crate::models::account_type::AccountTypeDefinition,
             crate::models::account_type::UpdateAccountTypeDefinition,
             // Loans
             loans::CreateLoanRequest,
             loans::LoanResponse,
             loans::ReturnResponse,
+            items::RouteItemRequest,
+            crate::models::loan::ReturnRouting,
+            crate::models::loan::ReturnRoutingAction,
             loans::OverdueLoansQuery,
+            batch::BatchReturnRequest,
+            batch::BatchReturnResponse,
+            batch::BatchReturnItemResult,
+            batch::BatchCreateLoansRequest,
+            batch::BatchCreateLoansResponse,
+            batch::BatchCreateLoanItemResult,
             // Holds
             crate::models::hold::Hold,
             crate::models::hold::HoldDetails,
+            crate::models::hold::HoldStatus,
+            crate::models::hold::HoldPriority,
+            crate::models::hold::AssignHoldLocker,
+            crate::models::hold::LockerEventKind,
+            crate::models::hold::LockerWebhookEvent,
+            crate::models::hold::LockerEventOutcome,
+            crate::models::hold::HoldShelfEntry,
+            crate::models::hold::HoldShelfGroup,
+            crate::models::hold::HoldListFormat,
             holds::CreateHoldRequest,
             holds::ListHoldsQuery,
-            biblios::PaginatedResponse<crate::models::hold::HoldDetails>,
-            biblios::PaginatedResponse<crate::models::inventory::InventorySession>,
-            biblios::PaginatedResponse<crate::models::inventory::InventoryScan>,
-            biblios::PaginatedResponse<crate::models::inventory::InventoryMissingRow>,
+            // Fines
+            crate::models::fine::Fine,
+            crate::models::fine::FineRule,
+            crate::models::fine::FineStatus,
+            crate::models::fine::PayFineRequest,
+            crate::models::fine::WaiveFineRequest,
+            crate::models::fine::FineWaiverReason,
+            crate::models::fine::FineAmnestyRequest,
+            crate::models::fine::FineAmnestyReport,
+            crate::models::fine::FineWaiverTotal,
+            crate::models::fine::WaivedFinesSummary,
+            fines::UnpaidFinesSummary,
+            fines::UpsertFineRuleRequest,
+            // Cash register
+            crate::models::payment::Payment,
+            crate::models::payment::PaymentMethod,
+            crate::models::payment::PaymentCategory,
+            crate::models::payment::RecordPayment,
+            crate::models::payment::RefundPayment,
+            crate::models::payment::PaymentsPage,
+            crate::models::payment::CashTotal,
+            crate::models::payment::OperatorCashTotal,
+            crate::models::payment::DailyCashSummary,
+            crate::models::payment::PaymentPeriodLock,
+            crate::models::payment::ReceiptGapReport,
             crate::models::inventory::InventorySession,
             crate::models::inventory::InventoryScan,
             crate::models::inventory::InventoryScanResult,
@@ -265,16 +668,53 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
             loans::ExportUserLoansMarcQuery,
             crate::models::loan::LoanMarcExportFormat,
             crate::models::loan::LoanMarcExportEncoding,
+            crate::models::item::ItemExportFormat,
+            crate::models::item::LabelStock,
+            crate::models::item::LabelFont,
             loans::SendRemindersQuery,
             crate::models::loan::LoanDetails,
+            crate::models::loan::LoanMatch,
+            crate::models::loan::LoanMatchField,
+            crate::models::loan_batch::LoanBatch,
+            crate::models::loan_batch::LoanBatchLine,
+            crate::models::loan_batch::CreateLoanBatch,
+            crate::models::loan_batch::ExtendLoanBatch,
+            crate::models::loan_batch::LoanBatchQuery,
+            crate::models::loan_batch::LoanBatchReturnReport,
+            crate::models::bundle::ItemBundle,
+            crate::models::bundle::BundlePart,
+            crate::models::bundle::CreateItemBundle,
+            crate::models::bundle::UpdateItemBundle,
+            crate::models::bundle::ReturnItemBundle,
+            crate::models::bundle::BundleReturnReport,
+            crate::models::deposit::Deposit,
+            crate::models::deposit::DepositStatus,
+            crate::models::deposit::DepositCopy,
+            crate::models::deposit::CreateDeposit,
+            crate::models::deposit::UpdateDeposit,
+            crate::models::deposit::DepositCloseReport,
+            crate::models::loan::CheckoutBlockCode,
+            crate::models::loan::RenewalBlockCode,
+            crate::models::loan::RenewalBlock,
+            crate::models::loan::LoanRenewability,
+            crate::models::loan::CheckoutBlock,
+            crate::models::loan::CheckoutBlockedResponse,
+            crate::models::user_message::UserMessage,
+            crate::models::user_message::UserMessageSeverity,
+            crate::models::user_message::CreateUserMessage,
             crate::services::reminders::ReminderReport,
             crate::services::reminders::ReminderDetail,
             crate::services::reminders::ReminderError,
             crate::services::reminders::OverdueLoansPage,
             crate::services::reminders::OverdueLoanInfo,
+            crate::services::reminders::ClosureExtensionReport,
             // Z39.50
             z3950::Z3950SearchQuery,
             z3950::Z3950SearchResponse,
+            z3950::Z3950DegradedSource,
+            z3950::Z3950SourceReport,
+            z3950::Z3950RecordFailure,
+            z3950::Z3950DegradedReason,
             z3950::Z3950ImportRequest,
             z3950::Z3950ImportResponse,
             z3950::ImportItem,
@@ -287,10 +727,20 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
             biblios::CreateBiblioResponse,
             // Stats
             stats::StatsResponse,
+            stats::ItemStats,
+            stats::UserStats,
+            stats::LoanStats,
+            stats::StatEntry,
+            stats::UserStatsMode,
+            stats::UserStatsResponse,
+            stats::UserStatsAggregate,
             stats::StatsQuery,
             stats::LoanStatsResponse,
+            stats::LoanLocationStats,
             stats::UserLoanStats,
             stats::Interval,
+            stats::StatsComparison,
+            stats::StatDelta,
             stats::LoanStatsQuery,
             stats::TimeSeriesEntry,
             stats::UserStatsSortBy,
@@ -299,7 +749,15 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
             stats::CatalogStatsResponse,
             stats::CatalogStatsTotals,
             stats::CatalogSourceStats,
+            stats::CatalogLocationStats,
             stats::CatalogBreakdownStats,
+            stats::CatalogDigitalResourceStats,
+            stats::AcquisitionStatsResponse,
+            stats::AcquisitionValueTotals,
+            stats::AcquisitionValueStats,
+            stats::CatalogQualityResponse,
+            stats::CatalogQualityStats,
+            stats::CompletenessRate,
             crate::models::stats_builder::StatsBuilderBody,
             crate::models::stats_builder::SelectField,
             crate::models::stats_builder::GroupByField,
@@ -316,6 +774,13 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
             crate::models::stats_builder::ColumnMeta,
             crate::models::stats_builder::SavedStatsQuery,
             crate::models::stats_builder::SavedStatsQueryWrite,
+            crate::models::stats_builder::ReportDimension,
+            crate::models::stats_builder::ReportMeasure,
+            crate::models::stats_builder::ReportDefinition,
+            crate::models::stats_builder::ReportTable,
+            crate::models::stats_builder::ReportFormat,
+            crate::models::stats_builder::SavedReport,
+            crate::models::stats_builder::SavedReportWrite,
             // Library info
             library_info::LibraryInfo,
             library_info::UpdateLibraryInfoRequest,
@@ -325,9 +790,14 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
             loans::LoanSettings,
             loans::UpdateLoanSettingsRequest,
             z3950::Z3950ServerConfig,
+            z3950::Z3950ServerHealth,
             z3950::UpdateZ3950ServersRequest,
             // Visitor counts
             crate::models::visitor_count::VisitorCount,
+            crate::models::visitor_count::VisitorStats,
+            crate::models::visitor_count::VisitorWeekdayStats,
+            crate::models::visitor_count::VisitorCountAnomaly,
+            crate::models::visitor_count::VisitorCountAnomalyReason,
             crate::models::visitor_count::CreateVisitorCount,
             crate::models::visitor_count::VisitorCountQuery,
             // Schedules
@@ -338,23 +808,212 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
             crate::models::schedule::UpdateSchedulePeriod,
             crate::models::schedule::CreateScheduleSlot,
             crate::models::schedule::CreateScheduleClosure,
+            crate::models::schedule::ScheduleClosureCreated,
             crate::models::schedule::ScheduleClosureQuery,
+            crate::models::schedule::OpeningHours,
+            crate::models::schedule::DaySchedule,
+            crate::models::schedule::WeekSchedule,
+            crate::models::schedule::ScheduleStatus,
+            crate::models::schedule::WeekScheduleQuery,
+            // Staffing
+            crate::models::staffing::ShiftTemplate,
+            crate::models::staffing::ShiftTemplateFields,
+            crate::models::staffing::ShiftOccurrence,
+            crate::models::staffing::ShiftAssignment,
+            crate::models::staffing::CreateShiftAssignment,
+            crate::models::staffing::RosterShift,
+            crate::models::staffing::StaffAbsence,
+            crate::models::staffing::CreateStaffAbsence,
+            crate::models::staffing::StaffAbsenceCreated,
+            crate::models::staffing::StaffCalendarToken,
             // Sources
             crate::models::source::Source,
             crate::models::source::CreateSource,
             crate::models::source::UpdateSource,
             crate::models::source::MergeSources,
             sources::SourcesQuery,
+            crate::models::source::SourceStats,
+            crate::models::source::SourceYearStats,
+            crate::models::source::DeleteSourceQuery,
+            crate::models::source::SourceDeletionReport,
+            crate::models::source::SetSourceVendor,
+            // Vendors
+            crate::models::vendor::Vendor,
+            crate::models::vendor::CreateVendor,
+            crate::models::vendor::UpdateVendor,
+            crate::models::vendor::PurchaseOrder,
+            crate::models::vendor::PurchaseOrderStatus,
+            crate::models::vendor::CreatePurchaseOrder,
+            crate::models::vendor::UpdatePurchaseOrder,
+            crate::models::vendor::PurchaseOrderQuery,
+            crate::models::vendor::VendorSpendQuery,
+            crate::models::vendor::VendorAnnualSpend,
+            vendors::VendorsQuery,
+            // Donations
+            crate::models::donation::Donation,
+            crate::models::donation::DonationItem,
+            crate::models::donation::DonationTriage,
+            crate::models::donation::CreateDonation,
+            crate::models::donation::CreateDonationItem,
+            crate::models::donation::UpdateDonation,
+            crate::models::donation::TriageDonationItem,
+            crate::models::donation::DonationQuery,
+            crate::models::donation::DonorsReportQuery,
+            crate::models::donation::DonorSummary,
+            crate::models::donation::DonorsReport,
+            crate::models::purchase_suggestion::PurchaseSuggestionStatus,
+            crate::models::purchase_suggestion::PurchaseSuggestion,
+            crate::models::purchase_suggestion::CreatePurchaseSuggestion,
+            crate::models::purchase_suggestion::TriagePurchaseSuggestion,
+            crate::models::purchase_suggestion::PurchaseSuggestionQuery,
+            // Reading programs
+            crate::models::reading_program::ReadingProgram,
+            crate::models::reading_program::CreateReadingProgram,
+            crate::models::reading_program::UpdateReadingProgram,
+            crate::models::reading_program::ReadingEnrollment,
+            crate::models::reading_program::EnrollReader,
+            crate::models::reading_program::ReadingEntry,
+            crate::models::reading_program::CreateReadingEntry,
+            crate::models::reading_program::ReadingLoansSyncReport,
+            crate::models::reading_program::ReadingAgeBracketStats,
+            crate::models::reading_program::ReadingProgramStats,
+            crate::models::reading_program::ReadingProgramStatsQuery,
+            // Item states
+            crate::models::item_state::ItemState,
+            crate::models::item_state::CreateItemState,
+            crate::models::item_state::UpdateItemState,
+            crate::models::location::LocationKind,
+            crate::models::location::ShelvingLocation,
+            crate::models::location::LocationNode,
+            crate::models::location::CreateLocation,
+            crate::models::location::UpdateLocation,
+            crate::models::location::LocationMappingRow,
+            crate::models::location::LocationMapping,
+            crate::models::location::ApplyLocationMappings,
+            crate::models::location::LocationMappingReport,
+            crate::models::media_type::MediaTypeDefinition,
+            crate::models::media_type::CreateMediaType,
+            crate::models::media_type::UpdateMediaType,
+            crate::models::relator::RelatorDefinition,
+            crate::models::relator::CreateRelator,
+            crate::models::relator::UpdateRelator,
+            crate::models::item_template::ItemTemplate,
+            crate::models::item_template::CreateItemTemplate,
+            crate::models::item_template::ItemTemplateFields,
+            crate::models::label::LabelKind,
+            crate::models::label::Label,
+            crate::models::label::LabelSet,
+            crate::models::label::UpsertLabel,
+            crate::models::label::LabelsQuery,
+            crate::models::heading::GenreHeading,
+            crate::models::heading::CreateGenreHeading,
+            crate::models::heading::UpdateGenreHeading,
+            crate::models::heading::SubjectHeading,
+            crate::models::heading::CreateSubjectHeading,
+            crate::models::heading::UpdateSubjectHeading,
+            crate::models::heading::SubjectQuery,
+            crate::models::heading::MergeHeadings,
+            crate::models::heading::MergeHeadingsReport,
+            crate::models::heading::SetBiblioHeadings,
+            headings::PaginatedSubjects,
+            crate::models::kiosk::Kiosk,
+            crate::models::kiosk::KioskWithToken,
+            crate::models::kiosk::CreateKiosk,
+            crate::models::kiosk::UpdateKiosk,
+            crate::models::kiosk::KioskSession,
+            crate::models::api_key::ApiKey,
+            crate::models::snapshot::CatalogSnapshot,
+            crate::models::snapshot::SnapshotRestoreReport,
+            crate::models::backup::Backup,
+            crate::models::backup::BackupStatus,
+            crate::models::backup::BackupDestination,
+            crate::models::backup::RedisNotes,
+            backups::BackupStarted,
+            crate::models::eod::EodRun,
+            crate::models::eod::EodRunDetails,
+            crate::models::eod::EodRunStatus,
+            crate::models::eod::EodTrigger,
+            crate::models::eod::EodStep,
+            crate::models::eod::EodStepStatus,
+            crate::models::eod::EodStepResult,
+            crate::models::eod::DailyStats,
+            eod::EodRunStarted,
+            crate::models::reengagement::ReengagementPreview,
+            crate::models::reengagement::ReengagementReport,
+            crate::models::reengagement::ReengagementStats,
+            crate::models::reengagement::ReengagementMonthStats,
+            crate::models::sitemap::SitemapReport,
+            crate::models::citation::Citation,
+            crate::models::citation::CitationStyle,
+            crate::models::api_key::ApiKeyWithToken,
+            crate::models::api_key::CreateApiKey,
+            crate::models::api_key::UpdateApiKey,
+            crate::models::api_key::ApiKeyUsage,
+            crate::models::api_key::ApiKeyUsageQuery,
+            crate::models::harvest::HarvestSource,
+            crate::models::harvest::CreateHarvestSource,
+            crate::models::harvest::UpdateHarvestSource,
+            crate::models::harvest::HarvestMarcFormat,
+            crate::models::harvest::HarvestMappingProfile,
+            crate::models::harvest::HarvestRecordIdField,
+            crate::models::harvest::HarvestDeleteAction,
+            crate::models::harvest::HarvestRun,
+            crate::models::harvest::HarvestTrigger,
+            crate::models::harvest::HarvestRunStatus,
+            crate::models::harvest::HarvestRecordError,
+            harvest::HarvestRunStarted,
+            crate::models::warehouse::WarehouseTarget,
+            crate::models::warehouse::CreateWarehouseTarget,
+            crate::models::warehouse::UpdateWarehouseTarget,
+            crate::models::warehouse::WarehouseFormat,
+            crate::models::warehouse::WarehouseFact,
+            crate::models::warehouse::WarehouseRun,
+            crate::models::warehouse::WarehouseTrigger,
+            crate::models::warehouse::WarehouseRunStatus,
+            crate::models::warehouse::WarehouseManifest,
+            crate::models::warehouse::WarehouseManifestFile,
+            crate::models::warehouse::WarehouseColumn,
+            warehouse::WarehouseRunStarted,
+            crate::models::campaign::Campaign,
+            crate::models::campaign::CampaignStatus,
+            crate::models::campaign::CampaignSegment,
+            crate::models::campaign::CreateCampaign,
+            crate::models::campaign::UpdateCampaign,
+            crate::models::campaign::CampaignSegmentPreview,
+            crate::models::campaign::CampaignRecipient,
+            crate::models::campaign::CampaignRecipientStatus,
+            crate::models::campaign::CampaignBounces,
+            crate::models::campaign::CampaignBounceReport,
+            crate::models::campaign::CampaignsOptIn,
+            campaigns::CampaignSendStarted,
+            crate::models::email_health::EmailFeedbackKind,
+            crate::models::email_health::EmailFeedbackEvent,
+            crate::models::email_health::EmailFeedbackBatch,
+            crate::models::email_health::EmailFeedbackReport,
+            crate::models::email_health::EmailHealth,
+            crate::models::email_health::EmailHealthStatus,
+            crate::models::artifact::Artifact,
+            crate::models::artifact::ArtifactKind,
+            crate::models::artifact::ArtifactLink,
+            crate::models::artifact::ExportDelivery,
             // Equipment
             crate::models::equipment::Equipment,
             crate::models::equipment::CreateEquipment,
             crate::models::equipment::UpdateEquipment,
+            crate::models::equipment::EquipmentShort,
             // Events
             crate::models::event::Event,
             crate::models::event::EventAttachmentInput,
             crate::models::event::CreateEvent,
             crate::models::event::UpdateEvent,
             crate::models::event::EventQuery,
+            crate::models::event::EventConflict,
+            crate::models::event::EventConflictKind,
+            crate::models::event::EventStatus,
+            crate::models::event::CancelEvent,
+            crate::models::event::RegisterEvent,
+            crate::models::event::EventRegistration,
+            crate::services::events::CancelEventResponse,
             events::EventsListResponse,
             crate::services::events::SendAnnouncementRequest,
             crate::services::events::AnnouncementReport,
@@ -364,6 +1023,7 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
             admin_config::ConfigSectionInfo,
             admin_config::UpdateConfigSectionRequest,
             admin_config::TestEmailRequest,
+            admin_config::ReindexSearchResponse,
             // Maintenance
             maintenance::MaintenanceRequest,
             maintenance::MaintenanceAction,
@@ -394,6 +1054,13 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
             crate::models::public_type::ReplacePublicTypeLoanSettingsRequest,
             crate::repository::events::EventAnnualStats,
             crate::repository::events::EventTypeStats,
+            // Opac
+            crate::models::biblio::BiblioAvailability,
+            opac::WidgetFormat,
+            opac::WidgetQuery,
+            opac::AvailabilityBatchQuery,
+            crate::models::biblio::IsbnAvailability,
+            crate::models::biblio::AvailabilityBatch,
             // Health
             health::HealthResponse,
             health::HealthDatabaseStatus,
@@ -406,6 +1073,9 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
 
             // Errors
             crate::error::ErrorResponse,
+            crate::error::InvalidFieldsResponse,
+            crate::error::ProblemDetails,
+            crate::error::FieldError,
         )
     ),
     tags(
@@ -416,13 +1086,37 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
         (name = "users", description = "User management"),
         (name = "loans", description = "Loan management"),
         (name = "holds", description = "Physical item hold queue"),
+        (name = "payments", description = "Cash register: payments for fines, memberships and sundries, refunds, daily cash-up"),
         (name = "inventory", description = "Stocktaking (inventory) sessions and barcode scans"),
         (name = "z3950", description = "Z39.50 catalog search"),
         (name = "stats", description = "Statistics"),
         (name = "visitor_counts", description = "Visitor counting"),
         (name = "schedules", description = "Library schedules (hours, closures)"),
+        (name = "staffing", description = "Staff and volunteer shifts, absences and iCal feeds"),
         (name = "sources", description = "Acquisition source management"),
+        (name = "vendors", description = "Suppliers, purchase orders and spend reporting"),
+        (name = "donations", description = "Donations intake, triage and donors report"),
+        (name = "purchase_suggestions", description = "Patron purchase suggestions, staff triage and availability notifications"),
+        (name = "reading_programs", description = "Reading programs: enrollments, reading log and participation statistics"),
         (name = "equipment", description = "Library equipment management"),
+        (name = "item_states", description = "Item state taxonomy (circulation status of copies)"),
+        (name = "locations", description = "Shelving map (site → room → shelf range) and copy location mapping assistant"),
+        (name = "media_types", description = "Media type taxonomy (labels, icons, default loan rules, MARC leader codes)"),
+        (name = "relators", description = "Author role vocabulary (MARC relator codes, labels, aliases recognised on import)"),
+        (name = "item_templates", description = "Cataloging templates for quick record creation"),
+        (name = "labels", description = "Translated display labels of media types, audiences, genres, account types and author roles"),
+        (name = "headings", description = "Genre and subject heading vocabularies and their assignment to records"),
+        (name = "authors", description = "Author display forms and batch normalization of author names"),
+        (name = "kiosks", description = "Catalog terminal tokens (OPAC read and patron self-service login)"),
+        (name = "api_keys", description = "API keys of partner applications (scopes, rate limit, usage)"),
+        (name = "snapshots", description = "Copies captured before bulk edits and their restore"),
+        (name = "backups", description = "Logical database backups (artifacts store or S3) restored with `elidune-server restore`"),
+        (name = "eod", description = "End-of-day job (returned loans archiving, daily statistics, pull list, staff digest) and its run log"),
+        (name = "reengagement", description = "Re-engagement emails suggesting new acquisitions to inactive members"),
+        (name = "harvest", description = "Scheduled harvest of union catalog delta files (HTTP, FTP, SFTP) and run reports"),
+        (name = "warehouse", description = "Nightly anonymized fact extracts (loans, acquisitions, visits) pushed to S3 or SFTP as CSV or Parquet"),
+        (name = "campaigns", description = "Email campaigns to patron segments (throttled sending, delivery and bounce counts)"),
+        (name = "artifacts", description = "Generated files (exports, import reports) downloaded through signed URLs"),
         (name = "events", description = "Cultural events and school visits"),
         (name = "account_types", description = "Library account types (guest, reader, librarian, admin, group) and per-domain rights"),
         (name = "library_info", description = "Library global information (name, address, phones, email)"),
@@ -433,7 +1127,8 @@ use crate::api::{account_types, admin_config, audit, auth, biblios, collections,
         (name = "admin", description = "Admin runtime configuration"),
         (name = "audit", description = "Audit log"),
         (name = "maintenance", description = "Data-quality maintenance operations (admin only)"),
-        (name = "tasks", description = "Background task status polling")
+        (name = "tasks", description = "Background task status polling"),
+        (name = "notifications", description = "In-app notifications inbox (task outcomes, import reports, overdue escalations)")
     ),
     modifiers(&SecurityAddon)
 )]
@@ -457,12 +1152,27 @@ impl Modify for SecurityAddon {
                         .build(),
                 ),
             );
+            components.add_security_scheme(
+                "kiosk_token",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                    "X-Kiosk-Token",
+                    "Kiosk token of a registered catalog terminal (see /settings/kiosks)",
+                ))),
+            );
+            components.add_security_scheme(
+                "api_key",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                    "X-Api-Key",
+                    "API key of a partner application (see /settings/api-keys)",
+                ))),
+            );
         }
     }
 }
 
-/// Create the OpenAPI documentation router
+/// Create the OpenAPI documentation router (Swagger UI, OpenAPI JSON and Postman collection)
 pub fn create_openapi_router() -> Router {
     Router::new()
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
+        .route("/openapi/collection", get(crate::api::postman::get_collection))
 }
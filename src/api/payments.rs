@@ -0,0 +1,265 @@
+//! Cash register endpoints (`/payments`): payments, refunds and daily cash-up
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Local;
+
+use crate::{
+    error::AppResult,
+    models::payment::{
+        DailyCashSummary, DailySummaryQuery, Payment, PaymentExportQuery, PaymentPeriodLock, PaymentQuery,
+        PaymentsPage, ReceiptGapReport, ReceiptGapsQuery, RecordPayment, RefundPayment,
+    },
+    services::audit,
+};
+
+use super::{AdminUser, ClientIp, StaffUser};
+
+/// Build the `/payments*` routes.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{delete, get, post};
+    axum::Router::new()
+        .route("/payments", get(list_payments).post(record_payment))
+        .route("/payments/daily-summary", get(get_daily_summary))
+        .route("/payments/export", get(export_payments))
+        .route("/payments/period-locks", get(list_period_locks))
+        .route("/payments/period-locks/:month", delete(unlock_period))
+        .route("/payments/receipt-gaps", get(get_receipt_gaps))
+        .route("/payments/:id", get(get_payment))
+        .route("/payments/:id/refund", post(refund_payment))
+}
+
+/// List payments and refunds, newest first
+#[utoipa::path(
+    get,
+    path = "/payments",
+    tag = "payments",
+    security(("bearer_auth" = [])),
+    params(PaymentQuery),
+    responses(
+        (status = 200, description = "Payments", body = PaymentsPage),
+        (status = 400, description = "Invalid filters", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Staff access required", body = ErrorResponse),
+    )
+)]
+pub async fn list_payments(
+    State(state): State<crate::AppState>,
+    StaffUser(_staff): StaffUser,
+    Query(query): Query<PaymentQuery>,
+) -> AppResult<Json<PaymentsPage>> {
+    let page = state.services.payments.list(&query).await?;
+    Ok(Json(page))
+}
+
+/// Get a payment
+#[utoipa::path(
+    get,
+    path = "/payments/{id}",
+    tag = "payments",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Payment ID")),
+    responses(
+        (status = 200, description = "Payment", body = Payment),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Staff access required", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_payment(
+    State(state): State<crate::AppState>,
+    StaffUser(_staff): StaffUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Payment>> {
+    let payment = state.services.payments.get(id).await?;
+    Ok(Json(payment))
+}
+
+/// Record a payment taken at the desk (fine, membership renewal, printing...)
+#[utoipa::path(
+    post,
+    path = "/payments",
+    tag = "payments",
+    security(("bearer_auth" = [])),
+    request_body = RecordPayment,
+    responses(
+        (status = 201, description = "Payment recorded", body = Payment),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Staff access required", body = ErrorResponse),
+        (status = 404, description = "User or fine not found", body = ErrorResponse),
+        (status = 422, description = "Fine already settled, or no subscription duration for a renewal", body = ErrorResponse),
+    )
+)]
+pub async fn record_payment(
+    State(state): State<crate::AppState>,
+    StaffUser(claims): StaffUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<RecordPayment>,
+) -> AppResult<(StatusCode, Json<Payment>)> {
+    let payment = state.services.payments.record(&data, claims.user_id).await?;
+    state.services.audit.log(audit::event::PAYMENT_RECORDED, Some(claims.user_id), Some("payment"), Some(payment.id), ip, Some(&payment), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(payment)))
+}
+
+/// Refund all or part of a payment (a refunded fine payment is owed again)
+#[utoipa::path(
+    post,
+    path = "/payments/{id}/refund",
+    tag = "payments",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Payment ID")),
+    request_body = RefundPayment,
+    responses(
+        (status = 201, description = "Refund recorded (negative amount)", body = Payment),
+        (status = 400, description = "Invalid amount or above what is left to refund", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Staff access required", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 422, description = "Already fully refunded, or a refund", body = ErrorResponse),
+    )
+)]
+pub async fn refund_payment(
+    State(state): State<crate::AppState>,
+    StaffUser(claims): StaffUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<RefundPayment>,
+) -> AppResult<(StatusCode, Json<Payment>)> {
+    let refund = state.services.payments.refund(id, &data, claims.user_id).await?;
+    state.services.audit.log(audit::event::PAYMENT_REFUNDED, Some(claims.user_id), Some("payment"), Some(id), ip, Some(&refund), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(refund)))
+}
+
+/// Daily cash-up: totals received and refunded by method, category and operator
+#[utoipa::path(
+    get,
+    path = "/payments/daily-summary",
+    tag = "payments",
+    security(("bearer_auth" = [])),
+    params(DailySummaryQuery),
+    responses(
+        (status = 200, description = "Totals of the day", body = DailyCashSummary),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Staff access required", body = ErrorResponse),
+    )
+)]
+pub async fn get_daily_summary(
+    State(state): State<crate::AppState>,
+    StaffUser(_staff): StaffUser,
+    Query(query): Query<DailySummaryQuery>,
+) -> AppResult<Json<DailyCashSummary>> {
+    let date = query.date.unwrap_or_else(|| Local::now().date_naive());
+    let summary = state.services.payments.daily_summary(date).await?;
+    Ok(Json(summary))
+}
+
+/// Accounting export of a finished month (CSV for the municipal accounting software).
+/// The first export locks the month: its payments can no longer be changed.
+#[utoipa::path(
+    get,
+    path = "/payments/export",
+    tag = "payments",
+    security(("bearer_auth" = [])),
+    params(PaymentExportQuery),
+    responses(
+        (status = 200, description = "`;`-separated CSV file, one line per receipt", content_type = "text/csv"),
+        (status = 400, description = "Invalid month", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Staff access required", body = ErrorResponse),
+        (status = 422, description = "Month not over, or receipt numbers missing", body = ErrorResponse),
+    )
+)]
+pub async fn export_payments(
+    State(state): State<crate::AppState>,
+    StaffUser(claims): StaffUser,
+    ClientIp(ip): ClientIp,
+    Query(query): Query<PaymentExportQuery>,
+) -> AppResult<Response> {
+    let (lock, csv) = state.services.payments.export_month(&query.month, claims.user_id).await?;
+    state.services.audit.log(audit::event::PAYMENT_PERIOD_EXPORTED, Some(claims.user_id), Some("payment_period"), None, ip, Some(&lock), audit::AuditLogMeta::success());
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"payments_{}.csv\"", lock.month),
+            ),
+        ],
+        csv,
+    )
+        .into_response())
+}
+
+/// Months exported to accounting, newest first
+#[utoipa::path(
+    get,
+    path = "/payments/period-locks",
+    tag = "payments",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Locked months", body = Vec<PaymentPeriodLock>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Staff access required", body = ErrorResponse),
+    )
+)]
+pub async fn list_period_locks(
+    State(state): State<crate::AppState>,
+    StaffUser(_staff): StaffUser,
+) -> AppResult<Json<Vec<PaymentPeriodLock>>> {
+    let locks = state.services.payments.period_locks().await?;
+    Ok(Json(locks))
+}
+
+/// Reopen an exported month, e.g. after an export rejected by accounting (admin only)
+#[utoipa::path(
+    delete,
+    path = "/payments/period-locks/{month}",
+    tag = "payments",
+    security(("bearer_auth" = [])),
+    params(("month" = String, Path, description = "Month (YYYY-MM)")),
+    responses(
+        (status = 204, description = "Month unlocked"),
+        (status = 400, description = "Invalid month", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Month not locked", body = ErrorResponse),
+    )
+)]
+pub async fn unlock_period(
+    State(state): State<crate::AppState>,
+    AdminUser(claims): AdminUser,
+    ClientIp(ip): ClientIp,
+    Path(month): Path<String>,
+) -> AppResult<StatusCode> {
+    state.services.payments.unlock_period(&month).await?;
+    state.services.audit.log(audit::event::PAYMENT_PERIOD_UNLOCKED, Some(claims.user_id), Some("payment_period"), None, ip, Some(&serde_json::json!({ "month": month })), audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Receipt numbers of a year missing from the sequence
+#[utoipa::path(
+    get,
+    path = "/payments/receipt-gaps",
+    tag = "payments",
+    security(("bearer_auth" = [])),
+    params(ReceiptGapsQuery),
+    responses(
+        (status = 200, description = "Sequence check of the year", body = ReceiptGapReport),
+        (status = 400, description = "Invalid year", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Staff access required", body = ErrorResponse),
+    )
+)]
+pub async fn get_receipt_gaps(
+    State(state): State<crate::AppState>,
+    StaffUser(_staff): StaffUser,
+    Query(query): Query<ReceiptGapsQuery>,
+) -> AppResult<Json<ReceiptGapReport>> {
+    let report = state.services.payments.receipt_gaps(query.year).await?;
+    Ok(Json(report))
+}
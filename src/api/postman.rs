@@ -0,0 +1,408 @@
+//! Postman collection (v2.1) built from the OpenAPI document
+//!
+//! The collection is derived from `ApiDoc` at runtime, so it always matches the documented routes.
+//! Bruno and Insomnia import the same format.
+
+use std::sync::LazyLock;
+
+use axum::{
+    http::{header, HeaderMap},
+    Json,
+};
+use serde_json::{json, Map, Value};
+use utoipa::OpenApi;
+
+use crate::api::openapi::ApiDoc;
+
+const SCHEMA_URL: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+const METHODS: &[&str] = &["get", "post", "put", "patch", "delete"];
+/// Depth at which nested schemas stop being expanded in body examples
+const MAX_EXAMPLE_DEPTH: usize = 4;
+
+static COLLECTION: LazyLock<Value> = LazyLock::new(|| {
+    let doc = serde_json::to_value(ApiDoc::openapi()).unwrap_or(Value::Null);
+    collection(&doc)
+});
+
+/// Postman collection of the documented operations, one folder per tag
+///
+/// `baseUrl` defaults to the host of the request and the first documented server (`/api/v2`).
+pub async fn get_collection(headers: HeaderMap) -> Json<Value> {
+    let mut collection = COLLECTION.clone();
+    if let Some(host) = headers.get(header::HOST).and_then(|h| h.to_str().ok()) {
+        let scheme = headers
+            .get("x-forwarded-proto")
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or("http");
+        if let Some(base_url) = collection
+            .pointer_mut("/variable/0/value")
+            .filter(|v| v.as_str().is_some_and(|s| s.starts_with('/')))
+        {
+            *base_url = json!(format!("{}://{}{}", scheme, host, base_url.as_str().unwrap_or_default()));
+        }
+    }
+    Json(collection)
+}
+
+/// Convert an OpenAPI 3 document (as JSON) into a Postman collection
+pub fn collection(doc: &Value) -> Value {
+    let server = doc
+        .pointer("/servers/0/url")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+
+    // Folders in the order of the document tags, then any undeclared tag
+    let mut folders: Vec<(String, Option<String>, Vec<Value>)> = doc["tags"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|t| {
+            let name = t["name"].as_str()?.to_string();
+            Some((name, t["description"].as_str().map(str::to_string), Vec::new()))
+        })
+        .collect();
+
+    let paths = doc["paths"].as_object().into_iter().flatten();
+    for (path, item) in paths {
+        for method in METHODS {
+            let Some(operation) = item.get(*method) else {
+                continue;
+            };
+            let tag = operation
+                .pointer("/tags/0")
+                .and_then(Value::as_str)
+                .unwrap_or("default")
+                .to_string();
+            let request = request_item(doc, path, method, operation);
+            match folders.iter_mut().find(|(name, _, _)| *name == tag) {
+                Some((_, _, items)) => items.push(request),
+                None => folders.push((tag, None, vec![request])),
+            }
+        }
+    }
+
+    let items: Vec<Value> = folders
+        .into_iter()
+        .filter(|(_, _, items)| !items.is_empty())
+        .map(|(name, description, item)| json!({ "name": name, "description": description, "item": item }))
+        .collect();
+
+    json!({
+        "info": {
+            "name": doc.pointer("/info/title").and_then(Value::as_str).unwrap_or("API"),
+            "description": doc.pointer("/info/description"),
+            "version": doc.pointer("/info/version"),
+            "schema": SCHEMA_URL,
+        },
+        "auth": bearer_auth(),
+        "variable": [
+            { "key": "baseUrl", "value": server, "type": "string" },
+            { "key": "token", "value": "", "type": "string", "description": "JWT returned by POST /auth/login" },
+            { "key": "kioskToken", "value": "", "type": "string", "description": "Token of a registered kiosk" },
+            { "key": "apiKey", "value": "", "type": "string", "description": "API key of a partner application" },
+        ],
+        "item": items,
+    })
+}
+
+fn bearer_auth() -> Value {
+    json!({ "type": "bearer", "bearer": [{ "key": "token", "value": "{{token}}", "type": "string" }] })
+}
+
+fn api_key_auth(header: &str, variable: &str) -> Value {
+    json!({
+        "type": "apikey",
+        "apikey": [
+            { "key": "key", "value": header, "type": "string" },
+            { "key": "value", "value": format!("{{{{{}}}}}", variable), "type": "string" },
+            { "key": "in", "value": "header", "type": "string" },
+        ],
+    })
+}
+
+/// Authentication of an operation: the first documented scheme, `noauth` for public operations
+fn operation_auth(operation: &Value) -> Value {
+    let scheme = operation["security"]
+        .as_array()
+        .and_then(|s| s.first())
+        .and_then(Value::as_object)
+        .and_then(|s| s.keys().next().cloned());
+    match scheme.as_deref() {
+        Some("bearer_auth") => bearer_auth(),
+        Some("kiosk_token") => api_key_auth("X-Kiosk-Token", "kioskToken"),
+        Some("api_key") => api_key_auth("X-Api-Key", "apiKey"),
+        _ => json!({ "type": "noauth" }),
+    }
+}
+
+fn request_item(doc: &Value, path: &str, method: &str, operation: &Value) -> Value {
+    let parameters: Vec<&Value> = operation["parameters"].as_array().into_iter().flatten().collect();
+    let by_location = |location: &'static str| parameters.iter().filter(move |p| p["in"] == location);
+
+    // `/items/{id}` becomes `/items/:id`, the Postman path variable syntax
+    let segments: Vec<String> = path
+        .trim_start_matches('/')
+        .split('/')
+        .map(|s| match s.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(name) => format!(":{}", name),
+            None => s.to_string(),
+        })
+        .collect();
+    let variables: Vec<Value> = by_location("path")
+        .map(|p| json!({ "key": p["name"], "value": "", "description": p["description"] }))
+        .collect();
+    // Optional query parameters are listed but disabled
+    let query: Vec<Value> = by_location("query")
+        .map(|p| {
+            json!({
+                "key": p["name"],
+                "value": "",
+                "description": p["description"],
+                "disabled": p["required"] != true,
+            })
+        })
+        .collect();
+    let enabled_query: Vec<String> = query
+        .iter()
+        .filter(|q| q["disabled"] == false)
+        .filter_map(|q| q["key"].as_str().map(|k| format!("{}=", k)))
+        .collect();
+    let mut raw = format!("{{{{baseUrl}}}}/{}", segments.join("/"));
+    if !enabled_query.is_empty() {
+        raw = format!("{}?{}", raw, enabled_query.join("&"));
+    }
+
+    let mut headers: Vec<Value> = by_location("header")
+        .map(|p| {
+            json!({
+                "key": p["name"],
+                "value": "",
+                "description": p["description"],
+                "disabled": p["required"] != true,
+            })
+        })
+        .collect();
+
+    let mut request = Map::new();
+    request.insert("method".into(), json!(method.to_uppercase()));
+    request.insert("auth".into(), operation_auth(operation));
+    if let Some(description) = operation.get("description") {
+        request.insert("description".into(), description.clone());
+    }
+    if let Some(body) = request_body(doc, operation, &mut headers) {
+        request.insert("body".into(), body);
+    }
+    request.insert("header".into(), json!(headers));
+    request.insert(
+        "url".into(),
+        json!({
+            "raw": raw,
+            "host": ["{{baseUrl}}"],
+            "path": segments,
+            "query": query,
+            "variable": variables,
+        }),
+    );
+
+    let name = operation["summary"]
+        .as_str()
+        .or_else(|| operation["operationId"].as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} {}", method.to_uppercase(), path));
+    json!({ "name": name, "request": request })
+}
+
+/// JSON body with an example built from the schema, or form fields for multipart uploads
+fn request_body(doc: &Value, operation: &Value, headers: &mut Vec<Value>) -> Option<Value> {
+    let content = operation.pointer("/requestBody/content")?.as_object()?;
+    if let Some(json_body) = content.get("application/json") {
+        headers.push(json!({ "key": "Content-Type", "value": "application/json" }));
+        let example = json_body
+            .get("example")
+            .cloned()
+            .unwrap_or_else(|| example(doc, &json_body["schema"], 0));
+        return Some(json!({
+            "mode": "raw",
+            "raw": serde_json::to_string_pretty(&example).unwrap_or_default(),
+            "options": { "raw": { "language": "json" } },
+        }));
+    }
+    if let Some(form) = content.get("multipart/form-data") {
+        let schema = resolve(doc, &form["schema"]);
+        let fields: Vec<Value> = schema["properties"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(name, property)| {
+                let property = resolve(doc, property);
+                if property["format"] == "binary" {
+                    json!({ "key": name, "type": "file", "src": [] })
+                } else {
+                    json!({ "key": name, "type": "text", "value": "" })
+                }
+            })
+            .collect();
+        return Some(json!({ "mode": "formdata", "formdata": fields }));
+    }
+    let (media_type, _) = content.iter().next()?;
+    headers.push(json!({ "key": "Content-Type", "value": media_type }));
+    Some(json!({ "mode": "raw", "raw": "" }))
+}
+
+/// Follow a `#/components/schemas/...` reference
+fn resolve<'a>(doc: &'a Value, schema: &'a Value) -> &'a Value {
+    match schema["$ref"].as_str().and_then(|r| r.strip_prefix('#')) {
+        Some(pointer) => doc.pointer(pointer).unwrap_or(schema),
+        None => schema,
+    }
+}
+
+/// Placeholder value matching a schema: documented examples, first enum value, or a zero value
+fn example(doc: &Value, schema: &Value, depth: usize) -> Value {
+    let schema = resolve(doc, schema);
+    if let Some(example) = schema.get("example").or_else(|| schema.get("default")) {
+        return example.clone();
+    }
+    if let Some(first) = schema["enum"].as_array().and_then(|e| e.first()) {
+        return first.clone();
+    }
+    for key in ["allOf", "oneOf", "anyOf"] {
+        if let Some(parts) = schema[key].as_array() {
+            let parts: Vec<&Value> = match key {
+                "allOf" => parts.iter().collect(),
+                // Variants: the first one that is not `null`
+                _ => parts.iter().filter(|p| p["type"] != "null").take(1).collect(),
+            };
+            let mut merged = Map::new();
+            for part in parts {
+                match example(doc, part, depth) {
+                    Value::Object(fields) => merged.extend(fields),
+                    other => return other,
+                }
+            }
+            return Value::Object(merged);
+        }
+    }
+    let kind = match &schema["type"] {
+        Value::Array(types) => types.iter().find(|t| *t != "null").cloned().unwrap_or(Value::Null),
+        kind => kind.clone(),
+    };
+    match kind.as_str() {
+        Some("object") | None if schema.get("properties").is_some() => {
+            if depth >= MAX_EXAMPLE_DEPTH {
+                return json!({});
+            }
+            let fields = schema["properties"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(name, property)| (name.clone(), example(doc, property, depth + 1)))
+                .collect();
+            Value::Object(fields)
+        }
+        Some("object") => json!({}),
+        Some("array") if depth < MAX_EXAMPLE_DEPTH => json!([example(doc, &schema["items"], depth + 1)]),
+        Some("array") => json!([]),
+        Some("string") => json!(""),
+        Some("integer") | Some("number") => json!(0),
+        Some("boolean") => json!(false),
+        _ => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Value {
+        json!({
+            "info": { "title": "Elidune API", "version": "1.0.0" },
+            "servers": [{ "url": "/api/v2" }],
+            "tags": [{ "name": "items", "description": "Items" }, { "name": "health" }],
+            "paths": {
+                "/items/{id}": {
+                    "get": {
+                        "tags": ["items"],
+                        "summary": "Get an item",
+                        "security": [{ "bearer_auth": [] }],
+                        "parameters": [
+                            { "name": "id", "in": "path", "required": true, "description": "Item ID" },
+                            { "name": "full", "in": "query", "required": false }
+                        ]
+                    },
+                    "put": {
+                        "tags": ["items"],
+                        "operationId": "update_item",
+                        "security": [{ "bearer_auth": [] }],
+                        "requestBody": { "content": { "application/json": {
+                            "schema": { "$ref": "#/components/schemas/Item" }
+                        } } }
+                    }
+                },
+                "/health": { "get": { "tags": ["health"], "summary": "Health" } },
+                "/opac/search": {
+                    "get": { "tags": ["opac"], "summary": "Search", "security": [{ "api_key": [] }] }
+                }
+            },
+            "components": { "schemas": { "Item": {
+                "type": "object",
+                "properties": {
+                    "barcode": { "type": "string" },
+                    "copies": { "type": "integer" },
+                    "status": { "type": "string", "enum": ["active", "archived"] },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "note": { "type": ["string", "null"] }
+                }
+            } } }
+        })
+    }
+
+    fn folder<'a>(collection: &'a Value, name: &str) -> &'a Value {
+        collection["item"].as_array().unwrap().iter().find(|f| f["name"] == name).unwrap()
+    }
+
+    #[test]
+    fn operations_are_grouped_by_tag_with_auth() {
+        let collection = collection(&sample());
+        assert_eq!(collection["info"]["schema"], SCHEMA_URL);
+        assert_eq!(collection["variable"][0]["value"], "/api/v2");
+        let names: Vec<&str> = collection["item"].as_array().unwrap().iter().map(|f| f["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["items", "health", "opac"]);
+
+        let get = &folder(&collection, "items")["item"][0]["request"];
+        assert_eq!(get["method"], "GET");
+        assert_eq!(get["auth"]["type"], "bearer");
+        assert_eq!(get["url"]["raw"], "{{baseUrl}}/items/:id");
+        assert_eq!(get["url"]["variable"][0]["key"], "id");
+        assert_eq!(get["url"]["query"][0]["disabled"], true);
+
+        assert_eq!(folder(&collection, "health")["item"][0]["request"]["auth"]["type"], "noauth");
+        let opac = &folder(&collection, "opac")["item"][0]["request"]["auth"];
+        assert_eq!((opac["type"].as_str(), opac["apikey"][1]["value"].as_str()), (Some("apikey"), Some("{{apiKey}}")));
+    }
+
+    #[test]
+    fn json_bodies_get_an_example_from_the_schema() {
+        let collection = collection(&sample());
+        let put = &folder(&collection, "items")["item"][1];
+        assert_eq!(put["name"], "update_item");
+        let body: Value = serde_json::from_str(put["request"]["body"]["raw"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            json!({ "barcode": "", "copies": 0, "status": "active", "tags": [""], "note": "" })
+        );
+    }
+
+    #[test]
+    fn every_documented_operation_is_exported() {
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let operations: usize = doc["paths"]
+            .as_object()
+            .unwrap()
+            .values()
+            .map(|item| METHODS.iter().filter(|m| item.get(**m).is_some()).count())
+            .sum();
+        let exported: usize = COLLECTION["item"].as_array().unwrap().iter().map(|f| f["item"].as_array().unwrap().len()).sum();
+        assert_eq!(exported, operations);
+    }
+}
@@ -46,7 +46,7 @@ pub async fn list_public_types(
     security(("bearer_auth" = [])),
     params(("id" = i64, Path, description = "Public type ID")),
     responses(
-        (status = 200, description = "Public type with loan settings"),
+        (status = 200, description = "Public type with loan settings", body = serde_json::Value),
         (status = 404, description = "Not found")
     )
 )]
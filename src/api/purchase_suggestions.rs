@@ -0,0 +1,155 @@
+//! Purchase suggestions API endpoints (patron suggestions and staff triage)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::purchase_suggestion::{
+        CreatePurchaseSuggestion, PurchaseSuggestion, PurchaseSuggestionQuery, TriagePurchaseSuggestion,
+    },
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp, SelfServiceUser};
+
+/// Build the purchase suggestions routes for this domain.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post};
+    axum::Router::new()
+        .route("/opac/suggestions", get(list_my_suggestions).post(create_suggestion))
+        .route("/suggestions", get(list_suggestions))
+        .route("/suggestions/:id", get(get_suggestion))
+        .route("/suggestions/:id/triage", post(triage_suggestion))
+}
+
+/// Suggest a title the library does not own
+#[utoipa::path(
+    post,
+    path = "/opac/suggestions",
+    tag = "purchase_suggestions",
+    security(("bearer_auth" = [])),
+    request_body = CreatePurchaseSuggestion,
+    responses(
+        (status = 201, description = "Suggestion recorded", body = PurchaseSuggestion),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 409, description = "Title already in the catalog, or already suggested", body = ErrorResponse),
+    )
+)]
+pub async fn create_suggestion(
+    State(state): State<crate::AppState>,
+    SelfServiceUser(claims): SelfServiceUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreatePurchaseSuggestion>,
+) -> AppResult<(StatusCode, Json<PurchaseSuggestion>)> {
+    let suggestion = state.services.purchase_suggestions.create(claims.user_id, &data).await?;
+    state.services.audit.log(audit::event::PURCHASE_SUGGESTION_CREATED, Some(claims.user_id), Some("purchase_suggestion"), Some(suggestion.id), ip, Some(&suggestion), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(suggestion)))
+}
+
+/// List the current patron's suggestions with their status
+#[utoipa::path(
+    get,
+    path = "/opac/suggestions",
+    tag = "purchase_suggestions",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Own suggestions", body = Vec<PurchaseSuggestion>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+    )
+)]
+pub async fn list_my_suggestions(
+    State(state): State<crate::AppState>,
+    SelfServiceUser(claims): SelfServiceUser,
+) -> AppResult<Json<Vec<PurchaseSuggestion>>> {
+    let query = PurchaseSuggestionQuery {
+        status: None,
+        user_id: Some(claims.user_id),
+    };
+    let suggestions = state.services.purchase_suggestions.list(&query).await?;
+    Ok(Json(suggestions))
+}
+
+/// List purchase suggestions for triage
+#[utoipa::path(
+    get,
+    path = "/suggestions",
+    tag = "purchase_suggestions",
+    security(("bearer_auth" = [])),
+    params(PurchaseSuggestionQuery),
+    responses(
+        (status = 200, description = "Suggestions", body = Vec<PurchaseSuggestion>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_suggestions(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<PurchaseSuggestionQuery>,
+) -> AppResult<Json<Vec<PurchaseSuggestion>>> {
+    claims.require_read_items()?;
+    let suggestions = state.services.purchase_suggestions.list(&query).await?;
+    Ok(Json(suggestions))
+}
+
+/// Get a purchase suggestion
+#[utoipa::path(
+    get,
+    path = "/suggestions/{id}",
+    tag = "purchase_suggestions",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Suggestion ID")),
+    responses(
+        (status = 200, description = "Suggestion", body = PurchaseSuggestion),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_suggestion(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<PurchaseSuggestion>> {
+    claims.require_read_items()?;
+    let suggestion = state.services.purchase_suggestions.get_by_id(id).await?;
+    Ok(Json(suggestion))
+}
+
+/// Triage a suggestion: ordered (optionally on a purchase order), rejected with a reason, added
+/// to the catalog, or back to pending
+///
+/// `added` emails the patron as soon as the linked record has a copy.
+#[utoipa::path(
+    post,
+    path = "/suggestions/{id}/triage",
+    tag = "purchase_suggestions",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Suggestion ID")),
+    request_body = TriagePurchaseSuggestion,
+    responses(
+        (status = 200, description = "Triage recorded", body = PurchaseSuggestion),
+        (status = 400, description = "Missing rejectionReason or biblioId", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Suggestion, purchase order or biblio not found", body = ErrorResponse),
+        (status = 409, description = "Suggestion already added", body = ErrorResponse),
+    )
+)]
+pub async fn triage_suggestion(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<TriagePurchaseSuggestion>,
+) -> AppResult<Json<PurchaseSuggestion>> {
+    claims.require_write_items()?;
+    let suggestion = state.services.purchase_suggestions.triage(id, &data, claims.user_id).await?;
+    state.services.audit.log(audit::event::PURCHASE_SUGGESTION_TRIAGED, Some(claims.user_id), Some("purchase_suggestion"), Some(id), ip, Some(&suggestion), audit::AuditLogMeta::success());
+    Ok(Json(suggestion))
+}
@@ -0,0 +1,448 @@
+//! Reading programs API endpoints (summer reading challenge)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::reading_program::{
+        CreateReadingEntry, CreateReadingProgram, EnrollReader, ReadingEnrollment, ReadingEntry,
+        ReadingLoansSyncReport, ReadingProgram, ReadingProgramStatsQuery,
+        UpdateReadingProgram,
+    },
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Build the reading programs routes for this domain.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{delete, get, post};
+    axum::Router::new()
+        .route("/reading-programs", get(list_reading_programs).post(create_reading_program))
+        .route(
+            "/reading-programs/:id",
+            get(get_reading_program).put(update_reading_program).delete(delete_reading_program),
+        )
+        .route("/reading-programs/:id/enrollments", get(list_enrollments).post(enroll_reader))
+        .route(
+            "/reading-programs/:id/enrollments/:enrollment_id",
+            get(get_enrollment).delete(unenroll_reader),
+        )
+        .route(
+            "/reading-programs/:id/enrollments/:enrollment_id/entries",
+            get(list_reading_entries).post(add_reading_entry),
+        )
+        .route(
+            "/reading-programs/:id/enrollments/:enrollment_id/entries/:entry_id",
+            delete(delete_reading_entry),
+        )
+        .route("/reading-programs/:id/sync-loans", post(sync_reading_loans))
+        .route("/reading-programs/:id/stats", get(get_reading_program_stats))
+}
+
+/// List reading programs
+#[utoipa::path(
+    get,
+    path = "/reading-programs",
+    tag = "reading_programs",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Reading programs", body = Vec<ReadingProgram>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_reading_programs(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<ReadingProgram>>> {
+    claims.require_read_events()?;
+    let programs = state.services.reading_programs.list().await?;
+    Ok(Json(programs))
+}
+
+/// Get a reading program
+#[utoipa::path(
+    get,
+    path = "/reading-programs/{id}",
+    tag = "reading_programs",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Reading program ID")),
+    responses(
+        (status = 200, description = "Reading program", body = ReadingProgram),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_reading_program(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<ReadingProgram>> {
+    claims.require_read_events()?;
+    let program = state.services.reading_programs.get(id).await?;
+    Ok(Json(program))
+}
+
+/// Create a reading program
+#[utoipa::path(
+    post,
+    path = "/reading-programs",
+    tag = "reading_programs",
+    security(("bearer_auth" = [])),
+    request_body = CreateReadingProgram,
+    responses(
+        (status = 201, description = "Reading program created", body = ReadingProgram),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn create_reading_program(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateReadingProgram>,
+) -> AppResult<(StatusCode, Json<ReadingProgram>)> {
+    claims.require_write_events()?;
+    let program = state.services.reading_programs.create(&data).await?;
+    state.services.audit.log(audit::event::READING_PROGRAM_CREATED, Some(claims.user_id), Some("reading_program"), Some(program.id), ip, Some(&program), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(program)))
+}
+
+/// Update a reading program (completion is re-evaluated for every enrollment)
+#[utoipa::path(
+    put,
+    path = "/reading-programs/{id}",
+    tag = "reading_programs",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Reading program ID")),
+    request_body = UpdateReadingProgram,
+    responses(
+        (status = 200, description = "Reading program updated", body = ReadingProgram),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_reading_program(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdateReadingProgram>,
+) -> AppResult<Json<ReadingProgram>> {
+    claims.require_write_events()?;
+    let program = state.services.reading_programs.update(id, &data).await?;
+    state.services.audit.log(audit::event::READING_PROGRAM_UPDATED, Some(claims.user_id), Some("reading_program"), Some(id), ip, Some(&data), audit::AuditLogMeta::success());
+    Ok(Json(program))
+}
+
+/// Delete a reading program with its enrollments and reading log
+#[utoipa::path(
+    delete,
+    path = "/reading-programs/{id}",
+    tag = "reading_programs",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Reading program ID")),
+    responses(
+        (status = 204, description = "Reading program deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_reading_program(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_events()?;
+    state.services.reading_programs.delete(id).await?;
+    state.services.audit.log(audit::event::READING_PROGRAM_DELETED, Some(claims.user_id), Some("reading_program"), Some(id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List enrolled patrons with their progress
+#[utoipa::path(
+    get,
+    path = "/reading-programs/{id}/enrollments",
+    tag = "reading_programs",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Reading program ID")),
+    responses(
+        (status = 200, description = "Enrollments", body = Vec<ReadingEnrollment>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Program not found", body = ErrorResponse),
+    )
+)]
+pub async fn list_enrollments(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Vec<ReadingEnrollment>>> {
+    claims.require_read_events()?;
+    let enrollments = state.services.reading_programs.enrollments(id).await?;
+    Ok(Json(enrollments))
+}
+
+/// Get an enrollment with its progress
+#[utoipa::path(
+    get,
+    path = "/reading-programs/{id}/enrollments/{enrollment_id}",
+    tag = "reading_programs",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "Reading program ID"),
+        ("enrollment_id" = i64, Path, description = "Enrollment ID"),
+    ),
+    responses(
+        (status = 200, description = "Enrollment", body = ReadingEnrollment),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_enrollment(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path((id, enrollment_id)): Path<(i64, i64)>,
+) -> AppResult<Json<ReadingEnrollment>> {
+    claims.require_read_events()?;
+    let enrollment = state.services.reading_programs.get_enrollment(id, enrollment_id).await?;
+    Ok(Json(enrollment))
+}
+
+/// Enroll a patron
+#[utoipa::path(
+    post,
+    path = "/reading-programs/{id}/enrollments",
+    tag = "reading_programs",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Reading program ID")),
+    request_body = EnrollReader,
+    responses(
+        (status = 201, description = "Patron enrolled", body = ReadingEnrollment),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Program or user not found", body = ErrorResponse),
+        (status = 409, description = "Already enrolled", body = ErrorResponse),
+    )
+)]
+pub async fn enroll_reader(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<EnrollReader>,
+) -> AppResult<(StatusCode, Json<ReadingEnrollment>)> {
+    claims.require_write_events()?;
+    let enrollment = state.services.reading_programs.enroll(id, data.user_id).await?;
+    state.services.audit.log(audit::event::READING_PROGRAM_ENROLLED, Some(claims.user_id), Some("reading_program"), Some(id), ip, Some(&data), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(enrollment)))
+}
+
+/// Remove a patron from a program (its reading log is deleted)
+#[utoipa::path(
+    delete,
+    path = "/reading-programs/{id}/enrollments/{enrollment_id}",
+    tag = "reading_programs",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "Reading program ID"),
+        ("enrollment_id" = i64, Path, description = "Enrollment ID"),
+    ),
+    responses(
+        (status = 204, description = "Enrollment removed"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn unenroll_reader(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path((id, enrollment_id)): Path<(i64, i64)>,
+) -> AppResult<StatusCode> {
+    claims.require_write_events()?;
+    state.services.reading_programs.unenroll(id, enrollment_id).await?;
+    state.services.audit.log(audit::event::READING_PROGRAM_UNENROLLED, Some(claims.user_id), Some("reading_program"), Some(id), ip, Some(serde_json::json!({ "enrollmentId": enrollment_id.to_string() })), audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Reading log of an enrollment
+#[utoipa::path(
+    get,
+    path = "/reading-programs/{id}/enrollments/{enrollment_id}/entries",
+    tag = "reading_programs",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "Reading program ID"),
+        ("enrollment_id" = i64, Path, description = "Enrollment ID"),
+    ),
+    responses(
+        (status = 200, description = "Reading entries", body = Vec<ReadingEntry>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn list_reading_entries(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path((id, enrollment_id)): Path<(i64, i64)>,
+) -> AppResult<Json<Vec<ReadingEntry>>> {
+    claims.require_read_events()?;
+    let entries = state.services.reading_programs.entries(id, enrollment_id).await?;
+    Ok(Json(entries))
+}
+
+/// Log a book read by an enrolled patron (free title, catalog record or returned loan)
+#[utoipa::path(
+    post,
+    path = "/reading-programs/{id}/enrollments/{enrollment_id}/entries",
+    tag = "reading_programs",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "Reading program ID"),
+        ("enrollment_id" = i64, Path, description = "Enrollment ID"),
+    ),
+    request_body = CreateReadingEntry,
+    responses(
+        (status = 201, description = "Entry logged", body = ReadingEntry),
+        (status = 400, description = "Invalid input or date outside the program", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Enrollment, record or loan not found", body = ErrorResponse),
+        (status = 409, description = "Loan already logged", body = ErrorResponse),
+        (status = 422, description = "Program only counts returned loans", body = ErrorResponse),
+    )
+)]
+pub async fn add_reading_entry(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path((id, enrollment_id)): Path<(i64, i64)>,
+    Json(data): Json<CreateReadingEntry>,
+) -> AppResult<(StatusCode, Json<ReadingEntry>)> {
+    claims.require_write_events()?;
+    let entry = state.services.reading_programs.add_entry(id, enrollment_id, &data).await?;
+    state.services.audit.log(audit::event::READING_ENTRY_CREATED, Some(claims.user_id), Some("reading_program"), Some(id), ip, Some(&entry), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+/// Delete a reading entry
+#[utoipa::path(
+    delete,
+    path = "/reading-programs/{id}/enrollments/{enrollment_id}/entries/{entry_id}",
+    tag = "reading_programs",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "Reading program ID"),
+        ("enrollment_id" = i64, Path, description = "Enrollment ID"),
+        ("entry_id" = i64, Path, description = "Reading entry ID"),
+    ),
+    responses(
+        (status = 204, description = "Entry deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_reading_entry(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path((id, enrollment_id, entry_id)): Path<(i64, i64, i64)>,
+) -> AppResult<StatusCode> {
+    claims.require_write_events()?;
+    state.services.reading_programs.delete_entry(id, enrollment_id, entry_id).await?;
+    state.services.audit.log(audit::event::READING_ENTRY_DELETED, Some(claims.user_id), Some("reading_program"), Some(id), ip, Some(serde_json::json!({ "enrollmentId": enrollment_id.to_string(), "entryId": entry_id.to_string() })), audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Log the loans enrolled patrons returned during the program (already logged loans are skipped)
+#[utoipa::path(
+    post,
+    path = "/reading-programs/{id}/sync-loans",
+    tag = "reading_programs",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Reading program ID")),
+    responses(
+        (status = 200, description = "Returned loans logged", body = ReadingLoansSyncReport),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Program not found", body = ErrorResponse),
+    )
+)]
+pub async fn sync_reading_loans(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<Json<ReadingLoansSyncReport>> {
+    claims.require_write_events()?;
+    let report = state.services.reading_programs.sync_loans(id).await?;
+    state.services.audit.log(audit::event::READING_PROGRAM_LOANS_SYNCED, Some(claims.user_id), Some("reading_program"), Some(id), ip, Some(&report), audit::AuditLogMeta::success());
+    Ok(Json(report))
+}
+
+/// Participation statistics by age bracket (JSON, or CSV with `format=csv`)
+#[utoipa::path(
+    get,
+    path = "/reading-programs/{id}/stats",
+    tag = "reading_programs",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Reading program ID"), ReadingProgramStatsQuery),
+    responses(
+        (status = 200, description = "Participation statistics", body = ReadingProgramStats),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Program not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_reading_program_stats(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+    Query(query): Query<ReadingProgramStatsQuery>,
+) -> AppResult<Response> {
+    claims.require_read_events()?;
+    let stats = state.services.reading_programs.stats(id).await?;
+
+    if query.format.as_deref() != Some("csv") {
+        return Ok(Json(stats).into_response());
+    }
+    let mut csv = String::from("age_bracket,enrolled,completed,books_read\n");
+    for row in &stats.by_age_bracket {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            row.age_bracket, row.enrolled, row.completed, row.books_read
+        ));
+    }
+    csv.push_str(&format!(
+        "total,{},{},{}\n",
+        stats.enrolled, stats.completed, stats.books_read
+    ));
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"reading_program_{}.csv\"", id),
+            ),
+        ],
+        csv,
+    )
+        .into_response())
+}
@@ -0,0 +1,74 @@
+//! Re-engagement email endpoints (`/reengagement`)
+
+use axum::{extract::State, http::StatusCode, Json};
+
+use crate::{
+    error::AppResult,
+    models::{reengagement::ReengagementPreview, task::TaskKind},
+};
+
+use super::{tasks::TaskAcceptedResponse, AuthenticatedUser};
+
+/// Build the `/reengagement` routes (staff managing members).
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post};
+    axum::Router::new()
+        .route("/reengagement/preview", get(preview_reengagement))
+        .route("/reengagement/run", post(run_reengagement))
+}
+
+/// Members the next run would email
+///
+/// Inactive members (no loan for `reengagement.inactive_days`, nothing on loan) with an email,
+/// opted in to campaigns and not emailed within `reengagement.resend_after_days`. A run emails
+/// `maxPerRun` of them at most, and only those with a new title in their preferred media types.
+#[utoipa::path(
+    get,
+    path = "/reengagement/preview",
+    tag = "reengagement",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Members selected", body = ReengagementPreview),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+    )
+)]
+pub async fn preview_reengagement(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<ReengagementPreview>> {
+    claims.require_read_users()?;
+    Ok(Json(state.services.reengagement.preview().await?))
+}
+
+/// Send the re-engagement emails now, in the background
+///
+/// Same selection as the scheduled job (`[reengagement]` configuration), whether or not it is
+/// enabled. Poll `GET /tasks/{taskId}` for the `ReengagementReport`.
+#[utoipa::path(
+    post,
+    path = "/reengagement/run",
+    tag = "reengagement",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 202, description = "Run started; poll GET /tasks/:id", body = TaskAcceptedResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+    )
+)]
+pub async fn run_reengagement(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<(StatusCode, Json<TaskAcceptedResponse>)> {
+    claims.require_write_users()?;
+    let service = state.services.reengagement.clone();
+    let user_id = claims.user_id;
+    let task_id = state.services.tasks.spawn_task(TaskKind::Reengagement, user_id, move |handle| async move {
+        match service.run(Some(user_id), Some(handle.clone())).await {
+            Ok(report) => handle.complete(serde_json::to_value(&report).unwrap_or_default()).await,
+            Err(e) => handle.fail(e.to_string()).await,
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(TaskAcceptedResponse { task_id })))
+}
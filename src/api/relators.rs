@@ -0,0 +1,163 @@
+//! Relator vocabulary API endpoints (`/settings/relators`)
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::relator::{CreateRelator, RelatorDefinition, UpdateRelator},
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Build the `/settings/relators*` routes.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new()
+        .route("/settings/relators", get(list_relators).post(create_relator))
+        .route(
+            "/settings/relators/:code",
+            get(get_relator).put(update_relator).delete(delete_relator),
+        )
+}
+
+/// List relators (values allowed in an author's `function`)
+#[utoipa::path(
+    get,
+    path = "/settings/relators",
+    tag = "relators",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Relators", body = Vec<RelatorDefinition>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_relators(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<RelatorDefinition>>> {
+    claims.require_read_items()?;
+    let relators = state.services.relators.list().await?;
+    Ok(Json(relators))
+}
+
+/// Get a relator by code
+#[utoipa::path(
+    get,
+    path = "/settings/relators/{code}",
+    tag = "relators",
+    security(("bearer_auth" = [])),
+    params(("code" = String, Path, description = "Relator code")),
+    responses(
+        (status = 200, description = "Relator", body = RelatorDefinition),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_relator(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(code): Path<String>,
+) -> AppResult<Json<RelatorDefinition>> {
+    claims.require_read_items()?;
+    let relator = state.services.relators.get(&code).await?;
+    Ok(Json(relator))
+}
+
+/// Create a relator
+#[utoipa::path(
+    post,
+    path = "/settings/relators",
+    tag = "relators",
+    security(("bearer_auth" = [])),
+    request_body = CreateRelator,
+    responses(
+        (status = 201, description = "Relator created", body = RelatorDefinition),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 409, description = "Code, MARC code or UNIMARC code already used", body = ErrorResponse),
+    )
+)]
+pub async fn create_relator(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateRelator>,
+) -> AppResult<(StatusCode, Json<RelatorDefinition>)> {
+    claims.require_write_settings()?;
+    let relator = state.services.relators.create(&data).await?;
+    state.services.audit.log(audit::event::RELATOR_CREATED, Some(claims.user_id), Some("relator"), None, ip, Some(&relator), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(relator)))
+}
+
+/// Update a relator (MARC codes, label, aliases)
+#[utoipa::path(
+    put,
+    path = "/settings/relators/{code}",
+    tag = "relators",
+    security(("bearer_auth" = [])),
+    params(("code" = String, Path, description = "Relator code")),
+    request_body = UpdateRelator,
+    responses(
+        (status = 200, description = "Relator updated", body = RelatorDefinition),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "MARC or UNIMARC code already used", body = ErrorResponse),
+    )
+)]
+pub async fn update_relator(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(code): Path<String>,
+    Json(data): Json<UpdateRelator>,
+) -> AppResult<Json<RelatorDefinition>> {
+    claims.require_write_settings()?;
+    let relator = state.services.relators.update(&code, &data).await?;
+    state.services.audit.log(audit::event::RELATOR_UPDATED, Some(claims.user_id), Some("relator"), None, ip, Some((&data, &relator)), audit::AuditLogMeta::success());
+    Ok(Json(relator))
+}
+
+/// Delete a relator (built-in codes and codes used by author credits cannot be deleted)
+#[utoipa::path(
+    delete,
+    path = "/settings/relators/{code}",
+    tag = "relators",
+    security(("bearer_auth" = [])),
+    params(("code" = String, Path, description = "Relator code")),
+    responses(
+        (status = 204, description = "Relator deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Built-in or in use", body = ErrorResponse),
+    )
+)]
+pub async fn delete_relator(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(code): Path<String>,
+) -> AppResult<StatusCode> {
+    claims.require_write_settings()?;
+    state.services.relators.delete(&code).await?;
+    state.services.audit.log(
+        audit::event::RELATOR_DELETED,
+        Some(claims.user_id),
+        Some("relator"),
+        None,
+        ip,
+        Some(serde_json::json!({ "code": code })),
+        audit::AuditLogMeta::success(),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
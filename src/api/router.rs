@@ -0,0 +1,300 @@
+//! Application router: every domain router under each API version, plus rate limits and CORS
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use axum::{
+    extract::ConnectInfo,
+    http::Request,
+    middleware,
+    routing::get,
+    Router,
+};
+use tower_governor::{
+    governor::GovernorConfigBuilder, key_extractor::KeyExtractor, GovernorError, GovernorLayer,
+};
+use tower_http::trace::TraceLayer;
+
+use crate::{api, config::AppConfig, AppState};
+
+/// Create the application router with all routes.
+///
+/// Each domain's routes are registered in its own `api::<domain>::router()` function.
+/// This only merges them under every API version (see [`api::versioning`]) and applies
+/// middleware (rate limits, demo mode masking, problem+json errors, deprecation headers, CORS,
+/// tracing).
+pub fn create_router(state: AppState) -> Router {
+    let cors = build_cors(&state.config);
+
+    // Rate-limit auth endpoints: burst of 2, replenish 1 per 4s by default (secure preset).
+    let per_second = state.config.server.auth_rate_per_second.unwrap_or(4);
+    let burst_size = state.config.server.auth_rate_burst.unwrap_or(2);
+    // Box::leak gives a `'static` reference; the config lives for the entire process lifetime.
+    let governor_conf: &'static _ = Box::leak(Box::new(
+        GovernorConfigBuilder::default()
+            .per_second(per_second)
+            .burst_size(burst_size)
+            .finish()
+            .expect("Failed to build auth rate-limit configuration"),
+    ));
+
+    // Public anonymous APIs (OPAC, covers, library-info GET): separate quota from auth.
+    let public_per_second = state.config.server.public_rate_per_second.unwrap_or(30);
+    let public_burst = state.config.server.public_rate_burst.unwrap_or(100);
+    let public_governor_conf: &'static _ = Box::leak(Box::new(
+        GovernorConfigBuilder::default()
+            .per_second(public_per_second)
+            .burst_size(public_burst)
+            .finish()
+            .expect("Failed to build public rate-limit configuration"),
+    ));
+
+    // Embeddable availability widget: hit from third-party pages, so it gets its own quota.
+    let widget_per_second = state.config.server.widget_rate_per_second.unwrap_or(10);
+    let widget_burst = state.config.server.widget_rate_burst.unwrap_or(50);
+    let widget_governor_conf: &'static _ = Box::leak(Box::new(
+        GovernorConfigBuilder::default()
+            .per_second(widget_per_second)
+            .burst_size(widget_burst)
+            .finish()
+            .expect("Failed to build widget rate-limit configuration"),
+    ));
+
+    // Partner API (bulk availability): one quota per API key, whatever address the app calls from.
+    let api_key_per_second = state.config.server.api_key_rate_per_second.unwrap_or(20);
+    let api_key_burst = state.config.server.api_key_rate_burst.unwrap_or(200);
+    let api_key_governor_conf: &'static _ = Box::leak(Box::new(
+        GovernorConfigBuilder::default()
+            .key_extractor(ApiKeyOrIpKeyExtractor)
+            .per_second(api_key_per_second)
+            .burst_size(api_key_burst)
+            .finish()
+            .expect("Failed to build API key rate-limit configuration"),
+    ));
+
+    // Barcode + PIN login: short PINs, so its own quota, kept apart from password login.
+    let barcode_per_second = state.config.server.barcode_login_rate_per_second.unwrap_or(2);
+    let barcode_burst = state.config.server.barcode_login_rate_burst.unwrap_or(5);
+    let barcode_governor_conf: &'static _ = Box::leak(Box::new(
+        GovernorConfigBuilder::default()
+            .per_second(barcode_per_second)
+            .burst_size(barcode_burst)
+            .finish()
+            .expect("Failed to build barcode login rate-limit configuration"),
+    ));
+
+    // Periodically evict expired entries to bound memory usage (auth + barcode + public + widget + API key limiters).
+    let auth_limiter = governor_conf.limiter().clone();
+    let barcode_limiter = barcode_governor_conf.limiter().clone();
+    let public_limiter = public_governor_conf.limiter().clone();
+    let widget_limiter = widget_governor_conf.limiter().clone();
+    let api_key_limiter = api_key_governor_conf.limiter().clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(60));
+        auth_limiter.retain_recent();
+        barcode_limiter.retain_recent();
+        public_limiter.retain_recent();
+        widget_limiter.retain_recent();
+        api_key_limiter.retain_recent();
+    });
+
+    let auth_router = api::auth::router()
+        .layer(GovernorLayer { config: governor_conf });
+
+    let barcode_login_router = api::auth::router_barcode().layer(GovernorLayer {
+        config: barcode_governor_conf,
+    });
+
+    // OpenAPI documentation (unauthenticated; no governor — see plan).
+    let openapi = api::openapi::create_openapi_router();
+
+    // OPAC, sitemap, covers, Atom feeds, opening hours, translated labels, library-info GET, kiosk session,
+    // signed artifact downloads — rate-limited per IP.
+    let public_router = Router::new()
+        .merge(api::opac::router())
+        .merge(api::feeds::router())
+        .merge(api::sitemap::router_public())
+        .merge(api::covers::router())
+        .merge(api::library_info::router_public())
+        .merge(api::schedules::router_public())
+        .merge(api::staffing::router_public())
+        .merge(api::labels::router_public())
+        .merge(api::kiosks::router_kiosk())
+        .merge(api::artifacts::router_download())
+        .merge(api::lockers::router_webhook())
+        .merge(api::email_health::router_webhook())
+        .layer(GovernorLayer {
+            config: public_governor_conf,
+        });
+
+    let widget_router = api::opac::router_widget().layer(GovernorLayer {
+        config: widget_governor_conf,
+    });
+
+    let partner_router = api::opac::router_partner().layer(GovernorLayer {
+        config: api_key_governor_conf,
+    });
+
+    let api_routes = Router::new()
+        .merge(api::health::router())
+        .merge(api::first_setup::router())
+        .merge(auth_router)
+        .merge(barcode_login_router)
+        .merge(public_router)
+        .merge(widget_router)
+        .merge(partner_router)
+        .merge(api::biblios::router())
+        .merge(api::items::router())
+        .merge(api::users::router())
+        .merge(api::user_messages::router())
+        .merge(api::dashboards::router())
+        .merge(api::notifications::router())
+        .merge(api::loans::router())
+        .merge(api::loan_batches::router())
+        .merge(api::bundles::router())
+        .merge(api::deposits::router())
+        .merge(api::batch::router())
+        .merge(api::holds::router())
+        .merge(api::lockers::router())
+        .merge(api::fines::router())
+        .merge(api::payments::router())
+        .merge(api::inventory::router())
+        .merge(api::sse::router())
+        .merge(api::z3950::router())
+        .merge(api::stats::router())
+        .merge(api::library_info::router_staff())
+        .merge(api::email_templates::router())
+        .merge(api::admin_config::router())
+        .merge(api::audit::router())
+        .merge(api::artifacts::router())
+        .merge(api::public_types::router())
+        .merge(api::visitor_counts::router())
+        .merge(api::schedules::router())
+        .merge(api::staffing::router())
+        .merge(api::series::router())
+        .merge(api::collections::router())
+        .merge(api::sources::router())
+        .merge(api::equipment::router())
+        .merge(api::item_states::router())
+        .merge(api::locations::router())
+        .merge(api::media_types::router())
+        .merge(api::relators::router())
+        .merge(api::item_templates::router())
+        .merge(api::labels::router())
+        .merge(api::headings::router())
+        .merge(api::authors::router())
+        .merge(api::kiosks::router())
+        .merge(api::api_keys::router())
+        .merge(api::harvest::router())
+        .merge(api::warehouse::router())
+        .merge(api::campaigns::router())
+        .merge(api::email_health::router())
+        .merge(api::vendors::router())
+        .merge(api::donations::router())
+        .merge(api::purchase_suggestions::router())
+        .merge(api::reading_programs::router())
+        .merge(api::events::router())
+        .merge(api::account_types::router())
+        .merge(api::maintenance::router())
+        .merge(api::snapshots::router())
+        .merge(api::backups::router())
+        .merge(api::eod::router())
+        .merge(api::reengagement::router())
+        .merge(api::sitemap::router())
+        .merge(api::tasks::router());
+    #[cfg(feature = "graphql")]
+    let api_routes = api_routes.merge(api::graphql::router(state.services.clone()));
+    let api_routes = api_routes.with_state(state.clone());
+
+    let deprecations = Arc::new(api::versioning::Deprecations::from_config(&state.config.api));
+    let router = Router::new().route("/version", get(api::health::version));
+    let mut router = api::versioning::nest_versions(router, api_routes, &state.config.api);
+    if state.config.demo.enabled {
+        tracing::warn!("Demo mode: patron data is masked in API responses and file exports are refused");
+        router = router.layer(middleware::from_fn(api::demo::demo_mode));
+    }
+    router
+        .merge(openapi)
+        .layer(middleware::from_fn(crate::error::problem_json))
+        .layer(middleware::from_fn_with_state(deprecations, api::versioning::deprecation_headers))
+        .layer(TraceLayer::new_for_http())
+        .layer(cors)
+}
+
+/// Rate-limit key of the partner API: the `X-Api-Key` header, or the peer address when it is absent
+/// (those requests are rejected by the handler, but must not share one bucket).
+#[derive(Debug, Clone, Copy)]
+struct ApiKeyOrIpKeyExtractor;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ApiKeyOrIp {
+    ApiKey(String),
+    Ip(IpAddr),
+}
+
+impl KeyExtractor for ApiKeyOrIpKeyExtractor {
+    type Key = ApiKeyOrIp;
+
+    fn extract<T>(&self, req: &Request<T>) -> Result<Self::Key, GovernorError> {
+        if let Some(token) = req
+            .headers()
+            .get(crate::models::api_key::API_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+        {
+            return Ok(ApiKeyOrIp::ApiKey(token.to_string()));
+        }
+        req.extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| ApiKeyOrIp::Ip(addr.ip()))
+            .ok_or(GovernorError::UnableToExtractKey)
+    }
+}
+
+/// Build the CORS layer from configuration.
+///
+/// In production (`server.cors_origins` is set), only the listed origins are allowed.
+/// When the list is empty or the field is absent, CORS falls back to `Any` (dev mode).
+fn build_cors(config: &AppConfig) -> tower_http::cors::CorsLayer {
+    use tower_http::cors::{Any, CorsLayer};
+    use axum::http::HeaderValue;
+
+    if let Some(ref origins) = config.server.cors_origins {
+        if !origins.is_empty() {
+            let parsed: Vec<HeaderValue> = origins
+                .iter()
+                .filter_map(|o| o.parse().ok())
+                .collect();
+            if !parsed.is_empty() {
+                return CorsLayer::new()
+                    .allow_origin(parsed)
+                    .allow_methods(Any)
+                    .allow_headers(Any)
+                    .expose_headers(exposed_headers());
+            }
+        }
+    }
+
+    // Permissive default for development / unconfigured deployments.
+    CorsLayer::new()
+        .allow_origin(Any)
+        .allow_methods(Any)
+        .allow_headers(Any)
+        .expose_headers(exposed_headers())
+}
+
+/// Response headers browser clients may read: the deprecation notices and the demo mode flag
+fn exposed_headers() -> [axum::http::HeaderName; 4] {
+    use axum::http::HeaderName;
+
+    [
+        HeaderName::from_static(api::versioning::DEPRECATION_HEADER),
+        HeaderName::from_static(api::versioning::SUNSET_HEADER),
+        axum::http::header::LINK,
+        HeaderName::from_static(api::demo::DEMO_MODE_HEADER),
+    ]
+}
@@ -5,16 +5,17 @@ use axum::{
     http::StatusCode,
     Json,
 };
-use chrono::NaiveDate;
+use chrono::{Local, NaiveDate};
 use serde_json::json;
 
 use crate::{
-    error::AppResult,
+    error::{AppError, AppResult},
     models::schedule::{
         CreateScheduleClosure, CreateSchedulePeriod, CreateScheduleSlot,
-        ScheduleClosure, ScheduleClosureQuery, SchedulePeriod, ScheduleSlot,
-        UpdateSchedulePeriod,
+        ScheduleClosure, ScheduleClosureCreated, ScheduleClosureQuery, SchedulePeriod, ScheduleSlot, ScheduleStatus,
+        UpdateSchedulePeriod, WeekSchedule, WeekScheduleQuery,
     },
+    models::task::TaskKind,
     services::audit,
 };
 
@@ -33,6 +34,14 @@ pub fn router() -> axum::Router<crate::AppState> {
         .route("/schedules/closures/:id", delete(delete_closure))
 }
 
+/// Resolved timetable (OPAC footer, phone answering system) — rate-limited per IP.
+pub fn router_public() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new()
+        .route("/schedules/status", get(get_status))
+        .route("/schedules/week", get(get_week))
+}
+
 
 // ---- Periods ----
 
@@ -251,6 +260,10 @@ pub async fn list_closures(
 }
 
 /// Create a closure
+///
+/// With `extendDueDates: true`, loans due on the closure day are moved to the first open day
+/// and their borrowers are emailed by a background task; poll `GET /tasks/{extensionTaskId}`
+/// for the `ClosureExtensionReport`.
 #[utoipa::path(
     post,
     path = "/schedules/closures",
@@ -258,7 +271,7 @@ pub async fn list_closures(
     security(("bearer_auth" = [])),
     request_body = CreateScheduleClosure,
     responses(
-        (status = 201, description = "Closure created", body = ScheduleClosure),
+        (status = 201, description = "Closure created", body = ScheduleClosureCreated),
         (status = 400, description = "Bad request", body = ErrorResponse),
         (status = 401, description = "Not authenticated", body = ErrorResponse),
         (status = 403, description = "Insufficient permissions", body = ErrorResponse),
@@ -270,11 +283,47 @@ pub async fn create_closure(
     AuthenticatedUser(claims): AuthenticatedUser,
     ClientIp(ip): ClientIp,
     Json(data): Json<CreateScheduleClosure>,
-) -> AppResult<(StatusCode, Json<ScheduleClosure>)> {
+) -> AppResult<(StatusCode, Json<ScheduleClosureCreated>)> {
     claims.require_write_settings()?;
     let closure = state.services.schedules.create_closure(&data).await?;
     state.services.audit.log(audit::event::SCHEDULE_CLOSURE_CREATED, Some(claims.user_id), Some("schedule_closure"), Some(closure.id), ip, Some((&data, &closure)), audit::AuditLogMeta::success());
-    Ok((StatusCode::CREATED, Json(closure)))
+
+    let extension_task_id = if data.extend_due_dates {
+        let schedules = state.services.schedules.clone();
+        let reminders = state.services.reminders.clone();
+        let user_id = claims.user_id;
+        let closure_id = closure.id;
+        let closure_date = closure.closure_date;
+        let reason = closure.reason.clone();
+
+        Some(state.services.tasks.spawn_task(
+            TaskKind::ClosureDueDateExtension,
+            user_id,
+            move |handle| async move {
+                let result = async {
+                    let new_due_date = schedules.first_open_day_after(closure_date).await?;
+                    reminders
+                        .extend_due_dates_for_closure(
+                            closure_id,
+                            closure_date,
+                            reason.as_deref(),
+                            new_due_date,
+                            Some(user_id),
+                        )
+                        .await
+                }
+                .await;
+                match result {
+                    Ok(report) => handle.complete(serde_json::to_value(&report).unwrap_or_default()).await,
+                    Err(e) => handle.fail(e.to_string()).await,
+                }
+            },
+        ))
+    } else {
+        None
+    };
+
+    Ok((StatusCode::CREATED, Json(ScheduleClosureCreated { closure, extension_task_id })))
 }
 
 /// Delete a closure
@@ -304,3 +353,43 @@ pub async fn delete_closure(
     Ok(StatusCode::NO_CONTENT)
 }
 
+
+// ---- Resolved timetable ----
+
+/// Current opening status and next opening time
+#[utoipa::path(
+    get,
+    path = "/schedules/status",
+    tag = "schedules",
+    responses(
+        (status = 200, description = "Opening status (library local time)", body = ScheduleStatus),
+    )
+)]
+pub async fn get_status(State(state): State<crate::AppState>) -> AppResult<Json<ScheduleStatus>> {
+    let status = state.services.schedules.status(Local::now().naive_local()).await?;
+    Ok(Json(status))
+}
+
+/// Weekly timetable with closures applied
+#[utoipa::path(
+    get,
+    path = "/schedules/week",
+    tag = "schedules",
+    params(WeekScheduleQuery),
+    responses(
+        (status = 200, description = "Resolved Monday–Sunday timetable", body = WeekSchedule),
+        (status = 400, description = "Invalid date", body = ErrorResponse),
+    )
+)]
+pub async fn get_week(
+    State(state): State<crate::AppState>,
+    Query(query): Query<WeekScheduleQuery>,
+) -> AppResult<Json<WeekSchedule>> {
+    let date = match query.date.as_deref() {
+        Some(s) => NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .map_err(|_| AppError::Validation("Invalid date (use YYYY-MM-DD)".to_string()))?,
+        None => Local::now().date_naive(),
+    };
+    let week = state.services.schedules.week(date).await?;
+    Ok(Json(week))
+}
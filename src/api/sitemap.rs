@@ -0,0 +1,116 @@
+//! OPAC sitemap for search engines (`/sitemap.xml`), and its manual regeneration
+
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::{sitemap::SitemapFile, task::TaskKind},
+    services::sitemap::SITEMAP_FILE,
+};
+
+use super::{tasks::TaskAcceptedResponse, AuthenticatedUser};
+
+/// Regenerated once a day: crawlers and proxies may keep a copy for a day, and serve it a
+/// little longer while refetching it.
+const SITEMAP_CACHE_CONTROL: &str = "public, max-age=86400, stale-while-revalidate=3600";
+
+/// Public sitemap files — mounted with the OPAC rate limiter.
+pub fn router_public() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new()
+        .route("/sitemap.xml", get(get_sitemap))
+        .route("/sitemaps/:file", get(get_sitemap_shard))
+}
+
+/// Manual regeneration (admin).
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::post;
+    axum::Router::new().route("/admin/sitemap", post(generate_sitemap))
+}
+
+/// Sitemap of the OPAC record pages (public)
+///
+/// A sitemap index of `/sitemaps/sitemap-{n}.xml` shards when the catalog has more records than
+/// `sitemap.urls_per_file`. Reference it from the OPAC's `robots.txt` (`Sitemap: <url>`).
+#[utoipa::path(
+    get,
+    path = "/sitemap.xml",
+    tag = "opac",
+    responses(
+        (status = 200, description = "Sitemap or sitemap index", content_type = "application/xml", body = String),
+        (status = 404, description = "Not generated yet", body = ErrorResponse),
+    )
+)]
+pub async fn get_sitemap(State(state): State<crate::AppState>) -> AppResult<Response> {
+    let file = state.services.sitemap.get_file(SITEMAP_FILE).await?;
+    Ok(xml_response(file))
+}
+
+/// Shard of a sharded sitemap (public)
+#[utoipa::path(
+    get,
+    path = "/sitemaps/{file}",
+    tag = "opac",
+    params(("file" = String, Path, description = "Shard name, e.g. `sitemap-1.xml`")),
+    responses(
+        (status = 200, description = "Sitemap shard", content_type = "application/xml", body = String),
+        (status = 404, description = "No such shard", body = ErrorResponse),
+    )
+)]
+pub async fn get_sitemap_shard(
+    State(state): State<crate::AppState>,
+    Path(file): Path<String>,
+) -> AppResult<Response> {
+    let file = state.services.sitemap.get_file(&file).await?;
+    Ok(xml_response(file))
+}
+
+/// Regenerate the sitemap now, in the background
+///
+/// Same job as the scheduled one (`[sitemap]` configuration), whether or not it is enabled.
+/// Poll `GET /tasks/{taskId}` for the `SitemapReport`.
+#[utoipa::path(
+    post,
+    path = "/admin/sitemap",
+    tag = "opac",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 202, description = "Generation started; poll GET /tasks/:id", body = TaskAcceptedResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+    )
+)]
+pub async fn generate_sitemap(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<(StatusCode, Json<TaskAcceptedResponse>)> {
+    claims.require_admin()?;
+    let service = state.services.sitemap.clone();
+    let user_id = claims.user_id;
+    let task_id = state.services.tasks.spawn_task(TaskKind::Sitemap, user_id, move |handle| async move {
+        match service.generate(Some(user_id)).await {
+            Ok(report) => handle.complete(serde_json::to_value(&report).unwrap_or_default()).await,
+            Err(e) => handle.fail(e.to_string()).await,
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(TaskAcceptedResponse { task_id })))
+}
+
+fn xml_response(file: SitemapFile) -> Response {
+    let last_modified = file.generated_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+    (
+        [
+            (header::CONTENT_TYPE, "application/xml; charset=utf-8".to_string()),
+            (header::CACHE_CONTROL, SITEMAP_CACHE_CONTROL.to_string()),
+            (header::LAST_MODIFIED, last_modified),
+        ],
+        file.content,
+    )
+        .into_response()
+}
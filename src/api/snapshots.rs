@@ -0,0 +1,105 @@
+//! Catalog snapshot endpoints (`/admin/snapshots`)
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::snapshot::{CatalogSnapshot, SnapshotRestoreReport},
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Build the `/admin/snapshots*` routes (administrators).
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post};
+    axum::Router::new()
+        .route("/admin/snapshots", get(list_snapshots))
+        .route("/admin/snapshots/:id", get(get_snapshot))
+        .route("/admin/snapshots/:id/restore", post(restore_snapshot))
+}
+
+/// List catalog snapshots, newest first
+#[utoipa::path(
+    get,
+    path = "/admin/snapshots",
+    tag = "snapshots",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Snapshots", body = Vec<CatalogSnapshot>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+    )
+)]
+pub async fn list_snapshots(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<CatalogSnapshot>>> {
+    claims.require_admin()?;
+    let snapshots = state.services.snapshots.list().await?;
+    Ok(Json(snapshots))
+}
+
+/// Get a catalog snapshot
+#[utoipa::path(
+    get,
+    path = "/admin/snapshots/{id}",
+    tag = "snapshots",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Snapshot ID")),
+    responses(
+        (status = 200, description = "Snapshot", body = CatalogSnapshot),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_snapshot(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<CatalogSnapshot>> {
+    claims.require_admin()?;
+    let snapshot = state.services.snapshots.get(id).await?;
+    Ok(Json(snapshot))
+}
+
+/// Restore the copies of a snapshot as they were before the bulk edit
+///
+/// Copies deleted since are counted as missing. A snapshot is restored only once.
+#[utoipa::path(
+    post,
+    path = "/admin/snapshots/{id}/restore",
+    tag = "snapshots",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Snapshot ID")),
+    responses(
+        (status = 200, description = "Copies restored", body = SnapshotRestoreReport),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Already restored, or a captured barcode is now taken", body = ErrorResponse),
+    )
+)]
+pub async fn restore_snapshot(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<Json<SnapshotRestoreReport>> {
+    claims.require_admin()?;
+    let report = state.services.snapshots.restore(id, claims.user_id).await?;
+    state.services.audit.log(
+        audit::event::SNAPSHOT_RESTORED,
+        Some(claims.user_id),
+        Some("snapshot"),
+        Some(id),
+        ip,
+        Some(&report),
+        audit::AuditLogMeta::success(),
+    );
+    Ok(Json(report))
+}
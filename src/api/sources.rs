@@ -5,13 +5,16 @@ use axum::{
     http::StatusCode,
     Json,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 use crate::services::audit;
 
 use crate::{
     error::AppResult,
-    models::source::{CreateSource, MergeSources, Source, UpdateSource},
+    models::source::{
+        CreateSource, DeleteSourceQuery, MergeSources, SetSourceVendor, Source,
+        SourceDeletionReport, SourceStats, UpdateSource,
+    },
 };
 
 use super::{AuthenticatedUser, ClientIp};
@@ -24,12 +27,14 @@ pub fn router() -> axum::Router<crate::AppState> {
     axum::Router::new()
         .route("/sources", get(list_sources).post(create_source))
         .route("/sources/merge", post(merge_sources))
-        .route("/sources/:id", get(get_source).put(update_source))
+        .route("/sources/:id", get(get_source).put(update_source).delete(delete_source))
+        .route("/sources/:id/stats", get(get_source_stats))
+        .route("/sources/:id/vendor", put(set_source_vendor))
         .route("/sources/:id/archive", post(archive_source))
 }
 
 /// Query parameters for listing sources
-#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SourcesQuery {
     /// Include archived sources (default: false)
@@ -119,7 +124,7 @@ pub async fn get_source(
 
 /// Update a source (name and/or default status)
 #[utoipa::path(
-    post,
+    put,
     path = "/sources/{id}",
     tag = "sources",
     security(("bearer_auth" = [])),
@@ -192,7 +197,96 @@ pub async fn merge_sources(
     Json(data): Json<MergeSources>,
 ) -> AppResult<(StatusCode, Json<Source>)> {
     claims.require_write_items()?;
-    let source = state.services.sources.merge(&data).await?;
+    let source = state.services.sources.merge(&data, claims.user_id).await?;
     state.services.audit.log(audit::event::SOURCE_MERGED, Some(claims.user_id), Some("source"), Some(source.id), ip, Some((&data, &source)), audit::AuditLogMeta::success());
     Ok((StatusCode::CREATED, Json(source)))
 }
+
+/// Link a source to a vendor, or clear the link
+#[utoipa::path(
+    put,
+    path = "/sources/{id}/vendor",
+    tag = "sources",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Source ID")),
+    request_body = SetSourceVendor,
+    responses(
+        (status = 200, description = "Source updated", body = Source),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Source or vendor not found", body = ErrorResponse),
+    )
+)]
+pub async fn set_source_vendor(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<SetSourceVendor>,
+) -> AppResult<Json<Source>> {
+    claims.require_write_items()?;
+    let source = state.services.sources.set_vendor(id, data.vendor_id).await?;
+    state.services.audit.log(audit::event::SOURCE_UPDATED, Some(claims.user_id), Some("source"), Some(id), ip, Some((id, &data, &source)), audit::AuditLogMeta::success());
+    Ok(Json(source))
+}
+
+/// Usage statistics for a source (items, biblios, loans by year)
+#[utoipa::path(
+    get,
+    path = "/sources/{id}/stats",
+    tag = "sources",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Source ID")),
+    responses(
+        (status = 200, description = "Source statistics", body = SourceStats),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_source_stats(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<SourceStats>> {
+    claims.require_read_items()?;
+    let stats = state.services.sources.stats(id).await?;
+    Ok(Json(stats))
+}
+
+/// Delete a source, reassigning its items and biblios to a target source
+///
+/// Use `dryRun=true` to get the number of rows that would move without changing anything.
+#[utoipa::path(
+    delete,
+    path = "/sources/{id}",
+    tag = "sources",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Source ID"), DeleteSourceQuery),
+    responses(
+        (status = 200, description = "Source deleted (or dry-run report)", body = SourceDeletionReport),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Source or target not found", body = ErrorResponse),
+        (status = 422, description = "Target source is archived", body = ErrorResponse),
+    )
+)]
+pub async fn delete_source(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Query(query): Query<DeleteSourceQuery>,
+) -> AppResult<Json<SourceDeletionReport>> {
+    claims.require_write_items()?;
+    let report = state
+        .services
+        .sources
+        .delete(id, query.target_source_id, query.dry_run)
+        .await?;
+    if !report.dry_run {
+        state.services.audit.log(audit::event::SOURCE_DELETED, Some(claims.user_id), Some("source"), Some(id), ip, Some(&report), audit::AuditLogMeta::success());
+    }
+    Ok(Json(report))
+}
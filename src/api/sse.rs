@@ -3,9 +3,13 @@
 //! Clients subscribe with a valid JWT token. The server pushes events
 //! (loan created, item returned, hold ready) as they happen.
 //!
-//! Architecture: a tokio broadcast channel is held in AppState. All handlers
-//! that create/modify loans or holds publish to the channel. SSE
-//! subscribers receive a filtered stream.
+//! Each message is an [`EventEnvelope`] (`type`, `version`, `occurredAt`, `payload`); see
+//! [`crate::models::event_envelope`] for the versioning policy.
+//!
+//! Architecture: handlers that create/modify loans or holds call [`publish`], which sends the
+//! event on the Redis [`EVENTS_CHANNEL`]. Every instance relays that channel into the tokio
+//! broadcast channel held in AppState (see `main`), so clients receive the events of all
+//! instances behind the load balancer. SSE subscribers receive a filtered stream.
 
 use axum::{
     extract::State,
@@ -14,37 +18,188 @@ use axum::{
         IntoResponse,
     },
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::StreamExt;
 
-
+use crate::{
+    models::{
+        event_envelope::{sse_version, EventEnvelope},
+        hold::Hold,
+        loan::LoanDetails,
+        notification::Notification,
+    },
+    services::redis::EVENTS_CHANNEL,
+    AppState,
+};
 
 use super::AuthenticatedUser;
 
-/// Payload for SSE events
-#[derive(Debug, Clone, Serialize)]
+/// Event delivered to SSE subscribers
+pub type SseEvent = EventEnvelope<SsePayload>;
+
+/// Payload of SSE events
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SsePayload {
-    pub event: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub loan_id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub user_id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub item_id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub hold_id: Option<String>,
+    /// Circulation counters, on `dashboard.counters` events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counters: Option<DashboardCounters>,
+    /// New notification, on `notification.created` events (delivered to its recipient only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notification: Option<Box<Notification>>,
+    /// Unread notifications of the recipient, on `notification.created` events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unread: Option<i64>,
+}
+
+/// Live circulation counters of the staff dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardCounters {
+    pub active_loans: i64,
+    pub overdue_loans: i64,
+}
+
+impl SsePayload {
+    /// `event` occurring now, at the version registered for its type
+    pub fn event(event: &str, payload: Self) -> SseEvent {
+        // Types missing from the registry are caught by the `events` contract test
+        let version = sse_version(event).unwrap_or(1);
+        EventEnvelope::new(event, version, chrono::Utc::now(), payload)
+    }
+
+    /// Loan event (`loan.created`, `loan.returned`, `loan.renewed`)
+    pub fn loan(event: &str, loan_id: i64, user_id: Option<i64>, item_id: Option<i64>) -> SseEvent {
+        Self::event(
+            event,
+            Self {
+                loan_id: Some(loan_id.to_string()),
+                user_id: user_id.map(|id| id.to_string()),
+                item_id: item_id.map(|id| id.to_string()),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// `loan.returned` for a returned loan
+    pub fn loan_returned(loan: &LoanDetails) -> SseEvent {
+        Self::loan(
+            "loan.returned",
+            loan.id,
+            loan.user.as_ref().map(|u| u.id),
+            loan.item_id,
+        )
+    }
+
+    /// Hold event (`hold.created`, `hold.ready`, `hold.inLocker`, `hold.pickedUp`, `hold.expired`,
+    /// `hold.cancelled`)
+    pub fn hold(event: &str, hold: &Hold) -> SseEvent {
+        Self::event(
+            event,
+            Self {
+                user_id: Some(hold.user_id.to_string()),
+                item_id: Some(hold.item_id.to_string()),
+                hold_id: Some(hold.id.to_string()),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// `notification.created` for a new notification and the recipient's unread count
+    pub fn notification_created(notification: &Notification, unread: i64) -> SseEvent {
+        Self::event(
+            "notification.created",
+            Self {
+                user_id: Some(notification.user_id.to_string()),
+                notification: Some(Box::new(notification.clone())),
+                unread: Some(unread),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Whether `user_id` may receive this event: notifications go to their recipient only
+    pub fn visible_to(event: &SseEvent, user_id: i64) -> bool {
+        if !event.event_type.starts_with("notification.") {
+            return true;
+        }
+        event.payload.user_id.as_deref() == Some(user_id.to_string().as_str())
+    }
+}
+
+/// Deliver `event` to the SSE subscribers of every instance, in the background.
+///
+/// Falls back to this instance's subscribers when Redis is unreachable or when no instance
+/// relays the channel yet (publishing reached nobody).
+pub fn publish(state: &AppState, event: SseEvent) {
+    let redis = state.services.redis.clone();
+    let local = state.event_bus.clone();
+    tokio::spawn(async move {
+        match redis.publish(EVENTS_CHANNEL, &event).await {
+            Ok(receivers) if receivers > 0 => {}
+            Ok(_) => drop(local.send(event)),
+            Err(e) => {
+                tracing::warn!("SSE event delivered locally only: {}", e);
+                drop(local.send(event));
+            }
+        }
+    });
+}
+
+/// Publish fresh `dashboard.counters` after a circulation change
+pub fn publish_counters(state: &AppState) {
+    let state = state.clone();
+    tokio::spawn(async move {
+        let counters = async {
+            Ok::<_, crate::AppError>(DashboardCounters {
+                active_loans: state.services.loans.count_active().await?,
+                overdue_loans: state.services.loans.count_overdue().await?,
+            })
+        };
+        match counters.await {
+            Ok(counters) => publish(
+                &state,
+                SsePayload::event(
+                    "dashboard.counters",
+                    SsePayload {
+                        counters: Some(counters),
+                        ..Default::default()
+                    },
+                ),
+            ),
+            Err(e) => tracing::warn!("Failed to compute dashboard counters: {}", e),
+        }
+    });
 }
 
 /// Subscribe to real-time library events
 ///
 /// Returns a Server-Sent Events stream. Auth via `Authorization: Bearer <token>` header.
+/// The SSE event name is the event type; the data is the versioned envelope
+/// `{ "type", "version", "occurredAt", "payload" }`.
 ///
 /// **Event types published:**
 /// - `loan.created` — a new loan was created
 /// - `loan.returned` — a specimen was returned
 /// - `loan.renewed` — a loan was renewed
+/// - `hold.created` — a hold was placed
 /// - `hold.ready` — a hold is ready for pickup
+/// - `hold.inLocker` — a hold was placed in a pickup locker
+/// - `hold.pickedUp` / `hold.expired` — a locker compartment was opened / its pickup window lapsed
+/// - `hold.cancelled` — a hold was cancelled
+/// - `dashboard.counters` — active / overdue loan counts changed (`counters`)
+/// - `notification.created` — a notification reached the caller's inbox (`notification`, `unread`);
+///   only sent to its recipient
+///
+/// Events from every server instance are delivered, whichever instance the client is connected to.
 #[utoipa::path(
     get,
     path = "/events/stream",
@@ -57,15 +212,16 @@ pub struct SsePayload {
 )]
 pub async fn sse_stream(
     State(state): State<crate::AppState>,
-    AuthenticatedUser(_claims): AuthenticatedUser,
+    AuthenticatedUser(claims): AuthenticatedUser,
 ) -> impl IntoResponse {
     let rx = state.event_bus.subscribe();
-    let stream = BroadcastStream::new(rx).filter_map(|msg| {
-        msg.ok().map(|payload: SsePayload| {
-            let data = serde_json::to_string(&payload).unwrap_or_default();
+    let user_id = claims.user_id;
+    let stream = BroadcastStream::new(rx).filter_map(move |msg| {
+        msg.ok().filter(|event| SsePayload::visible_to(event, user_id)).map(|event: SseEvent| {
+            let data = serde_json::to_string(&event).unwrap_or_default();
             Ok::<_, std::convert::Infallible>(
                 Event::default()
-                    .event(payload.event.clone())
+                    .event(event.event_type.clone())
                     .data(data),
             )
         })
@@ -78,3 +234,21 @@ pub fn router() -> axum::Router<crate::AppState> {
     use axum::routing::get;
     axum::Router::new().route("/events/stream", get(sse_stream))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notifications_reach_their_recipient_only() {
+        let notification = SsePayload::event(
+            "notification.created",
+            SsePayload { user_id: Some("12".to_string()), ..Default::default() },
+        );
+        assert!(SsePayload::visible_to(&notification, 12));
+        assert!(!SsePayload::visible_to(&notification, 13));
+
+        let loan = SsePayload::loan("loan.created", 1, Some(12), Some(3));
+        assert!(SsePayload::visible_to(&loan, 13));
+    }
+}
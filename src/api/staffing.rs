@@ -0,0 +1,411 @@
+//! Staffing API endpoints: shift templates on opening slots, roster, assignments, absences and
+//! per-person iCal feeds
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::{
+    error::AppResult,
+    models::staffing::{
+        CreateShiftAssignment, CreateStaffAbsence, RosterShift, ShiftAssignment, ShiftTemplate,
+        ShiftTemplateFields, ShiftTemplateQuery, StaffAbsence, StaffAbsenceCreated, StaffAbsenceQuery,
+        StaffCalendarToken, StaffingRangeQuery,
+    },
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+const CALENDAR_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+/// Build the authenticated `/staffing*` routes.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{delete, get, post};
+    axum::Router::new()
+        .route("/staffing/shift-templates", get(list_shift_templates).post(create_shift_template))
+        .route(
+            "/staffing/shift-templates/:id",
+            get(get_shift_template).put(update_shift_template).delete(delete_shift_template),
+        )
+        .route("/staffing/roster", get(get_roster))
+        .route("/staffing/assignments", post(create_assignment))
+        .route("/staffing/assignments/:id", delete(delete_assignment))
+        .route("/staffing/absences", get(list_absences).post(create_absence))
+        .route("/staffing/absences/:id", delete(delete_absence))
+        .route("/staffing/me/shifts", get(my_shifts))
+        .route("/staffing/me/calendar-token", post(regenerate_calendar_token))
+}
+
+/// Build the public iCal feed route (authenticated by the secret token in the path).
+pub fn router_public() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new().route("/staffing/calendar/:token", get(get_calendar))
+}
+
+/// List shift templates
+#[utoipa::path(
+    get,
+    path = "/staffing/shift-templates",
+    tag = "staffing",
+    security(("bearer_auth" = [])),
+    params(ShiftTemplateQuery),
+    responses(
+        (status = 200, description = "Shift templates", body = Vec<ShiftTemplate>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_shift_templates(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<ShiftTemplateQuery>,
+) -> AppResult<Json<Vec<ShiftTemplate>>> {
+    claims.require_staff()?;
+    let templates = state.services.staffing.list_templates(query.period_id).await?;
+    Ok(Json(templates))
+}
+
+/// Get a shift template
+#[utoipa::path(
+    get,
+    path = "/staffing/shift-templates/{id}",
+    tag = "staffing",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Shift template ID")),
+    responses(
+        (status = 200, description = "Shift template", body = ShiftTemplate),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_shift_template(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<ShiftTemplate>> {
+    claims.require_staff()?;
+    let template = state.services.staffing.get_template(id).await?;
+    Ok(Json(template))
+}
+
+/// Create a shift template on an opening slot
+#[utoipa::path(
+    post,
+    path = "/staffing/shift-templates",
+    tag = "staffing",
+    security(("bearer_auth" = [])),
+    request_body = ShiftTemplateFields,
+    responses(
+        (status = 201, description = "Shift template created", body = ShiftTemplate),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Slot not found", body = ErrorResponse),
+    )
+)]
+pub async fn create_shift_template(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<ShiftTemplateFields>,
+) -> AppResult<(StatusCode, Json<ShiftTemplate>)> {
+    claims.require_write_settings()?;
+    let template = state.services.staffing.create_template(&data).await?;
+    state.services.audit.log(audit::event::SHIFT_TEMPLATE_CREATED, Some(claims.user_id), Some("shift_template"), Some(template.id), ip, Some(&template), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(template)))
+}
+
+/// Replace a shift template (omitted fields are cleared)
+#[utoipa::path(
+    put,
+    path = "/staffing/shift-templates/{id}",
+    tag = "staffing",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Shift template ID")),
+    request_body = ShiftTemplateFields,
+    responses(
+        (status = 200, description = "Shift template updated", body = ShiftTemplate),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_shift_template(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<ShiftTemplateFields>,
+) -> AppResult<Json<ShiftTemplate>> {
+    claims.require_write_settings()?;
+    let template = state.services.staffing.update_template(id, &data).await?;
+    state.services.audit.log(audit::event::SHIFT_TEMPLATE_UPDATED, Some(claims.user_id), Some("shift_template"), Some(id), ip, Some(&template), audit::AuditLogMeta::success());
+    Ok(Json(template))
+}
+
+/// Delete a shift template and its assignments
+#[utoipa::path(
+    delete,
+    path = "/staffing/shift-templates/{id}",
+    tag = "staffing",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Shift template ID")),
+    responses(
+        (status = 204, description = "Shift template deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_shift_template(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_settings()?;
+    let template = state.services.staffing.get_template(id).await?;
+    state.services.staffing.delete_template(id).await?;
+    state.services.audit.log(audit::event::SHIFT_TEMPLATE_DELETED, Some(claims.user_id), Some("shift_template"), Some(id), ip, Some(&template), audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Roster: every shift of the range with its assignees and understaffing flag
+#[utoipa::path(
+    get,
+    path = "/staffing/roster",
+    tag = "staffing",
+    security(("bearer_auth" = [])),
+    params(StaffingRangeQuery),
+    responses(
+        (status = 200, description = "Shifts by day and time", body = Vec<RosterShift>),
+        (status = 400, description = "Invalid range", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn get_roster(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<StaffingRangeQuery>,
+) -> AppResult<Json<Vec<RosterShift>>> {
+    claims.require_staff()?;
+    let roster = state.services.staffing.roster(query.start, query.end).await?;
+    Ok(Json(roster))
+}
+
+/// Assign a user to a shift
+#[utoipa::path(
+    post,
+    path = "/staffing/assignments",
+    tag = "staffing",
+    security(("bearer_auth" = [])),
+    request_body = CreateShiftAssignment,
+    responses(
+        (status = 201, description = "User assigned", body = ShiftAssignment),
+        (status = 400, description = "No such shift that day", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Template or user not found", body = ErrorResponse),
+        (status = 409, description = "Already assigned, absent or overlapping shift", body = ErrorResponse),
+    )
+)]
+pub async fn create_assignment(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateShiftAssignment>,
+) -> AppResult<(StatusCode, Json<ShiftAssignment>)> {
+    claims.require_write_settings()?;
+    let assignment = state.services.staffing.assign(&data, claims.user_id).await?;
+    state.services.audit.log(audit::event::SHIFT_ASSIGNED, Some(claims.user_id), Some("shift_assignment"), Some(assignment.id), ip, Some(&assignment), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(assignment)))
+}
+
+/// Remove a user from a shift
+#[utoipa::path(
+    delete,
+    path = "/staffing/assignments/{id}",
+    tag = "staffing",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Assignment ID")),
+    responses(
+        (status = 204, description = "Assignment removed"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_assignment(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_settings()?;
+    let assignment = state.services.staffing.get_assignment(id).await?;
+    state.services.staffing.unassign(id).await?;
+    state.services.audit.log(audit::event::SHIFT_UNASSIGNED, Some(claims.user_id), Some("shift_assignment"), Some(id), ip, Some(&assignment), audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List absences overlapping a range (staff, or one's own with `userId`)
+#[utoipa::path(
+    get,
+    path = "/staffing/absences",
+    tag = "staffing",
+    security(("bearer_auth" = [])),
+    params(StaffAbsenceQuery),
+    responses(
+        (status = 200, description = "Absences", body = Vec<StaffAbsence>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_absences(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<StaffAbsenceQuery>,
+) -> AppResult<Json<Vec<StaffAbsence>>> {
+    match query.user_id {
+        Some(user_id) => claims.require_self_or_staff(user_id)?,
+        None => claims.require_staff()?,
+    }
+    let absences = state.services.staffing.list_absences(&query).await?;
+    Ok(Json(absences))
+}
+
+/// Record an absence for oneself (or anyone, with settings write rights)
+#[utoipa::path(
+    post,
+    path = "/staffing/absences",
+    tag = "staffing",
+    security(("bearer_auth" = [])),
+    request_body = CreateStaffAbsence,
+    responses(
+        (status = 201, description = "Absence recorded, with the shifts left uncovered", body = StaffAbsenceCreated),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+    )
+)]
+pub async fn create_absence(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateStaffAbsence>,
+) -> AppResult<(StatusCode, Json<StaffAbsenceCreated>)> {
+    let user_id = data.user_id.unwrap_or(claims.user_id);
+    if user_id != claims.user_id {
+        claims.require_write_settings()?;
+    }
+    let created = state.services.staffing.create_absence(user_id, &data, claims.user_id).await?;
+    state.services.audit.log(audit::event::STAFF_ABSENCE_CREATED, Some(claims.user_id), Some("staff_absence"), Some(created.absence.id), ip, Some(&created.absence), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Delete an absence (one's own, or anyone's with settings write rights)
+#[utoipa::path(
+    delete,
+    path = "/staffing/absences/{id}",
+    tag = "staffing",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Absence ID")),
+    responses(
+        (status = 204, description = "Absence deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_absence(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    let absence = state.services.staffing.get_absence(id).await?;
+    if absence.user_id != claims.user_id {
+        claims.require_write_settings()?;
+    }
+    state.services.staffing.delete_absence(id).await?;
+    state.services.audit.log(audit::event::STAFF_ABSENCE_DELETED, Some(claims.user_id), Some("staff_absence"), Some(id), ip, Some(&absence), audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Shifts of the current user in a range
+#[utoipa::path(
+    get,
+    path = "/staffing/me/shifts",
+    tag = "staffing",
+    security(("bearer_auth" = [])),
+    params(StaffingRangeQuery),
+    responses(
+        (status = 200, description = "Assignments of the caller", body = Vec<ShiftAssignment>),
+        (status = 400, description = "Invalid range", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+    )
+)]
+pub async fn my_shifts(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<StaffingRangeQuery>,
+) -> AppResult<Json<Vec<ShiftAssignment>>> {
+    let shifts = state.services.staffing.user_shifts(claims.user_id, query.start, query.end).await?;
+    Ok(Json(shifts))
+}
+
+/// Create (or replace) the secret URL of the caller's iCal feed
+#[utoipa::path(
+    post,
+    path = "/staffing/me/calendar-token",
+    tag = "staffing",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "New feed URL (the previous one stops working)", body = StaffCalendarToken),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+    )
+)]
+pub async fn regenerate_calendar_token(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+) -> AppResult<Json<StaffCalendarToken>> {
+    let token = state.services.staffing.regenerate_calendar_token(claims.user_id).await?;
+    state.services.audit.log(audit::event::STAFF_CALENDAR_TOKEN_REGENERATED, Some(claims.user_id), Some("user"), Some(claims.user_id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(Json(token))
+}
+
+/// iCal feed of a person's shifts (the `.ics` suffix is optional)
+#[utoipa::path(
+    get,
+    path = "/staffing/calendar/{token}",
+    tag = "staffing",
+    params(("token" = String, Path, description = "Secret feed token, optionally followed by .ics")),
+    responses(
+        (status = 200, description = "iCalendar document", content_type = "text/calendar"),
+        (status = 404, description = "Unknown calendar", body = ErrorResponse),
+    )
+)]
+pub async fn get_calendar(
+    State(state): State<crate::AppState>,
+    Path(token): Path<String>,
+) -> AppResult<Response> {
+    let token = token.strip_suffix(".ics").unwrap_or(&token);
+    let body = state.services.staffing.calendar(token).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, CALENDAR_CONTENT_TYPE),
+            (header::CACHE_CONTROL, "private, max-age=300"),
+        ],
+        body,
+    )
+        .into_response())
+}
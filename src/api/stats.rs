@@ -6,6 +6,7 @@ use axum::response::IntoResponse;
 use axum::routing::{get, post, put};
 use axum::{extract::Query, extract::State, Json, Router};
 use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use utoipa::{IntoParams, ToSchema};
@@ -13,12 +14,22 @@ use utoipa::{IntoParams, ToSchema};
 use crate::{
     error::AppResult,
     models::biblio::MediaType,
-    models::stats_builder::{SavedStatsQuery, SavedStatsQueryWrite, StatsBuilderBody},
-    services::stats::{discovery_json, run_stats_query},
-    repository::stats::saved_queries,
+    models::item::ItemAccessType,
+    models::label::{LabelKind, LabelSet},
+    models::stats_builder::{
+        ReportDefinition, ReportFormat, ReportTable, SavedReport, SavedReportWrite,
+        SavedStatsQuery, SavedStatsQueryWrite, StatsBuilderBody,
+    },
+    models::reengagement::{ReengagementStats, ReengagementStatsQuery},
+    models::visitor_count::{VisitorStats, VisitorStatsQuery},
+    services::stats::{
+        comparison, discovery_json, report_csv, report_schema_json, report_xlsx, run_report,
+        run_stats_query, validate_report,
+    },
+    repository::stats::{saved_queries, saved_reports},
 };
 
-use super::{AuthenticatedUser, StaffUser};
+use super::{AuthenticatedUser, Locale, StaffUser};
 
 
 /// Build the stats routes for this domain (staff/authenticated; no IP governor — see public API layer in `main.rs`).
@@ -28,6 +39,10 @@ pub fn router() -> axum::Router<crate::AppState> {
         .route("/stats/loans", get(get_loan_stats))
         .route("/stats/users", get(get_user_stats))
         .route("/stats/catalog", get(get_catalog_stats))
+        .route("/stats/visitors", get(get_visitor_stats))
+        .route("/stats/acquisitions", get(get_acquisition_stats))
+        .route("/stats/catalog-quality", get(get_catalog_quality_stats))
+        .route("/stats/reengagement", get(get_reengagement_stats))
         .route("/stats/schema", get(get_stats_schema))
         .route("/stats/query", post(post_stats_query))
         .route(
@@ -39,10 +54,20 @@ pub fn router() -> axum::Router<crate::AppState> {
             put(update_saved_query).delete(delete_saved_query),
         )
         .route("/stats/saved/:id/run", get(run_saved_query))
+        .route("/stats/reports/schema", get(get_report_schema))
+        .route("/stats/reports/run", post(post_run_report))
+        .route("/stats/reports", get(list_saved_reports).post(create_saved_report))
+        .route(
+            "/stats/reports/:id",
+            put(update_saved_report).delete(delete_saved_report),
+        )
+        .route("/stats/reports/:id/run", get(run_saved_report))
 }
 
 /// Statistics response
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+#[cfg_attr(feature = "graphql", graphql(name = "Stats"))]
 #[serde(rename_all = "camelCase")]
 pub struct StatsResponse {
     /// Item statistics
@@ -51,9 +76,18 @@ pub struct StatsResponse {
     pub users: UserStats,
     /// Loan statistics
     pub loans: LoanStats,
+    /// Same statistics one year earlier (only with compare_to)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub previous: Option<Box<StatsResponse>>,
+    /// Item counts compared with `previous` (only with compare_to; users and loans are live counts)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub deltas: Option<Vec<StatDelta>>,
 }
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 #[serde(rename_all = "camelCase")]
 pub struct ItemStats {
     /// Total number of items
@@ -62,6 +96,8 @@ pub struct ItemStats {
     pub by_media_type: Vec<StatEntry>,
     /// Items by public type
     pub by_public_type: Vec<StatEntry>,
+    /// Items by reading level (`unknown` when not set)
+    pub by_reading_level: Vec<StatEntry>,
     /// Number of items acquired in the period (created_at in year)
     pub acquisitions: i64,
     /// Acquisitions by media type
@@ -72,7 +108,8 @@ pub struct ItemStats {
     pub withdrawals_by_media_type: Vec<StatEntry>,
 }
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 #[serde(rename_all = "camelCase")]
 pub struct UserStats {
     /// Total number of users
@@ -83,7 +120,8 @@ pub struct UserStats {
     pub by_account_type: Vec<StatEntry>,
 }
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 #[serde(rename_all = "camelCase")]
 pub struct LoanStats {
     /// Active loans
@@ -96,12 +134,17 @@ pub struct LoanStats {
     pub by_media_type: Vec<StatEntry>,
 }
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+#[serde(rename_all = "camelCase")]
 pub struct StatEntry {
-    /// Label
+    /// Label (raw code: media type, audience, account type…)
     pub label: String,
     /// Value
     pub value: i64,
+    /// `label` translated in the caller's language (the raw code when there is no translation)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_label: Option<String>,
 }
 
 /// Sorting options for user loan statistics
@@ -127,7 +170,7 @@ pub enum UserStatsMode {
 }
 
 /// Query parameters for user loan statistics
-#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserStatsQuery {
     /// Field to sort by (total_loans, active_loans, overdue_loans) - only used in leaderboard mode
@@ -146,7 +189,7 @@ pub struct UserStatsQuery {
 
 /// User loan statistics entry
 #[serde_as]
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserLoanStats {
     /// User ID
@@ -166,7 +209,7 @@ pub struct UserLoanStats {
 }
 
 /// Query parameters for main library statistics (GET /stats)
-#[derive(Debug, Default, Clone, Deserialize, IntoParams, ToSchema)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, IntoParams, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct StatsQuery {
     /// Reference year (e.g. 2024) — stats computed as of 31 December of this year
@@ -179,6 +222,32 @@ pub struct StatsQuery {
     pub public_type: Option<String>,
     /// Filter by media type (e.g. 'b', 'bc', 'p')
     pub media_type: Option<MediaType>,
+    /// Also compute the statistics one year before the reference date (today by default)
+    pub compare_to: Option<StatsComparison>,
+}
+
+/// Period the statistics are compared with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum StatsComparison {
+    /// Same period one year earlier
+    #[serde(alias = "previous_year")]
+    PreviousYear,
+}
+
+/// One count of a statistics response compared with the previous period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StatDelta {
+    /// Path of the count in the response, breakdown entries keyed by label
+    /// (e.g. `items.byMediaType.printedText`, `byLocation.2.loans`)
+    pub metric: String,
+    pub current: i64,
+    pub previous: i64,
+    /// `current - previous`
+    pub delta: i64,
+    /// `delta` as a percentage of `previous` (`null` when `previous` is 0)
+    pub percent: Option<f64>,
 }
 
 /// Time interval for grouping statistics
@@ -193,7 +262,7 @@ pub enum Interval {
 
 
 /// Advanced loan statistics query parameters
-#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LoanStatsQuery {
     /// Start date (ISO 8601 format)
@@ -208,10 +277,15 @@ pub struct LoanStatsQuery {
     pub public_type: Option<String>,
     /// Filter by specific user ID (admin only)
     pub user_id: Option<i64>,
+    /// Also break loans and returns down by shelving location, then media type
+    #[serde(default)]
+    pub by_location: Option<bool>,
+    /// Also compute the statistics for the same period one year earlier
+    pub compare_to: Option<StatsComparison>,
 }
 
 /// Loan statistics response with time series data
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LoanStatsResponse {
     /// Total number of loans in the period
@@ -222,10 +296,35 @@ pub struct LoanStatsResponse {
     pub time_series: Vec<TimeSeriesEntry>,
     /// Statistics by media type
     pub by_media_type: Vec<StatEntry>,
+    /// Loans in the period by borrower age band at checkout (`0-14`, `15-24`, `25-44`, `45-64`, `65+`, `unknown`)
+    pub by_age_band: Vec<StatEntry>,
+    /// Loans and returns by shelving location (only if by_location=true)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub by_location: Option<Vec<LoanLocationStats>>,
+    /// Same statistics one year earlier (only with compare_to)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<Box<LoanStatsResponse>>,
+    /// Counts compared with `previous`, time series excluded (only with compare_to)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deltas: Option<Vec<StatDelta>>,
+}
+
+/// Loan activity of the copies of one shelving location
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoanLocationStats {
+    /// Shelving location (`items.place`; `null`: copies without a location)
+    pub place: Option<i16>,
+    /// Loans in the period
+    pub loans: i64,
+    /// Returns in the period
+    pub returns: i64,
+    /// Loans in the period by media type
+    pub by_media_type: Vec<StatEntry>,
 }
 
 /// Aggregated user statistics for E1 section (new users, active borrowers)
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserStatsAggregate {
     /// Total number of users (all users, with or without loans)
@@ -249,7 +348,7 @@ pub struct UserStatsAggregate {
 }
 
 /// User statistics response, either leaderboard-style or aggregate
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(tag = "mode", rename_all = "camelCase")]
 pub enum UserStatsResponse {
     /// Leaderboard-style statistics
@@ -262,7 +361,7 @@ pub enum UserStatsResponse {
 }
 
 /// Time series entry for loan statistics
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TimeSeriesEntry {
     /// Period label (e.g., "2024-01-15" for day, "2024-W03" for week)
@@ -274,7 +373,7 @@ pub struct TimeSeriesEntry {
 }
 
 /// Query parameters for catalog statistics (GET /stats/catalog)
-#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CatalogStatsQuery {
     /// Start date (ISO 8601 format) for period-based statistics
@@ -290,10 +389,18 @@ pub struct CatalogStatsQuery {
     /// Group results by public type
     #[serde(default)]
     pub by_public_type: Option<bool>,
+    /// Per-resource usage of digital copies (click-throughs in the period)
+    #[serde(default)]
+    pub by_digital_resource: Option<bool>,
+    /// Group results by shelving location (nested by media type when by_media_type=true)
+    #[serde(default)]
+    pub by_location: Option<bool>,
+    /// Also compute the statistics for the same period one year earlier
+    pub compare_to: Option<StatsComparison>,
 }
 
 /// Catalog statistics response
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CatalogStatsResponse {
     /// Aggregated totals
@@ -307,10 +414,22 @@ pub struct CatalogStatsResponse {
     /// Breakdown by public type (only if by_public_type=true)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub by_public_type: Option<Vec<CatalogBreakdownStats>>,
+    /// Usage per digital resource, most accessed first (only if by_digital_resource=true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_digital_resource: Option<Vec<CatalogDigitalResourceStats>>,
+    /// Breakdown by shelving location (only if by_location=true)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub by_location: Option<Vec<CatalogLocationStats>>,
+    /// Same statistics one year earlier (only with compare_to)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<Box<CatalogStatsResponse>>,
+    /// Counts compared with `previous` (only with compare_to)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deltas: Option<Vec<StatDelta>>,
 }
 
 /// Aggregated catalog statistics totals
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CatalogStatsTotals {
     /// Number of active items/physical copies (not archived)
@@ -321,11 +440,34 @@ pub struct CatalogStatsTotals {
     pub archived_items: i64,
     /// Number of loans in the period (0 if no period specified)
     pub loans: i64,
+    /// Number of active copies with a digital resource link
+    pub digital_items: i64,
+    /// Click-throughs on digital resources in the period
+    pub digital_accesses: i64,
+}
+
+/// Usage of one digital resource (copy with an `accessUrl`)
+#[serde_as]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogDigitalResourceStats {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub item_id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub biblio_id: i64,
+    pub title: Option<String>,
+    pub access_type: Option<ItemAccessType>,
+    /// Click-throughs in the period
+    pub accesses: i64,
+    /// Distinct authenticated users among those click-throughs
+    pub unique_users: i64,
 }
 
 /// Catalog statistics per source
 #[serde_as]
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CatalogSourceStats {
     /// Source ID
@@ -350,12 +492,34 @@ pub struct CatalogSourceStats {
     pub by_public_type: Option<Vec<CatalogBreakdownStats>>,
 }
 
+/// Catalog statistics per shelving location
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogLocationStats {
+    /// Shelving location (`items.place`; `null`: copies without a location)
+    pub place: Option<i16>,
+    /// Number of active items/physical copies
+    pub active_items: i64,
+    /// Number of items entered in the period
+    pub entered_items: i64,
+    /// Number of items archived in the period
+    pub archived_items: i64,
+    /// Number of loans in the period
+    pub loans: i64,
+    /// Breakdown by media type (only when by_location=true AND by_media_type=true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_media_type: Option<Vec<CatalogBreakdownStats>>,
+}
+
 /// Catalog statistics breakdown (by media_type or public_type)
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CatalogBreakdownStats {
     /// Label (media type code or public type name)
     pub label: String,
+    /// `label` translated in the caller's language (the raw code when there is no translation)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_label: Option<String>,
     /// Number of active items/physical copies
     pub active_items: i64,
     /// Number of items entered in the period
@@ -370,6 +534,224 @@ pub struct CatalogBreakdownStats {
 }
 
 
+/// Query parameters of the acquisition value report (`GET /stats/acquisitions`)
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct AcquisitionStatsQuery {
+    /// First day (YYYY-MM-DD, default January 1st of the current year)
+    pub start_date: Option<NaiveDate>,
+    /// Last day (YYYY-MM-DD, default today)
+    pub end_date: Option<NaiveDate>,
+}
+
+/// Value of the copies acquired in a period, prices split into VAT and net amounts
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AcquisitionStatsResponse {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// ISO 4217 code of the amounts (`[currency] code`)
+    pub currency: String,
+    pub totals: AcquisitionValueTotals,
+    /// Largest amounts first
+    pub by_media_type: Vec<AcquisitionValueStats>,
+}
+
+/// Amounts over all media types
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AcquisitionValueTotals {
+    /// Copies entered in the period (deposit copies excluded, copies archived since included)
+    pub copies: i64,
+    /// Copies among them with a price (free-text prices are left out)
+    pub priced_copies: i64,
+    /// Sum of the prices, VAT included
+    #[schema(value_type = String, example = "1520.40")]
+    pub total_incl_tax: Decimal,
+    #[schema(value_type = String, example = "79.26")]
+    pub tax: Decimal,
+    #[schema(value_type = String, example = "1441.14")]
+    pub total_excl_tax: Decimal,
+}
+
+/// Amounts of one media type
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AcquisitionValueStats {
+    /// Media type code
+    pub label: String,
+    /// `label` translated in the caller's language (the raw code when there is no translation)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_label: Option<String>,
+    /// VAT rate of the media type (`/settings/media-types`); no tax is split out without one
+    #[schema(value_type = Option<String>, example = "5.50")]
+    pub vat_rate: Option<Decimal>,
+    pub copies: i64,
+    pub priced_copies: i64,
+    #[schema(value_type = String, example = "1450.00")]
+    pub total_incl_tax: Decimal,
+    #[schema(value_type = String, example = "75.59")]
+    pub tax: Decimal,
+    #[schema(value_type = String, example = "1374.41")]
+    pub total_excl_tax: Decimal,
+}
+
+/// Completeness of the catalog records per source (`GET /stats/catalog-quality`)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogQualityResponse {
+    /// All active records (a record with copies from several sources counts once)
+    pub totals: CatalogQualityStats,
+    /// Least complete sources first
+    pub by_source: Vec<CatalogQualityStats>,
+}
+
+/// Share of the active records of a source that have each field
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogQualityStats {
+    /// Source ID (`null`: records without a source, and the catalog totals)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub source_id: Option<i64>,
+    pub source_name: Option<String>,
+    /// Active records
+    pub records: i64,
+    pub isbn: CompletenessRate,
+    pub authors: CompletenessRate,
+    /// At least one active copy (of this source) with a call number
+    pub call_number: CompletenessRate,
+    /// Free-text subject or subject headings
+    pub subjects: CompletenessRate,
+    /// Covers are fetched by ISBN: records with a well-formed ISBN-10 or ISBN-13
+    pub cover: CompletenessRate,
+    /// Source MARC record kept on import
+    pub marc_record: CompletenessRate,
+    /// Average of the six percentages
+    pub score: f64,
+}
+
+/// Records having a field
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletenessRate {
+    pub count: i64,
+    /// Percentage of the records, one decimal (0 without records)
+    #[schema(example = 87.5)]
+    pub percent: f64,
+}
+
+fn localize_entries(entries: &mut [StatEntry], labels: &LabelSet, kind: LabelKind) {
+    for entry in entries {
+        entry.display_label = Some(labels.get(kind, &entry.label).unwrap_or(&entry.label).to_string());
+    }
+}
+
+fn localize_breakdown(entries: &mut [CatalogBreakdownStats], labels: &LabelSet, kind: LabelKind) {
+    for entry in entries {
+        entry.display_label = Some(labels.get(kind, &entry.label).unwrap_or(&entry.label).to_string());
+        if let Some(nested) = entry.by_public_type.as_mut() {
+            localize_breakdown(nested, labels, LabelKind::AudienceType);
+        }
+    }
+}
+
+impl StatsResponse {
+    /// Fill `displayLabel` of every breakdown from `labels`
+    pub fn localize(&mut self, labels: &LabelSet) {
+        localize_entries(&mut self.items.by_media_type, labels, LabelKind::MediaType);
+        localize_entries(&mut self.items.by_public_type, labels, LabelKind::AudienceType);
+        localize_entries(&mut self.items.by_reading_level, labels, LabelKind::ReadingLevel);
+        localize_entries(&mut self.items.acquisitions_by_media_type, labels, LabelKind::MediaType);
+        localize_entries(&mut self.items.withdrawals_by_media_type, labels, LabelKind::MediaType);
+        localize_entries(&mut self.users.by_account_type, labels, LabelKind::AccountType);
+        localize_entries(&mut self.loans.by_media_type, labels, LabelKind::MediaType);
+        if let Some(previous) = self.previous.as_mut() {
+            previous.localize(labels);
+        }
+    }
+}
+
+impl AcquisitionStatsResponse {
+    /// Fill `displayLabel` of the media types from `labels`
+    pub fn localize(&mut self, labels: &LabelSet) {
+        for entry in &mut self.by_media_type {
+            entry.display_label = Some(labels.get(LabelKind::MediaType, &entry.label).unwrap_or(&entry.label).to_string());
+        }
+    }
+}
+
+impl LoanStatsResponse {
+    /// Fill `displayLabel` of the media type breakdown from `labels`
+    pub fn localize(&mut self, labels: &LabelSet) {
+        localize_entries(&mut self.by_media_type, labels, LabelKind::MediaType);
+        for location in self.by_location.iter_mut().flatten() {
+            localize_entries(&mut location.by_media_type, labels, LabelKind::MediaType);
+        }
+        if let Some(previous) = self.previous.as_mut() {
+            previous.localize(labels);
+        }
+    }
+}
+
+impl UserStatsAggregate {
+    /// Fill `displayLabel` of the patron category breakdowns from `labels`
+    pub fn localize(&mut self, labels: &LabelSet) {
+        localize_entries(&mut self.users_by_public_type, labels, LabelKind::PublicType);
+        localize_entries(&mut self.new_users_by_public_type, labels, LabelKind::PublicType);
+        localize_entries(&mut self.active_borrowers_by_public_type, labels, LabelKind::PublicType);
+    }
+}
+
+impl CatalogStatsResponse {
+    /// Fill `displayLabel` of the media type / audience breakdowns from `labels`
+    pub fn localize(&mut self, labels: &LabelSet) {
+        if let Some(by_media_type) = self.by_media_type.as_mut() {
+            localize_breakdown(by_media_type, labels, LabelKind::MediaType);
+        }
+        if let Some(by_public_type) = self.by_public_type.as_mut() {
+            localize_breakdown(by_public_type, labels, LabelKind::AudienceType);
+        }
+        for source in self.by_source.iter_mut().flatten() {
+            if let Some(by_media_type) = source.by_media_type.as_mut() {
+                localize_breakdown(by_media_type, labels, LabelKind::MediaType);
+            }
+            if let Some(by_public_type) = source.by_public_type.as_mut() {
+                localize_breakdown(by_public_type, labels, LabelKind::AudienceType);
+            }
+        }
+        for location in self.by_location.iter_mut().flatten() {
+            if let Some(by_media_type) = location.by_media_type.as_mut() {
+                localize_breakdown(by_media_type, labels, LabelKind::MediaType);
+            }
+        }
+        if let Some(previous) = self.previous.as_mut() {
+            previous.localize(labels);
+        }
+    }
+}
+
+impl StatsQuery {
+    /// Service filter for these parameters (`None` when no parameter is set: current totals)
+    pub fn filter(&self) -> Option<crate::services::stats::StatsFilter> {
+        if self.year.is_none()
+            && self.start_date.is_none()
+            && self.end_date.is_none()
+            && self.public_type.is_none()
+            && self.media_type.is_none()
+        {
+            return None;
+        }
+        Some(crate::services::stats::StatsFilter {
+            reference_date: resolve_reference_date(self),
+            public_type: self.public_type.clone(),
+            media_type: self.media_type.as_ref().map(MediaType::as_db_str).map(String::from),
+        })
+    }
+}
+
 fn resolve_reference_date(query: &StatsQuery) -> Option<NaiveDate> {
     if let Some(ref s) = query.end_date {
         if let Ok(d) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
@@ -401,25 +783,16 @@ fn resolve_reference_date(query: &StatsQuery) -> Option<NaiveDate> {
 pub async fn get_stats(
     State(state): State<crate::AppState>,
     AuthenticatedUser(claims): AuthenticatedUser,
+    Locale(lang): Locale,
     Query(query): Query<StatsQuery>,
 ) -> AppResult<Json<StatsResponse>> {
     claims.require_read_items()?;
 
-    let filter = if query.year.is_none()
-        && query.start_date.is_none()
-        && query.end_date.is_none()
-        && query.public_type.is_none()
-        && query.media_type.is_none()
-    {
-        None
-    } else {
-        Some(crate::services::stats::StatsFilter {
-            reference_date: resolve_reference_date(&query),
-            public_type: query.public_type,
-            media_type: query.media_type.as_ref().map(MediaType::as_code).map(String::from),
-        })
+    let mut stats = match query.compare_to {
+        None => state.services.stats.get_stats(query.filter()).await?,
+        Some(StatsComparison::PreviousYear) => state.services.stats.get_stats_compared(query.filter()).await?,
     };
-    let stats = state.services.stats.get_stats(filter).await?;
+    stats.localize(&state.services.labels.label_set(&lang, None).await?);
     Ok(Json(stats))
 }
 
@@ -442,6 +815,7 @@ pub async fn get_stats(
 pub async fn get_loan_stats(
     State(state): State<crate::AppState>,
     AuthenticatedUser(claims): AuthenticatedUser,
+    Locale(lang): Locale,
     Query(query): Query<LoanStatsQuery>,
 ) -> AppResult<Json<LoanStatsResponse>> {
     claims.require_read_loans()?;
@@ -498,7 +872,41 @@ pub async fn get_loan_stats(
 
     let interval = query.interval.unwrap_or(Interval::Day);
 
-    let stats = state.services.stats.get_loan_stats(
+    let mut stats = match query.compare_to {
+        None => loan_stats_for_period(&state, &query, start_date, end_date, interval, user_id).await?,
+        Some(StatsComparison::PreviousYear) => {
+            // Resolve the default period (last 30 days) so both periods cover the same days
+            let start = start_date.unwrap_or_else(|| Utc::now() - chrono::Duration::days(30));
+            let end = end_date.unwrap_or_else(Utc::now);
+            let mut stats = loan_stats_for_period(&state, &query, Some(start), Some(end), interval, user_id).await?;
+            let previous = loan_stats_for_period(
+                &state,
+                &query,
+                Some(comparison::previous_year(start)),
+                Some(comparison::previous_year(end)),
+                interval,
+                user_id,
+            ).await?;
+            stats.deltas = Some(comparison::deltas(&stats, &previous, &["timeSeries"]));
+            stats.previous = Some(Box::new(previous));
+            stats
+        }
+    };
+    stats.localize(&state.services.labels.label_set(&lang, Some(LabelKind::MediaType)).await?);
+
+    Ok(Json(stats))
+}
+
+/// Loan statistics of one period, with the location breakdown when requested
+async fn loan_stats_for_period(
+    state: &crate::AppState,
+    query: &LoanStatsQuery,
+    start_date: Option<DateTime<Utc>>,
+    end_date: Option<DateTime<Utc>>,
+    interval: Interval,
+    user_id: Option<i64>,
+) -> AppResult<LoanStatsResponse> {
+    let mut stats = state.services.stats.get_loan_stats(
         start_date,
         end_date,
         interval,
@@ -506,8 +914,16 @@ pub async fn get_loan_stats(
         query.public_type.as_deref(),
         user_id,
     ).await?;
-
-    Ok(Json(stats))
+    if query.by_location.unwrap_or(false) {
+        stats.by_location = Some(state.services.stats.get_loan_location_stats(
+            start_date,
+            end_date,
+            query.media_type.as_ref(),
+            query.public_type.as_deref(),
+            user_id,
+        ).await?);
+    }
+    Ok(stats)
 }
 
 /// Get user loan statistics (leaderboard-style)
@@ -525,6 +941,7 @@ pub async fn get_loan_stats(
 pub async fn get_user_stats(
     State(state): State<crate::AppState>,
     AuthenticatedUser(claims): AuthenticatedUser,
+    Locale(lang): Locale,
     Query(query): Query<UserStatsQuery>,
 ) -> AppResult<Json<UserStatsResponse>> {
     // Reading this requires loan statistics access
@@ -593,11 +1010,12 @@ pub async fn get_user_stats(
             Ok(Json(UserStatsResponse::Leaderboard { users }))
         }
         UserStatsMode::Aggregate => {
-            let aggregates = state
+            let mut aggregates = state
                 .services
                 .stats
                 .get_user_aggregates(start_date, end_date)
                 .await?;
+            aggregates.localize(&state.services.labels.label_set(&lang, Some(LabelKind::PublicType)).await?);
 
             Ok(Json(UserStatsResponse::Aggregate(aggregates)))
         }
@@ -620,6 +1038,8 @@ pub async fn get_user_stats(
 /// | `by_source` + `by_media_type`                 | `by_source[].by_media_type[]` — each source contains its media detail  |
 /// | `by_media_type` + `by_public_type`            | `by_media_type[].by_public_type[]` — each media contains public detail |
 /// | `by_source` + `by_media_type` + `by_public_type` | 3-level nesting: `by_source[].by_media_type[].by_public_type[]`     |
+/// | `by_location`                                 | `by_location[]` — flat list of shelving locations (`items.place`)      |
+/// | `by_location` + `by_media_type`               | `by_location[].by_media_type[]` — each location contains its media     |
 ///
 /// **Rendering rules:**
 /// - When `by_source` has nested `by_media_type`, render a table/accordion per source
@@ -628,6 +1048,8 @@ pub async fn get_user_stats(
 ///   (e.g. expandable row or indented sub-rows) showing adult/children split.
 /// - Top-level `by_media_type` and `by_public_type` are always global aggregations
 ///   (regardless of nesting inside `by_source`), useful for summary charts/pie.
+/// - `by_location` is independent of the other flags: a branch or section manager's view
+///   of their own shelves, with its own loan counts.
 /// - Each entry at every level carries `active_items`, `entered_items`,
 ///   `archived_items` — the parent's counts are the sum of its children.
 #[utoipa::path(
@@ -644,6 +1066,7 @@ pub async fn get_user_stats(
 pub async fn get_catalog_stats(
     State(state): State<crate::AppState>,
     AuthenticatedUser(claims): AuthenticatedUser,
+    Locale(lang): Locale,
     Query(query): Query<CatalogStatsQuery>,
 ) -> AppResult<Json<CatalogStatsResponse>> {
     claims.require_read_items()?;
@@ -675,17 +1098,178 @@ pub async fn get_catalog_stats(
         .transpose()
         .map_err(|_| crate::error::AppError::Validation("Invalid end_date format. Use ISO 8601 (RFC 3339)".to_string()))?;
 
-    let stats = state.services.stats.get_catalog_stats(
+    let mut stats = match query.compare_to {
+        None => catalog_stats_for_period(&state, &query, start_date, end_date).await?,
+        Some(StatsComparison::PreviousYear) => {
+            // Without a start date both periods start with the catalog; only the end moves
+            let end = end_date.unwrap_or_else(Utc::now);
+            let mut stats = catalog_stats_for_period(&state, &query, start_date, Some(end)).await?;
+            let previous = catalog_stats_for_period(
+                &state,
+                &query,
+                start_date.map(comparison::previous_year),
+                Some(comparison::previous_year(end)),
+            ).await?;
+            stats.deltas = Some(comparison::deltas(&stats, &previous, &[]));
+            stats.previous = Some(Box::new(previous));
+            stats
+        }
+    };
+    stats.localize(&state.services.labels.label_set(&lang, None).await?);
+
+    Ok(Json(stats))
+}
+
+/// Catalog statistics of one period, with the location breakdown when requested
+async fn catalog_stats_for_period(
+    state: &crate::AppState,
+    query: &CatalogStatsQuery,
+    start_date: Option<DateTime<Utc>>,
+    end_date: Option<DateTime<Utc>>,
+) -> AppResult<CatalogStatsResponse> {
+    let mut stats = state.services.stats.get_catalog_stats(
         start_date,
         end_date,
         query.by_source.unwrap_or(false),
         query.by_media_type.unwrap_or(false),
         query.by_public_type.unwrap_or(false),
+        query.by_digital_resource.unwrap_or(false),
     ).await?;
+    if query.by_location.unwrap_or(false) {
+        stats.by_location = Some(state.services.stats.get_catalog_location_stats(
+            start_date,
+            end_date,
+            query.by_media_type.unwrap_or(false),
+        ).await?);
+    }
+    Ok(stats)
+}
 
+/// Attendance statistics: visitor counts compared with the opening hours.
+///
+/// Averages visitors per opened hour for each weekday, over the open days that have counts, and
+/// lists the counts registered while the library was closed (closure days, days without opening
+/// hours, hourly buckets outside the opening slots), typically sensor drift.
+#[utoipa::path(
+    get,
+    path = "/stats/visitors",
+    tag = "stats",
+    security(("bearer_auth" = [])),
+    params(VisitorStatsQuery),
+    responses(
+        (status = 200, description = "Attendance statistics", body = VisitorStats),
+        (status = 400, description = "Invalid or too long range", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn get_visitor_stats(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<VisitorStatsQuery>,
+) -> AppResult<Json<VisitorStats>> {
+    claims.require_read_settings()?;
+    Ok(Json(
+        state
+            .services
+            .visitor_counts
+            .stats(query.start_date, query.end_date)
+            .await?,
+    ))
+}
+
+/// Effectiveness of the re-engagement emails sent to inactive members.
+///
+/// A member counts as reactivated when they borrow within `reengagement.conversion_days` of
+/// their email; `suggestedLoans` counts the loans of the titles the email listed. Emails are
+/// grouped by the month they were sent in.
+#[utoipa::path(
+    get,
+    path = "/stats/reengagement",
+    tag = "stats",
+    security(("bearer_auth" = [])),
+    params(ReengagementStatsQuery),
+    responses(
+        (status = 200, description = "Re-engagement statistics", body = ReengagementStats),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 400, description = "endDate before startDate", body = ErrorResponse),
+    )
+)]
+pub async fn get_reengagement_stats(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<ReengagementStatsQuery>,
+) -> AppResult<Json<ReengagementStats>> {
+    claims.require_read_loans()?;
+    Ok(Json(
+        state
+            .services
+            .reengagement
+            .stats(query.start_date, query.end_date)
+            .await?,
+    ))
+}
+
+/// Value of the copies acquired in a period, per media type.
+///
+/// Copy prices include VAT; the media type's `vatRate` splits each total into tax and net
+/// amounts for acquisition budgets. Deposit copies are not library purchases and are left out;
+/// copies without a price, or with a free-text one, are counted in `copies` only.
+#[utoipa::path(
+    get,
+    path = "/stats/acquisitions",
+    tag = "stats",
+    security(("bearer_auth" = [])),
+    params(AcquisitionStatsQuery),
+    responses(
+        (status = 200, description = "Acquisition amounts", body = AcquisitionStatsResponse),
+        (status = 400, description = "Invalid range", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn get_acquisition_stats(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Locale(lang): Locale,
+    Query(query): Query<AcquisitionStatsQuery>,
+) -> AppResult<Json<AcquisitionStatsResponse>> {
+    claims.require_read_items()?;
+    let mut stats = state
+        .services
+        .stats
+        .get_acquisition_stats(query.start_date, query.end_date)
+        .await?;
+    stats.localize(&state.services.labels.label_set(&lang, None).await?);
     Ok(Json(stats))
 }
 
+/// Completeness of the catalog per source, to prioritize enrichment work.
+///
+/// Records are grouped by the sources of their active copies (the record's own source when no
+/// copy has one). For each source: the share of records with an ISBN, authors, a call number,
+/// subjects, a cover and a MARC record. Covers are not stored; they are fetched by ISBN, so a
+/// well-formed ISBN is what counts.
+#[utoipa::path(
+    get,
+    path = "/stats/catalog-quality",
+    tag = "stats",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Catalog completeness per source", body = CatalogQualityResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn get_catalog_quality_stats(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<CatalogQualityResponse>> {
+    claims.require_read_items()?;
+    Ok(Json(state.services.stats.get_catalog_quality().await?))
+}
+
 // --- Flexible stats builder (whitelist SQL) ---------------------------------
 
 /// Discovery document for the visual query builder (`entities`, `operators`, …).
@@ -695,7 +1279,7 @@ pub async fn get_catalog_stats(
     tag = "stats",
     security(("bearer_auth" = [])),
     responses(
-        (status = 200, description = "Stats schema for builder UI"),
+        (status = 200, description = "Stats schema for builder UI", body = serde_json::Value),
         (status = 403, description = "Staff only")
     )
 )]
@@ -713,8 +1297,8 @@ pub async fn get_stats_schema(
     security(("bearer_auth" = [])),
     request_body = StatsBuilderBody,
     responses(
-        (status = 200, description = "Tabular stats", body = crate::models::stats_builder::StatsTableResponse),
-        (status = 422, description = "PostgreSQL rejected the generated SQL", body = crate::models::stats_builder::StatsTableResponse),
+        (status = 200, description = "Tabular stats", body = StatsTableResponse),
+        (status = 422, description = "PostgreSQL rejected the generated SQL", body = StatsTableResponse),
         (status = 400, description = "Invalid query"),
         (status = 403, description = "Staff only")
     )
@@ -813,7 +1397,7 @@ pub async fn update_saved_query(
         ("id" = i64, Path, description = "Saved query id")
     ),
     responses(
-        (status = 200, description = "Deleted"),
+        (status = 200, description = "Deleted", body = serde_json::Value),
         (status = 403, description = "Forbidden"),
         (status = 404, description = "Not found")
     )
@@ -838,8 +1422,8 @@ pub async fn delete_saved_query(
         ("id" = i64, Path, description = "Saved query id")
     ),
     responses(
-        (status = 200, description = "Tabular stats", body = crate::models::stats_builder::StatsTableResponse),
-        (status = 422, description = "PostgreSQL rejected the generated SQL", body = crate::models::stats_builder::StatsTableResponse),
+        (status = 200, description = "Tabular stats", body = StatsTableResponse),
+        (status = 422, description = "PostgreSQL rejected the generated SQL", body = StatsTableResponse),
         (status = 403, description = "Forbidden"),
         (status = 404, description = "Not found")
     )
@@ -861,3 +1445,211 @@ pub async fn run_saved_query(
     };
     Ok((status, Json(res)))
 }
+
+// --- Report builder (whitelisted dimensions × measures) ---------------------
+
+/// Output format of report runs
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+pub struct ReportRunQuery {
+    /// `json` (default), `csv` or `xlsx`
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+fn report_response(
+    table: ReportTable,
+    format: ReportFormat,
+    basename: &str,
+) -> AppResult<axum::response::Response> {
+    let (content_type, extension, body) = match format {
+        ReportFormat::Json => return Ok(Json(table).into_response()),
+        ReportFormat::Csv => ("text/csv; charset=utf-8", "csv", report_csv(&table).into_bytes()),
+        ReportFormat::Xlsx => (
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            "xlsx",
+            report_xlsx(&table)?,
+        ),
+    };
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, content_type.to_string()),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.{}\"", basename, extension),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Dimensions, measures and output formats accepted by the report builder.
+#[utoipa::path(
+    get,
+    path = "/stats/reports/schema",
+    tag = "stats",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Report builder vocabulary", body = serde_json::Value),
+        (status = 403, description = "Staff only")
+    )
+)]
+pub async fn get_report_schema(
+    _staff: StaffUser,
+) -> AppResult<Json<serde_json::Value>> {
+    Ok(Json(report_schema_json()))
+}
+
+/// Run an unsaved report definition (results cached like `/stats/saved/{id}/run`).
+#[utoipa::path(
+    post,
+    path = "/stats/reports/run",
+    tag = "stats",
+    security(("bearer_auth" = [])),
+    params(ReportRunQuery),
+    request_body = ReportDefinition,
+    responses(
+        (status = 200, description = "Report rows (JSON, CSV or XLSX)", body = ReportTable),
+        (status = 403, description = "Staff only"),
+        (status = 422, description = "Unsupported dimension for a measure, or report too large")
+    )
+)]
+pub async fn post_run_report(
+    State(state): State<crate::AppState>,
+    _staff: StaffUser,
+    Query(query): Query<ReportRunQuery>,
+    Json(definition): Json<ReportDefinition>,
+) -> AppResult<axum::response::Response> {
+    let pool = state.services.repository_pool();
+    let table = run_report(pool, Some(&state.services.redis), &definition).await?;
+    report_response(table, query.format, "report")
+}
+
+/// List saved reports (own + shared; admins see all).
+#[utoipa::path(
+    get,
+    path = "/stats/reports",
+    tag = "stats",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Saved reports", body = [SavedReport]),
+        (status = 403, description = "Staff only")
+    )
+)]
+pub async fn list_saved_reports(
+    State(state): State<crate::AppState>,
+    StaffUser(claims): StaffUser,
+) -> AppResult<Json<Vec<SavedReport>>> {
+    let pool = state.services.repository_pool();
+    let list = saved_reports::list_for_user(pool, claims.user_id, claims.is_admin()).await?;
+    Ok(Json(list))
+}
+
+/// Save a report definition for reuse.
+#[utoipa::path(
+    post,
+    path = "/stats/reports",
+    tag = "stats",
+    security(("bearer_auth" = [])),
+    request_body = SavedReportWrite,
+    responses(
+        (status = 200, description = "Created saved report", body = SavedReport),
+        (status = 403, description = "Staff only"),
+        (status = 422, description = "Invalid definition")
+    )
+)]
+pub async fn create_saved_report(
+    State(state): State<crate::AppState>,
+    StaffUser(claims): StaffUser,
+    Json(body): Json<SavedReportWrite>,
+) -> AppResult<Json<SavedReport>> {
+    validate_report(&body.definition)?;
+    let pool = state.services.repository_pool();
+    let row = saved_reports::insert(pool, claims.user_id, &body).await?;
+    Ok(Json(row))
+}
+
+/// Update a saved report (owner or admin).
+#[utoipa::path(
+    put,
+    path = "/stats/reports/{id}",
+    tag = "stats",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "Saved report id")
+    ),
+    request_body = SavedReportWrite,
+    responses(
+        (status = 200, description = "Updated", body = SavedReport),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 422, description = "Invalid definition")
+    )
+)]
+pub async fn update_saved_report(
+    State(state): State<crate::AppState>,
+    StaffUser(claims): StaffUser,
+    Path(id): Path<i64>,
+    Json(body): Json<SavedReportWrite>,
+) -> AppResult<Json<SavedReport>> {
+    validate_report(&body.definition)?;
+    let pool = state.services.repository_pool();
+    let row = saved_reports::update(pool, id, claims.user_id, claims.is_admin(), &body).await?;
+    Ok(Json(row))
+}
+
+/// Delete a saved report (owner or admin).
+#[utoipa::path(
+    delete,
+    path = "/stats/reports/{id}",
+    tag = "stats",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "Saved report id")
+    ),
+    responses(
+        (status = 200, description = "Deleted", body = serde_json::Value),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    )
+)]
+pub async fn delete_saved_report(
+    State(state): State<crate::AppState>,
+    StaffUser(claims): StaffUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<serde_json::Value>> {
+    let pool = state.services.repository_pool();
+    saved_reports::delete_by_id(pool, id, claims.user_id, claims.is_admin()).await?;
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+/// Run a saved report by id.
+#[utoipa::path(
+    get,
+    path = "/stats/reports/{id}/run",
+    tag = "stats",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "Saved report id"),
+        ReportRunQuery
+    ),
+    responses(
+        (status = 200, description = "Report rows (JSON, CSV or XLSX)", body = ReportTable),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 422, description = "Report too large")
+    )
+)]
+pub async fn run_saved_report(
+    State(state): State<crate::AppState>,
+    StaffUser(claims): StaffUser,
+    Path(id): Path<i64>,
+    Query(query): Query<ReportRunQuery>,
+) -> AppResult<axum::response::Response> {
+    let pool = state.services.repository_pool();
+    let saved = saved_reports::get_by_id(pool, id, claims.user_id, claims.is_admin())
+        .await?
+        .ok_or_else(|| crate::error::AppError::NotFound("Saved report not found".into()))?;
+    let table = run_report(pool, Some(&state.services.redis), &saved.definition).await?;
+    report_response(table, query.format, &format!("report_{}", saved.id))
+}
@@ -12,7 +12,7 @@ use axum::{
     extract::{Path, State},
     Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use utoipa::ToSchema;
 
@@ -39,7 +39,7 @@ pub fn router() -> axum::Router<AppState> {
 
 /// Returned by endpoints that kick off a background task (`202 Accepted`).
 #[serde_as]
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskAcceptedResponse {
     /// Opaque task identifier — use with `GET /tasks/:id` to poll for progress.
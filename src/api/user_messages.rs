@@ -0,0 +1,172 @@
+//! Patron message API endpoints (staff notes on a patron account)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{
+        user::{Rights, UserClaims},
+        user_message::{CreateUserMessage, UserMessage, UserMessageQuery},
+    },
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp, SelfServiceUser};
+
+/// Build the patron message routes for this domain.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{delete, get, post};
+    axum::Router::new()
+        .route("/users/:id/messages", get(list_user_messages).post(create_user_message))
+        .route("/users/:id/messages/:message_id", delete(delete_user_message))
+        .route("/users/:id/messages/:message_id/acknowledge", post(acknowledge_user_message))
+}
+
+/// Patron view (the patron reading their own account) or staff view of the messages.
+/// Staff with users read rights see every message; the patron only sees patron-visible ones.
+fn patron_view(claims: &UserClaims, user_id: i64) -> AppResult<bool> {
+    if claims.rights.users_rights.rank() >= Rights::Read.rank() {
+        return Ok(false);
+    }
+    if claims.user_id == user_id {
+        return Ok(true);
+    }
+    Err(AppError::Authorization("Access denied".to_string()))
+}
+
+/// List the messages of a patron (pending ones unless `includeAcknowledged=true`).
+///
+/// Patrons reading their own account (self-service tokens included) only get the messages
+/// marked `patronVisible`.
+#[utoipa::path(
+    get,
+    path = "/users/{id}/messages",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "User ID"), UserMessageQuery),
+    responses(
+        (status = 200, description = "Messages, blocking first then newest", body = Vec<UserMessage>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_user_messages(
+    State(state): State<crate::AppState>,
+    SelfServiceUser(claims): SelfServiceUser,
+    Path(id): Path<i64>,
+    Query(query): Query<UserMessageQuery>,
+) -> AppResult<Json<Vec<UserMessage>>> {
+    let patron_view = patron_view(&claims, id)?;
+    Ok(Json(
+        state
+            .services
+            .user_messages
+            .list(id, query.include_acknowledged, patron_view)
+            .await?,
+    ))
+}
+
+/// Add a message to a patron account.
+///
+/// `blocking` messages stop checkouts until acknowledged (or `force=true` on the loan);
+/// `patronVisible` messages are also shown in the patron's self-service account.
+#[utoipa::path(
+    post,
+    path = "/users/{id}/messages",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "User ID")),
+    request_body = CreateUserMessage,
+    responses(
+        (status = 201, description = "Message created", body = UserMessage),
+        (status = 400, description = "Empty or too long body, or expiry in the past", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+    )
+)]
+pub async fn create_user_message(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<CreateUserMessage>,
+) -> AppResult<(StatusCode, Json<UserMessage>)> {
+    claims.require_write_users()?;
+    let message = state.services.user_messages.create(id, &data, claims.user_id).await?;
+    state.services.audit.log(audit::event::USER_MESSAGE_CREATED, Some(claims.user_id), Some("user_message"), Some(message.id), ip, Some(&message), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(message)))
+}
+
+/// Delete a message from a patron account
+#[utoipa::path(
+    delete,
+    path = "/users/{id}/messages/{message_id}",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "User ID"),
+        ("message_id" = i64, Path, description = "Message ID"),
+    ),
+    responses(
+        (status = 204, description = "Message deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_user_message(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path((id, message_id)): Path<(i64, i64)>,
+) -> AppResult<StatusCode> {
+    claims.require_write_users()?;
+    state.services.user_messages.delete(id, message_id).await?;
+    state.services.audit.log(audit::event::USER_MESSAGE_DELETED, Some(claims.user_id), Some("user_message"), Some(message_id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Acknowledge a message (idempotent).
+///
+/// Staff with loans or users write rights can acknowledge any message (e.g. at the desk before
+/// a checkout); patrons can acknowledge the patron-visible messages of their own account.
+#[utoipa::path(
+    post,
+    path = "/users/{id}/messages/{message_id}/acknowledge",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "User ID"),
+        ("message_id" = i64, Path, description = "Message ID"),
+    ),
+    responses(
+        (status = 200, description = "Message acknowledged", body = UserMessage),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn acknowledge_user_message(
+    State(state): State<crate::AppState>,
+    SelfServiceUser(claims): SelfServiceUser,
+    ClientIp(ip): ClientIp,
+    Path((id, message_id)): Path<(i64, i64)>,
+) -> AppResult<Json<UserMessage>> {
+    let staff = claims.rights.users_rights.rank() >= Rights::Write.rank()
+        || claims.rights.loans_rights.rank() >= Rights::Write.rank();
+    if !staff && claims.user_id != id {
+        return Err(AppError::Authorization("Access denied".to_string()));
+    }
+    let message = state
+        .services
+        .user_messages
+        .acknowledge(id, message_id, claims.user_id, !staff)
+        .await?;
+    state.services.audit.log(audit::event::USER_MESSAGE_ACKNOWLEDGED, Some(claims.user_id), Some("user_message"), Some(message_id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(Json(message))
+}
@@ -5,26 +5,44 @@ use axum::{
     http::StatusCode,
     Json,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use utoipa::ToSchema;
+use validator::Validate;
 
 use crate::{
     error::AppResult,
-    models::user::{UpdateAccountType, UpdateProfile, User, UserPayload, UserQuery, UserShort},
-    services::audit,
+    models::{
+        task::TaskKind,
+        user::{
+            MergeUsers, SetUserPin, UpdateAccountType, UpdateProfile, User, UserActivity, UserActivityQuery,
+            UserExpiryCampaign, UserMergeReport, UserPayload, UserQuery, UserShort,
+        },
+    },
+    services::{audit, users::IMPERSONATION_TOKEN_SECONDS},
 };
 
-use super::{biblios::PaginatedResponse, AuthenticatedUser, ClientIp, ValidatedJson};
+use super::{
+    auth::UserInfo, biblios::PaginatedResponse, tasks::TaskAcceptedResponse, AuthenticatedUser, ClientIp, ValidatedJson,
+};
 
 
 /// Build the users routes for this domain.
 pub fn router() -> axum::Router<crate::AppState> {
-    use axum::routing::{delete, get, put};
+    use axum::routing::{delete, get, post, put};
     axum::Router::new()
         .route("/users", get(list_users).post(create_user))
+        .route("/users/merge", post(merge_users))
+        .route("/users/expiry-campaign", post(run_expiry_campaign))
         .route("/users/:id", get(get_user).put(update_user).delete(delete_user))
         .route("/users/:id/account-type", put(update_account_type))
         .route("/users/:id/force-password-change", put(force_password_change))
+        .route("/users/:id/pin", put(set_user_pin))
+        .route("/users/:id/restore", post(restore_user))
+        .route("/users/:id/impersonate", post(impersonate_user))
+        .route("/users/:id/activity", get(get_user_activity))
         .route("/users/:id/loans", get(super::loans::get_user_loans))
+        .route("/users/:id/checkout-blocks", get(super::loans::get_checkout_blocks))
         .route(
             "/users/:id/loans/export",
             get(super::loans::export_user_loans_marc),
@@ -45,7 +63,7 @@ pub fn router() -> axum::Router<crate::AppState> {
         ("per_page" = Option<i64>, Query, description = "Items per page")
     ),
     responses(
-        (status = 200, description = "List of users", body = PaginatedResponse<UserShort>),
+        (status = 200, description = "List of users", body = PaginatedUsers),
         (status = 401, description = "Not authenticated")
     )
 )]
@@ -84,10 +102,43 @@ pub async fn get_user(
 ) -> AppResult<Json<User>> {
     claims.require_read_users()?;
 
-    let user = state.services.users.get_by_id(id).await?;
+    let user = state.services.users.get_record(id).await?;
     Ok(Json(user))
 }
 
+/// Activity timeline of a user
+///
+/// Loans, returns, holds, payments, notifications sent and profile changes merged into one list,
+/// newest first.
+#[utoipa::path(
+    get,
+    path = "/users/{id}/activity",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = i64, Path, description = "User ID"),
+        UserActivityQuery
+    ),
+    responses(
+        (status = 200, description = "Activity timeline", body = PaginatedUserActivity),
+        (status = 403, description = "Insufficient rights"),
+        (status = 404, description = "User not found")
+    )
+)]
+pub async fn get_user_activity(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+    Query(query): Query<UserActivityQuery>,
+) -> AppResult<Json<PaginatedResponse<UserActivity>>> {
+    claims.require_read_users()?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(20).clamp(1, 200);
+    let (entries, total) = state.services.users.activity(id, query.kind, page, per_page).await?;
+    Ok(Json(PaginatedResponse::new(entries, total, page, per_page)))
+}
+
 /// Create a new user
 #[utoipa::path(
     post,
@@ -97,8 +148,9 @@ pub async fn get_user(
     request_body = UserPayload,
     responses(
         (status = 201, description = "User created", body = User),
-        (status = 400, description = "Invalid input"),
-        (status = 409, description = "Login already exists")
+        (status = 400, description = "Malformed JSON"),
+        (status = 409, description = "Login already exists"),
+        (status = 422, description = "Missing or invalid fields", body = InvalidFieldsResponse)
     )
 )]
 pub async fn create_user(
@@ -149,7 +201,8 @@ pub async fn create_user(
     request_body = UserPayload,
     responses(
         (status = 200, description = "User updated", body = User),
-        (status = 404, description = "User not found")
+        (status = 404, description = "User not found"),
+        (status = 422, description = "Missing or invalid fields", body = InvalidFieldsResponse)
     )
 )]
 pub async fn update_user(
@@ -189,7 +242,11 @@ pub async fn update_user(
     }
 }
 
-/// Delete a user
+/// Delete a user.
+///
+/// The account becomes `pendingDeletion` (login and loans refused, holds cancelled) and is
+/// anonymized by the nightly retention job after `users.deletion_grace_days`; until then
+/// `POST /users/{id}/restore` brings it back. `immediate=true` anonymizes right away.
 #[utoipa::path(
     delete,
     path = "/users/{id}",
@@ -197,10 +254,11 @@ pub async fn update_user(
     security(("bearer_auth" = [])),
     params(
         ("id" = i32, Path, description = "User ID"),
-        ("force" = Option<bool>, Query, description = "Force delete even with active loans")
+        ("force" = Option<bool>, Query, description = "Force delete even with active loans"),
+        ("immediate" = Option<bool>, Query, description = "Anonymize now instead of after the grace period")
     ),
     responses(
-        (status = 204, description = "User deleted"),
+        (status = 204, description = "User deleted (or scheduled for anonymization)"),
         (status = 404, description = "User not found"),
         (status = 409, description = "User has active loans")
     )
@@ -214,15 +272,16 @@ pub async fn delete_user(
 ) -> AppResult<StatusCode> {
     claims.require_write_users()?;
     let force = params.force.unwrap_or(false);
-    match state.services.users.delete_user(id, force).await {
-        Ok(()) => {
+    let immediate = params.immediate.unwrap_or(false);
+    match state.services.users.delete_user(id, force, immediate).await {
+        Ok(scheduled_at) => {
             state.services.audit.log(
                 audit::event::USER_DELETED,
                 Some(claims.user_id),
                 Some("user"),
                 Some(id),
                 ip,
-                Some(serde_json::json!({ "id": id, "force": force })),
+                Some(serde_json::json!({ "id": id, "force": force, "scheduledAt": scheduled_at })),
                 audit::AuditLogMeta::success(),
             );
             Ok(StatusCode::NO_CONTENT)
@@ -242,9 +301,151 @@ pub async fn delete_user(
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct DeleteUserParams {
     pub force: Option<bool>,
+    pub immediate: Option<bool>,
+}
+
+/// Restore a user pending deletion (back to the status it had before the delete)
+#[utoipa::path(
+    post,
+    path = "/users/{id}/restore",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "User restored", body = User),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Insufficient rights"),
+        (status = 404, description = "User not found"),
+        (status = 422, description = "User is not pending deletion (or already anonymized)")
+    )
+)]
+pub async fn restore_user(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<Json<User>> {
+    claims.require_write_users()?;
+    let result = state.services.users.restore_user(id).await;
+    let meta = match &result {
+        Ok(_) => audit::AuditLogMeta::success(),
+        Err(e) => audit::AuditLogMeta::from_app_error(e),
+    };
+    state.services.audit.log(audit::event::USER_RESTORED, Some(claims.user_id), Some("user"), Some(id), ip, None::<serde_json::Value>, meta);
+    Ok(Json(result?))
+}
+
+/// Merge a duplicate patron account into the surviving one
+///
+/// Loans (active and archived), fines, holds, reading program enrollments, loan batches and
+/// donations move to the survivor; the duplicate is archived (`deleted`, `mergedInto` set).
+/// Survivor contact fields left empty are filled from the duplicate, differing ones are reported.
+#[utoipa::path(
+    post,
+    path = "/users/merge",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    request_body = MergeUsers,
+    responses(
+        (status = 200, description = "Merge report (nothing is written with `dryRun`)", body = UserMergeReport),
+        (status = 400, description = "Same account on both sides"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Insufficient rights"),
+        (status = 404, description = "User not found"),
+        (status = 422, description = "Staff accounts cannot be merged, or invalid fields (`invalid_fields`)")
+    )
+)]
+pub async fn merge_users(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    ValidatedJson(request): ValidatedJson<MergeUsers>,
+) -> AppResult<Json<UserMergeReport>> {
+    claims.require_write_users()?;
+    let result = state.services.users.merge_users(&request).await;
+    if !request.dry_run {
+        let (meta, payload) = match &result {
+            Ok(report) => (
+                audit::AuditLogMeta::success(),
+                serde_json::json!({
+                    "duplicateId": report.duplicate_id.to_string(),
+                    "moved": report.moved,
+                    "filled": report.filled,
+                    "conflicts": report.conflicts,
+                }),
+            ),
+            Err(e) => (
+                audit::AuditLogMeta::from_app_error(e),
+                serde_json::json!({ "duplicateId": request.duplicate_id.to_string() }),
+            ),
+        };
+        state.services.audit.log(audit::event::USER_MERGED, Some(claims.user_id), Some("user"), Some(request.survivor_id), ip, Some(payload), meta);
+    }
+    Ok(Json(result?))
+}
+
+/// Start a membership expiry campaign
+///
+/// Sets the membership end date (`expiryAt`) of every account matching the filters and, with
+/// `sendInvitations`, emails each one a renewal invitation (`membership_renewal` template). Runs
+/// as a background task: poll `GET /tasks/{taskId}` for the `UserExpiryCampaignReport`, which
+/// lists the outcome of every account. `dryRun` only reports the targeted accounts.
+#[utoipa::path(
+    post,
+    path = "/users/expiry-campaign",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    request_body = UserExpiryCampaign,
+    responses(
+        (status = 202, description = "Campaign started; poll GET /tasks/:id", body = TaskAcceptedResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Insufficient rights"),
+        (status = 422, description = "No filter, empty window or invitations without `renewalUrl` (`invalid_fields`)")
+    )
+)]
+pub async fn run_expiry_campaign(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    ValidatedJson(campaign): ValidatedJson<UserExpiryCampaign>,
+) -> AppResult<(StatusCode, Json<TaskAcceptedResponse>)> {
+    claims.require_write_users()?;
+    campaign.validate_targeting()?;
+
+    let users = state.services.users.clone();
+    let audit = state.services.audit.clone();
+    let throttle = std::time::Duration::from_millis(state.dynamic_config.read_reminders().smtp_throttle_ms);
+    let user_id = claims.user_id;
+    let task_id = state.services.tasks.spawn_task(TaskKind::UserExpiryCampaign, user_id, move |handle| async move {
+        let result = users.run_expiry_campaign(&campaign, throttle, Some(handle.clone())).await;
+        if !campaign.dry_run {
+            let meta = match &result {
+                Ok(_) => audit::AuditLogMeta::success(),
+                Err(e) => audit::AuditLogMeta::from_app_error(e),
+            };
+            let counts = result.as_ref().ok().map(|r| {
+                serde_json::json!({ "matched": r.matched, "updated": r.updated, "invited": r.invited })
+            });
+            audit.log(
+                audit::event::USER_EXPIRY_CAMPAIGN,
+                Some(user_id),
+                None,
+                None,
+                ip,
+                Some(serde_json::json!({ "campaign": &campaign, "report": counts })),
+                meta,
+            );
+        }
+        match result {
+            Ok(report) => handle.complete(serde_json::to_value(&report).unwrap_or_default()).await,
+            Err(e) => handle.fail(e.to_string()).await,
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(TaskAcceptedResponse { task_id })))
 }
 
 /// Update own profile (name, password)
@@ -257,7 +458,9 @@ pub struct DeleteUserParams {
     responses(
         (status = 200, description = "Profile updated", body = User),
         (status = 400, description = "Invalid input"),
-        (status = 401, description = "Not authenticated or wrong current password")
+        (status = 422, description = "Invalid fields", body = InvalidFieldsResponse),
+        (status = 401, description = "Not authenticated or wrong current password"),
+        (status = 403, description = "Credential change attempted with an impersonation token")
     )
 )]
 pub async fn update_my_profile(
@@ -265,6 +468,10 @@ pub async fn update_my_profile(
     AuthenticatedUser(claims): AuthenticatedUser,
     ValidatedJson(profile): ValidatedJson<UpdateProfile>,
 ) -> AppResult<Json<User>> {
+    // Credentials (password, email used for resets and 2FA codes, login) stay with the patron
+    if profile.new_password.is_some() || profile.email.is_some() || profile.login.is_some() {
+        claims.require_not_impersonating()?;
+    }
     let updated = state.services.users.update_profile(claims.user_id, profile).await?;
     Ok(Json(updated))
 }
@@ -282,7 +489,8 @@ pub async fn update_my_profile(
     responses(
         (status = 200, description = "Account type updated", body = User),
         (status = 403, description = "Admin privileges required"),
-        (status = 404, description = "User not found")
+        (status = 404, description = "User not found"),
+        (status = 422, description = "Invalid fields", body = InvalidFieldsResponse)
     )
 )]
 pub async fn update_account_type(
@@ -290,7 +498,7 @@ pub async fn update_account_type(
     AuthenticatedUser(claims): AuthenticatedUser,
     ClientIp(ip): ClientIp,
     Path(id): Path<i64>,
-    Json(request): Json<UpdateAccountType>,
+    ValidatedJson(request): ValidatedJson<UpdateAccountType>,
 ) -> AppResult<Json<User>> {
     claims.require_admin()?;
     match state
@@ -334,7 +542,7 @@ pub async fn update_account_type(
     security(("bearer_auth" = [])),
     params(("id" = i64, Path, description = "User ID")),
     responses(
-        (status = 200, description = "Flag updated"),
+        (status = 200, description = "Flag updated", body = serde_json::Value),
         (status = 401, description = "Not authenticated"),
         (status = 403, description = "Admin privileges required"),
         (status = 404, description = "User not found")
@@ -379,3 +587,110 @@ pub async fn force_password_change(
     }
 }
 
+/// Set or clear the self-service PIN of a patron (used with `POST /auth/login-barcode`).
+///
+/// Also lifts a barcode-login lockout. Staff accounts cannot have a PIN.
+#[utoipa::path(
+    put,
+    path = "/users/{id}/pin",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "User ID")),
+    request_body = SetUserPin,
+    responses(
+        (status = 204, description = "PIN updated"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Insufficient rights"),
+        (status = 404, description = "User not found"),
+        (status = 422, description = "PIN is not 4 to 8 digits (`invalid_fields`) or staff account")
+    )
+)]
+pub async fn set_user_pin(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    ValidatedJson(request): ValidatedJson<SetUserPin>,
+) -> AppResult<StatusCode> {
+    claims.require_write_users()?;
+    let result = state.services.users.set_pin(id, request.pin.as_deref()).await;
+    let meta = match &result {
+        Ok(()) => audit::AuditLogMeta::success(),
+        Err(e) => audit::AuditLogMeta::from_app_error(e),
+    };
+    state.services.audit.log(audit::event::USER_PIN_SET, Some(claims.user_id), Some("user"), Some(id), ip, Some(serde_json::json!({ "enabled": request.pin.is_some() })), meta);
+    result?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Start impersonation request body
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpersonateRequest {
+    /// Why support needs to act as this patron (kept in the audit log)
+    #[validate(length(max = 500, message = "Reason must be at most 500 characters"))]
+    pub reason: Option<String>,
+}
+
+/// Impersonation token
+#[serde_as]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpersonationResponse {
+    /// JWT acting as the patron, with the patron's rights
+    pub token: String,
+    /// Token type (always "Bearer")
+    pub token_type: String,
+    /// Token expiration time in seconds (no refresh)
+    pub expires_in: i64,
+    /// Admin behind the session
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub impersonated_by: i64,
+    pub user: UserInfo,
+}
+
+/// See what a patron sees: issue a short-lived token acting as them (admin only).
+///
+/// The token cannot change the patron's password, PIN, email, login or 2FA settings, and
+/// every request made with it is audited with both identities (`user.impersonated_request`).
+#[utoipa::path(
+    post,
+    path = "/users/{id}/impersonate",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "User ID")),
+    request_body = ImpersonateRequest,
+    responses(
+        (status = 200, description = "Impersonation token", body = ImpersonationResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Admin privileges required"),
+        (status = 404, description = "User not found"),
+        (status = 422, description = "Staff accounts and yourself cannot be impersonated, or invalid fields (`invalid_fields`)")
+    )
+)]
+pub async fn impersonate_user(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    ValidatedJson(request): ValidatedJson<ImpersonateRequest>,
+) -> AppResult<Json<ImpersonationResponse>> {
+    claims.require_admin()?;
+    claims.require_not_impersonating()?;
+    let result = state.services.users.impersonate(claims.user_id, id).await;
+    let meta = match &result {
+        Ok(_) => audit::AuditLogMeta::success(),
+        Err(e) => audit::AuditLogMeta::from_app_error(e),
+    };
+    state.services.audit.log(audit::event::USER_IMPERSONATION_STARTED, Some(claims.user_id), Some("user"), Some(id), ip, Some(serde_json::json!({ "impersonatorId": claims.user_id, "impersonatedUserId": id, "reason": request.reason })), meta);
+    let (token, user) = result?;
+
+    Ok(Json(ImpersonationResponse {
+        token,
+        token_type: "Bearer".to_string(),
+        expires_in: IMPERSONATION_TOKEN_SECONDS,
+        impersonated_by: claims.user_id,
+        user: UserInfo::from(user),
+    }))
+}
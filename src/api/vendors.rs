@@ -0,0 +1,325 @@
+//! Vendors (suppliers) and purchase orders API endpoints
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    error::AppResult,
+    models::vendor::{
+        CreatePurchaseOrder, CreateVendor, PurchaseOrder, PurchaseOrderQuery, UpdatePurchaseOrder,
+        UpdateVendor, Vendor, VendorAnnualSpend, VendorSpendQuery,
+    },
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Build the vendors and purchase orders routes for this domain.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::get;
+    axum::Router::new()
+        .route("/vendors", get(list_vendors).post(create_vendor))
+        .route("/vendors/spend", get(get_vendor_spend))
+        .route("/vendors/:id", get(get_vendor).put(update_vendor).delete(delete_vendor))
+        .route("/purchase-orders", get(list_purchase_orders).post(create_purchase_order))
+        .route(
+            "/purchase-orders/:id",
+            get(get_purchase_order).put(update_purchase_order).delete(delete_purchase_order),
+        )
+}
+
+/// Query parameters for listing vendors
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorsQuery {
+    /// Include inactive vendors (default: false)
+    pub include_inactive: Option<bool>,
+}
+
+/// List vendors
+#[utoipa::path(
+    get,
+    path = "/vendors",
+    tag = "vendors",
+    security(("bearer_auth" = [])),
+    params(VendorsQuery),
+    responses(
+        (status = 200, description = "Vendors list", body = Vec<Vendor>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_vendors(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<VendorsQuery>,
+) -> AppResult<Json<Vec<Vendor>>> {
+    claims.require_read_items()?;
+    let vendors = state.services.vendors.list(query.include_inactive.unwrap_or(false)).await?;
+    Ok(Json(vendors))
+}
+
+/// Get a vendor by ID
+#[utoipa::path(
+    get,
+    path = "/vendors/{id}",
+    tag = "vendors",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Vendor ID")),
+    responses(
+        (status = 200, description = "Vendor details", body = Vendor),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_vendor(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Vendor>> {
+    claims.require_read_items()?;
+    let vendor = state.services.vendors.get_by_id(id).await?;
+    Ok(Json(vendor))
+}
+
+/// Create a vendor
+#[utoipa::path(
+    post,
+    path = "/vendors",
+    tag = "vendors",
+    security(("bearer_auth" = [])),
+    request_body = CreateVendor,
+    responses(
+        (status = 201, description = "Vendor created", body = Vendor),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 409, description = "A vendor with this name already exists", body = ErrorResponse),
+    )
+)]
+pub async fn create_vendor(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateVendor>,
+) -> AppResult<(StatusCode, Json<Vendor>)> {
+    claims.require_write_items()?;
+    let vendor = state.services.vendors.create(&data).await?;
+    state.services.audit.log(audit::event::VENDOR_CREATED, Some(claims.user_id), Some("vendor"), Some(vendor.id), ip, Some(&vendor), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(vendor)))
+}
+
+/// Update a vendor
+#[utoipa::path(
+    put,
+    path = "/vendors/{id}",
+    tag = "vendors",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Vendor ID")),
+    request_body = UpdateVendor,
+    responses(
+        (status = 200, description = "Vendor updated", body = Vendor),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "A vendor with this name already exists", body = ErrorResponse),
+    )
+)]
+pub async fn update_vendor(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdateVendor>,
+) -> AppResult<Json<Vendor>> {
+    claims.require_write_items()?;
+    let vendor = state.services.vendors.update(id, &data).await?;
+    state.services.audit.log(audit::event::VENDOR_UPDATED, Some(claims.user_id), Some("vendor"), Some(id), ip, Some((&data, &vendor)), audit::AuditLogMeta::success());
+    Ok(Json(vendor))
+}
+
+/// Delete a vendor (fails while purchase orders reference it; linked sources are unlinked)
+#[utoipa::path(
+    delete,
+    path = "/vendors/{id}",
+    tag = "vendors",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Vendor ID")),
+    responses(
+        (status = 204, description = "Vendor deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Vendor has purchase orders", body = ErrorResponse),
+    )
+)]
+pub async fn delete_vendor(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_items()?;
+    state.services.vendors.delete(id).await?;
+    state.services.audit.log(audit::event::VENDOR_DELETED, Some(claims.user_id), Some("vendor"), Some(id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Annual spend per vendor (orders count, ordered and received amounts)
+#[utoipa::path(
+    get,
+    path = "/vendors/spend",
+    tag = "vendors",
+    security(("bearer_auth" = [])),
+    params(VendorSpendQuery),
+    responses(
+        (status = 200, description = "Spend per vendor and year", body = Vec<VendorAnnualSpend>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn get_vendor_spend(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<VendorSpendQuery>,
+) -> AppResult<Json<Vec<VendorAnnualSpend>>> {
+    claims.require_read_items()?;
+    let spend = state.services.vendors.annual_spend(&query).await?;
+    Ok(Json(spend))
+}
+
+/// List purchase orders
+#[utoipa::path(
+    get,
+    path = "/purchase-orders",
+    tag = "vendors",
+    security(("bearer_auth" = [])),
+    params(PurchaseOrderQuery),
+    responses(
+        (status = 200, description = "Purchase orders", body = Vec<PurchaseOrder>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+    )
+)]
+pub async fn list_purchase_orders(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Query(query): Query<PurchaseOrderQuery>,
+) -> AppResult<Json<Vec<PurchaseOrder>>> {
+    claims.require_read_items()?;
+    let orders = state.services.vendors.list_orders(&query).await?;
+    Ok(Json(orders))
+}
+
+/// Get a purchase order by ID
+#[utoipa::path(
+    get,
+    path = "/purchase-orders/{id}",
+    tag = "vendors",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Purchase order ID")),
+    responses(
+        (status = 200, description = "Purchase order", body = PurchaseOrder),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_purchase_order(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<PurchaseOrder>> {
+    claims.require_read_items()?;
+    let order = state.services.vendors.get_order(id).await?;
+    Ok(Json(order))
+}
+
+/// Create a purchase order
+#[utoipa::path(
+    post,
+    path = "/purchase-orders",
+    tag = "vendors",
+    security(("bearer_auth" = [])),
+    request_body = CreatePurchaseOrder,
+    responses(
+        (status = 201, description = "Purchase order created", body = PurchaseOrder),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Vendor or source not found", body = ErrorResponse),
+    )
+)]
+pub async fn create_purchase_order(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreatePurchaseOrder>,
+) -> AppResult<(StatusCode, Json<PurchaseOrder>)> {
+    claims.require_write_items()?;
+    let order = state.services.vendors.create_order(&data).await?;
+    state.services.audit.log(audit::event::PURCHASE_ORDER_CREATED, Some(claims.user_id), Some("purchase_order"), Some(order.id), ip, Some(&order), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(order)))
+}
+
+/// Update a purchase order
+#[utoipa::path(
+    put,
+    path = "/purchase-orders/{id}",
+    tag = "vendors",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Purchase order ID")),
+    request_body = UpdatePurchaseOrder,
+    responses(
+        (status = 200, description = "Purchase order updated", body = PurchaseOrder),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_purchase_order(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdatePurchaseOrder>,
+) -> AppResult<Json<PurchaseOrder>> {
+    claims.require_write_items()?;
+    let order = state.services.vendors.update_order(id, &data).await?;
+    state.services.audit.log(audit::event::PURCHASE_ORDER_UPDATED, Some(claims.user_id), Some("purchase_order"), Some(id), ip, Some((&data, &order)), audit::AuditLogMeta::success());
+    Ok(Json(order))
+}
+
+/// Delete a purchase order
+#[utoipa::path(
+    delete,
+    path = "/purchase-orders/{id}",
+    tag = "vendors",
+    security(("bearer_auth" = [])),
+    params(("id" = i32, Path, description = "Purchase order ID")),
+    responses(
+        (status = 204, description = "Purchase order deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_purchase_order(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_items()?;
+    state.services.vendors.delete_order(id).await?;
+    state.services.audit.log(audit::event::PURCHASE_ORDER_DELETED, Some(claims.user_id), Some("purchase_order"), Some(id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
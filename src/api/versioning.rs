@@ -0,0 +1,198 @@
+//! API versions and deprecation notices.
+//!
+//! Every version nests the same domain routers, so handlers are shared as long as an endpoint
+//! does not change. An endpoint whose contract changes in a new version moves out of its domain
+//! `router()` into one router per version, merged only under its own prefix. Retiring a version
+//! (`api.v1_enabled = false`) answers all of its paths with 410 Gone.
+//!
+//! Deprecations are announced from the `[api]` config section on the responses themselves:
+//! `Deprecation` (RFC 9745, `@<unix time>`), `Sunset` (RFC 8594, HTTP date) and `Link` to the
+//! replacement (`rel="deprecation"` for an endpoint, `rel="successor-version"` for all of v1).
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+    Router,
+};
+use chrono::{DateTime, Utc};
+
+use crate::{
+    config::{ApiConfig, DeprecatedEndpoint},
+    error::AppError,
+};
+
+/// `Deprecation` response header (RFC 9745)
+pub const DEPRECATION_HEADER: &str = "deprecation";
+/// `Sunset` response header (RFC 8594)
+pub const SUNSET_HEADER: &str = "sunset";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    /// Version of the links the server generates (feeds, covers, downloads): always served
+    pub const LATEST: ApiVersion = ApiVersion::V2;
+
+    pub fn prefix(self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "/api/v1",
+            ApiVersion::V2 => "/api/v2",
+        }
+    }
+}
+
+/// Nest `routes` under every version; v1 answers 410 Gone once disabled.
+pub fn nest_versions(router: Router, routes: Router, config: &ApiConfig) -> Router {
+    let router = router.nest(ApiVersion::V2.prefix(), routes.clone());
+    if config.v1_enabled {
+        router.nest(ApiVersion::V1.prefix(), routes)
+    } else {
+        router.nest(ApiVersion::V1.prefix(), Router::new().fallback(v1_retired))
+    }
+}
+
+async fn v1_retired() -> AppError {
+    AppError::Gone(format!("API v1 has been retired; use {}", ApiVersion::LATEST.prefix()))
+}
+
+/// Deprecation notices of the `[api]` config section, matched against request paths
+#[derive(Debug, Clone, Default)]
+pub struct Deprecations {
+    v1_deprecated_at: Option<DateTime<Utc>>,
+    v1_sunset_at: Option<DateTime<Utc>>,
+    endpoints: Vec<DeprecatedEndpoint>,
+}
+
+impl Deprecations {
+    pub fn from_config(config: &ApiConfig) -> Self {
+        Self {
+            v1_deprecated_at: config.v1_deprecated_at,
+            v1_sunset_at: config.v1_sunset_at,
+            endpoints: config.deprecated_endpoints.clone(),
+        }
+    }
+
+    /// Deprecation headers of a request (none when nothing is deprecated)
+    pub fn headers(&self, method: &Method, path: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let endpoint = self.endpoints.iter().find(|e| {
+            e.method.as_deref().is_none_or(|m| m.eq_ignore_ascii_case(method.as_str()))
+                && route_matches(&e.path, path)
+        });
+        if let Some(endpoint) = endpoint {
+            insert_dates(&mut headers, Some(endpoint.deprecated_at), endpoint.sunset_at);
+            if let Some(link) = &endpoint.link {
+                insert(&mut headers, "link", format!("<{}>; rel=\"deprecation\"", link));
+            }
+        } else if let Some(rest) = path.strip_prefix(ApiVersion::V1.prefix()) {
+            if self.v1_deprecated_at.is_none() && self.v1_sunset_at.is_none() {
+                return headers;
+            }
+            insert_dates(&mut headers, self.v1_deprecated_at, self.v1_sunset_at);
+            insert(
+                &mut headers,
+                "link",
+                format!("<{}{}>; rel=\"successor-version\"", ApiVersion::LATEST.prefix(), rest),
+            );
+        }
+        headers
+    }
+}
+
+fn insert_dates(headers: &mut HeaderMap, deprecated_at: Option<DateTime<Utc>>, sunset_at: Option<DateTime<Utc>>) {
+    if let Some(at) = deprecated_at {
+        insert(headers, DEPRECATION_HEADER, format!("@{}", at.timestamp()));
+    }
+    if let Some(at) = sunset_at {
+        insert(headers, SUNSET_HEADER, at.format("%a, %d %b %Y %H:%M:%S GMT").to_string());
+    }
+}
+
+fn insert(headers: &mut HeaderMap, name: &'static str, value: String) {
+    if let Ok(value) = HeaderValue::from_str(&value) {
+        headers.insert(HeaderName::from_static(name), value);
+    }
+}
+
+/// Whether `path` matches a route template (`:name` or `{name}` segments match any value,
+/// a trailing `*rest` the remaining segments)
+fn route_matches(template: &str, path: &str) -> bool {
+    let mut template = template.trim_end_matches('/').split('/');
+    let mut path = path.trim_end_matches('/').split('/');
+    loop {
+        match (template.next(), path.next()) {
+            (None, None) => return true,
+            (Some(t), Some(p)) if t.starts_with('*') => return !p.is_empty(),
+            (Some(t), Some(p)) if t.starts_with(':') || (t.starts_with('{') && t.ends_with('}')) => {
+                if p.is_empty() {
+                    return false;
+                }
+            }
+            (Some(t), Some(p)) if t == p => {}
+            _ => return false,
+        }
+    }
+}
+
+/// Middleware adding the deprecation headers to responses
+pub async fn deprecation_headers(State(deprecations): State<Arc<Deprecations>>, request: Request, next: Next) -> Response {
+    let headers = deprecations.headers(request.method(), request.uri().path());
+    let mut response = next.run(request).await;
+    response.headers_mut().extend(headers);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn templates_match_parameters() {
+        assert!(route_matches("/api/v1/items/:id", "/api/v1/items/42"));
+        assert!(route_matches("/api/v1/items/{id}", "/api/v1/items/42/"));
+        assert!(!route_matches("/api/v1/items/:id", "/api/v1/items"));
+        assert!(!route_matches("/api/v1/items/:id", "/api/v1/items/42/loans"));
+        assert!(!route_matches("/api/v1/items/:id", "/api/v2/items/42"));
+        assert!(route_matches("/api/v1/stats/*rest", "/api/v1/stats/loans/monthly"));
+    }
+
+    #[test]
+    fn endpoint_then_version_notices() {
+        let deprecations = Deprecations {
+            v1_deprecated_at: Some(at("2026-11-01T00:00:00Z")),
+            v1_sunset_at: Some(at("2027-06-30T00:00:00Z")),
+            endpoints: vec![DeprecatedEndpoint {
+                method: Some("get".to_string()),
+                path: "/api/v2/items/:id/loans".to_string(),
+                deprecated_at: at("2026-10-01T00:00:00Z"),
+                sunset_at: None,
+                link: Some("https://docs.example.org/items".to_string()),
+            }],
+        };
+
+        let headers = deprecations.headers(&Method::GET, "/api/v1/items/7");
+        assert_eq!(headers[DEPRECATION_HEADER], "@1793491200");
+        assert_eq!(headers[SUNSET_HEADER], "Wed, 30 Jun 2027 00:00:00 GMT");
+        assert_eq!(headers["link"], "</api/v2/items/7>; rel=\"successor-version\"");
+
+        let headers = deprecations.headers(&Method::GET, "/api/v2/items/7/loans");
+        assert_eq!(headers[DEPRECATION_HEADER], "@1790812800");
+        assert!(headers.get(SUNSET_HEADER).is_none());
+        assert_eq!(headers["link"], "<https://docs.example.org/items>; rel=\"deprecation\"");
+
+        assert!(deprecations.headers(&Method::POST, "/api/v2/items/7/loans").is_empty());
+        assert!(deprecations.headers(&Method::GET, "/api/v2/items/7").is_empty());
+        assert!(Deprecations::default().headers(&Method::GET, "/api/v1/items/7").is_empty());
+    }
+}
@@ -0,0 +1,264 @@
+//! Data warehouse export API endpoints (`/warehouse/targets`, `/warehouse/runs`)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use utoipa::ToSchema;
+
+use crate::{
+    error::AppResult,
+    models::{
+        task::TaskKind,
+        warehouse::{
+            CreateWarehouseTarget, UpdateWarehouseTarget, WarehouseRun, WarehouseRunStatus, WarehouseRunsQuery,
+            WarehouseTarget,
+        },
+    },
+    services::audit,
+};
+
+use super::{AuthenticatedUser, ClientIp};
+
+/// Manual run accepted: the run report is updated when the background task ends
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WarehouseRunStarted {
+    /// Background task id (`GET /tasks/:id`)
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub task_id: i64,
+    pub run: WarehouseRun,
+}
+
+/// Build the `/warehouse/*` routes.
+pub fn router() -> axum::Router<crate::AppState> {
+    use axum::routing::{get, post};
+    axum::Router::new()
+        .route("/warehouse/targets", get(list_warehouse_targets).post(create_warehouse_target))
+        .route(
+            "/warehouse/targets/:id",
+            get(get_warehouse_target).put(update_warehouse_target).delete(delete_warehouse_target),
+        )
+        .route("/warehouse/targets/:id/run", post(run_warehouse_target))
+        .route("/warehouse/targets/:id/runs", get(list_warehouse_runs))
+        .route("/warehouse/runs/:id", get(get_warehouse_run))
+}
+
+/// List warehouse export targets
+#[utoipa::path(
+    get,
+    path = "/warehouse/targets",
+    tag = "warehouse",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Warehouse targets", body = Vec<WarehouseTarget>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+    )
+)]
+pub async fn list_warehouse_targets(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> AppResult<Json<Vec<WarehouseTarget>>> {
+    claims.require_read_settings()?;
+    let targets = state.services.warehouse.list_targets().await?;
+    Ok(Json(targets))
+}
+
+/// Get a warehouse export target
+#[utoipa::path(
+    get,
+    path = "/warehouse/targets/{id}",
+    tag = "warehouse",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Warehouse target ID")),
+    responses(
+        (status = 200, description = "Warehouse target", body = WarehouseTarget),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_warehouse_target(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<WarehouseTarget>> {
+    claims.require_read_settings()?;
+    let target = state.services.warehouse.get_target(id).await?;
+    Ok(Json(target))
+}
+
+/// Create a warehouse export target
+#[utoipa::path(
+    post,
+    path = "/warehouse/targets",
+    tag = "warehouse",
+    security(("bearer_auth" = [])),
+    request_body = CreateWarehouseTarget,
+    responses(
+        (status = 201, description = "Warehouse target created", body = WarehouseTarget),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+    )
+)]
+pub async fn create_warehouse_target(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Json(data): Json<CreateWarehouseTarget>,
+) -> AppResult<(StatusCode, Json<WarehouseTarget>)> {
+    claims.require_write_settings()?;
+    let target = state.services.warehouse.create_target(&data).await?;
+    state.services.audit.log(audit::event::WAREHOUSE_TARGET_CREATED, Some(claims.user_id), Some("warehouse_target"), Some(target.id), ip, Some(&target), audit::AuditLogMeta::success());
+    Ok((StatusCode::CREATED, Json(target)))
+}
+
+/// Update a warehouse export target (an empty `password` removes it, `resetWatermarks` sends the full history again)
+#[utoipa::path(
+    put,
+    path = "/warehouse/targets/{id}",
+    tag = "warehouse",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Warehouse target ID")),
+    request_body = UpdateWarehouseTarget,
+    responses(
+        (status = 200, description = "Warehouse target updated", body = WarehouseTarget),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_warehouse_target(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+    Json(data): Json<UpdateWarehouseTarget>,
+) -> AppResult<Json<WarehouseTarget>> {
+    claims.require_write_settings()?;
+    let target = state.services.warehouse.update_target(id, &data).await?;
+    state.services.audit.log(audit::event::WAREHOUSE_TARGET_UPDATED, Some(claims.user_id), Some("warehouse_target"), Some(id), ip, Some(&target), audit::AuditLogMeta::success());
+    Ok(Json(target))
+}
+
+/// Delete a warehouse export target and its run history (exported files stay on the remote side)
+#[utoipa::path(
+    delete,
+    path = "/warehouse/targets/{id}",
+    tag = "warehouse",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Warehouse target ID")),
+    responses(
+        (status = 204, description = "Warehouse target deleted"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_warehouse_target(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    claims.require_write_settings()?;
+    state.services.warehouse.delete_target(id).await?;
+    state.services.audit.log(audit::event::WAREHOUSE_TARGET_DELETED, Some(claims.user_id), Some("warehouse_target"), Some(id), ip, None::<serde_json::Value>, audit::AuditLogMeta::success());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Run an export now, in the background
+#[utoipa::path(
+    post,
+    path = "/warehouse/targets/{id}/run",
+    tag = "warehouse",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Warehouse target ID")),
+    responses(
+        (status = 202, description = "Run started", body = WarehouseRunStarted),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights, or demo mode", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "An export to this target is in progress", body = ErrorResponse),
+    )
+)]
+pub async fn run_warehouse_target(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<(StatusCode, Json<WarehouseRunStarted>)> {
+    claims.require_write_settings()?;
+    super::demo::refuse_in_demo_mode(&state, "Warehouse exports")?;
+    let warehouse = state.services.warehouse.clone();
+    let (target, run) = warehouse.start_run(id, claims.user_id).await?;
+
+    let started = run.clone();
+    let task_id = state.services.tasks.spawn_task(TaskKind::WarehouseExport, claims.user_id, move |handle| async move {
+        match warehouse.execute(target, run).await {
+            Ok(run) if run.status == WarehouseRunStatus::Failed => {
+                handle.fail(run.error.unwrap_or_else(|| "Warehouse export failed".to_string())).await;
+            }
+            Ok(run) => handle.complete(serde_json::to_value(&run).unwrap_or_default()).await,
+            Err(e) => handle.fail(e.to_string()).await,
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(WarehouseRunStarted { task_id, run: started })))
+}
+
+/// Run history of a warehouse export target (most recent first)
+#[utoipa::path(
+    get,
+    path = "/warehouse/targets/{id}/runs",
+    tag = "warehouse",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Warehouse target ID"), WarehouseRunsQuery),
+    responses(
+        (status = 200, description = "Warehouse runs", body = Vec<WarehouseRun>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn list_warehouse_runs(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+    Query(query): Query<WarehouseRunsQuery>,
+) -> AppResult<Json<Vec<WarehouseRun>>> {
+    claims.require_read_settings()?;
+    let runs = state.services.warehouse.runs(id, query.limit).await?;
+    Ok(Json(runs))
+}
+
+/// Get a warehouse export run with its manifest
+#[utoipa::path(
+    get,
+    path = "/warehouse/runs/{id}",
+    tag = "warehouse",
+    security(("bearer_auth" = [])),
+    params(("id" = i64, Path, description = "Warehouse run ID")),
+    responses(
+        (status = 200, description = "Warehouse run", body = WarehouseRun),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Insufficient rights", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_warehouse_run(
+    State(state): State<crate::AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    Path(id): Path<i64>,
+) -> AppResult<Json<WarehouseRun>> {
+    claims.require_read_settings()?;
+    let run = state.services.warehouse.run(id).await?;
+    Ok(Json(run))
+}
@@ -5,6 +5,7 @@ use axum::{
     http::StatusCode,
     Json,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use utoipa::{IntoParams, ToSchema};
@@ -19,12 +20,16 @@ use crate::{
     services::audit,
 };
 
-use super::{AuthenticatedUser, ClientIp};
+use super::{biblios::store_processing_slips, AuthenticatedUser, ClientIp};
 
 fn default_z3950_encoding() -> String {
     "utf-8".to_string()
 }
 
+fn default_trust_weight() -> f64 {
+    1.0
+}
+
 /// Z39.50 server configuration (staff-editable).
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
@@ -44,10 +49,100 @@ pub struct Z3950ServerConfig {
     #[serde(default = "default_z3950_encoding")]
     pub encoding: String,
     pub is_active: bool,
+    /// Ranking weight of this server's records in merged search results (default 1, must not be negative)
+    #[serde(default = "default_trust_weight")]
+    pub trust_weight: f64,
+    /// Recent search outcomes (read-only, ignored on update)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<Z3950ServerHealth>,
+}
+
+/// Search outcomes of a Z39.50 server and state of its circuit breaker.
+///
+/// After repeated consecutive failures the circuit opens and searches skip the server until
+/// `openUntil`; the next search after that probes it again.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Z3950ServerHealth {
+    /// Successful searches
+    pub successes: i64,
+    /// Failed searches (connection, search or present errors)
+    pub failures: i64,
+    /// Share of successful searches, `null` before the first one
+    pub success_rate: Option<f64>,
+    /// Mean latency of successful searches in milliseconds
+    pub avg_latency_ms: Option<i64>,
+    /// Latency of the last search in milliseconds
+    pub last_latency_ms: Option<i64>,
+    /// Failures since the last success
+    pub consecutive_failures: i64,
+    /// True while searches skip this server
+    pub circuit_open: bool,
+    /// End of the current cooldown
+    pub open_until: Option<DateTime<Utc>>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_failure_at: Option<DateTime<Utc>>,
+    /// Error of the last failed search
+    pub last_error: Option<String>,
+}
+
+/// Why a server contributed no results to a search
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Z3950DegradedReason {
+    /// Skipped: the circuit is open after repeated failures
+    CircuitOpen,
+    /// Queried, but the search failed
+    Failed,
+    /// Queried, but some records could not be decoded (see `sourceReports`)
+    PartialRecords,
+}
+
+/// Server left out of a search
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Z3950DegradedSource {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub server_id: i64,
+    pub name: String,
+    pub reason: Z3950DegradedReason,
+    /// When the server will be queried again (open circuit only)
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+/// Record of a server's batch that could not be decoded, even in the alternative MARC syntax
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Z3950RecordFailure {
+    /// 1-based position of the record in the server's batch
+    pub position: i32,
+    /// Record leader as received, when readable
+    pub leader: Option<String>,
+    pub error: String,
+}
+
+/// Records returned by one queried server
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Z3950SourceReport {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub server_id: i64,
+    pub name: String,
+    /// Records decoded and listed in `biblios`
+    pub records_returned: i32,
+    /// Of those, records decoded only after retrying with the alternative MARC syntax
+    pub records_recovered: i32,
+    /// Records skipped because they could not be decoded
+    pub records_failed: i32,
+    pub failures: Vec<Z3950RecordFailure>,
 }
 
 /// Partial update of Z39.50 server list.
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateZ3950ServersRequest {
     pub z3950_servers: Option<Vec<Z3950ServerConfig>>,
@@ -55,30 +150,42 @@ pub struct UpdateZ3950ServersRequest {
 
 /// Z39.50 search query parameters
 #[serde_as]
-#[derive(Deserialize, IntoParams, ToSchema, Debug)]
+#[derive(Serialize, Deserialize, IntoParams, ToSchema, Debug)]
 #[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
 pub struct Z3950SearchQuery {
+    /// Search terms (ISBN, title and/or author)
     pub query: String,
+    /// Server to query (default: first active server)
     #[serde_as(as = "Option<DisplayFromStr>")]
     #[schema(value_type = Option<String>)]
+    #[param(value_type = Option<String>)]
     pub server_id: Option<i64>,
+    /// Max results (default: 50)
     pub max_results: Option<i32>,
 }
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Z3950SearchResponse {
     /// Total results found
     pub total: i32,
-    /// List of found bibliographic records
+    /// Found bibliographic records, best first: exact ISBN matches, then by richness of the
+    /// record (ISBN, collection, subjects) weighted by the server's `trustWeight`
     pub biblios: Vec<Biblio>,
     /// Source server name
     pub source: String,
+    /// Servers skipped or failed during this search (results may be incomplete)
+    #[serde(default)]
+    pub degraded_sources: Vec<Z3950DegradedSource>,
+    /// Per-server record counts, including records that could not be decoded
+    #[serde(default)]
+    pub source_reports: Vec<Z3950SourceReport>,
 }
 
 /// Z39.50 import request
 #[serde_as]
-#[derive(Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Z3950ImportRequest {
     /// Remote biblio ID to import
@@ -91,10 +198,13 @@ pub struct Z3950ImportRequest {
     #[serde_as(as = "Option<DisplayFromStr>")]
     #[schema(value_type = Option<String>)]
     pub confirm_replace_existing_id: Option<i64>,
+    /// If true, store a PDF processing slip artifact per created copy
+    #[serde(default)]
+    pub processing_slips: bool,
 }
 
 #[serde_as]
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ImportItem {
     /// Item barcode (must be unique when provided)
@@ -135,23 +245,34 @@ impl From<ImportItem> for Item {
             circulation_status: None,
             notes: s.notes,
             price: s.price,
+            access_url: None,
+            access_type: None,
             created_at: None,
             updated_at: None,
             archived_at: None,
             source_name: None,
             borrowed: false,
+            deposit_id: None,
+            transit_place: None,
+            inventory_number: None,
+            location_id: None,
+            location_path: None,
+            hidden_from_opac: false,
         }
     }
 }
 
 /// Response body for Z39.50 import (biblio + dedup report)
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Z3950ImportResponse {
     /// The imported or updated bibliographic record
     pub biblio: Biblio,
     /// Deduplication report
     pub import_report: ImportReport,
+    /// Processing slip artifacts, one per created copy (with `processingSlips`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slip_artifact_ids: Vec<String>,
 }
 
 /// Search remote catalogs via Z39.50
@@ -160,12 +281,7 @@ pub struct Z3950ImportResponse {
     path = "/z3950/search",
     tag = "z3950",
     security(("bearer_auth" = [])),
-    params(
-        ("isbn" = Option<String>, Query, description = "ISBN to search"),
-        ("title" = Option<String>, Query, description = "Title to search"),
-        ("author" = Option<String>, Query, description = "Author to search"),
-        ("max_results" = Option<i32>, Query, description = "Max results (default: 50)")
-    ),
+    params(Z3950SearchQuery),
     responses(
         (status = 200, description = "Search results", body = Z3950SearchResponse),
         (status = 502, description = "Z39.50 server error")
@@ -178,13 +294,7 @@ pub async fn search(
 ) -> AppResult<Json<Z3950SearchResponse>> {
     claims.require_read_items()?;
 
-    let (biblios, total, source) = state.services.z3950.search(&query).await?;
-
-    Ok(Json(Z3950SearchResponse {
-        total,
-        biblios,
-        source,
-    }))
+    Ok(Json(state.services.z3950.search(&query).await?))
 }
 
 /// Import a record from Z39.50 search results into local catalog.
@@ -198,7 +308,7 @@ pub async fn search(
     responses(
         (status = 201, description = "Record imported or merged", body = Z3950ImportResponse),
         (status = 404, description = "Remote item not found"),
-        (status = 409, description = "Duplicate ISBN requires confirmation", body = crate::models::import_report::DuplicateConfirmationRequired)
+        (status = 409, description = "Duplicate ISBN requires confirmation", body = DuplicateConfirmationRequired)
     )
 )]
 pub async fn import_record(
@@ -219,7 +329,16 @@ pub async fn import_record(
         )
         .await?;
 
-    Ok((StatusCode::CREATED, Json(Z3950ImportResponse { biblio, import_report })))
+    let mut slip_artifact_ids = Vec::new();
+    if request.processing_slips {
+        let item_ids: Vec<i64> = biblio.items.iter().filter_map(|item| item.id).collect();
+        match store_processing_slips(&state.services.exports, &state.services.artifacts, &item_ids, claims.user_id).await {
+            Ok(ids) => slip_artifact_ids = ids,
+            Err(e) => tracing::warn!("Failed to store processing slips of biblio {:?}: {}", biblio.id, e),
+        }
+    }
+
+    Ok((StatusCode::CREATED, Json(Z3950ImportResponse { biblio, import_report, slip_artifact_ids })))
 }
 
 /// List Z39.50 server definitions (staff).
@@ -2,7 +2,9 @@
 
 use config::{Config, ConfigError, Environment, File};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::{collections::HashMap, path::Path};
+
+use crate::models::hold::HoldPriority;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ServerConfig {
@@ -24,6 +26,32 @@ pub struct ServerConfig {
     /// Burst size for public endpoint rate limiter (default: 100).
     #[serde(default)]
     pub public_rate_burst: Option<u32>,
+    /// Sustained requests per second per IP on the embeddable availability widget (default: 10).
+    #[serde(default)]
+    pub widget_rate_per_second: Option<u64>,
+    /// Burst size for the widget rate limiter (default: 50).
+    #[serde(default)]
+    pub widget_rate_burst: Option<u32>,
+    /// Sustained requests per second per API key on the partner API (`GET /opac/availability`, default: 20).
+    #[serde(default)]
+    pub api_key_rate_per_second: Option<u64>,
+    /// Burst size for the API key rate limiter (default: 200).
+    #[serde(default)]
+    pub api_key_rate_burst: Option<u32>,
+    /// Seconds between replenished requests per IP on barcode + PIN login (default: 2).
+    #[serde(default)]
+    pub barcode_login_rate_per_second: Option<u64>,
+    /// Burst size for the barcode login rate limiter (default: 5).
+    #[serde(default)]
+    pub barcode_login_rate_burst: Option<u32>,
+    /// Public base URL of this server (e.g. `https://library.example.org`), used to build
+    /// absolute links in content embedded by third-party sites. Relative links when absent.
+    #[serde(default)]
+    pub public_base_url: Option<String>,
+    /// Reverse proxies (addresses or CIDR ranges) whose `X-Forwarded-For` / `X-Real-IP` headers
+    /// are believed when checking kiosk addresses. Empty: the TCP peer address is used.
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -41,6 +69,167 @@ pub struct UsersConfig {
     /// `POST /auth/request-password-reset`. Must contain the literal `<token>` placeholder.
     #[serde(default)]
     pub password_reset_url_template: Option<String>,
+    /// Days a deleted account stays restorable (`pendingDeletion`) before the nightly retention
+    /// job anonymizes it. 0 anonymizes immediately.
+    #[serde(default = "default_deletion_grace_days")]
+    pub deletion_grace_days: u32,
+    /// Lifetime of the codes sent for email 2FA, in seconds
+    #[serde(default = "default_email_2fa_code_ttl_seconds")]
+    pub email_2fa_code_ttl_seconds: u64,
+    /// Wrong codes allowed per email 2FA challenge before the user has to sign in again
+    #[serde(default = "default_email_2fa_max_attempts")]
+    pub email_2fa_max_attempts: u32,
+    /// Rules checked whenever a password is set
+    #[serde(default)]
+    pub password_policy: PasswordPolicyConfig,
+}
+
+fn default_deletion_grace_days() -> u32 {
+    30
+}
+
+fn default_email_2fa_code_ttl_seconds() -> u64 {
+    600
+}
+
+fn default_email_2fa_max_attempts() -> u32 {
+    5
+}
+
+fn default_password_account_types() -> HashMap<String, PasswordRules> {
+    let staff = PasswordRules {
+        min_length: 12,
+        min_character_classes: 3,
+        deny_common: true,
+        check_breaches: true,
+    };
+    HashMap::from([("librarian".to_string(), staff.clone()), ("admin".to_string(), staff)])
+}
+
+fn default_password_deny_list_path() -> Option<String> {
+    Some("data/common_passwords.txt".to_string())
+}
+
+fn default_password_breach_min_count() -> u64 {
+    1
+}
+
+/// Password policy: rules per account type, common password deny-list and offline breach check.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PasswordPolicyConfig {
+    /// Rules of account types without an entry in `account_types`
+    #[serde(default)]
+    pub default: PasswordRules,
+    /// Rules by account type slug (`reader`, `librarian`, `admin`...). Setting this table replaces
+    /// the built-in one (librarian and admin: 12 characters, 3 character classes, both checks).
+    #[serde(default = "default_password_account_types")]
+    pub account_types: HashMap<String, PasswordRules>,
+    /// Text file of common passwords, one per line (compared case-insensitively)
+    #[serde(default = "default_password_deny_list_path")]
+    pub deny_list_path: Option<String>,
+    /// Local HIBP-style range dataset: one `<first 5 hex digits of the SHA-1>.txt` file per prefix,
+    /// with `<remaining 35 hex digits>:<count>` lines. Unset disables the breach check.
+    #[serde(default)]
+    pub breach_dataset_dir: Option<String>,
+    /// Refuse passwords seen at least this many times in the breach dataset
+    #[serde(default = "default_password_breach_min_count")]
+    pub breach_min_count: u64,
+}
+
+impl Default for PasswordPolicyConfig {
+    fn default() -> Self {
+        Self {
+            default: PasswordRules::default(),
+            account_types: default_password_account_types(),
+            deny_list_path: default_password_deny_list_path(),
+            breach_dataset_dir: None,
+            breach_min_count: default_password_breach_min_count(),
+        }
+    }
+}
+
+fn default_password_min_length() -> usize {
+    4
+}
+
+fn default_password_min_character_classes() -> u8 {
+    1
+}
+
+/// Password rules of one account type
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PasswordRules {
+    /// Minimum number of characters
+    #[serde(default = "default_password_min_length")]
+    pub min_length: usize,
+    /// Minimum number of character classes used (lowercase, uppercase, digit, other)
+    #[serde(default = "default_password_min_character_classes")]
+    pub min_character_classes: u8,
+    /// Refuse passwords of the deny-list
+    #[serde(default)]
+    pub deny_common: bool,
+    /// Refuse passwords found in the breach dataset (when `breach_dataset_dir` is set)
+    #[serde(default)]
+    pub check_breaches: bool,
+}
+
+impl Default for PasswordRules {
+    fn default() -> Self {
+        Self {
+            min_length: default_password_min_length(),
+            min_character_classes: default_password_min_character_classes(),
+            deny_common: false,
+            check_breaches: false,
+        }
+    }
+}
+
+fn default_api_v1_enabled() -> bool {
+    true
+}
+
+/// API versions: retirement of `/api/v1` and deprecation notices (`Deprecation` / `Sunset` headers).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ApiConfig {
+    /// Serve `/api/v1`; once clients have migrated, `false` answers every v1 request with 410 Gone
+    #[serde(default = "default_api_v1_enabled")]
+    pub v1_enabled: bool,
+    /// Announce the whole of v1 as deprecated since this time
+    #[serde(default)]
+    pub v1_deprecated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Planned retirement of v1, sent as `Sunset` on every v1 response
+    #[serde(default)]
+    pub v1_sunset_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Single deprecated endpoints
+    #[serde(default)]
+    pub deprecated_endpoints: Vec<DeprecatedEndpoint>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            v1_enabled: default_api_v1_enabled(),
+            v1_deprecated_at: None,
+            v1_sunset_at: None,
+            deprecated_endpoints: Vec::new(),
+        }
+    }
+}
+
+/// A deprecated endpoint and when it goes away
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DeprecatedEndpoint {
+    /// HTTP method (any method when unset)
+    #[serde(default)]
+    pub method: Option<String>,
+    /// Route with its version prefix, parameters as `:name` (e.g. `/api/v1/items/:id`)
+    pub path: String,
+    pub deprecated_at: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    pub sunset_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Documentation of the replacement, sent as `Link: <url>; rel="deprecation"`
+    #[serde(default)]
+    pub link: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -63,6 +252,10 @@ fn default_email_templates_dir() -> String {
     "data/email_templates".to_string()
 }
 
+fn default_hard_bounce_threshold() -> u32 {
+    3
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct EmailConfig {
     pub smtp_host: String,
@@ -74,6 +267,13 @@ pub struct EmailConfig {
     pub smtp_use_tls: bool,
     #[serde(default = "default_email_templates_dir")]
     pub templates_dir: String,
+    /// Shared secret of the mail provider bounce webhook
+    /// (`X-Email-Signature: sha256=<hex HMAC of the body>`); the webhook is refused while unset
+    #[serde(default)]
+    pub bounce_webhook_secret: Option<String>,
+    /// Hard bounces after which an address is marked undeliverable (no email sent to it)
+    #[serde(default = "default_hard_bounce_threshold")]
+    pub hard_bounce_threshold: u32,
     /// Whether this section can be overridden via the DB settings table
     #[serde(default)]
     pub overridable: bool,
@@ -110,6 +310,14 @@ fn default_smtp_throttle_ms() -> u64 {
     100
 }
 
+fn default_due_soon_days() -> Vec<u32> {
+    vec![2]
+}
+
+fn default_escalation_after() -> u32 {
+    3
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct RemindersConfig {
     /// Whether the automatic reminder scheduler is enabled
@@ -124,6 +332,12 @@ pub struct RemindersConfig {
     /// Delay in milliseconds between each email send to avoid SMTP rate limits
     #[serde(default = "default_smtp_throttle_ms")]
     pub smtp_throttle_ms: u64,
+    /// Days before the due date on which patrons get the due-soon digest (empty disables it)
+    #[serde(default = "default_due_soon_days")]
+    pub due_soon_days: Vec<u32>,
+    /// Overdue reminders after which loans are escalated to staff (in-app notification; 0 disables it)
+    #[serde(default = "default_escalation_after")]
+    pub escalation_after: u32,
     /// Whether this section can be overridden via the DB settings table
     #[serde(default)]
     pub overridable: bool,
@@ -150,6 +364,8 @@ impl Default for RemindersConfig {
             frequency_days: 7,
             send_time: "09:00".to_string(),
             smtp_throttle_ms: 100,
+            due_soon_days: default_due_soon_days(),
+            escalation_after: default_escalation_after(),
             overridable: false,
         }
     }
@@ -174,15 +390,278 @@ pub struct HoldsConfig {
     /// Days a `ready` hold stays valid for pickup (`expires_at` after notification).
     #[serde(default = "default_hold_ready_expiry_days")]
     pub ready_expiry_days: u32,
+    /// Queue order of hold priorities, served first to last. A new hold goes behind every active hold
+    /// of the same or a higher priority; unlisted priorities rank with `normal` (empty: first come, first served).
+    #[serde(default = "default_hold_priority_order")]
+    pub priority_order: Vec<HoldPriority>,
     /// Whether this section can be overridden via the DB `settings` table and admin API
     #[serde(default)]
     pub overridable: bool,
 }
 
+fn default_hold_priority_order() -> Vec<HoldPriority> {
+    vec![HoldPriority::Teaching, HoldPriority::Staff, HoldPriority::Normal]
+}
+
 impl Default for HoldsConfig {
     fn default() -> Self {
         Self {
             ready_expiry_days: 7,
+            priority_order: default_hold_priority_order(),
+            overridable: false,
+        }
+    }
+}
+
+/// Circulation rules: checkout blocks thresholds (`CheckoutBlockCode`, leave a threshold unset to
+/// disable its block) and the floating rules of a library with several places (branches): a copy
+/// checked in at another place than its own stays there or travels back, first match wins (back
+/// home when no rule matches).
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct CirculationConfig {
+    /// Refuse checkouts to patrons with at least this many overdue loans
+    #[serde(default)]
+    pub max_overdue_loans: Option<u32>,
+    /// Refuse checkouts to patrons whose unpaid fines are above this amount
+    #[serde(default)]
+    pub max_unpaid_fines: Option<rust_decimal::Decimal>,
+    #[serde(default)]
+    pub floating_rules: Vec<FloatingRule>,
+    /// Days late without fine, whatever the fine rule (a rule's own `grace_days` wins when longer)
+    #[serde(default)]
+    pub fine_grace_days: u32,
+    /// Amnesty weeks: overdue returns made on these days accrue no fine (recorded as waived)
+    #[serde(default)]
+    pub fine_amnesties: Vec<FineAmnestyPeriod>,
+    /// Days a returned loan stays in `loans` before the nightly archival job moves it to
+    /// `loans_archives`; 0 archives it on return
+    #[serde(default)]
+    pub archive_returned_after_days: u32,
+    /// Whether this section can be overridden via the DB `settings` table and admin API
+    #[serde(default)]
+    pub overridable: bool,
+}
+
+/// Floating rule of the check-in routing; a rule without criteria matches every copy
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct FloatingRule {
+    pub name: String,
+    /// Media type code of the record
+    #[serde(default)]
+    pub media_type: Option<String>,
+    /// Key of a collection of the record (`GET /collections`)
+    #[serde(default)]
+    pub collection: Option<String>,
+    /// Place (`items.place`) the copy belongs to
+    #[serde(default)]
+    pub home_place: Option<i16>,
+    /// Stay at the return place (`true`) or go back to the home place (`false`)
+    pub floating: bool,
+    /// Ask the staff to confirm at check-in (`routing.promptOverride` of the return response)
+    #[serde(default)]
+    pub prompt: bool,
+}
+
+/// Amnesty week of the fines engine (days in library local time, both included)
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct FineAmnestyPeriod {
+    pub name: String,
+    pub start_date: chrono::NaiveDate,
+    pub end_date: chrono::NaiveDate,
+}
+
+fn default_locker_pickup_days() -> u32 {
+    3
+}
+
+/// Hold pickup lockers run by an external vendor (compartments the patron opens with a code).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LockersConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Vendor API base URL (compartments are reserved under `{api_url}/reservations`)
+    #[serde(default)]
+    pub api_url: Option<String>,
+    /// Sent as `Authorization: Bearer` to the vendor API
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Shared secret of the vendor webhook (`X-Locker-Signature: sha256=<hex HMAC of the body>`)
+    #[serde(default)]
+    pub webhook_secret: Option<String>,
+    /// Days a copy stays in its compartment before the hold expires
+    #[serde(default = "default_locker_pickup_days")]
+    pub pickup_days: u32,
+    /// Whether this section can be overridden via the DB `settings` table and admin API
+    #[serde(default)]
+    pub overridable: bool,
+}
+
+impl Default for LockersConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            api_url: None,
+            api_key: None,
+            webhook_secret: None,
+            pickup_days: default_locker_pickup_days(),
+            overridable: false,
+        }
+    }
+}
+
+/// End-of-day job closing the circulation day (`/admin/eod-runs`).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct EodConfig {
+    /// Whether the scheduler runs the job every day
+    #[serde(default)]
+    pub enabled: bool,
+    /// Time of day the job runs (HH:MM, 24h, local time), after closing
+    #[serde(default = "default_eod_run_time")]
+    pub run_time: String,
+    /// Generate the hold pull list PDF for the next morning (kept in the artifacts store)
+    #[serde(default = "default_eod_pull_list")]
+    pub pull_list: bool,
+    /// Addresses receiving the daily digest (empty: no digest)
+    #[serde(default)]
+    pub digest_recipients: Vec<String>,
+    /// Whether this section can be overridden via the DB `settings` table and admin API
+    #[serde(default)]
+    pub overridable: bool,
+}
+
+fn default_eod_run_time() -> String {
+    "20:00".to_string()
+}
+
+fn default_eod_pull_list() -> bool {
+    true
+}
+
+impl Default for EodConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            run_time: default_eod_run_time(),
+            pull_list: default_eod_pull_list(),
+            digest_recipients: Vec::new(),
+            overridable: false,
+        }
+    }
+}
+
+/// Re-engagement emails suggesting new acquisitions to members who stopped borrowing
+/// (`/reengagement`, `/stats/reengagement`). Only members opted in to campaigns are emailed.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ReengagementConfig {
+    /// Whether the scheduler runs the job every day
+    #[serde(default)]
+    pub enabled: bool,
+    /// Time of day the job runs (HH:MM, 24h, local time)
+    #[serde(default = "default_reengagement_run_time")]
+    pub run_time: String,
+    /// Days without a loan after which a member is considered inactive
+    #[serde(default = "default_reengagement_inactive_days")]
+    pub inactive_days: u32,
+    /// Days before the same member can be emailed again
+    #[serde(default = "default_reengagement_resend_after_days")]
+    pub resend_after_days: u32,
+    /// Titles acquired within this many days are suggested
+    #[serde(default = "default_reengagement_new_since_days")]
+    pub new_since_days: u32,
+    /// Titles listed per email
+    #[serde(default = "default_reengagement_max_titles")]
+    pub max_titles: u32,
+    /// Members emailed per run at most (the others wait for the next run)
+    #[serde(default = "default_reengagement_max_per_run")]
+    pub max_per_run: u32,
+    /// Days after an email during which a loan counts as a reactivation in the stats
+    #[serde(default = "default_reengagement_conversion_days")]
+    pub conversion_days: u32,
+    /// Whether this section can be overridden via the DB `settings` table and admin API
+    #[serde(default)]
+    pub overridable: bool,
+}
+
+fn default_reengagement_run_time() -> String {
+    "10:00".to_string()
+}
+
+fn default_reengagement_inactive_days() -> u32 {
+    180
+}
+
+fn default_reengagement_resend_after_days() -> u32 {
+    180
+}
+
+fn default_reengagement_new_since_days() -> u32 {
+    90
+}
+
+fn default_reengagement_max_titles() -> u32 {
+    5
+}
+
+fn default_reengagement_max_per_run() -> u32 {
+    200
+}
+
+fn default_reengagement_conversion_days() -> u32 {
+    60
+}
+
+impl Default for ReengagementConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            run_time: default_reengagement_run_time(),
+            inactive_days: default_reengagement_inactive_days(),
+            resend_after_days: default_reengagement_resend_after_days(),
+            new_since_days: default_reengagement_new_since_days(),
+            max_titles: default_reengagement_max_titles(),
+            max_per_run: default_reengagement_max_per_run(),
+            conversion_days: default_reengagement_conversion_days(),
+            overridable: false,
+        }
+    }
+}
+
+/// OPAC sitemap for search engines (`GET /sitemap.xml`), regenerated every day.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SitemapConfig {
+    /// Whether the scheduler regenerates the sitemap every day
+    #[serde(default)]
+    pub enabled: bool,
+    /// Time of day the job runs (HH:MM, 24h, local time)
+    #[serde(default = "default_sitemap_run_time")]
+    pub run_time: String,
+    /// Absolute URL of a record's OPAC page, `{id}` replaced by the biblio ID
+    /// (e.g. `https://catalog.library.example/records/{id}`)
+    #[serde(default)]
+    pub record_url: Option<String>,
+    /// Record URLs per file; larger catalogs get a sitemap index of shards (protocol limit: 50000)
+    #[serde(default = "default_sitemap_urls_per_file")]
+    pub urls_per_file: u32,
+    /// Whether this section can be overridden via the DB `settings` table and admin API
+    #[serde(default)]
+    pub overridable: bool,
+}
+
+fn default_sitemap_run_time() -> String {
+    "04:30".to_string()
+}
+
+fn default_sitemap_urls_per_file() -> u32 {
+    50_000
+}
+
+impl Default for SitemapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            run_time: default_sitemap_run_time(),
+            record_url: None,
+            urls_per_file: default_sitemap_urls_per_file(),
             overridable: false,
         }
     }
@@ -199,6 +678,293 @@ pub struct MeilisearchConfig {
     pub index_name: String,
 }
 
+fn default_z3950_server_host() -> String {
+    "0.0.0.0".to_string()
+}
+
+fn default_z3950_server_port() -> u16 {
+    2100
+}
+
+fn default_z3950_server_max_connections() -> usize {
+    20
+}
+
+fn default_z3950_server_database() -> String {
+    "elidune".to_string()
+}
+
+fn default_z3950_server_idle_timeout() -> u64 {
+    300
+}
+
+fn default_z3950_server_max_result_set() -> i64 {
+    1000
+}
+
+fn default_z3950_server_max_present() -> i64 {
+    50
+}
+
+fn default_z3950_server_record_syntax() -> String {
+    "unimarc".to_string()
+}
+
+/// Z39.50 target exposing the catalog to partner libraries (disabled by default).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Z3950ServerConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_z3950_server_host")]
+    pub host: String,
+    /// TCP port of the Z39.50 listener (default: 2100, the registered Z39.50 port is 210)
+    #[serde(default = "default_z3950_server_port")]
+    pub port: u16,
+    /// Maximum simultaneous client connections; extra connections are closed right away
+    #[serde(default = "default_z3950_server_max_connections")]
+    pub max_connections: usize,
+    /// Database name clients must search (case-insensitive)
+    #[serde(default = "default_z3950_server_database")]
+    pub database_name: String,
+    /// Connections idle for longer than this are closed (seconds)
+    #[serde(default = "default_z3950_server_idle_timeout")]
+    pub idle_timeout_seconds: u64,
+    /// Maximum number of hits kept in a result set
+    #[serde(default = "default_z3950_server_max_result_set")]
+    pub max_result_set: i64,
+    /// Maximum number of records returned by one Present request
+    #[serde(default = "default_z3950_server_max_present")]
+    pub max_records_per_present: i64,
+    /// Record syntax used when the client does not ask for one: "unimarc" | "marc21"
+    #[serde(default = "default_z3950_server_record_syntax")]
+    pub default_record_syntax: String,
+}
+
+impl Default for Z3950ServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: default_z3950_server_host(),
+            port: default_z3950_server_port(),
+            max_connections: default_z3950_server_max_connections(),
+            database_name: default_z3950_server_database(),
+            idle_timeout_seconds: default_z3950_server_idle_timeout(),
+            max_result_set: default_z3950_server_max_result_set(),
+            max_records_per_present: default_z3950_server_max_present(),
+            default_record_syntax: default_z3950_server_record_syntax(),
+        }
+    }
+}
+
+fn default_artifacts_backend() -> String {
+    "filesystem".to_string()
+}
+
+fn default_artifacts_directory() -> String {
+    "data/artifacts".to_string()
+}
+
+fn default_artifacts_ttl_hours() -> u32 {
+    24
+}
+
+fn default_artifacts_url_ttl_minutes() -> u32 {
+    60
+}
+
+/// Catalog snapshots taken before bulk edits of copies (call numbers, source merges).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SnapshotsConfig {
+    /// Snapshots are deleted this many days after creation
+    #[serde(default = "default_snapshots_retention_days")]
+    pub retention_days: u32,
+    /// Most recent snapshots kept; older ones are deleted when a new one is taken
+    #[serde(default = "default_snapshots_max_count")]
+    pub max_count: u32,
+}
+
+impl Default for SnapshotsConfig {
+    fn default() -> Self {
+        Self {
+            retention_days: default_snapshots_retention_days(),
+            max_count: default_snapshots_max_count(),
+        }
+    }
+}
+
+/// Training copy of production: patron PII is masked in every API response (stored data unchanged).
+///
+/// File-only on purpose: the admin configuration API cannot turn it off.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct DemoConfig {
+    /// Replace patron names, emails, phones and addresses with deterministic fakes in JSON
+    /// responses, and refuse other data bodies, backups and warehouse exports that would bypass
+    /// the masking
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Currency of prices (copies, fines, payments): display and validation.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CurrencyConfig {
+    /// ISO 4217 code, also accepted after an amount in input (`12.50 EUR`)
+    #[serde(default = "default_currency_code")]
+    pub code: String,
+    #[serde(default = "default_currency_symbol")]
+    pub symbol: String,
+    /// Digits after the decimal separator (0 for currencies without minor unit)
+    #[serde(default = "default_currency_decimals")]
+    pub decimals: u32,
+    /// Decimal separator of formatted amounts (exports, receipts); input accepts `.` and `,`
+    #[serde(default = "default_currency_decimal_separator")]
+    pub decimal_separator: String,
+    /// Symbol written before the amount (`$12.50`) rather than after it (`12,50 €`)
+    #[serde(default)]
+    pub symbol_before: bool,
+    /// Copy prices above this amount are rejected as typos (e.g. a barcode typed in the price)
+    #[serde(default = "default_currency_max_price")]
+    pub max_price: u32,
+}
+
+impl Default for CurrencyConfig {
+    fn default() -> Self {
+        Self {
+            code: default_currency_code(),
+            symbol: default_currency_symbol(),
+            decimals: default_currency_decimals(),
+            decimal_separator: default_currency_decimal_separator(),
+            symbol_before: false,
+            max_price: default_currency_max_price(),
+        }
+    }
+}
+
+fn default_currency_code() -> String {
+    "EUR".to_string()
+}
+
+fn default_currency_symbol() -> String {
+    "€".to_string()
+}
+
+fn default_currency_decimals() -> u32 {
+    2
+}
+
+fn default_currency_decimal_separator() -> String {
+    ",".to_string()
+}
+
+fn default_currency_max_price() -> u32 {
+    10_000
+}
+
+/// Logical database backups (`POST /admin/backup`) restored with `elidune-server restore`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BackupConfig {
+    /// "artifacts" (downloaded through `GET /artifacts/:id/link`) or "s3"
+    #[serde(default = "default_backup_destination")]
+    pub destination: String,
+    /// Days a backup is kept in the artifacts store (bucket lifecycle rules apply to S3)
+    #[serde(default = "default_backup_retention_days")]
+    pub retention_days: u32,
+    /// `s3://bucket/prefix/` the files are uploaded under (destination "s3")
+    #[serde(default)]
+    pub s3_url: Option<String>,
+    #[serde(default = "default_backup_s3_region")]
+    pub s3_region: String,
+    /// Endpoint of an S3-compatible store (e.g. `https://minio.city.local:9000`), path-style
+    #[serde(default)]
+    pub s3_endpoint: Option<String>,
+    #[serde(default)]
+    pub s3_access_key: Option<String>,
+    #[serde(default)]
+    pub s3_secret_key: Option<String>,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            destination: default_backup_destination(),
+            retention_days: default_backup_retention_days(),
+            s3_url: None,
+            s3_region: default_backup_s3_region(),
+            s3_endpoint: None,
+            s3_access_key: None,
+            s3_secret_key: None,
+        }
+    }
+}
+
+fn default_backup_destination() -> String {
+    "artifacts".to_string()
+}
+
+fn default_backup_retention_days() -> u32 {
+    14
+}
+
+fn default_backup_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_snapshots_retention_days() -> u32 {
+    30
+}
+
+fn default_snapshots_max_count() -> u32 {
+    50
+}
+
+/// MARC records stored with biblios.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MarcConfig {
+    /// Tags of the stored record never overwritten when it is regenerated after a biblio edit
+    /// (whole MARC blocks, e.g. "9XX", "3XX")
+    #[serde(default = "default_marc_preserved_tags")]
+    pub preserved_tags: Vec<String>,
+}
+
+impl Default for MarcConfig {
+    fn default() -> Self {
+        Self {
+            preserved_tags: default_marc_preserved_tags(),
+        }
+    }
+}
+
+fn default_marc_preserved_tags() -> Vec<String> {
+    vec!["9XX".to_string()]
+}
+
+/// Generated files (exports, import reports) kept for download through signed URLs.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ArtifactsConfig {
+    /// Blob store backend; only "filesystem" for now
+    #[serde(default = "default_artifacts_backend")]
+    pub backend: String,
+    /// Directory of the filesystem backend (created at startup)
+    #[serde(default = "default_artifacts_directory")]
+    pub directory: String,
+    /// Files are deleted this many hours after creation
+    #[serde(default = "default_artifacts_ttl_hours")]
+    pub ttl_hours: u32,
+    /// Lifetime of a signed download URL (minutes)
+    #[serde(default = "default_artifacts_url_ttl_minutes")]
+    pub url_ttl_minutes: u32,
+}
+
+impl Default for ArtifactsConfig {
+    fn default() -> Self {
+        Self {
+            backend: default_artifacts_backend(),
+            directory: default_artifacts_directory(),
+            ttl_hours: default_artifacts_ttl_hours(),
+            url_ttl_minutes: default_artifacts_url_ttl_minutes(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct AppConfig {
     pub server: ServerConfig,
@@ -215,7 +981,33 @@ pub struct AppConfig {
     #[serde(default, alias = "reservations")]
     pub holds: HoldsConfig,
     #[serde(default)]
+    pub lockers: LockersConfig,
+    #[serde(default)]
+    pub circulation: CirculationConfig,
+    #[serde(default)]
+    pub eod: EodConfig,
+    #[serde(default)]
+    pub reengagement: ReengagementConfig,
+    #[serde(default)]
+    pub sitemap: SitemapConfig,
+    #[serde(default)]
     pub meilisearch: Option<MeilisearchConfig>,
+    #[serde(default)]
+    pub z3950_server: Z3950ServerConfig,
+    #[serde(default)]
+    pub artifacts: ArtifactsConfig,
+    #[serde(default)]
+    pub api: ApiConfig,
+    #[serde(default)]
+    pub snapshots: SnapshotsConfig,
+    #[serde(default)]
+    pub marc: MarcConfig,
+    #[serde(default)]
+    pub demo: DemoConfig,
+    #[serde(default)]
+    pub backup: BackupConfig,
+    #[serde(default)]
+    pub currency: CurrencyConfig,
 }
 
 impl AppConfig {
@@ -248,6 +1040,14 @@ impl Default for ServerConfig {
             auth_rate_burst: None,
             public_rate_per_second: None,
             public_rate_burst: None,
+            widget_rate_per_second: None,
+            widget_rate_burst: None,
+            api_key_rate_per_second: None,
+            api_key_rate_burst: None,
+            barcode_login_rate_per_second: None,
+            barcode_login_rate_burst: None,
+            public_base_url: None,
+            trusted_proxies: Vec::new(),
         }
     }
 }
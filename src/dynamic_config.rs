@@ -9,7 +9,7 @@ use regex::Regex;
 use serde_json::Value;
 
 use crate::{
-    config::{AppConfig, AuditConfig, EmailConfig, HoldsConfig, LoggingConfig, RemindersConfig},
+    config::{AppConfig, AuditConfig, CirculationConfig, EmailConfig, EodConfig, HoldsConfig, LockersConfig, LoggingConfig, ReengagementConfig, RemindersConfig, SitemapConfig},
     error::{AppError, AppResult},
 };
 
@@ -25,6 +25,11 @@ struct DynamicConfigInner {
     pub reminders: RemindersConfig,
     pub audit: AuditConfig,
     pub holds: HoldsConfig,
+    pub lockers: LockersConfig,
+    pub circulation: CirculationConfig,
+    pub eod: EodConfig,
+    pub reengagement: ReengagementConfig,
+    pub sitemap: SitemapConfig,
 }
 
 /// Thread-safe, runtime-mutable configuration.
@@ -46,6 +51,11 @@ impl DynamicConfig {
                 reminders: config.reminders.clone(),
                 audit: config.audit.clone(),
                 holds: config.holds.clone(),
+                lockers: config.lockers.clone(),
+                circulation: config.circulation.clone(),
+                eod: config.eod.clone(),
+                reengagement: config.reengagement.clone(),
+                sitemap: config.sitemap.clone(),
             }),
             file_config: config,
             log_level_reload: RwLock::new(None),
@@ -89,6 +99,26 @@ impl DynamicConfig {
         self.inner.read().unwrap().holds.clone()
     }
 
+    pub fn read_lockers(&self) -> LockersConfig {
+        self.inner.read().unwrap().lockers.clone()
+    }
+
+    pub fn read_circulation(&self) -> CirculationConfig {
+        self.inner.read().unwrap().circulation.clone()
+    }
+
+    pub fn read_eod(&self) -> EodConfig {
+        self.inner.read().unwrap().eod.clone()
+    }
+
+    pub fn read_reengagement(&self) -> ReengagementConfig {
+        self.inner.read().unwrap().reengagement.clone()
+    }
+
+    pub fn read_sitemap(&self) -> SitemapConfig {
+        self.inner.read().unwrap().sitemap.clone()
+    }
+
     /// Returns true if the given section is marked overridable in the file config.
     pub fn is_overridable(&self, section: &str) -> bool {
         match section {
@@ -97,6 +127,11 @@ impl DynamicConfig {
             "reminders" => self.file_config.reminders.overridable,
             "audit" => self.file_config.audit.overridable,
             "holds" => self.file_config.holds.overridable,
+            "lockers" => self.file_config.lockers.overridable,
+            "circulation" => self.file_config.circulation.overridable,
+            "eod" => self.file_config.eod.overridable,
+            "reengagement" => self.file_config.reengagement.overridable,
+            "sitemap" => self.file_config.sitemap.overridable,
             _ => false,
         }
     }
@@ -144,6 +179,36 @@ impl DynamicConfig {
                 validate_holds_config(&cfg)?;
                 self.inner.write().unwrap().holds = cfg;
             }
+            "lockers" => {
+                let cfg: LockersConfig = serde_json::from_value(value)
+                    .map_err(|e| AppError::BadRequest(format!("Invalid lockers config: {}", e)))?;
+                validate_lockers_config(&cfg)?;
+                self.inner.write().unwrap().lockers = cfg;
+            }
+            "circulation" => {
+                let cfg: CirculationConfig = serde_json::from_value(value)
+                    .map_err(|e| AppError::BadRequest(format!("Invalid circulation config: {}", e)))?;
+                validate_circulation_config(&cfg)?;
+                self.inner.write().unwrap().circulation = cfg;
+            }
+            "eod" => {
+                let cfg: EodConfig = serde_json::from_value(value)
+                    .map_err(|e| AppError::BadRequest(format!("Invalid eod config: {}", e)))?;
+                validate_eod_config(&cfg)?;
+                self.inner.write().unwrap().eod = cfg;
+            }
+            "reengagement" => {
+                let cfg: ReengagementConfig = serde_json::from_value(value)
+                    .map_err(|e| AppError::BadRequest(format!("Invalid reengagement config: {}", e)))?;
+                validate_reengagement_config(&cfg)?;
+                self.inner.write().unwrap().reengagement = cfg;
+            }
+            "sitemap" => {
+                let cfg: SitemapConfig = serde_json::from_value(value)
+                    .map_err(|e| AppError::BadRequest(format!("Invalid sitemap config: {}", e)))?;
+                validate_sitemap_config(&cfg)?;
+                self.inner.write().unwrap().sitemap = cfg;
+            }
             _ => {
                 return Err(AppError::NotFound(format!(
                     "Unknown config section '{}'",
@@ -174,6 +239,15 @@ impl DynamicConfig {
             "holds" => {
                 self.inner.write().unwrap().holds = self.file_config.holds.clone()
             }
+            "lockers" => self.inner.write().unwrap().lockers = self.file_config.lockers.clone(),
+            "circulation" => {
+                self.inner.write().unwrap().circulation = self.file_config.circulation.clone()
+            }
+            "eod" => self.inner.write().unwrap().eod = self.file_config.eod.clone(),
+            "reengagement" => {
+                self.inner.write().unwrap().reengagement = self.file_config.reengagement.clone()
+            }
+            "sitemap" => self.inner.write().unwrap().sitemap = self.file_config.sitemap.clone(),
             _ => {
                 return Err(AppError::NotFound(format!(
                     "Unknown config section '{}'",
@@ -192,6 +266,11 @@ impl DynamicConfig {
             "reminders" => serde_json::to_value(self.read_reminders()),
             "audit" => serde_json::to_value(self.read_audit()),
             "holds" => serde_json::to_value(self.read_holds()),
+            "lockers" => serde_json::to_value(self.read_lockers()),
+            "circulation" => serde_json::to_value(self.read_circulation()),
+            "eod" => serde_json::to_value(self.read_eod()),
+            "reengagement" => serde_json::to_value(self.read_reengagement()),
+            "sitemap" => serde_json::to_value(self.read_sitemap()),
             _ => return Err(AppError::NotFound(format!("Unknown config section '{}'", section))),
         };
         val.map_err(|e| AppError::Internal(format!("Failed to serialize config: {}", e)))
@@ -205,6 +284,11 @@ impl DynamicConfig {
         if self.file_config.reminders.overridable { sections.push("reminders"); }
         if self.file_config.audit.overridable { sections.push("audit"); }
         if self.file_config.holds.overridable { sections.push("holds"); }
+        if self.file_config.lockers.overridable { sections.push("lockers"); }
+        if self.file_config.circulation.overridable { sections.push("circulation"); }
+        if self.file_config.eod.overridable { sections.push("eod"); }
+        if self.file_config.reengagement.overridable { sections.push("reengagement"); }
+        if self.file_config.sitemap.overridable { sections.push("sitemap"); }
         sections
     }
 }
@@ -226,6 +310,11 @@ fn validate_email_config(cfg: &EmailConfig) -> AppResult<()> {
             "email.smtp_from must be a valid email address".to_string(),
         ));
     }
+    if cfg.hard_bounce_threshold < 1 {
+        return Err(AppError::BadRequest(
+            "email.hard_bounce_threshold must be at least 1".to_string(),
+        ));
+    }
     Ok(())
 }
 
@@ -280,6 +369,11 @@ fn validate_reminders_config(cfg: &RemindersConfig) -> AppResult<()> {
             "reminders.send_time has invalid hour or minute value".to_string(),
         ));
     }
+    if cfg.due_soon_days.iter().any(|d| !(1..=30).contains(d)) {
+        return Err(AppError::BadRequest(
+            "reminders.due_soon_days values must be between 1 and 30".to_string(),
+        ));
+    }
     Ok(())
 }
 
@@ -298,5 +392,142 @@ fn validate_holds_config(cfg: &HoldsConfig) -> AppResult<()> {
             "holds.ready_expiry_days must be between 1 and 365".to_string(),
         ));
     }
+    let mut seen = std::collections::HashSet::new();
+    if !cfg.priority_order.iter().all(|p| seen.insert(*p)) {
+        return Err(AppError::BadRequest(
+            "holds.priority_order must not list a priority twice".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_lockers_config(cfg: &LockersConfig) -> AppResult<()> {
+    if cfg.pickup_days < 1 || cfg.pickup_days > 30 {
+        return Err(AppError::BadRequest(
+            "lockers.pickup_days must be between 1 and 30".to_string(),
+        ));
+    }
+    if !cfg.enabled {
+        return Ok(());
+    }
+    let api_url = cfg.api_url.as_deref().map(str::trim).unwrap_or("");
+    if !(api_url.starts_with("http://") || api_url.starts_with("https://")) {
+        return Err(AppError::BadRequest(
+            "lockers.api_url must be an http(s) URL when lockers are enabled".to_string(),
+        ));
+    }
+    if cfg.webhook_secret.as_deref().map(str::trim).unwrap_or("").is_empty() {
+        return Err(AppError::BadRequest(
+            "lockers.webhook_secret is required when lockers are enabled".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// `HH:MM` (24h) time of day
+fn is_valid_run_time(value: &str) -> bool {
+    value
+        .split_once(':')
+        .filter(|(h, m)| h.len() == 2 && m.len() == 2)
+        .and_then(|(h, m)| Some((h.parse::<u32>().ok()?, m.parse::<u32>().ok()?)))
+        .is_some_and(|(h, m)| h <= 23 && m <= 59)
+}
+
+fn validate_eod_config(cfg: &EodConfig) -> AppResult<()> {
+    if !is_valid_run_time(&cfg.run_time) {
+        return Err(AppError::BadRequest(
+            "eod.run_time must be in HH:MM format (24h)".to_string(),
+        ));
+    }
+    if let Some(address) = cfg.digest_recipients.iter().find(|a| !a.contains('@')) {
+        return Err(AppError::BadRequest(format!(
+            "eod.digest_recipients: '{}' is not an email address",
+            address
+        )));
+    }
+    Ok(())
+}
+
+fn validate_reengagement_config(cfg: &ReengagementConfig) -> AppResult<()> {
+    if !is_valid_run_time(&cfg.run_time) {
+        return Err(AppError::BadRequest(
+            "reengagement.run_time must be in HH:MM format (24h)".to_string(),
+        ));
+    }
+    let ranges = [
+        ("inactive_days", cfg.inactive_days, 30, 3650),
+        ("resend_after_days", cfg.resend_after_days, 1, 3650),
+        ("new_since_days", cfg.new_since_days, 1, 365),
+        ("max_titles", cfg.max_titles, 1, 20),
+        ("max_per_run", cfg.max_per_run, 1, 10_000),
+        ("conversion_days", cfg.conversion_days, 1, 365),
+    ];
+    for (name, value, min, max) in ranges {
+        if !(min..=max).contains(&value) {
+            return Err(AppError::BadRequest(format!(
+                "reengagement.{} must be between {} and {}",
+                name, min, max
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn validate_sitemap_config(cfg: &SitemapConfig) -> AppResult<()> {
+    if !is_valid_run_time(&cfg.run_time) {
+        return Err(AppError::BadRequest(
+            "sitemap.run_time must be in HH:MM format (24h)".to_string(),
+        ));
+    }
+    if let Some(ref url) = cfg.record_url {
+        if !(url.starts_with("https://") || url.starts_with("http://")) || !url.contains("{id}") {
+            return Err(AppError::BadRequest(
+                "sitemap.record_url must be an absolute http(s) URL containing {id}".to_string(),
+            ));
+        }
+    } else if cfg.enabled {
+        return Err(AppError::BadRequest(
+            "sitemap.record_url is required when the sitemap is enabled".to_string(),
+        ));
+    }
+    if !(100..=50_000).contains(&cfg.urls_per_file) {
+        return Err(AppError::BadRequest(
+            "sitemap.urls_per_file must be between 100 and 50000".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_circulation_config(cfg: &CirculationConfig) -> AppResult<()> {
+    if cfg.max_overdue_loans == Some(0) {
+        return Err(AppError::BadRequest(
+            "circulation.max_overdue_loans must be at least 1 (omit it to disable the block)".to_string(),
+        ));
+    }
+    if cfg.max_unpaid_fines.is_some_and(|amount| amount.is_sign_negative()) {
+        return Err(AppError::BadRequest(
+            "circulation.max_unpaid_fines must not be negative".to_string(),
+        ));
+    }
+    for rule in &cfg.floating_rules {
+        if rule.name.trim().is_empty() {
+            return Err(AppError::BadRequest(
+                "circulation.floating_rules entries need a name".to_string(),
+            ));
+        }
+    }
+    for amnesty in &cfg.fine_amnesties {
+        if amnesty.name.trim().is_empty() {
+            return Err(AppError::BadRequest(
+                "circulation.fine_amnesties entries need a name".to_string(),
+            ));
+        }
+        if amnesty.end_date < amnesty.start_date {
+            return Err(AppError::BadRequest(format!(
+                "circulation.fine_amnesties '{}' ends before it starts",
+                amnesty.name
+            )));
+        }
+    }
     Ok(())
 }
@@ -16,6 +16,7 @@ use crate::{
     error::{AppError, AppResult},
     email_templates::{self, EmailTemplate},
     models::Language,
+    repository::Repository,
 };
 
 #[derive(Clone)]
@@ -62,6 +63,86 @@ impl EmailService {
         self.send_email_with_html(to, &subject, &body_plain, &body_html).await
     }
 
+    /// Warn a user that 2FA verification was locked after repeated wrong codes
+    pub async fn send_2fa_failures_alert(
+        &self,
+        to: &str,
+        attempts: i16,
+        locked_until: &str,
+        lang: Option<Language>,
+    ) -> AppResult<()> {
+        let template = self.load_template("2fa_failures", lang).await?;
+        let attempts = attempts.to_string();
+        let (subject, body_plain, body_html) = email_templates::substitute(
+            &template,
+            &[("attempts", attempts.as_str()), ("locked_until", locked_until)],
+        );
+        self.send_email_with_html(to, &subject, &body_plain, &body_html).await
+    }
+
+    /// Invite a patron to renew a membership ending on `expiry_date`
+    pub async fn send_membership_renewal(
+        &self,
+        to: &str,
+        firstname: &str,
+        lastname: &str,
+        expiry_date: &str,
+        renewal_url: &str,
+        lang: Option<Language>,
+    ) -> AppResult<()> {
+        let template = self.load_template("membership_renewal", lang).await?;
+        let (subject, body_plain, body_html) = email_templates::substitute(
+            &template,
+            &[
+                ("firstname", firstname),
+                ("lastname", lastname),
+                ("expiry_date", expiry_date),
+                ("renewal_url", renewal_url),
+            ],
+        );
+        self.send_email_with_html(to, &subject, &body_plain, &body_html).await
+    }
+
+    /// Tell a patron that the title they suggested for purchase can now be borrowed
+    pub async fn send_suggestion_available(
+        &self,
+        to: &str,
+        firstname: &str,
+        lastname: &str,
+        title: &str,
+        lang: Option<Language>,
+    ) -> AppResult<()> {
+        let template = self.load_template("suggestion_available", lang).await?;
+        let (subject, body_plain, body_html) = email_templates::substitute(
+            &template,
+            &[("firstname", firstname), ("lastname", lastname), ("title", title)],
+        );
+        self.send_email_with_html(to, &subject, &body_plain, &body_html).await
+    }
+
+    /// Suggest new acquisitions to a member who stopped borrowing (`titles_*`: pre-formatted list)
+    pub async fn send_reengagement(
+        &self,
+        to: &str,
+        firstname: &str,
+        lastname: &str,
+        titles_list: &str,
+        titles_html: &str,
+        lang: Option<Language>,
+    ) -> AppResult<()> {
+        let template = self.load_template("reengagement", lang).await?;
+        let (subject, body_plain, body_html) = email_templates::substitute(
+            &template,
+            &[
+                ("firstname", firstname),
+                ("lastname", lastname),
+                ("titles_list", titles_list),
+                ("titles_html", titles_html),
+            ],
+        );
+        self.send_email_with_html(to, &subject, &body_plain, &body_html).await
+    }
+
     /// Send a recovery code via email
     pub async fn send_recovery_code(
         &self,
@@ -106,6 +187,7 @@ impl EmailService {
     }
 
     /// Low-level send: builds the SMTP transport from the current live config on each call.
+    /// Addresses marked undeliverable after repeated hard bounces are refused.
     pub async fn send_email_with_html(
         &self,
         to: &str,
@@ -113,6 +195,14 @@ impl EmailService {
         body_plain: &str,
         body_html: &str,
     ) -> AppResult<()> {
+        let repo = Repository::new(self.pool.clone(), None, None);
+        if repo.email_health_is_undeliverable(to).await? {
+            return Err(AppError::BusinessRule(format!(
+                "Email address {} is undeliverable (repeated hard bounces)",
+                to.trim()
+            )));
+        }
+
         let config = self.dynamic_config.read_email();
 
         let from_name = config.smtp_from_name.as_deref().unwrap_or("Elidune");
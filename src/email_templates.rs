@@ -25,11 +25,20 @@ pub struct EmailTemplate {
 /// Canonical list of template ids the server expects on disk / in DB.
 pub const KNOWN_TEMPLATE_IDS: &[&str] = &[
     "2fa_code",
+    "2fa_failures",
     "recovery_code",
     "password_reset",
     "hold_ready",
+    "hold_in_locker",
     "overdue_reminder",
+    "due_soon_reminder",
     "event_announcement",
+    "due_date_extended",
+    "event_cancelled",
+    "campaign",
+    "membership_renewal",
+    "suggestion_available",
+    "reengagement",
 ];
 
 /// Languages bootstrapped / accepted by the API.
@@ -1,15 +1,21 @@
 //! Error types for Elidune server
 
+use std::collections::BTreeMap;
+
 use axum::{
-    http::StatusCode,
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::models::biblio::BiblioShort;
 use crate::models::item::ItemShort;
+use crate::models::loan::CheckoutBlock;
 
 /// Machine-readable string error codes used in API responses.
 ///
@@ -21,6 +27,7 @@ pub mod error_code {
     pub const NOT_FOUND: &str = "not_found";
     pub const GONE: &str = "gone";
     pub const VALIDATION: &str = "validation_error";
+    pub const INVALID_FIELDS: &str = "invalid_fields";
     pub const DATABASE: &str = "database_error";
     pub const CONFLICT: &str = "conflict";
     pub const BAD_REQUEST: &str = "bad_request";
@@ -29,6 +36,7 @@ pub mod error_code {
     pub const BUSINESS_RULE: &str = "business_rule_violation";
     pub const DUPLICATE_ISBN: &str = "duplicate_isbn_needs_confirmation";
     pub const DUPLICATE_BARCODE: &str = "duplicate_barcode_needs_confirmation";
+    pub const CHECKOUT_BLOCKED: &str = "checkout_blocked";
 }
 
 /// Main application error type
@@ -80,10 +88,41 @@ pub enum AppError {
         existing_item: ItemShort,
         message: String,
     },
+
+    #[error("{}", checkout_blocked_message(.0))]
+    CheckoutBlocked(Vec<CheckoutBlock>),
+
+    /// Request body fields that failed validation (422 with the per-field errors)
+    #[error("{}", invalid_fields_message(.0))]
+    InvalidFields(FieldErrors),
+}
+
+/// Failed checks per field path (`email`, `attachment.fileName`, `loanSettings[0].durationDays`)
+pub type FieldErrors = BTreeMap<String, Vec<FieldError>>;
+
+/// One failed check on a request body field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct FieldError {
+    /// Check that failed (`required`, `length`, `range`, `email`, `type`…)
+    pub code: String,
+    pub message: String,
+}
+
+/// 422 body of `invalid_fields` errors
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+pub struct InvalidFieldsResponse {
+    /// Always `"invalid_fields"`
+    pub code: String,
+    pub error: String,
+    /// Summary naming the invalid fields
+    pub message: String,
+    /// Failed checks per field path
+    #[schema(value_type = Object)]
+    pub fields: FieldErrors,
 }
 
 /// Error response body returned for all API errors.
-#[derive(Serialize, utoipa::ToSchema)]
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ErrorResponse {
     /// Machine-readable error code (e.g. `"not_found"`, `"validation_error"`)
     pub code: String,
@@ -166,37 +205,151 @@ impl IntoResponse for AppError {
                 existing_item,
                 ref message,
             } => {
-                let body = Json(crate::models::import_report::DuplicateConfirmationRequired {
+                let body = crate::models::import_report::DuplicateConfirmationRequired {
                     code: ec::DUPLICATE_ISBN.to_string(),
                     existing_id: *existing_id,
                     existing_biblio: existing_item.clone(),
                     message: message.clone(),
-                });
-                return (StatusCode::CONFLICT, body).into_response();
+                };
+                return error_response(StatusCode::CONFLICT, "Conflict", body);
             }
             AppError::DuplicateBarcodeNeedsConfirmation {
                 existing_id,
                 existing_item,
                 ref message,
             } => {
-                let body = Json(crate::models::import_report::DuplicateItemBarcodeRequired {
+                let body = crate::models::import_report::DuplicateItemBarcodeRequired {
                     code: ec::DUPLICATE_BARCODE.to_string(),
                     existing_id: *existing_id,
                     existing_item: existing_item.clone(),
                     message: message.clone(),
-                });
-                return (StatusCode::CONFLICT, body).into_response();
+                };
+                return error_response(StatusCode::CONFLICT, "Conflict", body);
+            }
+            AppError::CheckoutBlocked(blocks) => {
+                let body = crate::models::loan::CheckoutBlockedResponse {
+                    code: ec::CHECKOUT_BLOCKED.to_string(),
+                    message: checkout_blocked_message(blocks),
+                    blocks: blocks.clone(),
+                };
+                return error_response(StatusCode::UNPROCESSABLE_ENTITY, "Unprocessable Entity", body);
+            }
+            AppError::InvalidFields(fields) => {
+                let body = InvalidFieldsResponse {
+                    code: ec::INVALID_FIELDS.to_string(),
+                    error: "Unprocessable Entity".to_string(),
+                    message: invalid_fields_message(fields),
+                    fields: fields.clone(),
+                };
+                return error_response(StatusCode::UNPROCESSABLE_ENTITY, "Unprocessable Entity", body);
             }
         };
 
-        let body = Json(ErrorResponse {
+        let body = ErrorResponse {
             code: code.to_string(),
             error: error_label.to_string(),
             message,
+        };
+
+        error_response(status, error_label, body)
+    }
+}
+
+/// JSON error response, carrying its [`ProblemDetails`] for clients that negotiate them
+fn error_response(status: StatusCode, title: &str, body: impl Serialize) -> Response {
+    let body = serde_json::to_value(body).unwrap_or_default();
+    let problem = ProblemDetails::from_body(status, title, &body);
+    let mut response = (status, Json(body)).into_response();
+    response.extensions_mut().insert(problem);
+    response
+}
+
+/// Media type of RFC 7807 error bodies
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// RFC 7807 error body, sent instead of the usual envelope to clients accepting `application/problem+json`.
+///
+/// `detail` is the envelope's `message`; every other envelope member (`code`, `fields`, `blocks`…)
+/// is kept as an extension member.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ProblemDetails {
+    /// `urn:elidune:problem:<code>`
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    /// Request path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Machine-readable error code, as in the usual envelope
+    pub code: String,
+    #[serde(flatten)]
+    #[schema(value_type = Object)]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ProblemDetails {
+    fn from_body(status: StatusCode, title: &str, body: &serde_json::Value) -> Self {
+        let mut extensions = body.as_object().cloned().unwrap_or_default();
+        let code = match extensions.remove("code") {
+            Some(serde_json::Value::String(code)) => code,
+            _ => error_code::INTERNAL.to_string(),
+        };
+        let detail = match extensions.remove("message") {
+            Some(serde_json::Value::String(message)) => message,
+            _ => String::new(),
+        };
+        extensions.remove("error");
+        Self {
+            problem_type: format!("urn:elidune:problem:{}", code),
+            title: title.to_string(),
+            status: status.as_u16(),
+            detail,
+            instance: None,
+            code,
+            extensions,
+        }
+    }
+}
+
+/// Whether an `Accept` header asks for `application/problem+json` (not refused with `q=0`)
+fn accepts_problem_json(accept: &str) -> bool {
+    accept.split(',').any(|range| {
+        let mut parts = range.split(';').map(str::trim);
+        let media_type = parts.next().unwrap_or_default();
+        let refused = parts.any(|p| {
+            p.strip_prefix("q=")
+                .and_then(|q| q.parse::<f32>().ok())
+                .is_some_and(|q| q <= 0.0)
         });
+        media_type.eq_ignore_ascii_case(PROBLEM_JSON) && !refused
+    })
+}
 
-        (status, body).into_response()
+/// Middleware replacing error bodies with [`ProblemDetails`] when the client accepts
+/// `application/problem+json`; other clients keep the usual envelope.
+pub async fn problem_json(request: Request, next: Next) -> Response {
+    let wanted = request
+        .headers()
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(accepts_problem_json);
+    let instance = request.uri().path().to_string();
+    let response = next.run(request).await;
+    if !wanted {
+        return response;
     }
+    let (mut parts, body) = response.into_parts();
+    let Some(mut problem) = parts.extensions.remove::<ProblemDetails>() else {
+        return Response::from_parts(parts, body);
+    };
+    problem.instance = Some(instance);
+    let body = serde_json::to_vec(&problem).unwrap_or_default();
+    parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(body))
 }
 
 impl AppError {
@@ -230,10 +383,174 @@ impl AppError {
             AppError::DuplicateBarcodeNeedsConfirmation { message, .. } => {
                 (409, ec::DUPLICATE_BARCODE, message.clone())
             }
+            AppError::CheckoutBlocked(blocks) => {
+                (422, ec::CHECKOUT_BLOCKED, checkout_blocked_message(blocks))
+            }
+            AppError::InvalidFields(fields) => (422, ec::INVALID_FIELDS, invalid_fields_message(fields)),
         }
     }
+
+    /// `invalid_fields` error on a single field
+    pub fn invalid_field(field: impl Into<String>, code: &str, message: impl Into<String>) -> Self {
+        let mut fields = FieldErrors::new();
+        fields.entry(field.into()).or_default().push(FieldError {
+            code: code.to_string(),
+            message: message.into(),
+        });
+        AppError::InvalidFields(fields)
+    }
+}
+
+impl From<validator::ValidationErrors> for AppError {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        let mut fields = FieldErrors::new();
+        collect_field_errors(&errors, "", &mut fields);
+        AppError::InvalidFields(fields)
+    }
+}
+
+/// Flatten nested `validator` errors into JSON field paths (camelCase, like the request bodies)
+fn collect_field_errors(errors: &validator::ValidationErrors, prefix: &str, fields: &mut FieldErrors) {
+    use validator::ValidationErrorsKind;
+
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            camel_case(field)
+        } else {
+            format!("{}.{}", prefix, camel_case(field))
+        };
+        match kind {
+            ValidationErrorsKind::Field(failures) => {
+                let entry = fields.entry(path).or_default();
+                for failure in failures {
+                    entry.push(FieldError {
+                        code: failure.code.to_string(),
+                        message: failure
+                            .message
+                            .as_ref()
+                            .map(|m| m.to_string())
+                            .unwrap_or_else(|| format!("Failed the {} check", failure.code)),
+                    });
+                }
+            }
+            ValidationErrorsKind::Struct(nested) => collect_field_errors(nested, &path, fields),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_field_errors(nested, &format!("{}[{}]", path, index), fields);
+                }
+            }
+        }
+    }
+}
+
+fn camel_case(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut upper = false;
+    for c in field.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Client message of an `invalid_fields` error: the invalid field paths
+fn invalid_fields_message(fields: &FieldErrors) -> String {
+    let names: Vec<&str> = fields.keys().map(String::as_str).collect();
+    format!("Invalid fields: {}", names.join(", "))
+}
+
+/// Client message of a `checkout_blocked` error: every block, in evaluation order
+fn checkout_blocked_message(blocks: &[CheckoutBlock]) -> String {
+    let reasons: Vec<&str> = blocks.iter().map(|b| b.message.as_str()).collect();
+    format!("Checkout blocked: {}", reasons.join("; "))
 }
 
 /// Result type alias for application operations
 pub type AppResult<T> = Result<T, AppError>;
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use validator::Validate;
+
+    #[derive(Validate)]
+    struct Attachment {
+        #[validate(length(min = 1, message = "File name must not be empty"))]
+        file_name: String,
+    }
+
+    #[derive(Validate)]
+    struct Body {
+        #[validate(email(message = "Invalid email format"))]
+        contact_email: String,
+        #[validate(range(min = 0))]
+        max_loans: i16,
+        #[validate(nested)]
+        attachment: Attachment,
+        #[validate(nested)]
+        rows: Vec<Attachment>,
+    }
+
+    #[test]
+    fn validation_errors_become_field_paths() {
+        let body = Body {
+            contact_email: "nope".to_string(),
+            max_loans: -1,
+            attachment: Attachment { file_name: String::new() },
+            rows: vec![Attachment { file_name: "a.pdf".to_string() }, Attachment { file_name: String::new() }],
+        };
+        let AppError::InvalidFields(fields) = AppError::from(body.validate().unwrap_err()) else {
+            panic!("expected invalid fields");
+        };
+        let paths: Vec<&str> = fields.keys().map(String::as_str).collect();
+        assert_eq!(paths, ["attachment.fileName", "contactEmail", "maxLoans", "rows[1].fileName"]);
+        assert_eq!(fields["contactEmail"][0], FieldError { code: "email".into(), message: "Invalid email format".into() });
+        assert_eq!(fields["maxLoans"][0].code, "range");
+        assert_eq!(
+            invalid_fields_message(&fields),
+            "Invalid fields: attachment.fileName, contactEmail, maxLoans, rows[1].fileName"
+        );
+    }
+
+    #[test]
+    fn invalid_fields_respond_422() {
+        let error = AppError::invalid_field("login", "required", "login is required");
+        assert_eq!(error.audit_http_fields().0, 422);
+        assert_eq!(error.into_response().status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[test]
+    fn problem_details_keep_the_envelope_members() {
+        let response = AppError::invalid_field("login", "required", "login is required").into_response();
+        let problem = response.extensions().get::<ProblemDetails>().unwrap();
+        let json = serde_json::to_value(problem).unwrap();
+        assert_eq!(json["type"], "urn:elidune:problem:invalid_fields");
+        assert_eq!(json["title"], "Unprocessable Entity");
+        assert_eq!(json["status"], 422);
+        assert_eq!(json["detail"], "Invalid fields: login");
+        assert_eq!(json["code"], "invalid_fields");
+        assert_eq!(json["fields"]["login"][0]["code"], "required");
+        assert!(json.get("error").is_none() && json.get("message").is_none());
+
+        let response = AppError::NotFound("Biblio 7 not found".into()).into_response();
+        let problem = response.extensions().get::<ProblemDetails>().unwrap();
+        assert_eq!((problem.title.as_str(), problem.status), ("Not Found", 404));
+        assert!(problem.extensions.is_empty());
+    }
+
+    #[test]
+    fn problem_json_is_negotiated() {
+        assert!(accepts_problem_json("application/problem+json"));
+        assert!(accepts_problem_json("application/json, application/problem+json;q=0.9"));
+        assert!(!accepts_problem_json("application/json"));
+        assert!(!accepts_problem_json("*/*"));
+        assert!(!accepts_problem_json("application/problem+json;q=0"));
+    }
+}
+
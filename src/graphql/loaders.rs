@@ -0,0 +1,73 @@
+//! DataLoaders batching the per-node lookups of a GraphQL request into one query per field.
+//!
+//! Each loader is created per request (see [`super::request_data`]), so its cache never outlives
+//! the caller's rights.
+
+use std::{collections::HashMap, sync::Arc};
+
+use async_graphql::{dataloader::Loader, ErrorExtensions};
+
+use crate::{
+    models::{author::Author, biblio::BiblioAvailability, item::ItemShort, loan::LoanDetails},
+    repository::{BibliosRepository, LoansRepository},
+};
+
+/// Authors of a biblio, in credit order
+pub struct AuthorsLoader(pub Arc<dyn BibliosRepository>);
+
+impl Loader<i64> for AuthorsLoader {
+    type Value = Vec<Author>;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[i64]) -> Result<HashMap<i64, Self::Value>, Self::Error> {
+        self.0
+            .biblios_get_authors_by_biblio_ids(keys)
+            .await
+            .map_err(|e| e.extend())
+    }
+}
+
+/// Active copies of a biblio
+pub struct ItemsLoader(pub Arc<dyn BibliosRepository>);
+
+impl Loader<i64> for ItemsLoader {
+    type Value = Vec<ItemShort>;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[i64]) -> Result<HashMap<i64, Self::Value>, Self::Error> {
+        self.0
+            .biblios_get_items_short_by_biblio_ids(keys)
+            .await
+            .map_err(|e| e.extend())
+    }
+}
+
+/// Copy availability of a biblio
+pub struct AvailabilityLoader(pub Arc<dyn BibliosRepository>);
+
+impl Loader<i64> for AvailabilityLoader {
+    type Value = BiblioAvailability;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[i64]) -> Result<HashMap<i64, Self::Value>, Self::Error> {
+        self.0
+            .biblios_get_availability_by_ids(keys)
+            .await
+            .map_err(|e| e.extend())
+    }
+}
+
+/// Active loans of a user
+pub struct LoansLoader(pub Arc<dyn LoansRepository>);
+
+impl Loader<i64> for LoansLoader {
+    type Value = Vec<LoanDetails>;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[i64]) -> Result<HashMap<i64, Self::Value>, Self::Error> {
+        self.0
+            .loans_get_active_for_users(keys)
+            .await
+            .map_err(|e| e.extend())
+    }
+}
@@ -0,0 +1,82 @@
+//! GraphQL facade for the frontend (`graphql` feature).
+//!
+//! A read-only graph over the same services as the REST API: records with their authors, copies
+//! and availability, users with their loans, and dashboard statistics. Nested fields are resolved
+//! through per-request [`DataLoader`]s, so a page of records costs one query per field rather
+//! than one per record. Every resolver applies the rights check of its REST counterpart.
+
+mod loaders;
+mod query;
+mod types;
+
+use std::sync::Arc;
+
+use async_graphql::{
+    dataloader::DataLoader, Context, EmptyMutation, EmptySubscription, ErrorExtensions, Schema, ID,
+};
+
+use crate::{error::AppError, models::user::UserClaims, services::Services, AppResult};
+
+pub use query::QueryRoot;
+
+pub type EliduneSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Deepest selection accepted (`users { items { loans { biblio { authors { id } } } } }` is 6)
+const MAX_DEPTH: usize = 8;
+/// Highest query complexity accepted (one point per field)
+const MAX_COMPLEXITY: usize = 500;
+
+/// Schema over `services`, with depth and complexity limits
+pub fn build_schema(services: Arc<Services>) -> EliduneSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(services)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// Attach the caller's claims and fresh loaders to a request
+pub fn request_data(
+    request: async_graphql::Request,
+    services: &Services,
+    claims: UserClaims,
+) -> async_graphql::Request {
+    let repository = Arc::new(services.minimal_repository());
+    request
+        .data(claims)
+        .data(DataLoader::new(loaders::AuthorsLoader(repository.clone()), tokio::spawn))
+        .data(DataLoader::new(loaders::ItemsLoader(repository.clone()), tokio::spawn))
+        .data(DataLoader::new(loaders::AvailabilityLoader(repository.clone()), tokio::spawn))
+        .data(DataLoader::new(loaders::LoansLoader(repository), tokio::spawn))
+}
+
+/// GraphQL errors carry the REST error code and status, with the same client-safe message.
+impl ErrorExtensions for AppError {
+    fn extend(&self) -> async_graphql::Error {
+        let (status, code, message) = self.audit_http_fields();
+        async_graphql::Error::new(message).extend_with(|_, e| {
+            e.set("code", code);
+            e.set("status", status);
+        })
+    }
+}
+
+/// Convert a service result; never use `?` on [`AppError`] directly, it would expose internal
+/// messages (e.g. database errors) instead of the client-safe one.
+fn gql<T>(result: AppResult<T>) -> async_graphql::Result<T> {
+    result.map_err(|e| e.extend())
+}
+
+fn claims<'a>(ctx: &Context<'a>) -> &'a UserClaims {
+    ctx.data_unchecked::<UserClaims>()
+}
+
+fn services<'a>(ctx: &Context<'a>) -> &'a Services {
+    ctx.data_unchecked::<Arc<Services>>()
+}
+
+fn parse_id(id: &ID) -> async_graphql::Result<i64> {
+    gql(id
+        .parse()
+        .map_err(|_| AppError::BadRequest(format!("Invalid id: {}", id.as_str()))))
+}
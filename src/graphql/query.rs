@@ -0,0 +1,133 @@
+//! Query root
+
+use async_graphql::{Context, Object, Result, ID};
+
+use super::{
+    claims, gql, parse_id, services,
+    types::{Biblio, Page, User},
+};
+use crate::{
+    api::stats::{StatsQuery, StatsResponse},
+    models::{
+        biblio::{BiblioQuery, BiblioShort, Isbn, MediaType},
+        user::UserQuery,
+    },
+};
+
+/// Largest page a search returns
+const MAX_PER_PAGE: i64 = 100;
+
+fn page_bounds(page: Option<i64>, per_page: Option<i64>) -> (i64, i64) {
+    (page.unwrap_or(1).max(1), per_page.unwrap_or(20).clamp(1, MAX_PER_PAGE))
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Search the catalog (same filters and rights as `GET /biblios`)
+    #[allow(clippy::too_many_arguments)]
+    async fn biblios(
+        &self,
+        ctx: &Context<'_>,
+        title: Option<String>,
+        author: Option<String>,
+        author_role: Option<String>,
+        isbn: Option<String>,
+        freesearch: Option<String>,
+        media_type: Option<String>,
+        page: Option<i64>,
+        per_page: Option<i64>,
+    ) -> Result<Page<Biblio>> {
+        gql(claims(ctx).require_read_items())?;
+        let (page, per_page) = page_bounds(page, per_page);
+        let query = BiblioQuery {
+            title,
+            author,
+            author_role,
+            isbn: isbn.map(Isbn::new),
+            freesearch,
+            media_type,
+            page: Some(page),
+            per_page: Some(per_page),
+            ..Default::default()
+        };
+        let (biblios, total) = gql(services(ctx).catalog.search_biblios(&query).await)?;
+        Ok(Page {
+            items: biblios.into_iter().map(Biblio).collect(),
+            total,
+            page,
+            per_page,
+        })
+    }
+
+    /// Record by id
+    async fn biblio(&self, ctx: &Context<'_>, id: ID) -> Result<Biblio> {
+        gql(claims(ctx).require_read_items())?;
+        let biblio = gql(services(ctx).catalog.get_biblio(parse_id(&id)?).await)?;
+        Ok(Biblio(BiblioShort::from(biblio)))
+    }
+
+    /// Search users by name or card barcode (same rights as `GET /users`)
+    async fn users(
+        &self,
+        ctx: &Context<'_>,
+        name: Option<String>,
+        barcode: Option<String>,
+        page: Option<i64>,
+        per_page: Option<i64>,
+    ) -> Result<Page<User>> {
+        gql(claims(ctx).require_read_users())?;
+        let (page, per_page) = page_bounds(page, per_page);
+        let query = UserQuery {
+            name,
+            barcode,
+            page: Some(page),
+            per_page: Some(per_page),
+        };
+        let (users, total) = gql(services(ctx).users.search_users(&query).await)?;
+        Ok(Page {
+            items: users.into_iter().map(User::from).collect(),
+            total,
+            page,
+            per_page,
+        })
+    }
+
+    /// User by id: the caller's own account, or any account with user read rights
+    async fn user(&self, ctx: &Context<'_>, id: ID) -> Result<User> {
+        let id = parse_id(&id)?;
+        let claims = claims(ctx);
+        if id != claims.user_id {
+            gql(claims.require_read_users())?;
+        }
+        Ok(User::from(gql(services(ctx).users.get_by_id(id).await)?))
+    }
+
+    /// The authenticated user
+    async fn me(&self, ctx: &Context<'_>) -> Result<User> {
+        Ok(User::from(gql(services(ctx).users.get_by_id(claims(ctx).user_id).await)?))
+    }
+
+    /// Dashboard statistics (same parameters and rights as `GET /stats`)
+    async fn stats(
+        &self,
+        ctx: &Context<'_>,
+        year: Option<i32>,
+        start_date: Option<String>,
+        end_date: Option<String>,
+        public_type: Option<String>,
+        media_type: Option<String>,
+    ) -> Result<StatsResponse> {
+        gql(claims(ctx).require_read_items())?;
+        let query = StatsQuery {
+            year,
+            start_date,
+            end_date,
+            public_type,
+            media_type: media_type.as_deref().map(MediaType::from),
+            compare_to: None,
+        };
+        gql(services(ctx).stats.get_stats(query.filter()).await)
+    }
+}
@@ -0,0 +1,261 @@
+//! Graph nodes wrapping the REST models.
+//!
+//! Ids are exposed as `ID` (strings) like in the REST API: snowflake ids exceed the 2^53 range
+//! of JavaScript numbers.
+
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, Object, OutputType, Result, SimpleObject, ID};
+use chrono::{DateTime, Utc};
+
+use super::{
+    claims, gql,
+    loaders::{AuthorsLoader, AvailabilityLoader, ItemsLoader, LoansLoader},
+};
+use crate::{
+    error::AppError,
+    models::{
+        author::Author as AuthorModel,
+        biblio::{BiblioAvailability, BiblioShort},
+        item::ItemShort,
+        loan::LoanDetails,
+        user::{Rights, User as UserModel, UserShort},
+    },
+};
+
+/// One page of a search
+#[derive(SimpleObject)]
+#[graphql(concrete(name = "BiblioPage", params(Biblio)))]
+#[graphql(concrete(name = "UserPage", params(User)))]
+pub struct Page<T: OutputType> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+/// Bibliographic record
+pub struct Biblio(pub BiblioShort);
+
+#[Object]
+impl Biblio {
+    async fn id(&self) -> ID {
+        self.0.id.into()
+    }
+
+    async fn title(&self) -> Option<&str> {
+        self.0.title.as_deref()
+    }
+
+    async fn isbn(&self) -> Option<String> {
+        self.0.isbn.as_ref().map(ToString::to_string)
+    }
+
+    /// Media type code (e.g. `b`, `bc`, `p`)
+    async fn media_type(&self) -> &str {
+        self.0.media_type.as_code()
+    }
+
+    async fn publication_date(&self) -> Option<&str> {
+        self.0.date.as_deref()
+    }
+
+    /// Authors, in credit order
+    async fn authors(&self, ctx: &Context<'_>) -> Result<Vec<Author>> {
+        let authors = ctx.data_unchecked::<DataLoader<AuthorsLoader>>().load_one(self.0.id).await?;
+        Ok(authors.unwrap_or_default().into_iter().map(Author).collect())
+    }
+
+    /// Active copies (specimens)
+    async fn items(&self, ctx: &Context<'_>) -> Result<Vec<Item>> {
+        let items = ctx.data_unchecked::<DataLoader<ItemsLoader>>().load_one(self.0.id).await?;
+        Ok(items.unwrap_or_default().into_iter().map(Item).collect())
+    }
+
+    /// Copy availability (`null` for archived records)
+    async fn availability(&self, ctx: &Context<'_>) -> Result<Option<Availability>> {
+        let availability = ctx
+            .data_unchecked::<DataLoader<AvailabilityLoader>>()
+            .load_one(self.0.id)
+            .await?;
+        Ok(availability.map(Availability))
+    }
+}
+
+/// Author credited on a record
+pub struct Author(pub AuthorModel);
+
+#[Object]
+impl Author {
+    async fn id(&self) -> ID {
+        self.0.id.into()
+    }
+
+    async fn lastname(&self) -> Option<&str> {
+        self.0.lastname.as_deref()
+    }
+
+    async fn firstname(&self) -> Option<&str> {
+        self.0.firstname.as_deref()
+    }
+
+    /// Preferred display form, when the cataloguer set one
+    async fn display_name(&self) -> Option<&str> {
+        self.0.display_name.as_deref()
+    }
+
+    /// Role on the record (`author`, `illustrator`, `translator`, …)
+    async fn function(&self) -> Option<&str> {
+        self.0.function.as_ref().map(|f| f.as_db_str())
+    }
+}
+
+/// Physical copy of a record
+pub struct Item(pub ItemShort);
+
+#[Object]
+impl Item {
+    async fn id(&self) -> ID {
+        self.0.id.into()
+    }
+
+    async fn barcode(&self) -> Option<&str> {
+        self.0.barcode.as_deref()
+    }
+
+    async fn call_number(&self) -> Option<&str> {
+        self.0.call_number.as_deref()
+    }
+
+    async fn borrowable(&self) -> bool {
+        self.0.borrowable
+    }
+
+    /// Currently on loan
+    async fn borrowed(&self) -> bool {
+        self.0.borrowed
+    }
+
+    async fn source_name(&self) -> Option<&str> {
+        self.0.source_name.as_deref()
+    }
+}
+
+/// Copy availability of a record
+pub struct Availability(pub BiblioAvailability);
+
+#[Object]
+impl Availability {
+    /// Active, borrowable copies
+    async fn total_items(&self) -> i64 {
+        self.0.total_items
+    }
+
+    /// Active, borrowable copies not currently on loan
+    async fn available_items(&self) -> i64 {
+        self.0.available_items
+    }
+
+    /// Pending or ready holds across all copies
+    async fn hold_count(&self) -> i64 {
+        self.0.hold_count
+    }
+
+    /// True when at least one copy can be borrowed right now
+    async fn available(&self) -> bool {
+        self.0.is_available()
+    }
+}
+
+/// Library user
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct User {
+    #[graphql(skip)]
+    pub user_id: i64,
+    pub id: ID,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    pub account_type: Option<String>,
+    pub expiry_at: Option<DateTime<Utc>>,
+}
+
+#[ComplexObject]
+impl User {
+    /// Active loans, by due date. Same rights as `GET /users/{id}/loans`.
+    async fn loans(&self, ctx: &Context<'_>) -> Result<Vec<Loan>> {
+        let claims = claims(ctx);
+        gql(claims.require_self_or_staff(self.user_id))?;
+        if claims.rights.loans_rights.rank() < Rights::Read.rank() && self.user_id != claims.user_id {
+            return gql(Err(AppError::Authorization(
+                "Insufficient rights to read loans for another user".into(),
+            )));
+        }
+        let loans = ctx.data_unchecked::<DataLoader<LoansLoader>>().load_one(self.user_id).await?;
+        Ok(loans.unwrap_or_default().into_iter().map(Loan).collect())
+    }
+}
+
+impl From<UserShort> for User {
+    fn from(user: UserShort) -> Self {
+        Self {
+            user_id: user.id,
+            id: user.id.into(),
+            firstname: user.firstname,
+            lastname: user.lastname,
+            account_type: user.account_type.map(|a| a.to_string()),
+            expiry_at: user.expiry_at,
+        }
+    }
+}
+
+impl From<UserModel> for User {
+    fn from(user: UserModel) -> Self {
+        Self {
+            user_id: user.id,
+            id: user.id.into(),
+            firstname: user.firstname,
+            lastname: user.lastname,
+            account_type: Some(user.account_type.to_string()),
+            expiry_at: user.expiry_at,
+        }
+    }
+}
+
+/// Active or returned loan
+pub struct Loan(pub LoanDetails);
+
+#[Object]
+impl Loan {
+    async fn id(&self) -> ID {
+        self.0.id.into()
+    }
+
+    async fn start_date(&self) -> DateTime<Utc> {
+        self.0.start_date
+    }
+
+    async fn expiry_at(&self) -> DateTime<Utc> {
+        self.0.expiry_at
+    }
+
+    async fn renewal_date(&self) -> Option<DateTime<Utc>> {
+        self.0.renewal_date
+    }
+
+    async fn nb_renews(&self) -> i16 {
+        self.0.nb_renews
+    }
+
+    async fn is_overdue(&self) -> bool {
+        self.0.is_overdue
+    }
+
+    /// Barcode of the borrowed copy or equipment
+    async fn item_identification(&self) -> Option<&str> {
+        self.0.item_identification.as_deref()
+    }
+
+    /// Borrowed record (`null` for equipment loans)
+    async fn biblio(&self) -> Option<Biblio> {
+        self.0.biblio.clone().map(Biblio)
+    }
+}
@@ -1,4 +1,5 @@
-//! Email notification when a hold becomes `ready` (after a loan return).
+//! Email notifications when a hold becomes `ready` (after a loan return) or its copy is placed in
+//! a pickup locker.
 
 use crate::{
     email::EmailService,
@@ -36,14 +37,14 @@ pub async fn send_hold_ready(
 
     let title = loan_details
         .biblio
-        .title
-        .as_deref()
+        .as_ref()
+        .and_then(|b| b.title.as_deref())
         .unwrap_or("(unknown title)");
 
     let barcode = loan_details
         .biblio
-        .items
-        .first()
+        .as_ref()
+        .and_then(|b| b.items.first())
         .and_then(|i| i.barcode.as_deref());
 
     let barcode_line = barcode.map(|b| format!("Barcode: {b}")).unwrap_or_default();
@@ -71,3 +72,50 @@ pub async fn send_hold_ready(
         .send_email_with_html(to, &subject, &body_plain, &body_html)
         .await
 }
+
+/// Send "hold in locker" email (compartment and pickup code) to the patron. No-op if user has no
+/// email.
+#[tracing::instrument(skip_all, fields(hold_id = hold.id, user_id = hold.user_id))]
+pub async fn send_hold_in_locker(
+    email_svc: &EmailService,
+    contact: Option<HoldReadyUserContact>,
+    hold: &Hold,
+    title: &str,
+) -> AppResult<()> {
+    let Some(row) = contact else {
+        tracing::warn!(user_id = hold.user_id, "User not found for hold in locker email");
+        return Ok(());
+    };
+    let to = match row.email.as_deref().map(str::trim) {
+        Some(e) if !e.is_empty() => e,
+        _ => {
+            tracing::debug!(user_id = hold.user_id, "No email — skipping hold in locker notification");
+            return Ok(());
+        }
+    };
+
+    let firstname = row.firstname.clone().unwrap_or_default();
+    let lastname = row.lastname.clone().unwrap_or_default();
+    let lang = row.language.as_deref().map(Language::from);
+    let compartment = hold.locker_compartment.clone().unwrap_or_default();
+    let pickup_code = hold.locker_pickup_code.clone().unwrap_or_else(|| "—".to_string());
+    let expires_at = hold
+        .expires_at
+        .map(|d| d.format("%d/%m/%Y %H:%M UTC").to_string())
+        .unwrap_or_else(|| "—".to_string());
+
+    let template = email_svc.load_template("hold_in_locker", lang).await?;
+    let vars: Vec<(&str, &str)> = vec![
+        ("firstname", firstname.as_str()),
+        ("lastname", lastname.as_str()),
+        ("title", title),
+        ("compartment", compartment.as_str()),
+        ("pickup_code", pickup_code.as_str()),
+        ("expires_at", expires_at.as_str()),
+    ];
+    let (subject, body_plain, body_html) = email_templates::substitute(&template, &vars);
+
+    email_svc
+        .send_email_with_html(to, &subject, &body_plain, &body_html)
+        .await
+}
@@ -13,17 +13,23 @@ pub mod dynamic_config;
 pub mod email;
 pub mod email_templates;
 pub mod error;
+#[cfg(feature = "graphql")]
+pub mod graphql;
 pub mod marc;
 pub mod models;
 pub mod repository;
 pub mod hold_email;
 pub mod services;
+pub mod utils;
 
 pub use config::AppConfig;
 pub use email::EmailService;
 pub use dynamic_config::DynamicConfig;
 pub use error::{AppError, AppResult};
 
+/// Database migrations embedded from `migrations/`
+pub static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("./migrations");
+
 /// Application state shared across all handlers
 #[derive(Clone)]
 pub struct AppState {
@@ -33,5 +39,5 @@ pub struct AppState {
     /// Wake handle for the reminder scheduler task (re-evaluates schedule on config change)
     pub scheduler_notify: Arc<Notify>,
     /// Broadcast channel for real-time SSE events (loan.created, loan.returned, etc.)
-    pub event_bus: broadcast::Sender<crate::api::sse::SsePayload>,
+    pub event_bus: broadcast::Sender<crate::api::sse::SseEvent>,
 }
@@ -2,15 +2,11 @@
 //!
 //! A modern Rust REST API server for library management.
 
-use axum::{routing::get, Router};
 use sqlx::postgres::PgPoolOptions;
 use std::env;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Duration;
-use tower_http::trace::TraceLayer;
 use std::path::Path;
-use tower_governor::{governor::GovernorConfigBuilder, GovernorLayer};
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, Layer, reload};
 
 use elidune_server::{
@@ -65,6 +61,54 @@ fn config_path_from_args() -> Option<String> {
     None
 }
 
+/// `restore <file> [--yes]` subcommand: load a backup instead of starting the server
+struct RestoreCommand {
+    file: String,
+    /// Replace the content of a database that already holds data
+    replace: bool,
+}
+
+/// Parse `elidune-server [--config <path>] restore <file> [--yes]`
+fn restore_command_from_args() -> Option<Result<RestoreCommand, String>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut positional = Vec::new();
+    let mut replace = false;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--config" | "-c" => i += 1,
+            "--yes" | "-y" => replace = true,
+            other => positional.push(other.to_string()),
+        }
+        i += 1;
+    }
+    if positional.first().map(String::as_str) != Some("restore") {
+        return None;
+    }
+    Some(match positional.get(1) {
+        Some(file) if positional.len() == 2 => Ok(RestoreCommand { file: file.clone(), replace }),
+        _ => Err("usage: elidune-server [--config <path>] restore <backup-file> [--yes]".to_string()),
+    })
+}
+
+/// Run the restore subcommand (server stopped) and report on stdout
+async fn run_restore(config: &AppConfig, command: Result<RestoreCommand, String>) -> anyhow::Result<()> {
+    let command = command.map_err(|usage| anyhow::anyhow!(usage))?;
+    println!("Restoring {} into the configured database…", command.file);
+    let report = elidune_server::services::backup::restore::restore_file(
+        &config.database.url,
+        Path::new(&command.file),
+        command.replace,
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!("Restore failed: {}", e))?;
+    println!(
+        "Restored {} rows in {} tables (backup at migration {}, taken by v{}); {} newer migrations applied.",
+        report.rows, report.tables, report.schema_version, report.backup_server_version, report.migrations_applied
+    );
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Load environment variables from .env file
@@ -74,6 +118,11 @@ async fn main() -> anyhow::Result<()> {
     let config = AppConfig::load(config_path_from_args().as_deref())
         .expect("Failed to load configuration");
 
+    // `elidune-server restore <file>`: load a backup, then exit without serving
+    if let Some(command) = restore_command_from_args() {
+        return run_restore(&config, command).await;
+    }
+
     // Initialize tracing
     let initial_filter = tracing_subscriber::EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| format!("elidune_server={},tower_http=debug,z3950_rs=debug", config.logging.level).into());
@@ -139,7 +188,7 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Connected to database");
 
     // Run migrations
-    sqlx::migrate!("./migrations")
+    elidune_server::MIGRATOR
         .run(&pool)
         .await
         .expect("Failed to run database migrations");
@@ -162,6 +211,11 @@ async fn main() -> anyhow::Result<()> {
                 "reminders" => config.reminders.overridable,
                 "audit" => config.audit.overridable,
                 "holds" => config.holds.overridable,
+                "lockers" => config.lockers.overridable,
+                "circulation" => config.circulation.overridable,
+                "eod" => config.eod.overridable,
+                "reengagement" => config.reengagement.overridable,
+                "sitemap" => config.sitemap.overridable,
                 _ => false,
             };
             if !overridable {
@@ -199,6 +253,36 @@ async fn main() -> anyhow::Result<()> {
                         tracing::info!("DB settings: overriding [holds]");
                     }
                 }
+                "lockers" => {
+                    if let Ok(v) = serde_json::from_value(value) {
+                        merged.lockers = v;
+                        tracing::info!("DB settings: overriding [lockers]");
+                    }
+                }
+                "circulation" => {
+                    if let Ok(v) = serde_json::from_value(value) {
+                        merged.circulation = v;
+                        tracing::info!("DB settings: overriding [circulation]");
+                    }
+                }
+                "eod" => {
+                    if let Ok(v) = serde_json::from_value(value) {
+                        merged.eod = v;
+                        tracing::info!("DB settings: overriding [eod]");
+                    }
+                }
+                "reengagement" => {
+                    if let Ok(v) = serde_json::from_value(value) {
+                        merged.reengagement = v;
+                        tracing::info!("DB settings: overriding [reengagement]");
+                    }
+                }
+                "sitemap" => {
+                    if let Ok(v) = serde_json::from_value(value) {
+                        merged.sitemap = v;
+                        tracing::info!("DB settings: overriding [sitemap]");
+                    }
+                }
                 _ => {}
             }
         }
@@ -270,6 +354,7 @@ async fn main() -> anyhow::Result<()> {
         redis_service,
         config.meilisearch.clone(),
         email_service,
+        config.artifacts.clone(),
     )
     .await
     .expect("Failed to create services");
@@ -313,16 +398,37 @@ async fn main() -> anyhow::Result<()> {
         audit::AuditLogMeta::success(),
     );
 
-    // Start background scheduler (reminder sender + audit cleanup)
+    // Start background scheduler (reminder sender + audit and artifact cleanup)
     let scheduler_notify = elidune_server::services::scheduler::spawn(
         dynamic_config.clone(),
         services.reminders.clone(),
         services.audit.clone(),
         services.holds.clone(),
+        services.lockers.clone(),
+        services.artifacts.clone(),
+        services.harvest.clone(),
+        services.users.clone(),
+        services.warehouse.clone(),
+        services.snapshots.clone(),
+        services.purchase_suggestions.clone(),
+        services.eod.clone(),
+        services.loans.clone(),
+        services.reengagement.clone(),
+        services.sitemap.clone(),
+    );
+
+    // Optional Z39.50 target for partner libraries (own port)
+    elidune_server::services::z3950_server::spawn(
+        config.z3950_server.clone(),
+        services.catalog.clone(),
     );
 
     // Broadcast channel for SSE real-time events (capacity = 256 messages)
     let (event_bus, _) = tokio::sync::broadcast::channel(256);
+    // Events published by any instance (this one included) reach this instance's SSE clients
+    services
+        .redis
+        .relay(elidune_server::services::redis::EVENTS_CHANNEL, event_bus.clone());
 
     // Create application state
     let state = AppState {
@@ -334,7 +440,7 @@ async fn main() -> anyhow::Result<()> {
     };
 
     // Build router
-    let app = create_router(state);
+    let app = api::router::create_router(state);
 
     // Start server
     let addr = SocketAddr::new(
@@ -383,128 +489,3 @@ async fn shutdown_signal() {
         _ = terminate => tracing::info!("Received SIGTERM, initiating graceful shutdown"),
     }
 }
-
-/// Create the application router with all routes.
-///
-/// Each domain's routes are registered in its own `api::<domain>::router()` function.
-/// `main.rs` only merges them under `/api/v1` and applies middleware.
-fn create_router(state: AppState) -> Router {
-    let cors = build_cors(&state.config);
-
-    // Rate-limit auth endpoints: burst of 2, replenish 1 per 4s by default (secure preset).
-    let per_second = state.config.server.auth_rate_per_second.unwrap_or(4);
-    let burst_size = state.config.server.auth_rate_burst.unwrap_or(2);
-    // Box::leak gives a `'static` reference; the config lives for the entire process lifetime.
-    let governor_conf: &'static _ = Box::leak(Box::new(
-        GovernorConfigBuilder::default()
-            .per_second(per_second)
-            .burst_size(burst_size)
-            .finish()
-            .expect("Failed to build auth rate-limit configuration"),
-    ));
-
-    // Public anonymous APIs (OPAC, covers, library-info GET): separate quota from auth.
-    let public_per_second = state.config.server.public_rate_per_second.unwrap_or(30);
-    let public_burst = state.config.server.public_rate_burst.unwrap_or(100);
-    let public_governor_conf: &'static _ = Box::leak(Box::new(
-        GovernorConfigBuilder::default()
-            .per_second(public_per_second)
-            .burst_size(public_burst)
-            .finish()
-            .expect("Failed to build public rate-limit configuration"),
-    ));
-
-    // Periodically evict expired entries to bound memory usage (auth + public limiters).
-    let auth_limiter = governor_conf.limiter().clone();
-    let public_limiter = public_governor_conf.limiter().clone();
-    std::thread::spawn(move || loop {
-        std::thread::sleep(Duration::from_secs(60));
-        auth_limiter.retain_recent();
-        public_limiter.retain_recent();
-    });
-
-    let auth_router = api::auth::router()
-        .layer(GovernorLayer { config: governor_conf });
-
-    // OpenAPI documentation (unauthenticated; no governor — see plan).
-    let openapi = api::openapi::create_openapi_router();
-
-    // OPAC, covers, library-info GET only — rate-limited per IP.
-    let public_router = Router::new()
-        .merge(api::opac::router())
-        .merge(api::covers::router())
-        .merge(api::library_info::router_public())
-        .layer(GovernorLayer {
-            config: public_governor_conf,
-        });
-
-    let api_v1 = Router::new()
-        .merge(api::health::router())
-        .merge(api::first_setup::router())
-        .merge(auth_router)
-        .merge(public_router)
-        .merge(api::biblios::router())
-        .merge(api::items::router())
-        .merge(api::users::router())
-        .merge(api::loans::router())
-        .merge(api::batch::router())
-        .merge(api::holds::router())
-        .merge(api::fines::router())
-        .merge(api::inventory::router())
-        .merge(api::sse::router())
-        .merge(api::z3950::router())
-        .merge(api::stats::router())
-        .merge(api::library_info::router_staff())
-        .merge(api::email_templates::router())
-        .merge(api::admin_config::router())
-        .merge(api::audit::router())
-        .merge(api::public_types::router())
-        .merge(api::visitor_counts::router())
-        .merge(api::schedules::router())
-        .merge(api::series::router())
-        .merge(api::collections::router())
-        .merge(api::sources::router())
-        .merge(api::equipment::router())
-        .merge(api::events::router())
-        .merge(api::account_types::router())
-        .merge(api::maintenance::router())
-        .merge(api::tasks::router())
-        .with_state(state.clone());
-
-    Router::new()
-        .route("/version", get(api::health::version))
-        .nest("/api/v1", api_v1)
-        .merge(openapi)
-        .layer(TraceLayer::new_for_http())
-        .layer(cors)
-}
-
-/// Build the CORS layer from configuration.
-///
-/// In production (`server.cors_origins` is set), only the listed origins are allowed.
-/// When the list is empty or the field is absent, CORS falls back to `Any` (dev mode).
-fn build_cors(config: &elidune_server::config::AppConfig) -> tower_http::cors::CorsLayer {
-    use tower_http::cors::{Any, CorsLayer};
-    use axum::http::HeaderValue;
-
-    if let Some(ref origins) = config.server.cors_origins {
-        if !origins.is_empty() {
-            let parsed: Vec<HeaderValue> = origins
-                .iter()
-                .filter_map(|o| o.parse().ok())
-                .collect();
-            if !parsed.is_empty() {
-                return CorsLayer::new()
-                    .allow_origin(parsed)
-                    .allow_methods(Any)
-                    .allow_headers(Any);
-            }
-        }
-    }
-
-    // Permissive default for development / unconfigured deployments.
-    CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any)
-}
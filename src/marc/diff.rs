@@ -0,0 +1,243 @@
+//! Field-level comparison of two MARC records and selective merge by tag
+//!
+//! Both records are written to ISO 2709 in the same format, so they are compared on the tags a
+//! cataloger knows rather than on the semantic model.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use z3950_rs::marc_rs::{raw::RawField, BinaryReader, BinaryWriter, Encoding, MarcFormat};
+
+use super::MarcRecord;
+use crate::error::{AppError, AppResult};
+
+const FIELD_TERMINATOR: u8 = 0x1E;
+const RECORD_TERMINATOR: u8 = 0x1D;
+const SUBFIELD_DELIMITER: u8 = 0x1F;
+
+/// MARC flavour in which records are compared
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MarcDiffFormat {
+    #[default]
+    Unimarc,
+    Marc21,
+}
+
+impl MarcDiffFormat {
+    fn marc_format(self) -> MarcFormat {
+        match self {
+            Self::Unimarc => MarcFormat::Unimarc(Encoding::Utf8),
+            Self::Marc21 => MarcFormat::Marc21(Encoding::Utf8),
+        }
+    }
+}
+
+/// How a tag differs between the local and the remote record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MarcFieldChange {
+    Unchanged,
+    /// Present on both sides with different occurrences
+    Changed,
+    /// Only in the remote record
+    Added,
+    /// Only in the local record (typically a local enrichment)
+    Removed,
+}
+
+/// Occurrences of one tag on each side, as `ind1ind2 $a value $b value` (control fields: raw value)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarcFieldDiff {
+    pub tag: String,
+    pub change: MarcFieldChange,
+    pub local: Vec<String>,
+    pub remote: Vec<String>,
+}
+
+/// One field of an ISO 2709 record
+struct Field {
+    tag: String,
+    /// Indicators and subfields (or control value), without the field terminator
+    data: Vec<u8>,
+    text: String,
+}
+
+/// Leader and fields of `record` written as ISO 2709 in `format`
+fn record_fields(record: &MarcRecord, format: &MarcFormat) -> AppResult<(Vec<u8>, Vec<Field>)> {
+    let mut record = record.clone();
+    let mut data = Vec::new();
+    BinaryWriter::new(&mut data)
+        .write_record(format, &mut record)
+        .map_err(|e| AppError::Internal(format!("Failed to write MARC record: {}", e)))?;
+    let view = BinaryReader::new(&data)
+        .next()
+        .ok_or_else(|| AppError::Internal("MARC writer produced no record".to_string()))?
+        .map_err(|e| AppError::Internal(format!("Failed to read MARC record: {}", e)))?;
+    let raw = view.as_raw();
+    let leader = raw
+        .leader()
+        .map_err(|e| AppError::Internal(format!("Failed to read MARC leader: {}", e)))?
+        .to_vec();
+    let fields = raw
+        .fields()
+        .map_err(|e| AppError::Internal(format!("Failed to read MARC fields: {}", e)))?
+        .into_iter()
+        .map(|field| match field {
+            RawField::Control { tag, data } => {
+                let data = data.strip_suffix(&[FIELD_TERMINATOR]).unwrap_or(data).to_vec();
+                Field {
+                    tag: String::from_utf8_lossy(&tag).into_owned(),
+                    text: String::from_utf8_lossy(&data).trim().to_string(),
+                    data,
+                }
+            }
+            RawField::Data { tag, indicators, body } => {
+                let body = body.strip_suffix(&[FIELD_TERMINATOR]).unwrap_or(body);
+                let subfields: Vec<String> = body
+                    .split(|&b| b == SUBFIELD_DELIMITER)
+                    .skip(1)
+                    .filter_map(|sub| {
+                        let (code, value) = sub.split_first()?;
+                        Some(format!("${} {}", *code as char, String::from_utf8_lossy(value)))
+                    })
+                    .collect();
+                let mut data = indicators.to_vec();
+                data.extend_from_slice(body);
+                Field {
+                    tag: String::from_utf8_lossy(&tag).into_owned(),
+                    text: format!("{} {}", String::from_utf8_lossy(&indicators), subfields.join(" ")),
+                    data,
+                }
+            }
+        })
+        .collect();
+    Ok((leader, fields))
+}
+
+/// Tag-by-tag comparison of `local` and `remote`, in tag order
+pub fn diff_records(local: &MarcRecord, remote: &MarcRecord, format: MarcDiffFormat) -> AppResult<Vec<MarcFieldDiff>> {
+    let format = format.marc_format();
+    let (_, local_fields) = record_fields(local, &format)?;
+    let (_, remote_fields) = record_fields(remote, &format)?;
+
+    let mut tags: Vec<&str> = local_fields.iter().chain(&remote_fields).map(|f| f.tag.as_str()).collect();
+    tags.sort_unstable();
+    tags.dedup();
+
+    let occurrences = |fields: &[Field], tag: &str| -> Vec<String> {
+        fields.iter().filter(|f| f.tag == tag).map(|f| f.text.clone()).collect()
+    };
+    Ok(tags
+        .into_iter()
+        .map(|tag| {
+            let local = occurrences(&local_fields, tag);
+            let remote = occurrences(&remote_fields, tag);
+            let change = match (local.is_empty(), remote.is_empty()) {
+                (true, _) => MarcFieldChange::Added,
+                (_, true) => MarcFieldChange::Removed,
+                _ if local == remote => MarcFieldChange::Unchanged,
+                _ => MarcFieldChange::Changed,
+            };
+            MarcFieldDiff { tag: tag.to_string(), change, local, remote }
+        })
+        .collect())
+}
+
+/// `local` with every occurrence of the given tags replaced by the remote ones (tags absent from
+/// the remote record are dropped); all other local fields are kept as they are.
+pub fn merge_records(
+    local: &MarcRecord,
+    remote: &MarcRecord,
+    remote_tags: &[String],
+    format: MarcDiffFormat,
+) -> AppResult<MarcRecord> {
+    let format = format.marc_format();
+    let (leader, local_fields) = record_fields(local, &format)?;
+    let (_, remote_fields) = record_fields(remote, &format)?;
+    let from_remote = |f: &Field| remote_tags.iter().any(|t| t == &f.tag);
+
+    let mut fields: Vec<Field> = local_fields
+        .into_iter()
+        .filter(|f| !from_remote(f))
+        .chain(remote_fields.into_iter().filter(|f| from_remote(f)))
+        .collect();
+    fields.sort_by(|a, b| a.tag.cmp(&b.tag));
+
+    let data = assemble_record(&leader, &fields);
+    let view = BinaryReader::new(&data)
+        .next()
+        .ok_or_else(|| AppError::Internal("Merged MARC record is empty".to_string()))?
+        .map_err(|e| AppError::Internal(format!("Failed to read merged MARC record: {}", e)))?;
+    format
+        .to_record(view.as_raw())
+        .map_err(|e| AppError::Internal(format!("Failed to decode merged MARC record: {}", e)))
+}
+
+/// ISO 2709 bytes of a record with the given leader (lengths and base address recomputed)
+fn assemble_record(leader: &[u8], fields: &[Field]) -> Vec<u8> {
+    let base_address = 24 + 12 * fields.len() + 1;
+    let body_len: usize = fields.iter().map(|f| f.data.len() + 1).sum();
+    let total_len = base_address + body_len + 1;
+
+    let mut out = Vec::with_capacity(total_len);
+    out.extend_from_slice(leader);
+    out[0..5].copy_from_slice(format!("{:05}", total_len).as_bytes());
+    out[12..17].copy_from_slice(format!("{:05}", base_address).as_bytes());
+    let mut offset = 0;
+    for field in fields {
+        let len = field.data.len() + 1;
+        out.extend_from_slice(format!("{:3.3}{:04}{:05}", field.tag, len, offset).as_bytes());
+        offset += len;
+    }
+    out.push(FIELD_TERMINATOR);
+    for field in fields {
+        out.extend_from_slice(&field.data);
+        out.push(FIELD_TERMINATOR);
+    }
+    out.push(RECORD_TERMINATOR);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::biblio::Biblio;
+
+    fn record(title: &str, notes: Option<&str>) -> MarcRecord {
+        let mut biblio = Biblio::from(MarcRecord::default());
+        biblio.marc_record = None;
+        biblio.title = Some(title.to_string());
+        biblio.notes = notes.map(str::to_string);
+        MarcRecord::from(&biblio)
+    }
+
+    #[test]
+    fn diff_lists_changed_and_local_only_tags() {
+        let local = record("Astérix le Gaulois", Some("Don de la mairie"));
+        let remote = record("Astérix le Gaulois (nouvelle édition)", None);
+
+        let diff = diff_records(&local, &remote, MarcDiffFormat::Unimarc).unwrap();
+        let title = diff.iter().find(|d| d.remote.iter().any(|v| v.contains("nouvelle"))).unwrap();
+        assert_eq!(title.change, MarcFieldChange::Changed);
+        assert!(title.local[0].contains("$a Astérix le Gaulois"));
+        let note = diff.iter().find(|d| d.local.iter().any(|v| v.contains("Don de la mairie"))).unwrap();
+        assert_eq!(note.change, MarcFieldChange::Removed);
+        assert!(diff.iter().all(|d| d.change != MarcFieldChange::Added));
+    }
+
+    #[test]
+    fn merge_takes_selected_tags_and_keeps_local_enrichments() {
+        let local = record("Astérix le Gaulois", Some("Don de la mairie"));
+        let remote = record("Astérix le Gaulois (nouvelle édition)", None);
+        for format in [MarcDiffFormat::Unimarc, MarcDiffFormat::Marc21] {
+            let diff = diff_records(&local, &remote, format).unwrap();
+            let title = diff.iter().find(|d| d.remote.iter().any(|v| v.contains("nouvelle"))).unwrap();
+            let title_tag = title.tag.clone();
+
+            let merged = Biblio::from(merge_records(&local, &remote, &[title_tag], format).unwrap());
+            assert_eq!(merged.title.as_deref(), Some("Astérix le Gaulois (nouvelle édition)"), "{:?}", format);
+            assert_eq!(merged.notes.as_deref(), Some("Don de la mairie"), "{:?}", format);
+        }
+    }
+}
@@ -3,9 +3,13 @@
 //! This module provides functionality to parse MARC21 and UNIMARC records
 //! and translate them into the internal Item structure.
 
+pub mod diff;
 pub mod translator;
 
-pub use translator::{biblio_items_to_marc_items, marc_record_for_loan_export};
+pub use translator::{
+    biblio_items_to_marc_items, marc_leader_codes, marc_record_for_loan_export, media_type_from_leader,
+    set_marc_leader_codes,
+};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -26,5 +30,7 @@ use crate::models::{Author, BiblioShort, ItemShort, MediaType, biblio::Isbn};
 pub struct MarcImportPreview {
     #[serde(flatten)]
     pub biblio: BiblioShort,
+    /// Catalog pattern failures (`tag`, `subfield`, `targetPath`, `value`, `pattern`)
+    #[schema(value_type = Vec<Object>)]
     pub validation_issues: Vec<RecordValidationIssue>,
 }
@@ -7,10 +7,10 @@ use z3950_rs::marc_rs::record::{
     TargetAudience, Title,
 };
 
-use crate::{marc::MarcImportPreview, models::{
+use crate::{error::{AppError, AppResult}, marc::MarcImportPreview, models::{
     Language, MediaType,
     author::{Author, Function},
-    biblio::{AudienceType, Biblio, Collection, Edition, Isbn, Serie},
+    biblio::{AccessibilityFeature, AudienceType, Biblio, Collection, Edition, Isbn, ReadingLevel, Serie},
     item::Item,
 }};
 
@@ -28,7 +28,7 @@ impl From<z3950_rs::marc_rs::record::Relator> for Function {
             R::Photographer => Function::Photographer,
             R::Publisher => Function::PublishingDirector,
             R::Composer => Function::Composer,
-            R::Other(_) => Function::Author,
+            R::Other(term) => Function::from_relator_term(&term),
         }
     }
 }
@@ -48,29 +48,155 @@ fn extract_volume_number(s: &str) -> Option<i16> {
         })
 }
 
+/// Wording (lowercase, English and French) that marks an accessible format in the physical
+/// description, edition statement, notes or index terms of a record.
+const ACCESSIBILITY_PHRASES: &[(AccessibilityFeature, &[&str])] = &[
+    (
+        AccessibilityFeature::LargePrint,
+        &["large print", "large type", "gros caract", "grands caract"],
+    ),
+    (AccessibilityFeature::Braille, &["braille"]),
+    (
+        AccessibilityFeature::Audiobook,
+        &["audiobook", "audio book", "livre audio", "livres audio", "livre-audio"],
+    ),
+    (AccessibilityFeature::DyslexiaFriendly, &["dyslexi"]),
+];
+
+/// Accessible formats of a record: a spoken-word recording is an audiobook; the other formats
+/// come from the record's wording (MARC21 250/300/5XX/6XX, UNIMARC 205/215/3XX/6XX).
+fn accessibility_from_record(record: &MarcRecord) -> Vec<AccessibilityFeature> {
+    let physical = record.description.physical_description.as_ref();
+    let text = [
+        record.description.edition.as_deref(),
+        physical.and_then(|p| p.extent.as_deref()),
+        physical.and_then(|p| p.other_physical_details.as_deref()),
+        physical.and_then(|p| p.dimensions.as_deref()),
+        physical.and_then(|p| p.accompanying_material.as_deref()),
+    ]
+    .into_iter()
+    .flatten()
+    .chain(record.notes.items.iter().map(|n| n.text.as_str()))
+    .chain(record.indexing.subjects.iter().map(|s| s.value.as_str()))
+    .chain(record.keywords().iter().map(String::as_str))
+    .collect::<Vec<_>>()
+    .join(" ")
+    .to_lowercase();
+
+    ACCESSIBILITY_PHRASES
+        .iter()
+        .filter(|(feature, phrases)| {
+            (*feature == AccessibilityFeature::Audiobook
+                && matches!(record.leader.record_type, RecordType::NonMusicalSound))
+                || phrases.iter().any(|p| text.contains(p))
+        })
+        .map(|(feature, _)| *feature)
+        .collect()
+}
+
 /// Reverse of [`MediaType`] as derived from MARC [`RecordType`] in [`From<&RecordType> for MediaType`].
+///
+/// marc-rs writes leader/06 from the rule value of the record type, so types without a matching
+/// rule in its MARC21/UNIMARC tables (video, computer file, graphic) are given by their leader code.
 fn record_type_from_media_type(mt: &MediaType) -> RecordType {
     match mt {
-        MediaType::PrintedText | MediaType::Comics | MediaType::Unknown | MediaType::All => {
-            RecordType::LanguageMaterial
-        }
-        MediaType::Periodic => RecordType::LanguageMaterial,
-        MediaType::Video | MediaType::VideoTape | MediaType::VideoDvd => {
-            RecordType::ProjectedOrVideo
-        }
+        MediaType::PrintedText
+        | MediaType::Comics
+        | MediaType::Periodic
+        | MediaType::Unknown
+        | MediaType::All
+        | MediaType::Other(_) => RecordType::LanguageMaterial,
+        MediaType::Video | MediaType::VideoTape | MediaType::VideoDvd => RecordType::Other('g'),
         MediaType::Audio
         | MediaType::AudioNonMusic
         | MediaType::AudioNonMusicTape
         | MediaType::AudioNonMusicCd => RecordType::NonMusicalSound,
         MediaType::AudioMusic | MediaType::AudioMusicTape | MediaType::AudioMusicCd => {
-            RecordType::NotatedMusic
+            RecordType::MusicalSound
         }
-        MediaType::Multimedia | MediaType::CdRom => RecordType::ElectronicResource,
-        MediaType::Images => RecordType::GraphicTwoDimensional,
+        MediaType::Multimedia => RecordType::MixedMaterials,
+        MediaType::CdRom => RecordType::Other('m'),
+        MediaType::Images => RecordType::Other('k'),
     }
 }
 
-fn function_to_relator(f: Function) -> Relator {
+/// Media type derived from the record leader (type of record, serials as periodicals) when no
+/// `media_types` entry claims its leader codes.
+pub fn media_type_from_leader(record: &MarcRecord) -> MediaType {
+    match (MediaType::from(&record.leader.record_type), &record.leader.bibliographic_level) {
+        (MediaType::PrintedText, BibliographicLevel::Serial) => MediaType::Periodic,
+        (media_type, _) => media_type,
+    }
+}
+
+/// MARC21 leader/06 and leader/07 codes of a record, as stored in the `media_types` taxonomy.
+pub fn marc_leader_codes(record: &MarcRecord) -> (char, char) {
+    let record_type = match &record.leader.record_type {
+        RecordType::LanguageMaterial | RecordType::PrintedText => 'a',
+        RecordType::ManuscriptText => 't',
+        RecordType::NotatedMusic => 'c',
+        RecordType::ManuscriptMusic => 'd',
+        RecordType::PrintedCartographic => 'e',
+        RecordType::ManuscriptCartographic => 'f',
+        RecordType::ProjectedOrVideo => 'g',
+        RecordType::NonMusicalSound => 'i',
+        RecordType::MusicalSound => 'j',
+        RecordType::GraphicTwoDimensional => 'k',
+        RecordType::ElectronicResource => 'm',
+        RecordType::MultimediaKit | RecordType::MixedMaterials => 'o',
+        RecordType::ObjectThreeDimensional => 'r',
+        // First letter of the parsed marc-rs rule values "projectedMedium", "visual", "computerFile"
+        RecordType::Other('p') => 'g',
+        RecordType::Other('v') => 'k',
+        RecordType::Other('c') => 'm',
+        RecordType::Other(c) => *c,
+    };
+    let bibliographic_level = match &record.leader.bibliographic_level {
+        BibliographicLevel::Monograph => 'm',
+        BibliographicLevel::Serial => 's',
+        BibliographicLevel::MonographicComponent => 'a',
+        BibliographicLevel::SerialComponent => 'b',
+        BibliographicLevel::Collection => 'c',
+        BibliographicLevel::Subunit => 'd',
+        BibliographicLevel::IntegratingResource => 'i',
+        BibliographicLevel::Other(c) => *c,
+    };
+    (record_type, bibliographic_level)
+}
+
+/// Set the leader from MARC21 leader/06 and leader/07 codes (reverse of [`marc_leader_codes`]).
+pub fn set_marc_leader_codes(record: &mut MarcRecord, record_type: char, bibliographic_level: char) {
+    record.leader.record_type = match record_type {
+        'a' => RecordType::LanguageMaterial,
+        'c' => RecordType::NotatedMusic,
+        'i' => RecordType::NonMusicalSound,
+        'j' => RecordType::MusicalSound,
+        'o' | 'p' => RecordType::MixedMaterials,
+        // No marc-rs rule value for the others: written as the code itself
+        c => RecordType::Other(c),
+    };
+    record.leader.bibliographic_level = match bibliographic_level {
+        'm' => BibliographicLevel::Monograph,
+        's' => BibliographicLevel::Serial,
+        'a' => BibliographicLevel::MonographicComponent,
+        'b' => BibliographicLevel::SerialComponent,
+        'c' => BibliographicLevel::Collection,
+        'd' => BibliographicLevel::Subunit,
+        'i' => BibliographicLevel::IntegratingResource,
+        c => BibliographicLevel::Other(c),
+    };
+}
+
+/// Four-digit year found in a free-text publication date ("c2019", "2019-05", "[1998?]").
+fn publication_year(date: &str) -> Option<String> {
+    date.as_bytes()
+        .windows(4)
+        .position(|w| w.iter().all(u8::is_ascii_digit))
+        .map(|i| date[i..i + 4].to_string())
+}
+
+/// Relators of the vocabulary other than the built-in ones are written with their code.
+fn function_to_relator(f: &Function) -> Relator {
     match f {
         Function::Author => Relator::Author,
         Function::Illustrator => Relator::Illustrator,
@@ -80,6 +206,7 @@ fn function_to_relator(f: Function) -> Relator {
         Function::Photographer => Relator::Photographer,
         Function::PublishingDirector => Relator::Publisher,
         Function::Composer => Relator::Composer,
+        Function::Other(code) => Relator::Other(code.clone()),
     }
 }
 
@@ -123,7 +250,7 @@ fn author_to_marc_agent(author: &Author) -> Option<Agent> {
         numeration: None,
         titles_associated: None,
         fuller_form: None,
-        relator: author.function.map(function_to_relator),
+        relator: author.function.as_ref().map(function_to_relator),
     }))
 }
 
@@ -141,7 +268,13 @@ impl From<&RecordType> for MediaType {
             RecordType::MusicalSound => MediaType::AudioMusic,
             RecordType::GraphicTwoDimensional => MediaType::Images,
             RecordType::ElectronicResource => MediaType::Multimedia,
-            RecordType::MixedMaterials => MediaType::Unknown,
+            RecordType::MixedMaterials => MediaType::Multimedia,
+            // Leader codes as built by [`record_type_from_media_type`], and the first letter of
+            // the marc-rs rule values without a matching variant once parsed
+            // ("projectedMedium", "visual", "computerFile").
+            RecordType::Other('g' | 'p') => MediaType::Video,
+            RecordType::Other('k' | 'v') => MediaType::Images,
+            RecordType::Other('m' | 'c') => MediaType::CdRom,
             _ => MediaType::Unknown,
         }
     }
@@ -281,7 +414,7 @@ impl From<MarcRecord> for Biblio {
         let title = record.title_main().map(|s| s.to_string());
 
         // --- Media type ---
-        let media_type = MediaType::from(&record.leader.record_type);
+        let media_type = media_type_from_leader(&record);
 
         // --- Authors: personal entries only ---
         let authors: Vec<Author> = record
@@ -294,6 +427,7 @@ impl From<MarcRecord> for Biblio {
                         key: None,
                         lastname: Some(person.name.clone()),
                         firstname: person.forename.clone(),
+                        display_name: None,
                         bio: None,
                         notes: None,
                         function: person.relator.clone().map(Function::from),
@@ -308,7 +442,10 @@ impl From<MarcRecord> for Biblio {
         let keywords = if kws.is_empty() { None } else { Some(kws.to_vec()) };
 
         // --- Edition info / publication date ---
-        let publication_date = record.publication_date().map(|s| s.to_string());
+        let publication_date = record
+            .publication_date()
+            .or(record.coded.date1.as_deref().filter(|d| d.bytes().all(|b| b.is_ascii_digit())))
+            .map(|s| s.to_string());
 
         let first_pub: Option<&Publication> = {
             let Description { publication, .. } = &record.description;
@@ -340,6 +477,10 @@ impl From<MarcRecord> for Biblio {
 
         // --- Audience type ---
         let audience_type: Option<AudienceType> = record.coded.target_audience.clone().map(AudienceType::from);
+        let reading_level = audience_type.as_ref().and_then(ReadingLevel::from_audience);
+
+        // --- Accessible formats ---
+        let accessibility = accessibility_from_record(&record);
 
         // --- Series / collection from description / links ---
         let mut series_list: Vec<Serie> = Vec::new();
@@ -424,6 +565,7 @@ impl From<MarcRecord> for Biblio {
             title,
             subject,
             audience_type,
+            reading_level,
             lang,
             lang_orig,
             publication_date,
@@ -434,6 +576,7 @@ impl From<MarcRecord> for Biblio {
             abstract_,
             notes,
             keywords,
+            accessibility,
             is_valid: Some(record.valid),
             series_ids: vec![],
             series_volume_numbers: series_list.iter().map(|s| s.volume_number).collect(),
@@ -449,6 +592,7 @@ impl From<MarcRecord> for Biblio {
             edition,
             items,
             marc_record: Some(record),
+            edit_lock: None,
         }
     }
 }
@@ -485,11 +629,19 @@ impl From<&MarcItem> for Item {
             circulation_status: None,
             notes,
             price: None,
+            access_url: None,
+            access_type: None,
             created_at: None,
             updated_at: None,
             archived_at: None,
             source_name: s.library.clone(),
             borrowed: false,
+            deposit_id: None,
+            transit_place: None,
+            inventory_number: None,
+            location_id: None,
+            location_path: None,
+            hidden_from_opac: false,
         }
     }
 }
@@ -660,6 +812,24 @@ impl From<&Biblio> for MarcRecord {
             }
         }
 
+        // Accessible formats not already conveyed by the record: general note (read back on import)
+        let stated = accessibility_from_record(&record);
+        for feature in item.accessibility.iter().filter(|f| !stated.contains(f)) {
+            let text = match feature {
+                AccessibilityFeature::LargePrint => "Large print",
+                AccessibilityFeature::Braille => "Braille",
+                AccessibilityFeature::Audiobook => "Audiobook",
+                AccessibilityFeature::DyslexiaFriendly => "Dyslexia-friendly edition",
+            };
+            record.notes.items.push(Note { note_type: Some(NoteType::General), text: text.to_string() });
+        }
+
+        // Coded data (MARC21 008 / UNIMARC 100): entry date and publication year; the type of
+        // date is left blank as its codes differ between the two formats
+        record.coded.date_entered_on_file =
+            Some(item.created_at.unwrap_or_else(Utc::now).format("%Y%m%d").to_string());
+        record.coded.date1 = item.publication_date.as_deref().and_then(publication_year);
+
         // Languages
         if let Some(ref lang) = item.lang {
             record.coded.languages.push((*lang).into());
@@ -668,8 +838,9 @@ impl From<&Biblio> for MarcRecord {
             record.coded.original_languages.push((*lang_orig).into());
         }
 
-        if let Some(ref aud) = item.audience_type {
-            record.coded.target_audience = Some(audience_type_to_target_audience(aud));
+        // Without an audience, the reading level gives the closest age-banded target audience
+        if let Some(aud) = item.audience_type.clone().or_else(|| item.reading_level.map(ReadingLevel::to_audience)) {
+            record.coded.target_audience = Some(audience_type_to_target_audience(&aud));
         }
 
         record.valid = item.is_valid.unwrap_or(true);
@@ -710,7 +881,7 @@ pub fn biblio_items_to_marc_items(
             level_code: None,
             barcode: s.barcode.clone(),
             call_number: s.call_number.clone(),
-            inventory_number: None,
+            inventory_number: s.inventory_number.clone(),
             creation_date: None,
             modification_date: None,
             loan_date: loan_date.clone(),
@@ -724,6 +895,138 @@ pub fn biblio_items_to_marc_items(
         .collect()
 }
 
+/// Serialized key of each block of [`MarcRecord`], by first digit of its tags
+const MARC_BLOCKS: [(char, &str); 10] = [
+    ('0', "identification"),
+    ('1', "coded"),
+    ('2', "description"),
+    ('3', "notes"),
+    ('4', "links"),
+    ('5', "associatedTitles"),
+    ('6', "indexing"),
+    ('7', "responsibility"),
+    ('8', "international"),
+    ('9', "local"),
+];
+
+/// Blocks of a stored record that regeneration from catalog data never overwrites
+/// (`[marc] preserved_tags`, e.g. `["9XX"]`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarcPreservationPolicy {
+    blocks: Vec<char>,
+}
+
+impl MarcPreservationPolicy {
+    /// Policy from block patterns (`"0XX"` to `"9XX"`); other patterns are logged and ignored.
+    pub fn new(patterns: &[String]) -> Self {
+        let blocks = patterns
+            .iter()
+            .filter_map(|p| {
+                let pattern = p.trim().to_ascii_uppercase();
+                let block = pattern.strip_suffix("XX").filter(|d| d.len() == 1).and_then(|d| d.chars().next());
+                if block.is_none_or(|d| !d.is_ascii_digit()) {
+                    tracing::warn!("Ignoring MARC preserved tag pattern '{}' (expected a block such as '9XX')", p);
+                    return None;
+                }
+                block
+            })
+            .collect();
+        Self { blocks }
+    }
+
+    /// Whether the block holding `tag` (e.g. `"995"`) is preserved
+    pub fn preserves(&self, tag: &str) -> bool {
+        tag.chars().next().is_some_and(|d| self.blocks.contains(&d))
+    }
+}
+
+impl Default for MarcPreservationPolicy {
+    fn default() -> Self {
+        Self::new(&crate::config::MarcConfig::default().preserved_tags)
+    }
+}
+
+/// Stored record of `biblio` updated with its current catalog data.
+///
+/// Each value is compared between the record the translator derives from `stored` itself and the
+/// one it builds from `biblio`: values the edit did not change keep their stored form (with the
+/// data the catalog does not model), changed ones are taken from `biblio`. List entries derived
+/// from the catalog are replaced, other stored entries are kept. Blocks preserved by `policy`,
+/// the leader and anything the translator never produces are left as stored.
+pub fn regenerate_marc_record(
+    biblio: &Biblio,
+    stored: &MarcRecord,
+    policy: &MarcPreservationPolicy,
+) -> AppResult<MarcRecord> {
+    let to_json = |record: &MarcRecord| {
+        serde_json::to_value(record).map_err(|e| AppError::Internal(format!("Failed to serialize MARC record: {}", e)))
+    };
+    let built = |mut biblio: Biblio| {
+        biblio.marc_record = None;
+        MarcRecord::from(&biblio)
+    };
+    let mut merged = to_json(stored)?;
+    let derived = to_json(&built(Biblio::from(stored.clone())))?;
+    let fresh = to_json(&built(biblio.clone()))?;
+
+    for (digit, key) in MARC_BLOCKS {
+        if policy.blocks.contains(&digit) {
+            continue;
+        }
+        let (derived, fresh) = (&derived[key], &fresh[key]);
+        match merged.get_mut(key) {
+            Some(block) => merge_marc_value(block, derived, fresh),
+            None if !fresh.is_null() => merged[key] = fresh.clone(),
+            None => {}
+        }
+    }
+    serde_json::from_value(merged).map_err(|e| AppError::Internal(format!("Failed to rebuild MARC record: {}", e)))
+}
+
+/// Applies to `stored` the change from `derived` to `fresh` (see [`regenerate_marc_record`])
+fn merge_marc_value(stored: &mut serde_json::Value, derived: &serde_json::Value, fresh: &serde_json::Value) {
+    use serde_json::Value;
+
+    if derived == fresh {
+        return;
+    }
+    // A value the translator did not derive from the stored record is absent (`Null`)
+    let no_fields = serde_json::Map::new();
+    match (stored, derived, fresh) {
+        (Value::Object(stored), derived @ (Value::Object(_) | Value::Null), Value::Object(fresh)) => {
+            let derived = derived.as_object().unwrap_or(&no_fields);
+            for (key, value) in fresh {
+                let before = derived.get(key).unwrap_or(&Value::Null);
+                match stored.get_mut(key) {
+                    Some(current) => merge_marc_value(current, before, value),
+                    None => {
+                        stored.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            for key in derived.keys().filter(|k| !fresh.contains_key(*k)) {
+                stored.remove(key);
+            }
+        }
+        (Value::Array(stored), derived @ (Value::Array(_) | Value::Null), Value::Array(fresh)) => {
+            let derived = derived.as_array().map(Vec::as_slice).unwrap_or_default();
+            stored.retain(|entry| !derived.iter().any(|d| marc_value_covers(entry, d)));
+            stored.extend(fresh.iter().cloned());
+        }
+        (stored, _, fresh) => *stored = fresh.clone(),
+    }
+}
+
+/// Whether `stored` holds every value of `derived` (stored entries may carry more subfields)
+fn marc_value_covers(stored: &serde_json::Value, derived: &serde_json::Value) -> bool {
+    match (stored, derived) {
+        (serde_json::Value::Object(stored), serde_json::Value::Object(derived)) => derived
+            .iter()
+            .all(|(key, value)| stored.get(key).is_some_and(|s| marc_value_covers(s, value))),
+        _ => stored == derived,
+    }
+}
+
 /// Builds a [`MarcRecord`] for loan export: uses stored `biblio.marc_record` when present
 /// (bibliographic notice without local items), otherwise [`MarcRecord::from`] the relational
 /// biblio. Always sets `local.items` to the borrowed copy(ies) in `biblio.items`, with loan dates.
@@ -759,4 +1062,237 @@ mod tests {
         assert_eq!(extract_volume_number("abc"), None);
         assert_eq!(extract_volume_number(""), None);
     }
+
+    #[test]
+    fn accessibility_detected_from_record_wording() {
+        let mut record = MarcRecord::default();
+        assert!(accessibility_from_record(&record).is_empty());
+
+        record.description.physical_description = Some(z3950_rs::marc_rs::record::PhysicalDescription {
+            extent: Some("312 p.".to_string()),
+            other_physical_details: None,
+            dimensions: Some("24 cm (Gros caractères)".to_string()),
+            accompanying_material: None,
+        });
+        record.notes.items.push(Note {
+            note_type: Some(NoteType::General),
+            text: "Police adaptée aux lecteurs dyslexiques".to_string(),
+        });
+        assert_eq!(
+            accessibility_from_record(&record),
+            vec![AccessibilityFeature::LargePrint, AccessibilityFeature::DyslexiaFriendly]
+        );
+
+        let mut record = MarcRecord::default();
+        record.leader.record_type = RecordType::NonMusicalSound;
+        record.indexing.uncontrolled_terms.push("Braille".to_string());
+        assert_eq!(
+            accessibility_from_record(&record),
+            vec![AccessibilityFeature::Braille, AccessibilityFeature::Audiobook]
+        );
+
+        let mut record = MarcRecord::default();
+        record.leader.record_type = RecordType::MusicalSound;
+        assert!(accessibility_from_record(&record).is_empty());
+    }
+
+    #[test]
+    fn accessibility_survives_marc_round_trip() {
+        let mut biblio = Biblio::from(MarcRecord::default());
+        biblio.marc_record = None;
+        biblio.accessibility = vec![AccessibilityFeature::LargePrint, AccessibilityFeature::DyslexiaFriendly];
+        let record = MarcRecord::from(&biblio);
+        assert_eq!(Biblio::from(record).accessibility, biblio.accessibility);
+    }
+
+    fn biblio_of(media_type: MediaType) -> Biblio {
+        let mut biblio = Biblio::from(MarcRecord::default());
+        biblio.marc_record = None;
+        biblio.media_type = media_type;
+        biblio.title = Some("Round trip".to_string());
+        biblio.publication_date = Some("c2019".to_string());
+        biblio.lang = Some(Language::French);
+        biblio
+    }
+
+    /// Media type recovered from the leader, one per record type family.
+    const MEDIA_TYPE_FAMILIES: [(MediaType, MediaType); 12] = [
+        (MediaType::PrintedText, MediaType::PrintedText),
+        (MediaType::Comics, MediaType::PrintedText),
+        (MediaType::Periodic, MediaType::Periodic),
+        (MediaType::Video, MediaType::Video),
+        (MediaType::VideoDvd, MediaType::Video),
+        (MediaType::AudioNonMusicCd, MediaType::Audio),
+        (MediaType::AudioMusic, MediaType::AudioMusic),
+        (MediaType::AudioMusicCd, MediaType::AudioMusic),
+        (MediaType::AudioMusicTape, MediaType::AudioMusic),
+        (MediaType::Multimedia, MediaType::Multimedia),
+        (MediaType::CdRom, MediaType::CdRom),
+        (MediaType::Images, MediaType::Images),
+    ];
+
+    #[test]
+    fn media_type_survives_marc_round_trip() {
+        for (media_type, expected) in MEDIA_TYPE_FAMILIES {
+            let record = MarcRecord::from(&biblio_of(media_type.clone()));
+            assert_eq!(Biblio::from(record).media_type, expected, "{:?}", media_type);
+        }
+    }
+
+    #[test]
+    fn media_type_survives_marc21_binary_round_trip() {
+        use z3950_rs::marc_rs::{parse_records, BinaryWriter, Encoding, MarcFormat};
+
+        for (media_type, expected) in MEDIA_TYPE_FAMILIES {
+            let mut record = MarcRecord::from(&biblio_of(media_type.clone()));
+            let mut data = Vec::new();
+            BinaryWriter::new(&mut data)
+                .write_record(&MarcFormat::Marc21(Encoding::Utf8), &mut record)
+                .unwrap();
+            let leader_type = &data[6..8];
+            let expected_leader: &[u8] = match media_type {
+                MediaType::Periodic => b"as",
+                MediaType::Video | MediaType::VideoDvd => b"gm",
+                MediaType::AudioNonMusicCd => b"im",
+                MediaType::AudioMusic | MediaType::AudioMusicCd | MediaType::AudioMusicTape => b"jm",
+                MediaType::Multimedia => b"om",
+                MediaType::CdRom => b"mm",
+                MediaType::Images => b"km",
+                _ => b"am",
+            };
+            assert_eq!(leader_type, expected_leader, "{:?}", media_type);
+
+            let parsed = parse_records(&data).unwrap().remove(0);
+            assert_eq!(parsed.coded.date1.as_deref(), Some("2019"));
+            let biblio = Biblio::from(parsed);
+            assert_eq!(biblio.media_type, expected, "{:?}", media_type);
+            assert_eq!(biblio.lang, Some(Language::French));
+        }
+    }
+
+    #[test]
+    fn taxonomy_leader_codes_round_trip() {
+        for (record_type, level) in [('a', 'm'), ('a', 's'), ('g', 'm'), ('j', 'm'), ('o', 'c'), ('r', 'm'), ('e', 'm')] {
+            let mut record = MarcRecord::default();
+            set_marc_leader_codes(&mut record, record_type, level);
+            assert_eq!(marc_leader_codes(&record), (record_type, level));
+        }
+        let mut record = MarcRecord::default();
+        set_marc_leader_codes(&mut record, 'a', 's');
+        assert_eq!(media_type_from_leader(&record), MediaType::Periodic);
+    }
+
+    #[test]
+    fn control_data_carries_publication_year() {
+        let record = MarcRecord::from(&biblio_of(MediaType::PrintedText));
+        assert_eq!(record.coded.date1.as_deref(), Some("2019"));
+        assert_eq!(record.coded.date_entered_on_file.as_deref().map(str::len), Some(8));
+
+        let mut biblio = biblio_of(MediaType::PrintedText);
+        biblio.publication_date = Some("s.d.".to_string());
+        assert_eq!(MarcRecord::from(&biblio).coded.date1, None);
+
+        let mut record = MarcRecord::default();
+        record.coded.date1 = Some("1998".to_string());
+        assert_eq!(Biblio::from(record).publication_date.as_deref(), Some("1998"));
+    }
+
+    #[test]
+    fn reading_level_derived_from_target_audience() {
+        let mut record = MarcRecord::default();
+        record.coded.target_audience = Some(TargetAudience::Primary);
+        assert_eq!(Biblio::from(record).reading_level, Some(ReadingLevel::Ages7To9));
+
+        let mut record = MarcRecord::default();
+        record.coded.target_audience = Some(TargetAudience::Adult);
+        assert_eq!(Biblio::from(record).reading_level, None);
+
+        let mut biblio = Biblio::from(MarcRecord::default());
+        biblio.marc_record = None;
+        biblio.audience_type = None;
+        biblio.reading_level = Some(ReadingLevel::Teen);
+        let record = MarcRecord::from(&biblio);
+        assert_eq!(Biblio::from(record).reading_level, Some(ReadingLevel::Teen));
+    }
+
+    /// Stored record with cataloger additions the catalog does not model: a subtitle, a local
+    /// 995 holding and bibliography / awards notes.
+    fn enriched_record() -> MarcRecord {
+        let mut record = MarcRecord::from(&biblio_of(MediaType::PrintedText));
+        if let Some(title) = record.description.title.as_mut() {
+            title.subtitle = Some("roman".to_string());
+        }
+        record.local.items.push(MarcItem {
+            library: Some("MAIN".to_string()),
+            barcode: Some("LOC-0042".to_string()),
+            call_number: Some("Réserve patrimoniale".to_string()),
+            ..Default::default()
+        });
+        record.notes.items.push(Note {
+            note_type: Some(NoteType::Bibliography),
+            text: "Bibliogr. p. 301-312".to_string(),
+        });
+        record.notes.items.push(Note {
+            note_type: Some(NoteType::Awards),
+            text: "Prix des lecteurs 2020".to_string(),
+        });
+        record
+    }
+
+    fn note_texts(record: &MarcRecord) -> Vec<&str> {
+        record.notes.items.iter().map(|n| n.text.as_str()).collect()
+    }
+
+    #[test]
+    fn local_and_untouched_fields_survive_biblio_edits() {
+        let stored = enriched_record();
+        let mut biblio = Biblio::from(stored.clone());
+        biblio.title = Some("Round trip (2e éd.)".to_string());
+        biblio.notes = Some("Exemplaire dédicacé".to_string());
+        biblio.items.clear();
+
+        let record = regenerate_marc_record(&biblio, &stored, &MarcPreservationPolicy::default()).unwrap();
+        assert_eq!(record.title_main(), Some("Round trip (2e éd.)"));
+        assert_eq!(record.description.title.as_ref().unwrap().subtitle.as_deref(), Some("roman"));
+        assert_eq!(record.local.items.len(), 1);
+        assert_eq!(record.local.items[0].barcode.as_deref(), Some("LOC-0042"));
+        assert_eq!(record.local.items[0].call_number.as_deref(), Some("Réserve patrimoniale"));
+        let notes = note_texts(&record);
+        assert!(notes.contains(&"Exemplaire dédicacé"), "{:?}", notes);
+        assert!(notes.contains(&"Bibliogr. p. 301-312"), "{:?}", notes);
+        assert!(notes.contains(&"Prix des lecteurs 2020"), "{:?}", notes);
+        assert_eq!(record.coded.date1.as_deref(), Some("2019"));
+
+        let again = regenerate_marc_record(&Biblio::from(record.clone()), &record, &MarcPreservationPolicy::default())
+            .unwrap();
+        assert_eq!(again.local.items.len(), 1);
+        assert_eq!(note_texts(&again), notes);
+    }
+
+    #[test]
+    fn preserved_tags_win_over_catalog_data() {
+        let stored = enriched_record();
+        let mut biblio = Biblio::from(stored.clone());
+        biblio.items.clear();
+
+        let kept = regenerate_marc_record(&biblio, &stored, &MarcPreservationPolicy::default()).unwrap();
+        assert_eq!(kept.local.items.len(), 1);
+
+        let rebuilt = regenerate_marc_record(&biblio, &stored, &MarcPreservationPolicy::new(&[])).unwrap();
+        assert!(rebuilt.local.items.is_empty());
+        assert_eq!(rebuilt.description.title.as_ref().unwrap().subtitle.as_deref(), Some("roman"));
+        assert!(note_texts(&rebuilt).contains(&"Prix des lecteurs 2020"));
+    }
+
+    #[test]
+    fn preservation_patterns_match_tags() {
+        let patterns = ["9xx", " 3XX", "85X", "9X", "AXX"].map(String::from);
+        let policy = MarcPreservationPolicy::new(&patterns);
+        assert!(policy.preserves("995"));
+        assert!(policy.preserves("930"));
+        assert!(policy.preserves("320"));
+        assert!(!policy.preserves("856"));
+        assert!(!policy.preserves("200"));
+        assert!(!MarcPreservationPolicy::new(&[]).preserves("995"));
+    }
 }
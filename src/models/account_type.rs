@@ -17,10 +17,18 @@ pub struct AccountTypeDefinition {
     pub holds_rights: Option<String>,
     pub settings_rights: Option<String>,
     pub events_rights: Option<String>,
+    /// `w` = `force=true` overrides checkout blocks; `n` = it does not.
+    pub circulation_override_rights: Option<String>,
+    /// `w` = may place `staff` / `teaching` priority holds; `n` = normal holds only.
+    pub priority_holds_rights: Option<String>,
+    /// Maximum copies in one group loan batch; `null` = group loans not allowed.
+    pub group_loans_max_items: Option<i16>,
+    /// Maximum days between today and the shared due date of a group loan batch (default 42).
+    pub group_loans_max_days: Option<i16>,
 }
 
 /// Partial update for `account_types` (admin only). Omit a field to leave it unchanged.
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateAccountTypeDefinition {
     pub name: Option<String>,
@@ -31,4 +39,12 @@ pub struct UpdateAccountTypeDefinition {
     pub holds_rights: Option<String>,
     pub settings_rights: Option<String>,
     pub events_rights: Option<String>,
+    /// `w` allows the override (`n` / `r` do not)
+    pub circulation_override_rights: Option<String>,
+    /// `w` allows priority holds (`n` / `r` do not)
+    pub priority_holds_rights: Option<String>,
+    /// Positive number; `0` clears the limit (disables group loans for `groupLoansMaxItems`).
+    pub group_loans_max_items: Option<i16>,
+    /// Positive number; `0` resets to the default.
+    pub group_loans_max_days: Option<i16>,
 }
@@ -0,0 +1,110 @@
+//! API keys for partner applications calling public endpoints
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::error::AppError;
+
+/// HTTP header carrying the API key
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Prefix of every API key (tells them apart from JWTs and kiosk tokens)
+pub const API_KEY_PREFIX: &str = "apikey_";
+
+/// Bulk availability by ISBN (`GET /opac/availability`)
+pub const SCOPE_OPAC_AVAILABILITY: &str = "opac.availability";
+
+/// Every scope a key can be granted
+pub const API_KEY_SCOPES: &[&str] = &[SCOPE_OPAC_AVAILABILITY];
+
+/// Registered API key (the key itself is never returned after creation)
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKey {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub name: String,
+    /// First characters of the key, to recognise it in the partner's configuration
+    pub token_prefix: String,
+    #[schema(example = json!(["opac.availability"]))]
+    pub scopes: Vec<String>,
+    pub is_active: bool,
+    /// No expiry when absent
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    /// Reject a call to an endpoint outside the key's scopes
+    pub fn require_scope(&self, scope: &str) -> Result<(), AppError> {
+        if self.scopes.iter().any(|s| s == scope) {
+            Ok(())
+        } else {
+            Err(AppError::Authorization(format!("API key '{}' lacks the {} scope", self.name, scope)))
+        }
+    }
+}
+
+/// API key with its secret, returned once on creation and on regeneration
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyWithToken {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    /// Value to send in the `X-Api-Key` header; cannot be retrieved later
+    pub token: String,
+}
+
+/// Register an API key
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKey {
+    pub name: String,
+    /// At least one of the known scopes (`opac.availability`)
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Update an API key (absent fields are kept)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateApiKey {
+    pub name: Option<String>,
+    pub scopes: Option<Vec<String>>,
+    /// `false` revokes the key without deleting it
+    pub is_active: Option<bool>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Remove the expiry date (ignored when `expiresAt` is set)
+    #[serde(default)]
+    pub clear_expires_at: bool,
+}
+
+/// Requests made with a key on one day, per endpoint scope
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyUsage {
+    pub day: NaiveDate,
+    #[schema(example = "opac.availability")]
+    pub endpoint: String,
+    pub request_count: i64,
+    /// Records asked for (ISBNs for availability)
+    pub record_count: i64,
+}
+
+/// `GET /settings/api-keys/{id}/usage` query (default: the last 30 days)
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyUsageQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
@@ -0,0 +1,154 @@
+//! Generated files (exports, import reports) kept in the blob store for a limited time
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// What produced an artifact
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ArtifactKind {
+    CatalogExport,
+    AuditExport,
+    LoansExport,
+    ImportReport,
+    ProcessingSlip,
+    /// Database backup (`POST /admin/backup`), kept `backup.retention_days`
+    Backup,
+    /// Hold pull list prepared by the end-of-day job for the next morning
+    PullList,
+}
+
+impl ArtifactKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CatalogExport => "catalog_export",
+            Self::AuditExport => "audit_export",
+            Self::LoansExport => "loans_export",
+            Self::ImportReport => "import_report",
+            Self::ProcessingSlip => "processing_slip",
+            Self::Backup => "backup",
+            Self::PullList => "pull_list",
+        }
+    }
+}
+
+impl From<String> for ArtifactKind {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "audit_export" => Self::AuditExport,
+            "loans_export" => Self::LoansExport,
+            "import_report" => Self::ImportReport,
+            "processing_slip" => Self::ProcessingSlip,
+            "backup" => Self::Backup,
+            "pull_list" => Self::PullList,
+            _ => Self::CatalogExport,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for ArtifactKind {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for ArtifactKind {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for ArtifactKind {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Stored generated file
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub kind: ArtifactKind,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// Key of the content in the blob store
+    #[serde(skip)]
+    pub storage_key: String,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    /// The file is deleted after this date
+    pub expires_at: DateTime<Utc>,
+    pub download_count: i32,
+    pub last_downloaded_at: Option<DateTime<Utc>>,
+}
+
+/// Row written once the content is in the blob store
+#[derive(Debug, Clone)]
+pub struct NewArtifact {
+    pub kind: ArtifactKind,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub storage_key: String,
+    pub created_by: Option<i64>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Artifact with a signed download URL (no bearer token needed to follow it)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactLink {
+    #[serde(flatten)]
+    pub artifact: Artifact,
+    /// `GET /artifacts/:id?expires=…&signature=…`
+    pub download_url: String,
+    /// The URL stops working after this date (a new one can be fetched from `GET /artifacts`)
+    pub url_expires_at: DateTime<Utc>,
+}
+
+/// Signature query params of `GET /artifacts/:id`
+#[derive(Debug, Serialize, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct ArtifactDownloadQuery {
+    /// Unix timestamp after which the URL is refused
+    pub expires: i64,
+    /// Hex HMAC-SHA256 of `<id>:<expires>`
+    pub signature: String,
+}
+
+/// How an export endpoint returns its file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportDelivery {
+    /// File in the response body (default)
+    #[default]
+    Inline,
+    /// `201` with an [`ArtifactLink`]; download later through the signed URL
+    Link,
+}
+
+/// `delivery` query param shared by export endpoints
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportDeliveryQuery {
+    /// `inline` (default): file in the response body; `link`: `201` with a signed download URL
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    pub delivery: ExportDelivery,
+}
@@ -47,7 +47,7 @@ pub struct AuditQueryParams {
 }
 
 /// Paginated audit log response.
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AuditLogPage {
     pub entries: Vec<AuditLogEntry>,
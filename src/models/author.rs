@@ -3,10 +3,14 @@
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use sqlx::FromRow;
-use utoipa::ToSchema;
+use unicode_normalization::UnicodeNormalization;
+use utoipa::{IntoParams, ToSchema};
 
-/// Author function in item relationship
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+/// Author function in item relationship (`biblio_authors.function`).
+///
+/// The built-in functions are seeded in the `relators` vocabulary, which holds their MARC codes,
+/// label and aliases; relators added there by an administrator are [`Function::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum Function {
     Author,
@@ -17,10 +21,13 @@ pub enum Function {
     Photographer,
     PublishingDirector,
     Composer,
+    /// Code defined in the `relators` vocabulary (or a term still to be resolved against it)
+    #[serde(untagged)]
+    Other(String),
 }
 
 impl Function {
-    pub fn as_db_str(&self) -> &'static str {
+    pub fn as_db_str(&self) -> &str {
         match self {
             Function::Author => "author",
             Function::Illustrator => "illustrator",
@@ -30,8 +37,24 @@ impl Function {
             Function::Photographer => "photographer",
             Function::PublishingDirector => "publishingDirector",
             Function::Composer => "composer",
+            Function::Other(code) => code,
         }
     }
+
+    /// Function named by a free-text relator term of a MARC record ("Illustrateur.", "ill",
+    /// "director"): trimmed of the punctuation ending `$e` terms and lowercased. Terms that are
+    /// not a built-in code are kept as [`Function::Other`] and resolved against the `relators`
+    /// vocabulary when stored.
+    pub fn from_relator_term(term: &str) -> Function {
+        let term = term
+            .trim()
+            .trim_matches(|c: char| c.is_whitespace() || matches!(c, '.' | ',' | ';' | ':' | '(' | ')' | '[' | ']'))
+            .to_lowercase();
+        if term.is_empty() {
+            return Function::Author;
+        }
+        Function::from(term.as_str())
+    }
 }
 
 impl From<&str> for Function {
@@ -45,11 +68,29 @@ impl From<&str> for Function {
             "photographer" | "pht" => Function::Photographer,
             "publishingDirector" | "pbd" | "publisher" => Function::PublishingDirector,
             "composer" | "cmp" => Function::Composer,
-            _ => Function::Author,
+            other => Function::Other(other.to_string()),
         }
     }
 }
 
+/// Documented as a plain string: the built-in codes are only examples of the vocabulary's codes.
+impl<'s> ToSchema<'s> for Function {
+    fn schema() -> (&'s str, utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>) {
+        (
+            "Function",
+            utoipa::openapi::ObjectBuilder::new()
+                .schema_type(utoipa::openapi::SchemaType::String)
+                .description(Some(
+                    "Code of the relators vocabulary (GET /settings/relators); built-in codes: \
+                     author, illustrator, translator, scientificAdvisor, prefaceWriter, photographer, \
+                     publishingDirector, composer. MARC relator codes and aliases are accepted as input.",
+                ))
+                .example(Some(serde_json::json!("illustrator")))
+                .into(),
+        )
+    }
+}
+
 impl std::fmt::Display for Function {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_db_str())
@@ -99,6 +140,9 @@ pub struct Author {
     pub key: Option<String>,
     pub lastname: Option<String>,
     pub firstname: Option<String>,
+    /// Preferred display form ("J. R. R. Tolkien"); lists fall back to lastname / firstname
+    #[serde(default, rename = "displayName")]
+    pub display_name: Option<String>,
     pub bio: Option<String>,
     pub notes: Option<String>,
     pub function: Option<Function>,
@@ -121,3 +165,256 @@ pub struct UpdateAuthor {
     pub bio: Option<String>,
     pub notes: Option<String>,
 }
+
+/// Set or clear the preferred display form of an author
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAuthorDisplayName {
+    /// Empty or null: back to the lastname / firstname composition
+    pub display_name: Option<String>,
+}
+
+/// Options of the batch normalization
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorNormalizationQuery {
+    /// Changes listed in the report (default 200, max 1000); counters cover all authors
+    pub limit: Option<usize>,
+}
+
+/// One author whose name the normalization rewrites
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorNormalizationChange {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub lastname: Option<String>,
+    pub firstname: Option<String>,
+    pub normalized_lastname: Option<String>,
+    pub normalized_firstname: Option<String>,
+    /// Author with the same normalized name this one is merged into (lowest id kept)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub merged_into: Option<i64>,
+}
+
+/// Outcome (or preview) of the batch normalization of the authors table
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorNormalizationReport {
+    /// Nothing was written (preview)
+    pub dry_run: bool,
+    /// Authors examined
+    pub scanned: i64,
+    /// Authors renamed in place
+    pub renamed: i64,
+    /// Authors merged into a duplicate and deleted
+    pub merged: i64,
+    /// First changes (see `limit`)
+    pub changes: Vec<AuthorNormalizationChange>,
+    /// Records whose authors changed (reindexed for search)
+    #[serde(skip)]
+    pub biblio_ids: Vec<i64>,
+}
+
+/// Name particles kept lowercase inside a name written in capitals ("VAN DER BERG" → "Van der Berg")
+const NAME_PARTICLES: &[&str] = &["de", "del", "della", "den", "der", "des", "di", "do", "dos", "du", "van", "von"];
+
+/// Ingest form of an author name.
+///
+/// Maps typographic punctuation to ASCII, applies NFC, collapses whitespace, strips stray
+/// surrounding punctuation (ISBD "Tolkien ;", trailing period), splits an inverted
+/// "Tolkien, J.R.R." or "TOLKIEN J.R.R." lastname when no firstname is given, spaces initials
+/// ("J. R. R.") and title-cases name parts written entirely in capitals or in lowercase.
+/// Mixed-case parts ("McCarthy", "d'Ormesson") are kept as typed.
+pub fn normalize_author_name(
+    lastname: Option<&str>,
+    firstname: Option<&str>,
+) -> (Option<String>, Option<String>) {
+    let mut lastname = lastname.and_then(clean_name_part);
+    let mut firstname = firstname.and_then(clean_name_part);
+    if firstname.is_none() {
+        if let Some((last, first)) = lastname.as_deref().and_then(split_inverted_name) {
+            lastname = Some(last);
+            firstname = Some(first);
+        }
+    }
+    (
+        lastname.map(|l| case_name_part(&l)),
+        firstname.map(|f| case_name_part(&space_initials(&f))),
+    )
+}
+
+fn clean_name_part(value: &str) -> Option<String> {
+    let mapped: String = value
+        .chars()
+        .map(|c| match c {
+            '\u{2019}' | '\u{2018}' | '\u{02BC}' => '\'',
+            '\u{2013}' | '\u{2014}' => '-',
+            '\u{00A0}' => ' ',
+            _ => c,
+        })
+        .collect();
+    let nfc: String = mapped.nfc().collect();
+    let mut name = nfc.split_whitespace().collect::<Vec<_>>().join(" ");
+    loop {
+        let trimmed = name.trim_matches(|c: char| c.is_whitespace() || ",;:/[]()\"".contains(c));
+        let trimmed = match trimmed.strip_suffix('.') {
+            Some(rest) if !ends_with_initial(rest) => rest,
+            _ => trimmed,
+        };
+        if trimmed.len() == name.len() {
+            break;
+        }
+        name = trimmed.to_string();
+    }
+    (!name.is_empty()).then_some(name)
+}
+
+/// "J.R" ends with an initial, "Tolkien" does not
+fn ends_with_initial(value: &str) -> bool {
+    let last = value.rsplit([' ', '.', '-']).next().unwrap_or_default();
+    last.chars().count() == 1 && last.chars().all(char::is_alphabetic)
+}
+
+/// "J.", "J.R.R." or "J.-P."
+fn is_initials(token: &str) -> bool {
+    token.contains('.')
+        && token
+            .split(['.', '-'])
+            .filter(|s| !s.is_empty())
+            .all(|s| s.chars().count() == 1 && s.chars().all(char::is_alphabetic))
+}
+
+/// "Tolkien, J.R.R." → ("Tolkien", "J.R.R."); "TOLKIEN J.R.R." → ("TOLKIEN", "J.R.R.")
+fn split_inverted_name(name: &str) -> Option<(String, String)> {
+    if let Some((last, first)) = name.split_once(',') {
+        return Some((clean_name_part(last)?, clean_name_part(first)?));
+    }
+    let tokens: Vec<&str> = name.split(' ').collect();
+    let initials = tokens.iter().rev().take_while(|t| is_initials(t)).count();
+    if initials == 0 || initials == tokens.len() {
+        return None;
+    }
+    let split = tokens.len() - initials;
+    Some((tokens[..split].join(" "), tokens[split..].join(" ")))
+}
+
+/// "J.R.R." → "J. R. R.", "j.-p." → "J.-P.", "John R" → "John R."
+fn space_initials(firstname: &str) -> String {
+    firstname
+        .split(' ')
+        .map(|token| {
+            let single_letter = token.chars().count() == 1 && token.chars().all(char::is_alphabetic);
+            if !single_letter && !is_initials(token) {
+                return token.to_string();
+            }
+            token
+                .split('-')
+                .map(|group| {
+                    group
+                        .chars()
+                        .filter(|c| c.is_alphabetic())
+                        .map(|c| format!("{}.", c.to_uppercase()))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Title case for a part typed in capitals or in lowercase, at space / hyphen / apostrophe
+/// boundaries; particles after the first word stay lowercase.
+fn case_name_part(part: &str) -> String {
+    let has_lower = part.chars().any(char::is_lowercase);
+    let has_upper = part.chars().any(char::is_uppercase);
+    if has_lower && has_upper {
+        return part.to_string();
+    }
+    part.split(' ')
+        .enumerate()
+        .map(|(i, word)| {
+            let lower = word.to_lowercase();
+            if i > 0 && NAME_PARTICLES.contains(&lower.as_str()) {
+                return lower;
+            }
+            let mut out = String::with_capacity(lower.len());
+            let mut after_boundary = true;
+            for c in lower.chars() {
+                if after_boundary && c.is_alphabetic() {
+                    out.extend(c.to_uppercase());
+                    after_boundary = false;
+                } else {
+                    out.push(c);
+                    after_boundary = matches!(c, '-' | '\'' | '.');
+                }
+            }
+            out
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_author_name, Function};
+
+    fn normalize(lastname: &str, firstname: Option<&str>) -> (Option<String>, Option<String>) {
+        normalize_author_name(Some(lastname), firstname)
+    }
+
+    fn name(lastname: &str, firstname: Option<&str>) -> (Option<String>, Option<String>) {
+        (Some(lastname.to_string()), firstname.map(str::to_string))
+    }
+
+    #[test]
+    fn capitals_and_initials() {
+        assert_eq!(normalize("TOLKIEN", Some("J.R.R.")), name("Tolkien", Some("J. R. R.")));
+        assert_eq!(normalize("TOLKIEN J.R.R.", None), name("Tolkien", Some("J. R. R.")));
+        assert_eq!(normalize("sartre", Some("jean-paul")), name("Sartre", Some("Jean-Paul")));
+        assert_eq!(normalize("SARTRE", Some("j.-p.")), name("Sartre", Some("J.-P.")));
+        assert_eq!(normalize("Le Guin", Some("Ursula K")), name("Le Guin", Some("Ursula K.")));
+    }
+
+    #[test]
+    fn inverted_form_is_split() {
+        assert_eq!(
+            normalize("Tolkien, John Ronald Reuel", None),
+            name("Tolkien", Some("John Ronald Reuel"))
+        );
+        // An explicit firstname wins
+        assert_eq!(normalize("Dupont, Martin", Some("Anne")), name("Dupont, Martin", Some("Anne")));
+    }
+
+    #[test]
+    fn punctuation_and_spacing() {
+        assert_eq!(normalize("  Le  Guin ;", Some("Ursula K. ")), name("Le Guin", Some("Ursula K.")));
+        assert_eq!(normalize("Verne.", Some("Jules,")), name("Verne", Some("Jules")));
+        assert_eq!(normalize("O\u{2019}BRIEN", None), name("O'Brien", None));
+        assert_eq!(normalize("Ce\u{301}line", None), name("C\u{e9}line", None));
+        assert_eq!(normalize_author_name(Some(" ; "), Some("")), (None, None));
+    }
+
+    #[test]
+    fn particles_and_mixed_case() {
+        assert_eq!(normalize("VAN DER BERG", Some("PIETER")), name("Van der Berg", Some("Pieter")));
+        assert_eq!(normalize("MAUPASSANT", Some("GUY DE")), name("Maupassant", Some("Guy de")));
+        assert_eq!(normalize("McCarthy", Some("Cormac")), name("McCarthy", Some("Cormac")));
+        assert_eq!(normalize("d'Ormesson", Some("Jean")), name("d'Ormesson", Some("Jean")));
+    }
+
+    #[test]
+    fn relator_terms() {
+        assert_eq!(Function::from_relator_term("Illustrator."), Function::Illustrator);
+        assert_eq!(Function::from_relator_term(" trl "), Function::Translator);
+        assert_eq!(Function::from_relator_term("Réalisateur,"), Function::Other("réalisateur".to_string()));
+        assert_eq!(Function::from_relator_term("drt"), Function::Other("drt".to_string()));
+        assert_eq!(Function::from_relator_term(" . "), Function::Author);
+        assert_eq!(Function::from("director").as_db_str(), "director");
+    }
+}
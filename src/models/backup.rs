@@ -0,0 +1,210 @@
+//! Logical database backups (`POST /admin/backup`) and the manifest heading each backup file
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Format tag of the first line of a backup file
+pub const BACKUP_FORMAT: &str = "elidune-backup/1";
+
+/// Lifecycle of a backup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupStatus {
+    Running,
+    /// File written and stored
+    Succeeded,
+    Failed,
+}
+
+impl BackupStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl From<String> for BackupStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "succeeded" => Self::Succeeded,
+            "failed" => Self::Failed,
+            _ => Self::Running,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for BackupStatus {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for BackupStatus {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for BackupStatus {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Where backup files go (`backup.destination`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupDestination {
+    /// Artifacts store, downloadable through `GET /artifacts/:id/link`
+    Artifacts,
+    /// S3 bucket (or S3-compatible store) of `backup.s3_url`
+    S3,
+}
+
+impl BackupDestination {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Artifacts => "artifacts",
+            Self::S3 => "s3",
+        }
+    }
+}
+
+impl From<String> for BackupDestination {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "s3" => Self::S3,
+            _ => Self::Artifacts,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for BackupDestination {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for BackupDestination {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for BackupDestination {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Redis state at backup time. Redis only holds transient data (sessions, 2FA challenges, edit
+/// locks, caches, task progress), so it is described rather than backed up.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RedisNotes {
+    /// Number of keys per prefix (text before the first `:`)
+    pub keys: BTreeMap<String, u64>,
+    /// What a restore means for this state (or why it could not be read)
+    pub note: String,
+}
+
+/// Backup history entry
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Backup {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub status: BackupStatus,
+    pub destination: BackupDestination,
+    pub filename: String,
+    /// Artifact holding the file (destination `artifacts`); null once it expired
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub artifact_id: Option<i64>,
+    /// `s3://bucket/key` of the file (destination `s3`)
+    pub location: Option<String>,
+    /// Size of the compressed file
+    pub size_bytes: Option<i64>,
+    pub sha256: Option<String>,
+    /// Last migration applied when the backup was taken
+    pub schema_version: Option<i64>,
+    pub table_count: Option<i32>,
+    pub row_count: Option<i64>,
+    #[sqlx(json)]
+    pub redis_notes: Option<RedisNotes>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub created_by: Option<i64>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Why the backup failed
+    pub error: Option<String>,
+}
+
+/// Query parameters of the backup history
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BackupsQuery {
+    /// Most recent backups returned (default 20, max 100)
+    pub limit: Option<i64>,
+}
+
+/// Migration applied to the database a backup was taken from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupMigration {
+    pub version: i64,
+    pub description: String,
+    /// Hex SHA-384 of the migration script, as recorded by sqlx
+    pub checksum: String,
+}
+
+/// Table of a backup file, in load order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupTable {
+    pub name: String,
+    /// Columns of the COPY data (generated columns are left out and recomputed on load)
+    pub columns: Vec<String>,
+}
+
+/// First line of a backup file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    /// [`BACKUP_FORMAT`]
+    pub format: String,
+    pub server_version: String,
+    pub created_at: DateTime<Utc>,
+    /// Last applied migration
+    pub schema_version: i64,
+    pub migrations: Vec<BackupMigration>,
+    pub tables: Vec<BackupTable>,
+    /// Last value of each sequence (null when never used)
+    pub sequences: BTreeMap<String, Option<i64>>,
+    pub redis: RedisNotes,
+}
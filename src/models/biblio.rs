@@ -5,8 +5,7 @@
 //! from marc-rs types are provided where applicable.
 
 use chrono::{DateTime, Utc};
-use serde::{de::Error as SerdeError, Deserialize, Deserializer, Serialize};
-use serde::de::Visitor;
+use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use sqlx::FromRow;
 use utoipa::{IntoParams, ToSchema};
@@ -58,6 +57,16 @@ impl Isbn {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// ISBN-13 (13 digits) or ISBN-10 (9 digits, then a digit or `X`); the check digit is not verified
+    pub fn is_well_formed(&self) -> bool {
+        let s = self.0.as_bytes();
+        match s.len() {
+            13 => s.iter().all(u8::is_ascii_digit),
+            10 => s[..9].iter().all(u8::is_ascii_digit) && (s[9].is_ascii_digit() || s[9] == b'X'),
+            _ => false,
+        }
+    }
 }
 
 impl std::fmt::Display for Isbn {
@@ -179,6 +188,20 @@ pub enum AudienceType {
 }
 
 impl AudienceType {
+    /// Every audience with a canonical code (all but [`AudienceType::Other`]).
+    pub const KNOWN: [AudienceType; 10] = [
+        AudienceType::Juvenile,
+        AudienceType::Preschool,
+        AudienceType::Primary,
+        AudienceType::Children,
+        AudienceType::YoungAdult,
+        AudienceType::AdultSerious,
+        AudienceType::Adult,
+        AudienceType::General,
+        AudienceType::Specialized,
+        AudienceType::Unknown,
+    ];
+
     /// Canonical camelCase string stored in the DB column.
     pub fn as_db_str(&self) -> &str {
         match self {
@@ -239,9 +262,176 @@ impl sqlx::Encode<'_, sqlx::Postgres> for AudienceType {
     }
 }
 
+/// Accessible format of a biblio, for patrons with visual or reading impairments.
+///
+/// DB encoding: camelCase strings in the `biblios.accessibility` array (e.g. `"largePrint"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum AccessibilityFeature {
+    LargePrint,
+    Braille,
+    Audiobook,
+    DyslexiaFriendly,
+}
+
+impl AccessibilityFeature {
+    pub const ALL: [AccessibilityFeature; 4] = [
+        AccessibilityFeature::LargePrint,
+        AccessibilityFeature::Braille,
+        AccessibilityFeature::Audiobook,
+        AccessibilityFeature::DyslexiaFriendly,
+    ];
+
+    /// Canonical camelCase string stored in the DB column and the search index.
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            AccessibilityFeature::LargePrint => "largePrint",
+            AccessibilityFeature::Braille => "braille",
+            AccessibilityFeature::Audiobook => "audiobook",
+            AccessibilityFeature::DyslexiaFriendly => "dyslexiaFriendly",
+        }
+    }
+
+    /// Parse from the DB string, returning `None` for unrecognised values.
+    pub fn from_db_str(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.as_db_str() == s)
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for AccessibilityFeature {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl sqlx::postgres::PgHasArrayType for AccessibilityFeature {
+    fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::postgres::PgHasArrayType>::array_type_info()
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for AccessibilityFeature {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + 'static + Send + Sync>> {
+        let s = <String as sqlx::Decode<sqlx::Postgres>>::decode(value)?;
+        AccessibilityFeature::from_db_str(&s)
+            .ok_or_else(|| format!("unknown accessibility feature '{}'", s).into())
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for AccessibilityFeature {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_db_str().to_string(), buf)
+    }
+}
+
+/// Recommended reading age of a children's or teen biblio, finer than [`AudienceType`].
+///
+/// DB encoding: camelCase strings in `biblios.reading_level` (e.g. `"ages4To6"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ReadingLevel {
+    /// 0–3 years
+    Ages0To3,
+    /// 4–6 years
+    Ages4To6,
+    /// 7–9 years
+    Ages7To9,
+    /// 10–12 years
+    Ages10To12,
+    /// 13 years and over
+    Teen,
+}
+
+impl ReadingLevel {
+    pub const ALL: [ReadingLevel; 5] = [
+        ReadingLevel::Ages0To3,
+        ReadingLevel::Ages4To6,
+        ReadingLevel::Ages7To9,
+        ReadingLevel::Ages10To12,
+        ReadingLevel::Teen,
+    ];
+
+    /// Canonical camelCase string stored in the DB column and the search index.
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            ReadingLevel::Ages0To3 => "ages0To3",
+            ReadingLevel::Ages4To6 => "ages4To6",
+            ReadingLevel::Ages7To9 => "ages7To9",
+            ReadingLevel::Ages10To12 => "ages10To12",
+            ReadingLevel::Teen => "teen",
+        }
+    }
+
+    /// Parse from the DB string, returning `None` for unrecognised values.
+    pub fn from_db_str(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.as_db_str() == s)
+    }
+
+    /// Level implied by a MARC target audience, for the age-banded codes only: the band that
+    /// overlaps most with the code's age range (MARC21 008/22: preschool 0–5, primary 6–8,
+    /// pre-adolescent 9–13, adolescent 14–17).
+    pub fn from_audience(audience: &AudienceType) -> Option<Self> {
+        match audience {
+            AudienceType::Preschool => Some(ReadingLevel::Ages0To3),
+            AudienceType::Primary => Some(ReadingLevel::Ages7To9),
+            AudienceType::Children => Some(ReadingLevel::Ages10To12),
+            AudienceType::YoungAdult => Some(ReadingLevel::Teen),
+            _ => None,
+        }
+    }
+
+    /// Target audience written to MARC for a biblio that has a level but no audience.
+    pub fn to_audience(self) -> AudienceType {
+        match self {
+            ReadingLevel::Ages0To3 | ReadingLevel::Ages4To6 => AudienceType::Preschool,
+            ReadingLevel::Ages7To9 => AudienceType::Primary,
+            ReadingLevel::Ages10To12 => AudienceType::Children,
+            ReadingLevel::Teen => AudienceType::YoungAdult,
+        }
+    }
+
+    /// A reading level describes a children's or teen book: it contradicts an adult audience.
+    pub fn fits_audience(self, audience: Option<&AudienceType>) -> bool {
+        !matches!(audience, Some(AudienceType::Adult | AudienceType::AdultSerious))
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for ReadingLevel {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for ReadingLevel {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + 'static + Send + Sync>> {
+        let s = <String as sqlx::Decode<sqlx::Postgres>>::decode(value)?;
+        ReadingLevel::from_db_str(&s).ok_or_else(|| format!("unknown reading level '{}'", s).into())
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for ReadingLevel {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_db_str().to_string(), buf)
+    }
+}
+
 /// Media type codes for catalog biblios.
-/// Maps from MARC Leader position 6 (record type) via `record_type_to_media_type_db` (see repository).
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+///
+/// The built-in codes are seeded in the `media_types` taxonomy, which holds their label, icon,
+/// default loan rules and MARC leader mapping; codes added there by an administrator are [`MediaType::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum MediaType {
     All,
@@ -262,11 +452,35 @@ pub enum MediaType {
     AudioNonMusicCd,
     CdRom,
     Images,
+    /// Code defined in the `media_types` taxonomy
+    #[serde(untagged)]
+    Other(String),
 }
 
 impl MediaType {
-    /// Return the legacy string code for this media type
-    pub fn as_code(&self) -> &'static str {
+    /// Built-in media types (all but [`MediaType::All`] and taxonomy codes).
+    pub const BUILT_IN: [MediaType; 17] = [
+        MediaType::Unknown,
+        MediaType::PrintedText,
+        MediaType::Multimedia,
+        MediaType::Comics,
+        MediaType::Periodic,
+        MediaType::Video,
+        MediaType::VideoTape,
+        MediaType::VideoDvd,
+        MediaType::Audio,
+        MediaType::AudioMusic,
+        MediaType::AudioMusicTape,
+        MediaType::AudioMusicCd,
+        MediaType::AudioNonMusic,
+        MediaType::AudioNonMusicTape,
+        MediaType::AudioNonMusicCd,
+        MediaType::CdRom,
+        MediaType::Images,
+    ];
+
+    /// Return the legacy string code for this media type (taxonomy codes have none and are returned as is)
+    pub fn as_code(&self) -> &str {
         match self {
             MediaType::All => "",
             MediaType::Unknown => "u",
@@ -286,11 +500,12 @@ impl MediaType {
             MediaType::AudioNonMusicCd => "anc",
             MediaType::CdRom => "c",
             MediaType::Images => "i",
+            MediaType::Other(code) => code,
         }
     }
 
     /// Canonical DB/API string representation (camelCase).
-    pub fn as_db_str(&self) -> &'static str {
+    pub fn as_db_str(&self) -> &str {
         match self {
             MediaType::All => "all",
             MediaType::Unknown => "unknown",
@@ -310,6 +525,7 @@ impl MediaType {
             MediaType::AudioNonMusicCd => "audioNonMusicCd",
             MediaType::CdRom => "cdRom",
             MediaType::Images => "images",
+            MediaType::Other(code) => code,
         }
     }
 }
@@ -355,11 +571,30 @@ impl From<&str> for MediaType {
             "audioNonMusicCd" => MediaType::AudioNonMusicCd,
             "cdRom" => MediaType::CdRom,
             "images" => MediaType::Images,
-            _ => MediaType::Unknown,
+            other => MediaType::Other(other.to_string()),
         }
     }
 }
 
+/// Documented as a plain string: the built-in codes are only examples of the taxonomy's codes.
+impl<'s> ToSchema<'s> for MediaType {
+    fn schema() -> (&'s str, utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>) {
+        (
+            "MediaType",
+            utoipa::openapi::ObjectBuilder::new()
+                .schema_type(utoipa::openapi::SchemaType::String)
+                .description(Some(
+                    "Code of the media_types taxonomy (GET /settings/media-types); built-in codes: \
+                     unknown, printedText, multimedia, comics, periodic, video, videoTape, videoDvd, audio, \
+                     audioMusic, audioMusicTape, audioMusicCd, audioNonMusic, audioNonMusicTape, \
+                     audioNonMusicCd, cdRom, images",
+                ))
+                .example(Some(serde_json::json!("printedText")))
+                .into(),
+        )
+    }
+}
+
 impl std::fmt::Display for MediaType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_db_str())
@@ -423,6 +658,9 @@ pub struct Biblio {
     pub title: Option<String>,
     pub subject: Option<String>,
     pub audience_type: Option<AudienceType>,
+    /// Recommended reading age (children and teens); derived from `audienceType` on MARC import
+    #[serde(default)]
+    pub reading_level: Option<ReadingLevel>,
     pub lang: Option<Language>,
     pub lang_orig: Option<Language>,
     pub publication_date: Option<String>,
@@ -433,6 +671,9 @@ pub struct Biblio {
     pub abstract_: Option<String>,
     pub notes: Option<String>,
     pub keywords: Option<Vec<String>>,
+    /// Accessible formats (large print, braille…), detected from MARC on import
+    #[serde(default)]
+    pub accessibility: Vec<AccessibilityFeature>,
     pub is_valid: Option<bool>,
     /// Resolved series IDs (same order as `series_volume_numbers` and `series`).
     #[serde_as(as = "Vec<DisplayFromStr>")]
@@ -479,6 +720,28 @@ pub struct Biblio {
     #[sqlx(skip)]
     #[serde(default, skip)]
     pub marc_record: Option<MarcRecord>,
+    /// Cataloger currently editing the record (`GET /biblios/:id` only; ignored on write)
+    #[sqlx(skip)]
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    #[schema(read_only)]
+    pub edit_lock: Option<EditLock>,
+}
+
+/// Advisory lock of a cataloger editing a record (`POST /biblios/:id/lock`, renewed by calling it again)
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EditLock {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub biblio_id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+    pub login: String,
+    pub acquired_at: DateTime<Utc>,
+    /// The lock lapses at this time unless renewed
+    pub expires_at: DateTime<Utc>,
 }
 
 /// Short biblio representation for lists
@@ -519,6 +782,73 @@ impl From<Biblio> for BiblioShort {
     }
 }
 
+/// Public availability summary for a biblio, looked up by ISBN (embeddable widget).
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BiblioAvailability {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub biblio_id: i64,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub isbn: Option<Isbn>,
+    pub title: Option<String>,
+    /// Active, borrowable copies
+    pub total_items: i64,
+    /// Active, borrowable copies not currently on loan (nor in a bundle with a part on loan, nor
+    /// in transit)
+    pub available_items: i64,
+    /// Pending or ready holds across all copies
+    pub hold_count: i64,
+    /// Absolute or server-relative cover image URL (filled by the API layer)
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub cover_url: Option<String>,
+}
+
+impl BiblioAvailability {
+    /// True when at least one copy can be borrowed right now.
+    pub fn is_available(&self) -> bool {
+        self.available_items > 0
+    }
+}
+
+/// Availability of one requested ISBN (`GET /opac/availability`)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IsbnAvailability {
+    /// Requested ISBN, normalized (hyphens and spaces removed)
+    #[schema(value_type = String)]
+    pub isbn: Isbn,
+    /// `null` when no active record carries this ISBN
+    pub availability: Option<BiblioAvailability>,
+}
+
+/// `GET /opac/availability` response, in the order of the requested ISBNs
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailabilityBatch {
+    pub results: Vec<IsbnAvailability>,
+}
+
+/// Recently acquired biblio (first active copy added in the look-back window), for the new-acquisitions feed.
+#[derive(Debug, Clone, FromRow)]
+pub struct NewAcquisition {
+    pub biblio_id: i64,
+    pub isbn: Option<Isbn>,
+    pub title: Option<String>,
+    pub media_type: MediaType,
+    /// First author display name (lastname, firstname)
+    pub author: Option<String>,
+    #[sqlx(rename = "abstract")]
+    pub abstract_: Option<String>,
+    /// Creation time of the oldest active copy
+    pub acquired_at: DateTime<Utc>,
+    /// Latest of `acquired_at` and the biblio's own `updated_at`
+    pub updated_at: DateTime<Utc>,
+}
+
 /// Serie model. Persistence shape for MARC series (440/490/225); source: marc-rs `SeriesStatementData` (statement → name, issn).
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -604,13 +934,31 @@ pub struct MeiliBiblioDocument {
     pub table_of_contents: Option<String>,
     pub lang: Option<String>,
     pub audience_type: Option<String>,
+    /// `ReadingLevel` DB string (filterable)
+    pub reading_level: Option<String>,
+    /// `AccessibilityFeature` DB strings (filterable and faceted)
+    pub accessibility: Vec<String>,
+    /// Genre codes (filterable and faceted)
+    pub genres: Vec<String>,
+    /// Relator codes of the contributors (filterable)
+    pub author_roles: Vec<String>,
+    /// Subject heading ids assigned to the biblio (faceted)
+    pub subject_ids: Vec<i64>,
+    /// Assigned subject ids and all their broader terms (filterable: a narrower term matches its broader ones)
+    pub subject_path_ids: Vec<i64>,
+    /// Shelving locations of the active copies (faceted)
+    pub location_ids: Vec<i64>,
+    /// Locations of the active copies and the rooms and sites above them (filterable)
+    pub location_path_ids: Vec<i64>,
     pub is_archived: bool,
     /// True when the biblio has at least one non-archived (`items.archived_at IS NULL`) linked item.
     pub has_active_items: bool,
+    /// True when all the active copies are in a state not shown in the OPAC (`biblio_opac_hidden`).
+    pub opac_hidden: bool,
 }
 
 /// Query/list parameters for series.
-#[derive(Debug, Default, Deserialize, ToSchema)]
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SerieQuery {
     /// Filter by name (substring, case-insensitive).
@@ -638,7 +986,7 @@ pub struct UpdateSerie {
 }
 
 /// Query/list parameters for collections.
-#[derive(Debug, Default, Deserialize, ToSchema)]
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CollectionQuery {
     /// Filter by name (substring, case-insensitive).
@@ -670,13 +1018,19 @@ pub struct UpdateCollection {
 }
 
 /// Biblio query parameters (API). Filter values are strings; use `MarcFormat` when filtering by MARC format where applicable.
-#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BiblioQuery {
     pub media_type: Option<String>,
     pub isbn: Option<Isbn>,
     pub barcode: Option<String>,
+    /// Inventory register number of one of the copies (`2026-000042`)
+    pub inventory_number: Option<String>,
     pub author: Option<String>,
+    /// Comma-separated author roles (relator codes, MARC relator codes or terms, e.g.
+    /// `illustrator,drt`); a contributor must have one of them. Combined with `author`, the same
+    /// contributor must match both.
+    pub author_role: Option<String>,
     pub title: Option<String>,
     pub editor: Option<String>,
     pub lang: Option<String>,
@@ -685,6 +1039,10 @@ pub struct BiblioQuery {
     pub keywords: Option<String>,
     pub freesearch: Option<String>,
     pub audience_type: Option<String>,
+    /// Comma-separated reading levels; the biblio must have one of them (e.g. `ages4To6,ages7To9`).
+    pub reading_level: Option<String>,
+    /// Comma-separated accessible formats the biblio must all have (e.g. `largePrint,audiobook`).
+    pub accessibility: Option<String>,
     pub archive: Option<bool>,
     /// Filter by series name (substring, case-insensitive).
     pub serie: Option<String>,
@@ -694,16 +1052,163 @@ pub struct BiblioQuery {
     pub collection: Option<String>,
     /// Filter by collection ID (exact match).
     pub collection_id: Option<i64>,
+    /// Comma-separated genre codes; the biblio must have at least one of them.
+    pub genre: Option<String>,
+    /// Filter by subject heading ID (narrower terms included).
+    pub subject_id: Option<i64>,
+    /// Filter by shelving location ID: an active copy is shelved there (narrower locations included).
+    pub location_id: Option<i64>,
     /// When `true`, include bibliographic records that have **no** active (non-archived) linked items.
     /// When omitted or `false`, only biblios with at least one active item are returned (recommended for patron-facing catalog).
     pub include_without_active_items: Option<bool>,
     pub page: Option<i64>,
     pub per_page: Option<i64>,
+    /// Public catalog search: hide records whose active copies are all in a state not shown in
+    /// the OPAC, and those copies. Set by the OPAC endpoints only.
+    #[serde(skip)]
+    pub opac: bool,
+}
+
+impl BiblioQuery {
+    /// Accessibility filter values (`accessibility` split on commas, blanks dropped).
+    pub fn accessibility_filter(&self) -> Vec<String> {
+        split_list(self.accessibility.as_deref())
+    }
+
+    /// Reading level filter values (`readingLevel` split on commas, blanks dropped).
+    pub fn reading_level_filter(&self) -> Vec<String> {
+        split_list(self.reading_level.as_deref())
+    }
+
+    /// Genre filter values (`genre` split on commas, blanks dropped).
+    pub fn genre_filter(&self) -> Vec<String> {
+        split_list(self.genre.as_deref())
+    }
+
+    /// Author role filter values (`authorRole` split on commas, blanks dropped).
+    pub fn author_role_filter(&self) -> Vec<String> {
+        split_list(self.author_role.as_deref())
+    }
+}
+
+fn split_list(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Number of matching biblios for one facet value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetCount {
+    pub value: String,
+    /// Display label when `value` is a code or an id (genres, subjects, locations)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub count: i64,
+}
+
+/// Facet counts over a whole catalog search (all pages), for sidebar filters.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BiblioFacets {
+    /// One entry per [`AccessibilityFeature`], zero counts included
+    pub accessibility: Vec<FacetCount>,
+    /// Media type codes with their default label, most used first
+    #[serde(default)]
+    pub media_types: Vec<FacetCount>,
+    /// Genre codes with their label, most used first
+    #[serde(default)]
+    pub genres: Vec<FacetCount>,
+    /// Subject heading ids (as strings) with their heading, most used first
+    #[serde(default)]
+    pub subjects: Vec<FacetCount>,
+    /// Shelving location ids (as strings) of active copies with their path, most used first
+    #[serde(default)]
+    pub locations: Vec<FacetCount>,
+}
+
+impl BiblioFacets {
+    /// Build the accessibility facet from per-feature counts (unknown values are ignored).
+    pub fn from_accessibility_counts<'a>(counts: impl IntoIterator<Item = (&'a str, i64)>) -> Self {
+        let counts: std::collections::HashMap<&str, i64> = counts.into_iter().collect();
+        Self {
+            accessibility: AccessibilityFeature::ALL
+                .iter()
+                .map(|f| FacetCount {
+                    value: f.as_db_str().to_string(),
+                    label: None,
+                    count: counts.get(f.as_db_str()).copied().unwrap_or(0),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Number of values kept in the genre and subject facets.
+pub const HEADING_FACET_LIMIT: usize = 20;
+
+/// Shortest text completed by the suggest endpoints.
+pub const SUGGEST_MIN_CHARS: usize = 2;
+
+/// Search-as-you-type completion (`GET /items/suggest`, `GET /authors/suggest`)
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Suggestion {
+    /// Biblio id (first record with this title) or author id
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    /// Title or author name, as cataloged
+    pub label: String,
+    /// Non-archived biblios with this title, or by this author
+    pub count: i64,
+}
+
+/// Query parameters for `GET /items/suggest` and `GET /authors/suggest`
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestQuery {
+    /// Text typed so far (shorter than 2 characters → no suggestions)
+    pub q: String,
+    /// Maximum suggestions (default 10, max 20)
+    pub limit: Option<i64>,
+}
+
+/// Index targeted by one term of a [`CatalogSearchNode`] (BIB-1 use attributes on the Z39.50 target).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogSearchField {
+    Title,
+    Author,
+    Isbn,
+    Issn,
+    Subject,
+    Publisher,
+    /// Title, author, subject or identifiers
+    Any,
+}
+
+/// Boolean search tree over active biblios (built from Z39.50 Type-1 queries).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CatalogSearchNode {
+    /// Case/accent-insensitive substring match (identifiers match exactly, ignoring dashes and spaces)
+    Term { field: CatalogSearchField, value: String },
+    And(Box<CatalogSearchNode>, Box<CatalogSearchNode>),
+    Or(Box<CatalogSearchNode>, Box<CatalogSearchNode>),
+    AndNot(Box<CatalogSearchNode>, Box<CatalogSearchNode>),
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{AudienceType, BiblioShort, Isbn, MediaType};
+    use super::{
+        AccessibilityFeature, AudienceType, BiblioFacets, BiblioQuery, BiblioShort, Isbn, MediaType, ReadingLevel,
+    };
     use serde_json;
     use z3950_rs::marc_rs::record::TargetAudience;
 
@@ -749,6 +1254,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reading_level_db_strings_and_audience_mapping() {
+        for level in ReadingLevel::ALL {
+            assert_eq!(ReadingLevel::from_db_str(level.as_db_str()), Some(level));
+            assert_eq!(serde_json::to_value(level).unwrap(), level.as_db_str());
+            // Writing a level as a MARC audience reads back as the same band, except 4–6
+            // which shares the preschool code with 0–3
+            let back = ReadingLevel::from_audience(&level.to_audience());
+            assert!(back == Some(level) || level == ReadingLevel::Ages4To6);
+        }
+        assert_eq!(ReadingLevel::from_db_str("adult"), None);
+        assert_eq!(ReadingLevel::from_audience(&AudienceType::Primary), Some(ReadingLevel::Ages7To9));
+        assert_eq!(ReadingLevel::from_audience(&AudienceType::General), None);
+        assert!(ReadingLevel::Teen.fits_audience(Some(&AudienceType::YoungAdult)));
+        assert!(ReadingLevel::Teen.fits_audience(None));
+        assert!(!ReadingLevel::Ages7To9.fits_audience(Some(&AudienceType::Adult)));
+    }
+
+    #[test]
+    fn accessibility_db_strings_round_trip() {
+        for feature in AccessibilityFeature::ALL {
+            assert_eq!(AccessibilityFeature::from_db_str(feature.as_db_str()), Some(feature));
+            assert_eq!(serde_json::to_value(feature).unwrap(), feature.as_db_str());
+        }
+        assert_eq!(AccessibilityFeature::from_db_str("tactile"), None);
+    }
+
+    #[test]
+    fn accessibility_filter_and_facets() {
+        let query = BiblioQuery {
+            accessibility: Some(" largePrint, ,audiobook".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(query.accessibility_filter(), vec!["largePrint", "audiobook"]);
+
+        let facets = BiblioFacets::from_accessibility_counts([("braille", 2), ("other", 9)]);
+        let counts: Vec<(&str, i64)> =
+            facets.accessibility.iter().map(|f| (f.value.as_str(), f.count)).collect();
+        assert_eq!(
+            counts,
+            vec![("largePrint", 0), ("braille", 2), ("audiobook", 0), ("dyslexiaFriendly", 0)]
+        );
+    }
+
     #[test]
     fn biblio_short_id_serializes_as_string() {
         let biblio = BiblioShort {
@@ -786,6 +1335,16 @@ mod tests {
         assert_eq!(isbn.as_str(), "ISBN978207");
     }
 
+    #[test]
+    fn isbn_well_formed_needs_10_or_13_characters() {
+        assert!(Isbn::new("978-2-07-040850-4").is_well_formed());
+        assert!(Isbn::new("2-07-040850-x").is_well_formed());
+        assert!(!Isbn::new("").is_well_formed());
+        assert!(!Isbn::new("978207").is_well_formed());
+        assert!(!Isbn::new("isbn: 978_2_07").is_well_formed());
+        assert!(!Isbn::new("97820704085X4").is_well_formed());
+    }
+
     #[test]
     fn isbn_deserializes_from_json_formatted_string() {
         let v: Isbn = serde_json::from_value(serde_json::json!("978-2-07-040850-4")).unwrap();
@@ -0,0 +1,109 @@
+//! Item bundles: box sets and multi-volume works whose copies circulate as one unit
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Bundle of copies checked out, renewed and returned together
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemBundle {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub name: String,
+    /// Label on the box; scanning it at checkout checks out every part
+    pub barcode: Option<String>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub update_at: Option<DateTime<Utc>>,
+    /// Active copies in the bundle
+    pub parts_count: i64,
+    /// Parts currently on loan
+    pub parts_on_loan: i64,
+    /// Parts of the bundle (filled on detail responses only)
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<BundlePart>,
+}
+
+impl ItemBundle {
+    /// The whole set is on the shelf: it has parts and none of them is out.
+    pub fn is_available(&self) -> bool {
+        self.parts_count > 0 && self.parts_on_loan == 0
+    }
+}
+
+/// One copy of a bundle
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BundlePart {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub item_id: i64,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub biblio_id: Option<i64>,
+    pub barcode: Option<String>,
+    pub call_number: Option<String>,
+    pub volume_designation: Option<String>,
+    pub title: Option<String>,
+    /// Active loan of this part, if any
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub loan_id: Option<i64>,
+}
+
+/// Create a bundle from copy barcodes
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateItemBundle {
+    pub name: String,
+    pub barcode: Option<String>,
+    pub notes: Option<String>,
+    /// Barcodes of the copies making up the bundle (at least two)
+    pub part_barcodes: Vec<String>,
+}
+
+/// Update a bundle; `partBarcodes` replaces the whole list of parts
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateItemBundle {
+    pub name: Option<String>,
+    /// Empty string removes the barcode
+    pub barcode: Option<String>,
+    pub notes: Option<String>,
+    pub part_barcodes: Option<Vec<String>>,
+}
+
+/// Return the parts of a bundle that were brought back
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnItemBundle {
+    /// Barcodes of the parts scanned at the desk
+    pub barcodes: Vec<String>,
+}
+
+/// Result of returning a bundle: parts checked in and parts still missing
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleReturnReport {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub bundle_id: i64,
+    pub returned: Vec<BundlePart>,
+    /// Parts still on loan after this return
+    pub missing: Vec<BundlePart>,
+}
+
+impl BundleReturnReport {
+    /// Every part is back on the shelf.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
@@ -0,0 +1,291 @@
+//! Email campaigns: announcements sent to a patron segment through a throttled outbound queue
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Campaign lifecycle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CampaignStatus {
+    /// Editable, nothing queued
+    #[default]
+    Draft,
+    /// Recipients queued, the sender is draining the queue
+    Sending,
+    /// Every queued recipient was attempted
+    Sent,
+}
+
+impl CampaignStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Sending => "sending",
+            Self::Sent => "sent",
+        }
+    }
+}
+
+impl From<String> for CampaignStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "sending" => Self::Sending,
+            "sent" => Self::Sent,
+            _ => Self::Draft,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for CampaignStatus {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for CampaignStatus {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for CampaignStatus {
+    fn encode_by_ref(
+        &self,
+        buf: &mut sqlx::postgres::PgArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Delivery state of a queued recipient
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CampaignRecipientStatus {
+    Pending,
+    /// Claimed by a sender
+    Sending,
+    /// Accepted by the SMTP server
+    Sent,
+    /// Refused by the SMTP server
+    Failed,
+    /// Reported as bounced by the mail provider after delivery
+    Bounced,
+}
+
+impl CampaignRecipientStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Sending => "sending",
+            Self::Sent => "sent",
+            Self::Failed => "failed",
+            Self::Bounced => "bounced",
+        }
+    }
+}
+
+impl From<String> for CampaignRecipientStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "sending" => Self::Sending,
+            "sent" => Self::Sent,
+            "failed" => Self::Failed,
+            "bounced" => Self::Bounced,
+            _ => Self::Pending,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for CampaignRecipientStatus {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for CampaignRecipientStatus {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for CampaignRecipientStatus {
+    fn encode_by_ref(
+        &self,
+        buf: &mut sqlx::postgres::PgArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Patrons targeted by a campaign. Only active accounts with an email address are selected;
+/// criteria left empty do not filter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignSegment {
+    /// Account type codes (empty = every account type)
+    #[serde(default)]
+    pub account_types: Vec<String>,
+    /// Minimum age in years, inclusive (patrons without a birthdate are excluded)
+    pub min_age: Option<i32>,
+    /// Maximum age in years, inclusive (patrons without a birthdate are excluded)
+    pub max_age: Option<i32>,
+    /// Only patrons who borrowed something in the last N days
+    pub active_within_days: Option<i32>,
+    /// Only patrons who opted in to campaigns (default). Turn off for service announcements
+    /// such as closures.
+    #[serde(default = "default_opted_in_only")]
+    pub opted_in_only: bool,
+}
+
+fn default_opted_in_only() -> bool {
+    true
+}
+
+impl Default for CampaignSegment {
+    fn default() -> Self {
+        Self {
+            account_types: Vec::new(),
+            min_age: None,
+            max_age: None,
+            active_within_days: None,
+            opted_in_only: true,
+        }
+    }
+}
+
+/// Campaign with its delivery counters
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Campaign {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub name: String,
+    pub subject: String,
+    /// Plain text inserted into the `campaign` email template
+    /// (`{{firstname}}` / `{{lastname}}` are replaced per recipient)
+    pub body: String,
+    #[sqlx(json)]
+    pub segment: CampaignSegment,
+    pub status: CampaignStatus,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub update_at: Option<DateTime<Utc>>,
+    /// When the recipients were queued
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Queued addresses
+    pub recipients_count: i64,
+    /// Not attempted yet
+    pub pending_count: i64,
+    /// Accepted by the SMTP server and not reported as bounced
+    pub delivered_count: i64,
+    pub failed_count: i64,
+    pub bounced_count: i64,
+}
+
+/// Create a draft campaign
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCampaign {
+    pub name: String,
+    pub subject: String,
+    pub body: String,
+    #[serde(default)]
+    pub segment: CampaignSegment,
+}
+
+/// Edit a draft campaign (absent fields are kept)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCampaign {
+    pub name: Option<String>,
+    pub subject: Option<String>,
+    pub body: Option<String>,
+    pub segment: Option<CampaignSegment>,
+}
+
+/// Recipients a segment currently resolves to
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignSegmentPreview {
+    /// Distinct email addresses
+    pub recipients: i64,
+}
+
+/// Queued recipient
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignRecipient {
+    pub email: String,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub user_id: Option<i64>,
+    pub status: CampaignRecipientStatus,
+    /// SMTP error of a failed send
+    pub error: Option<String>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub bounced_at: Option<DateTime<Utc>>,
+}
+
+/// Recipient claimed by the sender, with what is needed to compose the email
+#[derive(Debug, Clone, FromRow)]
+pub struct CampaignDelivery {
+    pub email: String,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    pub language: Option<String>,
+}
+
+/// Query parameters of the recipient list
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CampaignRecipientsQuery {
+    /// Only recipients in this state
+    pub status: Option<CampaignRecipientStatus>,
+}
+
+/// Bounces reported by the mail provider
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignBounces {
+    pub emails: Vec<String>,
+}
+
+/// Outcome of recording bounces
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignBounceReport {
+    /// Sent recipients now marked as bounced
+    pub bounced: u64,
+    /// Addresses that were not sent by this campaign (or already bounced)
+    pub ignored: Vec<String>,
+}
+
+/// Patron consent to receive campaigns
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignsOptIn {
+    pub opt_in: bool,
+}
@@ -0,0 +1,39 @@
+//! Bibliographic citations of a record (`/items/:id/citation`, `/opac/biblios/:id/citation`)
+
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Citation style, or reference manager format (`bibtex`, `ris`: file download)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CitationStyle {
+    /// APA, 7th edition
+    #[default]
+    Apa,
+    /// MLA, 9th edition
+    Mla,
+    /// ISO 690:2010
+    Iso690,
+    Bibtex,
+    Ris,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CitationQuery {
+    /// `apa` (default), `mla`, `iso690`; `bibtex` and `ris` download a file
+    pub style: Option<CitationStyle>,
+}
+
+/// Formatted citation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Citation {
+    pub style: CitationStyle,
+    /// Plain text
+    #[schema(example = "Camus, A. (1942). L'étranger. Gallimard.")]
+    pub text: String,
+    /// Same citation with the title in italics (`<i>`), escaped for HTML pages
+    #[schema(example = "Camus, A. (1942). <i>L&#39;étranger</i>. Gallimard.")]
+    pub html: String,
+}
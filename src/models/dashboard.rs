@@ -0,0 +1,183 @@
+//! Personal dashboards (`dashboard_widgets`): each user's homepage widgets over the statistics endpoints
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Statistics endpoint a widget shows; `params` holds the query parameters of that endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum DashboardWidgetType {
+    /// `GET /stats`
+    Stats,
+    /// `GET /stats/loans`
+    LoanStats,
+    /// `GET /stats/users`
+    UserStats,
+    /// `GET /stats/catalog`
+    CatalogStats,
+    /// `GET /stats/visitors`
+    VisitorStats,
+    /// `GET /stats/saved/{id}/run`, `params`: `{ "id": "<saved query id>" }`
+    SavedQuery,
+    /// `GET /stats/reports/{id}/run` (JSON rows), `params`: `{ "id": "<saved report id>" }`
+    SavedReport,
+}
+
+impl DashboardWidgetType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stats => "stats",
+            Self::LoanStats => "loanStats",
+            Self::UserStats => "userStats",
+            Self::CatalogStats => "catalogStats",
+            Self::VisitorStats => "visitorStats",
+            Self::SavedQuery => "savedQuery",
+            Self::SavedReport => "savedReport",
+        }
+    }
+}
+
+impl FromStr for DashboardWidgetType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stats" => Ok(Self::Stats),
+            "loanStats" => Ok(Self::LoanStats),
+            "userStats" => Ok(Self::UserStats),
+            "catalogStats" => Ok(Self::CatalogStats),
+            "visitorStats" => Ok(Self::VisitorStats),
+            "savedQuery" => Ok(Self::SavedQuery),
+            "savedReport" => Ok(Self::SavedReport),
+            _ => Err(format!("Unknown widget type '{}'", s)),
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for DashboardWidgetType {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for DashboardWidgetType {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(s.parse()?)
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for DashboardWidgetType {
+    fn encode_by_ref(
+        &self,
+        buf: &mut sqlx::postgres::PgArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Widget of a personal dashboard
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardWidget {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub widget_type: DashboardWidgetType,
+    pub title: Option<String>,
+    /// Query parameters of the endpoint, as a JSON object (e.g. `{ "interval": "month" }`)
+    #[schema(value_type = Object)]
+    pub params: Value,
+    /// Placement on the client grid (e.g. `{ "x": 0, "y": 0, "w": 6, "h": 4 }`), stored as sent
+    #[schema(value_type = Option<Object>)]
+    pub layout: Option<Value>,
+    /// Display order, from 0
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+    pub update_at: DateTime<Utc>,
+}
+
+/// Widget definition (`POST`/`PUT /auth/my-dashboard/widgets`, entries of `PUT /auth/my-dashboard`)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardWidgetWrite {
+    pub widget_type: DashboardWidgetType,
+    pub title: Option<String>,
+    /// Query parameters of the endpoint (omitted or `null`: none)
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub params: Value,
+    #[schema(value_type = Option<Object>)]
+    pub layout: Option<Value>,
+}
+
+/// The caller's dashboard, widgets in display order
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Dashboard {
+    pub widgets: Vec<DashboardWidget>,
+}
+
+/// Whole dashboard layout (`PUT /auth/my-dashboard`): replaces every widget, in this order
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveDashboard {
+    pub widgets: Vec<DashboardWidgetWrite>,
+}
+
+/// Data of one widget; `error` replaces `data` when the widget could not be resolved
+/// (missing rights, saved query deleted, …)
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardWidgetData {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub widget_id: i64,
+    pub widget_type: DashboardWidgetType,
+    /// Response body of the endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub data: Option<Value>,
+    /// Client-safe error message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Error code, as in error responses (`authorization_failed`, `not_found`, …)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+}
+
+/// Data of every widget of the caller's dashboard (`GET /auth/my-dashboard/data`)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardData {
+    pub widgets: Vec<DashboardWidgetData>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// `params` of the saved query and saved report widgets
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedStatsRef {
+    #[serde_as(as = "DisplayFromStr")]
+    pub id: i64,
+}
+
+/// Parse widget parameters as the query of the target endpoint (`null` counts as no parameter)
+pub fn widget_params<T: DeserializeOwned>(params: &Value) -> Result<T, String> {
+    let params = if params.is_null() { Value::Object(Default::default()) } else { params.clone() };
+    serde_json::from_value(params).map_err(|e| e.to_string())
+}
@@ -0,0 +1,189 @@
+//! Deposit collections: rotating deposits lent by another library (departmental lending library)
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Lifecycle of a deposit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DepositStatus {
+    /// Copies are on the shelves; they cannot be weeded
+    #[default]
+    Active,
+    /// Sent back to its owner; remaining copies were archived
+    Closed,
+}
+
+impl DepositStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Closed => "closed",
+        }
+    }
+}
+
+impl From<String> for DepositStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "closed" => Self::Closed,
+            _ => Self::Active,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for DepositStatus {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for DepositStatus {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for DepositStatus {
+    fn encode_by_ref(
+        &self,
+        buf: &mut sqlx::postgres::PgArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Deposit batch with copy counters
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Deposit {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    /// Lending institution
+    pub owner: String,
+    pub label: Option<String>,
+    pub received_at: NaiveDate,
+    /// Date the copies must be back with their owner
+    pub return_due: NaiveDate,
+    pub status: DepositStatus,
+    pub notes: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub update_at: Option<DateTime<Utc>>,
+    pub closed_at: Option<DateTime<Utc>>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub closed_by: Option<i64>,
+    /// Active copies of the deposit
+    pub copies_count: i64,
+    /// Active copies currently on loan
+    pub copies_on_loan: i64,
+}
+
+impl Deposit {
+    pub fn is_active(&self) -> bool {
+        self.status == DepositStatus::Active
+    }
+}
+
+/// One copy of the return manifest
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositCopy {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub item_id: i64,
+    pub barcode: Option<String>,
+    pub call_number: Option<String>,
+    pub title: Option<String>,
+    /// First author (lastname, firstname)
+    pub author: Option<String>,
+    pub isbn: Option<String>,
+    /// Active loan of the copy, if any
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub loan_id: Option<i64>,
+    /// Due date of the active loan
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+/// Register a deposit
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDeposit {
+    pub owner: String,
+    pub label: Option<String>,
+    /// Defaults to today
+    pub received_at: Option<NaiveDate>,
+    pub return_due: NaiveDate,
+    pub notes: Option<String>,
+}
+
+/// Update an active deposit (omitted fields are kept)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDeposit {
+    pub owner: Option<String>,
+    pub label: Option<String>,
+    pub return_due: Option<NaiveDate>,
+    pub notes: Option<String>,
+}
+
+/// Query parameters for listing deposits
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositQuery {
+    pub status: Option<DepositStatus>,
+}
+
+/// Query parameters for importing a cached MARC batch as deposit copies
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportDepositQuery {
+    /// Batch identifier returned by `POST /biblios/load-marc`
+    #[serde_as(as = "DisplayFromStr")]
+    #[param(value_type = String)]
+    #[schema(value_type = String)]
+    pub batch_id: i64,
+    /// Source attached to the imported copies
+    #[serde_as(as = "DisplayFromStr")]
+    #[param(value_type = String)]
+    #[schema(value_type = String)]
+    pub source_id: i64,
+    /// Allow creating a biblio even when another has the same ISBN
+    #[serde(default)]
+    pub allow_duplicate_isbn: bool,
+    /// Store a PDF processing slip artifact per created copy
+    #[serde(default)]
+    pub processing_slips: bool,
+}
+
+/// Result of closing a deposit
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositCloseReport {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub deposit_id: i64,
+    /// Copies on the shelf, archived with the deposit
+    pub archived: u64,
+    /// Copies still on loan; they stay in the catalog until they are back and sent on
+    pub on_loan: Vec<DepositCopy>,
+}
@@ -0,0 +1,242 @@
+//! Donation intake models (donors, donated books and their triage)
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Name of the source given to copies created from accepted donations
+pub const DONATION_SOURCE_NAME: &str = "donation";
+
+/// Triage decision for a donated book
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DonationTriage {
+    /// Not decided yet
+    Pending,
+    /// Added to the catalog (a copy is created)
+    Catalog,
+    /// Set aside for the book sale
+    Sale,
+    Recycle,
+}
+
+impl DonationTriage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Catalog => "catalog",
+            Self::Sale => "sale",
+            Self::Recycle => "recycle",
+        }
+    }
+}
+
+impl From<String> for DonationTriage {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "catalog" => Self::Catalog,
+            "sale" => Self::Sale,
+            "recycle" => Self::Recycle,
+            _ => Self::Pending,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for DonationTriage {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for DonationTriage {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for DonationTriage {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Donation received from one donor
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Donation {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub donor_name: String,
+    pub donor_email: Option<String>,
+    pub donor_phone: Option<String>,
+    /// Registered patron who made the donation, if any
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub donor_user_id: Option<i64>,
+    pub received_at: NaiveDate,
+    /// Estimated value of the whole donation
+    #[schema(value_type = Option<String>, example = "45.00")]
+    pub estimated_value: Option<Decimal>,
+    pub notes: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub update_at: Option<DateTime<Utc>>,
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub items: Vec<DonationItem>,
+}
+
+/// One donated book
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DonationItem {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub donation_id: i64,
+    pub title: String,
+    pub authors: Option<String>,
+    pub isbn: Option<String>,
+    pub triage: DonationTriage,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub biblio_id: Option<i64>,
+    /// Copy created when the book was added to the catalog
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub item_id: Option<i64>,
+    pub triaged_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+}
+
+/// Create donation request
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDonation {
+    pub donor_name: String,
+    pub donor_email: Option<String>,
+    pub donor_phone: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub donor_user_id: Option<i64>,
+    /// Reception date (defaults to today)
+    pub received_at: Option<NaiveDate>,
+    #[schema(value_type = Option<String>)]
+    pub estimated_value: Option<Decimal>,
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub items: Vec<CreateDonationItem>,
+}
+
+/// Donated book to record
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDonationItem {
+    pub title: String,
+    pub authors: Option<String>,
+    pub isbn: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Update donation request
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDonation {
+    pub donor_name: Option<String>,
+    pub donor_email: Option<String>,
+    pub donor_phone: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub donor_user_id: Option<i64>,
+    pub received_at: Option<NaiveDate>,
+    #[schema(value_type = Option<String>)]
+    pub estimated_value: Option<Decimal>,
+    pub notes: Option<String>,
+}
+
+/// Triage decision for a donated book.
+///
+/// `catalog` requires `biblioId`: a copy is created on that record with source `donation`.
+/// A book already added to the catalog cannot be triaged again.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TriageDonationItem {
+    pub decision: DonationTriage,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub biblio_id: Option<i64>,
+    /// Barcode of the copy to create
+    pub barcode: Option<String>,
+    /// Call number of the copy to create
+    pub call_number: Option<String>,
+}
+
+/// Query parameters for listing donations
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DonationQuery {
+    /// Filter by reception year
+    pub year: Option<i32>,
+    /// Donor name contains (case-insensitive)
+    pub donor: Option<String>,
+    /// Only donations with books still to triage
+    pub pending_only: Option<bool>,
+}
+
+/// Query parameters for the annual donors report
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DonorsReportQuery {
+    pub year: i32,
+}
+
+/// Donations of one donor over a year
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DonorSummary {
+    pub donor_name: String,
+    pub donor_email: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub donor_user_id: Option<i64>,
+    pub donations: i64,
+    pub books: i64,
+    pub cataloged: i64,
+    pub for_sale: i64,
+    pub recycled: i64,
+    pub pending: i64,
+    #[schema(value_type = String)]
+    pub estimated_value: Decimal,
+}
+
+/// Annual donors report (`GET /donations/report/donors`)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DonorsReport {
+    pub year: i32,
+    pub donors: Vec<DonorSummary>,
+    pub total_books: i64,
+    #[schema(value_type = String)]
+    pub total_estimated_value: Decimal,
+}
@@ -0,0 +1,151 @@
+//! Outbound email deliverability: bounces and complaints reported by the mail provider
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Kind of delivery problem reported by the mail provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum EmailFeedbackKind {
+    /// Permanent failure (unknown mailbox, domain does not exist)
+    HardBounce,
+    /// Temporary failure (mailbox full, greylisting); never suppresses the address
+    SoftBounce,
+    /// The recipient marked an email as spam
+    Complaint,
+}
+
+impl EmailFeedbackKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::HardBounce => "hard_bounce",
+            Self::SoftBounce => "soft_bounce",
+            Self::Complaint => "complaint",
+        }
+    }
+}
+
+/// One bounce or complaint from the provider webhook
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailFeedbackEvent {
+    pub email: String,
+    pub kind: EmailFeedbackKind,
+    /// Provider diagnostic (e.g. `550 5.1.1 user unknown`)
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Provider event id; events already recorded (webhook retries) are ignored
+    #[serde(default)]
+    pub event_id: Option<String>,
+    /// When the provider saw the bounce (defaults to reception time)
+    #[serde(default)]
+    pub occurred_at: Option<DateTime<Utc>>,
+}
+
+/// Webhook body: the events of one provider callback
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailFeedbackBatch {
+    pub events: Vec<EmailFeedbackEvent>,
+}
+
+/// Outcome of a webhook call
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailFeedbackReport {
+    pub recorded: u64,
+    /// Events already recorded under the same `eventId`
+    pub duplicates: u64,
+    /// Addresses that became undeliverable with this call
+    pub undeliverable: Vec<String>,
+}
+
+/// Deliverability summary of an address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailHealthStatus {
+    /// Nothing reported since the last reset
+    Healthy,
+    /// Bounced, still below the hard bounce threshold
+    Bouncing,
+    /// The recipient complained: no more campaigns are sent to the address
+    Complained,
+    /// Hard bounce threshold reached: no email is sent to the address
+    Undeliverable,
+}
+
+impl EmailHealthStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Healthy => "healthy",
+            Self::Bouncing => "bouncing",
+            Self::Complained => "complained",
+            Self::Undeliverable => "undeliverable",
+        }
+    }
+}
+
+impl From<String> for EmailHealthStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "bouncing" => Self::Bouncing,
+            "complained" => Self::Complained,
+            "undeliverable" => Self::Undeliverable,
+            _ => Self::Healthy,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for EmailHealthStatus {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for EmailHealthStatus {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for EmailHealthStatus {
+    fn encode_by_ref(
+        &self,
+        buf: &mut sqlx::postgres::PgArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Bounces and complaints counted for an address since its last reset
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailHealth {
+    pub email: String,
+    pub status: EmailHealthStatus,
+    pub hard_bounces: i32,
+    pub soft_bounces: i32,
+    pub complaints: i32,
+    pub last_feedback_at: Option<DateTime<Utc>>,
+    /// Provider diagnostic of the last event
+    pub last_reason: Option<String>,
+    /// When the hard bounce threshold was reached
+    pub undeliverable_at: Option<DateTime<Utc>>,
+}
+
+/// Result of recording one event
+#[derive(Debug, Clone)]
+pub struct RecordedEmailFeedback {
+    pub health: EmailHealth,
+    /// This event reached the hard bounce threshold
+    pub became_undeliverable: bool,
+}
@@ -0,0 +1,250 @@
+//! End-of-day job (`/admin/eod-runs`): run log and daily statistics snapshot
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Outcome of an end-of-day run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum EodRunStatus {
+    Running,
+    /// Every step succeeded (or was skipped)
+    Succeeded,
+    /// Some steps failed, the others were done
+    Partial,
+    /// Every step that ran failed
+    Failed,
+}
+
+impl EodRunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Partial => "partial",
+            Self::Failed => "failed",
+        }
+    }
+
+    /// Status of a finished run from the outcome of its steps
+    pub fn from_steps(steps: &[EodStepResult]) -> Self {
+        let ran = steps.iter().filter(|s| s.status != EodStepStatus::Skipped).count();
+        let failed = steps.iter().filter(|s| s.status == EodStepStatus::Failed).count();
+        match failed {
+            0 => Self::Succeeded,
+            n if n == ran => Self::Failed,
+            _ => Self::Partial,
+        }
+    }
+}
+
+impl From<String> for EodRunStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "succeeded" => Self::Succeeded,
+            "partial" => Self::Partial,
+            "failed" => Self::Failed,
+            _ => Self::Running,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for EodRunStatus {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for EodRunStatus {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for EodRunStatus {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// What started a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum EodTrigger {
+    /// `eod.run_time`, when `eod.enabled`
+    Scheduled,
+    /// `POST /admin/eod-runs`
+    Manual,
+}
+
+impl EodTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Scheduled => "scheduled",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+impl From<String> for EodTrigger {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "manual" => Self::Manual,
+            _ => Self::Scheduled,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for EodTrigger {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for EodTrigger {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for EodTrigger {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Steps of an end-of-day run, in execution order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum EodStep {
+    /// Archival policy: move loans returned long enough ago to `loans_archives`
+    ArchiveLoans,
+    /// Write the day's [`DailyStats`]
+    DailyStats,
+    /// Hold pull list PDF for the next morning (`eod.pull_list`)
+    PullList,
+    /// Email the digest to `eod.digest_recipients`
+    Digest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum EodStepStatus {
+    Succeeded,
+    /// Disabled in the configuration
+    Skipped,
+    Failed,
+}
+
+/// Outcome of one step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EodStepResult {
+    pub step: EodStep,
+    pub status: EodStepStatus,
+    /// Rows archived, pull list entries, digest recipients...
+    pub count: Option<i64>,
+    /// Error or skip reason
+    pub message: Option<String>,
+}
+
+/// End-of-day run log entry
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EodRun {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    /// Day closed by the run (local date)
+    pub business_day: NaiveDate,
+    pub status: EodRunStatus,
+    pub trigger: EodTrigger,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub triggered_by: Option<i64>,
+    #[sqlx(json)]
+    pub steps: Vec<EodStepResult>,
+    pub archived_loans: Option<i32>,
+    pub pull_list_entries: Option<i32>,
+    /// Pull list PDF (`GET /artifacts/:id/link`); null once the artifact expired
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub pull_list_artifact_id: Option<i64>,
+    pub digest_recipients: Option<i32>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Statistics snapshot of one business day, finalized by the end-of-day job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyStats {
+    pub day: NaiveDate,
+    /// Loans started during the day
+    pub loans: i32,
+    /// Loans returned during the day
+    pub returns: i32,
+    /// Accounts created during the day
+    pub new_users: i32,
+    pub holds_placed: i32,
+    /// Loans still out at closing time
+    pub active_loans: i32,
+    /// Loans past their due date at closing time
+    pub overdue_loans: i32,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// Run with the statistics of the day it closed
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EodRunDetails {
+    #[serde(flatten)]
+    pub run: EodRun,
+    pub daily_stats: Option<DailyStats>,
+}
+
+/// Query parameters of the end-of-day run log
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EodRunsQuery {
+    /// Most recent runs returned (default 30, max 365)
+    pub limit: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(step: EodStep, status: EodStepStatus) -> EodStepResult {
+        EodStepResult { step, status, count: None, message: None }
+    }
+
+    #[test]
+    fn run_status_from_steps() {
+        use EodStepStatus::*;
+        let ok = [step(EodStep::ArchiveLoans, Succeeded), step(EodStep::Digest, Skipped)];
+        assert_eq!(EodRunStatus::from_steps(&ok), EodRunStatus::Succeeded);
+        let partial = [step(EodStep::ArchiveLoans, Succeeded), step(EodStep::PullList, Failed)];
+        assert_eq!(EodRunStatus::from_steps(&partial), EodRunStatus::Partial);
+        let failed = [step(EodStep::ArchiveLoans, Failed), step(EodStep::Digest, Skipped)];
+        assert_eq!(EodRunStatus::from_steps(&failed), EodRunStatus::Failed);
+    }
+}
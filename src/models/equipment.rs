@@ -29,10 +29,53 @@ pub struct Equipment {
     pub notes: Option<String>,
     pub created_at: Option<DateTime<Utc>>,
     pub update_at: Option<DateTime<Utc>>,
+    /// Circulation barcode; equipment without a barcode cannot be checked out
+    pub barcode: Option<String>,
+    /// Loan period in days for equipment loans
+    pub loan_duration_days: i16,
+    /// Maximum number of renewals for equipment loans
+    pub max_renewals: i16,
+    /// Checkout requires a deposit to be confirmed by staff
+    pub requires_deposit: bool,
+}
+
+impl Equipment {
+    /// True when the equipment can currently be checked out (has a barcode and is active).
+    pub fn is_circulating(&self) -> bool {
+        self.barcode.as_deref().is_some_and(|b| !b.trim().is_empty())
+            && self.status.unwrap_or(0) == 0
+    }
+}
+
+/// Equipment summary embedded in loan details
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EquipmentShort {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub name: String,
+    /// Type (0=computer, 1=tablet, 2=ereader, 3=other)
+    pub equipment_type: i16,
+    pub barcode: Option<String>,
+    pub requires_deposit: bool,
+}
+
+impl From<&Equipment> for EquipmentShort {
+    fn from(e: &Equipment) -> Self {
+        Self {
+            id: e.id,
+            name: e.name.clone(),
+            equipment_type: e.equipment_type,
+            barcode: e.barcode.clone(),
+            requires_deposit: e.requires_deposit,
+        }
+    }
 }
 
 /// Create equipment request
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateEquipment {
     pub name: String,
@@ -42,10 +85,18 @@ pub struct CreateEquipment {
     pub is_public: Option<bool>,
     pub quantity: Option<i32>,
     pub notes: Option<String>,
+    /// Circulation barcode (must not collide with a copy barcode)
+    pub barcode: Option<String>,
+    /// Loan period in days (default: 7)
+    pub loan_duration_days: Option<i16>,
+    /// Maximum renewals (default: 0)
+    pub max_renewals: Option<i16>,
+    /// Checkout requires a deposit (default: false)
+    pub requires_deposit: Option<bool>,
 }
 
 /// Update equipment request
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateEquipment {
     pub name: Option<String>,
@@ -55,4 +106,8 @@ pub struct UpdateEquipment {
     pub quantity: Option<i32>,
     pub status: Option<i16>,
     pub notes: Option<String>,
+    pub barcode: Option<String>,
+    pub loan_duration_days: Option<i16>,
+    pub max_renewals: Option<i16>,
+    pub requires_deposit: Option<bool>,
 }
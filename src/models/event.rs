@@ -5,19 +5,85 @@ use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use sqlx::FromRow;
 use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::error::{AppError, AppResult};
 
 /// Optional attachment supplied when creating an event (Base64-encoded payload).
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EventAttachmentInput {
     /// Display file name (path segments are stripped server-side).
+    #[validate(length(min = 1, max = 512, message = "File name must be 1 to 512 characters"))]
     pub file_name: String,
     /// MIME type (e.g. `application/pdf`, `image/png`).
+    #[validate(length(min = 1, max = 255, message = "MIME type must be 1 to 255 characters"))]
     pub mime_type: String,
     /// File content encoded as standard Base64.
     pub data_base64: String,
 }
 
+/// Publication state of an event
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EventStatus {
+    /// Being prepared; visible to staff only
+    #[default]
+    Draft,
+    /// Visible on the OPAC and in the Atom feed
+    Published,
+    /// Called off; registered attendees may have been notified
+    Cancelled,
+}
+
+impl EventStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Published => "published",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl From<String> for EventStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "published" => Self::Published,
+            "cancelled" => Self::Cancelled,
+            _ => Self::Draft,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for EventStatus {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for EventStatus {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for EventStatus {
+    fn encode_by_ref(
+        &self,
+        buf: &mut sqlx::postgres::PgArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
 /// Event record
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -36,6 +102,8 @@ pub struct Event {
     pub start_time: Option<NaiveTime>,
     /// End time
     pub end_time: Option<NaiveTime>,
+    /// Room or location; two events cannot overlap in the same room
+    pub room: Option<String>,
     /// Number of attendees
     pub attendees_count: Option<i32>,
     /// Target audience: `public_types.name` (e.g. `child`, `adult`); `NULL` = all audiences.
@@ -54,6 +122,13 @@ pub struct Event {
     pub update_at: Option<DateTime<Utc>>,
     /// Date the announcement email was last sent
     pub announcement_sent_at: Option<DateTime<Utc>>,
+    /// Only published events are visible without events rights
+    pub status: EventStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub cancellation_reason: Option<String>,
+    /// Number of registered attendees
+    pub registrations_count: i64,
     /// Original attachment file name when present
     pub attachment_filename: Option<String>,
     /// Attachment MIME type when present
@@ -67,9 +142,10 @@ pub struct Event {
 }
 
 /// Create event request
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateEvent {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
     pub name: String,
     /// Type (0=animation, 1=school_visit, 2=exhibition, 3=conference, 4=workshop, 5=show, 6=other)
     pub event_type: Option<i16>,
@@ -79,45 +155,64 @@ pub struct CreateEvent {
     pub start_time: Option<String>,
     /// End time (HH:MM)
     pub end_time: Option<String>,
+    /// Room or location (at most 100 characters)
+    #[validate(length(max = 100, message = "Room must be at most 100 characters"))]
+    pub room: Option<String>,
+    #[validate(range(min = 0, message = "Attendees count must not be negative"))]
     pub attendees_count: Option<i32>,
     /// Target audience: `public_types.name` from `GET /public-types` (e.g. `child`, `adult`).
     pub public_type: Option<String>,
+    #[validate(length(max = 255, message = "School name must be at most 255 characters"))]
     pub school_name: Option<String>,
+    #[validate(length(max = 255, message = "Class name must be at most 255 characters"))]
     pub class_name: Option<String>,
+    #[validate(range(min = 0, message = "Students count must not be negative"))]
     pub students_count: Option<i32>,
+    #[validate(length(max = 255, message = "Partner name must be at most 255 characters"))]
     pub partner_name: Option<String>,
     pub description: Option<String>,
     pub notes: Option<String>,
     /// Optional attachment (stored in-database; max size enforced server-side).
+    #[validate(nested)]
     pub attachment: Option<EventAttachmentInput>,
 }
 
 /// Update event request
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateEvent {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
     pub name: Option<String>,
     pub event_type: Option<i16>,
     pub event_date: Option<String>,
     pub start_time: Option<String>,
     pub end_time: Option<String>,
+    /// Room or location; an empty string clears it
+    #[validate(length(max = 100, message = "Room must be at most 100 characters"))]
+    pub room: Option<String>,
+    #[validate(range(min = 0, message = "Attendees count must not be negative"))]
     pub attendees_count: Option<i32>,
     /// Target audience: `public_types.name` from `GET /public-types`.
     pub public_type: Option<String>,
+    #[validate(length(max = 255, message = "School name must be at most 255 characters"))]
     pub school_name: Option<String>,
+    #[validate(length(max = 255, message = "Class name must be at most 255 characters"))]
     pub class_name: Option<String>,
+    #[validate(range(min = 0, message = "Students count must not be negative"))]
     pub students_count: Option<i32>,
+    #[validate(length(max = 255, message = "Partner name must be at most 255 characters"))]
     pub partner_name: Option<String>,
     pub description: Option<String>,
     pub notes: Option<String>,
     /// When `true`, removes the attachment. Takes precedence over `attachment`.
     pub remove_attachment: Option<bool>,
     /// Replaces the attachment (same shape as in [`CreateEvent`]).
+    #[validate(nested)]
     pub attachment: Option<EventAttachmentInput>,
 }
 
 /// Query parameters for events
-#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EventQuery {
     /// Filter by start date (YYYY-MM-DD)
@@ -126,8 +221,207 @@ pub struct EventQuery {
     pub end_date: Option<String>,
     /// Filter by event type
     pub event_type: Option<i16>,
+    /// Filter by status (forced to `published` without events rights)
+    pub status: Option<EventStatus>,
     /// Page number (1-based)
     pub page: Option<i64>,
     /// Items per page
     pub per_page: Option<i64>,
 }
+
+/// Request body of `POST /events/{id}/cancel`
+#[derive(Debug, Default, Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelEvent {
+    /// Shown to attendees in the notification
+    pub reason: Option<String>,
+    /// Email registered attendees (default `true`)
+    pub notify: Option<bool>,
+}
+
+/// Request body of `POST /events/{id}/registrations`
+#[serde_as]
+#[derive(Debug, Default, Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterEvent {
+    /// Patron to register; defaults to the caller (registering someone else requires events write rights)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub user_id: Option<i64>,
+}
+
+/// Attendee registered to an event
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventRegistration {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Date, time span and room of an event, as checked for conflicts.
+///
+/// A missing start time means from the start of the day, a missing end time until its end.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventSlot {
+    pub date: NaiveDate,
+    pub start_time: Option<NaiveTime>,
+    pub end_time: Option<NaiveTime>,
+    pub room: Option<String>,
+}
+
+impl EventSlot {
+    /// Apply the date, time and room fields of an update to the current slot of an event
+    /// (unparsable times clear the field, as the update itself does).
+    pub fn updated(&self, data: &UpdateEvent) -> AppResult<Self> {
+        let date = match &data.event_date {
+            Some(d) => parse_event_date(d)?,
+            None => self.date,
+        };
+        let time = |value: &Option<String>, current| match value {
+            Some(t) => NaiveTime::parse_from_str(t, "%H:%M").ok(),
+            None => current,
+        };
+        Ok(Self {
+            date,
+            start_time: time(&data.start_time, self.start_time),
+            end_time: time(&data.end_time, self.end_time),
+            room: match &data.room {
+                Some(room) => normalize_room(Some(room)),
+                None => self.room.clone(),
+            },
+        })
+    }
+}
+
+impl From<&Event> for EventSlot {
+    fn from(event: &Event) -> Self {
+        Self {
+            date: event.event_date,
+            start_time: event.start_time,
+            end_time: event.end_time,
+            room: event.room.clone(),
+        }
+    }
+}
+
+impl TryFrom<&CreateEvent> for EventSlot {
+    type Error = AppError;
+
+    fn try_from(data: &CreateEvent) -> AppResult<Self> {
+        let time = |value: &Option<String>| {
+            value.as_ref().and_then(|t| NaiveTime::parse_from_str(t, "%H:%M").ok())
+        };
+        Ok(Self {
+            date: parse_event_date(&data.event_date)?,
+            start_time: time(&data.start_time),
+            end_time: time(&data.end_time),
+            room: normalize_room(data.room.as_ref()),
+        })
+    }
+}
+
+fn parse_event_date(value: &str) -> AppResult<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| AppError::Validation("Invalid event_date".to_string()))
+}
+
+/// Trimmed room name; blank means no room
+pub fn normalize_room(room: Option<&String>) -> Option<String> {
+    room.map(|r| r.trim()).filter(|r| !r.is_empty()).map(str::to_string)
+}
+
+/// Why an event cannot take place as planned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum EventConflictKind {
+    /// The library is closed that day (`/schedules/closures`)
+    Closure,
+    /// Another event overlaps in the same room
+    Room,
+}
+
+impl From<String> for EventConflictKind {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "closure" => Self::Closure,
+            _ => Self::Room,
+        }
+    }
+}
+
+/// Planning conflict returned by `GET /events/conflicts`
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventConflict {
+    #[sqlx(try_from = "String")]
+    pub kind: EventConflictKind,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub event_id: i64,
+    pub event_name: String,
+    pub event_date: NaiveDate,
+    pub start_time: Option<NaiveTime>,
+    pub end_time: Option<NaiveTime>,
+    pub room: Option<String>,
+    /// Overlapping event in the same room (`room` conflicts)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub other_event_id: Option<i64>,
+    pub other_event_name: Option<String>,
+    /// Reason of the closure (`closure` conflicts)
+    pub closure_reason: Option<String>,
+}
+
+/// Query of `GET /events/conflicts` (both days included)
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct EventConflictQuery {
+    /// First day (YYYY-MM-DD)
+    #[param(value_type = String)]
+    pub start: NaiveDate,
+    /// Last day (YYYY-MM-DD)
+    #[param(value_type = String)]
+    pub end: NaiveDate,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update() -> UpdateEvent {
+        serde_json::from_value(serde_json::json!({})).unwrap()
+    }
+
+    #[test]
+    fn slot_update_keeps_unchanged_fields() {
+        let slot = EventSlot {
+            date: NaiveDate::from_ymd_opt(2026, 5, 2).unwrap(),
+            start_time: NaiveTime::from_hms_opt(10, 0, 0),
+            end_time: NaiveTime::from_hms_opt(11, 0, 0),
+            room: Some("Auditorium".to_string()),
+        };
+        assert_eq!(slot.updated(&update()).unwrap(), slot);
+
+        let moved = slot
+            .updated(&UpdateEvent {
+                start_time: Some("14:30".to_string()),
+                end_time: Some(String::new()),
+                room: Some("  ".to_string()),
+                ..update()
+            })
+            .unwrap();
+        assert_eq!(moved.start_time, NaiveTime::from_hms_opt(14, 30, 0));
+        assert_eq!((moved.end_time, moved.room), (None, None));
+
+        let invalid = UpdateEvent { event_date: Some("02/05/2026".to_string()), ..update() };
+        assert!(matches!(slot.updated(&invalid), Err(AppError::Validation(_))));
+    }
+}
@@ -0,0 +1,81 @@
+//! Versioned envelope of the events the server emits (SSE stream, audit log event export).
+//! Inbound vendor webhooks (lockers, mail provider) keep the vendor's own format.
+//!
+//! Compatibility policy: within one `version` of an event type, payload fields are only ever
+//! added. Removing, renaming or retyping a field bumps the version of that type, and consumers
+//! must ignore fields they do not know. The golden samples under `tests/contract/events` pin
+//! every published version (see the `events` contract test).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Current payload version of each SSE event type
+pub const SSE_EVENT_VERSIONS: &[(&str, u32)] = &[
+    ("loan.created", 1),
+    ("loan.returned", 1),
+    ("loan.renewed", 1),
+    ("hold.created", 1),
+    ("hold.ready", 1),
+    ("hold.inLocker", 1),
+    ("hold.pickedUp", 1),
+    ("hold.expired", 1),
+    ("hold.cancelled", 1),
+    ("dashboard.counters", 1),
+    ("notification.created", 1),
+];
+
+/// Payload version of audit log entries (the same for every audit event type)
+pub const AUDIT_ENTRY_VERSION: u32 = 1;
+
+/// `{ type, version, occurredAt, payload }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventEnvelope<P> {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub version: u32,
+    pub occurred_at: DateTime<Utc>,
+    pub payload: P,
+}
+
+impl<P> EventEnvelope<P> {
+    pub fn new(event_type: impl Into<String>, version: u32, occurred_at: DateTime<Utc>, payload: P) -> Self {
+        Self {
+            event_type: event_type.into(),
+            version,
+            occurred_at,
+            payload,
+        }
+    }
+}
+
+/// Version of an SSE event type (`None` for a type missing from [`SSE_EVENT_VERSIONS`])
+pub fn sse_version(event_type: &str) -> Option<u32> {
+    SSE_EVENT_VERSIONS
+        .iter()
+        .find(|(name, _)| *name == event_type)
+        .map(|(_, version)| *version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn envelope_field_names() {
+        let at = Utc.with_ymd_and_hms(2026, 10, 17, 9, 0, 0).unwrap();
+        let envelope = EventEnvelope::new("loan.created", 1, at, serde_json::json!({ "loan_id": "1" }));
+        assert_eq!(
+            serde_json::to_value(&envelope).unwrap(),
+            serde_json::json!({
+                "type": "loan.created",
+                "version": 1,
+                "occurredAt": "2026-10-17T09:00:00Z",
+                "payload": { "loan_id": "1" }
+            })
+        );
+        assert_eq!(sse_version("hold.ready"), Some(1));
+        assert_eq!(sse_version("hold.unknown"), None);
+    }
+}
@@ -1,10 +1,12 @@
 //! Fine / penalty model
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use sqlx::FromRow;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
+
+use super::payment::PaymentMethod;
 
 /// Fine status
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
@@ -76,7 +78,9 @@ pub struct Fine {
     #[serde_as(as = "DisplayFromStr")]
     #[schema(value_type = String)]
     pub user_id: i64,
+    #[schema(value_type = String, example = "1.50")]
     pub amount: rust_decimal::Decimal,
+    #[schema(value_type = String, example = "0.00")]
     pub paid_amount: rust_decimal::Decimal,
     pub created_at: DateTime<Utc>,
     pub paid_at: Option<DateTime<Utc>>,
@@ -90,22 +94,152 @@ pub struct Fine {
 pub struct FineRule {
     pub id: i32,
     pub media_type: Option<String>,
+    #[schema(value_type = String, example = "0.10")]
     pub daily_rate: rust_decimal::Decimal,
+    #[schema(value_type = Option<String>, example = "5.00")]
     pub max_amount: Option<rust_decimal::Decimal>,
     pub grace_days: i32,
     pub notes: Option<String>,
 }
 
-/// Pay fine request
+/// Pay fine request (recorded in the cash register, see `POST /payments`)
 #[serde_as]
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PayFineRequest {
+    /// At most the outstanding balance
+    #[schema(value_type = String, example = "1.50")]
     pub amount: rust_decimal::Decimal,
+    /// Defaults to cash
+    pub method: Option<PaymentMethod>,
     pub notes: Option<String>,
 }
 
 /// Waive fine request
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct WaiveFineRequest {
     pub notes: Option<String>,
 }
+
+/// Why a fine was written off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum FineWaiverReason {
+    /// Waived by staff (`POST /fines/:id/waive`)
+    Manual,
+    /// Amnesty campaign over a date range (`POST /fines/amnesty`)
+    Amnesty,
+    /// Overdue return during an amnesty week (`circulation.fine_amnesties`)
+    AmnestyWeek,
+}
+
+impl FineWaiverReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::Amnesty => "amnesty",
+            Self::AmnestyWeek => "amnestyWeek",
+        }
+    }
+}
+
+impl From<String> for FineWaiverReason {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "amnesty" => Self::Amnesty,
+            "amnestyWeek" => Self::AmnestyWeek,
+            _ => Self::Manual,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for FineWaiverReason {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for FineWaiverReason {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for FineWaiverReason {
+    fn encode_by_ref(
+        &self,
+        buf: &mut sqlx::postgres::PgArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Amnesty campaign: waive every unpaid fine issued between two days (both included)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FineAmnestyRequest {
+    /// Campaign name, reported with the waived totals
+    pub name: String,
+    #[schema(value_type = String, example = "2026-01-01")]
+    pub start_date: NaiveDate,
+    #[schema(value_type = String, example = "2026-06-30")]
+    pub end_date: NaiveDate,
+}
+
+/// Result of an amnesty campaign
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FineAmnestyReport {
+    pub name: String,
+    #[schema(value_type = String)]
+    pub start_date: NaiveDate,
+    #[schema(value_type = String)]
+    pub end_date: NaiveDate,
+    pub fine_count: i64,
+    #[schema(value_type = String, example = "42.50")]
+    pub waived_total: rust_decimal::Decimal,
+}
+
+/// Query parameters of `GET /fines/waivers/summary` (days are in library local time, both included)
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct WaivedFinesQuery {
+    /// First day (YYYY-MM-DD)
+    #[param(value_type = Option<String>)]
+    pub start_date: Option<NaiveDate>,
+    /// Last day (YYYY-MM-DD)
+    #[param(value_type = Option<String>)]
+    pub end_date: Option<NaiveDate>,
+}
+
+/// Waived fines of one reason (and campaign or amnesty week)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FineWaiverTotal {
+    pub reason: FineWaiverReason,
+    pub amnesty: Option<String>,
+    pub fine_count: i64,
+    #[schema(value_type = String, example = "12.00")]
+    pub waived_total: rust_decimal::Decimal,
+}
+
+/// Waived fines over a period (`GET /fines/waivers/summary`)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WaivedFinesSummary {
+    #[schema(value_type = Option<String>)]
+    pub start_date: Option<NaiveDate>,
+    #[schema(value_type = Option<String>)]
+    pub end_date: Option<NaiveDate>,
+    pub fine_count: i64,
+    #[schema(value_type = String, example = "54.50")]
+    pub waived_total: rust_decimal::Decimal,
+    pub totals: Vec<FineWaiverTotal>,
+}
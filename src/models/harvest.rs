@@ -0,0 +1,363 @@
+//! Scheduled harvest of union catalog delta files (sources, runs, mapping profiles)
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Container of the delta files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HarvestMarcFormat {
+    /// Binary ISO 2709 (UNIMARC or MARC21, detected per record)
+    #[default]
+    Iso2709,
+    /// MARCXML collection
+    Marcxml,
+}
+
+impl HarvestMarcFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Iso2709 => "iso2709",
+            Self::Marcxml => "marcxml",
+        }
+    }
+}
+
+impl From<String> for HarvestMarcFormat {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "marcxml" => Self::Marcxml,
+            _ => Self::Iso2709,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for HarvestMarcFormat {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for HarvestMarcFormat {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for HarvestMarcFormat {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Field holding the union catalog's record id
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum HarvestRecordIdField {
+    /// 001
+    #[default]
+    ControlNumber,
+    /// 035 $a (system control number), optionally filtered by `recordIdPrefix`
+    SystemControlNumber,
+}
+
+/// What a deleted record (leader status `d`) does to the local record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HarvestDeleteAction {
+    /// Archive the local record (refused while one of its copies is on loan)
+    #[default]
+    Archive,
+    /// Keep the local record and only forget the link
+    Keep,
+}
+
+/// How harvested records are identified and applied to the catalog
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HarvestMappingProfile {
+    pub record_id_field: HarvestRecordIdField,
+    /// Only 035 values starting with this prefix (e.g. `(PPN)`) identify the record
+    pub record_id_prefix: Option<String>,
+    /// Overwrite the bibliographic data of records harvested before (copies are kept)
+    pub update_existing: bool,
+    /// A new record whose ISBN is already in the catalog is linked to (and updates) that record;
+    /// when `false` it is rejected
+    pub match_by_isbn: bool,
+    /// Create copies from the holdings fields of new records
+    pub import_items: bool,
+    pub on_delete: HarvestDeleteAction,
+}
+
+impl Default for HarvestMappingProfile {
+    fn default() -> Self {
+        Self {
+            record_id_field: HarvestRecordIdField::ControlNumber,
+            record_id_prefix: None,
+            update_existing: true,
+            match_by_isbn: true,
+            import_items: false,
+            on_delete: HarvestDeleteAction::Archive,
+        }
+    }
+}
+
+/// Union catalog harvested every night (the password is never returned)
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HarvestSource {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub name: String,
+    /// `https://…` (one delta file), `ftp://host/dir/` or `sftp://host[:port]/dir/` (directory)
+    pub url: String,
+    pub username: Option<String>,
+    #[serde(skip)]
+    pub password: Option<String>,
+    pub has_password: bool,
+    /// Expected SSH host key of an SFTP server (`SHA256:…`)
+    pub host_key_fingerprint: Option<String>,
+    /// Glob selecting the delta files of an FTP/SFTP directory
+    pub file_pattern: String,
+    pub marc_format: HarvestMarcFormat,
+    #[sqlx(json)]
+    pub mapping_profile: HarvestMappingProfile,
+    /// Catalog source of the copies created from harvested records
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub catalog_source_id: Option<i64>,
+    /// Local time of the nightly run (HH:MM)
+    pub schedule_time: String,
+    pub is_active: bool,
+    /// Notified when a run fails or rejects records
+    pub alert_emails: Vec<String>,
+    /// Last applied file (FTP/SFTP) or validator of the last fetched file (HTTP)
+    pub last_cursor: Option<String>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+/// Register a harvest source
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateHarvestSource {
+    pub name: String,
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Required for `sftp://` URLs
+    pub host_key_fingerprint: Option<String>,
+    /// Default `*`
+    pub file_pattern: Option<String>,
+    pub marc_format: Option<HarvestMarcFormat>,
+    pub mapping_profile: Option<HarvestMappingProfile>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub catalog_source_id: Option<i64>,
+    /// Default `02:30`
+    pub schedule_time: Option<String>,
+    pub is_active: Option<bool>,
+    pub alert_emails: Option<Vec<String>>,
+}
+
+/// Update a harvest source (absent fields are kept)
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateHarvestSource {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub username: Option<String>,
+    /// New password; an empty string removes it
+    pub password: Option<String>,
+    pub host_key_fingerprint: Option<String>,
+    pub file_pattern: Option<String>,
+    pub marc_format: Option<HarvestMarcFormat>,
+    pub mapping_profile: Option<HarvestMappingProfile>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub catalog_source_id: Option<i64>,
+    pub schedule_time: Option<String>,
+    pub is_active: Option<bool>,
+    pub alert_emails: Option<Vec<String>>,
+    /// Forget the last applied file, so the next run takes every file again
+    #[serde(default)]
+    pub reset_cursor: bool,
+}
+
+/// What started a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HarvestTrigger {
+    Schedule,
+    Manual,
+}
+
+impl HarvestTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Schedule => "schedule",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+impl From<String> for HarvestTrigger {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "manual" => Self::Manual,
+            _ => Self::Schedule,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for HarvestTrigger {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for HarvestTrigger {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for HarvestTrigger {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Outcome of a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HarvestRunStatus {
+    Running,
+    /// Every record applied
+    Succeeded,
+    /// Files applied, but some records were rejected
+    Partial,
+    /// Fetch or parse failure; the cursor stays on the last applied file
+    Failed,
+}
+
+impl HarvestRunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Partial => "partial",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl From<String> for HarvestRunStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "succeeded" => Self::Succeeded,
+            "partial" => Self::Partial,
+            "failed" => Self::Failed,
+            _ => Self::Running,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for HarvestRunStatus {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for HarvestRunStatus {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for HarvestRunStatus {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Record rejected during a run
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HarvestRecordError {
+    pub file: String,
+    /// Union catalog id, when the record has one
+    pub record_id: Option<String>,
+    pub error: String,
+}
+
+/// Report of one harvest run
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HarvestRun {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub source_id: i64,
+    pub trigger: HarvestTrigger,
+    pub status: HarvestRunStatus,
+    /// Staff member who started a manual run
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub started_by: Option<i64>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Delta files applied, in order
+    pub files: Vec<String>,
+    pub created_count: i32,
+    pub updated_count: i32,
+    pub deleted_count: i32,
+    /// Records without effect (unknown deletions, links kept by the mapping profile)
+    pub skipped_count: i32,
+    pub failed_count: i32,
+    /// First rejected records (at most 200)
+    #[sqlx(json)]
+    pub record_errors: Vec<HarvestRecordError>,
+    /// Why the run stopped
+    pub error: Option<String>,
+}
+
+/// Query parameters of the run history
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HarvestRunsQuery {
+    /// Most recent runs returned (default 20, max 100)
+    pub limit: Option<i64>,
+}
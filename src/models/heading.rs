@@ -0,0 +1,156 @@
+//! Managed genre and subject heading vocabularies (`genres`, `subjects`)
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Genre of the managed vocabulary
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GenreHeading {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    /// Stable code (also the `genre` code of translated labels)
+    pub code: String,
+    pub label: String,
+    pub sort_order: i16,
+    /// Bibliographic records with this genre
+    pub biblio_count: i64,
+    pub created_at: Option<DateTime<Utc>>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+/// Create genre request
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateGenreHeading {
+    pub code: String,
+    pub label: String,
+    pub sort_order: Option<i16>,
+}
+
+/// Update genre request
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateGenreHeading {
+    pub code: Option<String>,
+    pub label: Option<String>,
+    pub sort_order: Option<i16>,
+}
+
+/// Subject heading; `broader_id` links it to its broader term
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectHeading {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub heading: String,
+    /// Broader term (`None` for a top-level heading)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub broader_id: Option<i64>,
+    pub scope_note: Option<String>,
+    /// Number of direct narrower terms
+    pub narrower_count: i64,
+    /// Bibliographic records with this heading
+    pub biblio_count: i64,
+    pub created_at: Option<DateTime<Utc>>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+/// Create subject heading request
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSubjectHeading {
+    pub heading: String,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub broader_id: Option<i64>,
+    pub scope_note: Option<String>,
+}
+
+/// Update subject heading request (an empty `scopeNote` clears it; `topLevel: true` detaches the
+/// heading from its broader term)
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSubjectHeading {
+    pub heading: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub broader_id: Option<i64>,
+    #[serde(default)]
+    pub top_level: bool,
+    pub scope_note: Option<String>,
+}
+
+/// Query parameters for `GET /settings/subjects`
+#[serde_as]
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectQuery {
+    /// Heading contains (case and accent insensitive)
+    pub q: Option<String>,
+    /// Only the narrower terms of this heading
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    #[schema(value_type = Option<String>)]
+    pub broader_id: Option<i64>,
+    /// Only top-level headings (ignored with `broaderId`)
+    pub top_level: Option<bool>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+/// Merge duplicate headings into the one in the path
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeHeadings {
+    /// Headings to merge (deleted once their records and narrower terms are re-mapped)
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    #[schema(value_type = Vec<String>)]
+    pub source_ids: Vec<i64>,
+}
+
+/// Outcome of a heading merge
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeHeadingsReport {
+    /// Heading kept
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub target_id: i64,
+    /// Headings merged and deleted
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    #[schema(value_type = Vec<String>)]
+    pub merged_ids: Vec<i64>,
+    /// Records re-mapped to the kept heading
+    pub biblios_remapped: i64,
+    /// Narrower terms moved under the kept heading (subjects only)
+    pub narrower_moved: i64,
+    /// Records whose headings changed (reindexed for search)
+    #[serde(skip)]
+    pub biblio_ids: Vec<i64>,
+}
+
+/// Replace the genres or subjects of a bibliographic record
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetBiblioHeadings {
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    #[schema(value_type = Vec<String>)]
+    pub ids: Vec<i64>,
+}
@@ -1,20 +1,24 @@
 //! Hold (physical item queue) model
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use sqlx::FromRow;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::models::biblio::BiblioShort;
 use crate::models::user::UserShort;
 
-/// Hold lifecycle status (stored as lowercase strings in DB).
+/// Hold lifecycle status (stored as snake_case strings in DB).
+///
+/// Locker pickup: `ready` → `inLocker` (copy placed in a compartment) → `pickedUp` or `expired`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "camelCase")]
 pub enum HoldStatus {
     Pending,
     Ready,
+    InLocker,
+    PickedUp,
     Fulfilled,
     Cancelled,
     Expired,
@@ -25,6 +29,8 @@ impl HoldStatus {
         match self {
             Self::Pending => "pending",
             Self::Ready => "ready",
+            Self::InLocker => "in_locker",
+            Self::PickedUp => "picked_up",
             Self::Fulfilled => "fulfilled",
             Self::Cancelled => "cancelled",
             Self::Expired => "expired",
@@ -36,6 +42,8 @@ impl From<String> for HoldStatus {
     fn from(s: String) -> Self {
         match s.as_str() {
             "ready" => Self::Ready,
+            "in_locker" => Self::InLocker,
+            "picked_up" => Self::PickedUp,
             "fulfilled" => Self::Fulfilled,
             "cancelled" => Self::Cancelled,
             "expired" => Self::Expired,
@@ -72,6 +80,77 @@ impl sqlx::Encode<'_, sqlx::Postgres> for HoldStatus {
     }
 }
 
+/// Queue priority of a hold. Placing a `staff` or `teaching` hold needs `priority_holds_rights`;
+/// `holds.priority_order` decides where it enters the queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum HoldPriority {
+    #[default]
+    Normal,
+    /// Placed by staff for library needs (exhibitions, repairs, interlibrary requests)
+    Staff,
+    /// Teachers preparing classes
+    Teaching,
+}
+
+impl HoldPriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Staff => "staff",
+            Self::Teaching => "teaching",
+        }
+    }
+
+    /// Rank in a queue order (lower is served first). Priorities missing from the order rank
+    /// with `normal`, so an empty order is a plain first come, first served queue.
+    pub fn rank(&self, order: &[HoldPriority]) -> usize {
+        order
+            .iter()
+            .position(|p| p == self)
+            .or_else(|| order.iter().position(|p| *p == Self::Normal))
+            .unwrap_or(order.len())
+    }
+}
+
+impl From<String> for HoldPriority {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "staff" => Self::Staff,
+            "teaching" => Self::Teaching,
+            _ => Self::Normal,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for HoldPriority {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for HoldPriority {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for HoldPriority {
+    fn encode_by_ref(
+        &self,
+        buf: &mut sqlx::postgres::PgArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
 /// Hold row from database (`holds` table). `item_id` references `items.id`.
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -92,6 +171,15 @@ pub struct Hold {
     pub status: HoldStatus,
     pub position: i32,
     pub notes: Option<String>,
+    /// Patron collects the copy from a pickup locker
+    pub pickup_locker: bool,
+    pub locker_id: Option<String>,
+    pub locker_compartment: Option<String>,
+    /// Code the patron types at the locker
+    pub locker_pickup_code: Option<String>,
+    pub locker_assigned_at: Option<DateTime<Utc>>,
+    pub picked_up_at: Option<DateTime<Utc>>,
+    pub priority: HoldPriority,
 }
 
 /// Hold with bibliographic context and user details.
@@ -110,7 +198,15 @@ pub struct HoldDetails {
     pub expires_at: Option<DateTime<Utc>>,
     pub status: HoldStatus,
     pub position: i32,
+    /// Place in the queue of the copy (1 = next served) while the hold is active, `null` otherwise
+    pub queue_position: Option<i32>,
+    pub priority: HoldPriority,
     pub notes: Option<String>,
+    pub pickup_locker: bool,
+    pub locker_id: Option<String>,
+    pub locker_compartment: Option<String>,
+    pub locker_pickup_code: Option<String>,
+    pub picked_up_at: Option<DateTime<Utc>>,
 }
 
 /// Create hold request — `item_id` must be a physical copy ID (`items` table).
@@ -125,4 +221,156 @@ pub struct CreateHold {
     #[schema(value_type = String)]
     pub item_id: i64,
     pub notes: Option<String>,
+    /// Collect the copy from a pickup locker once it is available
+    #[serde(default)]
+    pub pickup_locker: bool,
+    #[serde(default)]
+    pub priority: HoldPriority,
+}
+
+/// Place a `ready` hold's copy in a pickup locker (`POST /holds/:id/locker`)
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignHoldLocker {
+    /// Locker bank, when the vendor runs several (the vendor picks the compartment)
+    pub locker_id: Option<String>,
+}
+
+/// Compartment reserved by the locker vendor for a hold
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockerReservation {
+    pub locker_id: String,
+    pub compartment: String,
+    pub pickup_code: Option<String>,
+}
+
+/// Locker vendor webhook event kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum LockerEventKind {
+    /// The patron opened the compartment (copy collected)
+    CompartmentOpened,
+    /// The pickup window lapsed; the vendor freed the compartment
+    Expired,
+}
+
+/// Locker vendor webhook body (`POST /lockers/webhook`)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LockerWebhookEvent {
+    pub event: LockerEventKind,
+    /// Hold id sent when the compartment was reserved
+    pub reference: String,
+    pub locker_id: Option<String>,
+    pub compartment: Option<String>,
+    pub occurred_at: Option<DateTime<Utc>>,
+}
+
+/// Outcome of a locker webhook event
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LockerEventOutcome {
+    pub hold: Hold,
+    /// Loan created for the patron on pickup
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub loan_id: Option<i64>,
+    /// Why no loan could be created on pickup (staff must check the copy out by hand)
+    pub loan_error: Option<String>,
+}
+
+/// One line of a hold shelf list: a copy to pull for a queued hold, or to reshelve after its hold expired
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HoldShelfEntry {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub hold_id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub item_id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub biblio_id: i64,
+    pub barcode: Option<String>,
+    pub call_number: Option<String>,
+    /// Place of the copy (`items.place`)
+    pub place: Option<i16>,
+    /// Shelving location of the copy (`/settings/locations`)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub location_id: Option<i64>,
+    #[schema(example = "Main library › Youth › Comics")]
+    pub location_path: Option<String>,
+    pub title: Option<String>,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+    /// Patron name (lastname firstname)
+    pub user_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Pickup deadline (expired list only)
+    pub expires_at: Option<DateTime<Utc>>,
+    pub pickup_locker: bool,
+}
+
+/// Hold shelf list entries of one shelving location, by call number
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HoldShelfGroup {
+    /// Place of the copies (`null`: copies without a place)
+    pub place: Option<i16>,
+    /// Shelving location of the copies (`null`: not located yet, see the mapping assistant)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub location_id: Option<i64>,
+    pub location_path: Option<String>,
+    pub entries: Vec<HoldShelfEntry>,
+}
+
+/// Output of the hold shelf lists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HoldListFormat {
+    #[default]
+    Json,
+    /// Printable A4 sheet
+    Pdf,
+}
+
+/// Query parameters of `GET /holds/pull-list`
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HoldPullListQuery {
+    #[serde(default)]
+    pub format: HoldListFormat,
+}
+
+/// Query parameters of `GET /holds/expired`
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpiredHoldsQuery {
+    /// Holds whose pickup deadline passed on or after this day (default: 7 days ago)
+    pub since: Option<NaiveDate>,
+    #[serde(default)]
+    pub format: HoldListFormat,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlisted_priorities_rank_with_normal() {
+        use HoldPriority::*;
+        let order = [Teaching, Staff, Normal];
+        assert_eq!((Teaching.rank(&order), Staff.rank(&order), Normal.rank(&order)), (0, 1, 2));
+        let order = [Teaching, Normal];
+        assert_eq!(Staff.rank(&order), Normal.rank(&order));
+        assert_eq!(Teaching.rank(&[]), Normal.rank(&[]));
+    }
 }
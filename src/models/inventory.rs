@@ -144,19 +144,29 @@ pub struct InventorySession {
     pub notes: Option<String>,
     /// When set, report and missing list only include active items with this `items.place`.
     pub scope_place: Option<i16>,
+    /// When set, only copies shelved at this location or under it (`/settings/locations`).
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub scope_location_id: Option<i64>,
     #[serde_as(as = "Option<DisplayFromStr>")]
     #[schema(value_type = Option<String>)]
     pub created_by: Option<i64>,
 }
 
 /// Create inventory session request
-#[derive(Debug, Deserialize, ToSchema)]
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateInventorySession {
     pub name: String,
     pub location_filter: Option<String>,
     pub notes: Option<String>,
     pub scope_place: Option<i16>,
+    /// Site, room or shelf range to inventory (its place becomes `scopePlace`)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub scope_location_id: Option<i64>,
 }
 
 /// Individual scan within a session
@@ -180,13 +190,13 @@ pub struct InventoryScan {
 }
 
 /// Scan a barcode in a session
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ScanBarcode {
     pub barcode: String,
 }
 
 /// Batch scan request (`POST .../scans/batch`)
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BatchScanBarcodes {
     pub barcodes: Vec<String>,
 }
@@ -202,13 +212,16 @@ pub struct InventoryMissingRow {
     pub barcode: Option<String>,
     pub call_number: Option<String>,
     pub place: Option<i16>,
+    /// Shelving location path of the copy
+    pub location_path: Option<String>,
     pub biblio_title: Option<String>,
 }
 
 /// Discrepancy report for a session (enriched).
 ///
 /// Count formulas (session `S`):
-/// - `expectedInScope`: active items where `(S.scope_place IS NULL OR item.place = S.scope_place)`.
+/// - `expectedInScope`: active items where `(S.scope_place IS NULL OR item.place = S.scope_place)`
+///   and, when `S.scope_location_id` is set, shelved at that location or under it.
 /// - `missingCount`: in-scope active items with no scan row having `item_id = item.id`.
 /// - `missingScannable`: subset of `missingCount` with non-null barcode.
 /// - `missingWithoutBarcode`: in-scope active with `barcode IS NULL` (cannot be captured by barcode scan).
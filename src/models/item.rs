@@ -3,17 +3,72 @@
 //! An Item is one borrowable physical copy of a bibliographic record (Biblio).
 //! Soft delete is tracked solely via `archived_at` (NULL = active, set = archived).
 
-use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use sqlx::FromRow;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
 fn default_borrowable() -> bool {
     true
 }
 
+/// Who may follow the `accessUrl` of a digital resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemAccessType {
+    /// Anyone, including anonymous visitors
+    Open,
+    /// Authenticated users only (licensed resources)
+    Restricted,
+}
+
+impl ItemAccessType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Restricted => "restricted",
+        }
+    }
+}
+
+impl From<String> for ItemAccessType {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "open" => Self::Open,
+            _ => Self::Restricted,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for ItemAccessType {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for ItemAccessType {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for ItemAccessType {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
 /// Full item (physical copy) model from database.
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema, Validate)]
@@ -38,9 +93,18 @@ pub struct Item {
     pub place: Option<i16>,
     #[serde(default = "default_borrowable")]
     pub borrowable: bool,
+    /// Item state code (see `GET /settings/item-states`); unknown codes are rejected
     pub circulation_status: Option<i16>,
     pub notes: Option<String>,
+    /// Amount in the `[currency]` of the server, stored as `12.50`; an empty string clears it
     pub price: Option<String>,
+    /// Link to the online resource (e-book, streaming…); an empty string clears it on update
+    #[validate(length(max = 2000, message = "Access URL must be at most 2000 characters"))]
+    #[serde(default)]
+    pub access_url: Option<String>,
+    /// Defaults to `restricted` when `accessUrl` is set
+    #[serde(default)]
+    pub access_type: Option<ItemAccessType>,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
     pub archived_at: Option<DateTime<Utc>>,
@@ -48,6 +112,34 @@ pub struct Item {
     pub source_name: Option<String>,
     #[serde(default)]
     pub borrowed: bool,
+    /// Deposit (`deposits.id`) the copy was lent with; deposit copies cannot be weeded while it is active
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    #[serde(default)]
+    #[sqlx(default)]
+    pub deposit_id: Option<i64>,
+    /// Home place the copy is travelling back to (floating rules), until received there
+    #[serde(default)]
+    #[sqlx(default)]
+    pub transit_place: Option<i16>,
+    /// Inventory register number (`2026-000042`), assigned at creation; read-only
+    #[serde(default)]
+    #[sqlx(default)]
+    pub inventory_number: Option<String>,
+    /// Shelving location (`/settings/locations`); sets `place` to the place of its site
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    #[serde(default)]
+    #[sqlx(default)]
+    pub location_id: Option<i64>,
+    /// Names of the shelving location from its site down (`Main library › Youth › Comics`); read-only
+    #[serde(default)]
+    #[sqlx(default)]
+    pub location_path: Option<String>,
+    /// The item state of the copy is not shown in the OPAC (`item_states.opac_visible`); read-only
+    #[serde(default)]
+    #[sqlx(default)]
+    pub hidden_from_opac: bool,
 }
 
 impl Item {
@@ -75,6 +167,10 @@ pub struct ItemShort {
     #[sqlx(skip)]
     #[serde(default)]
     pub borrowed: bool,
+    /// The item state of the copy is not shown in the OPAC; read-only
+    #[sqlx(default)]
+    #[serde(default)]
+    pub hidden_from_opac: bool,
 }
 
 impl From<Item> for ItemShort {
@@ -86,6 +182,414 @@ impl From<Item> for ItemShort {
             borrowable: item.borrowable,
             source_name: item.source_name,
             borrowed: item.borrowed,
+            hidden_from_opac: item.hidden_from_opac,
+        }
+    }
+}
+
+/// Rewrite of a call-number prefix (first matching rule wins).
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CallNumberPrefixRewrite {
+    pub from: String,
+    pub to: String,
+}
+
+/// Rule set applied by `POST /items/recalculate-call-numbers`.
+///
+/// Order: known audience prefix stripped, prefix rewrites, Dewey truncation, audience prefix re-added.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CallNumberRules {
+    #[serde(default)]
+    pub prefix_rewrites: Vec<CallNumberPrefixRewrite>,
+    /// Decimal digits kept on the leading Dewey number (`0` keeps the 3-digit class only).
+    pub dewey_truncation: Option<u8>,
+    /// Prefix per biblio `audienceType` (e.g. `{"children": "J"}`), separated by a space.
+    #[serde(default)]
+    pub audience_prefixes: HashMap<String, String>,
+}
+
+/// Request body for `POST /items/recalculate-call-numbers`.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct RecalculateCallNumbers {
+    pub rules: CallNumberRules,
+    /// Only report the changes (default: true)
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+    /// Restrict to copies of this source
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub source_id: Option<i64>,
+    /// Restrict to call numbers starting with this prefix
+    #[validate(length(max = 200, message = "Call number prefix must be at most 200 characters"))]
+    pub call_number_prefix: Option<String>,
+}
+
+impl CallNumberRules {
+    pub fn is_empty(&self) -> bool {
+        self.prefix_rewrites.is_empty()
+            && self.dewey_truncation.is_none()
+            && self.audience_prefixes.is_empty()
+    }
+
+    /// Recompute a call number for a copy whose biblio has the given `audience_type`.
+    pub fn apply(&self, call_number: &str, audience_type: Option<&str>) -> String {
+        let mut value = call_number.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        // Strip any audience prefix we manage so the rules are idempotent.
+        for prefix in self.audience_prefixes.values().filter(|p| !p.is_empty()) {
+            if let Some(rest) = value.strip_prefix(&format!("{} ", prefix)) {
+                value = rest.to_string();
+                break;
+            }
+        }
+
+        if let Some(rule) = self
+            .prefix_rewrites
+            .iter()
+            .find(|r| !r.from.is_empty() && value.starts_with(&r.from))
+        {
+            value = format!("{}{}", rule.to, &value[rule.from.len()..]);
+        }
+
+        if let Some(digits) = self.dewey_truncation {
+            value = truncate_dewey(&value, digits as usize);
+        }
+
+        let prefix = audience_type
+            .and_then(|a| self.audience_prefixes.get(a))
+            .filter(|p| !p.is_empty());
+        let value = value.trim();
+        match prefix {
+            Some(prefix) if value.is_empty() => prefix.clone(),
+            Some(prefix) => format!("{} {}", prefix, value),
+            None => value.to_string(),
+        }
+    }
+}
+
+/// Truncate the first Dewey-looking token (`ddd` or `ddd.d+`) to `digits` decimals.
+fn truncate_dewey(call_number: &str, digits: usize) -> String {
+    call_number
+        .split(' ')
+        .scan(false, |done, token| {
+            if *done || !is_dewey(token) {
+                return Some(token.to_string());
+            }
+            *done = true;
+            let (class, decimals) = token.split_once('.').unwrap_or((token, ""));
+            let kept: String = decimals.chars().take(digits).collect();
+            Some(if kept.is_empty() { class.to_string() } else { format!("{}.{}", class, kept) })
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_dewey(token: &str) -> bool {
+    let (class, decimals) = token.split_once('.').unwrap_or((token, ""));
+    class.len() == 3
+        && class.chars().all(|c| c.is_ascii_digit())
+        && decimals.chars().all(|c| c.is_ascii_digit())
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+/// Active copy considered for call-number recalculation.
+#[derive(Debug, Clone, FromRow)]
+pub struct CallNumberCandidate {
+    pub item_id: i64,
+    pub biblio_id: i64,
+    pub barcode: Option<String>,
+    pub call_number: String,
+    pub audience_type: Option<String>,
+}
+
+/// One call number that changes (or would change, in dry-run).
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CallNumberChange {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub item_id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub biblio_id: i64,
+    pub barcode: Option<String>,
+    pub before: String,
+    pub after: String,
+}
+
+/// Result of a call-number recalculation.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CallNumberRecalculationReport {
+    pub dry_run: bool,
+    /// Copies matching the filters
+    pub scanned: usize,
+    pub changes: Vec<CallNumberChange>,
+    /// Snapshot of the changed copies before the rewrite (`POST /admin/snapshots/:id/restore`)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub snapshot_id: Option<i64>,
+}
+
+/// File format of `GET /items/export`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemExportFormat {
+    /// One row per copy
+    #[default]
+    Csv,
+    /// One row per copy (Excel workbook, single sheet)
+    Xlsx,
+    /// JSON array, one object per copy
+    Json,
+    /// One record per biblio, active copies as local holdings (ISO2709)
+    Marc21,
+    Unimarc,
+    /// MARC-XML collection (UTF-8)
+    Marcxml,
+}
+
+impl ItemExportFormat {
+    pub fn content_type_filename(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::Csv => ("text/csv; charset=utf-8", "items.csv"),
+            Self::Xlsx => (
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+                "items.xlsx",
+            ),
+            Self::Json => ("application/json", "items.json"),
+            Self::Marc21 | Self::Unimarc => ("application/marc", "catalog.mrc"),
+            Self::Marcxml => ("application/xml", "catalog.xml"),
         }
     }
 }
+
+/// Active copy with the biblio columns written by row-oriented exports
+#[serde_as]
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemExportRow {
+    #[serde_as(as = "DisplayFromStr")]
+    pub item_id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    pub biblio_id: i64,
+    pub barcode: Option<String>,
+    pub call_number: Option<String>,
+    pub volume_designation: Option<String>,
+    pub isbn: Option<String>,
+    pub title: Option<String>,
+    /// "Lastname Firstname" of each author, comma-separated
+    pub authors: String,
+    pub media_type: Option<String>,
+    pub publication_date: Option<String>,
+    pub source: Option<String>,
+    pub state: Option<String>,
+    /// The state of the copy is shown in the OPAC (`item_states.opac_visible`)
+    pub opac_visible: bool,
+    pub borrowable: bool,
+    pub price: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Avery label stock of `GET /items/labels`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum LabelStock {
+    /// A4, 65 labels of 38.1 × 21.2 mm (5 × 13)
+    #[default]
+    #[serde(rename = "l7651")]
+    L7651,
+    /// A4, 80 labels of 35.6 × 16.9 mm (5 × 16)
+    #[serde(rename = "l4732")]
+    L4732,
+    /// A4, 21 labels of 63.5 × 38.1 mm (3 × 7)
+    #[serde(rename = "l7160")]
+    L7160,
+    /// US Letter, 30 labels of 2⅝ × 1 in (3 × 10)
+    #[serde(rename = "5160")]
+    Avery5160,
+    /// US Letter, 80 labels of 1¾ × ½ in (4 × 20)
+    #[serde(rename = "5167")]
+    Avery5167,
+}
+
+/// Sheet geometry of a label stock, in millimetres
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabelSheetLayout {
+    pub page_width: f32,
+    pub page_height: f32,
+    pub columns: u32,
+    pub rows: u32,
+    pub label_width: f32,
+    pub label_height: f32,
+    /// Page edge to the first label
+    pub margin_left: f32,
+    pub margin_top: f32,
+    /// Distance between the origins of two neighbouring labels
+    pub pitch_x: f32,
+    pub pitch_y: f32,
+}
+
+impl LabelSheetLayout {
+    pub fn labels_per_sheet(&self) -> u32 {
+        self.columns * self.rows
+    }
+}
+
+impl LabelStock {
+    pub fn layout(&self) -> LabelSheetLayout {
+        const A4: (f32, f32) = (210.0, 297.0);
+        const LETTER: (f32, f32) = (215.9, 279.4);
+        let (page, columns, rows, label, margin, pitch) = match self {
+            Self::L7651 => (A4, 5, 13, (38.1, 21.2), (4.75, 10.7), (40.6, 21.2)),
+            Self::L4732 => (A4, 5, 16, (35.6, 16.9), (11.0, 13.3), (38.1, 16.9)),
+            Self::L7160 => (A4, 3, 7, (63.5, 38.1), (7.25, 15.15), (66.0, 38.1)),
+            Self::Avery5160 => (LETTER, 3, 10, (66.675, 25.4), (4.7625, 12.7), (69.85, 25.4)),
+            Self::Avery5167 => (LETTER, 4, 20, (44.45, 12.7), (7.62, 12.7), (52.07, 12.7)),
+        };
+        LabelSheetLayout {
+            page_width: page.0,
+            page_height: page.1,
+            columns,
+            rows,
+            label_width: label.0,
+            label_height: label.1,
+            margin_left: margin.0,
+            margin_top: margin.1,
+            pitch_x: pitch.0,
+            pitch_y: pitch.1,
+        }
+    }
+}
+
+/// Standard PDF font used on spine labels (no embedding needed)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum LabelFont {
+    Helvetica,
+    #[default]
+    HelveticaBold,
+    Times,
+    TimesBold,
+    Courier,
+    CourierBold,
+}
+
+impl LabelFont {
+    /// PostScript name of the font
+    pub fn base_font(&self) -> &'static str {
+        match self {
+            Self::Helvetica => "Helvetica",
+            Self::HelveticaBold => "Helvetica-Bold",
+            Self::Times => "Times-Roman",
+            Self::TimesBold => "Times-Bold",
+            Self::Courier => "Courier",
+            Self::CourierBold => "Courier-Bold",
+        }
+    }
+}
+
+/// Query of `GET /items/labels`: which copies to print (at least one filter) and how
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct SpineLabelQuery {
+    /// Shelving location of the copies
+    pub place: Option<i16>,
+    /// Copies created on or after this day (`YYYY-MM-DD`)
+    #[param(value_type = Option<String>)]
+    pub created_from: Option<NaiveDate>,
+    /// Copies created on or before this day (`YYYY-MM-DD`)
+    #[param(value_type = Option<String>)]
+    pub created_to: Option<NaiveDate>,
+    /// `l7651` (default), `l4732`, `l7160`, `5160` or `5167`
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    pub stock: LabelStock,
+    /// `helveticaBold` (default), `helvetica`, `times`, `timesBold`, `courier` or `courierBold`
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    pub font: LabelFont,
+    /// Font size in points (default 10); reduced when the call number lines do not fit
+    pub font_size: Option<f32>,
+    /// Positions already used on the first sheet, filled from the top left
+    #[serde(default)]
+    pub skip: u32,
+}
+
+impl SpineLabelQuery {
+    pub fn has_filter(&self) -> bool {
+        self.place.is_some() || self.created_from.is_some() || self.created_to.is_some()
+    }
+}
+
+/// Inventory number as printed: year and sequence on at least 6 digits (`2026-000042`)
+pub fn format_inventory_number(year: i32, seq: i32) -> String {
+    format!("{year}-{seq:06}")
+}
+
+/// Active copy printed on a spine label
+#[derive(Debug, Clone, FromRow)]
+pub struct SpineLabelRow {
+    pub item_id: i64,
+    pub call_number: String,
+    pub inventory_number: Option<String>,
+}
+
+/// Copy printed on a processing slip (shelf-ready import)
+#[derive(Debug, Clone, FromRow)]
+pub struct ProcessingSlipRow {
+    pub item_id: i64,
+    pub biblio_id: i64,
+    pub barcode: Option<String>,
+    pub call_number: Option<String>,
+    pub volume_designation: Option<String>,
+    pub place: Option<i16>,
+    pub title: Option<String>,
+    pub authors: String,
+    pub isbn: Option<String>,
+    pub inventory_number: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> CallNumberRules {
+        CallNumberRules {
+            prefix_rewrites: vec![CallNumberPrefixRewrite { from: "R ".into(), to: "RP ".into() }],
+            dewey_truncation: Some(1),
+            audience_prefixes: HashMap::from([("children".to_string(), "J".to_string())]),
+        }
+    }
+
+    #[test]
+    fn dewey_truncation() {
+        assert_eq!(truncate_dewey("944.0816 DUR", 1), "944.0 DUR");
+        assert_eq!(truncate_dewey("944.0816 DUR", 0), "944 DUR");
+        assert_eq!(truncate_dewey("BD 741.5 GOS", 2), "BD 741.5 GOS");
+        assert_eq!(truncate_dewey("R DOY", 2), "R DOY");
+    }
+
+    #[test]
+    fn rules_apply_in_order_and_are_idempotent() {
+        let rules = rules();
+        assert_eq!(rules.apply("R  DOY", None), "RP DOY");
+        assert_eq!(rules.apply("944.0816 DUR", Some("children")), "J 944.0 DUR");
+        let once = rules.apply("R 823.914 ROW", Some("children"));
+        assert_eq!(once, "J RP 823.9 ROW");
+        assert_eq!(rules.apply(&once, Some("children")), once);
+        // Audience prefix is removed when the biblio audience no longer maps to one.
+        assert_eq!(rules.apply("J 944.0 DUR", Some("adult")), "944.0 DUR");
+    }
+}
@@ -0,0 +1,49 @@
+//! Item state taxonomy (`items.circulation_status`)
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Known value of `items.circulation_status`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemState {
+    /// Value stored in `items.circulation_status`
+    pub code: i16,
+    pub label: String,
+    /// Copies in this state cannot be checked out unless the loan is forced
+    pub blocks_circulation: bool,
+    /// Copies in this state are shown in the OPAC (public search, record, availability, Z39.50)
+    pub opac_visible: bool,
+    /// Display color (`#RRGGBB`)
+    pub color: Option<String>,
+    pub sort_order: i16,
+    pub created_at: Option<DateTime<Utc>>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+/// Create item state request
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateItemState {
+    pub code: i16,
+    pub label: String,
+    #[serde(default)]
+    pub blocks_circulation: bool,
+    /// Defaults to `true`
+    pub opac_visible: Option<bool>,
+    pub color: Option<String>,
+    pub sort_order: Option<i16>,
+}
+
+/// Update item state request
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateItemState {
+    pub label: Option<String>,
+    pub blocks_circulation: Option<bool>,
+    pub opac_visible: Option<bool>,
+    pub color: Option<String>,
+    pub sort_order: Option<i16>,
+}
@@ -0,0 +1,145 @@
+//! Cataloging templates (`item_templates`): field sets applied on quick record creation
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+use super::biblio::{AudienceType, MediaType};
+
+/// Predefined record and copy fields (unset fields are not part of the template)
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemTemplate {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    /// Value of `POST /items?template=`
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub media_type: Option<MediaType>,
+    pub audience_type: Option<AudienceType>,
+    /// Genre assigned to the record (see `/settings/genres`)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub genre_id: Option<i64>,
+    /// Default shelving location of new copies
+    pub place: Option<i16>,
+    pub borrowable: Option<bool>,
+    /// Item state code of new copies (see `/settings/item-states`)
+    pub circulation_status: Option<i16>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+impl ItemTemplate {
+    /// Fill the fields missing (absent or `null`) from a record creation body: record fields at
+    /// the top level, copy fields in each entry of `items`. Submitted values are kept.
+    pub fn apply(&self, body: &mut Value) {
+        let Some(record) = body.as_object_mut() else {
+            return;
+        };
+        fill(record, "mediaType", self.media_type.as_ref());
+        fill(record, "audienceType", self.audience_type.as_ref());
+        if let Some(items) = record.get_mut("items").and_then(Value::as_array_mut) {
+            for item in items.iter_mut().filter_map(Value::as_object_mut) {
+                fill(item, "place", self.place.as_ref());
+                fill(item, "borrowable", self.borrowable.as_ref());
+                fill(item, "circulationStatus", self.circulation_status.as_ref());
+            }
+        }
+    }
+}
+
+fn fill<T: Serialize>(object: &mut serde_json::Map<String, Value>, key: &str, value: Option<&T>) {
+    let Some(value) = value else {
+        return;
+    };
+    if object.get(key).is_none_or(Value::is_null) {
+        if let Ok(value) = serde_json::to_value(value) {
+            object.insert(key.to_string(), value);
+        }
+    }
+}
+
+/// Create template request
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateItemTemplate {
+    pub code: String,
+    #[serde(flatten)]
+    pub fields: ItemTemplateFields,
+}
+
+/// Template definition; `PUT` replaces every field (omitted ones are cleared)
+#[serde_as]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemTemplateFields {
+    pub name: String,
+    pub description: Option<String>,
+    pub media_type: Option<MediaType>,
+    pub audience_type: Option<AudienceType>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub genre_id: Option<i64>,
+    pub place: Option<i16>,
+    pub borrowable: Option<bool>,
+    pub circulation_status: Option<i16>,
+}
+
+/// Query parameters for `POST /items`
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickCreateQuery {
+    /// Template code whose fields fill the ones missing from the body
+    pub template: Option<String>,
+    /// Allow creating a record even when another has the same ISBN
+    #[serde(default)]
+    pub allow_duplicate_isbn: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn novel() -> ItemTemplate {
+        ItemTemplate {
+            id: 1,
+            code: "novel".to_string(),
+            name: "Novel".to_string(),
+            description: None,
+            media_type: Some(MediaType::PrintedText),
+            audience_type: Some(AudienceType::Adult),
+            genre_id: Some(2),
+            place: Some(3),
+            borrowable: None,
+            circulation_status: Some(0),
+            created_at: None,
+            update_at: None,
+        }
+    }
+
+    #[test]
+    fn template_fills_missing_fields_only() {
+        let mut body = json!({
+            "title": "Germinal",
+            "audienceType": "youngAdult",
+            "items": [{ "barcode": "A1" }, { "barcode": "A2", "place": 7, "circulationStatus": null }]
+        });
+        novel().apply(&mut body);
+        assert_eq!(body["mediaType"], "printedText");
+        assert_eq!(body["audienceType"], "youngAdult");
+        assert_eq!(body["items"][0]["place"], 3);
+        assert_eq!(body["items"][1]["place"], 7);
+        assert_eq!(body["items"][1]["circulationStatus"], 0);
+        assert!(body["items"][0].get("borrowable").is_none());
+    }
+}
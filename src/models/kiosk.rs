@@ -0,0 +1,88 @@
+//! Kiosk tokens for in-library catalog terminals
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// HTTP header carrying the kiosk token
+pub const KIOSK_TOKEN_HEADER: &str = "x-kiosk-token";
+
+/// Prefix of every kiosk token (tells them apart from JWTs in logs and headers)
+pub const KIOSK_TOKEN_PREFIX: &str = "kiosk_";
+
+/// Registered catalog terminal (the token itself is never returned after creation)
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Kiosk {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub name: String,
+    pub location: Option<String>,
+    /// First characters of the token, to recognise it on the terminal
+    pub token_prefix: String,
+    /// Addresses or CIDR ranges the terminal may connect from
+    pub allowed_ips: Vec<String>,
+    pub is_active: bool,
+    /// No expiry when absent
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub last_used_ip: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+/// Kiosk with its token, returned once on creation and on token regeneration
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct KioskWithToken {
+    #[serde(flatten)]
+    pub kiosk: Kiosk,
+    /// Value to configure on the terminal (`X-Kiosk-Token` header); cannot be retrieved later
+    pub token: String,
+}
+
+/// Register a kiosk
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateKiosk {
+    pub name: String,
+    pub location: Option<String>,
+    /// At least one address or CIDR range (e.g. `192.168.10.21`, `192.168.10.0/24`)
+    pub allowed_ips: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Update a kiosk (absent fields are kept; an empty `location` clears it)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateKiosk {
+    pub name: Option<String>,
+    pub location: Option<String>,
+    pub allowed_ips: Option<Vec<String>>,
+    /// `false` revokes the token without deleting the kiosk
+    pub is_active: Option<bool>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Remove the expiry date (ignored when `expiresAt` is set)
+    #[serde(default)]
+    pub clear_expires_at: bool,
+}
+
+/// Identity of the calling terminal (`GET /kiosk/session`)
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct KioskSession {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub kiosk_id: i64,
+    pub name: String,
+    pub location: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,249 @@
+//! Translated display labels (`labels` table) for codes returned by the API
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Language used when neither the user nor `Accept-Language` asks for a translated one
+pub const DEFAULT_LANG: &str = "en";
+
+/// Family of codes a label translates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum LabelKind {
+    /// `biblios.media_type`
+    MediaType,
+    /// `biblios.audience_type`
+    AudienceType,
+    /// `biblios.reading_level`
+    ReadingLevel,
+    /// Patron category (`public_types.name`)
+    PublicType,
+    /// [`crate::models::Genre`] names
+    Genre,
+    /// `account_types.code`
+    AccountType,
+    /// Author roles (`relators.code`)
+    Relator,
+}
+
+impl LabelKind {
+    pub const ALL: [LabelKind; 7] = [
+        LabelKind::MediaType,
+        LabelKind::AudienceType,
+        LabelKind::ReadingLevel,
+        LabelKind::PublicType,
+        LabelKind::Genre,
+        LabelKind::AccountType,
+        LabelKind::Relator,
+    ];
+
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            LabelKind::MediaType => "mediaType",
+            LabelKind::AudienceType => "audienceType",
+            LabelKind::ReadingLevel => "readingLevel",
+            LabelKind::PublicType => "publicType",
+            LabelKind::Genre => "genre",
+            LabelKind::AccountType => "accountType",
+            LabelKind::Relator => "relator",
+        }
+    }
+
+    pub fn from_db_str(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.as_db_str() == s)
+    }
+}
+
+impl std::fmt::Display for LabelKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_db_str())
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for LabelKind {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for LabelKind {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Self::from_db_str(&s).ok_or_else(|| format!("unknown label kind '{}'", s).into())
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for LabelKind {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_db_str().to_string(), buf)
+    }
+}
+
+/// Translation of one code in one language
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Label {
+    pub kind: LabelKind,
+    /// Code as returned by the API (e.g. `printedText`, `adult`, `librarian`)
+    pub code: String,
+    /// ISO 639-1 language code
+    pub lang: String,
+    pub label: String,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+/// Create or replace a translation (`PUT /labels/:kind/:code/:lang`)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpsertLabel {
+    pub label: String,
+}
+
+/// Query parameters for `GET /labels`
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelsQuery {
+    /// Only this kind of code
+    pub kind: Option<LabelKind>,
+    /// ISO 639-1 language (default: the caller's language)
+    pub lang: Option<String>,
+}
+
+/// Labels of one language, grouped by kind then code
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelSet {
+    /// Language the labels are in
+    pub lang: String,
+    /// `kind -> code -> label`
+    pub labels: HashMap<String, HashMap<String, String>>,
+    /// Languages having translations (filled by `GET /labels`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+}
+
+impl LabelSet {
+    pub fn new(lang: impl Into<String>, labels: Vec<Label>) -> Self {
+        let mut grouped: HashMap<String, HashMap<String, String>> = HashMap::new();
+        for label in labels {
+            grouped
+                .entry(label.kind.as_db_str().to_string())
+                .or_default()
+                .insert(label.code, label.label);
+        }
+        Self { lang: lang.into(), labels: grouped, languages: Vec::new() }
+    }
+
+    /// Translated label of `code`, if there is one
+    pub fn get(&self, kind: LabelKind, code: &str) -> Option<&str> {
+        self.labels.get(kind.as_db_str())?.get(code).map(String::as_str)
+    }
+
+    /// Set the label of `code` unless it already has a translation
+    pub fn insert_default(&mut self, kind: LabelKind, code: String, label: String) {
+        self.labels
+            .entry(kind.as_db_str().to_string())
+            .or_default()
+            .entry(code)
+            .or_insert(label);
+    }
+}
+
+/// Normalize a language tag (`fr-FR`, `EN`, `fr_CA`) to its ISO 639-1 primary subtag
+pub fn normalize_lang(tag: &str) -> Option<String> {
+    let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+    (primary.len() == 2 && primary.chars().all(|c| c.is_ascii_lowercase())).then_some(primary)
+}
+
+/// Languages of an `Accept-Language` header, most preferred first (`*` and `q=0` are skipped)
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut tags: Vec<(String, f32)> = header
+        .split(',')
+        .filter_map(|part| {
+            let mut pieces = part.split(';');
+            let tag = pieces.next()?.trim();
+            let q = pieces
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .map(|q| q.trim().parse::<f32>().unwrap_or(0.0))
+                .unwrap_or(1.0);
+            if q <= 0.0 {
+                return None;
+            }
+            normalize_lang(tag).map(|lang| (lang, q))
+        })
+        .collect();
+    // Stable sort keeps the header order between equal weights
+    tags.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let mut langs: Vec<String> = Vec::with_capacity(tags.len());
+    for (lang, _) in tags {
+        if !langs.contains(&lang) {
+            langs.push(lang);
+        }
+    }
+    langs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_language_order() {
+        assert_eq!(parse_accept_language("fr-FR,fr;q=0.9,en-US;q=0.8,en;q=0.7"), vec!["fr", "en"]);
+        assert_eq!(parse_accept_language("en;q=0.5, de, *;q=0.1"), vec!["de", "en"]);
+        assert_eq!(parse_accept_language("es;q=0, it"), vec!["it"]);
+        assert!(parse_accept_language("").is_empty());
+    }
+
+    #[test]
+    fn lang_normalization() {
+        assert_eq!(normalize_lang("fr_CA").as_deref(), Some("fr"));
+        assert_eq!(normalize_lang(" EN ").as_deref(), Some("en"));
+        assert_eq!(normalize_lang("fra"), None);
+        assert_eq!(normalize_lang("*"), None);
+    }
+
+    #[test]
+    fn label_set_lookup() {
+        let set = LabelSet::new(
+            "fr",
+            vec![Label {
+                kind: LabelKind::MediaType,
+                code: "printedText".to_string(),
+                lang: "fr".to_string(),
+                label: "Livre".to_string(),
+                update_at: None,
+            }],
+        );
+        assert_eq!(set.get(LabelKind::MediaType, "printedText"), Some("Livre"));
+        assert_eq!(set.get(LabelKind::AudienceType, "printedText"), None);
+    }
+
+    #[test]
+    fn default_label_keeps_translation() {
+        let mut set = LabelSet::new(
+            "fr",
+            vec![Label {
+                kind: LabelKind::MediaType,
+                code: "printedText".to_string(),
+                lang: "fr".to_string(),
+                label: "Livre".to_string(),
+                update_at: None,
+            }],
+        );
+        set.insert_default(LabelKind::MediaType, "printedText".to_string(), "Book".to_string());
+        set.insert_default(LabelKind::MediaType, "boardGame".to_string(), "Board game".to_string());
+        assert_eq!(set.get(LabelKind::MediaType, "printedText"), Some("Livre"));
+        assert_eq!(set.get(LabelKind::MediaType, "boardGame"), Some("Board game"));
+    }
+}
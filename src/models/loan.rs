@@ -4,9 +4,11 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use sqlx::FromRow;
+use unicode_normalization::UnicodeNormalization;
 use utoipa::ToSchema;
 
 use super::biblio::{Biblio, BiblioShort, MediaType};
+use super::equipment::EquipmentShort;
 use super::user::UserShort;
 
 /// Loan model from database
@@ -18,8 +20,12 @@ pub struct Loan {
     pub id: i64,
     #[serde_as(as = "DisplayFromStr")]
     pub user_id: i64,
-    #[serde_as(as = "DisplayFromStr")]
-    pub item_id: i64,
+    /// Borrowed copy (`items.id`); `None` for equipment loans.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub item_id: Option<i64>,
+    /// Borrowed equipment (`equipment.id`); `None` for copy loans.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub equipment_id: Option<i64>,
     pub date: DateTime<Utc>,
     pub renew_at: Option<DateTime<Utc>>,
     pub nb_renews: Option<i16>,
@@ -28,6 +34,14 @@ pub struct Loan {
     pub returned_at: Option<DateTime<Utc>>,
     pub last_reminder_sent_at: Option<DateTime<Utc>>,
     pub reminder_count: Option<i32>,
+    /// Deposit confirmed at checkout (equipment loans only)
+    pub deposit_received: Option<bool>,
+    /// Group loan batch (`loan_batches.id`) this loan was checked out with.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub batch_id: Option<i64>,
+    /// Item bundle (`item_bundles.id`) this loan was checked out with as part of the set.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub bundle_id: Option<i64>,
 }
 
 /// Loan with full details for display
@@ -38,19 +52,103 @@ pub struct LoanDetails {
     #[serde_as(as = "DisplayFromStr")]
     #[schema(value_type = String)]
     pub id: i64,
-    /// Borrowed specimen (`items.id`).
-    #[serde_as(as = "DisplayFromStr")]
-    #[schema(value_type = String)]
-    pub item_id: i64,
+    /// Borrowed specimen (`items.id`); `null` for equipment loans.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub item_id: Option<i64>,
     pub start_date: DateTime<Utc>,
     pub expiry_at: DateTime<Utc>,
     pub renewal_date: Option<DateTime<Utc>>,
     pub nb_renews: i16,
     pub returned_at: Option<DateTime<Utc>>,
-    pub biblio: BiblioShort,
+    /// Borrowed document; `null` for equipment loans.
+    pub biblio: Option<BiblioShort>,
+    /// Borrowed equipment; `null` for copy loans.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equipment: Option<EquipmentShort>,
     pub user: Option<UserShort>,
     pub item_identification: Option<String>,
     pub is_overdue: bool,
+    /// Field matching the search (`GET /users/:id/loans?q=` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched: Option<LoanMatch>,
+}
+
+/// Loan field matched by a patron loan search
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum LoanMatchField {
+    Title,
+    Author,
+    Barcode,
+}
+
+/// Where a patron loan search matched, with the matched part wrapped in `<mark>`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoanMatch {
+    pub field: LoanMatchField,
+    /// Matched value as stored
+    pub value: String,
+    /// HTML-escaped value with the match in `<mark>...</mark>`
+    pub highlighted: String,
+}
+
+impl LoanMatch {
+    /// First of title, author and barcode containing `query` (ignoring case and accents)
+    pub fn find(query: &str, title: Option<&str>, author: Option<&str>, barcode: Option<&str>) -> Option<Self> {
+        [
+            (LoanMatchField::Title, title),
+            (LoanMatchField::Author, author),
+            (LoanMatchField::Barcode, barcode),
+        ]
+        .into_iter()
+        .find_map(|(field, value)| {
+            let value = value?;
+            let highlighted = highlight(value, query)?;
+            Some(Self { field, value: value.to_string(), highlighted })
+        })
+    }
+}
+
+/// Lowercased, unaccented form of `c` (as the database `normalize_search`)
+fn fold_char(c: char) -> String {
+    c.nfd()
+        .filter(|c| !unicode_normalization::char::is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// `value` with the first occurrence of `query` marked; `None` when it does not occur
+fn highlight(value: &str, query: &str) -> Option<String> {
+    let needle: String = query.trim().chars().map(fold_char).collect();
+    if needle.is_empty() {
+        return None;
+    }
+    // Folded text, with the byte offset in `value` of the character each folded byte comes from
+    let mut folded = String::new();
+    let mut origins = Vec::new();
+    for (offset, c) in value.char_indices() {
+        let f = fold_char(c);
+        origins.extend(std::iter::repeat_n(offset, f.len()));
+        folded.push_str(&f);
+    }
+    let start = folded.find(&needle)?;
+    let end = start + needle.len();
+    let from = origins[start];
+    // End of the last original character covered by the match
+    let last = origins[end - 1];
+    let to = last + value[last..].chars().next().map_or(0, char::len_utf8);
+    Some(format!(
+        "{}<mark>{}</mark>{}",
+        escape_html(&value[..from]),
+        escape_html(&value[from..to]),
+        escape_html(&value[to..])
+    ))
 }
 
 /// Result of [`crate::repository::Repository::loans_return`]: archived loan details and optional hold advanced to `ready`.
@@ -58,6 +156,108 @@ pub struct LoanDetails {
 pub struct LoanReturnOutcome {
     pub details: LoanDetails,
     pub readied_hold: Option<crate::models::hold::Hold>,
+    /// Other parts of the loan's bundle still on loan (empty for standalone copies)
+    pub missing_bundle_parts: Vec<crate::models::bundle::BundlePart>,
+    /// Floating decision of a copy checked in at another place (set by the service)
+    pub routing: Option<ReturnRouting>,
+    /// The loan is returned but its copy could not be routed: staff must route it by hand
+    pub routing_failed: bool,
+}
+
+/// Where a copy checked in at another place than its own goes (`circulation.floating_rules`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReturnRoutingAction {
+    /// The copy stays at the return place, which becomes its place
+    Float,
+    /// The copy travels back to its home place (`transitPlace` until received there)
+    TransitHome,
+}
+
+/// Routing of a copy checked in at another place than its own. The action is applied at once,
+/// unless `promptOverride`: the copy then waits for the staff answer (`POST /items/{id}/routing`).
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnRouting {
+    pub action: ReturnRoutingAction,
+    pub home_place: i16,
+    pub return_place: i16,
+    /// Floating rule that decided; `null` when none matched (the copy goes home)
+    pub rule: Option<String>,
+    /// The staff chose the action instead of the rule
+    pub overridden: bool,
+    /// The rule asks the staff to confirm: offer both actions (`POST /items/{id}/routing`)
+    pub prompt_override: bool,
+}
+
+/// Copy facts the floating rules match on, with its current routing state
+#[derive(Debug, Clone, Default, FromRow)]
+pub struct ItemFloatingFacts {
+    /// Place of the copy; copies without a place never float
+    pub home_place: Option<i16>,
+    pub media_type: Option<String>,
+    /// Keys of the record's collections
+    pub collection_keys: Vec<String>,
+    pub on_loan: bool,
+    /// Home place the copy is travelling back to
+    pub transit_place: Option<i16>,
+    /// Return place of a routing waiting for the staff answer
+    pub routing_place: Option<i16>,
+}
+
+/// Reason a loan cannot be renewed (`GET /loans/:id/renewability`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RenewalBlockCode {
+    /// The loan is already returned
+    LoanReturned,
+    /// Renewal limit of the loan settings (or of the equipment) reached
+    MaxRenewalsReached,
+    /// Another patron has a hold queued on the copy
+    HoldQueued,
+    /// The copy belongs to a closed deposit and must go back to its owner
+    ItemRequestedBack,
+    /// Borrower's membership (`users.expiry_at`) in the past
+    MembershipExpired,
+    /// Borrower's account blocked or otherwise not allowed to borrow
+    AccountBlocked,
+}
+
+/// One renewal block with a message for the patron or librarian
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RenewalBlock {
+    pub code: RenewalBlockCode,
+    pub message: String,
+}
+
+/// Whether a loan can be renewed now, with every rule preventing it
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoanRenewability {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub loan_id: i64,
+    pub renewable: bool,
+    /// Empty when `renewable`
+    pub blocks: Vec<RenewalBlock>,
+    pub renew_count: i16,
+    pub max_renewals: i16,
+    pub expiry_at: Option<DateTime<Utc>>,
+    /// Due date a renewal made now would give
+    pub new_expiry_at: DateTime<Utc>,
+}
+
+impl LoanRenewability {
+    pub fn block(&mut self, code: RenewalBlockCode, message: String) {
+        self.blocks.push(RenewalBlock { code, message });
+        self.renewable = false;
+    }
+
+    pub fn is_blocked_by(&self, code: RenewalBlockCode) -> bool {
+        self.blocks.iter().any(|b| b.code == code)
+    }
 }
 
 /// How the new due date is computed when a loan is renewed (`loans_settings.renew_at`).
@@ -123,8 +323,75 @@ pub struct CreateLoan {
     pub user_id: i64,
     #[serde_as(as = "Option<DisplayFromStr>")]
     pub item_id: Option<i64>,
+    /// Equipment to check out instead of a copy.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub equipment_id: Option<i64>,
+    /// Barcode of a copy or of a piece of equipment.
     pub item_identification: Option<String>,
     pub force: bool,
+    /// Staff confirms the deposit was received (required for equipment with `requiresDeposit`).
+    #[serde(default)]
+    pub deposit_received: bool,
+    /// Check out despite the patron's checkout blocks; set by the API only for callers with
+    /// `circulation_override_rights` (never taken from a request body).
+    #[serde(skip)]
+    pub override_blocks: bool,
+}
+
+/// Reason a patron cannot borrow (`GET /users/:id/checkout-blocks`, 422 `checkout_blocked` body)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckoutBlockCode {
+    /// Account blocked or otherwise not allowed to borrow
+    AccountBlocked,
+    /// Membership (`users.expiry_at`) in the past
+    MembershipExpired,
+    /// At least `circulation.max_overdue_loans` loans overdue
+    OverdueLoans,
+    /// Unpaid fines above `circulation.max_unpaid_fines`
+    UnpaidFines,
+    /// Pending blocking patron message
+    BlockingMessage,
+}
+
+impl CheckoutBlockCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AccountBlocked => "account_blocked",
+            Self::MembershipExpired => "membership_expired",
+            Self::OverdueLoans => "overdue_loans",
+            Self::UnpaidFines => "unpaid_fines",
+            Self::BlockingMessage => "blocking_message",
+        }
+    }
+}
+
+/// One checkout block with a message for the librarian
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutBlock {
+    pub code: CheckoutBlockCode,
+    pub message: String,
+}
+
+/// Body returned on 422 when a checkout is refused by one or more blocks
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutBlockedResponse {
+    /// Always `checkout_blocked`
+    pub code: String,
+    pub message: String,
+    pub blocks: Vec<CheckoutBlock>,
+}
+
+/// Result of [`crate::services::loans::LoansService::create_loan`]
+#[derive(Debug, Clone)]
+pub struct LoanCheckoutOutcome {
+    pub loan_id: i64,
+    pub expiry_at: DateTime<Utc>,
+    /// Blocks the checkout went through (`override_blocks`), to audit
+    pub overridden_blocks: Vec<CheckoutBlock>,
 }
 
 /// Loan settings: `nb_max` on the default row (`media_type` IS NULL) caps **all** active loans;
@@ -184,6 +451,8 @@ pub struct LoanArchive {
     pub id: i64,
     #[serde_as(as = "Option<DisplayFromStr>")]
     pub item_id: Option<i64>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub equipment_id: Option<i64>,
     pub date: DateTime<Utc>,
     pub nb_renews: Option<i16>,
     pub expiry_at: Option<DateTime<Utc>>,
@@ -193,3 +462,23 @@ pub struct LoanArchive {
     pub addr_city: Option<String>,
     pub account_type: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_ignores_case_and_accents() {
+        let found = LoanMatch::find("asterix", Some("Astérix & Obélix"), None, Some("B-001")).unwrap();
+        assert_eq!(found.field, LoanMatchField::Title);
+        assert_eq!(found.highlighted, "<mark>Astérix</mark> &amp; Obélix");
+
+        let found = LoanMatch::find("goscinny", Some("Astérix"), Some("René Goscinny"), None).unwrap();
+        assert_eq!((found.field, found.highlighted.as_str()), (LoanMatchField::Author, "René <mark>Goscinny</mark>"));
+
+        let found = LoanMatch::find("b-00", Some("Astérix"), None, Some("B-001")).unwrap();
+        assert_eq!(found.highlighted, "<mark>B-00</mark>1");
+
+        assert!(LoanMatch::find("tintin", Some("Astérix"), None, Some("B-001")).is_none());
+    }
+}
@@ -0,0 +1,120 @@
+//! Group loans: batches of copies checked out to a group account with one shared due date
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Maximum days to the shared due date when the account type sets no `group_loans_max_days`.
+pub const DEFAULT_GROUP_LOAN_MAX_DAYS: i16 = 42;
+
+/// Group loan batch with its copies
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoanBatch {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    /// Group account the copies are checked out to
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    pub label: Option<String>,
+    /// Shared due date of the loans still out
+    pub due_at: DateTime<Utc>,
+    pub notes: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub update_at: Option<DateTime<Utc>>,
+    /// Copies still out
+    pub active_loans: i64,
+    /// Copies already returned
+    pub returned_loans: i64,
+    /// Copies of the batch (filled on detail responses only)
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub loans: Vec<LoanBatchLine>,
+}
+
+/// One copy of a batch, active or returned
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoanBatchLine {
+    /// `loans.id` until archived, then `loans_archives.id` (archival policy)
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub loan_id: i64,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub item_id: Option<i64>,
+    pub barcode: Option<String>,
+    pub title: Option<String>,
+    pub expiry_at: Option<DateTime<Utc>>,
+    pub returned_at: Option<DateTime<Utc>>,
+}
+
+/// Check out copies to a group account
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateLoanBatch {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+    pub label: Option<String>,
+    /// Shared due date; defaults to today + the account type `groupLoansMaxDays`
+    pub due_date: Option<NaiveDate>,
+    /// Copy barcodes
+    pub barcodes: Vec<String>,
+    pub notes: Option<String>,
+    /// Override copy-level rules (not borrowable, blocking state, hold queue)
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Move the due date of every copy still out
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendLoanBatch {
+    pub due_date: NaiveDate,
+}
+
+/// Query parameters for listing batches
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoanBatchQuery {
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    #[schema(value_type = Option<String>)]
+    pub user_id: Option<i64>,
+    /// Only batches with copies still out
+    pub active_only: Option<bool>,
+}
+
+/// Result of returning a whole batch
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoanBatchReturnReport {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub batch_id: i64,
+    pub returned: usize,
+}
+
+/// Group loan limits of the borrower's account type
+#[derive(Debug, Clone, FromRow)]
+pub struct GroupLoanLimits {
+    pub account_type: String,
+    pub max_items: Option<i16>,
+    pub max_days: Option<i16>,
+}
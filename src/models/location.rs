@@ -0,0 +1,220 @@
+//! Shelving locations (`shelving_locations`): site → room → shelf range
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Level of a shelving location
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum LocationKind {
+    /// A place of the library (`items.place`): branch, bookmobile...
+    Site,
+    /// Room or area of a site
+    Room,
+    /// Shelves of a room holding a run of call numbers
+    ShelfRange,
+}
+
+impl LocationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Site => "site",
+            Self::Room => "room",
+            Self::ShelfRange => "shelfRange",
+        }
+    }
+
+    /// Level a location of this kind is attached to (`None` for sites)
+    pub fn parent_kind(&self) -> Option<Self> {
+        match self {
+            Self::Site => None,
+            Self::Room => Some(Self::Site),
+            Self::ShelfRange => Some(Self::Room),
+        }
+    }
+}
+
+impl From<String> for LocationKind {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "site" => Self::Site,
+            "room" => Self::Room,
+            _ => Self::ShelfRange,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for LocationKind {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for LocationKind {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for LocationKind {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// One node of the shelving map
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShelvingLocation {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    /// Site of a room, room of a shelf range (`null` for sites)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub parent_id: Option<i64>,
+    pub kind: LocationKind,
+    /// Short code, matched against the first word of call numbers by the mapping assistant (`R`, `BD`)
+    #[schema(example = "BD")]
+    pub code: Option<String>,
+    #[schema(example = "Comics")]
+    pub name: String,
+    /// Place of the site (`items.place`), shared by its rooms and shelf ranges
+    pub place: i16,
+    /// Names from the site down to this location (`Main library › Youth › Comics`)
+    #[sqlx(default)]
+    #[schema(example = "Main library › Youth › Comics")]
+    pub path: String,
+    /// Active copies shelved here (not counting the child locations)
+    #[sqlx(default)]
+    pub item_count: i64,
+    pub sort_order: i16,
+    pub created_at: Option<DateTime<Utc>>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+/// Location with its child locations (`GET /settings/locations/tree`)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationNode {
+    #[serde(flatten)]
+    pub location: ShelvingLocation,
+    pub children: Vec<LocationNode>,
+}
+
+impl LocationNode {
+    /// Nest a flat list under the sites, keeping its order among siblings
+    pub fn build_tree(locations: Vec<ShelvingLocation>) -> Vec<LocationNode> {
+        fn nest(parent: Option<i64>, by_parent: &mut HashMap<Option<i64>, Vec<ShelvingLocation>>) -> Vec<LocationNode> {
+            by_parent
+                .remove(&parent)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|location| LocationNode { children: nest(Some(location.id), by_parent), location })
+                .collect()
+        }
+
+        let mut by_parent: HashMap<Option<i64>, Vec<ShelvingLocation>> = HashMap::new();
+        for location in locations {
+            by_parent.entry(location.parent_id).or_default().push(location);
+        }
+        nest(None, &mut by_parent)
+    }
+}
+
+/// Query parameters of `GET /settings/locations` and `GET /settings/locations/tree`
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct LocationQuery {
+    /// Only the locations of this place
+    pub place: Option<i16>,
+}
+
+/// Create location request
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateLocation {
+    pub kind: LocationKind,
+    /// Required for rooms (a site) and shelf ranges (a room); only set on creation
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub parent_id: Option<i64>,
+    pub code: Option<String>,
+    pub name: String,
+    /// Required for sites (`items.place` of their copies); rooms and shelf ranges take their site's
+    pub place: Option<i16>,
+    pub sort_order: Option<i16>,
+}
+
+/// Update location request: absent fields are kept, an empty `code` clears it
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateLocation {
+    pub code: Option<String>,
+    pub name: Option<String>,
+    pub sort_order: Option<i16>,
+}
+
+/// Copies of a place not yet given a location, grouped by the first word of their call number
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationMappingRow {
+    pub place: Option<i16>,
+    /// First word of the call numbers (`R` for `R DOY`); `""` for copies without call number
+    #[schema(example = "BD")]
+    pub prefix: String,
+    pub item_count: i64,
+    /// Location of the same site whose `code` is the prefix, if any
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub suggested_location_id: Option<i64>,
+    #[schema(example = "Main library › Youth › Comics")]
+    pub suggested_path: Option<String>,
+}
+
+/// One decision of the mapping assistant
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationMapping {
+    pub place: Option<i16>,
+    pub prefix: String,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub location_id: i64,
+}
+
+/// Apply mapping decisions (`POST /settings/locations/mapping`)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyLocationMappings {
+    pub mappings: Vec<LocationMapping>,
+}
+
+/// Result of the mapping assistant
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationMappingReport {
+    /// Copies given a location
+    pub updated: i64,
+    /// Copies still without a location, all places included
+    pub remaining: i64,
+}
+
@@ -0,0 +1,84 @@
+//! Media type taxonomy (`biblios.media_type`)
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Known value of `biblios.media_type`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaTypeDefinition {
+    /// Value stored in `biblios.media_type` (camelCase, e.g. `printedText`)
+    pub code: String,
+    /// Terse code of older versions (`b`, `vd`, ...), still accepted as input
+    pub legacy_code: Option<String>,
+    /// Default label; translations are managed through `/labels`
+    pub label: String,
+    /// Icon name for clients
+    pub icon: Option<String>,
+    /// Loan duration (days) when `loans_settings` has no row for this media type
+    pub loan_duration: Option<i16>,
+    /// Concurrent loans of this media type when `loans_settings` has no row for it
+    pub loan_nb_max: Option<i16>,
+    /// Renewals when `loans_settings` has no row for this media type
+    pub loan_nb_renews: Option<i16>,
+    /// MARC leader/06 (type of record) written for, and recognised on, these records
+    #[schema(example = "a")]
+    pub marc_record_type: String,
+    /// MARC leader/07 (bibliographic level)
+    #[schema(example = "m")]
+    pub marc_bibliographic_level: String,
+    /// VAT rate (percent) included in the prices of these copies, for acquisition reporting
+    #[schema(value_type = Option<String>, example = "5.50")]
+    pub vat_rate: Option<Decimal>,
+    pub sort_order: i16,
+    pub created_at: Option<DateTime<Utc>>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+/// Create media type request
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMediaType {
+    /// camelCase identifier (letters and digits, starting with a lowercase letter)
+    pub code: String,
+    pub label: String,
+    pub icon: Option<String>,
+    pub loan_duration: Option<i16>,
+    pub loan_nb_max: Option<i16>,
+    pub loan_nb_renews: Option<i16>,
+    /// Default `a` (language material)
+    pub marc_record_type: Option<String>,
+    /// Default `m` (monograph)
+    pub marc_bibliographic_level: Option<String>,
+    /// VAT rate (percent, 0 to 99.99) included in the prices of these copies
+    #[serde(default)]
+    #[schema(value_type = Option<String>, example = "5.50")]
+    pub vat_rate: Option<Decimal>,
+    pub sort_order: Option<i16>,
+}
+
+/// Update media type request (absent fields are kept; an empty `icon` clears it)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMediaType {
+    pub label: Option<String>,
+    pub icon: Option<String>,
+    pub loan_duration: Option<i16>,
+    pub loan_nb_max: Option<i16>,
+    pub loan_nb_renews: Option<i16>,
+    /// Remove the default loan rules (ignored for the ones given in this request)
+    #[serde(default)]
+    pub clear_loan_rules: bool,
+    pub marc_record_type: Option<String>,
+    pub marc_bibliographic_level: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>, example = "5.50")]
+    pub vat_rate: Option<Decimal>,
+    /// Remove the VAT rate (ignored when `vatRate` is given)
+    #[serde(default)]
+    pub clear_vat_rate: bool,
+    pub sort_order: Option<i16>,
+}
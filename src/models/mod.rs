@@ -1,26 +1,59 @@
 //! Data models for Elidune
 
 pub mod account_type;
+pub mod api_key;
+pub mod artifact;
 pub mod audit;
 pub mod author;
+pub mod backup;
 pub mod biblio;
 pub mod biblio_author;
+pub mod bundle;
+pub mod campaign;
+pub mod citation;
+pub mod dashboard;
+pub mod deposit;
+pub mod donation;
+pub mod email_health;
+pub mod eod;
 pub mod enums;
 pub mod equipment;
 pub mod event;
+pub mod event_envelope;
 pub mod fine;
+pub mod harvest;
+pub mod heading;
 pub mod import_report;
 pub mod inventory;
 pub mod item;
+pub mod item_state;
+pub mod item_template;
+pub mod label;
+pub mod kiosk;
 pub mod loan;
+pub mod loan_batch;
+pub mod location;
+pub mod media_type;
+pub mod notification;
+pub mod payment;
 pub mod public_type;
+pub mod purchase_suggestion;
+pub mod reading_program;
+pub mod reengagement;
+pub mod relator;
 pub mod hold;
 pub mod schedule;
+pub mod sitemap;
+pub mod snapshot;
+pub mod staffing;
 pub mod stats_builder;
 pub mod source;
 pub mod task;
 pub mod user;
+pub mod user_message;
+pub mod vendor;
 pub mod visitor_count;
+pub mod warehouse;
 
 // Re-export commonly used types
 pub use author::Author;
@@ -116,6 +149,45 @@ impl Language {
             Language::Malay => "malay",
         }
     }
+
+    /// ISO 639-1 code (`None` for [`Language::Unknown`])
+    pub fn iso_639_1(&self) -> Option<&'static str> {
+        Some(match self {
+            Language::Unknown => return None,
+            Language::French => "fr",
+            Language::English => "en",
+            Language::German => "de",
+            Language::Spanish => "es",
+            Language::Italian => "it",
+            Language::Portuguese => "pt",
+            Language::Japanese => "ja",
+            Language::Chinese => "zh",
+            Language::Russian => "ru",
+            Language::Arabic => "ar",
+            Language::Dutch => "nl",
+            Language::Swedish => "sv",
+            Language::Norwegian => "no",
+            Language::Danish => "da",
+            Language::Finnish => "fi",
+            Language::Polish => "pl",
+            Language::Czech => "cs",
+            Language::Hungarian => "hu",
+            Language::Romanian => "ro",
+            Language::Turkish => "tr",
+            Language::Korean => "ko",
+            Language::Latin => "la",
+            Language::Greek => "el",
+            Language::Croatian => "hr",
+            Language::Hindi => "hi",
+            Language::Hebrew => "he",
+            Language::Persian => "fa",
+            Language::Catalan => "ca",
+            Language::Thai => "th",
+            Language::Vietnamese => "vi",
+            Language::Indonesian => "id",
+            Language::Malay => "ms",
+        })
+    }
 }
 
 impl From<&str> for Language {
@@ -0,0 +1,154 @@
+//! In-app notifications (`notifications`): each user's inbox, fed by the events that also send emails
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Event a notification reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationKind {
+    /// A background task started by the recipient completed (`entityType`: `task`)
+    TaskCompleted,
+    /// A background task started by the recipient failed (`entityType`: `task`)
+    TaskFailed,
+    /// The report of a MARC batch import is available (`entityType`: `artifact`)
+    ImportReportReady,
+    /// Loans reached `reminders.escalation_after` overdue reminders (staff with loan rights)
+    OverdueEscalation,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TaskCompleted => "taskCompleted",
+            Self::TaskFailed => "taskFailed",
+            Self::ImportReportReady => "importReportReady",
+            Self::OverdueEscalation => "overdueEscalation",
+        }
+    }
+}
+
+impl FromStr for NotificationKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "taskCompleted" => Ok(Self::TaskCompleted),
+            "taskFailed" => Ok(Self::TaskFailed),
+            "importReportReady" => Ok(Self::ImportReportReady),
+            "overdueEscalation" => Ok(Self::OverdueEscalation),
+            _ => Err(format!("Unknown notification kind '{}'", s)),
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for NotificationKind {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for NotificationKind {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(s.parse()?)
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for NotificationKind {
+    fn encode_by_ref(
+        &self,
+        buf: &mut sqlx::postgres::PgArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Notification of the caller's inbox
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+    pub kind: NotificationKind,
+    /// English fallback text; clients can localize from `kind` and `data`
+    pub title: String,
+    pub body: Option<String>,
+    /// What the notification links to (`task`, `artifact`, …)
+    pub entity_type: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub entity_id: Option<i64>,
+    /// Kind-specific details (task kind and result, report counts, escalated loans, …)
+    #[schema(value_type = Option<Object>)]
+    pub data: Option<Value>,
+    pub created_at: DateTime<Utc>,
+    /// `null` while unread
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+/// Notification to write (server-side only)
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewNotification {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: Option<String>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i64>,
+    pub data: Option<Value>,
+}
+
+/// Query parameters of `GET /notifications`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct NotificationQuery {
+    /// Only unread notifications
+    #[serde(default)]
+    pub unread_only: bool,
+    pub page: Option<i64>,
+    /// Default 20, at most 200
+    pub per_page: Option<i64>,
+}
+
+/// Page of the caller's notifications, newest first
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationsPage {
+    pub notifications: Vec<Notification>,
+    /// Notifications matching the query
+    pub total: i64,
+    /// Unread notifications of the caller (whatever the query)
+    pub unread: i64,
+}
+
+/// Unread notifications of the caller (`GET /notifications/unread-count`)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UnreadNotificationCount {
+    pub unread: i64,
+}
+
+/// Notifications marked as read (`POST /notifications/read-all`)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationsMarkedRead {
+    pub updated: u64,
+}
@@ -0,0 +1,390 @@
+//! Cash register models: payments taken at the front desk, refunds and daily cash-up
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// How a payment was made
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PaymentMethod {
+    Cash,
+    Card,
+    Cheque,
+    Transfer,
+    Other,
+}
+
+impl PaymentMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cash => "cash",
+            Self::Card => "card",
+            Self::Cheque => "cheque",
+            Self::Transfer => "transfer",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl From<String> for PaymentMethod {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "cash" => Self::Cash,
+            "card" => Self::Card,
+            "cheque" => Self::Cheque,
+            "transfer" => Self::Transfer,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for PaymentMethod {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for PaymentMethod {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for PaymentMethod {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// What a payment is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PaymentCategory {
+    /// Overdue fine (`fineId` required); the fine balance is updated
+    Fine,
+    /// Membership renewal (`userId` required); the membership expiry is extended
+    Membership,
+    Printing,
+    Other,
+}
+
+impl PaymentCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fine => "fine",
+            Self::Membership => "membership",
+            Self::Printing => "printing",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl From<String> for PaymentCategory {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "fine" => Self::Fine,
+            "membership" => Self::Membership,
+            "printing" => Self::Printing,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for PaymentCategory {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for PaymentCategory {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for PaymentCategory {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Money taken (positive amount) or given back (negative amount, `refundOf` set)
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Payment {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    /// Sequential receipt number, restarting every year (refunds get their own number)
+    #[schema(example = "2026-000042")]
+    pub receipt_number: String,
+    /// Paying patron (none for anonymous sales such as printing)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub user_id: Option<i64>,
+    #[schema(value_type = String, example = "2.50")]
+    pub amount: Decimal,
+    pub method: PaymentMethod,
+    pub category: PaymentCategory,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub fine_id: Option<i64>,
+    /// Membership expiry after a renewal payment
+    pub membership_expiry_at: Option<DateTime<Utc>>,
+    /// Payment reversed by this refund
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub refund_of: Option<i64>,
+    /// Total already refunded on this payment
+    #[schema(value_type = String, example = "0.00")]
+    pub refunded_amount: Decimal,
+    pub notes: Option<String>,
+    /// Staff member who took the payment
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub operator_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Record a payment
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordPayment {
+    /// Paying patron; taken from the fine for `fine` payments, required for `membership`
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub user_id: Option<i64>,
+    /// Required except for `membership`, which defaults to the subscription price of the
+    /// patron's public type
+    #[schema(value_type = Option<String>, example = "2.50")]
+    pub amount: Option<Decimal>,
+    pub method: PaymentMethod,
+    pub category: PaymentCategory,
+    /// Fine paid (required for, and only allowed with, `fine`)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub fine_id: Option<i64>,
+    pub notes: Option<String>,
+}
+
+/// Refund all or part of a payment
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RefundPayment {
+    /// Defaults to what is left to refund
+    #[schema(value_type = Option<String>, example = "2.50")]
+    pub amount: Option<Decimal>,
+    /// Defaults to the method of the payment
+    pub method: Option<PaymentMethod>,
+    pub notes: Option<String>,
+}
+
+/// Subscription terms of a patron's public type
+#[derive(Debug, Clone, Default, FromRow)]
+pub struct MembershipTerms {
+    pub duration_days: Option<i32>,
+    /// Price in cents
+    pub price_cents: Option<i32>,
+}
+
+/// Query parameters of `GET /payments` (days are in library local time, both included)
+#[serde_as]
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct PaymentQuery {
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[param(value_type = Option<String>)]
+    pub user_id: Option<i64>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[param(value_type = Option<String>)]
+    pub fine_id: Option<i64>,
+    pub category: Option<PaymentCategory>,
+    pub method: Option<PaymentMethod>,
+    /// First day (YYYY-MM-DD)
+    #[param(value_type = Option<String>)]
+    pub start_date: Option<NaiveDate>,
+    /// Last day (YYYY-MM-DD)
+    #[param(value_type = Option<String>)]
+    pub end_date: Option<NaiveDate>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+/// Page of payments, newest first
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentsPage {
+    pub payments: Vec<Payment>,
+    pub total: i64,
+}
+
+/// Query parameters of `GET /payments/daily-summary`
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct DailySummaryQuery {
+    /// Day to cash up (YYYY-MM-DD, library local time); defaults to today
+    #[param(value_type = Option<String>)]
+    pub date: Option<NaiveDate>,
+}
+
+/// Grouping of the cash-up totals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CashTotalsBy {
+    Method,
+    Category,
+}
+
+/// Totals of one payment method or category
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CashTotal {
+    /// Method or category
+    pub key: String,
+    #[schema(value_type = String)]
+    pub received: Decimal,
+    #[schema(value_type = String)]
+    pub refunded: Decimal,
+    /// `received - refunded`
+    #[schema(value_type = String)]
+    pub net: Decimal,
+    /// Number of payments and refunds
+    pub count: i64,
+}
+
+/// Totals taken by one operator
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorCashTotal {
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub operator_id: Option<i64>,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    #[schema(value_type = String)]
+    pub received: Decimal,
+    #[schema(value_type = String)]
+    pub refunded: Decimal,
+    #[schema(value_type = String)]
+    pub net: Decimal,
+    pub count: i64,
+}
+
+/// Cash-up of one day (`GET /payments/daily-summary`)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyCashSummary {
+    pub date: NaiveDate,
+    #[schema(value_type = String)]
+    pub received: Decimal,
+    #[schema(value_type = String)]
+    pub refunded: Decimal,
+    #[schema(value_type = String)]
+    pub net: Decimal,
+    pub count: i64,
+    /// Compare the `cash` line with the drawer
+    pub by_method: Vec<CashTotal>,
+    pub by_category: Vec<CashTotal>,
+    pub by_operator: Vec<OperatorCashTotal>,
+}
+
+/// Receipt number as printed: year and sequence on at least 6 digits (`2026-000042`)
+pub fn format_receipt_number(year: i32, seq: i32) -> String {
+    format!("{year}-{seq:06}")
+}
+
+/// Query parameters of `GET /payments/export`
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct PaymentExportQuery {
+    /// Month to export (YYYY-MM, library local time); must be over
+    #[param(example = "2026-09")]
+    pub month: String,
+}
+
+/// Payment line of the accounting export
+#[derive(Debug, Clone, FromRow)]
+pub struct PaymentExportRow {
+    pub receipt_year: i16,
+    pub receipt_seq: i32,
+    pub receipt_number: String,
+    pub created_at: DateTime<Utc>,
+    pub amount: Decimal,
+    pub method: PaymentMethod,
+    pub category: PaymentCategory,
+    /// Receipt number of the refunded payment
+    pub refund_of_receipt: Option<String>,
+    /// "Lastname Firstname" of the paying patron
+    pub payer: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Month exported to the accounting software: its payments can no longer be changed
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentPeriodLock {
+    /// Locked month (YYYY-MM)
+    #[schema(example = "2026-09")]
+    pub month: String,
+    /// Start of the month (library local time)
+    pub starts_at: DateTime<Utc>,
+    /// Start of the following month
+    pub ends_at: DateTime<Utc>,
+    /// Payments and refunds exported
+    pub payments_count: i32,
+    /// Net total exported
+    #[schema(value_type = String)]
+    pub total: Decimal,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub locked_by: Option<i64>,
+    pub locked_at: DateTime<Utc>,
+}
+
+/// Query parameters of `GET /payments/receipt-gaps`
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ReceiptGapsQuery {
+    /// Defaults to the current year
+    pub year: Option<i32>,
+}
+
+/// Receipt numbers issued in a year and the ones missing from the sequence
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptGapReport {
+    pub year: i32,
+    /// Receipts found
+    pub issued: i64,
+    /// Last number handed out by the counter
+    pub last_number: Option<String>,
+    /// Numbers handed out but not found (should always be empty)
+    pub missing: Vec<String>,
+}
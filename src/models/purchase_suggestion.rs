@@ -0,0 +1,149 @@
+//! Purchase suggestion models (titles suggested by patrons, staff triage)
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Triage state of a purchase suggestion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PurchaseSuggestionStatus {
+    /// Not reviewed yet
+    Pending,
+    /// Placed on an acquisitions order
+    Ordered,
+    /// Declined (a reason is given to the patron)
+    Rejected,
+    /// Title is in the catalog
+    Added,
+}
+
+impl PurchaseSuggestionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Ordered => "ordered",
+            Self::Rejected => "rejected",
+            Self::Added => "added",
+        }
+    }
+}
+
+impl From<String> for PurchaseSuggestionStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "ordered" => Self::Ordered,
+            "rejected" => Self::Rejected,
+            "added" => Self::Added,
+            _ => Self::Pending,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for PurchaseSuggestionStatus {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for PurchaseSuggestionStatus {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for PurchaseSuggestionStatus {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Title suggested for purchase by a patron
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PurchaseSuggestion {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    /// Patron who made the suggestion
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+    /// Normalized ISBN (digits and `X` only)
+    pub isbn: Option<String>,
+    pub title: String,
+    pub author: Option<String>,
+    pub notes: Option<String>,
+    pub status: PurchaseSuggestionStatus,
+    /// Reason given to the patron (`rejected` only)
+    pub rejection_reason: Option<String>,
+    /// Acquisitions order the title was placed on
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub purchase_order_id: Option<i64>,
+    /// Catalog record of the title once acquired
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub biblio_id: Option<i64>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub triaged_by: Option<i64>,
+    pub triaged_at: Option<DateTime<Utc>>,
+    /// When the patron was told the title is available
+    pub notified_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Suggest a title for purchase (`POST /opac/suggestions`)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePurchaseSuggestion {
+    pub isbn: Option<String>,
+    pub title: String,
+    pub author: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Triage decision for a purchase suggestion.
+///
+/// `rejected` requires `rejectionReason`, `added` requires `biblioId`; `ordered` may link the
+/// acquisitions order with `purchaseOrderId`. A suggestion already added cannot be triaged again.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TriagePurchaseSuggestion {
+    pub status: PurchaseSuggestionStatus,
+    pub rejection_reason: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub purchase_order_id: Option<i64>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub biblio_id: Option<i64>,
+}
+
+/// Query parameters for listing purchase suggestions
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PurchaseSuggestionQuery {
+    pub status: Option<PurchaseSuggestionStatus>,
+    /// Only suggestions of this patron
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    #[schema(value_type = Option<String>)]
+    pub user_id: Option<i64>,
+}
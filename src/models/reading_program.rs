@@ -0,0 +1,183 @@
+//! Reading program models (summer reading challenge: enrollments, reading log, statistics)
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Reading program definition
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingProgram {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// Number of books to read within the program dates to complete it
+    pub goal_books: i16,
+    /// Only entries tied to a returned loan count towards the goal
+    pub loans_only: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+/// Create reading program request
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateReadingProgram {
+    pub name: String,
+    pub description: Option<String>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub goal_books: i16,
+    #[serde(default)]
+    pub loans_only: bool,
+}
+
+/// Update reading program request
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateReadingProgram {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub goal_books: Option<i16>,
+    pub loans_only: Option<bool>,
+}
+
+/// Enrolled patron with progress towards the program goal
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingEnrollment {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub program_id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    pub enrolled_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Entries counting towards the goal
+    pub books_read: i64,
+    pub goal_books: i16,
+}
+
+/// Enroll a patron in a program
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EnrollReader {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+}
+
+/// Book logged by an enrolled patron
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingEntry {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub enrollment_id: i64,
+    pub read_at: NaiveDate,
+    pub title: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub biblio_id: Option<i64>,
+    /// Returned loan this entry comes from
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub loan_archive_id: Option<i64>,
+    pub notes: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Log a book for an enrolled patron.
+///
+/// Give a free `title`, a `biblioId`, or a returned loan (`loanArchiveId`, from the patron's loan
+/// history); with a loan, the title, record and date are taken from it.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateReadingEntry {
+    /// Defaults to today (or to the return date for a loan)
+    pub read_at: Option<NaiveDate>,
+    pub title: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub biblio_id: Option<i64>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub loan_archive_id: Option<i64>,
+    pub notes: Option<String>,
+}
+
+/// Returned loan resolved for a reading entry
+#[derive(Debug, Clone, FromRow)]
+pub struct ReturnedLoanForEntry {
+    pub user_id: Option<i64>,
+    pub returned_at: DateTime<Utc>,
+    pub biblio_id: Option<i64>,
+    pub title: Option<String>,
+}
+
+/// Result of `POST /reading-programs/{id}/sync-loans`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingLoansSyncReport {
+    /// Entries created from returned loans
+    pub entries_created: u64,
+    /// Enrollments that reached the goal
+    pub completed: i64,
+}
+
+/// Participation for one age bracket (age at program start)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingAgeBracketStats {
+    /// `0-5`, `6-8`, `9-11`, `12-14`, `15-17`, `18+` or `unknown`
+    pub age_bracket: String,
+    pub enrolled: i64,
+    pub completed: i64,
+    pub books_read: i64,
+}
+
+/// Participation statistics for a program
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingProgramStats {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub program_id: i64,
+    pub by_age_bracket: Vec<ReadingAgeBracketStats>,
+    pub enrolled: i64,
+    pub completed: i64,
+    pub books_read: i64,
+}
+
+/// Query parameters for the statistics export
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingProgramStatsQuery {
+    /// `json` (default) or `csv`
+    pub format: Option<String>,
+}
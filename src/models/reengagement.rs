@@ -0,0 +1,84 @@
+//! Re-engagement emails: new acquisitions suggested to members who stopped borrowing
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Inactive member selected by a run, with what is needed to compose the email
+#[derive(Debug, Clone, FromRow)]
+pub struct ReengagementCandidate {
+    pub user_id: i64,
+    pub email: String,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    pub language: Option<String>,
+    /// Most recent loan (active tables and archives)
+    pub last_loan_at: DateTime<Utc>,
+}
+
+/// Members a run would currently email
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReengagementPreview {
+    /// Inactive, opted-in members not emailed within `reengagement.resend_after_days`
+    pub candidates: i64,
+    /// Members emailed per run at most (`reengagement.max_per_run`)
+    pub max_per_run: u32,
+}
+
+/// Outcome of a run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReengagementReport {
+    /// Members selected
+    pub candidates: u32,
+    /// Emails accepted by the SMTP server
+    pub sent: u32,
+    /// Emails refused by the SMTP server (retried by the next run)
+    pub failed: u32,
+    /// Members without a new title in their preferred media types (not emailed)
+    pub no_suggestions: u32,
+}
+
+/// Emails sent in one month and the members who borrowed again
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReengagementMonthStats {
+    /// `YYYY-MM`
+    pub month: String,
+    pub sent: i64,
+    pub reactivated: i64,
+    pub suggested_loans: i64,
+}
+
+/// Effectiveness of the re-engagement emails sent in a period
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReengagementStats {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// Days after an email during which a loan counts as a reactivation
+    pub conversion_days: u32,
+    /// Emails accepted by the SMTP server
+    pub sent: i64,
+    pub failed: i64,
+    /// Members who borrowed within `conversionDays` of their email
+    pub reactivated: i64,
+    /// `reactivated / sent` (0 when nothing was sent)
+    pub reactivation_rate: f64,
+    /// Loans of a title suggested in the email, within `conversionDays`
+    pub suggested_loans: i64,
+    pub by_month: Vec<ReengagementMonthStats>,
+}
+
+/// Query parameters of `GET /stats/reengagement`
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ReengagementStatsQuery {
+    /// First day (YYYY-MM-DD, default one year before `endDate`)
+    pub start_date: Option<NaiveDate>,
+    /// Last day (YYYY-MM-DD, default today)
+    pub end_date: Option<NaiveDate>,
+}
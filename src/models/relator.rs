@@ -0,0 +1,55 @@
+//! Relator vocabulary (`biblio_authors.function`)
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Known author role (value of an author's `function` on a biblio)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatorDefinition {
+    /// Value stored in `biblio_authors.function` (camelCase, e.g. `illustrator`)
+    pub code: String,
+    /// MARC 21 relator code (`$4`), e.g. `ill`
+    #[schema(example = "ill")]
+    pub marc_code: String,
+    /// UNIMARC relator code, e.g. `440`
+    #[schema(example = "440")]
+    pub unimarc_code: Option<String>,
+    /// Default label; translations are managed through `/labels`
+    pub label: String,
+    /// Other terms recognised on import and in the `authorRole` search filter (case and accents ignored)
+    pub aliases: Vec<String>,
+    pub sort_order: i16,
+    pub created_at: Option<DateTime<Utc>>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+/// Create relator request
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRelator {
+    /// camelCase identifier (letters and digits, starting with a lowercase letter)
+    pub code: String,
+    /// Three lowercase letters
+    pub marc_code: String,
+    /// Three digits
+    pub unimarc_code: Option<String>,
+    pub label: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub sort_order: Option<i16>,
+}
+
+/// Update relator request (absent fields are kept; an empty `unimarcCode` clears it)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRelator {
+    pub marc_code: Option<String>,
+    pub unimarc_code: Option<String>,
+    pub label: Option<String>,
+    /// Replaces the aliases
+    pub aliases: Option<Vec<String>>,
+    pub sort_order: Option<i16>,
+}
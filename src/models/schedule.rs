@@ -1,6 +1,6 @@
 //! Schedule models (periods, slots, closures)
 
-use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use sqlx::FromRow;
@@ -114,10 +114,28 @@ pub struct CreateScheduleClosure {
     /// Closure date (YYYY-MM-DD)
     pub closure_date: String,
     pub reason: Option<String>,
+    /// Move loans due on the closure day to the first open day and notify borrowers
+    /// (runs as a background task)
+    #[serde(default)]
+    pub extend_due_dates: bool,
+}
+
+/// Create closure response: the closure plus the due-date extension task, when requested
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleClosureCreated {
+    #[serde(flatten)]
+    pub closure: ScheduleClosure,
+    /// Background task extending due dates (poll `GET /tasks/:id`)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub extension_task_id: Option<i64>,
 }
 
 /// Query parameters for schedule closures
-#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ScheduleClosureQuery {
     /// Filter closures from this date (YYYY-MM-DD)
@@ -125,3 +143,65 @@ pub struct ScheduleClosureQuery {
     /// Filter closures until this date (YYYY-MM-DD)
     pub end_date: Option<String>,
 }
+
+// ---------------------------------------------------------------------------
+// Resolved timetable (periods + slots + closures)
+// ---------------------------------------------------------------------------
+
+/// One opening interval on a resolved day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OpeningHours {
+    pub open_time: NaiveTime,
+    pub close_time: NaiveTime,
+}
+
+/// Opening hours of a single date, with closures applied
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DaySchedule {
+    pub date: NaiveDate,
+    /// Day of week (0=Monday, 6=Sunday)
+    pub day_of_week: i16,
+    /// True when the date is an exceptional closure
+    pub is_closure: bool,
+    /// Reason of the exceptional closure, if any
+    pub closure_reason: Option<String>,
+    /// Opening intervals, sorted by opening time (empty when closed all day)
+    pub hours: Vec<OpeningHours>,
+}
+
+/// Resolved Monday–Sunday timetable
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WeekSchedule {
+    /// Monday of the week
+    pub week_start: NaiveDate,
+    /// Sunday of the week
+    pub week_end: NaiveDate,
+    pub days: Vec<DaySchedule>,
+}
+
+/// Current opening status (library local time)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleStatus {
+    /// True when the library is open right now
+    pub is_open: bool,
+    /// Local time the status was computed for
+    pub now: NaiveDateTime,
+    /// When the current opening interval ends (only when open)
+    pub closes_at: Option<NaiveDateTime>,
+    /// Start of the next opening interval (none when nothing is scheduled in the look-ahead window)
+    pub next_opening_at: Option<NaiveDateTime>,
+    /// Closure reason when today is an exceptional closure
+    pub closure_reason: Option<String>,
+}
+
+/// Query parameters for the weekly timetable
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WeekScheduleQuery {
+    /// Any date within the requested week (YYYY-MM-DD, defaults to today)
+    pub date: Option<String>,
+}
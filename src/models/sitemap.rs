@@ -0,0 +1,34 @@
+//! OPAC sitemap for search engines (`sitemap_files`)
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Record listed in the sitemap
+#[derive(Debug, Clone, FromRow)]
+pub struct SitemapEntry {
+    pub biblio_id: i64,
+    /// Last modification of the record (`updated_at`, else `created_at`)
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+/// Published sitemap file
+#[derive(Debug, Clone, FromRow)]
+pub struct SitemapFile {
+    pub name: String,
+    pub content: String,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Outcome of a generation
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SitemapReport {
+    /// Record URLs listed
+    pub records: u64,
+    /// Files published (`sitemap.xml`, plus one file per shard when the catalog is sharded)
+    pub files: u32,
+    /// Whether `sitemap.xml` is an index of shards
+    pub sharded: bool,
+}
@@ -0,0 +1,57 @@
+//! Catalog snapshots: copies captured before a bulk edit, restorable as they were
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Call-number recalculation (`POST /items/recalculate-call-numbers`)
+pub const OPERATION_CALL_NUMBERS: &str = "call_numbers.recalculate";
+/// Source merge (`POST /sources/merge`)
+pub const OPERATION_SOURCES_MERGE: &str = "sources.merge";
+
+/// Copies a snapshot captures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotScope {
+    Items(Vec<i64>),
+    /// Every copy (archived included) of these sources
+    Sources(Vec<i64>),
+}
+
+/// Copies captured before a bulk edit
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogSnapshot {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    /// Bulk operation that took the snapshot (`call_numbers.recalculate`, `sources.merge`)
+    pub operation: String,
+    /// Copies captured
+    pub item_count: i32,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    /// Set once restored (a snapshot is restored at most once)
+    pub restored_at: Option<DateTime<Utc>>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub restored_by: Option<i64>,
+}
+
+/// Result of `POST /admin/snapshots/:id/restore`
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotRestoreReport {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub snapshot_id: i64,
+    /// Copies written back
+    pub restored: u64,
+    /// Copies deleted since the snapshot (left as they are)
+    pub missing: u64,
+}
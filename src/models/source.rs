@@ -4,7 +4,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use sqlx::FromRow;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 /// Source record
 #[serde_as]
@@ -19,6 +19,10 @@ pub struct Source {
     pub is_archive: Option<i16>,
     pub archived_at: Option<DateTime<Utc>>,
     pub default: Option<bool>,
+    /// Supplier behind this source; `null` for donations and other non-commercial origins
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub vendor_id: Option<i64>,
 }
 
 /// Create source request
@@ -39,6 +43,17 @@ pub struct UpdateSource {
     pub default: Option<bool>,
 }
 
+/// Link a source to a vendor (`vendorId: null` marks it as non-commercial)
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetSourceVendor {
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub vendor_id: Option<i64>,
+}
+
 /// Merge sources request
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -51,3 +66,69 @@ pub struct MergeSources {
     /// Name for the new merged source
     pub name: String,
 }
+
+/// Per-year activity attributable to a source
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceYearStats {
+    pub year: i32,
+    /// Items (physical copies) added that year
+    pub items_added: i64,
+    /// Loans started that year (active and archived)
+    pub loans: i64,
+}
+
+/// Usage statistics for a source
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceStats {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub source_id: i64,
+    /// Biblios whose own `source_id` is this source
+    pub biblios: i64,
+    /// Non-archived items linked to the source
+    pub active_items: i64,
+    /// Archived items linked to the source
+    pub archived_items: i64,
+    /// Loans currently out on items of the source
+    pub active_loans: i64,
+    /// Yearly breakdown, most recent first
+    pub by_year: Vec<SourceYearStats>,
+}
+
+/// Query parameters for deleting a source
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteSourceQuery {
+    /// Source receiving the items and biblios of the deleted source
+    #[serde_as(as = "DisplayFromStr")]
+    #[param(value_type = String)]
+    #[schema(value_type = String)]
+    pub target_source_id: i64,
+    /// Only count the rows that would move (default: false)
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Result of a source deletion (or dry run)
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceDeletionReport {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub source_id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub target_source_id: i64,
+    pub dry_run: bool,
+    /// Items (physical copies, archived included) reassigned to the target
+    pub items_moved: i64,
+    /// Biblios reassigned to the target
+    pub biblios_moved: i64,
+    /// True when the deleted source was the default one (the target becomes default)
+    pub default_transferred: bool,
+}
@@ -0,0 +1,250 @@
+//! Staffing models: shift templates on opening slots, assignments, absences
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Position to fill during an opening slot (`/schedules/periods/:id/slots`), every week the
+/// slot's period applies
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShiftTemplate {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub slot_id: i64,
+    /// Period of the slot
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub period_id: i64,
+    /// Day of week of the slot (0=Monday, 6=Sunday)
+    pub day_of_week: i16,
+    /// Position name (e.g. "Front desk")
+    pub name: String,
+    /// Start of the shift (the slot opening time unless set)
+    pub start_time: NaiveTime,
+    /// End of the shift (the slot closing time unless set)
+    pub end_time: NaiveTime,
+    /// Number of people needed
+    pub staff_needed: i16,
+    pub notes: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+/// Create or replace a shift template (`PUT` clears omitted fields)
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShiftTemplateFields {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub slot_id: i64,
+    pub name: String,
+    /// Start time (HH:MM) within the slot; defaults to its opening time
+    pub start_time: Option<String>,
+    /// End time (HH:MM) within the slot; defaults to its closing time
+    pub end_time: Option<String>,
+    /// Number of people needed (1 by default)
+    pub staff_needed: Option<i16>,
+    pub notes: Option<String>,
+}
+
+/// Query of `GET /staffing/shift-templates`
+#[serde_as]
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ShiftTemplateQuery {
+    /// Only templates of this schedule period
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[param(value_type = Option<String>)]
+    pub period_id: Option<i64>,
+}
+
+/// Dated occurrence of a template: the slot applies that day and the library is not closed
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShiftOccurrence {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub template_id: i64,
+    pub name: String,
+    pub shift_date: NaiveDate,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub staff_needed: i16,
+}
+
+/// User assigned to a shift
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShiftAssignment {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub template_id: i64,
+    /// Template name
+    pub name: String,
+    pub shift_date: NaiveDate,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    /// The user recorded an absence covering the day
+    pub absent: bool,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ShiftAssignment {
+    /// Same day and overlapping time spans (back-to-back shifts do not overlap)
+    pub fn overlaps(&self, date: NaiveDate, start: NaiveTime, end: NaiveTime) -> bool {
+        self.shift_date == date && self.start_time < end && start < self.end_time
+    }
+}
+
+/// Assign a user to the occurrence of a template on a day
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateShiftAssignment {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub template_id: i64,
+    /// Day of the shift (YYYY-MM-DD)
+    #[schema(value_type = String)]
+    pub shift_date: NaiveDate,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+    pub notes: Option<String>,
+}
+
+/// One day's shift in the roster, with who works it
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RosterShift {
+    #[serde(flatten)]
+    pub shift: ShiftOccurrence,
+    pub assignments: Vec<ShiftAssignment>,
+    /// Fewer present (not absent) assignees than `staffNeeded`
+    pub understaffed: bool,
+}
+
+/// Query of `GET /staffing/roster` and `GET /staffing/me/shifts` (both days included)
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct StaffingRangeQuery {
+    /// First day (YYYY-MM-DD)
+    #[param(value_type = String)]
+    pub start: NaiveDate,
+    /// Last day (YYYY-MM-DD)
+    #[param(value_type = String)]
+    pub end: NaiveDate,
+}
+
+/// Days a user cannot work
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StaffAbsence {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    pub start_date: NaiveDate,
+    /// Last day of the absence (included)
+    pub end_date: NaiveDate,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Record an absence
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateStaffAbsence {
+    /// Absent user; defaults to the caller (someone else requires settings write rights)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub user_id: Option<i64>,
+    #[schema(value_type = String)]
+    pub start_date: NaiveDate,
+    #[schema(value_type = String)]
+    pub end_date: NaiveDate,
+    pub reason: Option<String>,
+}
+
+/// Recorded absence, with the shifts the user no longer covers
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StaffAbsenceCreated {
+    #[serde(flatten)]
+    pub absence: StaffAbsence,
+    /// Assignments of the user within the absence, to reassign
+    pub uncovered_shifts: Vec<ShiftAssignment>,
+}
+
+/// Query of `GET /staffing/absences`: absences overlapping the range
+#[serde_as]
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct StaffAbsenceQuery {
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[param(value_type = Option<String>)]
+    pub user_id: Option<i64>,
+    /// YYYY-MM-DD
+    #[param(value_type = Option<String>)]
+    pub start: Option<NaiveDate>,
+    /// YYYY-MM-DD
+    #[param(value_type = Option<String>)]
+    pub end: Option<NaiveDate>,
+}
+
+/// Secret iCal feed URL of the caller (shown once; a new one revokes the previous)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StaffCalendarToken {
+    pub token: String,
+    /// Path of the feed, relative to the API root
+    pub path: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn back_to_back_shifts_do_not_overlap() {
+        let time = |h| NaiveTime::from_hms_opt(h, 0, 0).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 5, 2).unwrap();
+        let assignment: ShiftAssignment = serde_json::from_value(serde_json::json!({
+            "id": "1", "templateId": "2", "name": "Front desk", "shiftDate": "2026-05-02",
+            "startTime": "10:00:00", "endTime": "12:00:00", "userId": "3", "firstname": null,
+            "lastname": null, "absent": false, "notes": null, "createdAt": "2026-04-01T08:00:00Z"
+        }))
+        .unwrap();
+        assert!(assignment.overlaps(date, time(11), time(13)));
+        assert!(!assignment.overlaps(date, time(12), time(14)));
+        assert!(!assignment.overlaps(date.succ_opt().unwrap(), time(10), time(12)));
+    }
+}
@@ -190,3 +190,138 @@ pub struct SavedStatsQueryWrite {
     #[serde(default)]
     pub is_shared: bool,
 }
+
+// --- Report builder (whitelisted dimensions × measures) ---------------------
+
+/// Grouping axis of a report; each measure supports a subset (see `GET /stats/reports/schema`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ReportDimension {
+    MediaType,
+    /// Document audience (`biblios.audience_type`)
+    Audience,
+    Source,
+    AccountType,
+    /// Shelving location of the copy (`items.place`)
+    Location,
+    /// Borrower age band captured at checkout (`loans.borrower_age_band`)
+    AgeBand,
+    /// Time bucket of the measure date, see `ReportDefinition::granularity`
+    Period,
+}
+
+impl ReportDimension {
+    pub const ALL: [ReportDimension; 7] = [
+        Self::MediaType,
+        Self::Audience,
+        Self::Source,
+        Self::AccountType,
+        Self::Location,
+        Self::AgeBand,
+        Self::Period,
+    ];
+
+    /// Column name in report results
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::MediaType => "mediaType",
+            Self::Audience => "audience",
+            Self::Source => "source",
+            Self::AccountType => "accountType",
+            Self::Location => "location",
+            Self::AgeBand => "ageBand",
+            Self::Period => "period",
+        }
+    }
+}
+
+/// Value computed per report row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ReportMeasure {
+    /// Loans started in the period (active and archived)
+    Loans,
+    /// Active copies (entered in the period when filtered or grouped by period)
+    Specimens,
+    /// Patrons (registered in the period when filtered or grouped by period)
+    Users,
+    /// Sum of fine amounts issued in the period
+    Fines,
+}
+
+impl ReportMeasure {
+    pub const ALL: [ReportMeasure; 4] = [Self::Loans, Self::Specimens, Self::Users, Self::Fines];
+
+    /// Column name in report results
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Loans => "loans",
+            Self::Specimens => "specimens",
+            Self::Users => "users",
+            Self::Fines => "fines",
+        }
+    }
+}
+
+/// Report definition: rows grouped by `dimensions`, one column per measure.
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportDefinition {
+    #[serde(default)]
+    pub dimensions: Vec<ReportDimension>,
+    pub measures: Vec<ReportMeasure>,
+    /// Bucket size of the `period` dimension (default: month)
+    pub granularity: Option<TimeGranularity>,
+    /// First day of the period (inclusive), applied to each measure's date
+    pub from: Option<chrono::NaiveDate>,
+    /// Last day of the period (inclusive)
+    pub to: Option<chrono::NaiveDate>,
+}
+
+/// Report result: dimension columns first, then measures.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportTable {
+    pub columns: Vec<ColumnMeta>,
+    pub rows: Vec<serde_json::Map<String, serde_json::Value>>,
+}
+
+/// Output of `…/run` report endpoints
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Csv,
+    Xlsx,
+}
+
+/// Saved report row for `GET /stats/reports`.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedReport {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub definition: ReportDefinition,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+    pub is_shared: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Create or update saved report.
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedReportWrite {
+    pub name: String,
+    pub description: Option<String>,
+    pub definition: ReportDefinition,
+    #[serde(default)]
+    pub is_shared: bool,
+}
@@ -12,6 +12,37 @@ pub enum TaskKind {
     MarcBatchImport,
     Maintenance,
     InventoryBatchScan,
+    ClosureDueDateExtension,
+    HarvestRun,
+    CampaignSend,
+    WarehouseExport,
+    UserExpiryCampaign,
+    DatabaseBackup,
+    EndOfDay,
+    Reengagement,
+    AuthorNormalization,
+    Sitemap,
+}
+
+impl TaskKind {
+    /// English label, used in notification titles
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::MarcBatchImport => "MARC batch import",
+            Self::Maintenance => "Maintenance",
+            Self::InventoryBatchScan => "Inventory batch scan",
+            Self::ClosureDueDateExtension => "Closure due date extension",
+            Self::HarvestRun => "Harvest run",
+            Self::CampaignSend => "Campaign send",
+            Self::WarehouseExport => "Warehouse export",
+            Self::UserExpiryCampaign => "Membership expiry campaign",
+            Self::DatabaseBackup => "Database backup",
+            Self::EndOfDay => "End of day",
+            Self::Reengagement => "Re-engagement emails",
+            Self::AuthorNormalization => "Author name normalization",
+            Self::Sitemap => "Sitemap generation",
+        }
+    }
 }
 
 /// Lifecycle status of a background task.
@@ -66,6 +97,10 @@ pub struct BackgroundTask {
     /// - `marcBatchImport`      → `MarcBatchImportReport`
     /// - `maintenance`          → `MaintenanceResponse` (per-action `details` may include Z39.50 summaries)
     /// - `inventoryBatchScan`   → `InventoryScan[]` (same order as request barcodes)
+    /// - `closureDueDateExtension` → `ClosureExtensionReport`
+    /// - `userExpiryCampaign`   → `UserExpiryCampaignReport`
+    /// - `reengagement`         → `ReengagementReport`
+    /// - `sitemap`              → `SitemapReport`
     #[serde(skip_serializing_if = "Option::is_none")]
     pub result: Option<serde_json::Value>,
 
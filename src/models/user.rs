@@ -7,8 +7,8 @@ use sqlx::{Decode, Encode, FromRow, Postgres, Type};
 use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
-use crate::error::AppError;
-use super::{Language, Sex};
+use crate::error::{AppError, FieldError, FieldErrors};
+use super::{email_health::EmailHealth, Language, Sex};
 
 /// User rights levels (DB single-letter codes; holds domain also uses `o` = own).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -220,12 +220,14 @@ impl From<FeeSlug> for Option<String> {
 // Note: FeeSlug conversions are handled manually in repository code
 // because SQLx doesn't support custom Decode/Encode for enums with Other(String) variant
 
-/// User account status (persisted as camelCase strings in PostgreSQL: `active`, `blocked`, `deleted`).
+/// User account status (persisted in PostgreSQL as `active`, `blocked`, `pending_deletion`, `deleted`).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum UserStatus {
     Active,
     Blocked,
+    /// Deleted but not anonymized yet: restorable until `deletionScheduledAt`
+    PendingDeletion,
     Deleted,
 }
 
@@ -234,9 +236,15 @@ impl UserStatus {
         match self {
             UserStatus::Active => "active",
             UserStatus::Blocked => "blocked",
+            UserStatus::PendingDeletion => "pending_deletion",
             UserStatus::Deleted => "deleted",
         }
     }
+
+    /// Deleted, whether or not the account has been anonymized yet
+    pub fn is_deleted(&self) -> bool {
+        matches!(self, UserStatus::PendingDeletion | UserStatus::Deleted)
+    }
 }
 
 impl std::fmt::Display for UserStatus {
@@ -252,6 +260,7 @@ impl std::str::FromStr for UserStatus {
         match s.to_lowercase().as_str() {
             "" | "active" => Ok(UserStatus::Active),
             "blocked" => Ok(UserStatus::Blocked),
+            "pending_deletion" | "pendingdeletion" => Ok(UserStatus::PendingDeletion),
             "deleted" => Ok(UserStatus::Deleted),
             _ => Err(format!("Invalid user status: {}", s)),
         }
@@ -307,6 +316,8 @@ pub struct UserRow {
     notes: Option<String>,
     status: Option<UserStatus>,
     archived_at: Option<DateTime<Utc>>,
+    deletion_scheduled_at: Option<DateTime<Utc>>,
+    merged_into: Option<i64>,
     language: Option<Language>,
     sex: Option<Sex>,
     staff_type: Option<i16>,
@@ -319,6 +330,7 @@ pub struct UserRow {
     recovery_codes: Option<String>,
     recovery_codes_used: Option<String>,
     receive_reminders: Option<bool>,
+    campaigns_opt_in: Option<bool>,
     must_change_password: Option<bool>,
 }
 
@@ -347,6 +359,8 @@ impl From<UserRow> for User {
             notes: row.notes,
             status: row.status,
             archived_at: row.archived_at,
+            deletion_scheduled_at: row.deletion_scheduled_at,
+            merged_into: row.merged_into,
             language: row.language,
             sex: row.sex,
             staff_type: row.staff_type,
@@ -359,7 +373,9 @@ impl From<UserRow> for User {
             recovery_codes: row.recovery_codes,
             recovery_codes_used: row.recovery_codes_used,
             receive_reminders: row.receive_reminders.unwrap_or(true),
+            campaigns_opt_in: row.campaigns_opt_in.unwrap_or(false),
             must_change_password: row.must_change_password.unwrap_or(false),
+            email_health: None,
         }
     }
 }
@@ -401,6 +417,12 @@ pub struct User {
     pub notes: Option<String>,
     pub status: Option<UserStatus>,
     pub archived_at: Option<DateTime<Utc>>,
+    /// When a `pendingDeletion` account gets anonymized (it can be restored until then)
+    pub deletion_scheduled_at: Option<DateTime<Utc>>,
+    /// Surviving account this duplicate was merged into (`POST /users/merge`)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub merged_into: Option<i64>,
     /// User preferred language
     pub language: Option<Language>,
     /// Sex: `"m"` or `"f"` in JSON; null = unknown / not set.
@@ -424,8 +446,14 @@ pub struct User {
     pub recovery_codes_used: Option<String>,
     /// Whether the user wants to receive overdue reminder emails
     pub receive_reminders: bool,
+    /// Whether the user agreed to receive email campaigns (announcements, newsletters)
+    #[serde(default)]
+    pub campaigns_opt_in: bool,
     /// When true, the user must change their password on next login
     pub must_change_password: bool,
+    /// Bounces and complaints reported for `email` (staff user record only; absent when none)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_health: Option<EmailHealth>,
 }
 
 
@@ -492,7 +520,7 @@ pub struct UserShort {
 }
 
 /// User query parameters
-#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserQuery {
     pub name: Option<String>,
@@ -501,6 +529,125 @@ pub struct UserQuery {
     pub per_page: Option<i64>,
 }
 
+/// Kind of entry in a user's activity timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum UserActivityKind {
+    /// Checkout (active or archived loan)
+    Loan,
+    Return,
+    /// Hold placed, ready for pickup, picked up or cancelled
+    Hold,
+    /// Payment or refund
+    Payment,
+    /// Email sent to the user (reminders, due date extensions, codes, password reset)
+    Notification,
+    /// Account change recorded in the audit log (update, account type, PIN, merge, ...)
+    ProfileChange,
+}
+
+impl UserActivityKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Loan => "loan",
+            Self::Return => "return",
+            Self::Hold => "hold",
+            Self::Payment => "payment",
+            Self::Notification => "notification",
+            Self::ProfileChange => "profileChange",
+        }
+    }
+
+    fn from_db(value: &str) -> Self {
+        match value {
+            "return" => Self::Return,
+            "hold" => Self::Hold,
+            "payment" => Self::Payment,
+            "notification" => Self::Notification,
+            "profileChange" => Self::ProfileChange,
+            _ => Self::Loan,
+        }
+    }
+}
+
+/// `GET /users/:id/activity` query
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserActivityQuery {
+    /// Only entries of this kind
+    pub kind: Option<UserActivityKind>,
+    /// Page number (1-based, default 1)
+    pub page: Option<i64>,
+    /// Page size (default 20, max 200)
+    pub per_page: Option<i64>,
+}
+
+/// One entry of a user's activity timeline (newest first)
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserActivity {
+    pub kind: UserActivityKind,
+    /// What happened: `loan.created`, `loan.returned`, `hold.created`, `hold.ready`,
+    /// `hold.pickedUp`, `hold.cancelled`, `payment.recorded`, `payment.refunded`, or the audit
+    /// event type of notifications and profile changes (e.g. `email.overdue_reminder_sent`)
+    pub event: String,
+    pub occurred_at: DateTime<Utc>,
+    /// Loan, archived loan, hold, payment or audit entry the event comes from
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub source_id: i64,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub item_id: Option<i64>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub biblio_id: Option<i64>,
+    /// Title of the copy's record
+    pub title: Option<String>,
+    /// Payment amount (negative for refunds)
+    #[schema(value_type = Option<String>, example = "2.50")]
+    pub amount: Option<rust_decimal::Decimal>,
+    /// Staff member (or the user themself) who performed the action, when recorded
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub actor_id: Option<i64>,
+    /// Notification that could not be sent
+    pub failed: bool,
+}
+
+/// Timeline row as selected by the repository (`kind` as text)
+#[derive(Debug, FromRow)]
+pub struct UserActivityRow {
+    pub kind: String,
+    pub event: String,
+    pub occurred_at: DateTime<Utc>,
+    pub source_id: i64,
+    pub item_id: Option<i64>,
+    pub biblio_id: Option<i64>,
+    pub title: Option<String>,
+    pub amount: Option<rust_decimal::Decimal>,
+    pub actor_id: Option<i64>,
+    pub failed: bool,
+}
+
+impl From<UserActivityRow> for UserActivity {
+    fn from(row: UserActivityRow) -> Self {
+        UserActivity {
+            kind: UserActivityKind::from_db(&row.kind),
+            event: row.event,
+            occurred_at: row.occurred_at,
+            source_id: row.source_id,
+            item_id: row.item_id,
+            biblio_id: row.biblio_id,
+            title: row.title,
+            amount: row.amount,
+            actor_id: row.actor_id,
+            failed: row.failed,
+        }
+    }
+}
+
 /// User create/update body. On create and on admin update (`PUT /users/:id`), the following
 /// fields are required: `login`, `firstname`, `lastname`, `sex`, `birthdate`, `publicType`, `addrCity`.
 #[serde_as]
@@ -510,7 +657,7 @@ pub struct UserPayload {
     pub barcode: Option<String>,
     /// Login (username); required on create and on admin update
     pub login: Option<String>,
-    #[validate(length(min = 4, message = "Password must be at least 4 characters"))]
+    /// Checked against the password policy of the account type
     pub password: Option<String>,
     pub firstname: Option<String>,
     pub lastname: Option<String>,
@@ -549,61 +696,38 @@ pub struct UserPayload {
 
 impl UserPayload {
     /// Validates required patron identity fields for admin create and full user update.
+    /// Every missing field is reported (`422 invalid_fields`).
     pub fn validate_required_patron_fields(&self) -> Result<(), AppError> {
-        let login = self
-            .login
-            .as_deref()
-            .map(str::trim)
-            .filter(|s| !s.is_empty());
-        if login.is_none() {
-            return Err(AppError::Validation("login is required".into()));
-        }
-
-        let firstname = self
-            .firstname
-            .as_deref()
-            .map(str::trim)
-            .filter(|s| !s.is_empty());
-        if firstname.is_none() {
-            return Err(AppError::Validation("firstname is required".into()));
+        fn blank(value: &Option<String>) -> bool {
+            value.as_deref().map(str::trim).unwrap_or_default().is_empty()
         }
 
-        let lastname = self
-            .lastname
-            .as_deref()
-            .map(str::trim)
-            .filter(|s| !s.is_empty());
-        if lastname.is_none() {
-            return Err(AppError::Validation("lastname is required".into()));
+        let missing = [
+            ("login", blank(&self.login)),
+            ("firstname", blank(&self.firstname)),
+            ("lastname", blank(&self.lastname)),
+            ("sex", self.sex.is_none()),
+            ("birthdate", self.birthdate.is_none()),
+            ("publicType", self.public_type.is_none()),
+            ("addrCity", blank(&self.addr_city)),
+        ];
+        let mut fields = FieldErrors::new();
+        for (field, _) in missing.iter().filter(|(_, missing)| *missing) {
+            fields.entry(field.to_string()).or_default().push(FieldError {
+                code: "required".to_string(),
+                message: format!("{} is required", field),
+            });
         }
-
-        if self.sex.is_none() {
-            return Err(AppError::Validation("sex is required".into()));
-        }
-
-        if self.birthdate.is_none() {
-            return Err(AppError::Validation("birthdate is required".into()));
-        }
-
-        if self.public_type.is_none() {
-            return Err(AppError::Validation("publicType is required".into()));
-        }
-
-        let city = self
-            .addr_city
-            .as_deref()
-            .map(str::trim)
-            .filter(|s| !s.is_empty());
-        if city.is_none() {
-            return Err(AppError::Validation("addrCity is required".into()));
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::InvalidFields(fields))
         }
-
-        Ok(())
     }
 }
 
 /// Update own profile request (for authenticated users)
-#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateProfile {
     /// First name
@@ -628,15 +752,14 @@ pub struct UpdateProfile {
     pub birthdate: Option<NaiveDate>,
     /// Current password (required to change password)
     pub current_password: Option<String>,
-    /// New password
-    #[validate(length(min = 4, message = "Password must be at least 4 characters"))]
+    /// New password, checked against the password policy of the account type
     pub new_password: Option<String>,
     /// Preferred language
     pub language: Option<Language>,
 }
 
 /// Update account type request (admin only)
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateAccountType {
     /// New account type slug (guest, reader, librarian, admin, group)
     pub account_type: AccountTypeSlug,
@@ -656,6 +779,12 @@ pub struct UserRights {
     /// Cultural events (`/events`): read list/detail vs write (create/update/delete/announce).
     #[serde(default)]
     pub events_rights: Rights,
+    /// Checkout despite checkout blocks (`force=true`): `w` allows the override.
+    #[serde(default)]
+    pub circulation_override_rights: Rights,
+    /// `staff` / `teaching` priority holds: `w` allows placing them.
+    #[serde(default)]
+    pub priority_holds_rights: Rights,
 }
 
 impl Default for UserRights {
@@ -667,6 +796,8 @@ impl Default for UserRights {
             holds_rights: Rights::None,
             settings_rights: Rights::None,
             events_rights: Rights::None,
+            circulation_override_rights: Rights::None,
+            priority_holds_rights: Rights::None,
         }
     }
 }
@@ -674,6 +805,245 @@ impl Default for UserRights {
 /// Scoped JWT for users who must change their password before full access.
 pub const SCOPE_CHANGE_PASSWORD: &str = "change_password_only";
 
+/// Scoped JWT issued by barcode + PIN login: only self-service endpoints accept it.
+pub const SCOPE_SELF_SERVICE: &str = "self_service";
+
+/// Barcode login state of a patron (`users.pin_*`)
+#[derive(Debug, Clone, FromRow)]
+pub struct UserPinState {
+    pub id: i64,
+    pub pin_hash: Option<String>,
+    pub pin_failed_attempts: i16,
+    pub pin_locked_until: Option<DateTime<Utc>>,
+}
+
+/// Set or clear a patron's self-service PIN (staff)
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetUserPin {
+    /// 4 to 8 digits; `null` disables barcode login
+    pub pin: Option<String>,
+}
+
+/// Merge a duplicate patron account into the surviving one (`POST /users/merge`)
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeUsers {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub survivor_id: i64,
+    /// Archived once its loans, fines, holds and enrollments have moved
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub duplicate_id: i64,
+    /// Report what would happen without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Contact data of the surviving account after a merge (computed by the service, written by
+/// the repository)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UserContactMerge {
+    pub barcode: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub addr_street: Option<String>,
+    pub addr_zip_code: Option<i32>,
+    pub addr_city: Option<String>,
+    pub birthdate: Option<NaiveDate>,
+    pub sex: Option<Sex>,
+    pub public_type: Option<i64>,
+    pub expiry_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+    /// Survivor fields taken from the duplicate (API field names)
+    pub filled: Vec<String>,
+    pub conflicts: Vec<UserMergeConflict>,
+}
+
+/// Field set differently on both accounts: the survivor's value is kept
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserMergeConflict {
+    pub field: String,
+    pub kept: String,
+    pub discarded: String,
+}
+
+/// Rows moved from the duplicate account
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserMergeCounts {
+    pub loans: i64,
+    pub archived_loans: i64,
+    pub fines: i64,
+    pub holds: i64,
+    /// Holds on copies the survivor already holds (dropped)
+    pub holds_dropped: i64,
+    /// Reading program enrollments (entries are folded into the survivor's enrollment when both
+    /// accounts joined the same program)
+    pub enrollments: i64,
+    pub loan_batches: i64,
+    pub item_accesses: i64,
+    pub donations: i64,
+}
+
+/// Outcome of a merge
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserMergeReport {
+    pub dry_run: bool,
+    /// Surviving account (as it is, or would be, after the merge)
+    pub survivor: User,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub duplicate_id: i64,
+    pub moved: UserMergeCounts,
+    /// Survivor fields filled from the duplicate
+    pub filled: Vec<String>,
+    pub conflicts: Vec<UserMergeConflict>,
+}
+
+/// Bulk membership expiry and renewal campaign (`POST /users/expiry-campaign`). Targets the
+/// accounts (not deleted) matching every filter given; at least one filter is required.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserExpiryCampaign {
+    /// Members of this group account
+    #[serde(default)]
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub group_id: Option<i64>,
+    #[serde(default)]
+    pub account_type: Option<AccountTypeSlug>,
+    #[serde(default)]
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub public_type: Option<i64>,
+    /// Accounts whose membership ends at or after this time
+    #[serde(default)]
+    pub expires_from: Option<DateTime<Utc>>,
+    /// Accounts whose membership ends before this time
+    #[serde(default)]
+    pub expires_to: Option<DateTime<Utc>>,
+    /// Also target accounts without a membership end date
+    #[serde(default)]
+    pub include_without_expiry: bool,
+    /// Membership end date set on every targeted account
+    pub expiry_at: DateTime<Utc>,
+    /// Email a renewal invitation (`membership_renewal` template) to every targeted account
+    #[serde(default)]
+    pub send_invitations: bool,
+    /// Link of the invitations; required with `sendInvitations`
+    #[validate(url(message = "Invalid renewal URL"))]
+    pub renewal_url: Option<String>,
+    /// Report the targeted accounts without changing or sending anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+impl UserExpiryCampaign {
+    /// Reject campaigns targeting every account, empty windows and invitations without a link
+    /// (`422 invalid_fields`).
+    pub fn validate_targeting(&self) -> Result<(), AppError> {
+        let mut fields = FieldErrors::new();
+        let mut fail = |field: &str, code: &str, message: &str| {
+            fields.entry(field.to_string()).or_default().push(FieldError {
+                code: code.to_string(),
+                message: message.to_string(),
+            });
+        };
+        let filtered = self.group_id.is_some()
+            || self.account_type.is_some()
+            || self.public_type.is_some()
+            || self.expires_from.is_some()
+            || self.expires_to.is_some()
+            || self.include_without_expiry;
+        if !filtered {
+            fail("groupId", "required", "At least one filter is required");
+        }
+        if let (Some(from), Some(to)) = (self.expires_from, self.expires_to) {
+            if from >= to {
+                fail("expiresTo", "range", "expiresTo must be after expiresFrom");
+            }
+        }
+        if self.send_invitations && self.renewal_url.as_deref().is_none_or(|u| u.trim().is_empty()) {
+            fail("renewalUrl", "required", "renewalUrl is required to send invitations");
+        }
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::InvalidFields(fields))
+        }
+    }
+}
+
+/// Renewal invitation outcome of one account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RenewalInvitationStatus {
+    NotRequested,
+    Sent,
+    /// The account has no email address
+    NoEmail,
+    Failed,
+}
+
+/// Per-account outcome of an expiry campaign
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserExpiryOutcome {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+    pub login: Option<String>,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    pub previous_expiry_at: Option<DateTime<Utc>>,
+    /// New membership end date (unchanged with `dryRun`)
+    pub expiry_at: Option<DateTime<Utc>>,
+    pub invitation: RenewalInvitationStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of the `userExpiryCampaign` task
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserExpiryCampaignReport {
+    pub dry_run: bool,
+    pub matched: u32,
+    /// Accounts whose membership end date was set
+    pub updated: u32,
+    /// Renewal invitations sent
+    pub invited: u32,
+    pub accounts: Vec<UserExpiryOutcome>,
+}
+
+/// Change own self-service PIN
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeOwnPin {
+    pub current_password: String,
+    /// 4 to 8 digits; `null` disables barcode login
+    pub pin: Option<String>,
+}
+
+/// Confirmation for regenerating own recovery codes: the account password or a current TOTP code
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegenerateRecoveryCodes {
+    #[serde(default)]
+    pub current_password: Option<String>,
+    /// Current TOTP code (TOTP accounts only)
+    #[serde(default)]
+    pub code: Option<String>,
+}
+
 /// JWT Claims for authenticated users
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserClaims {
@@ -685,8 +1055,12 @@ pub struct UserClaims {
     pub iat: i64,
     /// When set to `SCOPE_CHANGE_PASSWORD`, the token may only be used to
     /// call `POST /auth/change-password`. All other endpoints reject it.
+    /// `SCOPE_SELF_SERVICE` tokens are only accepted by self-service endpoints.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scope: Option<String>,
+    /// Admin acting as this user (`POST /users/:id/impersonate`); every request is audited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impersonated_by: Option<i64>,
 }
 
 impl UserClaims {
@@ -695,6 +1069,26 @@ impl UserClaims {
         self.scope.as_deref() == Some(SCOPE_CHANGE_PASSWORD)
     }
 
+    /// Returns true when this token comes from barcode + PIN login.
+    pub fn is_self_service_scope(&self) -> bool {
+        self.scope.as_deref() == Some(SCOPE_SELF_SERVICE)
+    }
+
+    /// Returns true when an admin is acting as this user.
+    pub fn is_impersonation(&self) -> bool {
+        self.impersonated_by.is_some()
+    }
+
+    /// Refuse account-security changes (password, PIN, 2FA) made through an impersonation token
+    pub fn require_not_impersonating(&self) -> Result<(), AppError> {
+        if self.is_impersonation() {
+            return Err(AppError::Authorization(
+                "Not allowed while impersonating a user".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     /// Create a new JWT token
     pub fn create_token(&self, secret: &str) -> Result<String, jsonwebtoken::errors::Error> {
         use jsonwebtoken::{encode, EncodingKey, Header};
@@ -823,6 +1217,16 @@ impl UserClaims {
         }
     }
 
+    /// Whether `force=true` on a checkout may override the patron's checkout blocks
+    pub fn can_override_checkout_blocks(&self) -> bool {
+        self.rights.circulation_override_rights.rank() >= Rights::Write.rank()
+    }
+
+    /// Whether holds may be placed with a `staff` or `teaching` priority
+    pub fn can_place_priority_holds(&self) -> bool {
+        self.rights.priority_holds_rights.rank() >= Rights::Write.rank()
+    }
+
     pub fn require_list_holds(&self) -> Result<(), AppError> {
         if self.rights.holds_rights.rank() >= Rights::Read.rank()
             || self.rights.holds_rights == Rights::Own
@@ -872,6 +1276,15 @@ impl UserClaims {
         }
     }
 
+    /// Require a librarian or admin account (staff-only views such as the shift roster)
+    pub fn require_staff(&self) -> Result<(), AppError> {
+        if self.is_librarian() {
+            Ok(())
+        } else {
+            Err(AppError::Authorization("Staff account required".to_string()))
+        }
+    }
+
     /// Allow access only when the caller is the target user, or a librarian/admin.
     pub fn require_self_or_staff(&self, target_user_id: i64) -> Result<(), AppError> {
         if self.user_id == target_user_id || self.is_librarian() {
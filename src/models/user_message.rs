@@ -0,0 +1,118 @@
+//! Patron messages: staff notes shown at checkout and, when patron-visible, in the self-service account
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// How a message affects circulation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UserMessageSeverity {
+    /// Shown at checkout
+    #[default]
+    Info,
+    /// Checkouts are refused until the message is acknowledged (or the checkout forced)
+    Blocking,
+}
+
+impl UserMessageSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Blocking => "blocking",
+        }
+    }
+}
+
+impl From<String> for UserMessageSeverity {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "blocking" => Self::Blocking,
+            _ => Self::Info,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for UserMessageSeverity {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for UserMessageSeverity {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for UserMessageSeverity {
+    fn encode_by_ref(
+        &self,
+        buf: &mut sqlx::postgres::PgArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Note attached to a patron account
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserMessage {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+    pub body: String,
+    pub severity: UserMessageSeverity,
+    /// Also shown to the patron in the self-service account
+    pub patron_visible: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    /// Staff member or patron who acknowledged the message
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub acknowledged_by: Option<i64>,
+}
+
+impl UserMessage {
+    pub fn is_blocking(&self) -> bool {
+        self.severity == UserMessageSeverity::Blocking
+    }
+}
+
+/// Leave a message on a patron account
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUserMessage {
+    pub body: String,
+    #[serde(default)]
+    pub severity: UserMessageSeverity,
+    #[serde(default)]
+    pub patron_visible: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Query parameters for listing a patron's messages
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserMessageQuery {
+    /// Include acknowledged and expired messages (history)
+    #[serde(default)]
+    pub include_acknowledged: bool,
+}
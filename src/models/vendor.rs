@@ -0,0 +1,247 @@
+//! Vendor (supplier) and purchase order models
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+// ---------------------------------------------------------------------------
+// Vendor
+// ---------------------------------------------------------------------------
+
+/// Supplier record
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Vendor {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub name: String,
+    pub contact_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    pub website: Option<String>,
+    /// Our customer number at the vendor
+    pub customer_number: Option<String>,
+    /// Negotiated discount in percent (0–100)
+    #[schema(value_type = String, example = "9.00")]
+    pub discount_rate: Decimal,
+    pub is_active: bool,
+    pub notes: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+/// Create vendor request
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateVendor {
+    pub name: String,
+    pub contact_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    pub website: Option<String>,
+    pub customer_number: Option<String>,
+    #[schema(value_type = Option<String>)]
+    pub discount_rate: Option<Decimal>,
+    pub notes: Option<String>,
+}
+
+/// Update vendor request
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateVendor {
+    pub name: Option<String>,
+    pub contact_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    pub website: Option<String>,
+    pub customer_number: Option<String>,
+    #[schema(value_type = Option<String>)]
+    pub discount_rate: Option<Decimal>,
+    pub is_active: Option<bool>,
+    pub notes: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// PurchaseOrder
+// ---------------------------------------------------------------------------
+
+/// Purchase order status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PurchaseOrderStatus {
+    Ordered,
+    Received,
+    Cancelled,
+}
+
+impl PurchaseOrderStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ordered => "ordered",
+            Self::Received => "received",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl From<String> for PurchaseOrderStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "received" => Self::Received,
+            "cancelled" => Self::Cancelled,
+            _ => Self::Ordered,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for PurchaseOrderStatus {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for PurchaseOrderStatus {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for PurchaseOrderStatus {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Purchase order placed with a vendor
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PurchaseOrder {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub vendor_id: i64,
+    /// Source the received items are attached to
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub source_id: Option<i64>,
+    /// Vendor-side or internal order reference
+    pub reference: Option<String>,
+    pub order_date: NaiveDate,
+    pub status: PurchaseOrderStatus,
+    /// Net amount spent (after discount)
+    #[schema(value_type = String, example = "254.80")]
+    pub total_amount: Decimal,
+    pub notes: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+/// Create purchase order request
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePurchaseOrder {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub vendor_id: i64,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub source_id: Option<i64>,
+    pub reference: Option<String>,
+    /// Order date (defaults to today)
+    pub order_date: Option<NaiveDate>,
+    pub status: Option<PurchaseOrderStatus>,
+    #[schema(value_type = String)]
+    pub total_amount: Decimal,
+    pub notes: Option<String>,
+}
+
+/// Update purchase order request
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePurchaseOrder {
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub source_id: Option<i64>,
+    pub reference: Option<String>,
+    pub order_date: Option<NaiveDate>,
+    pub status: Option<PurchaseOrderStatus>,
+    #[schema(value_type = Option<String>)]
+    pub total_amount: Option<Decimal>,
+    pub notes: Option<String>,
+}
+
+/// Query parameters for listing purchase orders
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PurchaseOrderQuery {
+    /// Filter by vendor
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    #[schema(value_type = Option<String>)]
+    pub vendor_id: Option<i64>,
+    /// Filter by order year
+    pub year: Option<i32>,
+    pub status: Option<PurchaseOrderStatus>,
+}
+
+// ---------------------------------------------------------------------------
+// Spend report
+// ---------------------------------------------------------------------------
+
+/// Query parameters for the vendor spend report
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorSpendQuery {
+    /// Restrict to one year (all years when absent)
+    pub year: Option<i32>,
+    /// Restrict to one vendor
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    #[schema(value_type = Option<String>)]
+    pub vendor_id: Option<i64>,
+}
+
+/// Spend with one vendor over one year (cancelled orders excluded)
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorAnnualSpend {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub vendor_id: i64,
+    pub vendor_name: String,
+    pub year: i32,
+    pub orders: i64,
+    /// Total of non-cancelled orders
+    #[schema(value_type = String)]
+    pub total_amount: Decimal,
+    /// Part of `totalAmount` already received
+    #[schema(value_type = String)]
+    pub received_amount: Decimal,
+}
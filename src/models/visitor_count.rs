@@ -22,6 +22,8 @@ pub struct VisitorCount {
     pub source: Option<String>,
     pub notes: Option<String>,
     pub created_at: Option<DateTime<Utc>>,
+    /// Hourly bucket `[countHour, countHour + 1)` in library local time; null for a daily total
+    pub count_hour: Option<i16>,
 }
 
 /// Create visitor count request
@@ -35,10 +37,12 @@ pub struct CreateVisitorCount {
     /// Source: manual, counter, estimate
     pub source: Option<String>,
     pub notes: Option<String>,
+    /// Hour of the count (0-23) for hourly counter buckets; omit for a daily total
+    pub count_hour: Option<i16>,
 }
 
 /// Query parameters for visitor counts
-#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct VisitorCountQuery {
     /// Start date (YYYY-MM-DD)
@@ -46,3 +50,76 @@ pub struct VisitorCountQuery {
     /// End date (YYYY-MM-DD)
     pub end_date: Option<String>,
 }
+
+/// Query parameters of the attendance statistics (`GET /stats/visitors`)
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct VisitorStatsQuery {
+    /// First day (YYYY-MM-DD, default January 1st of the current year)
+    pub start_date: Option<NaiveDate>,
+    /// Last day (YYYY-MM-DD, default today)
+    pub end_date: Option<NaiveDate>,
+}
+
+/// Attendance of one weekday over the range
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VisitorWeekdayStats {
+    /// Day of week (0=Monday, 6=Sunday)
+    pub day_of_week: i16,
+    /// Days with opening hours
+    pub open_days: i64,
+    /// Open days with at least one count
+    pub counted_days: i64,
+    /// Visitors counted during opening hours
+    pub visitors: i64,
+    /// Opening hours of the counted days
+    pub opened_hours: f64,
+    /// `visitors / openedHours` (null when nothing was counted on an open day)
+    pub average_per_opened_hour: Option<f64>,
+}
+
+/// Why a count looks registered while the library was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum VisitorCountAnomalyReason {
+    /// Exceptional closure day
+    Closure,
+    /// No opening hours scheduled that day
+    NoOpeningHours,
+    /// Hourly bucket outside every opening slot of the day
+    OutsideOpeningHours,
+}
+
+/// Count registered during closed hours (typically sensor drift or a wrong date)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VisitorCountAnomaly {
+    pub count: VisitorCount,
+    pub reason: VisitorCountAnomalyReason,
+}
+
+/// Visitor counts compared with the opening hours
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VisitorStats {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// Every count of the range, anomalies included
+    pub total_visitors: i64,
+    /// Visitors counted during opening hours
+    pub visitors_in_opening_hours: i64,
+    /// Visitors counted while the library was closed
+    pub visitors_in_closed_hours: i64,
+    /// Days with opening hours
+    pub open_days: i64,
+    /// Scheduled opening hours of the whole range
+    pub opened_hours: f64,
+    /// Over the counted open days (see `byWeekday`)
+    pub average_per_opened_hour: Option<f64>,
+    /// Monday to Sunday
+    pub by_weekday: Vec<VisitorWeekdayStats>,
+    /// Counts registered during closed hours, by date
+    pub closed_hours_counts: Vec<VisitorCountAnomaly>,
+}
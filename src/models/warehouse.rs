@@ -0,0 +1,401 @@
+//! Data warehouse exports: targets, runs and manifests of the nightly fact extracts
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// File format of the extracts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WarehouseFormat {
+    /// Comma-separated, header line, UTF-8
+    Csv,
+    /// Apache Parquet (one row group per 50 000 rows, uncompressed)
+    #[default]
+    Parquet,
+}
+
+impl WarehouseFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Parquet => "parquet",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        self.as_str()
+    }
+}
+
+impl From<String> for WarehouseFormat {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "csv" => Self::Csv,
+            _ => Self::Parquet,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for WarehouseFormat {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for WarehouseFormat {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for WarehouseFormat {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Fact table sent to the warehouse
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WarehouseFact {
+    /// Loans started or returned since the watermark (active and archived; upsert on `loan_key`)
+    Loans,
+    /// Copies entered in the catalog since the watermark
+    Acquisitions,
+    /// Visitor counts recorded since the watermark
+    Visits,
+}
+
+impl WarehouseFact {
+    pub const ALL: [WarehouseFact; 3] = [Self::Loans, Self::Acquisitions, Self::Visits];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Loans => "loans",
+            Self::Acquisitions => "acquisitions",
+            Self::Visits => "visits",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.as_str() == s)
+    }
+}
+
+/// Warehouse export target (the password / secret key is never returned)
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WarehouseTarget {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    pub name: String,
+    /// `s3://bucket/prefix/` or `sftp://host[:port]/dir/`
+    pub url: String,
+    /// S3 access key id, or SFTP user
+    pub username: Option<String>,
+    #[serde(skip)]
+    pub password: Option<String>,
+    pub has_password: bool,
+    /// Expected SSH host key of an SFTP server (`SHA256:…`)
+    pub host_key_fingerprint: Option<String>,
+    /// S3 region (default `us-east-1`)
+    pub region: String,
+    /// Endpoint of an S3-compatible store (e.g. `https://minio.city.local:9000`), path-style
+    pub endpoint: Option<String>,
+    pub format: WarehouseFormat,
+    /// `loans`, `acquisitions`, `visits`
+    pub facts: Vec<String>,
+    /// Local time of the nightly run (HH:MM)
+    pub schedule_time: String,
+    pub is_active: bool,
+    /// Per fact: rows changed up to this instant were sent by a successful run
+    #[sqlx(json)]
+    #[schema(value_type = Object)]
+    pub watermarks: BTreeMap<String, DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub update_at: Option<DateTime<Utc>>,
+}
+
+/// Register a warehouse export target
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWarehouseTarget {
+    pub name: String,
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Required for `sftp://` URLs
+    pub host_key_fingerprint: Option<String>,
+    /// Default `us-east-1`
+    pub region: Option<String>,
+    pub endpoint: Option<String>,
+    /// Default `parquet`
+    pub format: Option<WarehouseFormat>,
+    /// Default: every fact
+    pub facts: Option<Vec<WarehouseFact>>,
+    /// Default `01:30`
+    pub schedule_time: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+/// Update a warehouse export target (absent fields are kept)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWarehouseTarget {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub username: Option<String>,
+    /// New password / secret key; an empty string removes it
+    pub password: Option<String>,
+    pub host_key_fingerprint: Option<String>,
+    pub region: Option<String>,
+    /// An empty string removes it
+    pub endpoint: Option<String>,
+    pub format: Option<WarehouseFormat>,
+    pub facts: Option<Vec<WarehouseFact>>,
+    pub schedule_time: Option<String>,
+    pub is_active: Option<bool>,
+    /// Forget the watermarks, so the next run sends the full history again
+    #[serde(default)]
+    pub reset_watermarks: bool,
+}
+
+/// What started a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WarehouseTrigger {
+    Schedule,
+    Manual,
+}
+
+impl WarehouseTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Schedule => "schedule",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+impl From<String> for WarehouseTrigger {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "manual" => Self::Manual,
+            _ => Self::Schedule,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for WarehouseTrigger {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for WarehouseTrigger {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for WarehouseTrigger {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Outcome of a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WarehouseRunStatus {
+    Running,
+    /// Files and manifest uploaded, watermarks moved
+    Succeeded,
+    /// Extract or upload failure; the watermarks stay where they were
+    Failed,
+}
+
+impl WarehouseRunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl From<String> for WarehouseRunStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "succeeded" => Self::Succeeded,
+            "failed" => Self::Failed,
+            _ => Self::Running,
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for WarehouseRunStatus {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for WarehouseRunStatus {
+    fn decode(
+        value: sqlx::postgres::PgValueRef<'r>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let s: String = sqlx::Decode::<sqlx::Postgres>::decode(value)?;
+        Ok(Self::from(s))
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for WarehouseRunStatus {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str().to_string(), buf)
+    }
+}
+
+/// Column of an extract (`bigint`, `boolean`, `text`, `date` or `timestamp`)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WarehouseColumn {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub data_type: String,
+}
+
+/// One extract file of a run
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WarehouseManifestFile {
+    pub fact: WarehouseFact,
+    /// Path relative to the target URL
+    pub path: String,
+    pub rows: u64,
+    pub bytes: u64,
+    pub sha256: String,
+    /// Rows changed after this instant (absent on the first run: full history)
+    pub changed_after: Option<DateTime<Utc>>,
+    /// ... and up to this instant
+    pub changed_until: DateTime<Utc>,
+    pub columns: Vec<WarehouseColumn>,
+}
+
+/// `manifest.json`, uploaded last: a run directory without it is incomplete
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WarehouseManifest {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub run_id: i64,
+    pub generated_at: DateTime<Utc>,
+    pub format: WarehouseFormat,
+    pub files: Vec<WarehouseManifestFile>,
+}
+
+/// Report of one export run
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WarehouseRun {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub id: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub target_id: i64,
+    pub trigger: WarehouseTrigger,
+    pub status: WarehouseRunStatus,
+    /// Staff member who started a manual run
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub started_by: Option<i64>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    #[sqlx(json)]
+    pub manifest: Option<WarehouseManifest>,
+    /// Why the run stopped
+    pub error: Option<String>,
+}
+
+/// Query parameters of the run history
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WarehouseRunsQuery {
+    /// Most recent runs returned (default 20, max 100)
+    pub limit: Option<i64>,
+}
+
+/// Anonymized loan: borrower reduced to account type, audience type and age band at loan time
+#[derive(Debug, Clone, FromRow)]
+pub struct LoanFact {
+    /// `loan-<id>` or `archive-<id>`
+    pub loan_key: String,
+    pub loan_date: Option<DateTime<Utc>>,
+    pub due_date: Option<DateTime<Utc>>,
+    pub returned_at: Option<DateTime<Utc>>,
+    pub renewals: Option<i64>,
+    pub item_id: Option<i64>,
+    pub biblio_id: Option<i64>,
+    pub media_type: Option<String>,
+    pub audience_type: Option<String>,
+    pub source_id: Option<i64>,
+    pub place: Option<i64>,
+    pub borrower_account_type: Option<String>,
+    pub borrower_public_type: Option<String>,
+    pub borrower_age_band: Option<String>,
+    /// Change instant compared with the watermark (loan or return)
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Copy entered in the catalog
+#[derive(Debug, Clone, FromRow)]
+pub struct AcquisitionFact {
+    pub item_id: i64,
+    pub biblio_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub media_type: Option<String>,
+    pub audience_type: Option<String>,
+    pub source_id: Option<i64>,
+    pub place: Option<i64>,
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+/// Visitor count (daily total, or one hour when `count_hour` is set)
+#[derive(Debug, Clone, FromRow)]
+pub struct VisitFact {
+    pub visit_id: i64,
+    pub count_date: NaiveDate,
+    pub count_hour: Option<i64>,
+    pub visitors: i64,
+    pub source: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
@@ -42,7 +42,8 @@ impl Repository {
         sqlx::query_as::<_, AccountTypeDefinition>(
             r#"
             SELECT code, name, items_rights, users_rights, loans_rights,
-                   items_archive_rights, holds_rights, settings_rights, events_rights
+                   items_archive_rights, holds_rights, settings_rights, events_rights,
+                   circulation_override_rights, priority_holds_rights, group_loans_max_items, group_loans_max_days
             FROM account_types
             ORDER BY code
             "#,
@@ -56,7 +57,8 @@ impl Repository {
         sqlx::query_as::<_, AccountTypeDefinition>(
             r#"
             SELECT code, name, items_rights, users_rights, loans_rights,
-                   items_archive_rights, holds_rights, settings_rights, events_rights
+                   items_archive_rights, holds_rights, settings_rights, events_rights,
+                   circulation_override_rights, priority_holds_rights, group_loans_max_items, group_loans_max_days
             FROM account_types
             WHERE code = $1
             "#,
@@ -93,6 +95,10 @@ impl Repository {
         add_opt!(data.holds_rights, "holds_rights");
         add_opt!(data.settings_rights, "settings_rights");
         add_opt!(data.events_rights, "events_rights");
+        add_opt!(data.circulation_override_rights, "circulation_override_rights");
+        add_opt!(data.priority_holds_rights, "priority_holds_rights");
+        add_opt!(data.group_loans_max_items, "group_loans_max_items");
+        add_opt!(data.group_loans_max_days, "group_loans_max_days");
 
         if sets.is_empty() {
             return Err(AppError::Validation(
@@ -102,7 +108,7 @@ impl Repository {
 
         let q = format!(
             "UPDATE account_types SET {} WHERE code = ${} RETURNING code, name, items_rights, users_rights, loans_rights, \
-             items_archive_rights, holds_rights, settings_rights, events_rights",
+             items_archive_rights, holds_rights, settings_rights, events_rights, circulation_override_rights, priority_holds_rights, group_loans_max_items, group_loans_max_days",
             sets.join(", "),
             idx
         );
@@ -125,6 +131,15 @@ impl Repository {
         bind_opt!(data.holds_rights);
         bind_opt!(data.settings_rights);
         bind_opt!(data.events_rights);
+        bind_opt!(data.circulation_override_rights);
+        bind_opt!(data.priority_holds_rights);
+        // `0` clears a group loan limit
+        if let Some(v) = data.group_loans_max_items {
+            b = b.bind((v > 0).then_some(v));
+        }
+        if let Some(v) = data.group_loans_max_days {
+            b = b.bind((v > 0).then_some(v));
+        }
 
         b = b.bind(code);
 
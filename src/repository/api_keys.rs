@@ -0,0 +1,247 @@
+//! API keys (`api_keys`, `api_key_usage`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::api_key::{ApiKey, ApiKeyUsage, CreateApiKey, UpdateApiKey},
+};
+
+/// Every column but `token_hash`
+const API_KEY_COLUMNS: &str = "id, name, token_prefix, scopes, is_active, expires_at, last_used_at, \
+     created_by, created_at, update_at";
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ApiKeysRepository: Send + Sync {
+    async fn api_keys_list(&self) -> AppResult<Vec<ApiKey>>;
+    async fn api_keys_get(&self, id: i64) -> AppResult<ApiKey>;
+    /// Key owning this token hash, whatever its state.
+    async fn api_keys_find_by_token_hash(&self, token_hash: &str) -> AppResult<Option<ApiKey>>;
+    async fn api_keys_create(
+        &self,
+        data: &CreateApiKey,
+        token_hash: &str,
+        token_prefix: &str,
+        created_by: i64,
+    ) -> AppResult<ApiKey>;
+    async fn api_keys_update(&self, id: i64, data: &UpdateApiKey) -> AppResult<ApiKey>;
+    async fn api_keys_set_token(&self, id: i64, token_hash: &str, token_prefix: &str) -> AppResult<ApiKey>;
+    async fn api_keys_delete(&self, id: i64) -> AppResult<()>;
+    /// Count one request (and `records` requested records) on today's usage row for `endpoint`.
+    async fn api_keys_record_usage(&self, id: i64, endpoint: &str, records: i64) -> AppResult<()>;
+    /// Usage rows between two days (inclusive), newest first.
+    async fn api_keys_usage(&self, id: i64, from: NaiveDate, to: NaiveDate) -> AppResult<Vec<ApiKeyUsage>>;
+}
+
+#[async_trait]
+impl ApiKeysRepository for Repository {
+    async fn api_keys_list(&self) -> AppResult<Vec<ApiKey>> {
+        Repository::api_keys_list(self).await
+    }
+    async fn api_keys_get(&self, id: i64) -> AppResult<ApiKey> {
+        Repository::api_keys_get(self, id).await
+    }
+    async fn api_keys_find_by_token_hash(&self, token_hash: &str) -> AppResult<Option<ApiKey>> {
+        Repository::api_keys_find_by_token_hash(self, token_hash).await
+    }
+    async fn api_keys_create(
+        &self,
+        data: &CreateApiKey,
+        token_hash: &str,
+        token_prefix: &str,
+        created_by: i64,
+    ) -> AppResult<ApiKey> {
+        Repository::api_keys_create(self, data, token_hash, token_prefix, created_by).await
+    }
+    async fn api_keys_update(&self, id: i64, data: &UpdateApiKey) -> AppResult<ApiKey> {
+        Repository::api_keys_update(self, id, data).await
+    }
+    async fn api_keys_set_token(&self, id: i64, token_hash: &str, token_prefix: &str) -> AppResult<ApiKey> {
+        Repository::api_keys_set_token(self, id, token_hash, token_prefix).await
+    }
+    async fn api_keys_delete(&self, id: i64) -> AppResult<()> {
+        Repository::api_keys_delete(self, id).await
+    }
+    async fn api_keys_record_usage(&self, id: i64, endpoint: &str, records: i64) -> AppResult<()> {
+        Repository::api_keys_record_usage(self, id, endpoint, records).await
+    }
+    async fn api_keys_usage(&self, id: i64, from: NaiveDate, to: NaiveDate) -> AppResult<Vec<ApiKeyUsage>> {
+        Repository::api_keys_usage(self, id, from, to).await
+    }
+}
+
+impl Repository {
+    /// List API keys by name
+    #[tracing::instrument(skip(self), err)]
+    pub async fn api_keys_list(&self) -> AppResult<Vec<ApiKey>> {
+        let rows = sqlx::query_as::<_, ApiKey>(&format!(
+            "SELECT {} FROM api_keys ORDER BY name, id",
+            API_KEY_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get an API key by ID
+    #[tracing::instrument(skip(self), err)]
+    pub async fn api_keys_get(&self, id: i64) -> AppResult<ApiKey> {
+        sqlx::query_as::<_, ApiKey>(&format!("SELECT {} FROM api_keys WHERE id = $1", API_KEY_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("API key {} not found", id)))
+    }
+
+    /// Find the API key owning a token hash
+    #[tracing::instrument(skip(self, token_hash), err)]
+    pub async fn api_keys_find_by_token_hash(&self, token_hash: &str) -> AppResult<Option<ApiKey>> {
+        let row = sqlx::query_as::<_, ApiKey>(&format!(
+            "SELECT {} FROM api_keys WHERE token_hash = $1",
+            API_KEY_COLUMNS
+        ))
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Create an API key
+    #[tracing::instrument(skip(self, token_hash), err)]
+    pub async fn api_keys_create(
+        &self,
+        data: &CreateApiKey,
+        token_hash: &str,
+        token_prefix: &str,
+        created_by: i64,
+    ) -> AppResult<ApiKey> {
+        let row = sqlx::query_as::<_, ApiKey>(&format!(
+            r#"
+            INSERT INTO api_keys (name, token_hash, token_prefix, scopes, expires_at, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            API_KEY_COLUMNS
+        ))
+        .bind(data.name.trim())
+        .bind(token_hash)
+        .bind(token_prefix)
+        .bind(&data.scopes)
+        .bind(data.expires_at)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Update an API key (absent fields are kept)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn api_keys_update(&self, id: i64, data: &UpdateApiKey) -> AppResult<ApiKey> {
+        sqlx::query_as::<_, ApiKey>(&format!(
+            r#"
+            UPDATE api_keys SET
+                name = COALESCE($1, name),
+                scopes = COALESCE($2, scopes),
+                is_active = COALESCE($3, is_active),
+                expires_at = CASE WHEN $4::timestamptz IS NOT NULL THEN $4
+                                  WHEN $5 THEN NULL
+                                  ELSE expires_at END,
+                update_at = $6
+            WHERE id = $7
+            RETURNING {}
+            "#,
+            API_KEY_COLUMNS
+        ))
+        .bind(data.name.as_deref().map(str::trim))
+        .bind(&data.scopes)
+        .bind(data.is_active)
+        .bind(data.expires_at)
+        .bind(data.clear_expires_at)
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("API key {} not found", id)))
+    }
+
+    /// Replace the secret of an API key (the previous one stops working immediately)
+    #[tracing::instrument(skip(self, token_hash), err)]
+    pub async fn api_keys_set_token(&self, id: i64, token_hash: &str, token_prefix: &str) -> AppResult<ApiKey> {
+        sqlx::query_as::<_, ApiKey>(&format!(
+            r#"
+            UPDATE api_keys SET token_hash = $1, token_prefix = $2, last_used_at = NULL, update_at = $3
+            WHERE id = $4
+            RETURNING {}
+            "#,
+            API_KEY_COLUMNS
+        ))
+        .bind(token_hash)
+        .bind(token_prefix)
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("API key {} not found", id)))
+    }
+
+    /// Delete an API key (and its usage history)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn api_keys_delete(&self, id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM api_keys WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("API key {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Count a request on today's usage row and stamp the key's last use
+    #[tracing::instrument(skip(self), err)]
+    pub async fn api_keys_record_usage(&self, id: i64, endpoint: &str, records: i64) -> AppResult<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO api_key_usage (api_key_id, day, endpoint, request_count, record_count)
+            VALUES ($1, CURRENT_DATE, $2, 1, $3)
+            ON CONFLICT (api_key_id, day, endpoint) DO UPDATE SET
+                request_count = api_key_usage.request_count + 1,
+                record_count = api_key_usage.record_count + EXCLUDED.record_count
+            "#,
+        )
+        .bind(id)
+        .bind(endpoint)
+        .bind(records)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE api_keys SET last_used_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Usage rows of a key between two days (inclusive)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn api_keys_usage(&self, id: i64, from: NaiveDate, to: NaiveDate) -> AppResult<Vec<ApiKeyUsage>> {
+        let rows = sqlx::query_as::<_, ApiKeyUsage>(
+            r#"
+            SELECT day, endpoint, request_count, record_count
+            FROM api_key_usage
+            WHERE api_key_id = $1 AND day BETWEEN $2 AND $3
+            ORDER BY day DESC, endpoint
+            "#,
+        )
+        .bind(id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
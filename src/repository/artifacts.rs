@@ -0,0 +1,141 @@
+//! Generated files (`artifacts`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::artifact::{Artifact, NewArtifact},
+};
+
+const ARTIFACT_COLUMNS: &str = "id, kind, filename, content_type, size_bytes, storage_key, created_by, \
+     created_at, expires_at, download_count, last_downloaded_at";
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ArtifactsRepository: Send + Sync {
+    async fn artifacts_create(&self, data: &NewArtifact) -> AppResult<Artifact>;
+    async fn artifacts_get(&self, id: i64) -> AppResult<Artifact>;
+    /// Unexpired artifacts, newest first (all users when `created_by` is `None`).
+    async fn artifacts_list(&self, created_by: Option<i64>) -> AppResult<Vec<Artifact>>;
+    async fn artifacts_record_download(&self, id: i64) -> AppResult<()>;
+    /// Expired artifacts, oldest first.
+    async fn artifacts_list_expired(&self, limit: i64) -> AppResult<Vec<Artifact>>;
+    async fn artifacts_delete(&self, id: i64) -> AppResult<()>;
+}
+
+#[async_trait]
+impl ArtifactsRepository for Repository {
+    async fn artifacts_create(&self, data: &NewArtifact) -> AppResult<Artifact> {
+        Repository::artifacts_create(self, data).await
+    }
+    async fn artifacts_get(&self, id: i64) -> AppResult<Artifact> {
+        Repository::artifacts_get(self, id).await
+    }
+    async fn artifacts_list(&self, created_by: Option<i64>) -> AppResult<Vec<Artifact>> {
+        Repository::artifacts_list(self, created_by).await
+    }
+    async fn artifacts_record_download(&self, id: i64) -> AppResult<()> {
+        Repository::artifacts_record_download(self, id).await
+    }
+    async fn artifacts_list_expired(&self, limit: i64) -> AppResult<Vec<Artifact>> {
+        Repository::artifacts_list_expired(self, limit).await
+    }
+    async fn artifacts_delete(&self, id: i64) -> AppResult<()> {
+        Repository::artifacts_delete(self, id).await
+    }
+}
+
+impl Repository {
+    /// Record a stored artifact
+    #[tracing::instrument(skip(self), err)]
+    pub async fn artifacts_create(&self, data: &NewArtifact) -> AppResult<Artifact> {
+        let row = sqlx::query_as::<_, Artifact>(&format!(
+            r#"
+            INSERT INTO artifacts (kind, filename, content_type, size_bytes, storage_key, created_by, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            ARTIFACT_COLUMNS
+        ))
+        .bind(data.kind)
+        .bind(&data.filename)
+        .bind(&data.content_type)
+        .bind(data.size_bytes)
+        .bind(&data.storage_key)
+        .bind(data.created_by)
+        .bind(data.expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Get an artifact by ID (expired ones included until cleanup)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn artifacts_get(&self, id: i64) -> AppResult<Artifact> {
+        sqlx::query_as::<_, Artifact>(&format!("SELECT {} FROM artifacts WHERE id = $1", ARTIFACT_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Artifact {} not found", id)))
+    }
+
+    /// Unexpired artifacts, newest first, optionally for one user
+    #[tracing::instrument(skip(self), err)]
+    pub async fn artifacts_list(&self, created_by: Option<i64>) -> AppResult<Vec<Artifact>> {
+        let rows = sqlx::query_as::<_, Artifact>(&format!(
+            r#"
+            SELECT {} FROM artifacts
+            WHERE expires_at > $1 AND ($2::bigint IS NULL OR created_by = $2)
+            ORDER BY created_at DESC, id DESC
+            "#,
+            ARTIFACT_COLUMNS
+        ))
+        .bind(Utc::now())
+        .bind(created_by)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Count a download
+    #[tracing::instrument(skip(self), err)]
+    pub async fn artifacts_record_download(&self, id: i64) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE artifacts SET download_count = download_count + 1, last_downloaded_at = $1 WHERE id = $2",
+        )
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Expired artifacts, oldest first
+    #[tracing::instrument(skip(self), err)]
+    pub async fn artifacts_list_expired(&self, limit: i64) -> AppResult<Vec<Artifact>> {
+        let rows = sqlx::query_as::<_, Artifact>(&format!(
+            "SELECT {} FROM artifacts WHERE expires_at <= $1 ORDER BY expires_at LIMIT $2",
+            ARTIFACT_COLUMNS
+        ))
+        .bind(Utc::now())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Delete an artifact row (the blob is removed by the caller)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn artifacts_delete(&self, id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM artifacts WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Artifact {} not found", id)));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+//! Backup history (`backups`), domain methods on Repository
+
+use async_trait::async_trait;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::backup::{Backup, BackupDestination},
+};
+
+/// `redis_notes` is read as JSON `null` rather than SQL NULL so it decodes as `Option`
+const BACKUP_COLUMNS: &str = "id, status, destination, filename, artifact_id, location, size_bytes, sha256, \
+     schema_version, table_count, row_count, COALESCE(redis_notes, 'null'::jsonb) AS redis_notes, \
+     created_by, started_at, finished_at, error";
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait BackupsRepository: Send + Sync {
+    /// Open a backup unless another one is running (`None` then)
+    async fn backups_start(
+        &self,
+        destination: BackupDestination,
+        filename: &str,
+        created_by: Option<i64>,
+    ) -> AppResult<Option<Backup>>;
+    async fn backups_finish(&self, backup: &Backup) -> AppResult<Backup>;
+    async fn backups_list(&self, limit: i64) -> AppResult<Vec<Backup>>;
+    async fn backups_get(&self, id: i64) -> AppResult<Backup>;
+}
+
+#[async_trait]
+impl BackupsRepository for Repository {
+    async fn backups_start(
+        &self,
+        destination: BackupDestination,
+        filename: &str,
+        created_by: Option<i64>,
+    ) -> AppResult<Option<Backup>> {
+        Repository::backups_start(self, destination, filename, created_by).await
+    }
+    async fn backups_finish(&self, backup: &Backup) -> AppResult<Backup> {
+        Repository::backups_finish(self, backup).await
+    }
+    async fn backups_list(&self, limit: i64) -> AppResult<Vec<Backup>> {
+        Repository::backups_list(self, limit).await
+    }
+    async fn backups_get(&self, id: i64) -> AppResult<Backup> {
+        Repository::backups_get(self, id).await
+    }
+}
+
+impl Repository {
+    /// Open a backup. One at a time: backups left `running` for more than 12 hours (server
+    /// stopped mid-way) no longer block new ones.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn backups_start(
+        &self,
+        destination: BackupDestination,
+        filename: &str,
+        created_by: Option<i64>,
+    ) -> AppResult<Option<Backup>> {
+        let mut tx = self.pool.begin().await?;
+        // Serializes concurrent starts (the NOT EXISTS alone would let two through)
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('elidune.backups'))")
+            .execute(&mut *tx)
+            .await?;
+        let row = sqlx::query_as::<_, Backup>(&format!(
+            r#"
+            INSERT INTO backups (destination, filename, created_by)
+            SELECT $1, $2, $3
+            WHERE NOT EXISTS (
+                SELECT 1 FROM backups
+                WHERE status = 'running' AND started_at > NOW() - INTERVAL '12 hours'
+            )
+            RETURNING {}
+            "#,
+            BACKUP_COLUMNS
+        ))
+        .bind(destination)
+        .bind(filename)
+        .bind(created_by)
+        .fetch_optional(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(row)
+    }
+
+    /// Store the outcome of a backup
+    #[tracing::instrument(skip(self, backup), fields(backup_id = backup.id), err)]
+    pub async fn backups_finish(&self, backup: &Backup) -> AppResult<Backup> {
+        sqlx::query_as::<_, Backup>(&format!(
+            r#"
+            UPDATE backups SET
+                status = $1, artifact_id = $2, location = $3, size_bytes = $4, sha256 = $5,
+                schema_version = $6, table_count = $7, row_count = $8, redis_notes = $9,
+                error = $10, finished_at = NOW()
+            WHERE id = $11
+            RETURNING {}
+            "#,
+            BACKUP_COLUMNS
+        ))
+        .bind(backup.status)
+        .bind(backup.artifact_id)
+        .bind(backup.location.as_deref())
+        .bind(backup.size_bytes)
+        .bind(backup.sha256.as_deref())
+        .bind(backup.schema_version)
+        .bind(backup.table_count)
+        .bind(backup.row_count)
+        .bind(backup.redis_notes.as_ref().map(sqlx::types::Json))
+        .bind(backup.error.as_deref())
+        .bind(backup.id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Backup {} not found", backup.id)))
+    }
+
+    /// Most recent backups
+    #[tracing::instrument(skip(self), err)]
+    pub async fn backups_list(&self, limit: i64) -> AppResult<Vec<Backup>> {
+        let rows = sqlx::query_as::<_, Backup>(&format!(
+            "SELECT {} FROM backups ORDER BY started_at DESC, id DESC LIMIT $1",
+            BACKUP_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get a backup by ID
+    #[tracing::instrument(skip(self), err)]
+    pub async fn backups_get(&self, id: i64) -> AppResult<Backup> {
+        sqlx::query_as::<_, Backup>(&format!("SELECT {} FROM backups WHERE id = $1", BACKUP_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Backup {} not found", id)))
+    }
+}
@@ -4,7 +4,7 @@
 //! uses the associated char or int (e.g. media_type string from Leader record_type).
 
 use std::collections::HashMap;
-use chrono::Utc;
+use chrono::{Datelike, Local, Utc};
 use sqlx::{FromRow, Row};
 use sqlx::types::Json;
 
@@ -15,14 +15,45 @@ use crate::{
     marc::MarcRecord,
     models::{
         author::Author,
-        author::Function,
+        author::{normalize_author_name, Function},
         import_report::DuplicateCandidate,
-        biblio::{Collection, Edition, Isbn, Biblio, BiblioQuery, BiblioShort, MeiliBiblioDocument, MediaType, Serie},
-        item::Item,
+        biblio::{
+            Collection, Edition, Isbn, Biblio, BiblioAvailability, BiblioFacets, BiblioQuery, BiblioShort,
+            CatalogSearchField, FacetCount, Suggestion, HEADING_FACET_LIMIT,
+            CatalogSearchNode, MeiliBiblioDocument, MediaType, NewAcquisition, Serie,
+        },
+        item::{
+            format_inventory_number, CallNumberCandidate, Item, ItemExportRow, ProcessingSlipRow, SpineLabelQuery,
+            SpineLabelRow,
+        },
     },
 };
 use async_trait::async_trait;
 
+/// Availability columns of [`BiblioAvailability`] (copies of `i`, grouped by `b.id`).
+///
+/// A copy whose bundle has a part out is not available: the set only circulates complete, nor
+/// is a copy travelling back to its place.
+const AVAILABILITY_COLUMNS_SQL: &str = r#"b.id AS biblio_id, b.isbn, b.title,
+       COUNT(i.id) FILTER (WHERE i.borrowable) AS total_items,
+       COUNT(i.id) FILTER (
+           WHERE i.borrowable
+             AND i.transit_place IS NULL
+             AND NOT EXISTS (SELECT 1 FROM loans l WHERE l.item_id = i.id AND l.returned_at IS NULL)
+             AND NOT EXISTS (
+                 SELECT 1 FROM items bi
+                 JOIN loans bl ON bl.item_id = bi.id AND bl.returned_at IS NULL
+                 WHERE bi.bundle_id = i.bundle_id
+             )
+       ) AS available_items,
+       (SELECT COUNT(*) FROM holds h
+        INNER JOIN items hi ON hi.id = h.item_id
+        WHERE hi.biblio_id = b.id AND h.status IN ('pending','ready','in_locker')) AS hold_count"#;
+
+/// Biblios with their active copies, for [`AVAILABILITY_COLUMNS_SQL`] (more copy conditions may follow)
+const AVAILABILITY_FROM_SQL: &str =
+    "FROM biblios b LEFT JOIN items i ON i.biblio_id = b.id AND i.archived_at IS NULL";
+
 /// Contract for [`Repository`] biblio/item persistence. Implemented below; services may use
 /// `Arc<dyn BibliosRepository>` for substitution in tests.
 #[async_trait]
@@ -30,6 +61,15 @@ pub trait BibliosRepository: Send + Sync {
     async fn biblios_get_by_id(&self, id: i64) -> AppResult<Biblio>;
     async fn biblios_get_short_by_id(&self, id: i64) -> AppResult<BiblioShort>;
     async fn biblios_search(&self, query: &BiblioQuery) -> AppResult<(Vec<BiblioShort>, i64)>;
+    /// Facet counts over every biblio matching `query` (pagination ignored).
+    async fn biblios_search_facets(&self, query: &BiblioQuery) -> AppResult<BiblioFacets>;
+    /// Titles of non-archived biblios completing `q` (prefix matches first, then fuzzy ones).
+    async fn biblios_suggest_titles(&self, q: &str, limit: i64) -> AppResult<Vec<Suggestion>>;
+    /// Authors of non-archived biblios whose name completes `q`.
+    async fn biblios_suggest_authors(&self, q: &str, limit: i64) -> AppResult<Vec<Suggestion>>;
+    /// Ids of active biblios (with at least one active copy) matching a boolean search tree,
+    /// ordered by id and capped at `limit`, with the total number of hits.
+    async fn biblios_search_tree(&self, node: &CatalogSearchNode, limit: i64) -> AppResult<(Vec<i64>, i64)>;
     async fn biblios_get_by_series(&self, series_id: i64) -> AppResult<Vec<BiblioShort>>;
     async fn biblios_get_by_collection(&self, collection_id: i64) -> AppResult<Vec<BiblioShort>>;
     async fn biblios_get_meili_document(&self, id: i64) -> AppResult<Option<MeiliBiblioDocument>>;
@@ -53,6 +93,11 @@ pub trait BibliosRepository: Send + Sync {
         &self,
         biblio_ids: &[i64],
     ) -> AppResult<HashMap<i64, Vec<ItemShort>>>;
+    /// Authors of many biblios (in credit order), keyed by biblio id.
+    async fn biblios_get_authors_by_biblio_ids(
+        &self,
+        biblio_ids: &[i64],
+    ) -> AppResult<HashMap<i64, Vec<Author>>>;
     async fn biblios_create_item(&self, biblio_id: i64, item: &Item) -> AppResult<Item>;
     async fn upsert_item<'a>(&self, item: &'a mut Item) -> AppResult<&'a mut Item>;
     async fn items_update<'a>(&self, item: &'a mut Item) -> AppResult<&'a mut Item>;
@@ -82,7 +127,36 @@ pub trait BibliosRepository: Send + Sync {
     async fn biblios_find_by_isbn_for_import(&self, isbn: &str) -> AppResult<Option<DuplicateCandidate>>;
     async fn biblios_update_marc_record(&self, biblio: &mut Biblio) -> AppResult<()>;
     async fn biblios_isbn_exists(&self, isbn: &str, exclude_id: Option<i64>) -> AppResult<bool>;
+    /// Copy availability for the active biblio carrying this ISBN (`None` when unknown).
+    async fn biblios_get_availability_by_isbn(&self, isbn: &Isbn) -> AppResult<Option<BiblioAvailability>>;
+    /// Copy availability for many ISBNs, keyed by ISBN (unknown ISBNs are absent).
+    async fn biblios_get_availability_by_isbns(&self, isbns: &[Isbn]) -> AppResult<HashMap<Isbn, BiblioAvailability>>;
+    /// Copy availability for many biblios, keyed by biblio id (archived or unknown ids are absent).
+    async fn biblios_get_availability_by_ids(
+        &self,
+        biblio_ids: &[i64],
+    ) -> AppResult<HashMap<i64, BiblioAvailability>>;
+    /// Active biblios whose first copy was added within the last `days` days, newest first.
+    async fn biblios_list_new_acquisitions(&self, days: i64, limit: i64) -> AppResult<Vec<NewAcquisition>>;
     async fn biblios_count_items_for_source(&self, source_id: i64) -> AppResult<i64>;
+    /// Active copies with a call number, optionally filtered by source and call-number prefix.
+    async fn items_call_number_candidates(
+        &self,
+        source_id: Option<i64>,
+        call_number_prefix: Option<&str>,
+    ) -> AppResult<Vec<CallNumberCandidate>>;
+    /// Write new call numbers (single statement); returns the number of updated rows.
+    async fn items_set_call_numbers(&self, changes: &[(i64, String)]) -> AppResult<u64>;
+    /// Next `limit` active copies with `id > after_id`, by id (keyset pagination for exports).
+    async fn items_export_page(&self, after_id: i64, limit: i64) -> AppResult<Vec<ItemExportRow>>;
+    /// Active copies with a call number matching the label filter, in shelf order (at most `limit`).
+    async fn items_spine_labels(&self, query: &SpineLabelQuery, limit: i64) -> AppResult<Vec<SpineLabelRow>>;
+    /// Copies to print processing slips for, in the order of `item_ids` (unknown ids are skipped).
+    async fn items_processing_slips(&self, item_ids: &[i64]) -> AppResult<Vec<ProcessingSlipRow>>;
+    /// Next `limit` active biblio ids with at least one active copy, `id > after_id`, ascending.
+    async fn biblios_export_ids_page(&self, after_id: i64, limit: i64) -> AppResult<Vec<i64>>;
+    /// Record one click-through on a digital resource (`user_id` is `None` for anonymous access).
+    async fn items_log_access(&self, item_id: i64, user_id: Option<i64>) -> AppResult<()>;
     async fn biblios_reassign_items_source(
         &self,
         old_source_ids: &[i64],
@@ -116,6 +190,18 @@ impl BibliosRepository for Repository {
     async fn biblios_search(&self, query: &crate::models::biblio::BiblioQuery) -> crate::error::AppResult<(Vec<crate::models::biblio::BiblioShort>, i64)> {
         Repository::biblios_search(self, query).await
     }
+    async fn biblios_search_facets(&self, query: &BiblioQuery) -> crate::error::AppResult<BiblioFacets> {
+        Repository::biblios_search_facets(self, query).await
+    }
+    async fn biblios_suggest_titles(&self, q: &str, limit: i64) -> crate::error::AppResult<Vec<Suggestion>> {
+        Repository::biblios_suggest_titles(self, q, limit).await
+    }
+    async fn biblios_suggest_authors(&self, q: &str, limit: i64) -> crate::error::AppResult<Vec<Suggestion>> {
+        Repository::biblios_suggest_authors(self, q, limit).await
+    }
+    async fn biblios_search_tree(&self, node: &CatalogSearchNode, limit: i64) -> crate::error::AppResult<(Vec<i64>, i64)> {
+        Repository::biblios_search_tree(self, node, limit).await
+    }
     async fn biblios_get_by_series(&self, series_id: i64) -> crate::error::AppResult<Vec<crate::models::biblio::BiblioShort>> {
         Repository::biblios_get_by_series(self, series_id).await
     }
@@ -152,6 +238,9 @@ impl BibliosRepository for Repository {
     async fn biblios_get_items_short_by_biblio_ids(&self, biblio_ids: &[i64]) -> crate::error::AppResult<std::collections::HashMap<i64, Vec<crate::models::item::ItemShort>>> {
         Repository::biblios_get_items_short_by_biblio_ids(self, biblio_ids).await
     }
+    async fn biblios_get_authors_by_biblio_ids(&self, biblio_ids: &[i64]) -> crate::error::AppResult<std::collections::HashMap<i64, Vec<Author>>> {
+        Repository::biblios_get_authors_by_biblio_ids(self, biblio_ids).await
+    }
     async fn biblios_create_item(&self, biblio_id: i64, item: &crate::models::item::Item) -> crate::error::AppResult<crate::models::item::Item> {
         Repository::biblios_create_item(self, biblio_id, item).await
     }
@@ -188,6 +277,39 @@ impl BibliosRepository for Repository {
     async fn biblios_isbn_exists(&self, isbn: &str, exclude_id: Option<i64>) -> crate::error::AppResult<bool> {
         Repository::biblios_isbn_exists(self, isbn, exclude_id).await
     }
+    async fn biblios_get_availability_by_isbn(&self, isbn: &Isbn) -> crate::error::AppResult<Option<BiblioAvailability>> {
+        Repository::biblios_get_availability_by_isbn(self, isbn).await
+    }
+    async fn biblios_get_availability_by_isbns(&self, isbns: &[Isbn]) -> crate::error::AppResult<HashMap<Isbn, BiblioAvailability>> {
+        Repository::biblios_get_availability_by_isbns(self, isbns).await
+    }
+    async fn biblios_get_availability_by_ids(&self, biblio_ids: &[i64]) -> crate::error::AppResult<std::collections::HashMap<i64, BiblioAvailability>> {
+        Repository::biblios_get_availability_by_ids(self, biblio_ids).await
+    }
+    async fn biblios_list_new_acquisitions(&self, days: i64, limit: i64) -> crate::error::AppResult<Vec<NewAcquisition>> {
+        Repository::biblios_list_new_acquisitions(self, days, limit).await
+    }
+    async fn items_call_number_candidates(&self, source_id: Option<i64>, call_number_prefix: Option<&str>) -> crate::error::AppResult<Vec<CallNumberCandidate>> {
+        Repository::items_call_number_candidates(self, source_id, call_number_prefix).await
+    }
+    async fn items_set_call_numbers(&self, changes: &[(i64, String)]) -> crate::error::AppResult<u64> {
+        Repository::items_set_call_numbers(self, changes).await
+    }
+    async fn items_export_page(&self, after_id: i64, limit: i64) -> crate::error::AppResult<Vec<ItemExportRow>> {
+        Repository::items_export_page(self, after_id, limit).await
+    }
+    async fn items_spine_labels(&self, query: &SpineLabelQuery, limit: i64) -> crate::error::AppResult<Vec<SpineLabelRow>> {
+        Repository::items_spine_labels(self, query, limit).await
+    }
+    async fn items_processing_slips(&self, item_ids: &[i64]) -> crate::error::AppResult<Vec<ProcessingSlipRow>> {
+        Repository::items_processing_slips(self, item_ids).await
+    }
+    async fn biblios_export_ids_page(&self, after_id: i64, limit: i64) -> crate::error::AppResult<Vec<i64>> {
+        Repository::biblios_export_ids_page(self, after_id, limit).await
+    }
+    async fn items_log_access(&self, item_id: i64, user_id: Option<i64>) -> crate::error::AppResult<()> {
+        Repository::items_log_access(self, item_id, user_id).await
+    }
     async fn biblios_count_items_for_source(&self, source_id: i64) -> crate::error::AppResult<i64> {
         Repository::biblios_count_items_for_source(self, source_id).await
     }
@@ -239,6 +361,8 @@ struct ItemShortRow {
     borrowable: bool,
     source_name: Option<String>,
     borrowed: bool,
+    #[sqlx(default)]
+    hidden_from_opac: bool,
 }
 
 impl From<ItemShortRow> for ItemShort {
@@ -250,6 +374,7 @@ impl From<ItemShortRow> for ItemShort {
             borrowable: r.borrowable,
             source_name: r.source_name,
             borrowed: r.borrowed,
+            hidden_from_opac: r.hidden_from_opac,
         }
     }
 }
@@ -272,8 +397,370 @@ impl From<BiblioShortRow> for BiblioShort {
 }
 
 
+/// SQL condition on `b` (biblios) for a search tree; values are appended to `params` as text binds.
+fn search_node_sql(node: &CatalogSearchNode, params: &mut Vec<String>) -> String {
+    match node {
+        CatalogSearchNode::And(a, b) => {
+            format!("({} AND {})", search_node_sql(a, params), search_node_sql(b, params))
+        }
+        CatalogSearchNode::Or(a, b) => {
+            format!("({} OR {})", search_node_sql(a, params), search_node_sql(b, params))
+        }
+        CatalogSearchNode::AndNot(a, b) => {
+            format!("({} AND NOT {})", search_node_sql(a, params), search_node_sql(b, params))
+        }
+        CatalogSearchNode::Term { field, value } => {
+            let identifier: String = value
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric())
+                .collect::<String>()
+                .to_uppercase();
+            let like = format!("%{}%", like_escape(value.trim()));
+            let isbn_sql = |idx: usize| format!("regexp_replace(upper(b.isbn), '[^0-9A-Z]', '', 'g') = ${idx}");
+            let issn_sql = |idx: usize| {
+                format!(
+                    "(EXISTS (SELECT 1 FROM biblio_series bs JOIN series s ON s.id = bs.series_id \
+                      WHERE bs.biblio_id = b.id AND regexp_replace(upper(s.issn), '[^0-9A-Z]', '', 'g') = ${idx}) \
+                     OR EXISTS (SELECT 1 FROM biblio_collections bc JOIN collections c ON c.id = bc.collection_id \
+                      WHERE bc.biblio_id = b.id AND regexp_replace(upper(c.issn), '[^0-9A-Z]', '', 'g') = ${idx}))"
+                )
+            };
+            let title_sql = |idx: usize| format!("b.title_normalized LIKE normalize_search(${idx})");
+            let subject_sql = |idx: usize| format!("unaccent(lower(b.subject)) LIKE unaccent(lower(${idx}))");
+            let author_sql = |idx: usize| {
+                format!(
+                    "EXISTS (SELECT 1 FROM biblio_authors ba JOIN authors a ON a.id = ba.author_id \
+                     WHERE ba.biblio_id = b.id \
+                     AND (concat_ws(' ', a.firstname_normalized, a.lastname_normalized) LIKE normalize_search(${idx}) \
+                          OR concat_ws(', ', a.lastname_normalized, a.firstname_normalized) LIKE normalize_search(${idx})))"
+                )
+            };
+            match field {
+                CatalogSearchField::Title => {
+                    params.push(like);
+                    title_sql(params.len())
+                }
+                CatalogSearchField::Subject => {
+                    params.push(like);
+                    subject_sql(params.len())
+                }
+                CatalogSearchField::Author => {
+                    params.push(like);
+                    author_sql(params.len())
+                }
+                CatalogSearchField::Publisher => {
+                    params.push(like);
+                    let idx = params.len();
+                    format!(
+                        "EXISTS (SELECT 1 FROM editions e WHERE e.id = b.edition_id \
+                         AND unaccent(lower(e.publisher_name)) LIKE unaccent(lower(${idx})))"
+                    )
+                }
+                CatalogSearchField::Isbn => {
+                    params.push(identifier);
+                    isbn_sql(params.len())
+                }
+                CatalogSearchField::Issn => {
+                    params.push(identifier);
+                    issn_sql(params.len())
+                }
+                CatalogSearchField::Any => {
+                    params.push(like);
+                    let like_idx = params.len();
+                    params.push(identifier);
+                    let id_idx = params.len();
+                    format!(
+                        "({} OR {} OR {} OR {})",
+                        title_sql(like_idx),
+                        subject_sql(like_idx),
+                        author_sql(like_idx),
+                        isbn_sql(id_idx)
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// Bind value of a [`biblio_search_where`] placeholder
+#[derive(Debug)]
+enum Param {
+    Text(String),
+    TextArray(Vec<String>),
+    I64(i64),
+}
+
+/// WHERE clause (over `biblios b`) and its parameters for every non-pagination [`BiblioQuery`]
+/// field, shared by the search page and its facet counts.
+fn biblio_search_where(query: &BiblioQuery) -> (String, Vec<Param>) {
+    let mut where_parts: Vec<String> = Vec::new();
+    let mut params: Vec<Param> = Vec::new();
+
+    if query.archive.unwrap_or(false) {
+        where_parts.push("b.archived_at IS NOT NULL".to_string());
+    } else {
+        where_parts.push("b.archived_at IS NULL".to_string());
+    }
+
+    if !query.include_without_active_items.unwrap_or(false) {
+        where_parts.push(
+            "EXISTS (SELECT 1 FROM items i WHERE i.biblio_id = b.id AND i.archived_at IS NULL)"
+                .to_string(),
+        );
+    }
+
+    if query.opac {
+        where_parts.push("NOT biblio_opac_hidden(b.id)".to_string());
+    }
+
+    if let Some(ref mt) = query.media_type {
+        params.push(Param::Text(mt.clone()));
+        where_parts.push(format!("b.media_type = ${}", params.len()));
+    }
+
+    if let Some(ref isbn) = query.isbn {
+        params.push(Param::Text(isbn.to_string()));
+        where_parts.push(format!("b.isbn = ${}", params.len()));
+    }
+
+    // barcode → item lookup
+    if let Some(ref barcode) = query.barcode {
+        params.push(Param::Text(barcode.clone()));
+        where_parts.push(format!(
+            "EXISTS (SELECT 1 FROM items i WHERE i.biblio_id = b.id AND i.barcode = ${})",
+            params.len()
+        ));
+    }
+
+    if let Some(ref number) = query.inventory_number {
+        params.push(Param::Text(number.trim().to_string()));
+        where_parts.push(format!(
+            "EXISTS (SELECT 1 FROM items i WHERE i.biblio_id = b.id AND i.inventory_number = ${})",
+            params.len()
+        ));
+    }
+
+    if let Some(ref at) = query.audience_type {
+        params.push(Param::Text(at.clone()));
+        where_parts.push(format!("b.audience_type = ${}", params.len()));
+    }
+
+    if let Some(ref lang) = query.lang {
+        params.push(Param::Text(lang.clone()));
+        where_parts.push(format!("b.lang = ${}", params.len()));
+    }
+
+    if let Some(ref title) = query.title {
+        params.push(Param::Text(format!("%{}%", like_escape(title))));
+        let idx = params.len();
+        where_parts.push(format!(
+            "b.title_normalized LIKE normalize_search(${idx})"
+        ));
+    }
+
+    if let Some(ref subject) = query.subject {
+        params.push(Param::Text(format!("%{}%", like_escape(subject))));
+        let idx = params.len();
+        where_parts.push(format!(
+            "unaccent(lower(b.subject)) LIKE unaccent(lower(${idx}))"
+        ));
+    }
+
+    if let Some(ref kw) = query.keywords {
+        params.push(Param::Text(format!("%{}%", like_escape(kw))));
+        let idx = params.len();
+        where_parts.push(format!(
+            "EXISTS (SELECT 1 FROM unnest(b.keywords) AS kw \
+             WHERE unaccent(lower(kw)) LIKE unaccent(lower(${idx})))"
+        ));
+    }
+
+    if let Some(ref content) = query.content {
+        params.push(Param::Text(format!("%{}%", like_escape(content))));
+        let idx = params.len();
+        where_parts.push(format!(
+            "(unaccent(lower(b.table_of_contents)) LIKE unaccent(lower(${idx})) \
+             OR unaccent(lower(b.abstract)) LIKE unaccent(lower(${idx})))"
+        ));
+    }
+
+    // Author name and role apply to the same contributor ("Miyazaki as director")
+    let author_roles = query.author_role_filter();
+    if query.author.is_some() || !author_roles.is_empty() {
+        let mut conds: Vec<String> = Vec::new();
+        if let Some(ref author) = query.author {
+            params.push(Param::Text(format!("%{}%", like_escape(author))));
+            let idx = params.len();
+            conds.push(format!(
+                "(a.lastname_normalized LIKE normalize_search(${idx}) \
+                 OR a.firstname_normalized LIKE normalize_search(${idx}))"
+            ));
+        }
+        if !author_roles.is_empty() {
+            params.push(Param::TextArray(author_roles));
+            conds.push(format!(
+                "ba.function IN (SELECT resolve_relator(r) FROM unnest(${}::TEXT[]) r)",
+                params.len()
+            ));
+        }
+        where_parts.push(format!(
+            "EXISTS (\
+                SELECT 1 FROM biblio_authors ba \
+                JOIN authors a ON a.id = ba.author_id \
+                WHERE ba.biblio_id = b.id \
+                AND {}\
+            )",
+            conds.join(" AND ")
+        ));
+    }
+
+    if let Some(ref editor) = query.editor {
+        params.push(Param::Text(format!("%{}%", like_escape(editor))));
+        let idx = params.len();
+        where_parts.push(format!(
+            "EXISTS (\
+                SELECT 1 FROM editions e \
+                WHERE e.id = b.edition_id \
+                AND unaccent(lower(e.publisher_name)) LIKE unaccent(lower(${idx}))\
+            )"
+        ));
+    }
+
+    if query.serie.is_some() || query.serie_id.is_some() {
+        let mut conds: Vec<String> = Vec::new();
+        if let Some(ref serie) = query.serie {
+            params.push(Param::Text(format!("%{}%", like_escape(serie))));
+            let idx = params.len();
+            conds.push(format!("unaccent(lower(s.name)) LIKE unaccent(lower(${idx}))"));
+        }
+        if let Some(serie_id) = query.serie_id {
+            params.push(Param::I64(serie_id));
+            let idx = params.len();
+            conds.push(format!("s.id = ${idx}"));
+        }
+        where_parts.push(format!(
+            "EXISTS (\
+                SELECT 1 FROM biblio_series bsx \
+                JOIN series s ON s.id = bsx.series_id \
+                WHERE bsx.biblio_id = b.id \
+                AND ({})\
+            )",
+            conds.join(" OR ")
+        ));
+    }
+
+    if query.collection.is_some() || query.collection_id.is_some() {
+        let mut conds: Vec<String> = Vec::new();
+        if let Some(ref collection) = query.collection {
+            params.push(Param::Text(format!("%{}%", like_escape(collection))));
+            let idx = params.len();
+            conds.push(format!("unaccent(lower(c.name)) LIKE unaccent(lower(${idx}))"));
+        }
+        if let Some(collection_id) = query.collection_id {
+            params.push(Param::I64(collection_id));
+            let idx = params.len();
+            conds.push(format!("c.id = ${idx}"));
+        }
+        where_parts.push(format!(
+            "EXISTS (\
+                SELECT 1 FROM biblio_collections bcx \
+                JOIN collections c ON c.id = bcx.collection_id \
+                WHERE bcx.biblio_id = b.id \
+                AND ({})\
+            )",
+            conds.join(" OR ")
+        ));
+    }
+
+    if let Some(ref fs) = query.freesearch {
+        let fs = fs.trim();
+        if !fs.is_empty() {
+            params.push(Param::Text(format!("%{}%", like_escape(fs))));
+            let idx = params.len();
+            where_parts.push(format!(
+                "(b.title_normalized LIKE normalize_search(${idx}) \
+                 OR unaccent(lower(b.subject)) LIKE unaccent(lower(${idx})) \
+                 OR unaccent(lower(b.notes)) LIKE unaccent(lower(${idx})))"
+            ));
+        }
+    }
+
+    let accessibility = query.accessibility_filter();
+    if !accessibility.is_empty() {
+        params.push(Param::TextArray(accessibility));
+        where_parts.push(format!("b.accessibility @> ${}", params.len()));
+    }
+
+    let reading_levels = query.reading_level_filter();
+    if !reading_levels.is_empty() {
+        params.push(Param::TextArray(reading_levels));
+        where_parts.push(format!("b.reading_level = ANY(${})", params.len()));
+    }
+
+    let genres = query.genre_filter();
+    if !genres.is_empty() {
+        params.push(Param::TextArray(genres));
+        where_parts.push(format!(
+            "EXISTS (\
+                SELECT 1 FROM biblio_genres bgx \
+                JOIN genres g ON g.id = bgx.genre_id \
+                WHERE bgx.biblio_id = b.id AND g.code = ANY(${})\
+            )",
+            params.len()
+        ));
+    }
+
+    if let Some(subject_id) = query.subject_id {
+        params.push(Param::I64(subject_id));
+        where_parts.push(format!(
+            "EXISTS (\
+                WITH RECURSIVE tree AS (\
+                    SELECT ${}::BIGINT AS id \
+                    UNION \
+                    SELECT sub.id FROM subjects sub JOIN tree t ON sub.broader_id = t.id\
+                ) \
+                SELECT 1 FROM biblio_subjects bsj \
+                WHERE bsj.biblio_id = b.id AND bsj.subject_id IN (SELECT id FROM tree)\
+            )",
+            params.len()
+        ));
+    }
+
+    if let Some(location_id) = query.location_id {
+        params.push(Param::I64(location_id));
+        where_parts.push(format!(
+            "EXISTS (\
+                SELECT 1 FROM items itl \
+                JOIN shelving_location_paths lpx ON lpx.id = itl.location_id \
+                WHERE itl.biblio_id = b.id AND itl.archived_at IS NULL AND ${}::BIGINT = ANY(lpx.ancestor_ids)\
+            )",
+            params.len()
+        ));
+    }
+
+    let where_sql = if where_parts.is_empty() {
+        "1=1".to_string()
+    } else {
+        where_parts.join(" AND ")
+    };
+    (where_sql, params)
+}
+
+fn search_args(params: &[Param]) -> sqlx::postgres::PgArguments {
+    use sqlx::Arguments;
+    let mut pg_args = sqlx::postgres::PgArguments::default();
+    for p in params {
+        match p {
+            Param::Text(s) => pg_args.add(s.clone()),
+            Param::TextArray(v) => pg_args.add(v.clone()),
+            Param::I64(v) => pg_args.add(*v),
+        }
+    }
+    pg_args
+}
+
 /// Escape a string for use as a LIKE pattern (ESCAPE '\').
-fn like_escape(s: &str) -> String {
+pub(super) fn like_escape(s: &str) -> String {
     s.replace('\\', "\\\\")
         .replace('%', "\\%")
         .replace('_', "\\_")
@@ -312,9 +799,9 @@ impl Repository {
         let query = r#"
             SELECT id, media_type, isbn,
                    publication_date, lang, lang_orig, title,
-                   subject, audience_type, page_extent, format,
+                   subject, audience_type, reading_level, page_extent, format,
                    table_of_contents, accompanying_material,
-                   abstract as abstract_, notes, keywords,
+                   abstract as abstract_, notes, keywords, accessibility,
                    edition_id,
                    is_valid,
                    created_at, updated_at, archived_at
@@ -354,7 +841,7 @@ impl Repository {
     async fn get_biblio_authors(&self, biblio_id: i64) -> AppResult<Vec<Author>> {
         let rows = sqlx::query(
             r#"
-            SELECT a.id, a.lastname, a.firstname, a.bio, a.notes, ba.function
+            SELECT a.id, a.lastname, a.firstname, a.display_name, a.bio, a.notes, ba.function
             FROM biblio_authors ba
             JOIN authors a ON a.id = ba.author_id
             WHERE ba.biblio_id = $1
@@ -372,6 +859,7 @@ impl Repository {
                 key: None,
                 lastname: r.get("lastname"),
                 firstname: r.get("firstname"),
+                display_name: r.get("display_name"),
                 bio: r.get::<Option<String>, _>("bio"),
                 notes: r.get::<Option<String>, _>("notes"),
                 function: r.get::<Option<Function>, _>("function"),
@@ -579,6 +1067,7 @@ impl Repository {
                            'id', a.id::text,
                            'lastname', a.lastname,
                            'firstname', a.firstname,
+                           'displayName', a.display_name,
                            'bio', a.bio,
                            'notes', a.notes,
                            'function', ba.function
@@ -618,182 +1107,7 @@ impl Repository {
         let per_page = query.per_page.unwrap_or(20).clamp(1, 200);
         let offset = (page - 1) * per_page;
 
-        #[derive(Debug)]
-        enum Param {
-            Text(String),
-            I16(i16),
-            I64(i64),
-        }
-
-        let mut where_parts: Vec<String> = Vec::new();
-        let mut params: Vec<Param> = Vec::new();
-
-        if query.archive.unwrap_or(false) {
-            where_parts.push("b.archived_at IS NOT NULL".to_string());
-        } else {
-            where_parts.push("b.archived_at IS NULL".to_string());
-        }
-
-        if !query.include_without_active_items.unwrap_or(false) {
-            where_parts.push(
-                "EXISTS (SELECT 1 FROM items i WHERE i.biblio_id = b.id AND i.archived_at IS NULL)"
-                    .to_string(),
-            );
-        }
-
-        if let Some(ref mt) = query.media_type {
-            params.push(Param::Text(mt.clone()));
-            where_parts.push(format!("b.media_type = ${}", params.len()));
-        }
-
-        if let Some(ref isbn) = query.isbn {
-            params.push(Param::Text(isbn.to_string()));
-            where_parts.push(format!("b.isbn = ${}", params.len()));
-        }
-
-        // barcode → item lookup
-        if let Some(ref barcode) = query.barcode {
-            params.push(Param::Text(barcode.clone()));
-            where_parts.push(format!(
-                "EXISTS (SELECT 1 FROM items i WHERE i.biblio_id = b.id AND i.barcode = ${})",
-                params.len()
-            ));
-        }
-
-        if let Some(ref at) = query.audience_type {
-            params.push(Param::Text(at.clone()));
-            where_parts.push(format!("b.audience_type = ${}", params.len()));
-        }
-
-        if let Some(ref lang) = query.lang {
-            params.push(Param::Text(lang.clone()));
-            where_parts.push(format!("b.lang = ${}", params.len()));
-        }
-
-        if let Some(ref title) = query.title {
-            params.push(Param::Text(format!("%{}%", like_escape(title))));
-            let idx = params.len();
-            where_parts.push(format!(
-                "unaccent(lower(b.title)) LIKE unaccent(lower(${idx}))"
-            ));
-        }
-
-        if let Some(ref subject) = query.subject {
-            params.push(Param::Text(format!("%{}%", like_escape(subject))));
-            let idx = params.len();
-            where_parts.push(format!(
-                "unaccent(lower(b.subject)) LIKE unaccent(lower(${idx}))"
-            ));
-        }
-
-        if let Some(ref kw) = query.keywords {
-            params.push(Param::Text(format!("%{}%", like_escape(kw))));
-            let idx = params.len();
-            where_parts.push(format!(
-                "EXISTS (SELECT 1 FROM unnest(b.keywords) AS kw \
-                 WHERE unaccent(lower(kw)) LIKE unaccent(lower(${idx})))"
-            ));
-        }
-
-        if let Some(ref content) = query.content {
-            params.push(Param::Text(format!("%{}%", like_escape(content))));
-            let idx = params.len();
-            where_parts.push(format!(
-                "(unaccent(lower(b.table_of_contents)) LIKE unaccent(lower(${idx})) \
-                 OR unaccent(lower(b.abstract)) LIKE unaccent(lower(${idx})))"
-            ));
-        }
-
-        if let Some(ref author) = query.author {
-            params.push(Param::Text(format!("%{}%", like_escape(author))));
-            let idx = params.len();
-            where_parts.push(format!(
-                "EXISTS (\
-                    SELECT 1 FROM biblio_authors ba \
-                    JOIN authors a ON a.id = ba.author_id \
-                    WHERE ba.biblio_id = b.id \
-                    AND (unaccent(lower(a.lastname)) LIKE unaccent(lower(${idx})) \
-                         OR unaccent(lower(a.firstname)) LIKE unaccent(lower(${idx})))\
-                )"
-            ));
-        }
-
-        if let Some(ref editor) = query.editor {
-            params.push(Param::Text(format!("%{}%", like_escape(editor))));
-            let idx = params.len();
-            where_parts.push(format!(
-                "EXISTS (\
-                    SELECT 1 FROM editions e \
-                    WHERE e.id = b.edition_id \
-                    AND unaccent(lower(e.publisher_name)) LIKE unaccent(lower(${idx}))\
-                )"
-            ));
-        }
-
-        if query.serie.is_some() || query.serie_id.is_some() {
-            let mut conds: Vec<String> = Vec::new();
-            if let Some(ref serie) = query.serie {
-                params.push(Param::Text(format!("%{}%", like_escape(serie))));
-                let idx = params.len();
-                conds.push(format!("unaccent(lower(s.name)) LIKE unaccent(lower(${idx}))"));
-            }
-            if let Some(serie_id) = query.serie_id {
-                params.push(Param::I64(serie_id));
-                let idx = params.len();
-                conds.push(format!("s.id = ${idx}"));
-            }
-            where_parts.push(format!(
-                "EXISTS (\
-                    SELECT 1 FROM biblio_series bsx \
-                    JOIN series s ON s.id = bsx.series_id \
-                    WHERE bsx.biblio_id = b.id \
-                    AND ({})\
-                )",
-                conds.join(" OR ")
-            ));
-        }
-
-        if query.collection.is_some() || query.collection_id.is_some() {
-            let mut conds: Vec<String> = Vec::new();
-            if let Some(ref collection) = query.collection {
-                params.push(Param::Text(format!("%{}%", like_escape(collection))));
-                let idx = params.len();
-                conds.push(format!("unaccent(lower(c.name)) LIKE unaccent(lower(${idx}))"));
-            }
-            if let Some(collection_id) = query.collection_id {
-                params.push(Param::I64(collection_id));
-                let idx = params.len();
-                conds.push(format!("c.id = ${idx}"));
-            }
-            where_parts.push(format!(
-                "EXISTS (\
-                    SELECT 1 FROM biblio_collections bcx \
-                    JOIN collections c ON c.id = bcx.collection_id \
-                    WHERE bcx.biblio_id = b.id \
-                    AND ({})\
-                )",
-                conds.join(" OR ")
-            ));
-        }
-
-        if let Some(ref fs) = query.freesearch {
-            let fs = fs.trim();
-            if !fs.is_empty() {
-                params.push(Param::Text(format!("%{}%", like_escape(fs))));
-                let idx = params.len();
-                where_parts.push(format!(
-                    "(unaccent(lower(b.title)) LIKE unaccent(lower(${idx})) \
-                     OR unaccent(lower(b.subject)) LIKE unaccent(lower(${idx})) \
-                     OR unaccent(lower(b.notes)) LIKE unaccent(lower(${idx})))"
-                ));
-            }
-        }
-
-        let where_sql = if where_parts.is_empty() {
-            "1=1".to_string()
-        } else {
-            where_parts.join(" AND ")
-        };
+        let (where_sql, params) = biblio_search_where(query);
 
         let order_sql = "b.title ASC NULLS LAST".to_string();
 
@@ -807,6 +1121,7 @@ impl Repository {
                            'id', a.id::text,
                            'lastname', a.lastname,
                            'firstname', a.firstname,
+                           'displayName', a.display_name,
                            'bio', a.bio,
                            'notes', a.notes,
                            'function', ba.function
@@ -828,15 +1143,7 @@ impl Repository {
             offset = offset,
         );
 
-        use sqlx::Arguments;
-        let mut pg_args = sqlx::postgres::PgArguments::default();
-        for p in &params {
-            match p {
-                Param::Text(s) => pg_args.add(s.clone()),
-                Param::I16(v) => pg_args.add(*v),
-                Param::I64(v) => pg_args.add(*v),
-            }
-        }
+        let pg_args = search_args(&params);
 
         #[derive(FromRow)]
         struct BiblioShortWithCount {
@@ -885,6 +1192,165 @@ impl Repository {
         Ok((biblios, total))
     }
 
+    /// Facet counts for [`Self::biblios_search`]: same filters, over all matching biblios.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn biblios_search_facets(&self, query: &BiblioQuery) -> AppResult<BiblioFacets> {
+        let (where_sql, params) = biblio_search_where(query);
+        let sql = format!(
+            r#"
+            SELECT feature, COUNT(*) AS n
+            FROM biblios b
+            CROSS JOIN LATERAL unnest(b.accessibility) AS feature
+            WHERE {where_sql}
+            GROUP BY feature
+            "#
+        );
+        let rows: Vec<(String, i64)> = sqlx::query_as_with(&sql, search_args(&params))
+            .fetch_all(&self.pool)
+            .await?;
+        let mut facets = BiblioFacets::from_accessibility_counts(
+            rows.iter().map(|(feature, n)| (feature.as_str(), *n)),
+        );
+
+        let sql = format!(
+            r#"
+            SELECT b.media_type, mt.label, COUNT(*) AS n
+            FROM biblios b
+            LEFT JOIN media_types mt ON mt.code = b.media_type
+            WHERE {where_sql}
+            GROUP BY b.media_type, mt.label
+            ORDER BY n DESC, b.media_type
+            LIMIT {HEADING_FACET_LIMIT}
+            "#
+        );
+        let rows: Vec<(String, Option<String>, i64)> = sqlx::query_as_with(&sql, search_args(&params))
+            .fetch_all(&self.pool)
+            .await?;
+        facets.media_types = rows
+            .into_iter()
+            .map(|(value, label, count)| FacetCount { value, label, count })
+            .collect();
+
+        let sql = format!(
+            r#"
+            SELECT g.code, g.label, COUNT(*) AS n
+            FROM biblios b
+            JOIN biblio_genres bgf ON bgf.biblio_id = b.id
+            JOIN genres g ON g.id = bgf.genre_id
+            WHERE {where_sql}
+            GROUP BY g.id
+            ORDER BY n DESC, g.sort_order, g.label
+            LIMIT {HEADING_FACET_LIMIT}
+            "#
+        );
+        let rows: Vec<(String, String, i64)> = sqlx::query_as_with(&sql, search_args(&params))
+            .fetch_all(&self.pool)
+            .await?;
+        facets.genres = rows
+            .into_iter()
+            .map(|(value, label, count)| FacetCount { value, label: Some(label), count })
+            .collect();
+
+        let sql = format!(
+            r#"
+            SELECT sf.id::text, sf.heading, COUNT(*) AS n
+            FROM biblios b
+            JOIN biblio_subjects bsf ON bsf.biblio_id = b.id
+            JOIN subjects sf ON sf.id = bsf.subject_id
+            WHERE {where_sql}
+            GROUP BY sf.id
+            ORDER BY n DESC, lower(sf.heading)
+            LIMIT {HEADING_FACET_LIMIT}
+            "#
+        );
+        let rows: Vec<(String, String, i64)> = sqlx::query_as_with(&sql, search_args(&params))
+            .fetch_all(&self.pool)
+            .await?;
+        facets.subjects = rows
+            .into_iter()
+            .map(|(value, label, count)| FacetCount { value, label: Some(label), count })
+            .collect();
+
+        let sql = format!(
+            r#"
+            SELECT lf.id::text, lf.path, COUNT(DISTINCT b.id) AS n
+            FROM biblios b
+            JOIN items itf ON itf.biblio_id = b.id AND itf.archived_at IS NULL
+            JOIN shelving_location_paths lf ON lf.id = itf.location_id
+            WHERE {where_sql}
+            GROUP BY lf.id, lf.path, lf.sort_key
+            ORDER BY n DESC, lf.sort_key
+            LIMIT {HEADING_FACET_LIMIT}
+            "#
+        );
+        let rows: Vec<(String, String, i64)> = sqlx::query_as_with(&sql, search_args(&params))
+            .fetch_all(&self.pool)
+            .await?;
+        facets.locations = rows
+            .into_iter()
+            .map(|(value, label, count)| FacetCount { value, label: Some(label), count })
+            .collect();
+
+        Ok(facets)
+    }
+
+    /// Title completions: one per distinct (normalized) title, titles starting with `q` first,
+    /// then by word similarity. Served by the partial trigram index on active titles; records
+    /// hidden from the OPAC are left out.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn biblios_suggest_titles(&self, q: &str, limit: i64) -> AppResult<Vec<Suggestion>> {
+        let rows = sqlx::query_as::<_, Suggestion>(
+            r#"
+            SELECT MIN(b.id) AS id, MIN(b.title) AS label, COUNT(*) AS count
+            FROM biblios b
+            WHERE b.archived_at IS NULL
+              AND (b.title_normalized LIKE normalize_search($2) OR normalize_search($1) <% b.title_normalized)
+              AND NOT biblio_opac_hidden(b.id)
+            GROUP BY b.title_normalized
+            ORDER BY (b.title_normalized LIKE normalize_search($2)) DESC,
+                     word_similarity(normalize_search($1), b.title_normalized) DESC,
+                     COUNT(*) DESC, length(b.title_normalized), b.title_normalized
+            LIMIT $3
+            "#,
+        )
+        .bind(q)
+        .bind(format!("{}%", like_escape(q)))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Author completions: last names starting with `q` first, then full names ("Victor Hu"),
+    /// then by word similarity; authors without active biblios shown in the OPAC are left out.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn biblios_suggest_authors(&self, q: &str, limit: i64) -> AppResult<Vec<Suggestion>> {
+        let rows = sqlx::query_as::<_, Suggestion>(
+            r#"
+            SELECT a.id, COALESCE(a.display_name, btrim(concat_ws(' ', a.firstname, a.lastname))) AS label,
+                   COUNT(DISTINCT ba.biblio_id) AS count
+            FROM authors a
+            JOIN biblio_authors ba ON ba.author_id = a.id
+            JOIN biblios b ON b.id = ba.biblio_id AND b.archived_at IS NULL AND NOT biblio_opac_hidden(b.id)
+            WHERE a.lastname_normalized LIKE normalize_search($2)
+               OR a.name_normalized LIKE normalize_search($2)
+               OR normalize_search($1) <% a.name_normalized
+            GROUP BY a.id
+            ORDER BY (a.lastname_normalized LIKE normalize_search($2)) DESC,
+                     (a.name_normalized LIKE normalize_search($2)) DESC,
+                     word_similarity(normalize_search($1), a.name_normalized) DESC,
+                     count DESC, label
+            LIMIT $3
+            "#,
+        )
+        .bind(q)
+        .bind(format!("{}%", like_escape(q)))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
     /// List all biblios belonging to a series
     #[tracing::instrument(skip(self), err)]
     pub async fn biblios_get_by_series(&self, series_id: i64) -> AppResult<Vec<BiblioShort>> {
@@ -898,6 +1364,7 @@ impl Repository {
                            'id', a.id::text,
                            'lastname', a.lastname,
                            'firstname', a.firstname,
+                           'displayName', a.display_name,
                            'bio', a.bio,
                            'notes', a.notes,
                            'function', ba.function
@@ -944,6 +1411,7 @@ impl Repository {
                            'id', a.id::text,
                            'lastname', a.lastname,
                            'firstname', a.firstname,
+                           'displayName', a.display_name,
                            'bio', a.bio,
                            'notes', a.notes,
                            'function', ba.function
@@ -1020,11 +1488,46 @@ impl Repository {
                 b.table_of_contents,
                 b.lang,
                 b.audience_type,
+                b.reading_level,
+                b.accessibility,
+                ARRAY(
+                    SELECT g.code FROM biblio_genres bg JOIN genres g ON g.id = bg.genre_id
+                    WHERE bg.biblio_id = b.id ORDER BY g.code
+                ) AS genres,
+                ARRAY(
+                    SELECT DISTINCT ba_r.function FROM biblio_authors ba_r
+                    WHERE ba_r.biblio_id = b.id AND ba_r.function IS NOT NULL ORDER BY 1
+                ) AS author_roles,
+                ARRAY(
+                    SELECT bsj.subject_id FROM biblio_subjects bsj
+                    WHERE bsj.biblio_id = b.id ORDER BY bsj.subject_id
+                ) AS subject_ids,
+                ARRAY(
+                    WITH RECURSIVE path AS (
+                        SELECT bsj.subject_id AS id FROM biblio_subjects bsj WHERE bsj.biblio_id = b.id
+                        UNION
+                        SELECT sub.broader_id FROM subjects sub JOIN path p ON sub.id = p.id
+                        WHERE sub.broader_id IS NOT NULL
+                    )
+                    SELECT id FROM path ORDER BY id
+                ) AS subject_path_ids,
+                ARRAY(
+                    SELECT DISTINCT it_loc.location_id FROM items it_loc
+                    WHERE it_loc.biblio_id = b.id AND it_loc.archived_at IS NULL AND it_loc.location_id IS NOT NULL
+                    ORDER BY 1
+                ) AS location_ids,
+                ARRAY(
+                    SELECT DISTINCT unnest(lp.ancestor_ids) FROM items it_loc
+                    JOIN shelving_location_paths lp ON lp.id = it_loc.location_id
+                    WHERE it_loc.biblio_id = b.id AND it_loc.archived_at IS NULL
+                    ORDER BY 1
+                ) AS location_path_ids,
                 (b.archived_at IS NOT NULL) AS is_archived,
                 EXISTS (
                     SELECT 1 FROM items it_act
                     WHERE it_act.biblio_id = b.id AND it_act.archived_at IS NULL
-                ) AS has_active_items
+                ) AS has_active_items,
+                biblio_opac_hidden(b.id) AS opac_hidden
             FROM biblios b
             LEFT JOIN biblio_authors ba ON ba.biblio_id = b.id
             LEFT JOIN authors a ON a.id = ba.author_id
@@ -1088,11 +1591,46 @@ impl Repository {
                 b.table_of_contents,
                 b.lang,
                 b.audience_type,
+                b.reading_level,
+                b.accessibility,
+                ARRAY(
+                    SELECT g.code FROM biblio_genres bg JOIN genres g ON g.id = bg.genre_id
+                    WHERE bg.biblio_id = b.id ORDER BY g.code
+                ) AS genres,
+                ARRAY(
+                    SELECT DISTINCT ba_r.function FROM biblio_authors ba_r
+                    WHERE ba_r.biblio_id = b.id AND ba_r.function IS NOT NULL ORDER BY 1
+                ) AS author_roles,
+                ARRAY(
+                    SELECT bsj.subject_id FROM biblio_subjects bsj
+                    WHERE bsj.biblio_id = b.id ORDER BY bsj.subject_id
+                ) AS subject_ids,
+                ARRAY(
+                    WITH RECURSIVE path AS (
+                        SELECT bsj.subject_id AS id FROM biblio_subjects bsj WHERE bsj.biblio_id = b.id
+                        UNION
+                        SELECT sub.broader_id FROM subjects sub JOIN path p ON sub.id = p.id
+                        WHERE sub.broader_id IS NOT NULL
+                    )
+                    SELECT id FROM path ORDER BY id
+                ) AS subject_path_ids,
+                ARRAY(
+                    SELECT DISTINCT it_loc.location_id FROM items it_loc
+                    WHERE it_loc.biblio_id = b.id AND it_loc.archived_at IS NULL AND it_loc.location_id IS NOT NULL
+                    ORDER BY 1
+                ) AS location_ids,
+                ARRAY(
+                    SELECT DISTINCT unnest(lp.ancestor_ids) FROM items it_loc
+                    JOIN shelving_location_paths lp ON lp.id = it_loc.location_id
+                    WHERE it_loc.biblio_id = b.id AND it_loc.archived_at IS NULL
+                    ORDER BY 1
+                ) AS location_path_ids,
                 (b.archived_at IS NOT NULL) AS is_archived,
                 EXISTS (
                     SELECT 1 FROM items it_act
                     WHERE it_act.biblio_id = b.id AND it_act.archived_at IS NULL
-                ) AS has_active_items
+                ) AS has_active_items,
+                biblio_opac_hidden(b.id) AS opac_hidden
             FROM biblios b
             LEFT JOIN biblio_authors ba ON ba.biblio_id = b.id
             LEFT JOIN authors a ON a.id = ba.author_id
@@ -1131,6 +1669,7 @@ impl Repository {
                            'id', a.id::text,
                            'lastname', a.lastname,
                            'firstname', a.firstname,
+                           'displayName', a.display_name,
                            'bio', a.bio,
                            'notes', a.notes,
                            'function', ba.function
@@ -1188,6 +1727,7 @@ impl Repository {
                            'id', a.id::text,
                            'lastname', a.lastname,
                            'firstname', a.firstname,
+                           'displayName', a.display_name,
                            'bio', a.bio,
                            'notes', a.notes,
                            'function', ba.function
@@ -1219,6 +1759,39 @@ impl Repository {
     // CREATE
     // =========================================================================
 
+    /// MARC record of a biblio: the stored one updated with its fields under the preservation
+    /// policy, else one built from its fields with the leader codes of its media type.
+    async fn biblio_marc_record(&self, biblio: &Biblio) -> AppResult<MarcRecord> {
+        if let Some(stored) = &biblio.marc_record {
+            return crate::marc::translator::regenerate_marc_record(
+                biblio,
+                stored,
+                &self.marc_preservation_policy(),
+            );
+        }
+        let mut record = MarcRecord::from(biblio);
+        if let Some((record_type, level)) = self.media_types_marc_codes(&biblio.media_type).await? {
+            crate::marc::set_marc_leader_codes(&mut record, record_type, level);
+        }
+        Ok(record)
+    }
+
+    /// Media type of an imported record from the `media_types` entries claiming its leader codes,
+    /// unless it was changed from the one derived by the translator.
+    async fn classify_imported_media_type(&self, biblio: &mut Biblio) -> AppResult<()> {
+        let Some(record) = &biblio.marc_record else {
+            return Ok(());
+        };
+        if biblio.media_type != crate::marc::media_type_from_leader(record) {
+            return Ok(());
+        }
+        let (record_type, level) = crate::marc::marc_leader_codes(record);
+        if let Some(media_type) = self.media_types_for_marc(record_type, level, &biblio.media_type).await? {
+            biblio.media_type = media_type;
+        }
+        Ok(())
+    }
+
     /// Create a new biblio.
     #[tracing::instrument(skip(self), err)]
     pub async fn biblios_create<'a>(&self, biblio: &'a mut Biblio) -> AppResult<&'a mut Biblio> {
@@ -1230,6 +1803,8 @@ impl Repository {
         self.resolve_series_ids_from_biblio(biblio).await?;
         self.resolve_collection_ids_from_biblio(biblio).await?;
         biblio.edition_id = self.process_edition(&biblio.edition).await?;
+        self.classify_imported_media_type(biblio).await?;
+        self.media_types_ensure_known(&biblio.media_type).await?;
 
         let mut tx = self.pool.begin().await?;
 
@@ -1240,13 +1815,13 @@ impl Repository {
                 lang, lang_orig, title, subject,
                 audience_type, page_extent, format, table_of_contents, accompanying_material,
                 abstract, notes, keywords, is_valid,
-                edition_id, created_at, updated_at
+                edition_id, created_at, updated_at, accessibility, reading_level
             ) VALUES (
                 $1, $2, $3,
                 $4, $5, $6, $7,
                 $8, $9, $10, $11, $12,
                 $13, $14, $15, $16,
-                $17, $18, $19
+                $17, $18, $19, $20, $21
             ) RETURNING id
             "#,
         )
@@ -1269,6 +1844,8 @@ impl Repository {
         .bind(&biblio.edition_id)
         .bind(&biblio.created_at)
         .bind(&biblio.updated_at)
+        .bind(&biblio.accessibility)
+        .bind(biblio.reading_level)
         .fetch_one(&mut *tx)
         .await?;
 
@@ -1280,7 +1857,7 @@ impl Repository {
             .await?;
         self.sync_biblio_authors_tx(&mut tx, id, &biblio.authors).await?;
 
-        biblio.marc_record = Some(crate::marc::MarcRecord::from(&*biblio));
+        biblio.marc_record = Some(self.biblio_marc_record(biblio).await?);
         sqlx::query("UPDATE biblios SET marc_record = $1 WHERE id = $2")
             .bind(serde_json::to_value(&biblio.marc_record).unwrap_or_default())
             .bind(id)
@@ -1337,6 +1914,10 @@ impl Repository {
         self.resolve_series_ids_from_biblio(biblio).await?;
         self.resolve_collection_ids_from_biblio(biblio).await?;
         biblio.edition_id = self.process_edition(&biblio.edition).await?;
+        self.media_types_ensure_known(&biblio.media_type).await?;
+        if biblio.marc_record.is_none() {
+            biblio.marc_record = self.biblios_get_marc_record_optional(id).await?;
+        }
 
         let mut tx = self.pool.begin().await?;
 
@@ -1369,7 +1950,7 @@ impl Repository {
             self.sync_biblio_authors_tx(&mut tx, id, &biblio.authors).await?;
         }
 
-        biblio.marc_record = Some(crate::marc::MarcRecord::from(&*biblio));
+        biblio.marc_record = Some(self.biblio_marc_record(biblio).await?);
         sqlx::query("UPDATE biblios SET marc_record = $1 WHERE id = $2")
             .bind(serde_json::to_value(&biblio.marc_record).unwrap_or_default())
             .bind(id)
@@ -1425,7 +2006,9 @@ impl Repository {
                 is_valid = $16,
                 edition_id = $17,
                 updated_at = $18,
-                marc_record = $19
+                marc_record = $19,
+                accessibility = $21,
+                reading_level = $22
             WHERE id = $20 AND archived_at IS NULL
             "#,
         )
@@ -1449,6 +2032,8 @@ impl Repository {
         .bind(&biblio.updated_at)
         .bind(&marc_json)
         .bind(id)
+        .bind(&biblio.accessibility)
+        .bind(biblio.reading_level)
         .execute(&mut *tx)
         .await?
         .rows_affected();
@@ -1476,17 +2061,10 @@ impl Repository {
     #[tracing::instrument(skip(self), err)]
     pub async fn biblios_update_marc_record(&self, biblio: &mut Biblio) -> AppResult<()> {
         if biblio.marc_record.is_none() {
-            biblio.marc_record = sqlx::query_scalar::<_, Option<serde_json::Value>>(
-                "SELECT marc_record FROM biblios WHERE id = $1",
-            )
-            .bind(biblio.id.unwrap_or(0))
-            .fetch_optional(&self.pool)
-            .await?
-            .flatten()
-            .and_then(|v| serde_json::from_value::<MarcRecord>(v).ok());
+            biblio.marc_record = self.biblios_get_marc_record_optional(biblio.id.unwrap_or(0)).await?;
         }
 
-        biblio.marc_record = Some(MarcRecord::from(&*biblio));
+        biblio.marc_record = Some(self.biblio_marc_record(biblio).await?);
 
         sqlx::query(
             "UPDATE biblios SET marc_record = $1 WHERE id = $2",
@@ -1508,6 +2086,23 @@ impl Repository {
     pub async fn biblios_delete(&self, id: i64, force: bool) -> AppResult<()> {
         let now = Utc::now();
 
+        let deposit_copies: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM items i
+            JOIN deposits d ON d.id = i.deposit_id
+            WHERE i.biblio_id = $1 AND i.archived_at IS NULL AND d.status = 'active'
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+        if deposit_copies > 0 {
+            return Err(AppError::BusinessRule(format!(
+                "Biblio has {} copies from an active deposit and cannot be weeded",
+                deposit_copies
+            )));
+        }
+
         let loans = self.loans_get_active_ids_for_biblio(id).await?;
 
         if loans.len() > 0 {
@@ -1564,13 +2159,15 @@ impl Repository {
             .execute(&mut **tx)
             .await?;
 
+        // Functions are stored as codes of the `relators` vocabulary; unknown terms fall back to
+        // `author` as they always did.
         for (idx, (author, author_id)) in authors.iter().zip(author_ids.iter()).enumerate() {
             let Some(author_id) = author_id else { continue };
 
             sqlx::query(
                 r#"
                 INSERT INTO biblio_authors (biblio_id, author_id, function, author_type, position)
-                VALUES ($1, $2, $3, $4, $5)
+                VALUES ($1, $2, CASE WHEN $3::TEXT IS NULL THEN NULL ELSE COALESCE(resolve_relator($3), 'author') END, $4, $5)
                 ON CONFLICT (biblio_id, author_id, function) DO UPDATE SET position = $5
                 "#,
             )
@@ -1587,20 +2184,24 @@ impl Repository {
     }
 
     /// Insert author if new, or return existing id (uses pool, idempotent).
+    /// The name is normalized first (see [`normalize_author_name`]) so that "TOLKIEN J.R.R."
+    /// and "Tolkien, J. R. R." resolve to the same author.
     async fn ensure_author(&self, author: &Author) -> AppResult<Option<i64>> {
         if author.id != 0 {
             return Ok(Some(author.id));
         }
 
-        let Some(ref lastname) = author.lastname else {
+        let (lastname, firstname) =
+            normalize_author_name(author.lastname.as_deref(), author.firstname.as_deref());
+        let Some(lastname) = lastname else {
             return Ok(None);
         };
 
         let existing: Option<i64> = sqlx::query_scalar(
             "SELECT id FROM authors WHERE lastname = $1 AND firstname IS NOT DISTINCT FROM $2",
         )
-        .bind(lastname)
-        .bind(&author.firstname)
+        .bind(&lastname)
+        .bind(&firstname)
         .fetch_optional(&self.pool)
         .await?;
 
@@ -1610,8 +2211,8 @@ impl Repository {
             let id = sqlx::query_scalar::<_, i64>(
                 "INSERT INTO authors (lastname, firstname) VALUES ($1, $2) RETURNING id",
             )
-            .bind(lastname)
-            .bind(&author.firstname)
+            .bind(&lastname)
+            .bind(&firstname)
             .fetch_one(&self.pool)
             .await?;
             Ok(Some(id))
@@ -1747,11 +2348,16 @@ impl Repository {
             r#"
             SELECT i.id, i.biblio_id, i.source_id, i.barcode, i.call_number, i.volume_designation,
                    i.place, i.borrowable, i.circulation_status, i.notes, i.price,
-                   i.created_at, i.updated_at, i.archived_at,
+                   i.access_url, i.access_type, i.created_at, i.updated_at, i.archived_at, i.deposit_id,
+                   i.transit_place,
+                   i.inventory_number,
+                   i.location_id, lp.path AS location_path,
+                   NOT item_opac_visible(i.circulation_status) AS hidden_from_opac,
                    so.name as source_name,
                    EXISTS(SELECT 1 FROM loans l WHERE l.item_id = i.id AND l.returned_at IS NULL) as borrowed
             FROM items i
             LEFT JOIN sources so ON i.source_id = so.id
+            LEFT JOIN shelving_location_paths lp ON lp.id = i.location_id
             WHERE i.biblio_id = $1 AND i.archived_at IS NULL
             ORDER BY i.barcode
             "#,
@@ -1770,11 +2376,16 @@ impl Repository {
             r#"
             SELECT i.id, i.biblio_id, i.source_id, i.barcode, i.call_number, i.volume_designation,
                    i.place, i.borrowable, i.circulation_status, i.notes, i.price,
-                   i.created_at, i.updated_at, i.archived_at,
+                   i.access_url, i.access_type, i.created_at, i.updated_at, i.archived_at, i.deposit_id,
+                   i.transit_place,
+                   i.inventory_number,
+                   i.location_id, lp.path AS location_path,
+                   NOT item_opac_visible(i.circulation_status) AS hidden_from_opac,
                    so.name as source_name,
                    EXISTS(SELECT 1 FROM loans l WHERE l.item_id = i.id AND l.returned_at IS NULL) as borrowed
             FROM items i
             LEFT JOIN sources so ON i.source_id = so.id
+            LEFT JOIN shelving_location_paths lp ON lp.id = i.location_id
             WHERE i.id = $1 AND i.archived_at IS NULL
             "#,
         )
@@ -1791,11 +2402,16 @@ impl Repository {
             r#"
             SELECT i.id, i.biblio_id, i.source_id, i.barcode, i.call_number, i.volume_designation,
                    i.place, i.borrowable, i.circulation_status, i.notes, i.price,
-                   i.created_at, i.updated_at, i.archived_at,
+                   i.access_url, i.access_type, i.created_at, i.updated_at, i.archived_at, i.deposit_id,
+                   i.transit_place,
+                   i.inventory_number,
+                   i.location_id, lp.path AS location_path,
+                   NOT item_opac_visible(i.circulation_status) AS hidden_from_opac,
                    so.name as source_name,
                    EXISTS(SELECT 1 FROM loans l WHERE l.item_id = i.id AND l.returned_at IS NULL) as borrowed
             FROM items i
             LEFT JOIN sources so ON i.source_id = so.id
+            LEFT JOIN shelving_location_paths lp ON lp.id = i.location_id
             WHERE i.barcode = $1 AND i.archived_at IS NULL
             "#,
         )
@@ -1818,7 +2434,8 @@ impl Repository {
             r#"
             SELECT i.biblio_id, i.id, i.barcode, i.call_number, i.borrowable,
                    so.name as source_name,
-                   EXISTS(SELECT 1 FROM loans l WHERE l.item_id = i.id AND l.returned_at IS NULL) as borrowed
+                   EXISTS(SELECT 1 FROM loans l WHERE l.item_id = i.id AND l.returned_at IS NULL) as borrowed,
+                   NOT item_opac_visible(i.circulation_status) AS hidden_from_opac
             FROM items i
             LEFT JOIN sources so ON i.source_id = so.id
             WHERE i.biblio_id = ANY($1) AND i.archived_at IS NULL
@@ -1838,6 +2455,44 @@ impl Repository {
         Ok(map)
     }
 
+    /// Authors for many biblios via the biblio_authors junction table, in credit order.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn biblios_get_authors_by_biblio_ids(
+        &self,
+        biblio_ids: &[i64],
+    ) -> AppResult<HashMap<i64, Vec<Author>>> {
+        if biblio_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows = sqlx::query(
+            r#"
+            SELECT ba.biblio_id, a.id, a.lastname, a.firstname, a.display_name, a.bio, a.notes, ba.function
+            FROM biblio_authors ba
+            JOIN authors a ON a.id = ba.author_id
+            WHERE ba.biblio_id = ANY($1)
+            ORDER BY ba.biblio_id, ba.position
+            "#,
+        )
+        .bind(biblio_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut map: HashMap<i64, Vec<Author>> = HashMap::new();
+        for r in rows {
+            map.entry(r.get("biblio_id")).or_default().push(Author {
+                id: r.get("id"),
+                key: None,
+                lastname: r.get("lastname"),
+                firstname: r.get("firstname"),
+                display_name: r.get("display_name"),
+                bio: r.get::<Option<String>, _>("bio"),
+                notes: r.get::<Option<String>, _>("notes"),
+                function: r.get::<Option<Function>, _>("function"),
+            });
+        }
+        Ok(map)
+    }
+
     /// Create an item (physical copy) for a biblio
     #[tracing::instrument(skip(self), err)]
     pub async fn biblios_create_item(&self, biblio_id: i64, item: &Item) -> AppResult<Item> {
@@ -1851,12 +2506,18 @@ impl Repository {
             None
         };
         new_item.source_id = source_id;
+        self.item_states_ensure_known(item.circulation_status).await?;
 
+        let mut tx = self.pool.begin().await?;
+        let inventory_year = Local::now().year();
+        let inventory_seq = next_inventory_seq(&mut tx, inventory_year).await?;
         let id = sqlx::query_scalar::<_, i64>(
             r#"
             INSERT INTO items (
-                biblio_id, barcode, call_number, volume_designation, place, borrowable, notes, price, source_id, created_at, updated_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10)
+                biblio_id, barcode, call_number, volume_designation, place, borrowable, notes, price, source_id,
+                circulation_status, access_url, access_type, created_at, updated_at, deposit_id,
+                inventory_year, inventory_seq, location_id
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $13, $14, $15, $16, $17)
             RETURNING id
             "#,
         )
@@ -1869,11 +2530,20 @@ impl Repository {
         .bind(&item.notes)
         .bind(&item.price)
         .bind(source_id)
+        .bind(item.circulation_status)
+        .bind(&item.access_url)
+        .bind(item.access_type)
         .bind(now)
-        .fetch_one(&self.pool)
+        .bind(item.deposit_id)
+        .bind(inventory_year as i16)
+        .bind(inventory_seq)
+        .bind(item.location_id)
+        .fetch_one(&mut *tx)
         .await?;
+        tx.commit().await?;
 
         new_item.id = Some(id);
+        new_item.inventory_number = Some(format_inventory_number(inventory_year, inventory_seq));
         Ok(new_item)
     }
 
@@ -1896,7 +2566,9 @@ impl Repository {
                     notes = $7,
                     price = $8,
                     source_id = $9,
-                    updated_at = $10
+                    updated_at = $10,
+                    deposit_id = COALESCE($12, deposit_id),
+                    location_id = COALESCE($13, location_id)
                 WHERE id = $11
                 "#,
             )
@@ -1911,6 +2583,8 @@ impl Repository {
             .bind(&item.source_id)
             .bind(&item.updated_at)
             .bind(id)
+            .bind(item.deposit_id)
+            .bind(item.location_id)
             .execute(&self.pool)
             .await?;
         } else {
@@ -1937,7 +2611,9 @@ impl Repository {
                         notes = $7,
                         price = $8,
                         source_id = $9,
-                        updated_at = $10
+                        updated_at = $10,
+                        deposit_id = COALESCE($12, deposit_id),
+                        location_id = COALESCE($13, location_id)
                     WHERE id = $11
                     "#,
                 )
@@ -1952,16 +2628,22 @@ impl Repository {
                 .bind(&item.source_id)
                 .bind(&item.updated_at)
                 .bind(id)
+                .bind(item.deposit_id)
+                .bind(item.location_id)
                 .execute(&self.pool)
                 .await?;
             } else {
+                let mut tx = self.pool.begin().await?;
+                let inventory_year = Local::now().year();
+                let inventory_seq = next_inventory_seq(&mut tx, inventory_year).await?;
                 let id = sqlx::query_scalar::<_, i64>(
                     r#"
                     INSERT INTO items (
                         biblio_id, barcode, call_number, volume_designation,
-                        place, borrowable, notes, price, source_id, created_at, updated_at
+                        place, borrowable, notes, price, source_id, created_at, updated_at, deposit_id,
+                        inventory_year, inventory_seq, location_id
                     )
-                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10, $11, $12, $13, $14)
                     RETURNING id
                     "#,
                 )
@@ -1975,10 +2657,16 @@ impl Repository {
                 .bind(&item.price)
                 .bind(&item.source_id)
                 .bind(&item.updated_at)
-                .fetch_one(&self.pool)
+                .bind(item.deposit_id)
+                .bind(inventory_year as i16)
+                .bind(inventory_seq)
+                .bind(item.location_id)
+                .fetch_one(&mut *tx)
                 .await?;
+                tx.commit().await?;
 
                 item.id = Some(id);
+                item.inventory_number = Some(format_inventory_number(inventory_year, inventory_seq));
             }
         }
        
@@ -1988,6 +2676,7 @@ impl Repository {
     /// Update an item (physical copy)
     #[tracing::instrument(skip(self), err)]
     pub async fn items_update<'a>(&self, item: &'a mut Item) -> AppResult<&'a mut Item> {
+        self.item_states_ensure_known(item.circulation_status).await?;
         let now = Utc::now();
         item.updated_at = Some(now);
         sqlx::query(
@@ -2001,8 +2690,15 @@ impl Repository {
                 notes = COALESCE($6, notes),
                 price = COALESCE($7, price),
                 source_id = COALESCE($8, source_id),
-                updated_at = $9
-            WHERE id = $10
+                circulation_status = COALESCE($9, circulation_status),
+                access_url = CASE WHEN $10::text IS NULL THEN access_url ELSE NULLIF($10, '') END,
+                access_type = CASE
+                    WHEN $10::text = '' THEN NULL
+                    ELSE COALESCE($11, access_type)
+                END,
+                location_id = COALESCE($14, location_id),
+                updated_at = $12
+            WHERE id = $13
             "#
         )
         .bind(&item.barcode)
@@ -2013,8 +2709,12 @@ impl Repository {
         .bind(&item.notes)
         .bind(&item.price)
         .bind(&item.source_id)
+        .bind(item.circulation_status)
+        .bind(&item.access_url)
+        .bind(item.access_type)
         .bind(&item.updated_at)
         .bind(item.id.unwrap_or(0))
+        .bind(item.location_id)
         .execute(&self.pool)
         .await?;
 
@@ -2026,6 +2726,24 @@ impl Repository {
     pub async fn items_delete(&self, id: i64, force: bool) -> AppResult<()> {
         let now = Utc::now();
 
+        // Deposit copies belong to another library: they leave with the deposit, even with `force`
+        let deposit_owner: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT d.owner FROM items i
+            JOIN deposits d ON d.id = i.deposit_id
+            WHERE i.id = $1 AND d.status = 'active'
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        if let Some(owner) = deposit_owner {
+            return Err(AppError::BusinessRule(format!(
+                "Item belongs to an active deposit from {} and cannot be weeded",
+                owner
+            )));
+        }
+
         let borrowed = self.loans_count_active_for_item(id).await?;
 
         if borrowed > 0 {
@@ -2111,9 +2829,11 @@ impl Repository {
                 biblio_id = $1, barcode = $2, call_number = $3, volume_designation = $4,
                 place = $5, borrowable = $6,
                 notes = $7, price = $8, source_id = $9,
+                access_url = $10, access_type = $11,
+                location_id = $14,
                 archived_at = NULL,
-                updated_at = $10
-            WHERE id = $11
+                updated_at = $12
+            WHERE id = $13
             "#,
         )
         .bind(biblio_id)
@@ -2125,8 +2845,11 @@ impl Repository {
         .bind(&item.notes)
         .bind(&item.price)
         .bind(source_id)
+        .bind(&item.access_url)
+        .bind(item.access_type)
         .bind(now)
         .bind(item_id)
+        .bind(item.location_id)
         .execute(&self.pool)
         .await?;
 
@@ -2134,11 +2857,16 @@ impl Repository {
             r#"
             SELECT i.id, i.biblio_id, i.source_id, i.barcode, i.call_number, i.volume_designation,
                    i.place, i.borrowable, i.circulation_status, i.notes, i.price,
-                   i.created_at, i.updated_at, i.archived_at,
+                   i.access_url, i.access_type, i.created_at, i.updated_at, i.archived_at, i.deposit_id,
+                   i.transit_place,
+                   i.inventory_number,
+                   i.location_id, lp.path AS location_path,
+                   NOT item_opac_visible(i.circulation_status) AS hidden_from_opac,
                    so.name as source_name,
                    EXISTS(SELECT 1 FROM loans l WHERE l.item_id = i.id AND l.returned_at IS NULL) as borrowed
             FROM items i
             LEFT JOIN sources so ON i.source_id = so.id
+            LEFT JOIN shelving_location_paths lp ON lp.id = i.location_id
             WHERE i.id = $1
             "#,
         )
@@ -2264,6 +2992,117 @@ impl Repository {
         Ok(exists)
     }
 
+    /// Availability summary for the active biblio with the given ISBN (oldest record wins on duplicates).
+    ///
+    /// Copies hidden from the OPAC are not counted, and a biblio whose copies are all hidden is
+    /// not found.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn biblios_get_availability_by_isbn(&self, isbn: &Isbn) -> AppResult<Option<BiblioAvailability>> {
+        let row = sqlx::query_as::<_, BiblioAvailability>(&format!(
+            r#"
+            SELECT {AVAILABILITY_COLUMNS_SQL}
+            {AVAILABILITY_FROM_SQL} AND item_opac_visible(i.circulation_status)
+            WHERE b.isbn = $1 AND b.archived_at IS NULL AND NOT biblio_opac_hidden(b.id)
+            GROUP BY b.id
+            ORDER BY b.id
+            LIMIT 1
+            "#
+        ))
+        .bind(isbn)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Availability summaries for many ISBNs, keyed by ISBN (the oldest active biblio when
+    /// several share one, as for [`Self::biblios_get_availability_by_isbn`]).
+    #[tracing::instrument(skip(self), err)]
+    pub async fn biblios_get_availability_by_isbns(
+        &self,
+        isbns: &[Isbn],
+    ) -> AppResult<HashMap<Isbn, BiblioAvailability>> {
+        if isbns.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let isbns: Vec<&str> = isbns.iter().map(Isbn::as_str).collect();
+        let rows = sqlx::query_as::<_, BiblioAvailability>(&format!(
+            r#"
+            SELECT DISTINCT ON (b.isbn) {AVAILABILITY_COLUMNS_SQL}
+            {AVAILABILITY_FROM_SQL} AND item_opac_visible(i.circulation_status)
+            WHERE b.isbn = ANY($1) AND b.archived_at IS NULL AND NOT biblio_opac_hidden(b.id)
+            GROUP BY b.id
+            ORDER BY b.isbn, b.id
+            "#
+        ))
+        .bind(&isbns)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| row.isbn.clone().map(|isbn| (isbn, row)))
+            .collect())
+    }
+
+    /// Availability summaries for many active biblios, keyed by biblio id.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn biblios_get_availability_by_ids(
+        &self,
+        biblio_ids: &[i64],
+    ) -> AppResult<HashMap<i64, BiblioAvailability>> {
+        if biblio_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows = sqlx::query_as::<_, BiblioAvailability>(&format!(
+            r#"
+            SELECT {AVAILABILITY_COLUMNS_SQL}
+            {AVAILABILITY_FROM_SQL}
+            WHERE b.id = ANY($1) AND b.archived_at IS NULL
+            GROUP BY b.id
+            "#
+        ))
+        .bind(biblio_ids)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|row| (row.biblio_id, row)).collect())
+    }
+
+    /// Recently acquired active biblios, ordered by acquisition time (then id, for stable paging).
+    /// A biblio is acquired with its first copy shown in the OPAC.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn biblios_list_new_acquisitions(&self, days: i64, limit: i64) -> AppResult<Vec<NewAcquisition>> {
+        let rows = sqlx::query_as::<_, NewAcquisition>(
+            r#"
+            WITH acquired AS (
+                SELECT i.biblio_id, MIN(i.created_at) AS acquired_at
+                FROM items i
+                WHERE i.archived_at IS NULL AND i.created_at IS NOT NULL
+                  AND item_opac_visible(i.circulation_status)
+                GROUP BY i.biblio_id
+            )
+            SELECT b.id AS biblio_id, b.isbn, b.title, b.media_type, b.abstract,
+                   (SELECT COALESCE(a.display_name, CONCAT_WS(', ', a.lastname, a.firstname))
+                    FROM biblio_authors ba
+                    INNER JOIN authors a ON a.id = ba.author_id
+                    WHERE ba.biblio_id = b.id
+                    ORDER BY ba.position
+                    LIMIT 1) AS author,
+                   acq.acquired_at,
+                   GREATEST(acq.acquired_at, COALESCE(b.updated_at, acq.acquired_at)) AS updated_at
+            FROM acquired acq
+            INNER JOIN biblios b ON b.id = acq.biblio_id
+            WHERE b.archived_at IS NULL
+              AND acq.acquired_at >= NOW() - make_interval(days => $1::int)
+            ORDER BY acq.acquired_at DESC, b.id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(days)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
     /// Count non-archived items (physical copies) for a source
     #[tracing::instrument(skip(self), err)]
     pub async fn biblios_count_items_for_source(&self, source_id: i64) -> AppResult<i64> {
@@ -2291,6 +3130,212 @@ impl Repository {
         Ok(result.rows_affected() as i64)
     }
 
+    /// Active copies with a call number, optionally filtered by source and call-number prefix.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn items_call_number_candidates(
+        &self,
+        source_id: Option<i64>,
+        call_number_prefix: Option<&str>,
+    ) -> AppResult<Vec<CallNumberCandidate>> {
+        let rows = sqlx::query_as::<_, CallNumberCandidate>(
+            r#"
+            SELECT i.id AS item_id, i.biblio_id, i.barcode, i.call_number, b.audience_type
+            FROM items i
+            JOIN biblios b ON b.id = i.biblio_id
+            WHERE i.archived_at IS NULL
+              AND i.call_number IS NOT NULL AND i.call_number <> ''
+              AND ($1::bigint IS NULL OR i.source_id = $1)
+              AND ($2::text IS NULL OR starts_with(i.call_number, $2))
+            ORDER BY i.id
+            "#,
+        )
+        .bind(source_id)
+        .bind(call_number_prefix)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Write new call numbers in a single statement; returns the number of updated rows.
+    #[tracing::instrument(skip(self, changes), err)]
+    pub async fn items_set_call_numbers(&self, changes: &[(i64, String)]) -> AppResult<u64> {
+        let (ids, call_numbers): (Vec<i64>, Vec<String>) = changes.iter().cloned().unzip();
+        let result = sqlx::query(
+            r#"
+            UPDATE items i SET call_number = c.call_number, updated_at = NOW()
+            FROM UNNEST($1::bigint[], $2::text[]) AS c(id, call_number)
+            WHERE i.id = c.id
+            "#,
+        )
+        .bind(&ids)
+        .bind(&call_numbers)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// One page of active copies for row-oriented exports, ordered by item id
+    #[tracing::instrument(skip(self), err)]
+    pub async fn items_export_page(&self, after_id: i64, limit: i64) -> AppResult<Vec<ItemExportRow>> {
+        let rows = sqlx::query_as::<_, ItemExportRow>(
+            r#"
+            SELECT
+                i.id AS item_id,
+                b.id AS biblio_id,
+                i.barcode,
+                i.call_number,
+                i.volume_designation,
+                b.isbn::text AS isbn,
+                b.title,
+                COALESCE(
+                    (SELECT string_agg(COALESCE(a.display_name, concat_ws(' ', a.lastname, a.firstname)), ', ' ORDER BY ba.position)
+                     FROM biblio_authors ba JOIN authors a ON a.id = ba.author_id
+                     WHERE ba.biblio_id = b.id),
+                    ''
+                ) AS authors,
+                b.media_type,
+                b.publication_date,
+                so.name AS source,
+                st.label AS state,
+                item_opac_visible(i.circulation_status) AS opac_visible,
+                i.borrowable,
+                i.price,
+                i.created_at
+            FROM items i
+            JOIN biblios b ON b.id = i.biblio_id
+            LEFT JOIN sources so ON so.id = i.source_id
+            LEFT JOIN item_states st ON st.code = i.circulation_status
+            WHERE i.archived_at IS NULL AND b.archived_at IS NULL AND i.id > $1
+            ORDER BY i.id
+            LIMIT $2
+            "#,
+        )
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Copies to print spine labels for, ordered by call number (`createdTo` is inclusive)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn items_spine_labels(&self, query: &SpineLabelQuery, limit: i64) -> AppResult<Vec<SpineLabelRow>> {
+        let rows = sqlx::query_as::<_, SpineLabelRow>(
+            r#"
+            SELECT i.id AS item_id, btrim(i.call_number) AS call_number, i.inventory_number
+            FROM items i
+            JOIN biblios b ON b.id = i.biblio_id
+            WHERE i.archived_at IS NULL AND b.archived_at IS NULL
+              AND btrim(COALESCE(i.call_number, '')) <> ''
+              AND ($1::smallint IS NULL OR i.place = $1)
+              AND ($2::date IS NULL OR i.created_at >= $2::date)
+              AND ($3::date IS NULL OR i.created_at < $3::date + 1)
+            ORDER BY btrim(i.call_number), i.id
+            LIMIT $4
+            "#,
+        )
+        .bind(query.place)
+        .bind(query.created_from)
+        .bind(query.created_to)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Copies to print processing slips for, in the order of `item_ids`
+    #[tracing::instrument(skip(self), err)]
+    pub async fn items_processing_slips(&self, item_ids: &[i64]) -> AppResult<Vec<ProcessingSlipRow>> {
+        let rows = sqlx::query_as::<_, ProcessingSlipRow>(
+            r#"
+            SELECT
+                i.id AS item_id,
+                b.id AS biblio_id,
+                i.barcode,
+                btrim(i.call_number) AS call_number,
+                i.volume_designation,
+                i.place,
+                b.title,
+                COALESCE(
+                    (SELECT string_agg(COALESCE(a.display_name, concat_ws(' ', a.lastname, a.firstname)), ', ' ORDER BY ba.position)
+                     FROM biblio_authors ba JOIN authors a ON a.id = ba.author_id
+                     WHERE ba.biblio_id = b.id),
+                    ''
+                ) AS authors,
+                b.isbn::text AS isbn,
+                i.inventory_number
+            FROM unnest($1::bigint[]) WITH ORDINALITY AS ids(id, position)
+            JOIN items i ON i.id = ids.id
+            JOIN biblios b ON b.id = i.biblio_id
+            ORDER BY ids.position
+            "#,
+        )
+        .bind(item_ids)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// One page of exportable biblio ids (active, with at least one active copy)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn biblios_export_ids_page(&self, after_id: i64, limit: i64) -> AppResult<Vec<i64>> {
+        let ids = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT b.id FROM biblios b
+            WHERE b.archived_at IS NULL AND b.id > $1
+              AND EXISTS (SELECT 1 FROM items i WHERE i.biblio_id = b.id AND i.archived_at IS NULL)
+            ORDER BY b.id
+            LIMIT $2
+            "#,
+        )
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
+
+    /// Boolean search over active biblios with a copy shown in the OPAC (Z39.50 target); returns up
+    /// to `limit` ids and the hit count
+    #[tracing::instrument(skip(self), err)]
+    pub async fn biblios_search_tree(&self, node: &CatalogSearchNode, limit: i64) -> AppResult<(Vec<i64>, i64)> {
+        let mut params: Vec<String> = Vec::new();
+        let condition = search_node_sql(node, &mut params);
+        let sql = format!(
+            r#"
+            SELECT b.id, COUNT(*) OVER () AS total
+            FROM biblios b
+            WHERE b.archived_at IS NULL
+              AND EXISTS (
+                  SELECT 1 FROM items i
+                  WHERE i.biblio_id = b.id AND i.archived_at IS NULL AND item_opac_visible(i.circulation_status)
+              )
+              AND {condition}
+            ORDER BY b.id
+            LIMIT ${}
+            "#,
+            params.len() + 1
+        );
+        let mut query = sqlx::query(&sql);
+        for p in &params {
+            query = query.bind(p);
+        }
+        let rows = query.bind(limit).fetch_all(&self.pool).await?;
+        let total = rows.first().map(|r| r.get::<i64, _>("total")).unwrap_or(0);
+        Ok((rows.iter().map(|r| r.get("id")).collect(), total))
+    }
+
+    /// Record a click-through on a digital resource
+    #[tracing::instrument(skip(self), err)]
+    pub async fn items_log_access(&self, item_id: i64, user_id: Option<i64>) -> AppResult<()> {
+        sqlx::query("INSERT INTO item_accesses (item_id, user_id) VALUES ($1, $2)")
+            .bind(item_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
     /// Reassign biblios from given source IDs to a new source (no-op: sources are attached to items)
     #[tracing::instrument(skip(self), err)]
     pub async fn biblios_reassign_biblios_source(
@@ -2323,4 +3368,17 @@ impl Repository {
     }
 }
 
-
+/// Take the next inventory number of `year` (the counter row stays locked until the transaction ends)
+async fn next_inventory_seq(tx: &mut sqlx::Transaction<'_, sqlx::Postgres>, year: i32) -> AppResult<i32> {
+    let seq: i32 = sqlx::query_scalar(
+        r#"
+        INSERT INTO item_inventory_counters (year, last_seq) VALUES ($1, 1)
+        ON CONFLICT (year) DO UPDATE SET last_seq = item_inventory_counters.last_seq + 1
+        RETURNING last_seq
+        "#,
+    )
+    .bind(year as i16)
+    .fetch_one(&mut **tx)
+    .await?;
+    Ok(seq)
+}
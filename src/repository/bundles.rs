@@ -0,0 +1,299 @@
+//! Item bundles (`item_bundles`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::Row;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::bundle::{BundlePart, BundleReturnReport, CreateItemBundle, ItemBundle, UpdateItemBundle},
+};
+
+/// Bundle columns with part counters (`ib` = `item_bundles`).
+const BUNDLE_SELECT_SQL: &str = r#"
+    SELECT ib.id, ib.name, ib.barcode, ib.notes, ib.created_at, ib.update_at,
+           (SELECT COUNT(*) FROM items it
+            WHERE it.bundle_id = ib.id AND it.archived_at IS NULL) AS parts_count,
+           (SELECT COUNT(*) FROM items it
+            JOIN loans l ON l.item_id = it.id AND l.returned_at IS NULL
+            WHERE it.bundle_id = ib.id AND it.archived_at IS NULL) AS parts_on_loan
+    FROM item_bundles ib
+"#;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait BundlesRepository: Send + Sync {
+    async fn bundles_list(&self) -> AppResult<Vec<ItemBundle>>;
+    async fn bundles_get(&self, id: i64) -> AppResult<ItemBundle>;
+    async fn bundles_create(&self, data: &CreateItemBundle) -> AppResult<ItemBundle>;
+    async fn bundles_update(&self, id: i64, data: &UpdateItemBundle) -> AppResult<ItemBundle>;
+    /// Delete a bundle; its copies become standalone again.
+    async fn bundles_delete(&self, id: i64) -> AppResult<()>;
+    /// Return the scanned parts still on loan and report the parts missing from the set.
+    async fn bundles_return(&self, id: i64, barcodes: &[String]) -> AppResult<BundleReturnReport>;
+}
+
+#[async_trait]
+impl BundlesRepository for Repository {
+    async fn bundles_list(&self) -> AppResult<Vec<ItemBundle>> {
+        Repository::bundles_list(self).await
+    }
+    async fn bundles_get(&self, id: i64) -> AppResult<ItemBundle> {
+        Repository::bundles_get(self, id).await
+    }
+    async fn bundles_create(&self, data: &CreateItemBundle) -> AppResult<ItemBundle> {
+        Repository::bundles_create(self, data).await
+    }
+    async fn bundles_update(&self, id: i64, data: &UpdateItemBundle) -> AppResult<ItemBundle> {
+        Repository::bundles_update(self, id, data).await
+    }
+    async fn bundles_delete(&self, id: i64) -> AppResult<()> {
+        Repository::bundles_delete(self, id).await
+    }
+    async fn bundles_return(&self, id: i64, barcodes: &[String]) -> AppResult<BundleReturnReport> {
+        Repository::bundles_return(self, id, barcodes).await
+    }
+}
+
+impl Repository {
+    /// List bundles by name
+    #[tracing::instrument(skip(self), err)]
+    pub async fn bundles_list(&self) -> AppResult<Vec<ItemBundle>> {
+        let sql = format!("{BUNDLE_SELECT_SQL} ORDER BY ib.name, ib.id");
+        let rows = sqlx::query_as::<_, ItemBundle>(&sql)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    /// Get a bundle with its parts
+    #[tracing::instrument(skip(self), err)]
+    pub async fn bundles_get(&self, id: i64) -> AppResult<ItemBundle> {
+        let sql = format!("{BUNDLE_SELECT_SQL} WHERE ib.id = $1");
+        let mut bundle = sqlx::query_as::<_, ItemBundle>(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Bundle {} not found", id)))?;
+        bundle.parts = self.bundles_parts(id).await?;
+        Ok(bundle)
+    }
+
+    /// Active copies of a bundle with their current loan
+    pub(crate) async fn bundles_parts(&self, id: i64) -> AppResult<Vec<BundlePart>> {
+        let parts = sqlx::query_as::<_, BundlePart>(
+            r#"
+            SELECT it.id AS item_id, it.biblio_id, it.barcode, it.call_number, it.volume_designation, b.title,
+                   (SELECT l.id FROM loans l
+                    WHERE l.item_id = it.id AND l.returned_at IS NULL
+                    ORDER BY l.id LIMIT 1) AS loan_id
+            FROM items it
+            LEFT JOIN biblios b ON b.id = it.biblio_id
+            WHERE it.bundle_id = $1 AND it.archived_at IS NULL
+            ORDER BY it.call_number, it.volume_designation, it.barcode, it.id
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(parts)
+    }
+
+    /// Resolve part barcodes to copy ids; copies must be active and not in another bundle.
+    async fn bundles_resolve_parts(&self, barcodes: &[String], bundle_id: Option<i64>) -> AppResult<Vec<i64>> {
+        let mut item_ids = Vec::with_capacity(barcodes.len());
+        for barcode in barcodes {
+            let row = sqlx::query(
+                "SELECT id, bundle_id FROM items WHERE barcode = $1 AND archived_at IS NULL",
+            )
+            .bind(barcode)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Item with barcode {} not found", barcode)))?;
+
+            let current: Option<i64> = row.get("bundle_id");
+            if current.is_some() && current != bundle_id {
+                return Err(AppError::BusinessRule(format!(
+                    "Item {} already belongs to another bundle",
+                    barcode
+                )));
+            }
+            item_ids.push(row.get::<i64, _>("id"));
+        }
+        Ok(item_ids)
+    }
+
+    /// A bundle barcode must not be used by a copy or another bundle (scans must be unambiguous).
+    async fn bundles_check_barcode(&self, barcode: &str, bundle_id: Option<i64>) -> AppResult<()> {
+        let taken: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(SELECT 1 FROM items WHERE barcode = $1)
+                OR EXISTS(SELECT 1 FROM item_bundles WHERE barcode = $1 AND ($2::bigint IS NULL OR id <> $2))
+            "#,
+        )
+        .bind(barcode)
+        .bind(bundle_id)
+        .fetch_one(&self.pool)
+        .await?;
+        if taken {
+            return Err(AppError::Conflict(format!("Barcode {} is already in use", barcode)));
+        }
+        Ok(())
+    }
+
+    /// Fail when a part of the bundle is on loan (parts cannot change while the set is out).
+    async fn bundles_ensure_not_on_loan(&self, id: i64) -> AppResult<()> {
+        let on_loan: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM items it
+                JOIN loans l ON l.item_id = it.id AND l.returned_at IS NULL
+                WHERE it.bundle_id = $1
+            )
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+        if on_loan {
+            return Err(AppError::BusinessRule(
+                "Bundle has parts on loan — return them before changing the bundle".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Create a bundle and attach its parts
+    #[tracing::instrument(skip(self), err)]
+    pub async fn bundles_create(&self, data: &CreateItemBundle) -> AppResult<ItemBundle> {
+        if let Some(barcode) = data.barcode.as_deref() {
+            self.bundles_check_barcode(barcode, None).await?;
+        }
+        let item_ids = self.bundles_resolve_parts(&data.part_barcodes, None).await?;
+
+        let mut tx = self.pool.begin().await?;
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO item_bundles (name, barcode, notes, created_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+        )
+        .bind(&data.name)
+        .bind(&data.barcode)
+        .bind(&data.notes)
+        .bind(Utc::now())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE items SET bundle_id = $1, updated_at = NOW() WHERE id = ANY($2)")
+            .bind(id)
+            .bind(&item_ids)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        self.bundles_get(id).await
+    }
+
+    /// Update a bundle; a new part list replaces the current one
+    #[tracing::instrument(skip(self), err)]
+    pub async fn bundles_update(&self, id: i64, data: &UpdateItemBundle) -> AppResult<ItemBundle> {
+        // Existence check (and 404 before any validation error)
+        self.bundles_get(id).await?;
+
+        if let Some(barcode) = data.barcode.as_deref().filter(|b| !b.is_empty()) {
+            self.bundles_check_barcode(barcode, Some(id)).await?;
+        }
+        let item_ids = match &data.part_barcodes {
+            Some(barcodes) => {
+                self.bundles_ensure_not_on_loan(id).await?;
+                Some(self.bundles_resolve_parts(barcodes, Some(id)).await?)
+            }
+            None => None,
+        };
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            UPDATE item_bundles
+            SET name = COALESCE($1, name),
+                barcode = CASE WHEN $2::text IS NULL THEN barcode ELSE NULLIF($2, '') END,
+                notes = COALESCE($3, notes),
+                update_at = $4
+            WHERE id = $5
+            "#,
+        )
+        .bind(&data.name)
+        .bind(&data.barcode)
+        .bind(&data.notes)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        if let Some(item_ids) = item_ids {
+            sqlx::query(
+                "UPDATE items SET bundle_id = NULL, updated_at = NOW() WHERE bundle_id = $1 AND NOT (id = ANY($2))",
+            )
+            .bind(id)
+            .bind(&item_ids)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query("UPDATE items SET bundle_id = $1, updated_at = NOW() WHERE id = ANY($2)")
+                .bind(id)
+                .bind(&item_ids)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        self.bundles_get(id).await
+    }
+
+    /// Delete a bundle (not while parts are on loan)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn bundles_delete(&self, id: i64) -> AppResult<()> {
+        self.bundles_ensure_not_on_loan(id).await?;
+        let result = sqlx::query("DELETE FROM item_bundles WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Bundle {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Return the scanned parts; parts still out after the return are reported as missing
+    #[tracing::instrument(skip(self), err)]
+    pub async fn bundles_return(&self, id: i64, barcodes: &[String]) -> AppResult<BundleReturnReport> {
+        let parts = self.bundles_get(id).await?.parts;
+
+        let mut returned = Vec::new();
+        for barcode in barcodes {
+            let part = parts
+                .iter()
+                .find(|p| p.barcode.as_deref() == Some(barcode.as_str()))
+                .ok_or_else(|| {
+                    AppError::BusinessRule(format!("Item {} is not a part of this bundle", barcode))
+                })?;
+            if let Some(loan_id) = part.loan_id {
+                self.loans_return(loan_id).await?;
+                returned.push(BundlePart { loan_id: None, ..part.clone() });
+            }
+        }
+        if returned.is_empty() {
+            return Err(AppError::BusinessRule("None of the scanned parts is on loan".to_string()));
+        }
+
+        let missing = self
+            .bundles_parts(id)
+            .await?
+            .into_iter()
+            .filter(|p| p.loan_id.is_some())
+            .collect();
+        Ok(BundleReturnReport { bundle_id: id, returned, missing })
+    }
+}
@@ -0,0 +1,401 @@
+//! Email campaigns (`campaigns`, `campaign_recipients`) domain methods on Repository
+
+use async_trait::async_trait;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::campaign::{
+        Campaign, CampaignDelivery, CampaignRecipient, CampaignRecipientStatus, CampaignSegment, CreateCampaign,
+        UpdateCampaign,
+    },
+};
+
+/// Campaign columns with the delivery counters; callers append `WHERE …` then `GROUP BY c.id`
+const CAMPAIGN_SELECT: &str = r#"
+    SELECT c.id, c.name, c.subject, c.body, c.segment, c.status, c.created_by, c.created_at,
+           c.update_at, c.started_at, c.completed_at,
+           COUNT(r.email) AS recipients_count,
+           COUNT(r.email) FILTER (WHERE r.status IN ('pending', 'sending')) AS pending_count,
+           COUNT(r.email) FILTER (WHERE r.status = 'sent') AS delivered_count,
+           COUNT(r.email) FILTER (WHERE r.status = 'failed') AS failed_count,
+           COUNT(r.email) FILTER (WHERE r.status = 'bounced') AS bounced_count
+    FROM campaigns c
+    LEFT JOIN campaign_recipients r ON r.campaign_id = c.id
+"#;
+
+/// Patrons matching a segment (`$1` account types, `$2` min age, `$3` max age,
+/// `$4` active within days, `$5` opted-in only). Undeliverable addresses and addresses that
+/// complained are never selected.
+const SEGMENT_WHERE: &str = r#"
+    COALESCE(u.status, 'active') = 'active'
+    AND u.archived_at IS NULL
+    AND NULLIF(TRIM(u.email), '') IS NOT NULL
+    AND (cardinality($1::text[]) = 0 OR u.account_type = ANY($1))
+    AND ($2::int IS NULL OR u.birthdate <= CURRENT_DATE - make_interval(years => $2))
+    AND ($3::int IS NULL OR u.birthdate > CURRENT_DATE - make_interval(years => $3 + 1))
+    AND ($4::int IS NULL
+         OR EXISTS (SELECT 1 FROM loans_all l
+                    WHERE l.user_id = u.id AND l.date >= NOW() - make_interval(days => $4)))
+    AND (NOT $5 OR u.campaigns_opt_in)
+    AND NOT EXISTS (SELECT 1 FROM email_health h
+                    WHERE h.email = LOWER(TRIM(u.email))
+                      AND (h.undeliverable_at IS NOT NULL OR h.complaints > 0))
+"#;
+
+/// Recipients claimed longer ago than this are considered lost (crashed sender) and re-queued
+const STALLED_CLAIM_MINUTES: i32 = 10;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait CampaignsRepository: Send + Sync {
+    async fn campaigns_list(&self) -> AppResult<Vec<Campaign>>;
+    async fn campaigns_get(&self, id: i64) -> AppResult<Campaign>;
+    async fn campaigns_create(&self, data: &CreateCampaign, created_by: i64) -> AppResult<Campaign>;
+    /// Edit a draft; `false` when the campaign is no longer a draft
+    async fn campaigns_update(&self, id: i64, data: &UpdateCampaign) -> AppResult<bool>;
+    /// Delete a campaign unless it is sending; `false` when it is
+    async fn campaigns_delete(&self, id: i64) -> AppResult<bool>;
+    async fn campaigns_segment_count(&self, segment: &CampaignSegment) -> AppResult<i64>;
+    /// Move a draft to `sending` and queue its segment; `None` when it is not a draft
+    async fn campaigns_enqueue(&self, id: i64) -> AppResult<Option<u64>>;
+    /// Put recipients claimed by a crashed sender back in the queue
+    async fn campaigns_requeue_stalled(&self, id: i64) -> AppResult<u64>;
+    /// Claim the next pending recipient
+    async fn campaigns_claim_next(&self, id: i64) -> AppResult<Option<CampaignDelivery>>;
+    /// Record the SMTP outcome of a claimed recipient (`error` = failed)
+    async fn campaigns_mark_delivery(&self, id: i64, email: &str, error: Option<String>) -> AppResult<()>;
+    /// Mark a sending campaign `sent` once nothing is left in its queue
+    async fn campaigns_finish(&self, id: i64) -> AppResult<bool>;
+    async fn campaigns_recipients(
+        &self,
+        id: i64,
+        status: Option<CampaignRecipientStatus>,
+    ) -> AppResult<Vec<CampaignRecipient>>;
+    /// Mark sent recipients as bounced; returns the addresses updated
+    async fn campaigns_record_bounces(&self, id: i64, emails: &[String]) -> AppResult<Vec<String>>;
+    async fn campaigns_set_opt_in(&self, user_id: i64, opt_in: bool) -> AppResult<()>;
+}
+
+#[async_trait]
+impl CampaignsRepository for Repository {
+    async fn campaigns_list(&self) -> AppResult<Vec<Campaign>> {
+        Repository::campaigns_list(self).await
+    }
+    async fn campaigns_get(&self, id: i64) -> AppResult<Campaign> {
+        Repository::campaigns_get(self, id).await
+    }
+    async fn campaigns_create(&self, data: &CreateCampaign, created_by: i64) -> AppResult<Campaign> {
+        Repository::campaigns_create(self, data, created_by).await
+    }
+    async fn campaigns_update(&self, id: i64, data: &UpdateCampaign) -> AppResult<bool> {
+        Repository::campaigns_update(self, id, data).await
+    }
+    async fn campaigns_delete(&self, id: i64) -> AppResult<bool> {
+        Repository::campaigns_delete(self, id).await
+    }
+    async fn campaigns_segment_count(&self, segment: &CampaignSegment) -> AppResult<i64> {
+        Repository::campaigns_segment_count(self, segment).await
+    }
+    async fn campaigns_enqueue(&self, id: i64) -> AppResult<Option<u64>> {
+        Repository::campaigns_enqueue(self, id).await
+    }
+    async fn campaigns_requeue_stalled(&self, id: i64) -> AppResult<u64> {
+        Repository::campaigns_requeue_stalled(self, id).await
+    }
+    async fn campaigns_claim_next(&self, id: i64) -> AppResult<Option<CampaignDelivery>> {
+        Repository::campaigns_claim_next(self, id).await
+    }
+    async fn campaigns_mark_delivery(&self, id: i64, email: &str, error: Option<String>) -> AppResult<()> {
+        Repository::campaigns_mark_delivery(self, id, email, error.as_deref()).await
+    }
+    async fn campaigns_finish(&self, id: i64) -> AppResult<bool> {
+        Repository::campaigns_finish(self, id).await
+    }
+    async fn campaigns_recipients(
+        &self,
+        id: i64,
+        status: Option<CampaignRecipientStatus>,
+    ) -> AppResult<Vec<CampaignRecipient>> {
+        Repository::campaigns_recipients(self, id, status).await
+    }
+    async fn campaigns_record_bounces(&self, id: i64, emails: &[String]) -> AppResult<Vec<String>> {
+        Repository::campaigns_record_bounces(self, id, emails).await
+    }
+    async fn campaigns_set_opt_in(&self, user_id: i64, opt_in: bool) -> AppResult<()> {
+        Repository::campaigns_set_opt_in(self, user_id, opt_in).await
+    }
+}
+
+impl Repository {
+    /// List campaigns, most recent first
+    #[tracing::instrument(skip(self), err)]
+    pub async fn campaigns_list(&self) -> AppResult<Vec<Campaign>> {
+        let rows = sqlx::query_as::<_, Campaign>(&format!(
+            "{} GROUP BY c.id ORDER BY c.created_at DESC, c.id DESC",
+            CAMPAIGN_SELECT
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get a campaign by ID
+    #[tracing::instrument(skip(self), err)]
+    pub async fn campaigns_get(&self, id: i64) -> AppResult<Campaign> {
+        sqlx::query_as::<_, Campaign>(&format!("{} WHERE c.id = $1 GROUP BY c.id", CAMPAIGN_SELECT))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Campaign {} not found", id)))
+    }
+
+    /// Create a draft campaign
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn campaigns_create(&self, data: &CreateCampaign, created_by: i64) -> AppResult<Campaign> {
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO campaigns (name, subject, body, segment, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(&data.name)
+        .bind(&data.subject)
+        .bind(&data.body)
+        .bind(sqlx::types::Json(&data.segment))
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+        self.campaigns_get(id).await
+    }
+
+    /// Edit a draft campaign
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn campaigns_update(&self, id: i64, data: &UpdateCampaign) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE campaigns SET
+                name = COALESCE($1, name),
+                subject = COALESCE($2, subject),
+                body = COALESCE($3, body),
+                segment = COALESCE($4, segment),
+                update_at = NOW()
+            WHERE id = $5 AND status = 'draft'
+            "#,
+        )
+        .bind(data.name.as_deref())
+        .bind(data.subject.as_deref())
+        .bind(data.body.as_deref())
+        .bind(data.segment.as_ref().map(sqlx::types::Json))
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a campaign (with its queue) unless it is sending
+    #[tracing::instrument(skip(self), err)]
+    pub async fn campaigns_delete(&self, id: i64) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM campaigns WHERE id = $1 AND status <> 'sending'")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Distinct email addresses a segment resolves to
+    #[tracing::instrument(skip(self), err)]
+    pub async fn campaigns_segment_count(&self, segment: &CampaignSegment) -> AppResult<i64> {
+        let count: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(DISTINCT LOWER(TRIM(u.email))) FROM users u WHERE {}",
+            SEGMENT_WHERE
+        ))
+        .bind(&segment.account_types)
+        .bind(segment.min_age)
+        .bind(segment.max_age)
+        .bind(segment.active_within_days)
+        .bind(segment.opted_in_only)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    /// Move a draft to `sending` and queue one recipient per distinct address of its segment,
+    /// in one transaction. Returns `None` when the campaign is not a draft (or does not exist).
+    #[tracing::instrument(skip(self), err)]
+    pub async fn campaigns_enqueue(&self, id: i64) -> AppResult<Option<u64>> {
+        let mut tx = self.pool.begin().await?;
+        let segment: Option<sqlx::types::Json<CampaignSegment>> = sqlx::query_scalar(
+            r#"
+            UPDATE campaigns SET status = 'sending', started_at = NOW(), update_at = NOW()
+            WHERE id = $1 AND status = 'draft'
+            RETURNING segment
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(sqlx::types::Json(segment)) = segment else {
+            return Ok(None);
+        };
+        let result = sqlx::query(&format!(
+            r#"
+            INSERT INTO campaign_recipients (campaign_id, email, user_id)
+            SELECT DISTINCT ON (LOWER(TRIM(u.email))) $6, LOWER(TRIM(u.email)), u.id
+            FROM users u
+            WHERE {}
+            ORDER BY LOWER(TRIM(u.email)), u.id
+            "#,
+            SEGMENT_WHERE
+        ))
+        .bind(&segment.account_types)
+        .bind(segment.min_age)
+        .bind(segment.max_age)
+        .bind(segment.active_within_days)
+        .bind(segment.opted_in_only)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(Some(result.rows_affected()))
+    }
+
+    /// Put recipients claimed long ago back in the queue (the sender that claimed them died)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn campaigns_requeue_stalled(&self, id: i64) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE campaign_recipients SET status = 'pending', attempted_at = NULL
+            WHERE campaign_id = $1 AND status = 'sending'
+              AND attempted_at < NOW() - make_interval(mins => $2)
+            "#,
+        )
+        .bind(id)
+        .bind(STALLED_CLAIM_MINUTES)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Claim the next pending recipient (skipping rows locked by another sender)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn campaigns_claim_next(&self, id: i64) -> AppResult<Option<CampaignDelivery>> {
+        let row = sqlx::query_as::<_, CampaignDelivery>(
+            r#"
+            WITH next AS (
+                SELECT email FROM campaign_recipients
+                WHERE campaign_id = $1 AND status = 'pending'
+                ORDER BY email
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            ), claimed AS (
+                UPDATE campaign_recipients r SET status = 'sending', attempted_at = NOW()
+                FROM next
+                WHERE r.campaign_id = $1 AND r.email = next.email
+                RETURNING r.email, r.user_id
+            )
+            SELECT c.email, u.firstname, u.lastname, u.language
+            FROM claimed c
+            LEFT JOIN users u ON u.id = c.user_id
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Record the SMTP outcome of a claimed recipient
+    #[tracing::instrument(skip(self, error), err)]
+    pub async fn campaigns_mark_delivery(&self, id: i64, email: &str, error: Option<&str>) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE campaign_recipients SET
+                status = CASE WHEN $3::text IS NULL THEN 'sent' ELSE 'failed' END,
+                error = $3,
+                sent_at = CASE WHEN $3::text IS NULL THEN NOW() END
+            WHERE campaign_id = $1 AND email = $2
+            "#,
+        )
+        .bind(id)
+        .bind(email)
+        .bind(error)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Mark a sending campaign `sent` once no recipient is pending or claimed
+    #[tracing::instrument(skip(self), err)]
+    pub async fn campaigns_finish(&self, id: i64) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE campaigns SET status = 'sent', completed_at = NOW(), update_at = NOW()
+            WHERE id = $1 AND status = 'sending'
+              AND NOT EXISTS (
+                  SELECT 1 FROM campaign_recipients
+                  WHERE campaign_id = $1 AND status IN ('pending', 'sending')
+              )
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Queued recipients of a campaign, by address
+    #[tracing::instrument(skip(self), err)]
+    pub async fn campaigns_recipients(
+        &self,
+        id: i64,
+        status: Option<CampaignRecipientStatus>,
+    ) -> AppResult<Vec<CampaignRecipient>> {
+        let rows = sqlx::query_as::<_, CampaignRecipient>(
+            r#"
+            SELECT email, user_id, status, error, sent_at, bounced_at
+            FROM campaign_recipients
+            WHERE campaign_id = $1 AND ($2::text IS NULL OR status = $2)
+            ORDER BY email
+            "#,
+        )
+        .bind(id)
+        .bind(status.map(|s| s.as_str()))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Mark sent recipients as bounced (addresses compared case-insensitively)
+    #[tracing::instrument(skip(self, emails), err)]
+    pub async fn campaigns_record_bounces(&self, id: i64, emails: &[String]) -> AppResult<Vec<String>> {
+        let normalized: Vec<String> = emails.iter().map(|e| e.trim().to_lowercase()).collect();
+        let rows: Vec<String> = sqlx::query_scalar(
+            r#"
+            UPDATE campaign_recipients SET status = 'bounced', bounced_at = NOW()
+            WHERE campaign_id = $1 AND email = ANY($2) AND status = 'sent'
+            RETURNING email
+            "#,
+        )
+        .bind(id)
+        .bind(&normalized)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Record a patron's consent to receive campaigns
+    #[tracing::instrument(skip(self), err)]
+    pub async fn campaigns_set_opt_in(&self, user_id: i64, opt_in: bool) -> AppResult<()> {
+        let result = sqlx::query("UPDATE users SET campaigns_opt_in = $1 WHERE id = $2")
+            .bind(opt_in)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("User {} not found", user_id)));
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
-//! CRUD operations for catalog reference entities: series and collections.
+//! CRUD operations for catalog reference entities: series, collections, the genre and
+//! subject heading vocabularies assigned to biblios, and author name forms.
+
+use std::collections::HashMap;
 
 use async_trait::async_trait;
 use chrono::Utc;
@@ -6,12 +9,30 @@ use chrono::Utc;
 use super::Repository;
 use crate::{
     error::{AppError, AppResult},
-    models::biblio::{
-        Collection, CollectionQuery, CreateCollection, CreateSerie, Serie, SerieQuery, UpdateCollection,
-        UpdateSerie,
+    models::{
+        author::{normalize_author_name, Author, AuthorNormalizationChange, AuthorNormalizationReport},
+        biblio::{
+            Collection, CollectionQuery, CreateCollection, CreateSerie, Serie, SerieQuery, UpdateCollection,
+            UpdateSerie,
+        },
+        heading::{
+            CreateGenreHeading, CreateSubjectHeading, GenreHeading, MergeHeadingsReport, SubjectHeading,
+            SubjectQuery, UpdateGenreHeading, UpdateSubjectHeading,
+        },
     },
 };
 
+const GENRE_SELECT: &str = r#"SELECT g.id, g.code, g.label, g.sort_order,
+           (SELECT COUNT(*) FROM biblio_genres bg WHERE bg.genre_id = g.id) AS biblio_count,
+           g.created_at, g.update_at
+    FROM genres g"#;
+
+const SUBJECT_SELECT: &str = r#"SELECT s.id, s.heading, s.broader_id, s.scope_note,
+           (SELECT COUNT(*) FROM subjects n WHERE n.broader_id = s.id) AS narrower_count,
+           (SELECT COUNT(*) FROM biblio_subjects bs WHERE bs.subject_id = s.id) AS biblio_count,
+           s.created_at, s.update_at
+    FROM subjects s"#;
+
 #[async_trait]
 pub trait CatalogEntitiesRepository: Send + Sync {
     // ── Series ────────────────────────────────────────────────────────────────
@@ -27,6 +48,40 @@ pub trait CatalogEntitiesRepository: Send + Sync {
     async fn collections_create(&self, data: &CreateCollection) -> AppResult<Collection>;
     async fn collections_update(&self, id: i64, data: &UpdateCollection) -> AppResult<Collection>;
     async fn collections_delete(&self, id: i64) -> AppResult<()>;
+
+    // ── Genres ────────────────────────────────────────────────────────────────
+    async fn genres_list(&self) -> AppResult<Vec<GenreHeading>>;
+    async fn genres_get(&self, id: i64) -> AppResult<GenreHeading>;
+    async fn genres_create(&self, data: &CreateGenreHeading) -> AppResult<GenreHeading>;
+    async fn genres_update(&self, id: i64, data: &UpdateGenreHeading) -> AppResult<GenreHeading>;
+    async fn genres_delete(&self, id: i64) -> AppResult<()>;
+    async fn genres_merge(&self, target_id: i64, source_ids: &[i64]) -> AppResult<MergeHeadingsReport>;
+    /// Biblios having this genre.
+    async fn genres_biblio_ids(&self, id: i64) -> AppResult<Vec<i64>>;
+    async fn biblio_genres_get(&self, biblio_id: i64) -> AppResult<Vec<GenreHeading>>;
+    async fn biblio_genres_set(&self, biblio_id: i64, genre_ids: &[i64]) -> AppResult<Vec<GenreHeading>>;
+
+    // ── Subjects ──────────────────────────────────────────────────────────────
+    async fn subjects_list(&self, query: &SubjectQuery) -> AppResult<(Vec<SubjectHeading>, i64)>;
+    async fn subjects_get(&self, id: i64) -> AppResult<SubjectHeading>;
+    async fn subjects_get_by_ids(&self, ids: &[i64]) -> AppResult<Vec<SubjectHeading>>;
+    async fn subjects_create(&self, data: &CreateSubjectHeading) -> AppResult<SubjectHeading>;
+    async fn subjects_update(&self, id: i64, data: &UpdateSubjectHeading) -> AppResult<SubjectHeading>;
+    async fn subjects_delete(&self, id: i64) -> AppResult<()>;
+    async fn subjects_merge(&self, target_id: i64, source_ids: &[i64]) -> AppResult<MergeHeadingsReport>;
+    /// Biblios having this subject or one of its narrower terms.
+    async fn subjects_biblio_ids(&self, id: i64) -> AppResult<Vec<i64>>;
+    async fn biblio_subjects_get(&self, biblio_id: i64) -> AppResult<Vec<SubjectHeading>>;
+    async fn biblio_subjects_set(&self, biblio_id: i64, subject_ids: &[i64]) -> AppResult<Vec<SubjectHeading>>;
+
+    // ── Authors ───────────────────────────────────────────────────────────────
+    async fn authors_get(&self, id: i64) -> AppResult<Author>;
+    async fn authors_set_display_name(&self, id: i64, display_name: Option<String>) -> AppResult<Author>;
+    /// Normalize every author name and merge the authors that end up identical; `apply = false`
+    /// only reports. At most `limit` changes are listed.
+    async fn authors_normalize(&self, apply: bool, limit: usize) -> AppResult<AuthorNormalizationReport>;
+    /// Relator codes designated by author role terms (codes, MARC codes, aliases); unknown terms are dropped.
+    async fn authors_resolve_roles(&self, roles: &[String]) -> AppResult<Vec<String>>;
 }
 
 #[async_trait]
@@ -61,6 +116,75 @@ impl CatalogEntitiesRepository for Repository {
     async fn collections_delete(&self, id: i64) -> AppResult<()> {
         Repository::collections_delete(self, id).await
     }
+    async fn genres_list(&self) -> AppResult<Vec<GenreHeading>> {
+        Repository::genres_list(self).await
+    }
+    async fn genres_get(&self, id: i64) -> AppResult<GenreHeading> {
+        Repository::genres_get(self, id).await
+    }
+    async fn genres_create(&self, data: &CreateGenreHeading) -> AppResult<GenreHeading> {
+        Repository::genres_create(self, data).await
+    }
+    async fn genres_update(&self, id: i64, data: &UpdateGenreHeading) -> AppResult<GenreHeading> {
+        Repository::genres_update(self, id, data).await
+    }
+    async fn genres_delete(&self, id: i64) -> AppResult<()> {
+        Repository::genres_delete(self, id).await
+    }
+    async fn genres_merge(&self, target_id: i64, source_ids: &[i64]) -> AppResult<MergeHeadingsReport> {
+        Repository::genres_merge(self, target_id, source_ids).await
+    }
+    async fn genres_biblio_ids(&self, id: i64) -> AppResult<Vec<i64>> {
+        Repository::genres_biblio_ids(self, id).await
+    }
+    async fn biblio_genres_get(&self, biblio_id: i64) -> AppResult<Vec<GenreHeading>> {
+        Repository::biblio_genres_get(self, biblio_id).await
+    }
+    async fn biblio_genres_set(&self, biblio_id: i64, genre_ids: &[i64]) -> AppResult<Vec<GenreHeading>> {
+        Repository::biblio_genres_set(self, biblio_id, genre_ids).await
+    }
+    async fn subjects_list(&self, query: &SubjectQuery) -> AppResult<(Vec<SubjectHeading>, i64)> {
+        Repository::subjects_list(self, query).await
+    }
+    async fn subjects_get(&self, id: i64) -> AppResult<SubjectHeading> {
+        Repository::subjects_get(self, id).await
+    }
+    async fn subjects_get_by_ids(&self, ids: &[i64]) -> AppResult<Vec<SubjectHeading>> {
+        Repository::subjects_get_by_ids(self, ids).await
+    }
+    async fn subjects_create(&self, data: &CreateSubjectHeading) -> AppResult<SubjectHeading> {
+        Repository::subjects_create(self, data).await
+    }
+    async fn subjects_update(&self, id: i64, data: &UpdateSubjectHeading) -> AppResult<SubjectHeading> {
+        Repository::subjects_update(self, id, data).await
+    }
+    async fn subjects_delete(&self, id: i64) -> AppResult<()> {
+        Repository::subjects_delete(self, id).await
+    }
+    async fn subjects_merge(&self, target_id: i64, source_ids: &[i64]) -> AppResult<MergeHeadingsReport> {
+        Repository::subjects_merge(self, target_id, source_ids).await
+    }
+    async fn subjects_biblio_ids(&self, id: i64) -> AppResult<Vec<i64>> {
+        Repository::subjects_biblio_ids(self, id).await
+    }
+    async fn biblio_subjects_get(&self, biblio_id: i64) -> AppResult<Vec<SubjectHeading>> {
+        Repository::biblio_subjects_get(self, biblio_id).await
+    }
+    async fn biblio_subjects_set(&self, biblio_id: i64, subject_ids: &[i64]) -> AppResult<Vec<SubjectHeading>> {
+        Repository::biblio_subjects_set(self, biblio_id, subject_ids).await
+    }
+    async fn authors_get(&self, id: i64) -> AppResult<Author> {
+        Repository::authors_get(self, id).await
+    }
+    async fn authors_set_display_name(&self, id: i64, display_name: Option<String>) -> AppResult<Author> {
+        Repository::authors_set_display_name(self, id, display_name.as_deref()).await
+    }
+    async fn authors_normalize(&self, apply: bool, limit: usize) -> AppResult<AuthorNormalizationReport> {
+        Repository::authors_normalize(self, apply, limit).await
+    }
+    async fn authors_resolve_roles(&self, roles: &[String]) -> AppResult<Vec<String>> {
+        Repository::authors_resolve_roles(self, roles).await
+    }
 }
 
 impl Repository {
@@ -372,4 +496,700 @@ impl Repository {
         Ok(())
     }
 
+    // =========================================================================
+    // GENRES
+    // =========================================================================
+
+    pub async fn genres_list(&self) -> AppResult<Vec<GenreHeading>> {
+        let rows = sqlx::query_as(&format!("{GENRE_SELECT} ORDER BY g.sort_order, g.label"))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn genres_get(&self, id: i64) -> AppResult<GenreHeading> {
+        sqlx::query_as(&format!("{GENRE_SELECT} WHERE g.id = $1"))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Genre {id} not found")))
+    }
+
+    pub async fn genres_create(&self, data: &CreateGenreHeading) -> AppResult<GenreHeading> {
+        let id: i64 = sqlx::query_scalar(
+            r#"INSERT INTO genres (code, label, sort_order, created_at, update_at)
+               VALUES ($1, $2, COALESCE($3, (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM genres)), $4, $4)
+               RETURNING id"#,
+        )
+        .bind(data.code.trim())
+        .bind(data.label.trim())
+        .bind(data.sort_order)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Self::genre_conflict(e, data.code.trim()))?;
+
+        self.genres_get(id).await
+    }
+
+    pub async fn genres_update(&self, id: i64, data: &UpdateGenreHeading) -> AppResult<GenreHeading> {
+        let code = data.code.as_deref().map(str::trim);
+        let updated = sqlx::query_scalar::<_, bool>(
+            r#"UPDATE genres SET
+                   code       = COALESCE($1, code),
+                   label      = COALESCE($2, label),
+                   sort_order = COALESCE($3, sort_order),
+                   update_at  = $4
+               WHERE id = $5
+               RETURNING true"#,
+        )
+        .bind(code)
+        .bind(data.label.as_deref().map(str::trim))
+        .bind(data.sort_order)
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Self::genre_conflict(e, code.unwrap_or_default()))?;
+
+        if updated.is_none() {
+            return Err(AppError::NotFound(format!("Genre {id} not found")));
+        }
+
+        self.genres_get(id).await
+    }
+
+    pub async fn genres_delete(&self, id: i64) -> AppResult<()> {
+        let used: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM biblio_genres WHERE genre_id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        if used > 0 {
+            return Err(AppError::Conflict(format!(
+                "Genre {id} is still assigned to {used} biblio(s); merge it into another genre instead"
+            )));
+        }
+
+        let deleted = sqlx::query_scalar::<_, bool>("DELETE FROM genres WHERE id = $1 RETURNING true")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if deleted.is_none() {
+            return Err(AppError::NotFound(format!("Genre {id} not found")));
+        }
+
+        Ok(())
+    }
+
+    /// Re-map every biblio of `source_ids` to `target_id`, then delete the source genres.
+    pub async fn genres_merge(&self, target_id: i64, source_ids: &[i64]) -> AppResult<MergeHeadingsReport> {
+        let mut tx = self.pool.begin().await?;
+        let source_ids = Self::check_merge_sources(&mut tx, "genres", "Genre", target_id, source_ids).await?;
+
+        let biblio_ids: Vec<i64> = sqlx::query_scalar(
+            "SELECT DISTINCT biblio_id FROM biblio_genres WHERE genre_id = ANY($1) ORDER BY biblio_id",
+        )
+        .bind(&source_ids)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"INSERT INTO biblio_genres (biblio_id, genre_id)
+               SELECT DISTINCT biblio_id, $2 FROM biblio_genres WHERE genre_id = ANY($1)
+               ON CONFLICT DO NOTHING"#,
+        )
+        .bind(&source_ids)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM genres WHERE id = ANY($1)")
+            .bind(&source_ids)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(MergeHeadingsReport {
+            target_id,
+            merged_ids: source_ids,
+            biblios_remapped: biblio_ids.len() as i64,
+            narrower_moved: 0,
+            biblio_ids,
+        })
+    }
+
+    pub async fn genres_biblio_ids(&self, id: i64) -> AppResult<Vec<i64>> {
+        let ids = sqlx::query_scalar("SELECT biblio_id FROM biblio_genres WHERE genre_id = $1 ORDER BY biblio_id")
+            .bind(id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(ids)
+    }
+
+    pub async fn biblio_genres_get(&self, biblio_id: i64) -> AppResult<Vec<GenreHeading>> {
+        self.biblio_ensure_exists(biblio_id).await?;
+        let rows = sqlx::query_as(&format!(
+            "{GENRE_SELECT} JOIN biblio_genres l ON l.genre_id = g.id WHERE l.biblio_id = $1 ORDER BY g.sort_order, g.label"
+        ))
+        .bind(biblio_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Replace the genres of a biblio.
+    pub async fn biblio_genres_set(&self, biblio_id: i64, genre_ids: &[i64]) -> AppResult<Vec<GenreHeading>> {
+        let mut tx = self.pool.begin().await?;
+        Self::check_heading_assignment(&mut tx, "genres", "genre", biblio_id, genre_ids).await?;
+
+        sqlx::query("DELETE FROM biblio_genres WHERE biblio_id = $1")
+            .bind(biblio_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO biblio_genres (biblio_id, genre_id) SELECT $1, UNNEST($2::BIGINT[]) ON CONFLICT DO NOTHING",
+        )
+        .bind(biblio_id)
+        .bind(genre_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        self.biblio_genres_get(biblio_id).await
+    }
+
+    fn genre_conflict(e: sqlx::Error, code: &str) -> AppError {
+        if e.to_string().contains("unique") {
+            AppError::Conflict(format!("A genre with code '{code}' already exists"))
+        } else {
+            AppError::Internal(e.to_string())
+        }
+    }
+
+    // =========================================================================
+    // SUBJECTS
+    // =========================================================================
+
+    pub async fn subjects_list(&self, query: &SubjectQuery) -> AppResult<(Vec<SubjectHeading>, i64)> {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(50).clamp(1, 200);
+        let offset = (page - 1) * per_page;
+        let pattern = query
+            .q
+            .as_deref()
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .map(|q| format!("%{}%", q.replace('%', "\\%").replace('_', "\\_")));
+        let top_level = query.broader_id.is_none() && query.top_level.unwrap_or(false);
+
+        const FILTER: &str = r#"WHERE ($1::TEXT IS NULL OR unaccent(lower(s.heading)) LIKE unaccent(lower($1)))
+              AND ($2::BIGINT IS NULL OR s.broader_id = $2)
+              AND (NOT $3 OR s.broader_id IS NULL)"#;
+
+        let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM subjects s {FILTER}"))
+            .bind(&pattern)
+            .bind(query.broader_id)
+            .bind(top_level)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let rows = sqlx::query_as(&format!(
+            "{SUBJECT_SELECT} {FILTER} ORDER BY lower(s.heading) LIMIT $4 OFFSET $5"
+        ))
+        .bind(&pattern)
+        .bind(query.broader_id)
+        .bind(top_level)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((rows, total))
+    }
+
+    pub async fn subjects_get(&self, id: i64) -> AppResult<SubjectHeading> {
+        sqlx::query_as(&format!("{SUBJECT_SELECT} WHERE s.id = $1"))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Subject {id} not found")))
+    }
+
+    pub async fn subjects_get_by_ids(&self, ids: &[i64]) -> AppResult<Vec<SubjectHeading>> {
+        let rows = sqlx::query_as(&format!("{SUBJECT_SELECT} WHERE s.id = ANY($1) ORDER BY lower(s.heading)"))
+            .bind(ids)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn subjects_create(&self, data: &CreateSubjectHeading) -> AppResult<SubjectHeading> {
+        if let Some(broader_id) = data.broader_id {
+            self.subjects_ensure_exists(broader_id, "Broader subject").await?;
+        }
+
+        let heading = data.heading.trim();
+        let id: i64 = sqlx::query_scalar(
+            r#"INSERT INTO subjects (heading, broader_id, scope_note, created_at, update_at)
+               VALUES ($1, $2, NULLIF($3, ''), $4, $4)
+               RETURNING id"#,
+        )
+        .bind(heading)
+        .bind(data.broader_id)
+        .bind(data.scope_note.as_deref().map(str::trim))
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Self::subject_conflict(e, heading))?;
+
+        self.subjects_get(id).await
+    }
+
+    pub async fn subjects_update(&self, id: i64, data: &UpdateSubjectHeading) -> AppResult<SubjectHeading> {
+        let broader_id = if data.top_level { None } else { data.broader_id };
+        if let Some(broader_id) = broader_id {
+            if broader_id == id {
+                return Err(AppError::Validation("A subject cannot be its own broader term".into()));
+            }
+            self.subjects_ensure_exists(broader_id, "Broader subject").await?;
+            if self.subjects_is_narrower(id, broader_id).await? {
+                return Err(AppError::BusinessRule(format!(
+                    "Subject {broader_id} is a narrower term of subject {id}; the hierarchy would loop"
+                )));
+            }
+        }
+
+        let heading = data.heading.as_deref().map(str::trim);
+        let updated = sqlx::query_scalar::<_, bool>(
+            r#"UPDATE subjects SET
+                   heading    = COALESCE($1, heading),
+                   broader_id = CASE WHEN $2 THEN NULL ELSE COALESCE($3, broader_id) END,
+                   scope_note = CASE WHEN $4::TEXT IS NULL THEN scope_note ELSE NULLIF($4, '') END,
+                   update_at  = $5
+               WHERE id = $6
+               RETURNING true"#,
+        )
+        .bind(heading)
+        .bind(data.top_level)
+        .bind(broader_id)
+        .bind(data.scope_note.as_deref().map(str::trim))
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Self::subject_conflict(e, heading.unwrap_or_default()))?;
+
+        if updated.is_none() {
+            return Err(AppError::NotFound(format!("Subject {id} not found")));
+        }
+
+        self.subjects_get(id).await
+    }
+
+    pub async fn subjects_delete(&self, id: i64) -> AppResult<()> {
+        let (used, narrower): (i64, i64) = sqlx::query_as(
+            r#"SELECT (SELECT COUNT(*) FROM biblio_subjects WHERE subject_id = $1),
+                      (SELECT COUNT(*) FROM subjects WHERE broader_id = $1)"#,
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        if used > 0 {
+            return Err(AppError::Conflict(format!(
+                "Subject {id} is still assigned to {used} biblio(s); merge it into another subject instead"
+            )));
+        }
+        if narrower > 0 {
+            return Err(AppError::Conflict(format!(
+                "Subject {id} still has {narrower} narrower term(s)"
+            )));
+        }
+
+        let deleted = sqlx::query_scalar::<_, bool>("DELETE FROM subjects WHERE id = $1 RETURNING true")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if deleted.is_none() {
+            return Err(AppError::NotFound(format!("Subject {id} not found")));
+        }
+
+        Ok(())
+    }
+
+    /// Re-map every biblio of `source_ids` to `target_id`, move their narrower terms under
+    /// `target_id`, then delete the source subjects.
+    pub async fn subjects_merge(&self, target_id: i64, source_ids: &[i64]) -> AppResult<MergeHeadingsReport> {
+        let mut tx = self.pool.begin().await?;
+        let source_ids = Self::check_merge_sources(&mut tx, "subjects", "Subject", target_id, source_ids).await?;
+
+        let under_source: bool = sqlx::query_scalar(
+            r#"WITH RECURSIVE ancestors AS (
+                   SELECT broader_id AS id FROM subjects WHERE id = $1
+                   UNION
+                   SELECT s.broader_id FROM subjects s JOIN ancestors a ON s.id = a.id
+               )
+               SELECT EXISTS (SELECT 1 FROM ancestors WHERE id = ANY($2))"#,
+        )
+        .bind(target_id)
+        .bind(&source_ids)
+        .fetch_one(&mut *tx)
+        .await?;
+        if under_source {
+            return Err(AppError::BusinessRule(format!(
+                "Subject {target_id} is a narrower term of a merged subject; merge into a broader term instead"
+            )));
+        }
+
+        let biblio_ids: Vec<i64> = sqlx::query_scalar(
+            "SELECT DISTINCT biblio_id FROM biblio_subjects WHERE subject_id = ANY($1) ORDER BY biblio_id",
+        )
+        .bind(&source_ids)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"INSERT INTO biblio_subjects (biblio_id, subject_id)
+               SELECT DISTINCT biblio_id, $2 FROM biblio_subjects WHERE subject_id = ANY($1)
+               ON CONFLICT DO NOTHING"#,
+        )
+        .bind(&source_ids)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let narrower_moved = sqlx::query(
+            "UPDATE subjects SET broader_id = $2, update_at = NOW() WHERE broader_id = ANY($1)",
+        )
+        .bind(&source_ids)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+
+        sqlx::query("DELETE FROM subjects WHERE id = ANY($1)")
+            .bind(&source_ids)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(MergeHeadingsReport {
+            target_id,
+            merged_ids: source_ids,
+            biblios_remapped: biblio_ids.len() as i64,
+            narrower_moved,
+            biblio_ids,
+        })
+    }
+
+    pub async fn subjects_biblio_ids(&self, id: i64) -> AppResult<Vec<i64>> {
+        let ids = sqlx::query_scalar(
+            r#"WITH RECURSIVE tree AS (
+                   SELECT $1::BIGINT AS id
+                   UNION
+                   SELECT s.id FROM subjects s JOIN tree t ON s.broader_id = t.id
+               )
+               SELECT DISTINCT biblio_id FROM biblio_subjects
+               WHERE subject_id IN (SELECT id FROM tree)
+               ORDER BY biblio_id"#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
+
+    pub async fn biblio_subjects_get(&self, biblio_id: i64) -> AppResult<Vec<SubjectHeading>> {
+        self.biblio_ensure_exists(biblio_id).await?;
+        let rows = sqlx::query_as(&format!(
+            "{SUBJECT_SELECT} JOIN biblio_subjects l ON l.subject_id = s.id WHERE l.biblio_id = $1 ORDER BY lower(s.heading)"
+        ))
+        .bind(biblio_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Replace the subjects of a biblio.
+    pub async fn biblio_subjects_set(&self, biblio_id: i64, subject_ids: &[i64]) -> AppResult<Vec<SubjectHeading>> {
+        let mut tx = self.pool.begin().await?;
+        Self::check_heading_assignment(&mut tx, "subjects", "subject", biblio_id, subject_ids).await?;
+
+        sqlx::query("DELETE FROM biblio_subjects WHERE biblio_id = $1")
+            .bind(biblio_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO biblio_subjects (biblio_id, subject_id) SELECT $1, UNNEST($2::BIGINT[]) ON CONFLICT DO NOTHING",
+        )
+        .bind(biblio_id)
+        .bind(subject_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        self.biblio_subjects_get(biblio_id).await
+    }
+
+    async fn subjects_ensure_exists(&self, id: i64, what: &str) -> AppResult<()> {
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM subjects WHERE id = $1)")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+        if !exists {
+            return Err(AppError::NotFound(format!("{what} {id} not found")));
+        }
+        Ok(())
+    }
+
+    /// Whether `candidate` sits somewhere below `id` in the hierarchy.
+    async fn subjects_is_narrower(&self, id: i64, candidate: i64) -> AppResult<bool> {
+        let found = sqlx::query_scalar(
+            r#"WITH RECURSIVE narrower AS (
+                   SELECT id FROM subjects WHERE broader_id = $1
+                   UNION
+                   SELECT s.id FROM subjects s JOIN narrower n ON s.broader_id = n.id
+               )
+               SELECT EXISTS (SELECT 1 FROM narrower WHERE id = $2)"#,
+        )
+        .bind(id)
+        .bind(candidate)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(found)
+    }
+
+    fn subject_conflict(e: sqlx::Error, heading: &str) -> AppError {
+        if e.to_string().contains("unique") {
+            AppError::Conflict(format!("A subject '{heading}' already exists"))
+        } else {
+            AppError::Internal(e.to_string())
+        }
+    }
+
+    // =========================================================================
+    // HEADING HELPERS
+    // =========================================================================
+
+    /// Validate a merge: the target and every source exist and the target is not a source.
+    /// Returns the de-duplicated source ids.
+    async fn check_merge_sources(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        table: &str,
+        what: &str,
+        target_id: i64,
+        source_ids: &[i64],
+    ) -> AppResult<Vec<i64>> {
+        let mut ids = source_ids.to_vec();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() {
+            return Err(AppError::Validation("sourceIds must not be empty".into()));
+        }
+        if ids.contains(&target_id) {
+            return Err(AppError::Validation(format!("{what} {target_id} cannot be merged into itself")));
+        }
+
+        // Lock the headings involved so concurrent merges cannot interleave
+        let found: Vec<i64> = sqlx::query_scalar(&format!(
+            "SELECT id FROM {table} WHERE id = ANY($1) OR id = $2 FOR UPDATE"
+        ))
+        .bind(&ids)
+        .bind(target_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        if let Some(missing) = std::iter::once(target_id).chain(ids.iter().copied()).find(|id| !found.contains(id)) {
+            return Err(AppError::NotFound(format!("{what} {missing} not found")));
+        }
+        Ok(ids)
+    }
+
+    pub async fn authors_get(&self, id: i64) -> AppResult<Author> {
+        sqlx::query_as(
+            r#"SELECT id, key, lastname, firstname, display_name, bio, notes, NULL::VARCHAR AS function
+               FROM authors WHERE id = $1"#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Author {id} not found")))
+    }
+
+    pub async fn authors_set_display_name(&self, id: i64, display_name: Option<&str>) -> AppResult<Author> {
+        let display_name = display_name.map(str::trim).filter(|d| !d.is_empty());
+        let updated = sqlx::query("UPDATE authors SET display_name = $1, update_at = $2 WHERE id = $3")
+            .bind(display_name)
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        if updated == 0 {
+            return Err(AppError::NotFound(format!("Author {id} not found")));
+        }
+        self.authors_get(id).await
+    }
+
+    pub async fn authors_resolve_roles(&self, roles: &[String]) -> AppResult<Vec<String>> {
+        let codes: Vec<String> = sqlx::query_scalar(
+            r#"SELECT DISTINCT code FROM (SELECT resolve_relator(r) AS code FROM unnest($1::TEXT[]) r) x
+               WHERE code IS NOT NULL ORDER BY code"#,
+        )
+        .bind(roles)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(codes)
+    }
+
+    /// Names are normalized in Rust ([`normalize_author_name`]), oldest author first: the first
+    /// author of each normalized name is kept (renamed if needed), the next ones are merged into
+    /// it (credits re-pointed, bio / notes / display form kept when the kept one has none).
+    pub async fn authors_normalize(&self, apply: bool, limit: usize) -> AppResult<AuthorNormalizationReport> {
+        let mut tx = self.pool.begin().await?;
+        if apply {
+            // Keep ingest from creating an author between the scan and the merges
+            sqlx::query("LOCK TABLE authors IN SHARE ROW EXCLUSIVE MODE")
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let rows: Vec<(i64, Option<String>, Option<String>)> =
+            sqlx::query_as("SELECT id, lastname, firstname FROM authors ORDER BY id")
+                .fetch_all(&mut *tx)
+                .await?;
+
+        let mut report = AuthorNormalizationReport { dry_run: !apply, scanned: rows.len() as i64, ..Default::default() };
+        let mut kept: HashMap<(String, Option<String>), i64> = HashMap::new();
+        let mut changes = Vec::new();
+        for (id, lastname, firstname) in rows {
+            let (normalized_lastname, normalized_firstname) =
+                normalize_author_name(lastname.as_deref(), firstname.as_deref());
+            // Nameless authors are left to the authors cleanup
+            let Some(key_lastname) = normalized_lastname.clone() else {
+                continue;
+            };
+            let key = (key_lastname.to_lowercase(), normalized_firstname.as_ref().map(|f| f.to_lowercase()));
+            let merged_into = kept.get(&key).copied();
+            if merged_into.is_none() {
+                kept.insert(key, id);
+                if normalized_lastname == lastname && normalized_firstname == firstname {
+                    continue;
+                }
+                report.renamed += 1;
+            } else {
+                report.merged += 1;
+            }
+            changes.push(AuthorNormalizationChange {
+                id,
+                lastname,
+                firstname,
+                normalized_lastname,
+                normalized_firstname,
+                merged_into,
+            });
+        }
+
+        if apply && !changes.is_empty() {
+            let ids: Vec<i64> = changes.iter().map(|c| c.id).collect();
+            report.biblio_ids = sqlx::query_scalar(
+                "SELECT DISTINCT biblio_id FROM biblio_authors WHERE author_id = ANY($1) ORDER BY biblio_id",
+            )
+            .bind(&ids)
+            .fetch_all(&mut *tx)
+            .await?;
+
+            let now = Utc::now();
+            for change in &changes {
+                let Some(target_id) = change.merged_into else {
+                    sqlx::query("UPDATE authors SET lastname = $1, firstname = $2, update_at = $3 WHERE id = $4")
+                        .bind(&change.normalized_lastname)
+                        .bind(&change.normalized_firstname)
+                        .bind(now)
+                        .bind(change.id)
+                        .execute(&mut *tx)
+                        .await?;
+                    continue;
+                };
+                sqlx::query(
+                    r#"UPDATE authors k SET
+                           bio          = COALESCE(k.bio, d.bio),
+                           notes        = COALESCE(k.notes, d.notes),
+                           display_name = COALESCE(k.display_name, d.display_name),
+                           update_at    = $3
+                       FROM authors d
+                       WHERE k.id = $1 AND d.id = $2"#,
+                )
+                .bind(target_id)
+                .bind(change.id)
+                .bind(now)
+                .execute(&mut *tx)
+                .await?;
+                // Re-point credits; skip those the kept author already has
+                sqlx::query(
+                    r#"INSERT INTO biblio_authors (biblio_id, author_id, function, author_type, position)
+                       SELECT biblio_id, $1, function, author_type, position
+                       FROM   biblio_authors
+                       WHERE  author_id = $2
+                       ON CONFLICT (biblio_id, author_id, function) DO NOTHING"#,
+                )
+                .bind(target_id)
+                .bind(change.id)
+                .execute(&mut *tx)
+                .await?;
+                // Remaining credits go with the duplicate (ON DELETE CASCADE)
+                sqlx::query("DELETE FROM authors WHERE id = $1")
+                    .bind(change.id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+        tx.commit().await?;
+
+        changes.truncate(limit);
+        report.changes = changes;
+        Ok(report)
+    }
+
+    async fn biblio_ensure_exists(&self, biblio_id: i64) -> AppResult<()> {
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM biblios WHERE id = $1)")
+            .bind(biblio_id)
+            .fetch_one(&self.pool)
+            .await?;
+        if !exists {
+            return Err(AppError::NotFound(format!("Biblio {biblio_id} not found")));
+        }
+        Ok(())
+    }
+
+    /// Validate an assignment: the biblio and every heading exist.
+    async fn check_heading_assignment(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        table: &str,
+        what: &str,
+        biblio_id: i64,
+        ids: &[i64],
+    ) -> AppResult<()> {
+        let biblio_exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM biblios WHERE id = $1)")
+            .bind(biblio_id)
+            .fetch_one(&mut **tx)
+            .await?;
+        if !biblio_exists {
+            return Err(AppError::NotFound(format!("Biblio {biblio_id} not found")));
+        }
+
+        let found: Vec<i64> = sqlx::query_scalar(&format!("SELECT id FROM {table} WHERE id = ANY($1)"))
+            .bind(ids)
+            .fetch_all(&mut **tx)
+            .await?;
+        if let Some(unknown) = ids.iter().find(|id| !found.contains(id)) {
+            return Err(AppError::Validation(format!("Unknown {what} id {unknown}")));
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,198 @@
+//! Personal dashboards (`dashboard_widgets`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::dashboard::{DashboardWidget, DashboardWidgetWrite},
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait DashboardsRepository: Send + Sync {
+    /// Widgets of a user in display order
+    async fn dashboards_list_widgets(&self, user_id: i64) -> AppResult<Vec<DashboardWidget>>;
+    async fn dashboards_count_widgets(&self, user_id: i64) -> AppResult<i64>;
+    /// Append a widget after the last one
+    async fn dashboards_add_widget(&self, user_id: i64, data: &DashboardWidgetWrite) -> AppResult<DashboardWidget>;
+    async fn dashboards_update_widget(
+        &self,
+        user_id: i64,
+        id: i64,
+        data: &DashboardWidgetWrite,
+    ) -> AppResult<DashboardWidget>;
+    async fn dashboards_delete_widget(&self, user_id: i64, id: i64) -> AppResult<()>;
+    /// Replace every widget of a user (empty list: reset)
+    async fn dashboards_replace(
+        &self,
+        user_id: i64,
+        widgets: &[DashboardWidgetWrite],
+    ) -> AppResult<Vec<DashboardWidget>>;
+}
+
+#[async_trait]
+impl DashboardsRepository for Repository {
+    async fn dashboards_list_widgets(&self, user_id: i64) -> AppResult<Vec<DashboardWidget>> {
+        Repository::dashboards_list_widgets(self, user_id).await
+    }
+    async fn dashboards_count_widgets(&self, user_id: i64) -> AppResult<i64> {
+        Repository::dashboards_count_widgets(self, user_id).await
+    }
+    async fn dashboards_add_widget(&self, user_id: i64, data: &DashboardWidgetWrite) -> AppResult<DashboardWidget> {
+        Repository::dashboards_add_widget(self, user_id, data).await
+    }
+    async fn dashboards_update_widget(
+        &self,
+        user_id: i64,
+        id: i64,
+        data: &DashboardWidgetWrite,
+    ) -> AppResult<DashboardWidget> {
+        Repository::dashboards_update_widget(self, user_id, id, data).await
+    }
+    async fn dashboards_delete_widget(&self, user_id: i64, id: i64) -> AppResult<()> {
+        Repository::dashboards_delete_widget(self, user_id, id).await
+    }
+    async fn dashboards_replace(
+        &self,
+        user_id: i64,
+        widgets: &[DashboardWidgetWrite],
+    ) -> AppResult<Vec<DashboardWidget>> {
+        Repository::dashboards_replace(self, user_id, widgets).await
+    }
+}
+
+impl Repository {
+    /// Widgets of a user in display order
+    #[tracing::instrument(skip(self), err)]
+    pub async fn dashboards_list_widgets(&self, user_id: i64) -> AppResult<Vec<DashboardWidget>> {
+        let rows = sqlx::query_as::<_, DashboardWidget>(
+            "SELECT * FROM dashboard_widgets WHERE user_id = $1 ORDER BY position, id",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn dashboards_count_widgets(&self, user_id: i64) -> AppResult<i64> {
+        let count = sqlx::query_scalar("SELECT COUNT(*) FROM dashboard_widgets WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    /// Append a widget after the last one
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn dashboards_add_widget(&self, user_id: i64, data: &DashboardWidgetWrite) -> AppResult<DashboardWidget> {
+        let now = Utc::now();
+        let widget = sqlx::query_as::<_, DashboardWidget>(
+            r#"
+            INSERT INTO dashboard_widgets
+                (user_id, position, widget_type, title, params, layout, created_at, update_at)
+            VALUES (
+                $1,
+                (SELECT COALESCE(MAX(position) + 1, 0) FROM dashboard_widgets WHERE user_id = $1),
+                $2, $3, $4, $5, $6, $6
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(data.widget_type)
+        .bind(&data.title)
+        .bind(&data.params)
+        .bind(&data.layout)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(widget)
+    }
+
+    /// Replace the definition of a widget (its position is kept)
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn dashboards_update_widget(
+        &self,
+        user_id: i64,
+        id: i64,
+        data: &DashboardWidgetWrite,
+    ) -> AppResult<DashboardWidget> {
+        sqlx::query_as::<_, DashboardWidget>(
+            r#"
+            UPDATE dashboard_widgets SET
+                widget_type = $1,
+                title = $2,
+                params = $3,
+                layout = $4,
+                update_at = $5
+            WHERE id = $6 AND user_id = $7
+            RETURNING *
+            "#,
+        )
+        .bind(data.widget_type)
+        .bind(&data.title)
+        .bind(&data.params)
+        .bind(&data.layout)
+        .bind(Utc::now())
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Widget {} not found", id)))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn dashboards_delete_widget(&self, user_id: i64, id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM dashboard_widgets WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Widget {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Replace every widget of a user, positions following the list order
+    #[tracing::instrument(skip(self, widgets), err)]
+    pub async fn dashboards_replace(
+        &self,
+        user_id: i64,
+        widgets: &[DashboardWidgetWrite],
+    ) -> AppResult<Vec<DashboardWidget>> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM dashboard_widgets WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut saved = Vec::with_capacity(widgets.len());
+        for (position, data) in widgets.iter().enumerate() {
+            let widget = sqlx::query_as::<_, DashboardWidget>(
+                r#"
+                INSERT INTO dashboard_widgets
+                    (user_id, position, widget_type, title, params, layout, created_at, update_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $7)
+                RETURNING *
+                "#,
+            )
+            .bind(user_id)
+            .bind(position as i32)
+            .bind(data.widget_type)
+            .bind(&data.title)
+            .bind(&data.params)
+            .bind(&data.layout)
+            .bind(now)
+            .fetch_one(&mut *tx)
+            .await?;
+            saved.push(widget);
+        }
+
+        tx.commit().await?;
+        Ok(saved)
+    }
+}
@@ -0,0 +1,236 @@
+//! Deposit collections (`deposits`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::deposit::{CreateDeposit, Deposit, DepositCopy, DepositQuery, UpdateDeposit},
+};
+
+/// Deposit columns with copy counters (`d` = `deposits`).
+const DEPOSIT_SELECT_SQL: &str = r#"
+    SELECT d.id, d.owner, d.label, d.received_at, d.return_due, d.status, d.notes,
+           d.created_by, d.created_at, d.update_at, d.closed_at, d.closed_by,
+           (SELECT COUNT(*) FROM items it
+            WHERE it.deposit_id = d.id AND it.archived_at IS NULL) AS copies_count,
+           (SELECT COUNT(*) FROM items it
+            JOIN loans l ON l.item_id = it.id AND l.returned_at IS NULL
+            WHERE it.deposit_id = d.id AND it.archived_at IS NULL) AS copies_on_loan
+    FROM deposits d
+"#;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait DepositsRepository: Send + Sync {
+    async fn deposits_list(&self, query: &DepositQuery) -> AppResult<Vec<Deposit>>;
+    async fn deposits_get(&self, id: i64) -> AppResult<Deposit>;
+    async fn deposits_create(&self, data: &CreateDeposit, created_by: i64) -> AppResult<Deposit>;
+    async fn deposits_update(&self, id: i64, data: &UpdateDeposit) -> AppResult<Deposit>;
+    /// Delete a deposit registered by mistake (no copy was ever attached to it).
+    async fn deposits_delete(&self, id: i64) -> AppResult<()>;
+    /// Active copies of the deposit, on loan or not (return manifest).
+    async fn deposits_copies(&self, id: i64) -> AppResult<Vec<DepositCopy>>;
+    /// Mark the deposit closed and archive its copies on the shelf; returns the number archived.
+    async fn deposits_close(&self, id: i64, closed_by: i64) -> AppResult<u64>;
+}
+
+#[async_trait]
+impl DepositsRepository for Repository {
+    async fn deposits_list(&self, query: &DepositQuery) -> AppResult<Vec<Deposit>> {
+        Repository::deposits_list(self, query).await
+    }
+    async fn deposits_get(&self, id: i64) -> AppResult<Deposit> {
+        Repository::deposits_get(self, id).await
+    }
+    async fn deposits_create(&self, data: &CreateDeposit, created_by: i64) -> AppResult<Deposit> {
+        Repository::deposits_create(self, data, created_by).await
+    }
+    async fn deposits_update(&self, id: i64, data: &UpdateDeposit) -> AppResult<Deposit> {
+        Repository::deposits_update(self, id, data).await
+    }
+    async fn deposits_delete(&self, id: i64) -> AppResult<()> {
+        Repository::deposits_delete(self, id).await
+    }
+    async fn deposits_copies(&self, id: i64) -> AppResult<Vec<DepositCopy>> {
+        Repository::deposits_copies(self, id).await
+    }
+    async fn deposits_close(&self, id: i64, closed_by: i64) -> AppResult<u64> {
+        Repository::deposits_close(self, id, closed_by).await
+    }
+}
+
+impl Repository {
+    /// List deposits, active first, then by return date
+    #[tracing::instrument(skip(self), err)]
+    pub async fn deposits_list(&self, query: &DepositQuery) -> AppResult<Vec<Deposit>> {
+        let sql = format!(
+            r#"{DEPOSIT_SELECT_SQL}
+            WHERE ($1::text IS NULL OR d.status = $1)
+            ORDER BY (d.status = 'closed'), d.return_due, d.id
+            "#
+        );
+        let rows = sqlx::query_as::<_, Deposit>(&sql)
+            .bind(query.status.map(|s| s.as_str()))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn deposits_get(&self, id: i64) -> AppResult<Deposit> {
+        let sql = format!("{DEPOSIT_SELECT_SQL} WHERE d.id = $1");
+        sqlx::query_as::<_, Deposit>(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Deposit {} not found", id)))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn deposits_create(&self, data: &CreateDeposit, created_by: i64) -> AppResult<Deposit> {
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO deposits (owner, label, received_at, return_due, notes, created_by, created_at)
+            VALUES ($1, $2, COALESCE($3, CURRENT_DATE), $4, $5, $6, $7)
+            RETURNING id
+            "#,
+        )
+        .bind(&data.owner)
+        .bind(&data.label)
+        .bind(data.received_at)
+        .bind(data.return_due)
+        .bind(&data.notes)
+        .bind(created_by)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+        self.deposits_get(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn deposits_update(&self, id: i64, data: &UpdateDeposit) -> AppResult<Deposit> {
+        let result = sqlx::query(
+            r#"
+            UPDATE deposits
+            SET owner = COALESCE($1, owner),
+                label = COALESCE($2, label),
+                return_due = COALESCE($3, return_due),
+                notes = COALESCE($4, notes),
+                update_at = $5
+            WHERE id = $6
+            "#,
+        )
+        .bind(&data.owner)
+        .bind(&data.label)
+        .bind(data.return_due)
+        .bind(&data.notes)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Deposit {} not found", id)));
+        }
+        self.deposits_get(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn deposits_delete(&self, id: i64) -> AppResult<()> {
+        let has_copies: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM items WHERE deposit_id = $1)")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+        if has_copies {
+            return Err(AppError::BusinessRule(
+                "Deposit has copies — close it instead of deleting it".to_string(),
+            ));
+        }
+        let result = sqlx::query("DELETE FROM deposits WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Deposit {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Active copies of a deposit by call number, with their current loan
+    #[tracing::instrument(skip(self), err)]
+    pub async fn deposits_copies(&self, id: i64) -> AppResult<Vec<DepositCopy>> {
+        let rows = sqlx::query_as::<_, DepositCopy>(
+            r#"
+            SELECT it.id AS item_id, it.barcode, it.call_number, b.title, b.isbn,
+                   (SELECT COALESCE(a.display_name, CONCAT_WS(', ', a.lastname, a.firstname))
+                    FROM biblio_authors ba
+                    JOIN authors a ON a.id = ba.author_id
+                    WHERE ba.biblio_id = b.id
+                    ORDER BY ba.position
+                    LIMIT 1) AS author,
+                   l.id AS loan_id, l.expiry_at AS due_at
+            FROM items it
+            LEFT JOIN biblios b ON b.id = it.biblio_id
+            LEFT JOIN loans l ON l.item_id = it.id AND l.returned_at IS NULL
+            WHERE it.deposit_id = $1 AND it.archived_at IS NULL
+            ORDER BY it.call_number, b.title, it.barcode, it.id
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Close a deposit: copies on the shelf are archived (barcode prefixed like a deleted copy, so the
+    /// owner's barcodes can come back with a later deposit); copies on loan stay until they are back.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn deposits_close(&self, id: i64, closed_by: i64) -> AppResult<u64> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE deposits SET status = 'closed', closed_at = $1, closed_by = $2, update_at = $1
+            WHERE id = $3 AND status = 'active'
+            "#,
+        )
+        .bind(now)
+        .bind(closed_by)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::BusinessRule(format!("Deposit {} is not active", id)));
+        }
+
+        let archived = sqlx::query(
+            r#"
+            UPDATE items SET archived_at = $1, updated_at = $1, barcode = CONCAT('ARCH_', $2, '_', barcode)
+            WHERE deposit_id = $3 AND archived_at IS NULL
+              AND NOT EXISTS (SELECT 1 FROM loans l WHERE l.item_id = items.id AND l.returned_at IS NULL)
+            "#,
+        )
+        .bind(now)
+        .bind(now.format("%Y%m%d%H%M%S").to_string())
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE holds SET status = 'cancelled'
+            WHERE status IN ('pending', 'ready')
+              AND item_id IN (SELECT id FROM items WHERE deposit_id = $1 AND archived_at = $2)
+            "#,
+        )
+        .bind(id)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(archived.rows_affected())
+    }
+}
@@ -0,0 +1,395 @@
+//! Donations domain methods on Repository (donations, donated books, donors report)
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::donation::{
+        CreateDonation, CreateDonationItem, Donation, DonationItem, DonationQuery, DonationTriage,
+        DonorSummary, UpdateDonation,
+    },
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait DonationsRepository: Send + Sync {
+    async fn donations_list(&self, query: &DonationQuery) -> AppResult<Vec<Donation>>;
+    async fn donations_get_by_id(&self, id: i64) -> AppResult<Donation>;
+    async fn donations_create(&self, data: &CreateDonation) -> AppResult<Donation>;
+    async fn donations_update(&self, id: i64, data: &UpdateDonation) -> AppResult<Donation>;
+    async fn donations_delete(&self, id: i64) -> AppResult<()>;
+    async fn donations_add_item(
+        &self,
+        donation_id: i64,
+        data: &CreateDonationItem,
+    ) -> AppResult<DonationItem>;
+    async fn donations_get_item(&self, donation_id: i64, line_id: i64) -> AppResult<DonationItem>;
+    async fn donations_delete_item(&self, donation_id: i64, line_id: i64) -> AppResult<()>;
+    /// Record a triage decision; `biblio_id` / `item_id` are set for books added to the catalog.
+    async fn donations_set_triage(
+        &self,
+        line_id: i64,
+        triage: DonationTriage,
+        biblio_id: Option<i64>,
+        item_id: Option<i64>,
+    ) -> AppResult<DonationItem>;
+    /// Number of books of this donation already added to the catalog.
+    async fn donations_count_cataloged(&self, donation_id: i64) -> AppResult<i64>;
+    async fn donations_donors_report(&self, year: i32) -> AppResult<Vec<DonorSummary>>;
+}
+
+#[async_trait::async_trait]
+impl DonationsRepository for Repository {
+    async fn donations_list(&self, query: &DonationQuery) -> AppResult<Vec<Donation>> {
+        Repository::donations_list(self, query).await
+    }
+    async fn donations_get_by_id(&self, id: i64) -> AppResult<Donation> {
+        Repository::donations_get_by_id(self, id).await
+    }
+    async fn donations_create(&self, data: &CreateDonation) -> AppResult<Donation> {
+        Repository::donations_create(self, data).await
+    }
+    async fn donations_update(&self, id: i64, data: &UpdateDonation) -> AppResult<Donation> {
+        Repository::donations_update(self, id, data).await
+    }
+    async fn donations_delete(&self, id: i64) -> AppResult<()> {
+        Repository::donations_delete(self, id).await
+    }
+    async fn donations_add_item(&self, donation_id: i64, data: &CreateDonationItem) -> AppResult<DonationItem> {
+        Repository::donations_add_item(self, donation_id, data).await
+    }
+    async fn donations_get_item(&self, donation_id: i64, line_id: i64) -> AppResult<DonationItem> {
+        Repository::donations_get_item(self, donation_id, line_id).await
+    }
+    async fn donations_delete_item(&self, donation_id: i64, line_id: i64) -> AppResult<()> {
+        Repository::donations_delete_item(self, donation_id, line_id).await
+    }
+    async fn donations_set_triage(
+        &self,
+        line_id: i64,
+        triage: DonationTriage,
+        biblio_id: Option<i64>,
+        item_id: Option<i64>,
+    ) -> AppResult<DonationItem> {
+        Repository::donations_set_triage(self, line_id, triage, biblio_id, item_id).await
+    }
+    async fn donations_count_cataloged(&self, donation_id: i64) -> AppResult<i64> {
+        Repository::donations_count_cataloged(self, donation_id).await
+    }
+    async fn donations_donors_report(&self, year: i32) -> AppResult<Vec<DonorSummary>> {
+        Repository::donations_donors_report(self, year).await
+    }
+}
+
+impl Repository {
+    /// List donations (newest first) with their books
+    #[tracing::instrument(skip(self), err)]
+    pub async fn donations_list(&self, query: &DonationQuery) -> AppResult<Vec<Donation>> {
+        let mut donations = sqlx::query_as::<_, Donation>(
+            r#"
+            SELECT d.* FROM donations d
+            WHERE ($1::int IS NULL OR EXTRACT(YEAR FROM d.received_at)::int = $1)
+              AND ($2::text IS NULL OR d.donor_name ILIKE '%' || $2 || '%')
+              AND (NOT $3 OR EXISTS (
+                    SELECT 1 FROM donation_items di
+                    WHERE di.donation_id = d.id AND di.triage = 'pending'))
+            ORDER BY d.received_at DESC, d.id DESC
+            "#,
+        )
+        .bind(query.year)
+        .bind(query.donor.as_deref().map(str::trim).filter(|s| !s.is_empty()))
+        .bind(query.pending_only.unwrap_or(false))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let ids: Vec<i64> = donations.iter().map(|d| d.id).collect();
+        let items = sqlx::query_as::<_, DonationItem>(
+            "SELECT * FROM donation_items WHERE donation_id = ANY($1) ORDER BY id",
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await?;
+        for item in items {
+            if let Some(donation) = donations.iter_mut().find(|d| d.id == item.donation_id) {
+                donation.items.push(item);
+            }
+        }
+        Ok(donations)
+    }
+
+    /// Get a donation with its books
+    #[tracing::instrument(skip(self), err)]
+    pub async fn donations_get_by_id(&self, id: i64) -> AppResult<Donation> {
+        let mut donation = sqlx::query_as::<_, Donation>("SELECT * FROM donations WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Donation {} not found", id)))?;
+        donation.items = sqlx::query_as::<_, DonationItem>(
+            "SELECT * FROM donation_items WHERE donation_id = $1 ORDER BY id",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(donation)
+    }
+
+    /// Create a donation and its books in one transaction
+    #[tracing::instrument(skip(self), err)]
+    pub async fn donations_create(&self, data: &CreateDonation) -> AppResult<Donation> {
+        self.donations_ensure_donor_user(data.donor_user_id).await?;
+        let mut tx = self.pool.begin().await?;
+        let mut donation = sqlx::query_as::<_, Donation>(
+            r#"
+            INSERT INTO donations (
+                donor_name, donor_email, donor_phone, donor_user_id, received_at, estimated_value, notes
+            )
+            VALUES ($1, $2, $3, $4, COALESCE($5, CURRENT_DATE), $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(data.donor_name.trim())
+        .bind(&data.donor_email)
+        .bind(&data.donor_phone)
+        .bind(data.donor_user_id)
+        .bind(data.received_at)
+        .bind(data.estimated_value)
+        .bind(&data.notes)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for item in &data.items {
+            let row = sqlx::query_as::<_, DonationItem>(
+                r#"
+                INSERT INTO donation_items (donation_id, title, authors, isbn, notes)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING *
+                "#,
+            )
+            .bind(donation.id)
+            .bind(item.title.trim())
+            .bind(&item.authors)
+            .bind(&item.isbn)
+            .bind(&item.notes)
+            .fetch_one(&mut *tx)
+            .await?;
+            donation.items.push(row);
+        }
+
+        tx.commit().await?;
+        Ok(donation)
+    }
+
+    /// Update donation header fields
+    #[tracing::instrument(skip(self), err)]
+    pub async fn donations_update(&self, id: i64, data: &UpdateDonation) -> AppResult<Donation> {
+        self.donations_ensure_donor_user(data.donor_user_id).await?;
+        let now = Utc::now();
+        let mut sets = vec!["update_at = $1".to_string()];
+        let mut idx = 2;
+
+        macro_rules! add_field {
+            ($field:expr, $name:expr) => {
+                if $field.is_some() {
+                    sets.push(format!("{} = ${}", $name, idx));
+                    idx += 1;
+                }
+            };
+        }
+
+        add_field!(data.donor_name, "donor_name");
+        add_field!(data.donor_email, "donor_email");
+        add_field!(data.donor_phone, "donor_phone");
+        add_field!(data.donor_user_id, "donor_user_id");
+        add_field!(data.received_at, "received_at");
+        add_field!(data.estimated_value, "estimated_value");
+        add_field!(data.notes, "notes");
+
+        let query = format!("UPDATE donations SET {} WHERE id = ${}", sets.join(", "), idx);
+
+        let mut builder = sqlx::query(&query).bind(now);
+
+        macro_rules! bind_field {
+            ($field:expr) => {
+                if let Some(ref val) = $field {
+                    builder = builder.bind(val);
+                }
+            };
+        }
+
+        if let Some(ref name) = data.donor_name {
+            builder = builder.bind(name.trim().to_string());
+        }
+        bind_field!(data.donor_email);
+        bind_field!(data.donor_phone);
+        bind_field!(data.donor_user_id);
+        bind_field!(data.received_at);
+        bind_field!(data.estimated_value);
+        bind_field!(data.notes);
+
+        let result = builder.bind(id).execute(&self.pool).await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Donation {} not found", id)));
+        }
+        self.donations_get_by_id(id).await
+    }
+
+    async fn donations_ensure_donor_user(&self, user_id: Option<i64>) -> AppResult<()> {
+        let Some(user_id) = user_id else {
+            return Ok(());
+        };
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await?;
+        if !exists {
+            return Err(AppError::NotFound(format!("User {} not found", user_id)));
+        }
+        Ok(())
+    }
+
+    /// Delete a donation and its books (copies already created are kept)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn donations_delete(&self, id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM donations WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Donation {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Add a book to an existing donation
+    #[tracing::instrument(skip(self), err)]
+    pub async fn donations_add_item(&self, donation_id: i64, data: &CreateDonationItem) -> AppResult<DonationItem> {
+        let row = sqlx::query_as::<_, DonationItem>(
+            r#"
+            INSERT INTO donation_items (donation_id, title, authors, isbn, notes)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(donation_id)
+        .bind(data.title.trim())
+        .bind(&data.authors)
+        .bind(&data.isbn)
+        .bind(&data.notes)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Get one book of a donation
+    #[tracing::instrument(skip(self), err)]
+    pub async fn donations_get_item(&self, donation_id: i64, line_id: i64) -> AppResult<DonationItem> {
+        sqlx::query_as::<_, DonationItem>(
+            "SELECT * FROM donation_items WHERE id = $1 AND donation_id = $2",
+        )
+        .bind(line_id)
+        .bind(donation_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Donated book {} not found in donation {}", line_id, donation_id)))
+    }
+
+    /// Remove a book from a donation
+    #[tracing::instrument(skip(self), err)]
+    pub async fn donations_delete_item(&self, donation_id: i64, line_id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM donation_items WHERE id = $1 AND donation_id = $2")
+            .bind(line_id)
+            .bind(donation_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Donated book {} not found in donation {}", line_id, donation_id)));
+        }
+        Ok(())
+    }
+
+    /// Record a triage decision for a donated book
+    #[tracing::instrument(skip(self), err)]
+    pub async fn donations_set_triage(
+        &self,
+        line_id: i64,
+        triage: DonationTriage,
+        biblio_id: Option<i64>,
+        item_id: Option<i64>,
+    ) -> AppResult<DonationItem> {
+        sqlx::query_as::<_, DonationItem>(
+            r#"
+            UPDATE donation_items SET
+                triage = $1,
+                biblio_id = $2,
+                item_id = $3,
+                triaged_at = CASE WHEN $1 = 'pending' THEN NULL ELSE NOW() END
+            WHERE id = $4
+            RETURNING *
+            "#,
+        )
+        .bind(triage)
+        .bind(biblio_id)
+        .bind(item_id)
+        .bind(line_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Donated book {} not found", line_id)))
+    }
+
+    /// Number of books of this donation already added to the catalog
+    #[tracing::instrument(skip(self), err)]
+    pub async fn donations_count_cataloged(&self, donation_id: i64) -> AppResult<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM donation_items WHERE donation_id = $1 AND triage = 'catalog'",
+        )
+        .bind(donation_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    /// Per-donor totals for donations received during `year`.
+    /// Donors are grouped by patron account when known, otherwise by name (case-insensitive).
+    #[tracing::instrument(skip(self), err)]
+    pub async fn donations_donors_report(&self, year: i32) -> AppResult<Vec<DonorSummary>> {
+        let rows = sqlx::query_as::<_, DonorSummary>(
+            r#"
+            WITH per_donation AS (
+                SELECT d.id, d.donor_name, d.donor_email, d.donor_user_id,
+                       COALESCE(d.estimated_value, 0) AS estimated_value,
+                       COALESCE(d.donor_user_id::text, LOWER(TRIM(d.donor_name))) AS donor_key,
+                       COUNT(di.id) AS books,
+                       COUNT(di.id) FILTER (WHERE di.triage = 'catalog') AS cataloged,
+                       COUNT(di.id) FILTER (WHERE di.triage = 'sale') AS for_sale,
+                       COUNT(di.id) FILTER (WHERE di.triage = 'recycle') AS recycled,
+                       COUNT(di.id) FILTER (WHERE di.triage = 'pending') AS pending
+                FROM donations d
+                LEFT JOIN donation_items di ON di.donation_id = d.id
+                WHERE EXTRACT(YEAR FROM d.received_at)::int = $1
+                GROUP BY d.id
+            )
+            SELECT
+                (ARRAY_AGG(donor_name ORDER BY id DESC))[1] AS donor_name,
+                (ARRAY_AGG(donor_email ORDER BY id DESC) FILTER (WHERE donor_email IS NOT NULL))[1] AS donor_email,
+                MAX(donor_user_id) AS donor_user_id,
+                COUNT(*) AS donations,
+                SUM(books)::bigint AS books,
+                SUM(cataloged)::bigint AS cataloged,
+                SUM(for_sale)::bigint AS for_sale,
+                SUM(recycled)::bigint AS recycled,
+                SUM(pending)::bigint AS pending,
+                SUM(estimated_value) AS estimated_value
+            FROM per_donation
+            GROUP BY donor_key
+            ORDER BY estimated_value DESC, books DESC, donor_name
+            "#,
+        )
+        .bind(year)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,173 @@
+//! Email deliverability (`email_feedback`, `email_health`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::Repository;
+use crate::{
+    error::AppResult,
+    models::email_health::{EmailFeedbackEvent, EmailFeedbackKind, EmailHealth, RecordedEmailFeedback},
+};
+
+/// Health columns with the derived status
+const HEALTH_SELECT: &str = r#"
+    SELECT email, hard_bounces, soft_bounces, complaints, last_feedback_at, last_reason, undeliverable_at,
+           CASE WHEN undeliverable_at IS NOT NULL THEN 'undeliverable'
+                WHEN complaints > 0 THEN 'complained'
+                WHEN hard_bounces + soft_bounces > 0 THEN 'bouncing'
+                ELSE 'healthy'
+           END AS status
+    FROM email_health
+"#;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait EmailHealthRepository: Send + Sync {
+    /// Health of an address; `None` when nothing was ever reported
+    async fn email_health_get(&self, email: &str) -> AppResult<Option<EmailHealth>>;
+    /// Record a bounce or complaint and update the address counters. The address becomes
+    /// undeliverable when its hard bounces reach `hard_bounce_threshold`; a complaint also
+    /// withdraws the campaign consent of the accounts using the address. `None` when the
+    /// provider event id was already recorded.
+    async fn email_health_record(
+        &self,
+        event: &EmailFeedbackEvent,
+        hard_bounce_threshold: u32,
+    ) -> AppResult<Option<RecordedEmailFeedback>>;
+    /// Clear the counters and the suppression of an address; `false` when nothing was reported
+    async fn email_health_reset(&self, email: &str) -> AppResult<bool>;
+}
+
+#[async_trait]
+impl EmailHealthRepository for Repository {
+    async fn email_health_get(&self, email: &str) -> AppResult<Option<EmailHealth>> {
+        Repository::email_health_get(self, email).await
+    }
+    async fn email_health_record(
+        &self,
+        event: &EmailFeedbackEvent,
+        hard_bounce_threshold: u32,
+    ) -> AppResult<Option<RecordedEmailFeedback>> {
+        Repository::email_health_record(self, event, hard_bounce_threshold).await
+    }
+    async fn email_health_reset(&self, email: &str) -> AppResult<bool> {
+        Repository::email_health_reset(self, email).await
+    }
+}
+
+/// Addresses are compared trimmed and lowercased
+fn normalize(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+impl Repository {
+    #[tracing::instrument(skip(self), err)]
+    pub async fn email_health_get(&self, email: &str) -> AppResult<Option<EmailHealth>> {
+        let row = sqlx::query_as::<_, EmailHealth>(&format!("{} WHERE email = $1", HEALTH_SELECT))
+            .bind(normalize(email))
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row)
+    }
+
+    /// Whether no email may be sent to the address (hard bounce threshold reached)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn email_health_is_undeliverable(&self, email: &str) -> AppResult<bool> {
+        let undeliverable: Option<bool> = sqlx::query_scalar(
+            "SELECT undeliverable_at IS NOT NULL FROM email_health WHERE email = $1",
+        )
+        .bind(normalize(email))
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(undeliverable.unwrap_or(false))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn email_health_record(
+        &self,
+        event: &EmailFeedbackEvent,
+        hard_bounce_threshold: u32,
+    ) -> AppResult<Option<RecordedEmailFeedback>> {
+        let email = normalize(&event.email);
+        let occurred_at = event.occurred_at.unwrap_or_else(Utc::now);
+        let mut tx = self.pool.begin().await?;
+
+        let inserted: Option<i64> = sqlx::query_scalar(
+            r#"
+            INSERT INTO email_feedback (email, kind, reason, provider_event_id, occurred_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (provider_event_id) WHERE provider_event_id IS NOT NULL DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(&email)
+        .bind(event.kind.as_str())
+        .bind(&event.reason)
+        .bind(&event.event_id)
+        .bind(occurred_at)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if inserted.is_none() {
+            return Ok(None);
+        }
+
+        let (hard, soft, complaint) = match event.kind {
+            EmailFeedbackKind::HardBounce => (1, 0, 0),
+            EmailFeedbackKind::SoftBounce => (0, 1, 0),
+            EmailFeedbackKind::Complaint => (0, 0, 1),
+        };
+        // NOW() is the transaction start time: equal to `undeliverable_at` only when this event
+        // crossed the threshold
+        let became_undeliverable: bool = sqlx::query_scalar(
+            r#"
+            INSERT INTO email_health AS h
+                (email, hard_bounces, soft_bounces, complaints, last_feedback_at, last_reason, undeliverable_at)
+            VALUES ($1, $2, $3, $4, $5, $6, CASE WHEN $2 >= $7 THEN NOW() END)
+            ON CONFLICT (email) DO UPDATE SET
+                hard_bounces = h.hard_bounces + EXCLUDED.hard_bounces,
+                soft_bounces = h.soft_bounces + EXCLUDED.soft_bounces,
+                complaints = h.complaints + EXCLUDED.complaints,
+                last_feedback_at = GREATEST(h.last_feedback_at, EXCLUDED.last_feedback_at),
+                last_reason = COALESCE(EXCLUDED.last_reason, h.last_reason),
+                undeliverable_at = COALESCE(
+                    h.undeliverable_at,
+                    CASE WHEN h.hard_bounces + EXCLUDED.hard_bounces >= $7 THEN NOW() END
+                ),
+                update_at = NOW()
+            RETURNING h.undeliverable_at IS NOT DISTINCT FROM NOW()
+            "#,
+        )
+        .bind(&email)
+        .bind(hard)
+        .bind(soft)
+        .bind(complaint)
+        .bind(occurred_at)
+        .bind(&event.reason)
+        .bind(hard_bounce_threshold as i32)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if event.kind == EmailFeedbackKind::Complaint {
+            sqlx::query("UPDATE users SET campaigns_opt_in = FALSE WHERE LOWER(TRIM(email)) = $1")
+                .bind(&email)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let health = sqlx::query_as::<_, EmailHealth>(&format!("{} WHERE email = $1", HEALTH_SELECT))
+            .bind(&email)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(Some(RecordedEmailFeedback { health, became_undeliverable }))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn email_health_reset(&self, email: &str) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM email_health WHERE email = $1")
+            .bind(normalize(email))
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
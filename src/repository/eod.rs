@@ -0,0 +1,191 @@
+//! End-of-day job (`eod_runs`, `daily_stats`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::eod::{DailyStats, EodRun, EodTrigger},
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait EodRepository: Send + Sync {
+    /// Open a run unless another one is running (`None` then)
+    async fn eod_runs_start(
+        &self,
+        business_day: NaiveDate,
+        trigger: EodTrigger,
+        triggered_by: Option<i64>,
+    ) -> AppResult<Option<EodRun>>;
+    async fn eod_runs_finish(&self, run: &EodRun) -> AppResult<EodRun>;
+    async fn eod_runs_list(&self, limit: i64) -> AppResult<Vec<EodRun>>;
+    async fn eod_runs_get(&self, id: i64) -> AppResult<EodRun>;
+    /// Compute and store the statistics of `day` (`[from, to)` in UTC).
+    async fn eod_daily_stats_upsert(
+        &self,
+        day: NaiveDate,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> AppResult<DailyStats>;
+    async fn eod_daily_stats_get(&self, day: NaiveDate) -> AppResult<Option<DailyStats>>;
+}
+
+#[async_trait]
+impl EodRepository for Repository {
+    async fn eod_runs_start(
+        &self,
+        business_day: NaiveDate,
+        trigger: EodTrigger,
+        triggered_by: Option<i64>,
+    ) -> AppResult<Option<EodRun>> {
+        Repository::eod_runs_start(self, business_day, trigger, triggered_by).await
+    }
+    async fn eod_runs_finish(&self, run: &EodRun) -> AppResult<EodRun> {
+        Repository::eod_runs_finish(self, run).await
+    }
+    async fn eod_runs_list(&self, limit: i64) -> AppResult<Vec<EodRun>> {
+        Repository::eod_runs_list(self, limit).await
+    }
+    async fn eod_runs_get(&self, id: i64) -> AppResult<EodRun> {
+        Repository::eod_runs_get(self, id).await
+    }
+    async fn eod_daily_stats_upsert(
+        &self,
+        day: NaiveDate,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> AppResult<DailyStats> {
+        Repository::eod_daily_stats_upsert(self, day, from, to).await
+    }
+    async fn eod_daily_stats_get(&self, day: NaiveDate) -> AppResult<Option<DailyStats>> {
+        Repository::eod_daily_stats_get(self, day).await
+    }
+}
+
+impl Repository {
+    /// Open a run. One at a time: runs left `running` for more than 6 hours (server stopped
+    /// mid-way) no longer block new ones.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn eod_runs_start(
+        &self,
+        business_day: NaiveDate,
+        trigger: EodTrigger,
+        triggered_by: Option<i64>,
+    ) -> AppResult<Option<EodRun>> {
+        let mut tx = self.pool.begin().await?;
+        // Serializes concurrent starts (the NOT EXISTS alone would let two through)
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('elidune.eod_runs'))")
+            .execute(&mut *tx)
+            .await?;
+        let row = sqlx::query_as::<_, EodRun>(
+            r#"
+            INSERT INTO eod_runs (business_day, trigger, triggered_by)
+            SELECT $1, $2, $3
+            WHERE NOT EXISTS (
+                SELECT 1 FROM eod_runs
+                WHERE status = 'running' AND started_at > NOW() - INTERVAL '6 hours'
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(business_day)
+        .bind(trigger)
+        .bind(triggered_by)
+        .fetch_optional(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(row)
+    }
+
+    /// Store the outcome of a run
+    #[tracing::instrument(skip(self, run), fields(run_id = run.id), err)]
+    pub async fn eod_runs_finish(&self, run: &EodRun) -> AppResult<EodRun> {
+        sqlx::query_as::<_, EodRun>(
+            r#"
+            UPDATE eod_runs SET
+                status = $1, steps = $2, archived_loans = $3, pull_list_entries = $4,
+                pull_list_artifact_id = $5, digest_recipients = $6, finished_at = NOW()
+            WHERE id = $7
+            RETURNING *
+            "#,
+        )
+        .bind(run.status)
+        .bind(sqlx::types::Json(&run.steps))
+        .bind(run.archived_loans)
+        .bind(run.pull_list_entries)
+        .bind(run.pull_list_artifact_id)
+        .bind(run.digest_recipients)
+        .bind(run.id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("End-of-day run {} not found", run.id)))
+    }
+
+    /// Most recent runs
+    #[tracing::instrument(skip(self), err)]
+    pub async fn eod_runs_list(&self, limit: i64) -> AppResult<Vec<EodRun>> {
+        let rows = sqlx::query_as::<_, EodRun>(
+            "SELECT * FROM eod_runs ORDER BY started_at DESC, id DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get a run by ID
+    #[tracing::instrument(skip(self), err)]
+    pub async fn eod_runs_get(&self, id: i64) -> AppResult<EodRun> {
+        sqlx::query_as::<_, EodRun>("SELECT * FROM eod_runs WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("End-of-day run {} not found", id)))
+    }
+
+    /// Compute the statistics of `day` (`[from, to)`) and store them, replacing a previous snapshot
+    #[tracing::instrument(skip(self), err)]
+    pub async fn eod_daily_stats_upsert(
+        &self,
+        day: NaiveDate,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> AppResult<DailyStats> {
+        let row = sqlx::query_as::<_, DailyStats>(
+            r#"
+            INSERT INTO daily_stats (day, loans, returns, new_users, holds_placed, active_loans, overdue_loans, computed_at)
+            SELECT $1,
+                   (SELECT COUNT(*) FROM loans_all WHERE date >= $2 AND date < $3)::int,
+                   (SELECT COUNT(*) FROM loans_all WHERE returned_at >= $2 AND returned_at < $3)::int,
+                   (SELECT COUNT(*) FROM users WHERE created_at >= $2 AND created_at < $3)::int,
+                   (SELECT COUNT(*) FROM holds WHERE created_at >= $2 AND created_at < $3)::int,
+                   (SELECT COUNT(*) FROM loans WHERE returned_at IS NULL)::int,
+                   (SELECT COUNT(*) FROM loans WHERE returned_at IS NULL AND expiry_at < NOW())::int,
+                   NOW()
+            ON CONFLICT (day) DO UPDATE SET
+                loans = EXCLUDED.loans, returns = EXCLUDED.returns, new_users = EXCLUDED.new_users,
+                holds_placed = EXCLUDED.holds_placed, active_loans = EXCLUDED.active_loans,
+                overdue_loans = EXCLUDED.overdue_loans, computed_at = EXCLUDED.computed_at
+            RETURNING *
+            "#,
+        )
+        .bind(day)
+        .bind(from)
+        .bind(to)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Statistics snapshot of a day, if the end-of-day job wrote one
+    #[tracing::instrument(skip(self), err)]
+    pub async fn eod_daily_stats_get(&self, day: NaiveDate) -> AppResult<Option<DailyStats>> {
+        let row = sqlx::query_as::<_, DailyStats>("SELECT * FROM daily_stats WHERE day = $1")
+            .bind(day)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row)
+    }
+}
@@ -23,6 +23,9 @@ pub trait EquipmentRepository: Send + Sync {
     async fn equipment_delete(&self, id: i64) -> AppResult<()>;
     async fn equipment_count_public_internet_stations(&self) -> AppResult<i64>;
     async fn equipment_count_public_devices(&self) -> AppResult<i64>;
+    /// True when `barcode` is already used by another equipment or by a physical copy.
+    async fn equipment_barcode_in_use(&self, barcode: &str, exclude_id: Option<i64>) -> AppResult<bool>;
+    async fn equipment_has_active_loan(&self, id: i64) -> AppResult<bool>;
 }
 
 
@@ -49,6 +52,12 @@ impl EquipmentRepository for super::Repository {
     async fn equipment_count_public_devices(&self) -> crate::error::AppResult<i64> {
         super::Repository::equipment_count_public_devices(self).await
     }
+    async fn equipment_barcode_in_use(&self, barcode: &str, exclude_id: Option<i64>) -> crate::error::AppResult<bool> {
+        super::Repository::equipment_barcode_in_use(self, barcode, exclude_id).await
+    }
+    async fn equipment_has_active_loan(&self, id: i64) -> crate::error::AppResult<bool> {
+        super::Repository::equipment_has_active_loan(self, id).await
+    }
 }
 
 
@@ -79,8 +88,11 @@ impl Repository {
     pub async fn equipment_create(&self, data: &CreateEquipment) -> AppResult<Equipment> {
         let row = sqlx::query_as::<_, Equipment>(
             r#"
-            INSERT INTO equipment (name, equipment_type, has_internet, is_public, quantity, notes)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO equipment (
+                name, equipment_type, has_internet, is_public, quantity, notes,
+                barcode, loan_duration_days, max_renewals, requires_deposit
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             RETURNING *
             "#,
         )
@@ -90,6 +102,10 @@ impl Repository {
         .bind(data.is_public)
         .bind(data.quantity)
         .bind(&data.notes)
+        .bind(data.barcode.as_deref().map(str::trim).filter(|b| !b.is_empty()))
+        .bind(data.loan_duration_days.unwrap_or(7))
+        .bind(data.max_renewals.unwrap_or(0))
+        .bind(data.requires_deposit.unwrap_or(false))
         .fetch_one(&self.pool)
         .await?;
         Ok(row)
@@ -118,6 +134,10 @@ impl Repository {
         add_field!(data.quantity, "quantity");
         add_field!(data.status, "status");
         add_field!(data.notes, "notes");
+        add_field!(data.barcode, "barcode");
+        add_field!(data.loan_duration_days, "loan_duration_days");
+        add_field!(data.max_renewals, "max_renewals");
+        add_field!(data.requires_deposit, "requires_deposit");
 
         let query = format!("UPDATE equipment SET {} WHERE id = {} RETURNING *", sets.join(", "), id);
 
@@ -138,6 +158,14 @@ impl Repository {
         bind_field!(data.quantity);
         bind_field!(data.status);
         bind_field!(data.notes);
+        // An empty barcode clears it (equipment leaves circulation).
+        if let Some(ref barcode) = data.barcode {
+            let barcode = barcode.trim();
+            builder = builder.bind((!barcode.is_empty()).then(|| barcode.to_string()));
+        }
+        bind_field!(data.loan_duration_days);
+        bind_field!(data.max_renewals);
+        bind_field!(data.requires_deposit);
 
         builder
             .fetch_optional(&self.pool)
@@ -188,5 +216,32 @@ impl Repository {
             .await?;
         Ok(count)
     }
-}
 
+    /// Check whether a barcode is taken by another equipment or by a physical copy
+    #[tracing::instrument(skip(self), err)]
+    pub async fn equipment_barcode_in_use(&self, barcode: &str, exclude_id: Option<i64>) -> AppResult<bool> {
+        let in_use: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(SELECT 1 FROM equipment WHERE barcode = $1 AND ($2::bigint IS NULL OR id <> $2))
+                OR EXISTS(SELECT 1 FROM items WHERE barcode = $1 AND archived_at IS NULL)
+            "#
+        )
+        .bind(barcode)
+        .bind(exclude_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(in_use)
+    }
+
+    /// True when the equipment is currently checked out
+    #[tracing::instrument(skip(self), err)]
+    pub async fn equipment_has_active_loan(&self, id: i64) -> AppResult<bool> {
+        let active: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM loans WHERE equipment_id = $1 AND returned_at IS NULL)"
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(active)
+    }
+}
@@ -7,14 +7,23 @@ use sqlx::Row;
 use super::Repository;
 use crate::{
     error::{AppError, AppResult},
-    models::event::{CreateEvent, Event, EventQuery, UpdateEvent},
+    models::{
+        event::{
+            normalize_room, CreateEvent, Event, EventConflict, EventQuery, EventRegistration, EventSlot,
+            EventStatus, UpdateEvent,
+        },
+        schedule::ScheduleClosure,
+    },
+    repository::users::UserEmailTarget,
 };
 
 /// Columns for [`Event`] mapping (excludes `attachment_data` BYTEA; exposes `attachment_size`).
 const EVENT_COLUMNS: &str = r#"
-  id, name, event_type, event_date, start_time, end_time,
+  id, name, event_type, event_date, start_time, end_time, room,
   attendees_count, public_type, school_name, class_name, students_count,
   partner_name, description, notes, created_at, update_at, announcement_sent_at,
+  status, published_at, cancelled_at, cancellation_reason,
+  (SELECT COUNT(*) FROM event_registrations r WHERE r.event_id = events.id) AS registrations_count,
   attachment_filename,
   attachment_mime_type,
   CASE WHEN attachment_data IS NULL THEN NULL ELSE octet_length(attachment_data)::integer END AS attachment_size
@@ -43,6 +52,27 @@ pub trait EventsRepository: Send + Sync {
     async fn events_delete_attachment(&self, id: i64) -> AppResult<Event>;
     async fn events_get_attachment_blob(&self, id: i64) -> AppResult<Option<(Vec<u8>, String, String)>>;
     async fn events_annual_stats(&self, year: i32) -> AppResult<EventAnnualStats>;
+    /// Events in the same room as `slot` whose time span overlaps it (`exclude_id` = event being edited).
+    async fn events_room_overlaps(&self, slot: &EventSlot, exclude_id: Option<i64>) -> AppResult<Vec<Event>>;
+    /// Closure of the library on that day, if any.
+    async fn events_closure_on(&self, date: NaiveDate) -> AppResult<Option<ScheduleClosure>>;
+    /// Events on closure days and overlapping pairs in the same room, between two days included.
+    async fn events_conflicts(&self, start: NaiveDate, end: NaiveDate) -> AppResult<Vec<EventConflict>>;
+    /// Move an event from one of `from` to `to`, stamping `published_at` / `cancelled_at`.
+    /// `None` when the event is not in one of the `from` states.
+    async fn events_set_status(
+        &self,
+        id: i64,
+        from: &[EventStatus],
+        to: EventStatus,
+        reason: Option<String>,
+    ) -> AppResult<Option<Event>>;
+    async fn events_registrations(&self, event_id: i64) -> AppResult<Vec<EventRegistration>>;
+    /// `false` when the user was already registered
+    async fn events_register(&self, event_id: i64, user_id: i64) -> AppResult<bool>;
+    async fn events_unregister(&self, event_id: i64, user_id: i64) -> AppResult<()>;
+    /// Registered attendees with an email address, for notifications
+    async fn events_registrant_emails(&self, event_id: i64) -> AppResult<Vec<UserEmailTarget>>;
 }
 
 /// Combined repository trait used by [`crate::services::events::EventsService`].
@@ -103,6 +133,36 @@ impl EventsRepository for super::Repository {
     async fn events_annual_stats(&self, year: i32) -> crate::error::AppResult<EventAnnualStats> {
         super::Repository::events_annual_stats(self, year).await
     }
+    async fn events_room_overlaps(&self, slot: &EventSlot, exclude_id: Option<i64>) -> crate::error::AppResult<Vec<Event>> {
+        super::Repository::events_room_overlaps(self, slot, exclude_id).await
+    }
+    async fn events_closure_on(&self, date: NaiveDate) -> crate::error::AppResult<Option<ScheduleClosure>> {
+        super::Repository::events_closure_on(self, date).await
+    }
+    async fn events_conflicts(&self, start: NaiveDate, end: NaiveDate) -> crate::error::AppResult<Vec<EventConflict>> {
+        super::Repository::events_conflicts(self, start, end).await
+    }
+    async fn events_set_status(
+        &self,
+        id: i64,
+        from: &[EventStatus],
+        to: EventStatus,
+        reason: Option<String>,
+    ) -> crate::error::AppResult<Option<Event>> {
+        super::Repository::events_set_status(self, id, from, to, reason).await
+    }
+    async fn events_registrations(&self, event_id: i64) -> crate::error::AppResult<Vec<EventRegistration>> {
+        super::Repository::events_registrations(self, event_id).await
+    }
+    async fn events_register(&self, event_id: i64, user_id: i64) -> crate::error::AppResult<bool> {
+        super::Repository::events_register(self, event_id, user_id).await
+    }
+    async fn events_unregister(&self, event_id: i64, user_id: i64) -> crate::error::AppResult<()> {
+        super::Repository::events_unregister(self, event_id, user_id).await
+    }
+    async fn events_registrant_emails(&self, event_id: i64) -> crate::error::AppResult<Vec<UserEmailTarget>> {
+        super::Repository::events_registrant_emails(self, event_id).await
+    }
 }
 
 
@@ -127,6 +187,10 @@ impl Repository {
         }
         if query.event_type.is_some() {
             conditions.push(format!("event_type = ${}", idx));
+            idx += 1;
+        }
+        if query.status.is_some() {
+            conditions.push(format!("status = ${}", idx));
         }
 
         let where_clause = if conditions.is_empty() {
@@ -147,6 +211,7 @@ impl Repository {
         if let Some(sd) = start { count_builder = count_builder.bind(sd); }
         if let Some(ed) = end { count_builder = count_builder.bind(ed); }
         if let Some(et) = query.event_type { count_builder = count_builder.bind(et); }
+        if let Some(st) = query.status { count_builder = count_builder.bind(st); }
         let total = count_builder.fetch_one(&self.pool).await?;
 
         // Fetch rows
@@ -161,6 +226,7 @@ impl Repository {
         if let Some(sd) = start { builder = builder.bind(sd); }
         if let Some(ed) = end { builder = builder.bind(ed); }
         if let Some(et) = query.event_type { builder = builder.bind(et); }
+        if let Some(st) = query.status { builder = builder.bind(st); }
 
         let rows = builder.fetch_all(&self.pool).await?;
         Ok((rows, total))
@@ -203,8 +269,8 @@ impl Repository {
                 attendees_count, public_type,
                 school_name, class_name, students_count,
                 partner_name, description, notes,
-                attachment_data, attachment_filename, attachment_mime_type
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+                attachment_data, attachment_filename, attachment_mime_type, room
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
             RETURNING {}
             "#,
             EVENT_COLUMNS
@@ -226,6 +292,7 @@ impl Repository {
         .bind(att_data.as_deref())
         .bind(att_name.as_ref())
         .bind(att_mime.as_ref())
+        .bind(normalize_room(data.room.as_ref()))
         .fetch_one(&self.pool)
         .await?;
         Ok(row)
@@ -249,6 +316,7 @@ impl Repository {
         add_f!(data.event_date, "event_date");
         add_f!(data.start_time, "start_time");
         add_f!(data.end_time, "end_time");
+        add_f!(data.room, "room");
         add_f!(data.attendees_count, "attendees_count");
         add_f!(data.public_type, "public_type");
         add_f!(data.school_name, "school_name");
@@ -286,6 +354,7 @@ impl Repository {
         if data.event_date.is_some() { builder = builder.bind(event_date); }
         if data.start_time.is_some() { builder = builder.bind(start_time); }
         if data.end_time.is_some() { builder = builder.bind(end_time); }
+        if data.room.is_some() { builder = builder.bind(normalize_room(data.room.as_ref())); }
         bind_f!(data.attendees_count);
         if data.public_type.is_some() {
             builder = builder.bind(data.public_type.as_ref().map(|s| s.trim()));
@@ -303,6 +372,175 @@ impl Repository {
             .ok_or_else(|| AppError::NotFound(format!("Event {} not found", id)))
     }
 
+    /// Events overlapping a slot in the same room (room names compared trimmed, case-insensitively)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn events_room_overlaps(&self, slot: &EventSlot, exclude_id: Option<i64>) -> AppResult<Vec<Event>> {
+        let Some(room) = &slot.room else {
+            return Ok(Vec::new());
+        };
+        let q = format!(
+            r#"
+            SELECT {} FROM events
+            WHERE lower(room) = lower($1) AND event_date = $2 AND status <> 'cancelled'
+              AND ($3::bigint IS NULL OR id <> $3)
+              AND COALESCE(start_time, '00:00') < COALESCE($5::time, '24:00')
+              AND COALESCE($4::time, '00:00') < COALESCE(end_time, '24:00')
+            ORDER BY start_time NULLS FIRST, id
+            "#,
+            EVENT_COLUMNS
+        );
+        let rows = sqlx::query_as::<_, Event>(&q)
+            .bind(room)
+            .bind(slot.date)
+            .bind(exclude_id)
+            .bind(slot.start_time)
+            .bind(slot.end_time)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn events_closure_on(&self, date: NaiveDate) -> AppResult<Option<ScheduleClosure>> {
+        let row = sqlx::query_as::<_, ScheduleClosure>(
+            "SELECT * FROM schedule_closures WHERE closure_date = $1 ORDER BY id LIMIT 1",
+        )
+        .bind(date)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Planning conflicts between two days: events on a closure day, then each overlapping pair
+    /// of events in the same room (reported once, on the event with the lower id).
+    #[tracing::instrument(skip(self), err)]
+    pub async fn events_conflicts(&self, start: NaiveDate, end: NaiveDate) -> AppResult<Vec<EventConflict>> {
+        let rows = sqlx::query_as::<_, EventConflict>(
+            r#"
+            SELECT * FROM (
+                SELECT 'closure' AS kind, e.id AS event_id, e.name AS event_name, e.event_date,
+                       e.start_time, e.end_time, e.room,
+                       NULL::bigint AS other_event_id, NULL::text AS other_event_name,
+                       c.reason AS closure_reason
+                FROM events e
+                JOIN schedule_closures c ON c.closure_date = e.event_date
+                WHERE e.event_date BETWEEN $1 AND $2 AND e.status <> 'cancelled'
+                UNION ALL
+                SELECT 'room', a.id, a.name, a.event_date, a.start_time, a.end_time, a.room,
+                       b.id, b.name, NULL
+                FROM events a
+                JOIN events b
+                  ON b.event_date = a.event_date AND b.id > a.id AND lower(b.room) = lower(a.room)
+                 AND COALESCE(a.start_time, '00:00') < COALESCE(b.end_time, '24:00')
+                 AND COALESCE(b.start_time, '00:00') < COALESCE(a.end_time, '24:00')
+                WHERE a.room IS NOT NULL AND a.event_date BETWEEN $1 AND $2
+                  AND a.status <> 'cancelled' AND b.status <> 'cancelled'
+            ) conflicts
+            ORDER BY event_date, start_time NULLS FIRST, event_id, kind
+            "#,
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn events_set_status(
+        &self,
+        id: i64,
+        from: &[EventStatus],
+        to: EventStatus,
+        reason: Option<String>,
+    ) -> AppResult<Option<Event>> {
+        let from: Vec<&str> = from.iter().map(EventStatus::as_str).collect();
+        let q = format!(
+            r#"
+            UPDATE events SET
+                status = $3,
+                published_at = CASE WHEN $3 = 'published' THEN NOW() ELSE published_at END,
+                cancelled_at = CASE WHEN $3 = 'cancelled' THEN NOW() ELSE cancelled_at END,
+                cancellation_reason = CASE WHEN $3 = 'cancelled' THEN $4 ELSE cancellation_reason END,
+                update_at = NOW()
+            WHERE id = $1 AND status = ANY($2)
+            RETURNING {}
+            "#,
+            EVENT_COLUMNS
+        );
+        let row = sqlx::query_as::<_, Event>(&q)
+            .bind(id)
+            .bind(&from)
+            .bind(to)
+            .bind(reason)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn events_registrations(&self, event_id: i64) -> AppResult<Vec<EventRegistration>> {
+        let rows = sqlx::query_as::<_, EventRegistration>(
+            r#"
+            SELECT r.user_id, u.firstname, u.lastname, u.email, r.created_at
+            FROM event_registrations r
+            JOIN users u ON u.id = r.user_id
+            WHERE r.event_id = $1
+            ORDER BY r.created_at, r.user_id
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn events_register(&self, event_id: i64, user_id: i64) -> AppResult<bool> {
+        let result = sqlx::query(
+            "INSERT INTO event_registrations (event_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn events_unregister(&self, event_id: i64, user_id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM event_registrations WHERE event_id = $1 AND user_id = $2")
+            .bind(event_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "User {} is not registered to event {}",
+                user_id, event_id
+            )));
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn events_registrant_emails(&self, event_id: i64) -> AppResult<Vec<UserEmailTarget>> {
+        let rows = sqlx::query_as::<_, UserEmailTarget>(
+            r#"
+            SELECT u.id, u.email, u.firstname, u.lastname, u.language
+            FROM event_registrations r
+            JOIN users u ON u.id = r.user_id
+            WHERE r.event_id = $1 AND u.email IS NOT NULL AND u.email <> ''
+              AND (u.status IS NULL OR u.status <> 'deleted')
+            ORDER BY r.created_at, u.id
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
     /// Set the announcement_sent_at timestamp on an event
     #[tracing::instrument(skip(self), err)]
     pub async fn events_set_announcement_sent_at(&self, id: i64) -> AppResult<()> {
@@ -408,7 +646,7 @@ impl Repository {
         }
     }
 
-    /// Get event stats for a year (for annual report)
+    /// Get event stats for a year (for annual report; cancelled events are left out)
     #[tracing::instrument(skip(self), err)]
     pub async fn events_annual_stats(&self, year: i32) -> AppResult<EventAnnualStats> {
         let start = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
@@ -421,7 +659,7 @@ impl Repository {
                 COUNT(*) as total_events,
                 COALESCE(SUM(attendees_count), 0)::bigint as total_attendees
             FROM events
-            WHERE event_date >= $1 AND event_date <= $2
+            WHERE event_date >= $1 AND event_date <= $2 AND status <> 'cancelled'
             "#
         )
         .bind(start)
@@ -440,7 +678,7 @@ impl Repository {
                 COUNT(DISTINCT class_name) as distinct_classes,
                 COALESCE(SUM(students_count), 0)::bigint as total_students
             FROM events
-            WHERE event_date >= $1 AND event_date <= $2 AND event_type = 1
+            WHERE event_date >= $1 AND event_date <= $2 AND status <> 'cancelled' AND event_type = 1
             "#
         )
         .bind(start)
@@ -457,7 +695,7 @@ impl Repository {
             r#"
             SELECT event_type, COUNT(*) as count, COALESCE(SUM(attendees_count), 0)::bigint as attendees
             FROM events
-            WHERE event_date >= $1 AND event_date <= $2
+            WHERE event_date >= $1 AND event_date <= $2 AND status <> 'cancelled'
             GROUP BY event_type ORDER BY count DESC
             "#
         )
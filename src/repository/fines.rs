@@ -1,13 +1,14 @@
 //! Fine domain methods on Repository
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use snowflaked::Generator;
 
 use super::Repository;
 use crate::{
     error::{AppError, AppResult},
-    models::fine::{Fine, FineRule, FineStatus},
+    models::fine::{Fine, FineRule, FineStatus, FineWaiverReason, FineWaiverTotal},
 };
 
 #[async_trait]
@@ -21,13 +22,26 @@ pub trait FinesRepository: Send + Sync {
         amount: Decimal,
         notes: Option<&str>,
     ) -> AppResult<Fine>;
-    async fn fines_pay(
+    async fn fines_waive(
         &self,
         id: i64,
-        payment: Decimal,
         notes: Option<&str>,
+        reason: FineWaiverReason,
+        amnesty: Option<&str>,
+        waived_by: Option<i64>,
     ) -> AppResult<Fine>;
-    async fn fines_waive(&self, id: i64, notes: Option<&str>) -> AppResult<Fine>;
+    async fn fines_amnesty(
+        &self,
+        name: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        waived_by: i64,
+    ) -> AppResult<(i64, Decimal)>;
+    async fn fines_waivers_summary(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> AppResult<Vec<FineWaiverTotal>>;
     async fn fines_list_rules(&self) -> AppResult<Vec<FineRule>>;
     async fn fines_upsert_rule(
         &self,
@@ -52,13 +66,31 @@ impl FinesRepository for Repository {
     ) -> AppResult<Fine> {
         Repository::fines_create(self, loan_id, user_id, amount, notes).await
     }
-    async fn fines_pay(
-        &self, id: i64, payment: Decimal, notes: Option<&str>,
+    async fn fines_waive(
+        &self,
+        id: i64,
+        notes: Option<&str>,
+        reason: FineWaiverReason,
+        amnesty: Option<&str>,
+        waived_by: Option<i64>,
     ) -> AppResult<Fine> {
-        Repository::fines_pay(self, id, payment, notes).await
+        Repository::fines_waive(self, id, notes, reason, amnesty, waived_by).await
     }
-    async fn fines_waive(&self, id: i64, notes: Option<&str>) -> AppResult<Fine> {
-        Repository::fines_waive(self, id, notes).await
+    async fn fines_amnesty(
+        &self,
+        name: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        waived_by: i64,
+    ) -> AppResult<(i64, Decimal)> {
+        Repository::fines_amnesty(self, name, from, to, waived_by).await
+    }
+    async fn fines_waivers_summary(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> AppResult<Vec<FineWaiverTotal>> {
+        Repository::fines_waivers_summary(self, from, to).await
     }
     async fn fines_list_rules(&self) -> AppResult<Vec<FineRule>> {
         Repository::fines_list_rules(self).await
@@ -138,48 +170,116 @@ impl Repository {
         Ok(row)
     }
 
-    /// Apply a payment to a fine
+    /// Waive a fine (write off its outstanding balance), recording the waiver for reporting
     #[tracing::instrument(skip(self), err)]
-    pub async fn fines_pay(
+    pub async fn fines_waive(
         &self,
         id: i64,
-        payment: Decimal,
         notes: Option<&str>,
+        reason: FineWaiverReason,
+        amnesty: Option<&str>,
+        waived_by: Option<i64>,
     ) -> AppResult<Fine> {
-        sqlx::query_as::<_, Fine>(
-            r#"
-            UPDATE fines SET
-                paid_amount = paid_amount + $2,
-                notes       = COALESCE($3, notes),
-                paid_at     = CASE WHEN paid_amount + $2 >= amount THEN NOW() ELSE NULL END,
-                status      = CASE
-                    WHEN paid_amount + $2 >= amount THEN 'paid'
-                    ELSE 'partial'
-                END
-            WHERE id = $1
-            RETURNING *
-            "#,
+        let mut tx = self.pool.begin().await?;
+        let current: Option<(i64, Decimal, bool)> = sqlx::query_as(
+            "SELECT user_id, amount - paid_amount, status IN ('paid', 'waived') FROM fines WHERE id = $1 FOR UPDATE",
         )
         .bind(id)
-        .bind(payment)
-        .bind(notes)
-        .fetch_optional(&self.pool)
-        .await?
-        .ok_or_else(|| AppError::NotFound(format!("Fine {id} not found")))
-    }
+        .fetch_optional(&mut *tx)
+        .await?;
+        let (user_id, outstanding, settled) =
+            current.ok_or_else(|| AppError::NotFound(format!("Fine {id} not found")))?;
+        if settled {
+            return Err(AppError::BusinessRule(format!("Fine {id} is already settled")));
+        }
 
-    /// Waive a fine (write off)
-    #[tracing::instrument(skip(self), err)]
-    pub async fn fines_waive(&self, id: i64, notes: Option<&str>) -> AppResult<Fine> {
-        sqlx::query_as::<_, Fine>(
+        let fine = sqlx::query_as::<_, Fine>(
             "UPDATE fines SET status = 'waived', paid_at = NOW(), notes = COALESCE($2, notes)
              WHERE id = $1 RETURNING *",
         )
         .bind(id)
         .bind(notes)
-        .fetch_optional(&self.pool)
-        .await?
-        .ok_or_else(|| AppError::NotFound(format!("Fine {id} not found")))
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query(
+            "INSERT INTO fine_waivers (fine_id, user_id, amount, reason, amnesty, waived_by)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(outstanding)
+        .bind(reason)
+        .bind(amnesty)
+        .bind(waived_by)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(fine)
+    }
+
+    /// Waive every unpaid fine issued in `[from, to)`; returns the number of fines and the amount written off
+    #[tracing::instrument(skip(self), err)]
+    pub async fn fines_amnesty(
+        &self,
+        name: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        waived_by: i64,
+    ) -> AppResult<(i64, Decimal)> {
+        // `fines` is not created by the bundled migrations on every deployment
+        let has_fines: bool = sqlx::query_scalar("SELECT to_regclass('public.fines') IS NOT NULL")
+            .fetch_one(&self.pool)
+            .await?;
+        if !has_fines {
+            return Ok((0, Decimal::ZERO));
+        }
+
+        let (count, total): (i64, Option<Decimal>) = sqlx::query_as(
+            r#"
+            WITH waived AS (
+                UPDATE fines SET status = 'waived', paid_at = NOW()
+                WHERE status IN ('pending', 'partial') AND created_at >= $1 AND created_at < $2
+                RETURNING id, user_id, amount - paid_amount AS outstanding
+            ), recorded AS (
+                INSERT INTO fine_waivers (fine_id, user_id, amount, reason, amnesty, waived_by)
+                SELECT id, user_id, outstanding, $3, $4, $5 FROM waived
+                RETURNING amount
+            )
+            SELECT COUNT(*), SUM(amount) FROM recorded
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(FineWaiverReason::Amnesty)
+        .bind(name)
+        .bind(waived_by)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok((count, total.unwrap_or(Decimal::ZERO)))
+    }
+
+    /// Waived totals by reason and amnesty over `[from, to)` (waiver time)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn fines_waivers_summary(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> AppResult<Vec<FineWaiverTotal>> {
+        let rows = sqlx::query_as::<_, FineWaiverTotal>(
+            r#"
+            SELECT reason, amnesty, COUNT(*) AS fine_count, SUM(amount) AS waived_total
+            FROM fine_waivers
+            WHERE ($1::timestamptz IS NULL OR waived_at >= $1)
+              AND ($2::timestamptz IS NULL OR waived_at < $2)
+            GROUP BY reason, amnesty
+            ORDER BY reason, amnesty NULLS FIRST
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
     }
 
     /// Get fine rules (per media type + default)
@@ -0,0 +1,363 @@
+//! Union catalog harvest (`harvest_sources`, `harvest_runs`, `harvest_records`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::harvest::{
+        CreateHarvestSource, HarvestMappingProfile, HarvestRun, HarvestSource, HarvestTrigger,
+        UpdateHarvestSource,
+    },
+};
+
+const SOURCE_COLUMNS: &str = "id, name, url, username, password, (password IS NOT NULL) AS has_password, \
+     host_key_fingerprint, file_pattern, marc_format, mapping_profile, catalog_source_id, schedule_time, \
+     is_active, alert_emails, last_cursor, last_run_at, created_at, update_at";
+
+const RUN_COLUMNS: &str = "id, source_id, trigger, status, started_by, started_at, finished_at, files, \
+     created_count, updated_count, deleted_count, skipped_count, failed_count, record_errors, error";
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait HarvestRepository: Send + Sync {
+    async fn harvest_sources_list(&self) -> AppResult<Vec<HarvestSource>>;
+    async fn harvest_sources_get(&self, id: i64) -> AppResult<HarvestSource>;
+    async fn harvest_sources_create(&self, data: &CreateHarvestSource) -> AppResult<HarvestSource>;
+    async fn harvest_sources_update(&self, id: i64, data: &UpdateHarvestSource) -> AppResult<HarvestSource>;
+    async fn harvest_sources_delete(&self, id: i64) -> AppResult<()>;
+    async fn harvest_sources_set_cursor(&self, id: i64, cursor: &str) -> AppResult<()>;
+    /// Open a run unless another instance claimed the source since `last_run_at` was read, or a
+    /// run is still in progress.
+    async fn harvest_runs_start(
+        &self,
+        source_id: i64,
+        last_run_at: Option<DateTime<Utc>>,
+        trigger: HarvestTrigger,
+        started_by: Option<i64>,
+    ) -> AppResult<Option<HarvestRun>>;
+    async fn harvest_runs_finish(&self, run: &HarvestRun) -> AppResult<HarvestRun>;
+    async fn harvest_runs_list(&self, source_id: i64, limit: i64) -> AppResult<Vec<HarvestRun>>;
+    async fn harvest_runs_get(&self, id: i64) -> AppResult<HarvestRun>;
+    /// Local biblio linked to a union catalog record
+    async fn harvest_records_get(&self, source_id: i64, remote_id: &str) -> AppResult<Option<i64>>;
+    async fn harvest_records_link(&self, source_id: i64, remote_id: &str, biblio_id: i64) -> AppResult<()>;
+    async fn harvest_records_unlink(&self, source_id: i64, remote_id: &str) -> AppResult<()>;
+}
+
+#[async_trait]
+impl HarvestRepository for Repository {
+    async fn harvest_sources_list(&self) -> AppResult<Vec<HarvestSource>> {
+        Repository::harvest_sources_list(self).await
+    }
+    async fn harvest_sources_get(&self, id: i64) -> AppResult<HarvestSource> {
+        Repository::harvest_sources_get(self, id).await
+    }
+    async fn harvest_sources_create(&self, data: &CreateHarvestSource) -> AppResult<HarvestSource> {
+        Repository::harvest_sources_create(self, data).await
+    }
+    async fn harvest_sources_update(&self, id: i64, data: &UpdateHarvestSource) -> AppResult<HarvestSource> {
+        Repository::harvest_sources_update(self, id, data).await
+    }
+    async fn harvest_sources_delete(&self, id: i64) -> AppResult<()> {
+        Repository::harvest_sources_delete(self, id).await
+    }
+    async fn harvest_sources_set_cursor(&self, id: i64, cursor: &str) -> AppResult<()> {
+        Repository::harvest_sources_set_cursor(self, id, cursor).await
+    }
+    async fn harvest_runs_start(
+        &self,
+        source_id: i64,
+        last_run_at: Option<DateTime<Utc>>,
+        trigger: HarvestTrigger,
+        started_by: Option<i64>,
+    ) -> AppResult<Option<HarvestRun>> {
+        Repository::harvest_runs_start(self, source_id, last_run_at, trigger, started_by).await
+    }
+    async fn harvest_runs_finish(&self, run: &HarvestRun) -> AppResult<HarvestRun> {
+        Repository::harvest_runs_finish(self, run).await
+    }
+    async fn harvest_runs_list(&self, source_id: i64, limit: i64) -> AppResult<Vec<HarvestRun>> {
+        Repository::harvest_runs_list(self, source_id, limit).await
+    }
+    async fn harvest_runs_get(&self, id: i64) -> AppResult<HarvestRun> {
+        Repository::harvest_runs_get(self, id).await
+    }
+    async fn harvest_records_get(&self, source_id: i64, remote_id: &str) -> AppResult<Option<i64>> {
+        Repository::harvest_records_get(self, source_id, remote_id).await
+    }
+    async fn harvest_records_link(&self, source_id: i64, remote_id: &str, biblio_id: i64) -> AppResult<()> {
+        Repository::harvest_records_link(self, source_id, remote_id, biblio_id).await
+    }
+    async fn harvest_records_unlink(&self, source_id: i64, remote_id: &str) -> AppResult<()> {
+        Repository::harvest_records_unlink(self, source_id, remote_id).await
+    }
+}
+
+impl Repository {
+    /// List harvest sources by name
+    #[tracing::instrument(skip(self), err)]
+    pub async fn harvest_sources_list(&self) -> AppResult<Vec<HarvestSource>> {
+        let rows = sqlx::query_as::<_, HarvestSource>(&format!(
+            "SELECT {} FROM harvest_sources ORDER BY name, id",
+            SOURCE_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get a harvest source by ID
+    #[tracing::instrument(skip(self), err)]
+    pub async fn harvest_sources_get(&self, id: i64) -> AppResult<HarvestSource> {
+        sqlx::query_as::<_, HarvestSource>(&format!(
+            "SELECT {} FROM harvest_sources WHERE id = $1",
+            SOURCE_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Harvest source {} not found", id)))
+    }
+
+    /// Create a harvest source
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn harvest_sources_create(&self, data: &CreateHarvestSource) -> AppResult<HarvestSource> {
+        let row = sqlx::query_as::<_, HarvestSource>(&format!(
+            r#"
+            INSERT INTO harvest_sources (name, url, username, password, host_key_fingerprint,
+                file_pattern, marc_format, mapping_profile, catalog_source_id, schedule_time,
+                is_active, alert_emails)
+            VALUES ($1, $2, NULLIF($3, ''), NULLIF($4, ''), NULLIF($5, ''), COALESCE($6, '*'),
+                COALESCE($7, 'iso2709'), $8, $9, COALESCE($10, '02:30'), COALESCE($11, TRUE),
+                COALESCE($12, '{{}}'))
+            RETURNING {}
+            "#,
+            SOURCE_COLUMNS
+        ))
+        .bind(data.name.trim())
+        .bind(data.url.trim())
+        .bind(data.username.as_deref().map(str::trim))
+        .bind(data.password.as_deref())
+        .bind(data.host_key_fingerprint.as_deref().map(str::trim))
+        .bind(data.file_pattern.as_deref().map(str::trim))
+        .bind(data.marc_format)
+        .bind(sqlx::types::Json(data.mapping_profile.clone().unwrap_or_default()))
+        .bind(data.catalog_source_id)
+        .bind(data.schedule_time.as_deref())
+        .bind(data.is_active)
+        .bind(&data.alert_emails)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Update a harvest source (absent fields are kept; an empty `password` clears it)
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn harvest_sources_update(&self, id: i64, data: &UpdateHarvestSource) -> AppResult<HarvestSource> {
+        sqlx::query_as::<_, HarvestSource>(&format!(
+            r#"
+            UPDATE harvest_sources SET
+                name = COALESCE($1, name),
+                url = COALESCE($2, url),
+                username = CASE WHEN $3::text IS NULL THEN username ELSE NULLIF($3, '') END,
+                password = CASE WHEN $4::text IS NULL THEN password ELSE NULLIF($4, '') END,
+                host_key_fingerprint = CASE WHEN $5::text IS NULL THEN host_key_fingerprint
+                                            ELSE NULLIF($5, '') END,
+                file_pattern = COALESCE($6, file_pattern),
+                marc_format = COALESCE($7, marc_format),
+                mapping_profile = COALESCE($8, mapping_profile),
+                catalog_source_id = COALESCE($9, catalog_source_id),
+                schedule_time = COALESCE($10, schedule_time),
+                is_active = COALESCE($11, is_active),
+                alert_emails = COALESCE($12, alert_emails),
+                last_cursor = CASE WHEN $13 THEN NULL ELSE last_cursor END,
+                update_at = $14
+            WHERE id = $15
+            RETURNING {}
+            "#,
+            SOURCE_COLUMNS
+        ))
+        .bind(data.name.as_deref().map(str::trim))
+        .bind(data.url.as_deref().map(str::trim))
+        .bind(data.username.as_deref().map(str::trim))
+        .bind(data.password.as_deref())
+        .bind(data.host_key_fingerprint.as_deref().map(str::trim))
+        .bind(data.file_pattern.as_deref().map(str::trim))
+        .bind(data.marc_format)
+        .bind(data.mapping_profile.clone().map(sqlx::types::Json::<HarvestMappingProfile>))
+        .bind(data.catalog_source_id)
+        .bind(data.schedule_time.as_deref())
+        .bind(data.is_active)
+        .bind(&data.alert_emails)
+        .bind(data.reset_cursor)
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Harvest source {} not found", id)))
+    }
+
+    /// Delete a harvest source with its runs and record links (harvested biblios are kept)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn harvest_sources_delete(&self, id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM harvest_sources WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Harvest source {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Record the last applied file (or HTTP validator)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn harvest_sources_set_cursor(&self, id: i64, cursor: &str) -> AppResult<()> {
+        sqlx::query("UPDATE harvest_sources SET last_cursor = $1 WHERE id = $2")
+            .bind(cursor)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Claim the source and open a run.
+    ///
+    /// The claim only succeeds while `last_run_at` still holds the value the caller read: when
+    /// several instances run the scheduler, the first one wins (the row lock makes the others
+    /// re-check the updated value). Runs left `running` for more than 12 hours (crashed
+    /// instance) no longer block the source.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn harvest_runs_start(
+        &self,
+        source_id: i64,
+        last_run_at: Option<DateTime<Utc>>,
+        trigger: HarvestTrigger,
+        started_by: Option<i64>,
+    ) -> AppResult<Option<HarvestRun>> {
+        let row = sqlx::query_as::<_, HarvestRun>(&format!(
+            r#"
+            WITH claimed AS (
+                UPDATE harvest_sources SET last_run_at = NOW()
+                WHERE id = $1
+                  AND last_run_at IS NOT DISTINCT FROM $2
+                  AND NOT EXISTS (
+                      SELECT 1 FROM harvest_runs
+                      WHERE source_id = $1 AND status = 'running'
+                        AND started_at > NOW() - INTERVAL '12 hours'
+                  )
+                RETURNING id
+            )
+            INSERT INTO harvest_runs (source_id, trigger, started_by)
+            SELECT id, $3, $4 FROM claimed
+            RETURNING {}
+            "#,
+            RUN_COLUMNS
+        ))
+        .bind(source_id)
+        .bind(last_run_at)
+        .bind(trigger)
+        .bind(started_by)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Store the outcome of a run
+    #[tracing::instrument(skip(self, run), fields(run_id = run.id), err)]
+    pub async fn harvest_runs_finish(&self, run: &HarvestRun) -> AppResult<HarvestRun> {
+        sqlx::query_as::<_, HarvestRun>(&format!(
+            r#"
+            UPDATE harvest_runs SET
+                status = $1, finished_at = NOW(), files = $2, created_count = $3,
+                updated_count = $4, deleted_count = $5, skipped_count = $6, failed_count = $7,
+                record_errors = $8, error = $9
+            WHERE id = $10
+            RETURNING {}
+            "#,
+            RUN_COLUMNS
+        ))
+        .bind(run.status)
+        .bind(&run.files)
+        .bind(run.created_count)
+        .bind(run.updated_count)
+        .bind(run.deleted_count)
+        .bind(run.skipped_count)
+        .bind(run.failed_count)
+        .bind(sqlx::types::Json(&run.record_errors))
+        .bind(run.error.as_deref())
+        .bind(run.id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Harvest run {} not found", run.id)))
+    }
+
+    /// Most recent runs of a source
+    #[tracing::instrument(skip(self), err)]
+    pub async fn harvest_runs_list(&self, source_id: i64, limit: i64) -> AppResult<Vec<HarvestRun>> {
+        let rows = sqlx::query_as::<_, HarvestRun>(&format!(
+            "SELECT {} FROM harvest_runs WHERE source_id = $1 ORDER BY started_at DESC, id DESC LIMIT $2",
+            RUN_COLUMNS
+        ))
+        .bind(source_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get a run by ID
+    #[tracing::instrument(skip(self), err)]
+    pub async fn harvest_runs_get(&self, id: i64) -> AppResult<HarvestRun> {
+        sqlx::query_as::<_, HarvestRun>(&format!("SELECT {} FROM harvest_runs WHERE id = $1", RUN_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Harvest run {} not found", id)))
+    }
+
+    /// Local biblio linked to a union catalog record
+    #[tracing::instrument(skip(self), err)]
+    pub async fn harvest_records_get(&self, source_id: i64, remote_id: &str) -> AppResult<Option<i64>> {
+        let biblio_id = sqlx::query_scalar::<_, i64>(
+            "SELECT biblio_id FROM harvest_records WHERE source_id = $1 AND remote_id = $2",
+        )
+        .bind(source_id)
+        .bind(remote_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(biblio_id)
+    }
+
+    /// Link (or re-link) a union catalog record to a local biblio
+    #[tracing::instrument(skip(self), err)]
+    pub async fn harvest_records_link(&self, source_id: i64, remote_id: &str, biblio_id: i64) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO harvest_records (source_id, remote_id, biblio_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (source_id, remote_id)
+            DO UPDATE SET biblio_id = EXCLUDED.biblio_id, harvested_at = NOW()
+            "#,
+        )
+        .bind(source_id)
+        .bind(remote_id)
+        .bind(biblio_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Forget the link of a union catalog record
+    #[tracing::instrument(skip(self), err)]
+    pub async fn harvest_records_unlink(&self, source_id: i64, remote_id: &str) -> AppResult<()> {
+        sqlx::query("DELETE FROM harvest_records WHERE source_id = $1 AND remote_id = $2")
+            .bind(source_id)
+            .bind(remote_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
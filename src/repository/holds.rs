@@ -12,7 +12,7 @@ use crate::{
     error::{AppError, AppResult},
     models::{
         biblio::BiblioShort,
-        hold::{CreateHold, Hold, HoldDetails},
+        hold::{CreateHold, Hold, HoldDetails, HoldPriority, HoldShelfEntry, HoldStatus, LockerReservation},
         item::ItemShort,
         user::{UserShort, UserShortRow},
     },
@@ -21,7 +21,7 @@ use crate::{
 #[async_trait]
 pub trait HoldsRepository: Send + Sync {
     /// All holds, newest first, with total count (for pagination).
-    /// When `active_only`, only `pending` and `ready` rows.
+    /// When `active_only`, only `pending`, `ready` and `in_locker` rows.
     async fn holds_list_all(&self, page: i64, per_page: i64, active_only: bool) -> AppResult<(Vec<HoldDetails>, i64)>;
     /// Holds for one user (paginated), same ordering/filters as [`HoldsRepository::holds_list_all`].
     async fn holds_list_for_user_paginated(
@@ -45,6 +45,23 @@ pub trait HoldsRepository: Send + Sync {
     async fn holds_fulfill(&self, id: i64) -> AppResult<Hold>;
     /// First `pending` hold for the item becomes `ready` with `expires_at` set.
     async fn holds_notify_next(&self, item_id: i64, expiry_days: i32) -> AppResult<Option<Hold>>;
+    /// `ready` → `in_locker` with the vendor's compartment; `None` when the hold is no longer ready.
+    async fn holds_assign_locker(
+        &self,
+        id: i64,
+        reservation: &LockerReservation,
+        expires_at: chrono::DateTime<Utc>,
+    ) -> AppResult<Option<Hold>>;
+    /// `in_locker` → `picked_up`; `None` when the hold is not in a locker.
+    async fn holds_mark_picked_up(&self, id: i64) -> AppResult<Option<Hold>>;
+    /// `in_locker` → `expired`; `None` when the hold is not in a locker.
+    async fn holds_expire_from_locker(&self, id: i64) -> AppResult<Option<Hold>>;
+    /// Expire `in_locker` holds past `expires_at` (their compartments must be released).
+    async fn holds_expire_lockers(&self) -> AppResult<Vec<Hold>>;
+    /// Copies on the shelf to pull for the first hold of their queue, by location and call number.
+    async fn holds_pull_list(&self) -> AppResult<Vec<HoldShelfEntry>>;
+    /// Copies waiting on the hold shelf whose hold expired since `since`, to reshelve.
+    async fn holds_expired_to_reshelve(&self, since: chrono::DateTime<Utc>) -> AppResult<Vec<HoldShelfEntry>>;
 }
 
 #[async_trait::async_trait]
@@ -100,8 +117,45 @@ impl HoldsRepository for Repository {
     async fn holds_notify_next(&self, item_id: i64, expiry_days: i32) -> AppResult<Option<Hold>> {
         Repository::holds_notify_next(self, item_id, expiry_days).await
     }
+    async fn holds_assign_locker(
+        &self,
+        id: i64,
+        reservation: &LockerReservation,
+        expires_at: chrono::DateTime<Utc>,
+    ) -> AppResult<Option<Hold>> {
+        Repository::holds_assign_locker(self, id, reservation, expires_at).await
+    }
+    async fn holds_mark_picked_up(&self, id: i64) -> AppResult<Option<Hold>> {
+        Repository::holds_mark_picked_up(self, id).await
+    }
+    async fn holds_expire_from_locker(&self, id: i64) -> AppResult<Option<Hold>> {
+        Repository::holds_expire_from_locker(self, id).await
+    }
+    async fn holds_expire_lockers(&self) -> AppResult<Vec<Hold>> {
+        Repository::holds_expire_lockers(self).await
+    }
+    async fn holds_pull_list(&self) -> AppResult<Vec<HoldShelfEntry>> {
+        Repository::holds_pull_list(self).await
+    }
+    async fn holds_expired_to_reshelve(&self, since: chrono::DateTime<Utc>) -> AppResult<Vec<HoldShelfEntry>> {
+        Repository::holds_expired_to_reshelve(self, since).await
+    }
 }
 
+/// Hold shelf list columns (`h` = hold, `it` = copy, `lp` = copy location path, `b` = biblio,
+/// `u` = patron). `location_sort_key` orders the lists along the shelving map.
+const HOLD_SHELF_SELECT_SQL: &str = r#"
+    SELECT h.id AS hold_id, it.id AS item_id, it.biblio_id, it.barcode, it.call_number, it.place,
+           it.location_id, lp.path AS location_path, lp.sort_key AS location_sort_key,
+           b.title, h.user_id, NULLIF(CONCAT_WS(' ', u.lastname, u.firstname), '') AS user_name,
+           h.created_at, h.expires_at, h.pickup_locker
+    FROM holds h
+    JOIN items it ON it.id = h.item_id
+    LEFT JOIN shelving_location_paths lp ON lp.id = it.location_id
+    LEFT JOIN biblios b ON b.id = it.biblio_id
+    LEFT JOIN users u ON u.id = h.user_id
+"#;
+
 static SNOWFLAKE: std::sync::LazyLock<std::sync::Mutex<Generator>> =
     std::sync::LazyLock::new(|| std::sync::Mutex::new(Generator::new(1)));
 
@@ -157,6 +211,7 @@ impl Repository {
                 borrowable: r.borrowable,
                 source_name: r.source_name,
                 borrowed: r.borrowed,
+                hidden_from_opac: false,
             };
             m.insert(id, (r.biblio_id, item));
         }
@@ -190,6 +245,27 @@ impl Repository {
             .collect())
     }
 
+    /// Place of active holds in the queue of their copy (1 = next served).
+    async fn holds_queue_position_map(&self, ids: &[i64]) -> AppResult<HashMap<i64, i32>> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows: Vec<(i64, i32)> = sqlx::query_as(
+            r#"
+            SELECT h.id,
+                   (SELECT COUNT(*) FROM holds o
+                    WHERE o.item_id = h.item_id AND o.status IN ('pending','ready','in_locker')
+                      AND o.position < h.position)::int + 1
+            FROM holds h
+            WHERE h.id = ANY($1) AND h.status IN ('pending','ready','in_locker')
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().collect())
+    }
+
     /// Expand [`Hold`] rows into [`HoldDetails`] with biblio (single copy) and user snapshots.
     pub async fn holds_holds_to_details(&self, holds: Vec<Hold>) -> AppResult<Vec<HoldDetails>> {
         if holds.is_empty() {
@@ -207,6 +283,8 @@ impl Repository {
             .collect();
         let biblio_meta = self.biblios_get_short_metadata_map_by_biblio_ids(&biblio_ids).await?;
         let users_map = self.holds_user_short_map(&user_ids).await?;
+        let hold_ids: Vec<i64> = holds.iter().map(|h| h.id).collect();
+        let queue_positions = self.holds_queue_position_map(&hold_ids).await?;
 
         let mut out = Vec::with_capacity(holds.len());
         for h in holds {
@@ -233,7 +311,14 @@ impl Repository {
                 expires_at: h.expires_at,
                 status: h.status,
                 position: h.position,
+                queue_position: queue_positions.get(&h.id).copied(),
+                priority: h.priority,
                 notes: h.notes,
+                pickup_locker: h.pickup_locker,
+                locker_id: h.locker_id,
+                locker_compartment: h.locker_compartment,
+                locker_pickup_code: h.locker_pickup_code,
+                picked_up_at: h.picked_up_at,
             });
         }
         Ok(out)
@@ -244,13 +329,13 @@ impl Repository {
     pub async fn holds_list_all(&self, page: i64, per_page: i64, active_only: bool) -> AppResult<(Vec<HoldDetails>, i64)> {
         let (total, rows) = if active_only {
             let total: i64 = sqlx::query_scalar(
-                "SELECT COUNT(*)::bigint FROM holds WHERE status IN ('pending','ready')",
+                "SELECT COUNT(*)::bigint FROM holds WHERE status IN ('pending','ready','in_locker')",
             )
             .fetch_one(&self.pool)
             .await?;
             let offset = (page - 1).max(0) * per_page;
             let rows = sqlx::query_as::<_, Hold>(
-                "SELECT * FROM holds WHERE status IN ('pending','ready') ORDER BY created_at ASC LIMIT $1 OFFSET $2",
+                "SELECT * FROM holds WHERE status IN ('pending','ready','in_locker') ORDER BY created_at ASC LIMIT $1 OFFSET $2",
             )
             .bind(per_page)
             .bind(offset)
@@ -286,14 +371,14 @@ impl Repository {
     ) -> AppResult<(Vec<HoldDetails>, i64)> {
         let (total, rows) = if active_only {
             let total: i64 = sqlx::query_scalar(
-                "SELECT COUNT(*)::bigint FROM holds WHERE user_id = $1 AND status IN ('pending','ready')",
+                "SELECT COUNT(*)::bigint FROM holds WHERE user_id = $1 AND status IN ('pending','ready','in_locker')",
             )
             .bind(user_id)
             .fetch_one(&self.pool)
             .await?;
             let offset = (page - 1).max(0) * per_page;
             let rows = sqlx::query_as::<_, Hold>(
-                "SELECT * FROM holds WHERE user_id = $1 AND status IN ('pending','ready') \
+                "SELECT * FROM holds WHERE user_id = $1 AND status IN ('pending','ready','in_locker') \
                  ORDER BY created_at ASC LIMIT $2 OFFSET $3",
             )
             .bind(user_id)
@@ -339,7 +424,7 @@ impl Repository {
     #[tracing::instrument(skip(self), err)]
     pub async fn holds_list_for_item(&self, item_id: i64) -> AppResult<Vec<HoldDetails>> {
         let rows = sqlx::query_as::<_, Hold>(
-            "SELECT * FROM holds WHERE item_id = $1 AND status IN ('pending','ready')
+            "SELECT * FROM holds WHERE item_id = $1 AND status IN ('pending','ready','in_locker')
              ORDER BY position ASC",
         )
         .bind(item_id)
@@ -368,27 +453,58 @@ impl Repository {
             .ok_or_else(|| AppError::NotFound(format!("Hold {id} not found")))
     }
 
+    /// Queue a hold on a copy. It goes behind `ready` / `in_locker` holds and every pending hold of
+    /// the same or a higher priority (`holds.priority_order`); the holds it passes move back one place.
     #[tracing::instrument(skip(self), err)]
     pub async fn holds_create(&self, data: &CreateHold) -> AppResult<Hold> {
-        let id = next_id();
+        let order = self.hold_priority_order();
+        let rank = data.priority.rank(&order);
+        let mut tx = self.pool.begin().await?;
+        // One queue change at a time per copy
+        sqlx::query("SELECT id FROM items WHERE id = $1 FOR UPDATE")
+            .bind(data.item_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let queue: Vec<(i32, HoldStatus, HoldPriority)> = sqlx::query_as(
+            "SELECT position, status, priority FROM holds
+             WHERE item_id = $1 AND status IN ('pending','ready','in_locker')",
+        )
+        .bind(data.item_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        let position = queue
+            .iter()
+            .filter(|(_, status, priority)| *status != HoldStatus::Pending || priority.rank(&order) <= rank)
+            .map(|(position, _, _)| *position)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        sqlx::query(
+            "UPDATE holds SET position = position + 1
+             WHERE item_id = $1 AND status IN ('pending','ready','in_locker') AND position >= $2",
+        )
+        .bind(data.item_id)
+        .bind(position)
+        .execute(&mut *tx)
+        .await?;
+
         let row = sqlx::query_as::<_, Hold>(
             r#"
-            INSERT INTO holds (id, user_id, item_id, position, notes)
-            VALUES (
-                $1, $2, $3,
-                COALESCE((SELECT MAX(position) FROM holds
-                          WHERE item_id = $3 AND status IN ('pending','ready')), 0) + 1,
-                $4
-            )
+            INSERT INTO holds (id, user_id, item_id, position, notes, pickup_locker, priority)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             RETURNING *
             "#,
         )
-        .bind(id)
+        .bind(next_id())
         .bind(data.user_id)
         .bind(data.item_id)
+        .bind(position)
         .bind(&data.notes)
-        .fetch_one(&self.pool)
+        .bind(data.pickup_locker)
+        .bind(data.priority)
+        .fetch_one(&mut *tx)
         .await?;
+        tx.commit().await?;
         Ok(row)
     }
 
@@ -433,7 +549,7 @@ impl Repository {
     #[tracing::instrument(skip(self), err)]
     pub async fn holds_count_for_item(&self, item_id: i64) -> AppResult<i64> {
         let count: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM holds WHERE item_id = $1 AND status IN ('pending','ready')",
+            "SELECT COUNT(*) FROM holds WHERE item_id = $1 AND status IN ('pending','ready','in_locker')",
         )
         .bind(item_id)
         .fetch_one(&self.pool)
@@ -464,11 +580,77 @@ impl Repository {
         .ok_or_else(|| AppError::NotFound(format!("Hold {id} not found")))
     }
 
-    /// Patron allowed to borrow this copy next: `ready` first, else first `pending` by queue position.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn holds_assign_locker(
+        &self,
+        id: i64,
+        reservation: &LockerReservation,
+        expires_at: chrono::DateTime<Utc>,
+    ) -> AppResult<Option<Hold>> {
+        let row = sqlx::query_as::<_, Hold>(
+            r#"
+            UPDATE holds SET
+                status = 'in_locker',
+                locker_id = $2,
+                locker_compartment = $3,
+                locker_pickup_code = $4,
+                locker_assigned_at = NOW(),
+                expires_at = $5
+            WHERE id = $1 AND status = 'ready'
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(&reservation.locker_id)
+        .bind(&reservation.compartment)
+        .bind(&reservation.pickup_code)
+        .bind(expires_at)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn holds_mark_picked_up(&self, id: i64) -> AppResult<Option<Hold>> {
+        let row = sqlx::query_as::<_, Hold>(
+            "UPDATE holds SET status = 'picked_up', picked_up_at = NOW()
+             WHERE id = $1 AND status = 'in_locker' RETURNING *",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn holds_expire_from_locker(&self, id: i64) -> AppResult<Option<Hold>> {
+        let row = sqlx::query_as::<_, Hold>(
+            "UPDATE holds SET status = 'expired' WHERE id = $1 AND status = 'in_locker' RETURNING *",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn holds_expire_lockers(&self) -> AppResult<Vec<Hold>> {
+        let rows = sqlx::query_as::<_, Hold>(
+            "UPDATE holds SET status = 'expired'
+             WHERE status = 'in_locker' AND expires_at < NOW()
+             RETURNING *",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Patron allowed to borrow this copy next: `ready` (or `in_locker`) first, else first `pending`
+    /// by queue position.
     #[tracing::instrument(skip(self), err)]
     pub async fn holds_eligible_borrower_for_item(&self, item_id: i64) -> AppResult<Option<i64>> {
         let ready: Option<i64> = sqlx::query_scalar(
-            "SELECT user_id FROM holds WHERE item_id = $1 AND status = 'ready' ORDER BY position ASC LIMIT 1",
+            "SELECT user_id FROM holds WHERE item_id = $1 AND status IN ('ready','in_locker') ORDER BY position ASC LIMIT 1",
         )
         .bind(item_id)
         .fetch_optional(&self.pool)
@@ -485,7 +667,8 @@ impl Repository {
         Ok(pending)
     }
 
-    /// Mark the patron’s active hold on this copy as fulfilled (after a normal checkout).
+    /// Mark the patron’s active hold on this copy as fulfilled (after a normal checkout); a copy
+    /// taken from a pickup locker becomes `picked_up`.
     #[tracing::instrument(skip(self, tx), err)]
     pub async fn holds_fulfill_active_for_user_item_tx(
         &self,
@@ -494,7 +677,12 @@ impl Repository {
         item_id: i64,
     ) -> AppResult<u64> {
         let r = sqlx::query(
-            "UPDATE holds SET status = 'fulfilled' WHERE user_id = $1 AND item_id = $2 AND status IN ('pending','ready')",
+            r#"
+            UPDATE holds SET
+                status = CASE WHEN status = 'in_locker' THEN 'picked_up' ELSE 'fulfilled' END,
+                picked_up_at = CASE WHEN status = 'in_locker' THEN NOW() ELSE picked_up_at END
+            WHERE user_id = $1 AND item_id = $2 AND status IN ('pending','ready','in_locker')
+            "#,
         )
         .bind(user_id)
         .bind(item_id)
@@ -511,7 +699,7 @@ impl Repository {
         item_id: i64,
     ) -> AppResult<u64> {
         let r = sqlx::query(
-            "UPDATE holds SET status = 'cancelled' WHERE item_id = $1 AND status IN ('pending','ready')",
+            "UPDATE holds SET status = 'cancelled' WHERE item_id = $1 AND status IN ('pending','ready','in_locker')",
         )
         .bind(item_id)
         .execute(&mut **tx)
@@ -523,7 +711,7 @@ impl Repository {
     #[tracing::instrument(skip(self), err)]
     pub async fn holds_cancel_active_for_item(&self, item_id: i64) -> AppResult<u64> {
         let r = sqlx::query(
-            "UPDATE holds SET status = 'cancelled' WHERE item_id = $1 AND status IN ('pending','ready')",
+            "UPDATE holds SET status = 'cancelled' WHERE item_id = $1 AND status IN ('pending','ready','in_locker')",
         )
         .bind(item_id)
         .execute(&self.pool)
@@ -531,14 +719,14 @@ impl Repository {
         Ok(r.rows_affected())
     }
 
-    /// Whether the user already has a `pending` or `ready` hold on this copy.
+    /// Whether the user already has an active (`pending`, `ready`, `in_locker`) hold on this copy.
     #[tracing::instrument(skip(self), err)]
     pub async fn holds_has_active_for_user_item(&self, user_id: i64, item_id: i64) -> AppResult<bool> {
         let b: bool = sqlx::query_scalar(
             r#"
             SELECT EXISTS(
                 SELECT 1 FROM holds
-                WHERE user_id = $1 AND item_id = $2 AND status IN ('pending','ready')
+                WHERE user_id = $1 AND item_id = $2 AND status IN ('pending','ready','in_locker')
             )
             "#,
         )
@@ -549,14 +737,71 @@ impl Repository {
         Ok(b)
     }
 
-    /// Count active holds across all copies of a bibliographic record.
+    /// Daily pull list: the first `pending` hold of each copy that is on the shelf (not on loan,
+    /// not archived, not in transit) and not already set aside for a `ready` or `in_locker` hold.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn holds_pull_list(&self) -> AppResult<Vec<HoldShelfEntry>> {
+        let sql = format!(
+            r#"{HOLD_SHELF_SELECT_SQL}
+            WHERE h.status = 'pending'
+              AND it.archived_at IS NULL
+              AND it.transit_place IS NULL
+              AND NOT EXISTS (SELECT 1 FROM loans l WHERE l.item_id = it.id AND l.returned_at IS NULL)
+              AND NOT EXISTS (
+                  SELECT 1 FROM holds o
+                  WHERE o.item_id = h.item_id
+                    AND (o.status IN ('ready', 'in_locker')
+                         OR (o.status = 'pending' AND o.position < h.position))
+              )
+            ORDER BY it.place NULLS LAST, lp.sort_key NULLS LAST, it.call_number NULLS LAST, b.title, h.created_at
+            "#
+        );
+        let rows = sqlx::query_as::<_, HoldShelfEntry>(&sql).fetch_all(&self.pool).await?;
+        Ok(rows)
+    }
+
+    /// Copies left on the hold shelf by holds that expired (or are past their pickup deadline and
+    /// not yet swept) since `since`. A copy is listed once, and not when it went out on loan since,
+    /// or when another hold is queued on it (it then appears on the pull list instead).
+    #[tracing::instrument(skip(self), err)]
+    pub async fn holds_expired_to_reshelve(&self, since: chrono::DateTime<Utc>) -> AppResult<Vec<HoldShelfEntry>> {
+        let sql = format!(
+            r#"
+            SELECT * FROM (
+                SELECT DISTINCT ON (hold_shelf.item_id) hold_shelf.*
+                FROM ({HOLD_SHELF_SELECT_SQL}
+                    WHERE (h.status = 'expired' OR (h.status = 'ready' AND h.expires_at < NOW()))
+                      AND h.expires_at >= $1
+                      AND it.archived_at IS NULL
+                      AND NOT EXISTS (SELECT 1 FROM loans_all l WHERE l.item_id = it.id AND l.date > h.expires_at)
+                      AND NOT EXISTS (SELECT 1 FROM loans l WHERE l.item_id = it.id AND l.returned_at IS NULL)
+                      AND NOT EXISTS (
+                          SELECT 1 FROM holds o
+                          WHERE o.item_id = h.item_id AND o.id <> h.id
+                            AND o.status IN ('pending', 'ready', 'in_locker')
+                      )
+                ) hold_shelf
+                ORDER BY hold_shelf.item_id, hold_shelf.expires_at DESC
+            ) latest
+            ORDER BY place NULLS LAST, location_sort_key NULLS LAST, call_number NULLS LAST, title, expires_at
+            "#
+        );
+        let rows = sqlx::query_as::<_, HoldShelfEntry>(&sql)
+            .bind(since)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    /// Count active holds across the copies of a bibliographic record shown in the OPAC.
     #[tracing::instrument(skip(self), err)]
     pub async fn holds_count_active_for_biblio(&self, biblio_id: i64) -> AppResult<i64> {
         let count: i64 = sqlx::query_scalar(
             r#"
             SELECT COUNT(*)::bigint FROM holds h
             INNER JOIN items i ON i.id = h.item_id
-            WHERE i.biblio_id = $1 AND h.status IN ('pending','ready')
+            WHERE i.biblio_id = $1 AND h.status IN ('pending','ready','in_locker')
+              AND i.archived_at IS NULL AND item_opac_visible(i.circulation_status)
             "#,
         )
         .bind(biblio_id)
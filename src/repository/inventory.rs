@@ -28,6 +28,7 @@ pub trait InventoryRepository: Send + Sync {
         location_filter: Option<&str>,
         notes: Option<&str>,
         scope_place: Option<i16>,
+        scope_location_id: Option<i64>,
         created_by: Option<i64>,
     ) -> AppResult<InventorySession>;
     async fn inventory_close_session(&self, id: i64) -> AppResult<InventorySession>;
@@ -71,9 +72,10 @@ impl InventoryRepository for Repository {
         location_filter: Option<&str>,
         notes: Option<&str>,
         scope_place: Option<i16>,
+        scope_location_id: Option<i64>,
         created_by: Option<i64>,
     ) -> AppResult<InventorySession> {
-        Repository::inventory_create_session(self, name, location_filter, notes, scope_place, created_by)
+        Repository::inventory_create_session(self, name, location_filter, notes, scope_place, scope_location_id, created_by)
             .await
     }
     async fn inventory_close_session(&self, id: i64) -> AppResult<InventorySession> {
@@ -183,13 +185,34 @@ impl Repository {
         location_filter: Option<&str>,
         notes: Option<&str>,
         scope_place: Option<i16>,
+        scope_location_id: Option<i64>,
         created_by: Option<i64>,
     ) -> AppResult<InventorySession> {
+        // A session scoped to a part of the shelving map counts the copies of its site's place
+        let scope_place = match scope_location_id {
+            None => scope_place,
+            Some(location_id) => {
+                let place: i16 = sqlx::query_scalar("SELECT place FROM shelving_locations WHERE id = $1")
+                    .bind(location_id)
+                    .fetch_optional(&self.pool)
+                    .await?
+                    .ok_or_else(|| {
+                        AppError::Validation(format!("scopeLocationId {} is not a shelving location", location_id))
+                    })?;
+                if scope_place.is_some_and(|p| p != place) {
+                    return Err(AppError::Validation(format!(
+                        "scopePlace must be the place of scopeLocationId ({})",
+                        place
+                    )));
+                }
+                Some(place)
+            }
+        };
         let id = next_id();
         let row = sqlx::query_as::<_, InventorySession>(
             r#"
-            INSERT INTO inventory_sessions (id, name, location_filter, notes, scope_place, created_by)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO inventory_sessions (id, name, location_filter, notes, scope_place, scope_location_id, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             RETURNING *
             "#,
         )
@@ -198,6 +221,7 @@ impl Repository {
         .bind(location_filter)
         .bind(notes)
         .bind(scope_place)
+        .bind(scope_location_id)
         .bind(created_by)
         .fetch_one(&self.pool)
         .await?;
@@ -304,6 +328,9 @@ impl Repository {
             INNER JOIN inventory_sessions inv ON inv.id = $1
             WHERE i.archived_at IS NULL
               AND (inv.scope_place IS NULL OR i.place = inv.scope_place)
+              AND (inv.scope_location_id IS NULL OR i.location_id IN (
+                  SELECT lp.id FROM shelving_location_paths lp WHERE inv.scope_location_id = ANY(lp.ancestor_ids)
+              ))
               AND NOT EXISTS (
                   SELECT 1 FROM inventory_scans sc
                   WHERE sc.session_id = $1 AND sc.item_id = i.id
@@ -316,17 +343,22 @@ impl Repository {
 
         let rows = sqlx::query_as::<_, InventoryMissingRow>(
             r#"
-            SELECT i.id AS item_id, i.barcode, i.call_number, i.place, b.title AS biblio_title
+            SELECT i.id AS item_id, i.barcode, i.call_number, i.place, lpm.path AS location_path,
+                   b.title AS biblio_title
             FROM items i
             INNER JOIN inventory_sessions inv ON inv.id = $1
+            LEFT JOIN shelving_location_paths lpm ON lpm.id = i.location_id
             LEFT JOIN biblios b ON b.id = i.biblio_id
             WHERE i.archived_at IS NULL
               AND (inv.scope_place IS NULL OR i.place = inv.scope_place)
+              AND (inv.scope_location_id IS NULL OR i.location_id IN (
+                  SELECT lp.id FROM shelving_location_paths lp WHERE inv.scope_location_id = ANY(lp.ancestor_ids)
+              ))
               AND NOT EXISTS (
                   SELECT 1 FROM inventory_scans sc
                   WHERE sc.session_id = $1 AND sc.item_id = i.id
               )
-            ORDER BY i.id
+            ORDER BY lpm.sort_key NULLS LAST, i.call_number NULLS LAST, i.id
             LIMIT $2 OFFSET $3
             "#,
         )
@@ -339,7 +371,7 @@ impl Repository {
         Ok((rows, total))
     }
 
-    /// Enriched discrepancy report (respects session `scope_place`).
+    /// Enriched discrepancy report (respects session `scope_place` and `scope_location_id`).
     #[tracing::instrument(skip(self), err)]
     pub async fn inventory_report(&self, session_id: i64) -> AppResult<InventoryReport> {
         let expected_in_scope: i64 = sqlx::query_scalar(
@@ -348,6 +380,9 @@ impl Repository {
             INNER JOIN inventory_sessions inv ON inv.id = $1
             WHERE i.archived_at IS NULL
               AND (inv.scope_place IS NULL OR i.place = inv.scope_place)
+              AND (inv.scope_location_id IS NULL OR i.location_id IN (
+                  SELECT lp.id FROM shelving_location_paths lp WHERE inv.scope_location_id = ANY(lp.ancestor_ids)
+              ))
             "#,
         )
         .bind(session_id)
@@ -360,6 +395,9 @@ impl Repository {
             INNER JOIN inventory_sessions inv ON inv.id = $1
             WHERE i.archived_at IS NULL
               AND (inv.scope_place IS NULL OR i.place = inv.scope_place)
+              AND (inv.scope_location_id IS NULL OR i.location_id IN (
+                  SELECT lp.id FROM shelving_location_paths lp WHERE inv.scope_location_id = ANY(lp.ancestor_ids)
+              ))
               AND NOT EXISTS (
                   SELECT 1 FROM inventory_scans sc
                   WHERE sc.session_id = $1 AND sc.item_id = i.id
@@ -376,6 +414,9 @@ impl Repository {
             INNER JOIN inventory_sessions inv ON inv.id = $1
             WHERE i.archived_at IS NULL
               AND (inv.scope_place IS NULL OR i.place = inv.scope_place)
+              AND (inv.scope_location_id IS NULL OR i.location_id IN (
+                  SELECT lp.id FROM shelving_location_paths lp WHERE inv.scope_location_id = ANY(lp.ancestor_ids)
+              ))
               AND i.barcode IS NOT NULL
               AND NOT EXISTS (
                   SELECT 1 FROM inventory_scans sc
@@ -393,6 +434,9 @@ impl Repository {
             INNER JOIN inventory_sessions inv ON inv.id = $1
             WHERE i.archived_at IS NULL
               AND (inv.scope_place IS NULL OR i.place = inv.scope_place)
+              AND (inv.scope_location_id IS NULL OR i.location_id IN (
+                  SELECT lp.id FROM shelving_location_paths lp WHERE inv.scope_location_id = ANY(lp.ancestor_ids)
+              ))
               AND i.barcode IS NULL
               AND NOT EXISTS (
                   SELECT 1 FROM inventory_scans sc
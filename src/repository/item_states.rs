@@ -0,0 +1,171 @@
+//! Item state taxonomy (`item_states`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::item_state::{CreateItemState, ItemState, UpdateItemState},
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ItemStatesRepository: Send + Sync {
+    async fn item_states_list(&self) -> AppResult<Vec<ItemState>>;
+    async fn item_states_get(&self, code: i16) -> AppResult<ItemState>;
+    async fn item_states_create(&self, data: &CreateItemState) -> AppResult<ItemState>;
+    async fn item_states_update(&self, code: i16, data: &UpdateItemState) -> AppResult<ItemState>;
+    async fn item_states_delete(&self, code: i16) -> AppResult<()>;
+    /// Number of copies (archived included) currently in this state.
+    async fn item_states_count_items(&self, code: i16) -> AppResult<i64>;
+    /// Records with an active copy in this state.
+    async fn item_states_biblio_ids(&self, code: i16) -> AppResult<Vec<i64>>;
+}
+
+#[async_trait]
+impl ItemStatesRepository for Repository {
+    async fn item_states_list(&self) -> AppResult<Vec<ItemState>> {
+        Repository::item_states_list(self).await
+    }
+    async fn item_states_get(&self, code: i16) -> AppResult<ItemState> {
+        Repository::item_states_get(self, code).await
+    }
+    async fn item_states_create(&self, data: &CreateItemState) -> AppResult<ItemState> {
+        Repository::item_states_create(self, data).await
+    }
+    async fn item_states_update(&self, code: i16, data: &UpdateItemState) -> AppResult<ItemState> {
+        Repository::item_states_update(self, code, data).await
+    }
+    async fn item_states_delete(&self, code: i16) -> AppResult<()> {
+        Repository::item_states_delete(self, code).await
+    }
+    async fn item_states_count_items(&self, code: i16) -> AppResult<i64> {
+        Repository::item_states_count_items(self, code).await
+    }
+    async fn item_states_biblio_ids(&self, code: i16) -> AppResult<Vec<i64>> {
+        Repository::item_states_biblio_ids(self, code).await
+    }
+}
+
+impl Repository {
+    /// List item states in display order
+    #[tracing::instrument(skip(self), err)]
+    pub async fn item_states_list(&self) -> AppResult<Vec<ItemState>> {
+        let rows = sqlx::query_as::<_, ItemState>(
+            "SELECT * FROM item_states ORDER BY sort_order, code",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get an item state by code
+    #[tracing::instrument(skip(self), err)]
+    pub async fn item_states_get(&self, code: i16) -> AppResult<ItemState> {
+        sqlx::query_as::<_, ItemState>("SELECT * FROM item_states WHERE code = $1")
+            .bind(code)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Item state {} not found", code)))
+    }
+
+    /// Create an item state
+    #[tracing::instrument(skip(self), err)]
+    pub async fn item_states_create(&self, data: &CreateItemState) -> AppResult<ItemState> {
+        let row = sqlx::query_as::<_, ItemState>(
+            r#"
+            INSERT INTO item_states (code, label, blocks_circulation, color, sort_order, opac_visible)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(data.code)
+        .bind(data.label.trim())
+        .bind(data.blocks_circulation)
+        .bind(data.color.as_deref().filter(|c| !c.is_empty()))
+        .bind(data.sort_order.unwrap_or(data.code))
+        .bind(data.opac_visible.unwrap_or(true))
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Update an item state (absent fields are kept; an empty `color` clears it)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn item_states_update(&self, code: i16, data: &UpdateItemState) -> AppResult<ItemState> {
+        sqlx::query_as::<_, ItemState>(
+            r#"
+            UPDATE item_states SET
+                label = COALESCE($1, label),
+                blocks_circulation = COALESCE($2, blocks_circulation),
+                color = CASE WHEN $3::text IS NULL THEN color ELSE NULLIF($3, '') END,
+                sort_order = COALESCE($4, sort_order),
+                opac_visible = COALESCE($5, opac_visible),
+                update_at = $6
+            WHERE code = $7
+            RETURNING *
+            "#,
+        )
+        .bind(data.label.as_deref().map(str::trim))
+        .bind(data.blocks_circulation)
+        .bind(&data.color)
+        .bind(data.sort_order)
+        .bind(data.opac_visible)
+        .bind(Utc::now())
+        .bind(code)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Item state {} not found", code)))
+    }
+
+    /// Delete an item state
+    #[tracing::instrument(skip(self), err)]
+    pub async fn item_states_delete(&self, code: i16) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM item_states WHERE code = $1")
+            .bind(code)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Item state {} not found", code)));
+        }
+        Ok(())
+    }
+
+    /// Number of copies (archived included) currently in this state
+    #[tracing::instrument(skip(self), err)]
+    pub async fn item_states_count_items(&self, code: i16) -> AppResult<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items WHERE circulation_status = $1")
+            .bind(code)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    /// Records with an active copy in this state (reindexed when its OPAC visibility changes)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn item_states_biblio_ids(&self, code: i16) -> AppResult<Vec<i64>> {
+        let ids = sqlx::query_scalar::<_, i64>(
+            "SELECT DISTINCT biblio_id FROM items WHERE circulation_status = $1 AND archived_at IS NULL",
+        )
+        .bind(code)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
+
+    /// Reject unknown `circulation_status` values before they reach `items`.
+    pub(crate) async fn item_states_ensure_known(&self, code: Option<i16>) -> AppResult<()> {
+        let Some(code) = code else {
+            return Ok(());
+        };
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM item_states WHERE code = $1)")
+            .bind(code)
+            .fetch_one(&self.pool)
+            .await?;
+        if !exists {
+            return Err(AppError::Validation(format!("Unknown item state {}", code)));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+//! Cataloging templates (`item_templates`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::item_template::{ItemTemplate, ItemTemplateFields},
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ItemTemplatesRepository: Send + Sync {
+    async fn item_templates_list(&self) -> AppResult<Vec<ItemTemplate>>;
+    async fn item_templates_get_by_code(&self, code: &str) -> AppResult<Option<ItemTemplate>>;
+    async fn item_templates_create(&self, code: &str, data: &ItemTemplateFields) -> AppResult<ItemTemplate>;
+    async fn item_templates_update(&self, code: &str, data: &ItemTemplateFields) -> AppResult<ItemTemplate>;
+    async fn item_templates_delete(&self, code: &str) -> AppResult<()>;
+    /// Whether a genre heading exists.
+    async fn item_templates_genre_exists(&self, genre_id: i64) -> AppResult<bool>;
+    /// Reject unknown item state codes.
+    async fn item_templates_ensure_state(&self, code: Option<i16>) -> AppResult<()>;
+}
+
+#[async_trait]
+impl ItemTemplatesRepository for Repository {
+    async fn item_templates_list(&self) -> AppResult<Vec<ItemTemplate>> {
+        Repository::item_templates_list(self).await
+    }
+    async fn item_templates_get_by_code(&self, code: &str) -> AppResult<Option<ItemTemplate>> {
+        Repository::item_templates_get_by_code(self, code).await
+    }
+    async fn item_templates_create(&self, code: &str, data: &ItemTemplateFields) -> AppResult<ItemTemplate> {
+        Repository::item_templates_create(self, code, data).await
+    }
+    async fn item_templates_update(&self, code: &str, data: &ItemTemplateFields) -> AppResult<ItemTemplate> {
+        Repository::item_templates_update(self, code, data).await
+    }
+    async fn item_templates_delete(&self, code: &str) -> AppResult<()> {
+        Repository::item_templates_delete(self, code).await
+    }
+    async fn item_templates_genre_exists(&self, genre_id: i64) -> AppResult<bool> {
+        Repository::item_templates_genre_exists(self, genre_id).await
+    }
+    async fn item_templates_ensure_state(&self, code: Option<i16>) -> AppResult<()> {
+        Repository::item_states_ensure_known(self, code).await
+    }
+}
+
+impl Repository {
+    /// List templates by name
+    #[tracing::instrument(skip(self), err)]
+    pub async fn item_templates_list(&self) -> AppResult<Vec<ItemTemplate>> {
+        let rows = sqlx::query_as::<_, ItemTemplate>("SELECT * FROM item_templates ORDER BY name, code")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    /// Get a template by code
+    #[tracing::instrument(skip(self), err)]
+    pub async fn item_templates_get_by_code(&self, code: &str) -> AppResult<Option<ItemTemplate>> {
+        let row = sqlx::query_as::<_, ItemTemplate>("SELECT * FROM item_templates WHERE code = $1")
+            .bind(code)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row)
+    }
+
+    /// Create a template
+    #[tracing::instrument(skip(self), err)]
+    pub async fn item_templates_create(&self, code: &str, data: &ItemTemplateFields) -> AppResult<ItemTemplate> {
+        let now = Utc::now();
+        sqlx::query_as::<_, ItemTemplate>(
+            r#"
+            INSERT INTO item_templates
+                (code, name, description, media_type, audience_type, genre_id, place, borrowable,
+                 circulation_status, created_at, update_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10)
+            RETURNING *
+            "#,
+        )
+        .bind(code)
+        .bind(data.name.trim())
+        .bind(data.description.as_deref().map(str::trim).filter(|d| !d.is_empty()))
+        .bind(&data.media_type)
+        .bind(&data.audience_type)
+        .bind(data.genre_id)
+        .bind(data.place)
+        .bind(data.borrowable)
+        .bind(data.circulation_status)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("unique") {
+                AppError::Conflict(format!("Template '{}' already exists", code))
+            } else {
+                AppError::Internal(e.to_string())
+            }
+        })
+    }
+
+    /// Replace every field of a template
+    #[tracing::instrument(skip(self), err)]
+    pub async fn item_templates_update(&self, code: &str, data: &ItemTemplateFields) -> AppResult<ItemTemplate> {
+        sqlx::query_as::<_, ItemTemplate>(
+            r#"
+            UPDATE item_templates SET
+                name = $1,
+                description = $2,
+                media_type = $3,
+                audience_type = $4,
+                genre_id = $5,
+                place = $6,
+                borrowable = $7,
+                circulation_status = $8,
+                update_at = $9
+            WHERE code = $10
+            RETURNING *
+            "#,
+        )
+        .bind(data.name.trim())
+        .bind(data.description.as_deref().map(str::trim).filter(|d| !d.is_empty()))
+        .bind(&data.media_type)
+        .bind(&data.audience_type)
+        .bind(data.genre_id)
+        .bind(data.place)
+        .bind(data.borrowable)
+        .bind(data.circulation_status)
+        .bind(Utc::now())
+        .bind(code)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Template '{}' not found", code)))
+    }
+
+    /// Delete a template
+    #[tracing::instrument(skip(self), err)]
+    pub async fn item_templates_delete(&self, code: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM item_templates WHERE code = $1")
+            .bind(code)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Template '{}' not found", code)));
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn item_templates_genre_exists(&self, genre_id: i64) -> AppResult<bool> {
+        let exists = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM genres WHERE id = $1)")
+            .bind(genre_id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(exists)
+    }
+}
@@ -0,0 +1,219 @@
+//! Kiosk tokens (`kiosks`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::kiosk::{CreateKiosk, Kiosk, UpdateKiosk},
+};
+
+/// Every column but `token_hash`
+const KIOSK_COLUMNS: &str = "id, name, location, token_prefix, allowed_ips, is_active, expires_at, \
+     last_used_at, last_used_ip, created_by, created_at, update_at";
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait KiosksRepository: Send + Sync {
+    async fn kiosks_list(&self) -> AppResult<Vec<Kiosk>>;
+    async fn kiosks_get(&self, id: i64) -> AppResult<Kiosk>;
+    /// Kiosk owning this token hash, whatever its state.
+    async fn kiosks_find_by_token_hash(&self, token_hash: &str) -> AppResult<Option<Kiosk>>;
+    async fn kiosks_create(
+        &self,
+        data: &CreateKiosk,
+        token_hash: &str,
+        token_prefix: &str,
+        created_by: i64,
+    ) -> AppResult<Kiosk>;
+    async fn kiosks_update(&self, id: i64, data: &UpdateKiosk) -> AppResult<Kiosk>;
+    async fn kiosks_set_token(&self, id: i64, token_hash: &str, token_prefix: &str) -> AppResult<Kiosk>;
+    async fn kiosks_delete(&self, id: i64) -> AppResult<()>;
+    /// Record a use of the token (throttled to one write per minute).
+    async fn kiosks_touch(&self, id: i64, ip: Option<String>) -> AppResult<()>;
+}
+
+#[async_trait]
+impl KiosksRepository for Repository {
+    async fn kiosks_list(&self) -> AppResult<Vec<Kiosk>> {
+        Repository::kiosks_list(self).await
+    }
+    async fn kiosks_get(&self, id: i64) -> AppResult<Kiosk> {
+        Repository::kiosks_get(self, id).await
+    }
+    async fn kiosks_find_by_token_hash(&self, token_hash: &str) -> AppResult<Option<Kiosk>> {
+        Repository::kiosks_find_by_token_hash(self, token_hash).await
+    }
+    async fn kiosks_create(
+        &self,
+        data: &CreateKiosk,
+        token_hash: &str,
+        token_prefix: &str,
+        created_by: i64,
+    ) -> AppResult<Kiosk> {
+        Repository::kiosks_create(self, data, token_hash, token_prefix, created_by).await
+    }
+    async fn kiosks_update(&self, id: i64, data: &UpdateKiosk) -> AppResult<Kiosk> {
+        Repository::kiosks_update(self, id, data).await
+    }
+    async fn kiosks_set_token(&self, id: i64, token_hash: &str, token_prefix: &str) -> AppResult<Kiosk> {
+        Repository::kiosks_set_token(self, id, token_hash, token_prefix).await
+    }
+    async fn kiosks_delete(&self, id: i64) -> AppResult<()> {
+        Repository::kiosks_delete(self, id).await
+    }
+    async fn kiosks_touch(&self, id: i64, ip: Option<String>) -> AppResult<()> {
+        Repository::kiosks_touch(self, id, ip).await
+    }
+}
+
+impl Repository {
+    /// List kiosks by name
+    #[tracing::instrument(skip(self), err)]
+    pub async fn kiosks_list(&self) -> AppResult<Vec<Kiosk>> {
+        let rows = sqlx::query_as::<_, Kiosk>(&format!(
+            "SELECT {} FROM kiosks ORDER BY name, id",
+            KIOSK_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get a kiosk by ID
+    #[tracing::instrument(skip(self), err)]
+    pub async fn kiosks_get(&self, id: i64) -> AppResult<Kiosk> {
+        sqlx::query_as::<_, Kiosk>(&format!("SELECT {} FROM kiosks WHERE id = $1", KIOSK_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Kiosk {} not found", id)))
+    }
+
+    /// Find the kiosk owning a token hash
+    #[tracing::instrument(skip(self, token_hash), err)]
+    pub async fn kiosks_find_by_token_hash(&self, token_hash: &str) -> AppResult<Option<Kiosk>> {
+        let row = sqlx::query_as::<_, Kiosk>(&format!(
+            "SELECT {} FROM kiosks WHERE token_hash = $1",
+            KIOSK_COLUMNS
+        ))
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Create a kiosk
+    #[tracing::instrument(skip(self, token_hash), err)]
+    pub async fn kiosks_create(
+        &self,
+        data: &CreateKiosk,
+        token_hash: &str,
+        token_prefix: &str,
+        created_by: i64,
+    ) -> AppResult<Kiosk> {
+        let row = sqlx::query_as::<_, Kiosk>(&format!(
+            r#"
+            INSERT INTO kiosks (name, location, token_hash, token_prefix, allowed_ips, expires_at, created_by)
+            VALUES ($1, NULLIF($2, ''), $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            KIOSK_COLUMNS
+        ))
+        .bind(data.name.trim())
+        .bind(data.location.as_deref().map(str::trim))
+        .bind(token_hash)
+        .bind(token_prefix)
+        .bind(&data.allowed_ips)
+        .bind(data.expires_at)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Update a kiosk (absent fields are kept; an empty `location` clears it)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn kiosks_update(&self, id: i64, data: &UpdateKiosk) -> AppResult<Kiosk> {
+        sqlx::query_as::<_, Kiosk>(&format!(
+            r#"
+            UPDATE kiosks SET
+                name = COALESCE($1, name),
+                location = CASE WHEN $2::text IS NULL THEN location ELSE NULLIF($2, '') END,
+                allowed_ips = COALESCE($3, allowed_ips),
+                is_active = COALESCE($4, is_active),
+                expires_at = CASE WHEN $5::timestamptz IS NOT NULL THEN $5
+                                  WHEN $6 THEN NULL
+                                  ELSE expires_at END,
+                update_at = $7
+            WHERE id = $8
+            RETURNING {}
+            "#,
+            KIOSK_COLUMNS
+        ))
+        .bind(data.name.as_deref().map(str::trim))
+        .bind(data.location.as_deref().map(str::trim))
+        .bind(&data.allowed_ips)
+        .bind(data.is_active)
+        .bind(data.expires_at)
+        .bind(data.clear_expires_at)
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Kiosk {} not found", id)))
+    }
+
+    /// Replace the token of a kiosk (the previous one stops working immediately)
+    #[tracing::instrument(skip(self, token_hash), err)]
+    pub async fn kiosks_set_token(&self, id: i64, token_hash: &str, token_prefix: &str) -> AppResult<Kiosk> {
+        sqlx::query_as::<_, Kiosk>(&format!(
+            r#"
+            UPDATE kiosks SET token_hash = $1, token_prefix = $2, last_used_at = NULL,
+                last_used_ip = NULL, update_at = $3
+            WHERE id = $4
+            RETURNING {}
+            "#,
+            KIOSK_COLUMNS
+        ))
+        .bind(token_hash)
+        .bind(token_prefix)
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Kiosk {} not found", id)))
+    }
+
+    /// Delete a kiosk
+    #[tracing::instrument(skip(self), err)]
+    pub async fn kiosks_delete(&self, id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM kiosks WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Kiosk {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Record a use of the token; at most one write per kiosk per minute
+    #[tracing::instrument(skip(self), err)]
+    pub async fn kiosks_touch(&self, id: i64, ip: Option<String>) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE kiosks SET last_used_at = NOW(), last_used_ip = $2
+            WHERE id = $1
+              AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute'
+                   OR last_used_ip IS DISTINCT FROM $2)
+            "#,
+        )
+        .bind(id)
+        .bind(ip)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+//! Translated display labels (`labels`) domain methods on Repository
+
+use async_trait::async_trait;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::{
+        label::{Label, LabelKind},
+        Language,
+    },
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait LabelsRepository: Send + Sync {
+    /// Labels of one language, optionally restricted to one kind.
+    async fn labels_list(&self, lang: &str, kind: Option<LabelKind>) -> AppResult<Vec<Label>>;
+    /// Languages having at least one label.
+    async fn labels_languages(&self) -> AppResult<Vec<String>>;
+    async fn labels_upsert(&self, kind: LabelKind, code: &str, lang: &str, label: &str) -> AppResult<Label>;
+    async fn labels_delete(&self, kind: LabelKind, code: &str, lang: &str) -> AppResult<()>;
+    /// Preferred language stored on the user account.
+    async fn labels_user_language(&self, user_id: i64) -> AppResult<Option<Language>>;
+    /// Default labels of the media type taxonomy (`code`, `label`).
+    async fn labels_media_type_defaults(&self) -> AppResult<Vec<(String, String)>>;
+    /// Default labels of the relator vocabulary (`code`, `label`).
+    async fn labels_relator_defaults(&self) -> AppResult<Vec<(String, String)>>;
+}
+
+#[async_trait]
+impl LabelsRepository for Repository {
+    async fn labels_list(&self, lang: &str, kind: Option<LabelKind>) -> AppResult<Vec<Label>> {
+        Repository::labels_list(self, lang, kind).await
+    }
+    async fn labels_languages(&self) -> AppResult<Vec<String>> {
+        Repository::labels_languages(self).await
+    }
+    async fn labels_upsert(&self, kind: LabelKind, code: &str, lang: &str, label: &str) -> AppResult<Label> {
+        Repository::labels_upsert(self, kind, code, lang, label).await
+    }
+    async fn labels_delete(&self, kind: LabelKind, code: &str, lang: &str) -> AppResult<()> {
+        Repository::labels_delete(self, kind, code, lang).await
+    }
+    async fn labels_user_language(&self, user_id: i64) -> AppResult<Option<Language>> {
+        Repository::labels_user_language(self, user_id).await
+    }
+    async fn labels_media_type_defaults(&self) -> AppResult<Vec<(String, String)>> {
+        Repository::labels_media_type_defaults(self).await
+    }
+    async fn labels_relator_defaults(&self) -> AppResult<Vec<(String, String)>> {
+        Repository::labels_relator_defaults(self).await
+    }
+}
+
+impl Repository {
+    /// List the labels of a language
+    #[tracing::instrument(skip(self), err)]
+    pub async fn labels_list(&self, lang: &str, kind: Option<LabelKind>) -> AppResult<Vec<Label>> {
+        let rows = sqlx::query_as::<_, Label>(
+            r#"
+            SELECT kind, code, lang, label, update_at
+            FROM labels
+            WHERE lang = $1 AND ($2::TEXT IS NULL OR kind = $2)
+            ORDER BY kind, code
+            "#,
+        )
+        .bind(lang)
+        .bind(kind)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Languages with translations, alphabetically
+    #[tracing::instrument(skip(self), err)]
+    pub async fn labels_languages(&self) -> AppResult<Vec<String>> {
+        let langs = sqlx::query_scalar::<_, String>("SELECT DISTINCT lang FROM labels ORDER BY lang")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(langs)
+    }
+
+    /// Create or replace a translation
+    #[tracing::instrument(skip(self), err)]
+    pub async fn labels_upsert(&self, kind: LabelKind, code: &str, lang: &str, label: &str) -> AppResult<Label> {
+        let row = sqlx::query_as::<_, Label>(
+            r#"
+            INSERT INTO labels (kind, code, lang, label)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (kind, code, lang)
+            DO UPDATE SET label = EXCLUDED.label, update_at = NOW()
+            RETURNING kind, code, lang, label, update_at
+            "#,
+        )
+        .bind(kind)
+        .bind(code)
+        .bind(lang)
+        .bind(label)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Delete a translation
+    #[tracing::instrument(skip(self), err)]
+    pub async fn labels_delete(&self, kind: LabelKind, code: &str, lang: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM labels WHERE kind = $1 AND code = $2 AND lang = $3")
+            .bind(kind)
+            .bind(code)
+            .bind(lang)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Label {}/{}/{} not found", kind, code, lang)));
+        }
+        Ok(())
+    }
+
+    /// Preferred language of a user (`None` when unset or the user does not exist)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn labels_user_language(&self, user_id: i64) -> AppResult<Option<Language>> {
+        let language = sqlx::query_scalar::<_, Option<Language>>("SELECT language FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(language.flatten())
+    }
+
+    /// Default labels of the media types (used where no translation exists)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn labels_media_type_defaults(&self) -> AppResult<Vec<(String, String)>> {
+        let rows = sqlx::query_as::<_, (String, String)>("SELECT code, label FROM media_types ORDER BY code")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    /// Default labels of the relators (used where no translation exists)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn labels_relator_defaults(&self) -> AppResult<Vec<(String, String)>> {
+        let rows = sqlx::query_as::<_, (String, String)>("SELECT code, label FROM relators ORDER BY code")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,277 @@
+//! Group loan batches (`loan_batches`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::loan_batch::{CreateLoanBatch, GroupLoanLimits, LoanBatch, LoanBatchLine, LoanBatchQuery},
+};
+
+/// Batch columns with borrower name and loan counters (`lb` = `loan_batches`, `u` = `users`).
+const LOAN_BATCH_SELECT_SQL: &str = r#"
+    SELECT lb.id, lb.user_id, u.firstname, u.lastname, lb.label, lb.due_at, lb.notes,
+           lb.created_by, lb.created_at, lb.update_at,
+           (SELECT COUNT(*) FROM loans l WHERE l.batch_id = lb.id AND l.returned_at IS NULL) AS active_loans,
+           (SELECT COUNT(*) FROM loans_all la WHERE la.batch_id = lb.id AND la.returned_at IS NOT NULL) AS returned_loans
+    FROM loan_batches lb
+    JOIN users u ON u.id = lb.user_id
+"#;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait LoanBatchesRepository: Send + Sync {
+    async fn loan_batches_list(&self, query: &LoanBatchQuery) -> AppResult<Vec<LoanBatch>>;
+    async fn loan_batches_get(&self, id: i64) -> AppResult<LoanBatch>;
+    /// Group loan limits of the user's account type.
+    async fn loan_batches_limits_for_user(&self, user_id: i64) -> AppResult<GroupLoanLimits>;
+    /// Check out every barcode of `data` (all or nothing) with the shared due date `due_at`.
+    async fn loan_batches_create(&self, data: &CreateLoanBatch, due_at: DateTime<Utc>, created_by: i64) -> AppResult<LoanBatch>;
+    /// Move the due date of the batch and of every copy still out; returns the number of loans moved.
+    async fn loan_batches_extend(&self, id: i64, due_at: DateTime<Utc>) -> AppResult<u64>;
+    /// Return every copy still out; returns the number of loans returned.
+    async fn loan_batches_return(&self, id: i64) -> AppResult<usize>;
+}
+
+#[async_trait]
+impl LoanBatchesRepository for Repository {
+    async fn loan_batches_list(&self, query: &LoanBatchQuery) -> AppResult<Vec<LoanBatch>> {
+        Repository::loan_batches_list(self, query).await
+    }
+    async fn loan_batches_get(&self, id: i64) -> AppResult<LoanBatch> {
+        Repository::loan_batches_get(self, id).await
+    }
+    async fn loan_batches_limits_for_user(&self, user_id: i64) -> AppResult<GroupLoanLimits> {
+        Repository::loan_batches_limits_for_user(self, user_id).await
+    }
+    async fn loan_batches_create(&self, data: &CreateLoanBatch, due_at: DateTime<Utc>, created_by: i64) -> AppResult<LoanBatch> {
+        Repository::loan_batches_create(self, data, due_at, created_by).await
+    }
+    async fn loan_batches_extend(&self, id: i64, due_at: DateTime<Utc>) -> AppResult<u64> {
+        Repository::loan_batches_extend(self, id, due_at).await
+    }
+    async fn loan_batches_return(&self, id: i64) -> AppResult<usize> {
+        Repository::loan_batches_return(self, id).await
+    }
+}
+
+impl Repository {
+    /// List batches, most recent first
+    #[tracing::instrument(skip(self), err)]
+    pub async fn loan_batches_list(&self, query: &LoanBatchQuery) -> AppResult<Vec<LoanBatch>> {
+        let sql = format!(
+            r#"{LOAN_BATCH_SELECT_SQL}
+            WHERE ($1::bigint IS NULL OR lb.user_id = $1)
+              AND (NOT $2 OR EXISTS (SELECT 1 FROM loans l WHERE l.batch_id = lb.id AND l.returned_at IS NULL))
+            ORDER BY lb.created_at DESC, lb.id DESC
+            "#
+        );
+        let rows = sqlx::query_as::<_, LoanBatch>(&sql)
+            .bind(query.user_id)
+            .bind(query.active_only.unwrap_or(false))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    /// Get a batch with its copies (still out first, then returned)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn loan_batches_get(&self, id: i64) -> AppResult<LoanBatch> {
+        let sql = format!("{LOAN_BATCH_SELECT_SQL} WHERE lb.id = $1");
+        let mut batch = sqlx::query_as::<_, LoanBatch>(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Loan batch {} not found", id)))?;
+
+        batch.loans = sqlx::query_as::<_, LoanBatchLine>(
+            r#"
+            SELECT l.id AS loan_id, l.item_id, it.barcode, b.title, l.expiry_at, l.returned_at
+            FROM loans_all l
+            LEFT JOIN items it ON it.id = l.item_id
+            LEFT JOIN biblios b ON b.id = it.biblio_id
+            WHERE l.batch_id = $1
+            ORDER BY (l.returned_at IS NOT NULL), b.title, it.barcode
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(batch)
+    }
+
+    /// Group loan limits of the user's account type
+    #[tracing::instrument(skip(self), err)]
+    pub async fn loan_batches_limits_for_user(&self, user_id: i64) -> AppResult<GroupLoanLimits> {
+        sqlx::query_as::<_, GroupLoanLimits>(
+            r#"
+            SELECT at.code AS account_type, at.group_loans_max_items AS max_items, at.group_loans_max_days AS max_days
+            FROM users u
+            JOIN account_types at ON at.code = u.account_type
+            WHERE u.id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))
+    }
+
+    /// Check out every barcode (all or nothing).
+    ///
+    /// Patron loan quotas do not apply: the account type limits checked by the service replace them.
+    /// Copies must be free; `force` overrides the borrowable flag, blocking states and hold queues.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn loan_batches_create(
+        &self,
+        data: &CreateLoanBatch,
+        due_at: DateTime<Utc>,
+        created_by: i64,
+    ) -> AppResult<LoanBatch> {
+        let mut item_ids = Vec::with_capacity(data.barcodes.len());
+        for barcode in &data.barcodes {
+            let row = sqlx::query(
+                r#"
+                SELECT it.id, it.borrowable,
+                       st.label AS state_label, COALESCE(st.blocks_circulation, FALSE) AS state_blocks,
+                       EXISTS(SELECT 1 FROM loans l WHERE l.item_id = it.id AND l.returned_at IS NULL) AS borrowed
+                FROM items it
+                LEFT JOIN item_states st ON st.code = it.circulation_status
+                WHERE it.barcode = $1 AND it.archived_at IS NULL
+                "#,
+            )
+            .bind(barcode)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Item with barcode {} not found", barcode)))?;
+
+            let item_id: i64 = row.get("id");
+            if row.get::<bool, _>("borrowed") {
+                return Err(AppError::BusinessRule(format!("Item {} is already borrowed", barcode)));
+            }
+            if !data.force {
+                if !row.get::<bool, _>("borrowable") {
+                    return Err(AppError::BusinessRule(format!("Item {} is not borrowable", barcode)));
+                }
+                if row.get::<bool, _>("state_blocks") {
+                    let label: Option<String> = row.get("state_label");
+                    return Err(AppError::BusinessRule(format!(
+                        "Item {}: state '{}' blocks circulation",
+                        barcode,
+                        label.unwrap_or_default()
+                    )));
+                }
+                if let Some(eligible) = self.holds_eligible_borrower_for_item(item_id).await? {
+                    if eligible != data.user_id {
+                        return Err(AppError::BusinessRule(format!(
+                            "Item {} has an active hold for another patron — use force=true to override",
+                            barcode
+                        )));
+                    }
+                }
+            }
+            item_ids.push(item_id);
+        }
+
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+
+        let batch_id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO loan_batches (user_id, label, due_at, notes, created_by, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id
+            "#,
+        )
+        .bind(data.user_id)
+        .bind(data.label.as_deref().map(str::trim).filter(|l| !l.is_empty()))
+        .bind(due_at)
+        .bind(&data.notes)
+        .bind(created_by)
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for (&item_id, barcode) in item_ids.iter().zip(&data.barcodes) {
+            let already_borrowed = format!("Item {} is already borrowed", barcode);
+            self.loans_lock_free_item_tx(&mut tx, item_id, &already_borrowed)
+                .await?;
+            sqlx::query(
+                r#"
+                INSERT INTO loans (user_id, item_id, date, expiry_at, nb_renews, batch_id)
+                VALUES ($1, $2, $3, $4, 0, $5)
+                "#,
+            )
+            .bind(data.user_id)
+            .bind(item_id)
+            .bind(now)
+            .bind(due_at)
+            .bind(batch_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| super::loans::active_loan_conflict(e, &already_borrowed))?;
+
+            if data.force {
+                self.holds_cancel_active_for_item_tx(&mut tx, item_id).await?;
+            } else {
+                self.holds_fulfill_active_for_user_item_tx(&mut tx, data.user_id, item_id)
+                    .await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        self.loan_batches_get(batch_id).await
+    }
+
+    /// Move the shared due date; copies still out are renewed to the new date
+    #[tracing::instrument(skip(self), err)]
+    pub async fn loan_batches_extend(&self, id: i64, due_at: DateTime<Utc>) -> AppResult<u64> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query("UPDATE loan_batches SET due_at = $1, update_at = $2 WHERE id = $3")
+            .bind(due_at)
+            .bind(now)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Loan batch {} not found", id)));
+        }
+
+        let moved = sqlx::query(
+            r#"
+            UPDATE loans
+            SET expiry_at = $1, renew_at = $2, nb_renews = COALESCE(nb_renews, 0) + 1
+            WHERE batch_id = $3 AND returned_at IS NULL
+            "#,
+        )
+        .bind(due_at)
+        .bind(now)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(moved.rows_affected())
+    }
+
+    /// Return every copy still out (each return archives the loan and advances the hold queue)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn loan_batches_return(&self, id: i64) -> AppResult<usize> {
+        let loan_ids: Vec<i64> = sqlx::query_scalar(
+            "SELECT id FROM loans WHERE batch_id = $1 AND returned_at IS NULL ORDER BY id",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for &loan_id in &loan_ids {
+            self.loans_return(loan_id).await?;
+        }
+        Ok(loan_ids.len())
+    }
+}
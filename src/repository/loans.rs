@@ -1,8 +1,10 @@
 //! Loans domain methods on Repository
 
+use std::collections::HashMap;
+
 use async_trait::async_trait;
-use chrono::{DateTime, Duration, Utc};
-use sqlx::Row;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use sqlx::{Postgres, Row};
 
 use super::Repository;
 use crate::{
@@ -11,10 +13,12 @@ use crate::{
     models::{
         author::Author,
         biblio::{Biblio, BiblioShort, Collection, Edition, Isbn, Serie},
+        equipment::{Equipment, EquipmentShort},
         item::{Item, ItemShort},
         loan::{
-            CreateLoan, Loan, LoanDetails, LoanMarcExportRow, LoanReturnOutcome, LoanSettings,
-            LoanSettingsRenewAt,
+            CreateLoan, ItemFloatingFacts, Loan, LoanDetails, LoanMarcExportRow, LoanMatch, LoanRenewability,
+            LoanReturnOutcome, LoanSettings, LoanSettingsRenewAt, RenewalBlockCode, ReturnRouting,
+            ReturnRoutingAction,
         },
         user::{UserShort, UserShortRow},
     },
@@ -31,12 +35,22 @@ pub trait LoansRepository: Send + Sync {
         page: i64,
         per_page: i64,
     ) -> AppResult<(Vec<LoanDetails>, i64)>;
+    /// Active loans of many users (no pagination), keyed by user id.
+    async fn loans_get_active_for_users(&self, user_ids: &[i64]) -> AppResult<HashMap<i64, Vec<LoanDetails>>>;
     async fn loans_archives_get_for_user(
         &self,
         user_id: i64,
         page: i64,
         per_page: i64,
     ) -> AppResult<(Vec<LoanDetails>, i64)>;
+    /// Active and archived loans of a user whose title, author or barcode contains `q` (paginated).
+    async fn loans_search_for_user(
+        &self,
+        user_id: i64,
+        q: &str,
+        page: i64,
+        per_page: i64,
+    ) -> AppResult<(Vec<LoanDetails>, i64)>;
     /// All loans for MARC export (no pagination). Active or archived only.
     async fn loans_get_for_marc_export(
         &self,
@@ -45,6 +59,16 @@ pub trait LoansRepository: Send + Sync {
     ) -> AppResult<Vec<LoanMarcExportRow>>;
     async fn loans_create(&self, loan: &CreateLoan) -> AppResult<(i64, DateTime<Utc>)>;
     async fn loans_return(&self, loan_id: i64) -> AppResult<LoanReturnOutcome>;
+    /// Move loans returned before `returned_before` to loans_archives; returns how many were moved.
+    async fn loans_archive_returned(&self, returned_before: DateTime<Utc>) -> AppResult<u64>;
+    /// Loan-level renewal rules (the borrower's account is checked by the service).
+    async fn loans_renewability(&self, loan_id: i64) -> AppResult<LoanRenewability>;
+    /// Place, media type, collections and routing state of a copy (floating rules)
+    async fn loans_item_floating_facts(&self, item_id: i64) -> AppResult<ItemFloatingFacts>;
+    /// Record the routing of a copy checked in at another place than its own
+    async fn loans_route_item(&self, item_id: i64, routing: &ReturnRouting) -> AppResult<()>;
+    /// Clear the routing state of a copy back at its place (transit received, pending answer)
+    async fn loans_clear_item_routing(&self, item_id: i64) -> AppResult<()>;
     async fn loans_renew(&self, loan_id: i64) -> AppResult<(DateTime<Utc>, i16)>;
     async fn loans_get_settings(&self) -> AppResult<Vec<LoanSettings>>;
     async fn loans_count_active(&self) -> AppResult<i64>;
@@ -55,6 +79,7 @@ pub trait LoansRepository: Send + Sync {
     async fn loans_get_active_ids_for_user(&self, user_id: i64) -> AppResult<Vec<i64>>;
     async fn loans_count_active_for_biblio(&self, biblio_id: i64) -> AppResult<i64>;
     async fn loans_count_active_for_user(&self, user_id: i64) -> AppResult<i64>;
+    async fn loans_count_overdue_for_user(&self, user_id: i64) -> AppResult<i64>;
     async fn loans_get_overdue_for_reminders(
         &self,
         frequency_days: u32,
@@ -65,6 +90,15 @@ pub trait LoansRepository: Send + Sync {
         per_page: i64,
     ) -> AppResult<(Vec<OverdueLoanRow>, i64)>;
     async fn loans_update_reminder_sent(&self, loan_ids: &[i64]) -> AppResult<()>;
+    /// Active loans due in exactly one of `lead_days` days, not yet in a due-soon digest today.
+    async fn loans_get_due_soon(&self, lead_days: &[u32]) -> AppResult<Vec<OverdueLoanRow>>;
+    async fn loans_update_due_soon_notified(&self, loan_ids: &[i64]) -> AppResult<()>;
+    /// Move active loans due on `due_date` to `new_due_date` (time of day kept).
+    async fn loans_shift_due_dates(
+        &self,
+        due_date: NaiveDate,
+        new_due_date: NaiveDate,
+    ) -> AppResult<Vec<ShiftedLoanRow>>;
     /// Upsert global loan rules (`loans_settings`). `media_type == None` updates the default row (`media_type` IS NULL).
     async fn loans_settings_upsert_row(
         &self,
@@ -82,9 +116,25 @@ pub trait LoansRepository: Send + Sync {
 /// Combined repository trait used by [`crate::services::loans::LoansService`].
 ///
 /// Implemented by the concrete [`Repository`] via blanket impl below.
-pub trait LoansServiceRepository: LoansRepository + crate::repository::UsersRepository + Send + Sync {}
+pub trait LoansServiceRepository:
+    LoansRepository
+    + crate::repository::UsersRepository
+    + crate::repository::FinesRepository
+    + crate::repository::UserMessagesRepository
+    + Send
+    + Sync
+{
+}
 
-impl<T: LoansRepository + crate::repository::UsersRepository + Send + Sync> LoansServiceRepository for T {}
+impl<T> LoansServiceRepository for T where
+    T: LoansRepository
+        + crate::repository::UsersRepository
+        + crate::repository::FinesRepository
+        + crate::repository::UserMessagesRepository
+        + Send
+        + Sync
+{
+}
 
 // ---------------------------------------------------------------------------
 // Trait implementation — forwards to inherent methods above.
@@ -106,6 +156,12 @@ impl LoansRepository for Repository {
     ) -> crate::error::AppResult<(Vec<LoanDetails>, i64)> {
         Repository::loans_get_for_user(self, user_id, page, per_page).await
     }
+    async fn loans_get_active_for_users(
+        &self,
+        user_ids: &[i64],
+    ) -> crate::error::AppResult<HashMap<i64, Vec<LoanDetails>>> {
+        Repository::loans_get_active_for_users(self, user_ids).await
+    }
     async fn loans_archives_get_for_user(
         &self,
         user_id: i64,
@@ -114,6 +170,15 @@ impl LoansRepository for Repository {
     ) -> crate::error::AppResult<(Vec<LoanDetails>, i64)> {
         Repository::loans_archives_get_for_user(self, user_id, page, per_page).await
     }
+    async fn loans_search_for_user(
+        &self,
+        user_id: i64,
+        q: &str,
+        page: i64,
+        per_page: i64,
+    ) -> crate::error::AppResult<(Vec<LoanDetails>, i64)> {
+        Repository::loans_search_for_user(self, user_id, q, page, per_page).await
+    }
     async fn loans_get_for_marc_export(
         &self,
         user_id: i64,
@@ -127,6 +192,21 @@ impl LoansRepository for Repository {
     async fn loans_return(&self, loan_id: i64) -> crate::error::AppResult<LoanReturnOutcome> {
         Repository::loans_return(self, loan_id).await
     }
+    async fn loans_archive_returned(&self, returned_before: chrono::DateTime<chrono::Utc>) -> crate::error::AppResult<u64> {
+        Repository::loans_archive_returned(self, returned_before).await
+    }
+    async fn loans_renewability(&self, loan_id: i64) -> crate::error::AppResult<LoanRenewability> {
+        Repository::loans_renewability(self, loan_id).await
+    }
+    async fn loans_item_floating_facts(&self, item_id: i64) -> crate::error::AppResult<ItemFloatingFacts> {
+        Repository::loans_item_floating_facts(self, item_id).await
+    }
+    async fn loans_route_item(&self, item_id: i64, routing: &ReturnRouting) -> crate::error::AppResult<()> {
+        Repository::loans_route_item(self, item_id, routing).await
+    }
+    async fn loans_clear_item_routing(&self, item_id: i64) -> crate::error::AppResult<()> {
+        Repository::loans_clear_item_routing(self, item_id).await
+    }
     async fn loans_renew(&self, loan_id: i64) -> crate::error::AppResult<(chrono::DateTime<chrono::Utc>, i16)> {
         Repository::loans_renew(self, loan_id).await
     }
@@ -154,6 +234,9 @@ impl LoansRepository for Repository {
     async fn loans_count_active_for_biblio(&self, biblio_id: i64) -> crate::error::AppResult<i64> {
         Repository::loans_count_active_for_biblio(self, biblio_id).await
     }
+    async fn loans_count_overdue_for_user(&self, user_id: i64) -> crate::error::AppResult<i64> {
+        Repository::loans_count_overdue_for_user(self, user_id).await
+    }
     async fn loans_count_active_for_user(&self, user_id: i64) -> crate::error::AppResult<i64> {
         Repository::loans_count_active_for_user(self, user_id).await
     }
@@ -166,6 +249,15 @@ impl LoansRepository for Repository {
     async fn loans_update_reminder_sent(&self, loan_ids: &[i64]) -> crate::error::AppResult<()> {
         Repository::loans_update_reminder_sent(self, loan_ids).await
     }
+    async fn loans_get_due_soon(&self, lead_days: &[u32]) -> crate::error::AppResult<Vec<OverdueLoanRow>> {
+        Repository::loans_get_due_soon(self, lead_days).await
+    }
+    async fn loans_update_due_soon_notified(&self, loan_ids: &[i64]) -> crate::error::AppResult<()> {
+        Repository::loans_update_due_soon_notified(self, loan_ids).await
+    }
+    async fn loans_shift_due_dates(&self, due_date: NaiveDate, new_due_date: NaiveDate) -> crate::error::AppResult<Vec<ShiftedLoanRow>> {
+        Repository::loans_shift_due_dates(self, due_date, new_due_date).await
+    }
     async fn loans_settings_upsert_row(
         &self,
         media_type: Option<String>,
@@ -189,13 +281,21 @@ impl LoansRepository for Repository {
     }
 }
 
+/// Maximum active equipment loans per patron (overridable with `force`).
+pub const MAX_ACTIVE_EQUIPMENT_LOANS: i64 = 1;
+
 /// Scalar subquery (column alias `author`): first author on biblio `b` as JSON for [`BiblioShort`].
 const LOAN_DETAILS_FIRST_AUTHOR_SQL: &str = r#"(SELECT jsonb_build_object(
                 'id', a.id::text, 'lastname', a.lastname, 'firstname', a.firstname,
-                'bio', a.bio, 'notes', a.notes, 'function', ba.function
+                'displayName', a.display_name, 'bio', a.bio, 'notes', a.notes, 'function', ba.function
             ) FROM biblio_authors ba JOIN authors a ON a.id = ba.author_id
             WHERE ba.biblio_id = b.id ORDER BY ba.position LIMIT 1) as author"#;
 
+/// Equipment columns for loan detail queries (`e` = `LEFT JOIN equipment`); all NULL for copy loans.
+const LOAN_DETAILS_EQUIPMENT_SQL: &str = r#"e.id as equipment_id, e.name as equipment_name,
+                e.equipment_type, e.barcode as equipment_barcode,
+                e.requires_deposit as equipment_requires_deposit"#;
+
 impl Repository {
     /// Resolve loan settings: (duration_days, nb_max_media, nb_max_total_all_media, nb_renews, renew_at_policy).
     ///
@@ -251,6 +351,18 @@ impl Repository {
             None
         };
 
+        // Default loan rules of the media type (`media_types`), below its `loans_settings` row
+        let mt_spec = if let Some(mt) = media_type {
+            sqlx::query(
+                "SELECT loan_duration AS duration, loan_nb_max AS nb_max, loan_nb_renews AS nb_renews FROM media_types WHERE code = $1",
+            )
+            .bind(mt)
+            .fetch_optional(&self.pool)
+            .await?
+        } else {
+            None
+        };
+
         let ls_default = sqlx::query(
             "SELECT duration, nb_max, nb_renews, renew_at FROM loans_settings WHERE media_type IS NULL",
         )
@@ -262,6 +374,7 @@ impl Repository {
             .and_then(|r| r.get::<Option<i16>, _>("duration"))
             .or_else(|| ptls_default.as_ref().and_then(|r| r.get::<Option<i16>, _>("duration")))
             .or_else(|| ls_spec.as_ref().and_then(|r| r.get::<Option<i16>, _>("duration")))
+            .or_else(|| mt_spec.as_ref().and_then(|r| r.get::<Option<i16>, _>("duration")))
             .or_else(|| ls_default.as_ref().and_then(|r| r.get::<Option<i16>, _>("duration")))
             .unwrap_or(default_duration);
 
@@ -269,6 +382,7 @@ impl Repository {
             .as_ref()
             .and_then(|r| r.get::<Option<i16>, _>("nb_max"))
             .or_else(|| ls_spec.as_ref().and_then(|r| r.get::<Option<i16>, _>("nb_max")))
+            .or_else(|| mt_spec.as_ref().and_then(|r| r.get::<Option<i16>, _>("nb_max")))
             .unwrap_or(default_nb_max_media);
 
         let nb_max_total = ptls_default
@@ -282,6 +396,7 @@ impl Repository {
             .and_then(|r| r.get::<Option<i16>, _>("nb_renews"))
             .or_else(|| ptls_default.as_ref().and_then(|r| r.get::<Option<i16>, _>("nb_renews")))
             .or_else(|| ls_spec.as_ref().and_then(|r| r.get::<Option<i16>, _>("nb_renews")))
+            .or_else(|| mt_spec.as_ref().and_then(|r| r.get::<Option<i16>, _>("nb_renews")))
             .or_else(|| ls_default.as_ref().and_then(|r| r.get::<Option<i16>, _>("nb_renews")))
             .unwrap_or(default_nb_renews);
 
@@ -308,8 +423,9 @@ impl Repository {
         sqlx::query_as::<_, Loan>(
             r#"
             SELECT l.* FROM loans l
-            JOIN items it ON l.item_id = it.id
-            WHERE it.barcode = $1 AND l.returned_at IS NULL
+            LEFT JOIN items it ON l.item_id = it.id
+            LEFT JOIN equipment e ON l.equipment_id = e.id
+            WHERE (it.barcode = $1 OR e.barcode = $1) AND l.returned_at IS NULL
             ORDER BY l.id DESC LIMIT 1
             "#
         )
@@ -339,22 +455,24 @@ impl Repository {
             r#"
             SELECT l.id, l.date, l.renew_at, l.nb_renews, l.expiry_at,
                    l.returned_at,
-                   it.barcode as item_identification,
+                   COALESCE(it.barcode, e.barcode) as item_identification,
                    it.id as item_copy_id, it.barcode as item_barcode,
                    it.call_number as item_call_number, it.borrowable as item_borrowable,
                    so.name as item_source_name,
                    b.id as biblio_id, b.media_type, b.isbn as biblio_isbn,
                    b.title, b.publication_date,
+                   {},
                    {}
             FROM loans l
-            JOIN items it ON l.item_id = it.id
+            LEFT JOIN items it ON l.item_id = it.id
             LEFT JOIN sources so ON it.source_id = so.id
-            JOIN biblios b ON it.biblio_id = b.id
+            LEFT JOIN biblios b ON it.biblio_id = b.id
+            LEFT JOIN equipment e ON l.equipment_id = e.id
             WHERE l.user_id = $1 AND l.returned_at IS NULL
             ORDER BY l.expiry_at
             LIMIT $2 OFFSET $3
         "#,
-            LOAN_DETAILS_FIRST_AUTHOR_SQL
+            LOAN_DETAILS_FIRST_AUTHOR_SQL, LOAN_DETAILS_EQUIPMENT_SQL
         );
 
         let rows = sqlx::query(&sql)
@@ -367,7 +485,49 @@ impl Repository {
         Ok((Self::map_loan_rows(rows), total))
     }
 
-    /// Get archived (returned) loans for a user (paginated).
+    /// Active loans for many users, by due date, keyed by user id.
+    pub async fn loans_get_active_for_users(
+        &self,
+        user_ids: &[i64],
+    ) -> AppResult<HashMap<i64, Vec<LoanDetails>>> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let sql = format!(
+            r#"
+            SELECT l.id, l.user_id, l.date, l.renew_at, l.nb_renews, l.expiry_at,
+                   l.returned_at,
+                   COALESCE(it.barcode, e.barcode) as item_identification,
+                   it.id as item_copy_id, it.barcode as item_barcode,
+                   it.call_number as item_call_number, it.borrowable as item_borrowable,
+                   so.name as item_source_name,
+                   b.id as biblio_id, b.media_type, b.isbn as biblio_isbn,
+                   b.title, b.publication_date,
+                   {},
+                   {}
+            FROM loans l
+            LEFT JOIN items it ON l.item_id = it.id
+            LEFT JOIN sources so ON it.source_id = so.id
+            LEFT JOIN biblios b ON it.biblio_id = b.id
+            LEFT JOIN equipment e ON l.equipment_id = e.id
+            WHERE l.user_id = ANY($1) AND l.returned_at IS NULL
+            ORDER BY l.expiry_at
+        "#,
+            LOAN_DETAILS_FIRST_AUTHOR_SQL, LOAN_DETAILS_EQUIPMENT_SQL
+        );
+
+        let rows = sqlx::query(&sql).bind(user_ids).fetch_all(&self.pool).await?;
+        let owners: Vec<i64> = rows.iter().map(|row| row.get("user_id")).collect();
+
+        let mut map: HashMap<i64, Vec<LoanDetails>> = HashMap::new();
+        for (user_id, loan) in owners.into_iter().zip(Self::map_loan_rows(rows)) {
+            map.entry(user_id).or_default().push(loan);
+        }
+        Ok(map)
+    }
+
+    /// Get returned loans for a user (paginated), archived or not yet.
     pub async fn loans_archives_get_for_user(
         &self,
         user_id: i64,
@@ -377,7 +537,12 @@ impl Repository {
         let offset = (page - 1) * per_page;
 
         let total: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*)::bigint FROM loans_archives la WHERE la.user_id = $1",
+            r#"
+            SELECT COUNT(*)::bigint FROM loans_all la
+            WHERE la.user_id = $1 AND la.returned_at IS NOT NULL
+              AND (EXISTS(SELECT 1 FROM items it WHERE it.id = la.item_id)
+                   OR EXISTS(SELECT 1 FROM equipment e WHERE e.id = la.equipment_id))
+            "#,
         )
         .bind(user_id)
         .fetch_one(&self.pool)
@@ -385,25 +550,28 @@ impl Repository {
 
         let sql = format!(
             r#"
-            SELECT la.id, la.date, NULL::timestamptz as renew_at, la.nb_renews,
+            SELECT la.id, la.date, la.renew_at, la.nb_renews,
                    la.expiry_at, la.returned_at,
-                   it.barcode as item_identification,
+                   COALESCE(it.barcode, e.barcode) as item_identification,
                    it.id as item_copy_id, it.barcode as item_barcode,
                    it.call_number as item_call_number, it.borrowable as item_borrowable,
                    so.name as item_source_name,
 
                    b.id as biblio_id, b.media_type, b.isbn as biblio_isbn,
                    b.title, b.publication_date,
+                   {},
                    {}
-            FROM loans_archives la
-            JOIN items it ON la.item_id = it.id
+            FROM loans_all la
+            LEFT JOIN items it ON la.item_id = it.id
             LEFT JOIN sources so ON it.source_id = so.id
-            JOIN biblios b ON it.biblio_id = b.id
-            WHERE la.user_id = $1
+            LEFT JOIN biblios b ON it.biblio_id = b.id
+            LEFT JOIN equipment e ON la.equipment_id = e.id
+            WHERE la.user_id = $1 AND la.returned_at IS NOT NULL
+              AND (it.id IS NOT NULL OR e.id IS NOT NULL)
             ORDER BY la.returned_at DESC
             LIMIT $2 OFFSET $3
         "#,
-            LOAN_DETAILS_FIRST_AUTHOR_SQL
+            LOAN_DETAILS_FIRST_AUTHOR_SQL, LOAN_DETAILS_EQUIPMENT_SQL
         );
 
         let rows = sqlx::query(&sql)
@@ -416,6 +584,85 @@ impl Repository {
         Ok((Self::map_loan_rows(rows), total))
     }
 
+    /// Active and archived loans of a user whose title, any author or barcode contains `q`
+    /// (case and accents ignored), active ones first then by return date. Each loan carries the
+    /// field that matched in `matched`.
+    pub async fn loans_search_for_user(
+        &self,
+        user_id: i64,
+        q: &str,
+        page: i64,
+        per_page: i64,
+    ) -> AppResult<(Vec<LoanDetails>, i64)> {
+        let offset = (page - 1) * per_page;
+        let like = format!("%{}%", super::biblios::like_escape(q.trim()));
+
+        // Active and archived loans of the user matching $2
+        let from = r#"
+            FROM loans_all m
+            LEFT JOIN items it ON m.item_id = it.id
+            LEFT JOIN sources so ON it.source_id = so.id
+            LEFT JOIN biblios b ON it.biblio_id = b.id
+            LEFT JOIN equipment e ON m.equipment_id = e.id
+            WHERE m.user_id = $1
+              AND (it.id IS NOT NULL OR e.id IS NOT NULL)
+              AND (b.title_normalized LIKE normalize_search($2)
+                   OR EXISTS (SELECT 1 FROM biblio_authors ba JOIN authors a ON a.id = ba.author_id
+                              WHERE ba.biblio_id = b.id
+                                AND concat_ws(' ', a.firstname_normalized, a.lastname_normalized) LIKE normalize_search($2))
+                   OR lower(COALESCE(it.barcode, e.barcode)) LIKE lower($2))
+        "#;
+
+        let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*)::bigint {}", from))
+            .bind(user_id)
+            .bind(&like)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let sql = format!(
+            r#"
+            SELECT m.id, m.date, m.renew_at, m.nb_renews, m.expiry_at, m.returned_at,
+                   COALESCE(it.barcode, e.barcode) as item_identification,
+                   it.id as item_copy_id, it.barcode as item_barcode,
+                   it.call_number as item_call_number, it.borrowable as item_borrowable,
+                   so.name as item_source_name,
+                   b.id as biblio_id, b.media_type, b.isbn as biblio_isbn,
+                   b.title, b.publication_date,
+                   {},
+                   {},
+                   (SELECT COALESCE(a.display_name, concat_ws(' ', a.firstname, a.lastname))
+                    FROM biblio_authors ba JOIN authors a ON a.id = ba.author_id
+                    WHERE ba.biblio_id = b.id
+                      AND concat_ws(' ', a.firstname_normalized, a.lastname_normalized) LIKE normalize_search($2)
+                    ORDER BY ba.position LIMIT 1) as matched_author
+            {}
+            ORDER BY (m.returned_at IS NULL) DESC, m.returned_at DESC, m.date DESC
+            LIMIT $3 OFFSET $4
+        "#,
+            LOAN_DETAILS_FIRST_AUTHOR_SQL, LOAN_DETAILS_EQUIPMENT_SQL, from
+        );
+
+        let rows = sqlx::query(&sql)
+            .bind(user_id)
+            .bind(&like)
+            .bind(per_page)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+        let matched_authors: Vec<Option<String>> = rows.iter().map(|row| row.get("matched_author")).collect();
+
+        let loans = Self::map_loan_rows(rows)
+            .into_iter()
+            .zip(matched_authors)
+            .map(|(mut loan, author)| {
+                let title = loan.biblio.as_ref().and_then(|b| b.title.as_deref());
+                loan.matched = LoanMatch::find(q, title, author.as_deref(), loan.item_identification.as_deref());
+                loan
+            })
+            .collect();
+        Ok((loans, total))
+    }
+
     /// All loans for one user for MARC file export (no pagination): one round-trip with full [`Biblio`] per row.
     pub async fn loans_get_for_marc_export(
         &self,
@@ -432,6 +679,8 @@ impl Repository {
             b.title,
             b.subject,
             b.audience_type,
+            b.reading_level,
+            b.accessibility,
             b.page_extent,
             b.format,
             b.table_of_contents,
@@ -452,6 +701,7 @@ impl Repository {
                         'key', a.key,
                         'lastname', a.lastname,
                         'firstname', a.firstname,
+                        'displayName', a.display_name,
                         'bio', a.bio,
                         'notes', a.notes,
                         'function', ba.function
@@ -518,9 +768,14 @@ impl Repository {
             it.circulation_status AS item_circulation_status,
             it.notes AS item_notes,
             it.price AS item_price,
+            it.access_url AS item_access_url,
+            it.access_type AS item_access_type,
             it.created_at AS item_created_at,
             it.updated_at AS item_updated_at,
             it.archived_at AS item_archived_at,
+            it.deposit_id AS item_deposit_id,
+            it.transit_place AS item_transit_place,
+            it.inventory_number AS item_inventory_number,
             so.name AS item_source_name,
             EXISTS(
                 SELECT 1 FROM loans ln WHERE ln.item_id = it.id AND ln.returned_at IS NULL
@@ -535,11 +790,11 @@ impl Repository {
                     la.expiry_at,
                     la.returned_at,
                     {BIBLIO_MARC_EXPORT_SELECT}
-                FROM loans_archives la
+                FROM loans_all la
                 JOIN items it ON la.item_id = it.id
                 LEFT JOIN sources so ON it.source_id = so.id
                 JOIN biblios b ON it.biblio_id = b.id
-                WHERE la.user_id = $1
+                WHERE la.user_id = $1 AND la.returned_at IS NOT NULL
                 ORDER BY la.returned_at DESC NULLS LAST
                 "#
             )
@@ -652,11 +907,19 @@ impl Repository {
             circulation_status: row.try_get("item_circulation_status").ok().flatten(),
             notes: row.try_get("item_notes").ok().flatten(),
             price: row.try_get("item_price").ok().flatten(),
+            access_url: row.try_get("item_access_url").ok().flatten(),
+            access_type: row.try_get("item_access_type").ok().flatten(),
             created_at: row.try_get("item_created_at").ok().flatten(),
             updated_at: row.try_get("item_updated_at").ok().flatten(),
             archived_at: row.try_get("item_archived_at").ok().flatten(),
             source_name: row.try_get("item_source_name").ok().flatten(),
             borrowed: row.try_get("item_borrowed").unwrap_or(false),
+            deposit_id: row.try_get("item_deposit_id").ok().flatten(),
+            transit_place: row.try_get("item_transit_place").ok().flatten(),
+            inventory_number: row.try_get("item_inventory_number").ok().flatten(),
+            location_id: None,
+            location_path: None,
+            hidden_from_opac: false,
         };
 
         let series_ids: Vec<i64> = series.iter().filter_map(|s| s.id).collect();
@@ -685,6 +948,8 @@ impl Repository {
             abstract_: row.try_get("abstract_").ok().flatten(),
             notes: row.try_get("notes").ok().flatten(),
             keywords: row.try_get("keywords").ok().flatten(),
+            reading_level: row.try_get("reading_level").ok().flatten(),
+            accessibility: row.try_get("accessibility").unwrap_or_default(),
             is_valid: row.try_get("is_valid").ok().flatten(),
             series_ids,
             series_volume_numbers,
@@ -700,6 +965,7 @@ impl Repository {
             edition,
             items: vec![item],
             marc_record,
+            edit_lock: None,
         };
 
         Ok(LoanMarcExportRow {
@@ -717,66 +983,122 @@ impl Repository {
             let expiry_at: Option<DateTime<Utc>> = row.get("expiry_at");
             let renew_at: Option<DateTime<Utc>> = row.get("renew_at");
             let returned_at: Option<DateTime<Utc>> = row.get("returned_at");
-
-            let borrowed_item = ItemShort {
-                id: row.get("item_copy_id"),
-                barcode: row.get("item_barcode"),
-                call_number: row.get("item_call_number"),
-                borrowable: row.get("item_borrowable"),
-                source_name: row.get("item_source_name"),
-                borrowed: true,
-            };
+            let item_id: Option<i64> = row.get("item_copy_id");
+
+            let biblio_id: Option<i64> = row.get("biblio_id");
+            let biblio = biblio_id.zip(item_id).map(|(biblio_id, item_id)| BiblioShort {
+                id: biblio_id,
+                media_type: row.get("media_type"),
+                isbn: row
+                    .get::<Option<String>, _>("biblio_isbn")
+                    .map(Isbn::new)
+                    .filter(|i| !i.is_empty()),
+                title: row.get("title"),
+                date: row.get("publication_date"),
+                status: 0,
+                is_valid: Some(true),
+                archived_at: None,
+                author: row.get::<Option<serde_json::Value>, _>("author")
+                    .and_then(|v| serde_json::from_value(v).ok()),
+                items: vec![ItemShort {
+                    id: item_id,
+                    barcode: row.get("item_barcode"),
+                    call_number: row.get("item_call_number"),
+                    borrowable: row.get::<Option<bool>, _>("item_borrowable").unwrap_or(true),
+                    source_name: row.get("item_source_name"),
+                    borrowed: true,
+                    hidden_from_opac: false,
+                }],
+            });
 
             LoanDetails {
                 id: row.get("id"),
-                item_id: row.get("item_copy_id"),
+                item_id,
                 start_date,
                 expiry_at: expiry_at.unwrap_or(now),
                 renewal_date: renew_at,
                 nb_renews: row.get::<Option<i16>, _>("nb_renews").unwrap_or(0),
                 returned_at,
-                biblio: BiblioShort {
-                    id: row.get("biblio_id"),
-                    media_type: row.get("media_type"),
-                    isbn: row
-                        .get::<Option<String>, _>("biblio_isbn")
-                        .map(Isbn::new)
-                        .filter(|i| !i.is_empty()),
-                    title: row.get("title"),
-                    date: row.get("publication_date"),
-                    status: 0,
-                    is_valid: Some(true),
-                    archived_at: None,
-                    author: row.get::<Option<serde_json::Value>, _>("author")
-                        .and_then(|v| serde_json::from_value(v).ok()),
-                    items: vec![borrowed_item],
-                },
+                biblio,
+                equipment: Self::equipment_short_from_row(&row),
                 user: None,
                 item_identification: row.get("item_identification"),
                 is_overdue: returned_at.is_none() && expiry_at.map(|d| d < now).unwrap_or(false),
+                matched: None,
             }
         }).collect()
     }
 
+    /// Equipment summary from [`LOAN_DETAILS_EQUIPMENT_SQL`] columns (`None` for copy loans).
+    fn equipment_short_from_row(row: &sqlx::postgres::PgRow) -> Option<EquipmentShort> {
+        let id: i64 = row.get::<Option<i64>, _>("equipment_id")?;
+        Some(EquipmentShort {
+            id,
+            name: row.get::<Option<String>, _>("equipment_name").unwrap_or_default(),
+            equipment_type: row.get::<Option<i16>, _>("equipment_type").unwrap_or(0),
+            barcode: row.get("equipment_barcode"),
+            requires_deposit: row
+                .get::<Option<bool>, _>("equipment_requires_deposit")
+                .unwrap_or(false),
+        })
+    }
+
     /// Create a new loan
     pub async fn loans_create(&self, loan: &CreateLoan) -> AppResult<(i64, DateTime<Utc>)> {
         let now = Utc::now();
 
-        // Get item (physical copy) ID
+        // Get item (physical copy) ID; a barcode matching no copy may designate equipment.
         let item_id = if let Some(id) = loan.item_id {
             id
+        } else if let Some(equipment_id) = loan.equipment_id {
+            return self.loans_create_equipment(loan, equipment_id).await;
         } else if let Some(ref identification) = loan.item_identification {
-            sqlx::query_scalar::<_, i64>(
+            let item_id = sqlx::query_scalar::<_, i64>(
                 "SELECT id FROM items WHERE barcode = $1"
             )
             .bind(identification)
             .fetch_optional(&self.pool)
-            .await?
-            .ok_or_else(|| AppError::NotFound("Item not found".to_string()))?
+            .await?;
+            match item_id {
+                Some(id) => id,
+                None => {
+                    let bundle_id = sqlx::query_scalar::<_, i64>(
+                        "SELECT id FROM item_bundles WHERE barcode = $1"
+                    )
+                    .bind(identification)
+                    .fetch_optional(&self.pool)
+                    .await?;
+                    if let Some(bundle_id) = bundle_id {
+                        return self.loans_create_bundle(loan, bundle_id).await;
+                    }
+                    let equipment_id = sqlx::query_scalar::<_, i64>(
+                        "SELECT id FROM equipment WHERE barcode = $1"
+                    )
+                    .bind(identification)
+                    .fetch_optional(&self.pool)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound("Item not found".to_string()))?;
+                    return self.loans_create_equipment(loan, equipment_id).await;
+                }
+            }
         } else {
-            return Err(AppError::BadRequest("item_id or item_identification required".to_string()));
+            return Err(AppError::BadRequest(
+                "item_id, equipment_id or item_identification required".to_string(),
+            ));
         };
 
+        // Parts of a bundle circulate with the rest of the set
+        let bundle_id: Option<i64> = sqlx::query_scalar::<_, Option<i64>>(
+            "SELECT bundle_id FROM items WHERE id = $1"
+        )
+        .bind(item_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .flatten();
+        if let Some(bundle_id) = bundle_id {
+            return self.loans_create_bundle(loan, bundle_id).await;
+        }
+
         // Check if item is already borrowed
         let loan_id: Option<i64> = sqlx::query_scalar::<_, i64>(
             "SELECT id FROM loans WHERE item_id = $1 AND returned_at IS NULL"
@@ -797,9 +1119,11 @@ impl Repository {
         // Get item info and loan settings
         let item_row = sqlx::query(
             r#"
-            SELECT it.borrowable, b.media_type
+            SELECT it.borrowable, b.media_type,
+                   st.label AS state_label, COALESCE(st.blocks_circulation, FALSE) AS state_blocks
             FROM items it
             JOIN biblios b ON it.biblio_id = b.id
+            LEFT JOIN item_states st ON st.code = it.circulation_status
             WHERE it.id = $1
             "#
         )
@@ -813,6 +1137,13 @@ impl Repository {
         if !borrowable && !loan.force {
             return Err(AppError::BusinessRule("Item is not borrowable".to_string()));
         }
+        if item_row.get::<bool, _>("state_blocks") && !loan.force {
+            let label: Option<String> = item_row.get("state_label");
+            return Err(AppError::BusinessRule(format!(
+                "Item state '{}' blocks circulation",
+                label.unwrap_or_default()
+            )));
+        }
 
         let user_public_type: Option<i64> = sqlx::query_scalar::<_, Option<i64>>(
             "SELECT public_type FROM users WHERE id = $1"
@@ -828,49 +1159,9 @@ impl Repository {
 
         let expiry_at = now + Duration::days(duration_days as i64);
 
-        let current_loans_total: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM loans WHERE user_id = $1 AND returned_at IS NULL"
-        )
-        .bind(loan.user_id)
-        .fetch_one(&self.pool)
-        .await?;
-        let current_loans_media: i64 = if let Some(ref mt) = media_type {
-            sqlx::query_scalar(
-                r#"
-                SELECT COUNT(*) FROM loans l
-                JOIN items it ON l.item_id = it.id
-                JOIN biblios b ON it.biblio_id = b.id
-                WHERE l.user_id = $1 AND l.returned_at IS NULL AND b.media_type = $2
-                "#
-            )
-            .bind(loan.user_id)
-            .bind(mt)
-            .fetch_one(&self.pool)
-            .await?
-        } else {
-            0
-        };
-
-        let total_limit_reached = current_loans_total >= nb_max_total as i64;
-        let media_limit_reached = current_loans_media >= nb_max_media as i64;
-
-        if (total_limit_reached || media_limit_reached) && !loan.force {
-            let msg = match (total_limit_reached, media_limit_reached) {
-                (true, true) => format!(
-                    "Maximum loans reached: total ({}/{}), this media type ({}/{})",
-                    current_loans_total, nb_max_total, current_loans_media, nb_max_media
-                ),
-                (true, false) => format!(
-                    "Maximum total loans reached ({}/{})",
-                    current_loans_total, nb_max_total
-                ),
-                (false, true) => format!(
-                    "Maximum loans for this document type reached ({}/{})",
-                    current_loans_media, nb_max_media
-                ),
-                (false, false) => unreachable!(),
-            };
-            return Err(AppError::BusinessRule(msg));
+        if !loan.force {
+            self.loans_check_quotas(loan.user_id, media_type.as_deref(), nb_max_media, nb_max_total)
+                .await?;
         }
 
         // Hold queue: only the patron whose turn it is (`ready`, else first `pending`) may borrow,
@@ -886,6 +1177,8 @@ impl Repository {
         }
 
         let mut tx = self.pool.begin().await?;
+        self.loans_lock_free_item_tx(&mut tx, item_id, "Item is already borrowed")
+            .await?;
 
         let loan_id = sqlx::query_scalar::<_, i64>(
             r#"
@@ -899,7 +1192,8 @@ impl Repository {
         .bind(now)
         .bind(expiry_at)
         .fetch_one(&mut *tx)
-        .await?;
+        .await
+        .map_err(|e| active_loan_conflict(e, "Item is already borrowed"))?;
 
         if loan.force {
             self.holds_cancel_active_for_item_tx(&mut tx, item_id).await?;
@@ -913,40 +1207,356 @@ impl Repository {
         Ok((loan_id, expiry_at))
     }
 
-    /// Return a loan (moves it to loans_archives).
-    pub async fn loans_return(&self, loan_id: i64) -> AppResult<LoanReturnOutcome> {
-        let now = Utc::now();
-
-        let loan = self.loans_get_by_id(loan_id).await?;
+    /// Lock the copy row until the checkout transaction ends and fail when it is on loan.
+    ///
+    /// Availability is checked before the transaction; the lock serializes desks checking out
+    /// the same copy, so the second one sees the first loan here instead of inserting its own.
+    pub(crate) async fn loans_lock_free_item_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        item_id: i64,
+        already_borrowed: &str,
+    ) -> AppResult<()> {
+        sqlx::query("SELECT id FROM items WHERE id = $1 FOR UPDATE")
+            .bind(item_id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Item not found".to_string()))?;
 
-        if loan.returned_at.is_some() {
-            return Err(AppError::BusinessRule("Loan already returned".to_string()));
+        let borrowed: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM loans WHERE item_id = $1 AND returned_at IS NULL)"
+        )
+        .bind(item_id)
+        .fetch_one(&mut **tx)
+        .await?;
+        if borrowed {
+            return Err(AppError::BusinessRule(already_borrowed.to_string()));
         }
+        Ok(())
+    }
 
-        let user_row = sqlx::query(
-            "SELECT addr_city, account_type, public_type FROM users WHERE id = $1"
+    /// Fail when the patron has reached the total or per media type loan quota.
+    ///
+    /// Equipment loans have their own cap and do not count against document quotas; a bundle
+    /// checked out as a set counts as one loan.
+    async fn loans_check_quotas(
+        &self,
+        user_id: i64,
+        media_type: Option<&str>,
+        nb_max_media: i16,
+        nb_max_total: i16,
+    ) -> AppResult<()> {
+        let current_loans_total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FILTER (WHERE bundle_id IS NULL) + COUNT(DISTINCT bundle_id)
+            FROM loans
+            WHERE user_id = $1 AND item_id IS NOT NULL AND returned_at IS NULL
+            "#
         )
-        .bind(loan.user_id)
-        .fetch_optional(&self.pool)
+        .bind(user_id)
+        .fetch_one(&self.pool)
         .await?;
+        let current_loans_media: i64 = if let Some(mt) = media_type {
+            sqlx::query_scalar(
+                r#"
+                SELECT COUNT(*) FILTER (WHERE l.bundle_id IS NULL) + COUNT(DISTINCT l.bundle_id)
+                FROM loans l
+                JOIN items it ON l.item_id = it.id
+                JOIN biblios b ON it.biblio_id = b.id
+                WHERE l.user_id = $1 AND l.returned_at IS NULL AND b.media_type = $2
+                "#
+            )
+            .bind(user_id)
+            .bind(mt)
+            .fetch_one(&self.pool)
+            .await?
+        } else {
+            0
+        };
 
-        let account_type: Option<String> = user_row.as_ref().and_then(|r| r.get("account_type"));
+        let total_limit_reached = current_loans_total >= nb_max_total as i64;
+        let media_limit_reached = current_loans_media >= nb_max_media as i64;
 
-        let mut tx = self.pool.begin().await?;
+        if total_limit_reached || media_limit_reached {
+            let msg = match (total_limit_reached, media_limit_reached) {
+                (true, true) => format!(
+                    "Maximum loans reached: total ({}/{}), this media type ({}/{})",
+                    current_loans_total, nb_max_total, current_loans_media, nb_max_media
+                ),
+                (true, false) => format!(
+                    "Maximum total loans reached ({}/{})",
+                    current_loans_total, nb_max_total
+                ),
+                (false, true) => format!(
+                    "Maximum loans for this document type reached ({}/{})",
+                    current_loans_media, nb_max_media
+                ),
+                (false, false) => unreachable!(),
+            };
+            return Err(AppError::BusinessRule(msg));
+        }
+        Ok(())
+    }
 
-        sqlx::query(
+    /// Check out every part of a bundle with one due date.
+    ///
+    /// Copy rules apply to each part (already borrowed, borrowable flag, blocking state, hold
+    /// queue); loan settings are those of the first part's media type and the set counts as one
+    /// loan in the quotas. `force` overrides each rule (and returns parts still out).
+    /// Returns the loan of the first part.
+    async fn loans_create_bundle(
+        &self,
+        loan: &CreateLoan,
+        bundle_id: i64,
+    ) -> AppResult<(i64, DateTime<Utc>)> {
+        let now = Utc::now();
+
+        let parts = sqlx::query(
             r#"
-            INSERT INTO loans_archives (
-                user_id, item_id, date, nb_renews, expiry_at,
-                returned_at, notes, borrower_public_type,
-                addr_city, account_type
-            )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            SELECT it.id, it.barcode, it.borrowable, b.media_type,
+                   st.label AS state_label, COALESCE(st.blocks_circulation, FALSE) AS state_blocks,
+                   (SELECT l.id FROM loans l
+                    WHERE l.item_id = it.id AND l.returned_at IS NULL
+                    ORDER BY l.id LIMIT 1) AS loan_id
+            FROM items it
+            JOIN biblios b ON it.biblio_id = b.id
+            LEFT JOIN item_states st ON st.code = it.circulation_status
+            WHERE it.bundle_id = $1 AND it.archived_at IS NULL
+            ORDER BY it.call_number, it.volume_designation, it.barcode, it.id
             "#
         )
-        .bind(loan.user_id)
-        .bind(loan.item_id)
-        .bind(loan.date)
+        .bind(bundle_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        if parts.is_empty() {
+            return Err(AppError::BusinessRule("Bundle has no parts".to_string()));
+        }
+
+        for part in &parts {
+            let barcode: Option<String> = part.get("barcode");
+            let barcode = barcode.unwrap_or_default();
+            if let Some(active_loan_id) = part.get::<Option<i64>, _>("loan_id") {
+                if !loan.force {
+                    return Err(AppError::BusinessRule(format!(
+                        "Bundle part {} is already borrowed",
+                        barcode
+                    )));
+                }
+                self.loans_return(active_loan_id).await?;
+            }
+            if loan.force {
+                continue;
+            }
+            if !part.get::<bool, _>("borrowable") {
+                return Err(AppError::BusinessRule(format!(
+                    "Bundle part {} is not borrowable",
+                    barcode
+                )));
+            }
+            if part.get::<bool, _>("state_blocks") {
+                let label: Option<String> = part.get("state_label");
+                return Err(AppError::BusinessRule(format!(
+                    "Bundle part {}: state '{}' blocks circulation",
+                    barcode,
+                    label.unwrap_or_default()
+                )));
+            }
+            if let Some(eligible) = self.holds_eligible_borrower_for_item(part.get("id")).await? {
+                if eligible != loan.user_id {
+                    return Err(AppError::BusinessRule(format!(
+                        "Bundle part {} has an active hold for another patron — use force=true to override",
+                        barcode
+                    )));
+                }
+            }
+        }
+
+        let media_type: Option<String> = parts[0].get("media_type");
+        let user_public_type: Option<i64> = sqlx::query_scalar::<_, Option<i64>>(
+            "SELECT public_type FROM users WHERE id = $1"
+        )
+        .bind(loan.user_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .flatten();
+
+        let (duration_days, nb_max_media, nb_max_total, _, _) = self
+            .resolve_loan_settings(user_public_type, media_type.as_deref())
+            .await?;
+
+        if !loan.force {
+            self.loans_check_quotas(loan.user_id, media_type.as_deref(), nb_max_media, nb_max_total)
+                .await?;
+        }
+
+        let expiry_at = now + Duration::days(duration_days as i64);
+
+        let mut tx = self.pool.begin().await?;
+        let mut first_loan_id = None;
+        for part in &parts {
+            let item_id: i64 = part.get("id");
+            let already_borrowed = format!(
+                "Bundle part {} is already borrowed",
+                part.get::<Option<String>, _>("barcode").unwrap_or_default()
+            );
+            self.loans_lock_free_item_tx(&mut tx, item_id, &already_borrowed)
+                .await?;
+            let loan_id = sqlx::query_scalar::<_, i64>(
+                r#"
+                INSERT INTO loans (user_id, item_id, date, expiry_at, nb_renews, bundle_id)
+                VALUES ($1, $2, $3, $4, 0, $5)
+                RETURNING id
+                "#
+            )
+            .bind(loan.user_id)
+            .bind(item_id)
+            .bind(now)
+            .bind(expiry_at)
+            .bind(bundle_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| active_loan_conflict(e, &already_borrowed))?;
+            first_loan_id.get_or_insert(loan_id);
+
+            if loan.force {
+                self.holds_cancel_active_for_item_tx(&mut tx, item_id).await?;
+            } else {
+                self.holds_fulfill_active_for_user_item_tx(&mut tx, loan.user_id, item_id)
+                    .await?;
+            }
+        }
+        tx.commit().await?;
+
+        Ok((first_loan_id.unwrap_or_default(), expiry_at))
+    }
+
+    /// Check out a piece of equipment.
+    ///
+    /// Equipment policy replaces `loans_settings`: loan period from the equipment record, at most
+    /// [`MAX_ACTIVE_EQUIPMENT_LOANS`] per patron, and deposit confirmation when required.
+    /// `force` overrides each rule (and returns a still-open loan on the same equipment).
+    async fn loans_create_equipment(
+        &self,
+        loan: &CreateLoan,
+        equipment_id: i64,
+    ) -> AppResult<(i64, DateTime<Utc>)> {
+        let now = Utc::now();
+        let equipment: Equipment = self.equipment_get_by_id(equipment_id).await?;
+
+        if !equipment.is_circulating() && !loan.force {
+            return Err(AppError::BusinessRule(
+                "Equipment is not available for loan (no barcode, in maintenance or retired)".to_string(),
+            ));
+        }
+
+        let active_loan_id: Option<i64> = sqlx::query_scalar(
+            "SELECT id FROM loans WHERE equipment_id = $1 AND returned_at IS NULL"
+        )
+        .bind(equipment_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if let Some(active_loan_id) = active_loan_id {
+            if !loan.force {
+                return Err(AppError::BusinessRule("Equipment is already borrowed".to_string()));
+            }
+            self.loans_return(active_loan_id).await?;
+        }
+
+        if equipment.requires_deposit && !loan.deposit_received && !loan.force {
+            return Err(AppError::BusinessRule(
+                "A deposit is required for this equipment — confirm it with depositReceived=true".to_string(),
+            ));
+        }
+
+        let active_equipment_loans: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM loans WHERE user_id = $1 AND equipment_id IS NOT NULL AND returned_at IS NULL"
+        )
+        .bind(loan.user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        if active_equipment_loans >= MAX_ACTIVE_EQUIPMENT_LOANS && !loan.force {
+            return Err(AppError::BusinessRule(format!(
+                "Maximum equipment loans reached ({}/{})",
+                active_equipment_loans, MAX_ACTIVE_EQUIPMENT_LOANS
+            )));
+        }
+
+        let expiry_at = now + Duration::days(equipment.loan_duration_days as i64);
+
+        let loan_id = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO loans (user_id, equipment_id, date, expiry_at, nb_renews, deposit_received)
+            VALUES ($1, $2, $3, $4, 0, $5)
+            RETURNING id
+            "#
+        )
+        .bind(loan.user_id)
+        .bind(equipment_id)
+        .bind(now)
+        .bind(expiry_at)
+        .bind(equipment.requires_deposit.then_some(loan.deposit_received))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((loan_id, expiry_at))
+    }
+
+    /// Return a loan. It moves to loans_archives right away, or stays in `loans` with its return
+    /// date until the archival job when `circulation.archive_returned_after_days` is set.
+    pub async fn loans_return(&self, loan_id: i64) -> AppResult<LoanReturnOutcome> {
+        let now = Utc::now();
+
+        let loan = self.loans_get_by_id(loan_id).await?;
+
+        if loan.returned_at.is_some() {
+            return Err(AppError::BusinessRule("Loan already returned".to_string()));
+        }
+
+        if self.loans_archive_returned_after_days() > 0 {
+            sqlx::query("UPDATE loans SET returned_at = $2 WHERE id = $1")
+                .bind(loan_id)
+                .bind(now)
+                .execute(&self.pool)
+                .await?;
+        } else {
+            self.loans_move_to_archives(&loan, now).await?;
+        }
+
+        self.loans_return_outcome(loan, now).await
+    }
+
+    /// Copy a returned loan to loans_archives with the borrower columns, then delete it
+    async fn loans_move_to_archives(&self, loan: &Loan, now: DateTime<Utc>) -> AppResult<()> {
+        let loan_id = loan.id;
+        let user_row = sqlx::query(
+            "SELECT addr_city, account_type, public_type FROM users WHERE id = $1"
+        )
+        .bind(loan.user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let account_type: Option<String> = user_row.as_ref().and_then(|r| r.get("account_type"));
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO loans_archives (
+                user_id, item_id, equipment_id, date, nb_renews, expiry_at,
+                returned_at, notes, borrower_public_type,
+                addr_city, account_type, batch_id, bundle_id, borrower_age_band
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13,
+                    (SELECT borrower_age_band FROM loans WHERE id = $14))
+            "#
+        )
+        .bind(loan.user_id)
+        .bind(loan.item_id)
+        .bind(loan.equipment_id)
+        .bind(loan.date)
         .bind(loan.nb_renews)
         .bind(loan.expiry_at)
         .bind(now)
@@ -954,6 +1564,9 @@ impl Repository {
         .bind(user_row.as_ref().and_then(|r| r.get::<Option<i64>, _>("public_type")))
         .bind(user_row.as_ref().and_then(|r| r.get::<Option<String>, _>("addr_city")))
         .bind(account_type)
+        .bind(loan.batch_id)
+        .bind(loan.bundle_id)
+        .bind(loan_id)
         .execute(&mut *tx)
         .await?;
 
@@ -963,16 +1576,94 @@ impl Repository {
             .await?;
 
         tx.commit().await?;
+        Ok(())
+    }
+
+    /// Archival job: move loans returned before `returned_before` to loans_archives, with the
+    /// borrower columns a return copies.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn loans_archive_returned(&self, returned_before: DateTime<Utc>) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            WITH moved AS (
+                DELETE FROM loans WHERE returned_at IS NOT NULL AND returned_at < $1
+                RETURNING user_id, item_id, equipment_id, date, nb_renews, expiry_at, returned_at,
+                          notes, batch_id, bundle_id, borrower_age_band
+            )
+            INSERT INTO loans_archives (
+                user_id, item_id, equipment_id, date, nb_renews, expiry_at,
+                returned_at, notes, borrower_public_type,
+                addr_city, account_type, batch_id, bundle_id, borrower_age_band
+            )
+            SELECT m.user_id, m.item_id, m.equipment_id, m.date, m.nb_renews, m.expiry_at,
+                   m.returned_at, m.notes, u.public_type,
+                   u.addr_city, u.account_type, m.batch_id, m.bundle_id, m.borrower_age_band
+            FROM moved m
+            LEFT JOIN users u ON u.id = m.user_id
+            "#,
+        )
+        .bind(returned_before)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Details of a loan just returned, with the hold it readies and the check-in routing
+    async fn loans_return_outcome(&self, loan: Loan, now: DateTime<Utc>) -> AppResult<LoanReturnOutcome> {
+        let user_short_row = sqlx::query_as::<_, UserShortRow>(
+            r#"
+            SELECT u.id, u.firstname, u.lastname, u.account_type, u.public_type,
+                   u.status, u.created_at, u.expiry_at,
+                   0::bigint as nb_loans, 0::bigint as nb_late_loans
+            FROM users u
+            WHERE u.id = $1
+            "#
+        )
+        .bind(loan.user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let user: Option<UserShort> = user_short_row.map(|r| r.into());
+
+        // Equipment loans have no hold queue and no bibliographic record.
+        let Some(item_id) = loan.item_id else {
+            let equipment = match loan.equipment_id {
+                Some(id) => Some(EquipmentShort::from(&self.equipment_get_by_id(id).await?)),
+                None => None,
+            };
+            let details = LoanDetails {
+                id: loan.id,
+                item_id: None,
+                start_date: loan.date,
+                expiry_at: loan.expiry_at.unwrap_or(now),
+                renewal_date: loan.renew_at,
+                nb_renews: loan.nb_renews.unwrap_or(0),
+                returned_at: Some(now),
+                biblio: None,
+                item_identification: equipment.as_ref().and_then(|e| e.barcode.clone()),
+                equipment,
+                user,
+                is_overdue: false,
+                matched: None,
+            };
+            return Ok(LoanReturnOutcome {
+                details,
+                readied_hold: None,
+                missing_bundle_parts: Vec::new(),
+                routing: None,
+                routing_failed: false,
+            });
+        };
 
         let readied_hold = match self
-            .holds_notify_next(loan.item_id, self.hold_ready_expiry_days())
+            .holds_notify_next(item_id, self.hold_ready_expiry_days())
             .await
         {
             Ok(Some(res)) => {
                 tracing::debug!(
                     target: "loans",
                     hold_id = res.id,
-                    item_id = loan.item_id,
+                    item_id,
                     "Marked next pending hold as ready after loan return"
                 );
                 Some(res)
@@ -982,7 +1673,7 @@ impl Repository {
                 tracing::warn!(
                     target: "loans",
                     error = %e,
-                    item_id = loan.item_id,
+                    item_id,
                     "Failed to advance hold queue after loan return"
                 );
                 None
@@ -1005,25 +1696,10 @@ impl Repository {
             "#,
             LOAN_DETAILS_FIRST_AUTHOR_SQL
         ))
-        .bind(loan.item_id)
+        .bind(item_id)
         .fetch_one(&self.pool)
         .await?;
 
-        let user_short_row = sqlx::query_as::<_, UserShortRow>(
-            r#"
-            SELECT u.id, u.firstname, u.lastname, u.account_type, u.public_type,
-                   u.status, u.created_at, u.expiry_at,
-                   0::bigint as nb_loans, 0::bigint as nb_late_loans
-            FROM users u
-            WHERE u.id = $1
-            "#
-        )
-        .bind(loan.user_id)
-        .fetch_optional(&self.pool)
-        .await?;
-
-        let user: Option<UserShort> = user_short_row.map(|r| r.into());
-
         let item_short = ItemShort {
             id: biblio_row.get("item_copy_id"),
             barcode: biblio_row.get("item_barcode"),
@@ -1031,17 +1707,18 @@ impl Repository {
             borrowable: biblio_row.get("item_borrowable"),
             source_name: biblio_row.get("item_source_name"),
             borrowed: true,
+            hidden_from_opac: false,
         };
 
         let details = LoanDetails {
             id: loan.id,
-            item_id: loan.item_id,
+            item_id: Some(item_id),
             start_date: loan.date,
             expiry_at: loan.expiry_at.unwrap_or(now),
             renewal_date: loan.renew_at,
             nb_renews: loan.nb_renews.unwrap_or(0),
             returned_at: Some(now),
-            biblio: BiblioShort {
+            biblio: Some(BiblioShort {
                 id: biblio_row.get("biblio_id"),
                 media_type: biblio_row.get("media_type"),
                 isbn: biblio_row.get("isbn"),
@@ -1054,10 +1731,12 @@ impl Repository {
                     .get::<Option<serde_json::Value>, _>("author")
                     .and_then(|v| serde_json::from_value(v).ok()),
                 items: vec![item_short],
-            },
+            }),
+            equipment: None,
             user,
             item_identification: biblio_row.get("item_identification"),
             is_overdue: false,
+            matched: None,
         };
 
         if let (Some(ref h), Some(ref email_svc)) = (&readied_hold, &self.email_service) {
@@ -1078,66 +1757,217 @@ impl Repository {
             }
         }
 
+        // Completeness check: parts of the set still out are reported to the desk
+        let missing_bundle_parts = match loan.bundle_id {
+            Some(bundle_id) => self
+                .bundles_parts(bundle_id)
+                .await?
+                .into_iter()
+                .filter(|p| p.loan_id.is_some())
+                .collect(),
+            None => Vec::new(),
+        };
+
         Ok(LoanReturnOutcome {
             details,
             readied_hold,
+            missing_bundle_parts,
+            routing: None,
+            routing_failed: false,
         })
     }
 
-    /// Renew a loan
-    pub async fn loans_renew(&self, loan_id: i64) -> AppResult<(DateTime<Utc>, i16)> {
-        let now = Utc::now();
-
-        let loan = self.loans_get_by_id(loan_id).await?;
+    /// Floating rule facts of a copy: its place, the record's media type and collection keys,
+    /// whether it is on loan and its routing state
+    #[tracing::instrument(skip(self), err)]
+    pub async fn loans_item_floating_facts(&self, item_id: i64) -> AppResult<ItemFloatingFacts> {
+        sqlx::query_as::<_, ItemFloatingFacts>(
+            r#"
+            SELECT it.place AS home_place, b.media_type,
+                   ARRAY(
+                       SELECT c.key FROM biblio_collections bc
+                       JOIN collections c ON c.id = bc.collection_id
+                       WHERE bc.biblio_id = b.id AND c.key IS NOT NULL
+                   ) AS collection_keys,
+                   EXISTS (SELECT 1 FROM loans l WHERE l.item_id = it.id AND l.returned_at IS NULL) AS on_loan,
+                   it.transit_place, it.routing_place
+            FROM items it
+            JOIN biblios b ON b.id = it.biblio_id
+            WHERE it.id = $1
+            "#,
+        )
+        .bind(item_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Item {} not found", item_id)))
+    }
 
-        if loan.returned_at.is_some() {
-            return Err(AppError::BusinessRule("Cannot renew a returned loan".to_string()));
-        }
+    /// Record a routing: a routing waiting for the staff keeps the copy where it is, a floating
+    /// copy takes the return place (its previous place is kept to reshelve it), a copy sent home
+    /// is in transit until received there.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn loans_route_item(&self, item_id: i64, routing: &ReturnRouting) -> AppResult<()> {
+        let (sql, place) = match (routing.prompt_override, routing.action) {
+            (true, _) => (
+                "UPDATE items SET routing_place = $2, updated_at = NOW() WHERE id = $1",
+                routing.return_place,
+            ),
+            (false, ReturnRoutingAction::Float) => (
+                r#"UPDATE items
+                   SET floated_from_place = place, place = $2, transit_place = NULL, routing_place = NULL,
+                       updated_at = NOW()
+                   WHERE id = $1"#,
+                routing.return_place,
+            ),
+            (false, ReturnRoutingAction::TransitHome) => (
+                "UPDATE items SET transit_place = $2, routing_place = NULL, updated_at = NOW() WHERE id = $1",
+                routing.home_place,
+            ),
+        };
+        sqlx::query(sql)
+            .bind(item_id)
+            .bind(place)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
 
-        let item_row = sqlx::query(
-            "SELECT b.media_type FROM items it JOIN biblios b ON it.biblio_id = b.id WHERE it.id = $1"
+    /// Clear the transit and pending routing of a copy
+    #[tracing::instrument(skip(self), err)]
+    pub async fn loans_clear_item_routing(&self, item_id: i64) -> AppResult<()> {
+        sqlx::query(
+            r#"UPDATE items SET transit_place = NULL, routing_place = NULL, updated_at = NOW()
+               WHERE id = $1 AND (transit_place IS NOT NULL OR routing_place IS NOT NULL)"#,
         )
-        .bind(loan.item_id)
-        .fetch_one(&self.pool)
+        .bind(item_id)
+        .execute(&self.pool)
         .await?;
+        Ok(())
+    }
 
-        let media_type: Option<String> = item_row.get("media_type");
+    /// Check the renewal rules of a loan (loan settings or equipment limit, holds queued by
+    /// other patrons, copies of a closed deposit). Borrower rules are checked by the service.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn loans_renewability(&self, loan_id: i64) -> AppResult<LoanRenewability> {
+        let now = Utc::now();
+        let loan = self.loans_get_by_id(loan_id).await?;
 
-        let user_public_type: Option<i64> = sqlx::query_scalar::<_, Option<i64>>(
-            "SELECT public_type FROM users WHERE id = $1"
-        )
-        .bind(loan.user_id)
-        .fetch_optional(&self.pool)
-        .await?
-        .flatten();
+        let (duration_days, max_renews, renew_at_policy) = match (loan.item_id, loan.equipment_id) {
+            (Some(item_id), _) => {
+                let item_row = sqlx::query(
+                    "SELECT b.media_type FROM items it JOIN biblios b ON it.biblio_id = b.id WHERE it.id = $1"
+                )
+                .bind(item_id)
+                .fetch_one(&self.pool)
+                .await?;
 
-        let (duration_days, _nb_max_media, _nb_max_total, max_renews, renew_at_policy) = self
-            .resolve_loan_settings(user_public_type, media_type.as_deref())
-            .await?;
+                let media_type: Option<String> = item_row.get("media_type");
 
-        let current_renews = loan.nb_renews.unwrap_or(0);
+                let user_public_type: Option<i64> = sqlx::query_scalar::<_, Option<i64>>(
+                    "SELECT public_type FROM users WHERE id = $1"
+                )
+                .bind(loan.user_id)
+                .fetch_optional(&self.pool)
+                .await?
+                .flatten();
 
-        if current_renews >= max_renews {
-            return Err(AppError::BusinessRule(format!(
-                "Maximum renewals reached ({}/{})",
-                current_renews, max_renews
-            )));
-        }
+                let (duration_days, _nb_max_media, _nb_max_total, max_renews, renew_at_policy) = self
+                    .resolve_loan_settings(user_public_type, media_type.as_deref())
+                    .await?;
+                (duration_days, max_renews, renew_at_policy)
+            }
+            (None, Some(equipment_id)) => {
+                let equipment = self.equipment_get_by_id(equipment_id).await?;
+                (equipment.loan_duration_days, equipment.max_renewals, LoanSettingsRenewAt::Now)
+            }
+            (None, None) => {
+                return Err(AppError::Internal(format!("Loan {} has neither item nor equipment", loan_id)));
+            }
+        };
 
+        let current_renews = loan.nb_renews.unwrap_or(0);
         let anchor = match renew_at_policy {
             LoanSettingsRenewAt::Now => now,
             LoanSettingsRenewAt::AtDueDate => loan.expiry_at.unwrap_or(now),
         };
-        let new_expiry_date = anchor + Duration::days(duration_days as i64);
-        let new_renews = current_renews + 1;
+        let mut renewability = LoanRenewability {
+            loan_id,
+            renewable: true,
+            blocks: Vec::new(),
+            renew_count: current_renews,
+            max_renewals: max_renews,
+            expiry_at: loan.expiry_at,
+            new_expiry_at: anchor + Duration::days(duration_days as i64),
+        };
+
+        if loan.returned_at.is_some() {
+            renewability.block(RenewalBlockCode::LoanReturned, "Cannot renew a returned loan".to_string());
+        }
+        if current_renews >= max_renews {
+            renewability.block(
+                RenewalBlockCode::MaxRenewalsReached,
+                format!("Maximum renewals reached ({}/{})", current_renews, max_renews),
+            );
+        }
+        if let Some(item_id) = loan.item_id {
+            let (hold_queued, requested_back): (bool, bool) = sqlx::query_as(
+                r#"
+                SELECT
+                    EXISTS (SELECT 1 FROM holds h
+                            WHERE h.item_id = $1 AND h.user_id <> $2
+                              AND h.status IN ('pending', 'ready', 'in_locker')),
+                    EXISTS (SELECT 1 FROM items i JOIN deposits d ON d.id = i.deposit_id
+                            WHERE i.id = $1 AND d.status = 'closed')
+                "#,
+            )
+            .bind(item_id)
+            .bind(loan.user_id)
+            .fetch_one(&self.pool)
+            .await?;
+            if hold_queued {
+                renewability.block(
+                    RenewalBlockCode::HoldQueued,
+                    "Another patron has a hold on this copy".to_string(),
+                );
+            }
+            if requested_back {
+                renewability.block(
+                    RenewalBlockCode::ItemRequestedBack,
+                    "This copy belongs to a closed deposit and must be sent back".to_string(),
+                );
+            }
+        }
+
+        Ok(renewability)
+    }
+
+    /// Renew a loan (refused when [`Self::loans_renewability`] reports a blocking rule)
+    pub async fn loans_renew(&self, loan_id: i64) -> AppResult<(DateTime<Utc>, i16)> {
+        let now = Utc::now();
+        let renewability = self.loans_renewability(loan_id).await?;
+
+        if let Some(block) = renewability.blocks.first() {
+            return Err(AppError::BusinessRule(block.message.clone()));
+        }
 
+        let loan = self.loans_get_by_id(loan_id).await?;
+        let new_expiry_date = renewability.new_expiry_at;
+        let new_renews = renewability.renew_count + 1;
+
+        // Parts of a bundle keep one due date: renewing one part renews the parts still out
         sqlx::query(
-            "UPDATE loans SET expiry_at = $1, renew_at = $2, nb_renews = $3 WHERE id = $4"
+            r#"
+            UPDATE loans SET expiry_at = $1, renew_at = $2, nb_renews = $3
+            WHERE id = $4
+               OR ($5::bigint IS NOT NULL AND bundle_id = $5 AND user_id = $6 AND returned_at IS NULL)
+            "#
         )
         .bind(new_expiry_date)
         .bind(now)
         .bind(new_renews)
         .bind(loan_id)
+        .bind(loan.bundle_id)
+        .bind(loan.user_id)
         .execute(&self.pool)
         .await?;
 
@@ -1301,13 +2131,14 @@ impl Repository {
         Ok(ids)
     }
 
-    /// Count active loans for a biblio (via its physical items)
+    /// Count active loans for a biblio, on its copies shown in the OPAC (`item_opac_visible`)
     pub async fn loans_count_active_for_biblio(&self, biblio_id: i64) -> AppResult<i64> {
         let count: i64 = sqlx::query_scalar(
             r#"
             SELECT COUNT(*) FROM loans l
             JOIN items it ON l.item_id = it.id
             WHERE it.biblio_id = $1 AND l.returned_at IS NULL
+              AND it.archived_at IS NULL AND item_opac_visible(it.circulation_status)
             "#
         )
         .bind(biblio_id)
@@ -1327,6 +2158,17 @@ impl Repository {
         Ok(count)
     }
 
+    /// Count overdue active loans of a user (checkout block)
+    pub async fn loans_count_overdue_for_user(&self, user_id: i64) -> AppResult<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM loans WHERE user_id = $1 AND returned_at IS NULL AND expiry_at < NOW()"
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
     /// Get overdue loans eligible for reminder emails.
     pub async fn loans_get_overdue_for_reminders(
         &self,
@@ -1346,17 +2188,19 @@ impl Repository {
                 u.email as user_email,
                 u.language as user_language,
                 b.id as biblio_id,
-                b.title,
+                l.equipment_id,
+                COALESCE(b.title, e.name) as title,
                 (
-                    SELECT string_agg(a.lastname || ' ' || COALESCE(a.firstname, ''), ', ' ORDER BY ba.position)
+                    SELECT string_agg(COALESCE(a.display_name, a.lastname || ' ' || COALESCE(a.firstname, '')), ', ' ORDER BY ba.position)
                     FROM biblio_authors ba
                     JOIN authors a ON a.id = ba.author_id
                     WHERE ba.biblio_id = b.id
                 ) as authors,
-                it.barcode as item_barcode
+                COALESCE(it.barcode, e.barcode) as item_barcode
             FROM loans l
-            JOIN items it ON l.item_id = it.id
-            JOIN biblios b ON it.biblio_id = b.id
+            LEFT JOIN items it ON l.item_id = it.id
+            LEFT JOIN biblios b ON it.biblio_id = b.id
+            LEFT JOIN equipment e ON l.equipment_id = e.id
             JOIN users u ON l.user_id = u.id
             WHERE l.returned_at IS NULL
               AND l.expiry_at < NOW()
@@ -1374,25 +2218,59 @@ impl Repository {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(rows
-            .into_iter()
-            .map(|row| OverdueLoanRow {
-                loan_id: row.get("loan_id"),
-                user_id: row.get("user_id"),
-                loan_date: row.get("loan_date"),
-                expiry_at: row.get("expiry_at"),
-                last_reminder_sent_at: row.get("last_reminder_sent_at"),
-                reminder_count: row.get::<Option<i32>, _>("reminder_count").unwrap_or(0),
-                firstname: row.get("firstname"),
-                lastname: row.get("lastname"),
-                user_email: row.get("user_email"),
-                user_language: row.get::<Option<String>, _>("user_language"),
-                biblio_id: row.get("biblio_id"),
-                title: row.get("title"),
-                authors: row.get("authors"),
-                item_barcode: row.get("item_barcode"),
-            })
-            .collect())
+        Ok(rows.iter().map(overdue_loan_row).collect())
+    }
+
+    /// Active loans due in exactly one of `lead_days` days (calendar days), for the due-soon digest.
+    /// Loans already listed in a digest today are skipped, so a second run the same day sends nothing.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn loans_get_due_soon(&self, lead_days: &[u32]) -> AppResult<Vec<OverdueLoanRow>> {
+        if lead_days.is_empty() {
+            return Ok(Vec::new());
+        }
+        let lead_days: Vec<i32> = lead_days.iter().map(|d| *d as i32).collect();
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                l.id as loan_id,
+                l.user_id,
+                l.date as loan_date,
+                l.expiry_at,
+                l.last_reminder_sent_at,
+                l.reminder_count,
+                u.firstname,
+                u.lastname,
+                u.email as user_email,
+                u.language as user_language,
+                b.id as biblio_id,
+                l.equipment_id,
+                COALESCE(b.title, e.name) as title,
+                (
+                    SELECT string_agg(COALESCE(a.display_name, a.lastname || ' ' || COALESCE(a.firstname, '')), ', ' ORDER BY ba.position)
+                    FROM biblio_authors ba
+                    JOIN authors a ON a.id = ba.author_id
+                    WHERE ba.biblio_id = b.id
+                ) as authors,
+                COALESCE(it.barcode, e.barcode) as item_barcode
+            FROM loans l
+            LEFT JOIN items it ON l.item_id = it.id
+            LEFT JOIN biblios b ON it.biblio_id = b.id
+            LEFT JOIN equipment e ON l.equipment_id = e.id
+            JOIN users u ON l.user_id = u.id
+            WHERE l.returned_at IS NULL
+              AND (l.expiry_at::date - CURRENT_DATE) = ANY($1)
+              AND (l.due_soon_notified_at IS NULL OR l.due_soon_notified_at::date < CURRENT_DATE)
+              AND u.email IS NOT NULL
+              AND u.email != ''
+              AND u.receive_reminders = TRUE
+            ORDER BY u.id, l.expiry_at
+            "#,
+        )
+        .bind(&lead_days)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(overdue_loan_row).collect())
     }
 
     /// Get all overdue loans for the admin dashboard (paginated).
@@ -1423,17 +2301,19 @@ impl Repository {
                 u.email as user_email,
                 u.language as user_language,
                 b.id as biblio_id,
-                b.title,
+                l.equipment_id,
+                COALESCE(b.title, e.name) as title,
                 (
-                    SELECT string_agg(a.lastname || ' ' || COALESCE(a.firstname, ''), ', ' ORDER BY ba.position)
+                    SELECT string_agg(COALESCE(a.display_name, a.lastname || ' ' || COALESCE(a.firstname, '')), ', ' ORDER BY ba.position)
                     FROM biblio_authors ba
                     JOIN authors a ON a.id = ba.author_id
                     WHERE ba.biblio_id = b.id
                 ) as authors,
-                it.barcode as item_barcode
+                COALESCE(it.barcode, e.barcode) as item_barcode
             FROM loans l
-            JOIN items it ON l.item_id = it.id
-            JOIN biblios b ON it.biblio_id = b.id
+            LEFT JOIN items it ON l.item_id = it.id
+            LEFT JOIN biblios b ON it.biblio_id = b.id
+            LEFT JOIN equipment e ON l.equipment_id = e.id
             JOIN users u ON l.user_id = u.id
             WHERE l.returned_at IS NULL
               AND l.expiry_at < NOW()
@@ -1460,6 +2340,7 @@ impl Repository {
                 user_email: row.get("user_email"),
                 user_language: row.get::<Option<String>, _>("user_language"),
                 biblio_id: row.get("biblio_id"),
+                equipment_id: row.get("equipment_id"),
                 title: row.get("title"),
                 authors: row.get("authors"),
                 item_barcode: row.get("item_barcode"),
@@ -1487,9 +2368,120 @@ impl Repository {
         .await?;
         Ok(())
     }
+
+    /// Stamp loans listed in a due-soon digest
+    #[tracing::instrument(skip(self), err)]
+    pub async fn loans_update_due_soon_notified(&self, loan_ids: &[i64]) -> AppResult<()> {
+        if loan_ids.is_empty() {
+            return Ok(());
+        }
+        sqlx::query("UPDATE loans SET due_soon_notified_at = NOW() WHERE id = ANY($1)")
+            .bind(loan_ids)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Move every active loan due on `due_date` to `new_due_date`, keeping the time of day.
+    /// Returns the moved loans with borrower contact details for notification.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn loans_shift_due_dates(
+        &self,
+        due_date: NaiveDate,
+        new_due_date: NaiveDate,
+    ) -> AppResult<Vec<ShiftedLoanRow>> {
+        let days = (new_due_date - due_date).num_days() as i32;
+        if days <= 0 {
+            return Err(AppError::Validation(
+                "New due date must be after the closure date".to_string(),
+            ));
+        }
+
+        let rows = sqlx::query(
+            r#"
+            WITH moved AS (
+                UPDATE loans
+                SET expiry_at = expiry_at + make_interval(days => $2)
+                WHERE returned_at IS NULL
+                  AND expiry_at::date = $1
+                RETURNING id, user_id, item_id, equipment_id, expiry_at
+            )
+            SELECT
+                m.id as loan_id,
+                m.user_id,
+                u.firstname,
+                u.lastname,
+                u.email as user_email,
+                u.language as user_language,
+                COALESCE(b.title, e.name) as title,
+                m.expiry_at - make_interval(days => $2) as previous_expiry_at,
+                m.expiry_at
+            FROM moved m
+            JOIN users u ON m.user_id = u.id
+            LEFT JOIN items it ON m.item_id = it.id
+            LEFT JOIN biblios b ON it.biblio_id = b.id
+            LEFT JOIN equipment e ON m.equipment_id = e.id
+            ORDER BY m.user_id, m.id
+            "#,
+        )
+        .bind(due_date)
+        .bind(days)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ShiftedLoanRow {
+                loan_id: row.get("loan_id"),
+                user_id: row.get("user_id"),
+                firstname: row.get("firstname"),
+                lastname: row.get("lastname"),
+                user_email: row.get("user_email"),
+                user_language: row.get::<Option<String>, _>("user_language"),
+                title: row.get("title"),
+                previous_expiry_at: row.get("previous_expiry_at"),
+                expiry_at: row.get("expiry_at"),
+            })
+            .collect())
+    }
 }
 
-/// A flat row from overdue loan queries, used by the reminders service and API
+/// A loan whose due date was moved by [`Repository::loans_shift_due_dates`], with borrower contact.
+#[derive(Debug, Clone)]
+pub struct ShiftedLoanRow {
+    pub loan_id: i64,
+    pub user_id: i64,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    pub user_email: Option<String>,
+    pub user_language: Option<String>,
+    /// Document title, or equipment name for equipment loans
+    pub title: Option<String>,
+    pub previous_expiry_at: DateTime<Utc>,
+    pub expiry_at: DateTime<Utc>,
+}
+
+fn overdue_loan_row(row: &sqlx::postgres::PgRow) -> OverdueLoanRow {
+    OverdueLoanRow {
+        loan_id: row.get("loan_id"),
+        user_id: row.get("user_id"),
+        loan_date: row.get("loan_date"),
+        expiry_at: row.get("expiry_at"),
+        last_reminder_sent_at: row.get("last_reminder_sent_at"),
+        reminder_count: row.get::<Option<i32>, _>("reminder_count").unwrap_or(0),
+        firstname: row.get("firstname"),
+        lastname: row.get("lastname"),
+        user_email: row.get("user_email"),
+        user_language: row.get::<Option<String>, _>("user_language"),
+        biblio_id: row.get("biblio_id"),
+        equipment_id: row.get("equipment_id"),
+        title: row.get("title"),
+        authors: row.get("authors"),
+        item_barcode: row.get("item_barcode"),
+    }
+}
+
+/// A flat row from overdue (and due-soon) loan queries, used by the reminders service and API
 #[derive(Debug, Clone)]
 pub struct OverdueLoanRow {
     pub loan_id: i64,
@@ -1502,9 +2494,23 @@ pub struct OverdueLoanRow {
     pub lastname: Option<String>,
     pub user_email: Option<String>,
     pub user_language: Option<String>,
-    pub biblio_id: i64,
+    /// `None` for equipment loans
+    pub biblio_id: Option<i64>,
+    /// Set for equipment loans (`title` is then the equipment name)
+    pub equipment_id: Option<i64>,
     pub title: Option<String>,
     pub authors: Option<String>,
     pub item_barcode: Option<String>,
 }
 
+/// Map a violation of the one-active-loan-per-copy index to a business rule error.
+pub(crate) fn active_loan_conflict(e: sqlx::Error, already_borrowed: &str) -> AppError {
+    match e {
+        sqlx::Error::Database(ref db)
+            if db.is_unique_violation() && db.constraint() == Some("idx_loans_active_item") =>
+        {
+            AppError::BusinessRule(already_borrowed.to_string())
+        }
+        e => e.into(),
+    }
+}
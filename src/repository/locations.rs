@@ -0,0 +1,244 @@
+//! Shelving locations (`shelving_locations`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::location::{CreateLocation, LocationMappingRow, ShelvingLocation, UpdateLocation},
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait LocationsRepository: Send + Sync {
+    /// Locations in map order (site, its rooms, their shelf ranges), optionally of one place.
+    async fn locations_list(&self, place: Option<i16>) -> AppResult<Vec<ShelvingLocation>>;
+    async fn locations_get(&self, id: i64) -> AppResult<ShelvingLocation>;
+    async fn locations_create(&self, data: &CreateLocation, place: i16) -> AppResult<ShelvingLocation>;
+    async fn locations_update(&self, id: i64, data: &UpdateLocation) -> AppResult<ShelvingLocation>;
+    async fn locations_delete(&self, id: i64) -> AppResult<()>;
+    /// `(child locations, copies)` attached to a location, archived copies included.
+    async fn locations_count_usage(&self, id: i64) -> AppResult<(i64, i64)>;
+    /// Active copies without a location, by place and first word of the call number.
+    async fn locations_mapping_candidates(&self) -> AppResult<Vec<LocationMappingRow>>;
+    /// Give the copies of one mapping group a location (and the place of its site); returns the
+    /// biblio id of each copy located.
+    async fn locations_apply_mapping(
+        &self,
+        place: Option<i16>,
+        prefix: &str,
+        location: &ShelvingLocation,
+    ) -> AppResult<Vec<i64>>;
+    /// Active copies still without a location.
+    async fn locations_count_unlocated(&self) -> AppResult<i64>;
+}
+
+#[async_trait]
+impl LocationsRepository for Repository {
+    async fn locations_list(&self, place: Option<i16>) -> AppResult<Vec<ShelvingLocation>> {
+        Repository::locations_list(self, place).await
+    }
+    async fn locations_get(&self, id: i64) -> AppResult<ShelvingLocation> {
+        Repository::locations_get(self, id).await
+    }
+    async fn locations_create(&self, data: &CreateLocation, place: i16) -> AppResult<ShelvingLocation> {
+        Repository::locations_create(self, data, place).await
+    }
+    async fn locations_update(&self, id: i64, data: &UpdateLocation) -> AppResult<ShelvingLocation> {
+        Repository::locations_update(self, id, data).await
+    }
+    async fn locations_delete(&self, id: i64) -> AppResult<()> {
+        Repository::locations_delete(self, id).await
+    }
+    async fn locations_count_usage(&self, id: i64) -> AppResult<(i64, i64)> {
+        Repository::locations_count_usage(self, id).await
+    }
+    async fn locations_mapping_candidates(&self) -> AppResult<Vec<LocationMappingRow>> {
+        Repository::locations_mapping_candidates(self).await
+    }
+    async fn locations_apply_mapping(
+        &self,
+        place: Option<i16>,
+        prefix: &str,
+        location: &ShelvingLocation,
+    ) -> AppResult<Vec<i64>> {
+        Repository::locations_apply_mapping(self, place, prefix, location).await
+    }
+    async fn locations_count_unlocated(&self) -> AppResult<i64> {
+        Repository::locations_count_unlocated(self).await
+    }
+}
+
+/// Location columns with their path and active copy count (`l` = location, `p` = path view)
+const LOCATION_SELECT_SQL: &str = r#"
+    SELECT l.*, p.path,
+           (SELECT COUNT(*) FROM items i WHERE i.location_id = l.id AND i.archived_at IS NULL) AS item_count
+    FROM shelving_locations l
+    JOIN shelving_location_paths p ON p.id = l.id
+"#;
+
+/// First word of a copy's call number (`R` for `R DOY`), as grouped by the mapping assistant
+const CALL_NUMBER_PREFIX_SQL: &str = "split_part(btrim(COALESCE(i.call_number, '')), ' ', 1)";
+
+impl Repository {
+    /// List locations in map order
+    #[tracing::instrument(skip(self), err)]
+    pub async fn locations_list(&self, place: Option<i16>) -> AppResult<Vec<ShelvingLocation>> {
+        let rows = sqlx::query_as::<_, ShelvingLocation>(&format!(
+            "{LOCATION_SELECT_SQL} WHERE ($1::SMALLINT IS NULL OR l.place = $1) ORDER BY p.sort_key"
+        ))
+        .bind(place)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get a location by id
+    #[tracing::instrument(skip(self), err)]
+    pub async fn locations_get(&self, id: i64) -> AppResult<ShelvingLocation> {
+        sqlx::query_as::<_, ShelvingLocation>(&format!("{LOCATION_SELECT_SQL} WHERE l.id = $1"))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Location {} not found", id)))
+    }
+
+    /// Create a location (`place` is the one of its site, checked by the service)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn locations_create(&self, data: &CreateLocation, place: i16) -> AppResult<ShelvingLocation> {
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO shelving_locations (parent_id, kind, code, name, place, sort_order)
+            VALUES ($1, $2, NULLIF(btrim($3), ''), btrim($4), $5, $6)
+            RETURNING id
+            "#,
+        )
+        .bind(data.parent_id)
+        .bind(data.kind)
+        .bind(data.code.as_deref())
+        .bind(&data.name)
+        .bind(place)
+        .bind(data.sort_order.unwrap_or(50))
+        .fetch_one(&self.pool)
+        .await?;
+        self.locations_get(id).await
+    }
+
+    /// Update name, code and order of a location
+    #[tracing::instrument(skip(self), err)]
+    pub async fn locations_update(&self, id: i64, data: &UpdateLocation) -> AppResult<ShelvingLocation> {
+        let updated = sqlx::query(
+            r#"
+            UPDATE shelving_locations SET
+                code = CASE WHEN $1::text IS NULL THEN code ELSE NULLIF(btrim($1), '') END,
+                name = COALESCE(btrim($2), name),
+                sort_order = COALESCE($3, sort_order),
+                update_at = $4
+            WHERE id = $5
+            "#,
+        )
+        .bind(data.code.as_deref())
+        .bind(data.name.as_deref())
+        .bind(data.sort_order)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Location {} not found", id)));
+        }
+        self.locations_get(id).await
+    }
+
+    /// Delete a location (the service checks it is empty)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn locations_delete(&self, id: i64) -> AppResult<()> {
+        let deleted = sqlx::query("DELETE FROM shelving_locations WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if deleted.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Location {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Child locations and copies (archived included) attached to a location
+    #[tracing::instrument(skip(self), err)]
+    pub async fn locations_count_usage(&self, id: i64) -> AppResult<(i64, i64)> {
+        let counts = sqlx::query_as::<_, (i64, i64)>(
+            r#"SELECT (SELECT COUNT(*) FROM shelving_locations WHERE parent_id = $1),
+                      (SELECT COUNT(*) FROM items WHERE location_id = $1)"#,
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(counts)
+    }
+
+    /// Mapping assistant: active copies without a location grouped by place and call number
+    /// prefix, each group with the room or shelf range of the same place whose code is the prefix
+    #[tracing::instrument(skip(self), err)]
+    pub async fn locations_mapping_candidates(&self) -> AppResult<Vec<LocationMappingRow>> {
+        let rows = sqlx::query_as::<_, LocationMappingRow>(&format!(
+            r#"
+            SELECT i.place, {CALL_NUMBER_PREFIX_SQL} AS prefix, COUNT(*) AS item_count,
+                   s.id AS suggested_location_id, s.path AS suggested_path
+            FROM items i
+            LEFT JOIN LATERAL (
+                SELECT l.id, p.path
+                FROM shelving_locations l
+                JOIN shelving_location_paths p ON p.id = l.id
+                WHERE l.place = i.place AND l.kind <> 'site'
+                  AND lower(l.code) = lower({CALL_NUMBER_PREFIX_SQL})
+                ORDER BY array_length(p.ancestor_ids, 1) DESC, p.sort_key
+                LIMIT 1
+            ) s ON TRUE
+            WHERE i.location_id IS NULL AND i.archived_at IS NULL
+            GROUP BY 1, 2, 4, 5
+            ORDER BY i.place NULLS LAST, COUNT(*) DESC, 2
+            "#
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Locate the active copies of one mapping group (biblio id of each copy located)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn locations_apply_mapping(
+        &self,
+        place: Option<i16>,
+        prefix: &str,
+        location: &ShelvingLocation,
+    ) -> AppResult<Vec<i64>> {
+        let biblio_ids = sqlx::query_scalar::<_, i64>(&format!(
+            r#"
+            UPDATE items i SET location_id = $3, place = $4, updated_at = NOW()
+            WHERE i.location_id IS NULL AND i.archived_at IS NULL
+              AND i.place IS NOT DISTINCT FROM $1
+              AND {CALL_NUMBER_PREFIX_SQL} = $2
+            RETURNING i.biblio_id
+            "#
+        ))
+        .bind(place)
+        .bind(prefix)
+        .bind(location.id)
+        .bind(location.place)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(biblio_ids)
+    }
+
+    /// Active copies without a location
+    #[tracing::instrument(skip(self), err)]
+    pub async fn locations_count_unlocated(&self) -> AppResult<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM items WHERE location_id IS NULL AND archived_at IS NULL",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+}
@@ -0,0 +1,206 @@
+//! Media type taxonomy (`media_types`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::{
+        media_type::{CreateMediaType, MediaTypeDefinition, UpdateMediaType},
+        MediaType,
+    },
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait MediaTypesRepository: Send + Sync {
+    async fn media_types_list(&self) -> AppResult<Vec<MediaTypeDefinition>>;
+    async fn media_types_get(&self, code: &str) -> AppResult<MediaTypeDefinition>;
+    async fn media_types_create(&self, data: &CreateMediaType) -> AppResult<MediaTypeDefinition>;
+    async fn media_types_update(&self, code: &str, data: &UpdateMediaType) -> AppResult<MediaTypeDefinition>;
+    async fn media_types_delete(&self, code: &str) -> AppResult<()>;
+    /// Number of biblios (archived included) of this media type.
+    async fn media_types_count_biblios(&self, code: &str) -> AppResult<i64>;
+}
+
+#[async_trait]
+impl MediaTypesRepository for Repository {
+    async fn media_types_list(&self) -> AppResult<Vec<MediaTypeDefinition>> {
+        Repository::media_types_list(self).await
+    }
+    async fn media_types_get(&self, code: &str) -> AppResult<MediaTypeDefinition> {
+        Repository::media_types_get(self, code).await
+    }
+    async fn media_types_create(&self, data: &CreateMediaType) -> AppResult<MediaTypeDefinition> {
+        Repository::media_types_create(self, data).await
+    }
+    async fn media_types_update(&self, code: &str, data: &UpdateMediaType) -> AppResult<MediaTypeDefinition> {
+        Repository::media_types_update(self, code, data).await
+    }
+    async fn media_types_delete(&self, code: &str) -> AppResult<()> {
+        Repository::media_types_delete(self, code).await
+    }
+    async fn media_types_count_biblios(&self, code: &str) -> AppResult<i64> {
+        Repository::media_types_count_biblios(self, code).await
+    }
+}
+
+impl Repository {
+    /// List media types in display order
+    #[tracing::instrument(skip(self), err)]
+    pub async fn media_types_list(&self) -> AppResult<Vec<MediaTypeDefinition>> {
+        let rows = sqlx::query_as::<_, MediaTypeDefinition>(
+            "SELECT * FROM media_types ORDER BY sort_order, code",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get a media type by code
+    #[tracing::instrument(skip(self), err)]
+    pub async fn media_types_get(&self, code: &str) -> AppResult<MediaTypeDefinition> {
+        sqlx::query_as::<_, MediaTypeDefinition>("SELECT * FROM media_types WHERE code = $1")
+            .bind(code)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Media type {} not found", code)))
+    }
+
+    /// Create a media type
+    #[tracing::instrument(skip(self), err)]
+    pub async fn media_types_create(&self, data: &CreateMediaType) -> AppResult<MediaTypeDefinition> {
+        let row = sqlx::query_as::<_, MediaTypeDefinition>(
+            r#"
+            INSERT INTO media_types (
+                code, label, icon, loan_duration, loan_nb_max, loan_nb_renews,
+                marc_record_type, marc_bibliographic_level, sort_order, vat_rate
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING *
+            "#,
+        )
+        .bind(&data.code)
+        .bind(data.label.trim())
+        .bind(data.icon.as_deref().filter(|i| !i.is_empty()))
+        .bind(data.loan_duration)
+        .bind(data.loan_nb_max)
+        .bind(data.loan_nb_renews)
+        .bind(data.marc_record_type.as_deref().unwrap_or("a"))
+        .bind(data.marc_bibliographic_level.as_deref().unwrap_or("m"))
+        .bind(data.sort_order.unwrap_or(50))
+        .bind(data.vat_rate)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Update a media type (absent fields are kept; an empty `icon` clears it)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn media_types_update(&self, code: &str, data: &UpdateMediaType) -> AppResult<MediaTypeDefinition> {
+        sqlx::query_as::<_, MediaTypeDefinition>(
+            r#"
+            UPDATE media_types SET
+                label = COALESCE($1, label),
+                icon = CASE WHEN $2::text IS NULL THEN icon ELSE NULLIF($2, '') END,
+                loan_duration = COALESCE($3, CASE WHEN $6 THEN NULL ELSE loan_duration END),
+                loan_nb_max = COALESCE($4, CASE WHEN $6 THEN NULL ELSE loan_nb_max END),
+                loan_nb_renews = COALESCE($5, CASE WHEN $6 THEN NULL ELSE loan_nb_renews END),
+                marc_record_type = COALESCE($7, marc_record_type),
+                marc_bibliographic_level = COALESCE($8, marc_bibliographic_level),
+                sort_order = COALESCE($9, sort_order),
+                vat_rate = COALESCE($12, CASE WHEN $13 THEN NULL ELSE vat_rate END),
+                update_at = $10
+            WHERE code = $11
+            RETURNING *
+            "#,
+        )
+        .bind(data.label.as_deref().map(str::trim))
+        .bind(&data.icon)
+        .bind(data.loan_duration)
+        .bind(data.loan_nb_max)
+        .bind(data.loan_nb_renews)
+        .bind(data.clear_loan_rules)
+        .bind(&data.marc_record_type)
+        .bind(&data.marc_bibliographic_level)
+        .bind(data.sort_order)
+        .bind(Utc::now())
+        .bind(code)
+        .bind(data.vat_rate)
+        .bind(data.clear_vat_rate)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Media type {} not found", code)))
+    }
+
+    /// Delete a media type
+    #[tracing::instrument(skip(self), err)]
+    pub async fn media_types_delete(&self, code: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM media_types WHERE code = $1")
+            .bind(code)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Media type {} not found", code)));
+        }
+        Ok(())
+    }
+
+    /// Number of biblios (archived included) of this media type
+    #[tracing::instrument(skip(self), err)]
+    pub async fn media_types_count_biblios(&self, code: &str) -> AppResult<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM biblios WHERE media_type = $1")
+            .bind(code)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    /// Reject media types missing from the taxonomy before they reach `biblios`.
+    pub(crate) async fn media_types_ensure_known(&self, media_type: &MediaType) -> AppResult<()> {
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM media_types WHERE code = $1)")
+            .bind(media_type.as_db_str())
+            .fetch_one(&self.pool)
+            .await?;
+        if !exists {
+            return Err(AppError::Validation(format!("Unknown media type {}", media_type)));
+        }
+        Ok(())
+    }
+
+    /// MARC leader codes (type of record, bibliographic level) of a media type.
+    pub(crate) async fn media_types_marc_codes(&self, media_type: &MediaType) -> AppResult<Option<(char, char)>> {
+        let row: Option<(String, String)> = sqlx::query_as(
+            "SELECT marc_record_type, marc_bibliographic_level FROM media_types WHERE code = $1",
+        )
+        .bind(media_type.as_db_str())
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.and_then(|(record_type, level)| Some((record_type.chars().next()?, level.chars().next()?))))
+    }
+
+    /// Media type of a record with these MARC leader codes: `preferred` when its own codes match,
+    /// else the first matching one in display order.
+    pub(crate) async fn media_types_for_marc(
+        &self,
+        record_type: char,
+        bibliographic_level: char,
+        preferred: &MediaType,
+    ) -> AppResult<Option<MediaType>> {
+        let code: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT code FROM media_types
+            WHERE marc_record_type = $1 AND marc_bibliographic_level = $2
+            ORDER BY (code = $3) DESC, sort_order, code
+            LIMIT 1
+            "#,
+        )
+        .bind(record_type.to_string())
+        .bind(bibliographic_level.to_string())
+        .bind(preferred.as_db_str())
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(code.map(|c| MediaType::from(c.as_str())))
+    }
+}
@@ -7,46 +7,106 @@
 //! like loans or biblios.
 
 pub mod account_types;
+pub mod api_keys;
+pub mod artifacts;
 pub mod audit_log;
+pub mod backups;
 pub mod biblios;
+pub mod bundles;
+pub mod campaigns;
 pub mod catalog_entities;
+pub mod dashboards;
+pub mod deposits;
+pub mod donations;
+pub mod email_health;
 pub mod email_templates;
+pub mod eod;
 pub mod equipment;
 pub mod events;
 pub mod fines;
+pub mod harvest;
 pub mod inventory;
+pub mod item_states;
+pub mod item_templates;
+pub mod kiosks;
+pub mod labels;
 pub mod library_info;
+pub mod loan_batches;
+pub mod locations;
 pub mod loans;
 pub mod maintenance;
+pub mod media_types;
+pub mod notifications;
+pub mod payments;
 pub mod public_types;
+pub mod purchase_suggestions;
+pub mod reading_programs;
+pub mod reengagement;
+pub mod relators;
 pub mod holds;
 pub mod schedules;
+pub mod sitemap;
+pub mod staffing;
 pub mod stats;
 pub mod settings;
+pub mod snapshots;
 pub mod sources;
 pub mod z3950;
+pub mod user_messages;
 pub mod users;
+pub mod vendors;
 pub mod visitor_counts;
+pub mod warehouse;
 
 pub use account_types::AccountTypesCatalogRepository;
+pub use api_keys::ApiKeysRepository;
+pub use artifacts::ArtifactsRepository;
 pub use audit_log::AuditLogRepository;
+pub use backups::BackupsRepository;
 pub use biblios::BibliosRepository;
+pub use bundles::BundlesRepository;
+pub use campaigns::CampaignsRepository;
 pub use catalog_entities::CatalogEntitiesRepository;
+pub use dashboards::DashboardsRepository;
+pub use deposits::DepositsRepository;
+pub use donations::DonationsRepository;
+pub use email_health::EmailHealthRepository;
 pub use email_templates::{EmailTemplateRow, EmailTemplatesRepository};
+pub use eod::EodRepository;
 pub use equipment::EquipmentRepository;
 pub use events::{EventsRepository, EventsServiceRepository};
 pub use fines::FinesRepository;
+pub use harvest::HarvestRepository;
 pub use inventory::InventoryRepository;
+pub use item_states::ItemStatesRepository;
+pub use item_templates::ItemTemplatesRepository;
+pub use kiosks::KiosksRepository;
+pub use labels::LabelsRepository;
 pub use library_info::{LibraryInfoRepository, LibraryInfoSnapshot};
+pub use loan_batches::LoanBatchesRepository;
+pub use locations::LocationsRepository;
 pub use loans::{LoansRepository, LoansServiceRepository};
 pub use maintenance::MaintenanceRepository;
+pub use media_types::MediaTypesRepository;
+pub use notifications::NotificationsRepository;
+pub use payments::PaymentsRepository;
 pub use public_types::PublicTypesRepository;
+pub use purchase_suggestions::PurchaseSuggestionsRepository;
+pub use reading_programs::ReadingProgramsRepository;
+pub use reengagement::ReengagementRepository;
+pub use relators::RelatorsRepository;
 pub use holds::HoldsRepository;
 pub use schedules::SchedulesRepository;
+pub use sitemap::SitemapRepository;
+pub use staffing::StaffingRepository;
 pub use settings::RuntimeSettingsRepository;
+pub use snapshots::SnapshotsRepository;
 pub use sources::SourcesRepository;
+pub use user_messages::UserMessagesRepository;
 pub use users::UsersRepository;
+pub use vendors::VendorsRepository;
 pub use visitor_counts::VisitorCountsRepository;
+pub use warehouse::WarehouseRepository;
 pub use z3950::{Z3950Repository, Z3950ServerRecord};
 
 use std::sync::Arc;
@@ -90,6 +150,31 @@ impl Repository {
             .unwrap_or_else(|| crate::config::HoldsConfig::default().ready_expiry_days as i32)
     }
 
+    /// Queue order of hold priorities (`holds.priority_order`), from config or the default order.
+    pub(crate) fn hold_priority_order(&self) -> Vec<crate::models::hold::HoldPriority> {
+        self.dynamic_config
+            .as_ref()
+            .map(|dc| dc.read_holds().priority_order)
+            .unwrap_or_else(|| crate::config::HoldsConfig::default().priority_order)
+    }
+
+    /// Days a returned loan stays in `loans` before the archival job (`circulation.archive_returned_after_days`);
+    /// **0** (also without dynamic config) archives it on return.
+    pub(crate) fn loans_archive_returned_after_days(&self) -> u32 {
+        self.dynamic_config
+            .as_ref()
+            .map(|dc| dc.read_circulation().archive_returned_after_days)
+            .unwrap_or(0)
+    }
+
+    /// Tags kept from stored MARC records when they are regenerated (`marc.preserved_tags`).
+    pub(crate) fn marc_preservation_policy(&self) -> crate::marc::translator::MarcPreservationPolicy {
+        self.dynamic_config
+            .as_ref()
+            .map(|dc| crate::marc::translator::MarcPreservationPolicy::new(&dc.file_config.marc.preserved_tags))
+            .unwrap_or_default()
+    }
+
     /// Expose the underlying pool for callers that need to begin transactions directly.
     pub fn pool(&self) -> &Pool<Postgres> {
         &self.pool
@@ -0,0 +1,171 @@
+//! In-app notifications (`notifications`) domain methods on Repository
+
+use async_trait::async_trait;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::notification::{NewNotification, Notification},
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait NotificationsRepository: Send + Sync {
+    async fn notifications_create(&self, user_id: i64, data: &NewNotification) -> AppResult<Notification>;
+    /// Notifications of a user, newest first, with the total matching count
+    async fn notifications_list(
+        &self,
+        user_id: i64,
+        unread_only: bool,
+        page: i64,
+        per_page: i64,
+    ) -> AppResult<(Vec<Notification>, i64)>;
+    async fn notifications_unread_count(&self, user_id: i64) -> AppResult<i64>;
+    /// Mark one of the user's notifications as read (kept as is when already read)
+    async fn notifications_mark_read(&self, user_id: i64, id: i64) -> AppResult<Notification>;
+    /// Mark every unread notification of the user as read; returns how many were
+    async fn notifications_mark_all_read(&self, user_id: i64) -> AppResult<u64>;
+    /// Active staff accounts with loan write rights (recipients of circulation escalations)
+    async fn notifications_staff_recipients(&self) -> AppResult<Vec<i64>>;
+}
+
+#[async_trait]
+impl NotificationsRepository for Repository {
+    async fn notifications_create(&self, user_id: i64, data: &NewNotification) -> AppResult<Notification> {
+        Repository::notifications_create(self, user_id, data).await
+    }
+    async fn notifications_list(
+        &self,
+        user_id: i64,
+        unread_only: bool,
+        page: i64,
+        per_page: i64,
+    ) -> AppResult<(Vec<Notification>, i64)> {
+        Repository::notifications_list(self, user_id, unread_only, page, per_page).await
+    }
+    async fn notifications_unread_count(&self, user_id: i64) -> AppResult<i64> {
+        Repository::notifications_unread_count(self, user_id).await
+    }
+    async fn notifications_mark_read(&self, user_id: i64, id: i64) -> AppResult<Notification> {
+        Repository::notifications_mark_read(self, user_id, id).await
+    }
+    async fn notifications_mark_all_read(&self, user_id: i64) -> AppResult<u64> {
+        Repository::notifications_mark_all_read(self, user_id).await
+    }
+    async fn notifications_staff_recipients(&self) -> AppResult<Vec<i64>> {
+        Repository::notifications_staff_recipients(self).await
+    }
+}
+
+impl Repository {
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn notifications_create(&self, user_id: i64, data: &NewNotification) -> AppResult<Notification> {
+        let notification = sqlx::query_as::<_, Notification>(
+            r#"
+            INSERT INTO notifications (user_id, kind, title, body, entity_type, entity_id, data)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(data.kind)
+        .bind(&data.title)
+        .bind(&data.body)
+        .bind(&data.entity_type)
+        .bind(data.entity_id)
+        .bind(&data.data)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(notification)
+    }
+
+    /// Notifications of a user, newest first, with the total matching count
+    #[tracing::instrument(skip(self), err)]
+    pub async fn notifications_list(
+        &self,
+        user_id: i64,
+        unread_only: bool,
+        page: i64,
+        per_page: i64,
+    ) -> AppResult<(Vec<Notification>, i64)> {
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)",
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let rows = sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT * FROM notifications
+            WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)
+            ORDER BY created_at DESC, id DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(per_page)
+        .bind((page - 1) * per_page)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok((rows, total))
+    }
+
+    pub async fn notifications_unread_count(&self, user_id: i64) -> AppResult<i64> {
+        let count = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    /// Mark one of the user's notifications as read (the first read time is kept)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn notifications_mark_read(&self, user_id: i64, id: i64) -> AppResult<Notification> {
+        sqlx::query_as::<_, Notification>(
+            r#"
+            UPDATE notifications SET read_at = COALESCE(read_at, NOW())
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Notification {} not found", id)))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn notifications_mark_all_read(&self, user_id: i64) -> AppResult<u64> {
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = NOW() WHERE user_id = $1 AND read_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Active staff accounts with loan write rights (recipients of circulation escalations)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn notifications_staff_recipients(&self) -> AppResult<Vec<i64>> {
+        let ids = sqlx::query_scalar(
+            r#"
+            SELECT u.id FROM users u
+            JOIN account_types at ON at.code = u.account_type
+            WHERE at.loans_rights = 'w'
+              AND u.archived_at IS NULL
+              AND COALESCE(u.status, 'active') = 'active'
+            ORDER BY u.id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
+}
@@ -0,0 +1,607 @@
+//! Cash register domain methods on Repository (payments, refunds, cash-up totals)
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::payment::{
+        format_receipt_number, CashTotal, CashTotalsBy, MembershipTerms, OperatorCashTotal, Payment,
+        PaymentExportRow, PaymentPeriodLock, PaymentQuery, ReceiptGapReport, RecordPayment, RefundPayment,
+    },
+};
+
+const PAYMENT_SELECT: &str = r#"
+    SELECT p.id,
+           p.receipt_year || '-' || lpad(p.receipt_seq::text, GREATEST(6, length(p.receipt_seq::text)), '0')
+               AS receipt_number,
+           p.user_id, p.amount, p.method, p.category, p.fine_id, p.membership_expiry_at,
+           p.refund_of,
+           COALESCE((SELECT -SUM(r.amount) FROM payments r WHERE r.refund_of = p.id), 0) AS refunded_amount,
+           p.notes, p.operator_id, p.created_at
+    FROM payments p
+"#;
+
+const PAYMENT_FILTERS: &str = r#"
+    WHERE ($1::bigint IS NULL OR p.user_id = $1)
+      AND ($2::bigint IS NULL OR p.fine_id = $2)
+      AND ($3::varchar IS NULL OR p.category = $3)
+      AND ($4::varchar IS NULL OR p.method = $4)
+      AND ($5::timestamptz IS NULL OR p.created_at >= $5)
+      AND ($6::timestamptz IS NULL OR p.created_at < $6)
+"#;
+
+const PERIOD_LOCK_SELECT: &str = r#"
+    SELECT to_char(period, 'YYYY-MM') AS month, starts_at, ends_at, payments_count, total, locked_by, locked_at
+    FROM payment_period_locks
+"#;
+
+/// Received / refunded / net sums of a set of payments
+const TOTALS_COLUMNS: &str = r#"
+    COALESCE(SUM(p.amount) FILTER (WHERE p.amount > 0), 0) AS received,
+    COALESCE(-SUM(p.amount) FILTER (WHERE p.amount < 0), 0) AS refunded,
+    COALESCE(SUM(p.amount), 0) AS net,
+    COUNT(*) AS count
+"#;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait PaymentsRepository: Send + Sync {
+    /// Payments matching the filters, created in `[from, to)`, newest first, with the total count
+    async fn payments_list(
+        &self,
+        query: &PaymentQuery,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> AppResult<(Vec<Payment>, i64)>;
+    async fn payments_get(&self, id: i64) -> AppResult<Payment>;
+    /// Subscription duration and price of the user's public type (NotFound for an unknown user)
+    async fn payments_membership_terms(&self, user_id: i64) -> AppResult<MembershipTerms>;
+    /// Record a payment in one transaction with its effects: the fine balance for `fine_id`,
+    /// the membership expiry pushed `membership_days` further for a renewal, and the next
+    /// receipt number of `receipt_year`
+    async fn payments_create(
+        &self,
+        data: &RecordPayment,
+        amount: Decimal,
+        membership_days: Option<i32>,
+        operator_id: i64,
+        receipt_year: i32,
+    ) -> AppResult<Payment>;
+    /// Refund (part of) a payment under the next receipt number of `receipt_year`;
+    /// a refunded fine payment reopens the fine balance
+    async fn payments_refund(
+        &self,
+        id: i64,
+        data: &RefundPayment,
+        operator_id: i64,
+        receipt_year: i32,
+    ) -> AppResult<Payment>;
+    async fn payments_totals(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        by: CashTotalsBy,
+    ) -> AppResult<Vec<CashTotal>>;
+    async fn payments_operator_totals(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> AppResult<Vec<OperatorCashTotal>>;
+    /// Payments created in `[from, to)` in receipt number order
+    async fn payments_export_rows(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> AppResult<Vec<PaymentExportRow>>;
+    /// Lock the month starting on `period`; a month already locked keeps its first lock
+    async fn payments_lock_period(
+        &self,
+        period: NaiveDate,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        payments_count: i32,
+        total: Decimal,
+        locked_by: i64,
+    ) -> AppResult<PaymentPeriodLock>;
+    async fn payments_period_locks(&self) -> AppResult<Vec<PaymentPeriodLock>>;
+    /// Remove the lock of the month starting on `period` (NotFound when not locked)
+    async fn payments_unlock_period(&self, period: NaiveDate) -> AppResult<()>;
+    async fn payments_receipt_gaps(&self, year: i32) -> AppResult<ReceiptGapReport>;
+}
+
+#[async_trait]
+impl PaymentsRepository for Repository {
+    async fn payments_list(
+        &self,
+        query: &PaymentQuery,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> AppResult<(Vec<Payment>, i64)> {
+        Repository::payments_list(self, query, from, to).await
+    }
+    async fn payments_get(&self, id: i64) -> AppResult<Payment> {
+        Repository::payments_get(self, id).await
+    }
+    async fn payments_membership_terms(&self, user_id: i64) -> AppResult<MembershipTerms> {
+        Repository::payments_membership_terms(self, user_id).await
+    }
+    async fn payments_create(
+        &self,
+        data: &RecordPayment,
+        amount: Decimal,
+        membership_days: Option<i32>,
+        operator_id: i64,
+        receipt_year: i32,
+    ) -> AppResult<Payment> {
+        Repository::payments_create(self, data, amount, membership_days, operator_id, receipt_year).await
+    }
+    async fn payments_refund(
+        &self,
+        id: i64,
+        data: &RefundPayment,
+        operator_id: i64,
+        receipt_year: i32,
+    ) -> AppResult<Payment> {
+        Repository::payments_refund(self, id, data, operator_id, receipt_year).await
+    }
+    async fn payments_totals(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        by: CashTotalsBy,
+    ) -> AppResult<Vec<CashTotal>> {
+        Repository::payments_totals(self, from, to, by).await
+    }
+    async fn payments_operator_totals(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> AppResult<Vec<OperatorCashTotal>> {
+        Repository::payments_operator_totals(self, from, to).await
+    }
+    async fn payments_export_rows(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> AppResult<Vec<PaymentExportRow>> {
+        Repository::payments_export_rows(self, from, to).await
+    }
+    async fn payments_lock_period(
+        &self,
+        period: NaiveDate,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        payments_count: i32,
+        total: Decimal,
+        locked_by: i64,
+    ) -> AppResult<PaymentPeriodLock> {
+        Repository::payments_lock_period(self, period, from, to, payments_count, total, locked_by).await
+    }
+    async fn payments_period_locks(&self) -> AppResult<Vec<PaymentPeriodLock>> {
+        Repository::payments_period_locks(self).await
+    }
+    async fn payments_unlock_period(&self, period: NaiveDate) -> AppResult<()> {
+        Repository::payments_unlock_period(self, period).await
+    }
+    async fn payments_receipt_gaps(&self, year: i32) -> AppResult<ReceiptGapReport> {
+        Repository::payments_receipt_gaps(self, year).await
+    }
+}
+
+impl Repository {
+    #[tracing::instrument(skip(self), err)]
+    pub async fn payments_list(
+        &self,
+        query: &PaymentQuery,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> AppResult<(Vec<Payment>, i64)> {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(50).clamp(1, 200);
+
+        let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM payments p {PAYMENT_FILTERS}"))
+            .bind(query.user_id)
+            .bind(query.fine_id)
+            .bind(query.category)
+            .bind(query.method)
+            .bind(from)
+            .bind(to)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let payments = sqlx::query_as::<_, Payment>(&format!(
+            "{PAYMENT_SELECT} {PAYMENT_FILTERS} ORDER BY p.created_at DESC, p.id DESC LIMIT $7 OFFSET $8"
+        ))
+        .bind(query.user_id)
+        .bind(query.fine_id)
+        .bind(query.category)
+        .bind(query.method)
+        .bind(from)
+        .bind(to)
+        .bind(per_page)
+        .bind((page - 1) * per_page)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok((payments, total))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn payments_get(&self, id: i64) -> AppResult<Payment> {
+        sqlx::query_as::<_, Payment>(&format!("{PAYMENT_SELECT} WHERE p.id = $1"))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Payment {id} not found")))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn payments_membership_terms(&self, user_id: i64) -> AppResult<MembershipTerms> {
+        sqlx::query_as::<_, MembershipTerms>(
+            r#"
+            SELECT pt.subscription_duration_days AS duration_days, pt.subscription_price AS price_cents
+            FROM users u
+            LEFT JOIN public_types pt ON pt.id = u.public_type
+            WHERE u.id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("User {user_id} not found")))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn payments_create(
+        &self,
+        data: &RecordPayment,
+        amount: Decimal,
+        membership_days: Option<i32>,
+        operator_id: i64,
+        receipt_year: i32,
+    ) -> AppResult<Payment> {
+        let mut tx = self.pool.begin().await?;
+        let mut user_id = data.user_id;
+
+        if let Some(fine_id) = data.fine_id {
+            // `fines` is not created by the bundled migrations on every deployment
+            let has_fines: bool = sqlx::query_scalar("SELECT to_regclass('public.fines') IS NOT NULL")
+                .fetch_one(&mut *tx)
+                .await?;
+            let fine: Option<(i64, Decimal, bool)> = if has_fines {
+                sqlx::query_as(
+                    "SELECT user_id, amount - paid_amount, status IN ('paid', 'waived') FROM fines WHERE id = $1 FOR UPDATE",
+                )
+                .bind(fine_id)
+                .fetch_optional(&mut *tx)
+                .await?
+            } else {
+                None
+            };
+            let (fine_user_id, outstanding, settled) =
+                fine.ok_or_else(|| AppError::NotFound(format!("Fine {fine_id} not found")))?;
+            if settled {
+                return Err(AppError::BusinessRule(format!("Fine {fine_id} is already settled")));
+            }
+            if amount > outstanding {
+                return Err(AppError::Validation(format!(
+                    "Payment exceeds the outstanding balance of the fine ({outstanding})"
+                )));
+            }
+            if user_id.is_some_and(|id| id != fine_user_id) {
+                return Err(AppError::Validation("userId does not match the fine".to_string()));
+            }
+            user_id = Some(fine_user_id);
+            sqlx::query(
+                r#"
+                UPDATE fines SET
+                    paid_amount = paid_amount + $2,
+                    notes       = COALESCE($3, notes),
+                    paid_at     = CASE WHEN paid_amount + $2 >= amount THEN NOW() ELSE NULL END,
+                    status      = CASE WHEN paid_amount + $2 >= amount THEN 'paid' ELSE 'partial' END
+                WHERE id = $1
+                "#,
+            )
+            .bind(fine_id)
+            .bind(amount)
+            .bind(&data.notes)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let mut membership_expiry_at = None;
+        if let Some(user_id) = user_id {
+            let expiry: Option<Option<DateTime<Utc>>> = match membership_days {
+                // A renewal starts from the current expiry, or today when already expired
+                Some(days) => sqlx::query_scalar(
+                    r#"
+                    UPDATE users
+                    SET expiry_at = GREATEST(COALESCE(expiry_at, NOW()), NOW()) + make_interval(days => $2)
+                    WHERE id = $1
+                    RETURNING expiry_at
+                    "#,
+                )
+                .bind(user_id)
+                .bind(days)
+                .fetch_optional(&mut *tx)
+                .await?,
+                None => sqlx::query_scalar("SELECT NULL::timestamptz FROM users WHERE id = $1")
+                    .bind(user_id)
+                    .fetch_optional(&mut *tx)
+                    .await?,
+            };
+            membership_expiry_at = expiry.ok_or_else(|| AppError::NotFound(format!("User {user_id} not found")))?;
+        }
+
+        let receipt_seq = next_receipt_seq(&mut tx, receipt_year).await?;
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO payments (user_id, amount, method, category, fine_id, membership_expiry_at, notes, operator_id,
+                                  receipt_year, receipt_seq)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(amount)
+        .bind(data.method)
+        .bind(data.category)
+        .bind(data.fine_id)
+        .bind(membership_expiry_at)
+        .bind(&data.notes)
+        .bind(operator_id)
+        .bind(receipt_year)
+        .bind(receipt_seq)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        self.payments_get(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn payments_refund(
+        &self,
+        id: i64,
+        data: &RefundPayment,
+        operator_id: i64,
+        receipt_year: i32,
+    ) -> AppResult<Payment> {
+        let mut tx = self.pool.begin().await?;
+        let payment = sqlx::query_as::<_, Payment>(&format!("{PAYMENT_SELECT} WHERE p.id = $1 FOR UPDATE OF p"))
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Payment {id} not found")))?;
+        if payment.refund_of.is_some() {
+            return Err(AppError::BusinessRule("A refund cannot be refunded".to_string()));
+        }
+        let refundable = payment.amount - payment.refunded_amount;
+        if refundable <= Decimal::ZERO {
+            return Err(AppError::BusinessRule(format!("Payment {id} is already fully refunded")));
+        }
+        let amount = data.amount.unwrap_or(refundable);
+        if amount > refundable {
+            return Err(AppError::Validation(format!("Refund exceeds the refundable amount ({refundable})")));
+        }
+
+        if let Some(fine_id) = payment.fine_id {
+            let has_fines: bool = sqlx::query_scalar("SELECT to_regclass('public.fines') IS NOT NULL")
+                .fetch_one(&mut *tx)
+                .await?;
+            if has_fines {
+                // Waived fines stay waived; otherwise the refunded amount is owed again
+                sqlx::query(
+                    r#"
+                    UPDATE fines SET
+                        paid_amount = GREATEST(paid_amount - $2, 0),
+                        paid_at     = CASE WHEN status = 'waived' THEN paid_at ELSE NULL END,
+                        status      = CASE
+                            WHEN status = 'waived' THEN status
+                            WHEN paid_amount - $2 <= 0 THEN 'pending'
+                            ELSE 'partial'
+                        END
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(fine_id)
+                .bind(amount)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        let receipt_seq = next_receipt_seq(&mut tx, receipt_year).await?;
+        let refund_id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO payments (user_id, amount, method, category, fine_id, refund_of, notes, operator_id,
+                                  receipt_year, receipt_seq)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id
+            "#,
+        )
+        .bind(payment.user_id)
+        .bind(-amount)
+        .bind(data.method.unwrap_or(payment.method))
+        .bind(payment.category)
+        .bind(payment.fine_id)
+        .bind(id)
+        .bind(&data.notes)
+        .bind(operator_id)
+        .bind(receipt_year)
+        .bind(receipt_seq)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        self.payments_get(refund_id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn payments_totals(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        by: CashTotalsBy,
+    ) -> AppResult<Vec<CashTotal>> {
+        let column = match by {
+            CashTotalsBy::Method => "p.method",
+            CashTotalsBy::Category => "p.category",
+        };
+        let totals = sqlx::query_as::<_, CashTotal>(&format!(
+            r#"
+            SELECT {column}::text AS key, {TOTALS_COLUMNS}
+            FROM payments p
+            WHERE p.created_at >= $1 AND p.created_at < $2
+            GROUP BY {column}
+            ORDER BY {column}
+            "#
+        ))
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(totals)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn payments_operator_totals(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> AppResult<Vec<OperatorCashTotal>> {
+        let totals = sqlx::query_as::<_, OperatorCashTotal>(&format!(
+            r#"
+            SELECT p.operator_id, u.firstname, u.lastname, {TOTALS_COLUMNS}
+            FROM payments p
+            LEFT JOIN users u ON u.id = p.operator_id
+            WHERE p.created_at >= $1 AND p.created_at < $2
+            GROUP BY p.operator_id, u.firstname, u.lastname
+            ORDER BY u.lastname NULLS LAST, u.firstname, p.operator_id
+            "#
+        ))
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(totals)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn payments_export_rows(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> AppResult<Vec<PaymentExportRow>> {
+        let rows = sqlx::query_as::<_, PaymentExportRow>(
+            r#"
+            SELECT p.receipt_year, p.receipt_seq,
+                   p.receipt_year || '-' || lpad(p.receipt_seq::text, GREATEST(6, length(p.receipt_seq::text)), '0')
+                       AS receipt_number,
+                   p.created_at, p.amount, p.method, p.category,
+                   r.receipt_year || '-' || lpad(r.receipt_seq::text, GREATEST(6, length(r.receipt_seq::text)), '0')
+                       AS refund_of_receipt,
+                   NULLIF(TRIM(CONCAT_WS(' ', u.lastname, u.firstname)), '') AS payer,
+                   p.notes
+            FROM payments p
+            LEFT JOIN payments r ON r.id = p.refund_of
+            LEFT JOIN users u ON u.id = p.user_id
+            WHERE p.created_at >= $1 AND p.created_at < $2
+            ORDER BY p.receipt_year, p.receipt_seq
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn payments_lock_period(
+        &self,
+        period: NaiveDate,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        payments_count: i32,
+        total: Decimal,
+        locked_by: i64,
+    ) -> AppResult<PaymentPeriodLock> {
+        sqlx::query(
+            r#"
+            INSERT INTO payment_period_locks (period, starts_at, ends_at, payments_count, total, locked_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (period) DO NOTHING
+            "#,
+        )
+        .bind(period)
+        .bind(from)
+        .bind(to)
+        .bind(payments_count)
+        .bind(total)
+        .bind(locked_by)
+        .execute(&self.pool)
+        .await?;
+
+        let lock = sqlx::query_as::<_, PaymentPeriodLock>(&format!("{PERIOD_LOCK_SELECT} WHERE period = $1"))
+            .bind(period)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(lock)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn payments_period_locks(&self) -> AppResult<Vec<PaymentPeriodLock>> {
+        let locks = sqlx::query_as::<_, PaymentPeriodLock>(&format!("{PERIOD_LOCK_SELECT} ORDER BY period DESC"))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(locks)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn payments_unlock_period(&self, period: NaiveDate) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM payment_period_locks WHERE period = $1")
+            .bind(period)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Period {} is not locked", period.format("%Y-%m"))));
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn payments_receipt_gaps(&self, year: i32) -> AppResult<ReceiptGapReport> {
+        let issued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM payments WHERE receipt_year = $1")
+            .bind(year)
+            .fetch_one(&self.pool)
+            .await?;
+        let last_seq: Option<i32> = sqlx::query_scalar("SELECT last_seq FROM payment_receipt_counters WHERE year = $1")
+            .bind(year)
+            .fetch_optional(&self.pool)
+            .await?;
+        let missing: Vec<i32> = sqlx::query_scalar(
+            r#"
+            SELECT s.seq
+            FROM generate_series(1, $2::integer) AS s(seq)
+            WHERE NOT EXISTS (SELECT 1 FROM payments p WHERE p.receipt_year = $1 AND p.receipt_seq = s.seq)
+            ORDER BY s.seq
+            "#,
+        )
+        .bind(year)
+        .bind(last_seq.unwrap_or(0))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ReceiptGapReport {
+            year,
+            issued,
+            last_number: last_seq.map(|seq| format_receipt_number(year, seq)),
+            missing: missing.into_iter().map(|seq| format_receipt_number(year, seq)).collect(),
+        })
+    }
+}
+
+/// Take the next receipt number of `year` (the counter row stays locked until the transaction ends)
+async fn next_receipt_seq(tx: &mut sqlx::Transaction<'_, sqlx::Postgres>, year: i32) -> AppResult<i32> {
+    let seq: i32 = sqlx::query_scalar(
+        r#"
+        INSERT INTO payment_receipt_counters (year, last_seq) VALUES ($1, 1)
+        ON CONFLICT (year) DO UPDATE SET last_seq = payment_receipt_counters.last_seq + 1
+        RETURNING last_seq
+        "#,
+    )
+    .bind(year)
+    .fetch_one(&mut **tx)
+    .await?;
+    Ok(seq)
+}
@@ -0,0 +1,257 @@
+//! Purchase suggestions domain methods on Repository (patron suggestions, triage, availability)
+
+use async_trait::async_trait;
+
+use super::{users::HoldReadyUserContact, Repository};
+use crate::{
+    error::{AppError, AppResult},
+    models::purchase_suggestion::{
+        CreatePurchaseSuggestion, PurchaseSuggestion, PurchaseSuggestionQuery, TriagePurchaseSuggestion,
+    },
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait PurchaseSuggestionsRepository: Send + Sync {
+    async fn purchase_suggestions_list(&self, query: &PurchaseSuggestionQuery) -> AppResult<Vec<PurchaseSuggestion>>;
+    async fn purchase_suggestions_get_by_id(&self, id: i64) -> AppResult<PurchaseSuggestion>;
+    /// Record a suggestion; `isbn` is the normalized ISBN.
+    async fn purchase_suggestions_create(
+        &self,
+        user_id: i64,
+        isbn: Option<String>,
+        data: &CreatePurchaseSuggestion,
+    ) -> AppResult<PurchaseSuggestion>;
+    /// Whether the patron already has a pending or ordered suggestion for this ISBN.
+    async fn purchase_suggestions_open_exists(&self, user_id: i64, isbn: String) -> AppResult<bool>;
+    /// Active catalog record with this normalized ISBN and at least one copy, if any.
+    async fn purchase_suggestions_owned_biblio(&self, isbn: String) -> AppResult<Option<i64>>;
+    async fn purchase_suggestions_triage(
+        &self,
+        id: i64,
+        data: &TriagePurchaseSuggestion,
+        triaged_by: i64,
+    ) -> AppResult<PurchaseSuggestion>;
+    /// Mark as `added` (and stamp `notified_at`) the suggestions whose title now has a copy in the
+    /// catalog and whose patron was not notified yet; limited to one suggestion when `id` is set.
+    async fn purchase_suggestions_claim_available(&self, id: Option<i64>) -> AppResult<Vec<PurchaseSuggestion>>;
+    async fn purchase_suggestions_contact(&self, user_id: i64) -> AppResult<Option<HoldReadyUserContact>>;
+}
+
+#[async_trait]
+impl PurchaseSuggestionsRepository for Repository {
+    async fn purchase_suggestions_list(&self, query: &PurchaseSuggestionQuery) -> AppResult<Vec<PurchaseSuggestion>> {
+        Repository::purchase_suggestions_list(self, query).await
+    }
+    async fn purchase_suggestions_get_by_id(&self, id: i64) -> AppResult<PurchaseSuggestion> {
+        Repository::purchase_suggestions_get_by_id(self, id).await
+    }
+    async fn purchase_suggestions_create(
+        &self,
+        user_id: i64,
+        isbn: Option<String>,
+        data: &CreatePurchaseSuggestion,
+    ) -> AppResult<PurchaseSuggestion> {
+        Repository::purchase_suggestions_create(self, user_id, isbn, data).await
+    }
+    async fn purchase_suggestions_open_exists(&self, user_id: i64, isbn: String) -> AppResult<bool> {
+        Repository::purchase_suggestions_open_exists(self, user_id, &isbn).await
+    }
+    async fn purchase_suggestions_owned_biblio(&self, isbn: String) -> AppResult<Option<i64>> {
+        Repository::purchase_suggestions_owned_biblio(self, &isbn).await
+    }
+    async fn purchase_suggestions_triage(
+        &self,
+        id: i64,
+        data: &TriagePurchaseSuggestion,
+        triaged_by: i64,
+    ) -> AppResult<PurchaseSuggestion> {
+        Repository::purchase_suggestions_triage(self, id, data, triaged_by).await
+    }
+    async fn purchase_suggestions_claim_available(&self, id: Option<i64>) -> AppResult<Vec<PurchaseSuggestion>> {
+        Repository::purchase_suggestions_claim_available(self, id).await
+    }
+    async fn purchase_suggestions_contact(&self, user_id: i64) -> AppResult<Option<HoldReadyUserContact>> {
+        Repository::users_hold_ready_contact(self, user_id).await
+    }
+}
+
+impl Repository {
+    /// List suggestions (newest first)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn purchase_suggestions_list(&self, query: &PurchaseSuggestionQuery) -> AppResult<Vec<PurchaseSuggestion>> {
+        let rows = sqlx::query_as::<_, PurchaseSuggestion>(
+            r#"
+            SELECT * FROM purchase_suggestions
+            WHERE ($1::text IS NULL OR status = $1)
+              AND ($2::bigint IS NULL OR user_id = $2)
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .bind(query.status)
+        .bind(query.user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn purchase_suggestions_get_by_id(&self, id: i64) -> AppResult<PurchaseSuggestion> {
+        sqlx::query_as::<_, PurchaseSuggestion>("SELECT * FROM purchase_suggestions WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Purchase suggestion {} not found", id)))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn purchase_suggestions_create(
+        &self,
+        user_id: i64,
+        isbn: Option<String>,
+        data: &CreatePurchaseSuggestion,
+    ) -> AppResult<PurchaseSuggestion> {
+        let row = sqlx::query_as::<_, PurchaseSuggestion>(
+            r#"
+            INSERT INTO purchase_suggestions (user_id, isbn, title, author, notes)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(isbn)
+        .bind(data.title.trim())
+        .bind(data.author.as_deref().map(str::trim).filter(|a| !a.is_empty()))
+        .bind(&data.notes)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn purchase_suggestions_open_exists(&self, user_id: i64, isbn: &str) -> AppResult<bool> {
+        let exists: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM purchase_suggestions
+                WHERE user_id = $1 AND isbn = $2 AND status IN ('pending', 'ordered'))
+            "#,
+        )
+        .bind(user_id)
+        .bind(isbn)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(exists)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn purchase_suggestions_owned_biblio(&self, isbn: &str) -> AppResult<Option<i64>> {
+        let id: Option<i64> = sqlx::query_scalar(
+            r#"
+            SELECT b.id FROM biblios b
+            WHERE b.archived_at IS NULL
+              AND regexp_replace(upper(b.isbn), '[^0-9A-Z]', '', 'g') = $1
+              AND EXISTS (SELECT 1 FROM items i WHERE i.biblio_id = b.id AND i.archived_at IS NULL)
+            ORDER BY b.id
+            LIMIT 1
+            "#,
+        )
+        .bind(isbn)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(id)
+    }
+
+    /// Record a triage decision. Linked purchase order and biblio must exist.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn purchase_suggestions_triage(
+        &self,
+        id: i64,
+        data: &TriagePurchaseSuggestion,
+        triaged_by: i64,
+    ) -> AppResult<PurchaseSuggestion> {
+        if let Some(order_id) = data.purchase_order_id {
+            let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM purchase_orders WHERE id = $1)")
+                .bind(order_id)
+                .fetch_one(&self.pool)
+                .await?;
+            if !exists {
+                return Err(AppError::NotFound(format!("Purchase order {} not found", order_id)));
+            }
+        }
+        if let Some(biblio_id) = data.biblio_id {
+            let exists: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM biblios WHERE id = $1 AND archived_at IS NULL)",
+            )
+            .bind(biblio_id)
+            .fetch_one(&self.pool)
+            .await?;
+            if !exists {
+                return Err(AppError::NotFound(format!("Biblio {} not found", biblio_id)));
+            }
+        }
+
+        sqlx::query_as::<_, PurchaseSuggestion>(
+            r#"
+            UPDATE purchase_suggestions SET
+                status = $1,
+                rejection_reason = CASE WHEN $1 = 'rejected' THEN $2 END,
+                purchase_order_id = COALESCE($3, purchase_order_id),
+                biblio_id = COALESCE($4, biblio_id),
+                triaged_by = $5,
+                triaged_at = CASE WHEN $1 = 'pending' THEN NULL ELSE NOW() END
+            WHERE id = $6
+            RETURNING *
+            "#,
+        )
+        .bind(data.status)
+        .bind(data.rejection_reason.as_deref().map(str::trim))
+        .bind(data.purchase_order_id)
+        .bind(data.biblio_id)
+        .bind(triaged_by)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Purchase suggestion {} not found", id)))
+    }
+
+    /// Claim the suggestions whose title became available: the linked biblio, or else an active
+    /// biblio with the suggested ISBN, has a copy. Claimed rows are `added` with `notified_at`
+    /// set, so concurrent runs never notify a patron twice.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn purchase_suggestions_claim_available(&self, id: Option<i64>) -> AppResult<Vec<PurchaseSuggestion>> {
+        let rows = sqlx::query_as::<_, PurchaseSuggestion>(
+            r#"
+            WITH candidates AS (
+                SELECT s.id,
+                       COALESCE(s.biblio_id, (
+                           SELECT b.id FROM biblios b
+                           WHERE s.isbn IS NOT NULL
+                             AND b.archived_at IS NULL
+                             AND regexp_replace(upper(b.isbn), '[^0-9A-Z]', '', 'g') = s.isbn
+                           ORDER BY b.id
+                           LIMIT 1
+                       )) AS biblio_id
+                FROM purchase_suggestions s
+                WHERE s.status IN ('pending', 'ordered', 'added')
+                  AND s.notified_at IS NULL
+                  AND ($1::bigint IS NULL OR s.id = $1)
+                FOR UPDATE OF s SKIP LOCKED
+            )
+            UPDATE purchase_suggestions s SET
+                status = 'added',
+                biblio_id = c.biblio_id,
+                triaged_at = CASE WHEN s.status = 'added' THEN s.triaged_at ELSE NOW() END,
+                notified_at = NOW()
+            FROM candidates c
+            WHERE s.id = c.id
+              AND EXISTS (SELECT 1 FROM items i WHERE i.biblio_id = c.biblio_id AND i.archived_at IS NULL)
+            RETURNING s.*
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
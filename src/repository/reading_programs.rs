@@ -0,0 +1,453 @@
+//! Reading programs (`reading_programs`, enrollments, reading log) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::reading_program::{
+        CreateReadingEntry, CreateReadingProgram, ReadingAgeBracketStats, ReadingEnrollment,
+        ReadingEntry, ReadingProgram, ReturnedLoanForEntry, UpdateReadingProgram,
+    },
+};
+
+/// Entries of an enrollment that count towards the goal of its program (`p` = program, `e` = enrollment).
+const COUNTED_ENTRIES_SQL: &str = r#"
+    SELECT COUNT(*) FROM reading_entries r
+    WHERE r.enrollment_id = e.id
+      AND r.read_at BETWEEN p.start_date AND p.end_date
+      AND (NOT p.loans_only OR r.loan_archive_id IS NOT NULL)
+"#;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ReadingProgramsRepository: Send + Sync {
+    async fn reading_programs_list(&self) -> AppResult<Vec<ReadingProgram>>;
+    async fn reading_programs_get(&self, id: i64) -> AppResult<ReadingProgram>;
+    async fn reading_programs_create(&self, data: &CreateReadingProgram) -> AppResult<ReadingProgram>;
+    async fn reading_programs_update(&self, id: i64, data: &UpdateReadingProgram) -> AppResult<ReadingProgram>;
+    async fn reading_programs_delete(&self, id: i64) -> AppResult<()>;
+    async fn reading_programs_enrollments(&self, program_id: i64) -> AppResult<Vec<ReadingEnrollment>>;
+    async fn reading_programs_get_enrollment(&self, program_id: i64, enrollment_id: i64) -> AppResult<ReadingEnrollment>;
+    async fn reading_programs_enroll(&self, program_id: i64, user_id: i64) -> AppResult<ReadingEnrollment>;
+    async fn reading_programs_unenroll(&self, program_id: i64, enrollment_id: i64) -> AppResult<()>;
+    async fn reading_programs_entries(&self, enrollment_id: i64) -> AppResult<Vec<ReadingEntry>>;
+    async fn reading_programs_add_entry(&self, enrollment_id: i64, data: &CreateReadingEntry) -> AppResult<ReadingEntry>;
+    async fn reading_programs_delete_entry(&self, enrollment_id: i64, entry_id: i64) -> AppResult<()>;
+    /// Returned loan (`loans_archives`) with the record it was for.
+    async fn reading_programs_returned_loan(&self, loan_archive_id: i64) -> AppResult<ReturnedLoanForEntry>;
+    /// Log every loan returned within the program dates that is not logged yet; returns the number of entries created.
+    /// Only archived loans are logged (`circulation.archive_returned_after_days`).
+    async fn reading_programs_sync_loans(&self, program_id: i64) -> AppResult<u64>;
+    /// Set or clear `completed_at` from the entry count (one enrollment, or all when `None`); returns the number newly completed.
+    async fn reading_programs_refresh_completion(&self, program_id: i64, enrollment_id: Option<i64>) -> AppResult<i64>;
+    async fn reading_programs_stats(&self, program_id: i64) -> AppResult<Vec<ReadingAgeBracketStats>>;
+}
+
+#[async_trait]
+impl ReadingProgramsRepository for Repository {
+    async fn reading_programs_list(&self) -> AppResult<Vec<ReadingProgram>> {
+        Repository::reading_programs_list(self).await
+    }
+    async fn reading_programs_get(&self, id: i64) -> AppResult<ReadingProgram> {
+        Repository::reading_programs_get(self, id).await
+    }
+    async fn reading_programs_create(&self, data: &CreateReadingProgram) -> AppResult<ReadingProgram> {
+        Repository::reading_programs_create(self, data).await
+    }
+    async fn reading_programs_update(&self, id: i64, data: &UpdateReadingProgram) -> AppResult<ReadingProgram> {
+        Repository::reading_programs_update(self, id, data).await
+    }
+    async fn reading_programs_delete(&self, id: i64) -> AppResult<()> {
+        Repository::reading_programs_delete(self, id).await
+    }
+    async fn reading_programs_enrollments(&self, program_id: i64) -> AppResult<Vec<ReadingEnrollment>> {
+        Repository::reading_programs_enrollments(self, program_id).await
+    }
+    async fn reading_programs_get_enrollment(&self, program_id: i64, enrollment_id: i64) -> AppResult<ReadingEnrollment> {
+        Repository::reading_programs_get_enrollment(self, program_id, enrollment_id).await
+    }
+    async fn reading_programs_enroll(&self, program_id: i64, user_id: i64) -> AppResult<ReadingEnrollment> {
+        Repository::reading_programs_enroll(self, program_id, user_id).await
+    }
+    async fn reading_programs_unenroll(&self, program_id: i64, enrollment_id: i64) -> AppResult<()> {
+        Repository::reading_programs_unenroll(self, program_id, enrollment_id).await
+    }
+    async fn reading_programs_entries(&self, enrollment_id: i64) -> AppResult<Vec<ReadingEntry>> {
+        Repository::reading_programs_entries(self, enrollment_id).await
+    }
+    async fn reading_programs_add_entry(&self, enrollment_id: i64, data: &CreateReadingEntry) -> AppResult<ReadingEntry> {
+        Repository::reading_programs_add_entry(self, enrollment_id, data).await
+    }
+    async fn reading_programs_delete_entry(&self, enrollment_id: i64, entry_id: i64) -> AppResult<()> {
+        Repository::reading_programs_delete_entry(self, enrollment_id, entry_id).await
+    }
+    async fn reading_programs_returned_loan(&self, loan_archive_id: i64) -> AppResult<ReturnedLoanForEntry> {
+        Repository::reading_programs_returned_loan(self, loan_archive_id).await
+    }
+    async fn reading_programs_sync_loans(&self, program_id: i64) -> AppResult<u64> {
+        Repository::reading_programs_sync_loans(self, program_id).await
+    }
+    async fn reading_programs_refresh_completion(&self, program_id: i64, enrollment_id: Option<i64>) -> AppResult<i64> {
+        Repository::reading_programs_refresh_completion(self, program_id, enrollment_id).await
+    }
+    async fn reading_programs_stats(&self, program_id: i64) -> AppResult<Vec<ReadingAgeBracketStats>> {
+        Repository::reading_programs_stats(self, program_id).await
+    }
+}
+
+impl Repository {
+    /// List reading programs, most recent first
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_list(&self) -> AppResult<Vec<ReadingProgram>> {
+        let rows = sqlx::query_as::<_, ReadingProgram>(
+            "SELECT * FROM reading_programs ORDER BY start_date DESC, id DESC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get a reading program by ID
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_get(&self, id: i64) -> AppResult<ReadingProgram> {
+        sqlx::query_as::<_, ReadingProgram>("SELECT * FROM reading_programs WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Reading program {} not found", id)))
+    }
+
+    /// Create a reading program
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_create(&self, data: &CreateReadingProgram) -> AppResult<ReadingProgram> {
+        let row = sqlx::query_as::<_, ReadingProgram>(
+            r#"
+            INSERT INTO reading_programs (name, description, start_date, end_date, goal_books, loans_only)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(data.name.trim())
+        .bind(&data.description)
+        .bind(data.start_date)
+        .bind(data.end_date)
+        .bind(data.goal_books)
+        .bind(data.loans_only)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Update a reading program (absent fields are kept)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_update(&self, id: i64, data: &UpdateReadingProgram) -> AppResult<ReadingProgram> {
+        sqlx::query_as::<_, ReadingProgram>(
+            r#"
+            UPDATE reading_programs SET
+                name = COALESCE($1, name),
+                description = COALESCE($2, description),
+                start_date = COALESCE($3, start_date),
+                end_date = COALESCE($4, end_date),
+                goal_books = COALESCE($5, goal_books),
+                loans_only = COALESCE($6, loans_only),
+                update_at = $7
+            WHERE id = $8
+            RETURNING *
+            "#,
+        )
+        .bind(data.name.as_deref().map(str::trim))
+        .bind(&data.description)
+        .bind(data.start_date)
+        .bind(data.end_date)
+        .bind(data.goal_books)
+        .bind(data.loans_only)
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Reading program {} not found", id)))
+    }
+
+    /// Delete a reading program with its enrollments and reading log
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_delete(&self, id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM reading_programs WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Reading program {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Enrollments of a program with their progress, by patron name
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_enrollments(&self, program_id: i64) -> AppResult<Vec<ReadingEnrollment>> {
+        let sql = format!(
+            r#"
+            SELECT e.id, e.program_id, e.user_id, u.firstname, u.lastname, e.enrolled_at, e.completed_at,
+                   ({COUNTED_ENTRIES_SQL}) AS books_read, p.goal_books
+            FROM reading_program_enrollments e
+            JOIN reading_programs p ON p.id = e.program_id
+            JOIN users u ON u.id = e.user_id
+            WHERE e.program_id = $1
+            ORDER BY u.lastname, u.firstname, e.id
+            "#
+        );
+        let rows = sqlx::query_as::<_, ReadingEnrollment>(&sql)
+            .bind(program_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    /// Get one enrollment of a program with its progress
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_get_enrollment(&self, program_id: i64, enrollment_id: i64) -> AppResult<ReadingEnrollment> {
+        let sql = format!(
+            r#"
+            SELECT e.id, e.program_id, e.user_id, u.firstname, u.lastname, e.enrolled_at, e.completed_at,
+                   ({COUNTED_ENTRIES_SQL}) AS books_read, p.goal_books
+            FROM reading_program_enrollments e
+            JOIN reading_programs p ON p.id = e.program_id
+            JOIN users u ON u.id = e.user_id
+            WHERE e.program_id = $1 AND e.id = $2
+            "#
+        );
+        sqlx::query_as::<_, ReadingEnrollment>(&sql)
+            .bind(program_id)
+            .bind(enrollment_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Enrollment {} not found", enrollment_id)))
+    }
+
+    /// Enroll a patron (Conflict when already enrolled)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_enroll(&self, program_id: i64, user_id: i64) -> AppResult<ReadingEnrollment> {
+        let user_exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await?;
+        if !user_exists {
+            return Err(AppError::NotFound(format!("User {} not found", user_id)));
+        }
+
+        let id: Option<i64> = sqlx::query_scalar(
+            r#"
+            INSERT INTO reading_program_enrollments (program_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT (program_id, user_id) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(program_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        let id = id.ok_or_else(|| {
+            AppError::Conflict(format!("User {} is already enrolled in this program", user_id))
+        })?;
+        self.reading_programs_get_enrollment(program_id, id).await
+    }
+
+    /// Remove an enrollment and its reading log
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_unenroll(&self, program_id: i64, enrollment_id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM reading_program_enrollments WHERE program_id = $1 AND id = $2")
+            .bind(program_id)
+            .bind(enrollment_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Enrollment {} not found", enrollment_id)));
+        }
+        Ok(())
+    }
+
+    /// Reading log of an enrollment, most recent first
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_entries(&self, enrollment_id: i64) -> AppResult<Vec<ReadingEntry>> {
+        let rows = sqlx::query_as::<_, ReadingEntry>(
+            "SELECT * FROM reading_entries WHERE enrollment_id = $1 ORDER BY read_at DESC, id DESC",
+        )
+        .bind(enrollment_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Add a reading entry (fields already resolved by the service)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_add_entry(&self, enrollment_id: i64, data: &CreateReadingEntry) -> AppResult<ReadingEntry> {
+        if let Some(loan_archive_id) = data.loan_archive_id {
+            let logged: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM reading_entries WHERE enrollment_id = $1 AND loan_archive_id = $2)",
+            )
+            .bind(enrollment_id)
+            .bind(loan_archive_id)
+            .fetch_one(&self.pool)
+            .await?;
+            if logged {
+                return Err(AppError::Conflict(format!(
+                    "Loan {} is already logged for this enrollment",
+                    loan_archive_id
+                )));
+            }
+        }
+        if let Some(biblio_id) = data.biblio_id {
+            let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM biblios WHERE id = $1)")
+                .bind(biblio_id)
+                .fetch_one(&self.pool)
+                .await?;
+            if !exists {
+                return Err(AppError::NotFound(format!("Biblio {} not found", biblio_id)));
+            }
+        }
+
+        let row = sqlx::query_as::<_, ReadingEntry>(
+            r#"
+            INSERT INTO reading_entries (enrollment_id, read_at, title, biblio_id, loan_archive_id, notes)
+            VALUES ($1, COALESCE($2, CURRENT_DATE), $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(enrollment_id)
+        .bind(data.read_at)
+        .bind(data.title.as_deref().map(str::trim))
+        .bind(data.biblio_id)
+        .bind(data.loan_archive_id)
+        .bind(&data.notes)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Delete a reading entry
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_delete_entry(&self, enrollment_id: i64, entry_id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM reading_entries WHERE enrollment_id = $1 AND id = $2")
+            .bind(enrollment_id)
+            .bind(entry_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Reading entry {} not found", entry_id)));
+        }
+        Ok(())
+    }
+
+    /// Returned loan with the record it was for
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_returned_loan(&self, loan_archive_id: i64) -> AppResult<ReturnedLoanForEntry> {
+        sqlx::query_as::<_, ReturnedLoanForEntry>(
+            r#"
+            SELECT la.user_id, la.returned_at, b.id AS biblio_id, b.title
+            FROM loans_archives la
+            LEFT JOIN items i ON i.id = la.item_id
+            LEFT JOIN biblios b ON b.id = i.biblio_id
+            WHERE la.id = $1 AND la.returned_at IS NOT NULL
+            "#,
+        )
+        .bind(loan_archive_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Returned loan {} not found", loan_archive_id)))
+    }
+
+    /// Log every loan returned by enrolled patrons within the program dates
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_sync_loans(&self, program_id: i64) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO reading_entries (enrollment_id, read_at, title, biblio_id, loan_archive_id)
+            SELECT e.id, la.returned_at::date, b.title, b.id, la.id
+            FROM reading_program_enrollments e
+            JOIN reading_programs p ON p.id = e.program_id
+            JOIN loans_archives la ON la.user_id = e.user_id
+            LEFT JOIN items i ON i.id = la.item_id
+            LEFT JOIN biblios b ON b.id = i.biblio_id
+            WHERE e.program_id = $1
+              AND la.returned_at::date BETWEEN p.start_date AND p.end_date
+            ON CONFLICT (enrollment_id, loan_archive_id) WHERE loan_archive_id IS NOT NULL DO NOTHING
+            "#,
+        )
+        .bind(program_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Set `completed_at` on enrollments that reached the goal, clear it on the others
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_refresh_completion(&self, program_id: i64, enrollment_id: Option<i64>) -> AppResult<i64> {
+        let sql = format!(
+            r#"
+            WITH progress AS (
+                SELECT e.id, e.completed_at, ({COUNTED_ENTRIES_SQL}) >= p.goal_books AS reached
+                FROM reading_program_enrollments e
+                JOIN reading_programs p ON p.id = e.program_id
+                WHERE e.program_id = $1 AND ($2::bigint IS NULL OR e.id = $2)
+            ), updated AS (
+                UPDATE reading_program_enrollments t
+                SET completed_at = CASE WHEN pr.reached THEN COALESCE(t.completed_at, $3) ELSE NULL END
+                FROM progress pr
+                WHERE t.id = pr.id AND (pr.reached = (pr.completed_at IS NULL))
+                RETURNING pr.reached
+            )
+            SELECT COUNT(*) FILTER (WHERE reached) FROM updated
+            "#
+        );
+        let newly_completed: i64 = sqlx::query_scalar(&sql)
+            .bind(program_id)
+            .bind(enrollment_id)
+            .bind(Utc::now())
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(newly_completed)
+    }
+
+    /// Participation per age bracket (age at program start)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reading_programs_stats(&self, program_id: i64) -> AppResult<Vec<ReadingAgeBracketStats>> {
+        let sql = format!(
+            r#"
+            WITH participants AS (
+                SELECT
+                    CASE
+                        WHEN u.birthdate IS NULL THEN 'unknown'
+                        WHEN EXTRACT(YEAR FROM AGE(p.start_date, u.birthdate)) < 6 THEN '0-5'
+                        WHEN EXTRACT(YEAR FROM AGE(p.start_date, u.birthdate)) < 9 THEN '6-8'
+                        WHEN EXTRACT(YEAR FROM AGE(p.start_date, u.birthdate)) < 12 THEN '9-11'
+                        WHEN EXTRACT(YEAR FROM AGE(p.start_date, u.birthdate)) < 15 THEN '12-14'
+                        WHEN EXTRACT(YEAR FROM AGE(p.start_date, u.birthdate)) < 18 THEN '15-17'
+                        ELSE '18+'
+                    END AS age_bracket,
+                    e.completed_at,
+                    ({COUNTED_ENTRIES_SQL}) AS books_read
+                FROM reading_program_enrollments e
+                JOIN reading_programs p ON p.id = e.program_id
+                JOIN users u ON u.id = e.user_id
+                WHERE e.program_id = $1
+            )
+            SELECT age_bracket,
+                   COUNT(*) AS enrolled,
+                   COUNT(completed_at) AS completed,
+                   COALESCE(SUM(books_read), 0)::bigint AS books_read
+            FROM participants
+            GROUP BY age_bracket
+            ORDER BY CASE age_bracket
+                WHEN '0-5' THEN 0 WHEN '6-8' THEN 1 WHEN '9-11' THEN 2 WHEN '12-14' THEN 3
+                WHEN '15-17' THEN 4 WHEN '18+' THEN 5 ELSE 6 END
+            "#
+        );
+        let rows = sqlx::query_as::<_, ReadingAgeBracketStats>(&sql)
+            .bind(program_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,354 @@
+//! Re-engagement emails (`reengagement_emails`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::Repository;
+use crate::{
+    error::AppResult,
+    models::{
+        biblio::NewAcquisition,
+        reengagement::{ReengagementCandidate, ReengagementMonthStats},
+    },
+};
+
+/// Inactive members: active account with an email, opted in to campaigns, membership not
+/// expired, nothing on loan, last loan before `$1`, not emailed since `$2` (failed sends
+/// excepted) and address not reported undeliverable. Members who never borrowed are left out.
+const CANDIDATES_FROM: &str = r#"
+    FROM users u
+    JOIN LATERAL (SELECT MAX(l.date) AS last_loan_at FROM loans_all l WHERE l.user_id = u.id) last ON TRUE
+    WHERE COALESCE(u.status, 'active') = 'active'
+      AND u.archived_at IS NULL
+      AND u.campaigns_opt_in
+      AND NULLIF(TRIM(u.email), '') IS NOT NULL
+      AND (u.expiry_at IS NULL OR u.expiry_at >= NOW())
+      AND last.last_loan_at < $1
+      AND NOT EXISTS (SELECT 1 FROM loans l WHERE l.user_id = u.id AND l.returned_at IS NULL)
+      AND NOT EXISTS (SELECT 1 FROM reengagement_emails r
+                      WHERE r.user_id = u.id AND r.status <> 'failed' AND r.created_at >= $2)
+      AND NOT EXISTS (SELECT 1 FROM email_health h
+                      WHERE h.email = LOWER(TRIM(u.email))
+                        AND (h.undeliverable_at IS NOT NULL OR h.complaints > 0))
+"#;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ReengagementRepository: Send + Sync {
+    async fn reengagement_count_candidates(
+        &self,
+        inactive_before: DateTime<Utc>,
+        contacted_after: DateTime<Utc>,
+    ) -> AppResult<i64>;
+    /// Inactive members, most recently active first
+    async fn reengagement_candidates(
+        &self,
+        inactive_before: DateTime<Utc>,
+        contacted_after: DateTime<Utc>,
+        limit: i64,
+    ) -> AppResult<Vec<ReengagementCandidate>>;
+    /// Media type codes a member borrowed most
+    async fn reengagement_preferred_media_types(&self, user_id: i64, limit: i64) -> AppResult<Vec<String>>;
+    /// Titles acquired since `acquired_after` in `media_types` that the member never borrowed
+    async fn reengagement_new_titles(
+        &self,
+        user_id: i64,
+        media_types: &[String],
+        acquired_after: DateTime<Utc>,
+        limit: i64,
+    ) -> AppResult<Vec<NewAcquisition>>;
+    /// Record an email about to be sent; `None` when the member was emailed since
+    /// `contacted_after` (concurrent run)
+    async fn reengagement_claim(
+        &self,
+        candidate: &ReengagementCandidate,
+        media_types: &[String],
+        biblio_ids: &[i64],
+        contacted_after: DateTime<Utc>,
+    ) -> AppResult<Option<i64>>;
+    /// Record the SMTP outcome of a claimed email (`error` = failed)
+    async fn reengagement_finish(&self, id: i64, error: Option<String>) -> AppResult<()>;
+    /// Emails sent in `[from, to)` by month with their outcome, and the failed count
+    async fn reengagement_stats(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        conversion_days: i32,
+    ) -> AppResult<(Vec<ReengagementMonthStats>, i64)>;
+}
+
+#[async_trait]
+impl ReengagementRepository for Repository {
+    async fn reengagement_count_candidates(
+        &self,
+        inactive_before: DateTime<Utc>,
+        contacted_after: DateTime<Utc>,
+    ) -> AppResult<i64> {
+        Repository::reengagement_count_candidates(self, inactive_before, contacted_after).await
+    }
+    async fn reengagement_candidates(
+        &self,
+        inactive_before: DateTime<Utc>,
+        contacted_after: DateTime<Utc>,
+        limit: i64,
+    ) -> AppResult<Vec<ReengagementCandidate>> {
+        Repository::reengagement_candidates(self, inactive_before, contacted_after, limit).await
+    }
+    async fn reengagement_preferred_media_types(&self, user_id: i64, limit: i64) -> AppResult<Vec<String>> {
+        Repository::reengagement_preferred_media_types(self, user_id, limit).await
+    }
+    async fn reengagement_new_titles(
+        &self,
+        user_id: i64,
+        media_types: &[String],
+        acquired_after: DateTime<Utc>,
+        limit: i64,
+    ) -> AppResult<Vec<NewAcquisition>> {
+        Repository::reengagement_new_titles(self, user_id, media_types, acquired_after, limit).await
+    }
+    async fn reengagement_claim(
+        &self,
+        candidate: &ReengagementCandidate,
+        media_types: &[String],
+        biblio_ids: &[i64],
+        contacted_after: DateTime<Utc>,
+    ) -> AppResult<Option<i64>> {
+        Repository::reengagement_claim(self, candidate, media_types, biblio_ids, contacted_after).await
+    }
+    async fn reengagement_finish(&self, id: i64, error: Option<String>) -> AppResult<()> {
+        Repository::reengagement_finish(self, id, error.as_deref()).await
+    }
+    async fn reengagement_stats(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        conversion_days: i32,
+    ) -> AppResult<(Vec<ReengagementMonthStats>, i64)> {
+        Repository::reengagement_stats(self, from, to, conversion_days).await
+    }
+}
+
+impl Repository {
+    /// Number of members a run would select (without the per-run cap)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reengagement_count_candidates(
+        &self,
+        inactive_before: DateTime<Utc>,
+        contacted_after: DateTime<Utc>,
+    ) -> AppResult<i64> {
+        let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) {}", CANDIDATES_FROM))
+            .bind(inactive_before)
+            .bind(contacted_after)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    /// Inactive members, most recently active first (they are the likeliest to come back)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reengagement_candidates(
+        &self,
+        inactive_before: DateTime<Utc>,
+        contacted_after: DateTime<Utc>,
+        limit: i64,
+    ) -> AppResult<Vec<ReengagementCandidate>> {
+        let rows = sqlx::query_as::<_, ReengagementCandidate>(&format!(
+            r#"
+            SELECT u.id AS user_id, TRIM(u.email) AS email, u.firstname, u.lastname, u.language,
+                   last.last_loan_at
+            {}
+            ORDER BY last.last_loan_at DESC, u.id
+            LIMIT $3
+            "#,
+            CANDIDATES_FROM
+        ))
+        .bind(inactive_before)
+        .bind(contacted_after)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Media type codes of the member's loans (active, returned and archived), most borrowed first
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reengagement_preferred_media_types(&self, user_id: i64, limit: i64) -> AppResult<Vec<String>> {
+        let rows: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT b.media_type
+            FROM loans_all l
+            JOIN items i ON i.id = l.item_id
+            JOIN biblios b ON b.id = i.biblio_id
+            WHERE l.user_id = $1
+            GROUP BY b.media_type
+            ORDER BY COUNT(*) DESC, MAX(l.date) DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// New acquisitions (first OPAC-visible copy created after `acquired_after`) in the given
+    /// media types, newest first, leaving out the titles the member already borrowed
+    #[tracing::instrument(skip(self, media_types), err)]
+    pub async fn reengagement_new_titles(
+        &self,
+        user_id: i64,
+        media_types: &[String],
+        acquired_after: DateTime<Utc>,
+        limit: i64,
+    ) -> AppResult<Vec<NewAcquisition>> {
+        let rows = sqlx::query_as::<_, NewAcquisition>(
+            r#"
+            WITH acquired AS (
+                SELECT i.biblio_id, MIN(i.created_at) AS acquired_at
+                FROM items i
+                WHERE i.archived_at IS NULL AND i.created_at IS NOT NULL
+                  AND item_opac_visible(i.circulation_status)
+                GROUP BY i.biblio_id
+            )
+            SELECT b.id AS biblio_id, b.isbn, b.title, b.media_type, b.abstract,
+                   (SELECT COALESCE(a.display_name, CONCAT_WS(', ', a.lastname, a.firstname))
+                    FROM biblio_authors ba
+                    INNER JOIN authors a ON a.id = ba.author_id
+                    WHERE ba.biblio_id = b.id
+                    ORDER BY ba.position
+                    LIMIT 1) AS author,
+                   acq.acquired_at,
+                   GREATEST(acq.acquired_at, COALESCE(b.updated_at, acq.acquired_at)) AS updated_at
+            FROM acquired acq
+            INNER JOIN biblios b ON b.id = acq.biblio_id
+            WHERE b.archived_at IS NULL
+              AND acq.acquired_at >= $3
+              AND b.media_type = ANY($2)
+              AND NOT EXISTS (
+                  SELECT 1 FROM loans_all l
+                  JOIN items li ON li.id = l.item_id
+                  WHERE l.user_id = $1 AND li.biblio_id = b.id
+              )
+            ORDER BY acq.acquired_at DESC, b.id DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(user_id)
+        .bind(media_types)
+        .bind(acquired_after)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Record an email as `sending` unless the member was emailed since `contacted_after`
+    #[tracing::instrument(skip(self, candidate, media_types, biblio_ids), fields(user_id = candidate.user_id), err)]
+    pub async fn reengagement_claim(
+        &self,
+        candidate: &ReengagementCandidate,
+        media_types: &[String],
+        biblio_ids: &[i64],
+        contacted_after: DateTime<Utc>,
+    ) -> AppResult<Option<i64>> {
+        let mut tx = self.pool.begin().await?;
+        // Serializes concurrent runs (several instances), so a member is emailed once
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('elidune.reengagement'))")
+            .execute(&mut *tx)
+            .await?;
+        let id: Option<i64> = sqlx::query_scalar(
+            r#"
+            INSERT INTO reengagement_emails (user_id, last_loan_at, media_types, biblio_ids)
+            SELECT $1, $2, $3, $4
+            WHERE NOT EXISTS (
+                SELECT 1 FROM reengagement_emails
+                WHERE user_id = $1 AND status <> 'failed' AND created_at >= $5
+            )
+            RETURNING id
+            "#,
+        )
+        .bind(candidate.user_id)
+        .bind(candidate.last_loan_at)
+        .bind(media_types)
+        .bind(biblio_ids)
+        .bind(contacted_after)
+        .fetch_optional(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    /// Record the SMTP outcome of a claimed email
+    #[tracing::instrument(skip(self, error), err)]
+    pub async fn reengagement_finish(&self, id: i64, error: Option<&str>) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE reengagement_emails SET
+                status = CASE WHEN $2::text IS NULL THEN 'sent' ELSE 'failed' END,
+                error = $2,
+                sent_at = CASE WHEN $2::text IS NULL THEN NOW() END
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Emails sent in `[from, to)` by month: members who borrowed again within `conversion_days`
+    /// and loans of the suggested titles. Also returns the failed sends of the period.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reengagement_stats(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        conversion_days: i32,
+    ) -> AppResult<(Vec<ReengagementMonthStats>, i64)> {
+        let months = sqlx::query_as::<_, ReengagementMonthStats>(
+            r#"
+            WITH sent AS (
+                SELECT r.user_id, r.sent_at, r.biblio_ids,
+                       r.sent_at + make_interval(days => $3) AS window_end
+                FROM reengagement_emails r
+                WHERE r.status = 'sent' AND r.sent_at >= $1 AND r.sent_at < $2
+            ),
+            outcome AS (
+                SELECT s.sent_at,
+                       EXISTS (
+                           SELECT 1 FROM loans_all l
+                           WHERE l.user_id = s.user_id AND l.date > s.sent_at AND l.date <= s.window_end
+                       ) AS reactivated,
+                       (SELECT COUNT(*) FROM loans_all l
+                        JOIN items i ON i.id = l.item_id
+                        WHERE l.user_id = s.user_id AND i.biblio_id = ANY(s.biblio_ids)
+                          AND l.date > s.sent_at AND l.date <= s.window_end) AS suggested_loans
+                FROM sent s
+            )
+            SELECT TO_CHAR(DATE_TRUNC('month', o.sent_at), 'YYYY-MM') AS month,
+                   COUNT(*) AS sent,
+                   COUNT(*) FILTER (WHERE o.reactivated) AS reactivated,
+                   COALESCE(SUM(o.suggested_loans), 0)::bigint AS suggested_loans
+            FROM outcome o
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(conversion_days)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let failed: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM reengagement_emails WHERE status = 'failed' AND created_at >= $1 AND created_at < $2",
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok((months, failed))
+    }
+}
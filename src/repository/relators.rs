@@ -0,0 +1,162 @@
+//! Relator vocabulary (`relators`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::relator::{CreateRelator, RelatorDefinition, UpdateRelator},
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait RelatorsRepository: Send + Sync {
+    async fn relators_list(&self) -> AppResult<Vec<RelatorDefinition>>;
+    async fn relators_get(&self, code: &str) -> AppResult<RelatorDefinition>;
+    async fn relators_create(&self, data: &CreateRelator) -> AppResult<RelatorDefinition>;
+    async fn relators_update(&self, code: &str, data: &UpdateRelator) -> AppResult<RelatorDefinition>;
+    async fn relators_delete(&self, code: &str) -> AppResult<()>;
+    /// Number of author credits (`biblio_authors`) with this function.
+    async fn relators_count_credits(&self, code: &str) -> AppResult<i64>;
+}
+
+#[async_trait]
+impl RelatorsRepository for Repository {
+    async fn relators_list(&self) -> AppResult<Vec<RelatorDefinition>> {
+        Repository::relators_list(self).await
+    }
+    async fn relators_get(&self, code: &str) -> AppResult<RelatorDefinition> {
+        Repository::relators_get(self, code).await
+    }
+    async fn relators_create(&self, data: &CreateRelator) -> AppResult<RelatorDefinition> {
+        Repository::relators_create(self, data).await
+    }
+    async fn relators_update(&self, code: &str, data: &UpdateRelator) -> AppResult<RelatorDefinition> {
+        Repository::relators_update(self, code, data).await
+    }
+    async fn relators_delete(&self, code: &str) -> AppResult<()> {
+        Repository::relators_delete(self, code).await
+    }
+    async fn relators_count_credits(&self, code: &str) -> AppResult<i64> {
+        Repository::relators_count_credits(self, code).await
+    }
+}
+
+/// Aliases trimmed, blanks and duplicates dropped
+fn clean_aliases(aliases: &[String]) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::with_capacity(aliases.len());
+    for alias in aliases.iter().map(|a| a.trim()).filter(|a| !a.is_empty()) {
+        if !cleaned.iter().any(|c| c.eq_ignore_ascii_case(alias)) {
+            cleaned.push(alias.to_string());
+        }
+    }
+    cleaned
+}
+
+/// The MARC and UNIMARC codes identify one relator each
+fn marc_code_conflict(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(ref db) if db.is_unique_violation() => AppError::Conflict(
+            "Another relator already has this code, MARC code or UNIMARC code".to_string(),
+        ),
+        e => e.into(),
+    }
+}
+
+impl Repository {
+    /// List relators in display order
+    #[tracing::instrument(skip(self), err)]
+    pub async fn relators_list(&self) -> AppResult<Vec<RelatorDefinition>> {
+        let rows = sqlx::query_as::<_, RelatorDefinition>(
+            "SELECT * FROM relators ORDER BY sort_order, code",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get a relator by code
+    #[tracing::instrument(skip(self), err)]
+    pub async fn relators_get(&self, code: &str) -> AppResult<RelatorDefinition> {
+        sqlx::query_as::<_, RelatorDefinition>("SELECT * FROM relators WHERE code = $1")
+            .bind(code)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Relator {} not found", code)))
+    }
+
+    /// Create a relator
+    #[tracing::instrument(skip(self), err)]
+    pub async fn relators_create(&self, data: &CreateRelator) -> AppResult<RelatorDefinition> {
+        let row = sqlx::query_as::<_, RelatorDefinition>(
+            r#"
+            INSERT INTO relators (code, marc_code, unimarc_code, label, aliases, sort_order)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(&data.code)
+        .bind(&data.marc_code)
+        .bind(data.unimarc_code.as_deref().filter(|c| !c.is_empty()))
+        .bind(data.label.trim())
+        .bind(clean_aliases(&data.aliases))
+        .bind(data.sort_order.unwrap_or(50))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(marc_code_conflict)?;
+        Ok(row)
+    }
+
+    /// Update a relator (absent fields are kept; an empty `unimarc_code` clears it)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn relators_update(&self, code: &str, data: &UpdateRelator) -> AppResult<RelatorDefinition> {
+        sqlx::query_as::<_, RelatorDefinition>(
+            r#"
+            UPDATE relators SET
+                marc_code = COALESCE($1, marc_code),
+                unimarc_code = CASE WHEN $2::text IS NULL THEN unimarc_code ELSE NULLIF($2, '') END,
+                label = COALESCE($3, label),
+                aliases = COALESCE($4, aliases),
+                sort_order = COALESCE($5, sort_order),
+                update_at = $6
+            WHERE code = $7
+            RETURNING *
+            "#,
+        )
+        .bind(&data.marc_code)
+        .bind(&data.unimarc_code)
+        .bind(data.label.as_deref().map(str::trim))
+        .bind(data.aliases.as_deref().map(clean_aliases))
+        .bind(data.sort_order)
+        .bind(Utc::now())
+        .bind(code)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(marc_code_conflict)?
+        .ok_or_else(|| AppError::NotFound(format!("Relator {} not found", code)))
+    }
+
+    /// Delete a relator
+    #[tracing::instrument(skip(self), err)]
+    pub async fn relators_delete(&self, code: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM relators WHERE code = $1")
+            .bind(code)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Relator {} not found", code)));
+        }
+        Ok(())
+    }
+
+    /// Number of author credits with this function
+    #[tracing::instrument(skip(self), err)]
+    pub async fn relators_count_credits(&self, code: &str) -> AppResult<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM biblio_authors WHERE function = $1")
+            .bind(code)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+}
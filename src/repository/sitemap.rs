@@ -0,0 +1,124 @@
+//! OPAC sitemap (`sitemap_files`) domain methods on Repository
+
+use async_trait::async_trait;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::sitemap::{SitemapEntry, SitemapFile},
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SitemapRepository: Send + Sync {
+    /// Records shown by the OPAC with an ID above `after_id`, in ID order.
+    async fn sitemap_entries(&self, after_id: i64, limit: i64) -> AppResult<Vec<SitemapEntry>>;
+    /// New generation number for the files of a run.
+    async fn sitemap_begin(&self) -> AppResult<i64>;
+    async fn sitemap_store_file(&self, generation: i64, name: &str, content: &str, url_count: i64) -> AppResult<()>;
+    /// Publish the files of `generation` and delete the older generations.
+    async fn sitemap_publish(&self, generation: i64) -> AppResult<()>;
+    async fn sitemap_get_file(&self, name: &str) -> AppResult<SitemapFile>;
+}
+
+#[async_trait]
+impl SitemapRepository for Repository {
+    async fn sitemap_entries(&self, after_id: i64, limit: i64) -> AppResult<Vec<SitemapEntry>> {
+        Repository::sitemap_entries(self, after_id, limit).await
+    }
+    async fn sitemap_begin(&self) -> AppResult<i64> {
+        Repository::sitemap_begin(self).await
+    }
+    async fn sitemap_store_file(&self, generation: i64, name: &str, content: &str, url_count: i64) -> AppResult<()> {
+        Repository::sitemap_store_file(self, generation, name, content, url_count).await
+    }
+    async fn sitemap_publish(&self, generation: i64) -> AppResult<()> {
+        Repository::sitemap_publish(self, generation).await
+    }
+    async fn sitemap_get_file(&self, name: &str) -> AppResult<SitemapFile> {
+        Repository::sitemap_get_file(self, name).await
+    }
+}
+
+impl Repository {
+    /// Active records with an active copy, not hidden from the OPAC (see `biblio_opac_hidden`)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn sitemap_entries(&self, after_id: i64, limit: i64) -> AppResult<Vec<SitemapEntry>> {
+        let rows = sqlx::query_as::<_, SitemapEntry>(
+            r#"
+            SELECT b.id AS biblio_id, COALESCE(b.updated_at, b.created_at) AS lastmod
+            FROM biblios b
+            WHERE b.id > $1
+              AND b.archived_at IS NULL
+              AND EXISTS (SELECT 1 FROM items i WHERE i.biblio_id = b.id AND i.archived_at IS NULL)
+              AND NOT biblio_opac_hidden(b.id)
+            ORDER BY b.id
+            LIMIT $2
+            "#,
+        )
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn sitemap_begin(&self) -> AppResult<i64> {
+        let generation: i64 = sqlx::query_scalar("SELECT nextval('sitemap_generation_seq')")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(generation)
+    }
+
+    /// Store an unpublished file of a generation
+    #[tracing::instrument(skip(self, content), err)]
+    pub async fn sitemap_store_file(&self, generation: i64, name: &str, content: &str, url_count: i64) -> AppResult<()> {
+        sqlx::query(
+            "INSERT INTO sitemap_files (generation, name, content, url_count) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(generation)
+        .bind(name)
+        .bind(content)
+        .bind(url_count as i32)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Swap the published files for those of `generation` in one transaction. Older generations
+    /// (previously published, or left by failed runs) are deleted; runs started later by another
+    /// instance are left alone, and a generation older than the published one stays unpublished.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn sitemap_publish(&self, generation: i64) -> AppResult<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM sitemap_files WHERE generation < $1")
+            .bind(generation)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            UPDATE sitemap_files SET published = TRUE
+            WHERE generation = $1
+              AND NOT EXISTS (SELECT 1 FROM sitemap_files n WHERE n.generation > $1 AND n.published)
+            "#,
+        )
+        .bind(generation)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Published file by name
+    #[tracing::instrument(skip(self), err)]
+    pub async fn sitemap_get_file(&self, name: &str) -> AppResult<SitemapFile> {
+        sqlx::query_as::<_, SitemapFile>(
+            "SELECT name, content, generated_at FROM sitemap_files WHERE name = $1 AND published",
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Sitemap file {} not found", name)))
+    }
+}
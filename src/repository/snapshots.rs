@@ -0,0 +1,222 @@
+//! Catalog snapshots (`catalog_snapshots`, `catalog_snapshot_items`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::snapshot::{CatalogSnapshot, SnapshotRestoreReport, SnapshotScope},
+};
+
+const SNAPSHOT_COLUMNS: &str = "id, operation, item_count, created_by, created_at, restored_at, restored_by";
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SnapshotsRepository: Send + Sync {
+    /// Capture the copies of `scope`; `None` when there is none.
+    async fn snapshots_capture(
+        &self,
+        operation: &str,
+        scope: &SnapshotScope,
+        created_by: Option<i64>,
+    ) -> AppResult<Option<CatalogSnapshot>>;
+    async fn snapshots_list(&self) -> AppResult<Vec<CatalogSnapshot>>;
+    async fn snapshots_get(&self, id: i64) -> AppResult<CatalogSnapshot>;
+    /// Write the captured copies back and mark the snapshot restored (409 when it already is).
+    async fn snapshots_restore(&self, id: i64, restored_by: i64) -> AppResult<SnapshotRestoreReport>;
+    /// Delete snapshots created before `before` and all but the `keep` most recent ones.
+    async fn snapshots_prune(&self, before: DateTime<Utc>, keep: i64) -> AppResult<u64>;
+}
+
+#[async_trait]
+impl SnapshotsRepository for Repository {
+    async fn snapshots_capture(
+        &self,
+        operation: &str,
+        scope: &SnapshotScope,
+        created_by: Option<i64>,
+    ) -> AppResult<Option<CatalogSnapshot>> {
+        Repository::snapshots_capture(self, operation, scope, created_by).await
+    }
+    async fn snapshots_list(&self) -> AppResult<Vec<CatalogSnapshot>> {
+        Repository::snapshots_list(self).await
+    }
+    async fn snapshots_get(&self, id: i64) -> AppResult<CatalogSnapshot> {
+        Repository::snapshots_get(self, id).await
+    }
+    async fn snapshots_restore(&self, id: i64, restored_by: i64) -> AppResult<SnapshotRestoreReport> {
+        Repository::snapshots_restore(self, id, restored_by).await
+    }
+    async fn snapshots_prune(&self, before: DateTime<Utc>, keep: i64) -> AppResult<u64> {
+        Repository::snapshots_prune(self, before, keep).await
+    }
+}
+
+impl Repository {
+    /// Capture the copies of `scope` as JSON rows in a new snapshot
+    #[tracing::instrument(skip(self), err)]
+    pub async fn snapshots_capture(
+        &self,
+        operation: &str,
+        scope: &SnapshotScope,
+        created_by: Option<i64>,
+    ) -> AppResult<Option<CatalogSnapshot>> {
+        let (by_source, ids) = match scope {
+            SnapshotScope::Items(ids) => (false, ids),
+            SnapshotScope::Sources(ids) => (true, ids),
+        };
+        if ids.is_empty() {
+            return Ok(None);
+        }
+
+        let mut tx = self.pool.begin().await?;
+        let snapshot_id: i64 = sqlx::query_scalar(
+            "INSERT INTO catalog_snapshots (operation, item_count, created_by) VALUES ($1, 0, $2) RETURNING id",
+        )
+        .bind(operation)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await?;
+        let captured = sqlx::query(
+            r#"
+            INSERT INTO catalog_snapshot_items (snapshot_id, item_id, data)
+            SELECT $1, i.id, to_jsonb(i.*)
+            FROM items i
+            WHERE CASE WHEN $2 THEN i.source_id = ANY($3) ELSE i.id = ANY($3) END
+            "#,
+        )
+        .bind(snapshot_id)
+        .bind(by_source)
+        .bind(ids)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if captured == 0 {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+        let snapshot = sqlx::query_as::<_, CatalogSnapshot>(&format!(
+            "UPDATE catalog_snapshots SET item_count = $2 WHERE id = $1 RETURNING {}",
+            SNAPSHOT_COLUMNS
+        ))
+        .bind(snapshot_id)
+        .bind(captured as i32)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(Some(snapshot))
+    }
+
+    /// Snapshots, newest first
+    #[tracing::instrument(skip(self), err)]
+    pub async fn snapshots_list(&self) -> AppResult<Vec<CatalogSnapshot>> {
+        let rows = sqlx::query_as::<_, CatalogSnapshot>(&format!(
+            "SELECT {} FROM catalog_snapshots ORDER BY created_at DESC, id DESC",
+            SNAPSHOT_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get a snapshot by ID
+    #[tracing::instrument(skip(self), err)]
+    pub async fn snapshots_get(&self, id: i64) -> AppResult<CatalogSnapshot> {
+        sqlx::query_as::<_, CatalogSnapshot>(&format!(
+            "SELECT {} FROM catalog_snapshots WHERE id = $1",
+            SNAPSHOT_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Snapshot {} not found", id)))
+    }
+
+    /// Write the captured copies back in one transaction.
+    ///
+    /// Every column but the id, record and creation date is restored. A source, bundle or deposit
+    /// deleted since the snapshot is left as it is now rather than failing the restore.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn snapshots_restore(&self, id: i64, restored_by: i64) -> AppResult<SnapshotRestoreReport> {
+        let mut tx = self.pool.begin().await?;
+        let snapshot = sqlx::query_as::<_, CatalogSnapshot>(&format!(
+            "SELECT {} FROM catalog_snapshots WHERE id = $1 FOR UPDATE",
+            SNAPSHOT_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Snapshot {} not found", id)))?;
+        if snapshot.restored_at.is_some() {
+            return Err(AppError::Conflict(format!("Snapshot {} was already restored", id)));
+        }
+
+        let restored = sqlx::query(
+            r#"
+            UPDATE items i SET
+                source_id = CASE WHEN s.source_id IS NULL OR EXISTS (SELECT 1 FROM sources so WHERE so.id = s.source_id)
+                            THEN s.source_id ELSE i.source_id END,
+                barcode = s.barcode,
+                call_number = s.call_number,
+                volume_designation = s.volume_designation,
+                place = s.place,
+                borrowable = s.borrowable,
+                circulation_status = s.circulation_status,
+                notes = s.notes,
+                price = s.price,
+                access_url = s.access_url,
+                access_type = s.access_type,
+                bundle_id = CASE WHEN s.bundle_id IS NULL OR EXISTS (SELECT 1 FROM item_bundles b WHERE b.id = s.bundle_id)
+                            THEN s.bundle_id ELSE i.bundle_id END,
+                deposit_id = CASE WHEN s.deposit_id IS NULL OR EXISTS (SELECT 1 FROM deposits d WHERE d.id = s.deposit_id)
+                             THEN s.deposit_id ELSE i.deposit_id END,
+                archived_at = s.archived_at,
+                updated_at = NOW()
+            FROM catalog_snapshot_items c
+            CROSS JOIN LATERAL jsonb_populate_record(NULL::items, c.data) s
+            WHERE c.snapshot_id = $1 AND i.id = c.item_id
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => AppError::Conflict(
+                "A captured barcode is now used by another copy; change it before restoring".to_string(),
+            ),
+            e => e.into(),
+        })?
+        .rows_affected();
+
+        sqlx::query("UPDATE catalog_snapshots SET restored_at = NOW(), restored_by = $2 WHERE id = $1")
+            .bind(id)
+            .bind(restored_by)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(SnapshotRestoreReport {
+            snapshot_id: id,
+            restored,
+            missing: (snapshot.item_count as u64).saturating_sub(restored),
+        })
+    }
+
+    /// Delete expired snapshots and the oldest beyond `keep`
+    #[tracing::instrument(skip(self), err)]
+    pub async fn snapshots_prune(&self, before: DateTime<Utc>, keep: i64) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM catalog_snapshots
+            WHERE created_at < $1
+               OR id NOT IN (SELECT id FROM catalog_snapshots ORDER BY created_at DESC, id DESC LIMIT $2)
+            "#,
+        )
+        .bind(before)
+        .bind(keep)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -7,7 +7,7 @@ use sqlx::{Pool, Postgres};
 use super::Repository;
 use crate::{
     error::{AppError, AppResult},
-    models::source::Source,
+    models::source::{Source, SourceDeletionReport, SourceStats, SourceYearStats},
 };
 
 // Note: not `mockall::automock` — trait has `&str` parameters that mockall cannot derive for.
@@ -38,6 +38,14 @@ pub trait SourcesRepository: Send + Sync {
     async fn sources_archive_many(&self, ids: &[i64]) -> AppResult<()>;
     async fn sources_find_or_create_by_name(&self, name: &str) -> AppResult<i64>;
     async fn sources_get_default(&self) -> AppResult<Option<Source>>;
+    async fn sources_stats(&self, id: i64) -> AppResult<SourceStats>;
+    async fn sources_set_vendor(&self, id: i64, vendor_id: Option<i64>) -> AppResult<Source>;
+    async fn sources_delete_with_reassign(
+        &self,
+        id: i64,
+        target_id: i64,
+        dry_run: bool,
+    ) -> AppResult<SourceDeletionReport>;
     /// Expose the underlying pool so service-level transactions can be initiated.
     fn pool(&self) -> &Pool<Postgres>;
 }
@@ -80,6 +88,15 @@ impl SourcesRepository for Repository {
     async fn sources_get_default(&self) -> AppResult<Option<Source>> {
         Repository::sources_get_default(self).await
     }
+    async fn sources_stats(&self, id: i64) -> AppResult<SourceStats> {
+        Repository::sources_stats(self, id).await
+    }
+    async fn sources_set_vendor(&self, id: i64, vendor_id: Option<i64>) -> AppResult<Source> {
+        Repository::sources_set_vendor(self, id, vendor_id).await
+    }
+    async fn sources_delete_with_reassign(&self, id: i64, target_id: i64, dry_run: bool) -> AppResult<SourceDeletionReport> {
+        Repository::sources_delete_with_reassign(self, id, target_id, dry_run).await
+    }
     fn pool(&self) -> &sqlx::Pool<sqlx::Postgres> {
         &self.pool
     }
@@ -302,5 +319,155 @@ impl Repository {
         .await?;
         Ok(source)
     }
-}
 
+    /// Link a source to a vendor, or clear the link with `None`
+    pub async fn sources_set_vendor(&self, id: i64, vendor_id: Option<i64>) -> AppResult<Source> {
+        if let Some(vendor_id) = vendor_id {
+            let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM vendors WHERE id = $1)")
+                .bind(vendor_id)
+                .fetch_one(&self.pool)
+                .await?;
+            if !exists {
+                return Err(AppError::NotFound(format!("Vendor {} not found", vendor_id)));
+            }
+        }
+        sqlx::query_as::<_, Source>("UPDATE sources SET vendor_id = $1 WHERE id = $2 RETURNING *")
+            .bind(vendor_id)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Source {} not found", id)))
+    }
+
+    /// Items, biblios and loans attributable to a source, with a per-year breakdown.
+    pub async fn sources_stats(&self, id: i64) -> AppResult<SourceStats> {
+        let (active_items, archived_items, active_loans): (i64, i64, i64) = sqlx::query_as(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE it.archived_at IS NULL),
+                COUNT(*) FILTER (WHERE it.archived_at IS NOT NULL),
+                (SELECT COUNT(*) FROM loans l JOIN items li ON li.id = l.item_id
+                 WHERE li.source_id = $1 AND l.returned_at IS NULL)
+            FROM items it
+            WHERE it.source_id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let biblios: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM biblios WHERE source_id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let by_year = sqlx::query_as::<_, SourceYearStats>(
+            r#"
+            WITH source_items AS (
+                SELECT id, created_at FROM items WHERE source_id = $1
+            ),
+            added AS (
+                SELECT EXTRACT(YEAR FROM created_at)::int AS year, COUNT(*) AS n
+                FROM source_items
+                WHERE created_at IS NOT NULL
+                GROUP BY 1
+            ),
+            borrowed AS (
+                SELECT EXTRACT(YEAR FROM l.date)::int AS year, COUNT(*) AS n
+                FROM loans_all l
+                JOIN source_items si ON si.id = l.item_id
+                WHERE l.date IS NOT NULL
+                GROUP BY 1
+            )
+            SELECT
+                COALESCE(a.year, b.year) AS year,
+                COALESCE(a.n, 0) AS items_added,
+                COALESCE(b.n, 0) AS loans
+            FROM added a
+            FULL OUTER JOIN borrowed b ON a.year = b.year
+            ORDER BY 1 DESC
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(SourceStats {
+            source_id: id,
+            biblios,
+            active_items,
+            archived_items,
+            active_loans,
+            by_year,
+        })
+    }
+
+    /// Move items and biblios of `id` to `target_id`, then delete `id` — all in one transaction.
+    /// With `dry_run`, only counts the rows that would move.
+    pub async fn sources_delete_with_reassign(
+        &self,
+        id: i64,
+        target_id: i64,
+        dry_run: bool,
+    ) -> AppResult<SourceDeletionReport> {
+        let source = self.sources_get_by_id(id).await?;
+        let default_transferred = source.default == Some(true);
+
+        if dry_run {
+            let items_moved: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items WHERE source_id = $1")
+                .bind(id)
+                .fetch_one(&self.pool)
+                .await?;
+            let biblios_moved: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM biblios WHERE source_id = $1")
+                .bind(id)
+                .fetch_one(&self.pool)
+                .await?;
+            return Ok(SourceDeletionReport {
+                source_id: id,
+                target_source_id: target_id,
+                dry_run,
+                items_moved,
+                biblios_moved,
+                default_transferred,
+            });
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let items_moved = sqlx::query("UPDATE items SET source_id = $1 WHERE source_id = $2")
+            .bind(target_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as i64;
+        let biblios_moved = sqlx::query("UPDATE biblios SET source_id = $1 WHERE source_id = $2")
+            .bind(target_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as i64;
+
+        // Delete first so the partial unique index on "default" never sees two defaults.
+        sqlx::query("DELETE FROM sources WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if default_transferred {
+            sqlx::query(r#"UPDATE sources SET "default" = true WHERE id = $1"#)
+                .bind(target_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(SourceDeletionReport {
+            source_id: id,
+            target_source_id: target_id,
+            dry_run,
+            items_moved,
+            biblios_moved,
+            default_transferred,
+        })
+    }
+}
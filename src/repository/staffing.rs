@@ -0,0 +1,511 @@
+//! Staffing (shift templates, assignments, absences, calendar tokens) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveTime, Utc};
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::{
+        schedule::ScheduleSlot,
+        staffing::{
+            CreateShiftAssignment, CreateStaffAbsence, ShiftAssignment, ShiftOccurrence, ShiftTemplate,
+            ShiftTemplateFields, StaffAbsence, StaffAbsenceQuery,
+        },
+    },
+};
+
+/// Templates with their slot's period, day and (default) times
+const TEMPLATE_SELECT: &str = r#"
+    SELECT t.id, t.slot_id, s.period_id, s.day_of_week, t.name,
+           COALESCE(t.start_time, s.open_time) AS start_time,
+           COALESCE(t.end_time, s.close_time) AS end_time,
+           t.staff_needed, t.notes, t.created_at, t.update_at
+    FROM staff_shift_templates t
+    JOIN schedule_slots s ON s.id = t.slot_id
+"#;
+
+/// Assignments with the shift times, the user's name and whether an absence covers the day
+const ASSIGNMENT_SELECT: &str = r#"
+    SELECT a.id, a.template_id, t.name, a.shift_date,
+           COALESCE(t.start_time, s.open_time) AS start_time,
+           COALESCE(t.end_time, s.close_time) AS end_time,
+           a.user_id, u.firstname, u.lastname,
+           EXISTS(
+               SELECT 1 FROM staff_absences x
+               WHERE x.user_id = a.user_id AND a.shift_date BETWEEN x.start_date AND x.end_date
+           ) AS absent,
+           a.notes, a.created_at
+    FROM staff_shift_assignments a
+    JOIN staff_shift_templates t ON t.id = a.template_id
+    JOIN schedule_slots s ON s.id = t.slot_id
+    JOIN users u ON u.id = a.user_id
+"#;
+
+/// Owner of a calendar token: user id, first name, last name
+pub type CalendarOwner = (i64, Option<String>, Option<String>);
+
+const ABSENCE_SELECT: &str = r#"
+    SELECT x.id, x.user_id, u.firstname, u.lastname, x.start_date, x.end_date, x.reason, x.created_at
+    FROM staff_absences x
+    JOIN users u ON u.id = x.user_id
+"#;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait StaffingRepository: Send + Sync {
+    async fn staffing_get_slot(&self, slot_id: i64) -> AppResult<Option<ScheduleSlot>>;
+    async fn staffing_list_templates(&self, period_id: Option<i64>) -> AppResult<Vec<ShiftTemplate>>;
+    async fn staffing_get_template(&self, id: i64) -> AppResult<ShiftTemplate>;
+    /// `start` / `end` are the parsed times of `data` (`None` = slot bounds)
+    async fn staffing_create_template(
+        &self,
+        data: &ShiftTemplateFields,
+        start: Option<NaiveTime>,
+        end: Option<NaiveTime>,
+    ) -> AppResult<ShiftTemplate>;
+    async fn staffing_update_template(
+        &self,
+        id: i64,
+        data: &ShiftTemplateFields,
+        start: Option<NaiveTime>,
+        end: Option<NaiveTime>,
+    ) -> AppResult<ShiftTemplate>;
+    async fn staffing_delete_template(&self, id: i64) -> AppResult<()>;
+    /// Template occurrences between two days included: the slot belongs to the period in force
+    /// that day (the one starting last) and the day is not a closure.
+    async fn staffing_occurrences(&self, start: NaiveDate, end: NaiveDate) -> AppResult<Vec<ShiftOccurrence>>;
+    async fn staffing_assignments(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        user_id: Option<i64>,
+    ) -> AppResult<Vec<ShiftAssignment>>;
+    async fn staffing_get_assignment(&self, id: i64) -> AppResult<ShiftAssignment>;
+    /// NotFound for an unknown user, Conflict when already assigned to that shift
+    async fn staffing_create_assignment(
+        &self,
+        data: &CreateShiftAssignment,
+        created_by: i64,
+    ) -> AppResult<ShiftAssignment>;
+    async fn staffing_delete_assignment(&self, id: i64) -> AppResult<()>;
+    async fn staffing_list_absences(&self, query: &StaffAbsenceQuery) -> AppResult<Vec<StaffAbsence>>;
+    async fn staffing_get_absence(&self, id: i64) -> AppResult<StaffAbsence>;
+    async fn staffing_create_absence(
+        &self,
+        user_id: i64,
+        data: &CreateStaffAbsence,
+        created_by: i64,
+    ) -> AppResult<StaffAbsence>;
+    async fn staffing_delete_absence(&self, id: i64) -> AppResult<()>;
+    /// Replace the user's calendar token hash
+    async fn staffing_set_calendar_token(&self, user_id: i64, token_hash: &str) -> AppResult<()>;
+    /// Owner of a calendar token hash
+    async fn staffing_calendar_owner(
+        &self,
+        token_hash: &str,
+    ) -> AppResult<Option<CalendarOwner>>;
+}
+
+#[async_trait]
+impl StaffingRepository for Repository {
+    async fn staffing_get_slot(&self, slot_id: i64) -> AppResult<Option<ScheduleSlot>> {
+        Repository::staffing_get_slot(self, slot_id).await
+    }
+    async fn staffing_list_templates(&self, period_id: Option<i64>) -> AppResult<Vec<ShiftTemplate>> {
+        Repository::staffing_list_templates(self, period_id).await
+    }
+    async fn staffing_get_template(&self, id: i64) -> AppResult<ShiftTemplate> {
+        Repository::staffing_get_template(self, id).await
+    }
+    async fn staffing_create_template(
+        &self,
+        data: &ShiftTemplateFields,
+        start: Option<NaiveTime>,
+        end: Option<NaiveTime>,
+    ) -> AppResult<ShiftTemplate> {
+        Repository::staffing_create_template(self, data, start, end).await
+    }
+    async fn staffing_update_template(
+        &self,
+        id: i64,
+        data: &ShiftTemplateFields,
+        start: Option<NaiveTime>,
+        end: Option<NaiveTime>,
+    ) -> AppResult<ShiftTemplate> {
+        Repository::staffing_update_template(self, id, data, start, end).await
+    }
+    async fn staffing_delete_template(&self, id: i64) -> AppResult<()> {
+        Repository::staffing_delete_template(self, id).await
+    }
+    async fn staffing_occurrences(&self, start: NaiveDate, end: NaiveDate) -> AppResult<Vec<ShiftOccurrence>> {
+        Repository::staffing_occurrences(self, start, end).await
+    }
+    async fn staffing_assignments(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        user_id: Option<i64>,
+    ) -> AppResult<Vec<ShiftAssignment>> {
+        Repository::staffing_assignments(self, start, end, user_id).await
+    }
+    async fn staffing_get_assignment(&self, id: i64) -> AppResult<ShiftAssignment> {
+        Repository::staffing_get_assignment(self, id).await
+    }
+    async fn staffing_create_assignment(
+        &self,
+        data: &CreateShiftAssignment,
+        created_by: i64,
+    ) -> AppResult<ShiftAssignment> {
+        Repository::staffing_create_assignment(self, data, created_by).await
+    }
+    async fn staffing_delete_assignment(&self, id: i64) -> AppResult<()> {
+        Repository::staffing_delete_assignment(self, id).await
+    }
+    async fn staffing_list_absences(&self, query: &StaffAbsenceQuery) -> AppResult<Vec<StaffAbsence>> {
+        Repository::staffing_list_absences(self, query).await
+    }
+    async fn staffing_get_absence(&self, id: i64) -> AppResult<StaffAbsence> {
+        Repository::staffing_get_absence(self, id).await
+    }
+    async fn staffing_create_absence(
+        &self,
+        user_id: i64,
+        data: &CreateStaffAbsence,
+        created_by: i64,
+    ) -> AppResult<StaffAbsence> {
+        Repository::staffing_create_absence(self, user_id, data, created_by).await
+    }
+    async fn staffing_delete_absence(&self, id: i64) -> AppResult<()> {
+        Repository::staffing_delete_absence(self, id).await
+    }
+    async fn staffing_set_calendar_token(&self, user_id: i64, token_hash: &str) -> AppResult<()> {
+        Repository::staffing_set_calendar_token(self, user_id, token_hash).await
+    }
+    async fn staffing_calendar_owner(
+        &self,
+        token_hash: &str,
+    ) -> AppResult<Option<CalendarOwner>> {
+        Repository::staffing_calendar_owner(self, token_hash).await
+    }
+}
+
+impl Repository {
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_get_slot(&self, slot_id: i64) -> AppResult<Option<ScheduleSlot>> {
+        let row = sqlx::query_as::<_, ScheduleSlot>("SELECT * FROM schedule_slots WHERE id = $1")
+            .bind(slot_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row)
+    }
+
+    /// Templates by day of week, start time and name
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_list_templates(&self, period_id: Option<i64>) -> AppResult<Vec<ShiftTemplate>> {
+        let q = format!(
+            "{} WHERE ($1::bigint IS NULL OR s.period_id = $1) ORDER BY s.period_id, s.day_of_week, start_time, t.name, t.id",
+            TEMPLATE_SELECT
+        );
+        let rows = sqlx::query_as::<_, ShiftTemplate>(&q)
+            .bind(period_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_get_template(&self, id: i64) -> AppResult<ShiftTemplate> {
+        let q = format!("{} WHERE t.id = $1", TEMPLATE_SELECT);
+        sqlx::query_as::<_, ShiftTemplate>(&q)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Shift template {} not found", id)))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_create_template(
+        &self,
+        data: &ShiftTemplateFields,
+        start: Option<NaiveTime>,
+        end: Option<NaiveTime>,
+    ) -> AppResult<ShiftTemplate> {
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO staff_shift_templates (slot_id, name, start_time, end_time, staff_needed, notes)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id
+            "#,
+        )
+        .bind(data.slot_id)
+        .bind(data.name.trim())
+        .bind(start)
+        .bind(end)
+        .bind(data.staff_needed.unwrap_or(1))
+        .bind(&data.notes)
+        .fetch_one(&self.pool)
+        .await?;
+        self.staffing_get_template(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_update_template(
+        &self,
+        id: i64,
+        data: &ShiftTemplateFields,
+        start: Option<NaiveTime>,
+        end: Option<NaiveTime>,
+    ) -> AppResult<ShiftTemplate> {
+        let result = sqlx::query(
+            r#"
+            UPDATE staff_shift_templates SET
+                slot_id = $2, name = $3, start_time = $4, end_time = $5, staff_needed = $6, notes = $7,
+                update_at = $8
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(data.slot_id)
+        .bind(data.name.trim())
+        .bind(start)
+        .bind(end)
+        .bind(data.staff_needed.unwrap_or(1))
+        .bind(&data.notes)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Shift template {} not found", id)));
+        }
+        self.staffing_get_template(id).await
+    }
+
+    /// Delete a template and its assignments
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_delete_template(&self, id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM staff_shift_templates WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Shift template {} not found", id)));
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_occurrences(&self, start: NaiveDate, end: NaiveDate) -> AppResult<Vec<ShiftOccurrence>> {
+        let rows = sqlx::query_as::<_, ShiftOccurrence>(
+            r#"
+            SELECT t.id AS template_id, t.name, d.day AS shift_date,
+                   COALESCE(t.start_time, s.open_time) AS start_time,
+                   COALESCE(t.end_time, s.close_time) AS end_time,
+                   t.staff_needed
+            FROM (SELECT generate_series($1::date, $2::date, interval '1 day')::date AS day) d
+            JOIN LATERAL (
+                SELECT p.id FROM schedule_periods p
+                WHERE d.day BETWEEN p.start_date AND p.end_date
+                ORDER BY p.start_date DESC, p.id DESC
+                LIMIT 1
+            ) period ON TRUE
+            JOIN schedule_slots s
+              ON s.period_id = period.id AND s.day_of_week = EXTRACT(ISODOW FROM d.day)::smallint - 1
+            JOIN staff_shift_templates t ON t.slot_id = s.id
+            WHERE NOT EXISTS (SELECT 1 FROM schedule_closures c WHERE c.closure_date = d.day)
+            ORDER BY shift_date, start_time, t.name, t.id
+            "#,
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_assignments(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        user_id: Option<i64>,
+    ) -> AppResult<Vec<ShiftAssignment>> {
+        let q = format!(
+            r#"{}
+            WHERE a.shift_date BETWEEN $1 AND $2 AND ($3::bigint IS NULL OR a.user_id = $3)
+            ORDER BY a.shift_date, start_time, t.name, u.lastname, u.firstname, a.id
+            "#,
+            ASSIGNMENT_SELECT
+        );
+        let rows = sqlx::query_as::<_, ShiftAssignment>(&q)
+            .bind(start)
+            .bind(end)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_get_assignment(&self, id: i64) -> AppResult<ShiftAssignment> {
+        let q = format!("{} WHERE a.id = $1", ASSIGNMENT_SELECT);
+        sqlx::query_as::<_, ShiftAssignment>(&q)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Shift assignment {} not found", id)))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_create_assignment(
+        &self,
+        data: &CreateShiftAssignment,
+        created_by: i64,
+    ) -> AppResult<ShiftAssignment> {
+        self.staffing_ensure_user(data.user_id).await?;
+        let id: Option<i64> = sqlx::query_scalar(
+            r#"
+            INSERT INTO staff_shift_assignments (template_id, shift_date, user_id, notes, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (template_id, shift_date, user_id) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(data.template_id)
+        .bind(data.shift_date)
+        .bind(data.user_id)
+        .bind(&data.notes)
+        .bind(created_by)
+        .fetch_optional(&self.pool)
+        .await?;
+        let id = id.ok_or_else(|| {
+            AppError::Conflict(format!("User {} is already assigned to this shift", data.user_id))
+        })?;
+        self.staffing_get_assignment(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_delete_assignment(&self, id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM staff_shift_assignments WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Shift assignment {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Absences overlapping the range (open-ended on a missing bound), by start date
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_list_absences(&self, query: &StaffAbsenceQuery) -> AppResult<Vec<StaffAbsence>> {
+        let q = format!(
+            r#"{}
+            WHERE ($1::bigint IS NULL OR x.user_id = $1)
+              AND ($2::date IS NULL OR x.end_date >= $2)
+              AND ($3::date IS NULL OR x.start_date <= $3)
+            ORDER BY x.start_date, x.id
+            "#,
+            ABSENCE_SELECT
+        );
+        let rows = sqlx::query_as::<_, StaffAbsence>(&q)
+            .bind(query.user_id)
+            .bind(query.start)
+            .bind(query.end)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_get_absence(&self, id: i64) -> AppResult<StaffAbsence> {
+        let q = format!("{} WHERE x.id = $1", ABSENCE_SELECT);
+        sqlx::query_as::<_, StaffAbsence>(&q)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Absence {} not found", id)))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_create_absence(
+        &self,
+        user_id: i64,
+        data: &CreateStaffAbsence,
+        created_by: i64,
+    ) -> AppResult<StaffAbsence> {
+        self.staffing_ensure_user(user_id).await?;
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO staff_absences (user_id, start_date, end_date, reason, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(data.start_date)
+        .bind(data.end_date)
+        .bind(data.reason.as_deref().map(str::trim).filter(|r| !r.is_empty()))
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+        self.staffing_get_absence(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn staffing_delete_absence(&self, id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM staff_absences WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Absence {} not found", id)));
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, token_hash), err)]
+    pub async fn staffing_set_calendar_token(&self, user_id: i64, token_hash: &str) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO staff_calendar_tokens (user_id, token_hash) VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE SET token_hash = EXCLUDED.token_hash, created_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(token_hash)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, token_hash), err)]
+    pub async fn staffing_calendar_owner(
+        &self,
+        token_hash: &str,
+    ) -> AppResult<Option<CalendarOwner>> {
+        let row = sqlx::query_as::<_, CalendarOwner>(
+            r#"
+            SELECT u.id, u.firstname, u.lastname
+            FROM staff_calendar_tokens c
+            JOIN users u ON u.id = c.user_id
+            WHERE c.token_hash = $1 AND (u.status IS NULL OR u.status <> 'deleted')
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    async fn staffing_ensure_user(&self, user_id: i64) -> AppResult<()> {
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await?;
+        if !exists {
+            return Err(AppError::NotFound(format!("User {} not found", user_id)));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+//! Media type, audience and reading level categories shared by the dashboard statistics.
+//!
+//! Breakdowns and filters never use the raw `biblios` columns: they go through
+//! [`StatsCategory::label_sql`], a `CASE` generated from the model enums, so a new audience or
+//! media type is picked up by every stats query without editing them.
+
+use crate::models::biblio::{AudienceType, MediaType, ReadingLevel};
+
+/// Label of records with no (or a blank) value
+pub const UNKNOWN_LABEL: &str = "unknown";
+
+/// A biblio column the statistics break down and filter by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsCategory {
+    MediaType,
+    Audience,
+    ReadingLevel,
+}
+
+impl StatsCategory {
+    fn column(self) -> &'static str {
+        match self {
+            Self::MediaType => "media_type",
+            Self::Audience => "audience_type",
+            Self::ReadingLevel => "reading_level",
+        }
+    }
+
+    /// Audiences and reading levels are matched regardless of case; media codes are exact
+    /// (taxonomy codes are case-sensitive).
+    fn case_insensitive(self) -> bool {
+        !matches!(self, Self::MediaType)
+    }
+
+    /// Stored values mapped to a label other than themselves (or to their canonical spelling).
+    fn mappings(self) -> Vec<(String, String)> {
+        match self {
+            // Legacy one/three-letter codes still found on records imported before the taxonomy
+            Self::MediaType => MediaType::BUILT_IN
+                .iter()
+                .map(|m| (m.as_code().to_string(), m.as_db_str().to_string()))
+                .collect(),
+            Self::Audience => AudienceType::KNOWN
+                .iter()
+                .map(|a| (a.as_db_str().to_string(), a.as_db_str().to_string()))
+                .collect(),
+            Self::ReadingLevel => ReadingLevel::ALL
+                .iter()
+                .map(|l| (l.as_db_str().to_string(), l.as_db_str().to_string()))
+                .collect(),
+        }
+    }
+
+    /// SQL expression of the normalized label of `alias`'s column (`alias` is the biblios alias).
+    pub fn label_sql(self, alias: &str) -> String {
+        let column = format!("{}.{}", alias, self.column());
+        let key = if self.case_insensitive() {
+            format!("lower({})", column)
+        } else {
+            column.clone()
+        };
+        let mut sql = format!("CASE WHEN NULLIF(btrim({}), '') IS NULL THEN '{}'", column, UNKNOWN_LABEL);
+        for (stored, label) in self.mappings() {
+            if stored.is_empty() {
+                continue;
+            }
+            let stored = if self.case_insensitive() {
+                stored.to_lowercase()
+            } else {
+                stored
+            };
+            sql.push_str(&format!(" WHEN {} = '{}' THEN '{}'", key, stored, label));
+        }
+        sql.push_str(&format!(" ELSE {} END", column));
+        sql
+    }
+
+    /// Condition matching rows whose normalized label equals bind parameter `$param`.
+    pub fn filter_sql(self, alias: &str, param: usize) -> String {
+        format!("({}) = ${}", self.label_sql(alias), param)
+    }
+
+    /// Normalized label of a value, as computed by [`Self::label_sql`] (used on filter input).
+    pub fn normalize(self, value: Option<&str>) -> String {
+        let Some(value) = value.filter(|v| !v.trim().is_empty()) else {
+            return UNKNOWN_LABEL.to_string();
+        };
+        self.mappings()
+            .into_iter()
+            .find(|(stored, _)| {
+                !stored.is_empty()
+                    && if self.case_insensitive() {
+                        stored.eq_ignore_ascii_case(value)
+                    } else {
+                        stored == value
+                    }
+            })
+            .map(|(_, label)| label)
+            .unwrap_or_else(|| value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_are_normalized_like_the_sql() {
+        assert_eq!(StatsCategory::MediaType.normalize(Some("b")), "printedText");
+        assert_eq!(StatsCategory::MediaType.normalize(Some("printedText")), "printedText");
+        assert_eq!(StatsCategory::MediaType.normalize(Some("boardGame")), "boardGame");
+        assert_eq!(StatsCategory::MediaType.normalize(Some("  ")), UNKNOWN_LABEL);
+        assert_eq!(StatsCategory::Audience.normalize(Some("YoungAdult")), "youngAdult");
+        assert_eq!(StatsCategory::Audience.normalize(None), UNKNOWN_LABEL);
+        assert_eq!(StatsCategory::ReadingLevel.normalize(Some("TEEN")), "teen");
+
+        let sql = StatsCategory::MediaType.label_sql("i");
+        assert!(sql.starts_with("CASE WHEN NULLIF(btrim(i.media_type), '') IS NULL THEN 'unknown'"));
+        assert!(sql.contains("WHEN i.media_type = 'bc' THEN 'comics'"));
+        assert!(sql.ends_with("ELSE i.media_type END"));
+        let sql = StatsCategory::Audience.label_sql("b");
+        assert!(sql.contains("WHEN lower(b.audience_type) = 'youngadult' THEN 'youngAdult'"));
+        assert_eq!(StatsCategory::Audience.filter_sql("i", 3), format!("({}) = $3", StatsCategory::Audience.label_sql("i")));
+    }
+
+    #[test]
+    fn every_known_audience_is_mapped() {
+        let sql = StatsCategory::Audience.label_sql("i");
+        for audience in AudienceType::KNOWN {
+            assert!(sql.contains(&format!("THEN '{}'", audience.as_db_str())));
+        }
+    }
+}
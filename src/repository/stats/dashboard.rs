@@ -5,7 +5,7 @@ use sqlx::Row;
 
 use crate::{
     api::stats::{
-        Interval, ItemStats, LoanStats, LoanStatsResponse,
+        Interval, ItemStats, LoanLocationStats, LoanStats, LoanStatsResponse,
         StatEntry, StatsResponse, TimeSeriesEntry, UserLoanStats, UserStats,
         UserStatsSortBy,
     },
@@ -14,9 +14,11 @@ use crate::{
     repository::Repository,
 };
 
+use super::categories::StatsCategory;
+
 /// Filter for GET /stats (optional year, time interval, public_type, media_type).
 /// When set, item stats are computed as of reference_date and filtered by public_type/media_type.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StatsFilter {
     /// Holdings as of this date (e.g. 31/12 for a given year).
     pub reference_date: Option<NaiveDate>,
@@ -26,6 +28,17 @@ pub struct StatsFilter {
     pub media_type: Option<String>,
 }
 
+impl StatsFilter {
+    /// Same filter with public_type/media_type mapped to the labels the stats queries compare to.
+    fn normalized(self) -> Self {
+        Self {
+            public_type: self.public_type.map(|pt| StatsCategory::Audience.normalize(Some(&pt))),
+            media_type: self.media_type.map(|mt| StatsCategory::MediaType.normalize(Some(&mt))),
+            ..self
+        }
+    }
+}
+
 impl Repository {
     /// Build WHERE clause for item-based queries.
     /// Items (physical copies) are joined with biblios via `s` (items) and `i` (biblios) aliases.
@@ -50,12 +63,12 @@ impl Repository {
         if f.public_type.is_some() {
             let i = param_order.len() + 1;
             param_order.push("public_type".into());
-            conditions.push(format!("i.audience_type = ${}", i));
+            conditions.push(StatsCategory::Audience.filter_sql("i", i));
         }
         if f.media_type.is_some() {
             let i = param_order.len() + 1;
             param_order.push("media_type".into());
-            conditions.push(format!("i.media_type = ${}", i));
+            conditions.push(StatsCategory::MediaType.filter_sql("i", i));
         }
         (conditions.join(" AND "), param_order)
     }
@@ -65,7 +78,9 @@ impl Repository {
     #[tracing::instrument(skip(self), err)]
     pub async fn stats_get_stats(&self, filter: Option<StatsFilter>) -> AppResult<StatsResponse> {
         let pool = &self.pool;
+        let filter = filter.map(StatsFilter::normalized);
         let (spec_where, _param_order) = Self::stats_item_where_clause(&filter);
+        let media_label = StatsCategory::MediaType.label_sql("i");
 
         // Specimen stats (with optional filter)
         let total_items: i64 = {
@@ -90,10 +105,9 @@ impl Repository {
 
         let items_by_media_type = {
             let q = format!(
-                r#"SELECT COALESCE(i.media_type, 'unknown') as label, COUNT(*) as value
+                r#"SELECT {media_label} as label, COUNT(*) as value
                    FROM items s JOIN biblios i ON s.biblio_id = i.id
-                   WHERE {} GROUP BY i.media_type ORDER BY value DESC"#,
-                spec_where
+                   WHERE {spec_where} GROUP BY 1 ORDER BY value DESC"#
             );
             let mut query = sqlx::query(&q);
             if let Some(ref f) = filter {
@@ -114,16 +128,18 @@ impl Repository {
                 .map(|row| StatEntry {
                     label: row.get("label"),
                     value: row.get("value"),
+                    display_label: None,
                 })
                 .collect()
         };
 
         let items_by_public_type = {
             let q = format!(
-                r#"SELECT COALESCE(i.audience_type, 'unknown') as label,
+                r#"SELECT {} as label,
                           COUNT(*) as value
                    FROM items s JOIN biblios i ON s.biblio_id = i.id
-                   WHERE {} GROUP BY i.audience_type ORDER BY value DESC"#,
+                   WHERE {} GROUP BY 1 ORDER BY value DESC"#,
+                StatsCategory::Audience.label_sql("i"),
                 spec_where
             );
             let mut query = sqlx::query(&q);
@@ -145,6 +161,40 @@ impl Repository {
                 .map(|row| StatEntry {
                     label: row.get("label"),
                     value: row.get("value"),
+                    display_label: None,
+                })
+                .collect()
+        };
+
+        let items_by_reading_level = {
+            let q = format!(
+                r#"SELECT {} as label,
+                          COUNT(*) as value
+                   FROM items s JOIN biblios i ON s.biblio_id = i.id
+                   WHERE {} GROUP BY 1 ORDER BY value DESC"#,
+                StatsCategory::ReadingLevel.label_sql("i"),
+                spec_where
+            );
+            let mut query = sqlx::query(&q);
+            if let Some(ref f) = filter {
+                if let Some(ref d) = f.reference_date {
+                    query = query.bind(d);
+                }
+                if let Some(ref pt) = f.public_type {
+                    query = query.bind(pt.as_str());
+                }
+                if let Some(ref mt) = f.media_type {
+                    query = query.bind(mt.as_str());
+                }
+            }
+            query
+                .fetch_all(pool)
+                .await?
+                .into_iter()
+                .map(|row| StatEntry {
+                    label: row.get("label"),
+                    value: row.get("value"),
+                    display_label: None,
                 })
                 .collect()
         };
@@ -177,6 +227,7 @@ impl Repository {
         .map(|row| StatEntry {
             label: row.get("label"),
             value: row.get("value"),
+            display_label: None,
         })
         .collect();
 
@@ -192,33 +243,35 @@ impl Repository {
         .await?;
 
         let returned_today: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM loans_archives WHERE returned_at >= DATE_TRUNC('day', NOW())"
+            "SELECT COUNT(*) FROM loans_all WHERE returned_at >= DATE_TRUNC('day', NOW())"
         )
         .fetch_one(pool)
         .await?;
 
-        let loans_by_media_type = sqlx::query(
+        let loans_by_media_type = sqlx::query(&format!(
             r#"
-            SELECT COALESCE(i.media_type, 'unknown') as label, COUNT(*) as value
+            SELECT {media_label} as label, COUNT(*) as value
             FROM loans l
             JOIN items s ON l.item_id = s.id
             JOIN biblios i ON s.biblio_id = i.id
             WHERE l.returned_at IS NULL
-            GROUP BY i.media_type
+            GROUP BY 1
             ORDER BY value DESC
             "#,
-        )
+        ))
         .fetch_all(pool)
         .await?
         .into_iter()
         .map(|row| StatEntry {
             label: row.get("label"),
             value: row.get("value"),
+            display_label: None,
         })
         .collect();
 
         // Acquisitions and withdrawals (only when a reference date / year is set)
         // Based on items table joined with biblios for media_type/public_type filters.
+        // Deposit copies are not library property: their arrival and return are not counted.
         let (acquisitions, acquisitions_by_media_type, withdrawals, withdrawals_by_media_type) = if let Some(ref f) = filter {
             if let Some(ref_date) = f.reference_date {
                 let year_start = chrono::NaiveDate::from_ymd_opt(ref_date.year(), 1, 1).unwrap();
@@ -229,17 +282,17 @@ impl Repository {
                 let mut param_offset = 2_usize; // $1 and $2 are year_start and year_end
                 if f.public_type.is_some() {
                     param_offset += 1;
-                    extra_cond.push_str(&format!(" AND i.audience_type = ${}", param_offset));
+                    extra_cond.push_str(&format!(" AND {}", StatsCategory::Audience.filter_sql("i", param_offset)));
                 }
                 if f.media_type.is_some() {
                     param_offset += 1;
-                    extra_cond.push_str(&format!(" AND i.media_type = ${}", param_offset));
+                    extra_cond.push_str(&format!(" AND {}", StatsCategory::MediaType.filter_sql("i", param_offset)));
                 }
                 let _ = param_offset;
 
                 // Acquisitions total
                 let acq_q = format!(
-                    "SELECT COUNT(*) FROM items s JOIN biblios i ON s.biblio_id = i.id WHERE s.created_at >= $1 AND s.created_at <= $2 AND s.archived_at IS NULL{}",
+                    "SELECT COUNT(*) FROM items s JOIN biblios i ON s.biblio_id = i.id WHERE s.created_at >= $1 AND s.created_at <= $2 AND s.archived_at IS NULL AND s.deposit_id IS NULL{}",
                     extra_cond
                 );
                 let mut acq_builder = sqlx::query_scalar::<_, i64>(&acq_q)
@@ -251,8 +304,8 @@ impl Repository {
 
                 // Acquisitions by media type
                 let acq_mt_q = format!(
-                    "SELECT COALESCE(i.media_type, 'unknown') as label, COUNT(*) as value FROM items s JOIN biblios i ON s.biblio_id = i.id WHERE s.created_at >= $1 AND s.created_at <= $2 AND s.archived_at IS NULL{} GROUP BY i.media_type ORDER BY value DESC",
-                    extra_cond
+                    "SELECT {} as label, COUNT(*) as value FROM items s JOIN biblios i ON s.biblio_id = i.id WHERE s.created_at >= $1 AND s.created_at <= $2 AND s.archived_at IS NULL AND s.deposit_id IS NULL{} GROUP BY 1 ORDER BY value DESC",
+                    media_label, extra_cond
                 );
                 let mut acq_mt_builder = sqlx::query(&acq_mt_q)
                     .bind(year_start)
@@ -260,11 +313,11 @@ impl Repository {
                 if let Some(ref pt) = f.public_type { acq_mt_builder = acq_mt_builder.bind(pt.as_str()); }
                 if let Some(ref mt) = f.media_type { acq_mt_builder = acq_mt_builder.bind(mt.as_str()); }
                 let acq_by_mt: Vec<StatEntry> = acq_mt_builder.fetch_all(pool).await?
-                    .into_iter().map(|row| StatEntry { label: row.get("label"), value: row.get("value") }).collect();
+                    .into_iter().map(|row| StatEntry { label: row.get("label"), value: row.get("value"), display_label: None }).collect();
 
                 // Withdrawals total
                 let wd_q = format!(
-                    "SELECT COUNT(*) FROM items s JOIN biblios i ON s.biblio_id = i.id WHERE s.archived_at >= $1 AND s.archived_at <= $2{}",
+                    "SELECT COUNT(*) FROM items s JOIN biblios i ON s.biblio_id = i.id WHERE s.archived_at >= $1 AND s.archived_at <= $2 AND s.deposit_id IS NULL{}",
                     extra_cond
                 );
                 let mut wd_builder = sqlx::query_scalar::<_, i64>(&wd_q)
@@ -276,8 +329,8 @@ impl Repository {
 
                 // Withdrawals by media type
                 let wd_mt_q = format!(
-                    "SELECT COALESCE(i.media_type, 'unknown') as label, COUNT(*) as value FROM items s JOIN biblios i ON s.biblio_id = i.id WHERE s.archived_at >= $1 AND s.archived_at <= $2{} GROUP BY i.media_type ORDER BY value DESC",
-                    extra_cond
+                    "SELECT {} as label, COUNT(*) as value FROM items s JOIN biblios i ON s.biblio_id = i.id WHERE s.archived_at >= $1 AND s.archived_at <= $2 AND s.deposit_id IS NULL{} GROUP BY 1 ORDER BY value DESC",
+                    media_label, extra_cond
                 );
                 let mut wd_mt_builder = sqlx::query(&wd_mt_q)
                     .bind(year_start)
@@ -285,7 +338,7 @@ impl Repository {
                 if let Some(ref pt) = f.public_type { wd_mt_builder = wd_mt_builder.bind(pt.as_str()); }
                 if let Some(ref mt) = f.media_type { wd_mt_builder = wd_mt_builder.bind(mt.as_str()); }
                 let wd_by_mt: Vec<StatEntry> = wd_mt_builder.fetch_all(pool).await?
-                    .into_iter().map(|row| StatEntry { label: row.get("label"), value: row.get("value") }).collect();
+                    .into_iter().map(|row| StatEntry { label: row.get("label"), value: row.get("value"), display_label: None }).collect();
 
                 (acq_total, acq_by_mt, wd_total, wd_by_mt)
             } else {
@@ -300,6 +353,7 @@ impl Repository {
                 total: total_items,
                 by_media_type: items_by_media_type,
                 by_public_type: items_by_public_type,
+                by_reading_level: items_by_reading_level,
                 acquisitions,
                 acquisitions_by_media_type,
                 withdrawals,
@@ -316,6 +370,8 @@ impl Repository {
                 returned_today,
                 by_media_type: loans_by_media_type,
             },
+            previous: None,
+            deltas: None,
         })
     }
 
@@ -346,11 +402,7 @@ impl Repository {
             FROM users u
             LEFT JOIN (
                 SELECT user_id, COUNT(*) as total_loans
-                FROM (
-                    SELECT user_id FROM loans
-                    UNION ALL
-                    SELECT user_id FROM loans_archives
-                ) l
+                FROM loans_all
                 GROUP BY user_id
             ) t ON t.user_id = u.id
             LEFT JOIN (
@@ -388,6 +440,22 @@ impl Repository {
         Ok(stats)
     }
 
+    /// Media type / public type conditions on the biblios alias `i`, bound as `$1`, `$2`...
+    /// Returns the conditions and the values to bind, in order.
+    fn stats_loan_category_filters(media_type: Option<&MediaType>, public_type: Option<&str>) -> (Vec<String>, Vec<String>) {
+        let mut conditions = Vec::new();
+        let mut binds = Vec::new();
+        if let Some(mt) = media_type {
+            binds.push(StatsCategory::MediaType.normalize(Some(mt.as_db_str())));
+            conditions.push(StatsCategory::MediaType.filter_sql("i", binds.len()));
+        }
+        if let Some(pt) = public_type {
+            binds.push(StatsCategory::Audience.normalize(Some(pt)));
+            conditions.push(StatsCategory::Audience.filter_sql("i", binds.len()));
+        }
+        (conditions, binds)
+    }
+
     /// Get advanced loan statistics with time series
     #[tracing::instrument(skip(self), err)]
     pub async fn stats_get_loan_stats(
@@ -420,19 +488,15 @@ impl Repository {
             Interval::Year => "YYYY",
         };
 
+        // Media type / public type filters, shared by every query below (bound as $1, $2...)
+        let (category_where, category_binds) = Self::stats_loan_category_filters(media_type, public_type);
+
         // Build WHERE clause
         let mut where_clauses = vec![
             format!("l.date >= '{}'", start.format("%Y-%m-%d %H:%M:%S")),
             format!("l.date <= '{}'", end.format("%Y-%m-%d %H:%M:%S")),
         ];
-
-        if let Some(mt) = media_type {
-            where_clauses.push(format!("i.media_type = '{}'", mt));
-        }
-
-        if let Some(pt) = public_type {
-            where_clauses.push(format!("i.audience_type = '{}'", pt));
-        }
+        where_clauses.extend(category_where.iter().cloned());
 
         if let Some(uid) = user_id {
             where_clauses.push(format!("l.user_id = {}", uid));
@@ -440,13 +504,13 @@ impl Repository {
 
         let where_clause = where_clauses.join(" AND ");
 
-        // Query for loans (from active loans table)
+        // Query for loans (active, returned and archived)
         let loans_query = format!(
             r#"
             SELECT 
                 TO_CHAR({}, '{}') as period,
                 COUNT(*) as count
-            FROM loans l
+            FROM loans_all l
             JOIN items s ON l.item_id = s.id
             JOIN biblios i ON s.biblio_id = i.id
             WHERE {}
@@ -456,54 +520,9 @@ impl Repository {
             date_trunc, date_format, where_clause, date_trunc
         );
 
-        let loans_data: Vec<(String, i64)> = sqlx::query(&loans_query)
-            .fetch_all(pool)
-            .await?
-            .into_iter()
-            .map(|row| {
-                let period: String = row.get("period");
-                let count: i64 = row.get("count");
-                (period, count)
-            })
-            .collect();
-
-        // Query for loans from archives table (historical loans)
-        let mut archived_loans_where = vec![
-            format!("la.date >= '{}'", start.format("%Y-%m-%d %H:%M:%S")),
-            format!("la.date <= '{}'", end.format("%Y-%m-%d %H:%M:%S")),
-        ];
-
-        if let Some(mt) = media_type {
-            archived_loans_where.push(format!("i.media_type = '{}'", mt));
-        }
-
-        if let Some(pt) = public_type {
-            archived_loans_where.push(format!("i.audience_type = '{}'", pt));
-        }
-
-        if let Some(uid) = user_id {
-            archived_loans_where.push(format!("la.user_id = {}", uid));
-        }
-
-        let archived_loans_where_clause = archived_loans_where.join(" AND ");
-        let archived_loans_date_trunc = date_trunc.replace("date", "la.date");
-
-        let archived_loans_query = format!(
-            r#"
-            SELECT 
-                TO_CHAR({}, '{}') as period,
-                COUNT(*) as count
-            FROM loans_archives la
-            JOIN items s ON la.item_id = s.id
-            JOIN biblios i ON s.biblio_id = i.id
-            WHERE {}
-            GROUP BY {}
-            ORDER BY period
-            "#,
-            archived_loans_date_trunc, date_format, archived_loans_where_clause, archived_loans_date_trunc
-        );
-
-        let archived_loans_data: Vec<(String, i64)> = sqlx::query(&archived_loans_query)
+        let loans_data: Vec<(String, i64)> = category_binds
+            .iter()
+            .fold(sqlx::query(&loans_query), |query, value| query.bind(value))
             .fetch_all(pool)
             .await?
             .into_iter()
@@ -514,20 +533,13 @@ impl Repository {
             })
             .collect();
 
-        // Query for returns (from loans_archives table)
+        // Query for returns (archived or not yet, see the loans archival policy)
         let mut returns_where = vec![
             format!("la.returned_at >= '{}'", start.format("%Y-%m-%d %H:%M:%S")),
             format!("la.returned_at <= '{}'", end.format("%Y-%m-%d %H:%M:%S")),
             "la.returned_at IS NOT NULL".to_string(),
         ];
-
-        if let Some(mt) = media_type {
-            returns_where.push(format!("i.media_type = '{}'", mt));
-        }
-
-        if let Some(pt) = public_type {
-            returns_where.push(format!("i.audience_type = '{}'", pt));
-        }
+        returns_where.extend(category_where.iter().cloned());
 
         if let Some(uid) = user_id {
             returns_where.push(format!("la.user_id = {}", uid));
@@ -541,7 +553,7 @@ impl Repository {
             SELECT 
                 TO_CHAR({}, '{}') as period,
                 COUNT(*) as count
-            FROM loans_archives la
+            FROM loans_all la
             JOIN items s ON la.item_id = s.id
             JOIN biblios i ON s.biblio_id = i.id
             WHERE {}
@@ -551,7 +563,9 @@ impl Repository {
             returns_date_trunc, date_format, returns_where_clause, returns_date_trunc
         );
 
-        let returns_data: Vec<(String, i64)> = sqlx::query(&returns_query)
+        let returns_data: Vec<(String, i64)> = category_binds
+            .iter()
+            .fold(sqlx::query(&returns_query), |query, value| query.bind(value))
             .fetch_all(pool)
             .await?
             .into_iter()
@@ -570,10 +584,6 @@ impl Repository {
             period_map.entry(period).or_insert((0, 0)).0 += count;
         }
 
-        for (period, count) in archived_loans_data {
-            period_map.entry(period).or_insert((0, 0)).0 += count;
-        }
-
         for (period, count) in returns_data {
             period_map.entry(period).or_insert((0, 0)).1 += count;
         }
@@ -597,25 +607,60 @@ impl Repository {
         let by_media_type_query = format!(
             r#"
             SELECT 
-                COALESCE(i.media_type, 'unknown') as label,
+                {} as label,
                 COUNT(*) as value
-            FROM loans l
+            FROM loans_all l
             JOIN items s ON l.item_id = s.id
             JOIN biblios i ON s.biblio_id = i.id
             WHERE {}
-            GROUP BY i.media_type
+            GROUP BY 1
             ORDER BY value DESC
             "#,
+            StatsCategory::MediaType.label_sql("i"),
             where_clause
         );
 
-        let by_media_type = sqlx::query(&by_media_type_query)
+        let by_media_type = category_binds
+            .iter()
+            .fold(sqlx::query(&by_media_type_query), |query, value| query.bind(value))
             .fetch_all(pool)
             .await?
             .into_iter()
             .map(|row| StatEntry {
                 label: row.get("label"),
                 value: row.get("value"),
+                display_label: None,
+            })
+            .collect();
+
+        // Loans (active and archived) by the age band captured at checkout, which survives
+        // the anonymization of the borrower
+        let by_age_band_query = format!(
+            r#"
+            SELECT COALESCE(b.band, 'unknown') as label, COUNT(*) as value
+            FROM (
+                SELECT l.borrower_age_band AS band
+                FROM loans_all l
+                JOIN items s ON l.item_id = s.id
+                JOIN biblios i ON s.biblio_id = i.id
+                WHERE {}
+            ) b
+            GROUP BY 1
+            ORDER BY label
+            "#,
+            where_clause
+        );
+
+        let by_age_band = category_binds
+            .iter()
+            .fold(sqlx::query(&by_age_band_query), |query, value| query.bind(value))
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .map(|row| StatEntry {
+                label: row.get("label"),
+                value: row.get("value"),
+                display_label: None,
             })
             .collect();
 
@@ -624,6 +669,10 @@ impl Repository {
             total_returns,
             time_series,
             by_media_type,
+            by_age_band,
+            by_location: None,
+            previous: None,
+            deltas: None,
         })
     }
 
@@ -667,7 +716,7 @@ impl Repository {
         .fetch_all(pool)
         .await?
         .into_iter()
-        .map(|row| StatEntry { label: row.get("label"), value: row.get("value") })
+        .map(|row| StatEntry { label: row.get("label"), value: row.get("value"), display_label: None })
         .collect();
 
         // Users by sex
@@ -683,7 +732,7 @@ impl Repository {
         .fetch_all(pool)
         .await?
         .into_iter()
-        .map(|row| StatEntry { label: row.get("label"), value: row.get("value") })
+        .map(|row| StatEntry { label: row.get("label"), value: row.get("value"), display_label: None })
         .collect();
 
         // New users: created in the period and not deleted
@@ -718,7 +767,7 @@ impl Repository {
         .fetch_all(pool)
         .await?
         .into_iter()
-        .map(|row| StatEntry { label: row.get("label"), value: row.get("value") })
+        .map(|row| StatEntry { label: row.get("label"), value: row.get("value"), display_label: None })
         .collect();
 
         // New users by sex
@@ -737,7 +786,7 @@ impl Repository {
         .fetch_all(pool)
         .await?
         .into_iter()
-        .map(|row| StatEntry { label: row.get("label"), value: row.get("value") })
+        .map(|row| StatEntry { label: row.get("label"), value: row.get("value"), display_label: None })
         .collect();
 
         // Active borrowers: at least one loan (active or archived) in the period
@@ -748,13 +797,7 @@ impl Repository {
             WHERE (u.status IS NULL OR u.status <> 'deleted')
               AND EXISTS (
                 SELECT 1
-                FROM (
-                  SELECT user_id, date
-                  FROM loans
-                  UNION ALL
-                  SELECT user_id, date
-                  FROM loans_archives
-                ) l
+                FROM loans_all l
                 WHERE l.user_id = u.id
                   AND l.date >= $1
                   AND l.date <= $2
@@ -775,11 +818,7 @@ impl Repository {
             WHERE (u.status IS NULL OR u.status <> 'deleted')
               AND EXISTS (
                 SELECT 1
-                FROM (
-                  SELECT user_id, date FROM loans
-                  UNION ALL
-                  SELECT user_id, date FROM loans_archives
-                ) l
+                FROM loans_all l
                 WHERE l.user_id = u.id AND l.date >= $1 AND l.date <= $2
               )
             GROUP BY pt.name ORDER BY value DESC
@@ -790,7 +829,7 @@ impl Repository {
         .fetch_all(pool)
         .await?
         .into_iter()
-        .map(|row| StatEntry { label: row.get("label"), value: row.get("value") })
+        .map(|row| StatEntry { label: row.get("label"), value: row.get("value"), display_label: None })
         .collect();
 
         // Groups total (collectivites with active registration up to end date)
@@ -830,6 +869,7 @@ impl Repository {
         by_source: bool,
         by_media_type: bool,
         by_public_type: bool,
+        by_digital_resource: bool,
     ) -> AppResult<crate::api::stats::CatalogStatsResponse> {
         let pool = &self.pool;
 
@@ -866,14 +906,22 @@ impl Repository {
             .fetch_one(pool)
             .await?;
 
-        // Loans in period (active + archived via items)
+        // Loans in period (active, returned and archived)
         let total_loans: i64 = 
             sqlx::query_scalar(
-                r#"SELECT COUNT(*) FROM (
-                    SELECT id, date FROM loans
-                    UNION ALL
-                    SELECT id, date FROM loans_archives
-                ) all_loans WHERE date >= $1 AND date <= $2"#
+                "SELECT COUNT(*) FROM loans_all WHERE date >= $1 AND date <= $2"
+            )
+            .bind(start)
+            .bind(end)
+            .fetch_one(pool)
+            .await?;
+
+        // Digital resources: linked copies and click-throughs in period
+        let (digital_items, digital_accesses): (i64, i64) =
+            sqlx::query_as(
+                r#"SELECT
+                    (SELECT COUNT(*) FROM items WHERE access_url IS NOT NULL AND archived_at IS NULL),
+                    (SELECT COUNT(*) FROM item_accesses WHERE accessed_at >= $1 AND accessed_at <= $2)"#
             )
             .bind(start)
             .bind(end)
@@ -885,8 +933,51 @@ impl Repository {
             entered_items,
             archived_items,
             loans: total_loans,
+            digital_items,
+            digital_accesses,
+        };
+
+        let by_digital_resource_data = if by_digital_resource {
+            let rows = 
+                sqlx::query(
+                    r#"
+                    SELECT
+                        sp.id as item_id,
+                        sp.biblio_id,
+                        b.title,
+                        sp.access_type,
+                        COUNT(a.id) as accesses,
+                        COUNT(DISTINCT a.user_id) as unique_users
+                    FROM items sp
+                    JOIN biblios b ON sp.biblio_id = b.id
+                    LEFT JOIN item_accesses a
+                        ON a.item_id = sp.id AND a.accessed_at >= $1 AND a.accessed_at <= $2
+                    WHERE sp.access_url IS NOT NULL AND sp.archived_at IS NULL
+                    GROUP BY sp.id, sp.biblio_id, b.title, sp.access_type
+                    ORDER BY accesses DESC, sp.id
+                    "#
+                )
+                .bind(start)
+                .bind(end)
+                .fetch_all(pool)
+                .await?;
+
+            Some(rows.iter().map(|row| crate::api::stats::CatalogDigitalResourceStats {
+                item_id: row.get("item_id"),
+                biblio_id: row.get("biblio_id"),
+                title: row.get("title"),
+                access_type: row.get("access_type"),
+                accesses: row.get("accesses"),
+                unique_users: row.get("unique_users"),
+            }).collect())
+        } else {
+            None
         };
 
+        // Media type / audience labels shared by every breakdown below
+        let media_label = StatsCategory::MediaType.label_sql("i");
+        let audience_label = StatsCategory::Audience.label_sql("i");
+
         // --- By source (with optional nested media_type / public_type breakdowns) ---
         // When multiple flags are active, only the nested result is returned.
         // Hierarchy: source → media_type → public_type
@@ -895,22 +986,22 @@ impl Repository {
             if by_media_type && by_public_type {
                 // 3-level nesting: source → media_type → public_type
                 let rows = 
-                    sqlx::query(
+                    sqlx::query(&format!(
                         r#"
                         SELECT
                             COALESCE(src.id, 0) as source_id,
                             COALESCE(src.name, 'unknown') as source_name,
-                            COALESCE(i.media_type, 'unknown') as media_type_label,
-                            COALESCE(i.audience_type, 'unknown') as public_type_label,
+                            {media_label} as media_type_label,
+                            {audience_label} as public_type_label,
                             COUNT(*) FILTER (WHERE (sp.archived_at IS NULL OR sp.archived_at > $3)) as active_items,
                             COUNT(*) FILTER (WHERE sp.created_at >= $1 AND sp.created_at <= $2) as entered_items,
                             COUNT(*) FILTER (WHERE sp.archived_at >= $1 AND sp.archived_at <= $2) as archived_items
                         FROM items sp
                         LEFT JOIN sources src ON sp.source_id = src.id
                         JOIN biblios i ON sp.biblio_id = i.id
-                        GROUP BY src.id, src.name, i.media_type, i.audience_type
+                        GROUP BY src.id, src.name, 3, 4
                         "#
-                    )
+                    ))
                     .bind(start)
                     .bind(end)
                     .bind(end_date.unwrap_or(start))
@@ -938,11 +1029,11 @@ impl Repository {
                 let mut result: Vec<crate::api::stats::CatalogSourceStats> = source_map.into_iter().map(|(source_id, (source_name, media_map))| {
                     let mut by_mt: Vec<crate::api::stats::CatalogBreakdownStats> = media_map.into_iter().map(|(label, pt_map)| {
                         let mut by_pt: Vec<crate::api::stats::CatalogBreakdownStats> = pt_map.into_iter().map(|(pt_label, (a, e, ar))| {
-                            crate::api::stats::CatalogBreakdownStats { label: pt_label, active_items: a, entered_items: e, archived_items: ar, loans: 0, by_public_type: None }
+                            crate::api::stats::CatalogBreakdownStats { label: pt_label, active_items: a, entered_items: e, archived_items: ar, loans: 0, by_public_type: None, display_label: None }
                         }).collect();
                         by_pt.sort_by(|a, b| b.active_items.cmp(&a.active_items));
                         let (active, entered, archived) = by_pt.iter().fold((0i64, 0i64, 0i64), |acc, x| (acc.0 + x.active_items, acc.1 + x.entered_items, acc.2 + x.archived_items));
-                        crate::api::stats::CatalogBreakdownStats { label, active_items: active, entered_items: entered, archived_items: archived, loans: 0, by_public_type: Some(by_pt) }
+                        crate::api::stats::CatalogBreakdownStats { label, active_items: active, entered_items: entered, archived_items: archived, loans: 0, by_public_type: Some(by_pt), display_label: None }
                     }).collect();
                     by_mt.sort_by(|a, b| b.active_items.cmp(&a.active_items));
                     let (active, entered, archived) = by_mt.iter().fold((0i64, 0i64, 0i64), |acc, x| (acc.0 + x.active_items, acc.1 + x.entered_items, acc.2 + x.archived_items));
@@ -954,21 +1045,21 @@ impl Repository {
             } else if by_media_type {
                 // 2-level nesting: source → media_type
                 let rows = 
-                    sqlx::query(
+                    sqlx::query(&format!(
                         r#"
                         SELECT
                             COALESCE(src.id, 0) as source_id,
                             COALESCE(src.name, 'unknown') as source_name,
-                            COALESCE(i.media_type, 'unknown') as media_type_label,
+                            {media_label} as media_type_label,
                             COUNT(*) FILTER (WHERE sp.archived_at IS NULL) as active_items,
                             COUNT(*) FILTER (WHERE sp.created_at >= $1 AND sp.created_at <= $2) as entered_items,
                             COUNT(*) FILTER (WHERE sp.archived_at >= $1 AND sp.archived_at <= $2) as archived_items
                         FROM items sp
                         LEFT JOIN sources src ON sp.source_id = src.id
                         JOIN biblios i ON sp.biblio_id = i.id
-                        GROUP BY src.id, src.name, i.media_type
+                        GROUP BY src.id, src.name, 3
                         "#
-                    )
+                    ))
                     .bind(start)
                     .bind(end)
                     .fetch_all(pool)
@@ -991,7 +1082,7 @@ impl Repository {
 
                 let mut result: Vec<crate::api::stats::CatalogSourceStats> = source_map.into_iter().map(|(source_id, (source_name, media_map))| {
                     let mut by_mt: Vec<crate::api::stats::CatalogBreakdownStats> = media_map.into_iter().map(|(label, (a, e, ar))| {
-                        crate::api::stats::CatalogBreakdownStats { label, active_items: a, entered_items: e, archived_items: ar, loans: 0, by_public_type: None }
+                        crate::api::stats::CatalogBreakdownStats { label, active_items: a, entered_items: e, archived_items: ar, loans: 0, by_public_type: None, display_label: None }
                     }).collect();
                     by_mt.sort_by(|a, b| b.active_items.cmp(&a.active_items));
                     let (active, entered, archived) = by_mt.iter().fold((0i64, 0i64, 0i64), |acc, x| (acc.0 + x.active_items, acc.1 + x.entered_items, acc.2 + x.archived_items));
@@ -1003,21 +1094,21 @@ impl Repository {
             } else if by_public_type {
                 // 2-level nesting: source → public_type
                 let rows = 
-                    sqlx::query(
+                    sqlx::query(&format!(
                         r#"
                         SELECT
                             COALESCE(src.id, 0) as source_id,
                             COALESCE(src.name, 'unknown') as source_name,
-                            COALESCE(i.audience_type, 'unknown') as public_type_label,
+                            {audience_label} as public_type_label,
                             COUNT(*) FILTER (WHERE sp.archived_at IS NULL) as active_items,
                             COUNT(*) FILTER (WHERE sp.created_at >= $1 AND sp.created_at <= $2) as entered_items,
                             COUNT(*) FILTER (WHERE sp.archived_at >= $1 AND sp.archived_at <= $2) as archived_items
                         FROM items sp
                         LEFT JOIN sources src ON sp.source_id = src.id
                         JOIN biblios i ON sp.biblio_id = i.id
-                        GROUP BY src.id, src.name, i.audience_type
+                        GROUP BY src.id, src.name, 3
                         "#
-                    )
+                    ))
                     .bind(start)
                     .bind(end)
                     .fetch_all(pool)
@@ -1041,7 +1132,7 @@ impl Repository {
 
                 let mut result: Vec<crate::api::stats::CatalogSourceStats> = source_map.into_iter().map(|(source_id, (source_name, pt_map))| {
                     let mut by_pt: Vec<crate::api::stats::CatalogBreakdownStats> = pt_map.into_iter().map(|(label, (a, e, ar))| {
-                        crate::api::stats::CatalogBreakdownStats { label, active_items: a, entered_items: e, archived_items: ar, loans: 0, by_public_type: None }
+                        crate::api::stats::CatalogBreakdownStats { label, active_items: a, entered_items: e, archived_items: ar, loans: 0, by_public_type: None, display_label: None }
                     }).collect();
                     by_pt.sort_by(|a, b| b.active_items.cmp(&a.active_items));
                     let (active, entered, archived) = by_pt.iter().fold((0i64, 0i64, 0i64), |acc, x| (acc.0 + x.active_items, acc.1 + x.entered_items, acc.2 + x.archived_items));
@@ -1093,19 +1184,19 @@ impl Repository {
             if by_public_type {
                 // 2-level nesting: media_type → public_type
                 let rows = 
-                    sqlx::query(
+                    sqlx::query(&format!(
                         r#"
                         SELECT
-                            COALESCE(i.media_type, 'unknown') as label,
-                            COALESCE(i.audience_type, 'unknown') as public_type_label,
+                            {media_label} as label,
+                            {audience_label} as public_type_label,
                             COUNT(*) FILTER (WHERE sp.archived_at IS NULL) as active_items,
                             COUNT(*) FILTER (WHERE sp.created_at >= $1 AND sp.created_at <= $2) as entered_items,
                             COUNT(*) FILTER (WHERE sp.archived_at >= $1 AND sp.archived_at <= $2) as archived_items
                         FROM items sp
                         JOIN biblios i ON sp.biblio_id = i.id
-                        GROUP BY i.media_type, i.audience_type
+                        GROUP BY 1, 2
                         "#
-                    )
+                    ))
                     .bind(start)
                     .bind(end)
                     .fetch_all(pool)
@@ -1128,30 +1219,30 @@ impl Repository {
 
                 let mut result: Vec<crate::api::stats::CatalogBreakdownStats> = media_map.into_iter().map(|(label, pt_map)| {
                     let mut by_pt: Vec<crate::api::stats::CatalogBreakdownStats> = pt_map.into_iter().map(|(pt_label, (a, e, ar))| {
-                        crate::api::stats::CatalogBreakdownStats { label: pt_label, active_items: a, entered_items: e, archived_items: ar, loans: 0, by_public_type: None }
+                        crate::api::stats::CatalogBreakdownStats { label: pt_label, active_items: a, entered_items: e, archived_items: ar, loans: 0, by_public_type: None, display_label: None }
                     }).collect();
                     by_pt.sort_by(|a, b| b.active_items.cmp(&a.active_items));
                     let (active, entered, archived) = by_pt.iter().fold((0i64, 0i64, 0i64), |acc, x| (acc.0 + x.active_items, acc.1 + x.entered_items, acc.2 + x.archived_items));
-                    crate::api::stats::CatalogBreakdownStats { label, active_items: active, entered_items: entered, archived_items: archived, loans: 0, by_public_type: Some(by_pt) }
+                    crate::api::stats::CatalogBreakdownStats { label, active_items: active, entered_items: entered, archived_items: archived, loans: 0, by_public_type: Some(by_pt), display_label: None }
                 }).collect();
                 result.sort_by(|a, b| b.active_items.cmp(&a.active_items));
                 Some(result)
             } else {
                 // Flat media type breakdown
                 let rows = 
-                    sqlx::query(
+                    sqlx::query(&format!(
                         r#"
                         SELECT
-                            COALESCE(i.media_type, 'unknown') as label,
+                            {media_label} as label,
                             COUNT(*) FILTER (WHERE sp.archived_at IS NULL) as active_items,
                             COUNT(*) FILTER (WHERE sp.created_at >= $1 AND sp.created_at <= $2) as entered_items,
                             COUNT(*) FILTER (WHERE sp.archived_at >= $1 AND sp.archived_at <= $2) as archived_items
                         FROM items sp
                         JOIN biblios i ON sp.biblio_id = i.id
-                        GROUP BY i.media_type
+                        GROUP BY 1
                         ORDER BY active_items DESC
                         "#
-                    )
+                    ))
                     .bind(start)
                     .bind(end)
                     .fetch_all(pool)
@@ -1159,6 +1250,7 @@ impl Repository {
                
                 Some(rows.into_iter().map(|row| crate::api::stats::CatalogBreakdownStats {
                     label: row.get("label"),
+                    display_label: None,
                     active_items: row.get("active_items"),
                     entered_items: row.get("entered_items"),
                     archived_items: row.get("archived_items"),
@@ -1174,19 +1266,19 @@ impl Repository {
         // Only when neither by_source nor by_media_type is on (otherwise public is nested)
         let mut by_public_type_data = if by_public_type && !by_source && !by_media_type {
             let rows = 
-                sqlx::query(
+                sqlx::query(&format!(
                     r#"
                     SELECT
-                        COALESCE(i.audience_type, 'unknown') as label,
+                        {audience_label} as label,
                         COUNT(*) FILTER (WHERE sp.archived_at IS NULL) as active_items,
                         COUNT(*) FILTER (WHERE sp.created_at >= $1 AND sp.created_at <= $2) as entered_items,
                         COUNT(*) FILTER (WHERE sp.archived_at >= $1 AND sp.archived_at <= $2) as archived_items
                     FROM items sp
                     JOIN biblios i ON sp.biblio_id = i.id
-                    GROUP BY i.audience_type
+                    GROUP BY 1
                     ORDER BY active_items DESC
                     "#
-                )
+                ))
                 .bind(start)
                 .bind(end)
                 .fetch_all(pool)
@@ -1194,6 +1286,7 @@ impl Repository {
                
             Some(rows.into_iter().map(|row| crate::api::stats::CatalogBreakdownStats {
                 label: row.get("label"),
+                display_label: None,
                 active_items: row.get("active_items"),
                 entered_items: row.get("entered_items"),
                 archived_items: row.get("archived_items"),
@@ -1205,27 +1298,23 @@ impl Repository {
         };
 
         // --- Merge loan counts into breakdown structures ---
-        // All loans (active + archived) grouped by (source_id, media_type, public_type)
-        // via items for both tables.
+        // All loans (active, returned and archived) grouped by (source_id, media_type, public_type)
+        // via items.
         
-            let loan_rows = sqlx::query(
+            let loan_rows = sqlx::query(&format!(
                 r#"
                 SELECT
                     COALESCE(sp.source_id, 0) as source_id,
-                    COALESCE(i.media_type, 'unknown') as media_type,
-                    COALESCE(i.audience_type, 'unknown') as public_type,
+                    {media_label} as media_type,
+                    {audience_label} as public_type,
                     COUNT(*) as loans
-                FROM (
-                    SELECT item_id, date FROM loans
-                    UNION ALL
-                    SELECT item_id, date FROM loans_archives
-                ) all_loans
+                FROM loans_all all_loans
                 JOIN items sp ON all_loans.item_id = sp.id
                 JOIN biblios i ON sp.biblio_id = i.id
                 WHERE all_loans.date >= $1 AND all_loans.date <= $2
-                GROUP BY sp.source_id, i.media_type, i.audience_type
+                GROUP BY 1, 2, 3
                 "#
-            )
+            ))
             .bind(start)
             .bind(end)
             .fetch_all(pool)
@@ -1312,8 +1401,340 @@ impl Repository {
             by_source: by_source_data,
             by_media_type: by_media_type_data,
             by_public_type: by_public_type_data,
+            by_digital_resource: by_digital_resource_data,
+            by_location: None,
+            previous: None,
+            deltas: None,
         })
     }
+
+    /// Loans and returns per shelving location (`items.place`) with their media type breakdown,
+    /// with the same period and filters as [`Self::stats_get_loan_stats`]. Busiest locations first.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn stats_get_loan_location_stats(
+        &self,
+        start_date: Option<DateTime<Utc>>,
+        end_date: Option<DateTime<Utc>>,
+        media_type: Option<&MediaType>,
+        public_type: Option<&str>,
+        user_id: Option<i64>,
+    ) -> AppResult<Vec<LoanLocationStats>> {
+        let start = start_date.unwrap_or_else(|| Utc::now() - chrono::Duration::days(30));
+        let end = end_date.unwrap_or_else(Utc::now);
+
+        let (mut conditions, binds) = Self::stats_loan_category_filters(media_type, public_type);
+        let (start_param, end_param, user_param) = (binds.len() + 1, binds.len() + 2, binds.len() + 3);
+        conditions.push(format!(
+            "((l.date >= ${start_param} AND l.date <= ${end_param}) OR (l.returned_at >= ${start_param} AND l.returned_at <= ${end_param}))"
+        ));
+        conditions.push(format!("(${user_param}::bigint IS NULL OR l.user_id = ${user_param})"));
+
+        let query = format!(
+            r#"
+            SELECT
+                s.place,
+                {} as label,
+                COUNT(*) FILTER (WHERE l.date >= ${start_param} AND l.date <= ${end_param}) as loans,
+                COUNT(*) FILTER (WHERE l.returned_at >= ${start_param} AND l.returned_at <= ${end_param}) as returns
+            FROM loans_all l
+            JOIN items s ON l.item_id = s.id
+            JOIN biblios i ON s.biblio_id = i.id
+            WHERE {}
+            GROUP BY 1, 2
+            "#,
+            StatsCategory::MediaType.label_sql("i"),
+            conditions.join(" AND "),
+        );
+        let rows = binds
+            .iter()
+            .fold(sqlx::query(&query), |query, value| query.bind(value))
+            .bind(start)
+            .bind(end)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut locations: Vec<LoanLocationStats> = Vec::new();
+        for row in rows {
+            let place: Option<i16> = row.get("place");
+            let loans: i64 = row.get("loans");
+            let returns: i64 = row.get("returns");
+            let index = match locations.iter().position(|l| l.place == place) {
+                Some(index) => index,
+                None => {
+                    locations.push(LoanLocationStats { place, loans: 0, returns: 0, by_media_type: Vec::new() });
+                    locations.len() - 1
+                }
+            };
+            let location = &mut locations[index];
+            location.loans += loans;
+            location.returns += returns;
+            if loans > 0 {
+                location.by_media_type.push(StatEntry { label: row.get("label"), value: loans, display_label: None });
+            }
+        }
+        for location in &mut locations {
+            location.by_media_type.sort_by_key(|e| std::cmp::Reverse(e.value));
+        }
+        locations.sort_by(|a, b| b.loans.cmp(&a.loans).then(a.place.cmp(&b.place)));
+        Ok(locations)
+    }
+
+    /// Catalog counts (active, entered, archived copies and loans) per shelving location
+    /// (`items.place`), nested by media type when `by_media_type` is set. Largest locations first.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn stats_get_catalog_location_stats(
+        &self,
+        start_date: Option<DateTime<Utc>>,
+        end_date: Option<DateTime<Utc>>,
+        by_media_type: bool,
+    ) -> AppResult<Vec<crate::api::stats::CatalogLocationStats>> {
+        use crate::api::stats::{CatalogBreakdownStats, CatalogLocationStats};
+
+        let pool = &self.pool;
+        let start = start_date.unwrap_or(DateTime::UNIX_EPOCH);
+        let end = end_date.unwrap_or(Utc::now());
+        let media_label = StatsCategory::MediaType.label_sql("i");
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT
+                sp.place,
+                {media_label} as label,
+                COUNT(*) FILTER (WHERE sp.archived_at IS NULL) as active_items,
+                COUNT(*) FILTER (WHERE sp.created_at >= $1 AND sp.created_at <= $2) as entered_items,
+                COUNT(*) FILTER (WHERE sp.archived_at >= $1 AND sp.archived_at <= $2) as archived_items
+            FROM items sp
+            JOIN biblios i ON sp.biblio_id = i.id
+            GROUP BY 1, 2
+            "#
+        ))
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await?;
+
+        let loan_rows = sqlx::query(&format!(
+            r#"
+            SELECT sp.place, {media_label} as label, COUNT(*) as loans
+            FROM loans_all all_loans
+            JOIN items sp ON all_loans.item_id = sp.id
+            JOIN biblios i ON sp.biblio_id = i.id
+            WHERE all_loans.date >= $1 AND all_loans.date <= $2
+            GROUP BY 1, 2
+            "#
+        ))
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await?;
+
+        let mut loan_map: std::collections::HashMap<(Option<i16>, String), i64> = std::collections::HashMap::new();
+        for row in &loan_rows {
+            loan_map.insert((row.get("place"), row.get("label")), row.get("loans"));
+        }
+
+        let mut locations: Vec<CatalogLocationStats> = Vec::new();
+        for row in &rows {
+            let place: Option<i16> = row.get("place");
+            let label: String = row.get("label");
+            let loans = loan_map.get(&(place, label.clone())).copied().unwrap_or(0);
+            let media = CatalogBreakdownStats {
+                label,
+                display_label: None,
+                active_items: row.get("active_items"),
+                entered_items: row.get("entered_items"),
+                archived_items: row.get("archived_items"),
+                loans,
+                by_public_type: None,
+            };
+            let index = match locations.iter().position(|l| l.place == place) {
+                Some(index) => index,
+                None => {
+                    locations.push(CatalogLocationStats {
+                        place,
+                        active_items: 0,
+                        entered_items: 0,
+                        archived_items: 0,
+                        loans: 0,
+                        by_media_type: by_media_type.then(Vec::new),
+                    });
+                    locations.len() - 1
+                }
+            };
+            let location = &mut locations[index];
+            location.active_items += media.active_items;
+            location.entered_items += media.entered_items;
+            location.archived_items += media.archived_items;
+            location.loans += media.loans;
+            if let Some(medias) = location.by_media_type.as_mut() {
+                medias.push(media);
+            }
+        }
+        for location in &mut locations {
+            if let Some(medias) = location.by_media_type.as_mut() {
+                medias.sort_by_key(|m| std::cmp::Reverse(m.active_items));
+            }
+        }
+        locations.sort_by(|a, b| b.active_items.cmp(&a.active_items).then(a.place.cmp(&b.place)));
+        Ok(locations)
+    }
+
+    /// Copies entered from `start` to `end` (days, inclusive) per media type, with the sum of
+    /// their prices. Only plain decimal prices are summed; deposit copies are left out.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn stats_get_acquisition_prices(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> AppResult<Vec<AcquisitionPriceTotal>> {
+        let media_label = StatsCategory::MediaType.label_sql("i");
+        let rows = sqlx::query_as::<_, AcquisitionPriceTotal>(&format!(
+            r#"
+            SELECT
+                {media_label} AS label,
+                mt.vat_rate,
+                COUNT(*) AS copies,
+                COUNT(*) FILTER (WHERE s.price ~ '{PRICE_PATTERN}') AS priced_copies,
+                COALESCE(SUM(CASE WHEN s.price ~ '{PRICE_PATTERN}' THEN s.price::numeric END), 0) AS total
+            FROM items s
+            JOIN biblios i ON s.biblio_id = i.id
+            LEFT JOIN media_types mt ON mt.code = ({media_label})
+            WHERE s.created_at >= $1 AND s.created_at < $2 AND s.deposit_id IS NULL
+            GROUP BY 1, 2
+            ORDER BY total DESC, label
+            "#
+        ))
+        .bind(start)
+        .bind(end + chrono::Duration::days(1))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Completeness counts of the active records per source, and over the whole catalog (a record
+    /// with copies from several sources counts once per source, but once in the totals)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn stats_get_catalog_quality(
+        &self,
+    ) -> AppResult<(Vec<CatalogQualityCounts>, CatalogQualityCounts)> {
+        let by_source = sqlx::query_as::<_, CatalogQualityCounts>(&format!(
+            r#"
+            {CATALOG_QUALITY_FLAGS}
+            SELECT
+                f.source_id,
+                MAX(src.name)::TEXT AS source_name,
+                COUNT(*) AS records,
+                COUNT(*) FILTER (WHERE f.has_isbn) AS with_isbn,
+                COUNT(*) FILTER (WHERE f.has_authors) AS with_authors,
+                COUNT(*) FILTER (WHERE f.has_call_number) AS with_call_number,
+                COUNT(*) FILTER (WHERE f.has_subjects) AS with_subjects,
+                COUNT(*) FILTER (WHERE f.has_cover) AS with_cover,
+                COUNT(*) FILTER (WHERE f.has_marc_record) AS with_marc_record
+            FROM flags f
+            LEFT JOIN sources src ON src.id = f.source_id
+            GROUP BY f.source_id
+            ORDER BY records DESC, f.source_id NULLS LAST
+            "#
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let totals = sqlx::query_as::<_, CatalogQualityCounts>(&format!(
+            r#"
+            {CATALOG_QUALITY_FLAGS},
+            per_record AS (
+                SELECT
+                    biblio_id,
+                    bool_or(has_isbn) AS has_isbn,
+                    bool_or(has_authors) AS has_authors,
+                    bool_or(has_call_number) AS has_call_number,
+                    bool_or(has_subjects) AS has_subjects,
+                    bool_or(has_cover) AS has_cover,
+                    bool_or(has_marc_record) AS has_marc_record
+                FROM flags
+                GROUP BY biblio_id
+            )
+            SELECT
+                NULL::BIGINT AS source_id,
+                NULL::TEXT AS source_name,
+                COUNT(*) AS records,
+                COUNT(*) FILTER (WHERE has_isbn) AS with_isbn,
+                COUNT(*) FILTER (WHERE has_authors) AS with_authors,
+                COUNT(*) FILTER (WHERE has_call_number) AS with_call_number,
+                COUNT(*) FILTER (WHERE has_subjects) AS with_subjects,
+                COUNT(*) FILTER (WHERE has_cover) AS with_cover,
+                COUNT(*) FILTER (WHERE has_marc_record) AS with_marc_record
+            FROM per_record
+            "#
+        ))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((by_source, totals))
+    }
 }
 
+/// Stored copy prices summed by the acquisition report (see `services::currency`)
+const PRICE_PATTERN: &str = r"^[0-9]+(\.[0-9]+)?$";
+
+/// Copies of one media type entered in a period, with the sum of their prices
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AcquisitionPriceTotal {
+    pub label: String,
+    pub vat_rate: Option<rust_decimal::Decimal>,
+    pub copies: i64,
+    pub priced_copies: i64,
+    /// VAT included
+    pub total: rust_decimal::Decimal,
+}
 
+/// Active records per source (the sources of their active copies, else `biblios.source_id`),
+/// with one completeness flag per field. `has_call_number` only looks at the copies of that source
+/// when the source comes from the copies.
+const CATALOG_QUALITY_FLAGS: &str = r#"
+    WITH record_sources AS (
+        SELECT b.id AS biblio_id, COALESCE(cs.source_id, b.source_id) AS source_id,
+               cs.source_id IS NOT NULL AS from_copies
+        FROM biblios b
+        LEFT JOIN LATERAL (
+            SELECT DISTINCT c.source_id FROM items c
+            WHERE c.biblio_id = b.id AND c.archived_at IS NULL AND c.source_id IS NOT NULL
+        ) cs ON TRUE
+        WHERE b.archived_at IS NULL
+    ),
+    flags AS (
+        SELECT
+            rs.biblio_id,
+            rs.source_id,
+            NULLIF(btrim(b.isbn), '') IS NOT NULL AS has_isbn,
+            EXISTS (SELECT 1 FROM biblio_authors ba WHERE ba.biblio_id = b.id) AS has_authors,
+            EXISTS (
+                SELECT 1 FROM items c
+                WHERE c.biblio_id = b.id AND c.archived_at IS NULL
+                  AND NULLIF(btrim(c.call_number), '') IS NOT NULL
+                  AND (NOT rs.from_copies OR c.source_id = rs.source_id)
+            ) AS has_call_number,
+            NULLIF(btrim(b.subject), '') IS NOT NULL
+                OR EXISTS (SELECT 1 FROM biblio_subjects bs WHERE bs.biblio_id = b.id) AS has_subjects,
+            regexp_replace(COALESCE(b.isbn, ''), '[^0-9Xx]', '', 'g') ~ '^([0-9]{9}[0-9Xx]|97[89][0-9]{10})$' AS has_cover,
+            b.marc_record IS NOT NULL AS has_marc_record
+        FROM record_sources rs
+        JOIN biblios b ON b.id = rs.biblio_id
+    )
+"#;
+
+/// Completeness counts of the records of one source (or of the whole catalog)
+#[derive(Debug, Clone, Default, sqlx::FromRow)]
+pub struct CatalogQualityCounts {
+    /// `None` for records without a source (and for the catalog totals)
+    pub source_id: Option<i64>,
+    pub source_name: Option<String>,
+    pub records: i64,
+    pub with_isbn: i64,
+    pub with_authors: i64,
+    pub with_call_number: i64,
+    pub with_subjects: i64,
+    pub with_cover: i64,
+    pub with_marc_record: i64,
+}
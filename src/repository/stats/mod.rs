@@ -1,7 +1,9 @@
-//! Statistics persistence (saved queries, executor, dashboard aggregates).
+//! Statistics persistence (saved queries and reports, executor, dashboard aggregates).
 
+pub mod categories;
 pub mod dashboard;
 pub mod executor;
 pub mod saved_queries;
+pub mod saved_reports;
 
 pub use dashboard::StatsFilter;
@@ -0,0 +1,196 @@
+//! Persisted report definitions (`saved_reports` table).
+
+use sqlx::PgPool;
+
+use crate::error::AppError;
+use crate::models::stats_builder::{ReportDefinition, SavedReport, SavedReportWrite};
+
+pub async fn list_for_user(
+    pool: &PgPool,
+    user_id: i64,
+    is_admin: bool,
+) -> Result<Vec<SavedReport>, AppError> {
+    let rows: Vec<SavedReportRow> = if is_admin {
+        sqlx::query_as::<_, SavedReportRow>(
+            r#"
+            SELECT id, name, description, definition, user_id, is_shared, created_at, updated_at
+            FROM saved_reports
+            ORDER BY updated_at DESC
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+    } else {
+        sqlx::query_as::<_, SavedReportRow>(
+            r#"
+            SELECT id, name, description, definition, user_id, is_shared, created_at, updated_at
+            FROM saved_reports
+            WHERE user_id = $1 OR is_shared = TRUE
+            ORDER BY updated_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+    .map_err(|e| AppError::Internal(format!("List saved queries: {}", e)))?;
+
+    rows.into_iter()
+        .map(row_to_public)
+        .collect::<Result<Vec<_>, _>>()
+}
+
+pub async fn get_by_id(
+    pool: &PgPool,
+    id: i64,
+    user_id: i64,
+    is_admin: bool,
+) -> Result<Option<SavedReport>, AppError> {
+    let row = sqlx::query_as::<_, SavedReportRow>(
+        r#"
+        SELECT id, name, description, definition, user_id, is_shared, created_at, updated_at
+        FROM saved_reports WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Internal(format!("Get saved report: {}", e)))?;
+
+    let Some(r) = row else {
+        return Ok(None);
+    };
+
+    if !is_admin && r.user_id != user_id && !r.is_shared {
+        return Err(AppError::Authorization(
+            "Cannot access this saved report".into(),
+        ));
+    }
+
+    Ok(Some(row_to_public(r)?))
+}
+
+pub async fn insert(
+    pool: &PgPool,
+    user_id: i64,
+    body: &SavedReportWrite,
+) -> Result<SavedReport, AppError> {
+    let definition = serde_json::to_value(&body.definition)
+        .map_err(|e| AppError::Internal(format!("Serialize report definition: {}", e)))?;
+
+    let row = sqlx::query_as::<_, SavedReportRow>(
+        r#"
+        INSERT INTO saved_reports (name, description, definition, user_id, is_shared)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, name, description, definition, user_id, is_shared, created_at, updated_at
+        "#,
+    )
+    .bind(&body.name)
+    .bind(&body.description)
+    .bind(definition)
+    .bind(user_id)
+    .bind(body.is_shared)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::Internal(format!("Insert saved report: {}", e)))?;
+
+    row_to_public(row)
+}
+
+pub async fn update(
+    pool: &PgPool,
+    id: i64,
+    user_id: i64,
+    is_admin: bool,
+    body: &SavedReportWrite,
+) -> Result<SavedReport, AppError> {
+    let owner = sqlx::query_scalar::<_, i64>("SELECT user_id FROM saved_reports WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Saved report lookup: {}", e)))?
+        .ok_or_else(|| AppError::NotFound("Saved report not found".into()))?;
+
+    if !is_admin && owner != user_id {
+        return Err(AppError::Authorization(
+            "Only the owner can update this saved report".into(),
+        ));
+    }
+
+    let definition = serde_json::to_value(&body.definition)
+        .map_err(|e| AppError::Internal(format!("Serialize report definition: {}", e)))?;
+
+    let row = sqlx::query_as::<_, SavedReportRow>(
+        r#"
+        UPDATE saved_reports
+        SET name = $2, description = $3, definition = $4, is_shared = $5, updated_at = NOW()
+        WHERE id = $1
+        RETURNING id, name, description, definition, user_id, is_shared, created_at, updated_at
+        "#,
+    )
+    .bind(id)
+    .bind(&body.name)
+    .bind(&body.description)
+    .bind(definition)
+    .bind(body.is_shared)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::Internal(format!("Update saved report: {}", e)))?;
+
+    row_to_public(row)
+}
+
+pub async fn delete_by_id(
+    pool: &PgPool,
+    id: i64,
+    user_id: i64,
+    is_admin: bool,
+) -> Result<(), AppError> {
+    let owner = sqlx::query_scalar::<_, i64>("SELECT user_id FROM saved_reports WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Saved report lookup: {}", e)))?
+        .ok_or_else(|| AppError::NotFound("Saved report not found".into()))?;
+
+    if !is_admin && owner != user_id {
+        return Err(AppError::Authorization(
+            "Only the owner can delete this saved report".into(),
+        ));
+    }
+
+    sqlx::query("DELETE FROM saved_reports WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Delete saved report: {}", e)))?;
+
+    Ok(())
+}
+
+#[derive(sqlx::FromRow)]
+struct SavedReportRow {
+    id: i64,
+    name: String,
+    description: Option<String>,
+    definition: serde_json::Value,
+    user_id: i64,
+    is_shared: bool,
+    created_at: chrono::DateTime<chrono::Utc>,
+    updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+fn row_to_public(row: SavedReportRow) -> Result<SavedReport, AppError> {
+    let definition: ReportDefinition = serde_json::from_value(row.definition)
+        .map_err(|e| AppError::Internal(format!("Invalid stored report definition: {}", e)))?;
+    Ok(SavedReport {
+        id: row.id,
+        name: row.name,
+        description: row.description,
+        definition,
+        user_id: row.user_id,
+        is_shared: row.is_shared,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+    })
+}
@@ -0,0 +1,177 @@
+//! Patron messages (`user_messages`) domain methods on Repository
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::user_message::{CreateUserMessage, UserMessage},
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait UserMessagesRepository: Send + Sync {
+    /// Messages of a patron, newest first; `pending_only` keeps the unacknowledged, unexpired ones
+    /// and `patron_visible_only` the ones shown in the self-service account.
+    async fn user_messages_list(
+        &self,
+        user_id: i64,
+        pending_only: bool,
+        patron_visible_only: bool,
+    ) -> AppResult<Vec<UserMessage>>;
+    async fn user_messages_get(&self, user_id: i64, id: i64) -> AppResult<UserMessage>;
+    async fn user_messages_create(
+        &self,
+        user_id: i64,
+        data: &CreateUserMessage,
+        created_by: i64,
+    ) -> AppResult<UserMessage>;
+    async fn user_messages_delete(&self, user_id: i64, id: i64) -> AppResult<()>;
+    /// Mark a pending message acknowledged; `None` when it was already acknowledged.
+    async fn user_messages_acknowledge(
+        &self,
+        user_id: i64,
+        id: i64,
+        acknowledged_by: i64,
+    ) -> AppResult<Option<UserMessage>>;
+}
+
+#[async_trait]
+impl UserMessagesRepository for Repository {
+    async fn user_messages_list(
+        &self,
+        user_id: i64,
+        pending_only: bool,
+        patron_visible_only: bool,
+    ) -> AppResult<Vec<UserMessage>> {
+        Repository::user_messages_list(self, user_id, pending_only, patron_visible_only).await
+    }
+    async fn user_messages_get(&self, user_id: i64, id: i64) -> AppResult<UserMessage> {
+        Repository::user_messages_get(self, user_id, id).await
+    }
+    async fn user_messages_create(
+        &self,
+        user_id: i64,
+        data: &CreateUserMessage,
+        created_by: i64,
+    ) -> AppResult<UserMessage> {
+        Repository::user_messages_create(self, user_id, data, created_by).await
+    }
+    async fn user_messages_delete(&self, user_id: i64, id: i64) -> AppResult<()> {
+        Repository::user_messages_delete(self, user_id, id).await
+    }
+    async fn user_messages_acknowledge(
+        &self,
+        user_id: i64,
+        id: i64,
+        acknowledged_by: i64,
+    ) -> AppResult<Option<UserMessage>> {
+        Repository::user_messages_acknowledge(self, user_id, id, acknowledged_by).await
+    }
+}
+
+impl Repository {
+    #[tracing::instrument(skip(self), err)]
+    pub async fn user_messages_list(
+        &self,
+        user_id: i64,
+        pending_only: bool,
+        patron_visible_only: bool,
+    ) -> AppResult<Vec<UserMessage>> {
+        let rows = sqlx::query_as::<_, UserMessage>(
+            r#"
+            SELECT * FROM user_messages
+            WHERE user_id = $1
+              AND (NOT $2 OR (acknowledged_at IS NULL AND (expires_at IS NULL OR expires_at > NOW())))
+              AND (NOT $3 OR patron_visible)
+            ORDER BY (severity = 'blocking') DESC, created_at DESC, id DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(pending_only)
+        .bind(patron_visible_only)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn user_messages_get(&self, user_id: i64, id: i64) -> AppResult<UserMessage> {
+        sqlx::query_as::<_, UserMessage>("SELECT * FROM user_messages WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Message {} not found", id)))
+    }
+
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn user_messages_create(
+        &self,
+        user_id: i64,
+        data: &CreateUserMessage,
+        created_by: i64,
+    ) -> AppResult<UserMessage> {
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await?;
+        if !exists {
+            return Err(AppError::NotFound(format!("User {} not found", user_id)));
+        }
+        let row = sqlx::query_as::<_, UserMessage>(
+            r#"
+            INSERT INTO user_messages (user_id, body, severity, patron_visible, expires_at, created_by, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(&data.body)
+        .bind(data.severity)
+        .bind(data.patron_visible)
+        .bind(data.expires_at)
+        .bind(created_by)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn user_messages_delete(&self, user_id: i64, id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM user_messages WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Message {} not found", id)));
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn user_messages_acknowledge(
+        &self,
+        user_id: i64,
+        id: i64,
+        acknowledged_by: i64,
+    ) -> AppResult<Option<UserMessage>> {
+        let row = sqlx::query_as::<_, UserMessage>(
+            r#"
+            UPDATE user_messages SET acknowledged_at = $1, acknowledged_by = $2
+            WHERE id = $3 AND user_id = $4 AND acknowledged_at IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(acknowledged_by)
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+}
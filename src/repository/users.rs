@@ -1,13 +1,16 @@
 //! Users domain methods on Repository
 
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sqlx::Row;
 
 use super::Repository;
 use crate::{
     error::{AppError, AppResult},
-    models::user::{AccountTypeSlug, Rights, UpdateProfile, User, UserPayload, UserQuery, UserRights, UserShort, UserStatus},
+    models::user::{
+        AccountTypeSlug, Rights, UpdateProfile, User, UserActivity, UserActivityKind, UserActivityRow, UserContactMerge,
+        UserExpiryCampaign, UserMergeCounts, UserPayload, UserPinState, UserQuery, UserRights, UserShort, UserStatus,
+    },
 };
 
 
@@ -21,6 +24,18 @@ pub struct UserEmailTarget {
     pub language: Option<String>,
 }
 
+/// Account targeted by a membership expiry campaign
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UserExpiryTarget {
+    pub id: i64,
+    pub login: Option<String>,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    pub email: Option<String>,
+    pub language: Option<String>,
+    pub expiry_at: Option<DateTime<Utc>>,
+}
+
 /// Patron fields for hold-ready notification email.
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct HoldReadyUserContact {
@@ -41,6 +56,13 @@ pub trait UsersRepository: Send + Sync {
     async fn users_login_exists(&self, login: &str, exclude_id: Option<i64>) -> AppResult<bool>;
     async fn users_get_rights(&self, account_type: &AccountTypeSlug) -> AppResult<UserRights>;
     async fn users_search(&self, query: &UserQuery) -> AppResult<(Vec<UserShort>, i64)>;
+    async fn users_activity(
+        &self,
+        user_id: i64,
+        kind: Option<UserActivityKind>,
+        page: i64,
+        per_page: i64,
+    ) -> AppResult<(Vec<UserActivity>, i64)>;
     async fn users_create(
         &self,
         user: &UserPayload,
@@ -53,6 +75,16 @@ pub trait UsersRepository: Send + Sync {
         password: Option<String>,
     ) -> AppResult<User>;
     async fn users_delete(&self, id: i64, force: bool) -> AppResult<()>;
+    async fn users_schedule_deletion(&self, id: i64, force: bool, scheduled_at: DateTime<Utc>) -> AppResult<User>;
+    async fn users_restore(&self, id: i64) -> AppResult<User>;
+    async fn users_purge_pending_deletions(&self) -> AppResult<Vec<i64>>;
+    async fn users_merge(
+        &self,
+        survivor_id: i64,
+        duplicate_id: i64,
+        contact: &UserContactMerge,
+        dry_run: bool,
+    ) -> AppResult<UserMergeCounts>;
     async fn users_block(&self, id: i64) -> AppResult<User>;
     async fn users_unblock(&self, id: i64) -> AppResult<User>;
     async fn users_update_profile(
@@ -81,6 +113,31 @@ pub trait UsersRepository: Send + Sync {
     ) -> AppResult<Vec<UserEmailTarget>>;
     async fn users_count(&self) -> AppResult<i64>;
     async fn users_set_must_change_password(&self, id: i64, value: bool) -> AppResult<()>;
+    /// Barcode login state of the non-deleted users holding this card barcode.
+    async fn users_pin_states_by_barcode(&self, barcode: &str) -> AppResult<Vec<UserPinState>>;
+    async fn users_set_pin(&self, id: i64, pin_hash: Option<String>) -> AppResult<()>;
+    /// Count a wrong PIN; locks barcode login for `lock_minutes` once `max_attempts` is reached.
+    async fn users_record_pin_failure(&self, id: i64, max_attempts: i16, lock_minutes: i64) -> AppResult<()>;
+    async fn users_reset_pin_failures(&self, id: i64) -> AppResult<()>;
+    /// End of the current 2FA lockout, if any (may be in the past).
+    async fn users_two_factor_locked_until(&self, id: i64) -> AppResult<Option<DateTime<Utc>>>;
+    /// Count a wrong 2FA or recovery code. From the `free_attempts`-th failure in a row, locks
+    /// verification for `base_minutes`, doubled at each further failure (at most `max_minutes`).
+    /// Returns the failures in a row and the lock end.
+    async fn users_record_two_factor_failure(
+        &self,
+        id: i64,
+        free_attempts: i16,
+        base_minutes: i64,
+        max_minutes: i64,
+    ) -> AppResult<(i16, Option<DateTime<Utc>>)>;
+    async fn users_reset_two_factor_failures(&self, id: i64) -> AppResult<()>;
+    /// Replace the recovery codes and forget the used ones.
+    async fn users_replace_recovery_codes(&self, id: i64, recovery_codes: &str) -> AppResult<()>;
+    /// Accounts (not deleted) matching the filters of an expiry campaign, by name.
+    async fn users_expiry_campaign_targets(&self, campaign: &UserExpiryCampaign) -> AppResult<Vec<UserExpiryTarget>>;
+    /// Set the membership end date of `ids`; returns the number of accounts updated.
+    async fn users_set_expiry(&self, ids: &[i64], expiry_at: DateTime<Utc>) -> AppResult<u64>;
 }
 
 // ---------------------------------------------------------------------------
@@ -113,6 +170,9 @@ impl UsersRepository for Repository {
     async fn users_search(&self, query: &crate::models::user::UserQuery) -> crate::error::AppResult<(Vec<crate::models::user::UserShort>, i64)> {
         Repository::users_search(self, query).await
     }
+    async fn users_activity(&self, user_id: i64, kind: Option<UserActivityKind>, page: i64, per_page: i64) -> crate::error::AppResult<(Vec<UserActivity>, i64)> {
+        Repository::users_activity(self, user_id, kind, page, per_page).await
+    }
     async fn users_create(&self, user: &crate::models::user::UserPayload, password: Option<String>) -> crate::error::AppResult<User> {
         Repository::users_create(self, user, password).await
     }
@@ -122,6 +182,18 @@ impl UsersRepository for Repository {
     async fn users_delete(&self, id: i64, force: bool) -> crate::error::AppResult<()> {
         Repository::users_delete(self, id, force).await
     }
+    async fn users_schedule_deletion(&self, id: i64, force: bool, scheduled_at: DateTime<Utc>) -> crate::error::AppResult<User> {
+        Repository::users_schedule_deletion(self, id, force, scheduled_at).await
+    }
+    async fn users_restore(&self, id: i64) -> crate::error::AppResult<User> {
+        Repository::users_restore(self, id).await
+    }
+    async fn users_purge_pending_deletions(&self) -> crate::error::AppResult<Vec<i64>> {
+        Repository::users_purge_pending_deletions(self).await
+    }
+    async fn users_merge(&self, survivor_id: i64, duplicate_id: i64, contact: &UserContactMerge, dry_run: bool) -> crate::error::AppResult<UserMergeCounts> {
+        Repository::users_merge(self, survivor_id, duplicate_id, contact, dry_run).await
+    }
     async fn users_block(&self, id: i64) -> crate::error::AppResult<User> {
         Repository::users_block(self, id).await
     }
@@ -156,6 +228,42 @@ impl UsersRepository for Repository {
     async fn users_set_must_change_password(&self, id: i64, value: bool) -> crate::error::AppResult<()> {
         Repository::users_set_must_change_password(self, id, value).await
     }
+    async fn users_pin_states_by_barcode(&self, barcode: &str) -> crate::error::AppResult<Vec<UserPinState>> {
+        Repository::users_pin_states_by_barcode(self, barcode).await
+    }
+    async fn users_set_pin(&self, id: i64, pin_hash: Option<String>) -> crate::error::AppResult<()> {
+        Repository::users_set_pin(self, id, pin_hash).await
+    }
+    async fn users_record_pin_failure(&self, id: i64, max_attempts: i16, lock_minutes: i64) -> crate::error::AppResult<()> {
+        Repository::users_record_pin_failure(self, id, max_attempts, lock_minutes).await
+    }
+    async fn users_reset_pin_failures(&self, id: i64) -> crate::error::AppResult<()> {
+        Repository::users_reset_pin_failures(self, id).await
+    }
+    async fn users_two_factor_locked_until(&self, id: i64) -> crate::error::AppResult<Option<DateTime<Utc>>> {
+        Repository::users_two_factor_locked_until(self, id).await
+    }
+    async fn users_record_two_factor_failure(
+        &self,
+        id: i64,
+        free_attempts: i16,
+        base_minutes: i64,
+        max_minutes: i64,
+    ) -> crate::error::AppResult<(i16, Option<DateTime<Utc>>)> {
+        Repository::users_record_two_factor_failure(self, id, free_attempts, base_minutes, max_minutes).await
+    }
+    async fn users_reset_two_factor_failures(&self, id: i64) -> crate::error::AppResult<()> {
+        Repository::users_reset_two_factor_failures(self, id).await
+    }
+    async fn users_replace_recovery_codes(&self, id: i64, recovery_codes: &str) -> crate::error::AppResult<()> {
+        Repository::users_replace_recovery_codes(self, id, recovery_codes).await
+    }
+    async fn users_expiry_campaign_targets(&self, campaign: &UserExpiryCampaign) -> crate::error::AppResult<Vec<UserExpiryTarget>> {
+        Repository::users_expiry_campaign_targets(self, campaign).await
+    }
+    async fn users_set_expiry(&self, ids: &[i64], expiry_at: DateTime<Utc>) -> crate::error::AppResult<u64> {
+        Repository::users_set_expiry(self, ids, expiry_at).await
+    }
 }
 
 
@@ -256,6 +364,185 @@ impl Repository {
         Ok(())
     }
 
+    /// Barcode login state of the non-deleted users holding a card barcode (at most 2 rows)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn users_pin_states_by_barcode(&self, barcode: &str) -> AppResult<Vec<UserPinState>> {
+        let rows = sqlx::query_as::<_, UserPinState>(
+            r#"
+            SELECT id, pin_hash, pin_failed_attempts, pin_locked_until
+            FROM users
+            WHERE barcode = $1 AND (status IS NULL OR status <> 'deleted')
+            LIMIT 2
+            "#,
+        )
+        .bind(barcode)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Set or clear the self-service PIN hash (also clears any lockout)
+    #[tracing::instrument(skip(self, pin_hash), err)]
+    pub async fn users_set_pin(&self, id: i64, pin_hash: Option<String>) -> AppResult<()> {
+        let result = sqlx::query(
+            "UPDATE users SET pin_hash = $1, pin_failed_attempts = 0, pin_locked_until = NULL, update_at = NOW() WHERE id = $2"
+        )
+        .bind(pin_hash)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("User with id {} not found", id)));
+        }
+
+        Ok(())
+    }
+
+    /// Count a wrong PIN and lock barcode login once `max_attempts` is reached
+    #[tracing::instrument(skip(self), err)]
+    pub async fn users_record_pin_failure(&self, id: i64, max_attempts: i16, lock_minutes: i64) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE users SET
+                pin_failed_attempts = CASE WHEN pin_failed_attempts + 1 >= $2 THEN 0 ELSE pin_failed_attempts + 1 END,
+                pin_locked_until = CASE WHEN pin_failed_attempts + 1 >= $2
+                                        THEN NOW() + make_interval(mins => $3::int)
+                                        ELSE pin_locked_until END
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(max_attempts)
+        .bind(lock_minutes)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Clear the wrong-PIN counter after a successful barcode login
+    #[tracing::instrument(skip(self), err)]
+    pub async fn users_reset_pin_failures(&self, id: i64) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE users SET pin_failed_attempts = 0, pin_locked_until = NULL WHERE id = $1 AND (pin_failed_attempts <> 0 OR pin_locked_until IS NOT NULL)"
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// End of the current 2FA lockout, if any
+    #[tracing::instrument(skip(self), err)]
+    pub async fn users_two_factor_locked_until(&self, id: i64) -> AppResult<Option<DateTime<Utc>>> {
+        let locked_until: Option<Option<DateTime<Utc>>> =
+            sqlx::query_scalar("SELECT two_factor_locked_until FROM users WHERE id = $1")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(locked_until.flatten())
+    }
+
+    /// Count a wrong 2FA or recovery code, locking verification with an exponential back-off
+    #[tracing::instrument(skip(self), err)]
+    pub async fn users_record_two_factor_failure(
+        &self,
+        id: i64,
+        free_attempts: i16,
+        base_minutes: i64,
+        max_minutes: i64,
+    ) -> AppResult<(i16, Option<DateTime<Utc>>)> {
+        let row: (i16, Option<DateTime<Utc>>) = sqlx::query_as(
+            r#"
+            UPDATE users SET
+                two_factor_failed_attempts = LEAST(two_factor_failed_attempts + 1, 1000),
+                two_factor_locked_until = CASE WHEN two_factor_failed_attempts + 1 >= $2
+                    THEN NOW() + make_interval(mins => LEAST(
+                        $4::float8,
+                        $3::float8 * power(2, LEAST(two_factor_failed_attempts + 1 - $2, 30))
+                    )::int)
+                    ELSE two_factor_locked_until END
+            WHERE id = $1
+            RETURNING two_factor_failed_attempts, two_factor_locked_until
+            "#,
+        )
+        .bind(id)
+        .bind(free_attempts)
+        .bind(base_minutes)
+        .bind(max_minutes)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Clear the wrong 2FA code counter after a successful verification
+    #[tracing::instrument(skip(self), err)]
+    pub async fn users_reset_two_factor_failures(&self, id: i64) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE users SET two_factor_failed_attempts = 0, two_factor_locked_until = NULL WHERE id = $1 AND (two_factor_failed_attempts <> 0 OR two_factor_locked_until IS NOT NULL)"
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Replace the recovery codes (JSON array) and forget the used ones
+    #[tracing::instrument(skip(self, recovery_codes), err)]
+    pub async fn users_replace_recovery_codes(&self, id: i64, recovery_codes: &str) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE users SET recovery_codes = $1, recovery_codes_used = NULL, update_at = NOW() WHERE id = $2",
+        )
+        .bind(recovery_codes)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Accounts targeted by a membership expiry campaign. Accounts without an end date only
+    /// match with `include_without_expiry`; the window applies to the others.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn users_expiry_campaign_targets(&self, campaign: &UserExpiryCampaign) -> AppResult<Vec<UserExpiryTarget>> {
+        let targets = sqlx::query_as::<_, UserExpiryTarget>(
+            r#"
+            SELECT id, login, firstname, lastname, email, language, expiry_at
+            FROM users
+            WHERE (status IS NULL OR status NOT IN ('deleted', 'pending_deletion'))
+              AND ($1::bigint IS NULL OR group_id = $1)
+              AND ($2::text IS NULL OR account_type = $2)
+              AND ($3::bigint IS NULL OR public_type = $3)
+              AND (
+                  (expiry_at IS NULL AND $6)
+                  OR (expiry_at IS NOT NULL
+                      AND ($4::timestamptz IS NULL OR expiry_at >= $4)
+                      AND ($5::timestamptz IS NULL OR expiry_at < $5))
+              )
+            ORDER BY lastname, firstname, id
+            "#,
+        )
+        .bind(campaign.group_id)
+        .bind(campaign.account_type.as_ref().map(|t| t.as_str()))
+        .bind(campaign.public_type)
+        .bind(campaign.expires_from)
+        .bind(campaign.expires_to)
+        .bind(campaign.include_without_expiry)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(targets)
+    }
+
+    /// Set the membership end date of several accounts
+    #[tracing::instrument(skip(self, ids), err)]
+    pub async fn users_set_expiry(&self, ids: &[i64], expiry_at: DateTime<Utc>) -> AppResult<u64> {
+        let result = sqlx::query("UPDATE users SET expiry_at = $1, update_at = NOW() WHERE id = ANY($2)")
+            .bind(expiry_at)
+            .bind(ids)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
     /// Check if email already exists
     #[tracing::instrument(skip(self), err)]
     pub async fn users_email_exists(&self, email: &str, exclude_id: Option<i64>) -> AppResult<bool> {
@@ -298,7 +585,8 @@ impl Repository {
         let row = sqlx::query(
             r#"
             SELECT items_rights, users_rights, loans_rights,
-                   holds_rights, settings_rights, events_rights
+                   holds_rights, settings_rights, events_rights, circulation_override_rights,
+                   priority_holds_rights
             FROM account_types
             WHERE code = $1
             "#,
@@ -315,6 +603,10 @@ impl Repository {
             holds_rights: Rights::from(row.get::<Option<String>, _>("holds_rights")),
             settings_rights: Rights::from(row.get::<Option<String>, _>("settings_rights")),
             events_rights: Rights::from(row.get::<Option<String>, _>("events_rights")),
+            circulation_override_rights: Rights::from(
+                row.get::<Option<String>, _>("circulation_override_rights"),
+            ),
+            priority_holds_rights: Rights::from(row.get::<Option<String>, _>("priority_holds_rights")),
         })
     }
 
@@ -329,9 +621,9 @@ impl Repository {
         let mut params: Vec<String> = Vec::new();
 
         if let Some(ref name) = query.name {
-            params.push(format!("%{}%", name.to_lowercase()));
+            params.push(format!("%{}%", name));
             conditions.push(format!(
-                "(LOWER(firstname) LIKE ${} OR LOWER(lastname) LIKE ${})",
+                "(firstname_normalized LIKE normalize_search(${}) OR lastname_normalized LIKE normalize_search(${}))",
                 params.len(),
                 params.len()
             ));
@@ -394,6 +686,92 @@ impl Repository {
         Ok((users, total))
     }
 
+    /// Activity timeline of a user, newest first: checkouts and returns (active and archived
+    /// loans), hold milestones, payments, and the emails and account changes recorded in the
+    /// audit log for this user.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn users_activity(
+        &self,
+        user_id: i64,
+        kind: Option<UserActivityKind>,
+        page: i64,
+        per_page: i64,
+    ) -> AppResult<(Vec<UserActivity>, i64)> {
+        const TIMELINE: &str = r#"
+            WITH timeline AS (
+                SELECT 'loan' AS kind, 'loan.created' AS event, l.date AS occurred_at, l.id AS source_id,
+                       l.item_id, i.biblio_id, b.title, NULL::numeric AS amount, NULL::bigint AS actor_id,
+                       FALSE AS failed
+                FROM loans_all l
+                LEFT JOIN items i ON i.id = l.item_id
+                LEFT JOIN biblios b ON b.id = i.biblio_id
+                WHERE l.user_id = $1 AND l.date IS NOT NULL
+                UNION ALL
+                SELECT 'return', 'loan.returned', a.returned_at, a.id, a.item_id, i.biblio_id, b.title, NULL, NULL, FALSE
+                FROM loans_all a
+                LEFT JOIN items i ON i.id = a.item_id
+                LEFT JOIN biblios b ON b.id = i.biblio_id
+                WHERE a.user_id = $1 AND a.returned_at IS NOT NULL
+                UNION ALL
+                SELECT 'hold', e.event, e.at, h.id, h.item_id, i.biblio_id, b.title, NULL, NULL, FALSE
+                FROM holds h
+                CROSS JOIN LATERAL (VALUES
+                    ('hold.created', h.created_at),
+                    ('hold.ready', h.notified_at),
+                    ('hold.pickedUp', h.picked_up_at)
+                ) AS e(event, at)
+                LEFT JOIN items i ON i.id = h.item_id
+                LEFT JOIN biblios b ON b.id = i.biblio_id
+                WHERE h.user_id = $1 AND e.at IS NOT NULL
+                UNION ALL
+                SELECT 'hold', 'hold.cancelled', al.created_at, h.id, h.item_id, i.biblio_id, b.title, NULL,
+                       al.user_id, FALSE
+                FROM audit_log al
+                JOIN holds h ON h.id = al.entity_id
+                LEFT JOIN items i ON i.id = h.item_id
+                LEFT JOIN biblios b ON b.id = i.biblio_id
+                WHERE al.entity_type = 'hold' AND al.event_type = 'hold.cancelled'
+                  AND al.outcome = 'success' AND h.user_id = $1
+                UNION ALL
+                SELECT 'payment', CASE WHEN p.amount < 0 THEN 'payment.refunded' ELSE 'payment.recorded' END,
+                       p.created_at, p.id, NULL, NULL, NULL, p.amount, p.operator_id, FALSE
+                FROM payments p
+                WHERE p.user_id = $1
+                UNION ALL
+                SELECT CASE WHEN al.event_type LIKE 'email.%' THEN 'notification' ELSE 'profileChange' END,
+                       al.event_type, al.created_at, al.id, NULL, NULL, NULL, NULL, al.user_id,
+                       al.outcome = 'failure'
+                FROM audit_log al
+                WHERE al.entity_type = 'user' AND al.entity_id = $1
+                  AND (al.event_type LIKE 'email.%'
+                       OR (al.event_type LIKE 'user.%' AND al.event_type <> 'user.impersonated_request'
+                           AND al.outcome = 'success'))
+            )
+        "#;
+
+        let kind = kind.map(|k| k.as_str());
+        let total: i64 = sqlx::query_scalar(&format!(
+            "{TIMELINE} SELECT COUNT(*) FROM timeline WHERE ($2::text IS NULL OR kind = $2)"
+        ))
+        .bind(user_id)
+        .bind(kind)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let rows = sqlx::query_as::<_, UserActivityRow>(&format!(
+            "{TIMELINE} SELECT * FROM timeline WHERE ($2::text IS NULL OR kind = $2) \
+             ORDER BY occurred_at DESC, kind, source_id DESC LIMIT $3 OFFSET $4"
+        ))
+        .bind(user_id)
+        .bind(kind)
+        .bind(per_page)
+        .bind((page - 1) * per_page)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((rows.into_iter().map(UserActivity::from).collect(), total))
+    }
+
     /// Create a new user
     #[tracing::instrument(skip(self), err)]
     pub async fn users_create(&self, user: &UserPayload, password: Option<String>) -> AppResult<User> {
@@ -588,26 +966,366 @@ impl Repository {
     /// Delete a user (soft delete: anonymize data and set status to deleted)
     #[tracing::instrument(skip(self), err)]
     pub async fn users_delete(&self, id: i64, force: bool) -> AppResult<()> {
+        self.users_end_loans_for_deletion(id, force).await?;
+
+        // Soft-delete does not remove the `users` row, so ON DELETE CASCADE on `holds` does not run.
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM holds WHERE user_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        Self::users_anonymize(&mut tx, &[id]).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// First phase of a delete: the account stops working and gets anonymized by
+    /// [`Self::users_purge_pending_deletions`] once `scheduled_at` has passed.
+    ///
+    /// Loans and holds are handled as in [`Self::users_delete`]; the previous status is kept for
+    /// [`Self::users_restore`].
+    #[tracing::instrument(skip(self), err)]
+    pub async fn users_schedule_deletion(&self, id: i64, force: bool, scheduled_at: DateTime<Utc>) -> AppResult<User> {
+        let user = self.users_get_by_id(id).await?;
+        if user.status.is_some_and(|s| s.is_deleted()) {
+            return Err(AppError::NotFound(format!("User with id {} not found", id)));
+        }
+        self.users_end_loans_for_deletion(id, force).await?;
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM holds WHERE user_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            UPDATE users SET
+                status_before_deletion = COALESCE(status, 'active'),
+                status = $1,
+                deletion_scheduled_at = $2,
+                update_at = NOW()
+            WHERE id = $3
+            "#,
+        )
+        .bind(UserStatus::PendingDeletion)
+        .bind(scheduled_at)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        self.users_get_by_id(id).await
+    }
+
+    /// Cancel a pending deletion (the account gets back the status it had before)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn users_restore(&self, id: i64) -> AppResult<User> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users SET
+                status = COALESCE(status_before_deletion, 'active'),
+                status_before_deletion = NULL,
+                deletion_scheduled_at = NULL,
+                update_at = NOW()
+            WHERE id = $1 AND status = $2
+            "#,
+        )
+        .bind(id)
+        .bind(UserStatus::PendingDeletion)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            let user = self.users_get_by_id(id).await?;
+            return Err(match user.status {
+                Some(UserStatus::Deleted) => AppError::BusinessRule(
+                    "User has already been anonymized and cannot be restored".to_string(),
+                ),
+                _ => AppError::BusinessRule("User is not pending deletion".to_string()),
+            });
+        }
+        self.users_get_by_id(id).await
+    }
+
+    /// Second phase of a delete: anonymize the pending deletions whose date has passed.
+    /// Returns the anonymized user ids.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn users_purge_pending_deletions(&self) -> AppResult<Vec<i64>> {
+        let mut tx = self.pool.begin().await?;
+        // SKIP LOCKED: a concurrent run (another instance) takes the remaining rows
+        let ids: Vec<i64> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM users
+            WHERE status = $1 AND deletion_scheduled_at <= NOW()
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(UserStatus::PendingDeletion)
+        .fetch_all(&mut *tx)
+        .await?;
+        Self::users_anonymize(&mut tx, &ids).await?;
+        tx.commit().await?;
+        Ok(ids)
+    }
+
+    /// Move everything attached to `duplicate_id` to `survivor_id`, archive the duplicate and
+    /// write the merged contact data on the survivor, in one transaction (rolled back when
+    /// `dry_run` is set, so the counts can be previewed).
+    ///
+    /// When both accounts hold the same copy, only the better-placed hold is kept; when both
+    /// joined the same reading program, the duplicate's entries are folded into the survivor's
+    /// enrollment.
+    #[tracing::instrument(skip(self, contact), err)]
+    pub async fn users_merge(
+        &self,
+        survivor_id: i64,
+        duplicate_id: i64,
+        contact: &UserContactMerge,
+        dry_run: bool,
+    ) -> AppResult<UserMergeCounts> {
+        let mut tx = self.pool.begin().await?;
+
+        let locked: Vec<i64> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM users
+            WHERE id = ANY($1) AND status IS DISTINCT FROM $2 AND status IS DISTINCT FROM $3
+            ORDER BY id
+            FOR UPDATE
+            "#,
+        )
+        .bind([survivor_id, duplicate_id])
+        .bind(UserStatus::Deleted)
+        .bind(UserStatus::PendingDeletion)
+        .fetch_all(&mut *tx)
+        .await?;
+        for id in [survivor_id, duplicate_id] {
+            if !locked.contains(&id) {
+                return Err(AppError::NotFound(format!("User with id {} not found", id)));
+            }
+        }
+
+        let mut counts = UserMergeCounts::default();
+        let reassign = |table: &str, column: &str| {
+            format!("UPDATE {table} SET {column} = $1 WHERE {column} = $2")
+        };
+
+        counts.loans = sqlx::query(&reassign("loans", "user_id"))
+            .bind(survivor_id)
+            .bind(duplicate_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as i64;
+        counts.archived_loans = sqlx::query(&reassign("loans_archives", "user_id"))
+            .bind(survivor_id)
+            .bind(duplicate_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as i64;
+
+        // `fines` is not created by the bundled migrations on every deployment
+        let has_fines: bool = sqlx::query_scalar("SELECT to_regclass('public.fines') IS NOT NULL")
+            .fetch_one(&mut *tx)
+            .await?;
+        if has_fines {
+            counts.fines = sqlx::query(&reassign("fines", "user_id"))
+                .bind(survivor_id)
+                .bind(duplicate_id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected() as i64;
+        }
+
+        // Same copy held by both accounts: keep the hold that comes first in the queue
+        // (`in_locker`, then `ready`, then `pending` by position; the survivor's on a tie)
+        counts.holds_dropped = sqlx::query(
+            r#"
+            DELETE FROM holds h
+            USING holds o
+            WHERE h.item_id = o.item_id
+              AND h.status IN ('pending', 'ready', 'in_locker') AND o.status IN ('pending', 'ready', 'in_locker')
+              AND ((h.user_id = $1 AND o.user_id = $2) OR (h.user_id = $2 AND o.user_id = $1))
+              AND (CASE h.status WHEN 'in_locker' THEN 0 WHEN 'ready' THEN 1 ELSE 2 END, h.position, h.user_id = $2)
+                > (CASE o.status WHEN 'in_locker' THEN 0 WHEN 'ready' THEN 1 ELSE 2 END, o.position, o.user_id = $2)
+            "#,
+        )
+        .bind(survivor_id)
+        .bind(duplicate_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+        counts.holds = sqlx::query(&reassign("holds", "user_id"))
+            .bind(survivor_id)
+            .bind(duplicate_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as i64;
+
+        // Programs joined by both accounts: fold the duplicate's entries into the survivor's
+        // enrollment (a loan already counted there is not counted twice)
+        sqlx::query(
+            r#"
+            DELETE FROM reading_entries re
+            USING reading_program_enrollments de, reading_program_enrollments se, reading_entries sre
+            WHERE re.enrollment_id = de.id AND de.user_id = $2
+              AND se.program_id = de.program_id AND se.user_id = $1
+              AND sre.enrollment_id = se.id AND sre.loan_archive_id = re.loan_archive_id
+            "#,
+        )
+        .bind(survivor_id)
+        .bind(duplicate_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"
+            UPDATE reading_entries re SET enrollment_id = se.id
+            FROM reading_program_enrollments de, reading_program_enrollments se
+            WHERE re.enrollment_id = de.id AND de.user_id = $2
+              AND se.program_id = de.program_id AND se.user_id = $1
+            "#,
+        )
+        .bind(survivor_id)
+        .bind(duplicate_id)
+        .execute(&mut *tx)
+        .await?;
+        let folded = sqlx::query(
+            r#"
+            WITH folded AS (
+                DELETE FROM reading_program_enrollments de
+                USING reading_program_enrollments se
+                WHERE de.user_id = $2 AND se.program_id = de.program_id AND se.user_id = $1
+                RETURNING de.program_id, de.enrolled_at, de.completed_at
+            )
+            UPDATE reading_program_enrollments se SET
+                enrolled_at = LEAST(se.enrolled_at, folded.enrolled_at),
+                completed_at = LEAST(se.completed_at, folded.completed_at)
+            FROM folded
+            WHERE se.program_id = folded.program_id AND se.user_id = $1
+            "#,
+        )
+        .bind(survivor_id)
+        .bind(duplicate_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+        counts.enrollments = folded
+            + sqlx::query(&reassign("reading_program_enrollments", "user_id"))
+                .bind(survivor_id)
+                .bind(duplicate_id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected() as i64;
+
+        counts.loan_batches = sqlx::query(&reassign("loan_batches", "user_id"))
+            .bind(survivor_id)
+            .bind(duplicate_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as i64;
+        counts.item_accesses = sqlx::query(&reassign("item_accesses", "user_id"))
+            .bind(survivor_id)
+            .bind(duplicate_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as i64;
+        counts.donations = sqlx::query(&reassign("donations", "donor_user_id"))
+            .bind(survivor_id)
+            .bind(duplicate_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as i64;
+
+        // Archive the duplicate first: its card barcode may move to the survivor
+        sqlx::query(
+            r#"
+            UPDATE users SET
+                login = NULL,
+                password = NULL,
+                barcode = NULL,
+                pin_hash = NULL,
+                email = NULL,
+                phone = NULL,
+                addr_street = NULL,
+                addr_city = NULL,
+                totp_secret = NULL,
+                recovery_codes = NULL,
+                two_factor_enabled = FALSE,
+                status = $1,
+                merged_into = $2,
+                archived_at = NOW(),
+                update_at = NOW()
+            WHERE id = $3
+            "#,
+        )
+        .bind(UserStatus::Deleted)
+        .bind(survivor_id)
+        .bind(duplicate_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE users SET
+                barcode = $1,
+                email = $2,
+                phone = $3,
+                addr_street = $4,
+                addr_zip_code = $5,
+                addr_city = $6,
+                birthdate = $7,
+                sex = $8,
+                public_type = $9,
+                expiry_at = $10,
+                notes = $11,
+                update_at = NOW()
+            WHERE id = $12
+            "#,
+        )
+        .bind(&contact.barcode)
+        .bind(&contact.email)
+        .bind(&contact.phone)
+        .bind(&contact.addr_street)
+        .bind(contact.addr_zip_code)
+        .bind(&contact.addr_city)
+        .bind(contact.birthdate)
+        .bind(contact.sex)
+        .bind(contact.public_type)
+        .bind(contact.expiry_at)
+        .bind(&contact.notes)
+        .bind(survivor_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if dry_run {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
+        Ok(counts)
+    }
+
+    /// Refuse the delete while loans are active, or return them when `force` is set
+    async fn users_end_loans_for_deletion(&self, id: i64, force: bool) -> AppResult<()> {
         let active_loans = self.loans_get_active_ids_for_user(id).await?;
 
-        if active_loans.len() > 0 {
+        if !active_loans.is_empty() {
             if !force {
                 return Err(AppError::BusinessRule(
                     "User has active loans. Use force=true to delete anyway.".to_string()
                 ));
-            } else {
-                for loan_id in active_loans {
-                    self.loans_return(loan_id).await?;
-                }
+            }
+            for loan_id in active_loans {
+                self.loans_return(loan_id).await?;
             }
         }
+        Ok(())
+    }
 
-        // Soft-delete does not remove the `users` row, so ON DELETE CASCADE on `holds` does not run.
-        let mut tx = self.pool.begin().await?;
-        sqlx::query("DELETE FROM holds WHERE user_id = $1")
-            .bind(id)
-            .execute(&mut *tx)
-            .await?;
+    /// Clear the personal data of the given users and mark them deleted
+    async fn users_anonymize(tx: &mut sqlx::Transaction<'_, sqlx::Postgres>, ids: &[i64]) -> AppResult<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
         sqlx::query(
             r#"
             UPDATE users SET
@@ -619,18 +1337,19 @@ impl Repository {
                 phone = NULL,
                 addr_street = NULL,
                 addr_city = NULL,
+                birthdate = NULL,
                 status = $1,
+                status_before_deletion = NULL,
+                deletion_scheduled_at = NULL,
                 archived_at = NOW(),
                 update_at = NOW()
-            WHERE id = $2
+            WHERE id = ANY($2)
             "#,
         )
         .bind(UserStatus::Deleted)
-        .bind(id)
-        .execute(&mut *tx)
+        .bind(ids)
+        .execute(&mut **tx)
         .await?;
-        tx.commit().await?;
-
         Ok(())
     }
     
@@ -0,0 +1,376 @@
+//! Vendors domain methods on Repository (suppliers, purchase orders, spend report)
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::vendor::{
+        CreatePurchaseOrder, CreateVendor, PurchaseOrder, PurchaseOrderQuery,
+        PurchaseOrderStatus, UpdatePurchaseOrder, UpdateVendor, Vendor, VendorAnnualSpend,
+        VendorSpendQuery,
+    },
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait VendorsRepository: Send + Sync {
+    async fn vendors_list(&self, include_inactive: bool) -> AppResult<Vec<Vendor>>;
+    async fn vendors_get_by_id(&self, id: i64) -> AppResult<Vendor>;
+    async fn vendors_create(&self, data: &CreateVendor) -> AppResult<Vendor>;
+    async fn vendors_update(&self, id: i64, data: &UpdateVendor) -> AppResult<Vendor>;
+    async fn vendors_delete(&self, id: i64) -> AppResult<()>;
+    async fn vendors_count_orders(&self, vendor_id: i64) -> AppResult<i64>;
+    /// True when another vendor already uses `name` (case-insensitive).
+    async fn vendors_name_in_use(&self, name: &str, exclude_id: Option<i64>) -> AppResult<bool>;
+    async fn vendors_list_orders(&self, query: &PurchaseOrderQuery) -> AppResult<Vec<PurchaseOrder>>;
+    async fn vendors_get_order(&self, id: i64) -> AppResult<PurchaseOrder>;
+    async fn vendors_create_order(&self, data: &CreatePurchaseOrder) -> AppResult<PurchaseOrder>;
+    async fn vendors_update_order(
+        &self,
+        id: i64,
+        data: &UpdatePurchaseOrder,
+    ) -> AppResult<PurchaseOrder>;
+    async fn vendors_delete_order(&self, id: i64) -> AppResult<()>;
+    async fn vendors_annual_spend(&self, query: &VendorSpendQuery) -> AppResult<Vec<VendorAnnualSpend>>;
+}
+
+#[async_trait::async_trait]
+impl VendorsRepository for Repository {
+    async fn vendors_list(&self, include_inactive: bool) -> AppResult<Vec<Vendor>> {
+        Repository::vendors_list(self, include_inactive).await
+    }
+    async fn vendors_get_by_id(&self, id: i64) -> AppResult<Vendor> {
+        Repository::vendors_get_by_id(self, id).await
+    }
+    async fn vendors_create(&self, data: &CreateVendor) -> AppResult<Vendor> {
+        Repository::vendors_create(self, data).await
+    }
+    async fn vendors_update(&self, id: i64, data: &UpdateVendor) -> AppResult<Vendor> {
+        Repository::vendors_update(self, id, data).await
+    }
+    async fn vendors_delete(&self, id: i64) -> AppResult<()> {
+        Repository::vendors_delete(self, id).await
+    }
+    async fn vendors_count_orders(&self, vendor_id: i64) -> AppResult<i64> {
+        Repository::vendors_count_orders(self, vendor_id).await
+    }
+    async fn vendors_name_in_use(&self, name: &str, exclude_id: Option<i64>) -> AppResult<bool> {
+        Repository::vendors_name_in_use(self, name, exclude_id).await
+    }
+    async fn vendors_list_orders(&self, query: &PurchaseOrderQuery) -> AppResult<Vec<PurchaseOrder>> {
+        Repository::vendors_list_orders(self, query).await
+    }
+    async fn vendors_get_order(&self, id: i64) -> AppResult<PurchaseOrder> {
+        Repository::vendors_get_order(self, id).await
+    }
+    async fn vendors_create_order(&self, data: &CreatePurchaseOrder) -> AppResult<PurchaseOrder> {
+        Repository::vendors_create_order(self, data).await
+    }
+    async fn vendors_update_order(&self, id: i64, data: &UpdatePurchaseOrder) -> AppResult<PurchaseOrder> {
+        Repository::vendors_update_order(self, id, data).await
+    }
+    async fn vendors_delete_order(&self, id: i64) -> AppResult<()> {
+        Repository::vendors_delete_order(self, id).await
+    }
+    async fn vendors_annual_spend(&self, query: &VendorSpendQuery) -> AppResult<Vec<VendorAnnualSpend>> {
+        Repository::vendors_annual_spend(self, query).await
+    }
+}
+
+impl Repository {
+    // ---- Vendors ----
+
+    /// List vendors, ordered by name
+    #[tracing::instrument(skip(self), err)]
+    pub async fn vendors_list(&self, include_inactive: bool) -> AppResult<Vec<Vendor>> {
+        let rows = sqlx::query_as::<_, Vendor>(
+            "SELECT * FROM vendors WHERE $1 OR is_active ORDER BY name",
+        )
+        .bind(include_inactive)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get vendor by ID
+    #[tracing::instrument(skip(self), err)]
+    pub async fn vendors_get_by_id(&self, id: i64) -> AppResult<Vendor> {
+        sqlx::query_as::<_, Vendor>("SELECT * FROM vendors WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Vendor {} not found", id)))
+    }
+
+    /// Create a vendor
+    #[tracing::instrument(skip(self), err)]
+    pub async fn vendors_create(&self, data: &CreateVendor) -> AppResult<Vendor> {
+        let row = sqlx::query_as::<_, Vendor>(
+            r#"
+            INSERT INTO vendors (
+                name, contact_name, email, phone, address, website,
+                customer_number, discount_rate, notes
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#,
+        )
+        .bind(data.name.trim())
+        .bind(&data.contact_name)
+        .bind(&data.email)
+        .bind(&data.phone)
+        .bind(&data.address)
+        .bind(&data.website)
+        .bind(&data.customer_number)
+        .bind(data.discount_rate.unwrap_or_default())
+        .bind(&data.notes)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Update a vendor
+    #[tracing::instrument(skip(self), err)]
+    pub async fn vendors_update(&self, id: i64, data: &UpdateVendor) -> AppResult<Vendor> {
+        let now = Utc::now();
+        let mut sets = vec!["update_at = $1".to_string()];
+        let mut idx = 2;
+
+        macro_rules! add_field {
+            ($field:expr, $name:expr) => {
+                if $field.is_some() {
+                    sets.push(format!("{} = ${}", $name, idx));
+                    idx += 1;
+                }
+            };
+        }
+
+        add_field!(data.name, "name");
+        add_field!(data.contact_name, "contact_name");
+        add_field!(data.email, "email");
+        add_field!(data.phone, "phone");
+        add_field!(data.address, "address");
+        add_field!(data.website, "website");
+        add_field!(data.customer_number, "customer_number");
+        add_field!(data.discount_rate, "discount_rate");
+        add_field!(data.is_active, "is_active");
+        add_field!(data.notes, "notes");
+
+        let query = format!("UPDATE vendors SET {} WHERE id = ${} RETURNING *", sets.join(", "), idx);
+
+        let mut builder = sqlx::query_as::<_, Vendor>(&query).bind(now);
+
+        macro_rules! bind_field {
+            ($field:expr) => {
+                if let Some(ref val) = $field {
+                    builder = builder.bind(val);
+                }
+            };
+        }
+
+        if let Some(ref name) = data.name {
+            builder = builder.bind(name.trim().to_string());
+        }
+        bind_field!(data.contact_name);
+        bind_field!(data.email);
+        bind_field!(data.phone);
+        bind_field!(data.address);
+        bind_field!(data.website);
+        bind_field!(data.customer_number);
+        bind_field!(data.discount_rate);
+        bind_field!(data.is_active);
+        bind_field!(data.notes);
+
+        builder
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Vendor {} not found", id)))
+    }
+
+    /// Delete a vendor (linked sources keep existing and become non-commercial)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn vendors_delete(&self, id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM vendors WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Vendor {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Count purchase orders placed with a vendor
+    #[tracing::instrument(skip(self), err)]
+    pub async fn vendors_count_orders(&self, vendor_id: i64) -> AppResult<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM purchase_orders WHERE vendor_id = $1")
+            .bind(vendor_id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    /// True when another vendor already uses `name` (case-insensitive)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn vendors_name_in_use(&self, name: &str, exclude_id: Option<i64>) -> AppResult<bool> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM vendors WHERE LOWER(name) = LOWER($1) AND ($2::bigint IS NULL OR id <> $2))",
+        )
+        .bind(name)
+        .bind(exclude_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(exists)
+    }
+
+    // ---- Purchase orders ----
+
+    /// List purchase orders, most recent first
+    #[tracing::instrument(skip(self), err)]
+    pub async fn vendors_list_orders(&self, query: &PurchaseOrderQuery) -> AppResult<Vec<PurchaseOrder>> {
+        let rows = sqlx::query_as::<_, PurchaseOrder>(
+            r#"
+            SELECT * FROM purchase_orders
+            WHERE ($1::bigint IS NULL OR vendor_id = $1)
+              AND ($2::int IS NULL OR EXTRACT(YEAR FROM order_date)::int = $2)
+              AND ($3::text IS NULL OR status = $3)
+            ORDER BY order_date DESC, id DESC
+            "#,
+        )
+        .bind(query.vendor_id)
+        .bind(query.year)
+        .bind(query.status.map(|s| s.as_str()))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get purchase order by ID
+    #[tracing::instrument(skip(self), err)]
+    pub async fn vendors_get_order(&self, id: i64) -> AppResult<PurchaseOrder> {
+        sqlx::query_as::<_, PurchaseOrder>("SELECT * FROM purchase_orders WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Purchase order {} not found", id)))
+    }
+
+    /// Create a purchase order
+    #[tracing::instrument(skip(self), err)]
+    pub async fn vendors_create_order(&self, data: &CreatePurchaseOrder) -> AppResult<PurchaseOrder> {
+        let row = sqlx::query_as::<_, PurchaseOrder>(
+            r#"
+            INSERT INTO purchase_orders (
+                vendor_id, source_id, reference, order_date, status, total_amount, notes
+            )
+            VALUES ($1, $2, $3, COALESCE($4, CURRENT_DATE), $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(data.vendor_id)
+        .bind(data.source_id)
+        .bind(&data.reference)
+        .bind(data.order_date)
+        .bind(data.status.unwrap_or(PurchaseOrderStatus::Ordered))
+        .bind(data.total_amount)
+        .bind(&data.notes)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Update a purchase order
+    #[tracing::instrument(skip(self), err)]
+    pub async fn vendors_update_order(&self, id: i64, data: &UpdatePurchaseOrder) -> AppResult<PurchaseOrder> {
+        let now = Utc::now();
+        let mut sets = vec!["update_at = $1".to_string()];
+        let mut idx = 2;
+
+        macro_rules! add_field {
+            ($field:expr, $name:expr) => {
+                if $field.is_some() {
+                    sets.push(format!("{} = ${}", $name, idx));
+                    idx += 1;
+                }
+            };
+        }
+
+        add_field!(data.source_id, "source_id");
+        add_field!(data.reference, "reference");
+        add_field!(data.order_date, "order_date");
+        add_field!(data.status, "status");
+        add_field!(data.total_amount, "total_amount");
+        add_field!(data.notes, "notes");
+
+        let query = format!("UPDATE purchase_orders SET {} WHERE id = ${} RETURNING *", sets.join(", "), idx);
+
+        let mut builder = sqlx::query_as::<_, PurchaseOrder>(&query).bind(now);
+
+        macro_rules! bind_field {
+            ($field:expr) => {
+                if let Some(ref val) = $field {
+                    builder = builder.bind(val);
+                }
+            };
+        }
+
+        bind_field!(data.source_id);
+        bind_field!(data.reference);
+        bind_field!(data.order_date);
+        bind_field!(data.status);
+        bind_field!(data.total_amount);
+        bind_field!(data.notes);
+
+        builder
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Purchase order {} not found", id)))
+    }
+
+    /// Delete a purchase order
+    #[tracing::instrument(skip(self), err)]
+    pub async fn vendors_delete_order(&self, id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM purchase_orders WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Purchase order {} not found", id)));
+        }
+        Ok(())
+    }
+
+    // ---- Spend report ----
+
+    /// Spend per vendor and year from non-cancelled purchase orders
+    #[tracing::instrument(skip(self), err)]
+    pub async fn vendors_annual_spend(&self, query: &VendorSpendQuery) -> AppResult<Vec<VendorAnnualSpend>> {
+        let rows = sqlx::query_as::<_, VendorAnnualSpend>(
+            r#"
+            SELECT
+                v.id AS vendor_id,
+                v.name AS vendor_name,
+                EXTRACT(YEAR FROM po.order_date)::int AS year,
+                COUNT(*) AS orders,
+                COALESCE(SUM(po.total_amount), 0) AS total_amount,
+                COALESCE(SUM(po.total_amount) FILTER (WHERE po.status = 'received'), 0) AS received_amount
+            FROM purchase_orders po
+            JOIN vendors v ON v.id = po.vendor_id
+            WHERE po.status <> 'cancelled'
+              AND ($1::int IS NULL OR EXTRACT(YEAR FROM po.order_date)::int = $1)
+              AND ($2::bigint IS NULL OR po.vendor_id = $2)
+            GROUP BY v.id, v.name, 3
+            ORDER BY 3 DESC, total_amount DESC, v.name
+            "#,
+        )
+        .bind(query.year)
+        .bind(query.vendor_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
@@ -69,7 +69,7 @@ impl Repository {
         };
 
         let query = format!(
-            "SELECT * FROM visitor_counts {} ORDER BY count_date DESC",
+            "SELECT * FROM visitor_counts {} ORDER BY count_date DESC, count_hour NULLS FIRST",
             where_clause
         );
 
@@ -110,8 +110,8 @@ impl Repository {
 
         let row = sqlx::query_as::<_, VisitorCount>(
             r#"
-            INSERT INTO visitor_counts (count_date, count, source, notes)
-            VALUES ($1, $2, $3, $4)
+            INSERT INTO visitor_counts (count_date, count, source, notes, count_hour)
+            VALUES ($1, $2, $3, $4, $5)
             RETURNING *
             "#,
         )
@@ -119,6 +119,7 @@ impl Repository {
         .bind(data.count)
         .bind(&data.source)
         .bind(&data.notes)
+        .bind(data.count_hour)
         .fetch_one(&self.pool)
         .await?;
 
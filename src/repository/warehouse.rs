@@ -0,0 +1,479 @@
+//! Data warehouse exports (`warehouse_targets`, `warehouse_runs`) and the anonymized fact
+//! extracts, domain methods on Repository
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::Repository;
+use crate::{
+    error::{AppError, AppResult},
+    models::warehouse::{
+        AcquisitionFact, CreateWarehouseTarget, LoanFact, UpdateWarehouseTarget, VisitFact, WarehouseFact,
+        WarehouseRun, WarehouseTarget, WarehouseTrigger,
+    },
+};
+
+const TARGET_COLUMNS: &str = "id, name, url, username, password, (password IS NOT NULL) AS has_password, \
+     host_key_fingerprint, region, endpoint, format, facts, schedule_time, is_active, watermarks, \
+     last_run_at, created_at, update_at";
+
+/// `manifest` is read as JSON `null` rather than SQL NULL so it decodes as `Option`
+const RUN_COLUMNS: &str = "id, target_id, trigger, status, started_by, started_at, finished_at, \
+     COALESCE(manifest, 'null'::jsonb) AS manifest, error";
+
+fn fact_names(facts: &Option<Vec<WarehouseFact>>) -> Option<Vec<&'static str>> {
+    facts.as_ref().map(|f| f.iter().map(WarehouseFact::as_str).collect())
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait WarehouseRepository: Send + Sync {
+    async fn warehouse_targets_list(&self) -> AppResult<Vec<WarehouseTarget>>;
+    async fn warehouse_targets_get(&self, id: i64) -> AppResult<WarehouseTarget>;
+    async fn warehouse_targets_create(&self, data: &CreateWarehouseTarget) -> AppResult<WarehouseTarget>;
+    async fn warehouse_targets_update(&self, id: i64, data: &UpdateWarehouseTarget) -> AppResult<WarehouseTarget>;
+    async fn warehouse_targets_delete(&self, id: i64) -> AppResult<()>;
+    async fn warehouse_targets_set_watermarks(
+        &self,
+        id: i64,
+        watermarks: &BTreeMap<String, DateTime<Utc>>,
+    ) -> AppResult<()>;
+    /// Open a run unless another instance claimed the target since `last_run_at` was read, or a
+    /// run is still in progress.
+    async fn warehouse_runs_start(
+        &self,
+        target_id: i64,
+        last_run_at: Option<DateTime<Utc>>,
+        trigger: WarehouseTrigger,
+        started_by: Option<i64>,
+    ) -> AppResult<Option<WarehouseRun>>;
+    async fn warehouse_runs_finish(&self, run: &WarehouseRun) -> AppResult<WarehouseRun>;
+    async fn warehouse_runs_list(&self, target_id: i64, limit: i64) -> AppResult<Vec<WarehouseRun>>;
+    async fn warehouse_runs_get(&self, id: i64) -> AppResult<WarehouseRun>;
+    /// Loans changed in `(after, until]`, ordered by `(changed_at, loan_key)` after `cursor`
+    async fn warehouse_loan_facts(
+        &self,
+        after: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+        cursor: Option<(DateTime<Utc>, String)>,
+        limit: i64,
+    ) -> AppResult<Vec<LoanFact>>;
+    /// Copies created in `(after, until]`, ordered by `(created_at, item_id)` after `cursor`
+    async fn warehouse_acquisition_facts(
+        &self,
+        after: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+        cursor: Option<(DateTime<Utc>, i64)>,
+        limit: i64,
+    ) -> AppResult<Vec<AcquisitionFact>>;
+    /// Visitor counts recorded in `(after, until]`, ordered by `(recorded_at, visit_id)` after `cursor`
+    async fn warehouse_visit_facts(
+        &self,
+        after: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+        cursor: Option<(DateTime<Utc>, i64)>,
+        limit: i64,
+    ) -> AppResult<Vec<VisitFact>>;
+}
+
+#[async_trait]
+impl WarehouseRepository for Repository {
+    async fn warehouse_targets_list(&self) -> AppResult<Vec<WarehouseTarget>> {
+        Repository::warehouse_targets_list(self).await
+    }
+    async fn warehouse_targets_get(&self, id: i64) -> AppResult<WarehouseTarget> {
+        Repository::warehouse_targets_get(self, id).await
+    }
+    async fn warehouse_targets_create(&self, data: &CreateWarehouseTarget) -> AppResult<WarehouseTarget> {
+        Repository::warehouse_targets_create(self, data).await
+    }
+    async fn warehouse_targets_update(&self, id: i64, data: &UpdateWarehouseTarget) -> AppResult<WarehouseTarget> {
+        Repository::warehouse_targets_update(self, id, data).await
+    }
+    async fn warehouse_targets_delete(&self, id: i64) -> AppResult<()> {
+        Repository::warehouse_targets_delete(self, id).await
+    }
+    async fn warehouse_targets_set_watermarks(
+        &self,
+        id: i64,
+        watermarks: &BTreeMap<String, DateTime<Utc>>,
+    ) -> AppResult<()> {
+        Repository::warehouse_targets_set_watermarks(self, id, watermarks).await
+    }
+    async fn warehouse_runs_start(
+        &self,
+        target_id: i64,
+        last_run_at: Option<DateTime<Utc>>,
+        trigger: WarehouseTrigger,
+        started_by: Option<i64>,
+    ) -> AppResult<Option<WarehouseRun>> {
+        Repository::warehouse_runs_start(self, target_id, last_run_at, trigger, started_by).await
+    }
+    async fn warehouse_runs_finish(&self, run: &WarehouseRun) -> AppResult<WarehouseRun> {
+        Repository::warehouse_runs_finish(self, run).await
+    }
+    async fn warehouse_runs_list(&self, target_id: i64, limit: i64) -> AppResult<Vec<WarehouseRun>> {
+        Repository::warehouse_runs_list(self, target_id, limit).await
+    }
+    async fn warehouse_runs_get(&self, id: i64) -> AppResult<WarehouseRun> {
+        Repository::warehouse_runs_get(self, id).await
+    }
+    async fn warehouse_loan_facts(
+        &self,
+        after: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+        cursor: Option<(DateTime<Utc>, String)>,
+        limit: i64,
+    ) -> AppResult<Vec<LoanFact>> {
+        Repository::warehouse_loan_facts(self, after, until, cursor, limit).await
+    }
+    async fn warehouse_acquisition_facts(
+        &self,
+        after: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+        cursor: Option<(DateTime<Utc>, i64)>,
+        limit: i64,
+    ) -> AppResult<Vec<AcquisitionFact>> {
+        Repository::warehouse_acquisition_facts(self, after, until, cursor, limit).await
+    }
+    async fn warehouse_visit_facts(
+        &self,
+        after: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+        cursor: Option<(DateTime<Utc>, i64)>,
+        limit: i64,
+    ) -> AppResult<Vec<VisitFact>> {
+        Repository::warehouse_visit_facts(self, after, until, cursor, limit).await
+    }
+}
+
+impl Repository {
+    /// List warehouse targets by name
+    #[tracing::instrument(skip(self), err)]
+    pub async fn warehouse_targets_list(&self) -> AppResult<Vec<WarehouseTarget>> {
+        let rows = sqlx::query_as::<_, WarehouseTarget>(&format!(
+            "SELECT {} FROM warehouse_targets ORDER BY name, id",
+            TARGET_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get a warehouse target by ID
+    #[tracing::instrument(skip(self), err)]
+    pub async fn warehouse_targets_get(&self, id: i64) -> AppResult<WarehouseTarget> {
+        sqlx::query_as::<_, WarehouseTarget>(&format!(
+            "SELECT {} FROM warehouse_targets WHERE id = $1",
+            TARGET_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Warehouse target {} not found", id)))
+    }
+
+    /// Create a warehouse target
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn warehouse_targets_create(&self, data: &CreateWarehouseTarget) -> AppResult<WarehouseTarget> {
+        let row = sqlx::query_as::<_, WarehouseTarget>(&format!(
+            r#"
+            INSERT INTO warehouse_targets (name, url, username, password, host_key_fingerprint,
+                region, endpoint, format, facts, schedule_time, is_active)
+            VALUES ($1, $2, NULLIF($3, ''), NULLIF($4, ''), NULLIF($5, ''), COALESCE(NULLIF($6, ''), 'us-east-1'),
+                NULLIF($7, ''), COALESCE($8, 'parquet'), COALESCE($9, '{{loans,acquisitions,visits}}'),
+                COALESCE($10, '01:30'), COALESCE($11, TRUE))
+            RETURNING {}
+            "#,
+            TARGET_COLUMNS
+        ))
+        .bind(data.name.trim())
+        .bind(data.url.trim())
+        .bind(data.username.as_deref().map(str::trim))
+        .bind(data.password.as_deref())
+        .bind(data.host_key_fingerprint.as_deref().map(str::trim))
+        .bind(data.region.as_deref().map(str::trim))
+        .bind(data.endpoint.as_deref().map(str::trim))
+        .bind(data.format)
+        .bind(fact_names(&data.facts))
+        .bind(data.schedule_time.as_deref())
+        .bind(data.is_active)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Update a warehouse target (absent fields are kept; an empty `password` or `endpoint` clears it)
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn warehouse_targets_update(&self, id: i64, data: &UpdateWarehouseTarget) -> AppResult<WarehouseTarget> {
+        sqlx::query_as::<_, WarehouseTarget>(&format!(
+            r#"
+            UPDATE warehouse_targets SET
+                name = COALESCE($1, name),
+                url = COALESCE($2, url),
+                username = CASE WHEN $3::text IS NULL THEN username ELSE NULLIF($3, '') END,
+                password = CASE WHEN $4::text IS NULL THEN password ELSE NULLIF($4, '') END,
+                host_key_fingerprint = CASE WHEN $5::text IS NULL THEN host_key_fingerprint
+                                            ELSE NULLIF($5, '') END,
+                region = COALESCE(NULLIF($6, ''), region),
+                endpoint = CASE WHEN $7::text IS NULL THEN endpoint ELSE NULLIF($7, '') END,
+                format = COALESCE($8, format),
+                facts = COALESCE($9, facts),
+                schedule_time = COALESCE($10, schedule_time),
+                is_active = COALESCE($11, is_active),
+                watermarks = CASE WHEN $12 THEN '{{}}'::jsonb ELSE watermarks END,
+                update_at = $13
+            WHERE id = $14
+            RETURNING {}
+            "#,
+            TARGET_COLUMNS
+        ))
+        .bind(data.name.as_deref().map(str::trim))
+        .bind(data.url.as_deref().map(str::trim))
+        .bind(data.username.as_deref().map(str::trim))
+        .bind(data.password.as_deref())
+        .bind(data.host_key_fingerprint.as_deref().map(str::trim))
+        .bind(data.region.as_deref().map(str::trim))
+        .bind(data.endpoint.as_deref().map(str::trim))
+        .bind(data.format)
+        .bind(fact_names(&data.facts))
+        .bind(data.schedule_time.as_deref())
+        .bind(data.is_active)
+        .bind(data.reset_watermarks)
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Warehouse target {} not found", id)))
+    }
+
+    /// Delete a warehouse target with its run history (files already exported stay remote)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn warehouse_targets_delete(&self, id: i64) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM warehouse_targets WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Warehouse target {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Move the watermarks after a successful run (merged: facts not exported keep theirs)
+    #[tracing::instrument(skip(self, watermarks), err)]
+    pub async fn warehouse_targets_set_watermarks(
+        &self,
+        id: i64,
+        watermarks: &BTreeMap<String, DateTime<Utc>>,
+    ) -> AppResult<()> {
+        sqlx::query("UPDATE warehouse_targets SET watermarks = watermarks || $1 WHERE id = $2")
+            .bind(sqlx::types::Json(watermarks))
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Claim the target and open a run (same guard as the harvest runs: first instance wins,
+    /// runs left `running` for more than 12 hours no longer block the target)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn warehouse_runs_start(
+        &self,
+        target_id: i64,
+        last_run_at: Option<DateTime<Utc>>,
+        trigger: WarehouseTrigger,
+        started_by: Option<i64>,
+    ) -> AppResult<Option<WarehouseRun>> {
+        let row = sqlx::query_as::<_, WarehouseRun>(&format!(
+            r#"
+            WITH claimed AS (
+                UPDATE warehouse_targets SET last_run_at = NOW()
+                WHERE id = $1
+                  AND last_run_at IS NOT DISTINCT FROM $2
+                  AND NOT EXISTS (
+                      SELECT 1 FROM warehouse_runs
+                      WHERE target_id = $1 AND status = 'running'
+                        AND started_at > NOW() - INTERVAL '12 hours'
+                  )
+                RETURNING id
+            )
+            INSERT INTO warehouse_runs (target_id, trigger, started_by)
+            SELECT id, $3, $4 FROM claimed
+            RETURNING {}
+            "#,
+            RUN_COLUMNS
+        ))
+        .bind(target_id)
+        .bind(last_run_at)
+        .bind(trigger)
+        .bind(started_by)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Store the outcome of a run
+    #[tracing::instrument(skip(self, run), fields(run_id = run.id), err)]
+    pub async fn warehouse_runs_finish(&self, run: &WarehouseRun) -> AppResult<WarehouseRun> {
+        sqlx::query_as::<_, WarehouseRun>(&format!(
+            r#"
+            UPDATE warehouse_runs SET status = $1, finished_at = NOW(), manifest = $2, error = $3
+            WHERE id = $4
+            RETURNING {}
+            "#,
+            RUN_COLUMNS
+        ))
+        .bind(run.status)
+        .bind(run.manifest.as_ref().map(sqlx::types::Json))
+        .bind(run.error.as_deref())
+        .bind(run.id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Warehouse run {} not found", run.id)))
+    }
+
+    /// Most recent runs of a target
+    #[tracing::instrument(skip(self), err)]
+    pub async fn warehouse_runs_list(&self, target_id: i64, limit: i64) -> AppResult<Vec<WarehouseRun>> {
+        let rows = sqlx::query_as::<_, WarehouseRun>(&format!(
+            "SELECT {} FROM warehouse_runs WHERE target_id = $1 ORDER BY started_at DESC, id DESC LIMIT $2",
+            RUN_COLUMNS
+        ))
+        .bind(target_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get a run by ID
+    #[tracing::instrument(skip(self), err)]
+    pub async fn warehouse_runs_get(&self, id: i64) -> AppResult<WarehouseRun> {
+        sqlx::query_as::<_, WarehouseRun>(&format!("SELECT {} FROM warehouse_runs WHERE id = $1", RUN_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Warehouse run {} not found", id)))
+    }
+
+    /// Anonymized loans: they change when started, renewed and returned.
+    ///
+    /// An archived loan moves to `loans_archives` under a new id, so its `archive-<id>` row is
+    /// the final version of an earlier `loan-<id>` row (same item and loan date). It is only
+    /// exported when returned since the previous export: a loan returned then archived later
+    /// (`circulation.archive_returned_after_days`) was already exported returned as `loan-<id>`.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn warehouse_loan_facts(
+        &self,
+        after: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+        cursor: Option<(DateTime<Utc>, String)>,
+        limit: i64,
+    ) -> AppResult<Vec<LoanFact>> {
+        let (cursor_at, cursor_key) = cursor.unzip();
+        let rows = sqlx::query_as::<_, LoanFact>(
+            r#"
+            SELECT * FROM (
+                SELECT CASE WHEN l.archived THEN 'archive-' ELSE 'loan-' END || l.id AS loan_key,
+                       l.date AS loan_date, l.expiry_at AS due_date, l.returned_at,
+                       l.nb_renews::bigint AS renewals,
+                       l.item_id, i.biblio_id, b.media_type, b.audience_type, i.source_id,
+                       i.place::bigint AS place, l.account_type AS borrower_account_type,
+                       pt.name AS borrower_public_type, l.borrower_age_band,
+                       CASE WHEN l.archived THEN COALESCE(l.returned_at, l.date)
+                            ELSE GREATEST(l.date, l.renew_at, l.returned_at) END AS changed_at
+                FROM loans_all l
+                LEFT JOIN items i ON i.id = l.item_id
+                LEFT JOIN biblios b ON b.id = i.biblio_id
+                LEFT JOIN public_types pt ON pt.id = l.borrower_public_type
+                WHERE l.item_id IS NOT NULL
+            ) f
+            WHERE ($1::timestamptz IS NULL OR f.changed_at > $1)
+              AND f.changed_at <= $2
+              AND ($3::timestamptz IS NULL OR (f.changed_at, f.loan_key) > ($3, $4::text))
+            ORDER BY f.changed_at, f.loan_key
+            LIMIT $5
+            "#,
+        )
+        .bind(after)
+        .bind(until)
+        .bind(cursor_at)
+        .bind(cursor_key)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Copies entered in the catalog (archived copies included, with their archive date)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn warehouse_acquisition_facts(
+        &self,
+        after: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+        cursor: Option<(DateTime<Utc>, i64)>,
+        limit: i64,
+    ) -> AppResult<Vec<AcquisitionFact>> {
+        let (cursor_at, cursor_id) = cursor.unzip();
+        let rows = sqlx::query_as::<_, AcquisitionFact>(
+            r#"
+            SELECT i.id AS item_id, i.biblio_id, i.created_at, b.media_type, b.audience_type,
+                   i.source_id, i.place::bigint AS place, i.archived_at
+            FROM items i
+            LEFT JOIN biblios b ON b.id = i.biblio_id
+            WHERE i.created_at IS NOT NULL
+              AND ($1::timestamptz IS NULL OR i.created_at > $1)
+              AND i.created_at <= $2
+              AND ($3::timestamptz IS NULL OR (i.created_at, i.id) > ($3, $4::bigint))
+            ORDER BY i.created_at, i.id
+            LIMIT $5
+            "#,
+        )
+        .bind(after)
+        .bind(until)
+        .bind(cursor_at)
+        .bind(cursor_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Visitor counts (recorded time, or the counted day for rows without one)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn warehouse_visit_facts(
+        &self,
+        after: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+        cursor: Option<(DateTime<Utc>, i64)>,
+        limit: i64,
+    ) -> AppResult<Vec<VisitFact>> {
+        let (cursor_at, cursor_id) = cursor.unzip();
+        let rows = sqlx::query_as::<_, VisitFact>(
+            r#"
+            SELECT * FROM (
+                SELECT v.id AS visit_id, v.count_date, v.count_hour::bigint AS count_hour,
+                       v.count::bigint AS visitors, v.source,
+                       COALESCE(v.created_at, v.count_date::timestamptz) AS recorded_at
+                FROM visitor_counts v
+            ) f
+            WHERE ($1::timestamptz IS NULL OR f.recorded_at > $1)
+              AND f.recorded_at <= $2
+              AND ($3::timestamptz IS NULL OR (f.recorded_at, f.visit_id) > ($3, $4::bigint))
+            ORDER BY f.recorded_at, f.visit_id
+            LIMIT $5
+            "#,
+        )
+        .bind(after)
+        .bind(until)
+        .bind(cursor_at)
+        .bind(cursor_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
@@ -18,6 +18,7 @@ pub struct Z3950ServerRecord {
     pub password: Option<String>,
     pub encoding: Option<String>,
     pub activated: Option<bool>,
+    pub trust_weight: f64,
 }
 
 /// DB access for `z3950servers`. Implemented by [`Repository`].
@@ -40,6 +41,7 @@ pub trait Z3950Repository: Send + Sync {
         password: &Option<String>,
         encoding: &str,
         activated: bool,
+        trust_weight: f64,
     ) -> AppResult<()>;
     async fn z3950_server_insert(
         &self,
@@ -52,6 +54,7 @@ pub trait Z3950Repository: Send + Sync {
         password: &Option<String>,
         encoding: &str,
         activated: bool,
+        trust_weight: f64,
     ) -> AppResult<()>;
 }
 
@@ -80,9 +83,11 @@ impl Z3950Repository for Repository {
         password: &Option<String>,
         encoding: &str,
         activated: bool,
+        trust_weight: f64,
     ) -> AppResult<()> {
         Repository::z3950_server_update(
             self, id, name, address, port, database, format, login, password, encoding, activated,
+            trust_weight,
         )
         .await
     }
@@ -98,9 +103,11 @@ impl Z3950Repository for Repository {
         password: &Option<String>,
         encoding: &str,
         activated: bool,
+        trust_weight: f64,
     ) -> AppResult<()> {
         Repository::z3950_server_insert(
             self, name, address, port, database, format, login, password, encoding, activated,
+            trust_weight,
         )
         .await
     }
@@ -110,7 +117,7 @@ impl Repository {
     /// All servers for staff settings UI (ordered by name).
     pub async fn z3950_servers_list_all(&self) -> AppResult<Vec<Z3950ServerRecord>> {
         sqlx::query_as::<_, Z3950ServerRecord>(
-            r#"SELECT id, name, address, port, database, format, login, password, encoding, activated
+            r#"SELECT id, name, address, port, database, format, login, password, encoding, activated, trust_weight
                FROM z3950servers ORDER BY name"#,
         )
         .fetch_all(&self.pool)
@@ -118,14 +125,14 @@ impl Repository {
         .map_err(Into::into)
     }
 
-    /// Active servers for catalog search (optional filter by server id).
+    /// Active servers for catalog search (optional filter by server id), in a stable order.
     pub async fn z3950_servers_list_active_for_search(
         &self,
         server_id: Option<i64>,
     ) -> AppResult<Vec<Z3950ServerRecord>> {
         let rows = if let Some(id) = server_id {
             sqlx::query_as::<_, Z3950ServerRecord>(
-                r#"SELECT id, name, address, port, database, format, login, password, encoding, activated
+                r#"SELECT id, name, address, port, database, format, login, password, encoding, activated, trust_weight
                    FROM z3950servers WHERE id = $1 AND activated = TRUE"#,
             )
             .bind(id)
@@ -133,8 +140,8 @@ impl Repository {
             .await?
         } else {
             sqlx::query_as::<_, Z3950ServerRecord>(
-                r#"SELECT id, name, address, port, database, format, login, password, encoding, activated
-                   FROM z3950servers WHERE activated = TRUE"#,
+                r#"SELECT id, name, address, port, database, format, login, password, encoding, activated, trust_weight
+                   FROM z3950servers WHERE activated = TRUE ORDER BY id"#,
             )
             .fetch_all(&self.pool)
             .await?
@@ -154,13 +161,15 @@ impl Repository {
         password: &Option<String>,
         encoding: &str,
         activated: bool,
+        trust_weight: f64,
     ) -> AppResult<()> {
         sqlx::query(
             r#"
             UPDATE z3950servers SET
                 name = $1, address = $2, port = $3, database = $4,
-                format = $5, login = $6, password = $7, encoding = $8, activated = $9
-            WHERE id = $10
+                format = $5, login = $6, password = $7, encoding = $8, activated = $9,
+                trust_weight = $10
+            WHERE id = $11
             "#,
         )
         .bind(name)
@@ -172,6 +181,7 @@ impl Repository {
         .bind(password)
         .bind(encoding)
         .bind(activated)
+        .bind(trust_weight)
         .bind(id)
         .execute(&self.pool)
         .await?;
@@ -189,11 +199,12 @@ impl Repository {
         password: &Option<String>,
         encoding: &str,
         activated: bool,
+        trust_weight: f64,
     ) -> AppResult<()> {
         sqlx::query(
             r#"
-            INSERT INTO z3950servers (name, address, port, database, format, login, password, encoding, activated)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            INSERT INTO z3950servers (name, address, port, database, format, login, password, encoding, activated, trust_weight)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             "#,
         )
         .bind(name)
@@ -205,6 +216,7 @@ impl Repository {
         .bind(password)
         .bind(encoding)
         .bind(activated)
+        .bind(trust_weight)
         .execute(&self.pool)
         .await?;
         Ok(())
@@ -26,7 +26,7 @@ impl AccountTypesCatalogService {
         self.repository.account_types_get_by_code(code.trim()).await
     }
 
-    /// Normalize and validate `data` in place (rights letters, name length).
+    /// Normalize and validate `data` in place (rights letters, name length, group loan limits).
     pub async fn update(&self, code: &str, data: &mut UpdateAccountTypeDefinition) -> AppResult<AccountTypeDefinition> {
         let code = code.trim();
         if code.is_empty() {
@@ -51,6 +51,15 @@ impl AccountTypesCatalogService {
         normalize_holds_right_field(&mut data.holds_rights)?;
         normalize_right_field(&mut data.settings_rights)?;
         normalize_right_field(&mut data.events_rights)?;
+        normalize_right_field(&mut data.circulation_override_rights)?;
+        normalize_right_field(&mut data.priority_holds_rights)?;
+        if data.group_loans_max_items.is_some_and(|v| v < 0)
+            || data.group_loans_max_days.is_some_and(|v| v < 0)
+        {
+            return Err(AppError::Validation(
+                "group loan limits must be positive (0 clears the limit)".to_string(),
+            ));
+        }
 
         self.repository.account_types_update(code, data).await
     }
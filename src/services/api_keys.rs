@@ -0,0 +1,187 @@
+//! API keys: credentials for partner applications calling public endpoints
+//!
+//! An API key is an opaque random string (only its SHA-256 is stored) limited to a set of scopes.
+//! Each request made with it is counted per day and endpoint for the usage report.
+
+use std::sync::Arc;
+
+use base64::Engine;
+use chrono::{Duration, NaiveDate, Utc};
+use rand::Rng;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::api_key::{
+        ApiKey, ApiKeyUsage, ApiKeyWithToken, CreateApiKey, UpdateApiKey, API_KEY_PREFIX, API_KEY_SCOPES,
+    },
+    repository::ApiKeysRepository,
+    services::{
+        audit::{self, AuditService},
+        kiosks::hash_token,
+    },
+};
+
+/// Characters of the key kept in `token_prefix`
+const TOKEN_PREFIX_LEN: usize = 14;
+
+/// Days covered by the usage report when no range is given
+const DEFAULT_USAGE_DAYS: i64 = 30;
+
+#[derive(Clone)]
+pub struct ApiKeysService {
+    repository: Arc<dyn ApiKeysRepository>,
+    audit: AuditService,
+}
+
+impl ApiKeysService {
+    pub fn new(repository: Arc<dyn ApiKeysRepository>, audit: AuditService) -> Self {
+        Self { repository, audit }
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self) -> AppResult<Vec<ApiKey>> {
+        self.repository.api_keys_list().await
+    }
+
+    pub async fn get(&self, id: i64) -> AppResult<ApiKey> {
+        self.repository.api_keys_get(id).await
+    }
+
+    /// Register an API key; the generated key is only returned here
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create(&self, data: &CreateApiKey, created_by: i64) -> AppResult<ApiKeyWithToken> {
+        validate_name(Some(&data.name))?;
+        validate_scopes(&data.scopes)?;
+        if data.expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(AppError::Validation("expiresAt must be in the future".to_string()));
+        }
+        let token = generate_token();
+        let api_key = self
+            .repository
+            .api_keys_create(data, &hash_token(&token), &token[..TOKEN_PREFIX_LEN], created_by)
+            .await?;
+        Ok(ApiKeyWithToken { api_key, token })
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update(&self, id: i64, data: &UpdateApiKey) -> AppResult<ApiKey> {
+        validate_name(data.name.as_deref())?;
+        if let Some(scopes) = &data.scopes {
+            validate_scopes(scopes)?;
+        }
+        self.repository.api_keys_update(id, data).await
+    }
+
+    /// Issue a new key; the previous one stops working immediately
+    #[tracing::instrument(skip(self), err)]
+    pub async fn regenerate_token(&self, id: i64) -> AppResult<ApiKeyWithToken> {
+        let token = generate_token();
+        let api_key = self
+            .repository
+            .api_keys_set_token(id, &hash_token(&token), &token[..TOKEN_PREFIX_LEN])
+            .await?;
+        Ok(ApiKeyWithToken { api_key, token })
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete(&self, id: i64) -> AppResult<()> {
+        self.repository.api_keys_delete(id).await
+    }
+
+    /// Daily usage of a key (default: the last 30 days)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn usage(&self, id: i64, from: Option<NaiveDate>, to: Option<NaiveDate>) -> AppResult<Vec<ApiKeyUsage>> {
+        self.repository.api_keys_get(id).await?;
+        let to = to.unwrap_or_else(|| Utc::now().date_naive());
+        let from = from.unwrap_or(to - Duration::days(DEFAULT_USAGE_DAYS - 1));
+        if from > to {
+            return Err(AppError::Validation("from must not be after to".to_string()));
+        }
+        self.repository.api_keys_usage(id, from, to).await
+    }
+
+    /// Resolve the key presenting `token` for an endpoint of `scope`, and count the request.
+    ///
+    /// Refusals are audited (`api_key.access_denied`, with the key id when the token is known).
+    #[tracing::instrument(skip(self, token), err)]
+    pub async fn authenticate(&self, token: &str, scope: &str, records: i64, ip: Option<String>) -> AppResult<ApiKey> {
+        let api_key = match self.repository.api_keys_find_by_token_hash(&hash_token(token)).await? {
+            Some(api_key) => api_key,
+            None => {
+                let err = AppError::Authentication("Invalid API key".to_string());
+                self.audit.log(audit::event::API_KEY_ACCESS_DENIED, None, None, None, ip, None::<serde_json::Value>, audit::AuditLogMeta::from_app_error(&err));
+                return Err(err);
+            }
+        };
+
+        let refusal = if !api_key.is_active {
+            Some(AppError::Authentication("API key has been revoked".to_string()))
+        } else if api_key.expires_at.is_some_and(|at| at <= Utc::now()) {
+            Some(AppError::Authentication("API key has expired".to_string()))
+        } else {
+            api_key.require_scope(scope).err()
+        };
+        if let Some(err) = refusal {
+            self.audit.log(audit::event::API_KEY_ACCESS_DENIED, None, Some("api_key"), Some(api_key.id), ip, Some(serde_json::json!({ "apiKey": api_key.name, "scope": scope })), audit::AuditLogMeta::from_app_error(&err));
+            return Err(err);
+        }
+
+        if let Err(e) = self.repository.api_keys_record_usage(api_key.id, scope, records).await {
+            tracing::warn!("Failed to record use of API key {}: {}", api_key.id, e);
+        }
+        Ok(api_key)
+    }
+}
+
+fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    format!(
+        "{}{}",
+        API_KEY_PREFIX,
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+    )
+}
+
+fn validate_name(name: Option<&str>) -> AppResult<()> {
+    let Some(name) = name.map(str::trim) else {
+        return Ok(());
+    };
+    if name.is_empty() || name.chars().count() > 100 {
+        return Err(AppError::Validation(
+            "name must be between 1 and 100 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// At least one scope, all of them known
+fn validate_scopes(scopes: &[String]) -> AppResult<()> {
+    if scopes.is_empty() {
+        return Err(AppError::Validation("scopes must list at least one scope".to_string()));
+    }
+    if let Some(bad) = scopes.iter().find(|s| !API_KEY_SCOPES.contains(&s.as_str())) {
+        return Err(AppError::Validation(format!(
+            "Unknown scope '{}' (known scopes: {})",
+            bad,
+            API_KEY_SCOPES.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::api_key::SCOPE_OPAC_AVAILABILITY;
+
+    #[test]
+    fn keys_are_prefixed_and_scopes_checked() {
+        let token = generate_token();
+        assert!(token.starts_with(API_KEY_PREFIX));
+        assert_ne!(token, generate_token());
+
+        assert!(validate_scopes(&[]).is_err());
+        assert!(validate_scopes(&["opac.search".to_string()]).is_err());
+        assert!(validate_scopes(&[SCOPE_OPAC_AVAILABILITY.to_string()]).is_ok());
+    }
+}
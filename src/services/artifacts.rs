@@ -0,0 +1,414 @@
+//! Artifacts: generated files (exports, import reports) kept in a blob store for a limited time
+//!
+//! Producers write through an [`ArtifactWriter`], so large files go straight to the store instead
+//! of being built in memory. Downloads go through HMAC-signed URLs that expire on their own,
+//! independently of the artifact TTL (see `[artifacts]` in the configuration).
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufWriter};
+
+use crate::{
+    config::ArtifactsConfig,
+    error::{AppError, AppResult},
+    models::artifact::{Artifact, ArtifactKind, ArtifactLink, NewArtifact},
+    repository::ArtifactsRepository,
+};
+
+/// Expired artifacts removed per cleanup query
+const CLEANUP_BATCH: i64 = 200;
+
+pub type BlobReader = Box<dyn AsyncRead + Send + Unpin>;
+pub type BlobWriter = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// Storage backend for artifact contents, addressed by opaque keys.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Create (or truncate) the blob `key` and return a writer to it.
+    async fn writer(&self, key: &str) -> AppResult<BlobWriter>;
+    async fn reader(&self, key: &str) -> AppResult<BlobReader>;
+    /// Remove the blob; a missing blob is not an error.
+    async fn delete(&self, key: &str) -> AppResult<()>;
+}
+
+/// One file per blob under a local directory.
+pub struct FilesystemBlobStore {
+    root: PathBuf,
+}
+
+impl FilesystemBlobStore {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Keys are generated by [`ArtifactsService`]; refuse anything that could leave `root`.
+    fn path(&self, key: &str) -> AppResult<PathBuf> {
+        if key.is_empty()
+            || key.starts_with('.')
+            || !key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            return Err(AppError::Internal(format!("Invalid blob key: {}", key)));
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl BlobStore for FilesystemBlobStore {
+    async fn writer(&self, key: &str) -> AppResult<BlobWriter> {
+        let path = self.path(key)?;
+        tokio::fs::create_dir_all(&self.root)
+            .await
+            .map_err(|e| AppError::Internal(format!("Cannot create {}: {}", self.root.display(), e)))?;
+        let file = tokio::fs::File::create(&path)
+            .await
+            .map_err(|e| AppError::Internal(format!("Cannot create blob {}: {}", key, e)))?;
+        Ok(Box::new(file))
+    }
+
+    async fn reader(&self, key: &str) -> AppResult<BlobReader> {
+        let path = self.path(key)?;
+        match tokio::fs::File::open(&path).await {
+            Ok(file) => Ok(Box::new(file)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(AppError::Gone("Artifact content is no longer available".to_string()))
+            }
+            Err(e) => Err(AppError::Internal(format!("Cannot open blob {}: {}", key, e))),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        let path = self.path(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::Internal(format!("Cannot delete blob {}: {}", key, e))),
+        }
+    }
+}
+
+/// Build the blob store selected by `[artifacts] backend`.
+pub fn blob_store(config: &ArtifactsConfig) -> AppResult<Arc<dyn BlobStore>> {
+    match config.backend.as_str() {
+        "filesystem" => Ok(Arc::new(FilesystemBlobStore::new(&config.directory))),
+        other => Err(AppError::Internal(format!(
+            "Unknown artifacts backend '{}' (expected \"filesystem\")",
+            other
+        ))),
+    }
+}
+
+#[derive(Clone)]
+pub struct ArtifactsService {
+    repository: Arc<dyn ArtifactsRepository>,
+    store: Arc<dyn BlobStore>,
+    config: ArtifactsConfig,
+    signing_key: Arc<str>,
+}
+
+impl ArtifactsService {
+    pub fn new(
+        repository: Arc<dyn ArtifactsRepository>,
+        store: Arc<dyn BlobStore>,
+        config: ArtifactsConfig,
+        signing_key: &str,
+    ) -> Self {
+        Self {
+            repository,
+            store,
+            config,
+            signing_key: Arc::from(signing_key),
+        }
+    }
+
+    /// Start a new artifact; nothing is recorded until [`ArtifactWriter::finish`].
+    #[tracing::instrument(skip(self), err)]
+    pub async fn writer(
+        &self,
+        kind: ArtifactKind,
+        filename: &str,
+        content_type: &str,
+        created_by: Option<i64>,
+    ) -> AppResult<ArtifactWriter> {
+        let storage_key = format!("{}-{}", kind.as_str(), uuid::Uuid::new_v4().simple());
+        let inner = self.store.writer(&storage_key).await?;
+        Ok(ArtifactWriter {
+            service: self.clone(),
+            inner: BufWriter::new(inner),
+            kind,
+            filename: filename.to_string(),
+            content_type: content_type.to_string(),
+            created_by,
+            storage_key,
+            size: 0,
+            expires_at: None,
+            finished: false,
+        })
+    }
+
+    /// Store content that is already in memory (e.g. a file built by another service)
+    pub async fn store_bytes(
+        &self,
+        kind: ArtifactKind,
+        filename: &str,
+        content_type: &str,
+        created_by: Option<i64>,
+        bytes: &[u8],
+    ) -> AppResult<Artifact> {
+        let mut writer = self.writer(kind, filename, content_type, created_by).await?;
+        writer.write(bytes).await?;
+        writer.finish().await
+    }
+
+    pub async fn get(&self, id: i64) -> AppResult<Artifact> {
+        self.repository.artifacts_get(id).await
+    }
+
+    /// Unexpired artifacts of one user (all users when `created_by` is `None`)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self, created_by: Option<i64>) -> AppResult<Vec<Artifact>> {
+        self.repository.artifacts_list(created_by).await
+    }
+
+    /// Attach a fresh signed download URL under `api_root` (e.g. `https://library.example.org/api/v2`)
+    pub fn link(&self, artifact: Artifact, api_root: &str) -> ArtifactLink {
+        let url_expires_at = Utc::now() + Duration::minutes(self.config.url_ttl_minutes as i64);
+        let url_expires_at = url_expires_at.min(artifact.expires_at);
+        let expires = url_expires_at.timestamp();
+        let download_url = format!(
+            "{}/artifacts/{}?expires={}&signature={}",
+            api_root.trim_end_matches('/'),
+            artifact.id,
+            expires,
+            self.sign(artifact.id, expires)
+        );
+        ArtifactLink {
+            artifact,
+            download_url,
+            url_expires_at: Utc.timestamp_opt(expires, 0).single().unwrap_or(url_expires_at),
+        }
+    }
+
+    /// Open the content of an artifact (counts as a download)
+    #[tracing::instrument(skip(self, artifact), fields(id = artifact.id), err)]
+    pub async fn open(&self, artifact: &Artifact) -> AppResult<BlobReader> {
+        if artifact.expires_at <= Utc::now() {
+            return Err(AppError::Gone(format!("Artifact {} has expired", artifact.id)));
+        }
+        let reader = self.store.reader(&artifact.storage_key).await?;
+        if let Err(e) = self.repository.artifacts_record_download(artifact.id).await {
+            tracing::warn!("Failed to count download of artifact {}: {}", artifact.id, e);
+        }
+        Ok(reader)
+    }
+
+    /// Check a signed URL and open the artifact it points to
+    #[tracing::instrument(skip(self, signature), err)]
+    pub async fn open_signed(&self, id: i64, expires: i64, signature: &str) -> AppResult<(Artifact, BlobReader)> {
+        if !self.verify(id, expires, signature) {
+            return Err(AppError::Authorization("Invalid download signature".to_string()));
+        }
+        if expires <= Utc::now().timestamp() {
+            return Err(AppError::Gone("Download link has expired".to_string()));
+        }
+        let artifact = self.repository.artifacts_get(id).await?;
+        let reader = self.open(&artifact).await?;
+        Ok((artifact, reader))
+    }
+
+    /// Delete an artifact and its content
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete(&self, id: i64) -> AppResult<()> {
+        let artifact = self.repository.artifacts_get(id).await?;
+        self.store.delete(&artifact.storage_key).await?;
+        self.repository.artifacts_delete(id).await
+    }
+
+    /// Remove expired artifacts (row and content); returns how many were removed
+    #[tracing::instrument(skip(self), err)]
+    pub async fn cleanup_expired(&self) -> AppResult<u64> {
+        let mut removed = 0;
+        loop {
+            let expired = self.repository.artifacts_list_expired(CLEANUP_BATCH).await?;
+            if expired.is_empty() {
+                return Ok(removed);
+            }
+            for artifact in &expired {
+                self.store.delete(&artifact.storage_key).await?;
+                self.repository.artifacts_delete(artifact.id).await?;
+                removed += 1;
+            }
+        }
+    }
+
+    fn mac(&self, id: i64, expires: i64) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.signing_key.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(format!("artifact:{}:{}", id, expires).as_bytes());
+        mac
+    }
+
+    fn sign(&self, id: i64, expires: i64) -> String {
+        hex::encode(self.mac(id, expires).finalize().into_bytes())
+    }
+
+    fn verify(&self, id: i64, expires: i64, signature: &str) -> bool {
+        hex::decode(signature)
+            .map(|sig| self.mac(id, expires).verify_slice(&sig).is_ok())
+            .unwrap_or(false)
+    }
+}
+
+/// Streams the content of a new artifact into the blob store.
+///
+/// Dropping the writer without calling [`finish`](Self::finish) removes the partial blob.
+pub struct ArtifactWriter {
+    service: ArtifactsService,
+    inner: BufWriter<BlobWriter>,
+    kind: ArtifactKind,
+    filename: String,
+    content_type: String,
+    created_by: Option<i64>,
+    storage_key: String,
+    size: u64,
+    /// Overrides `artifacts.ttl_hours`
+    expires_at: Option<DateTime<Utc>>,
+    finished: bool,
+}
+
+impl ArtifactWriter {
+    pub async fn write(&mut self, bytes: &[u8]) -> AppResult<()> {
+        self.inner
+            .write_all(bytes)
+            .await
+            .map_err(|e| AppError::Internal(format!("Cannot write artifact: {}", e)))?;
+        self.size += bytes.len() as u64;
+        Ok(())
+    }
+
+    pub async fn write_str(&mut self, s: &str) -> AppResult<()> {
+        self.write(s.as_bytes()).await
+    }
+
+    /// Keep the artifact until `expires_at` instead of `artifacts.ttl_hours` after creation
+    pub fn expire_at(&mut self, expires_at: DateTime<Utc>) {
+        self.expires_at = Some(expires_at);
+    }
+
+    /// Flush the content and record the artifact
+    pub async fn finish(mut self) -> AppResult<Artifact> {
+        self.inner
+            .shutdown()
+            .await
+            .map_err(|e| AppError::Internal(format!("Cannot write artifact: {}", e)))?;
+        let data = NewArtifact {
+            kind: self.kind,
+            filename: self.filename.clone(),
+            content_type: self.content_type.clone(),
+            size_bytes: self.size as i64,
+            storage_key: self.storage_key.clone(),
+            created_by: self.created_by,
+            expires_at: self
+                .expires_at
+                .unwrap_or_else(|| Utc::now() + Duration::hours(self.service.config.ttl_hours as i64)),
+        };
+        let artifact = self.service.repository.artifacts_create(&data).await?;
+        self.finished = true;
+        Ok(artifact)
+    }
+}
+
+impl Drop for ArtifactWriter {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let store = self.service.store.clone();
+        let key = std::mem::take(&mut self.storage_key);
+        tokio::spawn(async move {
+            if let Err(e) = store.delete(&key).await {
+                tracing::warn!("Failed to remove unfinished artifact {}: {}", key, e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::artifacts::MockArtifactsRepository;
+
+    fn service(repository: MockArtifactsRepository, dir: &Path) -> ArtifactsService {
+        ArtifactsService::new(
+            Arc::new(repository),
+            Arc::new(FilesystemBlobStore::new(dir)),
+            ArtifactsConfig::default(),
+            "test-secret",
+        )
+    }
+
+    #[test]
+    fn signatures() {
+        let dir = std::env::temp_dir();
+        let svc = service(MockArtifactsRepository::new(), &dir);
+        let sig = svc.sign(42, 1_900_000_000);
+        assert!(svc.verify(42, 1_900_000_000, &sig));
+        assert!(!svc.verify(43, 1_900_000_000, &sig));
+        assert!(!svc.verify(42, 1_900_000_001, &sig));
+        assert!(!svc.verify(42, 1_900_000_000, "not-hex"));
+
+        let store = FilesystemBlobStore::new(&dir);
+        assert!(store.path("catalog_export-0a1b").is_ok());
+        assert!(store.path("../etc/passwd").is_err());
+        assert!(store.path("a/b").is_err());
+    }
+
+    #[tokio::test]
+    async fn writer_streams_to_store_and_records_size() {
+        let dir = std::env::temp_dir().join(format!("elidune-artifacts-{}", uuid::Uuid::new_v4().simple()));
+        let mut repo = MockArtifactsRepository::new();
+        repo.expect_artifacts_create().returning(|data| {
+            Ok(Artifact {
+                id: 1,
+                kind: data.kind,
+                filename: data.filename.clone(),
+                content_type: data.content_type.clone(),
+                size_bytes: data.size_bytes,
+                storage_key: data.storage_key.clone(),
+                created_by: data.created_by,
+                created_at: Utc::now(),
+                expires_at: data.expires_at,
+                download_count: 0,
+                last_downloaded_at: None,
+            })
+        });
+        let svc = service(repo, &dir);
+
+        let mut writer = svc
+            .writer(ArtifactKind::CatalogExport, "catalog.csv", "text/csv", Some(7))
+            .await
+            .unwrap();
+        writer.write_str("id,title\n").await.unwrap();
+        writer.write_str("1,Dune\n").await.unwrap();
+        let artifact = writer.finish().await.unwrap();
+
+        assert_eq!(artifact.size_bytes, 16);
+        let content = tokio::fs::read(dir.join(&artifact.storage_key)).await.unwrap();
+        assert_eq!(content, b"id,title\n1,Dune\n");
+
+        let link = svc.link(artifact, "https://library.example.org/api/v2/");
+        assert!(link
+            .download_url
+            .starts_with("https://library.example.org/api/v2/artifacts/1?expires="));
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+}
@@ -87,30 +87,146 @@ pub mod event {
     // Users
     pub const USER_CREATED: &str = "user.created";
     pub const USER_UPDATED: &str = "user.updated";
+    /// Delete request: `scheduledAt` is set while the account stays restorable
     pub const USER_DELETED: &str = "user.deleted";
+    pub const USER_RESTORED: &str = "user.restored";
+    /// Account anonymized by the retention job once its grace period lapsed
+    pub const USER_ANONYMIZED: &str = "user.anonymized";
+    /// Duplicate account merged into the surviving one (entity = survivor)
+    pub const USER_MERGED: &str = "user.merged";
+    /// Membership expiry campaign run (entity = none; payload = filters and counts)
+    pub const USER_EXPIRY_CAMPAIGN: &str = "user.expiry_campaign";
     pub const USER_ACCOUNT_TYPE_CHANGED: &str = "user.account_type_changed";
+    pub const USER_PIN_SET: &str = "user.pin_set";
+    /// Admin started acting as a patron (`userId` = admin, `entityId` = patron)
+    pub const USER_IMPERSONATION_STARTED: &str = "user.impersonation_started";
+    /// Request made with an impersonation token (`userId` = admin, `entityId` = patron)
+    pub const USER_IMPERSONATED_REQUEST: &str = "user.impersonated_request";
+    pub const USER_MESSAGE_CREATED: &str = "user_message.created";
+    pub const USER_MESSAGE_DELETED: &str = "user_message.deleted";
+    pub const USER_MESSAGE_ACKNOWLEDGED: &str = "user_message.acknowledged";
+    pub const USER_CAMPAIGNS_OPT_IN_UPDATED: &str = "user.campaigns_opt_in_updated";
+    /// Bounce and complaint counters of the user's address cleared (emails sent again)
+    pub const USER_EMAIL_HEALTH_RESET: &str = "user.email_health_reset";
     pub const ACCOUNT_TYPE_UPDATED: &str = "account_type.updated";
 
     // Biblios
     pub const BIBLIO_CREATED: &str = "biblio.created";
     pub const BIBLIO_UPDATED: &str = "biblio.updated";
     pub const BIBLIO_DELETED: &str = "biblio.deleted";
+    /// Edit lock of another cataloger removed by an administrator
+    pub const BIBLIO_LOCK_FORCE_RELEASED: &str = "biblio.lock_force_released";
 
     // Items
     pub const ITEM_CREATED: &str = "item.created";
     pub const ITEM_UPDATED: &str = "item.updated";
     pub const ITEM_DELETED: &str = "item.deleted";
+    pub const ITEM_STATE_CHANGED: &str = "item.state_changed";
+    pub const ITEM_CALL_NUMBER_RECALCULATED: &str = "item.call_number_recalculated";
+    pub const ITEM_ROUTED: &str = "item.routed";
+    pub const ITEM_RECEIVED: &str = "item.received";
+
+    // Item state taxonomy
+    pub const ITEM_STATE_CREATED: &str = "item_state.created";
+    pub const ITEM_STATE_UPDATED: &str = "item_state.updated";
+    pub const ITEM_STATE_DELETED: &str = "item_state.deleted";
+
+    // Media type taxonomy
+    pub const MEDIA_TYPE_CREATED: &str = "media_type.created";
+    pub const MEDIA_TYPE_UPDATED: &str = "media_type.updated";
+    pub const MEDIA_TYPE_DELETED: &str = "media_type.deleted";
+
+    // Relator vocabulary (author roles)
+    pub const RELATOR_CREATED: &str = "relator.created";
+    pub const RELATOR_UPDATED: &str = "relator.updated";
+    pub const RELATOR_DELETED: &str = "relator.deleted";
+
+    // Shelving map
+    pub const LOCATION_CREATED: &str = "location.created";
+    pub const LOCATION_UPDATED: &str = "location.updated";
+    pub const LOCATION_DELETED: &str = "location.deleted";
+    pub const LOCATIONS_MAPPED: &str = "locations.mapped";
+
+    // Cataloging templates
+    pub const ITEM_TEMPLATE_CREATED: &str = "item_template.created";
+    pub const ITEM_TEMPLATE_UPDATED: &str = "item_template.updated";
+    pub const ITEM_TEMPLATE_DELETED: &str = "item_template.deleted";
+
+    // Translated labels
+    pub const LABEL_UPDATED: &str = "label.updated";
+    pub const LABEL_DELETED: &str = "label.deleted";
+
+    // Genre and subject headings
+    pub const GENRE_CREATED: &str = "genre.created";
+    pub const GENRE_UPDATED: &str = "genre.updated";
+    pub const GENRE_DELETED: &str = "genre.deleted";
+    pub const GENRE_MERGED: &str = "genre.merged";
+    pub const SUBJECT_CREATED: &str = "subject.created";
+    pub const SUBJECT_UPDATED: &str = "subject.updated";
+    pub const SUBJECT_DELETED: &str = "subject.deleted";
+    pub const SUBJECT_MERGED: &str = "subject.merged";
+    pub const BIBLIO_HEADINGS_UPDATED: &str = "biblio.headings_updated";
+
+    // Authors
+    pub const AUTHOR_UPDATED: &str = "author.updated";
+    pub const AUTHORS_NORMALIZED: &str = "authors.normalized";
 
     // Loans
     pub const LOAN_CREATED: &str = "loan.created";
+    /// Checkout made despite checkout blocks (`force=true` with `circulation_override_rights`)
+    pub const LOAN_BLOCKS_OVERRIDDEN: &str = "loan.blocks_overridden";
     pub const LOAN_RETURNED: &str = "loan.returned";
     pub const LOAN_RENEWED: &str = "loan.renewed";
+    pub const LOAN_BATCH_CREATED: &str = "loan_batch.created";
+    pub const LOAN_BATCH_EXTENDED: &str = "loan_batch.extended";
+    pub const LOAN_BATCH_RETURNED: &str = "loan_batch.returned";
+
+    // Item bundles
+    pub const BUNDLE_CREATED: &str = "item_bundle.created";
+    pub const BUNDLE_UPDATED: &str = "item_bundle.updated";
+    pub const BUNDLE_DELETED: &str = "item_bundle.deleted";
+    pub const BUNDLE_RETURNED: &str = "item_bundle.returned";
+
+    // Deposit collections
+    pub const DEPOSIT_CREATED: &str = "deposit.created";
+    pub const DEPOSIT_UPDATED: &str = "deposit.updated";
+    pub const DEPOSIT_DELETED: &str = "deposit.deleted";
+    pub const DEPOSIT_CLOSED: &str = "deposit.closed";
 
     // Sources
     pub const SOURCE_CREATED: &str = "source.created";
     pub const SOURCE_UPDATED: &str = "source.updated";
     pub const SOURCE_ARCHIVED: &str = "source.archived";
     pub const SOURCE_MERGED: &str = "source.merged";
+    pub const SOURCE_DELETED: &str = "source.deleted";
+
+    // Vendors and purchase orders
+    pub const VENDOR_CREATED: &str = "vendor.created";
+    pub const VENDOR_UPDATED: &str = "vendor.updated";
+    pub const VENDOR_DELETED: &str = "vendor.deleted";
+    pub const PURCHASE_ORDER_CREATED: &str = "purchase_order.created";
+    pub const PURCHASE_ORDER_UPDATED: &str = "purchase_order.updated";
+    pub const PURCHASE_ORDER_DELETED: &str = "purchase_order.deleted";
+
+    // Donations
+    pub const DONATION_CREATED: &str = "donation.created";
+    pub const DONATION_UPDATED: &str = "donation.updated";
+    pub const DONATION_DELETED: &str = "donation.deleted";
+    pub const DONATION_ITEM_TRIAGED: &str = "donation.item_triaged";
+
+    // Purchase suggestions
+    pub const PURCHASE_SUGGESTION_CREATED: &str = "purchase_suggestion.created";
+    pub const PURCHASE_SUGGESTION_TRIAGED: &str = "purchase_suggestion.triaged";
+
+    // Reading programs
+    pub const READING_PROGRAM_CREATED: &str = "reading_program.created";
+    pub const READING_PROGRAM_UPDATED: &str = "reading_program.updated";
+    pub const READING_PROGRAM_DELETED: &str = "reading_program.deleted";
+    pub const READING_PROGRAM_ENROLLED: &str = "reading_program.enrolled";
+    pub const READING_PROGRAM_UNENROLLED: &str = "reading_program.unenrolled";
+    pub const READING_ENTRY_CREATED: &str = "reading_program.entry_created";
+    pub const READING_ENTRY_DELETED: &str = "reading_program.entry_deleted";
+    pub const READING_PROGRAM_LOANS_SYNCED: &str = "reading_program.loans_synced";
 
     // Equipment
     pub const EQUIPMENT_CREATED: &str = "equipment.created";
@@ -122,6 +238,10 @@ pub mod event {
     pub const EVENT_UPDATED: &str = "event.updated";
     pub const EVENT_DELETED: &str = "event.deleted";
     pub const EVENT_ANNOUNCEMENT_SENT: &str = "event.announcement_sent";
+    pub const EVENT_PUBLISHED: &str = "event.published";
+    pub const EVENT_CANCELLED: &str = "event.cancelled";
+    pub const EVENT_REGISTERED: &str = "event.registered";
+    pub const EVENT_UNREGISTERED: &str = "event.unregistered";
 
     // Public types
     pub const PUBLIC_TYPE_CREATED: &str = "public_type.created";
@@ -137,6 +257,17 @@ pub mod event {
     pub const SCHEDULE_SLOT_DELETED: &str = "schedule.slot_deleted";
     pub const SCHEDULE_CLOSURE_CREATED: &str = "schedule.closure_created";
     pub const SCHEDULE_CLOSURE_DELETED: &str = "schedule.closure_deleted";
+    pub const SCHEDULE_CLOSURE_DUE_DATES_EXTENDED: &str = "schedule.closure_due_dates_extended";
+
+    // Staffing (shifts, assignments, absences)
+    pub const SHIFT_TEMPLATE_CREATED: &str = "staffing.shift_template_created";
+    pub const SHIFT_TEMPLATE_UPDATED: &str = "staffing.shift_template_updated";
+    pub const SHIFT_TEMPLATE_DELETED: &str = "staffing.shift_template_deleted";
+    pub const SHIFT_ASSIGNED: &str = "staffing.shift_assigned";
+    pub const SHIFT_UNASSIGNED: &str = "staffing.shift_unassigned";
+    pub const STAFF_ABSENCE_CREATED: &str = "staffing.absence_created";
+    pub const STAFF_ABSENCE_DELETED: &str = "staffing.absence_deleted";
+    pub const STAFF_CALENDAR_TOKEN_REGENERATED: &str = "staffing.calendar_token_regenerated";
 
     // Visitor counts
     pub const VISITOR_COUNT_CREATED: &str = "visitor_count.created";
@@ -152,11 +283,23 @@ pub mod event {
 
     // Email
     pub const EMAIL_OVERDUE_REMINDER_SENT: &str = "email.overdue_reminder_sent";
+    pub const EMAIL_DUE_DATE_EXTENDED_SENT: &str = "email.due_date_extended_sent";
+    pub const EMAIL_DUE_SOON_REMINDER_SENT: &str = "email.due_soon_reminder_sent";
     pub const EMAIL_2FA_CODE_SENT: &str = "email.2fa_code_sent";
     pub const EMAIL_RECOVERY_CODE_SENT: &str = "email.recovery_code_sent";
     pub const EMAIL_PASSWORD_RESET_SENT: &str = "email.password_reset_sent";
     pub const EMAIL_TEST_SENT: &str = "email.test_sent";
     pub const EMAIL_TEMPLATE_UPDATED: &str = "email_template.updated";
+    /// Mail provider webhook reported bounces that made addresses undeliverable
+    pub const EMAIL_MARKED_UNDELIVERABLE: &str = "email.marked_undeliverable";
+
+    // Email campaigns
+    pub const CAMPAIGN_CREATED: &str = "campaign.created";
+    pub const CAMPAIGN_UPDATED: &str = "campaign.updated";
+    pub const CAMPAIGN_DELETED: &str = "campaign.deleted";
+    /// Recipients queued (or an interrupted send resumed)
+    pub const CAMPAIGN_SEND_STARTED: &str = "campaign.send_started";
+    pub const CAMPAIGN_BOUNCES_RECORDED: &str = "campaign.bounces_recorded";
 
     // Auth
     pub const AUTH_LOGIN_SUCCESS: &str = "auth.login_success";
@@ -167,6 +310,28 @@ pub mod event {
     pub const AUTH_PASSWORD_CHANGED: &str = "auth.password_changed";
     pub const AUTH_2FA_ENABLED: &str = "auth.2fa_enabled";
     pub const AUTH_2FA_DISABLED: &str = "auth.2fa_disabled";
+    pub const AUTH_RECOVERY_CODES_REGENERATED: &str = "auth.recovery_codes_regenerated";
+    pub const AUTH_BARCODE_LOGIN_SUCCESS: &str = "auth.barcode_login_success";
+    pub const AUTH_BARCODE_LOGIN_FAILED: &str = "auth.barcode_login_failed";
+    pub const AUTH_PIN_CHANGED: &str = "auth.pin_changed";
+
+    // Kiosk terminals (entity `kiosk`)
+    pub const KIOSK_CREATED: &str = "kiosk.created";
+    pub const KIOSK_UPDATED: &str = "kiosk.updated";
+    pub const KIOSK_DELETED: &str = "kiosk.deleted";
+    pub const KIOSK_TOKEN_REGENERATED: &str = "kiosk.token_regenerated";
+    pub const KIOSK_ACCESS_DENIED: &str = "kiosk.access_denied";
+    pub const KIOSK_PATRON_LOGIN: &str = "kiosk.patron_login";
+
+    // Partner API keys (entity `api_key`)
+    pub const API_KEY_CREATED: &str = "api_key.created";
+    pub const API_KEY_UPDATED: &str = "api_key.updated";
+    pub const API_KEY_DELETED: &str = "api_key.deleted";
+    pub const API_KEY_TOKEN_REGENERATED: &str = "api_key.token_regenerated";
+    pub const API_KEY_ACCESS_DENIED: &str = "api_key.access_denied";
+
+    // Catalog snapshots (entity `snapshot`)
+    pub const SNAPSHOT_RESTORED: &str = "snapshot.restored";
 
     // Config
     pub const CONFIG_SECTION_UPDATED: &str = "config.section_updated";
@@ -175,16 +340,36 @@ pub mod event {
     // Import
     pub const IMPORT_MARC_BATCH: &str = "import.marc_batch";
     pub const IMPORT_Z3950_RECORD: &str = "import.z3950_record";
+    pub const HARVEST_SOURCE_CREATED: &str = "harvest.source_created";
+    pub const HARVEST_SOURCE_UPDATED: &str = "harvest.source_updated";
+    pub const HARVEST_SOURCE_DELETED: &str = "harvest.source_deleted";
+    pub const HARVEST_RUN_COMPLETED: &str = "harvest.run_completed";
+    pub const HARVEST_RUN_FAILED: &str = "harvest.run_failed";
 
     // Holds
     pub const HOLD_CREATED: &str = "hold.created";
     pub const HOLD_CANCELLED: &str = "hold.cancelled";
     pub const HOLD_FULFILLED: &str = "hold.fulfilled";
+    /// Copy placed in a pickup locker compartment
+    pub const HOLD_LOCKER_ASSIGNED: &str = "hold.locker_assigned";
+    /// Locker vendor webhook: compartment opened (payload tells whether the loan was created)
+    pub const HOLD_PICKED_UP: &str = "hold.picked_up";
+    /// Pickup window of a locker lapsed (vendor webhook or nightly expiry)
+    pub const HOLD_LOCKER_EXPIRED: &str = "hold.locker_expired";
 
     // Fines
     pub const FINE_CREATED: &str = "fine.created";
     pub const FINE_PAID: &str = "fine.paid";
     pub const FINE_WAIVED: &str = "fine.waived";
+    /// Amnesty campaign waiving every unpaid fine issued in a date range
+    pub const FINES_AMNESTY: &str = "fines.amnesty";
+
+    // Cash register
+    pub const PAYMENT_RECORDED: &str = "payment.recorded";
+    pub const PAYMENT_REFUNDED: &str = "payment.refunded";
+    /// Month exported to accounting (and locked on first export)
+    pub const PAYMENT_PERIOD_EXPORTED: &str = "payment.period_exported";
+    pub const PAYMENT_PERIOD_UNLOCKED: &str = "payment.period_unlocked";
 
     // Inventory
     pub const INVENTORY_SESSION_CREATED: &str = "inventory.session_created";
@@ -195,9 +380,31 @@ pub mod event {
     pub const MAINTENANCE_DATABASE_DUMP: &str = "maintenance.database_dump";
     pub const MAINTENANCE_DATABASE_RESTORE: &str = "maintenance.database_restore";
 
+    // Database backups (entity `backup`)
+    pub const BACKUP_COMPLETED: &str = "backup.completed";
+    pub const BACKUP_FAILED: &str = "backup.failed";
+
+    // End-of-day job (entity `eod_run`)
+    pub const EOD_RUN_COMPLETED: &str = "eod.run_completed";
+    pub const EOD_RUN_FAILED: &str = "eod.run_failed";
+
+    // Re-engagement emails to inactive members
+    pub const REENGAGEMENT_RUN_COMPLETED: &str = "reengagement.run_completed";
+
+    // OPAC sitemap
+    pub const SITEMAP_GENERATED: &str = "sitemap.generated";
+
+    // Data warehouse exports
+    pub const WAREHOUSE_TARGET_CREATED: &str = "warehouse.target_created";
+    pub const WAREHOUSE_TARGET_UPDATED: &str = "warehouse.target_updated";
+    pub const WAREHOUSE_TARGET_DELETED: &str = "warehouse.target_deleted";
+    pub const WAREHOUSE_RUN_COMPLETED: &str = "warehouse.run_completed";
+    pub const WAREHOUSE_RUN_FAILED: &str = "warehouse.run_failed";
+
     // System
     pub const SYSTEM_STARTUP: &str = "system.startup";
     pub const SYSTEM_REMINDERS_BATCH_COMPLETED: &str = "system.reminders_batch_completed";
+    pub const SYSTEM_DUE_SOON_BATCH_COMPLETED: &str = "system.due_soon_batch_completed";
     pub const SYSTEM_AUDIT_CLEANUP: &str = "system.audit_cleanup";
 }
 
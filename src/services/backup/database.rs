@@ -0,0 +1,235 @@
+//! Catalog queries and the COPY load used by backups and restores, on a single connection
+
+use std::{collections::BTreeMap, io::BufRead};
+
+use sqlx::{migrate::Migrate, PgConnection};
+
+use super::format::{self, quote_ident};
+use crate::{
+    error::{AppError, AppResult},
+    models::backup::{BackupManifest, BackupMigration, BackupTable},
+};
+
+/// Table of sqlx, recreated by the migrations rather than copied
+const MIGRATIONS_TABLE: &str = "_sqlx_migrations";
+/// Rows sent to the server per COPY message during a restore
+const LOAD_CHUNK_BYTES: usize = 1 << 20;
+
+/// Migrations compiled into this server
+pub fn server_migrations() -> Vec<BackupMigration> {
+    crate::MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| BackupMigration {
+            version: m.version,
+            description: m.description.to_string(),
+            checksum: hex::encode(&m.checksum),
+        })
+        .collect()
+}
+
+/// Migrations applied to the database (none when it was never migrated)
+pub async fn applied_migrations(conn: &mut PgConnection) -> AppResult<Vec<BackupMigration>> {
+    let exists: bool = sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+        .bind(format!("public.{}", MIGRATIONS_TABLE))
+        .fetch_one(&mut *conn)
+        .await?;
+    if !exists {
+        return Ok(Vec::new());
+    }
+    let rows: Vec<(i64, String, Vec<u8>)> = sqlx::query_as(
+        "SELECT version, description, checksum FROM _sqlx_migrations WHERE success ORDER BY version",
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(version, description, checksum)| BackupMigration {
+            version,
+            description,
+            checksum: hex::encode(checksum),
+        })
+        .collect())
+}
+
+/// Apply the migrations of this server up to `version` included (restore into an empty database)
+pub async fn migrate_to(conn: &mut PgConnection, version: i64) -> AppResult<()> {
+    conn.ensure_migrations_table()
+        .await
+        .map_err(|e| AppError::Internal(format!("Cannot create the migrations table: {}", e)))?;
+    for migration in crate::MIGRATOR.iter() {
+        if migration.migration_type.is_down_migration() || migration.version > version {
+            continue;
+        }
+        conn.apply(migration)
+            .await
+            .map_err(|e| AppError::Internal(format!("Migration {} failed: {}", migration.version, e)))?;
+    }
+    Ok(())
+}
+
+/// Tables of the `public` schema with their stored columns, parents before children
+pub async fn tables(conn: &mut PgConnection) -> AppResult<Vec<BackupTable>> {
+    let names: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT table_name::text FROM information_schema.tables
+        WHERE table_schema = 'public' AND table_type = 'BASE TABLE' AND table_name <> $1
+        "#,
+    )
+    .bind(MIGRATIONS_TABLE)
+    .fetch_all(&mut *conn)
+    .await?;
+    let edges: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT c.conrelid::regclass::text, c.confrelid::regclass::text
+        FROM pg_constraint c JOIN pg_namespace n ON n.oid = c.connamespace
+        WHERE c.contype = 'f' AND n.nspname = 'public'
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    let columns: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT table_name::text, column_name::text FROM information_schema.columns
+        WHERE table_schema = 'public' AND is_generated = 'NEVER'
+        ORDER BY table_name, ordinal_position
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut by_table: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (table, column) in columns {
+        by_table.entry(table).or_default().push(column);
+    }
+    Ok(format::load_order(&names, &edges)
+        .into_iter()
+        .map(|name| BackupTable {
+            columns: by_table.remove(&name).unwrap_or_default(),
+            name,
+        })
+        .collect())
+}
+
+/// Last value of every sequence of the `public` schema
+pub async fn sequences(conn: &mut PgConnection) -> AppResult<BTreeMap<String, Option<i64>>> {
+    let rows: Vec<(String, Option<i64>)> =
+        sqlx::query_as("SELECT sequencename::text, last_value FROM pg_sequences WHERE schemaname = 'public'")
+            .fetch_all(&mut *conn)
+            .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// `COPY … TO STDOUT` statement of a table
+pub fn copy_out_statement(table: &BackupTable) -> String {
+    format!("COPY {} ({}) TO STDOUT", quote_ident(&table.name), column_list(table))
+}
+
+fn column_list(table: &BackupTable) -> String {
+    table.columns.iter().map(|c| quote_ident(c)).collect::<Vec<_>>().join(", ")
+}
+
+/// Replace the content of every table of the backup with the rows read from `reader`
+/// (positioned after the manifest line). Runs on a connection inside a transaction; user
+/// triggers are disabled while loading so rows are stored exactly as they were.
+///
+/// Returns the rows loaded per table.
+pub async fn load<R: BufRead>(
+    conn: &mut PgConnection,
+    manifest: &BackupManifest,
+    reader: &mut R,
+) -> AppResult<Vec<(String, u64)>> {
+    let names: Vec<String> = manifest.tables.iter().map(|t| quote_ident(&t.name)).collect();
+    if !names.is_empty() {
+        sqlx::query(&format!("TRUNCATE {} RESTART IDENTITY CASCADE", names.join(", ")))
+            .execute(&mut *conn)
+            .await?;
+    }
+    for name in &names {
+        sqlx::query(&format!("ALTER TABLE {} DISABLE TRIGGER USER", name))
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    let mut loaded = Vec::with_capacity(manifest.tables.len());
+    let mut line = Vec::new();
+    for table in &manifest.tables {
+        line.clear();
+        read_line(reader, &mut line)?;
+        if format::parse_section_start(&line) != Some(table.name.as_str()) {
+            return Err(AppError::Validation(format!(
+                "Corrupted backup: rows of table {} expected",
+                table.name
+            )));
+        }
+
+        let mut copy = conn
+            .copy_in_raw(&format!(
+                "COPY {} ({}) FROM STDIN",
+                quote_ident(&table.name),
+                column_list(table)
+            ))
+            .await?;
+        let mut chunk = Vec::with_capacity(LOAD_CHUNK_BYTES);
+        let result: AppResult<()> = async {
+            loop {
+                line.clear();
+                if read_line(reader, &mut line)? == 0 {
+                    return Err(AppError::Validation(format!(
+                        "Truncated backup: rows of table {} are cut short",
+                        table.name
+                    )));
+                }
+                if line == format::SECTION_END {
+                    break;
+                }
+                chunk.extend_from_slice(&line);
+                if chunk.len() >= LOAD_CHUNK_BYTES {
+                    copy.send(std::mem::take(&mut chunk)).await?;
+                }
+            }
+            if !chunk.is_empty() {
+                copy.send(chunk).await?;
+            }
+            Ok(())
+        }
+        .await;
+        match result {
+            Ok(()) => {
+                let rows = copy.finish().await?;
+                loaded.push((table.name.clone(), rows));
+            }
+            Err(e) => {
+                let _ = copy.abort(e.to_string()).await;
+                return Err(e);
+            }
+        }
+    }
+
+    for name in &names {
+        sqlx::query(&format!("ALTER TABLE {} ENABLE TRIGGER USER", name))
+            .execute(&mut *conn)
+            .await?;
+    }
+    for (sequence, last_value) in &manifest.sequences {
+        let exists: bool = sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+            .bind(format!("public.{}", quote_ident(sequence)))
+            .fetch_one(&mut *conn)
+            .await?;
+        if !exists {
+            continue;
+        }
+        sqlx::query("SELECT setval($1::regclass, COALESCE($2, 1), $2 IS NOT NULL)")
+            .bind(format!("public.{}", quote_ident(sequence)))
+            .bind(*last_value)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(loaded)
+}
+
+fn read_line<R: BufRead>(reader: &mut R, line: &mut Vec<u8>) -> AppResult<usize> {
+    reader
+        .read_until(b'\n', line)
+        .map_err(|e| AppError::Validation(format!("Unreadable backup: {}", e)))
+}
@@ -0,0 +1,236 @@
+//! Backup file layout and the compatibility rules checked before a restore
+//!
+//! A backup is one gzip stream of text lines:
+//!
+//! ```text
+//! {"format":"elidune-backup/1","schemaVersion":63,"migrations":[…],"tables":[…],…}
+//! \copy account_types
+//! <COPY text rows>
+//! \.
+//! \copy users
+//! …
+//! ```
+//!
+//! The first line is the [`BackupManifest`]. Then come the tables in manifest order (parents
+//! before the tables referencing them), each as a `\copy <table>` line, the rows in PostgreSQL's
+//! COPY text format, and a `\.` line. Data lines cannot start with a single backslash (COPY
+//! escapes it), so the markers are unambiguous.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    error::{AppError, AppResult},
+    models::backup::{BackupManifest, BackupMigration, BACKUP_FORMAT},
+};
+
+const SECTION_START: &str = "\\copy ";
+pub const SECTION_END: &[u8] = b"\\.\n";
+
+/// Line opening the rows of `table`
+pub fn section_start(table: &str) -> String {
+    format!("{}{}\n", SECTION_START, table)
+}
+
+/// Table named by a `\copy <table>` line
+pub fn parse_section_start(line: &[u8]) -> Option<&str> {
+    std::str::from_utf8(line)
+        .ok()?
+        .strip_prefix(SECTION_START)
+        .map(|t| t.trim_end_matches('\n'))
+        .filter(|t| !t.is_empty())
+}
+
+pub fn manifest_line(manifest: &BackupManifest) -> AppResult<String> {
+    let json = serde_json::to_string(manifest)
+        .map_err(|e| AppError::Internal(format!("Backup manifest serialization failed: {}", e)))?;
+    Ok(format!("{}\n", json))
+}
+
+pub fn parse_manifest(line: &[u8]) -> AppResult<BackupManifest> {
+    let manifest: BackupManifest = serde_json::from_slice(line)
+        .map_err(|e| AppError::Validation(format!("Not an Elidune backup (unreadable manifest: {})", e)))?;
+    if manifest.format != BACKUP_FORMAT {
+        return Err(AppError::Validation(format!(
+            "Unsupported backup format '{}' (expected '{}')",
+            manifest.format, BACKUP_FORMAT
+        )));
+    }
+    Ok(manifest)
+}
+
+/// The schema of a backup must be one this server knows: every migration it went through must
+/// exist here, with the same script. A backup from a newer server is refused.
+pub fn check_compatibility(backup: &[BackupMigration], server: &[BackupMigration]) -> AppResult<()> {
+    let known: BTreeMap<i64, &BackupMigration> = server.iter().map(|m| (m.version, m)).collect();
+    for migration in backup {
+        match known.get(&migration.version) {
+            None => {
+                return Err(AppError::Conflict(format!(
+                    "The backup went through migration {} ({}) which this server does not know: \
+                     restore it with the server version that took it, or a newer one",
+                    migration.version, migration.description
+                )))
+            }
+            Some(ours) if ours.checksum != migration.checksum => {
+                return Err(AppError::Conflict(format!(
+                    "Migration {} ({}) of the backup differs from this server's: the backup comes \
+                     from a different build",
+                    migration.version, migration.description
+                )))
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// What the restore does with the database it writes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreTarget {
+    /// No migration applied yet: the schema is created up to the backup's version first
+    Empty,
+    /// Same migrations as the backup: every table is emptied, then loaded
+    SameSchema,
+}
+
+/// The target database must be empty or at the backup's exact schema version
+pub fn restore_target(applied: &[BackupMigration], backup: &[BackupMigration]) -> AppResult<RestoreTarget> {
+    if applied.is_empty() {
+        return Ok(RestoreTarget::Empty);
+    }
+    let applied_versions: Vec<i64> = applied.iter().map(|m| m.version).collect();
+    let backup_versions: Vec<i64> = backup.iter().map(|m| m.version).collect();
+    if applied_versions == backup_versions {
+        return Ok(RestoreTarget::SameSchema);
+    }
+    Err(AppError::Conflict(format!(
+        "The database is at migration {} and the backup at migration {}: restore into an empty \
+         database (the remaining migrations run after the restore)",
+        applied_versions.last().copied().unwrap_or_default(),
+        backup_versions.last().copied().unwrap_or_default()
+    )))
+}
+
+/// Tables sorted so that each comes after the tables its foreign keys reference (`edges` are
+/// `(table, referenced table)`). Ties are broken by name; tables caught in a reference cycle
+/// come last, by name.
+pub fn load_order(tables: &[String], edges: &[(String, String)]) -> Vec<String> {
+    let known: BTreeSet<&str> = tables.iter().map(String::as_str).collect();
+    let mut parents: BTreeMap<&str, BTreeSet<&str>> = known.iter().map(|t| (*t, BTreeSet::new())).collect();
+    for (table, referenced) in edges {
+        if table != referenced && known.contains(table.as_str()) && known.contains(referenced.as_str()) {
+            parents.entry(table.as_str()).or_default().insert(referenced.as_str());
+        }
+    }
+
+    let mut order = Vec::with_capacity(tables.len());
+    let mut placed: BTreeSet<&str> = BTreeSet::new();
+    loop {
+        let ready: Vec<&str> = parents
+            .iter()
+            .filter(|(table, deps)| !placed.contains(*table) && deps.iter().all(|d| placed.contains(d)))
+            .map(|(table, _)| *table)
+            .collect();
+        let Some(next) = ready.first() else { break };
+        placed.insert(next);
+        order.push(next.to_string());
+    }
+    order.extend(known.iter().filter(|t| !placed.contains(*t)).map(|t| t.to_string()));
+    order
+}
+
+/// `"name"` with embedded quotes doubled
+pub fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::backup::{BackupTable, RedisNotes};
+
+    fn migration(version: i64, checksum: &str) -> BackupMigration {
+        BackupMigration {
+            version,
+            description: format!("migration {}", version),
+            checksum: checksum.to_string(),
+        }
+    }
+
+    #[test]
+    fn newer_or_different_backups_are_refused() {
+        let server = vec![migration(1, "aa"), migration(2, "bb"), migration(3, "cc")];
+
+        assert!(check_compatibility(&server[..2], &server).is_ok());
+        let newer = vec![migration(1, "aa"), migration(2, "bb"), migration(3, "cc"), migration(4, "dd")];
+        assert!(matches!(check_compatibility(&newer, &server), Err(AppError::Conflict(_))));
+        let other_build = vec![migration(1, "aa"), migration(2, "XX")];
+        assert!(matches!(check_compatibility(&other_build, &server), Err(AppError::Conflict(_))));
+    }
+
+    #[test]
+    fn target_must_be_empty_or_at_the_backup_version() {
+        let backup = vec![migration(1, "aa"), migration(2, "bb")];
+        assert_eq!(restore_target(&[], &backup).unwrap(), RestoreTarget::Empty);
+        assert_eq!(restore_target(&backup, &backup).unwrap(), RestoreTarget::SameSchema);
+        let ahead = vec![migration(1, "aa"), migration(2, "bb"), migration(3, "cc")];
+        assert!(restore_target(&ahead, &backup).is_err());
+        assert!(restore_target(&backup[..1], &backup).is_err());
+    }
+
+    #[test]
+    fn parents_load_before_children() {
+        let tables: Vec<String> = ["loans", "users", "items", "biblios", "account_types", "a_cycle", "b_cycle"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        let edge = |a: &str, b: &str| (a.to_string(), b.to_string());
+        let edges = vec![
+            edge("loans", "users"),
+            edge("loans", "items"),
+            edge("items", "biblios"),
+            edge("users", "account_types"),
+            edge("users", "users"),
+            edge("a_cycle", "b_cycle"),
+            edge("b_cycle", "a_cycle"),
+        ];
+        assert_eq!(
+            load_order(&tables, &edges),
+            vec!["account_types", "biblios", "items", "users", "loans", "a_cycle", "b_cycle"]
+        );
+    }
+
+    #[test]
+    fn markers_and_manifest_round_trip() {
+        assert_eq!(parse_section_start(section_start("users").as_bytes()), Some("users"));
+        assert_eq!(parse_section_start(b"\\\\copy users\n"), None);
+        assert_eq!(parse_section_start(b"1\tJeanne\n"), None);
+
+        let manifest = BackupManifest {
+            format: BACKUP_FORMAT.to_string(),
+            server_version: "1.0.0".to_string(),
+            created_at: chrono::Utc::now(),
+            schema_version: 2,
+            migrations: vec![migration(1, "aa"), migration(2, "bb")],
+            tables: vec![BackupTable { name: "users".to_string(), columns: vec!["id".to_string()] }],
+            sequences: BTreeMap::from([("users_id_seq".to_string(), Some(42))]),
+            redis: RedisNotes::default(),
+        };
+        let line = manifest_line(&manifest).unwrap();
+        assert!(line.ends_with('\n') && !line.trim_end().contains('\n'));
+        let parsed = parse_manifest(line.trim_end().as_bytes()).unwrap();
+        assert_eq!(parsed.tables, manifest.tables);
+        assert_eq!(parsed.sequences, manifest.sequences);
+
+        let mut other = manifest;
+        other.format = "pg_dump".to_string();
+        assert!(parse_manifest(manifest_line(&other).unwrap().as_bytes()).is_err());
+        assert!(parse_manifest(b"-- PostgreSQL database dump").is_err());
+    }
+
+    #[test]
+    fn identifiers_are_quoted() {
+        assert_eq!(quote_ident("users"), "\"users\"");
+        assert_eq!(quote_ident("we\"ird"), "\"we\"\"ird\"");
+    }
+}
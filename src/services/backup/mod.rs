@@ -0,0 +1,368 @@
+//! Logical database backups
+//!
+//! `POST /admin/backup` takes a pg_dump-equivalent export without PostgreSQL client tools: every
+//! table of the `public` schema is copied (`COPY … TO STDOUT`) inside one read-only, repeatable
+//! read transaction, so the file is a consistent snapshot while the library keeps working. The
+//! file is gzip-compressed on the fly and streamed to the artifacts store, or uploaded to an S3
+//! bucket (see `[backup]` in the configuration). Its first line lists the applied migrations,
+//! which `elidune-server restore` checks before loading anything (see [`restore`]).
+//!
+//! Redis only holds transient state (sessions, 2FA challenges, edit locks, caches, task
+//! progress): it is described in the manifest, not backed up.
+
+mod database;
+pub mod format;
+pub mod restore;
+
+use std::{io::Write, sync::Arc};
+
+use chrono::{Duration, Utc};
+use flate2::{write::GzEncoder, Compression};
+use reqwest::Url;
+use sha2::{Digest, Sha256};
+use sqlx::{Connection, PgPool};
+use tokio::io::AsyncWriteExt;
+use tokio_stream::StreamExt;
+
+use crate::{
+    config::BackupConfig,
+    error::{AppError, AppResult},
+    models::{
+        artifact::ArtifactKind,
+        backup::{Backup, BackupDestination, BackupManifest, BackupStatus, RedisNotes, BACKUP_FORMAT},
+    },
+    repository::BackupsRepository,
+    services::{
+        artifacts::{ArtifactWriter, ArtifactsService},
+        audit::{self, AuditService},
+        redis::RedisService,
+        warehouse::upload::S3Bucket,
+    },
+};
+
+pub const BACKUP_CONTENT_TYPE: &str = "application/gzip";
+/// Backups returned by default / at most by the history
+const DEFAULT_LIST_LIMIT: i64 = 20;
+const MAX_LIST_LIMIT: i64 = 100;
+/// Compressed bytes buffered before they are handed to the destination
+const FLUSH_BYTES: usize = 256 * 1024;
+
+const REDIS_NOTE: &str = "Redis holds transient state only (sessions, 2FA challenges, trusted devices, \
+     edit locks, caches, task progress); it is not backed up. After a restore, users sign in again.";
+
+#[derive(Clone)]
+pub struct BackupService {
+    repository: Arc<dyn BackupsRepository>,
+    pool: PgPool,
+    artifacts: ArtifactsService,
+    redis: RedisService,
+    audit: AuditService,
+    config: BackupConfig,
+    http: reqwest::Client,
+}
+
+/// Where the compressed bytes go while the backup is written
+enum Sink {
+    Artifact(ArtifactWriter),
+    /// Local file uploaded to S3 once complete
+    File(tokio::fs::File),
+}
+
+impl Sink {
+    async fn write(&mut self, bytes: &[u8]) -> AppResult<()> {
+        match self {
+            Self::Artifact(writer) => writer.write(bytes).await,
+            Self::File(file) => file
+                .write_all(bytes)
+                .await
+                .map_err(|e| AppError::Internal(format!("Cannot write backup file: {}", e))),
+        }
+    }
+}
+
+/// Gzip stream fed by the dump; compressed bytes are passed on to the [`Sink`] as they come
+struct BackupWriter {
+    gzip: GzEncoder<Vec<u8>>,
+    sink: Sink,
+    sha256: Sha256,
+    size: u64,
+}
+
+impl BackupWriter {
+    fn new(sink: Sink) -> Self {
+        Self {
+            gzip: GzEncoder::new(Vec::new(), Compression::default()),
+            sink,
+            sha256: Sha256::new(),
+            size: 0,
+        }
+    }
+
+    async fn write(&mut self, bytes: &[u8]) -> AppResult<()> {
+        self.gzip
+            .write_all(bytes)
+            .map_err(|e| AppError::Internal(format!("Backup compression failed: {}", e)))?;
+        if self.gzip.get_ref().len() >= FLUSH_BYTES {
+            let compressed = std::mem::take(self.gzip.get_mut());
+            self.pass_on(&compressed).await?;
+        }
+        Ok(())
+    }
+
+    async fn pass_on(&mut self, compressed: &[u8]) -> AppResult<()> {
+        self.sha256.update(compressed);
+        self.size += compressed.len() as u64;
+        self.sink.write(compressed).await
+    }
+
+    /// End the gzip stream; returns the sink with the size and SHA-256 of what it received
+    async fn finish(mut self) -> AppResult<(Sink, u64, String)> {
+        let rest = std::mem::replace(&mut self.gzip, GzEncoder::new(Vec::new(), Compression::default()))
+            .finish()
+            .map_err(|e| AppError::Internal(format!("Backup compression failed: {}", e)))?;
+        self.pass_on(&rest).await?;
+        Ok((self.sink, self.size, hex::encode(self.sha256.finalize())))
+    }
+}
+
+/// What the dump wrote
+struct DumpSummary {
+    manifest: BackupManifest,
+    rows: u64,
+}
+
+impl BackupService {
+    pub fn new(
+        repository: Arc<dyn BackupsRepository>,
+        pool: PgPool,
+        artifacts: ArtifactsService,
+        redis: RedisService,
+        audit: AuditService,
+        config: BackupConfig,
+    ) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(3600))
+            .connect_timeout(std::time::Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+        Self {
+            repository,
+            pool,
+            artifacts,
+            redis,
+            audit,
+            config,
+            http,
+        }
+    }
+
+    /// Most recent backups
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self, limit: Option<i64>) -> AppResult<Vec<Backup>> {
+        let limit = limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, MAX_LIST_LIMIT);
+        self.repository.backups_list(limit).await
+    }
+
+    /// Open a backup; the caller runs it in the background with [`Self::execute`]
+    #[tracing::instrument(skip(self), err)]
+    pub async fn start(&self, created_by: i64) -> AppResult<Backup> {
+        let destination = self.destination()?;
+        let filename = format!("elidune-backup-{}.gz", Utc::now().format("%Y%m%d-%H%M%S"));
+        self.repository
+            .backups_start(destination, &filename, Some(created_by))
+            .await?
+            .ok_or_else(|| AppError::Conflict("A backup is already running".to_string()))
+    }
+
+    /// Write the backup file, then store the outcome
+    #[tracing::instrument(skip(self, backup), fields(backup_id = backup.id))]
+    pub async fn execute(&self, mut backup: Backup) -> AppResult<Backup> {
+        if let Err(e) = self.export(&mut backup).await {
+            tracing::warn!("Backup {} failed: {}", backup.id, e);
+            backup.status = BackupStatus::Failed;
+            backup.error = Some(e.to_string());
+        } else {
+            backup.status = BackupStatus::Succeeded;
+        }
+        let backup = self.repository.backups_finish(&backup).await?;
+
+        let payload = serde_json::json!({
+            "destination": backup.destination.as_str(),
+            "filename": backup.filename,
+            "sizeBytes": backup.size_bytes,
+            "schemaVersion": backup.schema_version,
+            "rowCount": backup.row_count,
+            "error": backup.error,
+        });
+        if backup.status == BackupStatus::Succeeded {
+            self.audit.log(
+                audit::event::BACKUP_COMPLETED,
+                backup.created_by,
+                Some("backup"),
+                Some(backup.id),
+                None,
+                Some(payload),
+                audit::AuditLogMeta::success(),
+            );
+        } else {
+            self.audit.log(
+                audit::event::BACKUP_FAILED,
+                backup.created_by,
+                Some("backup"),
+                Some(backup.id),
+                None,
+                Some(payload),
+                audit::AuditLogMeta::failure_background(
+                    backup.status.as_str(),
+                    backup.error.clone().unwrap_or_default(),
+                ),
+            );
+        }
+        Ok(backup)
+    }
+
+    fn destination(&self) -> AppResult<BackupDestination> {
+        match self.config.destination.as_str() {
+            "artifacts" => Ok(BackupDestination::Artifacts),
+            "s3" => Ok(BackupDestination::S3),
+            other => Err(AppError::Internal(format!(
+                "Unknown backup destination '{}' (expected \"artifacts\" or \"s3\")",
+                other
+            ))),
+        }
+    }
+
+    async fn export(&self, backup: &mut Backup) -> AppResult<()> {
+        match backup.destination {
+            BackupDestination::Artifacts => {
+                let mut writer = self
+                    .artifacts
+                    .writer(ArtifactKind::Backup, &backup.filename, BACKUP_CONTENT_TYPE, backup.created_by)
+                    .await?;
+                writer.expire_at(Utc::now() + Duration::days(self.config.retention_days as i64));
+                let mut file = BackupWriter::new(Sink::Artifact(writer));
+                let summary = self.dump(&mut file).await?;
+                let (Sink::Artifact(writer), size, sha256) = file.finish().await? else {
+                    unreachable!("sink chosen above")
+                };
+                let artifact = writer.finish().await?;
+                backup.artifact_id = Some(artifact.id);
+                record(backup, summary, size, sha256);
+            }
+            BackupDestination::S3 => {
+                let bucket = self.bucket()?;
+                let path = std::env::temp_dir().join(format!("elidune-backup-{}.gz", uuid::Uuid::new_v4().simple()));
+                let result = async {
+                    let file = tokio::fs::File::create(&path)
+                        .await
+                        .map_err(|e| AppError::Internal(format!("Cannot create {}: {}", path.display(), e)))?;
+                    let mut file = BackupWriter::new(Sink::File(file));
+                    let summary = self.dump(&mut file).await?;
+                    let (Sink::File(mut file), size, sha256) = file.finish().await? else {
+                        unreachable!("sink chosen above")
+                    };
+                    file.shutdown()
+                        .await
+                        .map_err(|e| AppError::Internal(format!("Cannot write backup file: {}", e)))?;
+                    let body = tokio::fs::read(&path)
+                        .await
+                        .map_err(|e| AppError::Internal(format!("Cannot read backup file: {}", e)))?;
+                    bucket.put(&backup.filename, body, BACKUP_CONTENT_TYPE).await?;
+                    Ok::<_, AppError>((summary, size, sha256))
+                }
+                .await;
+                let _ = tokio::fs::remove_file(&path).await;
+                let (summary, size, sha256) = result?;
+                backup.location = Some(format!(
+                    "{}/{}",
+                    self.config.s3_url.as_deref().unwrap_or_default().trim_end_matches('/'),
+                    backup.filename
+                ));
+                record(backup, summary, size, sha256);
+            }
+        }
+        Ok(())
+    }
+
+    fn bucket(&self) -> AppResult<S3Bucket> {
+        let url = self
+            .config
+            .s3_url
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("backup.s3_url is not set".to_string()))?;
+        let url = Url::parse(url).map_err(|e| AppError::Internal(format!("Invalid backup.s3_url: {}", e)))?;
+        if url.scheme() != "s3" {
+            return Err(AppError::Internal("backup.s3_url must be an s3:// URL".to_string()));
+        }
+        let (Some(access_key), Some(secret_key)) = (&self.config.s3_access_key, &self.config.s3_secret_key) else {
+            return Err(AppError::Internal(
+                "backup.s3_access_key and backup.s3_secret_key are required".to_string(),
+            ));
+        };
+        S3Bucket::new(
+            &url,
+            &self.config.s3_region,
+            self.config.s3_endpoint.as_deref(),
+            access_key,
+            secret_key,
+            &self.http,
+        )
+    }
+
+    /// Manifest line, then the rows of every table, from one consistent snapshot
+    async fn dump(&self, file: &mut BackupWriter) -> AppResult<DumpSummary> {
+        let redis = match self.redis.key_counts_by_prefix().await {
+            Ok(keys) => RedisNotes {
+                keys,
+                note: REDIS_NOTE.to_string(),
+            },
+            Err(e) => RedisNotes {
+                keys: Default::default(),
+                note: format!("{} Key counts unavailable: {}", REDIS_NOTE, e),
+            },
+        };
+
+        let mut conn = self.pool.acquire().await?;
+        let mut tx = conn.begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+
+        let migrations = database::applied_migrations(&mut tx).await?;
+        let manifest = BackupManifest {
+            format: BACKUP_FORMAT.to_string(),
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: Utc::now(),
+            schema_version: migrations.last().map(|m| m.version).unwrap_or_default(),
+            migrations,
+            tables: database::tables(&mut tx).await?,
+            sequences: database::sequences(&mut tx).await?,
+            redis,
+        };
+        file.write(format::manifest_line(&manifest)?.as_bytes()).await?;
+
+        let mut rows = 0u64;
+        for table in &manifest.tables {
+            file.write(format::section_start(&table.name).as_bytes()).await?;
+            let mut stream = tx.copy_out_raw(&database::copy_out_statement(table)).await?;
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk?;
+                // COPY text format escapes newlines inside values: one line per row
+                rows += chunk.iter().filter(|b| **b == b'\n').count() as u64;
+                file.write(&chunk).await?;
+            }
+            file.write(format::SECTION_END).await?;
+        }
+        tx.commit().await?;
+        Ok(DumpSummary { manifest, rows })
+    }
+}
+
+fn record(backup: &mut Backup, summary: DumpSummary, size: u64, sha256: String) {
+    backup.size_bytes = Some(size as i64);
+    backup.sha256 = Some(sha256);
+    backup.schema_version = Some(summary.manifest.schema_version);
+    backup.table_count = Some(summary.manifest.tables.len() as i32);
+    backup.row_count = Some(summary.rows as i64);
+    backup.redis_notes = Some(summary.manifest.redis);
+}
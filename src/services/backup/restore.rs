@@ -0,0 +1,84 @@
+//! `elidune-server restore <file>`: load a backup file into the configured database
+//!
+//! Run with the server stopped. The backup must come from this server version or an older one
+//! (every migration it went through must be known here, unchanged). The database must be either
+//! empty, in which case the schema is first created up to the backup's version, or at exactly the
+//! backup's version, in which case its content is replaced (`--yes` required). The rows are
+//! loaded in one transaction, then the migrations added since the backup are applied.
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use flate2::read::GzDecoder;
+use serde::Serialize;
+use sqlx::{Connection, PgConnection};
+
+use super::{
+    database,
+    format::{self, RestoreTarget},
+};
+use crate::error::{AppError, AppResult};
+
+/// Outcome of a restore, printed by the command
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    /// Migration version of the backup
+    pub schema_version: i64,
+    /// Server version that took the backup
+    pub backup_server_version: String,
+    pub tables: usize,
+    pub rows: u64,
+    /// Migrations applied after loading (the backup came from an older server)
+    pub migrations_applied: usize,
+}
+
+/// Restore `path` into the database at `database_url`. `replace` allows overwriting a database
+/// that already holds data.
+pub async fn restore_file(database_url: &str, path: &Path, replace: bool) -> AppResult<RestoreReport> {
+    let file = File::open(path).map_err(|e| AppError::Validation(format!("Cannot open {}: {}", path.display(), e)))?;
+    let mut reader = BufReader::new(GzDecoder::new(file));
+    let mut first_line = Vec::new();
+    reader
+        .read_until(b'\n', &mut first_line)
+        .map_err(|e| AppError::Validation(format!("Not an Elidune backup: {}", e)))?;
+    let manifest = format::parse_manifest(&first_line)?;
+    format::check_compatibility(&manifest.migrations, &database::server_migrations())?;
+
+    let mut conn = PgConnection::connect(database_url).await?;
+    let applied = database::applied_migrations(&mut conn).await?;
+    match format::restore_target(&applied, &manifest.migrations)? {
+        RestoreTarget::Empty => database::migrate_to(&mut conn, manifest.schema_version).await?,
+        RestoreTarget::SameSchema if !replace => {
+            return Err(AppError::Conflict(
+                "The database already holds data: pass --yes to replace it with the backup".to_string(),
+            ))
+        }
+        RestoreTarget::SameSchema => {}
+    }
+
+    let mut tx = conn.begin().await?;
+    let loaded = database::load(&mut tx, &manifest, &mut reader).await?;
+    tx.commit().await?;
+
+    let migrations_applied = database::server_migrations()
+        .iter()
+        .filter(|m| m.version > manifest.schema_version)
+        .count();
+    crate::MIGRATOR
+        .run_direct(&mut conn)
+        .await
+        .map_err(|e| AppError::Internal(format!("Migrations after the restore failed: {}", e)))?;
+    conn.close().await?;
+
+    Ok(RestoreReport {
+        schema_version: manifest.schema_version,
+        backup_server_version: manifest.server_version,
+        tables: loaded.len(),
+        rows: loaded.iter().map(|(_, rows)| rows).sum(),
+        migrations_applied,
+    })
+}
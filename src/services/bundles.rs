@@ -0,0 +1,131 @@
+//! Item bundles service: box sets and multi-volume works circulating as one unit
+
+use std::{collections::HashSet, sync::Arc};
+
+use crate::{
+    error::{AppError, AppResult},
+    models::bundle::{BundleReturnReport, CreateItemBundle, ItemBundle, ReturnItemBundle, UpdateItemBundle},
+    repository::BundlesRepository,
+};
+
+#[derive(Clone)]
+pub struct BundlesService {
+    repository: Arc<dyn BundlesRepository>,
+}
+
+impl BundlesService {
+    pub fn new(repository: Arc<dyn BundlesRepository>) -> Self {
+        Self { repository }
+    }
+
+    pub async fn list(&self) -> AppResult<Vec<ItemBundle>> {
+        self.repository.bundles_list().await
+    }
+
+    pub async fn get(&self, id: i64) -> AppResult<ItemBundle> {
+        self.repository.bundles_get(id).await
+    }
+
+    /// Create a bundle of at least two copies
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create(&self, data: &CreateItemBundle) -> AppResult<ItemBundle> {
+        let data = CreateItemBundle {
+            name: normalize_name(&data.name)?,
+            barcode: data.barcode.as_deref().map(str::trim).filter(|b| !b.is_empty()).map(str::to_string),
+            notes: data.notes.clone(),
+            part_barcodes: normalize_parts(&data.part_barcodes)?,
+        };
+        self.repository.bundles_create(&data).await
+    }
+
+    /// Update a bundle; parts can only be changed while the whole set is on the shelf
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update(&self, id: i64, data: &UpdateItemBundle) -> AppResult<ItemBundle> {
+        let data = UpdateItemBundle {
+            name: data.name.as_deref().map(normalize_name).transpose()?,
+            barcode: data.barcode.as_deref().map(|b| b.trim().to_string()),
+            notes: data.notes.clone(),
+            part_barcodes: data.part_barcodes.as_deref().map(normalize_parts).transpose()?,
+        };
+        self.repository.bundles_update(id, &data).await
+    }
+
+    pub async fn delete(&self, id: i64) -> AppResult<()> {
+        self.repository.bundles_delete(id).await
+    }
+
+    /// Check in the parts brought back; the report lists the parts still missing
+    #[tracing::instrument(skip(self), err)]
+    pub async fn return_parts(&self, id: i64, data: &ReturnItemBundle) -> AppResult<BundleReturnReport> {
+        let barcodes = normalize_barcodes(&data.barcodes)?;
+        self.repository.bundles_return(id, &barcodes).await
+    }
+}
+
+fn normalize_name(name: &str) -> AppResult<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("name cannot be empty".to_string()));
+    }
+    Ok(name.to_string())
+}
+
+/// A bundle groups at least two copies.
+fn normalize_parts(barcodes: &[String]) -> AppResult<Vec<String>> {
+    let barcodes = normalize_barcodes(barcodes)?;
+    if barcodes.len() < 2 {
+        return Err(AppError::Validation("A bundle needs at least two parts".to_string()));
+    }
+    Ok(barcodes)
+}
+
+/// Trim barcodes and reject empty or duplicate entries.
+fn normalize_barcodes(barcodes: &[String]) -> AppResult<Vec<String>> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::with_capacity(barcodes.len());
+    for barcode in barcodes.iter().map(|b| b.trim()) {
+        if barcode.is_empty() {
+            return Err(AppError::Validation("barcodes cannot contain empty values".to_string()));
+        }
+        if !seen.insert(barcode.to_string()) {
+            return Err(AppError::Validation(format!("Barcode {} is listed twice", barcode)));
+        }
+        out.push(barcode.to_string());
+    }
+    if out.is_empty() {
+        return Err(AppError::Validation("barcodes cannot be empty".to_string()));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn barcodes(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn parts_are_trimmed_and_need_two_copies() {
+        assert_eq!(
+            normalize_parts(&barcodes(&[" V1 ", "V2"])).unwrap(),
+            barcodes(&["V1", "V2"])
+        );
+        assert!(matches!(normalize_parts(&barcodes(&["V1"])), Err(AppError::Validation(_))));
+        assert!(matches!(normalize_parts(&barcodes(&["V1", "V1 "])), Err(AppError::Validation(_))));
+        assert!(matches!(normalize_parts(&barcodes(&["V1", " "])), Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn create_rejects_blank_name_before_touching_the_database() {
+        let service = BundlesService::new(Arc::new(crate::repository::bundles::MockBundlesRepository::new()));
+        let data = CreateItemBundle {
+            name: "  ".to_string(),
+            barcode: None,
+            notes: None,
+            part_barcodes: barcodes(&["V1", "V2"]),
+        };
+        assert!(matches!(service.create(&data).await, Err(AppError::Validation(_))));
+    }
+}
@@ -0,0 +1,318 @@
+//! Email campaigns: closure and event announcements sent to a patron segment
+//!
+//! Sending a campaign resolves its segment into the `campaign_recipients` queue (one row per
+//! distinct address), then a background task drains the queue at the SMTP throttle rate of the
+//! reminders section. Each email is the campaign text inserted into the `campaign` template, in
+//! the recipient's language. Bounces reported later by the mail provider are recorded against
+//! the queue, so every campaign keeps its delivered / failed / bounced counts.
+
+use std::sync::Arc;
+
+use crate::{
+    dynamic_config::DynamicConfig,
+    email::EmailService,
+    email_templates::{self, EmailTemplate},
+    error::{AppError, AppResult},
+    models::{
+        campaign::{
+            Campaign, CampaignBounceReport, CampaignDelivery, CampaignRecipient, CampaignRecipientStatus,
+            CampaignSegment, CampaignSegmentPreview, CampaignStatus, CreateCampaign, UpdateCampaign,
+        },
+        Language,
+    },
+    repository::CampaignsRepository,
+};
+
+/// Email template the campaign text is inserted into
+pub const CAMPAIGN_TEMPLATE_ID: &str = "campaign";
+
+const MAX_NAME_CHARS: usize = 255;
+const MAX_SUBJECT_CHARS: usize = 255;
+const MAX_BODY_CHARS: usize = 20_000;
+/// Bounce addresses accepted per call
+const MAX_BOUNCES: usize = 1000;
+
+#[derive(Clone)]
+pub struct CampaignsService {
+    repository: Arc<dyn CampaignsRepository>,
+    email: EmailService,
+    dynamic_config: Arc<DynamicConfig>,
+}
+
+impl CampaignsService {
+    pub fn new(
+        repository: Arc<dyn CampaignsRepository>,
+        email: EmailService,
+        dynamic_config: Arc<DynamicConfig>,
+    ) -> Self {
+        Self { repository, email, dynamic_config }
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self) -> AppResult<Vec<Campaign>> {
+        self.repository.campaigns_list().await
+    }
+
+    pub async fn get(&self, id: i64) -> AppResult<Campaign> {
+        self.repository.campaigns_get(id).await
+    }
+
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn create(&self, data: &CreateCampaign, created_by: i64) -> AppResult<Campaign> {
+        let data = CreateCampaign {
+            name: validate_text("name", &data.name, MAX_NAME_CHARS)?,
+            subject: validate_text("subject", &data.subject, MAX_SUBJECT_CHARS)?,
+            body: validate_text("body", &data.body, MAX_BODY_CHARS)?,
+            segment: normalize_segment(&data.segment)?,
+        };
+        self.repository.campaigns_create(&data, created_by).await
+    }
+
+    /// Edit a draft (campaigns being sent or sent are frozen)
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn update(&self, id: i64, data: &UpdateCampaign) -> AppResult<Campaign> {
+        let data = UpdateCampaign {
+            name: data.name.as_deref().map(|v| validate_text("name", v, MAX_NAME_CHARS)).transpose()?,
+            subject: data.subject.as_deref().map(|v| validate_text("subject", v, MAX_SUBJECT_CHARS)).transpose()?,
+            body: data.body.as_deref().map(|v| validate_text("body", v, MAX_BODY_CHARS)).transpose()?,
+            segment: data.segment.as_ref().map(normalize_segment).transpose()?,
+        };
+        if !self.repository.campaigns_update(id, &data).await? {
+            return Err(self.status_conflict(id, "only drafts can be edited").await);
+        }
+        self.repository.campaigns_get(id).await
+    }
+
+    /// Delete a draft or a sent campaign with its delivery report
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete(&self, id: i64) -> AppResult<()> {
+        if !self.repository.campaigns_delete(id).await? {
+            return Err(self.status_conflict(id, "wait for the send to finish").await);
+        }
+        Ok(())
+    }
+
+    /// Number of addresses a segment currently resolves to
+    #[tracing::instrument(skip(self), err)]
+    pub async fn preview(&self, segment: &CampaignSegment) -> AppResult<CampaignSegmentPreview> {
+        let segment = normalize_segment(segment)?;
+        Ok(CampaignSegmentPreview { recipients: self.repository.campaigns_segment_count(&segment).await? })
+    }
+
+    /// Queue the recipients of a draft, or resume a send interrupted by a restart.
+    /// The queue is drained by [`Self::execute`].
+    #[tracing::instrument(skip(self), err)]
+    pub async fn start_send(&self, id: i64) -> AppResult<Campaign> {
+        let campaign = self.repository.campaigns_get(id).await?;
+        match campaign.status {
+            CampaignStatus::Draft => {
+                if self.repository.campaigns_enqueue(id).await?.is_none() {
+                    return Err(self.status_conflict(id, "it was sent concurrently").await);
+                }
+            }
+            CampaignStatus::Sending => {
+                self.repository.campaigns_requeue_stalled(id).await?;
+            }
+            CampaignStatus::Sent => {
+                return Err(AppError::Conflict(format!("Campaign {} was already sent", id)));
+            }
+        }
+        self.repository.campaigns_get(id).await
+    }
+
+    /// Send the queued emails one by one, pausing `reminders.smtp_throttle_ms` between sends.
+    /// Several senders can drain the same queue: each recipient is claimed before sending.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn execute(&self, id: i64) -> AppResult<Campaign> {
+        let campaign = self.repository.campaigns_get(id).await?;
+        let throttle_ms = self.dynamic_config.read_reminders().smtp_throttle_ms;
+        while let Some(delivery) = self.repository.campaigns_claim_next(id).await? {
+            let error = match self.send_one(&campaign, &delivery).await {
+                Ok(()) => None,
+                Err(e) => {
+                    tracing::warn!("Campaign {} email to {} failed: {}", id, delivery.email, e);
+                    Some(e.to_string())
+                }
+            };
+            self.repository.campaigns_mark_delivery(id, &delivery.email, error).await?;
+            if throttle_ms > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(throttle_ms)).await;
+            }
+        }
+        self.repository.campaigns_finish(id).await?;
+        self.repository.campaigns_get(id).await
+    }
+
+    async fn send_one(&self, campaign: &Campaign, delivery: &CampaignDelivery) -> AppResult<()> {
+        let lang = delivery.language.as_deref().map(Language::from);
+        let template = self.email.load_template(CAMPAIGN_TEMPLATE_ID, lang).await?;
+        let (subject, plain, html) = compose(campaign, delivery, &template);
+        self.email.send_email_with_html(&delivery.email, &subject, &plain, &html).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn recipients(
+        &self,
+        id: i64,
+        status: Option<CampaignRecipientStatus>,
+    ) -> AppResult<Vec<CampaignRecipient>> {
+        self.repository.campaigns_get(id).await?;
+        self.repository.campaigns_recipients(id, status).await
+    }
+
+    /// Record bounces reported by the mail provider. Only sent recipients can bounce; other
+    /// addresses are returned as ignored.
+    #[tracing::instrument(skip(self, emails), err)]
+    pub async fn record_bounces(&self, id: i64, emails: &[String]) -> AppResult<CampaignBounceReport> {
+        if emails.is_empty() {
+            return Err(AppError::Validation("emails cannot be empty".to_string()));
+        }
+        if emails.len() > MAX_BOUNCES {
+            return Err(AppError::Validation(format!("At most {} emails per call", MAX_BOUNCES)));
+        }
+        self.repository.campaigns_get(id).await?;
+        let bounced = self.repository.campaigns_record_bounces(id, emails).await?;
+        let ignored = emails
+            .iter()
+            .filter(|e| !bounced.contains(&e.trim().to_lowercase()))
+            .cloned()
+            .collect();
+        Ok(CampaignBounceReport { bounced: bounced.len() as u64, ignored })
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn set_opt_in(&self, user_id: i64, opt_in: bool) -> AppResult<()> {
+        self.repository.campaigns_set_opt_in(user_id, opt_in).await
+    }
+
+    /// 404 when the campaign does not exist, 409 with its current status otherwise
+    async fn status_conflict(&self, id: i64, rule: &str) -> AppError {
+        match self.repository.campaigns_get(id).await {
+            Ok(campaign) => AppError::Conflict(format!("Campaign {} is {}: {}", id, campaign.status.as_str(), rule)),
+            Err(e) => e,
+        }
+    }
+}
+
+/// Personalize the campaign text, then insert it into the `campaign` template
+/// (`{{subject}}`, `{{body}}` as plain text, `{{body_html}}` escaped with line breaks).
+fn compose(campaign: &Campaign, delivery: &CampaignDelivery, template: &EmailTemplate) -> (String, String, String) {
+    let names: Vec<(&str, &str)> = vec![
+        ("firstname", delivery.firstname.as_deref().unwrap_or("")),
+        ("lastname", delivery.lastname.as_deref().unwrap_or("")),
+    ];
+    let text = EmailTemplate {
+        subject: campaign.subject.clone(),
+        body_plain: campaign.body.clone(),
+        body_html: Some(String::new()),
+    };
+    let (subject, body, _) = email_templates::substitute(&text, &names);
+    let body_html = body
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\n', "<br>");
+    let mut vars = names;
+    vars.extend([("subject", subject.as_str()), ("body", body.as_str()), ("body_html", body_html.as_str())]);
+    email_templates::substitute(template, &vars)
+}
+
+fn validate_text(field: &str, value: &str, max_chars: usize) -> AppResult<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(AppError::Validation(format!("{} cannot be empty", field)));
+    }
+    if value.chars().count() > max_chars {
+        return Err(AppError::Validation(format!("{} must be at most {} characters", field, max_chars)));
+    }
+    Ok(value.to_string())
+}
+
+fn normalize_segment(segment: &CampaignSegment) -> AppResult<CampaignSegment> {
+    for (field, age) in [("minAge", segment.min_age), ("maxAge", segment.max_age)] {
+        if age.is_some_and(|a| !(0..=130).contains(&a)) {
+            return Err(AppError::Validation(format!("{} must be between 0 and 130", field)));
+        }
+    }
+    if let (Some(min), Some(max)) = (segment.min_age, segment.max_age) {
+        if min > max {
+            return Err(AppError::Validation("minAge must not exceed maxAge".to_string()));
+        }
+    }
+    if segment.active_within_days.is_some_and(|d| !(1..=3650).contains(&d)) {
+        return Err(AppError::Validation("activeWithinDays must be between 1 and 3650".to_string()));
+    }
+    let mut account_types: Vec<String> = segment
+        .account_types
+        .iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    account_types.sort();
+    account_types.dedup();
+    Ok(CampaignSegment { account_types, ..segment.clone() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn campaign() -> Campaign {
+        Campaign {
+            id: 1,
+            name: "Summer closure".to_string(),
+            subject: "Closed in August, {{firstname}}".to_string(),
+            body: "Dear {{firstname}} {{lastname}},\nwe close on <1 August> & reopen later.".to_string(),
+            segment: CampaignSegment::default(),
+            status: CampaignStatus::Draft,
+            created_by: None,
+            created_at: chrono::Utc::now(),
+            update_at: None,
+            started_at: None,
+            completed_at: None,
+            recipients_count: 0,
+            pending_count: 0,
+            delivered_count: 0,
+            failed_count: 0,
+            bounced_count: 0,
+        }
+    }
+
+    #[test]
+    fn compose_personalizes_text_inside_the_template() {
+        let delivery = CampaignDelivery {
+            email: "ada@example.org".to_string(),
+            firstname: Some("Ada".to_string()),
+            lastname: Some("Lovelace".to_string()),
+            language: None,
+        };
+        let template = EmailTemplate {
+            subject: "[Library] {{subject}}".to_string(),
+            body_plain: "{{body}}\n--\nUnsubscribe from your account".to_string(),
+            body_html: Some("<p>{{body_html}}</p>".to_string()),
+        };
+        let (subject, plain, html) = compose(&campaign(), &delivery, &template);
+        assert_eq!(subject, "[Library] Closed in August, Ada");
+        assert!(plain.starts_with("Dear Ada Lovelace,\nwe close on <1 August>"));
+        assert_eq!(
+            html,
+            "<p>Dear Ada Lovelace,<br>we close on &lt;1 August&gt; &amp; reopen later.</p>"
+        );
+    }
+
+    #[test]
+    fn segments_are_validated_and_normalized() {
+        let segment = |min_age, max_age, active_within_days| CampaignSegment {
+            account_types: vec![" reader ".to_string(), "".to_string(), "reader".to_string()],
+            min_age,
+            max_age,
+            active_within_days,
+            opted_in_only: true,
+        };
+        assert!(matches!(normalize_segment(&segment(Some(18), Some(12), None)), Err(AppError::Validation(_))));
+        assert!(matches!(normalize_segment(&segment(Some(-1), None, None)), Err(AppError::Validation(_))));
+        assert!(matches!(normalize_segment(&segment(None, None, Some(0))), Err(AppError::Validation(_))));
+        let normalized = normalize_segment(&segment(Some(12), Some(18), Some(365))).unwrap();
+        assert_eq!(normalized.account_types, vec!["reader".to_string()]);
+    }
+}
@@ -4,37 +4,100 @@ use std::sync::Arc;
 
 use crate::{
     error::{AppError, AppResult},
-    marc::MarcRecord,
+    marc::{biblio_items_to_marc_items, MarcRecord},
     models::{
+        author::{Author, AuthorNormalizationReport},
         import_report::{ImportAction, ImportReport},
         biblio::{
-            Biblio, BiblioQuery, BiblioShort, Collection, CollectionQuery, CreateCollection,
-            CreateSerie, Serie, SerieQuery, UpdateCollection, UpdateSerie,
+            Biblio, BiblioAvailability, BiblioFacets, BiblioQuery, BiblioShort, CatalogSearchNode, Collection,
+            CollectionQuery, CreateCollection, CreateSerie, Isbn, IsbnAvailability, NewAcquisition, Serie,
+            SerieQuery, SuggestQuery, Suggestion, UpdateCollection, UpdateSerie, SUGGEST_MIN_CHARS,
+        },
+        heading::{
+            CreateGenreHeading, CreateSubjectHeading, GenreHeading, MergeHeadingsReport, SubjectHeading,
+            SubjectQuery, UpdateGenreHeading, UpdateSubjectHeading,
+        },
+        snapshot::{SnapshotScope, OPERATION_CALL_NUMBERS},
+        item::{
+            CallNumberChange, CallNumberRecalculationReport, Item, ItemAccessType, ItemExportRow,
+            ProcessingSlipRow, RecalculateCallNumbers, SpineLabelQuery, SpineLabelRow,
         },
-        item::Item,
     },
-    repository::{BibliosRepository, CatalogEntitiesRepository},
-    services::search::{MeilisearchService, SearchFilters},
+    repository::{BibliosRepository, CatalogEntitiesRepository, LocationsRepository, MediaTypesRepository},
+    services::{
+        currency::Currency,
+        redis::RedisService,
+        search::{MeilisearchService, SearchFilters},
+        snapshots::SnapshotsService,
+    },
 };
 
+/// Suggestions returned when the client does not ask for a number
+const DEFAULT_SUGGEST_LIMIT: i64 = 10;
+const MAX_SUGGEST_LIMIT: i64 = 20;
+
+/// Changes listed by an author normalization report when the client does not ask for a number
+const DEFAULT_NORMALIZATION_CHANGES: usize = 200;
+const MAX_NORMALIZATION_CHANGES: usize = 1000;
+
+/// Trimmed text and capped limit of a suggest query, `None` when the text is too short to complete.
+fn suggest_params(query: &SuggestQuery) -> Option<(&str, i64)> {
+    let q = query.q.trim();
+    if q.chars().count() < SUGGEST_MIN_CHARS {
+        return None;
+    }
+    Some((q, query.limit.unwrap_or(DEFAULT_SUGGEST_LIMIT).clamp(1, MAX_SUGGEST_LIMIT)))
+}
+
+/// Lifetime of cached per-ISBN availability (`GET /opac/availability`)
+const AVAILABILITY_CACHE_TTL_SECS: u64 = 60;
+const AVAILABILITY_CACHE_PREFIX: &str = "elidune:availability:isbn:";
+
 #[derive(Clone)]
 pub struct CatalogService {
     repository: Arc<dyn BibliosRepository>,
     entities: Arc<dyn CatalogEntitiesRepository>,
+    media_types: Arc<dyn MediaTypesRepository>,
     search: Option<Arc<MeilisearchService>>,
+    snapshots: Option<SnapshotsService>,
+    currency: Currency,
+    locations: Option<Arc<dyn LocationsRepository>>,
 }
 
 impl CatalogService {
-    pub fn new(repository: Arc<dyn BibliosRepository>, entities: Arc<dyn CatalogEntitiesRepository>) -> Self {
-        Self { repository, entities, search: None }
+    pub fn new(
+        repository: Arc<dyn BibliosRepository>,
+        entities: Arc<dyn CatalogEntitiesRepository>,
+        media_types: Arc<dyn MediaTypesRepository>,
+    ) -> Self {
+        Self { repository, entities, media_types, search: None, snapshots: None, currency: Currency::default(), locations: None }
     }
 
     pub fn with_search(
         repository: Arc<dyn BibliosRepository>,
         entities: Arc<dyn CatalogEntitiesRepository>,
+        media_types: Arc<dyn MediaTypesRepository>,
         search: Arc<MeilisearchService>,
     ) -> Self {
-        Self { repository, entities, search: Some(search) }
+        Self { repository, entities, media_types, search: Some(search), snapshots: None, currency: Currency::default(), locations: None }
+    }
+
+    /// Snapshot copies before bulk edits (call-number recalculation)
+    pub fn with_snapshots(mut self, snapshots: SnapshotsService) -> Self {
+        self.snapshots = Some(snapshots);
+        self
+    }
+
+    /// Validate copy prices against the library's currency (`[currency]`)
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    /// Resolve the shelving location of copies (`locationId`) and label the location facet
+    pub fn with_locations(mut self, locations: Arc<dyn LocationsRepository>) -> Self {
+        self.locations = Some(locations);
+        self
     }
 
     // =========================================================================
@@ -72,9 +135,50 @@ impl CatalogService {
         Ok(())
     }
 
+    /// Store the price in its canonical form, rejecting negative or absurd amounts. A copy that
+    /// already holds a free-text price (older rows, imports) may keep it unchanged.
+    async fn check_price(&self, item: &mut Item) -> AppResult<()> {
+        match self.currency.normalize_price(item.price.as_deref()) {
+            Ok(price) => {
+                item.price = price;
+                Ok(())
+            }
+            Err(e) => match item.id {
+                Some(id) => match self.repository.items_get_active_by_id(id).await {
+                    Ok(existing) if existing.price == item.price => Ok(()),
+                    _ => Err(e),
+                },
+                None => Err(e),
+            },
+        }
+    }
+
+    /// A copy given a shelving location moves to the place of its site.
+    async fn check_location(&self, item: &mut Item) -> AppResult<()> {
+        let (Some(location_id), Some(locations)) = (item.location_id, &self.locations) else {
+            return Ok(());
+        };
+        let location = match locations.locations_get(location_id).await {
+            Ok(location) => location,
+            Err(AppError::NotFound(_)) => {
+                return Err(AppError::invalid_field(
+                    "locationId",
+                    "unknown",
+                    format!("Location {} does not exist", location_id),
+                ))
+            }
+            Err(e) => return Err(e),
+        };
+        item.place = Some(location.place);
+        item.location_path = Some(location.path);
+        Ok(())
+    }
+
     /// Process embedded items (physical copies) through barcode policy, then upsert each one.
     async fn process_embedded_items(&self, biblio_id: i64, mut items: Vec<Item>) -> AppResult<Vec<Item>> {
         for item in &mut items {
+            self.check_price(item).await?;
+            self.check_location(item).await?;
             if let Some(ref barcode) = item.barcode {
                 self.ensure_barcode_unique(barcode, item.id).await?;
             }
@@ -95,6 +199,15 @@ impl CatalogService {
         }
     }
 
+    /// Fire-and-forget: refresh the Meilisearch documents of several biblios.
+    pub(crate) async fn sync_index_many(&self, ids: &[i64]) {
+        if self.search.is_some() {
+            for &id in ids {
+                self.sync_index(id).await;
+            }
+        }
+    }
+
     /// Fire-and-forget: remove a document from the Meilisearch index.
     async fn sync_delete(&self, id: i64) {
         if let Some(ref svc) = self.search {
@@ -114,22 +227,65 @@ impl CatalogService {
     /// if Meilisearch is unavailable or not configured.
     #[tracing::instrument(skip(self), err)]
     pub async fn search_biblios(&self, query: &BiblioQuery) -> AppResult<(Vec<BiblioShort>, i64)> {
+        let (biblios, total, _) = self.search_biblios_inner(query, false).await?;
+        Ok((biblios, total))
+    }
+
+    /// [`Self::search_biblios`] plus facet counts over the whole result set (search sidebars).
+    #[tracing::instrument(skip(self), err)]
+    pub async fn search_biblios_with_facets(
+        &self,
+        query: &BiblioQuery,
+    ) -> AppResult<(Vec<BiblioShort>, i64, BiblioFacets)> {
+        let (biblios, total, facets) = self.search_biblios_inner(query, true).await?;
+        Ok((biblios, total, facets.unwrap_or_default()))
+    }
+
+    async fn search_biblios_inner(
+        &self,
+        query: &BiblioQuery,
+        with_facets: bool,
+    ) -> AppResult<(Vec<BiblioShort>, i64, Option<BiblioFacets>)> {
         if let (Some(ref fs), Some(ref svc)) = (query.freesearch.as_deref(), &self.search) {
             if !fs.trim().is_empty() {
+                let roles = query.author_role_filter();
+                let author_roles = if roles.is_empty() {
+                    Vec::new()
+                } else {
+                    // Terms without a relator match nothing, like in the PostgreSQL search
+                    let codes = self.entities.authors_resolve_roles(&roles).await?;
+                    if codes.is_empty() {
+                        return Ok((Vec::new(), 0, with_facets.then(BiblioFacets::default)));
+                    }
+                    codes
+                };
                 let filters = SearchFilters {
                     media_type: query.media_type.clone(),
                     lang: query.lang.clone(),
                     audience_type: query.audience_type.clone(),
+                    reading_levels: query.reading_level_filter(),
+                    accessibility: query.accessibility_filter(),
+                    genres: query.genre_filter(),
+                    author_roles,
+                    subject_id: query.subject_id,
+                    location_id: query.location_id,
                     archive: query.archive,
                     include_without_active_items: query.include_without_active_items.unwrap_or(false),
+                    opac: query.opac,
                 };
                 let page = query.page.unwrap_or(1).max(1);
                 let per_page = query.per_page.unwrap_or(20).clamp(1, 200);
 
                 match svc.search(fs, &filters, page, per_page).await {
-                    Ok((ids, total)) => {
-                        let biblios = self.repository.biblios_get_short_by_ids_ordered(&ids).await?;
-                        return Ok((biblios, total));
+                    Ok((ids, total, mut facets)) => {
+                        let mut biblios = self.repository.biblios_get_short_by_ids_ordered(&ids).await?;
+                        if query.opac {
+                            retain_opac_items(&mut biblios);
+                        }
+                        if with_facets {
+                            self.label_heading_facets(&mut facets).await?;
+                        }
+                        return Ok((biblios, total, Some(facets)));
                     }
                     Err(e) => {
                         tracing::warn!("Meilisearch search failed, falling back to PostgreSQL: {}", e);
@@ -138,7 +294,73 @@ impl CatalogService {
             }
         }
 
-        self.repository.biblios_search(query).await
+        let (mut biblios, total) = self.repository.biblios_search(query).await?;
+        if query.opac {
+            retain_opac_items(&mut biblios);
+        }
+        let facets = if with_facets {
+            Some(self.repository.biblios_search_facets(query).await?)
+        } else {
+            None
+        };
+        Ok((biblios, total, facets))
+    }
+
+    /// Fill the labels of Meilisearch media type, genre, subject and location facets (which only
+    /// carry codes and ids).
+    async fn label_heading_facets(&self, facets: &mut BiblioFacets) -> AppResult<()> {
+        if !facets.media_types.is_empty() {
+            let media_types = self.media_types.media_types_list().await?;
+            for facet in &mut facets.media_types {
+                facet.label = media_types.iter().find(|m| m.code == facet.value).map(|m| m.label.clone());
+            }
+        }
+        if !facets.genres.is_empty() {
+            let genres = self.entities.genres_list().await?;
+            for facet in &mut facets.genres {
+                facet.label = genres.iter().find(|g| g.code == facet.value).map(|g| g.label.clone());
+            }
+        }
+        if !facets.subjects.is_empty() {
+            let ids: Vec<i64> = facets.subjects.iter().filter_map(|f| f.value.parse().ok()).collect();
+            let subjects = self.entities.subjects_get_by_ids(&ids).await?;
+            for facet in &mut facets.subjects {
+                facet.label = subjects
+                    .iter()
+                    .find(|s| s.id.to_string() == facet.value)
+                    .map(|s| s.heading.clone());
+            }
+        }
+        if !facets.locations.is_empty() {
+            if let Some(ref repository) = self.locations {
+                let locations = repository.locations_list(None).await?;
+                for facet in &mut facets.locations {
+                    facet.label = locations
+                        .iter()
+                        .find(|l| l.id.to_string() == facet.value)
+                        .map(|l| l.path.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Title completions for the OPAC search box.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn suggest_titles(&self, query: &SuggestQuery) -> AppResult<Vec<Suggestion>> {
+        match suggest_params(query) {
+            Some((q, limit)) => self.repository.biblios_suggest_titles(q, limit).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Author completions for the OPAC search box.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn suggest_authors(&self, query: &SuggestQuery) -> AppResult<Vec<Suggestion>> {
+        match suggest_params(query) {
+            Some((q, limit)) => self.repository.biblios_suggest_authors(q, limit).await,
+            None => Ok(Vec::new()),
+        }
     }
 
     /// Get biblio by ID with full details
@@ -149,6 +371,78 @@ impl CatalogService {
             .await
     }
 
+    /// Get a biblio as shown in the OPAC: copies in a state hidden from the OPAC are left out,
+    /// and a biblio whose active copies are all hidden is not found.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn get_opac_biblio(&self, id: i64) -> AppResult<Biblio> {
+        let biblio = self.repository.biblios_get_by_id(id).await?;
+        opac_view(biblio).ok_or_else(|| AppError::NotFound(format!("Biblio {} not found", id)))
+    }
+
+    /// Get biblio summary by ID (404 when it does not exist)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn get_biblio_short(&self, id: i64) -> AppResult<BiblioShort> {
+        self.repository.biblios_get_short_by_id(id).await
+    }
+
+    /// Public copy availability for an ISBN (embeddable widget).
+    #[tracing::instrument(skip(self), err)]
+    pub async fn get_availability_by_isbn(&self, isbn: &Isbn) -> AppResult<BiblioAvailability> {
+        if isbn.is_empty() {
+            return Err(AppError::Validation("ISBN is required".to_string()));
+        }
+        if !isbn.is_well_formed() {
+            return Err(AppError::Validation(format!("Invalid ISBN {}: expected 10 or 13 characters", isbn)));
+        }
+        self.repository
+            .biblios_get_availability_by_isbn(isbn)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("No biblio with ISBN {}", isbn)))
+    }
+
+    /// Availability of many ISBNs, in request order (`availability: None` for unknown ISBNs).
+    ///
+    /// Each ISBN's answer (unknown ones included) is cached in Redis for 60 seconds, so partner
+    /// apps polling the same featured titles do not reach the database on every call.
+    #[tracing::instrument(skip(self, redis), err)]
+    pub async fn get_availability_by_isbns(
+        &self,
+        redis: Option<&RedisService>,
+        isbns: &[Isbn],
+    ) -> AppResult<Vec<IsbnAvailability>> {
+        let mut found: std::collections::HashMap<Isbn, Option<BiblioAvailability>> = match redis {
+            Some(redis) => availability_cache_get(redis, isbns).await,
+            None => Default::default(),
+        };
+
+        let missing: Vec<Isbn> = isbns.iter().filter(|isbn| !found.contains_key(*isbn)).cloned().collect();
+        if !missing.is_empty() {
+            let mut loaded = self.repository.biblios_get_availability_by_isbns(&missing).await?;
+            let fresh: Vec<(Isbn, Option<BiblioAvailability>)> =
+                missing.into_iter().map(|isbn| { let row = loaded.remove(&isbn); (isbn, row) }).collect();
+            if let Some(redis) = redis {
+                if let Err(e) = availability_cache_set(redis, &fresh).await {
+                    tracing::warn!("Failed to cache availability: {}", e);
+                }
+            }
+            found.extend(fresh);
+        }
+
+        Ok(isbns
+            .iter()
+            .map(|isbn| IsbnAvailability {
+                isbn: isbn.clone(),
+                availability: found.get(isbn).cloned().flatten(),
+            })
+            .collect())
+    }
+
+    /// Biblios whose first active copy arrived in the last `days` days (new-acquisitions feed).
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list_new_acquisitions(&self, days: i64, limit: i64) -> AppResult<Vec<NewAcquisition>> {
+        self.repository.biblios_list_new_acquisitions(days, limit).await
+    }
+
     /// Get the bibliographic record for a physical copy (`item_id`).
     ///
     /// The returned [`Biblio`].`items` contains **only** that item, not all copies of the record.
@@ -187,6 +481,57 @@ impl CatalogService {
         Ok(biblio)
     }
 
+    /// Boolean search over the public catalog (Z39.50 target): up to `limit` biblio ids and the hit count.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn search_tree(&self, node: &CatalogSearchNode, limit: i64) -> AppResult<(Vec<i64>, i64)> {
+        self.repository.biblios_search_tree(node, limit).await
+    }
+
+    /// MARC record served to partner libraries: stored `marc_record` (or one built from the biblio),
+    /// with every active copy as local holdings.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn get_marc_record_with_holdings(&self, id: i64) -> AppResult<MarcRecord> {
+        let mut biblio = self.repository.biblios_get_by_id(id).await?;
+        if let Some(rec) = self.repository.biblios_get_marc_record_optional(id).await? {
+            biblio.marc_record = Some(rec);
+        }
+        let mut record = MarcRecord::from(&biblio);
+        record.local.items = biblio_items_to_marc_items(&biblio.items, None, None, None);
+        Ok(record)
+    }
+
+    /// Like [`Self::get_marc_record_with_holdings`] for public clients (Z39.50 target): holdings
+    /// hidden from the OPAC are left out.
+    pub async fn get_opac_marc_record_with_holdings(&self, id: i64) -> AppResult<MarcRecord> {
+        let mut biblio = self.get_opac_biblio(id).await?;
+        if let Some(rec) = self.repository.biblios_get_marc_record_optional(id).await? {
+            biblio.marc_record = Some(rec);
+        }
+        let mut record = MarcRecord::from(&biblio);
+        record.local.items = biblio_items_to_marc_items(&biblio.items, None, None, None);
+        Ok(record)
+    }
+
+    /// Copies matching a spine label filter, at most `limit`
+    pub async fn items_spine_labels(&self, query: &SpineLabelQuery, limit: i64) -> AppResult<Vec<SpineLabelRow>> {
+        self.repository.items_spine_labels(query, limit).await
+    }
+
+    /// Copies to print processing slips for, in the given order
+    pub async fn items_processing_slips(&self, item_ids: &[i64]) -> AppResult<Vec<ProcessingSlipRow>> {
+        self.repository.items_processing_slips(item_ids).await
+    }
+
+    /// Keyset page of active copies for exports (`after_id` = last item id of the previous page)
+    pub async fn items_export_page(&self, after_id: i64, limit: i64) -> AppResult<Vec<ItemExportRow>> {
+        self.repository.items_export_page(after_id, limit).await
+    }
+
+    /// Keyset page of biblio ids for MARC exports (biblios with at least one active copy)
+    pub async fn biblios_export_ids_page(&self, after_id: i64, limit: i64) -> AppResult<Vec<i64>> {
+        self.repository.biblios_export_ids_page(after_id, limit).await
+    }
+
     /// Create a new biblio with ISBN deduplication.
     ///
     /// - No duplicate ISBN among active biblios → create OK.
@@ -202,6 +547,7 @@ impl CatalogService {
         allow_duplicate_isbn: bool,
         confirm_replace_existing_id: Option<i64>,
     ) -> AppResult<(Biblio, ImportReport)> {
+        validate_reading_level(&biblio)?;
         if !allow_duplicate_isbn {
             if let Some(ref isbn) = biblio.isbn {
                 if let Some(existing_id) = self.repository.biblios_find_active_by_isbn(isbn.as_str(), None).await? {
@@ -266,6 +612,7 @@ impl CatalogService {
     /// Update an existing biblio.
     #[tracing::instrument(skip(self), err)]
     pub async fn update_biblio(&self, id: i64, mut biblio: Biblio, allow_duplicate_isbn: bool) -> AppResult<Biblio> {
+        validate_reading_level(&biblio)?;
         self.repository
             .biblios_get_by_id(id)
             .await?;
@@ -300,6 +647,12 @@ impl CatalogService {
         merged.id = Some(biblio_id);
         merged.items = existing.items;
         merged.created_at = existing.created_at;
+        // A level set by staff is kept unless the remote audience contradicts it
+        if let Some(level) = existing.reading_level {
+            if level.fits_audience(merged.audience_type.as_ref()) {
+                merged.reading_level = Some(level);
+            }
+        }
         if let Some(ref isbn) = merged.isbn {
             self.ensure_isbn_unique(isbn.as_str(), Some(biblio_id)).await?;
         }
@@ -334,10 +687,13 @@ impl CatalogService {
     /// Create an item (physical copy) for a biblio.
     /// Barcode uniqueness is enforced through the shared policy.
     #[tracing::instrument(skip(self), err)]
-    pub async fn create_item(&self, biblio_id: i64, item: Item) -> AppResult<Item> {
+    pub async fn create_item(&self, biblio_id: i64, mut item: Item) -> AppResult<Item> {
         self.repository
             .biblios_get_by_id(biblio_id)
             .await?;
+        normalize_digital_access(&mut item, None)?;
+        self.check_price(&mut item).await?;
+        self.check_location(&mut item).await?;
 
         if let Some(ref barcode) = item.barcode {
             self.ensure_barcode_unique(barcode, None).await?;
@@ -351,12 +707,13 @@ impl CatalogService {
     /// Update an item (physical copy). Resolves the bibliographic parent via the item row.
     ///
     /// `item_id` (path) is the source of truth; if `item.id` is set it must match.
+    /// Also returns the `circulationStatus` the item had before the update.
     #[tracing::instrument(skip(self), err)]
     pub async fn update_item<'a>(
         &self,
         item_id: i64,
         item: &'a mut Item,
-    ) -> AppResult<(i64, &'a mut Item)> {
+    ) -> AppResult<(i64, Option<i16>, &'a mut Item)> {
         if let Some(body_id) = item.id {
             if body_id != item_id {
                 return Err(AppError::Validation(
@@ -380,6 +737,11 @@ impl CatalogService {
         }
 
         self.repository.biblios_get_by_id(biblio_id).await?;
+        normalize_digital_access(item, existing.access_type)?;
+        if item.price != existing.price {
+            self.check_price(item).await?;
+        }
+        self.check_location(item).await?;
 
         if let Some(ref barcode) = item.barcode {
             self.ensure_barcode_unique(barcode, Some(item_id)).await?;
@@ -387,7 +749,93 @@ impl CatalogService {
 
         let result = self.repository.items_update(item).await?;
         self.sync_index(biblio_id).await;
-        Ok((biblio_id, result))
+        Ok((biblio_id, existing.circulation_status, result))
+    }
+
+    /// Resolve the digital resource behind an active copy and record the click-through.
+    ///
+    /// Restricted resources require an authenticated user; returns the URL to redirect to.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn access_item(&self, item_id: i64, user_id: Option<i64>) -> AppResult<String> {
+        let item = self.repository.items_get_active_by_id(item_id).await?;
+        let url = item.access_url.filter(|u| !u.is_empty()).ok_or_else(|| {
+            AppError::NotFound(format!("Item {item_id} has no digital resource"))
+        })?;
+        if item.access_type != Some(ItemAccessType::Open) && user_id.is_none() {
+            return Err(AppError::Authentication(
+                "This digital resource is restricted to authenticated users".to_string(),
+            ));
+        }
+        self.repository.items_log_access(item_id, user_id).await?;
+        Ok(url)
+    }
+
+    /// Rewrite call numbers of active copies with a rule set; `dry_run` only reports the diff.
+    ///
+    /// The changed copies are snapshotted first, so the rewrite can be restored.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn recalculate_call_numbers(
+        &self,
+        request: &RecalculateCallNumbers,
+        user_id: i64,
+    ) -> AppResult<CallNumberRecalculationReport> {
+        let rules = &request.rules;
+        if rules.is_empty() {
+            return Err(AppError::Validation("At least one call-number rule is required".to_string()));
+        }
+        if rules.prefix_rewrites.iter().any(|r| r.from.is_empty()) {
+            return Err(AppError::Validation("prefixRewrites.from cannot be empty".to_string()));
+        }
+        if rules.dewey_truncation.is_some_and(|d| d > 10) {
+            return Err(AppError::Validation("deweyTruncation must be between 0 and 10".to_string()));
+        }
+
+        let candidates = self
+            .repository
+            .items_call_number_candidates(request.source_id, request.call_number_prefix.as_deref())
+            .await?;
+        let scanned = candidates.len();
+        let changes: Vec<CallNumberChange> = candidates
+            .into_iter()
+            .filter_map(|c| {
+                let after = rules.apply(&c.call_number, c.audience_type.as_deref());
+                (after != c.call_number).then_some(CallNumberChange {
+                    item_id: c.item_id,
+                    biblio_id: c.biblio_id,
+                    barcode: c.barcode,
+                    before: c.call_number,
+                    after,
+                })
+            })
+            .collect();
+
+        let mut snapshot_id = None;
+        if !request.dry_run && !changes.is_empty() {
+            if let Some(snapshots) = &self.snapshots {
+                let ids = changes.iter().map(|c| c.item_id).collect();
+                snapshot_id = snapshots
+                    .capture(OPERATION_CALL_NUMBERS, SnapshotScope::Items(ids), Some(user_id))
+                    .await?
+                    .map(|s| s.id);
+            }
+            let updates: Vec<(i64, String)> =
+                changes.iter().map(|c| (c.item_id, c.after.clone())).collect();
+            self.repository.items_set_call_numbers(&updates).await?;
+
+            let mut biblio_ids: Vec<i64> = changes.iter().map(|c| c.biblio_id).collect();
+            biblio_ids.sort_unstable();
+            biblio_ids.dedup();
+            for biblio_id in biblio_ids {
+                self.sync_index(biblio_id).await;
+            }
+        }
+
+        Ok(CallNumberRecalculationReport {
+            dry_run: request.dry_run,
+            scanned,
+            changes,
+            snapshot_id,
+        })
     }
 
     /// Delete an item (physical copy). Returns the bibliographic id for callers (e.g. audit).
@@ -485,6 +933,161 @@ impl CatalogService {
         self.entities.collections_delete(id).await
     }
 
+    // =========================================================================
+    // Genre and subject headings
+    // =========================================================================
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list_genres(&self) -> AppResult<Vec<GenreHeading>> {
+        self.entities.genres_list().await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn get_genre(&self, id: i64) -> AppResult<GenreHeading> {
+        self.entities.genres_get(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create_genre(&self, data: &CreateGenreHeading) -> AppResult<GenreHeading> {
+        validate_genre_code(&data.code)?;
+        if data.label.trim().is_empty() {
+            return Err(AppError::Validation("Genre label must not be empty".into()));
+        }
+        self.entities.genres_create(data).await
+    }
+
+    /// Update a genre; renaming its code reindexes the biblios using it.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update_genre(&self, id: i64, data: &UpdateGenreHeading) -> AppResult<GenreHeading> {
+        if let Some(ref code) = data.code {
+            validate_genre_code(code)?;
+        }
+        if data.label.as_deref().is_some_and(|l| l.trim().is_empty()) {
+            return Err(AppError::Validation("Genre label must not be empty".into()));
+        }
+        let before = self.entities.genres_get(id).await?;
+        let genre = self.entities.genres_update(id, data).await?;
+        if genre.code != before.code {
+            self.sync_index_many(&self.entities.genres_biblio_ids(id).await?).await;
+        }
+        Ok(genre)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete_genre(&self, id: i64) -> AppResult<()> {
+        self.entities.genres_delete(id).await
+    }
+
+    /// Merge duplicate genres into `target_id` and reindex the re-mapped biblios.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn merge_genres(&self, target_id: i64, source_ids: &[i64]) -> AppResult<MergeHeadingsReport> {
+        let report = self.entities.genres_merge(target_id, source_ids).await?;
+        self.sync_index_many(&report.biblio_ids).await;
+        Ok(report)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn get_biblio_genres(&self, biblio_id: i64) -> AppResult<Vec<GenreHeading>> {
+        self.entities.biblio_genres_get(biblio_id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn set_biblio_genres(&self, biblio_id: i64, genre_ids: &[i64]) -> AppResult<Vec<GenreHeading>> {
+        let genres = self.entities.biblio_genres_set(biblio_id, genre_ids).await?;
+        self.sync_index(biblio_id).await;
+        Ok(genres)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list_subjects(&self, query: &SubjectQuery) -> AppResult<(Vec<SubjectHeading>, i64)> {
+        self.entities.subjects_list(query).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn get_subject(&self, id: i64) -> AppResult<SubjectHeading> {
+        self.entities.subjects_get(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create_subject(&self, data: &CreateSubjectHeading) -> AppResult<SubjectHeading> {
+        if data.heading.trim().is_empty() {
+            return Err(AppError::Validation("Subject heading must not be empty".into()));
+        }
+        self.entities.subjects_create(data).await
+    }
+
+    /// Update a subject; moving it in the hierarchy reindexes the biblios below it.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update_subject(&self, id: i64, data: &UpdateSubjectHeading) -> AppResult<SubjectHeading> {
+        if data.heading.as_deref().is_some_and(|h| h.trim().is_empty()) {
+            return Err(AppError::Validation("Subject heading must not be empty".into()));
+        }
+        let before = self.entities.subjects_get(id).await?;
+        let subject = self.entities.subjects_update(id, data).await?;
+        if subject.broader_id != before.broader_id {
+            self.sync_index_many(&self.entities.subjects_biblio_ids(id).await?).await;
+        }
+        Ok(subject)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete_subject(&self, id: i64) -> AppResult<()> {
+        self.entities.subjects_delete(id).await
+    }
+
+    /// Merge duplicate subjects into `target_id` and reindex the affected biblios.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn merge_subjects(&self, target_id: i64, source_ids: &[i64]) -> AppResult<MergeHeadingsReport> {
+        let report = self.entities.subjects_merge(target_id, source_ids).await?;
+        if report.narrower_moved > 0 {
+            // Narrower terms changed ancestors: their biblios carry new subject paths
+            self.sync_index_many(&self.entities.subjects_biblio_ids(target_id).await?).await;
+        } else {
+            self.sync_index_many(&report.biblio_ids).await;
+        }
+        Ok(report)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn get_biblio_subjects(&self, biblio_id: i64) -> AppResult<Vec<SubjectHeading>> {
+        self.entities.biblio_subjects_get(biblio_id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn set_biblio_subjects(&self, biblio_id: i64, subject_ids: &[i64]) -> AppResult<Vec<SubjectHeading>> {
+        let subjects = self.entities.biblio_subjects_set(biblio_id, subject_ids).await?;
+        self.sync_index(biblio_id).await;
+        Ok(subjects)
+    }
+
+    // =========================================================================
+    // Authors
+    // =========================================================================
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn get_author(&self, id: i64) -> AppResult<Author> {
+        self.entities.authors_get(id).await
+    }
+
+    /// Set (or clear, when empty) the preferred display form of an author.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn set_author_display_name(&self, id: i64, display_name: Option<String>) -> AppResult<Author> {
+        if display_name.as_deref().is_some_and(|d| d.trim().chars().count() > 255) {
+            return Err(AppError::Validation("displayName must be at most 255 characters".into()));
+        }
+        self.entities.authors_set_display_name(id, display_name).await
+    }
+
+    /// Normalize the names of the authors table (preview unless `apply`), then reindex the
+    /// records whose authors were renamed or merged.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn normalize_authors(&self, apply: bool, limit: Option<usize>) -> AppResult<AuthorNormalizationReport> {
+        let limit = limit.unwrap_or(DEFAULT_NORMALIZATION_CHANGES).min(MAX_NORMALIZATION_CHANGES);
+        let report = self.entities.authors_normalize(apply, limit).await?;
+        self.sync_index_many(&report.biblio_ids).await;
+        Ok(report)
+    }
+
     // =========================================================================
     // Admin / reindex
     // =========================================================================
@@ -546,3 +1149,191 @@ impl CatalogService {
         Ok((total, true))
     }
 }
+
+/// Biblio as shown in the OPAC: `None` when it has active copies and all of them are hidden.
+fn opac_view(mut biblio: Biblio) -> Option<Biblio> {
+    if !biblio.items.is_empty() && biblio.items.iter().all(|i| i.hidden_from_opac) {
+        return None;
+    }
+    biblio.items.retain(|i| !i.hidden_from_opac);
+    Some(biblio)
+}
+
+/// Drop the copies hidden from the OPAC from public search results.
+fn retain_opac_items(biblios: &mut [BiblioShort]) {
+    for biblio in biblios {
+        biblio.items.retain(|i| !i.hidden_from_opac);
+    }
+}
+
+/// Check `accessUrl` (http/https only) and default `accessType` to restricted for new links.
+///
+/// `current` is the stored access type (None for a new copy); an empty URL clears the link.
+fn normalize_digital_access(item: &mut Item, current: Option<ItemAccessType>) -> AppResult<()> {
+    let Some(url) = item.access_url.as_deref().map(str::trim) else {
+        return Ok(());
+    };
+    if url.is_empty() {
+        item.access_type = None;
+        return Ok(());
+    }
+    if !(url.starts_with("https://") || url.starts_with("http://")) || url.contains(char::is_whitespace) {
+        return Err(AppError::Validation(format!(
+            "accessUrl must be an absolute http(s) URL (got {})",
+            url
+        )));
+    }
+    item.access_url = Some(url.to_string());
+    if item.access_type.is_none() && current.is_none() {
+        item.access_type = Some(ItemAccessType::Restricted);
+    }
+    Ok(())
+}
+
+/// A reading level is for children's and teen books: it cannot go with an adult audience.
+fn validate_reading_level(biblio: &Biblio) -> AppResult<()> {
+    match biblio.reading_level {
+        Some(level) if !level.fits_audience(biblio.audience_type.as_ref()) => Err(AppError::Validation(format!(
+            "readingLevel '{}' does not match audienceType '{}'",
+            level.as_db_str(),
+            biblio.audience_type.as_ref().map(|a| a.as_db_str()).unwrap_or_default()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Genre codes are search facet values and label codes: letters, digits, `_` and `-` only.
+fn validate_genre_code(code: &str) -> AppResult<()> {
+    let code = code.trim();
+    if code.is_empty() || code.len() > 64 || !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(AppError::Validation(format!(
+            "Genre code must be 1-64 letters, digits, '_' or '-' (got '{}')",
+            code
+        )));
+    }
+    Ok(())
+}
+
+/// Cached availability of `isbns` (an entry holding `None` caches an unknown ISBN).
+/// Redis errors count as cache misses.
+async fn availability_cache_get(
+    redis: &RedisService,
+    isbns: &[Isbn],
+) -> std::collections::HashMap<Isbn, Option<BiblioAvailability>> {
+    let Ok(mut conn) = redis.get_connection().await else {
+        return Default::default();
+    };
+    let keys: Vec<String> = isbns.iter().map(|isbn| format!("{}{}", AVAILABILITY_CACHE_PREFIX, isbn)).collect();
+    // Explicit MGET: the typed helper sends GET for a single key
+    let values: Vec<Option<String>> = match redis::cmd("MGET").arg(&keys).query_async(&mut conn).await {
+        Ok(values) => values,
+        Err(_) => return Default::default(),
+    };
+    isbns
+        .iter()
+        .zip(values)
+        .filter_map(|(isbn, value)| {
+            let cached = serde_json::from_str::<Option<BiblioAvailability>>(&value?).ok()?;
+            Some((isbn.clone(), cached))
+        })
+        .collect()
+}
+
+async fn availability_cache_set(redis: &RedisService, entries: &[(Isbn, Option<BiblioAvailability>)]) -> AppResult<()> {
+    let mut pipe = redis::pipe();
+    for (isbn, availability) in entries {
+        let json = serde_json::to_string(availability)
+            .map_err(|e| AppError::Internal(format!("Cache serialize: {}", e)))?;
+        pipe.set_ex(format!("{}{}", AVAILABILITY_CACHE_PREFIX, isbn), json, AVAILABILITY_CACHE_TTL_SECS)
+            .ignore();
+    }
+    let mut conn = redis.get_connection().await?;
+    pipe.query_async::<_, ()>(&mut conn)
+        .await
+        .map_err(|e| AppError::Internal(format!("Cache set: {}", e)))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn genre_codes() {
+        for code in ["LitteratureFiction", "manga", "sci-fi_2"] {
+            assert!(validate_genre_code(code).is_ok(), "{code}");
+        }
+        for code in ["", "  ", "science fiction", "a\"b", "é"] {
+            assert!(validate_genre_code(code).is_err(), "{code}");
+        }
+    }
+
+    #[test]
+    fn reading_level_must_fit_the_audience() {
+        use crate::models::biblio::{AudienceType, ReadingLevel};
+
+        let mut biblio = Biblio::from(MarcRecord::default());
+        biblio.reading_level = Some(ReadingLevel::Ages4To6);
+        for audience in [None, Some(AudienceType::Preschool), Some(AudienceType::General)] {
+            biblio.audience_type = audience;
+            assert!(validate_reading_level(&biblio).is_ok());
+        }
+        biblio.audience_type = Some(AudienceType::Adult);
+        assert!(matches!(validate_reading_level(&biblio), Err(AppError::Validation(_))));
+        biblio.reading_level = None;
+        assert!(validate_reading_level(&biblio).is_ok());
+    }
+
+    #[test]
+    fn suggest_query_bounds() {
+        let query = |q: &str, limit: Option<i64>| SuggestQuery { q: q.to_string(), limit };
+        assert_eq!(suggest_params(&query(" h ", None)), None);
+        assert_eq!(suggest_params(&query("é", Some(5))), None);
+        assert_eq!(suggest_params(&query(" hu ", None)), Some(("hu", DEFAULT_SUGGEST_LIMIT)));
+        assert_eq!(suggest_params(&query("hugo", Some(500))), Some(("hugo", MAX_SUGGEST_LIMIT)));
+        assert_eq!(suggest_params(&query("hugo", Some(0))), Some(("hugo", 1)));
+    }
+
+    #[test]
+    fn opac_view_hides_copies_and_fully_hidden_biblios() {
+        let copy = |hidden: bool| -> Item {
+            serde_json::from_value(serde_json::json!({ "hiddenFromOpac": hidden })).unwrap()
+        };
+        let mut biblio = Biblio::from(MarcRecord::default());
+        assert!(opac_view(biblio.clone()).is_some());
+
+        biblio.items = vec![copy(true), copy(false)];
+        assert_eq!(opac_view(biblio.clone()).unwrap().items.len(), 1);
+
+        biblio.items = vec![copy(true), copy(true)];
+        assert!(opac_view(biblio).is_none());
+    }
+
+    fn item_with_url(url: &str) -> Item {
+        serde_json::from_value(serde_json::json!({ "accessUrl": url })).unwrap()
+    }
+
+    #[test]
+    fn digital_access_defaults_to_restricted() {
+        let mut item = item_with_url(" https://ebooks.example.org/title/42 ");
+        normalize_digital_access(&mut item, None).unwrap();
+        assert_eq!(item.access_url.as_deref(), Some("https://ebooks.example.org/title/42"));
+        assert_eq!(item.access_type, Some(ItemAccessType::Restricted));
+
+        let mut item = item_with_url("https://ebooks.example.org/title/42");
+        normalize_digital_access(&mut item, Some(ItemAccessType::Open)).unwrap();
+        assert_eq!(item.access_type, None);
+
+        let mut item = item_with_url("");
+        normalize_digital_access(&mut item, Some(ItemAccessType::Open)).unwrap();
+        assert_eq!(item.access_type, None);
+    }
+
+    #[test]
+    fn digital_access_rejects_non_http_urls() {
+        for url in ["ftp://example.org/file.epub", "javascript:alert(1)", "example.org", "https://a b"] {
+            let mut item = item_with_url(url);
+            assert!(normalize_digital_access(&mut item, None).is_err(), "{url}");
+        }
+    }
+}
@@ -0,0 +1,552 @@
+//! Bibliographic citations (APA 7, MLA 9, ISO 690) and reference manager exports (BibTeX, RIS)
+//! built from a record's fields.
+//!
+//! Creators are the authors credited with the `author` function (all contributors when there is
+//! none, e.g. a film credited to its director). The year is the first four-digit number of the
+//! publication date, else of the edition date.
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{
+    models::{
+        author::{Author, Function},
+        biblio::{Biblio, MediaType},
+        citation::{Citation, CitationStyle},
+    },
+    utils::escape_html,
+};
+
+/// APA lists at most this many authors (the first 19, an ellipsis, then the last one)
+const APA_MAX_AUTHORS: usize = 20;
+
+/// Citation string in a text style (`apa`, `mla`, `iso690`; APA for the download formats)
+pub fn citation(biblio: &Biblio, style: CitationStyle) -> Citation {
+    let parts = match style {
+        CitationStyle::Mla => mla(biblio),
+        CitationStyle::Iso690 => iso690(biblio),
+        CitationStyle::Apa | CitationStyle::Bibtex | CitationStyle::Ris => apa(biblio),
+    };
+    let mut text = String::new();
+    let mut html = String::new();
+    for part in &parts {
+        match part {
+            Part::Text(s) => {
+                text.push_str(s);
+                html.push_str(&escape_html(s));
+            }
+            Part::Title(s) => {
+                text.push_str(s);
+                html.push_str(&format!("<i>{}</i>", escape_html(s)));
+            }
+        }
+    }
+    Citation { style, text, html }
+}
+
+/// BibTeX entry (`@book` for printed media, `@misc` otherwise)
+pub fn bibtex(biblio: &Biblio) -> String {
+    let names = creators(biblio);
+    let year = year(biblio);
+    let entry_type = if is_book(&biblio.media_type) { "book" } else { "misc" };
+    let key = format!(
+        "{}{}",
+        names
+            .first()
+            .map(|n| ascii_key(&n.last))
+            .filter(|k| !k.is_empty())
+            .unwrap_or_else(|| format!("record{}", biblio.id.unwrap_or_default())),
+        year.as_deref().unwrap_or("")
+    );
+
+    let mut fields: Vec<(&str, String)> = Vec::new();
+    if !names.is_empty() {
+        let authors: Vec<String> = names
+            .iter()
+            .map(|n| match &n.first {
+                Some(first) => format!("{}, {}", n.last, first),
+                None => format!("{{{}}}", n.last),
+            })
+            .collect();
+        fields.push(("author", authors.join(" and ")));
+    }
+    if let Some(title) = title(biblio) {
+        fields.push(("title", title));
+    }
+    if let Some(publisher) = publisher(biblio) {
+        fields.push(("publisher", publisher));
+    }
+    if let Some(place) = place(biblio) {
+        fields.push(("address", place));
+    }
+    if let Some(year) = year {
+        fields.push(("year", year));
+    }
+    if let Some(isbn) = biblio.isbn.as_ref().filter(|i| !i.is_empty()) {
+        fields.push(("isbn", isbn.to_string()));
+    }
+
+    let mut out = format!("@{}{{{},\n", entry_type, key);
+    for (name, value) in &fields {
+        out.push_str(&format!("  {} = {{{}}},\n", name, escape_bibtex(value)));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// RIS record (CRLF line endings, as reference managers expect)
+pub fn ris(biblio: &Biblio) -> String {
+    let mut lines: Vec<(&str, String)> = vec![("TY", ris_type(&biblio.media_type).to_string())];
+    for name in creators(biblio) {
+        lines.push((
+            "AU",
+            match name.first {
+                Some(first) => format!("{}, {}", name.last, first),
+                None => name.last,
+            },
+        ));
+    }
+    if let Some(title) = title(biblio) {
+        lines.push(("TI", title));
+    }
+    if let Some(year) = year(biblio) {
+        lines.push(("PY", year));
+    }
+    if let Some(publisher) = publisher(biblio) {
+        lines.push(("PB", publisher));
+    }
+    if let Some(place) = place(biblio) {
+        lines.push(("CY", place));
+    }
+    if let Some(isbn) = biblio.isbn.as_ref().filter(|i| !i.is_empty()) {
+        lines.push(("SN", isbn.to_string()));
+    }
+    if let Some(serie) = biblio.series.first().and_then(|s| s.name.clone()) {
+        lines.push(("T3", serie));
+    }
+    lines.push(("ER", String::new()));
+
+    lines
+        .into_iter()
+        .map(|(tag, value)| format!("{}  - {}\r\n", tag, value))
+        .collect()
+}
+
+enum Part {
+    Text(String),
+    Title(String),
+}
+
+/// Creator name (`first` is `None` for corporate bodies and single names)
+struct Name {
+    last: String,
+    first: Option<String>,
+}
+
+impl Name {
+    fn from_author(author: &Author) -> Option<Self> {
+        let last = author.lastname.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        let first = author.firstname.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        match (last, first) {
+            (Some(last), first) => Some(Self { last: last.to_string(), first: first.map(str::to_string) }),
+            (None, Some(first)) => Some(Self { last: first.to_string(), first: None }),
+            (None, None) => author
+                .display_name
+                .as_deref()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| Self { last: s.to_string(), first: None }),
+        }
+    }
+
+    /// `Camus, A.` (APA)
+    fn inverted_initials(&self) -> String {
+        match &self.first {
+            Some(first) => format!("{}, {}", self.last, initials(first)),
+            None => self.last.clone(),
+        }
+    }
+
+    /// `Camus, Albert` (MLA)
+    fn inverted(&self) -> String {
+        match &self.first {
+            Some(first) => format!("{}, {}", self.last, first),
+            None => self.last.clone(),
+        }
+    }
+
+    /// `Albert Camus` (MLA, second author)
+    fn direct(&self) -> String {
+        match &self.first {
+            Some(first) => format!("{} {}", first, self.last),
+            None => self.last.clone(),
+        }
+    }
+
+    /// `CAMUS, Albert` (ISO 690)
+    fn uppercase(&self) -> String {
+        match &self.first {
+            Some(first) => format!("{}, {}", self.last.to_uppercase(), first),
+            None => self.last.to_uppercase(),
+        }
+    }
+}
+
+/// `Jean-Paul` → `J.-P.`, `John Ronald` → `J. R.`
+fn initials(first: &str) -> String {
+    first
+        .split_whitespace()
+        .map(|word| {
+            word.split('-')
+                .filter_map(|part| part.chars().next())
+                .map(|c| format!("{}.", c.to_uppercase()))
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn creators(biblio: &Biblio) -> Vec<Name> {
+    let is_author = |a: &&Author| matches!(a.function, None | Some(Function::Author));
+    let credited: Vec<&Author> = if biblio.authors.iter().any(|a| is_author(&a)) {
+        biblio.authors.iter().filter(is_author).collect()
+    } else {
+        biblio.authors.iter().collect()
+    };
+    credited.into_iter().filter_map(Name::from_author).collect()
+}
+
+/// Title without the ISBD punctuation left over from MARC (` /`, ` :`...)
+fn title(biblio: &Biblio) -> Option<String> {
+    biblio
+        .title
+        .as_deref()
+        .map(|t| t.trim().trim_end_matches(|c: char| matches!(c, '/' | ':' | ';' | ',' | '=') || c.is_whitespace()))
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+}
+
+fn year(biblio: &Biblio) -> Option<String> {
+    let first_year = |date: &str| {
+        let digits: Vec<char> = date.chars().collect();
+        digits
+            .windows(4)
+            .enumerate()
+            .find(|(i, w)| {
+                w.iter().all(char::is_ascii_digit)
+                    && !(*i > 0 && digits[i - 1].is_ascii_digit())
+                    && !digits.get(i + 4).is_some_and(char::is_ascii_digit)
+            })
+            .map(|(_, w)| w.iter().collect::<String>())
+    };
+    biblio
+        .publication_date
+        .as_deref()
+        .and_then(first_year)
+        .or_else(|| biblio.edition.as_ref().and_then(|e| e.date.as_deref()).and_then(first_year))
+}
+
+fn publisher(biblio: &Biblio) -> Option<String> {
+    biblio
+        .edition
+        .as_ref()
+        .and_then(|e| e.publisher_name.as_deref())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn place(biblio: &Biblio) -> Option<String> {
+    biblio
+        .edition
+        .as_ref()
+        .and_then(|e| e.place_of_publication.as_deref())
+        .map(|p| p.trim().trim_end_matches([':', ';', ',']).trim())
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// `s` followed by a period, unless it already ends a sentence
+fn sentence(s: &str) -> String {
+    if s.ends_with(['.', '?', '!']) {
+        s.to_string()
+    } else {
+        format!("{}.", s)
+    }
+}
+
+/// Period to put after the title, unless it already ends a sentence
+fn title_end(title: &str) -> &'static str {
+    if title.ends_with(['.', '?', '!']) {
+        ""
+    } else {
+        "."
+    }
+}
+
+/// `Camus, A. (1942). L'étranger. Gallimard.`
+fn apa(biblio: &Biblio) -> Vec<Part> {
+    let names: Vec<String> = creators(biblio).iter().map(Name::inverted_initials).collect();
+    let authors = match names.len() {
+        0 => None,
+        1 => Some(names[0].clone()),
+        n if n <= APA_MAX_AUTHORS => Some(format!("{}, & {}", names[..n - 1].join(", "), names[n - 1])),
+        n => Some(format!("{}, . . . {}", names[..APA_MAX_AUTHORS - 1].join(", "), names[n - 1])),
+    };
+    let date = format!("({}).", year(biblio).unwrap_or_else(|| "n.d.".to_string()));
+    let title = title(biblio);
+
+    let mut parts = Vec::new();
+    match (authors, title) {
+        (Some(authors), title) => {
+            parts.push(Part::Text(format!("{} {}", sentence(&authors), date)));
+            if let Some(title) = title {
+                parts.push(Part::Text(" ".to_string()));
+                let end = title_end(&title);
+                parts.push(Part::Title(title));
+                parts.push(Part::Text(end.to_string()));
+            }
+        }
+        // Without an author, the title takes its place
+        (None, Some(title)) => {
+            let end = title_end(&title);
+            parts.push(Part::Title(title));
+            parts.push(Part::Text(format!("{} {}", end, date)));
+        }
+        (None, None) => parts.push(Part::Text(date)),
+    }
+    if let Some(publisher) = publisher(biblio) {
+        parts.push(Part::Text(format!(" {}", sentence(&publisher))));
+    }
+    parts
+}
+
+/// `Camus, Albert. L'étranger. Gallimard, 1942.`
+fn mla(biblio: &Biblio) -> Vec<Part> {
+    let names = creators(biblio);
+    let authors = match names.len() {
+        0 => None,
+        1 => Some(names[0].inverted()),
+        2 => Some(format!("{}, and {}", names[0].inverted(), names[1].direct())),
+        _ => Some(format!("{}, et al", names[0].inverted())),
+    };
+
+    let mut parts = Vec::new();
+    if let Some(authors) = authors {
+        parts.push(Part::Text(format!("{} ", sentence(&authors))));
+    }
+    if let Some(title) = title(biblio) {
+        let end = title_end(&title);
+        parts.push(Part::Title(title));
+        parts.push(Part::Text(end.to_string()));
+    }
+    let publication = match (publisher(biblio), year(biblio)) {
+        (Some(publisher), Some(year)) => Some(format!("{}, {}.", publisher, year)),
+        (Some(publisher), None) => Some(sentence(&publisher)),
+        (None, Some(year)) => Some(format!("{}.", year)),
+        (None, None) => None,
+    };
+    if let Some(publication) = publication {
+        parts.push(Part::Text(format!(" {}", publication)));
+    }
+    trim_parts(parts)
+}
+
+/// `CAMUS, Albert. L'étranger. Paris: Gallimard, 1942. ISBN 9782070360024.`
+fn iso690(biblio: &Biblio) -> Vec<Part> {
+    let names: Vec<String> = creators(biblio).iter().map(Name::uppercase).collect();
+    let authors = match names.len() {
+        0 => None,
+        1 => Some(names[0].clone()),
+        n @ 2..=3 => Some(format!("{} and {}", names[..n - 1].join(", "), names[n - 1])),
+        _ => Some(format!("{} et al", names[0])),
+    };
+
+    let mut parts = Vec::new();
+    if let Some(authors) = authors {
+        parts.push(Part::Text(format!("{} ", sentence(&authors))));
+    }
+    if let Some(title) = title(biblio) {
+        let end = title_end(&title);
+        parts.push(Part::Title(title));
+        parts.push(Part::Text(end.to_string()));
+    }
+    let publication = match (place(biblio), publisher(biblio)) {
+        (Some(place), Some(publisher)) => Some(format!("{}: {}", place, publisher)),
+        (place, publisher) => place.or(publisher),
+    };
+    let publication = match (publication, year(biblio)) {
+        (Some(publication), Some(year)) => Some(format!("{}, {}.", publication, year)),
+        (Some(publication), None) => Some(sentence(&publication)),
+        (None, Some(year)) => Some(format!("{}.", year)),
+        (None, None) => None,
+    };
+    if let Some(publication) = publication {
+        parts.push(Part::Text(format!(" {}", publication)));
+    }
+    if let Some(isbn) = biblio.isbn.as_ref().filter(|i| !i.is_empty()) {
+        parts.push(Part::Text(format!(" ISBN {}.", isbn)));
+    }
+    trim_parts(parts)
+}
+
+/// Drop the space left at the end when nothing follows the authors
+fn trim_parts(mut parts: Vec<Part>) -> Vec<Part> {
+    if let Some(Part::Text(last)) = parts.last_mut() {
+        let trimmed = last.trim_end().len();
+        last.truncate(trimmed);
+    }
+    parts
+}
+
+fn is_book(media_type: &MediaType) -> bool {
+    matches!(media_type, MediaType::PrintedText | MediaType::Comics | MediaType::Unknown)
+}
+
+fn ris_type(media_type: &MediaType) -> &'static str {
+    match media_type {
+        MediaType::PrintedText | MediaType::Comics | MediaType::Unknown => "BOOK",
+        MediaType::Periodic => "JFULL",
+        MediaType::Video | MediaType::VideoTape | MediaType::VideoDvd => "VIDEO",
+        MediaType::AudioMusic | MediaType::AudioMusicTape | MediaType::AudioMusicCd => "MUSIC",
+        MediaType::Audio | MediaType::AudioNonMusic | MediaType::AudioNonMusicTape | MediaType::AudioNonMusicCd => "SOUND",
+        MediaType::CdRom | MediaType::Multimedia => "MULTI",
+        MediaType::Images => "ART",
+        MediaType::All | MediaType::Other(_) => "GEN",
+    }
+}
+
+/// Lowercase ASCII letters and digits of `s`, accented letters transliterated ("Lefèvre" → "lefevre")
+fn ascii_key(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.nfd().filter(|c| !unicode_normalization::char::is_combining_mark(*c)) {
+        match c {
+            'æ' | 'Æ' => out.push_str("ae"),
+            'œ' | 'Œ' => out.push_str("oe"),
+            'ß' => out.push_str("ss"),
+            'ø' | 'Ø' => out.push('o'),
+            'ł' | 'Ł' => out.push('l'),
+            'đ' | 'Đ' => out.push('d'),
+            c if c.is_ascii_alphanumeric() => out.push(c.to_ascii_lowercase()),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Characters special to (La)TeX inside a BibTeX value
+fn escape_bibtex(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '&' | '%' | '$' | '#' | '_') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::biblio::{Edition, Isbn};
+    use z3950_rs::marc_rs::record::Record as MarcRecord;
+
+    fn author(lastname: &str, firstname: Option<&str>, function: Option<Function>) -> Author {
+        Author {
+            id: 1,
+            key: None,
+            lastname: Some(lastname.to_string()),
+            firstname: firstname.map(str::to_string),
+            display_name: None,
+            bio: None,
+            notes: None,
+            function,
+        }
+    }
+
+    fn record() -> Biblio {
+        let mut biblio = Biblio::from(MarcRecord::default());
+        biblio.marc_record = None;
+        biblio.id = Some(7);
+        biblio.media_type = MediaType::PrintedText;
+        biblio.title = Some("L'étranger /".to_string());
+        biblio.publication_date = Some("impr. 1942".to_string());
+        biblio.isbn = Some(Isbn::new("978-2-07-036002-4"));
+        biblio.authors = vec![
+            author("Camus", Some("Albert"), Some(Function::Author)),
+            author("Jaunet", Some("Jean-Paul"), Some(Function::Illustrator)),
+        ];
+        biblio.edition = Some(Edition {
+            id: None,
+            publisher_name: Some("Gallimard".to_string()),
+            place_of_publication: Some("Paris :".to_string()),
+            date: None,
+            created_at: None,
+            updated_at: None,
+        });
+        biblio
+    }
+
+    #[test]
+    fn text_styles() {
+        let biblio = record();
+        let apa = citation(&biblio, CitationStyle::Apa);
+        assert_eq!(apa.text, "Camus, A. (1942). L'étranger. Gallimard.");
+        assert_eq!(apa.html, "Camus, A. (1942). <i>L&#39;étranger</i>. Gallimard.");
+        assert_eq!(citation(&biblio, CitationStyle::Mla).text, "Camus, Albert. L'étranger. Gallimard, 1942.");
+        assert_eq!(
+            citation(&biblio, CitationStyle::Iso690).text,
+            "CAMUS, Albert. L'étranger. Paris: Gallimard, 1942. ISBN 9782070360024."
+        );
+    }
+
+    #[test]
+    fn several_or_no_authors() {
+        let mut biblio = record();
+        biblio.authors = vec![
+            author("Deleuze", Some("Gilles"), None),
+            author("Guattari", Some("Félix"), Some(Function::Author)),
+        ];
+        assert_eq!(
+            citation(&biblio, CitationStyle::Apa).text,
+            "Deleuze, G., & Guattari, F. (1942). L'étranger. Gallimard."
+        );
+        assert_eq!(
+            citation(&biblio, CitationStyle::Mla).text,
+            "Deleuze, Gilles, and Félix Guattari. L'étranger. Gallimard, 1942."
+        );
+
+        biblio.authors.clear();
+        biblio.publication_date = None;
+        assert_eq!(citation(&biblio, CitationStyle::Apa).text, "L'étranger. (n.d.). Gallimard.");
+        assert_eq!(initials("Jean-Paul"), "J.-P.");
+        assert_eq!(initials("john ronald reuel"), "J. R. R.");
+    }
+
+    #[test]
+    fn reference_manager_formats() {
+        let biblio = record();
+        assert_eq!(
+            bibtex(&biblio),
+            "@book{camus1942,\n  author = {Camus, Albert},\n  title = {L'étranger},\n  publisher = {Gallimard},\n  address = {Paris},\n  year = {1942},\n  isbn = {9782070360024},\n}\n"
+        );
+        assert_eq!(
+            ris(&biblio),
+            "TY  - BOOK\r\nAU  - Camus, Albert\r\nTI  - L'étranger\r\nPY  - 1942\r\nPB  - Gallimard\r\nCY  - Paris\r\nSN  - 9782070360024\r\nER  - \r\n"
+        );
+        assert_eq!(escape_bibtex("Fish & Chips 100%"), "Fish \\& Chips 100\\%");
+    }
+
+    #[test]
+    fn bibtex_keys_transliterate_accents() {
+        let mut biblio = record();
+        biblio.authors = vec![author("Lefèvre", Some("Bérénice"), None)];
+        assert!(bibtex(&biblio).starts_with("@book{lefevre1942,\n"));
+        assert_eq!(ascii_key("Çelebi-Œhlenschläger"), "celebioehlenschlager");
+        assert_eq!(ascii_key("Żółć"), "zolc");
+
+        biblio.authors = vec![author("北野", None, None)];
+        assert!(bibtex(&biblio).starts_with("@book{record71942,\n"));
+    }
+}
@@ -0,0 +1,222 @@
+//! Currency of the library (`[currency]`): parsing and validation of copy prices, and the one
+//! monetary format used by exports and receipts.
+//!
+//! Copy prices are stored as text in a canonical form (`12.50`, as many decimals as the currency
+//! has). Older rows and imports may hold free text (`12,50 €`, `env. 15 EUR`): they are kept as
+//! they are and left out of the amounts computed from prices.
+
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+
+use crate::{
+    config::CurrencyConfig,
+    error::{AppError, AppResult},
+};
+
+#[derive(Debug, Clone)]
+pub struct Currency {
+    config: CurrencyConfig,
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Self::new(CurrencyConfig::default())
+    }
+}
+
+impl Currency {
+    pub fn new(config: CurrencyConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn code(&self) -> &str {
+        &self.config.code
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.config.symbol
+    }
+
+    pub fn decimals(&self) -> u32 {
+        self.config.decimals
+    }
+
+    /// Parse a price typed by staff or read from an import: `12.5`, `12,50`, `12,50 €`,
+    /// `EUR 12.50`, `1 234,50`. Negative prices, more decimals than the currency has and
+    /// amounts above `max_price` are rejected (field `price`).
+    pub fn parse_price(&self, input: &str) -> AppResult<Decimal> {
+        let invalid = || {
+            AppError::invalid_field(
+                "price",
+                "format",
+                format!("Price must be an amount such as 12{}50 (got '{}')", self.config.decimal_separator, input),
+            )
+        };
+        let mut text = input.trim();
+        for affix in [self.config.symbol.as_str(), self.config.code.as_str()] {
+            if affix.is_empty() {
+                continue;
+            }
+            if let Some(rest) = strip_prefix_ignore_case(text, affix) {
+                text = rest.trim_start();
+            } else if let Some(rest) = strip_suffix_ignore_case(text, affix) {
+                text = rest.trim_end();
+            }
+        }
+        // Thousands are only grouped with spaces: with `.` or `,` they would read as decimals
+        let digits: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        if digits.starts_with('-') {
+            return Err(AppError::invalid_field("price", "negative", "Price cannot be negative"));
+        }
+        let digits = digits.replace(',', ".");
+        let (units, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+        let well_formed = !units.is_empty()
+            && units.chars().all(|c| c.is_ascii_digit())
+            && fraction.chars().all(|c| c.is_ascii_digit())
+            && !(digits.contains('.') && fraction.is_empty());
+        if !well_formed {
+            return Err(invalid());
+        }
+        if fraction.len() > self.config.decimals as usize {
+            return Err(AppError::invalid_field(
+                "price",
+                "decimals",
+                format!("Price has at most {} decimal(s) in {}", self.config.decimals, self.config.code),
+            ));
+        }
+        let amount = Decimal::from_str(&digits).map_err(|_| invalid())?;
+        if amount > Decimal::from(self.config.max_price) {
+            return Err(AppError::invalid_field(
+                "price",
+                "too_high",
+                format!("Price cannot exceed {}", self.format(Decimal::from(self.config.max_price))),
+            ));
+        }
+        Ok(amount)
+    }
+
+    /// Stored form of a price (`12.50`); blank clears it
+    pub fn normalize_price(&self, input: Option<&str>) -> AppResult<Option<String>> {
+        match input.map(str::trim) {
+            None => Ok(None),
+            Some("") => Ok(Some(String::new())),
+            Some(text) => Ok(Some(self.canonical(self.parse_price(text)?))),
+        }
+    }
+
+    /// Amount of a stored price, `None` for blank or free-text legacy values
+    pub fn stored_price(&self, price: &str) -> Option<Decimal> {
+        self.parse_price(price).ok()
+    }
+
+    /// `12,50`: the amount with the configured decimals and separator, without symbol
+    /// (spreadsheet columns, accounting files)
+    pub fn format_amount(&self, amount: Decimal) -> String {
+        self.canonical(amount).replace('.', &self.config.decimal_separator)
+    }
+
+    /// `12,50 €` / `$12.50`
+    pub fn format(&self, amount: Decimal) -> String {
+        let amount = self.format_amount(amount);
+        if self.config.symbol.is_empty() {
+            format!("{} {}", amount, self.config.code)
+        } else if self.config.symbol_before {
+            format!("{}{}", self.config.symbol, amount)
+        } else {
+            format!("{} {}", amount, self.config.symbol)
+        }
+    }
+
+    /// Price column of exports: [`Self::format_amount`] of a stored price, free text as it is
+    pub fn format_price(&self, price: &str) -> String {
+        match self.stored_price(price) {
+            Some(amount) => self.format_amount(amount),
+            None => price.to_string(),
+        }
+    }
+
+    /// `12.50`: rounded to the currency's decimals, always written with all of them
+    fn canonical(&self, amount: Decimal) -> String {
+        let mut amount = amount.round_dp(self.config.decimals);
+        amount.rescale(self.config.decimals);
+        amount.to_string()
+    }
+}
+
+fn strip_prefix_ignore_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = text.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix).then(|| &text[prefix.len()..])
+}
+
+fn strip_suffix_ignore_case<'a>(text: &'a str, suffix: &str) -> Option<&'a str> {
+    let start = text.len().checked_sub(suffix.len())?;
+    let tail = text.get(start..)?;
+    tail.eq_ignore_ascii_case(suffix).then(|| &text[..start])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(result: AppResult<Decimal>) -> String {
+        match result {
+            Err(AppError::InvalidFields(fields)) => fields["price"][0].code.clone(),
+            other => panic!("expected a price error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn prices_are_parsed_leniently() {
+        let eur = Currency::default();
+        for input in ["12.5", "12,50", "12,50 €", "12.50€", "EUR 12.50", "12.50 eur", " 12 "] {
+            let expected = if input.trim() == "12" { Decimal::new(12, 0) } else { Decimal::new(1250, 2) };
+            assert_eq!(eur.parse_price(input).unwrap(), expected, "{}", input);
+        }
+        assert_eq!(eur.parse_price("1 234,50").unwrap(), Decimal::new(123450, 2));
+        assert_eq!(eur.normalize_price(Some("12,5 €")).unwrap().as_deref(), Some("12.50"));
+        assert_eq!(eur.normalize_price(Some("  ")).unwrap().as_deref(), Some(""));
+        assert_eq!(eur.normalize_price(None).unwrap(), None);
+    }
+
+    #[test]
+    fn negative_or_absurd_prices_are_rejected() {
+        let eur = Currency::default();
+        assert_eq!(code(eur.parse_price("-3")), "negative");
+        assert_eq!(code(eur.parse_price("12.505")), "decimals");
+        assert_eq!(code(eur.parse_price("9782070360024")), "too_high");
+        assert_eq!(code(eur.parse_price("1.234,50")), "format");
+        assert_eq!(code(eur.parse_price("env. 15")), "format");
+        assert_eq!(code(eur.parse_price("12.")), "format");
+        assert_eq!(eur.stored_price("gratuit"), None);
+
+        let yen = Currency::new(CurrencyConfig {
+            code: "JPY".to_string(),
+            symbol: "¥".to_string(),
+            decimals: 0,
+            symbol_before: true,
+            max_price: 1_000_000,
+            ..CurrencyConfig::default()
+        });
+        assert_eq!(yen.parse_price("¥1500").unwrap(), Decimal::new(1500, 0));
+        assert_eq!(code(yen.parse_price("1500.5")), "decimals");
+    }
+
+    #[test]
+    fn amounts_are_formatted_with_the_configured_currency() {
+        let eur = Currency::default();
+        assert_eq!(eur.format(Decimal::new(125, 1)), "12,50 €");
+        assert_eq!(eur.format_amount(Decimal::new(-3, 0)), "-3,00");
+        assert_eq!(eur.format_price("12.50"), "12,50");
+        assert_eq!(eur.format_price("env. 15 EUR"), "env. 15 EUR");
+
+        let usd = Currency::new(CurrencyConfig {
+            code: "USD".to_string(),
+            symbol: "$".to_string(),
+            decimal_separator: ".".to_string(),
+            symbol_before: true,
+            ..CurrencyConfig::default()
+        });
+        assert_eq!(usd.format(Decimal::new(1999, 2)), "$19.99");
+    }
+}
@@ -0,0 +1,166 @@
+//! Personal dashboards service: per-user widget layouts over the statistics endpoints
+
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::{
+    api::stats::{CatalogStatsQuery, LoanStatsQuery, StatsQuery, UserStatsQuery},
+    error::{AppError, AppResult},
+    models::{
+        dashboard::{
+            widget_params, Dashboard, DashboardWidget, DashboardWidgetType, DashboardWidgetWrite,
+            SavedStatsRef,
+        },
+        visitor_count::VisitorStatsQuery,
+    },
+    repository::DashboardsRepository,
+};
+
+/// Most widgets on one dashboard (each one costs queries when the dashboard data is loaded)
+pub const MAX_DASHBOARD_WIDGETS: usize = 24;
+
+/// Longest widget title, in characters
+const MAX_TITLE_CHARS: usize = 200;
+
+#[derive(Clone)]
+pub struct DashboardsService {
+    repository: Arc<dyn DashboardsRepository>,
+}
+
+impl DashboardsService {
+    pub fn new(repository: Arc<dyn DashboardsRepository>) -> Self {
+        Self { repository }
+    }
+
+    pub async fn get(&self, user_id: i64) -> AppResult<Dashboard> {
+        Ok(Dashboard {
+            widgets: self.repository.dashboards_list_widgets(user_id).await?,
+        })
+    }
+
+    /// Replace the whole layout, widgets in the given order
+    #[tracing::instrument(skip(self, widgets), err)]
+    pub async fn save(&self, user_id: i64, widgets: &[DashboardWidgetWrite]) -> AppResult<Dashboard> {
+        if widgets.len() > MAX_DASHBOARD_WIDGETS {
+            return Err(too_many_widgets());
+        }
+        let widgets = widgets.iter().map(normalize).collect::<AppResult<Vec<_>>>()?;
+        Ok(Dashboard {
+            widgets: self.repository.dashboards_replace(user_id, &widgets).await?,
+        })
+    }
+
+    /// Remove every widget
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reset(&self, user_id: i64) -> AppResult<()> {
+        self.repository.dashboards_replace(user_id, &[]).await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn add_widget(&self, user_id: i64, data: &DashboardWidgetWrite) -> AppResult<DashboardWidget> {
+        let data = normalize(data)?;
+        if self.repository.dashboards_count_widgets(user_id).await? >= MAX_DASHBOARD_WIDGETS as i64 {
+            return Err(too_many_widgets());
+        }
+        self.repository.dashboards_add_widget(user_id, &data).await
+    }
+
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn update_widget(&self, user_id: i64, id: i64, data: &DashboardWidgetWrite) -> AppResult<DashboardWidget> {
+        let data = normalize(data)?;
+        self.repository.dashboards_update_widget(user_id, id, &data).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete_widget(&self, user_id: i64, id: i64) -> AppResult<()> {
+        self.repository.dashboards_delete_widget(user_id, id).await
+    }
+}
+
+fn too_many_widgets() -> AppError {
+    AppError::Validation(format!(
+        "A dashboard has at most {} widgets",
+        MAX_DASHBOARD_WIDGETS
+    ))
+}
+
+/// Trim the title and check that `params` is a valid query of the widget's endpoint
+fn normalize(data: &DashboardWidgetWrite) -> AppResult<DashboardWidgetWrite> {
+    let title = data.title.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    if title.is_some_and(|t| t.chars().count() > MAX_TITLE_CHARS) {
+        return Err(AppError::Validation(format!(
+            "title must be at most {} characters",
+            MAX_TITLE_CHARS
+        )));
+    }
+    let params = match &data.params {
+        Value::Null => Value::Object(Default::default()),
+        params @ Value::Object(_) => params.clone(),
+        _ => return Err(AppError::Validation("params must be an object".to_string())),
+    };
+    let checked = match data.widget_type {
+        DashboardWidgetType::Stats => widget_params::<StatsQuery>(&params).map(drop),
+        DashboardWidgetType::LoanStats => widget_params::<LoanStatsQuery>(&params).map(drop),
+        DashboardWidgetType::UserStats => widget_params::<UserStatsQuery>(&params).map(drop),
+        DashboardWidgetType::CatalogStats => widget_params::<CatalogStatsQuery>(&params).map(drop),
+        DashboardWidgetType::VisitorStats => widget_params::<VisitorStatsQuery>(&params).map(drop),
+        DashboardWidgetType::SavedQuery | DashboardWidgetType::SavedReport => {
+            widget_params::<SavedStatsRef>(&params).map(drop)
+        }
+    };
+    checked.map_err(|e| {
+        AppError::Validation(format!(
+            "Invalid params for a {} widget: {}",
+            data.widget_type.as_str(),
+            e
+        ))
+    })?;
+    Ok(DashboardWidgetWrite {
+        widget_type: data.widget_type,
+        title: title.map(str::to_string),
+        params,
+        layout: data.layout.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::repository::dashboards::MockDashboardsRepository;
+
+    fn widget(widget_type: DashboardWidgetType, params: Value) -> DashboardWidgetWrite {
+        DashboardWidgetWrite { widget_type, title: Some("  Loans  ".to_string()), params, layout: None }
+    }
+
+    #[test]
+    fn params_are_checked_against_the_endpoint_query() {
+        let loans = normalize(&widget(DashboardWidgetType::LoanStats, json!({ "interval": "month" }))).unwrap();
+        assert_eq!(loans.title.as_deref(), Some("Loans"));
+        assert_eq!(normalize(&widget(DashboardWidgetType::Stats, Value::Null)).unwrap().params, json!({}));
+        assert!(normalize(&widget(DashboardWidgetType::SavedQuery, json!({ "id": "12" }))).is_ok());
+
+        for (widget_type, params) in [
+            (DashboardWidgetType::LoanStats, json!({ "interval": "fortnight" })),
+            (DashboardWidgetType::SavedReport, json!({})),
+            (DashboardWidgetType::CatalogStats, json!(["byMediaType"])),
+        ] {
+            assert!(matches!(normalize(&widget(widget_type, params)), Err(AppError::Validation(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn dashboards_are_capped() {
+        let mut repo = MockDashboardsRepository::new();
+        repo.expect_dashboards_count_widgets()
+            .returning(|_| Ok(MAX_DASHBOARD_WIDGETS as i64));
+        let service = DashboardsService::new(Arc::new(repo));
+        let data = widget(DashboardWidgetType::Stats, json!({}));
+        assert!(matches!(service.add_widget(3, &data).await, Err(AppError::Validation(_))));
+        let layout = vec![data; MAX_DASHBOARD_WIDGETS + 1];
+        assert!(matches!(service.save(3, &layout).await, Err(AppError::Validation(_))));
+    }
+}
@@ -0,0 +1,177 @@
+//! Deposit collections service: rotating deposits lent by another library
+
+use std::sync::Arc;
+
+use chrono::{Local, NaiveDate};
+
+use crate::{
+    error::{AppError, AppResult},
+    models::deposit::{CreateDeposit, Deposit, DepositCloseReport, DepositCopy, DepositQuery, UpdateDeposit},
+    repository::DepositsRepository,
+};
+
+#[derive(Clone)]
+pub struct DepositsService {
+    repository: Arc<dyn DepositsRepository>,
+}
+
+impl DepositsService {
+    pub fn new(repository: Arc<dyn DepositsRepository>) -> Self {
+        Self { repository }
+    }
+
+    pub async fn list(&self, query: &DepositQuery) -> AppResult<Vec<Deposit>> {
+        self.repository.deposits_list(query).await
+    }
+
+    pub async fn get(&self, id: i64) -> AppResult<Deposit> {
+        self.repository.deposits_get(id).await
+    }
+
+    /// Register a deposit batch
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create(&self, data: &CreateDeposit, created_by: i64) -> AppResult<Deposit> {
+        let received_at = data.received_at.unwrap_or_else(|| Local::now().date_naive());
+        check_dates(received_at, data.return_due)?;
+        let data = CreateDeposit {
+            owner: normalize_owner(&data.owner)?,
+            label: data.label.clone(),
+            received_at: Some(received_at),
+            return_due: data.return_due,
+            notes: data.notes.clone(),
+        };
+        self.repository.deposits_create(&data, created_by).await
+    }
+
+    /// Update an active deposit (e.g. extend its return deadline)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update(&self, id: i64, data: &UpdateDeposit) -> AppResult<Deposit> {
+        let deposit = self.active(id).await?;
+        if let Some(return_due) = data.return_due {
+            check_dates(deposit.received_at, return_due)?;
+        }
+        let data = UpdateDeposit {
+            owner: data.owner.as_deref().map(normalize_owner).transpose()?,
+            label: data.label.clone(),
+            return_due: data.return_due,
+            notes: data.notes.clone(),
+        };
+        self.repository.deposits_update(id, &data).await
+    }
+
+    pub async fn delete(&self, id: i64) -> AppResult<()> {
+        self.repository.deposits_delete(id).await
+    }
+
+    /// Get a deposit that can still receive copies
+    pub async fn active(&self, id: i64) -> AppResult<Deposit> {
+        let deposit = self.repository.deposits_get(id).await?;
+        if !deposit.is_active() {
+            return Err(AppError::BusinessRule(format!("Deposit {} is closed", id)));
+        }
+        Ok(deposit)
+    }
+
+    /// Return manifest: every copy still in the catalog, on loan or not
+    pub async fn manifest(&self, id: i64) -> AppResult<(Deposit, String)> {
+        let deposit = self.repository.deposits_get(id).await?;
+        let copies = self.repository.deposits_copies(id).await?;
+        Ok((deposit, manifest_csv(&copies)))
+    }
+
+    /// End a deposit: archive the copies on the shelf and list those still on loan
+    #[tracing::instrument(skip(self), err)]
+    pub async fn close(&self, id: i64, closed_by: i64) -> AppResult<DepositCloseReport> {
+        self.active(id).await?;
+        let archived = self.repository.deposits_close(id, closed_by).await?;
+        let on_loan = self.repository.deposits_copies(id).await?;
+        Ok(DepositCloseReport { deposit_id: id, archived, on_loan })
+    }
+}
+
+fn normalize_owner(owner: &str) -> AppResult<String> {
+    let owner = owner.trim();
+    if owner.is_empty() {
+        return Err(AppError::Validation("owner cannot be empty".to_string()));
+    }
+    Ok(owner.to_string())
+}
+
+fn check_dates(received_at: NaiveDate, return_due: NaiveDate) -> AppResult<()> {
+    if return_due < received_at {
+        return Err(AppError::Validation("returnDue cannot be before receivedAt".to_string()));
+    }
+    Ok(())
+}
+
+/// `;`-separated manifest with CRLF line endings; due dates in library local time
+fn manifest_csv(copies: &[DepositCopy]) -> String {
+    let mut csv = String::from("barcode;call_number;title;author;isbn;on_loan;due_date\r\n");
+    for copy in copies {
+        let fields = [
+            copy.barcode.clone().unwrap_or_default(),
+            copy.call_number.clone().unwrap_or_default(),
+            copy.title.clone().unwrap_or_default(),
+            copy.author.clone().unwrap_or_default(),
+            copy.isbn.clone().unwrap_or_default(),
+            if copy.loan_id.is_some() { "yes" } else { "no" }.to_string(),
+            copy.due_at
+                .map(|d| d.with_timezone(&Local).format("%d/%m/%Y").to_string())
+                .unwrap_or_default(),
+        ];
+        let line: Vec<String> = fields.iter().map(|field| manifest_field(field)).collect();
+        csv.push_str(&line.join(";"));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// Quote a field when it contains the separator, a quote or a line break
+fn manifest_field(s: &str) -> String {
+    if s.contains([';', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn copy(barcode: &str, title: &str, loan_id: Option<i64>) -> DepositCopy {
+        DepositCopy {
+            item_id: 1,
+            barcode: Some(barcode.to_string()),
+            call_number: None,
+            title: Some(title.to_string()),
+            author: None,
+            isbn: None,
+            loan_id,
+            due_at: loan_id.map(|_| chrono::Utc::now()),
+        }
+    }
+
+    #[test]
+    fn manifest_flags_copies_on_loan_and_quotes_separators() {
+        let csv = manifest_csv(&[copy("D1", "Paris; la ville", None), copy("D2", "Lyon", Some(4))]);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("D1;;\"Paris; la ville\";"));
+        assert!(lines[1].ends_with(";no;"));
+        assert!(lines[2].contains(";yes;"));
+    }
+
+    #[tokio::test]
+    async fn create_rejects_return_date_before_reception() {
+        let service = DepositsService::new(Arc::new(crate::repository::deposits::MockDepositsRepository::new()));
+        let data = CreateDeposit {
+            owner: "BDP".to_string(),
+            label: None,
+            received_at: NaiveDate::from_ymd_opt(2026, 3, 1),
+            return_due: NaiveDate::from_ymd_opt(2026, 2, 1).unwrap(),
+            notes: None,
+        };
+        assert!(matches!(service.create(&data, 1).await, Err(AppError::Validation(_))));
+    }
+}
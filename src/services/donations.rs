@@ -0,0 +1,182 @@
+//! Donations intake service: recording donated books, triage and donors report
+
+use std::sync::Arc;
+
+use rust_decimal::Decimal;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{
+        donation::{
+            CreateDonation, CreateDonationItem, Donation, DonationItem, DonationQuery,
+            DonationTriage, DonorsReport, TriageDonationItem, UpdateDonation, DONATION_SOURCE_NAME,
+        },
+        item::Item,
+    },
+    repository::DonationsRepository,
+    services::catalog::CatalogService,
+};
+
+#[derive(Clone)]
+pub struct DonationsService {
+    repository: Arc<dyn DonationsRepository>,
+    catalog: CatalogService,
+}
+
+impl DonationsService {
+    pub fn new(repository: Arc<dyn DonationsRepository>, catalog: CatalogService) -> Self {
+        Self { repository, catalog }
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self, query: &DonationQuery) -> AppResult<Vec<Donation>> {
+        self.repository.donations_list(query).await
+    }
+
+    pub async fn get_by_id(&self, id: i64) -> AppResult<Donation> {
+        self.repository.donations_get_by_id(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create(&self, data: &CreateDonation) -> AppResult<Donation> {
+        validate_donor_name(Some(&data.donor_name))?;
+        validate_estimated_value(data.estimated_value)?;
+        for item in &data.items {
+            validate_item(item)?;
+        }
+        self.repository.donations_create(data).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update(&self, id: i64, data: &UpdateDonation) -> AppResult<Donation> {
+        validate_donor_name(data.donor_name.as_deref())?;
+        validate_estimated_value(data.estimated_value)?;
+        self.repository.donations_update(id, data).await
+    }
+
+    /// Delete a donation; refused once one of its books has been added to the catalog
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete(&self, id: i64) -> AppResult<()> {
+        if self.repository.donations_count_cataloged(id).await? > 0 {
+            return Err(AppError::Conflict(
+                "Donation has books added to the catalog and cannot be deleted".to_string(),
+            ));
+        }
+        self.repository.donations_delete(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn add_item(&self, donation_id: i64, data: &CreateDonationItem) -> AppResult<DonationItem> {
+        validate_item(data)?;
+        self.repository.donations_get_by_id(donation_id).await?;
+        self.repository.donations_add_item(donation_id, data).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete_item(&self, donation_id: i64, line_id: i64) -> AppResult<()> {
+        let line = self.repository.donations_get_item(donation_id, line_id).await?;
+        if line.triage == DonationTriage::Catalog {
+            return Err(AppError::Conflict(
+                "Book was added to the catalog and cannot be removed from the donation".to_string(),
+            ));
+        }
+        self.repository.donations_delete_item(donation_id, line_id).await
+    }
+
+    /// Apply a triage decision. Accepting a book into the catalog creates a copy on `biblio_id`
+    /// with source `donation`; the created copy is returned alongside the updated line.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn triage(
+        &self,
+        donation_id: i64,
+        line_id: i64,
+        data: &TriageDonationItem,
+    ) -> AppResult<(DonationItem, Option<Item>)> {
+        let line = self.repository.donations_get_item(donation_id, line_id).await?;
+        if line.triage == DonationTriage::Catalog {
+            return Err(AppError::Conflict(
+                "Book was already added to the catalog".to_string(),
+            ));
+        }
+
+        if data.decision != DonationTriage::Catalog {
+            let line = self
+                .repository
+                .donations_set_triage(line_id, data.decision, None, None)
+                .await?;
+            return Ok((line, None));
+        }
+
+        let biblio_id = data.biblio_id.ok_or_else(|| {
+            AppError::Validation("biblioId is required to add a donated book to the catalog".to_string())
+        })?;
+        let donation = self.repository.donations_get_by_id(donation_id).await?;
+        let item = Item {
+            id: None,
+            biblio_id: Some(biblio_id),
+            source_id: None,
+            barcode: data.barcode.as_deref().map(str::trim).filter(|b| !b.is_empty()).map(String::from),
+            call_number: data.call_number.clone(),
+            volume_designation: None,
+            place: None,
+            borrowable: true,
+            circulation_status: None,
+            notes: Some(format!("Donation from {} ({})", donation.donor_name, donation.received_at)),
+            price: None,
+            access_url: None,
+            access_type: None,
+            created_at: None,
+            updated_at: None,
+            archived_at: None,
+            source_name: Some(DONATION_SOURCE_NAME.to_string()),
+            borrowed: false,
+            deposit_id: None,
+            transit_place: None,
+            inventory_number: None,
+            location_id: None,
+            location_path: None,
+            hidden_from_opac: false,
+        };
+        let created = self.catalog.create_item(biblio_id, item).await?;
+        let line = self
+            .repository
+            .donations_set_triage(line_id, DonationTriage::Catalog, Some(biblio_id), created.id)
+            .await?;
+        Ok((line, Some(created)))
+    }
+
+    /// Donations received during `year`, aggregated per donor
+    #[tracing::instrument(skip(self), err)]
+    pub async fn donors_report(&self, year: i32) -> AppResult<DonorsReport> {
+        let donors = self.repository.donations_donors_report(year).await?;
+        let total_books = donors.iter().map(|d| d.books).sum();
+        let total_estimated_value = donors.iter().map(|d| d.estimated_value).sum();
+        Ok(DonorsReport {
+            year,
+            donors,
+            total_books,
+            total_estimated_value,
+        })
+    }
+}
+
+fn validate_donor_name(name: Option<&str>) -> AppResult<()> {
+    if name.is_some_and(|n| n.trim().is_empty()) {
+        return Err(AppError::Validation("donorName cannot be empty".to_string()));
+    }
+    Ok(())
+}
+
+fn validate_estimated_value(value: Option<Decimal>) -> AppResult<()> {
+    if value.is_some_and(|v| v.is_sign_negative()) {
+        return Err(AppError::Validation("estimatedValue cannot be negative".to_string()));
+    }
+    Ok(())
+}
+
+fn validate_item(item: &CreateDonationItem) -> AppResult<()> {
+    if item.title.trim().is_empty() {
+        return Err(AppError::Validation("Donated book title cannot be empty".to_string()));
+    }
+    Ok(())
+}
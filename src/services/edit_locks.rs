@@ -0,0 +1,131 @@
+//! Advisory edit locks on bibliographic records, kept in Redis.
+//!
+//! A cataloger opening a record takes its lock for [`EDIT_LOCK_TTL_SECS`] and renews it while the
+//! editor stays open (heartbeat); others see who holds it and are refused the lock, not the update.
+
+use chrono::{TimeZone, Utc};
+use crate::{
+    error::{AppError, AppResult},
+    models::biblio::EditLock,
+    services::redis::RedisService,
+};
+
+/// Lifetime of a lock without heartbeat
+pub const EDIT_LOCK_TTL_SECS: i64 = 120;
+const EDIT_LOCK_PREFIX: &str = "elidune:edit_lock:biblio:";
+
+/// Holder fields of a lock hash (user_id, login, acquired_at)
+type LockFields = (Option<i64>, Option<String>, Option<i64>);
+
+/// Take the lock when free or already ours (keeping its acquisition time), and extend it.
+const ACQUIRE_SCRIPT: &str = r#"
+local owner = redis.call('HGET', KEYS[1], 'user_id')
+if owner and owner ~= ARGV[1] then
+    return 0
+end
+if not owner then
+    redis.call('HSET', KEYS[1], 'user_id', ARGV[1], 'login', ARGV[2], 'acquired_at', ARGV[3])
+end
+redis.call('EXPIRE', KEYS[1], ARGV[4])
+return 1
+"#;
+
+/// Delete the lock when it is ours (or on force); 0 when someone else holds it.
+const RELEASE_SCRIPT: &str = r#"
+local owner = redis.call('HGET', KEYS[1], 'user_id')
+if owner and owner ~= ARGV[1] and ARGV[2] ~= '1' then
+    return 0
+end
+redis.call('DEL', KEYS[1])
+return 1
+"#;
+
+#[derive(Clone)]
+pub struct EditLocksService {
+    redis: RedisService,
+}
+
+impl EditLocksService {
+    pub fn new(redis: RedisService) -> Self {
+        Self { redis }
+    }
+
+    fn key(biblio_id: i64) -> String {
+        format!("{}{}", EDIT_LOCK_PREFIX, biblio_id)
+    }
+
+    /// Current lock of a record, if any
+    pub async fn get(&self, biblio_id: i64) -> AppResult<Option<EditLock>> {
+        let mut conn = self.redis.get_connection().await?;
+        let key = Self::key(biblio_id);
+        let ((owner, login, acquired_at), ttl): (LockFields, i64) = redis::pipe()
+            .cmd("HMGET")
+            .arg(&key)
+            .arg("user_id")
+            .arg("login")
+            .arg("acquired_at")
+            .ttl(&key)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to read edit lock from Redis: {}", e)))?;
+        let (Some(user_id), Some(acquired_at)) = (owner, acquired_at) else {
+            return Ok(None);
+        };
+        let now = Utc::now();
+        Ok(Some(EditLock {
+            biblio_id,
+            user_id,
+            login: login.unwrap_or_default(),
+            acquired_at: Utc.timestamp_opt(acquired_at, 0).single().unwrap_or(now),
+            expires_at: now + chrono::Duration::seconds(ttl.max(0)),
+        }))
+    }
+
+    /// Take or renew the lock of a record; 409 with the holder when another user has it.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn acquire(&self, biblio_id: i64, user_id: i64, login: &str) -> AppResult<EditLock> {
+        let mut conn = self.redis.get_connection().await?;
+        let acquired: i64 = redis::Script::new(ACQUIRE_SCRIPT)
+            .key(Self::key(biblio_id))
+            .arg(user_id)
+            .arg(login)
+            .arg(Utc::now().timestamp())
+            .arg(EDIT_LOCK_TTL_SECS)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to take edit lock in Redis: {}", e)))?;
+        let lock = self.get(biblio_id).await?;
+        match (acquired, lock) {
+            (1, Some(lock)) => Ok(lock),
+            (_, Some(lock)) => Err(AppError::Conflict(format!(
+                "Record {} is being edited by {} (lock expires at {})",
+                biblio_id,
+                lock.login,
+                lock.expires_at.to_rfc3339()
+            ))),
+            // Lapsed between the two calls
+            (_, None) => Err(AppError::Conflict(format!("Record {} lock changed, retry", biblio_id))),
+        }
+    }
+
+    /// Release one's own lock, or anyone's with `force`; releasing a free record is a no-op.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn release(&self, biblio_id: i64, user_id: i64, force: bool) -> AppResult<Option<EditLock>> {
+        let previous = self.get(biblio_id).await?;
+        let mut conn = self.redis.get_connection().await?;
+        let released: i64 = redis::Script::new(RELEASE_SCRIPT)
+            .key(Self::key(biblio_id))
+            .arg(user_id)
+            .arg(if force { "1" } else { "0" })
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to release edit lock in Redis: {}", e)))?;
+        if released == 0 {
+            return Err(AppError::Conflict(format!(
+                "Record {} is locked by another user; an administrator can force the unlock",
+                biblio_id
+            )));
+        }
+        Ok(previous)
+    }
+}
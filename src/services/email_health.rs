@@ -0,0 +1,82 @@
+//! Outbound email deliverability: bounces and complaints from the mail provider webhook.
+//!
+//! Every report is counted per address. Hard bounces reaching `email.hard_bounce_threshold` make
+//! the address undeliverable: [`crate::email::EmailService`] refuses to send to it and campaigns
+//! skip it, until staff reset it (e.g. once the patron fixed their mailbox). A complaint withdraws
+//! the campaign consent of the address.
+
+use std::sync::Arc;
+
+use crate::{
+    dynamic_config::DynamicConfig,
+    error::{AppError, AppResult},
+    models::email_health::{EmailFeedbackBatch, EmailFeedbackReport, EmailHealth},
+    repository::EmailHealthRepository,
+    services::webhooks::verify_signature,
+};
+
+/// Events accepted per webhook call
+const MAX_EVENTS: usize = 1000;
+
+#[derive(Clone)]
+pub struct EmailHealthService {
+    repository: Arc<dyn EmailHealthRepository>,
+    dynamic_config: Arc<DynamicConfig>,
+}
+
+impl EmailHealthService {
+    pub fn new(repository: Arc<dyn EmailHealthRepository>, dynamic_config: Arc<DynamicConfig>) -> Self {
+        Self { repository, dynamic_config }
+    }
+
+    /// Check the `X-Email-Signature` header of a webhook call against the raw body
+    pub fn verify_webhook(&self, body: &[u8], signature: Option<&str>) -> AppResult<()> {
+        let config = self.dynamic_config.read_email();
+        let secret = config.bounce_webhook_secret.as_deref().unwrap_or_default();
+        if secret.is_empty() || !verify_signature(secret, body, signature) {
+            return Err(AppError::Authentication("Invalid email webhook signature".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Record the bounces and complaints of a provider callback
+    #[tracing::instrument(skip(self, batch), err)]
+    pub async fn record(&self, batch: &EmailFeedbackBatch) -> AppResult<EmailFeedbackReport> {
+        if batch.events.len() > MAX_EVENTS {
+            return Err(AppError::Validation(format!("At most {} events per call", MAX_EVENTS)));
+        }
+        if let Some(event) = batch.events.iter().find(|e| !e.email.contains('@')) {
+            return Err(AppError::Validation(format!("Invalid email address '{}'", event.email)));
+        }
+
+        let threshold = self.dynamic_config.read_email().hard_bounce_threshold;
+        let mut report = EmailFeedbackReport::default();
+        for event in &batch.events {
+            match self.repository.email_health_record(event, threshold).await? {
+                Some(recorded) => {
+                    report.recorded += 1;
+                    if recorded.became_undeliverable {
+                        tracing::info!(email = %recorded.health.email, "Email address marked undeliverable");
+                        report.undeliverable.push(recorded.health.email);
+                    }
+                }
+                None => report.duplicates += 1,
+            }
+        }
+        Ok(report)
+    }
+
+    /// Health of an address; `None` when nothing was ever reported
+    pub async fn get(&self, email: &str) -> AppResult<Option<EmailHealth>> {
+        self.repository.email_health_get(email).await
+    }
+
+    /// Clear the counters of an address so emails are sent to it again
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reset(&self, email: &str) -> AppResult<()> {
+        if !self.repository.email_health_reset(email).await? {
+            return Err(AppError::NotFound("No bounce or complaint recorded for this address".to_string()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+//! End-of-day job (`[eod]`)
+//!
+//! Closes the circulation day after opening hours: applies the loans archival policy,
+//! finalizes the day's statistics snapshot, prepares the hold pull list PDF for the next
+//! morning and emails a digest to the staff. Steps run in order and a failed step does not
+//! stop the next ones; each run is logged in `eod_runs` (`/admin/eod-runs`).
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+
+use crate::{
+    dynamic_config::DynamicConfig,
+    email::EmailService,
+    error::{AppError, AppResult},
+    models::{
+        artifact::ArtifactKind,
+        eod::{
+            DailyStats, EodRun, EodRunDetails, EodRunStatus, EodStep, EodStepResult, EodStepStatus, EodTrigger,
+        },
+    },
+    repository::EodRepository,
+    services::{
+        artifacts::ArtifactsService,
+        audit::{self, AuditService},
+        exports::ExportsService,
+        holds::HoldsService,
+        loans::LoansService,
+    },
+};
+
+/// Runs returned by default / at most by the run log
+const DEFAULT_RUNS_LIMIT: i64 = 30;
+const MAX_RUNS_LIMIT: i64 = 365;
+
+#[derive(Clone)]
+pub struct EodService {
+    repository: Arc<dyn EodRepository>,
+    loans: LoansService,
+    holds: HoldsService,
+    exports: ExportsService,
+    artifacts: ArtifactsService,
+    email: EmailService,
+    audit: AuditService,
+    dynamic_config: Arc<DynamicConfig>,
+}
+
+impl EodService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        repository: Arc<dyn EodRepository>,
+        loans: LoansService,
+        holds: HoldsService,
+        exports: ExportsService,
+        artifacts: ArtifactsService,
+        email: EmailService,
+        audit: AuditService,
+        dynamic_config: Arc<DynamicConfig>,
+    ) -> Self {
+        Self { repository, loans, holds, exports, artifacts, email, audit, dynamic_config }
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self, limit: Option<i64>) -> AppResult<Vec<EodRun>> {
+        let limit = limit.unwrap_or(DEFAULT_RUNS_LIMIT).clamp(1, MAX_RUNS_LIMIT);
+        self.repository.eod_runs_list(limit).await
+    }
+
+    /// Run with the statistics snapshot of the day it closed
+    #[tracing::instrument(skip(self), err)]
+    pub async fn get(&self, id: i64) -> AppResult<EodRunDetails> {
+        let run = self.repository.eod_runs_get(id).await?;
+        let daily_stats = self.repository.eod_daily_stats_get(run.business_day).await?;
+        Ok(EodRunDetails { run, daily_stats })
+    }
+
+    /// Open a run for today (local date); the caller executes it with [`Self::execute`].
+    /// Fails with a conflict while another run is in progress.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn start(&self, trigger: EodTrigger, triggered_by: Option<i64>) -> AppResult<EodRun> {
+        let today = Local::now().date_naive();
+        self.repository
+            .eod_runs_start(today, trigger, triggered_by)
+            .await?
+            .ok_or_else(|| AppError::Conflict("An end-of-day run is already in progress".to_string()))
+    }
+
+    /// Run the steps, store the outcome and audit it
+    #[tracing::instrument(skip(self, run), fields(run_id = run.id))]
+    pub async fn execute(&self, mut run: EodRun) -> AppResult<EodRun> {
+        let config = self.dynamic_config.read_eod();
+        let day = run.business_day;
+        let mut steps = Vec::with_capacity(4);
+
+        let step = match self.loans.archive_returned().await {
+            Ok(n) => {
+                run.archived_loans = Some(n as i32);
+                succeeded(EodStep::ArchiveLoans, n as i64)
+            }
+            Err(e) => failed(EodStep::ArchiveLoans, e),
+        };
+        steps.push(step);
+
+        let stats = self
+            .repository
+            .eod_daily_stats_upsert(day, local_day_start(day), local_day_start(day + Duration::days(1)))
+            .await;
+        let (step, stats) = match stats {
+            Ok(stats) => (succeeded(EodStep::DailyStats, stats.loans as i64), Some(stats)),
+            Err(e) => (failed(EodStep::DailyStats, e), None),
+        };
+        steps.push(step);
+
+        let step = if !config.pull_list {
+            skipped(EodStep::PullList, "eod.pull_list is disabled")
+        } else {
+            match self.pull_list(day + Duration::days(1), run.triggered_by).await {
+                Ok((entries, artifact_id)) => {
+                    run.pull_list_entries = Some(entries as i32);
+                    run.pull_list_artifact_id = Some(artifact_id);
+                    succeeded(EodStep::PullList, entries as i64)
+                }
+                Err(e) => failed(EodStep::PullList, e),
+            }
+        };
+        steps.push(step);
+
+        let step = if config.digest_recipients.is_empty() {
+            skipped(EodStep::Digest, "no eod.digest_recipients")
+        } else {
+            let (subject, plain) = digest(day, &steps, stats.as_ref(), &run);
+            let html = format!(
+                "<html><body><pre>{}</pre></body></html>",
+                plain.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+            );
+            let mut sent = 0;
+            let mut last_error = None;
+            for to in &config.digest_recipients {
+                match self.email.send_email_with_html(to, &subject, &plain, &html).await {
+                    Ok(()) => sent += 1,
+                    Err(e) => {
+                        tracing::warn!("End-of-day digest to {} failed: {}", to, e);
+                        last_error = Some(e);
+                    }
+                }
+            }
+            run.digest_recipients = Some(sent);
+            match last_error {
+                Some(e) if sent == 0 => failed(EodStep::Digest, e),
+                _ => succeeded(EodStep::Digest, sent as i64),
+            }
+        };
+        steps.push(step);
+
+        run.status = EodRunStatus::from_steps(&steps);
+        run.steps = steps;
+        let run = self.repository.eod_runs_finish(&run).await?;
+
+        let payload = serde_json::json!({
+            "businessDay": run.business_day,
+            "status": run.status.as_str(),
+            "trigger": run.trigger.as_str(),
+            "steps": run.steps,
+        });
+        if run.status == EodRunStatus::Succeeded {
+            self.audit.log(
+                audit::event::EOD_RUN_COMPLETED,
+                run.triggered_by,
+                Some("eod_run"),
+                Some(run.id),
+                None,
+                Some(payload),
+                audit::AuditLogMeta::success(),
+            );
+        } else {
+            let errors: Vec<String> = run
+                .steps
+                .iter()
+                .filter(|s| s.status == EodStepStatus::Failed)
+                .map(|s| format!("{:?}: {}", s.step, s.message.as_deref().unwrap_or("")))
+                .collect();
+            self.audit.log(
+                audit::event::EOD_RUN_FAILED,
+                run.triggered_by,
+                Some("eod_run"),
+                Some(run.id),
+                None,
+                Some(payload),
+                audit::AuditLogMeta::failure_background(run.status.as_str(), errors.join("; ")),
+            );
+        }
+        Ok(run)
+    }
+
+    /// Scheduled run (`eod.run_time`); skipped when a run is already in progress
+    pub async fn run_scheduled(&self) -> AppResult<()> {
+        match self.start(EodTrigger::Scheduled, None).await {
+            Ok(run) => {
+                tracing::info!(run_id = run.id, "Starting end-of-day run");
+                self.execute(run).await.map(|_| ())
+            }
+            Err(AppError::Conflict(msg)) => {
+                tracing::info!("Scheduled end-of-day run skipped: {}", msg);
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Store the pull list PDF for `morning`: `(entries, artifact id)`
+    async fn pull_list(&self, morning: NaiveDate, created_by: Option<i64>) -> AppResult<(usize, i64)> {
+        let groups = self.holds.pull_list().await?;
+        let entries = groups.iter().map(|g| g.entries.len()).sum();
+        let title = format!("Pull list {}", morning.format("%Y-%m-%d"));
+        let pdf = self.exports.hold_shelf_list(&title, &groups)?;
+        let filename = format!("pull-list-{}.pdf", morning.format("%Y-%m-%d"));
+        let artifact = self
+            .artifacts
+            .store_bytes(ArtifactKind::PullList, &filename, "application/pdf", created_by, &pdf)
+            .await?;
+        Ok((entries, artifact.id))
+    }
+}
+
+fn succeeded(step: EodStep, count: i64) -> EodStepResult {
+    EodStepResult { step, status: EodStepStatus::Succeeded, count: Some(count), message: None }
+}
+
+fn skipped(step: EodStep, reason: &str) -> EodStepResult {
+    EodStepResult { step, status: EodStepStatus::Skipped, count: None, message: Some(reason.to_string()) }
+}
+
+fn failed(step: EodStep, error: AppError) -> EodStepResult {
+    tracing::warn!("End-of-day step {:?} failed: {}", step, error);
+    EodStepResult { step, status: EodStepStatus::Failed, count: None, message: Some(error.to_string()) }
+}
+
+/// Subject and plain text body of the daily digest
+fn digest(day: NaiveDate, steps: &[EodStepResult], stats: Option<&DailyStats>, run: &EodRun) -> (String, String) {
+    let subject = format!("End of day {}", day.format("%Y-%m-%d"));
+    let mut plain = format!("End-of-day run #{} for {}\n\n", run.id, day.format("%Y-%m-%d"));
+    if let Some(s) = stats {
+        plain.push_str(&format!(
+            "Loans: {}\nReturns: {}\nNew patrons: {}\nHolds placed: {}\nLoans out: {}\nOverdue: {}\n\n",
+            s.loans, s.returns, s.new_users, s.holds_placed, s.active_loans, s.overdue_loans,
+        ));
+    }
+    if let Some(n) = run.archived_loans {
+        plain.push_str(&format!("Returned loans archived: {}\n", n));
+    }
+    if let Some(n) = run.pull_list_entries {
+        plain.push_str(&format!("Copies on tomorrow's pull list: {}\n", n));
+    }
+    let failures: Vec<&EodStepResult> = steps.iter().filter(|s| s.status == EodStepStatus::Failed).collect();
+    if !failures.is_empty() {
+        plain.push_str("\nFailed steps:\n");
+        for s in failures {
+            plain.push_str(&format!("- {:?}: {}\n", s.step, s.message.as_deref().unwrap_or("")));
+        }
+    }
+    (subject, plain)
+}
+
+/// Start of `date` (local midnight) as a UTC instant
+fn local_day_start(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_lists_stats_and_failed_steps() {
+        let day = NaiveDate::from_ymd_opt(2026, 3, 14).unwrap();
+        let run = EodRun {
+            id: 7,
+            business_day: day,
+            status: EodRunStatus::Running,
+            trigger: EodTrigger::Scheduled,
+            triggered_by: None,
+            steps: Vec::new(),
+            archived_loans: Some(2),
+            pull_list_entries: None,
+            pull_list_artifact_id: None,
+            digest_recipients: None,
+            started_at: Utc::now(),
+            finished_at: None,
+        };
+        let stats = DailyStats {
+            day,
+            loans: 12,
+            returns: 9,
+            new_users: 1,
+            holds_placed: 3,
+            active_loans: 140,
+            overdue_loans: 6,
+            computed_at: Utc::now(),
+        };
+        let steps = [
+            succeeded(EodStep::ArchiveLoans, 2),
+            failed(EodStep::PullList, AppError::Internal("disk full".to_string())),
+        ];
+        let (subject, plain) = digest(day, &steps, Some(&stats), &run);
+        assert_eq!(subject, "End of day 2026-03-14");
+        assert!(plain.contains("Loans: 12\nReturns: 9\n"));
+        assert!(plain.contains("Returned loans archived: 2"));
+        assert!(plain.contains("- PullList: "));
+        assert!(plain.contains("disk full"));
+    }
+}
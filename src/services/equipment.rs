@@ -3,7 +3,7 @@
 use std::sync::Arc;
 
 use crate::{
-    error::AppResult,
+    error::{AppError, AppResult},
     models::equipment::{CreateEquipment, Equipment, UpdateEquipment},
     repository::EquipmentRepository,
 };
@@ -29,17 +29,49 @@ impl EquipmentService {
 
     #[tracing::instrument(skip(self), err)]
     pub async fn create(&self, data: &CreateEquipment) -> AppResult<Equipment> {
+        self.validate_circulation(data.barcode.as_deref(), data.loan_duration_days, data.max_renewals, None)
+            .await?;
         self.repository.equipment_create(data).await
     }
 
     pub async fn update(&self, id: i64, data: &UpdateEquipment) -> AppResult<Equipment> {
+        self.validate_circulation(data.barcode.as_deref(), data.loan_duration_days, data.max_renewals, Some(id))
+            .await?;
         self.repository
             .equipment_update_equipment(id, data)
             .await
     }
 
+    /// Circulation fields: barcode unique across equipment and copies, positive loan period.
+    async fn validate_circulation(
+        &self,
+        barcode: Option<&str>,
+        loan_duration_days: Option<i16>,
+        max_renewals: Option<i16>,
+        exclude_id: Option<i64>,
+    ) -> AppResult<()> {
+        if loan_duration_days.is_some_and(|d| d < 1) {
+            return Err(AppError::Validation("loanDurationDays must be at least 1".to_string()));
+        }
+        if max_renewals.is_some_and(|r| r < 0) {
+            return Err(AppError::Validation("maxRenewals cannot be negative".to_string()));
+        }
+        let Some(barcode) = barcode.map(str::trim).filter(|b| !b.is_empty()) else {
+            return Ok(());
+        };
+        if self.repository.equipment_barcode_in_use(barcode, exclude_id).await? {
+            return Err(AppError::Conflict(format!("Barcode {} is already in use", barcode)));
+        }
+        Ok(())
+    }
+
     #[tracing::instrument(skip(self), err)]
     pub async fn delete(&self, id: i64) -> AppResult<()> {
+        if self.repository.equipment_has_active_loan(id).await? {
+            return Err(AppError::Conflict(
+                "Equipment is currently on loan — return it before deleting".to_string(),
+            ));
+        }
         self.repository.equipment_delete(id).await
     }
 
@@ -3,18 +3,27 @@
 use std::path::Path;
 use std::sync::Arc;
 
+use chrono::NaiveDate;
+
 /// Maximum size for an event attachment (10 MiB).
 pub const MAX_EVENT_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
 
+/// Longest range accepted by `GET /events/conflicts`
+const MAX_CONFLICT_RANGE_DAYS: i64 = 366;
+
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use validator::Validate;
 
 use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
 
 use crate::{
     error::{AppError, AppResult},
     models::{
-        event::{CreateEvent, Event, EventAttachmentInput, EventQuery, UpdateEvent},
+        event::{
+            CancelEvent, CreateEvent, Event, EventAttachmentInput, EventConflict, EventQuery, EventRegistration,
+            EventSlot, EventStatus, UpdateEvent,
+        },
         Language,
     },
     repository::{events::EventAnnualStats, EventsServiceRepository},
@@ -27,10 +36,11 @@ use crate::{
 
 /// Request body for sending an event announcement email.
 /// All fields are optional: if omitted, the default template is used.
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SendAnnouncementRequest {
     /// Override email subject (uses template if absent)
+    #[validate(length(min = 1, max = 255, message = "Subject must be 1 to 255 characters"))]
     pub subject: Option<String>,
     /// Override plain-text body (uses template if absent)
     pub body_plain: Option<String>,
@@ -60,6 +70,15 @@ pub struct AnnouncementReport {
     pub errors: Vec<AnnouncementError>,
 }
 
+/// Result of `POST /events/{id}/cancel`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelEventResponse {
+    pub event: Event,
+    /// Emails sent to registered attendees (all zero when `notify` is `false`)
+    pub notifications: AnnouncementReport,
+}
+
 #[derive(Clone)]
 pub struct EventsService {
     repository: Arc<dyn EventsServiceRepository>,
@@ -96,6 +115,7 @@ impl EventsService {
     #[tracing::instrument(skip(self), err)]
     pub async fn create(&self, data: &CreateEvent) -> AppResult<Event> {
         Self::validate_public_type_name(&*self.repository, data.public_type.as_ref()).await?;
+        self.check_slot(&EventSlot::try_from(data)?, None).await?;
         let attachment = match &data.attachment {
             Some(a) => Some(decode_event_attachment_input(a)?),
             None => None,
@@ -107,6 +127,10 @@ impl EventsService {
     #[tracing::instrument(skip(self), err)]
     pub async fn update(&self, id: i64, data: &UpdateEvent) -> AppResult<Event> {
         Self::validate_public_type_name(&*self.repository, data.public_type.as_ref()).await?;
+        if data.event_date.is_some() || data.start_time.is_some() || data.end_time.is_some() || data.room.is_some() {
+            let current = self.repository.events_get_by_id(id).await?;
+            self.check_slot(&EventSlot::from(&current).updated(data)?, Some(id)).await?;
+        }
         let remove = data.remove_attachment == Some(true);
         let new_attachment = if !remove {
             match &data.attachment {
@@ -146,6 +170,152 @@ impl EventsService {
         self.repository.events_delete(id).await
     }
 
+    /// Make a draft event public
+    #[tracing::instrument(skip(self), err)]
+    pub async fn publish(&self, id: i64) -> AppResult<Event> {
+        match self.repository.events_set_status(id, &[EventStatus::Draft], EventStatus::Published, None).await? {
+            Some(event) => Ok(event),
+            None => Err(self.status_conflict(id, "only drafts can be published").await),
+        }
+    }
+
+    /// Cancel a draft or published event, then email its registered attendees unless
+    /// `notify` is `false`.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn cancel(&self, id: i64, data: &CancelEvent) -> AppResult<CancelEventResponse> {
+        let reason = data.reason.as_deref().map(str::trim).filter(|r| !r.is_empty()).map(str::to_string);
+        let Some(event) = self
+            .repository
+            .events_set_status(id, &[EventStatus::Draft, EventStatus::Published], EventStatus::Cancelled, reason)
+            .await?
+        else {
+            return Err(self.status_conflict(id, "it is already cancelled").await);
+        };
+        let mut notifications = AnnouncementReport { event_id: id, emails_sent: 0, skipped: 0, errors: Vec::new() };
+        if data.notify.unwrap_or(true) {
+            self.notify_cancellation(&event, &mut notifications).await?;
+        }
+        Ok(CancelEventResponse { event, notifications })
+    }
+
+    async fn notify_cancellation(&self, event: &Event, report: &mut AnnouncementReport) -> AppResult<()> {
+        let event_date = event.event_date.format("%d/%m/%Y").to_string();
+        let reason_suffix = event
+            .cancellation_reason
+            .as_deref()
+            .map(|r| format!(" ({})", r))
+            .unwrap_or_default();
+        for user in self.repository.events_registrant_emails(event.id).await? {
+            let Some(email_addr) = user.email.clone().filter(|e| !e.is_empty()) else {
+                report.skipped += 1;
+                continue;
+            };
+            let lang = user.language.as_deref().map(Language::from);
+            let sent = match self.email.load_template("event_cancelled", lang).await {
+                Ok(template) => {
+                    let vars: Vec<(&str, &str)> = vec![
+                        ("firstname", user.firstname.as_deref().unwrap_or("")),
+                        ("lastname", user.lastname.as_deref().unwrap_or("")),
+                        ("event_name", &event.name),
+                        ("event_date", &event_date),
+                        ("reason_suffix", &reason_suffix),
+                    ];
+                    let (subject, plain, html) = email_templates::substitute(&template, &vars);
+                    self.email.send_email_with_html(&email_addr, &subject, &plain, &html).await
+                }
+                Err(e) => Err(AppError::Internal(format!("Template load error: {}", e))),
+            };
+            match sent {
+                Ok(()) => report.emails_sent += 1,
+                Err(e) => report.errors.push(AnnouncementError {
+                    user_id: user.id,
+                    email: email_addr,
+                    error_message: e.to_string(),
+                }),
+            }
+        }
+        Ok(())
+    }
+
+    /// 404 when the event does not exist, 409 with its current status otherwise
+    async fn status_conflict(&self, id: i64, rule: &str) -> AppError {
+        match self.repository.events_get_by_id(id).await {
+            Ok(event) => AppError::Conflict(format!("Event {} is {}: {}", id, event.status.as_str(), rule)),
+            Err(e) => e,
+        }
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn registrations(&self, event_id: i64) -> AppResult<Vec<EventRegistration>> {
+        self.repository.events_get_by_id(event_id).await?;
+        self.repository.events_registrations(event_id).await
+    }
+
+    /// Register a patron to a published event (no-op when already registered)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn register(&self, event_id: i64, user_id: i64) -> AppResult<()> {
+        let event = self.repository.events_get_by_id(event_id).await?;
+        if event.status != EventStatus::Published {
+            return Err(AppError::Conflict(format!(
+                "Event {} is {}: only published events accept registrations",
+                event_id,
+                event.status.as_str()
+            )));
+        }
+        self.repository.users_get_by_id(user_id).await?;
+        self.repository.events_register(event_id, user_id).await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn unregister(&self, event_id: i64, user_id: i64) -> AppResult<()> {
+        self.repository.events_unregister(event_id, user_id).await
+    }
+
+    /// Events on closure days and double-booked rooms between two days (planning view)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn conflicts(&self, start: NaiveDate, end: NaiveDate) -> AppResult<Vec<EventConflict>> {
+        if end < start {
+            return Err(AppError::Validation("end must not be before start".to_string()));
+        }
+        if (end - start).num_days() >= MAX_CONFLICT_RANGE_DAYS {
+            return Err(AppError::Validation(format!(
+                "The range must not exceed {} days",
+                MAX_CONFLICT_RANGE_DAYS
+            )));
+        }
+        self.repository.events_conflicts(start, end).await
+    }
+
+    /// Reject a slot on a closure day or overlapping another event in the same room
+    /// (`exclude_id` = event being edited).
+    async fn check_slot(&self, slot: &EventSlot, exclude_id: Option<i64>) -> AppResult<()> {
+        if slot.room.as_ref().is_some_and(|r| r.chars().count() > 100) {
+            return Err(AppError::Validation("room must be at most 100 characters".to_string()));
+        }
+        let mut conflicts = Vec::new();
+        if let Some(closure) = self.repository.events_closure_on(slot.date).await? {
+            conflicts.push(match closure.reason.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+                Some(reason) => format!("the library is closed on {} ({})", slot.date, reason),
+                None => format!("the library is closed on {}", slot.date),
+            });
+        }
+        for other in self.repository.events_room_overlaps(slot, exclude_id).await? {
+            conflicts.push(format!(
+                "room '{}' is already booked by event {} '{}'{}",
+                other.room.as_deref().unwrap_or_default(),
+                other.id,
+                other.name,
+                time_span(&other)
+            ));
+        }
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Conflict(format!("Event conflicts: {}", conflicts.join("; "))))
+        }
+    }
+
     /// Get annual event statistics (for annual report)
     #[tracing::instrument(skip(self), err)]
     pub async fn annual_stats(&self, year: i32) -> AppResult<EventAnnualStats> {
@@ -166,6 +336,13 @@ impl EventsService {
         client_ip: Option<String>,
     ) -> AppResult<AnnouncementReport> {
         let event = self.repository.events_get_by_id(event_id).await?;
+        if event.status != EventStatus::Published {
+            return Err(AppError::Conflict(format!(
+                "Event {} is {}: only published events can be announced",
+                event_id,
+                event.status.as_str()
+            )));
+        }
 
         let event_date = event.event_date.format("%d/%m/%Y").to_string();
         let event_type_label = match event.event_type {
@@ -354,6 +531,16 @@ impl EventsService {
     }
 }
 
+/// " (10:00-11:30)", " (from 10:00)", " (until 11:30)" or "" for an all-day event
+fn time_span(event: &Event) -> String {
+    match (event.start_time, event.end_time) {
+        (Some(start), Some(end)) => format!(" ({}-{})", start.format("%H:%M"), end.format("%H:%M")),
+        (Some(start), None) => format!(" (from {})", start.format("%H:%M")),
+        (None, Some(end)) => format!(" (until {})", end.format("%H:%M")),
+        (None, None) => String::new(),
+    }
+}
+
 fn decode_event_attachment_input(input: &EventAttachmentInput) -> AppResult<(Vec<u8>, String, String)> {
     let bytes = B64
         .decode(input.data_base64.trim())
@@ -0,0 +1,95 @@
+//! Code 128 barcode encoding (code sets B and C), as read by circulation desk scanners.
+
+/// Bar and space widths (in modules) of symbol values 0 to 105, bar first
+const PATTERNS: [&str; 106] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212", "221213",
+    "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221", "223211", "221132",
+    "221231", "213212", "223112", "312131", "311222", "321122", "321221", "312212", "322112", "322211",
+    "212123", "212321", "232121", "111323", "131123", "131321", "112313", "132113", "132311", "211313",
+    "231113", "231311", "112133", "112331", "132131", "113123", "113321", "133121", "313121", "211331",
+    "231131", "213113", "213311", "213131", "311123", "311321", "331121", "312113", "312311", "332111",
+    "314111", "221411", "431111", "111224", "111422", "121124", "121421", "141122", "141221", "112214",
+    "112412", "122114", "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111",
+    "111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311", "113141",
+    "114131", "311141", "411131", "211412", "211214", "211232",
+];
+const STOP: &str = "2331112";
+const START_B: usize = 104;
+const START_C: usize = 105;
+
+/// Modules of the quiet zone scanners need on each side of the bars
+pub const QUIET_ZONE: usize = 10;
+
+/// Bar and space widths (in modules, bar first) of `data`, start, checksum and stop symbols
+/// included. Even runs of at least 4 digits use code set C (two digits per symbol), anything else
+/// printable ASCII code set B; `None` when `data` is empty or has other characters.
+pub fn encode(data: &str) -> Option<Vec<u8>> {
+    if data.is_empty() || !data.bytes().all(|b| (b' '..=b'~').contains(&b)) {
+        return None;
+    }
+    let numeric = data.len() >= 4 && data.len().is_multiple_of(2) && data.bytes().all(|b| b.is_ascii_digit());
+    let mut values = if numeric {
+        let mut values = vec![START_C];
+        values.extend(data.as_bytes().chunks(2).map(|pair| ((pair[0] - b'0') * 10 + pair[1] - b'0') as usize));
+        values
+    } else {
+        let mut values = vec![START_B];
+        values.extend(data.bytes().map(|b| (b - b' ') as usize));
+        values
+    };
+    let checksum = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| i.max(1) * v)
+        .sum::<usize>()
+        % 103;
+    values.push(checksum);
+
+    let widths = values
+        .iter()
+        .flat_map(|v| PATTERNS[*v].bytes())
+        .chain(STOP.bytes())
+        .map(|b| b - b'0')
+        .collect();
+    Some(widths)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn symbol_table_is_consistent() {
+        let mut seen = HashSet::new();
+        for pattern in PATTERNS {
+            let widths: Vec<u8> = pattern.bytes().map(|b| b - b'0').collect();
+            assert_eq!(widths.iter().map(|w| *w as u32).sum::<u32>(), 11, "{}", pattern);
+            // Bars always cover an even number of modules
+            assert_eq!((widths[0] + widths[2] + widths[4]) % 2, 0, "{}", pattern);
+            assert!(seen.insert(pattern), "duplicate {}", pattern);
+        }
+        assert_eq!(STOP.bytes().map(|b| (b - b'0') as u32).sum::<u32>(), 13);
+    }
+
+    #[test]
+    fn encodes_with_checksum() {
+        let modules = |w: &[u8]| w.iter().map(|w| *w as usize).sum::<usize>();
+        // Start B, "A" (33), checksum (104 + 33) % 103 = 34, stop
+        let widths = encode("A").unwrap();
+        let expected: Vec<u8> = ["211214", "111323", "131123", STOP]
+            .concat()
+            .bytes()
+            .map(|b| b - b'0')
+            .collect();
+        assert_eq!(widths, expected);
+
+        // Code set C packs digit pairs: start, 4 pairs, checksum, stop
+        assert_eq!(modules(&encode("00012345").unwrap()), 11 * 6 + 13);
+        assert_eq!(modules(&encode("B0012345").unwrap()), 11 * 10 + 13);
+        assert!(encode("").is_none());
+        assert!(encode("é1").is_none());
+    }
+}
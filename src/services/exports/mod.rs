@@ -0,0 +1,443 @@
+//! Streamed catalog exports (CSV, XLSX, JSON, MARC) and printable sheets (PDF): spine labels,
+//! hold shelf lists and processing slips.
+//!
+//! A producer task reads the catalog with keyset-paginated queries and pushes encoded chunks into
+//! a bounded channel; the HTTP body (or an artifact writer) drains it. When the consumer is slow
+//! the producer waits on the channel, and when the client disconnects the send fails and the
+//! producer stops — memory stays bounded by one page whatever the catalog size.
+
+mod code128;
+mod hold_lists;
+mod pdf;
+mod processing_slips;
+mod spine_labels;
+mod xlsx;
+
+use rust_decimal::prelude::ToPrimitive;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use z3950_rs::marc_rs::{BinaryWriter, Encoding as MarcEncoding, MarcFormat, XmlWriter};
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{
+        hold::HoldShelfGroup,
+        item::{ItemExportFormat, ItemExportRow, SpineLabelQuery},
+        loan::LoanMarcExportEncoding,
+    },
+    services::{catalog::CatalogService, currency::Currency},
+};
+
+pub use xlsx::{Cell, XlsxStreamWriter};
+
+/// Copies read per query for row-oriented formats
+const ITEMS_PAGE_SIZE: i64 = 1000;
+/// Biblios per MARC page (each record is built with its holdings)
+const BIBLIOS_PAGE_SIZE: i64 = 200;
+/// Encoded pages buffered between the producer and the client
+const CHANNEL_CAPACITY: usize = 4;
+/// Spine labels per request (about 25 sheets of the smallest stock)
+const MAX_SPINE_LABELS: i64 = 2000;
+/// Position of `price` in [`ITEM_COLUMNS`]
+const PRICE_COLUMN: usize = 14;
+
+const ITEM_COLUMNS: [&str; 16] = [
+    "item_id",
+    "biblio_id",
+    "barcode",
+    "call_number",
+    "volume_designation",
+    "isbn",
+    "title",
+    "authors",
+    "media_type",
+    "publication_date",
+    "source",
+    "state",
+    "opac_visible",
+    "borrowable",
+    "price",
+    "created_at",
+];
+
+pub type ExportChunk = Result<Vec<u8>, std::io::Error>;
+
+/// An export being produced: metadata plus the chunk stream
+pub struct ExportStream {
+    pub content_type: &'static str,
+    pub filename: &'static str,
+    pub chunks: ReceiverStream<ExportChunk>,
+}
+
+#[derive(Clone)]
+pub struct ExportsService {
+    catalog: CatalogService,
+    currency: Currency,
+}
+
+impl ExportsService {
+    pub fn new(catalog: CatalogService, currency: Currency) -> Self {
+        Self { catalog, currency }
+    }
+
+    /// Start exporting every active copy (`csv`, `xlsx`, `json`) or every biblio with active
+    /// copies (`marc21`, `unimarc`, `marcxml`, holdings included).
+    pub fn items(&self, format: ItemExportFormat, encoding: LoanMarcExportEncoding) -> ExportStream {
+        let (content_type, filename) = format.content_type_filename();
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let catalog = self.catalog.clone();
+        let currency = self.currency.clone();
+        tokio::spawn(async move {
+            let sink = ChunkSink { tx };
+            let result = match format {
+                ItemExportFormat::Csv | ItemExportFormat::Xlsx | ItemExportFormat::Json => {
+                    produce_item_rows(&catalog, &currency, format, &sink).await
+                }
+                ItemExportFormat::Marc21 | ItemExportFormat::Unimarc | ItemExportFormat::Marcxml => {
+                    produce_marc(&catalog, format, encoding, &sink).await
+                }
+            };
+            match result {
+                Ok(()) => {}
+                Err(ExportAbort::Disconnected) => tracing::debug!("Items export stopped: client went away"),
+                Err(ExportAbort::Failed(e)) => {
+                    tracing::error!("Items export failed: {}", e);
+                    sink.fail(e).await;
+                }
+            }
+        });
+        ExportStream {
+            content_type,
+            filename,
+            chunks: ReceiverStream::new(rx),
+        }
+    }
+
+    /// PDF sheet of spine labels for the copies matching the filter, in call number order.
+    pub async fn spine_labels(&self, query: &SpineLabelQuery) -> AppResult<Vec<u8>> {
+        if !query.has_filter() {
+            return Err(AppError::Validation(
+                "Filter copies by place, createdFrom or createdTo".to_string(),
+            ));
+        }
+        let per_sheet = query.stock.layout().labels_per_sheet();
+        if query.skip >= per_sheet {
+            return Err(AppError::Validation(format!(
+                "skip must be below {} (labels per sheet)",
+                per_sheet
+            )));
+        }
+        let font_size = query.font_size.unwrap_or(spine_labels::DEFAULT_FONT_SIZE);
+        if !(spine_labels::MIN_FONT_SIZE..=spine_labels::MAX_FONT_SIZE).contains(&font_size) {
+            return Err(AppError::Validation(format!(
+                "fontSize must be between {} and {}",
+                spine_labels::MIN_FONT_SIZE,
+                spine_labels::MAX_FONT_SIZE
+            )));
+        }
+
+        let rows = self.catalog.items_spine_labels(query, MAX_SPINE_LABELS + 1).await?;
+        if rows.is_empty() {
+            return Err(AppError::NotFound("No copy with a call number matches the filter".to_string()));
+        }
+        if rows.len() as i64 > MAX_SPINE_LABELS {
+            return Err(AppError::Validation(format!(
+                "More than {} copies match; narrow the filter",
+                MAX_SPINE_LABELS
+            )));
+        }
+        let options = spine_labels::SpineLabelOptions {
+            stock: query.stock,
+            font: query.font,
+            font_size,
+            skip: query.skip,
+        };
+        spine_labels::render(&rows, &options)
+            .map_err(|e| AppError::Internal(format!("Spine labels: {}", e)))
+    }
+
+    /// Printable A4 hold shelf list (pull list or expired holds), one section per location.
+    pub fn hold_shelf_list(&self, title: &str, groups: &[HoldShelfGroup]) -> AppResult<Vec<u8>> {
+        hold_lists::render(title, groups).map_err(|e| AppError::Internal(format!("Hold shelf list: {}", e)))
+    }
+
+    /// One A6 processing slip per copy, in the order of `item_ids`: `(item_id, PDF)`.
+    pub async fn processing_slips(&self, item_ids: &[i64]) -> AppResult<Vec<(i64, Vec<u8>)>> {
+        let rows = self.catalog.items_processing_slips(item_ids).await?;
+        let mut slips = Vec::with_capacity(rows.len());
+        for row in rows {
+            let pdf = processing_slips::render(&row)
+                .map_err(|e| AppError::Internal(format!("Processing slip: {}", e)))?;
+            slips.push((row.item_id, pdf));
+        }
+        Ok(slips)
+    }
+}
+
+enum ExportAbort {
+    /// The receiving side was dropped
+    Disconnected,
+    Failed(AppError),
+}
+
+impl From<AppError> for ExportAbort {
+    fn from(e: AppError) -> Self {
+        Self::Failed(e)
+    }
+}
+
+impl From<std::io::Error> for ExportAbort {
+    fn from(e: std::io::Error) -> Self {
+        Self::Failed(AppError::Internal(format!("Export encoding: {}", e)))
+    }
+}
+
+struct ChunkSink {
+    tx: mpsc::Sender<ExportChunk>,
+}
+
+impl ChunkSink {
+    /// Waits while the channel is full (backpressure)
+    async fn send(&self, chunk: Vec<u8>) -> Result<(), ExportAbort> {
+        if chunk.is_empty() {
+            return Ok(());
+        }
+        self.tx.send(Ok(chunk)).await.map_err(|_| ExportAbort::Disconnected)
+    }
+
+    /// Abort the stream: the body ends with an error instead of looking complete
+    async fn fail(&self, e: AppError) {
+        let _ = self
+            .tx
+            .send(Err(std::io::Error::other(e.to_string())))
+            .await;
+    }
+}
+
+/// Rows of the copies; CSV and XLSX prices are written in the library's currency format, JSON
+/// keeps the stored value
+async fn produce_item_rows(
+    catalog: &CatalogService,
+    currency: &Currency,
+    format: ItemExportFormat,
+    sink: &ChunkSink,
+) -> Result<(), ExportAbort> {
+    let mut xlsx = None;
+    match format {
+        ItemExportFormat::Xlsx => {
+            let (mut writer, start) = XlsxStreamWriter::new("Items")?;
+            let header: Vec<Cell> = ITEM_COLUMNS.iter().map(|c| Cell::Text(c)).collect();
+            let mut chunk = start;
+            chunk.extend(writer.write_row(&header)?);
+            sink.send(chunk).await?;
+            xlsx = Some(writer);
+        }
+        ItemExportFormat::Json => sink.send(b"[".to_vec()).await?,
+        _ => sink.send(format!("{}\n", ITEM_COLUMNS.join(",")).into_bytes()).await?,
+    }
+
+    let mut after_id = 0;
+    let mut first = true;
+    loop {
+        let rows = catalog.items_export_page(after_id, ITEMS_PAGE_SIZE).await?;
+        let Some(last) = rows.last() else {
+            break;
+        };
+        after_id = last.item_id;
+
+        let mut chunk = Vec::new();
+        for row in &rows {
+            match (&mut xlsx, format) {
+                (Some(writer), _) => {
+                    let fields = item_fields(row, currency);
+                    chunk.extend(writer.write_row(&item_cells(row, &fields, currency))?);
+                }
+                (None, ItemExportFormat::Json) => {
+                    if !first {
+                        chunk.push(b',');
+                    }
+                    serde_json::to_writer(&mut chunk, row)
+                        .map_err(|e| AppError::Internal(format!("Items export serialization: {}", e)))?;
+                }
+                (None, _) => chunk.extend(item_csv_line(row, currency).into_bytes()),
+            }
+            first = false;
+        }
+        sink.send(chunk).await?;
+    }
+
+    match (xlsx, format) {
+        (Some(writer), _) => sink.send(writer.finish()?).await,
+        (None, ItemExportFormat::Json) => sink.send(b"]".to_vec()).await,
+        (None, _) => Ok(()),
+    }
+}
+
+async fn produce_marc(
+    catalog: &CatalogService,
+    format: ItemExportFormat,
+    encoding: LoanMarcExportEncoding,
+    sink: &ChunkSink,
+) -> Result<(), ExportAbort> {
+    let marc_encoding = match encoding {
+        LoanMarcExportEncoding::Utf8 => MarcEncoding::Utf8,
+        LoanMarcExportEncoding::Marc8 => MarcEncoding::Marc8,
+    };
+    let marc_format = match format {
+        ItemExportFormat::Unimarc => MarcFormat::Unimarc(marc_encoding),
+        // MARC-XML is always UTF-8
+        ItemExportFormat::Marcxml => MarcFormat::Marc21(MarcEncoding::Utf8),
+        _ => MarcFormat::Marc21(marc_encoding),
+    };
+    let xml = format == ItemExportFormat::Marcxml;
+    if xml {
+        let mut chunk = Vec::new();
+        XmlWriter::new(&mut chunk).start_collection().map_err(marc_error)?;
+        sink.send(chunk).await?;
+    }
+
+    let mut after_id = 0;
+    loop {
+        let ids = catalog.biblios_export_ids_page(after_id, BIBLIOS_PAGE_SIZE).await?;
+        let Some(&last) = ids.last() else {
+            break;
+        };
+        after_id = last;
+
+        let mut chunk = Vec::new();
+        for id in ids {
+            let mut record = match catalog.get_marc_record_with_holdings(id).await {
+                Ok(record) => record,
+                // Archived or deleted since the page was read
+                Err(AppError::NotFound(_)) | Err(AppError::Gone(_)) => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if xml {
+                XmlWriter::new(&mut chunk)
+                    .write_record(&marc_format, &record)
+                    .map_err(marc_error)?;
+            } else {
+                BinaryWriter::new(&mut chunk)
+                    .write_record(&marc_format, &mut record)
+                    .map_err(marc_error)?;
+            }
+        }
+        sink.send(chunk).await?;
+    }
+
+    if xml {
+        let mut chunk = Vec::new();
+        XmlWriter::new(&mut chunk).end_collection().map_err(marc_error)?;
+        sink.send(chunk).await?;
+    }
+    Ok(())
+}
+
+fn marc_error(e: impl std::fmt::Display) -> ExportAbort {
+    ExportAbort::Failed(AppError::Internal(format!("MARC export write: {}", e)))
+}
+
+fn item_fields(row: &ItemExportRow, currency: &Currency) -> [String; 16] {
+    [
+        row.item_id.to_string(),
+        row.biblio_id.to_string(),
+        row.barcode.clone().unwrap_or_default(),
+        row.call_number.clone().unwrap_or_default(),
+        row.volume_designation.clone().unwrap_or_default(),
+        row.isbn.clone().unwrap_or_default(),
+        row.title.clone().unwrap_or_default(),
+        row.authors.clone(),
+        row.media_type.clone().unwrap_or_default(),
+        row.publication_date.clone().unwrap_or_default(),
+        row.source.clone().unwrap_or_default(),
+        row.state.clone().unwrap_or_default(),
+        row.opac_visible.to_string(),
+        row.borrowable.to_string(),
+        row.price.as_deref().map(|p| currency.format_price(p)).unwrap_or_default(),
+        row.created_at.map(|d| d.to_rfc3339()).unwrap_or_default(),
+    ]
+}
+
+fn item_csv_line(row: &ItemExportRow, currency: &Currency) -> String {
+    let fields = item_fields(row, currency);
+    let mut line = fields.iter().map(|f| escape_csv(f)).collect::<Vec<_>>().join(",");
+    line.push('\n');
+    line
+}
+
+/// XLSX cells borrowing `fields` (ids and prices stay numbers so spreadsheets sort and sum them)
+fn item_cells<'a>(row: &ItemExportRow, fields: &'a [String; 16], currency: &Currency) -> Vec<Cell<'a>> {
+    let mut cells = vec![Cell::Number(row.item_id), Cell::Number(row.biblio_id)];
+    cells.extend(fields[2..].iter().map(|f| {
+        if f.is_empty() {
+            Cell::Empty
+        } else {
+            Cell::Text(f)
+        }
+    }));
+    if let Some(amount) = row.price.as_deref().and_then(|p| currency.stored_price(p)) {
+        cells[PRICE_COLUMN] = Cell::Float(amount.to_f64().unwrap_or_default());
+    }
+    cells
+}
+
+/// Quote a CSV field when it contains a separator, quote or newline
+pub fn escape_csv(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn row() -> ItemExportRow {
+        ItemExportRow {
+            item_id: 12,
+            biblio_id: 3,
+            barcode: Some("0001234".into()),
+            call_number: Some("R DOY".into()),
+            volume_designation: None,
+            isbn: Some("9782070360024".into()),
+            title: Some("Le \"Petit\" Prince, illustré".into()),
+            authors: "Saint-Exupéry Antoine".into(),
+            media_type: Some("printedText".into()),
+            publication_date: Some("1946".into()),
+            source: None,
+            state: Some("Available".into()),
+            opac_visible: true,
+            borrowable: true,
+            price: None,
+            created_at: Some(Utc.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap()),
+        }
+    }
+
+    #[test]
+    fn csv_line_escapes_fields() {
+        assert_eq!(
+            item_csv_line(&row(), &Currency::default()),
+            "12,3,0001234,R DOY,,9782070360024,\"Le \"\"Petit\"\" Prince, illustré\",Saint-Exupéry Antoine,\
+             printedText,1946,,Available,true,true,,2024-03-01T10:00:00+00:00\n"
+        );
+        assert_eq!(ITEM_COLUMNS.len(), item_fields(&row(), &Currency::default()).len());
+        assert_eq!(ITEM_COLUMNS[PRICE_COLUMN], "price");
+    }
+
+    #[test]
+    fn prices_use_the_currency_format() {
+        let currency = Currency::default();
+        let priced = ItemExportRow { price: Some("12.50".into()), ..row() };
+        assert!(item_csv_line(&priced, &currency).contains(",\"12,50\","));
+        let fields = item_fields(&priced, &currency);
+        assert!(matches!(item_cells(&priced, &fields, &currency)[PRICE_COLUMN], Cell::Float(p) if p == 12.5));
+
+        let legacy = ItemExportRow { price: Some("env. 15".into()), ..row() };
+        let fields = item_fields(&legacy, &currency);
+        assert!(matches!(item_cells(&legacy, &fields, &currency)[PRICE_COLUMN], Cell::Text("env. 15")));
+    }
+}
@@ -0,0 +1,216 @@
+//! Minimal PDF writer for printable sheets (text and filled rectangles, one standard font).
+//!
+//! The font is one of the 14 standard PDF fonts, so nothing is embedded and text is written in
+//! WinAnsi encoding (Latin-1 plus the usual typographic signs; other characters become `?`).
+//! Page contents are deflated. Coordinates are in points from the bottom left corner of the page.
+
+use std::io::{self, Write};
+
+use flate2::{write::ZlibEncoder, Compression};
+
+pub const POINTS_PER_MM: f32 = 72.0 / 25.4;
+
+/// Drawing operations of one page
+#[derive(Default)]
+pub struct PageContent {
+    ops: Vec<u8>,
+}
+
+impl PageContent {
+    /// Clip what follows to a rectangle, until the matching [`PageContent::restore`]
+    pub fn clip(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.ops
+            .extend(format!("q {:.2} {:.2} {:.2} {:.2} re W n\n", x, y, width, height).into_bytes());
+    }
+
+    pub fn restore(&mut self) {
+        self.ops.extend(b"Q\n");
+    }
+
+    /// Fill a rectangle in black (barcode bars)
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.ops
+            .extend(format!("{:.2} {:.2} {:.2} {:.2} re f\n", x, y, width, height).into_bytes());
+    }
+
+    /// Write one line of text with its baseline starting at `(x, y)`
+    pub fn text(&mut self, x: f32, y: f32, size: f32, text: &str) {
+        self.ops
+            .extend(format!("BT /F1 {:.2} Tf {:.2} {:.2} Td (", size, x, y).into_bytes());
+        for byte in text.chars().map(win_ansi) {
+            if matches!(byte, b'(' | b')' | b'\\') {
+                self.ops.push(b'\\');
+            }
+            self.ops.push(byte);
+        }
+        self.ops.extend(b") Tj ET\n");
+    }
+}
+
+/// Pages in, PDF bytes out
+pub struct PdfWriter {
+    width: f32,
+    height: f32,
+    base_font: &'static str,
+    pages: Vec<Vec<u8>>,
+}
+
+impl PdfWriter {
+    /// `width` and `height` in points; `base_font` is a standard font name (e.g. `Helvetica`)
+    pub fn new(width: f32, height: f32, base_font: &'static str) -> Self {
+        Self { width, height, base_font, pages: Vec::new() }
+    }
+
+    pub fn add_page(&mut self, page: PageContent) -> io::Result<()> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&page.ops)?;
+        self.pages.push(encoder.finish()?);
+        Ok(())
+    }
+
+    /// Objects: 1 catalog, 2 page tree, 3 font, then a page and its content stream per page.
+    pub fn finish(self) -> Vec<u8> {
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::new();
+        let mut object = |out: &mut Vec<u8>, body: &[u8]| {
+            offsets.push(out.len());
+            out.extend(format!("{} 0 obj\n", offsets.len()).into_bytes());
+            out.extend(body);
+            out.extend(b"\nendobj\n");
+        };
+
+        let kids: Vec<String> = (0..self.pages.len()).map(|i| format!("{} 0 R", 4 + 2 * i)).collect();
+        object(&mut out, b"<< /Type /Catalog /Pages 2 0 R >>");
+        object(
+            &mut out,
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} /MediaBox [0 0 {:.2} {:.2}] >>",
+                kids.join(" "),
+                self.pages.len(),
+                self.width,
+                self.height
+            )
+            .as_bytes(),
+        );
+        object(
+            &mut out,
+            format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                self.base_font
+            )
+            .as_bytes(),
+        );
+        for (i, content) in self.pages.iter().enumerate() {
+            object(
+                &mut out,
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                    5 + 2 * i
+                )
+                .as_bytes(),
+            );
+            let mut stream = format!("<< /Length {} /Filter /FlateDecode >>\nstream\n", content.len()).into_bytes();
+            stream.extend(content);
+            stream.extend(b"\nendstream");
+            object(&mut out, &stream);
+        }
+
+        let xref = out.len();
+        out.extend(format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1).into_bytes());
+        for offset in &offsets {
+            out.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+        }
+        out.extend(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                offsets.len() + 1,
+                xref
+            )
+            .into_bytes(),
+        );
+        out
+    }
+}
+
+/// WinAnsiEncoding byte of a character (`?` when it has none)
+fn win_ansi(c: char) -> u8 {
+    match c as u32 {
+        0x20..=0x7E | 0xA0..=0xFF => c as u8,
+        _ => match c {
+            '€' => 0x80,
+            '‚' => 0x82,
+            'ƒ' => 0x83,
+            '„' => 0x84,
+            '…' => 0x85,
+            '†' => 0x86,
+            '‡' => 0x87,
+            'ˆ' => 0x88,
+            '‰' => 0x89,
+            'Š' => 0x8A,
+            '‹' => 0x8B,
+            'Œ' => 0x8C,
+            'Ž' => 0x8E,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            '˜' => 0x98,
+            '™' => 0x99,
+            'š' => 0x9A,
+            '›' => 0x9B,
+            'œ' => 0x9C,
+            'ž' => 0x9E,
+            'Ÿ' => 0x9F,
+            _ => b'?',
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::ZlibDecoder;
+
+    use super::*;
+
+    #[test]
+    fn text_is_escaped_and_win_ansi_encoded() {
+        let mut page = PageContent::default();
+        page.text(1.0, 2.0, 9.0, "Œuvre (é) \\ 漢");
+        assert_eq!(
+            page.ops,
+            b"BT /F1 9.00 Tf 1.00 2.00 Td (\x8Cuvre \\(\xE9\\) \\\\ ?) Tj ET\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn cross_reference_table_points_at_objects() {
+        let mut writer = PdfWriter::new(595.28, 841.89, "Helvetica");
+        let mut page = PageContent::default();
+        page.text(10.0, 10.0, 12.0, "R DUM");
+        writer.add_page(page).unwrap();
+        writer.add_page(PageContent::default()).unwrap();
+        let pdf = writer.finish();
+
+        let tail = pdf.windows(10).rposition(|w| w == b"startxref\n").unwrap();
+        let tail = std::str::from_utf8(&pdf[tail..]).unwrap();
+        let startxref: usize = tail.lines().nth(1).unwrap().parse().unwrap();
+        let xref = std::str::from_utf8(&pdf[startxref..]).unwrap();
+        assert!(xref.starts_with("xref\n0 8\n"));
+        let offsets: Vec<usize> = xref.lines().skip(3).take(7).map(|l| l[..10].parse().unwrap()).collect();
+        for (i, offset) in offsets.iter().enumerate() {
+            assert!(pdf[*offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+        assert!(pdf.windows(20).any(|w| w == b"/Kids [4 0 R 6 0 R] "));
+
+        // First content stream inflates back to the drawing operations
+        let start = pdf.windows(7).position(|w| w == b"stream\n").unwrap() + 7;
+        let mut ops = String::new();
+        ZlibDecoder::new(&pdf[start..]).read_to_string(&mut ops).unwrap();
+        assert_eq!(ops, "BT /F1 12.00 Tf 10.00 10.00 Td (R DUM) Tj ET\n");
+    }
+}
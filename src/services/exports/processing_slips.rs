@@ -0,0 +1,162 @@
+//! Processing slips for shelf-ready imports: one A6 page per copy with what technical services
+//! needs to finish it (title, call number, location, inventory number, barcode to stick).
+
+use std::io;
+
+use super::{
+    code128,
+    hold_lists::truncate,
+    pdf::{PageContent, PdfWriter, POINTS_PER_MM},
+};
+use crate::models::item::ProcessingSlipRow;
+
+const PAGE_WIDTH_MM: f32 = 105.0;
+const PAGE_HEIGHT_MM: f32 = 148.0;
+const MARGIN_MM: f32 = 8.0;
+const TITLE_SIZE: f32 = 13.0;
+/// Characters of a title line at [`TITLE_SIZE`]
+const TITLE_CHARS: usize = 36;
+const TITLE_LINES: usize = 3;
+const LABEL_SIZE: f32 = 8.0;
+const TEXT_SIZE: f32 = 10.0;
+const CALL_NUMBER_SIZE: f32 = 22.0;
+const BARCODE_HEIGHT_MM: f32 = 18.0;
+/// Widest module, so that short barcodes stay a reasonable size
+const MAX_MODULE_MM: f32 = 0.5;
+
+/// Word-wrap `text` in lines of `max` characters; the last of `max_lines` lines is truncated.
+fn wrap(text: &str, max: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > max {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    if lines.len() > max_lines {
+        let rest = lines.split_off(max_lines - 1).join(" ");
+        lines.push(rest);
+    }
+    lines.into_iter().map(|l| truncate(&l, max)).collect()
+}
+
+/// Single-page PDF slip for one copy. The barcode is printed as Code 128 bars above its text
+/// (text only when it has characters Code 128 cannot encode).
+pub fn render(slip: &ProcessingSlipRow) -> io::Result<Vec<u8>> {
+    let mm = |v: f32| v * POINTS_PER_MM;
+    let left = mm(MARGIN_MM);
+    let width = mm(PAGE_WIDTH_MM - 2.0 * MARGIN_MM);
+    let bottom = mm(MARGIN_MM);
+
+    let mut writer = PdfWriter::new(mm(PAGE_WIDTH_MM), mm(PAGE_HEIGHT_MM), "Helvetica");
+    let mut page = PageContent::default();
+    let mut y = mm(PAGE_HEIGHT_MM - MARGIN_MM) - LABEL_SIZE;
+    page.text(left, y, LABEL_SIZE, &format!("PROCESSING SLIP  -  copy {}", slip.item_id));
+
+    y -= TITLE_SIZE * 0.6;
+    for line in wrap(slip.title.as_deref().unwrap_or("(untitled)"), TITLE_CHARS, TITLE_LINES) {
+        y -= TITLE_SIZE * 1.2;
+        page.text(left, y, TITLE_SIZE, &line);
+    }
+    if !slip.authors.is_empty() {
+        y -= TEXT_SIZE * 1.4;
+        page.text(left, y, TEXT_SIZE, &truncate(&slip.authors, 50));
+    }
+    if let Some(isbn) = slip.isbn.as_deref().filter(|i| !i.is_empty()) {
+        y -= TEXT_SIZE * 1.4;
+        page.text(left, y, TEXT_SIZE, &format!("ISBN {}", isbn));
+    }
+
+    y -= LABEL_SIZE * 3.0;
+    page.text(left, y, LABEL_SIZE, "CALL NUMBER");
+    y -= CALL_NUMBER_SIZE * 1.1;
+    let call_number = slip.call_number.as_deref().filter(|c| !c.is_empty()).unwrap_or("-");
+    page.text(left, y, CALL_NUMBER_SIZE, &truncate(call_number, 16));
+    if let Some(volume) = slip.volume_designation.as_deref().filter(|v| !v.is_empty()) {
+        y -= TEXT_SIZE * 1.6;
+        page.text(left, y, TEXT_SIZE, &truncate(volume, 50));
+    }
+
+    y -= LABEL_SIZE * 3.0;
+    page.text(left, y, LABEL_SIZE, "LOCATION");
+    y -= TEXT_SIZE * 1.5;
+    let location = match slip.place {
+        Some(place) => format!("Location {}", place),
+        None => "No location".to_string(),
+    };
+    page.text(left, y, TEXT_SIZE, &location);
+
+    y -= LABEL_SIZE * 3.0;
+    page.text(left, y, LABEL_SIZE, "INVENTORY NUMBER");
+    y -= TEXT_SIZE * 1.5;
+    page.text(left, y, TEXT_SIZE, slip.inventory_number.as_deref().unwrap_or("-"));
+
+    // Barcode at the bottom of the slip, text under the bars
+    let barcode = slip.barcode.as_deref().filter(|b| !b.is_empty());
+    let text_y = bottom;
+    page.text(left, text_y + mm(BARCODE_HEIGHT_MM) + TEXT_SIZE, LABEL_SIZE, "BARCODE");
+    match barcode.and_then(|b| code128::encode(b).map(|widths| (b, widths))) {
+        Some((text, widths)) => {
+            let modules: f32 = widths.iter().map(|w| *w as f32).sum::<f32>() + 2.0 * code128::QUIET_ZONE as f32;
+            let module = (width / modules).min(mm(MAX_MODULE_MM));
+            let bars_bottom = text_y + TEXT_SIZE * 1.3;
+            let bars_height = mm(BARCODE_HEIGHT_MM) - TEXT_SIZE * 1.3;
+            let mut x = left + (width - modules * module) / 2.0 + code128::QUIET_ZONE as f32 * module;
+            for (i, w) in widths.iter().enumerate() {
+                let w = *w as f32 * module;
+                if i % 2 == 0 {
+                    page.rect(x, bars_bottom, w, bars_height);
+                }
+                x += w;
+            }
+            page.text(left, text_y, TEXT_SIZE, text);
+        }
+        None => page.text(left, text_y, TEXT_SIZE, barcode.unwrap_or("No barcode")),
+    }
+
+    writer.add_page(page)?;
+    Ok(writer.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slip(barcode: Option<&str>) -> ProcessingSlipRow {
+        ProcessingSlipRow {
+            item_id: 12,
+            biblio_id: 3,
+            barcode: barcode.map(str::to_string),
+            call_number: Some("R DUM".to_string()),
+            volume_designation: Some("Vol. 2".to_string()),
+            place: Some(1),
+            title: Some("Les trois mousquetaires : roman historique en plusieurs volumes illustrés".to_string()),
+            authors: "Dumas Alexandre".to_string(),
+            isbn: None,
+            inventory_number: Some("2026-000042".to_string()),
+        }
+    }
+
+    #[test]
+    fn one_page_per_slip_with_bars() {
+        let pdf = render(&slip(Some("00012345"))).unwrap();
+        assert_eq!(pdf.windows(12).filter(|w| w == b"/Type /Page ").count(), 1);
+        assert!(render(&slip(None)).is_ok());
+        assert!(render(&slip(Some("Ré-1"))).is_ok());
+    }
+
+    #[test]
+    fn titles_wrap_on_words() {
+        let lines = wrap("Les trois mousquetaires : roman historique", 20, 3);
+        assert_eq!(lines, vec!["Les trois", "mousquetaires :", "roman historique"]);
+        let lines = wrap("one two three four five six", 9, 2);
+        assert_eq!(lines, vec!["one two", "three fo…"]);
+    }
+}
@@ -0,0 +1,123 @@
+//! Spine label sheets: one call number per label, one word per line, with the inventory number
+//! in small print at the bottom.
+
+use std::io;
+
+use super::pdf::{PageContent, PdfWriter, POINTS_PER_MM};
+use crate::models::item::{LabelFont, LabelStock, SpineLabelRow};
+
+pub const DEFAULT_FONT_SIZE: f32 = 10.0;
+pub const MIN_FONT_SIZE: f32 = 5.0;
+pub const MAX_FONT_SIZE: f32 = 36.0;
+/// Blank border inside each label
+const PADDING_MM: f32 = 1.5;
+/// Baseline-to-baseline distance, relative to the font size
+const LINE_SPACING: f32 = 1.15;
+/// Font size of the inventory number
+const INVENTORY_FONT_SIZE: f32 = 5.0;
+
+pub struct SpineLabelOptions {
+    pub stock: LabelStock,
+    pub font: LabelFont,
+    pub font_size: f32,
+    /// Positions already used on the first sheet
+    pub skip: u32,
+}
+
+/// Split a call number over lines, one word each ("R DUM" → "R", "DUM"). Words beyond
+/// `max_lines` stay together on the last line.
+pub fn split_call_number(call_number: &str, max_lines: usize) -> Vec<String> {
+    let words: Vec<&str> = call_number.split_whitespace().collect();
+    let max_lines = max_lines.max(1);
+    if words.len() <= max_lines {
+        return words.into_iter().map(str::to_string).collect();
+    }
+    let mut lines: Vec<String> = words[..max_lines - 1].iter().map(|w| w.to_string()).collect();
+    lines.push(words[max_lines - 1..].join(" "));
+    lines
+}
+
+/// Lay the labels out sheet by sheet, left to right then top to bottom, starting after the
+/// `skip` first positions. Text is left-aligned and centred vertically above the inventory number;
+/// the font shrinks (down to [`MIN_FONT_SIZE`]) when a call number has more words than lines fit
+/// at the chosen size.
+pub fn render(labels: &[SpineLabelRow], options: &SpineLabelOptions) -> io::Result<Vec<u8>> {
+    let layout = options.stock.layout();
+    let per_sheet = layout.labels_per_sheet() as usize;
+    let mm = |v: f32| v * POINTS_PER_MM;
+    let padding = mm(PADDING_MM);
+
+    let mut writer = PdfWriter::new(mm(layout.page_width), mm(layout.page_height), options.font.base_font());
+    let mut page = PageContent::default();
+    for (i, label) in labels.iter().enumerate() {
+        let call_number = &label.call_number;
+        let inventory_number = label.inventory_number.as_deref().filter(|n| !n.is_empty());
+        let inventory_height = if inventory_number.is_some() { INVENTORY_FONT_SIZE * LINE_SPACING } else { 0.0 };
+        let text_height = mm(layout.label_height) - 2.0 * padding - inventory_height;
+        let position = options.skip as usize + i;
+        if position.is_multiple_of(per_sheet) && i > 0 {
+            writer.add_page(std::mem::take(&mut page))?;
+        }
+        let slot = (position % per_sheet) as u32;
+        let x = mm(layout.margin_left + (slot % layout.columns) as f32 * layout.pitch_x);
+        let top = mm(layout.page_height - layout.margin_top - (slot / layout.columns) as f32 * layout.pitch_y);
+        let y = top - mm(layout.label_height);
+
+        let words = call_number.split_whitespace().count().max(1) as f32;
+        let size = options
+            .font_size
+            .min(text_height / (words * LINE_SPACING))
+            .max(MIN_FONT_SIZE);
+        let max_lines = (text_height / (size * LINE_SPACING)).floor() as usize;
+        let lines = split_call_number(call_number, max_lines);
+
+        // Block from the cap height of the first line to the baseline of the last one
+        let block = size * (0.75 + (lines.len() as f32 - 1.0) * LINE_SPACING);
+        let first_baseline = y + inventory_height + (mm(layout.label_height) - inventory_height + block) / 2.0 - 0.75 * size;
+        page.clip(x, y, mm(layout.label_width), mm(layout.label_height));
+        for (n, line) in lines.iter().enumerate() {
+            page.text(x + padding, first_baseline - n as f32 * size * LINE_SPACING, size, line);
+        }
+        if let Some(number) = inventory_number {
+            page.text(x + padding, y + padding, INVENTORY_FONT_SIZE, number);
+        }
+        page.restore();
+    }
+    writer.add_page(page)?;
+    Ok(writer.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn call_numbers_split_one_word_per_line() {
+        assert_eq!(split_call_number("R  DUM", 4), vec!["R", "DUM"]);
+        assert_eq!(split_call_number("BD 741.5 GOS T.2", 3), vec!["BD", "741.5", "GOS T.2"]);
+        assert_eq!(split_call_number("944.08 DUR", 0), vec!["944.08 DUR"]);
+    }
+
+    #[test]
+    fn sheets_fill_after_skipped_positions() {
+        let options = |skip| SpineLabelOptions {
+            stock: LabelStock::L7160,
+            font: LabelFont::Courier,
+            font_size: DEFAULT_FONT_SIZE,
+            skip,
+        };
+        let labels: Vec<SpineLabelRow> = (0..21)
+            .map(|i| SpineLabelRow {
+                item_id: i,
+                call_number: format!("R N{}", i),
+                inventory_number: (i % 2 == 0).then(|| format!("2026-{:06}", i)),
+            })
+            .collect();
+        let page_count = |pdf: Vec<u8>| pdf.windows(12).filter(|w| w == b"/Type /Page ").count();
+
+        // 21 labels per sheet
+        assert_eq!(page_count(render(&labels, &options(0)).unwrap()), 1);
+        assert_eq!(page_count(render(&labels, &options(1)).unwrap()), 2);
+        assert_eq!(page_count(render(&[], &options(0)).unwrap()), 1);
+    }
+}
@@ -0,0 +1,323 @@
+//! Streaming XLSX writer (single worksheet, inline strings).
+//!
+//! The workbook is a zip archive written front to back: each entry is deflated on the fly and
+//! closed with a data descriptor, so no part of the file needs to be rewritten or kept in memory.
+//! Every call returns the bytes ready to send; callers forward them as they come.
+
+use std::io::{self, Write};
+
+use flate2::{write::DeflateEncoder, Compression};
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#;
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const WORKBOOK_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#;
+
+const SHEET_START: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#;
+
+const SHEET_END: &str = "</sheetData></worksheet>";
+
+/// One spreadsheet cell
+pub enum Cell<'a> {
+    Text(&'a str),
+    Number(i64),
+    Float(f64),
+    Empty,
+}
+
+/// Worksheet rows in, XLSX bytes out.
+pub struct XlsxStreamWriter {
+    zip: ZipStreamWriter,
+    rows: u32,
+}
+
+impl XlsxStreamWriter {
+    /// Write the workbook parts and open the sheet; returns the writer and the first bytes.
+    pub fn new(sheet_name: &str) -> io::Result<(Self, Vec<u8>)> {
+        let workbook = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            escape_xml(sheet_name)
+        );
+        let mut zip = ZipStreamWriter::default();
+        let mut out = Vec::new();
+        for (name, content) in [
+            ("[Content_Types].xml", CONTENT_TYPES),
+            ("_rels/.rels", ROOT_RELS),
+            ("xl/workbook.xml", workbook.as_str()),
+            ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS),
+        ] {
+            out.extend(zip.start_file(name)?);
+            out.extend(zip.write(content.as_bytes())?);
+            out.extend(zip.finish_file()?);
+        }
+        out.extend(zip.start_file("xl/worksheets/sheet1.xml")?);
+        out.extend(zip.write(SHEET_START.as_bytes())?);
+        Ok((Self { zip, rows: 0 }, out))
+    }
+
+    pub fn write_row(&mut self, cells: &[Cell<'_>]) -> io::Result<Vec<u8>> {
+        self.rows += 1;
+        let mut xml = format!(r#"<row r="{}">"#, self.rows);
+        for (i, cell) in cells.iter().enumerate() {
+            let reference = format!("{}{}", column_name(i), self.rows);
+            match cell {
+                Cell::Text(s) => xml.push_str(&format!(
+                    r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                    reference,
+                    escape_xml(s)
+                )),
+                Cell::Number(n) => xml.push_str(&format!(r#"<c r="{}"><v>{}</v></c>"#, reference, n)),
+                Cell::Float(n) => xml.push_str(&format!(r#"<c r="{}"><v>{}</v></c>"#, reference, n)),
+                Cell::Empty => {}
+            }
+        }
+        xml.push_str("</row>");
+        self.zip.write(xml.as_bytes())
+    }
+
+    /// Close the sheet and the archive; returns the last bytes.
+    pub fn finish(mut self) -> io::Result<Vec<u8>> {
+        let mut out = self.zip.write(SHEET_END.as_bytes())?;
+        out.extend(self.zip.finish_file()?);
+        out.extend(self.zip.finish()?);
+        Ok(out)
+    }
+}
+
+/// Spreadsheet column letters: 0 → A, 25 → Z, 26 → AA
+fn column_name(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// Escape text content; control characters other than tab/newline are not allowed in XML 1.0.
+fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+struct OpenEntry {
+    name: String,
+    header_offset: u32,
+    encoder: DeflateEncoder<Vec<u8>>,
+    crc: crc32fast::Hasher,
+    uncompressed: u64,
+    compressed: u64,
+}
+
+struct CentralEntry {
+    name: String,
+    header_offset: u32,
+    crc: u32,
+    compressed: u32,
+    uncompressed: u32,
+}
+
+/// Minimal zip writer for non-seekable output (deflate entries + data descriptors, no zip64).
+#[derive(Default)]
+struct ZipStreamWriter {
+    offset: u64,
+    current: Option<OpenEntry>,
+    entries: Vec<CentralEntry>,
+}
+
+/// "Data descriptor follows" general purpose flag
+const FLAG_DATA_DESCRIPTOR: u16 = 0x0008;
+const METHOD_DEFLATE: u16 = 8;
+const VERSION: u16 = 20;
+/// 1980-01-01 00:00 in MS-DOS format
+const DOS_TIME: u16 = 0;
+const DOS_DATE: u16 = 0x0021;
+
+impl ZipStreamWriter {
+    fn start_file(&mut self, name: &str) -> io::Result<Vec<u8>> {
+        let header_offset = self.checked_offset()?;
+        let mut out = Vec::with_capacity(30 + name.len());
+        out.extend(0x04034b50u32.to_le_bytes());
+        out.extend(VERSION.to_le_bytes());
+        out.extend(FLAG_DATA_DESCRIPTOR.to_le_bytes());
+        out.extend(METHOD_DEFLATE.to_le_bytes());
+        out.extend(DOS_TIME.to_le_bytes());
+        out.extend(DOS_DATE.to_le_bytes());
+        out.extend([0u8; 12]); // crc, sizes: in the data descriptor
+        out.extend((name.len() as u16).to_le_bytes());
+        out.extend(0u16.to_le_bytes());
+        out.extend(name.as_bytes());
+        self.offset += out.len() as u64;
+        self.current = Some(OpenEntry {
+            name: name.to_string(),
+            header_offset,
+            encoder: DeflateEncoder::new(Vec::new(), Compression::fast()),
+            crc: crc32fast::Hasher::new(),
+            uncompressed: 0,
+            compressed: 0,
+        });
+        Ok(out)
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let entry = self
+            .current
+            .as_mut()
+            .ok_or_else(|| io::Error::other("no open zip entry"))?;
+        entry.encoder.write_all(data)?;
+        entry.crc.update(data);
+        entry.uncompressed += data.len() as u64;
+        let out = std::mem::take(entry.encoder.get_mut());
+        entry.compressed += out.len() as u64;
+        self.offset += out.len() as u64;
+        Ok(out)
+    }
+
+    fn finish_file(&mut self) -> io::Result<Vec<u8>> {
+        let entry = self
+            .current
+            .take()
+            .ok_or_else(|| io::Error::other("no open zip entry"))?;
+        let mut out = entry.encoder.finish()?;
+        let compressed = entry.compressed + out.len() as u64;
+        let (Ok(compressed), Ok(uncompressed)) = (u32::try_from(compressed), u32::try_from(entry.uncompressed))
+        else {
+            return Err(io::Error::other("zip entry larger than 4 GiB"));
+        };
+        let crc = entry.crc.finalize();
+        out.extend(0x08074b50u32.to_le_bytes());
+        out.extend(crc.to_le_bytes());
+        out.extend(compressed.to_le_bytes());
+        out.extend(uncompressed.to_le_bytes());
+        self.offset += out.len() as u64;
+        self.entries.push(CentralEntry {
+            name: entry.name,
+            header_offset: entry.header_offset,
+            crc,
+            compressed,
+            uncompressed,
+        });
+        Ok(out)
+    }
+
+    /// Central directory and end record
+    fn finish(self) -> io::Result<Vec<u8>> {
+        let directory_offset = self.checked_offset()?;
+        let mut out = Vec::new();
+        for e in &self.entries {
+            out.extend(0x02014b50u32.to_le_bytes());
+            out.extend(VERSION.to_le_bytes()); // made by
+            out.extend(VERSION.to_le_bytes()); // needed
+            out.extend(FLAG_DATA_DESCRIPTOR.to_le_bytes());
+            out.extend(METHOD_DEFLATE.to_le_bytes());
+            out.extend(DOS_TIME.to_le_bytes());
+            out.extend(DOS_DATE.to_le_bytes());
+            out.extend(e.crc.to_le_bytes());
+            out.extend(e.compressed.to_le_bytes());
+            out.extend(e.uncompressed.to_le_bytes());
+            out.extend((e.name.len() as u16).to_le_bytes());
+            out.extend([0u8; 12]); // extra, comment, disk, internal and external attributes
+            out.extend(e.header_offset.to_le_bytes());
+            out.extend(e.name.as_bytes());
+        }
+        let directory_size = out.len() as u32;
+        let count = self.entries.len() as u16;
+        out.extend(0x06054b50u32.to_le_bytes());
+        out.extend([0u8; 4]); // disk numbers
+        out.extend(count.to_le_bytes());
+        out.extend(count.to_le_bytes());
+        out.extend(directory_size.to_le_bytes());
+        out.extend(directory_offset.to_le_bytes());
+        out.extend(0u16.to_le_bytes());
+        Ok(out)
+    }
+
+    fn checked_offset(&self) -> io::Result<u32> {
+        u32::try_from(self.offset)
+            .map_err(|_| io::Error::other("zip archive larger than 4 GiB"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    fn u16_at(b: &[u8], i: usize) -> u16 {
+        u16::from_le_bytes([b[i], b[i + 1]])
+    }
+
+    fn u32_at(b: &[u8], i: usize) -> u32 {
+        u32::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3]])
+    }
+
+    #[test]
+    fn column_names() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(27 * 26), "AAA");
+    }
+
+    #[test]
+    fn workbook_is_a_readable_zip() {
+        let (mut writer, mut bytes) = XlsxStreamWriter::new("Items").unwrap();
+        bytes.extend(writer.write_row(&[Cell::Text("title"), Cell::Text("count")]).unwrap());
+        bytes.extend(writer.write_row(&[Cell::Text("Tom & Jerry <1>"), Cell::Number(3)]).unwrap());
+        bytes.extend(writer.finish().unwrap());
+
+        // End of central directory: 5 entries, directory right before it
+        let eocd = bytes.len() - 22;
+        assert_eq!(u32_at(&bytes, eocd), 0x06054b50);
+        assert_eq!(u16_at(&bytes, eocd + 10), 5);
+        let mut pos = u32_at(&bytes, eocd + 16) as usize;
+
+        let mut sheet = None;
+        for _ in 0..5 {
+            assert_eq!(u32_at(&bytes, pos), 0x02014b50);
+            let crc = u32_at(&bytes, pos + 16);
+            let compressed = u32_at(&bytes, pos + 20) as usize;
+            let name_len = u16_at(&bytes, pos + 28) as usize;
+            let header = u32_at(&bytes, pos + 42) as usize;
+            let name = String::from_utf8(bytes[pos + 46..pos + 46 + name_len].to_vec()).unwrap();
+            pos += 46 + name_len;
+
+            assert_eq!(u32_at(&bytes, header), 0x04034b50);
+            let data = header + 30 + u16_at(&bytes, header + 26) as usize;
+            let mut content = Vec::new();
+            flate2::read::DeflateDecoder::new(&bytes[data..data + compressed])
+                .read_to_end(&mut content)
+                .unwrap();
+            assert_eq!(crc32fast::hash(&content), crc, "{}", name);
+            if name == "xl/worksheets/sheet1.xml" {
+                sheet = Some(String::from_utf8(content).unwrap());
+            }
+        }
+        let sheet = sheet.expect("sheet entry");
+        assert!(sheet.contains(r#"<c r="A2" t="inlineStr"><is><t xml:space="preserve">Tom &amp; Jerry &lt;1&gt;</t></is></c>"#));
+        assert!(sheet.contains(r#"<c r="B2"><v>3</v></c>"#));
+        assert!(sheet.ends_with("</sheetData></worksheet>"));
+    }
+}
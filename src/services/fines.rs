@@ -2,22 +2,29 @@
 
 use std::sync::Arc;
 
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
 use rust_decimal::Decimal;
 
 use crate::{
+    config::FineAmnestyPeriod,
+    dynamic_config::DynamicConfig,
     error::{AppError, AppResult},
-    models::fine::{Fine, FineRule},
+    models::fine::{
+        Fine, FineAmnestyReport, FineAmnestyRequest, FineRule, FineWaiverReason, WaivedFinesQuery,
+        WaivedFinesSummary,
+    },
     repository::FinesRepository,
 };
 
 #[derive(Clone)]
 pub struct FinesService {
     repository: Arc<dyn FinesRepository>,
+    dynamic_config: Arc<DynamicConfig>,
 }
 
 impl FinesService {
-    pub fn new(repository: Arc<dyn FinesRepository>) -> Self {
-        Self { repository }
+    pub fn new(repository: Arc<dyn FinesRepository>, dynamic_config: Arc<DynamicConfig>) -> Self {
+        Self { repository, dynamic_config }
     }
 
     /// List all fines for a user
@@ -32,7 +39,8 @@ impl FinesService {
         self.repository.fines_get_by_id(id).await
     }
 
-    /// Accrue a fine for an overdue loan (calculates amount from rules)
+    /// Accrue a fine for an overdue loan (calculates amount from rules). Days within the grace period
+    /// are free; a return during an amnesty week records the fine as waived.
     #[tracing::instrument(skip(self), err)]
     pub async fn accrue(
         &self,
@@ -40,6 +48,7 @@ impl FinesService {
         user_id: i64,
         media_type: Option<&str>,
         overdue_days: i64,
+        returned_on: NaiveDate,
     ) -> AppResult<Fine> {
         let rules = self.repository.fines_list_rules().await?;
         // Look for media-type specific rule first, then default
@@ -49,34 +58,74 @@ impl FinesService {
             .or_else(|| rules.iter().find(|r| r.media_type.is_none()))
             .ok_or_else(|| AppError::Internal("No fine rule configured".to_string()))?;
 
-        let effective_days = (overdue_days - rule.grace_days as i64).max(0);
-        let mut amount = rule.daily_rate * Decimal::from(effective_days);
-        if let Some(max) = rule.max_amount {
-            amount = amount.min(max);
-        }
-
+        let config = self.dynamic_config.read_circulation();
+        let amount = fine_amount(rule, overdue_days, config.fine_grace_days);
         if amount <= Decimal::ZERO {
             return Err(AppError::BusinessRule(
                 "Fine amount is zero — within grace period".to_string(),
             ));
         }
 
-        self.repository.fines_create(loan_id, user_id, amount, None).await
+        let fine = self.repository.fines_create(loan_id, user_id, amount, None).await?;
+        match amnesty_on(&config.fine_amnesties, returned_on) {
+            Some(amnesty) => {
+                let notes = format!("Amnesty: {}", amnesty.name);
+                self.repository
+                    .fines_waive(fine.id, Some(&notes), FineWaiverReason::AmnestyWeek, Some(&amnesty.name), None)
+                    .await
+            }
+            None => Ok(fine),
+        }
     }
 
-    /// Apply a payment to a fine
+    /// Waive (write off) a fine
     #[tracing::instrument(skip(self), err)]
-    pub async fn pay(&self, id: i64, amount: Decimal, notes: Option<&str>) -> AppResult<Fine> {
-        if amount <= Decimal::ZERO {
-            return Err(AppError::Validation("Payment amount must be positive".to_string()));
+    pub async fn waive(&self, id: i64, notes: Option<&str>, waived_by: i64) -> AppResult<Fine> {
+        self.repository
+            .fines_waive(id, notes, FineWaiverReason::Manual, None, Some(waived_by))
+            .await
+    }
+
+    /// Amnesty campaign: waive every unpaid fine issued between two days
+    #[tracing::instrument(skip(self), err)]
+    pub async fn amnesty(&self, request: &FineAmnestyRequest, waived_by: i64) -> AppResult<FineAmnestyReport> {
+        let name = request.name.trim();
+        if name.is_empty() {
+            return Err(AppError::Validation("name is required".to_string()));
+        }
+        if request.end_date < request.start_date {
+            return Err(AppError::Validation("endDate must not be before startDate".to_string()));
         }
-        self.repository.fines_pay(id, amount, notes).await
+        let from = local_day_start(request.start_date);
+        let to = local_day_start(request.end_date + Duration::days(1));
+        let (fine_count, waived_total) = self.repository.fines_amnesty(name, from, to, waived_by).await?;
+        Ok(FineAmnestyReport {
+            name: name.to_string(),
+            start_date: request.start_date,
+            end_date: request.end_date,
+            fine_count,
+            waived_total,
+        })
     }
 
-    /// Waive (write off) a fine
+    /// Waived totals by reason over a period
     #[tracing::instrument(skip(self), err)]
-    pub async fn waive(&self, id: i64, notes: Option<&str>) -> AppResult<Fine> {
-        self.repository.fines_waive(id, notes).await
+    pub async fn waived_summary(&self, query: &WaivedFinesQuery) -> AppResult<WaivedFinesSummary> {
+        if let (Some(start), Some(end)) = (query.start_date, query.end_date) {
+            if end < start {
+                return Err(AppError::Validation("endDate must not be before startDate".to_string()));
+            }
+        }
+        let from = query.start_date.map(local_day_start);
+        let to = query.end_date.map(|end| local_day_start(end + Duration::days(1)));
+        let totals = self.repository.fines_waivers_summary(from, to).await?;
+        Ok(WaivedFinesSummary {
+            start_date: query.start_date,
+            end_date: query.end_date,
+            fine_count: totals.iter().map(|t| t.fine_count).sum(),
+            waived_total: totals.iter().map(|t| t.waived_total).sum(),
+            totals,
+        })
     }
 
     /// Get total unpaid fines for a user
@@ -108,3 +157,69 @@ impl FinesService {
             .await
     }
 }
+
+/// Fine for `overdue_days` late, after the longest of the rule's and the library's grace periods
+fn fine_amount(rule: &FineRule, overdue_days: i64, grace_days: u32) -> Decimal {
+    let grace = (rule.grace_days as i64).max(grace_days as i64);
+    let effective_days = (overdue_days - grace).max(0);
+    let amount = rule.daily_rate * Decimal::from(effective_days);
+    match rule.max_amount {
+        Some(max) => amount.min(max),
+        None => amount,
+    }
+}
+
+/// Amnesty week covering a return day
+fn amnesty_on(amnesties: &[FineAmnestyPeriod], day: NaiveDate) -> Option<&FineAmnestyPeriod> {
+    amnesties.iter().find(|a| a.start_date <= day && day <= a.end_date)
+}
+
+/// First instant of a local day, in UTC
+fn local_day_start(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(grace_days: i32, max_amount: Option<Decimal>) -> FineRule {
+        FineRule {
+            id: 1,
+            media_type: None,
+            daily_rate: Decimal::new(20, 2),
+            max_amount,
+            grace_days,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn grace_days_are_free_and_the_longest_grace_wins() {
+        assert_eq!(fine_amount(&rule(0, None), 10, 0), Decimal::new(200, 2));
+        assert_eq!(fine_amount(&rule(3, None), 10, 0), Decimal::new(140, 2));
+        assert_eq!(fine_amount(&rule(3, None), 10, 5), Decimal::new(100, 2));
+        assert_eq!(fine_amount(&rule(5, None), 10, 3), Decimal::new(100, 2));
+        assert_eq!(fine_amount(&rule(0, None), 2, 3), Decimal::ZERO);
+        assert_eq!(fine_amount(&rule(0, Some(Decimal::ONE)), 10, 0), Decimal::ONE);
+    }
+
+    #[test]
+    fn amnesty_weeks_include_both_ends() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        let weeks = vec![FineAmnestyPeriod {
+            name: "Spring amnesty".to_string(),
+            start_date: day(9),
+            end_date: day(15),
+        }];
+        assert!(amnesty_on(&weeks, day(8)).is_none());
+        assert_eq!(amnesty_on(&weeks, day(9)).map(|a| a.name.as_str()), Some("Spring amnesty"));
+        assert!(amnesty_on(&weeks, day(15)).is_some());
+        assert!(amnesty_on(&weeks, day(16)).is_none());
+    }
+}
@@ -0,0 +1,361 @@
+//! Delta file retrieval: one file over HTTP(S), or the new files of an FTP/SFTP directory.
+//!
+//! Every file carries the cursor to store once it has been applied: its name for directories
+//! (files are taken in name order, so dated names work as is), and the `ETag`, `Last-Modified`
+//! or content hash for HTTP.
+
+use std::{sync::Arc, time::Duration};
+
+use reqwest::{header, StatusCode, Url};
+use russh::keys::{HashAlg, PublicKeyOrCertificate};
+use russh_sftp::client::SftpSession;
+use sha2::{Digest, Sha256};
+use suppaftp::{tokio::AsyncFtpStream, types::FileType};
+use tokio::io::AsyncReadExt;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::harvest::HarvestSource,
+};
+
+/// Longest wait for a remote server to accept a connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delta file waiting to be applied
+#[derive(Debug, Clone)]
+pub struct PendingFile {
+    pub name: String,
+    /// Stored as `last_cursor` once the file has been applied
+    pub cursor: String,
+}
+
+/// Open session on the remote side of a harvest source
+pub enum Remote {
+    /// Single file, already downloaded by [`Remote::pending_files`]
+    Http { url: Url, body: Option<Vec<u8>> },
+    Ftp { stream: AsyncFtpStream, dir: String },
+    Sftp { session: SftpSession, dir: String },
+}
+
+impl Remote {
+    pub async fn connect(source: &HarvestSource) -> AppResult<Self> {
+        let url = Url::parse(&source.url)
+            .map_err(|e| AppError::Validation(format!("Invalid harvest URL: {}", e)))?;
+        match url.scheme() {
+            "http" | "https" => Ok(Self::Http { url, body: None }),
+            "ftp" => {
+                let addr = (host(&url)?, url.port().unwrap_or(21));
+                let mut stream = timeout("FTP connect", AsyncFtpStream::connect(addr))
+                    .await?
+                    .map_err(|e| remote_error("FTP connect", e))?;
+                let user = source.username.as_deref().unwrap_or("anonymous");
+                let password = source.password.as_deref().unwrap_or("anonymous");
+                stream.login(user, password).await.map_err(|e| remote_error("FTP login", e))?;
+                stream
+                    .transfer_type(FileType::Binary)
+                    .await
+                    .map_err(|e| remote_error("FTP binary mode", e))?;
+                Ok(Self::Ftp { stream, dir: directory(&url) })
+            }
+            "sftp" => {
+                let session = sftp_connect(source, &url).await?;
+                Ok(Self::Sftp { session, dir: directory(&url) })
+            }
+            other => Err(AppError::Validation(format!("Unsupported harvest URL scheme: {}", other))),
+        }
+    }
+
+    /// Files not applied yet (after `last_cursor`), oldest first
+    pub async fn pending_files(
+        &mut self,
+        http: &reqwest::Client,
+        source: &HarvestSource,
+    ) -> AppResult<Vec<PendingFile>> {
+        let last = source.last_cursor.as_deref();
+        let names = match self {
+            Self::Http { url, body } => {
+                let Some((data, cursor)) = http_fetch(http, url, source).await? else {
+                    return Ok(Vec::new());
+                };
+                if last == Some(cursor.as_str()) {
+                    return Ok(Vec::new());
+                }
+                *body = Some(data);
+                let name = url
+                    .path_segments()
+                    .and_then(|mut s| s.next_back())
+                    .filter(|s| !s.is_empty())
+                    .unwrap_or("delta")
+                    .to_string();
+                return Ok(vec![PendingFile { name, cursor }]);
+            }
+            Self::Ftp { stream, dir } => stream
+                .nlst(Some(dir.as_str()))
+                .await
+                .map_err(|e| remote_error("FTP list", e))?
+                .into_iter()
+                // Some servers answer NLST with full paths
+                .map(|name| name.rsplit('/').next().unwrap_or_default().to_string())
+                .collect::<Vec<_>>(),
+            Self::Sftp { session, dir } => session
+                .read_dir(dir.as_str())
+                .await
+                .map_err(|e| remote_error("SFTP list", e))?
+                .filter(|entry| entry.file_type().is_file())
+                .map(|entry| entry.file_name())
+                .collect(),
+        };
+        Ok(select_files(names, &source.file_pattern, last))
+    }
+
+    pub async fn download(&mut self, file: &PendingFile) -> AppResult<Vec<u8>> {
+        match self {
+            Self::Http { body, .. } => body
+                .take()
+                .ok_or_else(|| AppError::Internal("Harvest file already consumed".to_string())),
+            Self::Ftp { stream, dir } => {
+                let path = format!("{}{}", dir, file.name);
+                stream
+                    .retr(path, |mut data| {
+                        Box::pin(async move {
+                            let mut buf = Vec::new();
+                            data.read_to_end(&mut buf)
+                                .await
+                                .map_err(suppaftp::FtpError::ConnectionError)?;
+                            Ok((buf, data))
+                        })
+                    })
+                    .await
+                    .map_err(|e| remote_error("FTP download", e))
+            }
+            Self::Sftp { session, dir } => session
+                .read(format!("{}{}", dir, file.name))
+                .await
+                .map_err(|e| remote_error("SFTP download", e)),
+        }
+    }
+
+    pub async fn close(self) {
+        match self {
+            Self::Http { .. } => {}
+            Self::Ftp { mut stream, .. } => {
+                let _ = stream.quit().await;
+            }
+            Self::Sftp { session, .. } => {
+                let _ = session.close().await;
+            }
+        }
+    }
+}
+
+/// Conditional GET: `None` when the server answers 304 Not Modified
+async fn http_fetch(
+    http: &reqwest::Client,
+    url: &Url,
+    source: &HarvestSource,
+) -> AppResult<Option<(Vec<u8>, String)>> {
+    let mut request = http.get(url.clone());
+    if let Some(user) = &source.username {
+        request = request.basic_auth(user, source.password.as_deref());
+    }
+    if let Some(cursor) = &source.last_cursor {
+        if let Some(etag) = cursor.strip_prefix("etag:") {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        } else if let Some(modified) = cursor.strip_prefix("modified:") {
+            request = request.header(header::IF_MODIFIED_SINCE, modified);
+        }
+    }
+    let response = request.send().await.map_err(|e| remote_error("HTTP request", e))?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(AppError::Internal(format!("HTTP request failed: {}", response.status())));
+    }
+    let header_value = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v: &header::HeaderValue| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let validator = header_value(header::ETAG)
+        .map(|etag| format!("etag:{}", etag))
+        .or_else(|| header_value(header::LAST_MODIFIED).map(|m| format!("modified:{}", m)));
+    let body = response.bytes().await.map_err(|e| remote_error("HTTP download", e))?.to_vec();
+    let cursor = validator.unwrap_or_else(|| format!("sha256:{}", hex::encode(Sha256::digest(&body))));
+    Ok(Some((body, cursor)))
+}
+
+/// Accepts the server only when its key matches the configured fingerprint
+struct PinnedHostKey(String);
+
+impl russh::client::Handler for PinnedHostKey {
+    type Error = russh::Error;
+
+    async fn check_server_key(&mut self, key: &PublicKeyOrCertificate) -> Result<bool, Self::Error> {
+        let fingerprint = match key {
+            PublicKeyOrCertificate::PublicKey { key, .. } => key.fingerprint(HashAlg::Sha256),
+            PublicKeyOrCertificate::Certificate(cert) => cert.public_key().fingerprint(HashAlg::Sha256),
+        };
+        Ok(fingerprint.to_string() == self.0)
+    }
+}
+
+async fn sftp_connect(source: &HarvestSource, url: &Url) -> AppResult<SftpSession> {
+    sftp_open(
+        url,
+        source.username.as_deref(),
+        source.password.as_deref(),
+        source.host_key_fingerprint.as_deref(),
+    )
+    .await
+}
+
+/// SFTP session authenticated by password, on a server whose host key matches `fingerprint`
+/// (also used by the warehouse exports)
+pub(crate) async fn sftp_open(
+    url: &Url,
+    username: Option<&str>,
+    password: Option<&str>,
+    fingerprint: Option<&str>,
+) -> AppResult<SftpSession> {
+    let fingerprint = fingerprint
+        .map(str::to_string)
+        .ok_or_else(|| AppError::Validation("SFTP connections need a host key fingerprint".to_string()))?;
+    let (Some(user), Some(password)) = (username, password) else {
+        return Err(AppError::Validation("SFTP connections need a username and a password".to_string()));
+    };
+
+    let config = Arc::new(russh::client::Config::default());
+    let addr = (host(url)?, url.port().unwrap_or(22));
+    let mut handle = timeout("SFTP connect", russh::client::connect(config, addr, PinnedHostKey(fingerprint)))
+        .await?
+        .map_err(|e| match e {
+            russh::Error::UnknownKey => AppError::Internal(
+                "SFTP server key does not match the configured fingerprint".to_string(),
+            ),
+            e => remote_error("SFTP connect", e),
+        })?;
+    let auth = handle
+        .authenticate_password(user, password)
+        .await
+        .map_err(|e| remote_error("SFTP login", e))?;
+    if !auth.success() {
+        return Err(AppError::Internal("SFTP login refused".to_string()));
+    }
+    let channel = handle
+        .channel_open_session()
+        .await
+        .map_err(|e| remote_error("SFTP session", e))?;
+    channel
+        .request_subsystem(true, "sftp")
+        .await
+        .map_err(|e| remote_error("SFTP session", e))?;
+    SftpSession::new(channel.into_stream())
+        .await
+        .map_err(|e| remote_error("SFTP session", e))
+}
+
+async fn timeout<T>(what: &str, future: impl std::future::Future<Output = T>) -> AppResult<T> {
+    tokio::time::timeout(CONNECT_TIMEOUT, future)
+        .await
+        .map_err(|_| AppError::Internal(format!("{} timed out", what)))
+}
+
+pub(crate) fn remote_error(what: &str, e: impl std::fmt::Display) -> AppError {
+    AppError::Internal(format!("{} failed: {}", what, e))
+}
+
+fn host(url: &Url) -> AppResult<String> {
+    url.host_str()
+        .map(str::to_string)
+        .ok_or_else(|| AppError::Validation("Harvest URL has no host".to_string()))
+}
+
+/// Directory part of the URL path, with a trailing slash
+fn directory(url: &Url) -> String {
+    let path = url.path();
+    let dir = &path[..path.rfind('/').map_or(0, |i| i + 1)];
+    if dir.is_empty() { "/".to_string() } else { dir.to_string() }
+}
+
+/// Names matching `pattern` that sort after `last`, in name order
+pub(super) fn select_files(names: Vec<String>, pattern: &str, last: Option<&str>) -> Vec<PendingFile> {
+    let mut names: Vec<String> = names
+        .into_iter()
+        .filter(|name| !name.is_empty() && glob_match(pattern, name))
+        .filter(|name| last.is_none_or(|last| name.as_str() > last))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .map(|name| PendingFile { cursor: name.clone(), name })
+        .collect()
+}
+
+/// Shell-style match with `*` and `?`
+pub(super) fn glob_match(pattern: &str, name: &str) -> bool {
+    let (p, n): (Vec<char>, Vec<char>) = (pattern.chars().collect(), name.chars().collect());
+    let (mut pi, mut ni) = (0, 0);
+    // Position after the last `*` and the name position it matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+    while ni < n.len() {
+        match p.get(pi) {
+            Some('*') => {
+                backtrack = Some((pi + 1, ni));
+                pi += 1;
+            }
+            Some(&c) if c == '?' || c == n[ni] => {
+                pi += 1;
+                ni += 1;
+            }
+            _ => match backtrack {
+                Some((star_pi, star_ni)) => {
+                    pi = star_pi;
+                    ni = star_ni + 1;
+                    backtrack = Some((star_pi, star_ni + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_patterns() {
+        assert!(glob_match("*", "delta_20261017.mrc"));
+        assert!(glob_match("delta_*.mrc", "delta_20261017.mrc"));
+        assert!(glob_match("delta_????????.mrc", "delta_20261017.mrc"));
+        assert!(!glob_match("delta_*.mrc", "delta_20261017.xml"));
+        assert!(!glob_match("delta_*.mrc", "full_20261017.mrc"));
+        assert!(glob_match("*_*.mrc", "a_b_c.mrc"));
+    }
+
+    #[test]
+    fn only_files_after_the_cursor_are_selected_in_name_order() {
+        let names = ["delta_03.mrc", "README", "delta_01.mrc", "delta_02.mrc"]
+            .map(str::to_string)
+            .to_vec();
+        let files = select_files(names.clone(), "delta_*.mrc", Some("delta_01.mrc"));
+        let selected: Vec<_> = files.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(selected, ["delta_02.mrc", "delta_03.mrc"]);
+        assert_eq!(files[1].cursor, "delta_03.mrc");
+
+        assert_eq!(select_files(names, "delta_*.mrc", None).len(), 3);
+    }
+
+    #[test]
+    fn directory_of_url() {
+        let dir = |u: &str| directory(&Url::parse(u).unwrap());
+        assert_eq!(dir("ftp://example.org/pub/deltas/"), "/pub/deltas/");
+        assert_eq!(dir("sftp://example.org:2222/deltas"), "/");
+        assert_eq!(dir("ftp://example.org"), "/");
+    }
+}
@@ -0,0 +1,540 @@
+//! Nightly harvest of union catalog deltas
+//!
+//! Each source points at the delta files published by a regional network (one HTTP file, or an
+//! FTP/SFTP directory). A run downloads the files not applied yet, then creates, refreshes or
+//! archives the local records linked to the remote record ids (`harvest_records`). Every run
+//! leaves a report in `harvest_runs`; failed runs and rejected records are mailed to the
+//! source's alert addresses.
+
+mod fetch;
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone, Utc};
+use z3950_rs::marc_rs::{
+    record::{Identification, RecordStatus},
+    MarcReader,
+};
+
+use crate::{
+    email::EmailService,
+    error::{AppError, AppResult},
+    marc::MarcRecord,
+    models::harvest::{
+        CreateHarvestSource, HarvestDeleteAction, HarvestMarcFormat, HarvestMappingProfile, HarvestRecordError,
+        HarvestRecordIdField, HarvestRun, HarvestRunStatus, HarvestSource, HarvestTrigger, UpdateHarvestSource,
+    },
+    repository::HarvestRepository,
+    services::{
+        audit::{self, AuditService},
+        catalog::CatalogService,
+    },
+};
+
+use fetch::{PendingFile, Remote};
+pub(crate) use fetch::{remote_error, sftp_open};
+
+/// Rejected records kept in a run report (the count keeps going)
+const MAX_RECORD_ERRORS: usize = 200;
+/// Runs returned by default / at most by the run history
+const DEFAULT_RUNS_LIMIT: i64 = 20;
+const MAX_RUNS_LIMIT: i64 = 100;
+
+#[derive(Clone)]
+pub struct HarvestService {
+    repository: Arc<dyn HarvestRepository>,
+    catalog: CatalogService,
+    email: EmailService,
+    audit: AuditService,
+    http: reqwest::Client,
+}
+
+/// What applying one record did
+enum Applied {
+    Created,
+    Updated,
+    Deleted,
+    Skipped,
+}
+
+impl HarvestService {
+    pub fn new(
+        repository: Arc<dyn HarvestRepository>,
+        catalog: CatalogService,
+        email: EmailService,
+        audit: AuditService,
+    ) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(600))
+            .connect_timeout(std::time::Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+        Self { repository, catalog, email, audit, http }
+    }
+
+    // =========================================================================
+    // Sources
+    // =========================================================================
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list_sources(&self) -> AppResult<Vec<HarvestSource>> {
+        self.repository.harvest_sources_list().await
+    }
+
+    pub async fn get_source(&self, id: i64) -> AppResult<HarvestSource> {
+        self.repository.harvest_sources_get(id).await
+    }
+
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn create_source(&self, data: &CreateHarvestSource) -> AppResult<HarvestSource> {
+        validate_name(Some(&data.name))?;
+        validate_schedule_time(data.schedule_time.as_deref())?;
+        validate_alert_emails(data.alert_emails.as_deref())?;
+        validate_connection(
+            &data.url,
+            data.username.as_deref(),
+            data.password.as_deref().is_some_and(|p| !p.is_empty()),
+            data.host_key_fingerprint.as_deref(),
+        )?;
+        self.repository.harvest_sources_create(data).await
+    }
+
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn update_source(&self, id: i64, data: &UpdateHarvestSource) -> AppResult<HarvestSource> {
+        validate_name(data.name.as_deref())?;
+        validate_schedule_time(data.schedule_time.as_deref())?;
+        validate_alert_emails(data.alert_emails.as_deref())?;
+        let current = self.repository.harvest_sources_get(id).await?;
+        // Checked against the merged configuration: a new URL may need the stored credentials
+        let has_password = match data.password.as_deref() {
+            Some(p) => !p.is_empty(),
+            None => current.has_password,
+        };
+        validate_connection(
+            data.url.as_deref().unwrap_or(&current.url),
+            data.username.as_deref().or(current.username.as_deref()),
+            has_password,
+            data.host_key_fingerprint.as_deref().or(current.host_key_fingerprint.as_deref()),
+        )?;
+        self.repository.harvest_sources_update(id, data).await
+    }
+
+    /// Delete a source with its run history (harvested records stay in the catalog)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete_source(&self, id: i64) -> AppResult<()> {
+        self.repository.harvest_sources_delete(id).await
+    }
+
+    // =========================================================================
+    // Runs
+    // =========================================================================
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn runs(&self, source_id: i64, limit: Option<i64>) -> AppResult<Vec<HarvestRun>> {
+        self.repository.harvest_sources_get(source_id).await?;
+        let limit = limit.unwrap_or(DEFAULT_RUNS_LIMIT).clamp(1, MAX_RUNS_LIMIT);
+        self.repository.harvest_runs_list(source_id, limit).await
+    }
+
+    pub async fn run(&self, id: i64) -> AppResult<HarvestRun> {
+        self.repository.harvest_runs_get(id).await
+    }
+
+    /// Open a manual run; the caller executes it in the background with [`Self::execute`]
+    #[tracing::instrument(skip(self), err)]
+    pub async fn start_run(&self, source_id: i64, started_by: i64) -> AppResult<(HarvestSource, HarvestRun)> {
+        let source = self.repository.harvest_sources_get(source_id).await?;
+        let run = self
+            .repository
+            .harvest_runs_start(source.id, source.last_run_at, HarvestTrigger::Manual, Some(started_by))
+            .await?
+            .ok_or_else(|| AppError::Conflict("A harvest of this source is already running".to_string()))?;
+        Ok((source, run))
+    }
+
+    /// Start the scheduled runs whose time has come (called every minute by the scheduler)
+    pub async fn run_due(&self) -> AppResult<()> {
+        let now = Local::now();
+        for source in self.repository.harvest_sources_list().await? {
+            let last = source.last_run_at.unwrap_or(source.created_at);
+            if !source.is_active || !is_due(&source.schedule_time, last, now) {
+                continue;
+            }
+            // Another instance may have claimed the source since it was listed
+            let Some(run) = self
+                .repository
+                .harvest_runs_start(source.id, source.last_run_at, HarvestTrigger::Schedule, None)
+                .await?
+            else {
+                continue;
+            };
+            tracing::info!(source_id = source.id, run_id = run.id, "Starting scheduled harvest");
+            if let Err(e) = self.execute(source, run).await {
+                tracing::error!("Could not store the harvest report: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetch and apply the pending delta files, then store the report and send the alerts
+    #[tracing::instrument(skip(self, source, run), fields(source_id = source.id, run_id = run.id))]
+    pub async fn execute(&self, source: HarvestSource, mut run: HarvestRun) -> AppResult<HarvestRun> {
+        if let Err(e) = self.apply_deltas(&source, &mut run).await {
+            tracing::warn!("Harvest of source {} failed: {}", source.id, e);
+            run.error = Some(e.to_string());
+        }
+        run.status = if run.error.is_some() {
+            HarvestRunStatus::Failed
+        } else if run.failed_count > 0 {
+            HarvestRunStatus::Partial
+        } else {
+            HarvestRunStatus::Succeeded
+        };
+        let run = self.repository.harvest_runs_finish(&run).await?;
+
+        let payload = serde_json::json!({
+            "sourceId": source.id,
+            "status": run.status.as_str(),
+            "files": run.files,
+            "created": run.created_count,
+            "updated": run.updated_count,
+            "deleted": run.deleted_count,
+            "skipped": run.skipped_count,
+            "failed": run.failed_count,
+            "error": run.error,
+        });
+        if run.status == HarvestRunStatus::Succeeded {
+            self.audit.log(
+                audit::event::HARVEST_RUN_COMPLETED,
+                run.started_by,
+                Some("harvest_run"),
+                Some(run.id),
+                None,
+                Some(payload),
+                audit::AuditLogMeta::success(),
+            );
+        } else {
+            self.audit.log(
+                audit::event::HARVEST_RUN_FAILED,
+                run.started_by,
+                Some("harvest_run"),
+                Some(run.id),
+                None,
+                Some(payload),
+                audit::AuditLogMeta::failure_background(
+                    run.status.as_str(),
+                    run.error.clone().unwrap_or_else(|| format!("{} records rejected", run.failed_count)),
+                ),
+            );
+            self.send_alert(&source, &run).await;
+        }
+        Ok(run)
+    }
+
+    async fn apply_deltas(&self, source: &HarvestSource, run: &mut HarvestRun) -> AppResult<()> {
+        let mut remote = Remote::connect(source).await?;
+        let result = async {
+            let files = remote.pending_files(&self.http, source).await?;
+            for file in files {
+                let data = remote.download(&file).await?;
+                self.apply_file(source, run, &file, data).await?;
+                // A file is never applied twice, even if a later one fails
+                self.repository.harvest_sources_set_cursor(source.id, &file.cursor).await?;
+                run.files.push(file.name);
+            }
+            Ok(())
+        }
+        .await;
+        remote.close().await;
+        result
+    }
+
+    async fn apply_file(
+        &self,
+        source: &HarvestSource,
+        run: &mut HarvestRun,
+        file: &PendingFile,
+        data: Vec<u8>,
+    ) -> AppResult<()> {
+        let records = match source.marc_format {
+            HarvestMarcFormat::Iso2709 => MarcReader::from_binary(data).into_records(),
+            HarvestMarcFormat::Marcxml => MarcReader::from_xml(&data).and_then(|r| r.into_records()),
+        }
+        .map_err(|e| AppError::Validation(format!("{}: MARC parse error: {}", file.name, e)))?;
+
+        let profile = &source.mapping_profile;
+        for record in records {
+            let Some(remote_id) = remote_record_id(&record.identification, profile) else {
+                run.failed_count += 1;
+                push_record_error(run, &file.name, None, "No record id in the configured field");
+                continue;
+            };
+            match self.apply_record(source, &remote_id, record).await {
+                Ok(Applied::Created) => run.created_count += 1,
+                Ok(Applied::Updated) => run.updated_count += 1,
+                Ok(Applied::Deleted) => run.deleted_count += 1,
+                Ok(Applied::Skipped) => run.skipped_count += 1,
+                Err(e) => {
+                    run.failed_count += 1;
+                    push_record_error(run, &file.name, Some(remote_id), &e.to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn apply_record(&self, source: &HarvestSource, remote_id: &str, record: MarcRecord) -> AppResult<Applied> {
+        let profile = &source.mapping_profile;
+        let mut linked = self.repository.harvest_records_get(source.id, remote_id).await?;
+
+        if matches!(record.leader.status, RecordStatus::Deleted) {
+            let Some(biblio_id) = linked else {
+                return Ok(Applied::Skipped);
+            };
+            if profile.on_delete == HarvestDeleteAction::Archive {
+                match self.catalog.delete_biblio(biblio_id, false).await {
+                    Ok(()) | Err(AppError::NotFound(_)) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            self.repository.harvest_records_unlink(source.id, remote_id).await?;
+            return Ok(Applied::Deleted);
+        }
+
+        if let Some(biblio_id) = linked {
+            if !profile.update_existing {
+                return Ok(Applied::Skipped);
+            }
+            match self.catalog.refresh_biblio_from_z3950_marc(biblio_id, record.clone()).await {
+                Ok(_) => {
+                    self.repository.harvest_records_link(source.id, remote_id, biblio_id).await?;
+                    return Ok(Applied::Updated);
+                }
+                // Removed locally since the last harvest: created again below
+                Err(AppError::NotFound(_)) => {
+                    self.repository.harvest_records_unlink(source.id, remote_id).await?;
+                    linked = None;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        debug_assert!(linked.is_none());
+
+        let mut biblio: crate::models::biblio::Biblio = record.clone().into();
+        if profile.import_items {
+            for item in &mut biblio.items {
+                item.source_id = source.catalog_source_id;
+            }
+        } else {
+            biblio.items.clear();
+        }
+        match self.catalog.create_biblio(biblio, false, None).await {
+            Ok((biblio, _)) => {
+                let id = biblio
+                    .id
+                    .ok_or_else(|| AppError::Internal("Created biblio has no id".to_string()))?;
+                self.repository.harvest_records_link(source.id, remote_id, id).await?;
+                Ok(Applied::Created)
+            }
+            Err(AppError::DuplicateNeedsConfirmation { existing_id, .. }) if profile.match_by_isbn => {
+                self.catalog.refresh_biblio_from_z3950_marc(existing_id, record).await?;
+                self.repository.harvest_records_link(source.id, remote_id, existing_id).await?;
+                Ok(Applied::Updated)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn send_alert(&self, source: &HarvestSource, run: &HarvestRun) {
+        if source.alert_emails.is_empty() {
+            return;
+        }
+        let subject = format!("Harvest of \"{}\" {}", source.name, match run.status {
+            HarvestRunStatus::Failed => "failed",
+            _ => "rejected records",
+        });
+        let mut plain = format!(
+            "Harvest run #{} of \"{}\" ({})\n\nFiles: {}\nCreated: {}\nUpdated: {}\nDeleted: {}\nSkipped: {}\nRejected: {}\n",
+            run.id,
+            source.name,
+            source.url,
+            if run.files.is_empty() { "-".to_string() } else { run.files.join(", ") },
+            run.created_count,
+            run.updated_count,
+            run.deleted_count,
+            run.skipped_count,
+            run.failed_count,
+        );
+        if let Some(error) = &run.error {
+            plain.push_str(&format!("\nError: {}\n", error));
+        }
+        if !run.record_errors.is_empty() {
+            plain.push_str("\nRejected records:\n");
+            for e in run.record_errors.iter().take(20) {
+                plain.push_str(&format!(
+                    "- {} {}: {}\n",
+                    e.file,
+                    e.record_id.as_deref().unwrap_or("?"),
+                    e.error
+                ));
+            }
+        }
+        let html = format!(
+            "<html><body><pre>{}</pre></body></html>",
+            plain.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+        );
+        for to in &source.alert_emails {
+            if let Err(e) = self.email.send_email_with_html(to, &subject, &plain, &html).await {
+                tracing::warn!("Harvest alert to {} failed: {}", to, e);
+            }
+        }
+    }
+}
+
+fn push_record_error(run: &mut HarvestRun, file: &str, record_id: Option<String>, error: &str) {
+    if run.record_errors.len() < MAX_RECORD_ERRORS {
+        run.record_errors.push(HarvestRecordError {
+            file: file.to_string(),
+            record_id,
+            error: error.to_string(),
+        });
+    }
+}
+
+/// Remote id of a record according to the mapping profile
+fn remote_record_id(identification: &Identification, profile: &HarvestMappingProfile) -> Option<String> {
+    let id = match profile.record_id_field {
+        HarvestRecordIdField::ControlNumber => identification.record_id.as_deref(),
+        HarvestRecordIdField::SystemControlNumber => identification
+            .system_control_numbers
+            .iter()
+            .map(String::as_str)
+            .find(|v| profile.record_id_prefix.as_deref().is_none_or(|p| v.starts_with(p))),
+    };
+    id.map(str::trim).filter(|id| !id.is_empty()).map(str::to_string)
+}
+
+/// Whether the last occurrence of `schedule_time` (local HH:MM) up to `now` is after `last_run`
+pub(crate) fn is_due<Tz: TimeZone>(schedule_time: &str, last_run: DateTime<Utc>, now: DateTime<Tz>) -> bool {
+    let Some(time) = parse_schedule_time(schedule_time) else {
+        return false;
+    };
+    let today = now.date_naive().and_time(time);
+    let occurrence = if now.naive_local() >= today { today } else { today - Duration::days(1) };
+    // Skipped or repeated local times (DST change) fall back to the earliest match
+    match now.timezone().from_local_datetime(&occurrence).earliest() {
+        Some(occurrence) => occurrence.with_timezone(&Utc) > last_run,
+        None => false,
+    }
+}
+
+fn parse_schedule_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M").ok().filter(|_| value.len() == 5)
+}
+
+pub(crate) fn validate_name(name: Option<&str>) -> AppResult<()> {
+    match name {
+        Some(n) if n.trim().is_empty() || n.len() > 100 => {
+            Err(AppError::Validation("name must be 1 to 100 characters".to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn validate_schedule_time(value: Option<&str>) -> AppResult<()> {
+    match value {
+        Some(v) if parse_schedule_time(v).is_none() => {
+            Err(AppError::Validation("scheduleTime must be HH:MM".to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate_alert_emails(emails: Option<&[String]>) -> AppResult<()> {
+    match emails.unwrap_or_default().iter().find(|e| !e.contains('@')) {
+        Some(e) => Err(AppError::Validation(format!("Invalid alert email: {}", e))),
+        None => Ok(()),
+    }
+}
+
+fn validate_connection(
+    url: &str,
+    username: Option<&str>,
+    has_password: bool,
+    host_key_fingerprint: Option<&str>,
+) -> AppResult<()> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| AppError::Validation(format!("Invalid url: {}", e)))?;
+    if parsed.host_str().is_none() {
+        return Err(AppError::Validation("url must include a host".to_string()));
+    }
+    match parsed.scheme() {
+        "http" | "https" | "ftp" => Ok(()),
+        "sftp" => {
+            if !host_key_fingerprint.is_some_and(|f| f.starts_with("SHA256:")) {
+                return Err(AppError::Validation(
+                    "SFTP sources need hostKeyFingerprint (SHA256:…, see `ssh-keygen -lf`)".to_string(),
+                ));
+            }
+            if username.is_none_or(str::is_empty) || !has_password {
+                return Err(AppError::Validation("SFTP sources need a username and a password".to_string()));
+            }
+            Ok(())
+        }
+        other => Err(AppError::Validation(format!(
+            "Unsupported url scheme {} (http, https, ftp or sftp)",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn at(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn source_is_due_once_per_scheduled_time() {
+        let last = |s: &str| at(s).with_timezone(&Utc);
+        // Last run yesterday night, it is 02:31: due
+        assert!(is_due("02:30", last("2026-10-16T02:30:05+02:00"), at("2026-10-17T02:31:00+02:00")));
+        // Already ran tonight
+        assert!(!is_due("02:30", last("2026-10-17T02:30:05+02:00"), at("2026-10-17T02:31:00+02:00")));
+        // Before tonight's time, the last occurrence (yesterday) was handled
+        assert!(!is_due("02:30", last("2026-10-16T02:30:05+02:00"), at("2026-10-17T01:00:00+02:00")));
+        // Instance down during the night: caught up at restart
+        assert!(is_due("02:30", last("2026-10-15T02:30:05+02:00"), at("2026-10-16T11:00:00+02:00")));
+        assert!(!is_due("2:30", last("2026-10-15T02:30:05+02:00"), at("2026-10-17T11:00:00+02:00")));
+    }
+
+    #[test]
+    fn record_id_from_control_number_or_prefixed_035() {
+        let identification = Identification {
+            record_id: Some(" 123456789 ".to_string()),
+            system_control_numbers: vec!["(OCoLC)42".to_string(), "(PPN)987".to_string()],
+            ..Default::default()
+        };
+        let mut profile = HarvestMappingProfile::default();
+        assert_eq!(remote_record_id(&identification, &profile).as_deref(), Some("123456789"));
+
+        profile.record_id_field = HarvestRecordIdField::SystemControlNumber;
+        assert_eq!(remote_record_id(&identification, &profile).as_deref(), Some("(OCoLC)42"));
+        profile.record_id_prefix = Some("(PPN)".to_string());
+        assert_eq!(remote_record_id(&identification, &profile).as_deref(), Some("(PPN)987"));
+        profile.record_id_prefix = Some("(BNF)".to_string());
+        assert_eq!(remote_record_id(&identification, &profile), None);
+    }
+
+    #[test]
+    fn connection_settings_are_checked_per_protocol() {
+        assert!(validate_connection("https://example.org/delta.mrc", None, false, None).is_ok());
+        assert!(validate_connection("ftp://example.org/deltas/", None, false, None).is_ok());
+        assert!(validate_connection("sftp://example.org/deltas/", Some("lib"), true, None).is_err());
+        assert!(validate_connection("sftp://example.org/deltas/", Some("lib"), false, Some("SHA256:abc")).is_err());
+        assert!(validate_connection("sftp://example.org/deltas/", Some("lib"), true, Some("SHA256:abc")).is_ok());
+        assert!(validate_connection("file:///etc/passwd", None, false, None).is_err());
+    }
+}
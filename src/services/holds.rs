@@ -2,9 +2,11 @@
 
 use std::sync::Arc;
 
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+
 use crate::{
     error::{AppError, AppResult},
-    models::hold::{CreateHold, Hold, HoldDetails},
+    models::hold::{CreateHold, Hold, HoldDetails, HoldShelfEntry, HoldShelfGroup},
     repository::HoldsRepository,
 };
 
@@ -91,9 +93,115 @@ impl HoldsService {
         self.repository.holds_count_for_item(item_id).await
     }
 
-    /// Active holds (`pending` / `ready`) across all copies of a biblio.
+    /// Copies to pull from the shelves for queued holds, grouped by shelving location.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn pull_list(&self) -> AppResult<Vec<HoldShelfGroup>> {
+        Ok(group_by_location(self.repository.holds_pull_list().await?))
+    }
+
+    /// Copies to take off the hold shelf and reshelve, for holds expired since `since`
+    /// (default: the last 7 days), grouped by shelving location.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn expired_to_reshelve(&self, since: Option<NaiveDate>) -> AppResult<Vec<HoldShelfGroup>> {
+        let since = since.unwrap_or_else(|| Local::now().date_naive() - Duration::days(7));
+        let rows = self.repository.holds_expired_to_reshelve(local_day_start(since)).await?;
+        Ok(group_by_location(rows))
+    }
+
+    /// Active holds (`pending` / `ready`) across the copies of a biblio shown in the OPAC.
     #[tracing::instrument(skip(self), err)]
     pub async fn count_active_for_biblio(&self, biblio_id: i64) -> AppResult<i64> {
         self.repository.holds_count_active_for_biblio(biblio_id).await
     }
 }
+
+/// First instant of a local day, in UTC
+fn local_day_start(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+/// Split rows already sorted by place and location into one group per shelving location
+/// (per place for copies not located yet), keeping their order.
+fn group_by_location(rows: Vec<HoldShelfEntry>) -> Vec<HoldShelfGroup> {
+    let mut groups: Vec<HoldShelfGroup> = Vec::new();
+    for row in rows {
+        match groups.last_mut() {
+            Some(group) if group.place == row.place && group.location_id == row.location_id => {
+                group.entries.push(row)
+            }
+            _ => groups.push(HoldShelfGroup {
+                place: row.place,
+                location_id: row.location_id,
+                location_path: row.location_path.clone(),
+                entries: vec![row],
+            }),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hold_id: i64, place: Option<i16>, location_id: Option<i64>) -> HoldShelfEntry {
+        HoldShelfEntry {
+            hold_id,
+            item_id: hold_id,
+            biblio_id: 1,
+            barcode: None,
+            call_number: None,
+            place,
+            location_id,
+            location_path: location_id.map(|id| format!("Location {}", id)),
+            title: None,
+            user_id: 1,
+            user_name: None,
+            created_at: Utc::now(),
+            expires_at: None,
+            pickup_locker: false,
+        }
+    }
+
+    #[test]
+    fn rows_are_grouped_by_consecutive_place() {
+        let groups = group_by_location(vec![
+            entry(1, Some(2), None),
+            entry(2, Some(2), None),
+            entry(3, Some(5), None),
+            entry(4, None, None),
+        ]);
+        let shape: Vec<(Option<i16>, Vec<i64>)> = groups
+            .iter()
+            .map(|g| (g.place, g.entries.iter().map(|e| e.hold_id).collect()))
+            .collect();
+        assert_eq!(shape, vec![(Some(2), vec![1, 2]), (Some(5), vec![3]), (None, vec![4])]);
+    }
+
+    #[test]
+    fn located_rows_are_grouped_by_location() {
+        let groups = group_by_location(vec![
+            entry(1, Some(2), Some(7)),
+            entry(2, Some(2), Some(7)),
+            entry(3, Some(2), Some(8)),
+            entry(4, Some(2), None),
+        ]);
+        let shape: Vec<(Option<String>, Vec<i64>)> = groups
+            .iter()
+            .map(|g| (g.location_path.clone(), g.entries.iter().map(|e| e.hold_id).collect()))
+            .collect();
+        assert_eq!(
+            shape,
+            vec![
+                (Some("Location 7".to_string()), vec![1, 2]),
+                (Some("Location 8".to_string()), vec![3]),
+                (None, vec![4]),
+            ]
+        );
+    }
+}
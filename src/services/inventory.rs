@@ -47,10 +47,11 @@ impl InventoryService {
         location_filter: Option<&str>,
         notes: Option<&str>,
         scope_place: Option<i16>,
+        scope_location_id: Option<i64>,
         created_by: Option<i64>,
     ) -> AppResult<InventorySession> {
         self.repository
-            .inventory_create_session(name, location_filter, notes, scope_place, created_by)
+            .inventory_create_session(name, location_filter, notes, scope_place, scope_location_id, created_by)
             .await
     }
 
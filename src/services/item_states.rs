@@ -0,0 +1,126 @@
+//! Item state taxonomy service
+
+use std::sync::Arc;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::item_state::{CreateItemState, ItemState, UpdateItemState},
+    repository::ItemStatesRepository,
+    services::catalog::CatalogService,
+};
+
+#[derive(Clone)]
+pub struct ItemStatesService {
+    repository: Arc<dyn ItemStatesRepository>,
+    catalog: Option<CatalogService>,
+}
+
+impl ItemStatesService {
+    pub fn new(repository: Arc<dyn ItemStatesRepository>) -> Self {
+        Self { repository, catalog: None }
+    }
+
+    /// Reindex the records holding copies of a state whose OPAC visibility changes
+    pub fn with_catalog(mut self, catalog: CatalogService) -> Self {
+        self.catalog = Some(catalog);
+        self
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self) -> AppResult<Vec<ItemState>> {
+        self.repository.item_states_list().await
+    }
+
+    pub async fn get(&self, code: i16) -> AppResult<ItemState> {
+        self.repository.item_states_get(code).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create(&self, data: &CreateItemState) -> AppResult<ItemState> {
+        if data.code < 0 {
+            return Err(AppError::Validation("code cannot be negative".to_string()));
+        }
+        validate_label(Some(&data.label))?;
+        validate_color(data.color.as_deref())?;
+        match self.repository.item_states_get(data.code).await {
+            Ok(_) => {
+                return Err(AppError::Conflict(format!("Item state {} already exists", data.code)))
+            }
+            Err(AppError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+        self.repository.item_states_create(data).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update(&self, code: i16, data: &UpdateItemState) -> AppResult<ItemState> {
+        validate_label(data.label.as_deref())?;
+        validate_color(data.color.as_deref())?;
+        let before = self.repository.item_states_get(code).await?;
+        let state = self.repository.item_states_update(code, data).await?;
+        if state.opac_visible != before.opac_visible {
+            if let Some(ref catalog) = self.catalog {
+                let biblio_ids = self.repository.item_states_biblio_ids(code).await?;
+                catalog.sync_index_many(&biblio_ids).await;
+            }
+        }
+        Ok(state)
+    }
+
+    /// Delete a state that no copy uses anymore
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete(&self, code: i16) -> AppResult<()> {
+        let in_use = self.repository.item_states_count_items(code).await?;
+        if in_use > 0 {
+            return Err(AppError::Conflict(format!(
+                "Item state {} is used by {} item(s)",
+                code, in_use
+            )));
+        }
+        self.repository.item_states_delete(code).await
+    }
+}
+
+fn validate_label(label: Option<&str>) -> AppResult<()> {
+    let Some(label) = label.map(str::trim) else {
+        return Ok(());
+    };
+    if label.is_empty() || label.chars().count() > 100 {
+        return Err(AppError::Validation(
+            "label must be between 1 and 100 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// `#RRGGBB`; an empty string is accepted (clears the color).
+fn validate_color(color: Option<&str>) -> AppResult<()> {
+    let Some(color) = color.filter(|c| !c.is_empty()) else {
+        return Ok(());
+    };
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "color must be formatted as #RRGGBB (got {})",
+            color
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_format() {
+        assert!(validate_color(None).is_ok());
+        assert!(validate_color(Some("")).is_ok());
+        assert!(validate_color(Some("#2e7D32")).is_ok());
+        assert!(validate_color(Some("2E7D32")).is_err());
+        assert!(validate_color(Some("#2E7D3")).is_err());
+        assert!(validate_color(Some("#GGGGGG")).is_err());
+    }
+}
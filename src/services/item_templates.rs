@@ -0,0 +1,152 @@
+//! Cataloging templates service
+
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{
+        biblio::Biblio,
+        item_template::{CreateItemTemplate, ItemTemplate, ItemTemplateFields},
+    },
+    repository::ItemTemplatesRepository,
+};
+
+#[derive(Clone)]
+pub struct ItemTemplatesService {
+    repository: Arc<dyn ItemTemplatesRepository>,
+}
+
+impl ItemTemplatesService {
+    pub fn new(repository: Arc<dyn ItemTemplatesRepository>) -> Self {
+        Self { repository }
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self) -> AppResult<Vec<ItemTemplate>> {
+        self.repository.item_templates_list().await
+    }
+
+    pub async fn get(&self, code: &str) -> AppResult<ItemTemplate> {
+        self.repository
+            .item_templates_get_by_code(code)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Template '{}' not found", code)))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create(&self, data: &CreateItemTemplate) -> AppResult<ItemTemplate> {
+        let code = data.code.trim();
+        validate_code(code)?;
+        self.validate_fields(&data.fields).await?;
+        self.repository.item_templates_create(code, &data.fields).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update(&self, code: &str, data: &ItemTemplateFields) -> AppResult<ItemTemplate> {
+        self.validate_fields(data).await?;
+        self.repository.item_templates_update(code, data).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete(&self, code: &str) -> AppResult<()> {
+        self.repository.item_templates_delete(code).await
+    }
+
+    /// Merge a template into a record creation body (submitted fields win) and parse it.
+    /// Returns the record and the genre to assign once it is created.
+    #[tracing::instrument(skip(self, body), err)]
+    pub async fn prepare_biblio(&self, template: Option<&str>, mut body: Value) -> AppResult<(Biblio, Option<i64>)> {
+        let genre_id = match template {
+            Some(code) => {
+                let template = self
+                    .repository
+                    .item_templates_get_by_code(code)
+                    .await?
+                    .ok_or_else(|| AppError::Validation(format!("Unknown template '{}'", code)))?;
+                template.apply(&mut body);
+                template.genre_id
+            }
+            None => None,
+        };
+        let biblio: Biblio = serde_json::from_value(body)
+            .map_err(|e| AppError::Validation(format!("Invalid record: {}", e)))?;
+        Ok((biblio, genre_id))
+    }
+
+    async fn validate_fields(&self, data: &ItemTemplateFields) -> AppResult<()> {
+        if data.name.trim().is_empty() || data.name.trim().chars().count() > 200 {
+            return Err(AppError::Validation("name must be between 1 and 200 characters".to_string()));
+        }
+        if let Some(genre_id) = data.genre_id {
+            if !self.repository.item_templates_genre_exists(genre_id).await? {
+                return Err(AppError::Validation(format!("Unknown genre {}", genre_id)));
+            }
+        }
+        self.repository.item_templates_ensure_state(data.circulation_status).await
+    }
+}
+
+/// Template codes appear in URLs: lowercase letters, digits, `_` and `-`.
+fn validate_code(code: &str) -> AppResult<()> {
+    if code.is_empty()
+        || code.len() > 64
+        || !code.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    {
+        return Err(AppError::Validation(
+            "code must be 1-64 lowercase letters, digits, '_' or '-'".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{models::biblio::MediaType, repository::item_templates::MockItemTemplatesRepository};
+
+    fn dvd() -> ItemTemplate {
+        serde_json::from_value(json!({
+            "id": "4", "code": "dvd", "name": "DVD", "description": null,
+            "mediaType": "videoDvd", "audienceType": null, "genreId": "9",
+            "place": 2, "borrowable": false, "circulationStatus": null,
+            "createdAt": null, "updateAt": null
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn prepare_biblio_merges_template() {
+        let mut repo = MockItemTemplatesRepository::new();
+        repo.expect_item_templates_get_by_code()
+            .withf(|code| code == "dvd")
+            .returning(|_| Ok(Some(dvd())));
+        repo.expect_item_templates_get_by_code().returning(|_| Ok(None));
+        let service = ItemTemplatesService::new(Arc::new(repo));
+
+        let body = json!({ "title": "Metropolis", "items": [{ "barcode": "D1" }] });
+        let (biblio, genre_id) = service.prepare_biblio(Some("dvd"), body).await.unwrap();
+        assert_eq!(biblio.media_type, MediaType::VideoDvd);
+        assert_eq!(biblio.items[0].place, Some(2));
+        assert!(!biblio.items[0].borrowable);
+        assert_eq!(genre_id, Some(9));
+
+        let err = service.prepare_biblio(Some("vhs"), json!({ "title": "x" })).await.unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+        // Without a template the body must be a complete record
+        let err = service.prepare_biblio(None, json!({ "title": "x" })).await.unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+    }
+
+    #[test]
+    fn template_codes() {
+        assert!(validate_code("novel").is_ok());
+        assert!(validate_code("kids-dvd_2").is_ok());
+        assert!(validate_code("").is_err());
+        assert!(validate_code("Novel").is_err());
+        assert!(validate_code("a b").is_err());
+    }
+}
@@ -0,0 +1,325 @@
+//! Kiosk tokens: credentials for in-library catalog terminals
+//!
+//! A kiosk token is an opaque random string (only its SHA-256 is stored), bound to a list of
+//! client addresses. It grants OPAC reads and patron self-service login, nothing else.
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+use axum::http::HeaderMap;
+use base64::Engine;
+use chrono::Utc;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::{AppError, AppResult},
+    models::kiosk::{CreateKiosk, Kiosk, KioskWithToken, UpdateKiosk, KIOSK_TOKEN_PREFIX},
+    repository::KiosksRepository,
+    services::audit::{self, AuditService},
+};
+
+/// Characters of the token kept in `token_prefix`
+const TOKEN_PREFIX_LEN: usize = 12;
+
+#[derive(Clone)]
+pub struct KiosksService {
+    repository: Arc<dyn KiosksRepository>,
+    audit: AuditService,
+}
+
+impl KiosksService {
+    pub fn new(repository: Arc<dyn KiosksRepository>, audit: AuditService) -> Self {
+        Self { repository, audit }
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self) -> AppResult<Vec<Kiosk>> {
+        self.repository.kiosks_list().await
+    }
+
+    pub async fn get(&self, id: i64) -> AppResult<Kiosk> {
+        self.repository.kiosks_get(id).await
+    }
+
+    /// Register a kiosk; the generated token is only returned here
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create(&self, data: &CreateKiosk, created_by: i64) -> AppResult<KioskWithToken> {
+        validate_name(Some(&data.name))?;
+        validate_location(data.location.as_deref())?;
+        validate_allowed_ips(&data.allowed_ips)?;
+        if data.expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(AppError::Validation("expiresAt must be in the future".to_string()));
+        }
+        let token = generate_token();
+        let kiosk = self
+            .repository
+            .kiosks_create(data, &hash_token(&token), &token[..TOKEN_PREFIX_LEN], created_by)
+            .await?;
+        Ok(KioskWithToken { kiosk, token })
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update(&self, id: i64, data: &UpdateKiosk) -> AppResult<Kiosk> {
+        validate_name(data.name.as_deref())?;
+        validate_location(data.location.as_deref())?;
+        if let Some(ips) = &data.allowed_ips {
+            validate_allowed_ips(ips)?;
+        }
+        self.repository.kiosks_update(id, data).await
+    }
+
+    /// Issue a new token; the previous one stops working immediately
+    #[tracing::instrument(skip(self), err)]
+    pub async fn regenerate_token(&self, id: i64) -> AppResult<KioskWithToken> {
+        let token = generate_token();
+        let kiosk = self
+            .repository
+            .kiosks_set_token(id, &hash_token(&token), &token[..TOKEN_PREFIX_LEN])
+            .await?;
+        Ok(KioskWithToken { kiosk, token })
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete(&self, id: i64) -> AppResult<()> {
+        self.repository.kiosks_delete(id).await
+    }
+
+    /// Resolve the kiosk presenting `token` from `ip`.
+    ///
+    /// Refusals are audited (`kiosk.access_denied`, with the kiosk id when the token is known).
+    #[tracing::instrument(skip(self, token), err)]
+    pub async fn authenticate(&self, token: &str, ip: Option<String>) -> AppResult<Kiosk> {
+        let kiosk = match self.repository.kiosks_find_by_token_hash(&hash_token(token)).await? {
+            Some(kiosk) => kiosk,
+            None => {
+                let err = AppError::Authentication("Invalid kiosk token".to_string());
+                self.audit.log(
+                    audit::event::KIOSK_ACCESS_DENIED,
+                    None,
+                    None,
+                    None,
+                    ip,
+                    None::<serde_json::Value>,
+                    audit::AuditLogMeta::from_app_error(&err),
+                );
+                return Err(err);
+            }
+        };
+
+        let refusal = if !kiosk.is_active {
+            Some(AppError::Authentication("Kiosk token has been revoked".to_string()))
+        } else if kiosk.expires_at.is_some_and(|at| at <= Utc::now()) {
+            Some(AppError::Authentication("Kiosk token has expired".to_string()))
+        } else if !ip_allowed(ip.as_deref(), &kiosk.allowed_ips) {
+            Some(AppError::Authorization(format!(
+                "Kiosk '{}' is not allowed from {}",
+                kiosk.name,
+                ip.as_deref().unwrap_or("an unknown address")
+            )))
+        } else {
+            None
+        };
+        if let Some(err) = refusal {
+            self.audit.log(
+                audit::event::KIOSK_ACCESS_DENIED,
+                None,
+                Some("kiosk"),
+                Some(kiosk.id),
+                ip,
+                Some(serde_json::json!({ "kiosk": kiosk.name })),
+                audit::AuditLogMeta::from_app_error(&err),
+            );
+            return Err(err);
+        }
+
+        if let Err(e) = self.repository.kiosks_touch(kiosk.id, ip).await {
+            tracing::warn!("Failed to record use of kiosk {}: {}", kiosk.id, e);
+        }
+        Ok(kiosk)
+    }
+}
+
+fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    format!(
+        "{}{}",
+        KIOSK_TOKEN_PREFIX,
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+    )
+}
+
+/// SHA-256 (hex) stored in place of an opaque token (kiosk tokens, API keys)
+pub(crate) fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Address a kiosk request comes from, checked against the kiosk's `allowedIps`.
+///
+/// The TCP peer address, unless the peer is one of `trusted_proxies` (`server.trusted_proxies`):
+/// then the nearest `X-Forwarded-For` hop that is not itself a trusted proxy (entries added by the
+/// client on the left are never reached), or `X-Real-IP` without `X-Forwarded-For`. Forwarded
+/// headers from any other peer are ignored, so a terminal cannot claim an allowed address.
+pub fn kiosk_client_ip(
+    headers: &HeaderMap,
+    peer: Option<SocketAddr>,
+    trusted_proxies: &[String],
+) -> Option<String> {
+    let peer = peer?.ip().to_string();
+    if !ip_allowed(Some(&peer), trusted_proxies) {
+        return Some(peer);
+    }
+    let forwarded: Vec<String> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(|hop| hop.trim().to_string())
+        .filter(|hop| !hop.is_empty())
+        .collect();
+    if !forwarded.is_empty() {
+        // Every hop trusted: the request started on the proxy side, the leftmost hop is the client
+        return forwarded
+            .iter()
+            .rev()
+            .find(|hop| !ip_allowed(Some(hop), trusted_proxies))
+            .or(forwarded.first())
+            .cloned();
+    }
+    headers
+        .get("x-real-ip")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty())
+        .map(str::to_string)
+        .or(Some(peer))
+}
+
+/// `address` or `address/prefix-length`, IPv4 or IPv6
+fn parse_ip_rule(rule: &str) -> Option<(IpAddr, u8)> {
+    let (addr, len) = match rule.trim().split_once('/') {
+        Some((addr, len)) => (addr.parse::<IpAddr>().ok()?, Some(len.parse::<u8>().ok()?)),
+        None => (rule.trim().parse::<IpAddr>().ok()?, None),
+    };
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    let len = len.unwrap_or(max);
+    (len <= max).then_some((addr, len))
+}
+
+/// Whether `ip` matches one of the address / CIDR rules (IPv4-mapped IPv6 addresses count as IPv4)
+fn ip_allowed(ip: Option<&str>, rules: &[String]) -> bool {
+    let Some(ip) = ip.and_then(|ip| ip.trim().parse::<IpAddr>().ok()) else {
+        return false;
+    };
+    let ip = match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+        v4 => v4,
+    };
+    rules.iter().filter_map(|r| parse_ip_rule(r)).any(|(net, len)| match (net, ip) {
+        (IpAddr::V4(net), IpAddr::V4(ip)) => {
+            let mask = u32::MAX.checked_shl(32 - len as u32).unwrap_or(0);
+            u32::from(net) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(net), IpAddr::V6(ip)) => {
+            let mask = u128::MAX.checked_shl(128 - len as u32).unwrap_or(0);
+            u128::from(net) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    })
+}
+
+fn validate_name(name: Option<&str>) -> AppResult<()> {
+    let Some(name) = name.map(str::trim) else {
+        return Ok(());
+    };
+    if name.is_empty() || name.chars().count() > 100 {
+        return Err(AppError::Validation(
+            "name must be between 1 and 100 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_location(location: Option<&str>) -> AppResult<()> {
+    if location.is_some_and(|l| l.trim().chars().count() > 255) {
+        return Err(AppError::Validation(
+            "location must be at most 255 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// A kiosk token is always IP-restricted: at least one valid address or CIDR range
+fn validate_allowed_ips(rules: &[String]) -> AppResult<()> {
+    if rules.is_empty() {
+        return Err(AppError::Validation(
+            "allowedIps must list at least one address or CIDR range".to_string(),
+        ));
+    }
+    if let Some(bad) = rules.iter().find(|r| parse_ip_rule(r).is_none()) {
+        return Err(AppError::Validation(format!(
+            "Invalid address or CIDR range in allowedIps: {}",
+            bad
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ip_rules() {
+        let rules = vec!["192.168.10.0/24".to_string(), "2001:db8::/32".to_string(), "10.0.0.7".to_string()];
+        assert!(ip_allowed(Some("192.168.10.42"), &rules));
+        assert!(ip_allowed(Some("::ffff:192.168.10.42"), &rules));
+        assert!(ip_allowed(Some("2001:db8:1::5"), &rules));
+        assert!(ip_allowed(Some("10.0.0.7"), &rules));
+        assert!(!ip_allowed(Some("10.0.0.8"), &rules));
+        assert!(!ip_allowed(Some("192.168.11.1"), &rules));
+        assert!(!ip_allowed(None, &rules));
+        assert!(ip_allowed(Some("203.0.113.9"), &["0.0.0.0/0".to_string()]));
+
+        assert!(validate_allowed_ips(&[]).is_err());
+        assert!(validate_allowed_ips(&["192.168.1.0/33".to_string()]).is_err());
+        assert!(validate_allowed_ips(&["library-pc".to_string()]).is_err());
+        assert!(validate_allowed_ips(&rules).is_ok());
+    }
+
+    #[test]
+    fn kiosk_ip_ignores_forwarded_headers_from_untrusted_peers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "192.168.10.42".parse().unwrap());
+        headers.insert("x-real-ip", "192.168.10.42".parse().unwrap());
+        let allowed = vec!["192.168.10.0/24".to_string()];
+        let peer: SocketAddr = "203.0.113.9:51000".parse().unwrap();
+
+        // Forged header sent straight to the server: the peer address is checked, and refused
+        let ip = kiosk_client_ip(&headers, Some(peer), &[]);
+        assert_eq!(ip.as_deref(), Some("203.0.113.9"));
+        assert!(!ip_allowed(ip.as_deref(), &allowed));
+        assert_eq!(kiosk_client_ip(&headers, None, &[]), None);
+
+        // Behind a trusted proxy, the nearest untrusted hop wins over what the client prepended
+        let proxies = vec!["10.0.0.0/8".to_string()];
+        let proxy: SocketAddr = "10.0.0.2:40000".parse().unwrap();
+        headers.insert("x-forwarded-for", "192.168.10.42, 203.0.113.9, 10.0.0.3".parse().unwrap());
+        assert_eq!(kiosk_client_ip(&headers, Some(proxy), &proxies).as_deref(), Some("203.0.113.9"));
+        headers.insert("x-forwarded-for", "192.168.10.42".parse().unwrap());
+        assert_eq!(kiosk_client_ip(&headers, Some(proxy), &proxies).as_deref(), Some("192.168.10.42"));
+        headers.remove("x-forwarded-for");
+        assert_eq!(kiosk_client_ip(&headers, Some(proxy), &proxies).as_deref(), Some("192.168.10.42"));
+    }
+
+    #[test]
+    fn tokens_are_prefixed_and_hashed() {
+        let token = generate_token();
+        assert!(token.starts_with(KIOSK_TOKEN_PREFIX));
+        assert_ne!(token, generate_token());
+        assert_eq!(hash_token(&token).len(), 64);
+        assert_eq!(hash_token(&token), hash_token(&token));
+    }
+}
@@ -0,0 +1,131 @@
+//! Translated display labels and caller language resolution
+
+use std::sync::Arc;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::label::{normalize_lang, parse_accept_language, Label, LabelKind, LabelSet, DEFAULT_LANG},
+    repository::LabelsRepository,
+};
+
+#[derive(Clone)]
+pub struct LabelsService {
+    repository: Arc<dyn LabelsRepository>,
+}
+
+impl LabelsService {
+    pub fn new(repository: Arc<dyn LabelsRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Language to answer in: the user's preferred language, then the `Accept-Language`
+    /// preferences, keeping the first one that has translations ([`DEFAULT_LANG`] otherwise).
+    #[tracing::instrument(skip(self), err)]
+    pub async fn resolve_lang(&self, user_id: Option<i64>, accept_language: Option<&str>) -> AppResult<String> {
+        let mut candidates: Vec<String> = Vec::new();
+        if let Some(user_id) = user_id {
+            if let Some(lang) = self
+                .repository
+                .labels_user_language(user_id)
+                .await?
+                .and_then(|l| l.iso_639_1())
+            {
+                candidates.push(lang.to_string());
+            }
+        }
+        candidates.extend(accept_language.map(parse_accept_language).unwrap_or_default());
+        if candidates.is_empty() {
+            return Ok(DEFAULT_LANG.to_string());
+        }
+
+        let available = self.repository.labels_languages().await?;
+        Ok(candidates
+            .into_iter()
+            .find(|lang| available.contains(lang))
+            .unwrap_or_else(|| DEFAULT_LANG.to_string()))
+    }
+
+    /// Languages having translations
+    pub async fn languages(&self) -> AppResult<Vec<String>> {
+        self.repository.labels_languages().await
+    }
+
+    /// Labels of `lang` (all kinds unless `kind` is given). Media types and relators without a
+    /// translation get the default label of their vocabulary.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn label_set(&self, lang: &str, kind: Option<LabelKind>) -> AppResult<LabelSet> {
+        let lang = validate_lang(lang)?;
+        let labels = self.repository.labels_list(&lang, kind).await?;
+        let mut set = LabelSet::new(lang, labels);
+        if kind.is_none() || kind == Some(LabelKind::MediaType) {
+            for (code, label) in self.repository.labels_media_type_defaults().await? {
+                set.insert_default(LabelKind::MediaType, code, label);
+            }
+        }
+        if kind.is_none() || kind == Some(LabelKind::Relator) {
+            for (code, label) in self.repository.labels_relator_defaults().await? {
+                set.insert_default(LabelKind::Relator, code, label);
+            }
+        }
+        Ok(set)
+    }
+
+    /// Create or replace the translation of `code` in `lang`
+    #[tracing::instrument(skip(self), err)]
+    pub async fn upsert(&self, kind: LabelKind, code: &str, lang: &str, label: &str) -> AppResult<Label> {
+        let lang = validate_lang(lang)?;
+        let code = code.trim();
+        if code.is_empty() || code.chars().count() > 64 {
+            return Err(AppError::Validation("code must be between 1 and 64 characters".to_string()));
+        }
+        let label = label.trim();
+        if label.is_empty() || label.chars().count() > 200 {
+            return Err(AppError::Validation("label must be between 1 and 200 characters".to_string()));
+        }
+        self.repository.labels_upsert(kind, code, &lang, label).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete(&self, kind: LabelKind, code: &str, lang: &str) -> AppResult<()> {
+        let lang = validate_lang(lang)?;
+        self.repository.labels_delete(kind, code.trim(), &lang).await
+    }
+}
+
+fn validate_lang(lang: &str) -> AppResult<String> {
+    normalize_lang(lang).ok_or_else(|| {
+        AppError::Validation(format!("lang must be an ISO 639-1 code (got '{}')", lang))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::Language, repository::labels::MockLabelsRepository};
+
+    fn service(user_language: Option<Language>) -> LabelsService {
+        let mut repo = MockLabelsRepository::new();
+        repo.expect_labels_user_language().returning(move |_| Ok(user_language));
+        repo.expect_labels_languages()
+            .returning(|| Ok(vec!["en".to_string(), "fr".to_string()]));
+        LabelsService::new(Arc::new(repo))
+    }
+
+    #[tokio::test]
+    async fn user_language_wins_over_accept_language() {
+        let svc = service(Some(Language::French));
+        let lang = svc.resolve_lang(Some(1), Some("en-US,en;q=0.9")).await.unwrap();
+        assert_eq!(lang, "fr");
+    }
+
+    #[tokio::test]
+    async fn accept_language_skips_untranslated_languages() {
+        let svc = service(Some(Language::German));
+        let lang = svc.resolve_lang(Some(1), Some("de-DE,fr;q=0.8,en;q=0.5")).await.unwrap();
+        assert_eq!(lang, "fr");
+        let lang = svc.resolve_lang(None, Some("ja")).await.unwrap();
+        assert_eq!(lang, DEFAULT_LANG);
+        let lang = svc.resolve_lang(None, None).await.unwrap();
+        assert_eq!(lang, DEFAULT_LANG);
+    }
+}
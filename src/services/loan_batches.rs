@@ -0,0 +1,217 @@
+//! Group loans service: batches checked out to a group account with one shared due date
+
+use std::{collections::HashSet, sync::Arc};
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+
+use crate::{
+    error::{AppError, AppResult},
+    models::loan_batch::{
+        CreateLoanBatch, ExtendLoanBatch, GroupLoanLimits, LoanBatch, LoanBatchQuery, LoanBatchReturnReport,
+        DEFAULT_GROUP_LOAN_MAX_DAYS,
+    },
+    repository::{LoanBatchesRepository, UsersRepository},
+};
+
+#[derive(Clone)]
+pub struct LoanBatchesService {
+    repository: Arc<dyn LoanBatchesRepository>,
+    users: Arc<dyn UsersRepository>,
+}
+
+impl LoanBatchesService {
+    pub fn new(repository: Arc<dyn LoanBatchesRepository>, users: Arc<dyn UsersRepository>) -> Self {
+        Self { repository, users }
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self, query: &LoanBatchQuery) -> AppResult<Vec<LoanBatch>> {
+        self.repository.loan_batches_list(query).await
+    }
+
+    pub async fn get(&self, id: i64) -> AppResult<LoanBatch> {
+        self.repository.loan_batches_get(id).await
+    }
+
+    /// Check out copies to a group account.
+    ///
+    /// The account type must allow group loans (`group_loans_max_items`); the batch size and the
+    /// due date are capped by its limits. Blocked or expired accounts need `force`, as for single loans.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create(&self, data: &CreateLoanBatch, created_by: i64) -> AppResult<LoanBatch> {
+        let user = self.users.users_get_by_id(data.user_id).await?;
+        if user.status.is_some_and(|s| s.is_deleted()) {
+            return Err(AppError::BusinessRule(
+                "Cannot create a loan for a deleted user account".to_string(),
+            ));
+        }
+        if !user.can_borrow() && !data.force {
+            return Err(AppError::BusinessRule(
+                "User account is not active or cannot borrow — use force=true to override".to_string(),
+            ));
+        }
+        if let Some(expiry_at) = user.expiry_at {
+            if expiry_at < Utc::now() && !data.force {
+                return Err(AppError::BusinessRule(format!(
+                    "User subscription expired on {} — use force=true to override",
+                    expiry_at.format("%Y-%m-%d")
+                )));
+            }
+        }
+
+        let barcodes = normalize_barcodes(&data.barcodes)?;
+        let limits = self.repository.loan_batches_limits_for_user(data.user_id).await?;
+        check_batch_size(&limits, barcodes.len())?;
+        let today = Utc::now().date_naive();
+        let due_date = data.due_date.unwrap_or(today + Duration::days(max_days(&limits) as i64));
+        check_due_date(&limits, due_date, today)?;
+
+        let data = CreateLoanBatch {
+            user_id: data.user_id,
+            label: data.label.clone(),
+            due_date: Some(due_date),
+            barcodes,
+            notes: data.notes.clone(),
+            force: data.force,
+        };
+        self.repository
+            .loan_batches_create(&data, due_at(due_date), created_by)
+            .await
+    }
+
+    /// Move the shared due date of the copies still out (within the account type limit from today)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn extend(&self, id: i64, data: &ExtendLoanBatch) -> AppResult<LoanBatch> {
+        let batch = self.repository.loan_batches_get(id).await?;
+        if batch.active_loans == 0 {
+            return Err(AppError::BusinessRule(
+                "Every copy of this batch has been returned".to_string(),
+            ));
+        }
+        if data.due_date <= batch.due_at.date_naive() {
+            return Err(AppError::Validation(
+                "dueDate must be after the current due date".to_string(),
+            ));
+        }
+        let limits = self.repository.loan_batches_limits_for_user(batch.user_id).await?;
+        check_due_date(&limits, data.due_date, Utc::now().date_naive())?;
+        self.repository.loan_batches_extend(id, due_at(data.due_date)).await?;
+        self.repository.loan_batches_get(id).await
+    }
+
+    /// Return every copy of the batch still out
+    #[tracing::instrument(skip(self), err)]
+    pub async fn return_all(&self, id: i64) -> AppResult<LoanBatchReturnReport> {
+        let batch = self.repository.loan_batches_get(id).await?;
+        if batch.active_loans == 0 {
+            return Err(AppError::BusinessRule(
+                "Every copy of this batch has already been returned".to_string(),
+            ));
+        }
+        let returned = self.repository.loan_batches_return(id).await?;
+        Ok(LoanBatchReturnReport { batch_id: id, returned })
+    }
+}
+
+/// Loans of a batch are due at the end of the due date.
+fn due_at(due_date: NaiveDate) -> DateTime<Utc> {
+    due_date
+        .and_time(NaiveTime::from_hms_opt(23, 59, 59).expect("valid time"))
+        .and_utc()
+}
+
+fn max_days(limits: &GroupLoanLimits) -> i16 {
+    limits.max_days.unwrap_or(DEFAULT_GROUP_LOAN_MAX_DAYS)
+}
+
+/// Trim barcodes and reject empty or duplicate entries.
+fn normalize_barcodes(barcodes: &[String]) -> AppResult<Vec<String>> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::with_capacity(barcodes.len());
+    for barcode in barcodes.iter().map(|b| b.trim()) {
+        if barcode.is_empty() {
+            return Err(AppError::Validation("barcodes cannot contain empty values".to_string()));
+        }
+        if !seen.insert(barcode.to_string()) {
+            return Err(AppError::Validation(format!("Barcode {} is listed twice", barcode)));
+        }
+        out.push(barcode.to_string());
+    }
+    if out.is_empty() {
+        return Err(AppError::Validation("barcodes cannot be empty".to_string()));
+    }
+    Ok(out)
+}
+
+fn check_batch_size(limits: &GroupLoanLimits, count: usize) -> AppResult<()> {
+    let Some(max_items) = limits.max_items else {
+        return Err(AppError::BusinessRule(format!(
+            "Account type '{}' does not allow group loans",
+            limits.account_type
+        )));
+    };
+    if count > max_items as usize {
+        return Err(AppError::BusinessRule(format!(
+            "Too many copies for a group loan ({}/{})",
+            count, max_items
+        )));
+    }
+    Ok(())
+}
+
+fn check_due_date(limits: &GroupLoanLimits, due_date: NaiveDate, today: NaiveDate) -> AppResult<()> {
+    if due_date <= today {
+        return Err(AppError::Validation("dueDate must be in the future".to_string()));
+    }
+    let latest = today + Duration::days(max_days(limits) as i64);
+    if due_date > latest {
+        return Err(AppError::Validation(format!(
+            "dueDate cannot be later than {} ({} days)",
+            latest,
+            max_days(limits)
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(max_items: Option<i16>, max_days: Option<i16>) -> GroupLoanLimits {
+        GroupLoanLimits { account_type: "group".to_string(), max_items, max_days }
+    }
+
+    #[test]
+    fn batch_size_follows_account_type() {
+        assert!(check_batch_size(&limits(Some(30), None), 30).is_ok());
+        assert!(matches!(
+            check_batch_size(&limits(Some(30), None), 31),
+            Err(AppError::BusinessRule(_))
+        ));
+        assert!(matches!(
+            check_batch_size(&limits(None, None), 1),
+            Err(AppError::BusinessRule(_))
+        ));
+    }
+
+    #[test]
+    fn due_date_within_limit() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+        let l = limits(Some(30), Some(28));
+        assert!(check_due_date(&l, today + Duration::days(28), today).is_ok());
+        assert!(check_due_date(&l, today + Duration::days(29), today).is_err());
+        assert!(check_due_date(&l, today, today).is_err());
+        // Default maximum applies when the account type sets none
+        let l = limits(Some(30), None);
+        assert!(check_due_date(&l, today + Duration::days(42), today).is_ok());
+    }
+
+    #[test]
+    fn barcodes_are_trimmed_and_unique() {
+        let ok = normalize_barcodes(&[" 001 ".to_string(), "002".to_string()]).unwrap();
+        assert_eq!(ok, vec!["001".to_string(), "002".to_string()]);
+        assert!(normalize_barcodes(&["001".to_string(), "001 ".to_string()]).is_err());
+        assert!(normalize_barcodes(&[]).is_err());
+    }
+}
@@ -6,13 +6,16 @@ use std::sync::Arc;
 
 use crate::{
     api::loans::{LoanSettings as LoanSettingsApi, UpdateLoanSettingsRequest},
+    config::{CirculationConfig, FloatingRule},
+    dynamic_config::DynamicConfig,
     error::{AppError, AppResult},
     marc::{MarcRecord, marc_record_for_loan_export},
     models::{
         Loan, loan::{
-            CreateLoan, LOANS_MARC_EXPORT_MAX, LoanDetails, LoanMarcExportEncoding, LoanMarcExportFormat,
-            LoanSettingsRenewAt,
-        }, user::UserStatus
+            CheckoutBlock, CheckoutBlockCode, CreateLoan, ItemFloatingFacts, LOANS_MARC_EXPORT_MAX,
+            LoanCheckoutOutcome, LoanDetails, LoanMarcExportEncoding, LoanMarcExportFormat, LoanRenewability,
+            LoanReturnOutcome, LoanSettingsRenewAt, RenewalBlockCode, ReturnRouting, ReturnRoutingAction,
+        }, user::{User, UserStatus}
     },
     repository::LoansServiceRepository,
 };
@@ -21,11 +24,21 @@ use z3950_rs::marc_rs::{BinaryWriter, Encoding as MarcEncoding, MarcFormat, XmlW
 #[derive(Clone)]
 pub struct LoansService {
     repository: Arc<dyn LoansServiceRepository>,
+    /// Checkout block thresholds and floating rules (`circulation` section); no block applies and
+    /// every copy goes home without it
+    dynamic_config: Option<Arc<DynamicConfig>>,
 }
 
 impl LoansService {
-    pub fn new(repository: Arc<dyn LoansServiceRepository>) -> Self {
-        Self { repository }
+    pub fn new(repository: Arc<dyn LoansServiceRepository>, dynamic_config: Option<Arc<DynamicConfig>>) -> Self {
+        Self { repository, dynamic_config }
+    }
+
+    fn circulation_config(&self) -> CirculationConfig {
+        self.dynamic_config
+            .as_ref()
+            .map(|dc| dc.read_circulation())
+            .unwrap_or_default()
     }
 
     /// Get active loans for a user (paginated). `page` and `per_page` must be valid (≥1, capped by caller).
@@ -39,7 +52,7 @@ impl LoansService {
         self.repository.loans_get_for_user(user_id, page, per_page).await
     }
 
-    /// Get archived (returned) loans for a user (paginated).
+    /// Get returned loans for a user (paginated), archived or not yet.
     pub async fn get_user_archived_loans(
         &self,
         user_id: i64,
@@ -50,53 +63,182 @@ impl LoansService {
         self.repository.loans_archives_get_for_user(user_id, page, per_page).await
     }
 
+    /// Search the active and archived loans of a user by title, author or barcode (paginated).
+    pub async fn search_user_loans(
+        &self,
+        user_id: i64,
+        q: &str,
+        page: i64,
+        per_page: i64,
+    ) -> AppResult<(Vec<LoanDetails>, i64)> {
+        self.repository.users_get_by_id(user_id).await?;
+        self.repository.loans_search_for_user(user_id, q, page, per_page).await
+    }
+
+    /// Every reason the patron cannot borrow right now, in a stable order
+    /// (account, membership, overdues, fines, blocking messages).
+    pub async fn checkout_blocks(&self, user_id: i64) -> AppResult<Vec<CheckoutBlock>> {
+        let user = self.repository.users_get_by_id(user_id).await?;
+        self.blocks_for(&user, &self.circulation_config()).await
+    }
+
+    async fn blocks_for(&self, user: &User, config: &CirculationConfig) -> AppResult<Vec<CheckoutBlock>> {
+        let mut blocks = Vec::new();
+        let mut block = |code, message: String| blocks.push(CheckoutBlock { code, message });
+
+        if !user.can_borrow() {
+            block(
+                CheckoutBlockCode::AccountBlocked,
+                "User account is not active or cannot borrow".to_string(),
+            );
+        }
+        if let Some(expiry_at) = user.expiry_at.filter(|at| *at < Utc::now()) {
+            block(
+                CheckoutBlockCode::MembershipExpired,
+                format!("User subscription expired on {}", expiry_at.format("%Y-%m-%d")),
+            );
+        }
+        if let Some(max) = config.max_overdue_loans {
+            let overdue = self.repository.loans_count_overdue_for_user(user.id).await?;
+            if overdue >= i64::from(max) {
+                block(
+                    CheckoutBlockCode::OverdueLoans,
+                    format!("{} overdue loan(s), limit {}", overdue, max),
+                );
+            }
+        }
+        if let Some(max) = config.max_unpaid_fines {
+            let unpaid = self.repository.fines_total_unpaid(user.id).await?;
+            if unpaid > max {
+                block(
+                    CheckoutBlockCode::UnpaidFines,
+                    format!("Unpaid fines of {}, limit {}", unpaid, max),
+                );
+            }
+        }
+        let messages = self.repository.user_messages_list(user.id, true, false).await?;
+        for message in messages.iter().filter(|m| m.is_blocking()) {
+            block(
+                CheckoutBlockCode::BlockingMessage,
+                format!("Blocking message on the patron account: {}", message.body),
+            );
+        }
+        Ok(blocks)
+    }
+
     /// Create a new loan (borrow an item).
     ///
-    /// Enforces user-level rules before delegating to the repository:
-    /// - blocked users cannot borrow unless `force` is set
-    /// - expired subscriptions are rejected unless `force` is set
+    /// Deleted accounts can never borrow. Any [`CheckoutBlock`] rejects the checkout with
+    /// [`AppError::CheckoutBlocked`] (listing them all) unless `override_blocks` is set; the
+    /// overridden blocks are returned for the audit log.
     ///
     /// The repository enforces the hold queue on the copy: only the patron whose turn it is
     /// (`ready`, else first `pending`) may borrow unless `force=true` (staff clears active holds on that copy).
-    pub async fn create_loan(&self, loan: CreateLoan) -> AppResult<(i64, DateTime<Utc>)> {
+    pub async fn create_loan(&self, loan: CreateLoan) -> AppResult<LoanCheckoutOutcome> {
         let user = self.repository.users_get_by_id(loan.user_id).await?;
 
         let status = user.status.unwrap_or(UserStatus::Active);
-        if status == UserStatus::Deleted {
+        if status.is_deleted() {
             return Err(AppError::BusinessRule(
                 "Cannot create a loan for a deleted user account".to_string(),
             ));
         }
 
-        if !user.can_borrow() && !loan.force {
-            return Err(AppError::BusinessRule(
-                "User account is not active or cannot borrow — use force=true to override".to_string()
-            ));
+        let blocks = self.blocks_for(&user, &self.circulation_config()).await?;
+        if !blocks.is_empty() && !loan.override_blocks {
+            return Err(AppError::CheckoutBlocked(blocks));
         }
 
-        if let Some(expiry_at) = user.expiry_at {
-            if expiry_at < Utc::now() && !loan.force {
-                return Err(AppError::BusinessRule(format!(
-                    "User subscription expired on {} — use force=true to override",
-                    expiry_at.format("%Y-%m-%d")
-                )));
+        let (loan_id, expiry_at) = self.repository.loans_create(&loan).await?;
+        Ok(LoanCheckoutOutcome { loan_id, expiry_at, overridden_blocks: blocks })
+    }
+
+    /// Return a borrowed item (with the hold it made ready, if any). Checked in at `place`, a
+    /// copy of another place is routed by the floating rules, or by `floating` (staff choice).
+    pub async fn return_loan(
+        &self,
+        loan_id: i64,
+        place: Option<i16>,
+        floating: Option<bool>,
+    ) -> AppResult<LoanReturnOutcome> {
+        let mut outcome = self.repository.loans_return(loan_id).await?;
+        if let (Some(item_id), Some(place)) = (outcome.details.item_id, place) {
+            // The loan is returned already: a routing failure is reported, not raised
+            match self.route_returned_item(item_id, place, floating).await {
+                Ok(routing) => outcome.routing = routing,
+                Err(e) => {
+                    tracing::warn!(target: "loans", error = %e, item_id, "Failed to route returned copy");
+                    outcome.routing_failed = true;
+                }
             }
         }
+        Ok(outcome)
+    }
 
-        self.repository.loans_create(&loan).await
+    /// Return a borrowed item by item identification (barcode or call number)
+    pub async fn return_loan_by_item(
+        &self,
+        item_identification: &str,
+        place: Option<i16>,
+        floating: Option<bool>,
+    ) -> AppResult<LoanReturnOutcome> {
+        let loan = self.repository.loans_get_by_item_identification(item_identification).await?;
+        self.return_loan(loan.id, place, floating).await
     }
 
-    /// Return a borrowed item
-    pub async fn return_loan(&self, loan_id: i64) -> AppResult<LoanDetails> {
-        let outcome = self.repository.loans_return(loan_id).await?;
-        Ok(outcome.details)
+    /// Route a copy just checked in at `place`; `None` at its own place (its transit, if any,
+    /// ends there) or for a copy without a place.
+    async fn route_returned_item(
+        &self,
+        item_id: i64,
+        place: i16,
+        floating: Option<bool>,
+    ) -> AppResult<Option<ReturnRouting>> {
+        let facts = self.repository.loans_item_floating_facts(item_id).await?;
+        let routing = return_routing(&self.circulation_config().floating_rules, &facts, place, floating);
+        match &routing {
+            Some(routing) => self.repository.loans_route_item(item_id, routing).await?,
+            None => self.repository.loans_clear_item_routing(item_id).await?,
+        }
+        Ok(routing)
     }
 
-    /// Return a borrowed item by item identification (barcode or call number)
-    pub async fn return_loan_by_item(&self, item_identification: &str) -> AppResult<LoanDetails> {
-        let loan = self.repository.loans_get_by_item_identification(item_identification).await?;
-        let outcome = self.repository.loans_return(loan.id).await?;
-        Ok(outcome.details)
+    /// Staff answer to a return that set `promptOverride`: the copy, back on the shelf at `place`,
+    /// floats there or travels home.
+    pub async fn answer_routing(&self, item_id: i64, place: i16, floating: bool) -> AppResult<ReturnRouting> {
+        let facts = self.repository.loans_item_floating_facts(item_id).await?;
+        if facts.on_loan {
+            return Err(AppError::Conflict("The copy is on loan".to_string()));
+        }
+        if facts.routing_place != Some(place) {
+            return Err(AppError::BusinessRule(format!(
+                "No return of the copy at place {} is waiting for a routing answer",
+                place
+            )));
+        }
+        let routing = return_routing(&self.circulation_config().floating_rules, &facts, place, Some(floating))
+            .ok_or_else(|| AppError::BusinessRule("The copy belongs to this place".to_string()))?;
+        self.repository.loans_route_item(item_id, &routing).await?;
+        Ok(routing)
+    }
+
+    /// A copy travelling home arrives there; returns its home place.
+    pub async fn receive_item(&self, item_id: i64) -> AppResult<i16> {
+        let facts = self.repository.loans_item_floating_facts(item_id).await?;
+        let place = facts
+            .transit_place
+            .ok_or_else(|| AppError::BusinessRule("The copy is not in transit".to_string()))?;
+        self.repository.loans_clear_item_routing(item_id).await?;
+        Ok(place)
+    }
+
+    /// Archival policy: move loans returned more than `circulation.archive_returned_after_days`
+    /// days ago to loans_archives (nightly, and by the end-of-day job). Returns how many moved.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn archive_returned(&self) -> AppResult<u64> {
+        let days = self.circulation_config().archive_returned_after_days;
+        let before = Utc::now() - chrono::Duration::days(days as i64);
+        self.repository.loans_archive_returned(before).await
     }
 
     /// Get a loan by id
@@ -104,16 +246,37 @@ impl LoansService {
         self.repository.loans_get_by_id(loan_id).await
     }
 
-    /// Renew a loan
-    pub async fn renew_loan(&self, loan_id: i64) -> AppResult<(DateTime<Utc>, i16)> {
+    /// Whether a loan can be renewed now: the loan rules of the repository, plus the account
+    /// and membership checkout blocks of the borrower.
+    pub async fn renewability(&self, loan_id: i64) -> AppResult<LoanRenewability> {
+        let mut renewability = self.repository.loans_renewability(loan_id).await?;
         let loan = self.repository.loans_get_by_id(loan_id).await?;
         let user = self.repository.users_get_by_id(loan.user_id).await?;
 
-        if !user.can_borrow() {
+        for block in self.blocks_for(&user, &CirculationConfig::default()).await? {
+            let code = match block.code {
+                CheckoutBlockCode::AccountBlocked => RenewalBlockCode::AccountBlocked,
+                CheckoutBlockCode::MembershipExpired => RenewalBlockCode::MembershipExpired,
+                // Overdues, fines and messages only hold back new checkouts
+                _ => continue,
+            };
+            renewability.block(code, block.message);
+        }
+        Ok(renewability)
+    }
+
+    /// Renew a loan
+    pub async fn renew_loan(&self, loan_id: i64) -> AppResult<(DateTime<Utc>, i16)> {
+        let renewability = self.renewability(loan_id).await?;
+
+        if renewability.is_blocked_by(RenewalBlockCode::AccountBlocked) {
             return Err(AppError::BusinessRule(
                 "User account is not active or cannot borrow — use force=true to override".to_string()
             ));
         }
+        if let Some(block) = renewability.blocks.first() {
+            return Err(AppError::BusinessRule(block.message.clone()));
+        }
         self.repository.loans_renew(loan_id).await
     }
 
@@ -140,7 +303,7 @@ impl LoansService {
         self.repository.loans_count_active_for_item(item_id).await
     }
 
-    /// Count active loans across all physical items of a biblio (used by OPAC availability)
+    /// Count active loans across the copies of a biblio shown in the OPAC (OPAC availability)
     pub async fn count_active_for_biblio(&self, biblio_id: i64) -> AppResult<i64> {
         self.repository.loans_count_active_for_biblio(biblio_id).await
     }
@@ -304,6 +467,36 @@ fn serialize_marc_export_records(
     }
 }
 
+/// First floating rule matching a copy
+fn floating_rule<'a>(rules: &'a [FloatingRule], facts: &ItemFloatingFacts) -> Option<&'a FloatingRule> {
+    rules.iter().find(|rule| {
+        rule.media_type.as_ref().is_none_or(|m| facts.media_type.as_ref() == Some(m))
+            && rule.collection.as_ref().is_none_or(|c| facts.collection_keys.contains(c))
+            && rule.home_place.is_none_or(|place| facts.home_place == Some(place))
+    })
+}
+
+/// Routing of a copy checked in at `return_place`; `None` at its own place, or without one
+fn return_routing(
+    rules: &[FloatingRule],
+    facts: &ItemFloatingFacts,
+    return_place: i16,
+    floating: Option<bool>,
+) -> Option<ReturnRouting> {
+    let home_place = facts.home_place.filter(|home| *home != return_place)?;
+    let rule = floating_rule(rules, facts);
+    let rule_floats = rule.is_some_and(|r| r.floating);
+    let floats = floating.unwrap_or(rule_floats);
+    Some(ReturnRouting {
+        action: if floats { ReturnRoutingAction::Float } else { ReturnRoutingAction::TransitHome },
+        home_place,
+        return_place,
+        rule: rule.map(|r| r.name.clone()),
+        overridden: floating.is_some_and(|f| f != rule_floats),
+        prompt_override: floating.is_none() && rule.is_some_and(|r| r.prompt),
+    })
+}
+
 // =============================================================================
 // Unit tests — use manual test doubles to avoid mockall lifetime issues
 // with async_trait + &str parameters.
@@ -316,9 +509,11 @@ mod tests {
         models::{
             loan::CreateLoan,
             user::{AccountTypeSlug, User, UserStatus},
+            user_message::{CreateUserMessage, UserMessage, UserMessageSeverity},
         },
-        repository::{LoansRepository, UsersRepository},
+        repository::{FinesRepository, LoansRepository, UserMessagesRepository, UsersRepository},
     };
+    use rust_decimal::Decimal;
     // ----- Minimal test double implementing both required traits -----
 
     struct FakeRepo {
@@ -326,6 +521,12 @@ mod tests {
         user: Option<User>,
         /// Return value for `loans_create`
         loan_id: i64,
+        /// Pending messages returned by `user_messages_list`
+        messages: Vec<UserMessage>,
+        /// Returned by `loans_count_overdue_for_user`
+        overdue: i64,
+        /// Returned by `fines_total_unpaid`
+        unpaid: Decimal,
     }
 
     fn make_user(id: i64, status: Option<UserStatus>, expiry_at: Option<chrono::DateTime<Utc>>) -> User {
@@ -351,6 +552,8 @@ mod tests {
             update_at: None,
             fee: None,
             archived_at: None,
+            deletion_scheduled_at: None,
+            merged_into: None,
             language: None,
             sex: None,
             staff_type: None,
@@ -365,14 +568,35 @@ mod tests {
             recovery_codes: None,
             recovery_codes_used: None,
             receive_reminders: true,
+            campaigns_opt_in: false,
             must_change_password: false,
+            email_health: None,
         }
     }
 
     #[async_trait::async_trait]
     impl LoansRepository for FakeRepo {
         async fn loans_settings_delete_rows(&self) -> AppResult<()> { Ok(()) }
-        async fn loans_get_by_id(&self, _: i64) -> AppResult<crate::models::loan::Loan> { unimplemented!() }
+        async fn loans_get_by_id(&self, id: i64) -> AppResult<crate::models::loan::Loan> {
+            let user_id = self.user.as_ref().map(|u| u.id).unwrap_or_default();
+            Ok(crate::models::loan::Loan {
+                id,
+                user_id,
+                item_id: Some(1),
+                equipment_id: None,
+                date: Utc::now(),
+                renew_at: None,
+                nb_renews: Some(0),
+                expiry_at: None,
+                notes: None,
+                returned_at: None,
+                last_reminder_sent_at: None,
+                reminder_count: None,
+                deposit_received: None,
+                batch_id: None,
+                bundle_id: None,
+            })
+        }
         async fn loans_get_by_item_identification(&self, _: &str) -> AppResult<crate::models::loan::Loan> { unimplemented!() }
         async fn loans_get_for_user(
             &self,
@@ -382,6 +606,12 @@ mod tests {
         ) -> AppResult<(Vec<LoanDetails>, i64)> {
             Ok((vec![], 0))
         }
+        async fn loans_get_active_for_users(
+            &self,
+            _: &[i64],
+        ) -> AppResult<std::collections::HashMap<i64, Vec<LoanDetails>>> {
+            Ok(Default::default())
+        }
         async fn loans_archives_get_for_user(
             &self,
             _: i64,
@@ -390,6 +620,15 @@ mod tests {
         ) -> AppResult<(Vec<LoanDetails>, i64)> {
             Ok((vec![], 0))
         }
+        async fn loans_search_for_user(
+            &self,
+            _: i64,
+            _: &str,
+            _: i64,
+            _: i64,
+        ) -> AppResult<(Vec<LoanDetails>, i64)> {
+            Ok((vec![], 0))
+        }
         async fn loans_get_for_marc_export(
             &self,
             _: i64,
@@ -403,6 +642,27 @@ mod tests {
         async fn loans_return(&self, _: i64) -> AppResult<crate::models::loan::LoanReturnOutcome> {
             unimplemented!()
         }
+        async fn loans_archive_returned(&self, _: chrono::DateTime<Utc>) -> AppResult<u64> { Ok(0) }
+        async fn loans_renewability(&self, id: i64) -> AppResult<LoanRenewability> {
+            Ok(LoanRenewability {
+                loan_id: id,
+                renewable: true,
+                blocks: vec![],
+                renew_count: 0,
+                max_renewals: 2,
+                expiry_at: None,
+                new_expiry_at: Utc::now(),
+            })
+        }
+        async fn loans_item_floating_facts(&self, _: i64) -> AppResult<ItemFloatingFacts> {
+            unimplemented!()
+        }
+        async fn loans_route_item(&self, _: i64, _: &ReturnRouting) -> AppResult<()> {
+            unimplemented!()
+        }
+        async fn loans_clear_item_routing(&self, _: i64) -> AppResult<()> {
+            unimplemented!()
+        }
         async fn loans_renew(&self, _: i64) -> AppResult<(chrono::DateTime<Utc>, i16)> { unimplemented!() }
         async fn loans_get_settings(&self) -> AppResult<Vec<crate::models::loan::LoanSettings>> { Ok(vec![]) }
         async fn loans_count_active(&self) -> AppResult<i64> { Ok(0) }
@@ -413,9 +673,13 @@ mod tests {
         async fn loans_get_active_ids_for_user(&self, _: i64) -> AppResult<Vec<i64>> { Ok(vec![]) }
         async fn loans_count_active_for_biblio(&self, _: i64) -> AppResult<i64> { Ok(0) }
         async fn loans_count_active_for_user(&self, _: i64) -> AppResult<i64> { Ok(0) }
+        async fn loans_count_overdue_for_user(&self, _: i64) -> AppResult<i64> { Ok(self.overdue) }
         async fn loans_get_overdue_for_reminders(&self, _: u32) -> AppResult<Vec<crate::repository::loans::OverdueLoanRow>> { Ok(vec![]) }
         async fn loans_get_overdue(&self, _: i64, _: i64) -> AppResult<(Vec<crate::repository::loans::OverdueLoanRow>, i64)> { Ok((vec![], 0)) }
         async fn loans_update_reminder_sent(&self, _: &[i64]) -> AppResult<()> { Ok(()) }
+        async fn loans_get_due_soon(&self, _: &[u32]) -> AppResult<Vec<crate::repository::loans::OverdueLoanRow>> { Ok(vec![]) }
+        async fn loans_update_due_soon_notified(&self, _: &[i64]) -> AppResult<()> { Ok(()) }
+        async fn loans_shift_due_dates(&self, _: chrono::NaiveDate, _: chrono::NaiveDate) -> AppResult<Vec<crate::repository::loans::ShiftedLoanRow>> { Ok(vec![]) }
         async fn loans_settings_upsert_row(
             &self,
             _: Option<String>,
@@ -442,9 +706,14 @@ mod tests {
         async fn users_login_exists(&self, _: &str, _: Option<i64>) -> AppResult<bool> { Ok(false) }
         async fn users_get_rights(&self, _: &AccountTypeSlug) -> AppResult<crate::models::user::UserRights> { unimplemented!() }
         async fn users_search(&self, _: &crate::models::user::UserQuery) -> AppResult<(Vec<crate::models::user::UserShort>, i64)> { Ok((vec![], 0)) }
+        async fn users_activity(&self, _: i64, _: Option<crate::models::user::UserActivityKind>, _: i64, _: i64) -> AppResult<(Vec<crate::models::user::UserActivity>, i64)> { Ok((vec![], 0)) }
         async fn users_create(&self, _: &crate::models::user::UserPayload, _: Option<String>) -> AppResult<User> { unimplemented!() }
         async fn users_update(&self, _: i64, _: &crate::models::user::UserPayload, _: Option<String>) -> AppResult<User> { unimplemented!() }
         async fn users_delete(&self, _: i64, _: bool) -> AppResult<()> { Ok(()) }
+        async fn users_schedule_deletion(&self, _: i64, _: bool, _: chrono::DateTime<Utc>) -> AppResult<User> { unimplemented!() }
+        async fn users_restore(&self, _: i64) -> AppResult<User> { unimplemented!() }
+        async fn users_purge_pending_deletions(&self) -> AppResult<Vec<i64>> { Ok(vec![]) }
+        async fn users_merge(&self, _: i64, _: i64, _: &crate::models::user::UserContactMerge, _: bool) -> AppResult<crate::models::user::UserMergeCounts> { unimplemented!() }
         async fn users_block(&self, _: i64) -> AppResult<User> { unimplemented!() }
         async fn users_unblock(&self, _: i64) -> AppResult<User> { unimplemented!() }
         async fn users_update_profile(&self, _: i64, _: &crate::models::user::UpdateProfile, _: Option<String>) -> AppResult<User> { unimplemented!() }
@@ -452,21 +721,79 @@ mod tests {
         async fn users_update_2fa_settings(&self, _: i64, _: bool, _: Option<&str>, _: Option<&str>, _: Option<&str>) -> AppResult<()> { Ok(()) }
         async fn users_mark_recovery_code_used(&self, _: i64, _: &str) -> AppResult<()> { Ok(()) }
         async fn users_get_emails_by_public_type(&self, _: Option<i64>) -> AppResult<Vec<crate::repository::users::UserEmailTarget>> { Ok(vec![]) }
+        async fn users_pin_states_by_barcode(&self, _: &str) -> AppResult<Vec<crate::models::user::UserPinState>> { Ok(vec![]) }
+        async fn users_set_pin(&self, _: i64, _: Option<String>) -> AppResult<()> { Ok(()) }
+        async fn users_record_pin_failure(&self, _: i64, _: i16, _: i64) -> AppResult<()> { Ok(()) }
+        async fn users_reset_pin_failures(&self, _: i64) -> AppResult<()> { Ok(()) }
+        async fn users_two_factor_locked_until(&self, _: i64) -> AppResult<Option<chrono::DateTime<Utc>>> { Ok(None) }
+        async fn users_record_two_factor_failure(&self, _: i64, _: i16, _: i64, _: i64) -> AppResult<(i16, Option<chrono::DateTime<Utc>>)> {
+            Ok((0, None))
+        }
+        async fn users_reset_two_factor_failures(&self, _: i64) -> AppResult<()> { Ok(()) }
+        async fn users_replace_recovery_codes(&self, _: i64, _: &str) -> AppResult<()> { Ok(()) }
+        async fn users_expiry_campaign_targets(&self, _: &crate::models::user::UserExpiryCampaign) -> AppResult<Vec<crate::repository::users::UserExpiryTarget>> { Ok(vec![]) }
+        async fn users_set_expiry(&self, _: &[i64], _: chrono::DateTime<chrono::Utc>) -> AppResult<u64> { Ok(0) }
     }
 
-    // LoansServiceRepository has a blanket impl for T: LoansRepository + UsersRepository + Send + Sync,
-    // so FakeRepo already implements it — no explicit impl needed.
+    #[async_trait::async_trait]
+    impl UserMessagesRepository for FakeRepo {
+        async fn user_messages_list(&self, _: i64, _: bool, _: bool) -> AppResult<Vec<UserMessage>> {
+            Ok(self.messages.clone())
+        }
+        async fn user_messages_get(&self, _: i64, _: i64) -> AppResult<UserMessage> { unimplemented!() }
+        async fn user_messages_create(&self, _: i64, _: &CreateUserMessage, _: i64) -> AppResult<UserMessage> { unimplemented!() }
+        async fn user_messages_delete(&self, _: i64, _: i64) -> AppResult<()> { Ok(()) }
+        async fn user_messages_acknowledge(&self, _: i64, _: i64, _: i64) -> AppResult<Option<UserMessage>> { Ok(None) }
+    }
+
+    #[async_trait::async_trait]
+    impl FinesRepository for FakeRepo {
+        async fn fines_list_for_user(&self, _: i64) -> AppResult<Vec<crate::models::fine::Fine>> { Ok(vec![]) }
+        async fn fines_get_by_id(&self, _: i64) -> AppResult<crate::models::fine::Fine> { unimplemented!() }
+        async fn fines_create(&self, _: i64, _: i64, _: Decimal, _: Option<&str>) -> AppResult<crate::models::fine::Fine> { unimplemented!() }
+        async fn fines_waive(&self, _: i64, _: Option<&str>, _: crate::models::fine::FineWaiverReason, _: Option<&str>, _: Option<i64>) -> AppResult<crate::models::fine::Fine> { unimplemented!() }
+        async fn fines_amnesty(&self, _: &str, _: chrono::DateTime<Utc>, _: chrono::DateTime<Utc>, _: i64) -> AppResult<(i64, Decimal)> { unimplemented!() }
+        async fn fines_waivers_summary(&self, _: Option<chrono::DateTime<Utc>>, _: Option<chrono::DateTime<Utc>>) -> AppResult<Vec<crate::models::fine::FineWaiverTotal>> { Ok(vec![]) }
+        async fn fines_list_rules(&self) -> AppResult<Vec<crate::models::fine::FineRule>> { Ok(vec![]) }
+        async fn fines_upsert_rule(&self, _: Option<&str>, _: Decimal, _: Option<Decimal>, _: i32) -> AppResult<crate::models::fine::FineRule> { unimplemented!() }
+        async fn fines_total_unpaid(&self, _: i64) -> AppResult<Decimal> { Ok(self.unpaid) }
+    }
+
+    // LoansServiceRepository has a blanket impl for T: LoansRepository + UsersRepository + FinesRepository
+    // + UserMessagesRepository + Send + Sync, so FakeRepo already implements it — no explicit impl needed.
+
+    fn fake_repo(user: Option<User>, loan_id: i64) -> FakeRepo {
+        FakeRepo { user, loan_id, messages: vec![], overdue: 0, unpaid: Decimal::ZERO }
+    }
 
     fn make_service(user: Option<User>, loan_id: i64) -> LoansService {
-        LoansService::new(Arc::new(FakeRepo { user, loan_id }))
+        LoansService::new(Arc::new(fake_repo(user, loan_id)), None)
+    }
+
+    fn make_message(severity: UserMessageSeverity) -> UserMessage {
+        UserMessage {
+            id: 1,
+            user_id: 8,
+            body: "Lost card reported, check ID".to_string(),
+            severity,
+            patron_visible: false,
+            expires_at: None,
+            created_by: None,
+            created_at: Utc::now(),
+            acknowledged_at: None,
+            acknowledged_by: None,
+        }
     }
 
     fn make_loan(user_id: i64, force: bool) -> CreateLoan {
         CreateLoan {
             user_id,
             item_id: Some(42),
+            equipment_id: None,
             item_identification: None,
             force,
+            deposit_received: false,
+            override_blocks: force,
         }
     }
 
@@ -485,7 +812,7 @@ mod tests {
         let svc = make_service(Some(user), 0);
         assert!(matches!(
             svc.create_loan(make_loan(2, false)).await,
-            Err(AppError::BusinessRule(_))
+            Err(AppError::CheckoutBlocked(_))
         ));
     }
 
@@ -507,6 +834,16 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_create_loan_pending_deletion_user_always_rejected() {
+        let user = make_user(6, Some(UserStatus::PendingDeletion), None);
+        let svc = make_service(Some(user), 0);
+        assert!(matches!(
+            svc.create_loan(make_loan(6, true)).await,
+            Err(AppError::BusinessRule(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_create_loan_expired_subscription_rejected() {
         let expired = Utc::now() - chrono::Duration::days(1);
@@ -514,7 +851,7 @@ mod tests {
         let svc = make_service(Some(user), 0);
         assert!(matches!(
             svc.create_loan(make_loan(5, false)).await,
-            Err(AppError::BusinessRule(_))
+            Err(AppError::CheckoutBlocked(_))
         ));
     }
 
@@ -542,4 +879,144 @@ mod tests {
         let svc = make_service(Some(user), 103);
         assert!(svc.create_loan(make_loan(7, false)).await.is_ok());
     }
+
+    fn floating(name: &str, media_type: Option<&str>, floating: bool, prompt: bool) -> FloatingRule {
+        FloatingRule {
+            name: name.to_string(),
+            media_type: media_type.map(str::to_string),
+            collection: None,
+            home_place: None,
+            floating,
+            prompt,
+        }
+    }
+
+    #[test]
+    fn test_floating_rules_route_copies_returned_at_another_place() {
+        let rules = vec![
+            floating("Floating DVDs", Some("videoDvd"), true, false),
+            floating("Comics to confirm", Some("comics"), true, true),
+        ];
+        let facts = |media_type: &str| ItemFloatingFacts {
+            home_place: Some(1),
+            media_type: Some(media_type.to_string()),
+            ..Default::default()
+        };
+
+        // Own place, or no place: nothing to route
+        assert!(return_routing(&rules, &facts("videoDvd"), 1, None).is_none());
+        assert!(return_routing(&rules, &ItemFloatingFacts::default(), 2, None).is_none());
+
+        let dvd = return_routing(&rules, &facts("videoDvd"), 2, None).unwrap();
+        assert_eq!(dvd.action, ReturnRoutingAction::Float);
+        assert_eq!((dvd.home_place, dvd.return_place), (1, 2));
+        assert_eq!(dvd.rule.as_deref(), Some("Floating DVDs"));
+        assert!(!dvd.overridden && !dvd.prompt_override);
+
+        // No matching rule: back home
+        let book = return_routing(&rules, &facts("printedText"), 2, None).unwrap();
+        assert_eq!(book.action, ReturnRoutingAction::TransitHome);
+        assert!(book.rule.is_none());
+
+        // Prompt rules wait for the staff; an explicit choice overrides the rule
+        assert!(return_routing(&rules, &facts("comics"), 2, None).unwrap().prompt_override);
+        let sent_home = return_routing(&rules, &facts("comics"), 2, Some(false)).unwrap();
+        assert_eq!(sent_home.action, ReturnRoutingAction::TransitHome);
+        assert!(sent_home.overridden && !sent_home.prompt_override);
+        assert!(!return_routing(&rules, &facts("comics"), 2, Some(true)).unwrap().overridden);
+    }
+
+    #[test]
+    fn test_floating_rule_criteria_must_all_match() {
+        let rule = FloatingRule {
+            collection: Some("folio".to_string()),
+            home_place: Some(1),
+            ..floating("Folio at the central library", None, true, false)
+        };
+        let facts = |home_place, key: &str| ItemFloatingFacts {
+            home_place: Some(home_place),
+            collection_keys: vec![key.to_string()],
+            ..Default::default()
+        };
+        let rules = [rule];
+        assert!(floating_rule(&rules, &facts(1, "folio")).is_some());
+        assert!(floating_rule(&rules, &facts(3, "folio")).is_none());
+        assert!(floating_rule(&rules, &facts(1, "pleiade")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_loan_blocking_message_requires_override() {
+        let repo = |severity| FakeRepo {
+            messages: vec![make_message(severity)],
+            ..fake_repo(Some(make_user(8, None, None)), 104)
+        };
+        let svc = LoansService::new(Arc::new(repo(UserMessageSeverity::Blocking)), None);
+        assert!(matches!(
+            svc.create_loan(make_loan(8, false)).await,
+            Err(AppError::CheckoutBlocked(_))
+        ));
+        let outcome = svc.create_loan(make_loan(8, true)).await.unwrap();
+        assert_eq!(outcome.overridden_blocks[0].code, CheckoutBlockCode::BlockingMessage);
+
+        let svc = LoansService::new(Arc::new(repo(UserMessageSeverity::Info)), None);
+        assert!(svc.create_loan(make_loan(8, false)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_checkout_blocks_lists_every_condition() {
+        let expired = Utc::now() - chrono::Duration::days(1);
+        let repo = FakeRepo {
+            messages: vec![make_message(UserMessageSeverity::Blocking)],
+            overdue: 3,
+            unpaid: Decimal::new(1250, 2),
+            ..fake_repo(Some(make_user(9, Some(UserStatus::Blocked), Some(expired))), 0)
+        };
+        let svc = LoansService::new(Arc::new(repo), None);
+        let user = make_user(9, Some(UserStatus::Blocked), Some(expired));
+        let config = CirculationConfig {
+            max_overdue_loans: Some(3),
+            max_unpaid_fines: Some(Decimal::new(1000, 2)),
+            ..CirculationConfig::default()
+        };
+        let codes: Vec<_> = svc.blocks_for(&user, &config).await.unwrap().iter().map(|b| b.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                CheckoutBlockCode::AccountBlocked,
+                CheckoutBlockCode::MembershipExpired,
+                CheckoutBlockCode::OverdueLoans,
+                CheckoutBlockCode::UnpaidFines,
+                CheckoutBlockCode::BlockingMessage,
+            ]
+        );
+
+        // Thresholds not reached, or not configured
+        let config = CirculationConfig {
+            max_overdue_loans: Some(4),
+            max_unpaid_fines: Some(Decimal::new(1250, 2)),
+            ..CirculationConfig::default()
+        };
+        assert_eq!(svc.blocks_for(&user, &config).await.unwrap().len(), 3);
+        assert_eq!(svc.blocks_for(&user, &CirculationConfig::default()).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_renewability_adds_borrower_blocks() {
+        let svc = make_service(Some(make_user(10, None, None)), 0);
+        let renewability = svc.renewability(5).await.unwrap();
+        assert!(renewability.renewable && renewability.blocks.is_empty());
+
+        // Overdues and messages hold back checkouts, not renewals
+        let expired = Utc::now() - chrono::Duration::days(1);
+        let repo = FakeRepo {
+            messages: vec![make_message(UserMessageSeverity::Blocking)],
+            ..fake_repo(Some(make_user(11, Some(UserStatus::Blocked), Some(expired))), 0)
+        };
+        let svc = LoansService::new(Arc::new(repo), None);
+        let renewability = svc.renewability(5).await.unwrap();
+        let codes: Vec<_> = renewability.blocks.iter().map(|b| b.code).collect();
+        assert!(!renewability.renewable);
+        assert_eq!(codes, vec![RenewalBlockCode::AccountBlocked, RenewalBlockCode::MembershipExpired]);
+        assert!(matches!(svc.renew_loan(5).await, Err(AppError::BusinessRule(_))));
+    }
 }
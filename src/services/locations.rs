@@ -0,0 +1,266 @@
+//! Shelving map service: locations site → room → shelf range and the mapping assistant that
+//! gives existing copies a location
+
+use std::sync::Arc;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::location::{
+        ApplyLocationMappings, CreateLocation, LocationKind, LocationMappingReport, LocationMappingRow,
+        LocationNode, ShelvingLocation, UpdateLocation,
+    },
+    repository::LocationsRepository,
+    services::catalog::CatalogService,
+};
+
+#[derive(Clone)]
+pub struct LocationsService {
+    repository: Arc<dyn LocationsRepository>,
+    catalog: Option<CatalogService>,
+}
+
+impl LocationsService {
+    pub fn new(repository: Arc<dyn LocationsRepository>) -> Self {
+        Self { repository, catalog: None }
+    }
+
+    /// Reindex the records whose copies the mapping assistant located (search location facet)
+    pub fn with_catalog(mut self, catalog: CatalogService) -> Self {
+        self.catalog = Some(catalog);
+        self
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self, place: Option<i16>) -> AppResult<Vec<ShelvingLocation>> {
+        self.repository.locations_list(place).await
+    }
+
+    /// Locations nested under their site
+    #[tracing::instrument(skip(self), err)]
+    pub async fn tree(&self, place: Option<i16>) -> AppResult<Vec<LocationNode>> {
+        Ok(LocationNode::build_tree(self.repository.locations_list(place).await?))
+    }
+
+    pub async fn get(&self, id: i64) -> AppResult<ShelvingLocation> {
+        self.repository.locations_get(id).await
+    }
+
+    /// Create a location; a site needs a place not taken by another site, rooms and shelf
+    /// ranges hang under a location of the level above and take its place
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create(&self, data: &CreateLocation) -> AppResult<ShelvingLocation> {
+        validate_name(Some(&data.name))?;
+        validate_code(data.code.as_deref())?;
+        let place = match (data.kind.parent_kind(), data.parent_id) {
+            (None, Some(_)) => {
+                return Err(AppError::Validation("A site cannot have a parentId".to_string()));
+            }
+            (None, None) => {
+                let place = data
+                    .place
+                    .ok_or_else(|| AppError::Validation("place is required for a site".to_string()))?;
+                let sites = self.repository.locations_list(Some(place)).await?;
+                if sites.iter().any(|l| l.kind == LocationKind::Site) {
+                    return Err(AppError::Conflict(format!("Place {} already has a site", place)));
+                }
+                place
+            }
+            (Some(parent_kind), None) => {
+                return Err(AppError::Validation(format!(
+                    "parentId is required for a {} (a {})",
+                    data.kind.as_str(),
+                    parent_kind.as_str()
+                )));
+            }
+            (Some(parent_kind), Some(parent_id)) => {
+                let parent = self.repository.locations_get(parent_id).await?;
+                if parent.kind != parent_kind {
+                    return Err(AppError::Validation(format!(
+                        "A {} goes under a {}, not a {}",
+                        data.kind.as_str(),
+                        parent_kind.as_str(),
+                        parent.kind.as_str()
+                    )));
+                }
+                if data.place.is_some_and(|p| p != parent.place) {
+                    return Err(AppError::Validation(format!(
+                        "place must be the one of the site ({})",
+                        parent.place
+                    )));
+                }
+                parent.place
+            }
+        };
+        self.repository.locations_create(data, place).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update(&self, id: i64, data: &UpdateLocation) -> AppResult<ShelvingLocation> {
+        validate_name(data.name.as_deref())?;
+        validate_code(data.code.as_deref())?;
+        self.repository.locations_update(id, data).await
+    }
+
+    /// Delete a location without child locations nor copies
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete(&self, id: i64) -> AppResult<()> {
+        let (children, items) = self.repository.locations_count_usage(id).await?;
+        if children > 0 || items > 0 {
+            return Err(AppError::Conflict(format!(
+                "Location {} still holds {} location(s) and {} copy(ies)",
+                id, children, items
+            )));
+        }
+        self.repository.locations_delete(id).await
+    }
+
+    /// Groups of copies without a location, with the suggested location of each
+    #[tracing::instrument(skip(self), err)]
+    pub async fn mapping_candidates(&self) -> AppResult<Vec<LocationMappingRow>> {
+        self.repository.locations_mapping_candidates().await
+    }
+
+    /// Give each group of copies its location, then reindex their records (location facet)
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn apply_mappings(&self, data: &ApplyLocationMappings) -> AppResult<LocationMappingReport> {
+        let mut targets = Vec::with_capacity(data.mappings.len());
+        for mapping in &data.mappings {
+            let location = self.repository.locations_get(mapping.location_id).await?;
+            if mapping.place.is_some_and(|p| p != location.place) {
+                return Err(AppError::Validation(format!(
+                    "Location {} is not in place {}",
+                    location.path,
+                    mapping.place.unwrap_or_default()
+                )));
+            }
+            targets.push((mapping, location));
+        }
+
+        let mut report = LocationMappingReport::default();
+        let mut biblio_ids = Vec::new();
+        for (mapping, location) in targets {
+            let located = self
+                .repository
+                .locations_apply_mapping(mapping.place, &mapping.prefix, &location)
+                .await?;
+            report.updated += located.len() as i64;
+            biblio_ids.extend(located);
+        }
+        report.remaining = self.repository.locations_count_unlocated().await?;
+
+        if let Some(ref catalog) = self.catalog {
+            biblio_ids.sort_unstable();
+            biblio_ids.dedup();
+            catalog.sync_index_many(&biblio_ids).await;
+        }
+        Ok(report)
+    }
+}
+
+fn validate_name(name: Option<&str>) -> AppResult<()> {
+    let Some(name) = name.map(str::trim) else {
+        return Ok(());
+    };
+    if name.is_empty() || name.chars().count() > 100 {
+        return Err(AppError::Validation("name must be between 1 and 100 characters".to_string()));
+    }
+    Ok(())
+}
+
+/// A single word (it is compared to the first word of call numbers); empty clears it
+fn validate_code(code: Option<&str>) -> AppResult<()> {
+    let Some(code) = code.map(str::trim) else {
+        return Ok(());
+    };
+    if code.chars().count() > 30 || code.contains(char::is_whitespace) {
+        return Err(AppError::Validation(format!(
+            "code must be a single word of at most 30 characters (got {})",
+            code
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::locations::MockLocationsRepository;
+
+    fn location(id: i64, parent_id: Option<i64>, kind: LocationKind, place: i16) -> ShelvingLocation {
+        ShelvingLocation {
+            id,
+            parent_id,
+            kind,
+            code: None,
+            name: format!("Location {}", id),
+            place,
+            path: format!("Location {}", id),
+            item_count: 0,
+            sort_order: 50,
+            created_at: None,
+            update_at: None,
+        }
+    }
+
+    fn create(kind: LocationKind, parent_id: Option<i64>, place: Option<i16>) -> CreateLocation {
+        CreateLocation { kind, parent_id, code: None, name: "Comics".to_string(), place, sort_order: None }
+    }
+
+    #[tokio::test]
+    async fn sites_need_a_free_place() {
+        let mut repo = MockLocationsRepository::new();
+        repo.expect_locations_list()
+            .returning(|_| Ok(vec![location(1, None, LocationKind::Site, 1)]));
+        let service = LocationsService::new(Arc::new(repo));
+
+        let err = service.create(&create(LocationKind::Site, None, Some(1))).await.unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)));
+        let err = service.create(&create(LocationKind::Site, None, None)).await.unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+        let err = service.create(&create(LocationKind::Site, Some(1), Some(2))).await.unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn rooms_and_shelf_ranges_follow_the_levels() {
+        let mut repo = MockLocationsRepository::new();
+        repo.expect_locations_get().returning(|id| {
+            Ok(match id {
+                1 => location(1, None, LocationKind::Site, 3),
+                _ => location(id, Some(1), LocationKind::Room, 3),
+            })
+        });
+        repo.expect_locations_create()
+            .withf(|data, place| data.kind == LocationKind::ShelfRange && *place == 3)
+            .returning(|data, place| Ok(location(9, data.parent_id, data.kind, place)));
+        let service = LocationsService::new(Arc::new(repo));
+
+        let err = service.create(&create(LocationKind::ShelfRange, Some(1), None)).await.unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+        let err = service.create(&create(LocationKind::Room, None, Some(3))).await.unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+        let err = service.create(&create(LocationKind::ShelfRange, Some(2), Some(4))).await.unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+
+        let shelf = service.create(&create(LocationKind::ShelfRange, Some(2), None)).await.unwrap();
+        assert_eq!((shelf.parent_id, shelf.place), (Some(2), 3));
+    }
+
+    #[tokio::test]
+    async fn only_empty_locations_are_deleted() {
+        let mut repo = MockLocationsRepository::new();
+        repo.expect_locations_count_usage().returning(|id| Ok(if id == 1 { (2, 0) } else { (0, 0) }));
+        repo.expect_locations_delete().times(1).returning(|_| Ok(()));
+        let service = LocationsService::new(Arc::new(repo));
+
+        assert!(matches!(service.delete(1).await.unwrap_err(), AppError::Conflict(_)));
+        service.delete(2).await.unwrap();
+    }
+
+    #[test]
+    fn codes_are_single_words() {
+        assert!(validate_code(Some("BD")).is_ok());
+        assert!(validate_code(Some("")).is_ok());
+        assert!(validate_code(Some("J R")).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! Hold pickup lockers: compartment reservations at the vendor, webhook events and expiry.
+//!
+//! A `ready` hold goes `in_locker` when staff place its copy in a compartment reserved through
+//! the vendor API. The vendor webhook then reports the compartment being opened (the hold is
+//! `picked_up` and the loan created) or the pickup window lapsing (`expired`).
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    config::LockersConfig,
+    dynamic_config::DynamicConfig,
+    email::EmailService,
+    error::{AppError, AppResult},
+    models::{
+        hold::{AssignHoldLocker, Hold, HoldStatus, LockerEventKind, LockerEventOutcome, LockerReservation, LockerWebhookEvent},
+        loan::CreateLoan,
+    },
+    repository::Repository,
+    services::{loans::LoansService, webhooks::verify_signature},
+};
+
+#[derive(Clone)]
+pub struct LockersService {
+    repository: Repository,
+    loans: LoansService,
+    email: EmailService,
+    dynamic_config: Arc<DynamicConfig>,
+    http: reqwest::Client,
+}
+
+impl LockersService {
+    pub fn new(
+        repository: Repository,
+        loans: LoansService,
+        email: EmailService,
+        dynamic_config: Arc<DynamicConfig>,
+    ) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .connect_timeout(std::time::Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self { repository, loans, email, dynamic_config, http }
+    }
+
+    fn config(&self) -> AppResult<LockersConfig> {
+        let config = self.dynamic_config.read_lockers();
+        if !config.enabled {
+            return Err(AppError::BusinessRule("Pickup lockers are not enabled".to_string()));
+        }
+        Ok(config)
+    }
+
+    /// Reserve a compartment for a `ready` hold and move it `in_locker` (the patron is emailed
+    /// the compartment and pickup code).
+    #[tracing::instrument(skip(self), err)]
+    pub async fn assign(&self, hold_id: i64, request: &AssignHoldLocker) -> AppResult<Hold> {
+        let config = self.config()?;
+        let hold = self.repository.holds_get_by_id(hold_id).await?;
+        if hold.status != HoldStatus::Ready {
+            return Err(AppError::BusinessRule(
+                "Only a ready hold can be placed in a pickup locker".to_string(),
+            ));
+        }
+
+        let expires_at = Utc::now() + Duration::days(i64::from(config.pickup_days));
+        let reservation = self
+            .reserve(&config, &hold, request.locker_id.as_deref(), expires_at)
+            .await?;
+        let Some(hold) = self
+            .repository
+            .holds_assign_locker(hold_id, &reservation, expires_at)
+            .await?
+        else {
+            // Cancelled or checked out meanwhile: give the compartment back
+            self.release(&hold).await;
+            return Err(AppError::Conflict("Hold is no longer ready".to_string()));
+        };
+
+        if let Err(e) = self.notify_patron(&hold).await {
+            tracing::warn!(hold_id, error = %e, "Failed to send hold in locker email");
+        }
+        Ok(hold)
+    }
+
+    /// Check the `X-Locker-Signature` header of a webhook call against the raw body
+    pub fn verify_webhook(&self, body: &[u8], signature: Option<&str>) -> AppResult<()> {
+        let config = self.config()?;
+        let secret = config.webhook_secret.as_deref().unwrap_or_default();
+        if secret.is_empty() || !verify_signature(secret, body, signature) {
+            return Err(AppError::Authentication("Invalid locker webhook signature".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Apply a vendor webhook event. Events already applied (vendor retries) are acknowledged
+    /// without changes.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn handle_event(&self, event: &LockerWebhookEvent) -> AppResult<LockerEventOutcome> {
+        let hold_id: i64 = event
+            .reference
+            .trim()
+            .parse()
+            .map_err(|_| AppError::Validation(format!("Invalid hold reference '{}'", event.reference)))?;
+        let hold = self.repository.holds_get_by_id(hold_id).await?;
+
+        match (event.event, hold.status) {
+            (LockerEventKind::CompartmentOpened, HoldStatus::InLocker) => self.picked_up(hold).await,
+            (LockerEventKind::Expired, HoldStatus::InLocker) => {
+                let hold = match self.repository.holds_expire_from_locker(hold_id).await? {
+                    Some(hold) => hold,
+                    None => self.repository.holds_get_by_id(hold_id).await?,
+                };
+                Ok(LockerEventOutcome { hold, loan_id: None, loan_error: None })
+            }
+            (LockerEventKind::CompartmentOpened, HoldStatus::PickedUp)
+            | (LockerEventKind::Expired, HoldStatus::Expired) => {
+                Ok(LockerEventOutcome { hold, loan_id: None, loan_error: None })
+            }
+            (_, status) => Err(AppError::Conflict(format!(
+                "Hold {} is not in a pickup locker (status {})",
+                hold_id,
+                status.as_str()
+            ))),
+        }
+    }
+
+    /// The patron took the copy: check it out to them. When the loan is refused (loan limit,
+    /// blocked account…) the hold is still closed and the reason returned for staff follow-up.
+    async fn picked_up(&self, hold: Hold) -> AppResult<LockerEventOutcome> {
+        let loan = self
+            .loans
+            .create_loan(CreateLoan {
+                user_id: hold.user_id,
+                item_id: Some(hold.item_id),
+                equipment_id: None,
+                item_identification: None,
+                force: false,
+                deposit_received: false,
+                override_blocks: false,
+            })
+            .await;
+
+        match loan {
+            // The checkout moved the hold to `picked_up`
+            Ok(outcome) => Ok(LockerEventOutcome {
+                hold: self.repository.holds_get_by_id(hold.id).await?,
+                loan_id: Some(outcome.loan_id),
+                loan_error: None,
+            }),
+            Err(e) => {
+                tracing::warn!(hold_id = hold.id, error = %e, "No loan created for locker pickup");
+                let closed = match self.repository.holds_mark_picked_up(hold.id).await? {
+                    Some(closed) => closed,
+                    None => self.repository.holds_get_by_id(hold.id).await?,
+                };
+                Ok(LockerEventOutcome { hold: closed, loan_id: None, loan_error: Some(e.to_string()) })
+            }
+        }
+    }
+
+    /// Expire `in_locker` holds whose pickup window lapsed and release their compartments
+    #[tracing::instrument(skip(self), err)]
+    pub async fn expire_overdue(&self) -> AppResult<usize> {
+        let expired = self.repository.holds_expire_lockers().await?;
+        for hold in &expired {
+            self.release(hold).await;
+        }
+        Ok(expired.len())
+    }
+
+    /// Give a hold's compartment back to the vendor (best effort: failures are logged)
+    pub async fn release(&self, hold: &Hold) {
+        let config = self.dynamic_config.read_lockers();
+        let Some(base) = api_base(&config) else {
+            tracing::debug!(hold_id = hold.id, "Lockers not configured — compartment not released");
+            return;
+        };
+        let url = format!("{}/reservations/{}", base, hold.id);
+        let result = with_auth(self.http.delete(&url), &config).send().await;
+        match result {
+            Ok(response) if response.status().is_success() || response.status() == reqwest::StatusCode::NOT_FOUND => {}
+            Ok(response) => {
+                tracing::warn!(hold_id = hold.id, status = %response.status(), "Locker vendor refused the release");
+            }
+            Err(e) => tracing::warn!(hold_id = hold.id, error = %e, "Locker vendor unreachable"),
+        }
+    }
+
+    async fn reserve(
+        &self,
+        config: &LockersConfig,
+        hold: &Hold,
+        locker_id: Option<&str>,
+        expires_at: DateTime<Utc>,
+    ) -> AppResult<LockerReservation> {
+        let base = api_base(config)
+            .ok_or_else(|| AppError::BusinessRule("lockers.api_url is not configured".to_string()))?;
+        let body = serde_json::json!({
+            "reference": hold.id.to_string(),
+            "lockerId": locker_id,
+            "patronId": hold.user_id.to_string(),
+            "itemId": hold.item_id.to_string(),
+            "expiresAt": expires_at,
+        });
+        let response = with_auth(self.http.post(format!("{}/reservations", base)), config)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("Locker vendor unreachable: {}", e)))?;
+
+        let status = response.status();
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| AppError::Internal(format!("Locker vendor response: {}", e)))?;
+        if status == reqwest::StatusCode::CONFLICT {
+            return Err(AppError::Conflict("No free compartment in this locker".to_string()));
+        }
+        if !status.is_success() {
+            return Err(AppError::Internal(format!("Locker vendor answered {}", status)));
+        }
+        serde_json::from_slice(&bytes)
+            .map_err(|e| AppError::Internal(format!("Invalid locker vendor response: {}", e)))
+    }
+
+    async fn notify_patron(&self, hold: &Hold) -> AppResult<()> {
+        let contact = self.repository.users_hold_ready_contact(hold.user_id).await?;
+        let details = self.repository.holds_holds_to_details(vec![hold.clone()]).await?;
+        let title = details
+            .first()
+            .and_then(|d| d.biblio.title.clone())
+            .unwrap_or_else(|| "(unknown title)".to_string());
+        crate::hold_email::send_hold_in_locker(&self.email, contact, hold, &title).await
+    }
+}
+
+fn api_base(config: &LockersConfig) -> Option<&str> {
+    config
+        .api_url
+        .as_deref()
+        .map(|url| url.trim().trim_end_matches('/'))
+        .filter(|url| !url.is_empty())
+}
+
+fn with_auth(request: reqwest::RequestBuilder, config: &LockersConfig) -> reqwest::RequestBuilder {
+    match config.api_key.as_deref().filter(|k| !k.is_empty()) {
+        Some(key) => request.bearer_auth(key),
+        None => request,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_base_trims_trailing_slash() {
+        let mut config = LockersConfig { api_url: Some(" https://lockers.example.com/api/ ".to_string()), ..Default::default() };
+        assert_eq!(api_base(&config), Some("https://lockers.example.com/api"));
+        config.api_url = Some("  ".to_string());
+        assert_eq!(api_base(&config), None);
+    }
+}
@@ -56,11 +56,14 @@ pub struct MarcBatchImportReport {
     pub imported: Vec<String>,
     /// Detailed list of records that failed to import.
     pub failed: Vec<MarcBatchImportError>,
+    /// Copies created by the import, in import order (for processing slips).
+    #[serde(skip)]
+    pub created_item_ids: Vec<i64>,
 }
 
 /// Summary of a MARC batch cached in Redis.
 #[serde_as]
-#[derive(Debug, Clone, Serialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MarcBatchInfo {
     /// Unique batch identifier (Snowflake ID).
@@ -84,7 +87,7 @@ pub struct MarcService {
 
 
 #[serde_as]
-#[derive(Debug, Clone, Serialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EnqueueResult {
     #[serde_as(as = "DisplayFromStr")]
@@ -268,6 +271,7 @@ impl MarcService {
     ///
     /// - If `record_id` is `None`, imports all records for the `batch_id`.
     /// - If `record_id` is `Some`, imports only the specified record.
+    /// - If `deposit_id` is `Some`, the copies are flagged as copies of that deposit.
     ///
     /// On error for a given record, the error is captured in the report and
     /// processing continues with the next record.
@@ -282,6 +286,7 @@ impl MarcService {
         record_id: Option<usize>,
         allow_duplicate_isbn: bool,
         confirm_replace_existing_id: Option<i64>,
+        deposit_id: Option<i64>,
         task_handle: Option<TaskHandle>,
     ) -> AppResult<MarcBatchImportReport> {
         let mut conn = self.redis.get_connection().await?;
@@ -301,6 +306,7 @@ impl MarcService {
 
         let mut imported = Vec::new();
         let mut failed = Vec::new();
+        let mut created_item_ids = Vec::new();
         let total = keys.len();
 
         for (idx, key) in keys.iter().enumerate() {
@@ -339,11 +345,13 @@ impl MarcService {
             let mut biblio: Biblio = record.into();
             for item in &mut biblio.items {
                 item.source_id = Some(source_id);
+                item.deposit_id = deposit_id;
             }
 
             match self.catalog.create_biblio(biblio, allow_duplicate_isbn, confirm_replace_existing_id).await {
-                Ok((_biblio, _report)) => {
+                Ok((biblio, _report)) => {
                     imported.push(Self::item_key_from_record_key(&key));
+                    created_item_ids.extend(biblio.items.iter().filter_map(|item| item.id));
                 }
                 Err(AppError::DuplicateNeedsConfirmation { existing_id, message, .. }) => {
                     failed.push(MarcBatchImportError {
@@ -382,6 +390,7 @@ impl MarcService {
             batch_id,
             imported,
             failed,
+            created_item_ids,
         })
     }
 }
@@ -0,0 +1,185 @@
+//! Media type taxonomy service
+
+use std::sync::Arc;
+
+use rust_decimal::Decimal;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{
+        media_type::{CreateMediaType, MediaTypeDefinition, UpdateMediaType},
+        MediaType,
+    },
+    repository::MediaTypesRepository,
+};
+
+#[derive(Clone)]
+pub struct MediaTypesService {
+    repository: Arc<dyn MediaTypesRepository>,
+}
+
+impl MediaTypesService {
+    pub fn new(repository: Arc<dyn MediaTypesRepository>) -> Self {
+        Self { repository }
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self) -> AppResult<Vec<MediaTypeDefinition>> {
+        self.repository.media_types_list().await
+    }
+
+    pub async fn get(&self, code: &str) -> AppResult<MediaTypeDefinition> {
+        self.repository.media_types_get(code).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create(&self, data: &CreateMediaType) -> AppResult<MediaTypeDefinition> {
+        validate_code(&data.code)?;
+        validate_label(Some(&data.label))?;
+        validate_loan_rules(data.loan_duration, data.loan_nb_max, data.loan_nb_renews)?;
+        validate_marc_code("marcRecordType", data.marc_record_type.as_deref())?;
+        validate_marc_code("marcBibliographicLevel", data.marc_bibliographic_level.as_deref())?;
+        validate_vat_rate(data.vat_rate)?;
+        match self.repository.media_types_get(&data.code).await {
+            Ok(_) => {
+                return Err(AppError::Conflict(format!("Media type {} already exists", data.code)))
+            }
+            Err(AppError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+        self.repository.media_types_create(data).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update(&self, code: &str, data: &UpdateMediaType) -> AppResult<MediaTypeDefinition> {
+        validate_label(data.label.as_deref())?;
+        validate_loan_rules(data.loan_duration, data.loan_nb_max, data.loan_nb_renews)?;
+        validate_marc_code("marcRecordType", data.marc_record_type.as_deref())?;
+        validate_marc_code("marcBibliographicLevel", data.marc_bibliographic_level.as_deref())?;
+        validate_vat_rate(data.vat_rate)?;
+        self.repository.media_types_update(code, data).await
+    }
+
+    /// Delete a media type added to the taxonomy that no biblio uses anymore
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete(&self, code: &str) -> AppResult<()> {
+        if !matches!(MediaType::from(code), MediaType::Other(_)) {
+            return Err(AppError::Conflict(format!(
+                "Media type {} is built in and cannot be deleted",
+                code
+            )));
+        }
+        let in_use = self.repository.media_types_count_biblios(code).await?;
+        if in_use > 0 {
+            return Err(AppError::Conflict(format!(
+                "Media type {} is used by {} biblio(s)",
+                code, in_use
+            )));
+        }
+        self.repository.media_types_delete(code).await
+    }
+}
+
+/// camelCase identifier, so that new codes look like the built-in ones and never parse as a
+/// legacy code (`b`, `vd`, ...)
+fn validate_code(code: &str) -> AppResult<()> {
+    let valid = (2..=30).contains(&code.len())
+        && code.starts_with(|c: char| c.is_ascii_lowercase())
+        && code.chars().all(|c| c.is_ascii_alphanumeric());
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "code must be a camelCase identifier of 2 to 30 letters or digits (got {})",
+            code
+        )));
+    }
+    if !matches!(MediaType::from(code), MediaType::Other(_)) {
+        return Err(AppError::Conflict(format!("Media type {} already exists", code)));
+    }
+    Ok(())
+}
+
+fn validate_label(label: Option<&str>) -> AppResult<()> {
+    let Some(label) = label.map(str::trim) else {
+        return Ok(());
+    };
+    if label.is_empty() || label.chars().count() > 100 {
+        return Err(AppError::Validation(
+            "label must be between 1 and 100 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_loan_rules(duration: Option<i16>, nb_max: Option<i16>, nb_renews: Option<i16>) -> AppResult<()> {
+    if duration.is_some_and(|d| d < 1) {
+        return Err(AppError::Validation("loanDuration must be at least 1 day".to_string()));
+    }
+    if nb_max.is_some_and(|n| n < 0) || nb_renews.is_some_and(|n| n < 0) {
+        return Err(AppError::Validation(
+            "loanNbMax and loanNbRenews cannot be negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// One lowercase letter (MARC leader position)
+fn validate_marc_code(field: &str, code: Option<&str>) -> AppResult<()> {
+    let Some(code) = code else {
+        return Ok(());
+    };
+    if code.len() != 1 || !code.starts_with(|c: char| c.is_ascii_lowercase()) {
+        return Err(AppError::Validation(format!(
+            "{} must be a single lowercase letter (got {})",
+            field, code
+        )));
+    }
+    Ok(())
+}
+
+/// Percent, below 100 with at most two decimals (`5.5`, `20`)
+fn validate_vat_rate(rate: Option<Decimal>) -> AppResult<()> {
+    let Some(rate) = rate else {
+        return Ok(());
+    };
+    if rate.is_sign_negative() || rate >= Decimal::ONE_HUNDRED || rate.normalize().scale() > 2 {
+        return Err(AppError::Validation(format!(
+            "vatRate must be a percentage from 0 to 99.99 (got {})",
+            rate
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_format() {
+        assert!(validate_code("boardGame").is_ok());
+        assert!(validate_code("vinyl33").is_ok());
+        assert!(validate_code("BoardGame").is_err());
+        assert!(validate_code("board-game").is_err());
+        assert!(validate_code("x").is_err());
+        assert!(matches!(validate_code("videoDvd"), Err(AppError::Conflict(_))));
+        assert!(matches!(validate_code("amc"), Err(AppError::Conflict(_))));
+    }
+
+    #[test]
+    fn marc_code_format() {
+        assert!(validate_marc_code("marcRecordType", None).is_ok());
+        assert!(validate_marc_code("marcRecordType", Some("r")).is_ok());
+        assert!(validate_marc_code("marcRecordType", Some("R")).is_err());
+        assert!(validate_marc_code("marcRecordType", Some("ab")).is_err());
+    }
+
+    #[test]
+    fn vat_rate_range() {
+        assert!(validate_vat_rate(None).is_ok());
+        assert!(validate_vat_rate(Some(Decimal::new(55, 1))).is_ok());
+        assert!(validate_vat_rate(Some(Decimal::ZERO)).is_ok());
+        assert!(validate_vat_rate(Some(Decimal::new(-1, 0))).is_err());
+        assert!(validate_vat_rate(Some(Decimal::ONE_HUNDRED)).is_err());
+        assert!(validate_vat_rate(Some(Decimal::new(5555, 3))).is_err());
+    }
+}
@@ -1,28 +1,66 @@
 //! Business logic services
 
 pub mod account_types_catalog;
+pub mod api_keys;
+pub mod artifacts;
 pub mod audit;
+pub mod backup;
+pub mod bundles;
+pub mod campaigns;
 pub mod catalog;
+pub mod citations;
+pub mod currency;
+pub mod dashboards;
+pub mod deposits;
+pub mod donations;
+pub mod edit_locks;
+pub mod email_health;
+pub mod eod;
 pub mod equipment;
+pub mod exports;
 pub mod events;
 pub mod fines;
+pub mod harvest;
 pub mod inventory;
+pub mod item_states;
+pub mod item_templates;
+pub mod kiosks;
+pub mod labels;
 pub mod library_info;
+pub mod loan_batches;
 pub mod loans;
+pub mod locations;
+pub mod lockers;
 pub mod marc;
+pub mod media_types;
+pub mod notifications;
+pub mod password_policy;
+pub mod payments;
 pub mod public_types;
+pub mod purchase_suggestions;
+pub mod reading_programs;
+pub mod reengagement;
 pub mod redis;
+pub mod relators;
 pub mod reminders;
 pub mod holds;
 pub mod schedules;
 pub mod scheduler;
 pub mod search;
+pub mod sitemap;
+pub mod snapshots;
 pub mod sources;
+pub mod staffing;
 pub mod stats;
 pub mod task_manager;
+pub mod user_messages;
 pub mod users;
+pub mod vendors;
 pub mod visitor_counts;
+pub mod warehouse;
+pub mod webhooks;
 pub mod z3950;
+pub mod z3950_server;
 
 // Re-export for existing `services::email` / `services::email_templates` paths
 pub use crate::email as email;
@@ -33,15 +71,16 @@ use std::sync::Arc;
 use sqlx::{Pool, Postgres};
 
 use crate::{
-    config::{MeilisearchConfig, RedisConfig, UsersConfig},
+    config::{ArtifactsConfig, MeilisearchConfig, RedisConfig, UsersConfig},
     dynamic_config::DynamicConfig,
     error::AppResult,
     repository::{
-        BibliosRepository, CatalogEntitiesRepository, EquipmentRepository, EventsServiceRepository,
-        FinesRepository, InventoryRepository, LoansRepository, LoansServiceRepository,
-        AccountTypesCatalogRepository,
-        PublicTypesRepository, Repository, HoldsRepository, SchedulesRepository,
-        SourcesRepository, UsersRepository, VisitorCountsRepository,
+        BibliosRepository, BundlesRepository, CampaignsRepository, CatalogEntitiesRepository, DashboardsRepository, DepositsRepository, DonationsRepository, EmailHealthRepository, EodRepository, EquipmentRepository, EventsServiceRepository,
+        FinesRepository, HarvestRepository, InventoryRepository, ItemStatesRepository, ItemTemplatesRepository, KiosksRepository, LabelsRepository, LoanBatchesRepository, LoansRepository, LocationsRepository, LoansServiceRepository, MediaTypesRepository, NotificationsRepository,
+        AccountTypesCatalogRepository, ApiKeysRepository, ArtifactsRepository, BackupsRepository,
+        PaymentsRepository, PublicTypesRepository, PurchaseSuggestionsRepository, ReadingProgramsRepository, ReengagementRepository, RelatorsRepository, Repository, HoldsRepository, SchedulesRepository, SitemapRepository,
+        SnapshotsRepository, SourcesRepository, StaffingRepository, UserMessagesRepository, UsersRepository, VendorsRepository, VisitorCountsRepository,
+        WarehouseRepository,
     },
 };
 
@@ -51,27 +90,93 @@ pub struct Services {
     pub audit: audit::AuditService,
     /// Library account roles (`account_types`) and rights.
     pub account_types_catalog: account_types_catalog::AccountTypesCatalogService,
+    /// Partner application keys (scoped public endpoints, usage per key).
+    pub api_keys: api_keys::ApiKeysService,
+    /// Generated files (exports, import reports) downloaded through signed URLs.
+    pub artifacts: artifacts::ArtifactsService,
+    /// Logical database backups (artifacts store or S3), restored with `elidune-server restore`.
+    pub backup: backup::BackupService,
+    /// Item bundles (box sets and multi-volume works circulating as one unit).
+    pub bundles: bundles::BundlesService,
+    /// Email campaigns to patron segments (throttled outbound queue, bounce tracking).
+    pub campaigns: campaigns::CampaignsService,
     pub catalog: catalog::CatalogService,
+    /// Currency of prices (`[currency]`): price validation and monetary formatting.
+    pub currency: currency::Currency,
+    /// Personal dashboards (per-user widgets over the statistics endpoints).
+    pub dashboards: dashboards::DashboardsService,
+    /// Deposit collections lent by another library (return manifest, closing).
+    pub deposits: deposits::DepositsService,
+    /// Donations intake (donated books, triage, donors report).
+    pub donations: donations::DonationsService,
+    /// Advisory locks of catalogers editing a record (Redis, heartbeat renewal).
+    pub edit_locks: edit_locks::EditLocksService,
     pub email: email::EmailService,
+    /// Bounces and complaints reported by the mail provider (undeliverable addresses).
+    pub email_health: email_health::EmailHealthService,
+    /// End-of-day job (loan archiving, daily statistics, pull list, staff digest) and its run log.
+    pub eod: eod::EodService,
     pub equipment: equipment::EquipmentService,
+    /// Streamed catalog exports (CSV, XLSX, MARC).
+    pub exports: exports::ExportsService,
     pub events: events::EventsService,
     pub fines: fines::FinesService,
+    /// Scheduled union catalog harvest (delta files over HTTP, FTP or SFTP).
+    pub harvest: harvest::HarvestService,
     pub inventory: inventory::InventoryService,
+    /// Item state taxonomy (`items.circulation_status`).
+    pub item_states: item_states::ItemStatesService,
+    pub item_templates: item_templates::ItemTemplatesService,
+    /// Catalog terminal tokens (OPAC read + patron self-service login).
+    pub kiosks: kiosks::KiosksService,
+    /// Translated display labels (media types, audiences, genres, account types).
+    pub labels: labels::LabelsService,
     pub library_info: library_info::LibraryInfoService,
+    /// Group loans (batches with a shared due date, extended and returned as a whole).
+    pub loan_batches: loan_batches::LoanBatchesService,
     pub loans: loans::LoansService,
+    /// Shelving map (site → room → shelf range) and the copy location mapping assistant.
+    pub locations: locations::LocationsService,
+    /// Hold pickup lockers (vendor compartments, webhook events).
+    pub lockers: lockers::LockersService,
     pub marc: marc::MarcService,
+    /// Media type taxonomy (`biblios.media_type`).
+    pub media_types: media_types::MediaTypesService,
+    /// In-app notifications inbox (task outcomes, import reports, overdue escalations).
+    pub notifications: notifications::NotificationsService,
+    /// Cash register (payments for fines, memberships and sundries, refunds, daily cash-up).
+    pub payments: payments::PaymentsService,
     pub public_types: public_types::PublicTypesService,
+    pub purchase_suggestions: purchase_suggestions::PurchaseSuggestionsService,
+    /// Reading programs (summer challenge: enrollments, reading log, statistics).
+    pub reading_programs: reading_programs::ReadingProgramsService,
     pub redis: redis::RedisService,
+    /// Re-engagement emails suggesting new acquisitions to inactive members, and their effectiveness.
+    pub reengagement: reengagement::ReengagementService,
+    /// Author role vocabulary (`biblio_authors.function`).
+    pub relators: relators::RelatorsService,
     pub reminders: reminders::RemindersService,
     pub holds: holds::HoldsService,
     pub schedules: schedules::SchedulesService,
     pub search: Option<Arc<search::MeilisearchService>>,
+    /// OPAC sitemap for search engines, regenerated by the scheduler.
+    pub sitemap: sitemap::SitemapService,
+    /// Copies captured before bulk edits, restorable once.
+    pub snapshots: snapshots::SnapshotsService,
     pub sources: sources::SourcesService,
+    /// Staff and volunteer shifts on the opening slots (assignments, absences, iCal feeds).
+    pub staffing: staffing::StaffingService,
     pub stats: stats::StatsService,
     /// Background task registry (MARC imports, maintenance, …).
     pub tasks: task_manager::TaskManager,
+    /// Staff notes on patron accounts (checkout warnings, self-service announcements).
+    pub user_messages: user_messages::UserMessagesService,
     pub users: users::UsersService,
+    /// Suppliers and purchase orders.
+    pub vendors: vendors::VendorsService,
     pub visitor_counts: visitor_counts::VisitorCountsService,
+    /// Nightly anonymized fact extracts pushed to a data warehouse (S3 or SFTP).
+    pub warehouse: warehouse::WarehouseService,
     pub z3950: z3950::Z3950Service,
     /// Exposed for admin endpoints that need direct DB access (config, settings)
     pool: Pool<Postgres>,
@@ -97,6 +202,7 @@ impl Services {
         redis_service: redis::RedisService,
         meilisearch_config: Option<MeilisearchConfig>,
         email_service: Arc<crate::email::EmailService>,
+        artifacts_config: ArtifactsConfig,
     ) -> AppResult<Self> {
         let pool = repository.pool.clone();
 
@@ -115,56 +221,185 @@ impl Services {
 
         let biblios_repo: Arc<dyn BibliosRepository> = repo.clone();
         let entities_repo: Arc<dyn CatalogEntitiesRepository> = repo.clone();
+        let media_types_repo: Arc<dyn MediaTypesRepository> = repo.clone();
+        let snapshots_service = snapshots::SnapshotsService::new(
+            repo.clone() as Arc<dyn SnapshotsRepository>,
+            dynamic_config.file_config.snapshots.clone(),
+        );
+        let currency_service = currency::Currency::new(dynamic_config.file_config.currency.clone());
         let catalog = if let Some(ref svc) = search_service {
-            catalog::CatalogService::with_search(biblios_repo.clone(), entities_repo, Arc::clone(svc))
+            catalog::CatalogService::with_search(biblios_repo.clone(), entities_repo, media_types_repo, Arc::clone(svc))
         } else {
-            catalog::CatalogService::new(biblios_repo, entities_repo)
-        };
+            catalog::CatalogService::new(biblios_repo, entities_repo, media_types_repo)
+        }
+        .with_snapshots(snapshots_service.clone())
+        .with_currency(currency_service.clone())
+        .with_locations(repo.clone() as Arc<dyn LocationsRepository>);
+
+        let artifacts_service = artifacts::ArtifactsService::new(
+            repo.clone() as Arc<dyn ArtifactsRepository>,
+            artifacts::blob_store(&artifacts_config)?,
+            artifacts_config,
+            &auth_config.jwt_secret,
+        );
 
         let marc_service = marc::MarcService::new(catalog.clone(), redis_service.clone());
         let audit_service = audit::AuditService::new(repository.clone());
 
         let loans_repo: Arc<dyn LoansServiceRepository> = repo.clone();
+        let loans_service = loans::LoansService::new(loans_repo, Some(dynamic_config.clone()));
         let loans_repo_only: Arc<dyn LoansRepository> = repo.clone();
         let email = email_service.as_ref().clone();
+        let notifications_service = notifications::NotificationsService::new(
+            repo.clone() as Arc<dyn NotificationsRepository>,
+            redis_service.clone(),
+        );
         let reminders_service = reminders::RemindersService::new(
             loans_repo_only,
             email.clone(),
             audit_service.clone(),
             dynamic_config.clone(),
+            notifications_service.clone(),
         );
 
+        let schedules_service = schedules::SchedulesService::new(repo.clone() as Arc<dyn SchedulesRepository>);
+        let holds_service = holds::HoldsService::new(repo.clone() as Arc<dyn HoldsRepository>);
+        let exports_service = exports::ExportsService::new(catalog.clone(), currency_service.clone());
+
         Ok(Self {
             pool,
             audit: audit_service.clone(),
             account_types_catalog: account_types_catalog::AccountTypesCatalogService::new(
                 repo.clone() as Arc<dyn AccountTypesCatalogRepository>,
             ),
+            api_keys: api_keys::ApiKeysService::new(
+                repo.clone() as Arc<dyn ApiKeysRepository>,
+                audit_service.clone(),
+            ),
+            artifacts: artifacts_service.clone(),
+            backup: backup::BackupService::new(
+                repo.clone() as Arc<dyn BackupsRepository>,
+                repository.pool.clone(),
+                artifacts_service.clone(),
+                redis_service.clone(),
+                audit_service.clone(),
+                dynamic_config.file_config.backup.clone(),
+            ),
+            bundles: bundles::BundlesService::new(repo.clone() as Arc<dyn BundlesRepository>),
+            campaigns: campaigns::CampaignsService::new(
+                repo.clone() as Arc<dyn CampaignsRepository>,
+                email.clone(),
+                dynamic_config.clone(),
+            ),
             catalog: catalog.clone(),
+            currency: currency_service.clone(),
+            dashboards: dashboards::DashboardsService::new(repo.clone() as Arc<dyn DashboardsRepository>),
+            deposits: deposits::DepositsService::new(repo.clone() as Arc<dyn DepositsRepository>),
+            donations: donations::DonationsService::new(
+                repo.clone() as Arc<dyn DonationsRepository>,
+                catalog.clone(),
+            ),
+            edit_locks: edit_locks::EditLocksService::new(redis_service.clone()),
             email: email.clone(),
+            email_health: email_health::EmailHealthService::new(
+                repo.clone() as Arc<dyn EmailHealthRepository>,
+                dynamic_config.clone(),
+            ),
+            eod: eod::EodService::new(
+                repo.clone() as Arc<dyn EodRepository>,
+                loans_service.clone(),
+                holds_service.clone(),
+                exports_service.clone(),
+                artifacts_service,
+                email.clone(),
+                audit_service.clone(),
+                dynamic_config.clone(),
+            ),
             equipment: equipment::EquipmentService::new(repo.clone() as Arc<dyn EquipmentRepository>),
+            exports: exports_service,
             events: events::EventsService::new(
                 repo.clone() as Arc<dyn EventsServiceRepository>,
                 email.clone(),
                 audit_service.clone(),
             ),
-            fines: fines::FinesService::new(repo.clone() as Arc<dyn FinesRepository>),
+            fines: fines::FinesService::new(repo.clone() as Arc<dyn FinesRepository>, dynamic_config.clone()),
+            harvest: harvest::HarvestService::new(
+                repo.clone() as Arc<dyn HarvestRepository>,
+                catalog.clone(),
+                email.clone(),
+                audit_service.clone(),
+            ),
             inventory: inventory::InventoryService::new(repo.clone() as Arc<dyn InventoryRepository>),
+            item_states: item_states::ItemStatesService::new(repo.clone() as Arc<dyn ItemStatesRepository>)
+                .with_catalog(catalog.clone()),
+            item_templates: item_templates::ItemTemplatesService::new(repo.clone() as Arc<dyn ItemTemplatesRepository>),
+            kiosks: kiosks::KiosksService::new(
+                repo.clone() as Arc<dyn KiosksRepository>,
+                audit_service.clone(),
+            ),
+            labels: labels::LabelsService::new(repo.clone() as Arc<dyn LabelsRepository>),
             library_info: library_info::LibraryInfoService::new(repository.clone()),
-            loans: loans::LoansService::new(loans_repo),
+            loan_batches: loan_batches::LoanBatchesService::new(
+                repo.clone() as Arc<dyn LoanBatchesRepository>,
+                repo.clone() as Arc<dyn UsersRepository>,
+            ),
+            loans: loans_service.clone(),
+            locations: locations::LocationsService::new(repo.clone() as Arc<dyn LocationsRepository>)
+                .with_catalog(catalog.clone()),
+            lockers: lockers::LockersService::new(
+                repository.clone(),
+                loans_service,
+                email.clone(),
+                dynamic_config.clone(),
+            ),
             marc: marc_service,
+            media_types: media_types::MediaTypesService::new(repo.clone() as Arc<dyn MediaTypesRepository>),
+            notifications: notifications_service.clone(),
+            payments: payments::PaymentsService::new(repo.clone() as Arc<dyn PaymentsRepository>, currency_service.clone()),
             public_types: public_types::PublicTypesService::new(repo.clone() as Arc<dyn PublicTypesRepository>),
+            purchase_suggestions: purchase_suggestions::PurchaseSuggestionsService::new(
+                repo.clone() as Arc<dyn PurchaseSuggestionsRepository>,
+                email.clone(),
+            ),
+            reading_programs: reading_programs::ReadingProgramsService::new(
+                repo.clone() as Arc<dyn ReadingProgramsRepository>,
+            ),
             redis: redis_service.clone(),
+            reengagement: reengagement::ReengagementService::new(
+                repo.clone() as Arc<dyn ReengagementRepository>,
+                email.clone(),
+                audit_service.clone(),
+                dynamic_config.clone(),
+            ),
+            relators: relators::RelatorsService::new(repo.clone() as Arc<dyn RelatorsRepository>),
             reminders: reminders_service,
-            holds: holds::HoldsService::new(repo.clone() as Arc<dyn HoldsRepository>),
-            schedules: schedules::SchedulesService::new(repo.clone() as Arc<dyn SchedulesRepository>),
+            holds: holds_service,
+            schedules: schedules_service.clone(),
             search: search_service,
-            sources: sources::SourcesService::new(repo.clone() as Arc<dyn SourcesRepository>),
-            stats: stats::StatsService::new(repository.clone()),
-            tasks: task_manager::TaskManager::new(redis_service.clone()),
-            users: users::UsersService::new(repository.clone(), auth_config, redis_service.clone()),
+            sitemap: sitemap::SitemapService::new(
+                repo.clone() as Arc<dyn SitemapRepository>,
+                audit_service.clone(),
+                dynamic_config.clone(),
+            ),
+            snapshots: snapshots_service.clone(),
+            sources: sources::SourcesService::new(repo.clone() as Arc<dyn SourcesRepository>, snapshots_service),
+            staffing: staffing::StaffingService::new(repo.clone() as Arc<dyn StaffingRepository>),
+            stats: stats::StatsService::new(repository.clone()).with_currency(currency_service.clone()),
+            tasks: task_manager::TaskManager::new(redis_service.clone())
+                .with_notifications(notifications_service),
+            user_messages: user_messages::UserMessagesService::new(repo.clone() as Arc<dyn UserMessagesRepository>),
+            users: users::UsersService::new(repository.clone(), auth_config, redis_service.clone(), email.clone()),
+            vendors: vendors::VendorsService::new(
+                repo.clone() as Arc<dyn VendorsRepository>,
+                repo.clone() as Arc<dyn SourcesRepository>,
+            ),
             visitor_counts: visitor_counts::VisitorCountsService::new(
                 repo.clone() as Arc<dyn VisitorCountsRepository>,
+                schedules_service,
+            ),
+            warehouse: warehouse::WarehouseService::new(
+                repo.clone() as Arc<dyn WarehouseRepository>,
+                audit_service.clone(),
             ),
             z3950: z3950::Z3950Service::new(
                 repository,
@@ -0,0 +1,89 @@
+//! In-app notifications service: per-user inbox written by background events, pushed over SSE
+
+use std::sync::Arc;
+
+use crate::{
+    api::sse::SsePayload,
+    error::AppResult,
+    models::notification::{
+        NewNotification, Notification, NotificationQuery, NotificationsMarkedRead, NotificationsPage,
+    },
+    repository::NotificationsRepository,
+    services::redis::{RedisService, EVENTS_CHANNEL},
+};
+
+#[derive(Clone)]
+pub struct NotificationsService {
+    repository: Arc<dyn NotificationsRepository>,
+    redis: RedisService,
+}
+
+impl NotificationsService {
+    pub fn new(repository: Arc<dyn NotificationsRepository>, redis: RedisService) -> Self {
+        Self { repository, redis }
+    }
+
+    /// The caller's notifications, newest first
+    pub async fn list(&self, user_id: i64, query: &NotificationQuery) -> AppResult<NotificationsPage> {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(20).clamp(1, 200);
+        let (notifications, total) = self
+            .repository
+            .notifications_list(user_id, query.unread_only, page, per_page)
+            .await?;
+        let unread = self.repository.notifications_unread_count(user_id).await?;
+        Ok(NotificationsPage { notifications, total, unread })
+    }
+
+    pub async fn unread_count(&self, user_id: i64) -> AppResult<i64> {
+        self.repository.notifications_unread_count(user_id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn mark_read(&self, user_id: i64, id: i64) -> AppResult<Notification> {
+        self.repository.notifications_mark_read(user_id, id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn mark_all_read(&self, user_id: i64) -> AppResult<NotificationsMarkedRead> {
+        let updated = self.repository.notifications_mark_all_read(user_id).await?;
+        Ok(NotificationsMarkedRead { updated })
+    }
+
+    /// Write a notification to `user_id`'s inbox and push it over SSE.
+    ///
+    /// Notifications accompany the operation that raised them: failures are logged, not returned.
+    pub async fn notify(&self, user_id: i64, data: &NewNotification) -> Option<Notification> {
+        let notification = match self.repository.notifications_create(user_id, data).await {
+            Ok(notification) => notification,
+            Err(e) => {
+                tracing::warn!("Failed to notify user {} ({}): {}", user_id, data.kind.as_str(), e);
+                return None;
+            }
+        };
+        let unread = self.repository.notifications_unread_count(user_id).await.unwrap_or(1);
+        let event = SsePayload::notification_created(&notification, unread);
+        if let Err(e) = self.redis.publish(EVENTS_CHANNEL, &event).await {
+            tracing::warn!("Notification {} not pushed over SSE: {}", notification.id, e);
+        }
+        Some(notification)
+    }
+
+    /// Notify every active staff account with loan write rights; returns how many were notified
+    pub async fn notify_staff(&self, data: &NewNotification) -> usize {
+        let recipients = match self.repository.notifications_staff_recipients().await {
+            Ok(recipients) => recipients,
+            Err(e) => {
+                tracing::warn!("Failed to list notification recipients ({}): {}", data.kind.as_str(), e);
+                return 0;
+            }
+        };
+        let mut notified = 0;
+        for user_id in recipients {
+            if self.notify(user_id, data).await.is_some() {
+                notified += 1;
+            }
+        }
+        notified
+    }
+}
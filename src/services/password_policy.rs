@@ -0,0 +1,179 @@
+//! Password policy: length and character classes per account type, common password deny-list and
+//! offline breach check against a local HIBP-style range dataset (k-anonymity: only the file of
+//! the first 5 hex digits of the SHA-1 is read).
+
+use std::{collections::HashSet, path::Path, sync::Arc};
+
+use sha1::{Digest, Sha1};
+
+use crate::{
+    config::{PasswordPolicyConfig, PasswordRules},
+    error::{AppError, AppResult},
+    models::user::AccountTypeSlug,
+};
+
+#[derive(Clone)]
+pub struct PasswordPolicy {
+    config: PasswordPolicyConfig,
+    deny_list: Arc<HashSet<String>>,
+}
+
+impl PasswordPolicy {
+    /// Load the deny-list; a missing or unreadable file only disables it (with a warning).
+    pub fn new(config: PasswordPolicyConfig) -> Self {
+        let deny_list = match config.deny_list_path.as_deref() {
+            Some(path) => match std::fs::read_to_string(path) {
+                Ok(content) => parse_deny_list(&content),
+                Err(e) => {
+                    tracing::warn!("Password deny-list {} not loaded: {}", path, e);
+                    HashSet::new()
+                }
+            },
+            None => HashSet::new(),
+        };
+        Self { config, deny_list: Arc::new(deny_list) }
+    }
+
+    /// Rules of an account type
+    pub fn rules(&self, account_type: &AccountTypeSlug) -> &PasswordRules {
+        self.config.account_types.get(account_type.as_str()).unwrap_or(&self.config.default)
+    }
+
+    /// Check `password` against the rules of `account_type`; failures are reported on `field`.
+    pub async fn check(&self, password: &str, account_type: &AccountTypeSlug, field: &str) -> AppResult<()> {
+        let rules = self.rules(account_type);
+        if let Some((code, message)) = check_rules(password, rules) {
+            return Err(AppError::invalid_field(field, code, message));
+        }
+        if rules.deny_common && self.deny_list.contains(&password.to_lowercase()) {
+            return Err(AppError::invalid_field(field, "common_password", "This password is too common"));
+        }
+        if let (true, Some(dir)) = (rules.check_breaches, self.config.breach_dataset_dir.as_deref()) {
+            let count = breach_count(Path::new(dir), password).await?;
+            if count >= self.config.breach_min_count.max(1) {
+                return Err(AppError::invalid_field(
+                    field,
+                    "breached_password",
+                    "This password appears in known data breaches",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_deny_list(content: &str) -> HashSet<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Error code and message of the first length or character class rule `password` breaks
+fn check_rules(password: &str, rules: &PasswordRules) -> Option<(&'static str, String)> {
+    if password.chars().count() < rules.min_length {
+        return Some(("length", format!("Password must be at least {} characters", rules.min_length)));
+    }
+    let classes = [
+        password.chars().any(char::is_lowercase),
+        password.chars().any(char::is_uppercase),
+        password.chars().any(|c| c.is_ascii_digit()),
+        password.chars().any(|c| !c.is_alphanumeric()),
+    ];
+    if classes.iter().filter(|c| **c).count() < rules.min_character_classes as usize {
+        return Some((
+            "character_classes",
+            format!(
+                "Password must mix at least {} of: lowercase letters, uppercase letters, digits, other characters",
+                rules.min_character_classes
+            ),
+        ));
+    }
+    None
+}
+
+/// Times `password` was seen in the range dataset under `dir` (0 when its prefix file is missing)
+async fn breach_count(dir: &Path, password: &str) -> AppResult<u64> {
+    let hash = hex::encode_upper(Sha1::digest(password.as_bytes()));
+    let (prefix, suffix) = hash.split_at(5);
+    let content = match tokio::fs::read_to_string(dir.join(format!("{}.txt", prefix))).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(AppError::Internal(format!("Failed to read breach dataset: {}", e))),
+    };
+    Ok(range_count(&content, suffix))
+}
+
+/// Count of `suffix` in a range file (`SUFFIX:COUNT` lines, a bare suffix counting once)
+fn range_count(content: &str, suffix: &str) -> u64 {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (hash, count) = line.trim().split_once(':').unwrap_or((line.trim(), "1"));
+            hash.eq_ignore_ascii_case(suffix).then(|| count.trim().parse().unwrap_or(1))
+        })
+        .next()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(min_length: usize, min_character_classes: u8) -> PasswordRules {
+        PasswordRules { min_length, min_character_classes, deny_common: true, check_breaches: true }
+    }
+
+    #[test]
+    fn length_and_character_classes() {
+        assert_eq!(check_rules("abc", &rules(4, 1)).unwrap().0, "length");
+        assert!(check_rules("abcd", &rules(4, 1)).is_none());
+        // Characters, not bytes
+        assert!(check_rules("éèàç", &rules(4, 1)).is_none());
+        assert_eq!(check_rules("abcdefghijkl", &rules(12, 3)).unwrap().0, "character_classes");
+        assert!(check_rules("abcdefghijK1", &rules(12, 3)).is_none());
+        assert!(check_rules("abcdefghij-1", &rules(12, 3)).is_none());
+    }
+
+    #[test]
+    fn range_files_match_hash_suffixes() {
+        // SHA-1("password") = 5BAA6 1E4C9B93F3F0682250B6CF8331B7EE68FD8
+        let hash = hex::encode_upper(Sha1::digest(b"password"));
+        assert_eq!(&hash[..5], "5BAA6");
+        let content = "003D68EB55068C33ACE09247EE4C639306B:3\r\n1E4C9B93F3F0682250B6CF8331B7EE68FD8:9545824\r\n";
+        assert_eq!(range_count(content, &hash[5..]), 9545824);
+        assert_eq!(range_count(content, "0000000000000000000000000000000000A"), 0);
+        assert_eq!(range_count("1e4c9b93f3f0682250b6cf8331b7ee68fd8\n", &hash[5..]), 1);
+    }
+
+    #[tokio::test]
+    async fn policy_by_account_type() {
+        let dir = std::env::temp_dir().join(format!("elidune-breaches-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let hash = hex::encode_upper(Sha1::digest(b"Correct-Horse-7"));
+        std::fs::write(dir.join(format!("{}.txt", &hash[..5])), format!("{}:2\n", &hash[5..])).unwrap();
+
+        let mut config = PasswordPolicyConfig {
+            deny_list_path: None,
+            breach_dataset_dir: Some(dir.to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+        config.default.deny_common = true;
+        let mut policy = PasswordPolicy::new(config);
+        policy.deny_list = Arc::new(parse_deny_list("# common\nLetMeIn\n\n"));
+
+        let reader = AccountTypeSlug::Reader;
+        let admin = AccountTypeSlug::Admin;
+        assert!(policy.check("abcd", &reader, "password").await.is_ok());
+        assert!(policy.check("letmein", &reader, "password").await.is_err());
+        // The default rules skip the breach check
+        assert!(policy.check("Correct-Horse-7", &reader, "password").await.is_ok());
+        assert!(policy.check("abcd", &admin, "password").await.is_err());
+        assert!(policy.check("Correct-Horse-7", &admin, "password").await.is_err());
+        assert!(policy.check("Correct-Horse-8", &admin, "newPassword").await.is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,367 @@
+//! Cash register service: payments for fines, memberships and sundries, refunds, daily cash-up
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::payment::{
+        format_receipt_number, CashTotal, CashTotalsBy, DailyCashSummary, OperatorCashTotal, Payment,
+        PaymentCategory, PaymentExportRow, PaymentPeriodLock, PaymentQuery, PaymentsPage, ReceiptGapReport,
+        RecordPayment, RefundPayment,
+    },
+    repository::PaymentsRepository,
+    services::currency::Currency,
+};
+
+#[derive(Clone)]
+pub struct PaymentsService {
+    repository: Arc<dyn PaymentsRepository>,
+    currency: Currency,
+}
+
+impl PaymentsService {
+    pub fn new(repository: Arc<dyn PaymentsRepository>, currency: Currency) -> Self {
+        Self { repository, currency }
+    }
+
+    /// List payments and refunds, newest first
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self, query: &PaymentQuery) -> AppResult<PaymentsPage> {
+        if let (Some(start), Some(end)) = (query.start_date, query.end_date) {
+            if end < start {
+                return Err(AppError::Validation("endDate must not be before startDate".to_string()));
+            }
+        }
+        let from = query.start_date.map(local_day_start);
+        let to = query.end_date.map(|end| local_day_start(end + Duration::days(1)));
+        let (payments, total) = self.repository.payments_list(query, from, to).await?;
+        Ok(PaymentsPage { payments, total })
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn get(&self, id: i64) -> AppResult<Payment> {
+        self.repository.payments_get(id).await
+    }
+
+    /// Record a payment taken by `operator_id`. Fine payments are applied to the fine balance;
+    /// membership payments renew the patron for the subscription duration of their public type.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn record(&self, data: &RecordPayment, operator_id: i64) -> AppResult<Payment> {
+        match (data.category, data.fine_id) {
+            (PaymentCategory::Fine, None) => {
+                return Err(AppError::Validation("fineId is required for fine payments".to_string()));
+            }
+            (PaymentCategory::Fine, Some(_)) | (_, None) => {}
+            (_, Some(_)) => {
+                return Err(AppError::Validation("fineId is only allowed for fine payments".to_string()));
+            }
+        }
+
+        let (amount, membership_days) = if data.category == PaymentCategory::Membership {
+            let user_id = data
+                .user_id
+                .ok_or_else(|| AppError::Validation("userId is required for membership payments".to_string()))?;
+            let terms = self.repository.payments_membership_terms(user_id).await?;
+            let days = terms.duration_days.filter(|days| *days > 0).ok_or_else(|| {
+                AppError::BusinessRule("The patron's public type has no subscription duration".to_string())
+            })?;
+            let amount = data
+                .amount
+                .or_else(|| terms.price_cents.map(|cents| Decimal::new(cents.into(), 2)))
+                .ok_or_else(|| AppError::Validation("amount is required (no subscription price)".to_string()))?;
+            (amount, Some(days))
+        } else {
+            let amount = data.amount.ok_or_else(|| AppError::Validation("amount is required".to_string()))?;
+            (amount, None)
+        };
+        validate_amount(amount)?;
+
+        self.repository
+            .payments_create(data, amount, membership_days, operator_id, Local::now().year())
+            .await
+    }
+
+    /// Refund all or part of a payment; returns the refund (negative amount)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn refund(&self, id: i64, data: &RefundPayment, operator_id: i64) -> AppResult<Payment> {
+        if let Some(amount) = data.amount {
+            validate_amount(amount)?;
+        }
+        self.repository.payments_refund(id, data, operator_id, Local::now().year()).await
+    }
+
+    /// Cash-up of one day (library local time)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn daily_summary(&self, date: NaiveDate) -> AppResult<DailyCashSummary> {
+        let from = local_day_start(date);
+        let to = local_day_start(date + Duration::days(1));
+        let by_method = self.repository.payments_totals(from, to, CashTotalsBy::Method).await?;
+        let by_category = self.repository.payments_totals(from, to, CashTotalsBy::Category).await?;
+        let by_operator = self.repository.payments_operator_totals(from, to).await?;
+        Ok(summarize(date, by_method, by_category, by_operator))
+    }
+
+    /// Accounting export of a finished month (`YYYY-MM`, library local time). The month is locked
+    /// on its first export so that what was handed to accounting cannot change afterwards.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn export_month(&self, month: &str, operator_id: i64) -> AppResult<(PaymentPeriodLock, String)> {
+        let period = parse_month(month)?;
+        let next = period
+            .checked_add_months(Months::new(1))
+            .ok_or_else(|| AppError::Validation("month is out of range".to_string()))?;
+        let (from, to) = (local_day_start(period), local_day_start(next));
+        if to > Utc::now() {
+            return Err(AppError::BusinessRule(format!("Month {month} is not over yet")));
+        }
+
+        let rows = self.repository.payments_export_rows(from, to).await?;
+        let missing = missing_receipts(&rows);
+        if !missing.is_empty() {
+            return Err(AppError::BusinessRule(format!(
+                "Receipt numbers missing in {month}: {}",
+                missing.join(", ")
+            )));
+        }
+        let count = i32::try_from(rows.len()).map_err(|_| AppError::Internal("Too many payments".to_string()))?;
+        let total = rows.iter().map(|row| row.amount).sum();
+        let lock = self.repository.payments_lock_period(period, from, to, count, total, operator_id).await?;
+        Ok((lock, accounting_csv(&rows, &self.currency)))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn period_locks(&self) -> AppResult<Vec<PaymentPeriodLock>> {
+        self.repository.payments_period_locks().await
+    }
+
+    /// Reopen an exported month (`YYYY-MM`)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn unlock_period(&self, month: &str) -> AppResult<()> {
+        let period = parse_month(month)?;
+        self.repository.payments_unlock_period(period).await
+    }
+
+    /// Receipt numbers of `year` (default: current year) missing from the sequence
+    #[tracing::instrument(skip(self), err)]
+    pub async fn receipt_gaps(&self, year: Option<i32>) -> AppResult<ReceiptGapReport> {
+        let year = year.unwrap_or_else(|| Local::now().year());
+        if !(1900..=9999).contains(&year) {
+            return Err(AppError::Validation("year is out of range".to_string()));
+        }
+        self.repository.payments_receipt_gaps(year).await
+    }
+}
+
+/// First day of a `YYYY-MM` month
+fn parse_month(month: &str) -> AppResult<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d")
+        .map_err(|_| AppError::Validation("month must be formatted YYYY-MM".to_string()))
+}
+
+/// Numbers skipped between consecutive receipts of the same year (rows in receipt order)
+fn missing_receipts(rows: &[PaymentExportRow]) -> Vec<String> {
+    rows.windows(2)
+        .filter(|pair| pair[0].receipt_year == pair[1].receipt_year)
+        .flat_map(|pair| {
+            let year = pair[0].receipt_year.into();
+            (pair[0].receipt_seq + 1..pair[1].receipt_seq).map(move |seq| format_receipt_number(year, seq))
+        })
+        .collect()
+}
+
+/// Import file of the municipal accounting software: `;`-separated, CRLF line endings,
+/// dates as `dd/mm/yyyy` (library local time), amounts in the currency format, refunds negative
+fn accounting_csv(rows: &[PaymentExportRow], currency: &Currency) -> String {
+    let mut csv = String::from("receipt_number;date;category;method;amount;refund_of;payer;notes\r\n");
+    for row in rows {
+        let fields = [
+            row.receipt_number.clone(),
+            row.created_at.with_timezone(&Local).format("%d/%m/%Y").to_string(),
+            row.category.as_str().to_string(),
+            row.method.as_str().to_string(),
+            currency.format_amount(row.amount),
+            row.refund_of_receipt.clone().unwrap_or_default(),
+            row.payer.clone().unwrap_or_default(),
+            row.notes.clone().unwrap_or_default(),
+        ];
+        let line: Vec<String> = fields.iter().map(|field| accounting_field(field)).collect();
+        csv.push_str(&line.join(";"));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// Quote a field when it contains the separator, a quote or a line break
+fn accounting_field(s: &str) -> String {
+    if s.contains([';', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Positive, at most two decimals, within the column precision
+fn validate_amount(amount: Decimal) -> AppResult<()> {
+    if amount <= Decimal::ZERO {
+        return Err(AppError::Validation("amount must be positive".to_string()));
+    }
+    if amount.normalize().scale() > 2 {
+        return Err(AppError::Validation("amount must have at most 2 decimals".to_string()));
+    }
+    // `payments.amount` is NUMERIC(10,2)
+    if amount >= Decimal::from(100_000_000) {
+        return Err(AppError::Validation("amount is too large".to_string()));
+    }
+    Ok(())
+}
+
+/// First instant of a local day, in UTC
+fn local_day_start(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+/// Day totals are the sum of the per-method lines (every payment has exactly one method)
+fn summarize(
+    date: NaiveDate,
+    by_method: Vec<CashTotal>,
+    by_category: Vec<CashTotal>,
+    by_operator: Vec<OperatorCashTotal>,
+) -> DailyCashSummary {
+    let received = by_method.iter().map(|t| t.received).sum();
+    let refunded = by_method.iter().map(|t| t.refunded).sum();
+    let net = by_method.iter().map(|t| t.net).sum();
+    let count = by_method.iter().map(|t| t.count).sum();
+    DailyCashSummary { date, received, refunded, net, count, by_method, by_category, by_operator }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::{
+        models::payment::{MembershipTerms, PaymentMethod},
+        repository::payments::MockPaymentsRepository,
+    };
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    fn request(category: PaymentCategory, amount: Option<&str>) -> RecordPayment {
+        RecordPayment {
+            user_id: Some(7),
+            amount: amount.map(dec),
+            method: PaymentMethod::Cash,
+            category,
+            fine_id: None,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn amounts_are_positive_with_two_decimals() {
+        assert!(validate_amount(dec("2.50")).is_ok());
+        assert!(validate_amount(dec("2.500")).is_ok());
+        assert!(validate_amount(dec("0")).is_err());
+        assert!(validate_amount(dec("-1")).is_err());
+        assert!(validate_amount(dec("0.125")).is_err());
+        assert!(validate_amount(dec("100000000")).is_err());
+    }
+
+    #[tokio::test]
+    async fn membership_defaults_to_public_type_terms() {
+        let mut repo = MockPaymentsRepository::new();
+        repo.expect_payments_membership_terms()
+            .returning(|_| Ok(MembershipTerms { duration_days: Some(365), price_cents: Some(1500) }));
+        repo.expect_payments_create()
+            .withf(|_, amount, days, operator, _| *amount == dec("15.00") && *days == Some(365) && *operator == 1)
+            .returning(|_, _, _, _, _| Err(AppError::Internal("stop".to_string())));
+        let service = PaymentsService::new(Arc::new(repo), Currency::default());
+
+        let err = service.record(&request(PaymentCategory::Membership, None), 1).await.unwrap_err();
+        assert!(matches!(err, AppError::Internal(_)));
+    }
+
+    #[tokio::test]
+    async fn fine_link_matches_category() {
+        let service = PaymentsService::new(Arc::new(MockPaymentsRepository::new()), Currency::default());
+        let err = service.record(&request(PaymentCategory::Fine, Some("1.00")), 1).await.unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+
+        let mut printing = request(PaymentCategory::Printing, Some("0.40"));
+        printing.fine_id = Some(3);
+        let err = service.record(&printing, 1).await.unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+    }
+
+    #[test]
+    fn summary_totals_come_from_methods() {
+        let line = |key: &str, received: &str, refunded: &str, count| CashTotal {
+            key: key.to_string(),
+            received: dec(received),
+            refunded: dec(refunded),
+            net: dec(received) - dec(refunded),
+            count,
+        };
+        let date = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let summary = summarize(
+            date,
+            vec![line("card", "15.00", "0", 1), line("cash", "4.40", "1.50", 4)],
+            vec![line("fine", "3.00", "1.50", 3), line("membership", "15.00", "0", 1), line("printing", "1.40", "0", 1)],
+            Vec::new(),
+        );
+        assert_eq!((summary.received, summary.refunded, summary.net), (dec("19.40"), dec("1.50"), dec("17.90")));
+        assert_eq!(summary.count, 5);
+    }
+
+    fn export_row(seq: i32, amount: &str) -> PaymentExportRow {
+        PaymentExportRow {
+            receipt_year: 2026,
+            receipt_seq: seq,
+            receipt_number: format_receipt_number(2026, seq),
+            created_at: Utc.with_ymd_and_hms(2026, 9, 14, 10, 0, 0).unwrap(),
+            amount: dec(amount),
+            method: PaymentMethod::Cash,
+            category: PaymentCategory::Printing,
+            refund_of_receipt: None,
+            payer: None,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn missing_receipts_are_reported() {
+        let rows = vec![export_row(4, "1.00"), export_row(5, "1.00"), export_row(8, "1.00")];
+        assert_eq!(missing_receipts(&rows), vec!["2026-000006", "2026-000007"]);
+        assert!(missing_receipts(&rows[..2]).is_empty());
+    }
+
+    #[test]
+    fn accounting_csv_uses_semicolons_and_decimal_commas() {
+        let mut refund = export_row(2, "-1.5");
+        refund.refund_of_receipt = Some("2026-000001".to_string());
+        refund.payer = Some("Doe Jane".to_string());
+        refund.notes = Some("jammed; reprinted".to_string());
+        let csv = accounting_csv(&[export_row(1, "2.5"), refund], &Currency::default());
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines[0], "receipt_number;date;category;method;amount;refund_of;payer;notes");
+        assert!(lines[1].starts_with("2026-000001;"));
+        assert!(lines[1].ends_with(";printing;cash;2,50;;;"));
+        assert!(lines[2].ends_with(";-1,50;2026-000001;Doe Jane;\"jammed; reprinted\""));
+    }
+
+    #[test]
+    fn months_are_parsed() {
+        assert_eq!(parse_month("2026-09").unwrap(), NaiveDate::from_ymd_opt(2026, 9, 1).unwrap());
+        assert!(parse_month("2026-13").is_err());
+        assert!(parse_month("09/2026").is_err());
+    }
+}
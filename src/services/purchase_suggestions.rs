@@ -0,0 +1,199 @@
+//! Purchase suggestions service: patron suggestions, staff triage and availability notifications
+
+use std::sync::Arc;
+
+use crate::{
+    email::EmailService,
+    error::{AppError, AppResult},
+    models::{
+        biblio::Isbn,
+        purchase_suggestion::{
+            CreatePurchaseSuggestion, PurchaseSuggestion, PurchaseSuggestionQuery, PurchaseSuggestionStatus,
+            TriagePurchaseSuggestion,
+        },
+        Language,
+    },
+    repository::PurchaseSuggestionsRepository,
+};
+
+#[derive(Clone)]
+pub struct PurchaseSuggestionsService {
+    repository: Arc<dyn PurchaseSuggestionsRepository>,
+    email: EmailService,
+}
+
+impl PurchaseSuggestionsService {
+    pub fn new(repository: Arc<dyn PurchaseSuggestionsRepository>, email: EmailService) -> Self {
+        Self { repository, email }
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self, query: &PurchaseSuggestionQuery) -> AppResult<Vec<PurchaseSuggestion>> {
+        self.repository.purchase_suggestions_list(query).await
+    }
+
+    pub async fn get_by_id(&self, id: i64) -> AppResult<PurchaseSuggestion> {
+        self.repository.purchase_suggestions_get_by_id(id).await
+    }
+
+    /// Record a patron suggestion. Refused when the ISBN is already in the catalog with a copy,
+    /// or when the patron already suggested it and the suggestion is still open.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create(&self, user_id: i64, data: &CreatePurchaseSuggestion) -> AppResult<PurchaseSuggestion> {
+        let isbn = validate_suggestion(data)?;
+        if let Some(ref isbn) = isbn {
+            if let Some(biblio_id) = self.repository.purchase_suggestions_owned_biblio(isbn.clone()).await? {
+                return Err(AppError::Conflict(format!(
+                    "This title is already in the catalog (biblio {})",
+                    biblio_id
+                )));
+            }
+            if self.repository.purchase_suggestions_open_exists(user_id, isbn.clone()).await? {
+                return Err(AppError::Conflict(
+                    "You already suggested this title".to_string(),
+                ));
+            }
+        }
+        self.repository.purchase_suggestions_create(user_id, isbn, data).await
+    }
+
+    /// Apply a triage decision. Moving a suggestion to `added` notifies the patron right away
+    /// when the record already has a copy; otherwise the scheduler does once one is created.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn triage(
+        &self,
+        id: i64,
+        data: &TriagePurchaseSuggestion,
+        triaged_by: i64,
+    ) -> AppResult<PurchaseSuggestion> {
+        let current = self.repository.purchase_suggestions_get_by_id(id).await?;
+        if current.status == PurchaseSuggestionStatus::Added {
+            return Err(AppError::Conflict(
+                "Suggested title was already added to the catalog".to_string(),
+            ));
+        }
+        validate_triage(data)?;
+
+        let suggestion = self.repository.purchase_suggestions_triage(id, data, triaged_by).await?;
+        if suggestion.status != PurchaseSuggestionStatus::Added {
+            return Ok(suggestion);
+        }
+        let claimed = self.repository.purchase_suggestions_claim_available(Some(id)).await?;
+        match claimed.into_iter().next() {
+            Some(notified) => {
+                self.notify(&notified).await;
+                Ok(notified)
+            }
+            None => Ok(suggestion),
+        }
+    }
+
+    /// Mark as added every open suggestion whose title now has a copy in the catalog, and email
+    /// the patrons. Returns the number of suggestions notified.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn notify_available(&self) -> AppResult<usize> {
+        let claimed = self.repository.purchase_suggestions_claim_available(None).await?;
+        for suggestion in &claimed {
+            self.notify(suggestion).await;
+        }
+        Ok(claimed.len())
+    }
+
+    /// Send the `suggestion_available` email; failures are logged (the suggestion stays notified).
+    async fn notify(&self, suggestion: &PurchaseSuggestion) {
+        let contact = match self.repository.purchase_suggestions_contact(suggestion.user_id).await {
+            Ok(Some(contact)) => contact,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!(suggestion_id = suggestion.id, "Suggestion contact lookup failed: {}", e);
+                return;
+            }
+        };
+        let Some(to) = contact.email.as_deref().map(str::trim).filter(|e| !e.is_empty()) else {
+            tracing::debug!(user_id = suggestion.user_id, "No email — skipping suggestion notification");
+            return;
+        };
+        if let Err(e) = self
+            .email
+            .send_suggestion_available(
+                to,
+                contact.firstname.as_deref().unwrap_or_default(),
+                contact.lastname.as_deref().unwrap_or_default(),
+                &suggestion.title,
+                contact.language.as_deref().map(Language::from),
+            )
+            .await
+        {
+            tracing::warn!(suggestion_id = suggestion.id, "Suggestion notification failed: {}", e);
+        }
+    }
+}
+
+/// Check a patron suggestion and return its normalized ISBN, if any
+fn validate_suggestion(data: &CreatePurchaseSuggestion) -> AppResult<Option<String>> {
+    if data.title.trim().is_empty() {
+        return Err(AppError::Validation("Suggested title cannot be empty".to_string()));
+    }
+    if data.title.trim().chars().count() > 500 {
+        return Err(AppError::Validation("Suggested title is too long (500 characters max)".to_string()));
+    }
+    let isbn = data.isbn.as_deref().map(Isbn::new).filter(|i| !i.is_empty());
+    if let Some(ref isbn) = isbn {
+        if !matches!(isbn.as_str().len(), 10 | 13) {
+            return Err(AppError::Validation(format!("Invalid ISBN: {}", isbn)));
+        }
+    }
+    Ok(isbn.map(|i| i.as_str().to_string()))
+}
+
+fn validate_triage(data: &TriagePurchaseSuggestion) -> AppResult<()> {
+    match data.status {
+        PurchaseSuggestionStatus::Rejected
+            if data.rejection_reason.as_deref().is_none_or(|r| r.trim().is_empty()) =>
+        {
+            Err(AppError::Validation("rejectionReason is required to reject a suggestion".to_string()))
+        }
+        PurchaseSuggestionStatus::Added if data.biblio_id.is_none() => Err(AppError::Validation(
+            "biblioId is required to mark a suggestion as added".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggestion(title: &str, isbn: Option<&str>) -> CreatePurchaseSuggestion {
+        CreatePurchaseSuggestion {
+            isbn: isbn.map(String::from),
+            title: title.to_string(),
+            author: None,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn suggestion_isbn_is_normalized() {
+        let isbn = validate_suggestion(&suggestion("Dune", Some("978-2-266-32083-9"))).unwrap();
+        assert_eq!(isbn.as_deref(), Some("9782266320839"));
+        assert_eq!(validate_suggestion(&suggestion("Dune", Some(" "))).unwrap(), None);
+        assert!(validate_suggestion(&suggestion("Dune", Some("12345"))).is_err());
+        assert!(validate_suggestion(&suggestion("  ", None)).is_err());
+    }
+
+    #[test]
+    fn triage_requires_reason_or_biblio() {
+        let triage = |status, reason: Option<&str>, biblio_id| TriagePurchaseSuggestion {
+            status,
+            rejection_reason: reason.map(String::from),
+            purchase_order_id: None,
+            biblio_id,
+        };
+        assert!(validate_triage(&triage(PurchaseSuggestionStatus::Rejected, None, None)).is_err());
+        assert!(validate_triage(&triage(PurchaseSuggestionStatus::Rejected, Some("Out of print"), None)).is_ok());
+        assert!(validate_triage(&triage(PurchaseSuggestionStatus::Added, None, None)).is_err());
+        assert!(validate_triage(&triage(PurchaseSuggestionStatus::Added, None, Some(7))).is_ok());
+        assert!(validate_triage(&triage(PurchaseSuggestionStatus::Ordered, None, None)).is_ok());
+    }
+}
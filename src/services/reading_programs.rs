@@ -0,0 +1,251 @@
+//! Reading programs service: enrollments, reading log, completion and participation statistics
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+
+use crate::{
+    error::{AppError, AppResult},
+    models::reading_program::{
+        CreateReadingEntry, CreateReadingProgram, ReadingEnrollment, ReadingEntry, ReadingLoansSyncReport,
+        ReadingProgram, ReadingProgramStats, UpdateReadingProgram,
+    },
+    repository::ReadingProgramsRepository,
+};
+
+#[derive(Clone)]
+pub struct ReadingProgramsService {
+    repository: Arc<dyn ReadingProgramsRepository>,
+}
+
+impl ReadingProgramsService {
+    pub fn new(repository: Arc<dyn ReadingProgramsRepository>) -> Self {
+        Self { repository }
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self) -> AppResult<Vec<ReadingProgram>> {
+        self.repository.reading_programs_list().await
+    }
+
+    pub async fn get(&self, id: i64) -> AppResult<ReadingProgram> {
+        self.repository.reading_programs_get(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create(&self, data: &CreateReadingProgram) -> AppResult<ReadingProgram> {
+        validate_name(Some(&data.name))?;
+        validate_program(data.start_date, data.end_date, data.goal_books)?;
+        self.repository.reading_programs_create(data).await
+    }
+
+    /// Update a program; completion of every enrollment is re-evaluated against the new rules
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update(&self, id: i64, data: &UpdateReadingProgram) -> AppResult<ReadingProgram> {
+        validate_name(data.name.as_deref())?;
+        let current = self.repository.reading_programs_get(id).await?;
+        validate_program(
+            data.start_date.unwrap_or(current.start_date),
+            data.end_date.unwrap_or(current.end_date),
+            data.goal_books.unwrap_or(current.goal_books),
+        )?;
+        let program = self.repository.reading_programs_update(id, data).await?;
+        self.repository.reading_programs_refresh_completion(id, None).await?;
+        Ok(program)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete(&self, id: i64) -> AppResult<()> {
+        self.repository.reading_programs_delete(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn enrollments(&self, program_id: i64) -> AppResult<Vec<ReadingEnrollment>> {
+        self.repository.reading_programs_get(program_id).await?;
+        self.repository.reading_programs_enrollments(program_id).await
+    }
+
+    pub async fn get_enrollment(&self, program_id: i64, enrollment_id: i64) -> AppResult<ReadingEnrollment> {
+        self.repository.reading_programs_get_enrollment(program_id, enrollment_id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn enroll(&self, program_id: i64, user_id: i64) -> AppResult<ReadingEnrollment> {
+        self.repository.reading_programs_get(program_id).await?;
+        self.repository.reading_programs_enroll(program_id, user_id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn unenroll(&self, program_id: i64, enrollment_id: i64) -> AppResult<()> {
+        self.repository.reading_programs_unenroll(program_id, enrollment_id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn entries(&self, program_id: i64, enrollment_id: i64) -> AppResult<Vec<ReadingEntry>> {
+        self.repository.reading_programs_get_enrollment(program_id, enrollment_id).await?;
+        self.repository.reading_programs_entries(enrollment_id).await
+    }
+
+    /// Log a book for an enrolled patron and re-evaluate completion.
+    ///
+    /// With `loanArchiveId`, the loan must be one the patron returned; its return date, record and
+    /// title fill the fields left empty.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn add_entry(
+        &self,
+        program_id: i64,
+        enrollment_id: i64,
+        data: &CreateReadingEntry,
+    ) -> AppResult<ReadingEntry> {
+        let program = self.repository.reading_programs_get(program_id).await?;
+        let enrollment = self
+            .repository
+            .reading_programs_get_enrollment(program_id, enrollment_id)
+            .await?;
+
+        let mut entry = CreateReadingEntry {
+            read_at: data.read_at,
+            title: data.title.clone().filter(|t| !t.trim().is_empty()),
+            biblio_id: data.biblio_id,
+            loan_archive_id: data.loan_archive_id,
+            notes: data.notes.clone(),
+        };
+        if let Some(loan_archive_id) = data.loan_archive_id {
+            let loan = self.repository.reading_programs_returned_loan(loan_archive_id).await?;
+            if loan.user_id != Some(enrollment.user_id) {
+                return Err(AppError::Validation(format!(
+                    "Loan {} was not returned by this patron",
+                    loan_archive_id
+                )));
+            }
+            entry.read_at = entry.read_at.or(Some(loan.returned_at.date_naive()));
+            entry.biblio_id = entry.biblio_id.or(loan.biblio_id);
+            entry.title = entry.title.or(loan.title);
+        } else if program.loans_only {
+            return Err(AppError::BusinessRule(
+                "This program only counts returned loans (loanArchiveId is required)".to_string(),
+            ));
+        }
+        if entry.title.is_none() && entry.biblio_id.is_none() {
+            return Err(AppError::Validation(
+                "title, biblioId or loanArchiveId is required".to_string(),
+            ));
+        }
+        let read_at = entry.read_at.unwrap_or_else(|| Utc::now().date_naive());
+        validate_read_at(&program, read_at)?;
+        entry.read_at = Some(read_at);
+
+        let created = self.repository.reading_programs_add_entry(enrollment_id, &entry).await?;
+        self.repository
+            .reading_programs_refresh_completion(program_id, Some(enrollment_id))
+            .await?;
+        Ok(created)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete_entry(&self, program_id: i64, enrollment_id: i64, entry_id: i64) -> AppResult<()> {
+        self.repository.reading_programs_get_enrollment(program_id, enrollment_id).await?;
+        self.repository.reading_programs_delete_entry(enrollment_id, entry_id).await?;
+        self.repository
+            .reading_programs_refresh_completion(program_id, Some(enrollment_id))
+            .await?;
+        Ok(())
+    }
+
+    /// Log the loans enrolled patrons returned during the program
+    #[tracing::instrument(skip(self), err)]
+    pub async fn sync_loans(&self, program_id: i64) -> AppResult<ReadingLoansSyncReport> {
+        self.repository.reading_programs_get(program_id).await?;
+        let entries_created = self.repository.reading_programs_sync_loans(program_id).await?;
+        let completed = self
+            .repository
+            .reading_programs_refresh_completion(program_id, None)
+            .await?;
+        Ok(ReadingLoansSyncReport { entries_created, completed })
+    }
+
+    /// Participation statistics by age bracket
+    #[tracing::instrument(skip(self), err)]
+    pub async fn stats(&self, program_id: i64) -> AppResult<ReadingProgramStats> {
+        self.repository.reading_programs_get(program_id).await?;
+        let by_age_bracket = self.repository.reading_programs_stats(program_id).await?;
+        Ok(ReadingProgramStats {
+            program_id,
+            enrolled: by_age_bracket.iter().map(|r| r.enrolled).sum(),
+            completed: by_age_bracket.iter().map(|r| r.completed).sum(),
+            books_read: by_age_bracket.iter().map(|r| r.books_read).sum(),
+            by_age_bracket,
+        })
+    }
+}
+
+fn validate_name(name: Option<&str>) -> AppResult<()> {
+    let Some(name) = name.map(str::trim) else {
+        return Ok(());
+    };
+    if name.is_empty() || name.chars().count() > 255 {
+        return Err(AppError::Validation(
+            "name must be between 1 and 255 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_program(start_date: NaiveDate, end_date: NaiveDate, goal_books: i16) -> AppResult<()> {
+    if end_date < start_date {
+        return Err(AppError::Validation(
+            "endDate must be on or after startDate".to_string(),
+        ));
+    }
+    if goal_books <= 0 {
+        return Err(AppError::Validation("goalBooks must be positive".to_string()));
+    }
+    Ok(())
+}
+
+/// Entries outside the program dates would never count: refuse them.
+fn validate_read_at(program: &ReadingProgram, read_at: NaiveDate) -> AppResult<()> {
+    if read_at < program.start_date || read_at > program.end_date {
+        return Err(AppError::Validation(format!(
+            "readAt {} is outside the program dates ({} to {})",
+            read_at, program.start_date, program.end_date
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn program_rules() {
+        assert!(validate_program(date(2026, 7, 1), date(2026, 8, 31), 5).is_ok());
+        assert!(validate_program(date(2026, 7, 1), date(2026, 7, 1), 1).is_ok());
+        assert!(validate_program(date(2026, 8, 31), date(2026, 7, 1), 5).is_err());
+        assert!(validate_program(date(2026, 7, 1), date(2026, 8, 31), 0).is_err());
+    }
+
+    #[test]
+    fn read_at_within_program_dates() {
+        let program = ReadingProgram {
+            id: 1,
+            name: "Summer".to_string(),
+            description: None,
+            start_date: date(2026, 7, 1),
+            end_date: date(2026, 8, 31),
+            goal_books: 5,
+            loans_only: false,
+            created_at: None,
+            update_at: None,
+        };
+        assert!(validate_read_at(&program, date(2026, 7, 1)).is_ok());
+        assert!(validate_read_at(&program, date(2026, 8, 31)).is_ok());
+        assert!(validate_read_at(&program, date(2026, 6, 30)).is_err());
+        assert!(validate_read_at(&program, date(2026, 9, 1)).is_err());
+    }
+}
@@ -1,9 +1,32 @@
-//! Redis service for managing 2FA codes and temporary data
+//! Redis service for managing 2FA codes, temporary data and pub/sub between instances
+
+use std::{collections::BTreeMap, time::Duration};
 
 use redis::{AsyncCommands, Client};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::{sync::broadcast, task::JoinHandle};
+use tokio_stream::StreamExt;
 
 use crate::error::{AppError, AppResult};
 
+/// Pub/sub channel carrying real-time events (SSE) between server instances
+pub const EVENTS_CHANNEL: &str = "elidune:events";
+
+/// Longest wait before re-subscribing after the pub/sub connection dropped
+const MAX_RESUBSCRIBE_DELAY: Duration = Duration::from_secs(30);
+
+/// Outcome of an email 2FA code check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TwoFactorCodeCheck {
+    Valid,
+    /// Wrong code, attempts left
+    Invalid,
+    /// Wrong code and no attempt left: the challenge is gone
+    TooManyAttempts,
+    /// No such challenge (expired, consumed or replaced by a newer login)
+    Expired,
+}
+
 #[derive(Clone)]
 pub struct RedisService {
     client: Client,
@@ -29,64 +52,108 @@ impl RedisService {
         Ok(Self { client })
     }
 
-    /// Store a 2FA code for a user with expiration (in seconds)
-    pub async fn store_2fa_code(&self, user_id: i64, code: &str, expiration_seconds: u64) -> AppResult<()> {
-        let mut conn = self.client
-            .get_multiplexed_async_connection()
+    /// Open an email 2FA challenge (code, owner, attempt counter) expiring after
+    /// `expiration_seconds`. Only the latest challenge of a user stays valid.
+    pub async fn store_2fa_challenge(
+        &self,
+        challenge_id: &str,
+        user_id: i64,
+        code: &str,
+        expiration_seconds: u64,
+    ) -> AppResult<()> {
+        let mut conn = self.get_connection().await?;
+        let latest_key = format!("2fa:email:{}", user_id);
+        let previous: Option<String> = conn
+            .get(&latest_key)
             .await
-            .map_err(|e| AppError::Internal(format!("Failed to get Redis connection: {}", e)))?;
-        
-        let key = format!("2fa:email:{}", user_id);
-        conn.set_ex::<_, _, ()>(&key, code, expiration_seconds)
+            .map_err(|e| AppError::Internal(format!("Failed to get 2FA challenge from Redis: {}", e)))?;
+
+        let key = format!("2fa:challenge:{}", challenge_id);
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        if let Some(previous) = previous {
+            pipe.del(format!("2fa:challenge:{}", previous)).ignore();
+        }
+        pipe.hset_multiple(&key, &[("user_id", user_id.to_string()), ("code", code.to_string()), ("attempts", "0".to_string())])
+            .ignore()
+            .expire(&key, expiration_seconds as i64)
+            .ignore()
+            .set_ex(&latest_key, challenge_id, expiration_seconds)
+            .ignore();
+        pipe.query_async::<_, ()>(&mut conn)
             .await
-            .map_err(|e| AppError::Internal(format!("Failed to store 2FA code in Redis: {}", e)))?;
-        
+            .map_err(|e| AppError::Internal(format!("Failed to store 2FA challenge in Redis: {}", e)))?;
         Ok(())
     }
 
-    /// Verify and consume a 2FA code for a user
-    pub async fn verify_2fa_code(&self, user_id: i64, code: &str) -> AppResult<bool> {
-        let mut conn = self.client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to get Redis connection: {}", e)))?;
-        
-        let key = format!("2fa:email:{}", user_id);
-        
-        // Get the stored code
-        let stored_code: Option<String> = conn
-            .get(&key)
+    /// Check a code against a user's email 2FA challenge (`None`: their latest one). Every check
+    /// counts as an attempt; the challenge is consumed by the right code or by the
+    /// `max_attempts`-th wrong one.
+    pub async fn verify_2fa_challenge(
+        &self,
+        user_id: i64,
+        challenge_id: Option<&str>,
+        code: &str,
+        max_attempts: u32,
+    ) -> AppResult<TwoFactorCodeCheck> {
+        let mut conn = self.get_connection().await?;
+        let latest_key = format!("2fa:email:{}", user_id);
+        let challenge_id = match challenge_id {
+            Some(id) => Some(id.to_string()),
+            None => conn
+                .get(&latest_key)
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to get 2FA challenge from Redis: {}", e)))?,
+        };
+        let Some(challenge_id) = challenge_id else {
+            return Ok(TwoFactorCodeCheck::Expired);
+        };
+
+        let key = format!("2fa:challenge:{}", challenge_id);
+        let (owner, stored_code): (Option<i64>, Option<String>) = redis::cmd("HMGET")
+            .arg(&key)
+            .arg("user_id")
+            .arg("code")
+            .query_async(&mut conn)
             .await
-            .map_err(|e| AppError::Internal(format!("Failed to get 2FA code from Redis: {}", e)))?;
-        
-        match stored_code {
-            Some(stored) if stored == code => {
-                // Code matches, delete it (one-time use)
-                let _: () = conn
-                    .del(&key)
-                    .await
-                    .map_err(|e| AppError::Internal(format!("Failed to delete 2FA code from Redis: {}", e)))?;
-                Ok(true)
-            }
-            Some(_) => Ok(false), // Code doesn't match
-            None => Ok(false), // Code not found or expired
+            .map_err(|e| AppError::Internal(format!("Failed to get 2FA challenge from Redis: {}", e)))?;
+        let (Some(owner), Some(stored_code)) = (owner, stored_code) else {
+            return Ok(TwoFactorCodeCheck::Expired);
+        };
+        if owner != user_id {
+            return Ok(TwoFactorCodeCheck::Expired);
         }
-    }
 
-    /// Check if a 2FA code exists for a user (without consuming it)
-    pub async fn has_2fa_code(&self, user_id: i64) -> AppResult<bool> {
-        let mut conn = self.client
-            .get_multiplexed_async_connection()
+        let attempts: u32 = conn
+            .hincr(&key, "attempts", 1)
             .await
-            .map_err(|e| AppError::Internal(format!("Failed to get Redis connection: {}", e)))?;
-        
-        let key = format!("2fa:email:{}", user_id);
-        let exists: bool = conn
-            .exists(&key)
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to check 2FA code in Redis: {}", e)))?;
-        
-        Ok(exists)
+            .map_err(|e| AppError::Internal(format!("Failed to count 2FA attempt in Redis: {}", e)))?;
+        let check = if attempts > max_attempts {
+            TwoFactorCodeCheck::TooManyAttempts
+        } else if stored_code == code {
+            TwoFactorCodeCheck::Valid
+        } else if attempts == max_attempts {
+            TwoFactorCodeCheck::TooManyAttempts
+        } else {
+            TwoFactorCodeCheck::Invalid
+        };
+
+        if check != TwoFactorCodeCheck::Invalid {
+            // One-time use
+            let latest: Option<String> = conn
+                .get(&latest_key)
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to get 2FA challenge from Redis: {}", e)))?;
+            let mut pipe = redis::pipe();
+            pipe.del(&key).ignore();
+            if latest.as_deref() == Some(challenge_id.as_str()) {
+                pipe.del(&latest_key).ignore();
+            }
+            pipe.query_async::<_, ()>(&mut conn)
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to delete 2FA challenge from Redis: {}", e)))?;
+        }
+        Ok(check)
     }
 
     /// Store a trusted device for a user (90 days expiration)
@@ -122,6 +189,82 @@ impl RedisService {
         Ok(exists)
     }
 
+    /// Publish `message` as JSON on a pub/sub channel; returns the number of subscribers it reached
+    pub async fn publish<T: Serialize>(&self, channel: &str, message: &T) -> AppResult<i64> {
+        let payload = serde_json::to_string(message)
+            .map_err(|e| AppError::Internal(format!("Failed to encode pub/sub message: {}", e)))?;
+        let mut conn = self.get_connection().await?;
+        conn.publish(channel, payload)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to publish on Redis channel {}: {}", channel, e)))
+    }
+
+    /// Forward every JSON message published on `channel` (by any instance, this one included) to `tx`.
+    ///
+    /// Runs until the process exits, re-subscribing with a growing delay when Redis goes away.
+    /// Messages that do not decode as `T` are skipped.
+    pub fn relay<T>(&self, channel: &'static str, tx: broadcast::Sender<T>) -> JoinHandle<()>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let client = self.client.clone();
+        tokio::spawn(async move {
+            let mut delay = Duration::from_secs(1);
+            loop {
+                match Self::subscribe(&client, channel).await {
+                    Ok(pubsub) => {
+                        delay = Duration::from_secs(1);
+                        let mut messages = pubsub.into_on_message();
+                        while let Some(msg) = messages.next().await {
+                            let Ok(payload) = msg.get_payload::<String>() else {
+                                continue;
+                            };
+                            match serde_json::from_str::<T>(&payload) {
+                                // No local subscriber is not an error
+                                Ok(message) => drop(tx.send(message)),
+                                Err(e) => tracing::warn!("Ignoring malformed message on {}: {}", channel, e),
+                            }
+                        }
+                        tracing::warn!("Redis subscription to {} closed, re-subscribing", channel);
+                    }
+                    Err(e) => tracing::warn!("Failed to subscribe to Redis channel {}: {}", channel, e),
+                }
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(MAX_RESUBSCRIBE_DELAY);
+            }
+        })
+    }
+
+    async fn subscribe(client: &Client, channel: &str) -> redis::RedisResult<redis::aio::PubSub> {
+        let mut pubsub = client.get_async_pubsub().await?;
+        pubsub.subscribe(channel).await?;
+        Ok(pubsub)
+    }
+
+    /// Number of keys per prefix (text before the first `:`), read with `SCAN`
+    pub async fn key_counts_by_prefix(&self) -> AppResult<BTreeMap<String, u64>> {
+        let mut conn = self.get_connection().await?;
+        let mut counts = BTreeMap::new();
+        let mut cursor: u64 = 0;
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("COUNT")
+                .arg(1000)
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| AppError::Internal(format!("Redis SCAN failed: {}", e)))?;
+            for key in keys {
+                let prefix = key.split(':').next().unwrap_or_default().to_string();
+                *counts.entry(prefix).or_insert(0) += 1;
+            }
+            if next == 0 {
+                return Ok(counts);
+            }
+            cursor = next;
+        }
+    }
+
     /// Get a Redis connection (for advanced operations)
     pub async fn get_connection(&self) -> AppResult<redis::aio::MultiplexedConnection> {
         self.client
@@ -0,0 +1,281 @@
+//! Re-engagement emails (`[reengagement]`)
+//!
+//! Members who stopped borrowing for `inactive_days` and opted in to campaigns receive, at most
+//! once every `resend_after_days`, the titles acquired recently in the media types they borrowed
+//! most (leaving out what they already borrowed). Members without such a title are not emailed.
+//! Loans made after the email measure the effectiveness (`/stats/reengagement`).
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+
+use crate::{
+    dynamic_config::DynamicConfig,
+    email::EmailService,
+    error::{AppError, AppResult},
+    models::{
+        biblio::NewAcquisition,
+        reengagement::{ReengagementPreview, ReengagementReport, ReengagementStats},
+        Language,
+    },
+    repository::ReengagementRepository,
+    services::{
+        audit::{self, AuditService},
+        task_manager::TaskHandle,
+    },
+};
+
+/// Media types (most borrowed first) the suggestions are picked from
+const PREFERRED_MEDIA_TYPES: i64 = 3;
+
+#[derive(Clone)]
+pub struct ReengagementService {
+    repository: Arc<dyn ReengagementRepository>,
+    email: EmailService,
+    audit: AuditService,
+    dynamic_config: Arc<DynamicConfig>,
+}
+
+impl ReengagementService {
+    pub fn new(
+        repository: Arc<dyn ReengagementRepository>,
+        email: EmailService,
+        audit: AuditService,
+        dynamic_config: Arc<DynamicConfig>,
+    ) -> Self {
+        Self { repository, email, audit, dynamic_config }
+    }
+
+    /// Members a run would currently select
+    #[tracing::instrument(skip(self), err)]
+    pub async fn preview(&self) -> AppResult<ReengagementPreview> {
+        let config = self.dynamic_config.read_reengagement();
+        let now = Utc::now();
+        let candidates = self
+            .repository
+            .reengagement_count_candidates(
+                now - Duration::days(config.inactive_days as i64),
+                now - Duration::days(config.resend_after_days as i64),
+            )
+            .await?;
+        Ok(ReengagementPreview { candidates, max_per_run: config.max_per_run })
+    }
+
+    /// Email up to `max_per_run` inactive members (one every `reminders.smtp_throttle_ms`).
+    /// A failed email is recorded and retried by the next run; it does not stop the run.
+    #[tracing::instrument(skip(self, task_handle), err)]
+    pub async fn run(
+        &self,
+        triggered_by: Option<i64>,
+        task_handle: Option<TaskHandle>,
+    ) -> AppResult<ReengagementReport> {
+        let config = self.dynamic_config.read_reengagement();
+        let throttle = std::time::Duration::from_millis(self.dynamic_config.read_reminders().smtp_throttle_ms);
+        let now = Utc::now();
+        let contacted_after = now - Duration::days(config.resend_after_days as i64);
+        let acquired_after = now - Duration::days(config.new_since_days as i64);
+
+        let candidates = self
+            .repository
+            .reengagement_candidates(
+                now - Duration::days(config.inactive_days as i64),
+                contacted_after,
+                config.max_per_run as i64,
+            )
+            .await?;
+
+        let total = candidates.len();
+        let mut report = ReengagementReport { candidates: total as u32, ..Default::default() };
+        for (i, candidate) in candidates.iter().enumerate() {
+            if let Some(handle) = &task_handle {
+                handle.set_progress(i, total, None).await;
+            }
+            let media_types = self
+                .repository
+                .reengagement_preferred_media_types(candidate.user_id, PREFERRED_MEDIA_TYPES)
+                .await?;
+            let titles = if media_types.is_empty() {
+                Vec::new()
+            } else {
+                self.repository
+                    .reengagement_new_titles(
+                        candidate.user_id,
+                        &media_types,
+                        acquired_after,
+                        config.max_titles as i64,
+                    )
+                    .await?
+            };
+            if titles.is_empty() {
+                report.no_suggestions += 1;
+                continue;
+            }
+
+            let biblio_ids: Vec<i64> = titles.iter().map(|t| t.biblio_id).collect();
+            let Some(id) = self
+                .repository
+                .reengagement_claim(candidate, &media_types, &biblio_ids, contacted_after)
+                .await?
+            else {
+                // Emailed by a concurrent run in the meantime
+                continue;
+            };
+
+            let (titles_list, titles_html) = format_titles(&titles);
+            let sent = self
+                .email
+                .send_reengagement(
+                    &candidate.email,
+                    candidate.firstname.as_deref().unwrap_or_default(),
+                    candidate.lastname.as_deref().unwrap_or_default(),
+                    &titles_list,
+                    &titles_html,
+                    candidate.language.as_deref().map(Language::from),
+                )
+                .await;
+            let error = match sent {
+                Ok(()) => {
+                    report.sent += 1;
+                    None
+                }
+                Err(e) => {
+                    tracing::warn!("Re-engagement email to user {} failed: {}", candidate.user_id, e);
+                    report.failed += 1;
+                    Some(e.to_string())
+                }
+            };
+            self.repository.reengagement_finish(id, error).await?;
+            tokio::time::sleep(throttle).await;
+        }
+        if let Some(handle) = &task_handle {
+            handle.set_progress(total, total, None).await;
+        }
+
+        self.audit.log(
+            audit::event::REENGAGEMENT_RUN_COMPLETED,
+            triggered_by,
+            Some("reengagement"),
+            None,
+            None,
+            Some(&report),
+            audit::AuditLogMeta::success(),
+        );
+        Ok(report)
+    }
+
+    /// Daily run of the scheduler
+    #[tracing::instrument(skip(self), err)]
+    pub async fn run_scheduled(&self) -> AppResult<()> {
+        let report = self.run(None, None).await?;
+        tracing::info!(
+            candidates = report.candidates,
+            sent = report.sent,
+            failed = report.failed,
+            "Re-engagement run completed"
+        );
+        Ok(())
+    }
+
+    /// Effectiveness of the emails sent between two days (default: the last year)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn stats(&self, start_date: Option<NaiveDate>, end_date: Option<NaiveDate>) -> AppResult<ReengagementStats> {
+        let end_date = end_date.unwrap_or_else(|| Local::now().date_naive());
+        let start_date = start_date.unwrap_or(end_date - Duration::days(365));
+        if end_date < start_date {
+            return Err(AppError::Validation("endDate must not be before startDate".to_string()));
+        }
+        let conversion_days = self.dynamic_config.read_reengagement().conversion_days;
+        let (by_month, failed) = self
+            .repository
+            .reengagement_stats(
+                local_day_start(start_date),
+                local_day_start(end_date + Duration::days(1)),
+                conversion_days as i32,
+            )
+            .await?;
+
+        let sent: i64 = by_month.iter().map(|m| m.sent).sum();
+        let reactivated: i64 = by_month.iter().map(|m| m.reactivated).sum();
+        Ok(ReengagementStats {
+            start_date,
+            end_date,
+            conversion_days,
+            sent,
+            failed,
+            reactivated,
+            reactivation_rate: if sent > 0 { reactivated as f64 / sent as f64 } else { 0.0 },
+            suggested_loans: by_month.iter().map(|m| m.suggested_loans).sum(),
+            by_month,
+        })
+    }
+}
+
+/// Plain text list and HTML list of the suggested titles
+fn format_titles(titles: &[NewAcquisition]) -> (String, String) {
+    let plain = titles
+        .iter()
+        .map(|t| {
+            let title = t.title.as_deref().unwrap_or("(unknown title)");
+            match t.author.as_deref().filter(|a| !a.is_empty()) {
+                Some(author) => format!("- {} ({})", title, author),
+                None => format!("- {}", title),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let items = titles
+        .iter()
+        .map(|t| {
+            let title = escape_html(t.title.as_deref().unwrap_or("(unknown title)"));
+            match t.author.as_deref().filter(|a| !a.is_empty()) {
+                Some(author) => format!("<li><strong>{}</strong> ({})</li>", title, escape_html(author)),
+                None => format!("<li><strong>{}</strong></li>", title),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    (plain, format!("<ul>{}</ul>", items))
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn local_day_start(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::biblio::MediaType;
+
+    fn title(title: &str, author: Option<&str>) -> NewAcquisition {
+        NewAcquisition {
+            biblio_id: 1,
+            isbn: None,
+            title: Some(title.to_string()),
+            media_type: MediaType::PrintedText,
+            author: author.map(str::to_string),
+            abstract_: None,
+            acquired_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn titles_are_listed_with_their_author() {
+        let (plain, html) = format_titles(&[title("Dune", Some("Herbert, Frank")), title("Tom & Jerry", None)]);
+        assert_eq!(plain, "- Dune (Herbert, Frank)\n- Tom & Jerry");
+        assert_eq!(
+            html,
+            "<ul><li><strong>Dune</strong> (Herbert, Frank)</li>\n<li><strong>Tom &amp; Jerry</strong></li></ul>"
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Relator vocabulary service
+
+use std::sync::Arc;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{
+        author::Function,
+        relator::{CreateRelator, RelatorDefinition, UpdateRelator},
+    },
+    repository::RelatorsRepository,
+};
+
+#[derive(Clone)]
+pub struct RelatorsService {
+    repository: Arc<dyn RelatorsRepository>,
+}
+
+impl RelatorsService {
+    pub fn new(repository: Arc<dyn RelatorsRepository>) -> Self {
+        Self { repository }
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self) -> AppResult<Vec<RelatorDefinition>> {
+        self.repository.relators_list().await
+    }
+
+    pub async fn get(&self, code: &str) -> AppResult<RelatorDefinition> {
+        self.repository.relators_get(code).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create(&self, data: &CreateRelator) -> AppResult<RelatorDefinition> {
+        validate_code(&data.code)?;
+        validate_marc_code(Some(&data.marc_code))?;
+        validate_unimarc_code(data.unimarc_code.as_deref())?;
+        validate_label(Some(&data.label))?;
+        validate_aliases(&data.aliases)?;
+        match self.repository.relators_get(&data.code).await {
+            Ok(_) => return Err(AppError::Conflict(format!("Relator {} already exists", data.code))),
+            Err(AppError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+        self.repository.relators_create(data).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update(&self, code: &str, data: &UpdateRelator) -> AppResult<RelatorDefinition> {
+        validate_marc_code(data.marc_code.as_deref())?;
+        validate_unimarc_code(data.unimarc_code.as_deref())?;
+        validate_label(data.label.as_deref())?;
+        validate_aliases(data.aliases.as_deref().unwrap_or_default())?;
+        self.repository.relators_update(code, data).await
+    }
+
+    /// Delete a relator added to the vocabulary that no author credit uses anymore
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete(&self, code: &str) -> AppResult<()> {
+        if !matches!(Function::from(code), Function::Other(_)) {
+            return Err(AppError::Conflict(format!(
+                "Relator {} is built in and cannot be deleted",
+                code
+            )));
+        }
+        let in_use = self.repository.relators_count_credits(code).await?;
+        if in_use > 0 {
+            return Err(AppError::Conflict(format!(
+                "Relator {} is used by {} author credit(s)",
+                code, in_use
+            )));
+        }
+        self.repository.relators_delete(code).await
+    }
+}
+
+/// camelCase identifier, like the built-in codes
+fn validate_code(code: &str) -> AppResult<()> {
+    let valid = (2..=50).contains(&code.len())
+        && code.starts_with(|c: char| c.is_ascii_lowercase())
+        && code.chars().all(|c| c.is_ascii_alphanumeric());
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "code must be a camelCase identifier of 2 to 50 letters or digits (got {})",
+            code
+        )));
+    }
+    if !matches!(Function::from(code), Function::Other(_)) {
+        return Err(AppError::Conflict(format!("Relator {} already exists", code)));
+    }
+    Ok(())
+}
+
+/// Three lowercase letters (MARC 21 relator code)
+fn validate_marc_code(code: Option<&str>) -> AppResult<()> {
+    let Some(code) = code else {
+        return Ok(());
+    };
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_lowercase()) {
+        return Err(AppError::Validation(format!(
+            "marcCode must be three lowercase letters (got {})",
+            code
+        )));
+    }
+    Ok(())
+}
+
+/// Three digits (UNIMARC relator code); empty clears it
+fn validate_unimarc_code(code: Option<&str>) -> AppResult<()> {
+    let Some(code) = code.filter(|c| !c.is_empty()) else {
+        return Ok(());
+    };
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_digit()) {
+        return Err(AppError::Validation(format!(
+            "unimarcCode must be three digits (got {})",
+            code
+        )));
+    }
+    Ok(())
+}
+
+fn validate_label(label: Option<&str>) -> AppResult<()> {
+    let Some(label) = label.map(str::trim) else {
+        return Ok(());
+    };
+    if label.is_empty() || label.chars().count() > 100 {
+        return Err(AppError::Validation(
+            "label must be between 1 and 100 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_aliases(aliases: &[String]) -> AppResult<()> {
+    if aliases.len() > 50 || aliases.iter().any(|a| a.trim().chars().count() > 100) {
+        return Err(AppError::Validation(
+            "aliases must be at most 50 terms of at most 100 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_format() {
+        assert!(validate_code("director").is_ok());
+        assert!(validate_code("soundEngineer").is_ok());
+        assert!(validate_code("Director").is_err());
+        assert!(validate_code("sound-engineer").is_err());
+        assert!(validate_code("x").is_err());
+        assert!(matches!(validate_code("illustrator"), Err(AppError::Conflict(_))));
+        assert!(matches!(validate_code("trl"), Err(AppError::Conflict(_))));
+    }
+
+    #[test]
+    fn marc_codes() {
+        assert!(validate_marc_code(Some("drt")).is_ok());
+        assert!(validate_marc_code(Some("DRT")).is_err());
+        assert!(validate_marc_code(Some("dr")).is_err());
+        assert!(validate_unimarc_code(Some("300")).is_ok());
+        assert!(validate_unimarc_code(Some("")).is_ok());
+        assert!(validate_unimarc_code(Some("30a")).is_err());
+    }
+}
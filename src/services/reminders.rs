@@ -1,24 +1,34 @@
-//! Overdue loan reminder service.
+//! Overdue and due-soon loan reminder service.
 //!
 //! Groups overdue loans by user, sends a single email per user listing all their overdue items,
-//! updates reminder tracking columns, and records audit events.
+//! updates reminder tracking columns, and records audit events. Loans due in one of the
+//! `reminders.due_soon_days` lead times are sent the same way, as one digest per patron.
+//! Loans reaching `reminders.escalation_after` reminders are escalated to staff as one in-app
+//! notification.
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::{
     dynamic_config::DynamicConfig,
     error::AppResult,
-    models::Language,
-    repository::LoansRepository,
+    models::{
+        notification::{NewNotification, NotificationKind},
+        Language,
+    },
+    repository::{
+        loans::{OverdueLoanRow, ShiftedLoanRow},
+        LoansRepository,
+    },
     services::{
         audit::{self, AuditService},
         email::EmailService,
         email_templates,
+        notifications::NotificationsService,
     },
 };
 
@@ -65,7 +75,10 @@ pub struct OverdueLoanInfo {
     pub firstname: Option<String>,
     pub lastname: Option<String>,
     pub user_email: Option<String>,
-    pub biblio_id: i64,
+    /// `None` for equipment loans
+    pub biblio_id: Option<i64>,
+    /// Set for equipment loans (`title` is then the equipment name)
+    pub equipment_id: Option<i64>,
     pub title: Option<String>,
     pub authors: Option<String>,
     pub item_barcode: Option<String>,
@@ -75,6 +88,24 @@ pub struct OverdueLoanInfo {
     pub reminder_count: i32,
 }
 
+/// Result of the closure due-date extension job (`closureDueDateExtension` task)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClosureExtensionReport {
+    pub closure_id: i64,
+    pub closure_date: NaiveDate,
+    /// First open day the affected loans were moved to
+    pub new_due_date: NaiveDate,
+    /// Number of loans whose due date was moved
+    pub loans_extended: u32,
+    /// Number of patrons emailed
+    pub patrons_notified: u32,
+    /// Patrons not emailed (no email address)
+    pub skipped: u32,
+    /// Email errors (due dates were still moved for these patrons)
+    pub errors: Vec<ReminderError>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct OverdueLoansPage {
@@ -90,6 +121,7 @@ pub struct RemindersService {
     email: EmailService,
     audit: AuditService,
     dynamic_config: Arc<DynamicConfig>,
+    notifications: NotificationsService,
 }
 
 impl RemindersService {
@@ -98,8 +130,9 @@ impl RemindersService {
         email: EmailService,
         audit: AuditService,
         dynamic_config: Arc<DynamicConfig>,
+        notifications: NotificationsService,
     ) -> Self {
-        Self { repository, email, audit, dynamic_config }
+        Self { repository, email, audit, dynamic_config, notifications }
     }
 
     /// Get paginated overdue loans for the admin dashboard.
@@ -118,6 +151,7 @@ impl RemindersService {
                 lastname: r.lastname,
                 user_email: r.user_email,
                 biblio_id: r.biblio_id,
+                equipment_id: r.equipment_id,
                 title: r.title,
                 authors: r.authors,
                 item_barcode: r.item_barcode,
@@ -339,6 +373,13 @@ impl RemindersService {
             self.repository
                 .loans_update_reminder_sent(&all_reminded_ids)
                 .await?;
+
+            let escalated = escalated_loans(&overdue_rows, &all_reminded_ids, reminders_cfg.escalation_after);
+            if !escalated.is_empty() {
+                self.notifications
+                    .notify_staff(&escalation_notification(&escalated, reminders_cfg.escalation_after))
+                    .await;
+            }
         }
 
         let emails_sent = details.len() as u32;
@@ -352,4 +393,427 @@ impl RemindersService {
             errors,
         })
     }
+
+    /// Send the due-soon digest: one email per patron listing every loan due in one of the
+    /// `reminders.due_soon_days` lead times (`due_soon_reminder` template).
+    /// If `dry_run` is true, builds the report but does NOT send emails or update the DB.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn send_due_soon_reminders(
+        &self,
+        dry_run: bool,
+        triggered_by: Option<i64>,
+        client_ip: Option<String>,
+    ) -> AppResult<ReminderReport> {
+        let reminders_cfg = self.dynamic_config.read_reminders();
+        let rows = self.repository.loans_get_due_soon(&reminders_cfg.due_soon_days).await?;
+
+        // Rows are ordered by user
+        let mut by_user: Vec<(i64, Vec<&OverdueLoanRow>)> = Vec::new();
+        for row in &rows {
+            match by_user.last_mut() {
+                Some((user_id, loans)) if *user_id == row.user_id => loans.push(row),
+                _ => by_user.push((row.user_id, vec![row])),
+            }
+        }
+
+        let mut details = Vec::new();
+        let mut errors = Vec::new();
+        let mut notified_ids: Vec<i64> = Vec::new();
+
+        for (user_id, loans) in &by_user {
+            let first = loans[0];
+            let email_addr = match first.user_email.as_deref() {
+                Some(e) if !e.is_empty() => e.to_string(),
+                _ => continue,
+            };
+            let detail = ReminderDetail {
+                user_id: *user_id,
+                email: email_addr.clone(),
+                firstname: first.firstname.clone(),
+                lastname: first.lastname.clone(),
+                loan_count: loans.len(),
+            };
+            if dry_run {
+                details.push(detail);
+                continue;
+            }
+
+            let lang = first.user_language.as_deref().map(Language::from);
+            let due_date = |l: &OverdueLoanRow| {
+                l.expiry_at
+                    .map(|d| d.format("%d/%m/%Y").to_string())
+                    .unwrap_or_else(|| "N/A".to_string())
+            };
+            let loans_list = loans
+                .iter()
+                .map(|l| {
+                    format!(
+                        "- {} ({}) — due: {}",
+                        l.title.as_deref().unwrap_or("(unknown title)"),
+                        l.authors.as_deref().unwrap_or(""),
+                        due_date(l)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let table_rows = loans
+                .iter()
+                .map(|l| {
+                    format!(
+                        "<tr><td style=\"padding:4px 8px;border:1px solid #ccc\">{}</td>\
+                         <td style=\"padding:4px 8px;border:1px solid #ccc\">{}</td>\
+                         <td style=\"padding:4px 8px;border:1px solid #ccc\"><strong>{}</strong></td></tr>",
+                        l.title.as_deref().unwrap_or("(unknown title)"),
+                        l.authors.as_deref().unwrap_or(""),
+                        due_date(l)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let loans_table_html = format!(
+                "<table style=\"border-collapse:collapse;width:100%\">\
+                 <thead><tr>\
+                 <th style=\"padding:4px 8px;border:1px solid #ccc;background:#f5f5f5\">Title</th>\
+                 <th style=\"padding:4px 8px;border:1px solid #ccc;background:#f5f5f5\">Author(s)</th>\
+                 <th style=\"padding:4px 8px;border:1px solid #ccc;background:#f5f5f5\">Due date</th>\
+                 </tr></thead><tbody>{}</tbody></table>",
+                table_rows
+            );
+
+            let template = match self.email.load_template("due_soon_reminder", lang).await {
+                Ok(t) => t,
+                Err(e) => {
+                    errors.push(ReminderError {
+                        user_id: *user_id,
+                        email: email_addr,
+                        error_message: format!("Template load error: {}", e),
+                    });
+                    continue;
+                }
+            };
+            let vars: Vec<(&str, &str)> = vec![
+                ("firstname", first.firstname.as_deref().unwrap_or("")),
+                ("lastname", first.lastname.as_deref().unwrap_or("")),
+                ("loans_list", &loans_list),
+                ("loans_table_html", &loans_table_html),
+            ];
+            let (subject, body_plain, body_html) = email_templates::substitute(&template, &vars);
+            let loan_ids: Vec<i64> = loans.iter().map(|l| l.loan_id).collect();
+
+            let result = self
+                .email
+                .send_email_with_html(&email_addr, &subject, &body_plain, &body_html)
+                .await;
+            let meta = match &result {
+                Ok(()) => audit::AuditLogMeta::success(),
+                Err(e) => audit::AuditLogMeta::from_app_error(e),
+            };
+            self.audit.log(
+                audit::event::EMAIL_DUE_SOON_REMINDER_SENT,
+                triggered_by,
+                Some("user"),
+                Some(*user_id),
+                client_ip.clone(),
+                Some(serde_json::json!({
+                    "email": email_addr,
+                    "loan_ids": loan_ids,
+                    "loan_count": loans.len(),
+                })),
+                meta,
+            );
+            match result {
+                Ok(()) => {
+                    notified_ids.extend(&loan_ids);
+                    details.push(detail);
+                }
+                Err(e) => errors.push(ReminderError {
+                    user_id: *user_id,
+                    email: email_addr,
+                    error_message: e.to_string(),
+                }),
+            }
+
+            if reminders_cfg.smtp_throttle_ms > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(reminders_cfg.smtp_throttle_ms)).await;
+            }
+        }
+
+        if !dry_run && !notified_ids.is_empty() {
+            self.repository.loans_update_due_soon_notified(&notified_ids).await?;
+        }
+
+        let loans_reminded = if dry_run {
+            details.iter().map(|d| d.loan_count as u32).sum()
+        } else {
+            notified_ids.len() as u32
+        };
+        Ok(ReminderReport {
+            dry_run,
+            emails_sent: details.len() as u32,
+            loans_reminded,
+            details,
+            errors,
+        })
+    }
+
+    /// Move loans due on a closure day to `new_due_date` and email each affected patron once.
+    ///
+    /// Due dates are moved even when an email cannot be sent; failures are listed in the report.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn extend_due_dates_for_closure(
+        &self,
+        closure_id: i64,
+        closure_date: NaiveDate,
+        closure_reason: Option<&str>,
+        new_due_date: NaiveDate,
+        triggered_by: Option<i64>,
+    ) -> AppResult<ClosureExtensionReport> {
+        let shifted = self
+            .repository
+            .loans_shift_due_dates(closure_date, new_due_date)
+            .await?;
+
+        self.audit.log(
+            audit::event::SCHEDULE_CLOSURE_DUE_DATES_EXTENDED,
+            triggered_by,
+            Some("schedule_closure"),
+            Some(closure_id),
+            None,
+            Some(serde_json::json!({
+                "closure_date": closure_date,
+                "new_due_date": new_due_date,
+                "loan_ids": shifted.iter().map(|l| l.loan_id).collect::<Vec<_>>(),
+            })),
+            audit::AuditLogMeta::success(),
+        );
+
+        let mut by_user: Vec<(i64, Vec<&ShiftedLoanRow>)> = Vec::new();
+        for loan in &shifted {
+            match by_user.last_mut() {
+                Some((user_id, loans)) if *user_id == loan.user_id => loans.push(loan),
+                _ => by_user.push((loan.user_id, vec![loan])),
+            }
+        }
+
+        let reminders_cfg = self.dynamic_config.read_reminders();
+        let closure_date_str = closure_date.format("%d/%m/%Y").to_string();
+        let reason_suffix = closure_reason
+            .filter(|r| !r.trim().is_empty())
+            .map(|r| format!(" ({})", r.trim()))
+            .unwrap_or_default();
+
+        let mut patrons_notified: u32 = 0;
+        let mut skipped: u32 = 0;
+        let mut errors: Vec<ReminderError> = Vec::new();
+
+        for (user_id, loans) in &by_user {
+            let first = loans[0];
+            let email_addr = match first.user_email.as_deref() {
+                Some(e) if !e.is_empty() => e.to_string(),
+                _ => {
+                    skipped += 1;
+                    continue;
+                }
+            };
+            let lang = first.user_language.as_deref().map(Language::from);
+
+            let loans_list = loans
+                .iter()
+                .map(|l| {
+                    format!(
+                        "- {} — due: {} → {}",
+                        l.title.as_deref().unwrap_or("(unknown title)"),
+                        l.previous_expiry_at.format("%d/%m/%Y"),
+                        l.expiry_at.format("%d/%m/%Y")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let table_rows = loans
+                .iter()
+                .map(|l| {
+                    format!(
+                        "<tr><td style=\"padding:4px 8px;border:1px solid #ccc\">{}</td>\
+                         <td style=\"padding:4px 8px;border:1px solid #ccc\"><s>{}</s></td>\
+                         <td style=\"padding:4px 8px;border:1px solid #ccc\"><strong>{}</strong></td></tr>",
+                        l.title.as_deref().unwrap_or("(unknown title)"),
+                        l.previous_expiry_at.format("%d/%m/%Y"),
+                        l.expiry_at.format("%d/%m/%Y")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let loans_table_html = format!(
+                "<table style=\"border-collapse:collapse;width:100%\">\
+                 <thead><tr>\
+                 <th style=\"padding:4px 8px;border:1px solid #ccc;background:#f5f5f5\">Title</th>\
+                 <th style=\"padding:4px 8px;border:1px solid #ccc;background:#f5f5f5\">Previous due date</th>\
+                 <th style=\"padding:4px 8px;border:1px solid #ccc;background:#f5f5f5\">New due date</th>\
+                 </tr></thead><tbody>{}</tbody></table>",
+                table_rows
+            );
+
+            let template = match self.email.load_template("due_date_extended", lang).await {
+                Ok(t) => t,
+                Err(e) => {
+                    errors.push(ReminderError {
+                        user_id: *user_id,
+                        email: email_addr,
+                        error_message: format!("Template load error: {}", e),
+                    });
+                    continue;
+                }
+            };
+            let vars: Vec<(&str, &str)> = vec![
+                ("firstname", first.firstname.as_deref().unwrap_or("")),
+                ("lastname", first.lastname.as_deref().unwrap_or("")),
+                ("closure_date", &closure_date_str),
+                ("reason_suffix", &reason_suffix),
+                ("loans_list", &loans_list),
+                ("loans_table_html", &loans_table_html),
+            ];
+            let (subject, body_plain, body_html) = email_templates::substitute(&template, &vars);
+            let loan_ids: Vec<i64> = loans.iter().map(|l| l.loan_id).collect();
+
+            let result = self
+                .email
+                .send_email_with_html(&email_addr, &subject, &body_plain, &body_html)
+                .await;
+            let meta = match &result {
+                Ok(()) => audit::AuditLogMeta::success(),
+                Err(e) => audit::AuditLogMeta::from_app_error(e),
+            };
+            self.audit.log(
+                audit::event::EMAIL_DUE_DATE_EXTENDED_SENT,
+                triggered_by,
+                Some("user"),
+                Some(*user_id),
+                None,
+                Some(serde_json::json!({
+                    "email": email_addr,
+                    "closure_id": closure_id,
+                    "loan_ids": loan_ids,
+                })),
+                meta,
+            );
+            match result {
+                Ok(()) => patrons_notified += 1,
+                Err(e) => errors.push(ReminderError {
+                    user_id: *user_id,
+                    email: email_addr,
+                    error_message: e.to_string(),
+                }),
+            }
+
+            if reminders_cfg.smtp_throttle_ms > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(reminders_cfg.smtp_throttle_ms)).await;
+            }
+        }
+
+        Ok(ClosureExtensionReport {
+            closure_id,
+            closure_date,
+            new_due_date,
+            loans_extended: shifted.len() as u32,
+            patrons_notified,
+            skipped,
+            errors,
+        })
+    }
+}
+
+/// Most loans listed in the body of an escalation notification (all ids stay in `data`)
+const MAX_ESCALATION_LINES: usize = 50;
+
+/// Loans just reminded whose reminder count reached `escalation_after` with this reminder
+/// (`escalation_after` 0: escalation disabled)
+fn escalated_loans<'a>(
+    rows: &'a [OverdueLoanRow],
+    reminded_ids: &[i64],
+    escalation_after: u32,
+) -> Vec<&'a OverdueLoanRow> {
+    if escalation_after == 0 {
+        return vec![];
+    }
+    rows.iter()
+        .filter(|r| r.reminder_count + 1 == escalation_after as i32 && reminded_ids.contains(&r.loan_id))
+        .collect()
+}
+
+/// Staff digest of the loans escalated by one reminder run.
+///
+/// Patrons are only referenced by id (`data.userIds`): free text is not masked in demo mode.
+fn escalation_notification(loans: &[&OverdueLoanRow], escalation_after: u32) -> NewNotification {
+    let mut lines: Vec<String> = loans
+        .iter()
+        .take(MAX_ESCALATION_LINES)
+        .map(|l| {
+            let due_date = l
+                .expiry_at
+                .map(|d| d.format("%d/%m/%Y").to_string())
+                .unwrap_or_else(|| "N/A".to_string());
+            format!(
+                "- {} [{}] — due: {}",
+                l.title.as_deref().unwrap_or("(unknown title)"),
+                l.item_barcode.as_deref().unwrap_or("-"),
+                due_date
+            )
+        })
+        .collect();
+    if loans.len() > MAX_ESCALATION_LINES {
+        lines.push(format!("… and {} more", loans.len() - MAX_ESCALATION_LINES));
+    }
+    let loan_ids: Vec<String> = loans.iter().map(|l| l.loan_id.to_string()).collect();
+    let user_ids: std::collections::BTreeSet<String> = loans.iter().map(|l| l.user_id.to_string()).collect();
+    NewNotification {
+        kind: NotificationKind::OverdueEscalation,
+        title: format!("{} overdue loan(s) reached {} reminders", loans.len(), escalation_after),
+        body: Some(lines.join("\n")),
+        entity_type: None,
+        entity_id: None,
+        data: Some(serde_json::json!({
+            "reminders": escalation_after,
+            "loanIds": loan_ids,
+            "userIds": user_ids,
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(loan_id: i64, reminder_count: i32) -> OverdueLoanRow {
+        OverdueLoanRow {
+            loan_id,
+            user_id: 7,
+            loan_date: Utc::now(),
+            expiry_at: None,
+            last_reminder_sent_at: None,
+            reminder_count,
+            firstname: Some("Ada".to_string()),
+            lastname: None,
+            user_email: Some("ada@example.org".to_string()),
+            user_language: None,
+            biblio_id: Some(1),
+            equipment_id: None,
+            title: Some("Dune".to_string()),
+            authors: None,
+            item_barcode: Some("B0001".to_string()),
+        }
+    }
+
+    #[test]
+    fn loans_escalate_once_when_reaching_the_threshold() {
+        let rows = vec![row(1, 1), row(2, 2), row(3, 3), row(4, 2)];
+        // Loan 4 was not reminded (no email address, send failure, …)
+        let escalated = escalated_loans(&rows, &[1, 2, 3], 3);
+        assert_eq!(escalated.iter().map(|r| r.loan_id).collect::<Vec<_>>(), vec![2]);
+        assert!(escalated_loans(&rows, &[1, 2, 3], 0).is_empty());
+
+        let notification = escalation_notification(&escalated, 3);
+        assert_eq!(notification.kind, NotificationKind::OverdueEscalation);
+        assert_eq!(notification.body.as_deref(), Some("- Dune [B0001] — due: N/A"));
+        assert_eq!(notification.data.unwrap()["loanIds"], serde_json::json!(["2"]));
+    }
 }
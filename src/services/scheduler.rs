@@ -1,9 +1,17 @@
-//! Background scheduler for overdue reminder emails, hold expiry, and audit log cleanup.
+//! Background scheduler for overdue reminder emails, hold expiry, audit log and artifact cleanup.
 //!
 //! Spawned at startup via `tokio::spawn`. Periodic tasks run concurrently:
-//! - Reminder sending at the configured time of day
-//! - Ready-hold expiry (missed pickup) at 02:00 daily
-//! - Audit log cleanup at 03:00 daily
+//! - Reminder sending at the configured time of day (overdue reminders, then the due-soon digest)
+//! - Ready-hold and pickup locker expiry (missed pickup) at 02:00 daily
+//! - Retention at 03:00 daily: audit log cleanup, anonymization of deleted users whose grace
+//!   period lapsed, expired catalog snapshots, returned loans due for archival
+//! - Expired artifact removal every hour
+//! - Purchase suggestion availability (patrons emailed once the title has a copy) every hour
+//! - Union catalog harvests, checked every minute against each source's schedule
+//! - Data warehouse exports, checked every minute against each target's schedule
+//! - End-of-day job at `eod.run_time` (when `eod.enabled`)
+//! - Re-engagement emails to inactive members at `reengagement.run_time` (when `reengagement.enabled`)
+//! - OPAC sitemap regeneration at `sitemap.run_time` (when `sitemap.enabled`)
 
 use std::sync::Arc;
 
@@ -14,20 +22,43 @@ use tokio::time::Duration;
 use crate::{
     dynamic_config::DynamicConfig,
     services::{
+        artifacts::ArtifactsService,
         audit,
         audit::AuditService,
+        eod::EodService,
+        harvest::HarvestService,
+        loans::LoansService,
+        lockers::LockersService,
+        purchase_suggestions::PurchaseSuggestionsService,
+        reengagement::ReengagementService,
+        sitemap::SitemapService,
+        users::UsersService,
         reminders::RemindersService,
         holds::HoldsService,
+        snapshots::SnapshotsService,
+        warehouse::WarehouseService,
     },
 };
 
 /// Start the background scheduler. Returns a `Notify` handle that can be used
 /// to wake up the reminder task early (e.g. after a config change).
+#[allow(clippy::too_many_arguments)]
 pub fn spawn(
     dynamic_config: Arc<DynamicConfig>,
     reminders_service: RemindersService,
     audit_service: AuditService,
     holds_service: HoldsService,
+    lockers_service: LockersService,
+    artifacts_service: ArtifactsService,
+    harvest_service: HarvestService,
+    users_service: UsersService,
+    warehouse_service: WarehouseService,
+    snapshots_service: SnapshotsService,
+    purchase_suggestions_service: PurchaseSuggestionsService,
+    eod_service: EodService,
+    loans_service: LoansService,
+    reengagement_service: ReengagementService,
+    sitemap_service: SitemapService,
 ) -> Arc<Notify> {
     let notify = Arc::new(Notify::new());
 
@@ -104,10 +135,50 @@ pub fn spawn(
                     );
                 }
             }
+
+            if cfg.due_soon_days.is_empty() {
+                continue;
+            }
+            tracing::info!("Running scheduled due-soon digest batch");
+            match rem_svc.send_due_soon_reminders(false, None, None).await {
+                Ok(report) => {
+                    tracing::info!(
+                        "Due-soon batch completed: {} emails sent, {} loans listed, {} errors",
+                        report.emails_sent,
+                        report.loans_reminded,
+                        report.errors.len()
+                    );
+                    audit_rem.log(
+                        audit::event::SYSTEM_DUE_SOON_BATCH_COMPLETED,
+                        None,
+                        None,
+                        None,
+                        None,
+                        Some(serde_json::json!({
+                            "emails_sent": report.emails_sent,
+                            "loans_reminded": report.loans_reminded,
+                            "errors": report.errors.len(),
+                        })),
+                        audit::AuditLogMeta::success(),
+                    );
+                }
+                Err(e) => {
+                    tracing::error!("Due-soon batch failed: {}", e);
+                    audit_rem.log(
+                        audit::event::SYSTEM_DUE_SOON_BATCH_COMPLETED,
+                        None,
+                        None,
+                        None,
+                        None,
+                        Some(serde_json::json!({ "error": e.to_string() })),
+                        audit::AuditLogMeta::from_app_error(&e),
+                    );
+                }
+            }
         }
     });
 
-    // Expire `ready` holds past `expires_at` (runs daily at 02:00 local)
+    // Expire `ready` and `in_locker` holds past `expires_at` (runs daily at 02:00 local)
     let hold_exp = holds_service.clone();
     tokio::spawn(async move {
         tracing::info!("Hold expiry scheduler started");
@@ -126,15 +197,64 @@ pub fn spawn(
                     tracing::error!("Hold expiry batch failed: {}", e);
                 }
             }
+
+            match lockers_service.expire_overdue().await {
+                Ok(n) if n > 0 => {
+                    tracing::info!("Expired {} uncollected locker hold(s)", n);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!("Locker hold expiry failed: {}", e);
+                }
+            }
+        }
+    });
+
+    // Remove expired artifacts (row + blob), hourly
+    tokio::spawn(async move {
+        tracing::info!("Artifact cleanup scheduler started");
+        let mut interval = tokio::time::interval(Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            match artifacts_service.cleanup_expired().await {
+                Ok(n) if n > 0 => {
+                    tracing::info!("Removed {} expired artifact(s)", n);
+                }
+                Ok(_) => {
+                    tracing::debug!("Artifact cleanup run: nothing to remove");
+                }
+                Err(e) => {
+                    tracing::error!("Artifact cleanup failed: {}", e);
+                }
+            }
+        }
+    });
+
+    // Notify patrons whose suggested titles now have a copy in the catalog, hourly
+    tokio::spawn(async move {
+        tracing::info!("Purchase suggestion scheduler started");
+        let mut interval = tokio::time::interval(Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            match purchase_suggestions_service.notify_available().await {
+                Ok(n) if n > 0 => {
+                    tracing::info!("Notified {} purchase suggestion(s) now available", n);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!("Purchase suggestion notification failed: {}", e);
+                }
+            }
         }
     });
 
-    // Audit log cleanup task (runs daily at 03:00)
+    // Retention task (runs daily at 03:00): audit log cleanup, deferred user anonymization,
+    // expired catalog snapshots, then the loans archival policy
     let dc_audit = dynamic_config.clone();
     let audit_cleanup = audit_service.clone();
 
     tokio::spawn(async move {
-        tracing::info!("Audit cleanup scheduler started");
+        tracing::info!("Retention scheduler started");
         loop {
             let sleep_dur = duration_until_next_send("03:00");
             tokio::time::sleep(sleep_dur).await;
@@ -171,6 +291,134 @@ pub fn spawn(
                     );
                 }
             }
+
+            match users_service.purge_pending_deletions().await {
+                Ok(ids) => {
+                    if !ids.is_empty() {
+                        tracing::info!("User retention: {} accounts anonymized", ids.len());
+                    }
+                    for id in ids {
+                        audit_cleanup.log(
+                            audit::event::USER_ANONYMIZED,
+                            None,
+                            Some("user"),
+                            Some(id),
+                            None,
+                            None::<serde_json::Value>,
+                            audit::AuditLogMeta::success(),
+                        );
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("User retention failed: {}", e);
+                    audit_cleanup.log(
+                        audit::event::USER_ANONYMIZED,
+                        None,
+                        Some("user"),
+                        None,
+                        None,
+                        None::<serde_json::Value>,
+                        audit::AuditLogMeta::from_app_error(&e),
+                    );
+                }
+            }
+
+            match snapshots_service.cleanup().await {
+                Ok(n) if n > 0 => tracing::info!("Snapshot retention: {} snapshot(s) deleted", n),
+                Ok(_) => {}
+                Err(e) => tracing::error!("Snapshot retention failed: {}", e),
+            }
+
+            match loans_service.archive_returned().await {
+                Ok(n) if n > 0 => tracing::info!("Loans archival: {} returned loan(s) archived", n),
+                Ok(_) => {}
+                Err(e) => tracing::error!("Loans archival failed: {}", e),
+            }
+        }
+    });
+
+    // Union catalog harvests (sources claim their run in DB, so several instances may poll)
+    tokio::spawn(async move {
+        tracing::info!("Harvest scheduler started");
+        loop {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            if let Err(e) = harvest_service.run_due().await {
+                tracing::error!("Harvest scheduling failed: {}", e);
+            }
+        }
+    });
+
+    // Data warehouse exports (same claim in DB as the harvests); never in demo mode, where
+    // they would copy unmasked patron data out of the server
+    let demo = dynamic_config.file_config.demo.enabled;
+    tokio::spawn(async move {
+        if demo {
+            tracing::info!("Warehouse export scheduler disabled in demo mode");
+            return;
+        }
+        tracing::info!("Warehouse export scheduler started");
+        loop {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            if let Err(e) = warehouse_service.run_due().await {
+                tracing::error!("Warehouse export scheduling failed: {}", e);
+            }
+        }
+    });
+
+    // End-of-day job (runs claim their slot in DB, so several instances may poll).
+    // Waits at most a minute at a time so changes to `[eod]` are picked up.
+    let dc_eod = dynamic_config.clone();
+    tokio::spawn(async move {
+        tracing::info!("End-of-day scheduler started");
+        loop {
+            let cfg = dc_eod.read_eod();
+            let sleep_dur = duration_until_next_send(&cfg.run_time);
+            if !cfg.enabled || sleep_dur > Duration::from_secs(60) {
+                tokio::time::sleep(Duration::from_secs(60).min(sleep_dur)).await;
+                continue;
+            }
+            tokio::time::sleep(sleep_dur).await;
+            if let Err(e) = eod_service.run_scheduled().await {
+                tracing::error!("End-of-day run failed: {}", e);
+            }
+        }
+    });
+
+    // Re-engagement emails (members are claimed in DB, so several instances may run).
+    // Waits at most a minute at a time so changes to `[reengagement]` are picked up.
+    let dc_reengagement = dynamic_config.clone();
+    tokio::spawn(async move {
+        tracing::info!("Re-engagement scheduler started");
+        loop {
+            let cfg = dc_reengagement.read_reengagement();
+            let sleep_dur = duration_until_next_send(&cfg.run_time);
+            if !cfg.enabled || sleep_dur > Duration::from_secs(60) {
+                tokio::time::sleep(Duration::from_secs(60).min(sleep_dur)).await;
+                continue;
+            }
+            tokio::time::sleep(sleep_dur).await;
+            if let Err(e) = reengagement_service.run_scheduled().await {
+                tracing::error!("Re-engagement run failed: {}", e);
+            }
+        }
+    });
+
+    // OPAC sitemap (a run publishes its files at once, so concurrent instances are harmless).
+    // Waits at most a minute at a time so changes to `[sitemap]` are picked up.
+    let dc_sitemap = dynamic_config.clone();
+    tokio::spawn(async move {
+        tracing::info!("Sitemap scheduler started");
+        loop {
+            let cfg = dc_sitemap.read_sitemap();
+            let sleep_dur = duration_until_next_send(&cfg.run_time);
+            if !cfg.enabled || sleep_dur > Duration::from_secs(60) {
+                tokio::time::sleep(Duration::from_secs(60).min(sleep_dur)).await;
+                continue;
+            }
+            tokio::time::sleep(sleep_dur).await;
+            if let Err(e) = sitemap_service.run_scheduled().await {
+                tracing::error!("Sitemap generation failed: {}", e);
+            }
         }
     });
 
@@ -1,18 +1,22 @@
 //! Schedules service (periods, slots, closures)
 
-use chrono::NaiveDate;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
 
 use std::sync::Arc;
 
 use crate::{
     error::AppResult,
     models::schedule::{
-        CreateScheduleClosure, CreateSchedulePeriod, CreateScheduleSlot,
-        ScheduleClosure, SchedulePeriod, ScheduleSlot, UpdateSchedulePeriod,
+        CreateScheduleClosure, CreateSchedulePeriod, CreateScheduleSlot, DaySchedule,
+        OpeningHours, ScheduleClosure, SchedulePeriod, ScheduleSlot, ScheduleStatus,
+        UpdateSchedulePeriod, WeekSchedule,
     },
     repository::SchedulesRepository,
 };
 
+/// How far ahead `status` looks for the next opening (covers long summer closures).
+const STATUS_LOOKAHEAD_DAYS: i64 = 31;
+
 #[derive(Clone)]
 pub struct SchedulesService {
     repository: Arc<dyn SchedulesRepository>,
@@ -91,4 +95,215 @@ impl SchedulesService {
     pub async fn weekly_hours(&self, year: i32) -> AppResult<f64> {
         self.repository.schedules_weekly_hours(year).await
     }
+
+    // ---- Resolved timetable ----
+
+    /// Resolved Monday–Sunday timetable of the week containing `date`.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn week(&self, date: NaiveDate) -> AppResult<WeekSchedule> {
+        let week_start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+        let week_end = week_start + Duration::days(6);
+        let days = self.resolve_range(week_start, week_end).await?;
+        Ok(WeekSchedule { week_start, week_end, days })
+    }
+
+    /// Open/closed status at `now` (library local time) and the next opening.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn status(&self, now: NaiveDateTime) -> AppResult<ScheduleStatus> {
+        let today = now.date();
+        let days = self
+            .resolve_range(today, today + Duration::days(STATUS_LOOKAHEAD_DAYS))
+            .await?;
+        Ok(compute_status(now, &days))
+    }
+
+    /// First day after `date` with opening hours, used to move due dates out of a closure.
+    /// Falls back to the first day that is not an exceptional closure when no slot is scheduled
+    /// in the look-ahead window (timetable not configured).
+    #[tracing::instrument(skip(self), err)]
+    pub async fn first_open_day_after(&self, date: NaiveDate) -> AppResult<NaiveDate> {
+        let days = self
+            .resolve_range(date + Duration::days(1), date + Duration::days(STATUS_LOOKAHEAD_DAYS))
+            .await?;
+        Ok(days
+            .iter()
+            .find(|d| !d.hours.is_empty())
+            .or_else(|| days.iter().find(|d| !d.is_closure))
+            .map(|d| d.date)
+            .unwrap_or(date + Duration::days(STATUS_LOOKAHEAD_DAYS + 1)))
+    }
+
+    /// Resolved opening hours of every date in `[start, end]` (attendance statistics).
+    pub async fn days(&self, start: NaiveDate, end: NaiveDate) -> AppResult<Vec<DaySchedule>> {
+        self.resolve_range(start, end).await
+    }
+
+    /// Resolve every date in `[start, end]` from periods, slots and closures.
+    async fn resolve_range(&self, start: NaiveDate, end: NaiveDate) -> AppResult<Vec<DaySchedule>> {
+        let periods: Vec<SchedulePeriod> = self
+            .repository
+            .schedules_list_periods()
+            .await?
+            .into_iter()
+            .filter(|p| p.start_date <= end && p.end_date >= start)
+            .collect();
+
+        let mut period_slots = Vec::with_capacity(periods.len());
+        for period in periods {
+            let slots = self.repository.schedules_list_slots(period.id).await?;
+            period_slots.push((period, slots));
+        }
+        let closures = self
+            .repository
+            .schedules_list_closures(Some(start), Some(end))
+            .await?;
+
+        Ok(start
+            .iter_days()
+            .take_while(|d| *d <= end)
+            .map(|d| resolve_day(d, &period_slots, &closures))
+            .collect())
+    }
+}
+
+/// Opening hours of `date`. When several periods cover the date, the one starting last wins
+/// (e.g. "Summer hours" nested inside a yearly period); an exceptional closure empties the day.
+fn resolve_day(
+    date: NaiveDate,
+    periods: &[(SchedulePeriod, Vec<ScheduleSlot>)],
+    closures: &[ScheduleClosure],
+) -> DaySchedule {
+    let day_of_week = date.weekday().num_days_from_monday() as i16;
+    let closure = closures.iter().find(|c| c.closure_date == date);
+
+    let mut hours: Vec<OpeningHours> = match closure {
+        Some(_) => Vec::new(),
+        None => periods
+            .iter()
+            .filter(|(p, _)| p.start_date <= date && p.end_date >= date)
+            .max_by_key(|(p, _)| (p.start_date, p.id))
+            .map(|(_, slots)| {
+                slots
+                    .iter()
+                    .filter(|s| s.day_of_week == day_of_week && s.open_time < s.close_time)
+                    .map(|s| OpeningHours { open_time: s.open_time, close_time: s.close_time })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    hours.sort_by_key(|h| h.open_time);
+
+    DaySchedule {
+        date,
+        day_of_week,
+        is_closure: closure.is_some(),
+        closure_reason: closure.and_then(|c| c.reason.clone()),
+        hours,
+    }
+}
+
+/// Status at `now` given resolved days starting with today.
+fn compute_status(now: NaiveDateTime, days: &[DaySchedule]) -> ScheduleStatus {
+    let today = days.iter().find(|d| d.date == now.date());
+    let closes_at = today.and_then(|d| {
+        d.hours
+            .iter()
+            .find(|h| h.open_time <= now.time() && now.time() < h.close_time)
+            .map(|h| d.date.and_time(h.close_time))
+    });
+    let next_opening_at = days
+        .iter()
+        .flat_map(|d| d.hours.iter().map(move |h| d.date.and_time(h.open_time)))
+        .find(|start| *start > now);
+
+    ScheduleStatus {
+        is_open: closes_at.is_some(),
+        now,
+        closes_at,
+        next_opening_at,
+        closure_reason: today.and_then(|d| d.closure_reason.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+
+    fn period(id: i64, start: &str, end: &str) -> SchedulePeriod {
+        SchedulePeriod {
+            id,
+            name: format!("Period {}", id),
+            start_date: start.parse().unwrap(),
+            end_date: end.parse().unwrap(),
+            notes: None,
+            created_at: None,
+            update_at: None,
+        }
+    }
+
+    fn slot(period_id: i64, day_of_week: i16, open: &str, close: &str) -> ScheduleSlot {
+        ScheduleSlot {
+            id: 0,
+            period_id,
+            day_of_week,
+            open_time: NaiveTime::parse_from_str(open, "%H:%M").unwrap(),
+            close_time: NaiveTime::parse_from_str(close, "%H:%M").unwrap(),
+            created_at: None,
+        }
+    }
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn latest_period_wins_and_closure_empties_day() {
+        // 2026-07-06 is a Monday.
+        let periods = vec![
+            (period(1, "2026-01-01", "2026-12-31"), vec![slot(1, 0, "14:00", "18:00"), slot(1, 0, "09:00", "12:00")]),
+            (period(2, "2026-07-01", "2026-08-31"), vec![slot(2, 0, "10:00", "13:00")]),
+        ];
+        let closures = vec![ScheduleClosure {
+            id: 1,
+            closure_date: "2026-07-14".parse().unwrap(),
+            reason: Some("Fête nationale".to_string()),
+            created_at: None,
+        }];
+
+        let june = resolve_day("2026-06-29".parse().unwrap(), &periods, &closures);
+        assert_eq!(june.hours.len(), 2);
+        assert_eq!(june.hours[0].open_time, NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+
+        let july = resolve_day("2026-07-06".parse().unwrap(), &periods, &closures);
+        assert_eq!(july.hours.len(), 1);
+        assert_eq!(july.hours[0].open_time, NaiveTime::from_hms_opt(10, 0, 0).unwrap());
+
+        let closed = resolve_day("2026-07-14".parse().unwrap(), &periods, &closures);
+        assert!(closed.is_closure && closed.hours.is_empty());
+        assert_eq!(closed.closure_reason.as_deref(), Some("Fête nationale"));
+    }
+
+    #[test]
+    fn status_reports_current_interval_and_next_opening() {
+        let periods = vec![(
+            period(1, "2026-01-01", "2026-12-31"),
+            vec![slot(1, 0, "09:00", "12:00"), slot(1, 0, "14:00", "18:00"), slot(1, 2, "10:00", "17:00")],
+        )];
+        let start: NaiveDate = "2026-07-06".parse().unwrap();
+        let days: Vec<DaySchedule> = start
+            .iter_days()
+            .take(7)
+            .map(|d| resolve_day(d, &periods, &[]))
+            .collect();
+
+        let open = compute_status(at("2026-07-06 10:30"), &days);
+        assert!(open.is_open);
+        assert_eq!(open.closes_at, Some(at("2026-07-06 12:00")));
+        assert_eq!(open.next_opening_at, Some(at("2026-07-06 14:00")));
+
+        let evening = compute_status(at("2026-07-06 18:00"), &days);
+        assert!(!evening.is_open);
+        assert_eq!(evening.next_opening_at, Some(at("2026-07-08 10:00")));
+    }
 }
@@ -3,14 +3,15 @@
 //! [`MeilisearchService`] is a thin wrapper around the Meilisearch client that:
 //! - Configures the index on startup (`ensure_index`)
 //! - Indexes / deletes individual documents on catalog mutations
-//! - Executes full-text searches and returns ordered item IDs
+//! - Executes full-text searches and returns ordered item IDs with facet counts
 //! - Supports a full reindex for recovery or initial population
 
-use meilisearch_sdk::{client::Client, settings::Settings};
+use meilisearch_sdk::{client::Client, search::Selectors, settings::Settings};
 use serde::Deserialize;
 use tracing::{info, warn};
 
 use crate::config::MeilisearchConfig;
+use crate::models::biblio::{BiblioFacets, FacetCount, HEADING_FACET_LIMIT};
 pub use crate::models::biblio::MeiliBiblioDocument;
 
 /// Optional filter parameters applied alongside the free-text query.
@@ -19,9 +20,23 @@ pub struct SearchFilters {
     pub media_type: Option<String>,
     pub lang: Option<String>,
     pub audience_type: Option<String>,
+    /// Reading levels; the biblio must have one of them (`ReadingLevel` DB strings)
+    pub reading_levels: Vec<String>,
+    /// Accessible formats the biblio must all have (`AccessibilityFeature` DB strings)
+    pub accessibility: Vec<String>,
+    /// Genre codes; the biblio must have at least one
+    pub genres: Vec<String>,
+    /// Relator codes; a contributor must have one of them
+    pub author_roles: Vec<String>,
+    /// Subject heading id (narrower terms included, via `subject_path_ids`)
+    pub subject_id: Option<i64>,
+    /// Shelving location id (rooms and shelf ranges under it included, via `location_path_ids`)
+    pub location_id: Option<i64>,
     pub archive: Option<bool>,
     /// When `true`, do not restrict to biblios that have active items (Meili `has_active_items`).
     pub include_without_active_items: bool,
+    /// Public catalog: leave out biblios whose active copies are all hidden from the OPAC (`opac_hidden`)
+    pub opac: bool,
 }
 
 // ---------------------------------------------------------------------------
@@ -93,8 +108,17 @@ impl MeilisearchService {
             "media_type",
             "lang",
             "audience_type",
+            "reading_level",
+            "accessibility",
+            "genres",
+            "author_roles",
+            "subject_ids",
+            "subject_path_ids",
+            "location_ids",
+            "location_path_ids",
             "is_archived",
             "has_active_items",
+            "opac_hidden",
         ];
         match index.set_filterable_attributes(&filterable).await {
             Ok(_) => {}
@@ -102,7 +126,7 @@ impl MeilisearchService {
         }
     }
 
-    /// Full-text search. Returns `(ordered_item_ids, total_hits)`.
+    /// Full-text search. Returns `(ordered_item_ids, total_hits, facets)`.
     ///
     /// `page` and `per_page` are 1-based / count-based, matching the API convention.
     #[tracing::instrument(skip(self), err)]
@@ -112,7 +136,7 @@ impl MeilisearchService {
         filters: &SearchFilters,
         page: i64,
         per_page: i64,
-    ) -> Result<(Vec<i64>, i64), meilisearch_sdk::errors::Error> {
+    ) -> Result<(Vec<i64>, i64, BiblioFacets), meilisearch_sdk::errors::Error> {
         let index = self.client.index(&self.index_name);
         let offset = ((page - 1) * per_page) as usize;
         let limit = per_page as usize;
@@ -127,7 +151,8 @@ impl MeilisearchService {
         let mut sq = index.search();
         sq.with_query(query)
             .with_offset(offset)
-            .with_limit(limit);
+            .with_limit(limit)
+            .with_facets(Selectors::Some(&["accessibility", "media_type", "genres", "subject_ids", "location_ids"]));
 
         if let Some(ref f) = filter_expr {
             sq.with_filter(f.as_str());
@@ -137,8 +162,23 @@ impl MeilisearchService {
 
         let ids: Vec<i64> = results.hits.into_iter().map(|h| h.result.id).collect();
         let total = results.estimated_total_hits.unwrap_or(ids.len()) as i64;
+        let distribution = |name: &str| {
+            results
+                .facet_distribution
+                .as_ref()
+                .and_then(|d| d.get(name))
+                .into_iter()
+                .flatten()
+                .map(|(value, n)| (value.as_str(), *n as i64))
+        };
+        let mut facets = BiblioFacets::from_accessibility_counts(distribution("accessibility"));
+        // Labels are filled in by the catalog service from the taxonomy and heading tables
+        facets.media_types = top_facet_values(distribution("media_type"));
+        facets.genres = top_facet_values(distribution("genres"));
+        facets.subjects = top_facet_values(distribution("subject_ids"));
+        facets.locations = top_facet_values(distribution("location_ids"));
 
-        Ok((ids, total))
+        Ok((ids, total, facets))
     }
 
     /// Index (create or replace) a single document.
@@ -194,6 +234,17 @@ impl MeilisearchService {
 // Helpers
 // ---------------------------------------------------------------------------
 
+/// Most frequent facet values (count descending, then value), capped at [`HEADING_FACET_LIMIT`].
+fn top_facet_values<'a>(counts: impl IntoIterator<Item = (&'a str, i64)>) -> Vec<FacetCount> {
+    let mut values: Vec<FacetCount> = counts
+        .into_iter()
+        .map(|(value, count)| FacetCount { value: value.to_string(), label: None, count })
+        .collect();
+    values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    values.truncate(HEADING_FACET_LIMIT);
+    values
+}
+
 /// Build a Meilisearch filter expression from structured filter params.
 /// Returns `None` if there are no active filters.
 fn build_filter_expr(filters: &SearchFilters) -> Option<String> {
@@ -208,6 +259,39 @@ fn build_filter_expr(filters: &SearchFilters) -> Option<String> {
     if let Some(ref at) = filters.audience_type {
         parts.push(format!("audience_type = \"{}\"", at.replace('"', "\\\"")));
     }
+    if !filters.reading_levels.is_empty() {
+        let any: Vec<String> = filters
+            .reading_levels
+            .iter()
+            .map(|l| format!("reading_level = \"{}\"", l.replace('"', "\\\"")))
+            .collect();
+        parts.push(format!("({})", any.join(" OR ")));
+    }
+    for feature in &filters.accessibility {
+        parts.push(format!("accessibility = \"{}\"", feature.replace('"', "\\\"")));
+    }
+    if !filters.genres.is_empty() {
+        let any: Vec<String> = filters
+            .genres
+            .iter()
+            .map(|g| format!("genres = \"{}\"", g.replace('"', "\\\"")))
+            .collect();
+        parts.push(format!("({})", any.join(" OR ")));
+    }
+    if !filters.author_roles.is_empty() {
+        let any: Vec<String> = filters
+            .author_roles
+            .iter()
+            .map(|r| format!("author_roles = \"{}\"", r.replace('"', "\\\"")))
+            .collect();
+        parts.push(format!("({})", any.join(" OR ")));
+    }
+    if let Some(subject_id) = filters.subject_id {
+        parts.push(format!("subject_path_ids = {}", subject_id));
+    }
+    if let Some(location_id) = filters.location_id {
+        parts.push(format!("location_path_ids = {}", location_id));
+    }
     match filters.archive {
         Some(true) => parts.push("is_archived = true".to_string()),
         Some(false) | None => parts.push("is_archived = false".to_string()),
@@ -215,6 +299,9 @@ fn build_filter_expr(filters: &SearchFilters) -> Option<String> {
     if !filters.include_without_active_items {
         parts.push("has_active_items = true".to_string());
     }
+    if filters.opac {
+        parts.push("opac_hidden = false".to_string());
+    }
 
     if parts.is_empty() {
         None
@@ -0,0 +1,215 @@
+//! OPAC sitemap for search engines (`[sitemap]`)
+//!
+//! Lists the OPAC page of every record the public catalog shows (`sitemap.record_url`), with the
+//! record's last modification as `lastmod`. Catalogs larger than `urls_per_file` are split into
+//! shards (`sitemap-1.xml`...) referenced by a sitemap index served as `sitemap.xml`.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::{
+    api::versioning::ApiVersion,
+    dynamic_config::DynamicConfig,
+    error::{AppError, AppResult},
+    models::sitemap::{SitemapEntry, SitemapFile, SitemapReport},
+    repository::SitemapRepository,
+    services::audit::{self, AuditService},
+};
+
+/// Name of the sitemap (or of the sitemap index when the catalog is sharded)
+pub const SITEMAP_FILE: &str = "sitemap.xml";
+
+const SITEMAP_NS: &str = "http://www.sitemaps.org/schemas/sitemap/0.9";
+
+#[derive(Clone)]
+pub struct SitemapService {
+    repository: Arc<dyn SitemapRepository>,
+    audit: AuditService,
+    dynamic_config: Arc<DynamicConfig>,
+}
+
+impl SitemapService {
+    pub fn new(
+        repository: Arc<dyn SitemapRepository>,
+        audit: AuditService,
+        dynamic_config: Arc<DynamicConfig>,
+    ) -> Self {
+        Self { repository, audit, dynamic_config }
+    }
+
+    /// Published file (`sitemap.xml` or a shard)
+    pub async fn get_file(&self, name: &str) -> AppResult<SitemapFile> {
+        self.repository.sitemap_get_file(name).await
+    }
+
+    /// Regenerate the sitemap and publish it in place of the previous one
+    #[tracing::instrument(skip(self), err)]
+    pub async fn generate(&self, triggered_by: Option<i64>) -> AppResult<SitemapReport> {
+        let result = self.build().await;
+        match &result {
+            Ok(report) => self.audit.log(
+                audit::event::SITEMAP_GENERATED,
+                triggered_by,
+                Some("sitemap"),
+                None,
+                None,
+                Some(report),
+                audit::AuditLogMeta::success(),
+            ),
+            Err(e) => self.audit.log(
+                audit::event::SITEMAP_GENERATED,
+                triggered_by,
+                Some("sitemap"),
+                None,
+                None,
+                None::<serde_json::Value>,
+                audit::AuditLogMeta::from_app_error(e),
+            ),
+        }
+        result
+    }
+
+    /// Daily run of the scheduler
+    #[tracing::instrument(skip(self), err)]
+    pub async fn run_scheduled(&self) -> AppResult<()> {
+        let report = self.generate(None).await?;
+        tracing::info!(records = report.records, files = report.files, "Sitemap generated");
+        Ok(())
+    }
+
+    async fn build(&self) -> AppResult<SitemapReport> {
+        let config = self.dynamic_config.read_sitemap();
+        let record_url = config
+            .record_url
+            .ok_or_else(|| AppError::Validation("sitemap.record_url is not configured".to_string()))?;
+        let limit = config.urls_per_file.max(1) as i64;
+        let generation = self.repository.sitemap_begin().await?;
+
+        let first = self.repository.sitemap_entries(0, limit).await?;
+        let mut next = match first.last() {
+            Some(last) if first.len() as i64 == limit => self.repository.sitemap_entries(last.biblio_id, limit).await?,
+            _ => Vec::new(),
+        };
+        let mut report = SitemapReport { records: first.len() as u64, ..Default::default() };
+
+        if next.is_empty() {
+            let content = render_urlset(&first, &record_url);
+            self.repository
+                .sitemap_store_file(generation, SITEMAP_FILE, &content, first.len() as i64)
+                .await?;
+            report.files = 1;
+        } else {
+            let base_url = self
+                .dynamic_config
+                .file_config
+                .server
+                .public_base_url
+                .as_deref()
+                .map(|url| url.trim_end_matches('/').to_string())
+                .ok_or_else(|| {
+                    AppError::Validation("server.public_base_url is required to index sitemap shards".to_string())
+                })?;
+
+            let mut shards: Vec<(String, Option<DateTime<Utc>>)> = Vec::new();
+            let mut page = first;
+            loop {
+                let name = format!("sitemap-{}.xml", shards.len() + 1);
+                let content = render_urlset(&page, &record_url);
+                self.repository
+                    .sitemap_store_file(generation, &name, &content, page.len() as i64)
+                    .await?;
+                shards.push((name, page.iter().filter_map(|e| e.lastmod).max()));
+                if next.is_empty() {
+                    break;
+                }
+                report.records += next.len() as u64;
+                page = std::mem::take(&mut next);
+                if page.len() as i64 == limit {
+                    let after = page.last().map(|e| e.biblio_id).unwrap_or_default();
+                    next = self.repository.sitemap_entries(after, limit).await?;
+                }
+            }
+
+            let shard_url = format!("{}{}/sitemaps/", base_url, ApiVersion::LATEST.prefix());
+            let content = render_index(&shards, &shard_url);
+            self.repository
+                .sitemap_store_file(generation, SITEMAP_FILE, &content, report.records as i64)
+                .await?;
+            report.files = shards.len() as u32 + 1;
+            report.sharded = true;
+        }
+
+        self.repository.sitemap_publish(generation).await?;
+        Ok(report)
+    }
+}
+
+fn w3c_datetime(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// `<urlset>` of the records' OPAC pages (`{id}` of `record_url` replaced by the biblio ID)
+fn render_urlset(entries: &[SitemapEntry], record_url: &str) -> String {
+    let mut xml = format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"{}\">\n", SITEMAP_NS);
+    for entry in entries {
+        let loc = escape_xml(&record_url.replace("{id}", &entry.biblio_id.to_string()));
+        match entry.lastmod {
+            Some(at) => xml.push_str(&format!("  <url><loc>{}</loc><lastmod>{}</lastmod></url>\n", loc, w3c_datetime(at))),
+            None => xml.push_str(&format!("  <url><loc>{}</loc></url>\n", loc)),
+        }
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+/// `<sitemapindex>` of the shards, served under `shard_url`
+fn render_index(shards: &[(String, Option<DateTime<Utc>>)], shard_url: &str) -> String {
+    let mut xml = format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<sitemapindex xmlns=\"{}\">\n", SITEMAP_NS);
+    for (name, lastmod) in shards {
+        let loc = escape_xml(&format!("{}{}", shard_url, name));
+        match lastmod {
+            Some(at) => xml.push_str(&format!("  <sitemap><loc>{}</loc><lastmod>{}</lastmod></sitemap>\n", loc, w3c_datetime(*at))),
+            None => xml.push_str(&format!("  <sitemap><loc>{}</loc></sitemap>\n", loc)),
+        }
+    }
+    xml.push_str("</sitemapindex>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn urlset_lists_record_pages() {
+        let at = Utc.with_ymd_and_hms(2026, 3, 14, 9, 30, 0).unwrap();
+        let entries = vec![
+            SitemapEntry { biblio_id: 42, lastmod: Some(at) },
+            SitemapEntry { biblio_id: 43, lastmod: None },
+        ];
+        let xml = render_urlset(&entries, "https://opac.example/records/{id}?lang=fr&view=full");
+        assert!(xml.contains("<url><loc>https://opac.example/records/42?lang=fr&amp;view=full</loc><lastmod>2026-03-14T09:30:00Z</lastmod></url>"));
+        assert!(xml.contains("<url><loc>https://opac.example/records/43?lang=fr&amp;view=full</loc></url>"));
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">"));
+    }
+
+    #[test]
+    fn index_references_shards() {
+        let at = Utc.with_ymd_and_hms(2026, 3, 14, 9, 30, 0).unwrap();
+        let shards = vec![("sitemap-1.xml".to_string(), Some(at)), ("sitemap-2.xml".to_string(), None)];
+        let xml = render_index(&shards, "https://library.example/api/v2/sitemaps/");
+        assert!(xml.contains("<sitemap><loc>https://library.example/api/v2/sitemaps/sitemap-1.xml</loc><lastmod>2026-03-14T09:30:00Z</lastmod></sitemap>"));
+        assert!(xml.contains("<sitemap><loc>https://library.example/api/v2/sitemaps/sitemap-2.xml</loc></sitemap>"));
+        assert!(xml.ends_with("</sitemapindex>\n"));
+    }
+}
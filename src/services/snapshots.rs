@@ -0,0 +1,61 @@
+//! Catalog snapshots: copies captured before a bulk edit and restorable once (see `[snapshots]`
+//! in the configuration for retention).
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+
+use crate::{
+    config::SnapshotsConfig,
+    error::AppResult,
+    models::snapshot::{CatalogSnapshot, SnapshotRestoreReport, SnapshotScope},
+    repository::SnapshotsRepository,
+};
+
+#[derive(Clone)]
+pub struct SnapshotsService {
+    repository: Arc<dyn SnapshotsRepository>,
+    config: SnapshotsConfig,
+}
+
+impl SnapshotsService {
+    pub fn new(repository: Arc<dyn SnapshotsRepository>, config: SnapshotsConfig) -> Self {
+        Self { repository, config }
+    }
+
+    /// Capture the copies of `scope` before `operation` changes them, then drop the snapshots
+    /// beyond the configured count. `None` when the scope has no copy.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn capture(
+        &self,
+        operation: &str,
+        scope: SnapshotScope,
+        created_by: Option<i64>,
+    ) -> AppResult<Option<CatalogSnapshot>> {
+        let snapshot = self.repository.snapshots_capture(operation, &scope, created_by).await?;
+        if snapshot.is_some() {
+            self.cleanup().await?;
+        }
+        Ok(snapshot)
+    }
+
+    pub async fn list(&self) -> AppResult<Vec<CatalogSnapshot>> {
+        self.repository.snapshots_list().await
+    }
+
+    pub async fn get(&self, id: i64) -> AppResult<CatalogSnapshot> {
+        self.repository.snapshots_get(id).await
+    }
+
+    /// Put the captured copies back as they were when the snapshot was taken
+    #[tracing::instrument(skip(self), err)]
+    pub async fn restore(&self, id: i64, restored_by: i64) -> AppResult<SnapshotRestoreReport> {
+        self.repository.snapshots_restore(id, restored_by).await
+    }
+
+    /// Delete snapshots past the retention period or beyond the configured count
+    pub async fn cleanup(&self) -> AppResult<u64> {
+        let before = Utc::now() - Duration::days(self.config.retention_days as i64);
+        self.repository.snapshots_prune(before, self.config.max_count.max(1) as i64).await
+    }
+}
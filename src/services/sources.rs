@@ -4,18 +4,23 @@ use std::sync::Arc;
 
 use crate::{
     error::{AppError, AppResult},
-    models::source::{CreateSource, MergeSources, Source, UpdateSource},
+    models::source::{
+        CreateSource, MergeSources, Source, SourceDeletionReport, SourceStats, UpdateSource,
+    },
+    models::snapshot::{SnapshotScope, OPERATION_SOURCES_MERGE},
     repository::SourcesRepository,
+    services::snapshots::SnapshotsService,
 };
 
 #[derive(Clone)]
 pub struct SourcesService {
     repository: Arc<dyn SourcesRepository>,
+    snapshots: SnapshotsService,
 }
 
 impl SourcesService {
-    pub fn new(repository: Arc<dyn SourcesRepository>) -> Self {
-        Self { repository }
+    pub fn new(repository: Arc<dyn SourcesRepository>, snapshots: SnapshotsService) -> Self {
+        Self { repository, snapshots }
     }
 
     /// List sources
@@ -91,8 +96,9 @@ impl SourcesService {
     /// Merge multiple sources into a new one.
     ///
     /// All three writes (create, reassign, archive) run inside a single transaction so
-    /// a failure cannot leave items pointing at a non-existent or wrong source.
-    pub async fn merge(&self, data: &MergeSources) -> AppResult<Source> {
+    /// a failure cannot leave items pointing at a non-existent or wrong source. The copies of the
+    /// merged sources are snapshotted first, so their former sources can be restored.
+    pub async fn merge(&self, data: &MergeSources, user_id: i64) -> AppResult<Source> {
         if data.name.trim().is_empty() {
             return Err(AppError::Validation(
                 "Merged source name cannot be empty".to_string(),
@@ -112,6 +118,10 @@ impl SourcesService {
         let name = data.name.trim();
         let old_ids = &data.source_ids;
 
+        self.snapshots
+            .capture(OPERATION_SOURCES_MERGE, SnapshotScope::Sources(old_ids.clone()), Some(user_id))
+            .await?;
+
         
         let new_source =self.repository.sources_create(name, Some(false)).await?;
 
@@ -124,4 +134,38 @@ impl SourcesService {
         
         self.repository.sources_get_by_id(new_source.id).await
     }
+
+    /// Link a source to a vendor (`None` marks it as a donation / non-commercial source)
+    pub async fn set_vendor(&self, id: i64, vendor_id: Option<i64>) -> AppResult<Source> {
+        self.repository.sources_set_vendor(id, vendor_id).await
+    }
+
+    /// Items, biblios and loans attributable to a source
+    pub async fn stats(&self, id: i64) -> AppResult<SourceStats> {
+        self.repository.sources_get_by_id(id).await?;
+        self.repository.sources_stats(id).await
+    }
+
+    /// Delete a source after moving its items and biblios to `target_id`.
+    ///
+    /// The target must exist, differ from the deleted source and not be archived.
+    /// With `dry_run`, nothing is written and the report gives the rows that would move.
+    pub async fn delete(&self, id: i64, target_id: i64, dry_run: bool) -> AppResult<SourceDeletionReport> {
+        if id == target_id {
+            return Err(AppError::Validation(
+                "Target source must differ from the deleted source".to_string(),
+            ));
+        }
+        self.repository.sources_get_by_id(id).await?;
+        let target = self.repository.sources_get_by_id(target_id).await?;
+        if target.is_archive == Some(1) {
+            return Err(AppError::BusinessRule(
+                "Target source is archived".to_string(),
+            ));
+        }
+
+        self.repository
+            .sources_delete_with_reassign(id, target_id, dry_run)
+            .await
+    }
 }
@@ -0,0 +1,396 @@
+//! Staffing service: shift templates on opening slots, assignments, absences and iCal feeds
+
+use std::sync::Arc;
+
+use base64::Engine;
+use chrono::{Duration, NaiveDate, NaiveTime, Utc};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::{AppError, AppResult},
+    models::staffing::{
+        CreateShiftAssignment, CreateStaffAbsence, RosterShift, ShiftAssignment, ShiftOccurrence, ShiftTemplate,
+        ShiftTemplateFields, StaffAbsence, StaffAbsenceCreated, StaffAbsenceQuery, StaffCalendarToken,
+    },
+    repository::StaffingRepository,
+};
+
+/// Longest range of `GET /staffing/roster` and `GET /staffing/me/shifts`
+const MAX_RANGE_DAYS: i64 = 62;
+/// Window of the iCal feed around today
+const CALENDAR_PAST_DAYS: i64 = 30;
+const CALENDAR_FUTURE_DAYS: i64 = 180;
+const MAX_STAFF_NEEDED: i16 = 50;
+
+#[derive(Clone)]
+pub struct StaffingService {
+    repository: Arc<dyn StaffingRepository>,
+}
+
+impl StaffingService {
+    pub fn new(repository: Arc<dyn StaffingRepository>) -> Self {
+        Self { repository }
+    }
+
+    // ---- Templates ----
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list_templates(&self, period_id: Option<i64>) -> AppResult<Vec<ShiftTemplate>> {
+        self.repository.staffing_list_templates(period_id).await
+    }
+
+    pub async fn get_template(&self, id: i64) -> AppResult<ShiftTemplate> {
+        self.repository.staffing_get_template(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create_template(&self, data: &ShiftTemplateFields) -> AppResult<ShiftTemplate> {
+        let (start, end) = self.validate_template(data).await?;
+        self.repository.staffing_create_template(data, start, end).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update_template(&self, id: i64, data: &ShiftTemplateFields) -> AppResult<ShiftTemplate> {
+        let (start, end) = self.validate_template(data).await?;
+        self.repository.staffing_update_template(id, data, start, end).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete_template(&self, id: i64) -> AppResult<()> {
+        self.repository.staffing_delete_template(id).await
+    }
+
+    /// Parse the times and check the shift fits in its slot
+    async fn validate_template(&self, data: &ShiftTemplateFields) -> AppResult<(Option<NaiveTime>, Option<NaiveTime>)> {
+        let name = data.name.trim();
+        if name.is_empty() || name.chars().count() > 100 {
+            return Err(AppError::Validation("name must be between 1 and 100 characters".to_string()));
+        }
+        if data.staff_needed.is_some_and(|n| !(1..=MAX_STAFF_NEEDED).contains(&n)) {
+            return Err(AppError::Validation(format!("staffNeeded must be between 1 and {}", MAX_STAFF_NEEDED)));
+        }
+        let slot = self
+            .repository
+            .staffing_get_slot(data.slot_id)
+            .await?
+            .ok_or_else(|| AppError::Validation(format!("Unknown schedule slot {}", data.slot_id)))?;
+        let start = parse_time(data.start_time.as_deref(), "startTime")?;
+        let end = parse_time(data.end_time.as_deref(), "endTime")?;
+        let (from, to) = (start.unwrap_or(slot.open_time), end.unwrap_or(slot.close_time));
+        if from >= to || from < slot.open_time || to > slot.close_time {
+            return Err(AppError::Validation(format!(
+                "The shift must end after it starts, within the slot ({}-{})",
+                slot.open_time.format("%H:%M"),
+                slot.close_time.format("%H:%M")
+            )));
+        }
+        Ok((start, end))
+    }
+
+    // ---- Roster and assignments ----
+
+    /// Every shift between two days with its assignees, flagging understaffed ones
+    #[tracing::instrument(skip(self), err)]
+    pub async fn roster(&self, start: NaiveDate, end: NaiveDate) -> AppResult<Vec<RosterShift>> {
+        validate_range(start, end)?;
+        let shifts = self.repository.staffing_occurrences(start, end).await?;
+        let assignments = self.repository.staffing_assignments(start, end, None).await?;
+        Ok(build_roster(shifts, assignments))
+    }
+
+    /// Assignments of one user between two days
+    #[tracing::instrument(skip(self), err)]
+    pub async fn user_shifts(&self, user_id: i64, start: NaiveDate, end: NaiveDate) -> AppResult<Vec<ShiftAssignment>> {
+        validate_range(start, end)?;
+        self.repository.staffing_assignments(start, end, Some(user_id)).await
+    }
+
+    /// Assign a user to a shift. Conflict when the user is absent that day or already works an
+    /// overlapping shift; Validation when the template has no shift that day.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn assign(&self, data: &CreateShiftAssignment, created_by: i64) -> AppResult<ShiftAssignment> {
+        let date = data.shift_date;
+        let template = self.repository.staffing_get_template(data.template_id).await?;
+        let shift = self
+            .repository
+            .staffing_occurrences(date, date)
+            .await?
+            .into_iter()
+            .find(|s| s.template_id == template.id)
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "'{}' has no shift on {} (other day of week, period not in force or closure)",
+                    template.name, date
+                ))
+            })?;
+
+        let mut conflicts = Vec::new();
+        let absences = self
+            .repository
+            .staffing_list_absences(&StaffAbsenceQuery { user_id: Some(data.user_id), start: Some(date), end: Some(date) })
+            .await?;
+        if let Some(absence) = absences.first() {
+            conflicts.push(format!(
+                "the user is absent from {} to {}",
+                absence.start_date, absence.end_date
+            ));
+        }
+        for other in self.repository.staffing_assignments(date, date, Some(data.user_id)).await? {
+            if other.template_id != template.id && other.overlaps(date, shift.start_time, shift.end_time) {
+                conflicts.push(format!(
+                    "the user already works '{}' ({}-{})",
+                    other.name,
+                    other.start_time.format("%H:%M"),
+                    other.end_time.format("%H:%M")
+                ));
+            }
+        }
+        if !conflicts.is_empty() {
+            return Err(AppError::Conflict(format!("Shift conflicts: {}", conflicts.join("; "))));
+        }
+        self.repository.staffing_create_assignment(data, created_by).await
+    }
+
+    pub async fn get_assignment(&self, id: i64) -> AppResult<ShiftAssignment> {
+        self.repository.staffing_get_assignment(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn unassign(&self, id: i64) -> AppResult<()> {
+        self.repository.staffing_delete_assignment(id).await
+    }
+
+    // ---- Absences ----
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list_absences(&self, query: &StaffAbsenceQuery) -> AppResult<Vec<StaffAbsence>> {
+        self.repository.staffing_list_absences(query).await
+    }
+
+    pub async fn get_absence(&self, id: i64) -> AppResult<StaffAbsence> {
+        self.repository.staffing_get_absence(id).await
+    }
+
+    /// Record an absence and list the user's shifts it leaves uncovered
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create_absence(
+        &self,
+        user_id: i64,
+        data: &CreateStaffAbsence,
+        created_by: i64,
+    ) -> AppResult<StaffAbsenceCreated> {
+        if data.end_date < data.start_date {
+            return Err(AppError::Validation("endDate must not be before startDate".to_string()));
+        }
+        if data.reason.as_ref().is_some_and(|r| r.trim().chars().count() > 255) {
+            return Err(AppError::Validation("reason must be at most 255 characters".to_string()));
+        }
+        let absence = self.repository.staffing_create_absence(user_id, data, created_by).await?;
+        let uncovered_shifts = self
+            .repository
+            .staffing_assignments(absence.start_date, absence.end_date, Some(user_id))
+            .await?;
+        Ok(StaffAbsenceCreated { absence, uncovered_shifts })
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete_absence(&self, id: i64) -> AppResult<()> {
+        self.repository.staffing_delete_absence(id).await
+    }
+
+    // ---- iCal feed ----
+
+    /// New secret feed URL for the user (the previous one stops working)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn regenerate_calendar_token(&self, user_id: i64) -> AppResult<StaffCalendarToken> {
+        let bytes: [u8; 32] = rand::thread_rng().gen();
+        let token = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+        self.repository.staffing_set_calendar_token(user_id, &hash_token(&token)).await?;
+        Ok(StaffCalendarToken { path: format!("/staffing/calendar/{}.ics", token), token })
+    }
+
+    /// iCalendar document of the token owner's shifts, from a month ago to six months ahead
+    #[tracing::instrument(skip(self, token), err)]
+    pub async fn calendar(&self, token: &str) -> AppResult<String> {
+        let (user_id, firstname, lastname) = self
+            .repository
+            .staffing_calendar_owner(&hash_token(token))
+            .await?
+            .ok_or_else(|| AppError::NotFound("Unknown calendar".to_string()))?;
+        let today = Utc::now().date_naive();
+        let shifts = self
+            .repository
+            .staffing_assignments(
+                today - Duration::days(CALENDAR_PAST_DAYS),
+                today + Duration::days(CALENDAR_FUTURE_DAYS),
+                Some(user_id),
+            )
+            .await?;
+        let name = [firstname, lastname].into_iter().flatten().collect::<Vec<_>>().join(" ");
+        Ok(render_calendar(&format!("Shifts — {}", name.trim()), &shifts))
+    }
+}
+
+fn parse_time(value: Option<&str>, field: &str) -> AppResult<Option<NaiveTime>> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) => NaiveTime::parse_from_str(v, "%H:%M")
+            .map(Some)
+            .map_err(|_| AppError::Validation(format!("{} must be HH:MM", field))),
+        None => Ok(None),
+    }
+}
+
+fn validate_range(start: NaiveDate, end: NaiveDate) -> AppResult<()> {
+    if end < start {
+        return Err(AppError::Validation("end must not be before start".to_string()));
+    }
+    if (end - start).num_days() >= MAX_RANGE_DAYS {
+        return Err(AppError::Validation(format!("The range must not exceed {} days", MAX_RANGE_DAYS)));
+    }
+    Ok(())
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Attach assignments to their shift (both lists ordered by day); assignments whose shift no
+/// longer exists (slot moved, new closure) are dropped from the roster.
+fn build_roster(
+    shifts: Vec<ShiftOccurrence>,
+    assignments: Vec<ShiftAssignment>,
+) -> Vec<RosterShift> {
+    shifts
+        .into_iter()
+        .map(|shift| {
+            let assignments: Vec<ShiftAssignment> = assignments
+                .iter()
+                .filter(|a| a.template_id == shift.template_id && a.shift_date == shift.shift_date)
+                .cloned()
+                .collect();
+            let present = assignments.iter().filter(|a| !a.absent).count();
+            RosterShift { understaffed: present < shift.staff_needed as usize, shift, assignments }
+        })
+        .collect()
+}
+
+/// iCalendar (RFC 5545) with one event per shift, in library local time. Shifts during an
+/// absence are kept as cancelled so calendar apps drop them.
+fn render_calendar(name: &str, shifts: &[ShiftAssignment]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Elidune//Staffing//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(name)),
+    ];
+    for shift in shifts {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:shift-{}@elidune", shift.id));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", shift.shift_date.and_time(shift.start_time).format("%Y%m%dT%H%M%S")));
+        lines.push(format!("DTEND:{}", shift.shift_date.and_time(shift.end_time).format("%Y%m%dT%H%M%S")));
+        lines.push(format!("SUMMARY:{}", escape_text(&shift.name)));
+        if let Some(notes) = shift.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+            lines.push(format!("DESCRIPTION:{}", escape_text(notes)));
+        }
+        if shift.absent {
+            lines.push("STATUS:CANCELLED".to_string());
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|l| fold_line(l)).collect()
+}
+
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Content line ending with CRLF, folded every 75 octets without splitting a character
+fn fold_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() + 4);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(h: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, 0, 0).unwrap()
+    }
+
+    fn assignment(id: i64, template_id: i64, absent: bool, notes: Option<&str>) -> ShiftAssignment {
+        ShiftAssignment {
+            id,
+            template_id,
+            name: "Front desk, ground floor".to_string(),
+            shift_date: NaiveDate::from_ymd_opt(2026, 5, 2).unwrap(),
+            start_time: time(10),
+            end_time: time(12),
+            user_id: 7,
+            firstname: None,
+            lastname: None,
+            absent,
+            notes: notes.map(str::to_string),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn roster_counts_present_assignees() {
+        let shift = |template_id, staff_needed| ShiftOccurrence {
+            template_id,
+            name: "Front desk".to_string(),
+            shift_date: NaiveDate::from_ymd_opt(2026, 5, 2).unwrap(),
+            start_time: time(10),
+            end_time: time(12),
+            staff_needed,
+        };
+        let roster = build_roster(
+            vec![shift(1, 2), shift(2, 1)],
+            vec![assignment(10, 1, false, None), assignment(11, 1, true, None), assignment(12, 2, false, None)],
+        );
+        assert_eq!(roster[0].assignments.len(), 2);
+        assert!(roster[0].understaffed);
+        assert!(!roster[1].understaffed);
+    }
+
+    #[test]
+    fn calendar_escapes_and_folds_lines() {
+        let notes = format!("Bring the keys; {}", "x".repeat(80));
+        let ics = render_calendar("Shifts — Ada", &[assignment(3, 1, false, Some(&notes)), assignment(4, 1, true, None)]);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("UID:shift-3@elidune\r\nDTSTAMP:"));
+        assert!(ics.contains("DTSTART:20260502T100000\r\nDTEND:20260502T120000\r\nSUMMARY:Front desk\\, ground floor\r\n"));
+        assert!(ics.contains("DESCRIPTION:Bring the keys\\; xxx"));
+        assert!(ics.contains("STATUS:CANCELLED\r\nEND:VEVENT"));
+        assert!(ics.split("\r\n").all(|l| l.len() <= 75));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn ranges_are_bounded() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 5, d).unwrap();
+        assert!(validate_range(day(1), day(31)).is_ok());
+        assert!(validate_range(day(2), day(1)).is_err());
+        assert!(validate_range(day(1), day(1) + Duration::days(MAX_RANGE_DAYS)).is_err());
+    }
+}
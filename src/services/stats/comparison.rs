@@ -0,0 +1,160 @@
+//! Year-over-year comparison of the dashboard statistics (`compare_to=previousYear`).
+//!
+//! Both periods are computed with the same query; the deltas are derived from the serialized
+//! responses, so every count (including breakdowns added later) is compared without listing it.
+
+use chrono::{DateTime, Months, NaiveDate, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api::stats::StatDelta;
+
+/// Same day one year earlier (29 February becomes 28 February)
+pub fn previous_year_date(date: NaiveDate) -> NaiveDate {
+    date.checked_sub_months(Months::new(12)).unwrap_or(date)
+}
+
+/// Same instant one year earlier
+pub fn previous_year(at: DateTime<Utc>) -> DateTime<Utc> {
+    at.checked_sub_months(Months::new(12)).unwrap_or(at)
+}
+
+/// Compare every integer count of two responses of the same shape.
+///
+/// Breakdown entries are matched by their key (`label`, `place`, `sourceId`, `itemId`) rather
+/// than by position; entries present in one period only are compared with 0. Top-level fields
+/// listed in `skip` are left out.
+pub fn deltas<T: Serialize>(current: &T, previous: &T, skip: &[&str]) -> Vec<StatDelta> {
+    let current = counts(current, skip);
+    let previous = counts(previous, skip);
+
+    let mut deltas: Vec<StatDelta> = current
+        .iter()
+        .map(|(metric, value)| {
+            let before = previous.iter().find(|(m, _)| m == metric).map(|(_, v)| *v).unwrap_or(0);
+            delta(metric, *value, before)
+        })
+        .collect();
+    deltas.extend(
+        previous
+            .iter()
+            .filter(|(metric, _)| !current.iter().any(|(m, _)| m == metric))
+            .map(|(metric, value)| delta(metric, 0, *value)),
+    );
+    deltas
+}
+
+fn delta(metric: &str, current: i64, previous: i64) -> StatDelta {
+    let delta = current - previous;
+    StatDelta {
+        metric: metric.to_string(),
+        current,
+        previous,
+        delta,
+        percent: (previous != 0).then(|| (delta as f64 * 10_000.0 / previous as f64).round() / 100.0),
+    }
+}
+
+/// Integer leaves of the serialized response, by path
+fn counts<T: Serialize>(response: &T, skip: &[&str]) -> Vec<(String, i64)> {
+    let mut out = Vec::new();
+    if let Ok(Value::Object(fields)) = serde_json::to_value(response) {
+        for (key, value) in fields {
+            if !skip.contains(&key.as_str()) && key != "previous" && key != "deltas" {
+                collect(&key, &value, &mut out);
+            }
+        }
+    }
+    out
+}
+
+fn collect(path: &str, value: &Value, out: &mut Vec<(String, i64)>) {
+    match value {
+        Value::Number(n) => {
+            if let Some(n) = n.as_i64() {
+                out.push((path.to_string(), n));
+            }
+        }
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                collect(&format!("{}.{}", path, key), value, out);
+            }
+        }
+        Value::Array(entries) => {
+            for (index, entry) in entries.iter().enumerate() {
+                let key = entry_key(entry).unwrap_or_else(|| index.to_string());
+                match entry {
+                    // A keyed `{label, value}` entry is a count of its own
+                    Value::Object(fields) if fields.contains_key("value") && fields.contains_key("label") => {
+                        collect(&format!("{}.{}", path, key), &fields["value"], out);
+                    }
+                    Value::Object(fields) => {
+                        for (field, value) in fields {
+                            if !matches!(field.as_str(), "label" | "place" | "sourceId" | "itemId" | "biblioId") {
+                                collect(&format!("{}.{}.{}", path, key, field), value, out);
+                            }
+                        }
+                    }
+                    _ => collect(&format!("{}.{}", path, key), entry, out),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Key identifying a breakdown entry across periods
+fn entry_key(entry: &Value) -> Option<String> {
+    ["label", "sourceId", "itemId", "place"].iter().find_map(|field| match entry.get(*field)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Null => Some("none".to_string()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::stats::{LoanLocationStats, LoanStatsResponse, StatEntry, TimeSeriesEntry};
+
+    fn entry(label: &str, value: i64) -> StatEntry {
+        StatEntry { label: label.to_string(), value, display_label: Some(label.to_uppercase()) }
+    }
+
+    fn loan_stats(total: i64, media: Vec<StatEntry>, place: Option<i16>) -> LoanStatsResponse {
+        LoanStatsResponse {
+            total_loans: total,
+            total_returns: total - 1,
+            time_series: vec![TimeSeriesEntry { period: "2026-01".to_string(), loans: total, returns: 0 }],
+            by_media_type: media,
+            by_age_band: vec![],
+            by_location: Some(vec![LoanLocationStats { place, loans: total, returns: 0, by_media_type: vec![] }]),
+            previous: None,
+            deltas: None,
+        }
+    }
+
+    #[test]
+    fn deltas_match_breakdowns_by_key() {
+        let current = loan_stats(150, vec![entry("comics", 30), entry("printedText", 120)], Some(2));
+        let previous = loan_stats(100, vec![entry("printedText", 100), entry("dvd", 5)], None);
+        let deltas = deltas(&current, &previous, &["timeSeries"]);
+        let find = |metric: &str| deltas.iter().find(|d| d.metric == metric).cloned().unwrap();
+
+        assert_eq!(find("totalLoans"), StatDelta { metric: "totalLoans".into(), current: 150, previous: 100, delta: 50, percent: Some(50.0) });
+        assert_eq!((find("byMediaType.printedText").delta, find("byMediaType.printedText").percent), (20, Some(20.0)));
+        assert_eq!((find("byMediaType.comics").previous, find("byMediaType.comics").percent), (0, None));
+        assert_eq!((find("byMediaType.dvd").current, find("byMediaType.dvd").delta), (0, -5));
+        assert_eq!(find("byLocation.2.loans").current, 150);
+        assert_eq!(find("byLocation.none.loans").previous, 100);
+        assert!(deltas.iter().all(|d| !d.metric.starts_with("timeSeries")));
+    }
+
+    #[test]
+    fn previous_year_clamps_leap_day() {
+        let leap = NaiveDate::from_ymd_opt(2028, 2, 29).unwrap();
+        assert_eq!(previous_year_date(leap), NaiveDate::from_ymd_opt(2027, 2, 28).unwrap());
+        assert_eq!(previous_year_date(NaiveDate::from_ymd_opt(2026, 12, 31).unwrap()), NaiveDate::from_ymd_opt(2025, 12, 31).unwrap());
+    }
+}
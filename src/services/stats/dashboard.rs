@@ -1,33 +1,67 @@
 //! Statistics dashboard (delegates to repository).
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc};
+use rust_decimal::Decimal;
 
 use crate::{
     api::stats::{
-        CatalogStatsResponse, Interval, LoanStatsResponse, StatsResponse, UserLoanStats,
+        AcquisitionStatsResponse, AcquisitionValueStats, AcquisitionValueTotals, CatalogLocationStats,
+        CatalogQualityResponse, CatalogQualityStats, CompletenessRate,
+        CatalogStatsResponse, Interval, LoanLocationStats, LoanStatsResponse, StatsResponse, UserLoanStats,
         UserStatsAggregate, UserStatsSortBy,
     },
-    error::AppResult,
+    error::{AppError, AppResult},
     models::biblio::MediaType,
-    repository::Repository,
+    repository::{stats::dashboard::CatalogQualityCounts, Repository},
+    services::currency::Currency,
 };
 
 pub use crate::repository::stats::StatsFilter;
 
+use super::comparison;
+
 #[derive(Clone)]
 pub struct StatsService {
     repository: Repository,
+    currency: Currency,
 }
 
 impl StatsService {
     pub fn new(repository: Repository) -> Self {
-        Self { repository }
+        Self { repository, currency: Currency::default() }
+    }
+
+    /// Round acquisition amounts to the library's currency (`[currency]`)
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
     }
 
     pub async fn get_stats(&self, filter: Option<StatsFilter>) -> AppResult<StatsResponse> {
         self.repository.stats_get_stats(filter).await
     }
 
+    /// Statistics as of the filter's reference date (today when unset) and of the same date one
+    /// year earlier, with the item deltas. Users and loans are live counts, so they are not compared.
+    pub async fn get_stats_compared(&self, filter: Option<StatsFilter>) -> AppResult<StatsResponse> {
+        let mut current = filter.unwrap_or(StatsFilter {
+            reference_date: None,
+            public_type: None,
+            media_type: None,
+        });
+        let reference_date = *current.reference_date.get_or_insert_with(|| Utc::now().date_naive());
+        let previous_filter = StatsFilter {
+            reference_date: Some(comparison::previous_year_date(reference_date)),
+            ..current.clone()
+        };
+
+        let mut stats = self.repository.stats_get_stats(Some(current)).await?;
+        let previous = self.repository.stats_get_stats(Some(previous_filter)).await?;
+        stats.deltas = Some(comparison::deltas(&stats, &previous, &["users", "loans"]));
+        stats.previous = Some(Box::new(previous));
+        Ok(stats)
+    }
+
     pub async fn get_user_stats(
         &self,
         sort_by: UserStatsSortBy,
@@ -74,6 +108,7 @@ impl StatsService {
         by_source: bool,
         by_media_type: bool,
         by_public_type: bool,
+        by_digital_resource: bool,
     ) -> AppResult<CatalogStatsResponse> {
         self.repository
             .stats_get_catalog_stats(
@@ -82,7 +117,170 @@ impl StatsService {
                 by_source,
                 by_media_type,
                 by_public_type,
+                by_digital_resource,
             )
             .await
     }
+
+    pub async fn get_loan_location_stats(
+        &self,
+        start_date: Option<DateTime<Utc>>,
+        end_date: Option<DateTime<Utc>>,
+        media_type: Option<&MediaType>,
+        public_type: Option<&str>,
+        user_id: Option<i64>,
+    ) -> AppResult<Vec<LoanLocationStats>> {
+        self.repository
+            .stats_get_loan_location_stats(start_date, end_date, media_type, public_type, user_id)
+            .await
+    }
+
+    pub async fn get_catalog_location_stats(
+        &self,
+        start_date: Option<DateTime<Utc>>,
+        end_date: Option<DateTime<Utc>>,
+        by_media_type: bool,
+    ) -> AppResult<Vec<CatalogLocationStats>> {
+        self.repository
+            .stats_get_catalog_location_stats(start_date, end_date, by_media_type)
+            .await
+    }
+
+    /// Value of the copies entered from `start_date` (default January 1st) to `end_date`
+    /// (default today), VAT split out with the rate of each media type
+    pub async fn get_acquisition_stats(
+        &self,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> AppResult<AcquisitionStatsResponse> {
+        let end = end_date.unwrap_or_else(|| Local::now().date_naive());
+        let start = match start_date {
+            Some(start) => start,
+            None => NaiveDate::from_ymd_opt(end.year(), 1, 1).unwrap_or(end),
+        };
+        if end < start {
+            return Err(AppError::Validation("endDate must not be before startDate".to_string()));
+        }
+
+        let mut totals = AcquisitionValueTotals::default();
+        let by_media_type = self
+            .repository
+            .stats_get_acquisition_prices(start, end)
+            .await?
+            .into_iter()
+            .map(|row| {
+                let total_incl_tax = row.total.round_dp(self.currency.decimals());
+                let (total_excl_tax, tax) = split_tax(total_incl_tax, row.vat_rate, self.currency.decimals());
+                totals.copies += row.copies;
+                totals.priced_copies += row.priced_copies;
+                totals.total_incl_tax += total_incl_tax;
+                totals.tax += tax;
+                totals.total_excl_tax += total_excl_tax;
+                AcquisitionValueStats {
+                    label: row.label,
+                    display_label: None,
+                    vat_rate: row.vat_rate,
+                    copies: row.copies,
+                    priced_copies: row.priced_copies,
+                    total_incl_tax,
+                    tax,
+                    total_excl_tax,
+                }
+            })
+            .collect();
+
+        Ok(AcquisitionStatsResponse {
+            start_date: start,
+            end_date: end,
+            currency: self.currency.code().to_string(),
+            totals,
+            by_media_type,
+        })
+    }
+
+    /// Completeness of the active records per source, least complete sources first
+    pub async fn get_catalog_quality(&self) -> AppResult<CatalogQualityResponse> {
+        let (rows, totals) = self.repository.stats_get_catalog_quality().await?;
+        let mut by_source: Vec<CatalogQualityStats> = rows.into_iter().map(quality_stats).collect();
+        by_source.sort_by(|a, b| a.score.total_cmp(&b.score).then(b.records.cmp(&a.records)));
+        Ok(CatalogQualityResponse { totals: quality_stats(totals), by_source })
+    }
+}
+
+fn completeness(count: i64, records: i64) -> CompletenessRate {
+    let percent = if records > 0 {
+        (count as f64 * 1000.0 / records as f64).round() / 10.0
+    } else {
+        0.0
+    };
+    CompletenessRate { count, percent }
+}
+
+fn quality_stats(row: CatalogQualityCounts) -> CatalogQualityStats {
+    let rates = [
+        completeness(row.with_isbn, row.records),
+        completeness(row.with_authors, row.records),
+        completeness(row.with_call_number, row.records),
+        completeness(row.with_subjects, row.records),
+        completeness(row.with_cover, row.records),
+        completeness(row.with_marc_record, row.records),
+    ];
+    let score = (rates.iter().map(|r| r.percent).sum::<f64>() * 10.0 / rates.len() as f64).round() / 10.0;
+    let [isbn, authors, call_number, subjects, cover, marc_record] = rates;
+    CatalogQualityStats {
+        source_id: row.source_id,
+        source_name: row.source_name,
+        records: row.records,
+        isbn,
+        authors,
+        call_number,
+        subjects,
+        cover,
+        marc_record,
+        score,
+    }
+}
+
+/// `(net, tax)` of an amount including VAT at `rate` percent, rounded to `decimals`; the net
+/// amount is rounded so that both always add up to the amount
+fn split_tax(amount: Decimal, rate: Option<Decimal>, decimals: u32) -> (Decimal, Decimal) {
+    match rate {
+        Some(rate) if !rate.is_zero() => {
+            let net = (amount * Decimal::ONE_HUNDRED / (Decimal::ONE_HUNDRED + rate)).round_dp(decimals);
+            (net, amount - net)
+        }
+        _ => (amount, Decimal::ZERO),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vat_is_split_out_of_prices() {
+        // 5.5 % (books in France): 105.50 incl. VAT is 100.00 net
+        assert_eq!(split_tax(Decimal::new(10550, 2), Some(Decimal::new(55, 1)), 2), (Decimal::new(10000, 2), Decimal::new(550, 2)));
+        let (net, tax) = split_tax(Decimal::new(1999, 2), Some(Decimal::new(20, 0)), 2);
+        assert_eq!((net, tax), (Decimal::new(1666, 2), Decimal::new(333, 2)));
+        assert_eq!(split_tax(Decimal::new(1999, 2), None, 2), (Decimal::new(1999, 2), Decimal::ZERO));
+        assert_eq!(split_tax(Decimal::new(1500, 0), Some(Decimal::new(10, 0)), 0).0, Decimal::new(1364, 0));
+    }
+
+    #[test]
+    fn completeness_percentages() {
+        assert_eq!(completeness(2, 3).percent, 66.7);
+        assert_eq!(completeness(0, 0).percent, 0.0);
+        let stats = quality_stats(CatalogQualityCounts {
+            source_id: Some(4),
+            records: 4,
+            with_isbn: 4,
+            with_authors: 2,
+            with_call_number: 1,
+            with_cover: 3,
+            ..Default::default()
+        });
+        assert_eq!((stats.isbn.percent, stats.authors.percent, stats.cover.percent), (100.0, 50.0, 75.0));
+        assert_eq!(stats.score, 41.7);
+    }
 }
@@ -1,13 +1,18 @@
-//! Statistics: dashboard aggregates and flexible query builder.
+//! Statistics: dashboard aggregates, flexible query builder and report builder.
 
 mod builder;
 mod cache;
+pub mod comparison;
 mod dashboard;
 mod join_graph;
 mod query_builder;
+mod reports;
 pub mod schema;
 mod validator;
 
 pub use builder::run_stats_query;
 pub use dashboard::{StatsFilter, StatsService};
+pub use reports::{
+    report_csv, report_schema_json, report_xlsx, run_report, validate as validate_report,
+};
 pub use schema::discovery_json;
@@ -218,7 +218,7 @@ fn build_union_from_clause(entity: &str, union_with: &[String]) -> Result<String
         })?;
         let lit = name.replace('\'', "''");
         parts.push(format!(
-            r#"SELECT '{}'::text AS __union_source, id, user_id, item_id, date, expiry_at, returned_at, nb_renews FROM {}"#,
+            r#"SELECT '{}'::text AS __union_source, id, user_id, item_id, date, expiry_at, returned_at, nb_renews, borrower_age_band FROM {}"#,
             lit, def.table
         ));
     }
@@ -0,0 +1,460 @@
+//! Report builder: compile a `ReportDefinition` (whitelisted dimensions × measures) into one
+//! stats builder query per measure, run them through the cached builder path and merge the
+//! results on the dimension values. Clients never see or send SQL.
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+use sqlx::PgPool;
+
+use crate::error::{AppError, AppResult};
+use crate::models::stats_builder::{
+    AggregateFunction, ColumnMeta, FilterOperator, GroupByField, ReportDefinition, ReportDimension,
+    ReportMeasure, ReportTable, SelectField, StatsAggregation, StatsBuilderBody, StatsFilter,
+    TimeBucket, TimeGranularity,
+};
+use crate::services::exports::{escape_csv, Cell, XlsxStreamWriter};
+use crate::services::redis::RedisService;
+
+use super::builder::run_stats_query;
+
+/// Rows per measure query; larger reports must be narrowed (period, fewer dimensions)
+const MAX_REPORT_ROWS: u32 = 10_000;
+
+/// Root entity, aggregation and date field of a measure.
+struct MeasureDef {
+    entity: &'static str,
+    union_with: &'static [&'static str],
+    function: AggregateFunction,
+    field: &'static str,
+    /// Timestamp bucketed by the `period` dimension
+    date_field: &'static str,
+    /// `YYYY-MM-DD` text field compared with `from` / `to`
+    day_field: &'static str,
+    /// Filters always applied (e.g. active copies only)
+    base_filters: &'static [(&'static str, FilterOperator)],
+}
+
+fn measure_def(measure: ReportMeasure) -> MeasureDef {
+    match measure {
+        ReportMeasure::Loans => MeasureDef {
+            entity: "loans",
+            union_with: &["loans_archives"],
+            function: AggregateFunction::Count,
+            field: "loans.id",
+            date_field: "loans.date",
+            day_field: "loans.date_day",
+            base_filters: &[],
+        },
+        ReportMeasure::Specimens => MeasureDef {
+            entity: "items",
+            union_with: &[],
+            function: AggregateFunction::Count,
+            field: "items.id",
+            date_field: "items.created_at",
+            day_field: "items.created_day",
+            base_filters: &[("items.archived_at", FilterOperator::IsNull)],
+        },
+        ReportMeasure::Users => MeasureDef {
+            entity: "users",
+            union_with: &[],
+            function: AggregateFunction::Count,
+            field: "users.id",
+            date_field: "users.created_at",
+            day_field: "users.created_day",
+            base_filters: &[],
+        },
+        ReportMeasure::Fines => MeasureDef {
+            entity: "fines",
+            union_with: &[],
+            function: AggregateFunction::Sum,
+            field: "fines.amount",
+            date_field: "fines.created_at",
+            day_field: "fines.created_day",
+            base_filters: &[],
+        },
+    }
+}
+
+/// Join path and field of a (non-period) dimension for a measure; `None` when not supported.
+fn dimension_field(
+    measure: ReportMeasure,
+    dimension: ReportDimension,
+) -> Option<(Option<&'static str>, &'static str)> {
+    use ReportDimension as D;
+    use ReportMeasure as M;
+    match (measure, dimension) {
+        (M::Loans, D::MediaType) => Some((Some("items.biblios"), "biblios.media_type")),
+        (M::Loans, D::Audience) => Some((Some("items.biblios"), "biblios.audience_type")),
+        (M::Loans, D::Source) => Some((Some("items.sources"), "sources.name")),
+        (M::Loans, D::AccountType) => Some((Some("users.account_types"), "account_types.name")),
+        (M::Loans, D::Location) => Some((Some("items"), "items.place")),
+        (M::Loans, D::AgeBand) => Some((None, "loans.borrower_age_band")),
+        (M::Specimens, D::MediaType) => Some((Some("biblios"), "biblios.media_type")),
+        (M::Specimens, D::Audience) => Some((Some("biblios"), "biblios.audience_type")),
+        (M::Specimens, D::Source) => Some((Some("sources"), "sources.name")),
+        (M::Specimens, D::Location) => Some((None, "items.place")),
+        (M::Users, D::AccountType) => Some((Some("account_types"), "account_types.name")),
+        (M::Fines, D::AccountType) => Some((Some("users.account_types"), "account_types.name")),
+        _ => None,
+    }
+}
+
+/// True when `measure` can be split along `dimension`.
+pub fn supports(measure: ReportMeasure, dimension: ReportDimension) -> bool {
+    dimension == ReportDimension::Period || dimension_field(measure, dimension).is_some()
+}
+
+/// Discovery document for report editors: dimensions with the measures they apply to.
+pub fn report_schema_json() -> Value {
+    let dimensions: Vec<Value> = ReportDimension::ALL
+        .iter()
+        .map(|d| {
+            let measures: Vec<&str> = ReportMeasure::ALL
+                .iter()
+                .filter(|m| supports(**m, *d))
+                .map(|m| m.key())
+                .collect();
+            json!({ "key": d.key(), "measures": measures })
+        })
+        .collect();
+    let measures: Vec<&str> = ReportMeasure::ALL.iter().map(|m| m.key()).collect();
+    json!({
+        "dimensions": dimensions,
+        "measures": measures,
+        "granularities": ["day", "week", "month", "quarter", "year"],
+        "formats": ["json", "csv", "xlsx"],
+    })
+}
+
+/// Check measures and dimensions before saving or running a definition.
+pub fn validate(definition: &ReportDefinition) -> AppResult<()> {
+    if definition.measures.is_empty() {
+        return Err(AppError::Validation("A report needs at least one measure".into()));
+    }
+    for (i, d) in definition.dimensions.iter().enumerate() {
+        if definition.dimensions[..i].contains(d) {
+            return Err(AppError::Validation(format!("Duplicate dimension: {}", d.key())));
+        }
+    }
+    for (i, m) in definition.measures.iter().enumerate() {
+        if definition.measures[..i].contains(m) {
+            return Err(AppError::Validation(format!("Duplicate measure: {}", m.key())));
+        }
+        for d in &definition.dimensions {
+            if !supports(*m, *d) {
+                return Err(AppError::Validation(format!(
+                    "Measure {} cannot be split by {}",
+                    m.key(),
+                    d.key()
+                )));
+            }
+        }
+    }
+    if let (Some(from), Some(to)) = (definition.from, definition.to) {
+        if from > to {
+            return Err(AppError::Validation("from must not be after to".into()));
+        }
+    }
+    Ok(())
+}
+
+/// Stats builder query computing one measure of the report.
+fn compile_measure(definition: &ReportDefinition, measure: ReportMeasure) -> StatsBuilderBody {
+    let def = measure_def(measure);
+    let mut joins: Vec<String> = Vec::new();
+    let mut select = Vec::new();
+    let mut group_by = Vec::new();
+    for dimension in &definition.dimensions {
+        let Some((join, field)) = dimension_field(measure, *dimension) else {
+            continue;
+        };
+        if let Some(join) = join {
+            if !joins.iter().any(|j| j == join) {
+                joins.push(join.to_string());
+            }
+        }
+        select.push(SelectField {
+            field: field.to_string(),
+            alias: Some(dimension.key().to_string()),
+        });
+        group_by.push(GroupByField {
+            field: field.to_string(),
+            alias: Some(dimension.key().to_string()),
+        });
+    }
+    let time_bucket = definition
+        .dimensions
+        .contains(&ReportDimension::Period)
+        .then(|| TimeBucket {
+            field: def.date_field.to_string(),
+            granularity: definition.granularity.unwrap_or(TimeGranularity::Month),
+            alias: Some(ReportDimension::Period.key().to_string()),
+        });
+
+    let mut filters: Vec<StatsFilter> = def
+        .base_filters
+        .iter()
+        .map(|(field, op)| StatsFilter {
+            field: field.to_string(),
+            op: *op,
+            value: Value::Null,
+        })
+        .collect();
+    for (bound, op) in [(definition.from, FilterOperator::Gte), (definition.to, FilterOperator::Lte)] {
+        if let Some(day) = bound {
+            filters.push(StatsFilter {
+                field: def.day_field.to_string(),
+                op,
+                value: Value::String(day.format("%Y-%m-%d").to_string()),
+            });
+        }
+    }
+
+    StatsBuilderBody {
+        entity: def.entity.to_string(),
+        union_with: def.union_with.iter().map(|s| s.to_string()).collect(),
+        joins,
+        select,
+        filters,
+        filter_groups: Vec::new(),
+        aggregations: vec![StatsAggregation {
+            function: def.function,
+            field: def.field.to_string(),
+            alias: measure.key().to_string(),
+        }],
+        group_by,
+        having: Vec::new(),
+        time_bucket,
+        order_by: Vec::new(),
+        limit: Some(MAX_REPORT_ROWS),
+        offset: None,
+    }
+}
+
+/// Run every measure (cached per compiled query) and merge rows on the dimension values.
+pub async fn run_report(
+    pool: &PgPool,
+    redis: Option<&RedisService>,
+    definition: &ReportDefinition,
+) -> AppResult<ReportTable> {
+    validate(definition)?;
+
+    let mut results = Vec::with_capacity(definition.measures.len());
+    for measure in &definition.measures {
+        let body = compile_measure(definition, *measure);
+        let response = run_stats_query(pool, redis, &body).await?;
+        if let Some(error) = response.sql_error {
+            return Err(AppError::Internal(format!(
+                "Report measure {} failed: {}",
+                measure.key(),
+                error
+            )));
+        }
+        if response.total_rows > response.rows.len() as u64 {
+            return Err(AppError::Validation(format!(
+                "Report too large (more than {} rows); narrow the period or remove dimensions",
+                MAX_REPORT_ROWS
+            )));
+        }
+        results.push((*measure, response.rows));
+    }
+    Ok(merge(definition, results))
+}
+
+fn merge(
+    definition: &ReportDefinition,
+    results: Vec<(ReportMeasure, Vec<Map<String, Value>>)>,
+) -> ReportTable {
+    let mut merged: BTreeMap<Vec<String>, Map<String, Value>> = BTreeMap::new();
+    for (measure, rows) in &results {
+        for row in rows {
+            let key: Vec<String> = definition
+                .dimensions
+                .iter()
+                .map(|d| sort_key(row.get(d.key()).unwrap_or(&Value::Null)))
+                .collect();
+            let entry = merged.entry(key).or_insert_with(|| {
+                let mut out = Map::new();
+                for d in &definition.dimensions {
+                    let value = row.get(d.key()).cloned().unwrap_or(Value::Null);
+                    out.insert(d.key().to_string(), value);
+                }
+                out
+            });
+            let value = row.get(measure.key()).cloned().unwrap_or(Value::Null);
+            entry.insert(measure.key().to_string(), value);
+        }
+    }
+
+    let rows = merged
+        .into_values()
+        .map(|mut row| {
+            for m in &definition.measures {
+                let value = row.entry(m.key().to_string()).or_insert(Value::Null);
+                if value.is_null() {
+                    *value = json!(0);
+                }
+            }
+            row
+        })
+        .collect();
+
+    let mut columns: Vec<ColumnMeta> = definition
+        .dimensions
+        .iter()
+        .map(|d| ColumnMeta {
+            name: d.key().to_string(),
+            label: d.key().to_string(),
+            data_type: if *d == ReportDimension::Period { "timestamptz" } else { "text" }.into(),
+        })
+        .collect();
+    columns.extend(definition.measures.iter().map(|m| ColumnMeta {
+        name: m.key().to_string(),
+        label: m.key().to_string(),
+        data_type: if *m == ReportMeasure::Fines { "numeric" } else { "bigint" }.into(),
+    }));
+
+    ReportTable { columns, rows }
+}
+
+/// Orders empty values last and numbers numerically within a column
+fn sort_key(value: &Value) -> String {
+    match value {
+        Value::Null => "\u{10FFFF}".to_string(),
+        Value::Number(n) => format!("{:>20}", n),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Comma-separated CSV with a header line
+pub fn report_csv(table: &ReportTable) -> String {
+    let mut out = table
+        .columns
+        .iter()
+        .map(|c| escape_csv(&c.name))
+        .collect::<Vec<_>>()
+        .join(",");
+    out.push('\n');
+    for row in &table.rows {
+        let line = table
+            .columns
+            .iter()
+            .map(|c| escape_csv(&cell_text(row.get(&c.name).unwrap_or(&Value::Null))))
+            .collect::<Vec<_>>()
+            .join(",");
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Single-sheet workbook; measures stay numeric so spreadsheets can sum them
+pub fn report_xlsx(table: &ReportTable) -> AppResult<Vec<u8>> {
+    let io_err = |e: std::io::Error| AppError::Internal(format!("Report XLSX: {}", e));
+    let (mut writer, mut out) = XlsxStreamWriter::new("Report").map_err(io_err)?;
+    let header: Vec<Cell> = table.columns.iter().map(|c| Cell::Text(&c.name)).collect();
+    out.extend(writer.write_row(&header).map_err(io_err)?);
+    for row in &table.rows {
+        let texts: Vec<String> = table
+            .columns
+            .iter()
+            .map(|c| cell_text(row.get(&c.name).unwrap_or(&Value::Null)))
+            .collect();
+        let cells: Vec<Cell> = table
+            .columns
+            .iter()
+            .zip(&texts)
+            .map(|(c, text)| match row.get(&c.name) {
+                Some(Value::Number(n)) => match n.as_i64() {
+                    Some(i) => Cell::Number(i),
+                    None => Cell::Float(n.as_f64().unwrap_or_default()),
+                },
+                Some(Value::Null) | None => Cell::Empty,
+                Some(_) => Cell::Text(text),
+            })
+            .collect();
+        out.extend(writer.write_row(&cells).map_err(io_err)?);
+    }
+    out.extend(writer.finish().map_err(io_err)?);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::services::stats::query_builder::build_sql;
+
+    fn definition(dimensions: Vec<ReportDimension>, measures: Vec<ReportMeasure>) -> ReportDefinition {
+        ReportDefinition {
+            dimensions,
+            measures,
+            granularity: Some(TimeGranularity::Year),
+            from: NaiveDate::from_ymd_opt(2025, 1, 1),
+            to: NaiveDate::from_ymd_opt(2025, 12, 31),
+        }
+    }
+
+    #[test]
+    fn every_supported_combination_compiles() {
+        for measure in ReportMeasure::ALL {
+            let dimensions: Vec<ReportDimension> = ReportDimension::ALL
+                .into_iter()
+                .filter(|d| supports(measure, *d))
+                .collect();
+            let def = definition(dimensions, vec![measure]);
+            validate(&def).unwrap();
+            let built = build_sql(&compile_measure(&def, measure)).unwrap();
+            assert!(built.data_sql.contains("GROUP BY"), "{}", built.data_sql);
+            assert_eq!(built.binds.len(), 2);
+        }
+    }
+
+    #[test]
+    fn unsupported_dimension_is_rejected() {
+        let def = definition(vec![ReportDimension::MediaType], vec![ReportMeasure::Users]);
+        assert!(matches!(validate(&def), Err(AppError::Validation(_))));
+        let def = definition(vec![], vec![]);
+        assert!(validate(&def).is_err());
+    }
+
+    #[test]
+    fn measures_are_merged_on_dimensions() {
+        let def = definition(
+            vec![ReportDimension::AccountType],
+            vec![ReportMeasure::Users, ReportMeasure::Loans],
+        );
+        let row = |kind: &str, measure: &str, n: i64| {
+            let mut m = Map::new();
+            m.insert("accountType".into(), json!(kind));
+            m.insert(measure.into(), json!(n));
+            m
+        };
+        let table = merge(
+            &def,
+            vec![
+                (ReportMeasure::Users, vec![row("Adult", "users", 40), row("Child", "users", 12)]),
+                (ReportMeasure::Loans, vec![row("Adult", "loans", 300)]),
+            ],
+        );
+        assert_eq!(table.columns.len(), 3);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0]["loans"], json!(300));
+        assert_eq!(table.rows[1]["loans"], json!(0));
+        assert_eq!(
+            report_csv(&table),
+            "accountType,users,loans\nAdult,40,300\nChild,12,0\n"
+        );
+    }
+}
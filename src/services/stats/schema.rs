@@ -173,6 +173,15 @@ pub static SCHEMA: Lazy<HashMap<&'static str, EntityDef>> = Lazy::new(|| {
                 ("expiry_at", f("expiry_at", "timestamptz", "Due date")),
                 ("returned_at", f("returned_at", "timestamptz", "Return date")),
                 ("nb_renews", f("nb_renews", "integer", "Renewals")),
+                ("borrower_age_band", f("borrower_age_band", "text", "Borrower age band at loan time")),
+                (
+                    "date_day",
+                    c(
+                        "to_char({alias}.date, 'YYYY-MM-DD')",
+                        "text",
+                        "Loan day (YYYY-MM-DD, for date range filters)",
+                    ),
+                ),
                 (
                     "union_source",
                     c(
@@ -236,6 +245,14 @@ pub static SCHEMA: Lazy<HashMap<&'static str, EntityDef>> = Lazy::new(|| {
                 ("created_at", f("created_at", "timestamptz", "Registration")),
                 ("expiry_at", f("expiry_at", "timestamptz", "Membership expiry")),
                 ("status", f("status", "text", "Status")),
+                (
+                    "created_day",
+                    c(
+                        "to_char({alias}.created_at, 'YYYY-MM-DD')",
+                        "text",
+                        "Registration day (YYYY-MM-DD, for date range filters)",
+                    ),
+                ),
             ]),
             relations: HashMap::from([
                 ("public_types", r("public_types", "public_type", "id", "Audience type")),
@@ -255,8 +272,17 @@ pub static SCHEMA: Lazy<HashMap<&'static str, EntityDef>> = Lazy::new(|| {
                 ("source_id", f("source_id", "bigint", "Catalog source id")),
                 ("barcode", f("barcode", "text", "Barcode")),
                 ("call_number", f("call_number", "text", "Call number")),
+                ("place", f("place", "smallint", "Shelving location")),
                 ("created_at", f("created_at", "timestamptz", "Created at")),
                 ("archived_at", f("archived_at", "timestamptz", "Archived at")),
+                (
+                    "created_day",
+                    c(
+                        "to_char({alias}.created_at, 'YYYY-MM-DD')",
+                        "text",
+                        "Entry day (YYYY-MM-DD, for date range filters)",
+                    ),
+                ),
             ]),
             relations: HashMap::from([
                 ("biblios", r("biblios", "biblio_id", "id", "Biblio")),
@@ -288,6 +314,7 @@ pub static SCHEMA: Lazy<HashMap<&'static str, EntityDef>> = Lazy::new(|| {
                 ("title", f("title", "text", "Title")),
                 ("media_type", f("media_type", "text", "Media type")),
                 ("audience_type", f("audience_type", "text", "Audience")),
+                ("reading_level", f("reading_level", "text", "Reading level")),
                 ("lang", f("lang", "text", "Language")),
                 ("publication_date", f("publication_date", "text", "Publication date")),
             ]),
@@ -357,6 +384,32 @@ pub static SCHEMA: Lazy<HashMap<&'static str, EntityDef>> = Lazy::new(|| {
         },
     );
 
+    m.insert(
+        "fines",
+        EntityDef {
+            table: "fines",
+            label: "Fines",
+            fields: HashMap::from([
+                ("id", f("id", "bigint", "Id")),
+                ("loan_id", f("loan_id", "bigint", "Loan id")),
+                ("user_id", f("user_id", "bigint", "User id")),
+                ("amount", f("amount", "numeric", "Amount")),
+                ("paid_amount", f("paid_amount", "numeric", "Paid amount")),
+                ("status", f("status", "text", "Status")),
+                ("created_at", f("created_at", "timestamptz", "Issued at")),
+                (
+                    "created_day",
+                    c(
+                        "to_char({alias}.created_at, 'YYYY-MM-DD')",
+                        "text",
+                        "Issue day (YYYY-MM-DD, for date range filters)",
+                    ),
+                ),
+            ]),
+            relations: HashMap::from([("users", r("users", "user_id", "id", "Patron"))]),
+        },
+    );
+
     m.insert(
         "loans_archives",
         EntityDef {
@@ -371,6 +424,7 @@ pub static SCHEMA: Lazy<HashMap<&'static str, EntityDef>> = Lazy::new(|| {
                 ("returned_at", f("returned_at", "timestamptz", "Return")),
                 ("nb_renews", f("nb_renews", "integer", "Renewals")),
                 ("addr_city", f("addr_city", "text", "Borrower city")),
+                ("borrower_age_band", f("borrower_age_band", "text", "Borrower age band at loan time")),
             ]),
             relations: HashMap::from([
                 ("users", r("users", "user_id", "id", "Borrower")),
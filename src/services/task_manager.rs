@@ -3,7 +3,8 @@
 //! Tracks long-running server-side operations (MARC batch import, maintenance, …).
 //! Active tasks are held in an in-memory map; completed/failed outcomes are
 //! persisted to Redis so the frontend can retrieve them after a page refresh or
-//! session reconnect.
+//! session reconnect. When notifications are attached, the user who started a task gets an
+//! in-app notification once it completes or fails.
 //!
 //! ## Redis keys
 //! | Key                       | Value                              | TTL  |
@@ -18,8 +19,11 @@ use redis::AsyncCommands;
 use tokio::sync::RwLock;
 
 use crate::{
-    models::task::{BackgroundTask, TaskKind, TaskProgress, TaskStatus},
-    services::redis::RedisService,
+    models::{
+        notification::{NewNotification, NotificationKind},
+        task::{BackgroundTask, TaskKind, TaskProgress, TaskStatus},
+    },
+    services::{notifications::NotificationsService, redis::RedisService},
 };
 
 const TASK_TTL_SECS: u64 = 24 * 60 * 60;
@@ -38,6 +42,7 @@ pub struct TaskHandle {
     pub id: i64,
     task: Arc<RwLock<BackgroundTask>>,
     redis: RedisService,
+    notifications: Option<NotificationsService>,
 }
 
 impl TaskHandle {
@@ -67,6 +72,7 @@ impl TaskHandle {
             task.progress = None;
         }
         self.persist().await;
+        self.notify_outcome().await;
     }
 
     /// Mark the task as failed and persist the error to Redis.
@@ -81,6 +87,7 @@ impl TaskHandle {
             task.progress = None;
         }
         self.persist().await;
+        self.notify_outcome().await;
     }
 
     /// Return a snapshot of the current task state.
@@ -88,6 +95,17 @@ impl TaskHandle {
         self.task.read().await.clone()
     }
 
+    /// Notify the user who started the task of its outcome (fire-and-forget).
+    async fn notify_outcome(&self) {
+        let Some(notifications) = self.notifications.clone() else { return };
+        let snapshot = self.task.read().await.clone();
+        if let Some(data) = outcome_notification(&snapshot) {
+            tokio::spawn(async move {
+                notifications.notify(snapshot.user_id, &data).await;
+            });
+        }
+    }
+
     /// Write the current state to Redis asynchronously (fire-and-forget).
     async fn persist(&self) {
         let snapshot = self.task.read().await.clone();
@@ -129,6 +147,7 @@ impl TaskHandle {
 pub struct TaskManager {
     active: TaskMap,
     redis: RedisService,
+    notifications: Option<NotificationsService>,
 }
 
 impl TaskManager {
@@ -136,9 +155,16 @@ impl TaskManager {
         Self {
             active: Arc::new(std::sync::RwLock::new(HashMap::new())),
             redis,
+            notifications: None,
         }
     }
 
+    /// Notify task owners in-app when their tasks complete or fail.
+    pub fn with_notifications(mut self, notifications: NotificationsService) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
     /// Spawn a background task and return its ID immediately (non-blocking).
     ///
     /// The closure receives a [`TaskHandle`] and **must** call either
@@ -178,6 +204,7 @@ impl TaskManager {
             id: task_id,
             task: task_arc,
             redis: self.redis.clone(),
+            notifications: self.notifications.clone(),
         };
 
         let active_clone = Arc::clone(&self.active);
@@ -289,3 +316,21 @@ impl TaskManager {
         tasks
     }
 }
+
+/// In-app notification of a finished task (`None` while it is still running)
+fn outcome_notification(task: &BackgroundTask) -> Option<NewNotification> {
+    let label = task.kind.label();
+    let (kind, title, body) = match task.status {
+        TaskStatus::Completed => (NotificationKind::TaskCompleted, format!("{} completed", label), None),
+        TaskStatus::Failed => (NotificationKind::TaskFailed, format!("{} failed", label), task.error.clone()),
+        TaskStatus::Pending | TaskStatus::Running => return None,
+    };
+    Some(NewNotification {
+        kind,
+        title,
+        body,
+        entity_type: Some("task".to_string()),
+        entity_id: Some(task.id),
+        data: Some(serde_json::json!({ "taskKind": task.kind })),
+    })
+}
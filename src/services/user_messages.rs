@@ -0,0 +1,120 @@
+//! Patron messages service: staff notes surfaced at checkout and in the self-service account
+
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::user_message::{CreateUserMessage, UserMessage},
+    repository::UserMessagesRepository,
+};
+
+/// Longest message body, in characters
+const MAX_BODY_CHARS: usize = 2000;
+
+#[derive(Clone)]
+pub struct UserMessagesService {
+    repository: Arc<dyn UserMessagesRepository>,
+}
+
+impl UserMessagesService {
+    pub fn new(repository: Arc<dyn UserMessagesRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Messages of a patron; the patron's own view only has patron-visible messages
+    pub async fn list(&self, user_id: i64, include_acknowledged: bool, patron_view: bool) -> AppResult<Vec<UserMessage>> {
+        self.repository
+            .user_messages_list(user_id, !include_acknowledged, patron_view)
+            .await
+    }
+
+    /// Unacknowledged, unexpired messages (shown at checkout)
+    pub async fn pending(&self, user_id: i64) -> AppResult<Vec<UserMessage>> {
+        self.repository.user_messages_list(user_id, true, false).await
+    }
+
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn create(&self, user_id: i64, data: &CreateUserMessage, created_by: i64) -> AppResult<UserMessage> {
+        let body = data.body.trim();
+        if body.is_empty() {
+            return Err(AppError::Validation("body cannot be empty".to_string()));
+        }
+        if body.chars().count() > MAX_BODY_CHARS {
+            return Err(AppError::Validation(format!(
+                "body must be at most {} characters",
+                MAX_BODY_CHARS
+            )));
+        }
+        if data.expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(AppError::Validation("expiresAt must be in the future".to_string()));
+        }
+        let data = CreateUserMessage { body: body.to_string(), ..data.clone() };
+        self.repository.user_messages_create(user_id, &data, created_by).await
+    }
+
+    pub async fn delete(&self, user_id: i64, id: i64) -> AppResult<()> {
+        self.repository.user_messages_delete(user_id, id).await
+    }
+
+    /// Acknowledge a message (idempotent). A patron can only acknowledge the messages shown to them.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn acknowledge(&self, user_id: i64, id: i64, acknowledged_by: i64, patron_view: bool) -> AppResult<UserMessage> {
+        let message = self.repository.user_messages_get(user_id, id).await?;
+        if patron_view && !message.patron_visible {
+            return Err(AppError::NotFound(format!("Message {} not found", id)));
+        }
+        if message.acknowledged_at.is_some() {
+            return Ok(message);
+        }
+        match self.repository.user_messages_acknowledge(user_id, id, acknowledged_by).await? {
+            Some(message) => Ok(message),
+            // Acknowledged concurrently
+            None => self.repository.user_messages_get(user_id, id).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::user_message::UserMessageSeverity, repository::user_messages::MockUserMessagesRepository};
+
+    fn message(patron_visible: bool) -> UserMessage {
+        UserMessage {
+            id: 7,
+            user_id: 3,
+            body: "Bring back the DVD case".to_string(),
+            severity: UserMessageSeverity::Info,
+            patron_visible,
+            expires_at: None,
+            created_by: Some(1),
+            created_at: Utc::now(),
+            acknowledged_at: None,
+            acknowledged_by: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn create_rejects_blank_body_and_past_expiry() {
+        let service = UserMessagesService::new(Arc::new(MockUserMessagesRepository::new()));
+        let data = |body: &str, expires_at| CreateUserMessage {
+            body: body.to_string(),
+            severity: UserMessageSeverity::Blocking,
+            patron_visible: false,
+            expires_at,
+        };
+        assert!(matches!(service.create(3, &data("  ", None), 1).await, Err(AppError::Validation(_))));
+        let past = Some(Utc::now() - chrono::Duration::hours(1));
+        assert!(matches!(service.create(3, &data("Note", past), 1).await, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn patrons_cannot_acknowledge_staff_only_messages() {
+        let mut repo = MockUserMessagesRepository::new();
+        repo.expect_user_messages_get().returning(|_, _| Ok(message(false)));
+        let service = UserMessagesService::new(Arc::new(repo));
+        assert!(matches!(service.acknowledge(3, 7, 3, true).await, Err(AppError::NotFound(_))));
+    }
+}
@@ -4,7 +4,7 @@ use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
-use chrono::{NaiveDate, Utc};
+use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 
 use std::collections::HashSet;
@@ -15,19 +15,41 @@ use crate::{
     error::{AppError, AppResult},
     models::{
         user::{
-            AccountTypeSlug, UpdateProfile, User, UserClaims, UserPayload, UserQuery, UserShort,
-            UserStatus, SCOPE_CHANGE_PASSWORD,
+            AccountTypeSlug, MergeUsers, RegenerateRecoveryCodes, RenewalInvitationStatus, UpdateProfile, User,
+            UserActivity, UserActivityKind, UserClaims, UserContactMerge, UserExpiryCampaign,
+            UserExpiryCampaignReport, UserExpiryOutcome, UserMergeConflict, UserMergeReport, UserPayload,
+            UserQuery, UserShort,
+            UserStatus,
+            SCOPE_CHANGE_PASSWORD, SCOPE_SELF_SERVICE,
         },
-        Sex,
+        Language, Sex,
     },
     repository::Repository,
+    services::{redis::TwoFactorCodeCheck, task_manager::TaskHandle},
 };
 
+/// Wrong PINs in a row before barcode login is locked
+const PIN_MAX_ATTEMPTS: i16 = 5;
+/// Barcode login lock duration after too many wrong PINs
+const PIN_LOCK_MINUTES: i64 = 15;
+/// Wrong 2FA or recovery codes in a row before verification locks
+const TWO_FACTOR_FREE_ATTEMPTS: i16 = 5;
+/// First 2FA lockout; each further wrong code doubles it
+const TWO_FACTOR_LOCK_BASE_MINUTES: i64 = 1;
+/// Longest 2FA lockout
+const TWO_FACTOR_LOCK_MAX_MINUTES: i64 = 24 * 60;
+/// Lifetime of barcode + PIN (self-service) tokens: one kiosk session
+pub const SELF_SERVICE_TOKEN_SECONDS: i64 = 15 * 60;
+/// Lifetime of impersonation tokens (no refresh: start a new session when it expires)
+pub const IMPERSONATION_TOKEN_SECONDS: i64 = 30 * 60;
+
 #[derive(Clone)]
 pub struct UsersService {
     repository: Repository,
     config: UsersConfig,
     redis: crate::services::redis::RedisService,
+    email: crate::email::EmailService,
+    password_policy: crate::services::password_policy::PasswordPolicy,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -40,8 +62,14 @@ struct PasswordResetClaims {
 }
 
 impl UsersService {
-    pub fn new(repository: Repository, config: UsersConfig, redis: crate::services::redis::RedisService) -> Self {
-        Self { repository, config, redis }
+    pub fn new(
+        repository: Repository,
+        config: UsersConfig,
+        redis: crate::services::redis::RedisService,
+        email: crate::email::EmailService,
+    ) -> Self {
+        let password_policy = crate::services::password_policy::PasswordPolicy::new(config.password_policy.clone());
+        Self { repository, config, redis, email, password_policy }
     }
 
     /// Authenticate user by login and return JWT token
@@ -58,7 +86,7 @@ impl UsersService {
             if status == UserStatus::Blocked {
                 return Err(AppError::Authentication("Account is blocked".to_string()));
             }
-            if status == UserStatus::Deleted {
+            if status.is_deleted() {
                 return Err(AppError::Authentication("Invalid login or password".to_string()));
             }
         }
@@ -88,43 +116,70 @@ impl UsersService {
         Ok((Some(token), user))
     }
 
-    /// Verify 2FA code and return JWT token
+    /// Open an email 2FA challenge after a successful password check.
+    /// Returns the challenge id (for the client) and the code to send.
     #[tracing::instrument(skip(self), err)]
-    pub async fn verify_2fa(&self, user_id: i64, code: &str, device_id: Option<&str>, trust_device: bool) -> AppResult<String> {
+    pub async fn start_email_2fa(&self, user_id: i64) -> AppResult<(String, String)> {
+        let challenge_id = uuid::Uuid::new_v4().to_string();
+        let code = generate_email_2fa_code();
+        self.redis
+            .store_2fa_challenge(&challenge_id, user_id, &code, self.config.email_2fa_code_ttl_seconds)
+            .await?;
+        Ok((challenge_id, code))
+    }
+
+    /// Verify 2FA code and return JWT token. Email codes are checked against `challenge_id`
+    /// (from the login response), or the user's latest challenge when it is omitted.
+    #[tracing::instrument(skip(self, code), err)]
+    pub async fn verify_2fa(
+        &self,
+        user_id: i64,
+        challenge_id: Option<&str>,
+        code: &str,
+        device_id: Option<&str>,
+        trust_device: bool,
+    ) -> AppResult<String> {
         let user = self.repository.users_get_by_id(user_id).await?;
 
         if !user.two_factor_enabled.unwrap_or(false) {
             return Err(AppError::Validation("2FA is not enabled for this user".to_string()));
         }
 
+        self.ensure_two_factor_unlocked(user_id).await?;
+
         let method = user.two_factor_method.as_deref().unwrap_or("totp");
 
         let is_valid = match method {
-            "totp" => {
-                if let Some(ref secret) = user.totp_secret {
-                    // Decode base32 secret to get original bytes
-                    let secret_bytes = base32::decode(base32::Alphabet::RFC4648 { padding: false }, secret)
-                        .ok_or_else(|| AppError::Internal("Invalid TOTP secret format".to_string()))?;
-                    
-                    let now = Utc::now().timestamp() as i64;
-                    // totp_custom(step, digits, secret, time)
-                    let totp_code = totp_custom::<sha1::Sha1>(30, 6, &secret_bytes, now as u64);
-                    
-                    code == totp_code
-                } else {
-                    false
-                }
-            }
+            "totp" => totp_matches(&user, code)?,
             "email" => {
-                // Verify email code from Redis
-                self.redis.verify_2fa_code(user_id, code).await?
+                let check = self
+                    .redis
+                    .verify_2fa_challenge(user_id, challenge_id, code, self.config.email_2fa_max_attempts)
+                    .await?;
+                match check {
+                    TwoFactorCodeCheck::Valid => true,
+                    TwoFactorCodeCheck::Invalid => false,
+                    TwoFactorCodeCheck::TooManyAttempts => {
+                        self.record_two_factor_failure(&user).await?;
+                        return Err(AppError::Authentication(
+                            "Too many invalid 2FA codes; sign in again to receive a new code".to_string(),
+                        ))
+                    }
+                    TwoFactorCodeCheck::Expired => {
+                        return Err(AppError::Authentication(
+                            "2FA code expired; sign in again to receive a new code".to_string(),
+                        ))
+                    }
+                }
             }
             _ => return Err(AppError::Validation("Invalid 2FA method".to_string())),
         };
 
         if !is_valid {
+            self.record_two_factor_failure(&user).await?;
             return Err(AppError::Authentication("Invalid 2FA code".to_string()));
         }
+        self.repository.users_reset_two_factor_failures(user_id).await?;
 
         // If device_id is provided and trust_device is true, store the device as trusted
         if trust_device {
@@ -146,6 +201,8 @@ impl UsersService {
             return Err(AppError::Validation("2FA is not enabled for this user".to_string()));
         }
 
+        self.ensure_two_factor_unlocked(user_id).await?;
+
         let recovery_codes: Vec<String> = user
             .recovery_codes
             .as_ref()
@@ -159,12 +216,15 @@ impl UsersService {
             .unwrap_or_default();
 
         if !recovery_codes.contains(&code.to_string()) {
+            self.record_two_factor_failure(&user).await?;
             return Err(AppError::Authentication("Invalid recovery code".to_string()));
         }
 
         if used_codes.contains(&code.to_string()) {
+            self.record_two_factor_failure(&user).await?;
             return Err(AppError::Authentication("Recovery code has already been used".to_string()));
         }
+        self.repository.users_reset_two_factor_failures(user_id).await?;
 
         // Mark code as used
         let mut new_used_codes = used_codes;
@@ -178,6 +238,174 @@ impl UsersService {
         self.token_respecting_password_policy(&user).await
     }
 
+    /// Replace the recovery codes of a user with 2FA enabled; the previous ones stop working.
+    ///
+    /// Confirmed with the account password or a current TOTP code (a wrong code counts towards the
+    /// 2FA lockout), so a stolen access token alone cannot replace them.
+    #[tracing::instrument(skip(self, confirmation), err)]
+    pub async fn regenerate_recovery_codes(
+        &self,
+        user_id: i64,
+        confirmation: &RegenerateRecoveryCodes,
+    ) -> AppResult<Vec<String>> {
+        let user = self.repository.users_get_by_id(user_id).await?;
+        if !user.two_factor_enabled.unwrap_or(false) {
+            return Err(AppError::Validation("2FA is not enabled for this user".to_string()));
+        }
+        match (confirmation.current_password.as_deref(), confirmation.code.as_deref()) {
+            (Some(password), _) => {
+                if !self.verify_password(&user, password)? {
+                    return Err(AppError::Authentication("Current password is incorrect".to_string()));
+                }
+            }
+            (None, Some(code)) => {
+                if user.two_factor_method.as_deref().unwrap_or("totp") != "totp" {
+                    return Err(AppError::Validation(
+                        "A 2FA code only confirms TOTP accounts; send currentPassword".to_string(),
+                    ));
+                }
+                self.ensure_two_factor_unlocked(user_id).await?;
+                if !totp_matches(&user, code)? {
+                    self.record_two_factor_failure(&user).await?;
+                    return Err(AppError::Authentication("Invalid 2FA code".to_string()));
+                }
+                self.repository.users_reset_two_factor_failures(user_id).await?;
+            }
+            (None, None) => {
+                return Err(AppError::Validation("currentPassword or code is required".to_string()));
+            }
+        }
+        let recovery_codes = self.generate_recovery_codes(10);
+        let recovery_codes_json = serde_json::to_string(&recovery_codes)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize recovery codes: {}", e)))?;
+        self.repository.users_replace_recovery_codes(user_id, &recovery_codes_json).await?;
+        Ok(recovery_codes)
+    }
+
+    /// Refuse 2FA and recovery code checks while the user is locked out
+    async fn ensure_two_factor_unlocked(&self, user_id: i64) -> AppResult<()> {
+        let locked_until = self.repository.users_two_factor_locked_until(user_id).await?;
+        if let Some(until) = locked_until.filter(|until| *until > Utc::now()) {
+            return Err(AppError::Authentication(format!(
+                "Too many invalid codes; try again after {}",
+                until.format("%Y-%m-%d %H:%M UTC")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Count a wrong 2FA or recovery code; the user is warned by email when the lockout starts.
+    async fn record_two_factor_failure(&self, user: &User) -> AppResult<()> {
+        let (failures, locked_until) = self
+            .repository
+            .users_record_two_factor_failure(
+                user.id,
+                TWO_FACTOR_FREE_ATTEMPTS,
+                TWO_FACTOR_LOCK_BASE_MINUTES,
+                TWO_FACTOR_LOCK_MAX_MINUTES,
+            )
+            .await?;
+        if failures != TWO_FACTOR_FREE_ATTEMPTS {
+            return Ok(());
+        }
+        tracing::warn!("2FA verification locked for user {} after {} wrong codes", user.id, failures);
+        if let (Some(to), Some(until)) = (user.email.clone(), locked_until) {
+            let email = self.email.clone();
+            let language = user.language;
+            // Do not let the mail delay (and reveal) the verification answer
+            tokio::spawn(async move {
+                let until = until.format("%Y-%m-%d %H:%M UTC").to_string();
+                if let Err(e) = email.send_2fa_failures_alert(&to, failures, &until, language).await {
+                    tracing::warn!("Failed to send 2FA lockout alert: {}", e);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Authenticate a patron by card barcode + PIN and return a self-service token.
+    ///
+    /// Staff accounts are refused; repeated wrong PINs lock barcode login for a while.
+    #[tracing::instrument(skip(self, pin), err)]
+    pub async fn authenticate_barcode(&self, barcode: &str, pin: &str) -> AppResult<(String, User)> {
+        let invalid = || AppError::Authentication("Invalid card or PIN".to_string());
+
+        let barcode = barcode.trim();
+        if barcode.is_empty() {
+            return Err(invalid());
+        }
+        // A barcode shared by several accounts cannot identify anyone
+        let mut states = self.repository.users_pin_states_by_barcode(barcode).await?;
+        if states.len() != 1 {
+            return Err(invalid());
+        }
+        let state = states.remove(0);
+
+        if state.pin_locked_until.is_some_and(|until| until > Utc::now()) {
+            return Err(AppError::Authentication(
+                "Too many wrong PINs; barcode login is temporarily locked".to_string(),
+            ));
+        }
+        let Some(pin_hash) = state.pin_hash.as_deref() else {
+            return Err(invalid());
+        };
+        let pin_valid = PasswordHash::new(pin_hash)
+            .map(|hash| Argon2::default().verify_password(pin.as_bytes(), &hash).is_ok())
+            .map_err(|_| AppError::Internal("Invalid PIN hash".to_string()))?;
+        if !pin_valid {
+            self.repository
+                .users_record_pin_failure(state.id, PIN_MAX_ATTEMPTS, PIN_LOCK_MINUTES)
+                .await?;
+            return Err(invalid());
+        }
+
+        let user = self.repository.users_get_by_id(state.id).await?;
+        if user.status == Some(UserStatus::PendingDeletion) {
+            return Err(invalid());
+        }
+        if user.status == Some(UserStatus::Blocked) {
+            return Err(AppError::Authentication("Account is blocked".to_string()));
+        }
+        if is_staff_account(&user.account_type) {
+            return Err(AppError::Authorization(
+                "Staff accounts cannot use barcode login".to_string(),
+            ));
+        }
+
+        self.repository.users_reset_pin_failures(user.id).await?;
+        let token = self.create_token_with_scope(&user, Some(SCOPE_SELF_SERVICE)).await?;
+        Ok((token, user))
+    }
+
+    /// Set or clear a patron's self-service PIN (`None` disables barcode login)
+    #[tracing::instrument(skip(self, pin), err)]
+    pub async fn set_pin(&self, user_id: i64, pin: Option<&str>) -> AppResult<()> {
+        let user = self.repository.users_get_by_id(user_id).await?;
+        let pin_hash = match pin {
+            Some(pin) => {
+                validate_pin(pin)?;
+                if is_staff_account(&user.account_type) {
+                    return Err(AppError::BusinessRule(
+                        "Staff accounts cannot have a self-service PIN".to_string(),
+                    ));
+                }
+                Some(self.hash_password(pin)?)
+            }
+            None => None,
+        };
+        self.repository.users_set_pin(user_id, pin_hash).await
+    }
+
+    /// Change own PIN, confirmed with the account password
+    #[tracing::instrument(skip(self, current_password, pin), err)]
+    pub async fn change_own_pin(&self, user_id: i64, current_password: &str, pin: Option<&str>) -> AppResult<()> {
+        let user = self.repository.users_get_by_id(user_id).await?;
+        if !self.verify_password(&user, current_password)? {
+            return Err(AppError::Authentication("Current password is incorrect".to_string()));
+        }
+        self.set_pin(user_id, pin).await
+    }
+
     /// Create a full JWT token for a user (no scope restrictions).
     async fn create_token_for_user(&self, user: &User) -> AppResult<String> {
         self.create_token_with_scope(user, None).await
@@ -193,14 +421,15 @@ impl UsersService {
     ///
     /// When `scope` is `Some(SCOPE_CHANGE_PASSWORD)`, the token is short-lived
     /// (1 hour) and can only be used at `POST /auth/change-password`.
+    /// `SCOPE_SELF_SERVICE` tokens last one kiosk session ([`SELF_SERVICE_TOKEN_SECONDS`]).
     async fn create_token_with_scope(&self, user: &User, scope: Option<&str>) -> AppResult<String> {
         let rights = self.repository.users_get_rights(&user.account_type).await?;
 
         let now = Utc::now().timestamp();
-        let exp = if scope.is_some() {
-            now + 3600 // 1-hour window to complete the password change
-        } else {
-            now + (self.config.jwt_expiration_hours as i64 * 3600)
+        let exp = match scope {
+            Some(SCOPE_SELF_SERVICE) => now + SELF_SERVICE_TOKEN_SECONDS,
+            Some(_) => now + 3600, // 1-hour window to complete the password change
+            None => now + (self.config.jwt_expiration_hours as i64 * 3600),
         };
 
         let claims = UserClaims {
@@ -211,6 +440,7 @@ impl UsersService {
             exp,
             iat: now,
             scope: scope.map(str::to_owned),
+            impersonated_by: None,
         };
 
         claims
@@ -218,6 +448,43 @@ impl UsersService {
             .map_err(|e| AppError::Internal(format!("Failed to create token: {}", e)))
     }
 
+    /// Issue a token letting admin `admin_id` act as patron `user_id` ([`IMPERSONATION_TOKEN_SECONDS`]).
+    ///
+    /// The token carries the patron's rights and `impersonatedBy`; staff accounts cannot be
+    /// impersonated.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn impersonate(&self, admin_id: i64, user_id: i64) -> AppResult<(String, User)> {
+        if admin_id == user_id {
+            return Err(AppError::BusinessRule("Cannot impersonate yourself".to_string()));
+        }
+        let user = self.repository.users_get_by_id(user_id).await?;
+        if user.status.is_some_and(|s| s.is_deleted()) {
+            return Err(AppError::NotFound(format!("User {} not found", user_id)));
+        }
+        if is_staff_account(&user.account_type) {
+            return Err(AppError::BusinessRule(
+                "Staff accounts cannot be impersonated".to_string(),
+            ));
+        }
+
+        let rights = self.repository.users_get_rights(&user.account_type).await?;
+        let now = Utc::now().timestamp();
+        let claims = UserClaims {
+            sub: user.login.clone().unwrap_or_default(),
+            user_id: user.id,
+            account_type: user.account_type.clone(),
+            rights,
+            exp: now + IMPERSONATION_TOKEN_SECONDS,
+            iat: now,
+            scope: None,
+            impersonated_by: Some(admin_id),
+        };
+        let token = claims
+            .create_token(&self.config.jwt_secret)
+            .map_err(|e| AppError::Internal(format!("Failed to create token: {}", e)))?;
+        Ok((token, user))
+    }
+
     /// Return a scoped token if the user must change their password, otherwise a full token.
     async fn token_respecting_password_policy(&self, user: &User) -> AppResult<String> {
         if user.must_change_password {
@@ -340,11 +607,32 @@ impl UsersService {
         self.repository.users_get_by_id(id).await
     }
 
+    /// User record shown to staff: the user with the deliverability of their email address
+    pub async fn get_record(&self, id: i64) -> AppResult<User> {
+        let mut user = self.repository.users_get_by_id(id).await?;
+        if let Some(email) = user.email.as_deref().filter(|e| !e.trim().is_empty()) {
+            user.email_health = self.repository.email_health_get(email).await?;
+        }
+        Ok(user)
+    }
+
     /// Search users
     pub async fn search_users(&self, query: &UserQuery) -> AppResult<(Vec<UserShort>, i64)> {
         self.repository.users_search(query).await
     }
 
+    /// Merged activity timeline of a user (404 when the user does not exist)
+    pub async fn activity(
+        &self,
+        user_id: i64,
+        kind: Option<UserActivityKind>,
+        page: i64,
+        per_page: i64,
+    ) -> AppResult<(Vec<UserActivity>, i64)> {
+        self.repository.users_get_by_id(user_id).await?;
+        self.repository.users_activity(user_id, kind, page, per_page).await
+    }
+
     /// Create a new user
     #[tracing::instrument(skip(self), err)]
     pub async fn create_user(&self, mut user: UserPayload) -> AppResult<User> {
@@ -357,8 +645,10 @@ impl UsersService {
             .filter(|s| !s.is_empty())
             .ok_or_else(|| AppError::Validation("Login is required".to_string()))?;
         if login.len() < 3 {
-            return Err(AppError::Validation(
-                "Login must be at least 3 characters".to_string(),
+            return Err(AppError::invalid_field(
+                "login",
+                "length",
+                "Login must be at least 3 characters",
             ));
         }
 
@@ -370,6 +660,8 @@ impl UsersService {
 
         // Hash password if provided
         let password = if let Some(ref password) = user.password {
+            let account_type = user.account_type.clone().unwrap_or(AccountTypeSlug::Guest);
+            self.password_policy.check(password, &account_type, "password").await?;
             Some(self.hash_password(password)?)
         } else {
             None
@@ -386,7 +678,7 @@ impl UsersService {
         // user.validate_required_patron_fields()?;
 
         // Check if user exists
-        self.repository.users_get_by_id(id).await?;
+        let existing = self.repository.users_get_by_id(id).await?;
 
         // Check if login already exists for another user (login is required and unique)
         if let Some(ref login) = user.login {
@@ -396,8 +688,10 @@ impl UsersService {
         }
         // Email is optional, no uniqueness check needed
 
-        // Hash password if provided
+        // Hash password if provided, under the rules of the account type it will have
         let password = if let Some(ref password) = user.password {
+            let account_type = user.account_type.as_ref().unwrap_or(&existing.account_type);
+            self.password_policy.check(password, account_type, "password").await?;
             Some(self.hash_password(password)?)
         } else {
             None
@@ -406,10 +700,166 @@ impl UsersService {
         self.repository.users_update(id, &user, password).await
     }
 
-    /// Delete a user
+    /// Delete a user.
+    ///
+    /// With a grace period (`users.deletion_grace_days`) the account only becomes
+    /// `pendingDeletion` and the returned date is when the retention job anonymizes it; until
+    /// then [`Self::restore_user`] brings it back. `immediate` (or no grace period) anonymizes
+    /// right away and returns `None`.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete_user(&self, id: i64, force: bool, immediate: bool) -> AppResult<Option<DateTime<Utc>>> {
+        if immediate || self.config.deletion_grace_days == 0 {
+            self.repository.users_delete(id, force).await?;
+            return Ok(None);
+        }
+        let scheduled_at = Utc::now() + Duration::days(i64::from(self.config.deletion_grace_days));
+        let user = self.repository.users_schedule_deletion(id, force, scheduled_at).await?;
+        Ok(user.deletion_scheduled_at)
+    }
+
+    /// Cancel the pending deletion of a user
+    #[tracing::instrument(skip(self), err)]
+    pub async fn restore_user(&self, id: i64) -> AppResult<User> {
+        self.repository.users_restore(id).await
+    }
+
+    /// Anonymize the accounts whose grace period has lapsed (nightly retention job)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn purge_pending_deletions(&self) -> AppResult<Vec<i64>> {
+        self.repository.users_purge_pending_deletions().await
+    }
+
+    /// Merge a duplicate patron account into the surviving one.
+    ///
+    /// The survivor keeps its own contact data; empty fields are filled from the duplicate and
+    /// differing values are reported as conflicts. Staff accounts cannot be merged.
     #[tracing::instrument(skip(self), err)]
-    pub async fn delete_user(&self, id: i64, force: bool) -> AppResult<()> {
-        self.repository.users_delete(id, force).await
+    pub async fn merge_users(&self, request: &MergeUsers) -> AppResult<UserMergeReport> {
+        if request.survivor_id == request.duplicate_id {
+            return Err(AppError::Validation("Cannot merge a user into itself".to_string()));
+        }
+        let survivor = self.repository.users_get_by_id(request.survivor_id).await?;
+        let duplicate = self.repository.users_get_by_id(request.duplicate_id).await?;
+        for user in [&survivor, &duplicate] {
+            if user.status.is_some_and(|s| s.is_deleted()) {
+                return Err(AppError::NotFound(format!("User with id {} not found", user.id)));
+            }
+            if is_staff_account(&user.account_type) {
+                return Err(AppError::BusinessRule("Staff accounts cannot be merged".to_string()));
+            }
+        }
+
+        let contact = merge_contact(&survivor, &duplicate);
+        let moved = self
+            .repository
+            .users_merge(survivor.id, duplicate.id, &contact, request.dry_run)
+            .await?;
+
+        let survivor = if request.dry_run {
+            let mut preview = survivor;
+            preview.barcode = contact.barcode;
+            preview.email = contact.email;
+            preview.phone = contact.phone;
+            preview.addr_street = contact.addr_street;
+            preview.addr_zip_code = contact.addr_zip_code;
+            preview.addr_city = contact.addr_city;
+            preview.birthdate = contact.birthdate;
+            preview.sex = contact.sex;
+            preview.public_type = contact.public_type;
+            preview.expiry_at = contact.expiry_at;
+            preview.notes = contact.notes;
+            preview
+        } else {
+            self.repository.users_get_by_id(survivor.id).await?
+        };
+
+        Ok(UserMergeReport {
+            dry_run: request.dry_run,
+            survivor,
+            duplicate_id: duplicate.id,
+            moved,
+            filled: contact.filled,
+            conflicts: contact.conflicts,
+        })
+    }
+
+    /// Run a membership expiry campaign: set the end date of the targeted accounts, then email
+    /// each of them a renewal invitation (one every `smtp_throttle`). A failed invitation is
+    /// reported on its account and does not stop the campaign.
+    #[tracing::instrument(skip(self, task_handle), err)]
+    pub async fn run_expiry_campaign(
+        &self,
+        campaign: &UserExpiryCampaign,
+        smtp_throttle: std::time::Duration,
+        task_handle: Option<TaskHandle>,
+    ) -> AppResult<UserExpiryCampaignReport> {
+        campaign.validate_targeting()?;
+        let targets = self.repository.users_expiry_campaign_targets(campaign).await?;
+        let updated = if campaign.dry_run || targets.is_empty() {
+            0
+        } else {
+            let ids: Vec<i64> = targets.iter().map(|t| t.id).collect();
+            self.repository.users_set_expiry(&ids, campaign.expiry_at).await? as u32
+        };
+
+        let expiry_date = campaign.expiry_at.format("%d/%m/%Y").to_string();
+        let renewal_url = campaign.renewal_url.as_deref().unwrap_or_default();
+        let total = targets.len();
+        let mut invited: u32 = 0;
+        let mut accounts = Vec::with_capacity(total);
+        for (i, target) in targets.into_iter().enumerate() {
+            let mut error = None;
+            let invitation = match target.email.as_deref().filter(|e| !e.trim().is_empty()) {
+                _ if !campaign.send_invitations || campaign.dry_run => RenewalInvitationStatus::NotRequested,
+                None => RenewalInvitationStatus::NoEmail,
+                Some(to) => {
+                    let sent = self
+                        .email
+                        .send_membership_renewal(
+                            to,
+                            target.firstname.as_deref().unwrap_or_default(),
+                            target.lastname.as_deref().unwrap_or_default(),
+                            &expiry_date,
+                            renewal_url,
+                            target.language.as_deref().map(Language::from),
+                        )
+                        .await;
+                    tokio::time::sleep(smtp_throttle).await;
+                    match sent {
+                        Ok(()) => {
+                            invited += 1;
+                            RenewalInvitationStatus::Sent
+                        }
+                        Err(e) => {
+                            tracing::warn!("Renewal invitation to user {} failed: {}", target.id, e);
+                            error = Some(e.to_string());
+                            RenewalInvitationStatus::Failed
+                        }
+                    }
+                }
+            };
+            accounts.push(UserExpiryOutcome {
+                user_id: target.id,
+                login: target.login,
+                firstname: target.firstname,
+                lastname: target.lastname,
+                previous_expiry_at: target.expiry_at,
+                expiry_at: if campaign.dry_run { target.expiry_at } else { Some(campaign.expiry_at) },
+                invitation,
+                error,
+            });
+            if let Some(handle) = &task_handle {
+                handle.set_progress(i + 1, total, None).await;
+            }
+        }
+
+        Ok(UserExpiryCampaignReport {
+            dry_run: campaign.dry_run,
+            matched: total as u32,
+            updated,
+            invited,
+            accounts,
+        })
     }
 
     /// Update user's own profile (name, password)
@@ -438,6 +888,7 @@ impl UsersService {
 
         // Hash new password if provided
         let password = if let Some(ref new_password) = profile.new_password {
+            self.password_policy.check(new_password, &user.account_type, "newPassword").await?;
             Some(self.hash_password(new_password)?)
         } else {
             None
@@ -520,6 +971,8 @@ impl UsersService {
             return Err(AppError::Authentication("Invalid reset token purpose".to_string()));
         }
 
+        let user = self.repository.users_get_by_id(claims.user_id).await?;
+        self.password_policy.check(new_password, &user.account_type, "newPassword").await?;
         let hash = self.hash_password(new_password)?;
         self.repository.users_update_password(claims.user_id, &hash).await
     }
@@ -532,9 +985,8 @@ impl UsersService {
     /// is returned.
     #[tracing::instrument(skip(self), err)]
     pub async fn change_password_first_login(&self, user_id: i64, new_password: &str) -> AppResult<String> {
-        if new_password.len() < 4 {
-            return Err(AppError::Validation("Password must be at least 4 characters".to_string()));
-        }
+        let user = self.repository.users_get_by_id(user_id).await?;
+        self.password_policy.check(new_password, &user.account_type, "newPassword").await?;
 
         let hash = self.hash_password(new_password)?;
         // users_update_password also resets must_change_password = false
@@ -568,3 +1020,237 @@ impl UsersService {
 
 }
 
+fn is_staff_account(account_type: &AccountTypeSlug) -> bool {
+    matches!(account_type, AccountTypeSlug::Librarian | AccountTypeSlug::Admin)
+}
+
+/// Contact data of `survivor` after absorbing `duplicate`: survivor values win, empty ones are
+/// filled from the duplicate, the later membership expiry is kept and notes are appended.
+fn merge_contact(survivor: &User, duplicate: &User) -> UserContactMerge {
+    let mut merged = UserContactMerge::default();
+    let (filled, conflicts) = (&mut merged.filled, &mut merged.conflicts);
+
+    merged.barcode = merge_text("barcode", &survivor.barcode, &duplicate.barcode, false, filled, conflicts);
+    merged.email = merge_text("email", &survivor.email, &duplicate.email, true, filled, conflicts);
+    merged.phone = merge_text("phone", &survivor.phone, &duplicate.phone, false, filled, conflicts);
+    merged.addr_street = merge_text("addrStreet", &survivor.addr_street, &duplicate.addr_street, false, filled, conflicts);
+    merged.addr_zip_code = merge_value("addrZipCode", survivor.addr_zip_code, duplicate.addr_zip_code, filled, conflicts);
+    merged.addr_city = merge_text("addrCity", &survivor.addr_city, &duplicate.addr_city, false, filled, conflicts);
+    merged.birthdate = merge_value("birthdate", survivor.birthdate, duplicate.birthdate, filled, conflicts);
+    merged.sex = merge_value("sex", survivor.sex, duplicate.sex, filled, conflicts);
+    merged.public_type = merge_value("publicType", survivor.public_type, duplicate.public_type, filled, conflicts);
+
+    merged.expiry_at = match (survivor.expiry_at, duplicate.expiry_at) {
+        (Some(kept), Some(other)) if other > kept => {
+            filled.push("expiryAt".to_string());
+            Some(other)
+        }
+        (None, Some(other)) => {
+            filled.push("expiryAt".to_string());
+            Some(other)
+        }
+        (kept, _) => kept,
+    };
+
+    let survivor_notes = non_empty(&survivor.notes);
+    merged.notes = match (survivor_notes, non_empty(&duplicate.notes)) {
+        (Some(kept), Some(other)) if kept != other => {
+            filled.push("notes".to_string());
+            Some(format!("{kept}\n{other}"))
+        }
+        (None, Some(other)) => {
+            filled.push("notes".to_string());
+            Some(other.to_string())
+        }
+        _ => survivor_notes.map(str::to_string),
+    };
+
+    merged
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn merge_text(
+    field: &str,
+    kept: &Option<String>,
+    other: &Option<String>,
+    ignore_case: bool,
+    filled: &mut Vec<String>,
+    conflicts: &mut Vec<UserMergeConflict>,
+) -> Option<String> {
+    match (non_empty(kept), non_empty(other)) {
+        (Some(kept), Some(other)) => {
+            let same = if ignore_case { kept.eq_ignore_ascii_case(other) } else { kept == other };
+            if !same {
+                conflicts.push(UserMergeConflict {
+                    field: field.to_string(),
+                    kept: kept.to_string(),
+                    discarded: other.to_string(),
+                });
+            }
+            Some(kept.to_string())
+        }
+        (None, Some(other)) => {
+            filled.push(field.to_string());
+            Some(other.to_string())
+        }
+        (kept, None) => kept.map(str::to_string),
+    }
+}
+
+fn merge_value<T: PartialEq + std::fmt::Display>(
+    field: &str,
+    kept: Option<T>,
+    other: Option<T>,
+    filled: &mut Vec<String>,
+    conflicts: &mut Vec<UserMergeConflict>,
+) -> Option<T> {
+    match (kept, other) {
+        (Some(kept), Some(other)) => {
+            if kept != other {
+                conflicts.push(UserMergeConflict {
+                    field: field.to_string(),
+                    kept: kept.to_string(),
+                    discarded: other.to_string(),
+                });
+            }
+            Some(kept)
+        }
+        (None, Some(other)) => {
+            filled.push(field.to_string());
+            Some(other)
+        }
+        (kept, None) => kept,
+    }
+}
+
+/// Whether `code` is the current TOTP code of `user` (false without a TOTP secret)
+fn totp_matches(user: &User, code: &str) -> AppResult<bool> {
+    let Some(secret) = user.totp_secret.as_deref() else {
+        return Ok(false);
+    };
+    // Decode base32 secret to get original bytes
+    let secret_bytes = base32::decode(base32::Alphabet::RFC4648 { padding: false }, secret)
+        .ok_or_else(|| AppError::Internal("Invalid TOTP secret format".to_string()))?;
+    // totp_custom(step, digits, secret, time)
+    let totp_code = totp_custom::<sha1::Sha1>(30, 6, &secret_bytes, Utc::now().timestamp() as u64);
+    Ok(code == totp_code)
+}
+
+/// Six random digits, leading zeros kept
+fn generate_email_2fa_code() -> String {
+    use rand::Rng;
+    format!("{:06}", rand::thread_rng().gen_range(0..1_000_000))
+}
+
+/// Self-service PINs are 4 to 8 digits
+fn validate_pin(pin: &str) -> AppResult<()> {
+    if !(4..=8).contains(&pin.len()) || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err(AppError::invalid_field("pin", "format", "PIN must be 4 to 8 digits"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn email_2fa_codes_have_six_digits() {
+        for _ in 0..100 {
+            let code = generate_email_2fa_code();
+            assert_eq!(code.len(), 6);
+            assert!(code.bytes().all(|b| b.is_ascii_digit()));
+        }
+    }
+
+    #[test]
+    fn pin_format() {
+        assert!(validate_pin("0420").is_ok());
+        assert!(validate_pin("12345678").is_ok());
+        assert!(validate_pin("123").is_err());
+        assert!(validate_pin("123456789").is_err());
+        assert!(validate_pin("12a4").is_err());
+        assert!(validate_pin("١٢٣٤").is_err());
+    }
+
+    #[test]
+    fn totp_codes_confirm_only_with_a_secret() {
+        const SECRET: &[u8] = b"elidune-totp-test-secret";
+        let code_now = || totp_custom::<sha1::Sha1>(30, 6, SECRET, Utc::now().timestamp() as u64);
+        let mut user = patron(1);
+        assert!(!totp_matches(&user, &code_now()).unwrap());
+
+        user.totp_secret = Some(base32::encode(base32::Alphabet::RFC4648 { padding: false }, SECRET));
+        let code = code_now();
+        // Retried once in case the 30 s step rolled over in between
+        assert!(totp_matches(&user, &code).unwrap() || totp_matches(&user, &code_now()).unwrap());
+        assert!(!totp_matches(&user, "not-a-code").unwrap());
+
+        user.totp_secret = Some("not base32!".to_string());
+        assert!(totp_matches(&user, &code).is_err());
+    }
+
+    fn patron(id: i64) -> User {
+        serde_json::from_value(serde_json::json!({
+            "id": id.to_string(),
+            "accountType": "reader",
+            "receiveReminders": true,
+            "mustChangePassword": false,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn merge_contact_fills_gaps_and_reports_conflicts() {
+        let mut survivor = patron(1);
+        survivor.email = Some("Jane@Example.org".to_string());
+        survivor.phone = Some("0102030405".to_string());
+        survivor.addr_city = Some("  ".to_string());
+        survivor.public_type = Some(2);
+        let mut duplicate = patron(2);
+        duplicate.barcode = Some("C-0042".to_string());
+        duplicate.email = Some("jane@example.org".to_string());
+        duplicate.phone = Some("0600000000".to_string());
+        duplicate.addr_city = Some("Lyon".to_string());
+        duplicate.public_type = Some(2);
+
+        let merged = merge_contact(&survivor, &duplicate);
+        assert_eq!(merged.barcode.as_deref(), Some("C-0042"));
+        assert_eq!(merged.email.as_deref(), Some("Jane@Example.org"));
+        assert_eq!(merged.phone.as_deref(), Some("0102030405"));
+        assert_eq!(merged.addr_city.as_deref(), Some("Lyon"));
+        assert_eq!(merged.public_type, Some(2));
+        assert_eq!(merged.filled, vec!["barcode", "addrCity"]);
+        assert_eq!(
+            merged.conflicts,
+            vec![UserMergeConflict {
+                field: "phone".to_string(),
+                kept: "0102030405".to_string(),
+                discarded: "0600000000".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn merge_contact_keeps_later_expiry_and_appends_notes() {
+        let now = Utc::now();
+        let mut survivor = patron(1);
+        survivor.expiry_at = Some(now);
+        survivor.notes = Some("Prefers large print".to_string());
+        let mut duplicate = patron(2);
+        duplicate.expiry_at = Some(now + Duration::days(200));
+        duplicate.notes = Some("Old card lost".to_string());
+
+        let merged = merge_contact(&survivor, &duplicate);
+        assert_eq!(merged.expiry_at, duplicate.expiry_at);
+        assert_eq!(merged.notes.as_deref(), Some("Prefers large print\nOld card lost"));
+        assert!(merged.conflicts.is_empty());
+
+        let unchanged = merge_contact(&duplicate, &survivor);
+        assert_eq!(unchanged.expiry_at, duplicate.expiry_at);
+        assert_eq!(unchanged.filled, vec!["notes"]);
+    }
+}
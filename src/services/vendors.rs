@@ -0,0 +1,130 @@
+//! Vendors service (suppliers, purchase orders, spend report)
+
+use std::sync::Arc;
+
+use rust_decimal::Decimal;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::vendor::{
+        CreatePurchaseOrder, CreateVendor, PurchaseOrder, PurchaseOrderQuery, UpdatePurchaseOrder,
+        UpdateVendor, Vendor, VendorAnnualSpend, VendorSpendQuery,
+    },
+    repository::{SourcesRepository, VendorsRepository},
+};
+
+#[derive(Clone)]
+pub struct VendorsService {
+    repository: Arc<dyn VendorsRepository>,
+    sources: Arc<dyn SourcesRepository>,
+}
+
+impl VendorsService {
+    pub fn new(repository: Arc<dyn VendorsRepository>, sources: Arc<dyn SourcesRepository>) -> Self {
+        Self { repository, sources }
+    }
+
+    // ---- Vendors ----
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list(&self, include_inactive: bool) -> AppResult<Vec<Vendor>> {
+        self.repository.vendors_list(include_inactive).await
+    }
+
+    pub async fn get_by_id(&self, id: i64) -> AppResult<Vendor> {
+        self.repository.vendors_get_by_id(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create(&self, data: &CreateVendor) -> AppResult<Vendor> {
+        self.validate_vendor(Some(&data.name), data.discount_rate, None).await?;
+        self.repository.vendors_create(data).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update(&self, id: i64, data: &UpdateVendor) -> AppResult<Vendor> {
+        self.validate_vendor(data.name.as_deref(), data.discount_rate, Some(id)).await?;
+        self.repository.vendors_update(id, data).await
+    }
+
+    /// Delete a vendor. Vendors with purchase orders must be deactivated instead, so spend
+    /// history is kept.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete(&self, id: i64) -> AppResult<()> {
+        let orders = self.repository.vendors_count_orders(id).await?;
+        if orders > 0 {
+            return Err(AppError::Conflict(format!(
+                "Vendor has {} purchase order(s) — deactivate it instead",
+                orders
+            )));
+        }
+        self.repository.vendors_delete(id).await
+    }
+
+    async fn validate_vendor(
+        &self,
+        name: Option<&str>,
+        discount_rate: Option<Decimal>,
+        exclude_id: Option<i64>,
+    ) -> AppResult<()> {
+        if discount_rate.is_some_and(|r| r < Decimal::ZERO || r > Decimal::ONE_HUNDRED) {
+            return Err(AppError::Validation("discountRate must be between 0 and 100".to_string()));
+        }
+        if let Some(name) = name {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(AppError::Validation("Vendor name cannot be empty".to_string()));
+            }
+            if self.repository.vendors_name_in_use(name, exclude_id).await? {
+                return Err(AppError::Conflict(format!("Vendor '{}' already exists", name)));
+            }
+        }
+        Ok(())
+    }
+
+    // ---- Purchase orders ----
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list_orders(&self, query: &PurchaseOrderQuery) -> AppResult<Vec<PurchaseOrder>> {
+        self.repository.vendors_list_orders(query).await
+    }
+
+    pub async fn get_order(&self, id: i64) -> AppResult<PurchaseOrder> {
+        self.repository.vendors_get_order(id).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create_order(&self, data: &CreatePurchaseOrder) -> AppResult<PurchaseOrder> {
+        self.repository.vendors_get_by_id(data.vendor_id).await?;
+        self.validate_order(data.source_id, Some(data.total_amount)).await?;
+        self.repository.vendors_create_order(data).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update_order(&self, id: i64, data: &UpdatePurchaseOrder) -> AppResult<PurchaseOrder> {
+        self.validate_order(data.source_id, data.total_amount).await?;
+        self.repository.vendors_update_order(id, data).await
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete_order(&self, id: i64) -> AppResult<()> {
+        self.repository.vendors_delete_order(id).await
+    }
+
+    async fn validate_order(&self, source_id: Option<i64>, total_amount: Option<Decimal>) -> AppResult<()> {
+        if total_amount.is_some_and(|a| a < Decimal::ZERO) {
+            return Err(AppError::Validation("totalAmount cannot be negative".to_string()));
+        }
+        if let Some(source_id) = source_id {
+            self.sources.sources_get_by_id(source_id).await?;
+        }
+        Ok(())
+    }
+
+    // ---- Spend report ----
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn annual_spend(&self, query: &VendorSpendQuery) -> AppResult<Vec<VendorAnnualSpend>> {
+        self.repository.vendors_annual_spend(query).await
+    }
+}
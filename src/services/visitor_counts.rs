@@ -1,23 +1,34 @@
 //! Visitor counts service
 
-use chrono::NaiveDate;
+use chrono::{Datelike, Local, NaiveDate, NaiveTime, Timelike};
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use crate::{
-    error::AppResult,
-    models::visitor_count::{CreateVisitorCount, VisitorCount},
+    error::{AppError, AppResult},
+    models::{
+        schedule::DaySchedule,
+        visitor_count::{
+            CreateVisitorCount, VisitorCount, VisitorCountAnomaly, VisitorCountAnomalyReason, VisitorStats,
+            VisitorWeekdayStats,
+        },
+    },
     repository::VisitorCountsRepository,
+    services::schedules::SchedulesService,
 };
 
+/// Longest range of the attendance statistics, in days
+const MAX_STATS_RANGE_DAYS: i64 = 3 * 366;
+
 #[derive(Clone)]
 pub struct VisitorCountsService {
     repository: Arc<dyn VisitorCountsRepository>,
+    schedules: SchedulesService,
 }
 
 impl VisitorCountsService {
-    pub fn new(repository: Arc<dyn VisitorCountsRepository>) -> Self {
-        Self { repository }
+    pub fn new(repository: Arc<dyn VisitorCountsRepository>, schedules: SchedulesService) -> Self {
+        Self { repository, schedules }
     }
 
     /// List visitor counts for a date range
@@ -39,6 +50,9 @@ impl VisitorCountsService {
     /// Create a visitor count record
     #[tracing::instrument(skip(self), err)]
     pub async fn create(&self, data: &CreateVisitorCount) -> AppResult<VisitorCount> {
+        if data.count_hour.is_some_and(|h| !(0..=23).contains(&h)) {
+            return Err(AppError::Validation("countHour must be between 0 and 23".to_string()));
+        }
         self.repository.visitor_counts_create(data).await
     }
 
@@ -47,4 +61,197 @@ impl VisitorCountsService {
     pub async fn delete(&self, id: i64) -> AppResult<()> {
         self.repository.visitor_counts_delete(id).await
     }
+
+    /// Visitor counts of a range compared with the resolved opening hours
+    /// (defaults: January 1st of the current year to today)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn stats(&self, start_date: Option<NaiveDate>, end_date: Option<NaiveDate>) -> AppResult<VisitorStats> {
+        let today = Local::now().date_naive();
+        let end = end_date.unwrap_or(today);
+        let start = match start_date {
+            Some(start) => start,
+            None => NaiveDate::from_ymd_opt(end.year(), 1, 1).unwrap_or(end),
+        };
+        if end < start {
+            return Err(AppError::Validation("endDate must not be before startDate".to_string()));
+        }
+        if (end - start).num_days() >= MAX_STATS_RANGE_DAYS {
+            return Err(AppError::Validation(format!(
+                "The range must not exceed {} days",
+                MAX_STATS_RANGE_DAYS
+            )));
+        }
+        let days = self.schedules.days(start, end).await?;
+        let counts = self.repository.visitor_counts_list(Some(start), Some(end)).await?;
+        Ok(correlate(start, end, &days, counts))
+    }
+}
+
+/// Opening hours of a day, in hours
+fn opened_hours(day: &DaySchedule) -> f64 {
+    day.hours
+        .iter()
+        .map(|h| (h.close_time - h.open_time).num_minutes() as f64 / 60.0)
+        .sum()
+}
+
+/// Why a count was registered while closed, `None` when it falls in the opening hours
+fn anomaly(count: &VisitorCount, day: Option<&DaySchedule>) -> Option<VisitorCountAnomalyReason> {
+    let Some(day) = day.filter(|d| !d.hours.is_empty()) else {
+        return Some(match day {
+            Some(d) if d.is_closure => VisitorCountAnomalyReason::Closure,
+            _ => VisitorCountAnomalyReason::NoOpeningHours,
+        });
+    };
+    let hour = count.count_hour?;
+    let bucket_start = NaiveTime::from_hms_opt(hour as u32, 0, 0)?;
+    let overlaps = day.hours.iter().any(|h| {
+        h.close_time > bucket_start && (h.open_time.hour() as i16) <= hour
+    });
+    (!overlaps).then_some(VisitorCountAnomalyReason::OutsideOpeningHours)
+}
+
+fn average(visitors: i64, hours: f64) -> Option<f64> {
+    (hours > 0.0).then(|| (visitors as f64 / hours * 100.0).round() / 100.0)
+}
+
+/// Split counts between opening and closed hours, and average the visitors per opened hour of
+/// each weekday over the open days that have counts (days without any count are not assumed empty).
+fn correlate(start: NaiveDate, end: NaiveDate, days: &[DaySchedule], mut counts: Vec<VisitorCount>) -> VisitorStats {
+    counts.sort_by_key(|c| (c.count_date, c.count_hour, c.id));
+    let by_date: HashMap<NaiveDate, &DaySchedule> = days.iter().map(|d| (d.date, d)).collect();
+
+    let mut weekdays: Vec<VisitorWeekdayStats> = (0..7)
+        .map(|day_of_week| VisitorWeekdayStats {
+            day_of_week,
+            open_days: 0,
+            counted_days: 0,
+            visitors: 0,
+            opened_hours: 0.0,
+            average_per_opened_hour: None,
+        })
+        .collect();
+    for day in days.iter().filter(|d| !d.hours.is_empty()) {
+        weekdays[day.day_of_week as usize].open_days += 1;
+    }
+
+    let mut total_visitors = 0;
+    let mut closed_hours_counts = Vec::new();
+    let mut counted_dates: Vec<NaiveDate> = Vec::new();
+    for count in counts {
+        total_visitors += count.count as i64;
+        let day = by_date.get(&count.count_date).copied();
+        match anomaly(&count, day) {
+            Some(reason) => closed_hours_counts.push(VisitorCountAnomaly { count, reason }),
+            None => {
+                if let Some(day) = day {
+                    let stats = &mut weekdays[day.day_of_week as usize];
+                    stats.visitors += count.count as i64;
+                    if counted_dates.last() != Some(&day.date) {
+                        counted_dates.push(day.date);
+                        stats.counted_days += 1;
+                        stats.opened_hours += opened_hours(day);
+                    }
+                }
+            }
+        }
+    }
+    for stats in &mut weekdays {
+        stats.average_per_opened_hour = average(stats.visitors, stats.opened_hours);
+    }
+
+    let visitors_in_opening_hours: i64 = weekdays.iter().map(|w| w.visitors).sum();
+    let counted_hours: f64 = weekdays.iter().map(|w| w.opened_hours).sum();
+    VisitorStats {
+        start_date: start,
+        end_date: end,
+        total_visitors,
+        visitors_in_opening_hours,
+        visitors_in_closed_hours: total_visitors - visitors_in_opening_hours,
+        open_days: weekdays.iter().map(|w| w.open_days).sum(),
+        opened_hours: days.iter().map(opened_hours).sum(),
+        average_per_opened_hour: average(visitors_in_opening_hours, counted_hours),
+        by_weekday: weekdays,
+        closed_hours_counts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::schedule::OpeningHours;
+
+    fn time(s: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+    }
+
+    fn day(date: &str, hours: &[(&str, &str)], is_closure: bool) -> DaySchedule {
+        let date: NaiveDate = date.parse().unwrap();
+        DaySchedule {
+            date,
+            day_of_week: date.weekday().num_days_from_monday() as i16,
+            is_closure,
+            closure_reason: None,
+            hours: hours
+                .iter()
+                .map(|(open, close)| OpeningHours { open_time: time(open), close_time: time(close) })
+                .collect(),
+        }
+    }
+
+    fn count(id: i64, date: &str, hour: Option<i16>, count: i32) -> VisitorCount {
+        VisitorCount {
+            id,
+            count_date: date.parse().unwrap(),
+            count,
+            source: Some("counter".to_string()),
+            notes: None,
+            created_at: None,
+            count_hour: hour,
+        }
+    }
+
+    #[test]
+    fn counts_are_averaged_per_opened_hour_and_closed_hours_flagged() {
+        // Monday 2026-10-05 and 2026-10-12 open 10:00-12:00 and 14:00-18:00 (6 hours),
+        // Tuesday 2026-10-06 closed (no slot), Wednesday 2026-10-07 exceptional closure
+        let days = vec![
+            day("2026-10-05", &[("10:00", "12:00"), ("14:00", "18:00")], false),
+            day("2026-10-06", &[], false),
+            day("2026-10-07", &[], true),
+            day("2026-10-12", &[("10:00", "12:00"), ("14:00", "18:00")], false),
+        ];
+        let counts = vec![
+            count(1, "2026-10-05", Some(10), 30),
+            count(2, "2026-10-05", Some(15), 60),
+            // 12:00-13:00 is the lunch break: sensor drift
+            count(3, "2026-10-05", Some(12), 4),
+            // Half-open slot hour: 17:00-18:00 is still open
+            count(4, "2026-10-05", Some(17), 30),
+            count(5, "2026-10-06", None, 12),
+            count(6, "2026-10-07", Some(11), 2),
+        ];
+        let stats = correlate(days[0].date, days[3].date, &days, counts);
+
+        assert_eq!(stats.total_visitors, 138);
+        assert_eq!(stats.visitors_in_opening_hours, 120);
+        assert_eq!(stats.visitors_in_closed_hours, 18);
+        assert_eq!(stats.open_days, 2);
+        assert_eq!(stats.opened_hours, 12.0);
+        let monday = &stats.by_weekday[0];
+        // The second Monday has no count: only the counted one is averaged
+        assert_eq!((monday.open_days, monday.counted_days, monday.visitors), (2, 1, 120));
+        assert_eq!(monday.average_per_opened_hour, Some(20.0));
+        assert_eq!(stats.average_per_opened_hour, Some(20.0));
+        assert_eq!(stats.by_weekday[1].average_per_opened_hour, None);
+        let reasons: Vec<_> = stats.closed_hours_counts.iter().map(|a| (a.count.id, a.reason)).collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (3, VisitorCountAnomalyReason::OutsideOpeningHours),
+                (5, VisitorCountAnomalyReason::NoOpeningHours),
+                (6, VisitorCountAnomalyReason::Closure),
+            ]
+        );
+    }
 }
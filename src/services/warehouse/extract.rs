@@ -0,0 +1,272 @@
+//! Fact table columns and the extract files (CSV or Parquet) built from them
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+
+use super::parquet::ParquetWriter;
+use crate::{
+    models::warehouse::{AcquisitionFact, LoanFact, VisitFact, WarehouseColumn, WarehouseFact, WarehouseFormat},
+    services::exports::escape_csv,
+};
+
+/// Column type of an extract
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Int64,
+    Bool,
+    Text,
+    Date,
+    /// UTC, microseconds
+    Timestamp,
+}
+
+impl ColumnType {
+    /// Name given in the manifest
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Int64 => "bigint",
+            Self::Bool => "boolean",
+            Self::Text => "text",
+            Self::Date => "date",
+            Self::Timestamp => "timestamp",
+        }
+    }
+}
+
+/// One cell of an extract
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Int(i64),
+    Bool(bool),
+    Text(String),
+    Date(NaiveDate),
+    Timestamp(DateTime<Utc>),
+}
+
+impl Value {
+    fn csv(&self) -> String {
+        match self {
+            Self::Null => String::new(),
+            Self::Int(v) => v.to_string(),
+            Self::Bool(v) => v.to_string(),
+            Self::Text(v) => escape_csv(v),
+            Self::Date(v) => v.format("%Y-%m-%d").to_string(),
+            Self::Timestamp(v) => v.to_rfc3339_opts(SecondsFormat::Micros, true),
+        }
+    }
+}
+
+impl From<Option<i64>> for Value {
+    fn from(v: Option<i64>) -> Self {
+        v.map_or(Self::Null, Self::Int)
+    }
+}
+
+impl From<Option<String>> for Value {
+    fn from(v: Option<String>) -> Self {
+        v.map_or(Self::Null, Self::Text)
+    }
+}
+
+impl From<Option<DateTime<Utc>>> for Value {
+    fn from(v: Option<DateTime<Utc>>) -> Self {
+        v.map_or(Self::Null, Self::Timestamp)
+    }
+}
+
+const LOAN_COLUMNS: &[(&str, ColumnType)] = &[
+    ("loan_key", ColumnType::Text),
+    ("loan_date", ColumnType::Timestamp),
+    ("due_date", ColumnType::Timestamp),
+    ("returned_at", ColumnType::Timestamp),
+    ("renewals", ColumnType::Int64),
+    ("item_id", ColumnType::Int64),
+    ("biblio_id", ColumnType::Int64),
+    ("media_type", ColumnType::Text),
+    ("audience_type", ColumnType::Text),
+    ("source_id", ColumnType::Int64),
+    ("place", ColumnType::Int64),
+    ("borrower_account_type", ColumnType::Text),
+    ("borrower_public_type", ColumnType::Text),
+    ("borrower_age_band", ColumnType::Text),
+    ("changed_at", ColumnType::Timestamp),
+];
+
+const ACQUISITION_COLUMNS: &[(&str, ColumnType)] = &[
+    ("item_id", ColumnType::Int64),
+    ("biblio_id", ColumnType::Int64),
+    ("created_at", ColumnType::Timestamp),
+    ("media_type", ColumnType::Text),
+    ("audience_type", ColumnType::Text),
+    ("source_id", ColumnType::Int64),
+    ("place", ColumnType::Int64),
+    ("archived", ColumnType::Bool),
+    ("archived_at", ColumnType::Timestamp),
+];
+
+const VISIT_COLUMNS: &[(&str, ColumnType)] = &[
+    ("visit_id", ColumnType::Int64),
+    ("count_date", ColumnType::Date),
+    ("count_hour", ColumnType::Int64),
+    ("visitors", ColumnType::Int64),
+    ("source", ColumnType::Text),
+    ("recorded_at", ColumnType::Timestamp),
+];
+
+pub fn columns(fact: WarehouseFact) -> &'static [(&'static str, ColumnType)] {
+    match fact {
+        WarehouseFact::Loans => LOAN_COLUMNS,
+        WarehouseFact::Acquisitions => ACQUISITION_COLUMNS,
+        WarehouseFact::Visits => VISIT_COLUMNS,
+    }
+}
+
+/// Columns as described in the manifest
+pub fn manifest_columns(fact: WarehouseFact) -> Vec<WarehouseColumn> {
+    columns(fact)
+        .iter()
+        .map(|(name, ty)| WarehouseColumn { name: name.to_string(), data_type: ty.name().to_string() })
+        .collect()
+}
+
+pub fn loan_row(f: LoanFact) -> Vec<Value> {
+    vec![
+        Value::Text(f.loan_key),
+        f.loan_date.into(),
+        f.due_date.into(),
+        f.returned_at.into(),
+        f.renewals.into(),
+        f.item_id.into(),
+        f.biblio_id.into(),
+        f.media_type.into(),
+        f.audience_type.into(),
+        f.source_id.into(),
+        f.place.into(),
+        f.borrower_account_type.into(),
+        f.borrower_public_type.into(),
+        f.borrower_age_band.into(),
+        Value::Timestamp(f.changed_at),
+    ]
+}
+
+pub fn acquisition_row(f: AcquisitionFact) -> Vec<Value> {
+    vec![
+        Value::Int(f.item_id),
+        f.biblio_id.into(),
+        Value::Timestamp(f.created_at),
+        f.media_type.into(),
+        f.audience_type.into(),
+        f.source_id.into(),
+        f.place.into(),
+        Value::Bool(f.archived_at.is_some()),
+        f.archived_at.into(),
+    ]
+}
+
+pub fn visit_row(f: VisitFact) -> Vec<Value> {
+    vec![
+        Value::Int(f.visit_id),
+        Value::Date(f.count_date),
+        f.count_hour.into(),
+        Value::Int(f.visitors),
+        f.source.into(),
+        Value::Timestamp(f.recorded_at),
+    ]
+}
+
+/// Extract file being written, one batch of rows at a time
+pub enum FileBuilder {
+    Csv(Vec<u8>),
+    /// One row group per batch
+    Parquet(ParquetWriter),
+}
+
+impl FileBuilder {
+    pub fn new(format: WarehouseFormat, fact: WarehouseFact) -> Self {
+        let columns = columns(fact);
+        match format {
+            WarehouseFormat::Csv => {
+                let header: Vec<&str> = columns.iter().map(|(name, _)| *name).collect();
+                Self::Csv(format!("{}\n", header.join(",")).into_bytes())
+            }
+            WarehouseFormat::Parquet => Self::Parquet(ParquetWriter::new(columns)),
+        }
+    }
+
+    pub fn write(&mut self, rows: &[Vec<Value>]) {
+        match self {
+            Self::Csv(out) => {
+                for row in rows {
+                    let line: Vec<String> = row.iter().map(Value::csv).collect();
+                    out.extend(line.join(",").as_bytes());
+                    out.push(b'\n');
+                }
+            }
+            Self::Parquet(writer) => writer.write_row_group(rows),
+        }
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        match self {
+            Self::Csv(out) => out,
+            Self::Parquet(writer) => writer.finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn rows_match_their_columns() {
+        let at = Utc.with_ymd_and_hms(2026, 10, 17, 1, 30, 0).unwrap();
+        let loan = LoanFact {
+            loan_key: "archive-7".to_string(),
+            loan_date: Some(at),
+            due_date: None,
+            returned_at: Some(at),
+            renewals: Some(1),
+            item_id: Some(3),
+            biblio_id: Some(2),
+            media_type: Some("printedText".to_string()),
+            audience_type: None,
+            source_id: None,
+            place: None,
+            borrower_account_type: Some("reader".to_string()),
+            borrower_public_type: Some("adult".to_string()),
+            borrower_age_band: Some("25-44".to_string()),
+            changed_at: at,
+        };
+        assert_eq!(loan_row(loan).len(), columns(WarehouseFact::Loans).len());
+        let visit = VisitFact {
+            visit_id: 1,
+            count_date: at.date_naive(),
+            count_hour: None,
+            visitors: 12,
+            source: None,
+            recorded_at: at,
+        };
+        assert_eq!(visit_row(visit).len(), columns(WarehouseFact::Visits).len());
+    }
+
+    #[test]
+    fn csv_file_has_header_and_escaped_values() {
+        let at = Utc.with_ymd_and_hms(2026, 10, 17, 1, 30, 0).unwrap();
+        let mut file = FileBuilder::new(WarehouseFormat::Csv, WarehouseFact::Visits);
+        file.write(&[vec![
+            Value::Int(1),
+            Value::Date(at.date_naive()),
+            Value::Null,
+            Value::Int(12),
+            Value::Text("door, main".to_string()),
+            Value::Timestamp(at),
+        ]]);
+        assert_eq!(
+            String::from_utf8(file.finish()).unwrap(),
+            "visit_id,count_date,count_hour,visitors,source,recorded_at\n\
+             1,2026-10-17,,12,\"door, main\",2026-10-17T01:30:00.000000Z\n"
+        );
+    }
+}
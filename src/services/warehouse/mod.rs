@@ -0,0 +1,436 @@
+//! Nightly data warehouse exports
+//!
+//! Each target is an S3 bucket (or S3-compatible store) or an SFTP directory. A run extracts the
+//! anonymized fact tables (loans, acquisitions, visits) changed since the target's watermarks,
+//! writes them as CSV or Parquet files under a directory named after the run, then uploads
+//! `manifest.json` (files, row counts, checksums, change window, columns). The watermarks only
+//! move once the manifest is uploaded, so a failed run is simply sent again by the next one.
+
+mod extract;
+mod parquet;
+pub(crate) mod upload;
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use chrono::{DateTime, Duration, Local, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::{AppError, AppResult},
+    models::warehouse::{
+        CreateWarehouseTarget, UpdateWarehouseTarget, WarehouseFact, WarehouseFormat, WarehouseManifest,
+        WarehouseManifestFile, WarehouseRun, WarehouseRunStatus, WarehouseTarget, WarehouseTrigger,
+    },
+    repository::WarehouseRepository,
+    services::{
+        audit::{self, AuditService},
+        harvest::{is_due, validate_name, validate_schedule_time},
+    },
+};
+
+use extract::FileBuilder;
+use upload::Destination;
+
+/// Rows fetched per query (and per Parquet row group)
+const PAGE_SIZE: i64 = 50_000;
+/// Rows changed during the last minute before a run wait for the next one, so transactions
+/// still in flight when the run starts are not skipped
+const SETTLE_DELAY: Duration = Duration::minutes(1);
+/// Runs returned by default / at most by the run history
+const DEFAULT_RUNS_LIMIT: i64 = 20;
+const MAX_RUNS_LIMIT: i64 = 100;
+
+#[derive(Clone)]
+pub struct WarehouseService {
+    repository: Arc<dyn WarehouseRepository>,
+    audit: AuditService,
+    http: reqwest::Client,
+}
+
+impl WarehouseService {
+    pub fn new(repository: Arc<dyn WarehouseRepository>, audit: AuditService) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(600))
+            .connect_timeout(std::time::Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+        Self { repository, audit, http }
+    }
+
+    // =========================================================================
+    // Targets
+    // =========================================================================
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list_targets(&self) -> AppResult<Vec<WarehouseTarget>> {
+        self.repository.warehouse_targets_list().await
+    }
+
+    pub async fn get_target(&self, id: i64) -> AppResult<WarehouseTarget> {
+        self.repository.warehouse_targets_get(id).await
+    }
+
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn create_target(&self, data: &CreateWarehouseTarget) -> AppResult<WarehouseTarget> {
+        validate_name(Some(&data.name))?;
+        validate_schedule_time(data.schedule_time.as_deref())?;
+        validate_facts(data.facts.as_deref())?;
+        validate_connection(
+            &data.url,
+            data.username.as_deref(),
+            data.password.as_deref().is_some_and(|p| !p.is_empty()),
+            data.host_key_fingerprint.as_deref(),
+            data.endpoint.as_deref(),
+        )?;
+        self.repository.warehouse_targets_create(data).await
+    }
+
+    #[tracing::instrument(skip(self, data), err)]
+    pub async fn update_target(&self, id: i64, data: &UpdateWarehouseTarget) -> AppResult<WarehouseTarget> {
+        validate_name(data.name.as_deref())?;
+        validate_schedule_time(data.schedule_time.as_deref())?;
+        validate_facts(data.facts.as_deref())?;
+        let current = self.repository.warehouse_targets_get(id).await?;
+        // Checked against the merged configuration: a new URL may need the stored credentials
+        let has_password = match data.password.as_deref() {
+            Some(p) => !p.is_empty(),
+            None => current.has_password,
+        };
+        let endpoint = match data.endpoint.as_deref() {
+            Some("") => None,
+            Some(e) => Some(e),
+            None => current.endpoint.as_deref(),
+        };
+        validate_connection(
+            data.url.as_deref().unwrap_or(&current.url),
+            data.username.as_deref().or(current.username.as_deref()),
+            has_password,
+            data.host_key_fingerprint.as_deref().or(current.host_key_fingerprint.as_deref()),
+            endpoint,
+        )?;
+        self.repository.warehouse_targets_update(id, data).await
+    }
+
+    /// Delete a target with its run history (exported files stay on the remote side)
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete_target(&self, id: i64) -> AppResult<()> {
+        self.repository.warehouse_targets_delete(id).await
+    }
+
+    // =========================================================================
+    // Runs
+    // =========================================================================
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn runs(&self, target_id: i64, limit: Option<i64>) -> AppResult<Vec<WarehouseRun>> {
+        self.repository.warehouse_targets_get(target_id).await?;
+        let limit = limit.unwrap_or(DEFAULT_RUNS_LIMIT).clamp(1, MAX_RUNS_LIMIT);
+        self.repository.warehouse_runs_list(target_id, limit).await
+    }
+
+    pub async fn run(&self, id: i64) -> AppResult<WarehouseRun> {
+        self.repository.warehouse_runs_get(id).await
+    }
+
+    /// Open a manual run; the caller executes it in the background with [`Self::execute`]
+    #[tracing::instrument(skip(self), err)]
+    pub async fn start_run(&self, target_id: i64, started_by: i64) -> AppResult<(WarehouseTarget, WarehouseRun)> {
+        let target = self.repository.warehouse_targets_get(target_id).await?;
+        let run = self
+            .repository
+            .warehouse_runs_start(target.id, target.last_run_at, WarehouseTrigger::Manual, Some(started_by))
+            .await?
+            .ok_or_else(|| AppError::Conflict("An export to this target is already running".to_string()))?;
+        Ok((target, run))
+    }
+
+    /// Start the scheduled runs whose time has come (called every minute by the scheduler)
+    pub async fn run_due(&self) -> AppResult<()> {
+        let now = Local::now();
+        for target in self.repository.warehouse_targets_list().await? {
+            let last = target.last_run_at.unwrap_or(target.created_at);
+            if !target.is_active || !is_due(&target.schedule_time, last, now) {
+                continue;
+            }
+            // Another instance may have claimed the target since it was listed
+            let Some(run) = self
+                .repository
+                .warehouse_runs_start(target.id, target.last_run_at, WarehouseTrigger::Schedule, None)
+                .await?
+            else {
+                continue;
+            };
+            tracing::info!(target_id = target.id, run_id = run.id, "Starting scheduled warehouse export");
+            if let Err(e) = self.execute(target, run).await {
+                tracing::error!("Could not store the warehouse export report: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Extract and upload the facts, move the watermarks, then store the report
+    #[tracing::instrument(skip(self, target, run), fields(target_id = target.id, run_id = run.id))]
+    pub async fn execute(&self, target: WarehouseTarget, mut run: WarehouseRun) -> AppResult<WarehouseRun> {
+        match self.export(&target, &run).await {
+            Ok(manifest) => {
+                run.status = WarehouseRunStatus::Succeeded;
+                run.manifest = Some(manifest);
+            }
+            Err(e) => {
+                tracing::warn!("Warehouse export to target {} failed: {}", target.id, e);
+                run.status = WarehouseRunStatus::Failed;
+                run.error = Some(e.to_string());
+            }
+        }
+        let run = self.repository.warehouse_runs_finish(&run).await?;
+
+        let payload = serde_json::json!({
+            "targetId": target.id,
+            "status": run.status.as_str(),
+            "files": run.manifest.as_ref().map(|m| m.files.iter().map(|f| serde_json::json!({
+                "path": f.path,
+                "rows": f.rows,
+            })).collect::<Vec<_>>()),
+            "error": run.error,
+        });
+        if run.status == WarehouseRunStatus::Succeeded {
+            self.audit.log(
+                audit::event::WAREHOUSE_RUN_COMPLETED,
+                run.started_by,
+                Some("warehouse_run"),
+                Some(run.id),
+                None,
+                Some(payload),
+                audit::AuditLogMeta::success(),
+            );
+        } else {
+            self.audit.log(
+                audit::event::WAREHOUSE_RUN_FAILED,
+                run.started_by,
+                Some("warehouse_run"),
+                Some(run.id),
+                None,
+                Some(payload),
+                audit::AuditLogMeta::failure_background(
+                    run.status.as_str(),
+                    run.error.clone().unwrap_or_default(),
+                ),
+            );
+        }
+        Ok(run)
+    }
+
+    async fn export(&self, target: &WarehouseTarget, run: &WarehouseRun) -> AppResult<WarehouseManifest> {
+        let until = run.started_at - SETTLE_DELAY;
+        let directory = run_directory(run);
+        let facts: Vec<WarehouseFact> = target.facts.iter().filter_map(|f| WarehouseFact::parse(f)).collect();
+
+        let mut destination = Destination::connect(target, &self.http).await?;
+        let result: AppResult<WarehouseManifest> = async {
+            let mut files = Vec::with_capacity(facts.len());
+            for fact in facts {
+                let after = target.watermarks.get(fact.as_str()).copied();
+                let (body, rows) = self.extract(fact, target.format, after, until).await?;
+                let path = format!("{}/{}.{}", directory, fact.as_str(), target.format.extension());
+                files.push(WarehouseManifestFile {
+                    fact,
+                    path: path.clone(),
+                    rows,
+                    bytes: body.len() as u64,
+                    sha256: hex::encode(Sha256::digest(&body)),
+                    changed_after: after,
+                    changed_until: until,
+                    columns: extract::manifest_columns(fact),
+                });
+                destination.put(&path, body, content_type(target.format)).await?;
+            }
+            let manifest = WarehouseManifest {
+                run_id: run.id,
+                generated_at: Utc::now(),
+                format: target.format,
+                files,
+            };
+            let body = serde_json::to_vec_pretty(&manifest)
+                .map_err(|e| AppError::Internal(format!("Manifest serialization failed: {}", e)))?;
+            destination
+                .put(&format!("{}/manifest.json", directory), body, "application/json")
+                .await?;
+            Ok(manifest)
+        }
+        .await;
+        destination.close().await;
+        let manifest = result?;
+
+        let watermarks: BTreeMap<String, DateTime<Utc>> = manifest
+            .files
+            .iter()
+            .map(|f| (f.fact.as_str().to_string(), f.changed_until))
+            .collect();
+        self.repository.warehouse_targets_set_watermarks(target.id, &watermarks).await?;
+        Ok(manifest)
+    }
+
+    /// Build the file of one fact; returns it with its row count
+    async fn extract(
+        &self,
+        fact: WarehouseFact,
+        format: WarehouseFormat,
+        after: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+    ) -> AppResult<(Vec<u8>, u64)> {
+        let mut file = FileBuilder::new(format, fact);
+        let mut rows = 0u64;
+        match fact {
+            WarehouseFact::Loans => {
+                let mut cursor = None;
+                loop {
+                    let page = self.repository.warehouse_loan_facts(after, until, cursor, PAGE_SIZE).await?;
+                    let Some(last) = page.last() else { break };
+                    cursor = Some((last.changed_at, last.loan_key.clone()));
+                    let full = page.len() as i64 == PAGE_SIZE;
+                    rows += page.len() as u64;
+                    file.write(&page.into_iter().map(extract::loan_row).collect::<Vec<_>>());
+                    if !full {
+                        break;
+                    }
+                }
+            }
+            WarehouseFact::Acquisitions => {
+                let mut cursor = None;
+                loop {
+                    let page = self
+                        .repository
+                        .warehouse_acquisition_facts(after, until, cursor, PAGE_SIZE)
+                        .await?;
+                    let Some(last) = page.last() else { break };
+                    cursor = Some((last.created_at, last.item_id));
+                    let full = page.len() as i64 == PAGE_SIZE;
+                    rows += page.len() as u64;
+                    file.write(&page.into_iter().map(extract::acquisition_row).collect::<Vec<_>>());
+                    if !full {
+                        break;
+                    }
+                }
+            }
+            WarehouseFact::Visits => {
+                let mut cursor = None;
+                loop {
+                    let page = self.repository.warehouse_visit_facts(after, until, cursor, PAGE_SIZE).await?;
+                    let Some(last) = page.last() else { break };
+                    cursor = Some((last.recorded_at, last.visit_id));
+                    let full = page.len() as i64 == PAGE_SIZE;
+                    rows += page.len() as u64;
+                    file.write(&page.into_iter().map(extract::visit_row).collect::<Vec<_>>());
+                    if !full {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok((file.finish(), rows))
+    }
+}
+
+/// Directory of a run's files, sortable by time (`20261017T013000Z-42`)
+fn run_directory(run: &WarehouseRun) -> String {
+    format!("{}-{}", run.started_at.format("%Y%m%dT%H%M%SZ"), run.id)
+}
+
+fn content_type(format: WarehouseFormat) -> &'static str {
+    match format {
+        WarehouseFormat::Csv => "text/csv",
+        WarehouseFormat::Parquet => "application/vnd.apache.parquet",
+    }
+}
+
+fn validate_facts(facts: Option<&[WarehouseFact]>) -> AppResult<()> {
+    match facts {
+        Some([]) => Err(AppError::Validation("facts must list at least one fact table".to_string())),
+        _ => Ok(()),
+    }
+}
+
+fn validate_connection(
+    url: &str,
+    username: Option<&str>,
+    has_password: bool,
+    host_key_fingerprint: Option<&str>,
+    endpoint: Option<&str>,
+) -> AppResult<()> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| AppError::Validation(format!("Invalid url: {}", e)))?;
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(AppError::Validation("url must include a bucket or a host".to_string()));
+    }
+    let has_credentials = username.is_some_and(|u| !u.is_empty()) && has_password;
+    match parsed.scheme() {
+        "s3" => {
+            if let Some(endpoint) = endpoint {
+                let endpoint = reqwest::Url::parse(endpoint)
+                    .map_err(|e| AppError::Validation(format!("Invalid endpoint: {}", e)))?;
+                if !matches!(endpoint.scheme(), "http" | "https") {
+                    return Err(AppError::Validation("endpoint must be an http(s) URL".to_string()));
+                }
+            }
+            if !has_credentials {
+                return Err(AppError::Validation(
+                    "S3 targets need an access key (username) and a secret key (password)".to_string(),
+                ));
+            }
+            Ok(())
+        }
+        "sftp" => {
+            if !host_key_fingerprint.is_some_and(|f| f.starts_with("SHA256:")) {
+                return Err(AppError::Validation(
+                    "SFTP targets need hostKeyFingerprint (SHA256:…, see `ssh-keygen -lf`)".to_string(),
+                ));
+            }
+            if !has_credentials {
+                return Err(AppError::Validation("SFTP targets need a username and a password".to_string()));
+            }
+            Ok(())
+        }
+        other => Err(AppError::Validation(format!("Unsupported url scheme {} (s3 or sftp)", other))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn connection_settings_are_checked_per_protocol() {
+        assert!(validate_connection("s3://warehouse/elidune/", Some("AKID"), true, None, None).is_ok());
+        assert!(validate_connection("s3://warehouse/elidune/", Some("AKID"), false, None, None).is_err());
+        assert!(validate_connection(
+            "s3://warehouse/",
+            Some("AKID"),
+            true,
+            None,
+            Some("https://minio.city.local:9000")
+        )
+        .is_ok());
+        assert!(validate_connection("s3://warehouse/", Some("AKID"), true, None, Some("ftp://minio")).is_err());
+        assert!(validate_connection("sftp://dw.example.org/in/", Some("lib"), true, None, None).is_err());
+        assert!(validate_connection("sftp://dw.example.org/in/", Some("lib"), true, Some("SHA256:abc"), None).is_ok());
+        assert!(validate_connection("https://dw.example.org/in/", None, false, None, None).is_err());
+        assert!(validate_facts(Some(&[])).is_err());
+        assert!(validate_facts(Some(&[WarehouseFact::Visits])).is_ok());
+    }
+
+    #[test]
+    fn run_directories_sort_by_start_time() {
+        let run = WarehouseRun {
+            id: 42,
+            target_id: 1,
+            trigger: WarehouseTrigger::Schedule,
+            status: WarehouseRunStatus::Running,
+            started_by: None,
+            started_at: Utc.with_ymd_and_hms(2026, 10, 17, 1, 30, 0).unwrap(),
+            finished_at: None,
+            manifest: None,
+            error: None,
+        };
+        assert_eq!(run_directory(&run), "20261017T013000Z-42");
+    }
+}
@@ -0,0 +1,369 @@
+//! Minimal Apache Parquet writer (flat schema, optional columns, no compression).
+//!
+//! Layout: `PAR1`, then one row group per [`ParquetWriter::write_row_group`] call holding one
+//! PLAIN data page (v1) per column, then the Thrift compact `FileMetaData` footer, its length and
+//! `PAR1` again. Definition levels are RLE runs (bit width 1); repetition levels are absent since
+//! no column repeats. That subset is read by every Parquet implementation (Spark, DuckDB,
+//! pandas/pyarrow, BigQuery, Snowflake).
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use super::extract::{ColumnType, Value};
+
+const MAGIC: &[u8] = b"PAR1";
+
+/// Thrift compact protocol type ids
+mod ct {
+    pub const I32: u8 = 5;
+    pub const I64: u8 = 6;
+    pub const BINARY: u8 = 8;
+    pub const LIST: u8 = 9;
+    pub const STRUCT: u8 = 12;
+}
+
+/// Parquet physical types
+const TYPE_BOOLEAN: i32 = 0;
+const TYPE_INT32: i32 = 1;
+const TYPE_INT64: i32 = 2;
+const TYPE_BYTE_ARRAY: i32 = 6;
+/// Converted (legacy logical) types
+const CONVERTED_UTF8: i32 = 0;
+const CONVERTED_DATE: i32 = 6;
+const CONVERTED_TIMESTAMP_MICROS: i32 = 10;
+const REPETITION_OPTIONAL: i32 = 1;
+const ENCODING_PLAIN: i32 = 0;
+const ENCODING_RLE: i32 = 3;
+const CODEC_UNCOMPRESSED: i32 = 0;
+const PAGE_DATA: i32 = 0;
+
+impl ColumnType {
+    fn physical(self) -> i32 {
+        match self {
+            Self::Int64 | Self::Timestamp => TYPE_INT64,
+            Self::Bool => TYPE_BOOLEAN,
+            Self::Text => TYPE_BYTE_ARRAY,
+            Self::Date => TYPE_INT32,
+        }
+    }
+
+    fn converted(self) -> Option<i32> {
+        match self {
+            Self::Int64 | Self::Bool => None,
+            Self::Text => Some(CONVERTED_UTF8),
+            Self::Date => Some(CONVERTED_DATE),
+            Self::Timestamp => Some(CONVERTED_TIMESTAMP_MICROS),
+        }
+    }
+}
+
+struct ChunkMeta {
+    column: usize,
+    offset: i64,
+    size: i64,
+    num_values: i64,
+}
+
+struct RowGroupMeta {
+    chunks: Vec<ChunkMeta>,
+    num_rows: i64,
+}
+
+/// Row groups in, Parquet bytes out
+pub struct ParquetWriter {
+    columns: Vec<(&'static str, ColumnType)>,
+    out: Vec<u8>,
+    row_groups: Vec<RowGroupMeta>,
+}
+
+impl ParquetWriter {
+    pub fn new(columns: &[(&'static str, ColumnType)]) -> Self {
+        Self { columns: columns.to_vec(), out: MAGIC.to_vec(), row_groups: Vec::new() }
+    }
+
+    /// Append a row group; every row has one value per column
+    pub fn write_row_group(&mut self, rows: &[Vec<Value>]) {
+        if rows.is_empty() {
+            return;
+        }
+        let mut chunks = Vec::with_capacity(self.columns.len());
+        for (column, &(_, ty)) in self.columns.iter().enumerate() {
+            let values = rows.iter().map(|row| &row[column]);
+            let page = data_page(ty, values);
+            let offset = self.out.len() as i64;
+            let mut header = Compact::default();
+            header.i32(1, PAGE_DATA);
+            header.i32(2, page.len() as i32);
+            header.i32(3, page.len() as i32);
+            header.begin_struct(5);
+            header.i32(1, rows.len() as i32);
+            header.i32(2, ENCODING_PLAIN);
+            header.i32(3, ENCODING_RLE);
+            header.i32(4, ENCODING_RLE);
+            header.end_struct();
+            header.stop();
+            self.out.extend(header.buf);
+            self.out.extend(page);
+            chunks.push(ChunkMeta {
+                column,
+                offset,
+                size: self.out.len() as i64 - offset,
+                num_values: rows.len() as i64,
+            });
+        }
+        self.row_groups.push(RowGroupMeta { chunks, num_rows: rows.len() as i64 });
+    }
+
+    /// Write the footer and return the file
+    pub fn finish(mut self) -> Vec<u8> {
+        let footer = self.footer();
+        self.out.extend(&footer);
+        self.out.extend((footer.len() as u32).to_le_bytes());
+        self.out.extend(MAGIC);
+        self.out
+    }
+
+    fn footer(&self) -> Vec<u8> {
+        let mut meta = Compact::default();
+        meta.i32(1, 1);
+        meta.list_begin(2, ct::STRUCT, self.columns.len() + 1);
+        meta.struct_begin_in_list();
+        meta.binary(4, b"schema");
+        meta.i32(5, self.columns.len() as i32);
+        meta.end_struct();
+        for &(name, ty) in &self.columns {
+            meta.struct_begin_in_list();
+            meta.i32(1, ty.physical());
+            meta.i32(3, REPETITION_OPTIONAL);
+            meta.binary(4, name.as_bytes());
+            if let Some(converted) = ty.converted() {
+                meta.i32(6, converted);
+            }
+            meta.end_struct();
+        }
+        meta.i64(3, self.row_groups.iter().map(|g| g.num_rows).sum());
+        meta.list_begin(4, ct::STRUCT, self.row_groups.len());
+        for group in &self.row_groups {
+            meta.struct_begin_in_list();
+            meta.list_begin(1, ct::STRUCT, group.chunks.len());
+            for chunk in &group.chunks {
+                let (name, ty) = self.columns[chunk.column];
+                meta.struct_begin_in_list();
+                meta.i64(2, chunk.offset);
+                meta.begin_struct(3);
+                meta.i32(1, ty.physical());
+                meta.list_begin(2, ct::I32, 2);
+                meta.list_i32(ENCODING_PLAIN);
+                meta.list_i32(ENCODING_RLE);
+                meta.list_begin(3, ct::BINARY, 1);
+                meta.list_binary(name.as_bytes());
+                meta.i32(4, CODEC_UNCOMPRESSED);
+                meta.i64(5, chunk.num_values);
+                meta.i64(6, chunk.size);
+                meta.i64(7, chunk.size);
+                meta.i64(9, chunk.offset);
+                meta.end_struct();
+                meta.end_struct();
+            }
+            meta.i64(2, group.chunks.iter().map(|c| c.size).sum());
+            meta.i64(3, group.num_rows);
+            meta.end_struct();
+        }
+        meta.binary(6, b"elidune-server");
+        meta.stop();
+        meta.buf
+    }
+}
+
+/// Definition levels (length-prefixed RLE runs) followed by the PLAIN non-null values
+fn data_page<'a>(ty: ColumnType, values: impl Iterator<Item = &'a Value>) -> Vec<u8> {
+    let mut levels = Vec::new();
+    let mut plain = Vec::new();
+    let mut bits: Vec<bool> = Vec::new();
+    for value in values {
+        levels.push(!matches!(value, Value::Null));
+        match (ty, value) {
+            (_, Value::Null) => {}
+            (ColumnType::Int64, Value::Int(v)) => plain.extend(v.to_le_bytes()),
+            (ColumnType::Bool, Value::Bool(v)) => bits.push(*v),
+            (ColumnType::Text, Value::Text(v)) => {
+                plain.extend((v.len() as u32).to_le_bytes());
+                plain.extend(v.as_bytes());
+            }
+            (ColumnType::Date, Value::Date(v)) => plain.extend(days_since_epoch(*v).to_le_bytes()),
+            (ColumnType::Timestamp, Value::Timestamp(v)) => plain.extend(micros(*v).to_le_bytes()),
+            (ty, value) => unreachable!("{:?} value in a {:?} column", value, ty),
+        }
+    }
+    for byte in bits.chunks(8) {
+        plain.push(byte.iter().enumerate().fold(0u8, |acc, (i, &b)| acc | ((b as u8) << i)));
+    }
+    let runs = rle_levels(&levels);
+    let mut page = Vec::with_capacity(4 + runs.len() + plain.len());
+    page.extend((runs.len() as u32).to_le_bytes());
+    page.extend(runs);
+    page.extend(plain);
+    page
+}
+
+/// RLE/bit-packing hybrid encoding of 0/1 levels, as RLE runs only
+fn rle_levels(levels: &[bool]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < levels.len() {
+        let value = levels[i];
+        let run = levels[i..].iter().take_while(|&&l| l == value).count();
+        varint(&mut out, (run as u64) << 1);
+        out.push(value as u8);
+        i += run;
+    }
+    out
+}
+
+fn days_since_epoch(date: NaiveDate) -> i32 {
+    (date - NaiveDate::from_ymd_opt(1970, 1, 1).unwrap_or_default()).num_days() as i32
+}
+
+fn micros(at: DateTime<Utc>) -> i64 {
+    at.timestamp_micros()
+}
+
+fn varint(out: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        out.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+fn zigzag(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+/// Thrift compact protocol encoder (only what the Parquet metadata needs)
+#[derive(Default)]
+struct Compact {
+    buf: Vec<u8>,
+    /// Last field id of each open struct
+    last_ids: Vec<i16>,
+    last_id: i16,
+}
+
+impl Compact {
+    fn field(&mut self, id: i16, ty: u8) {
+        let delta = id - self.last_id;
+        if (1..=15).contains(&delta) {
+            self.buf.push(((delta as u8) << 4) | ty);
+        } else {
+            self.buf.push(ty);
+            varint(&mut self.buf, zigzag(id as i64));
+        }
+        self.last_id = id;
+    }
+
+    fn i32(&mut self, id: i16, v: i32) {
+        self.field(id, ct::I32);
+        varint(&mut self.buf, zigzag(v as i64));
+    }
+
+    fn i64(&mut self, id: i16, v: i64) {
+        self.field(id, ct::I64);
+        varint(&mut self.buf, zigzag(v));
+    }
+
+    fn binary(&mut self, id: i16, v: &[u8]) {
+        self.field(id, ct::BINARY);
+        self.list_binary(v);
+    }
+
+    fn begin_struct(&mut self, id: i16) {
+        self.field(id, ct::STRUCT);
+        self.struct_begin_in_list();
+    }
+
+    fn struct_begin_in_list(&mut self) {
+        self.last_ids.push(self.last_id);
+        self.last_id = 0;
+    }
+
+    fn end_struct(&mut self) {
+        self.stop();
+        self.last_id = self.last_ids.pop().unwrap_or_default();
+    }
+
+    fn stop(&mut self) {
+        self.buf.push(0);
+    }
+
+    fn list_begin(&mut self, id: i16, elem: u8, len: usize) {
+        self.field(id, ct::LIST);
+        if len < 15 {
+            self.buf.push(((len as u8) << 4) | elem);
+        } else {
+            self.buf.push(0xF0 | elem);
+            varint(&mut self.buf, len as u64);
+        }
+    }
+
+    fn list_i32(&mut self, v: i32) {
+        varint(&mut self.buf, zigzag(v as i64));
+    }
+
+    fn list_binary(&mut self, v: &[u8]) {
+        varint(&mut self.buf, v.len() as u64);
+        self.buf.extend(v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compact_field_headers_use_deltas() {
+        let mut c = Compact::default();
+        c.i32(1, 1);
+        c.i64(3, -1);
+        c.begin_struct(20);
+        c.binary(4, b"ab");
+        c.end_struct();
+        c.stop();
+        assert_eq!(
+            c.buf,
+            [0x15, 0x02, 0x26, 0x01, 0x0C, 0x28, 0x48, 0x02, b'a', b'b', 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn definition_levels_are_rle_runs() {
+        assert_eq!(rle_levels(&[true, true, true, false, true]), [0x06, 1, 0x02, 0, 0x02, 1]);
+        assert_eq!(rle_levels(&[true; 200]), [0x90, 0x03, 1]);
+    }
+
+    #[test]
+    fn file_has_magic_and_footer_length() {
+        let mut writer = ParquetWriter::new(&[("id", ColumnType::Int64), ("name", ColumnType::Text)]);
+        writer.write_row_group(&[
+            vec![Value::Int(1), Value::Text("a".to_string())],
+            vec![Value::Int(2), Value::Null],
+        ]);
+        let file = writer.finish();
+        assert_eq!(&file[..4], MAGIC);
+        assert_eq!(&file[file.len() - 4..], MAGIC);
+        let footer_len = u32::from_le_bytes(file[file.len() - 8..file.len() - 4].try_into().unwrap()) as usize;
+        let footer = &file[file.len() - 8 - footer_len..file.len() - 8];
+        // version 1, then the schema list (3 elements, structs)
+        assert_eq!(&footer[..3], [0x15, 0x02, 0x19]);
+        assert_eq!(footer[3], 0x3C);
+        assert!(footer.windows(14).any(|w| w == b"elidune-server"));
+    }
+
+    #[test]
+    fn plain_values_skip_nulls() {
+        let values = [Value::Bool(true), Value::Null, Value::Bool(false), Value::Bool(true)];
+        let page = data_page(ColumnType::Bool, values.iter());
+        // 6 bytes of levels (3 runs), then one bit-packed byte 0b101
+        assert_eq!(page, [6, 0, 0, 0, 0x02, 1, 0x02, 0, 0x04, 1, 0b101]);
+        let days = data_page(ColumnType::Date, [Value::Date(NaiveDate::from_ymd_opt(1970, 1, 11).unwrap())].iter());
+        assert_eq!(&days[6..], 10i32.to_le_bytes());
+    }
+}
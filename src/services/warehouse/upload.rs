@@ -0,0 +1,290 @@
+//! Extract upload: S3 (or an S3-compatible store) with Signature V4, or an SFTP directory
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use russh_sftp::client::SftpSession;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::warehouse::WarehouseTarget,
+    services::harvest::{remote_error, sftp_open},
+};
+
+/// Bucket (or SFTP directory) location the run directories are written under
+pub enum Destination {
+    S3(S3Bucket),
+    Sftp { session: SftpSession, prefix: String },
+}
+
+pub struct S3Bucket {
+    http: reqwest::Client,
+    /// Base URL of the bucket (`https://bucket.s3.region.amazonaws.com` or `endpoint/bucket`)
+    base: Url,
+    prefix: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+}
+
+impl Destination {
+    pub async fn connect(target: &WarehouseTarget, http: &reqwest::Client) -> AppResult<Self> {
+        let url = Url::parse(&target.url)
+            .map_err(|e| AppError::Validation(format!("Invalid warehouse URL: {}", e)))?;
+        match url.scheme() {
+            "s3" => {
+                let (Some(access_key), Some(secret_key)) = (&target.username, &target.password) else {
+                    return Err(AppError::Validation(
+                        "S3 targets need an access key (username) and a secret key (password)".to_string(),
+                    ));
+                };
+                Ok(Self::S3(S3Bucket::new(
+                    &url,
+                    &target.region,
+                    target.endpoint.as_deref(),
+                    access_key,
+                    secret_key,
+                    http,
+                )?))
+            }
+            "sftp" => {
+                let session = sftp_open(
+                    &url,
+                    target.username.as_deref(),
+                    target.password.as_deref(),
+                    target.host_key_fingerprint.as_deref(),
+                )
+                .await?;
+                Ok(Self::Sftp { session, prefix: prefix(&url) })
+            }
+            other => Err(AppError::Validation(format!("Unsupported warehouse URL scheme: {}", other))),
+        }
+    }
+
+    /// Write `body` at `path` (relative to the target URL)
+    pub async fn put(&mut self, path: &str, body: Vec<u8>, content_type: &str) -> AppResult<()> {
+        match self {
+            Self::S3(bucket) => bucket.put(path, body, content_type).await,
+            Self::Sftp { session, prefix } => {
+                let full = join(prefix, path);
+                // Missing parent directories are created one level at a time
+                let parts: Vec<&str> = full.split('/').filter(|p| !p.is_empty()).collect();
+                for depth in 1..parts.len() {
+                    let dir = format!("/{}", parts[..depth].join("/"));
+                    if !session.try_exists(dir.as_str()).await.unwrap_or(false) {
+                        session.create_dir(dir).await.map_err(|e| remote_error("SFTP mkdir", e))?;
+                    }
+                }
+                let mut file = session
+                    .create(format!("/{}", full))
+                    .await
+                    .map_err(|e| remote_error("SFTP upload", e))?;
+                file.write_all(&body).await.map_err(|e| remote_error("SFTP upload", e))?;
+                file.shutdown().await.map_err(|e| remote_error("SFTP upload", e))
+            }
+        }
+    }
+
+    pub async fn close(self) {
+        if let Self::Sftp { session, .. } = self {
+            let _ = session.close().await;
+        }
+    }
+}
+
+impl S3Bucket {
+    /// Bucket of an `s3://bucket/prefix/` URL (AWS, or `endpoint` for an S3-compatible store)
+    pub fn new(
+        url: &Url,
+        region: &str,
+        endpoint: Option<&str>,
+        access_key: &str,
+        secret_key: &str,
+        http: &reqwest::Client,
+    ) -> AppResult<Self> {
+        let bucket = url
+            .host_str()
+            .ok_or_else(|| AppError::Validation("S3 URL has no bucket".to_string()))?;
+        let base = match endpoint {
+            Some(endpoint) => Url::parse(&format!("{}/{}/", endpoint.trim_end_matches('/'), bucket)),
+            None => Url::parse(&format!("https://{}.s3.{}.amazonaws.com/", bucket, region)),
+        }
+        .map_err(|e| AppError::Validation(format!("Invalid S3 endpoint: {}", e)))?;
+        Ok(Self {
+            http: http.clone(),
+            base,
+            prefix: prefix(url),
+            region: region.to_string(),
+            access_key: access_key.to_string(),
+            secret_key: secret_key.to_string(),
+        })
+    }
+
+    /// Write `body` at `path` (relative to the prefix of the bucket URL)
+    pub async fn put(&self, path: &str, body: Vec<u8>, content_type: &str) -> AppResult<()> {
+        let key = join(&self.prefix, path);
+        let url = self
+            .base
+            .join(&key.split('/').map(uri_encode).collect::<Vec<_>>().join("/"))
+            .map_err(|e| AppError::Internal(format!("Invalid S3 key {}: {}", key, e)))?;
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let now = Utc::now();
+        let authorization = sign_v4(
+            &SigningRequest {
+                method: "PUT",
+                uri: url.path(),
+                host: &host,
+                payload_hash: &payload_hash,
+                region: &self.region,
+                access_key: &self.access_key,
+                secret_key: &self.secret_key,
+            },
+            now,
+        );
+        let response = self
+            .http
+            .put(url)
+            .header("x-amz-date", now.format("%Y%m%dT%H%M%SZ").to_string())
+            .header("x-amz-content-sha256", &payload_hash)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| remote_error("S3 upload", e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(AppError::Internal(format!(
+                "S3 upload of {} failed: {} {}",
+                key,
+                status,
+                detail.chars().take(300).collect::<String>()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// What a Signature V4 covers (`host`, `x-amz-content-sha256` and `x-amz-date` are signed)
+struct SigningRequest<'a> {
+    method: &'a str,
+    /// Already URI-encoded path
+    uri: &'a str,
+    host: &'a str,
+    payload_hash: &'a str,
+    region: &'a str,
+    access_key: &'a str,
+    secret_key: &'a str,
+}
+
+/// `Authorization` header of an AWS Signature V4 request (service `s3`)
+fn sign_v4(request: &SigningRequest<'_>, now: DateTime<Utc>) -> String {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        request.method, request.uri, request.host, request.payload_hash, amz_date, signed_headers, request.payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, request.region);
+    let to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical.as_bytes()))
+    );
+    let key = signing_key(request.secret_key, &date, request.region, "s3");
+    let signature = hex::encode(hmac(&key, to_sign.as_bytes()));
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        request.access_key, scope, signed_headers, signature
+    )
+}
+
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let k_date = hmac(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let k_region = hmac(&k_date, region.as_bytes());
+    let k_service = hmac(&k_region, service.as_bytes());
+    hmac(&k_service, b"aws4_request")
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// RFC 3986 encoding of one path segment (unreserved characters kept)
+fn uri_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// URL path without its leading and trailing slashes
+fn prefix(url: &Url) -> String {
+    url.path().trim_matches('/').to_string()
+}
+
+fn join(prefix: &str, path: &str) -> String {
+    if prefix.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}/{}", prefix, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn signing_key_matches_aws_example() {
+        // https://docs.aws.amazon.com/general/latest/gr/signature-v4-examples.html
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        assert_eq!(hex::encode(key), "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d");
+    }
+
+    #[test]
+    fn authorization_header_names_scope_and_signed_headers() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 1, 30, 0).unwrap();
+        let header = sign_v4(
+            &SigningRequest {
+                method: "PUT",
+                uri: "/warehouse/run/loans.parquet",
+                host: "minio.local:9000",
+                payload_hash: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+                region: "eu-west-3",
+                access_key: "AKIDEXAMPLE",
+                secret_key: "secret",
+            },
+            now,
+        );
+        assert!(header.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20261017/eu-west-3/s3/aws4_request, \
+             SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature="
+        ));
+        assert_eq!(header.rsplit('=').next().unwrap().len(), 64);
+    }
+
+    #[test]
+    fn keys_are_joined_and_encoded() {
+        assert_eq!(prefix(&Url::parse("s3://bucket/exports/elidune/").unwrap()), "exports/elidune");
+        assert_eq!(prefix(&Url::parse("s3://bucket").unwrap()), "");
+        assert_eq!(join("", "a/manifest.json"), "a/manifest.json");
+        assert_eq!(join("exports", "a/manifest.json"), "exports/a/manifest.json");
+        assert_eq!(uri_encode("run 1+2"), "run%201%2B2");
+    }
+}
@@ -0,0 +1,40 @@
+//! Signature check shared by the inbound vendor webhooks (lockers, mail provider).
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// `signature` is `sha256=<hex HMAC-SHA256 of the body>`
+pub fn verify_signature(secret: &str, body: &[u8], signature: Option<&str>) -> bool {
+    let Some(signature) = signature.and_then(|s| s.trim().strip_prefix("sha256=")) else {
+        return false;
+    };
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn webhook_signature() {
+        let body = br#"{"event":"compartmentOpened","reference":"42"}"#;
+        let header = sign("s3cret", body);
+        assert!(verify_signature("s3cret", body, Some(&header)));
+        assert!(!verify_signature("other", body, Some(&header)));
+        assert!(!verify_signature("s3cret", br#"{"event":"expired","reference":"42"}"#, Some(&header)));
+        assert!(!verify_signature("s3cret", body, Some(header.trim_start_matches("sha256="))));
+        assert!(!verify_signature("s3cret", body, Some("sha256=zz")));
+        assert!(!verify_signature("s3cret", body, None));
+    }
+}
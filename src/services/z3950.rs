@@ -2,14 +2,22 @@
 //!
 //! Uses the z3950-rs crate for Z39.50 protocol communication.
 
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{DateTime, TimeZone, Utc};
 use serde_json;
 use redis::AsyncCommands;
 
-use z3950_rs::marc_rs::{ MarcFormat, Record as MarcRecord};
+use z3950_rs::marc_rs::{BinaryReader, MarcError, MarcFormat, Record as MarcRecord};
 use z3950_rs::{Client, QueryLanguage};
 use crate::{
-    api::z3950::{ImportItem, Z3950SearchQuery, Z3950ServerConfig},
+    api::z3950::{
+        ImportItem, Z3950DegradedReason, Z3950DegradedSource, Z3950RecordFailure,
+        Z3950SearchQuery, Z3950SearchResponse, Z3950ServerConfig, Z3950ServerHealth,
+        Z3950SourceReport,
+    },
     error::{AppError, AppResult},
+    marc::diff::{diff_records, merge_records, MarcDiffFormat, MarcFieldDiff},
     models::{
         biblio::{Biblio, Isbn},
         import_report::{ImportAction, ImportReport},
@@ -20,6 +28,18 @@ use crate::{
     services::redis::RedisService,
 };
 
+/// Consecutive failures that open a server's circuit
+const CIRCUIT_FAILURE_THRESHOLD: i64 = 3;
+
+/// How long searches skip a server once its circuit is open
+const CIRCUIT_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// Health counters of servers untouched for this long are dropped
+const HEALTH_TTL: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// ISO 2709 record terminator
+const RECORD_TERMINATOR: u8 = 0x1D;
+
 /// Z39.50 server configuration (from `z3950servers` row) for connect / query.
 #[derive(Debug, Clone)]
 pub struct Z3950Server {
@@ -32,6 +52,18 @@ pub struct Z3950Server {
     pub password: Option<String>,
     #[allow(dead_code)]
     pub format: Option<MarcFormat>,
+    /// Ranking weight of the server's records in merged results
+    pub trust_weight: f64,
+}
+
+/// Records of one present batch, decoded one by one
+#[derive(Debug, Default)]
+pub struct PresentedRecords {
+    pub records: Vec<MarcRecord>,
+    /// Records decoded only after retrying with the alternative MARC syntax
+    pub recovered: usize,
+    /// Records that could not be decoded at all
+    pub failures: Vec<Z3950RecordFailure>,
 }
 
 #[derive(Clone)]
@@ -54,7 +86,11 @@ impl Z3950Service {
 
     /// Search remote catalogs via Z39.50
     #[tracing::instrument(skip(self), err)]
-    pub async fn search(&self, query: &Z3950SearchQuery) -> AppResult<(Vec<Biblio>, i32, String)> {
+    ///
+    /// Servers whose circuit is open are skipped; skipped and failed servers are listed in
+    /// `degraded_sources`. Undecodable records are left out without dropping the rest of their
+    /// server's batch, and counted in `source_reports`.
+    pub async fn search(&self, query: &Z3950SearchQuery) -> AppResult<Z3950SearchResponse> {
         tracing::info!("Z39.50 search started");
         tracing::debug!("Search params - query: {}", query.query);
 
@@ -79,6 +115,7 @@ impl Z3950Service {
                 format: None,
                 login: row.login,
                 password: row.password,
+                trust_weight: row.trust_weight,
             })
             .collect();
 
@@ -91,18 +128,56 @@ impl Z3950Service {
         let max_results = query.max_results.unwrap_or(50) as usize;
         
 
+        let wanted_isbns = query_isbns(&query.query);
         let mut all_biblios = Vec::new();
         let mut sources = Vec::new();
+        let mut degraded_sources = Vec::new();
+        let mut source_reports = Vec::new();
         let search_start = std::time::Instant::now();
 
         // Query each server
         for (idx, server) in servers.iter().enumerate() {
+            let health = self.server_health(server.id).await;
+            if health.circuit_open {
+                tracing::info!("Skipping server {}: circuit open until {:?}", server.name, health.open_until);
+                degraded_sources.push(Z3950DegradedSource {
+                    server_id: server.id,
+                    name: server.name.clone(),
+                    reason: Z3950DegradedReason::CircuitOpen,
+                    retry_at: health.open_until,
+                });
+                continue;
+            }
+
             tracing::info!("Querying server {}/{}: {}", idx + 1, servers.len(), server.name);
-            
-            match self.query_server(server, &query).await {
-                Ok(records) => {
+
+            let started = std::time::Instant::now();
+            let result = self.query_server(server, query).await;
+            let latency_ms = started.elapsed().as_millis() as i64;
+
+            match result {
+                Ok(presented) => {
+                    let records = presented.records;
                     tracing::info!("Server {} returned {} records", server.name, records.len());
-                    
+                    self.record_success(server.id, latency_ms).await;
+
+                    source_reports.push(Z3950SourceReport {
+                        server_id: server.id,
+                        name: server.name.clone(),
+                        records_returned: records.len() as i32,
+                        records_recovered: presented.recovered as i32,
+                        records_failed: presented.failures.len() as i32,
+                        failures: presented.failures,
+                    });
+                    if source_reports.last().is_some_and(|r| r.records_failed > 0) {
+                        degraded_sources.push(Z3950DegradedSource {
+                            server_id: server.id,
+                            name: server.name.clone(),
+                            reason: Z3950DegradedReason::PartialRecords,
+                            retry_at: None,
+                        });
+                    }
+
                     if !records.is_empty() {
                         sources.push(server.name.clone());
                         let len = records.len();
@@ -114,7 +189,11 @@ impl Z3950Service {
                                     tracing::debug!("Cached record as remote_biblio id={:?}", id);
                                     let mut biblio = Biblio::from(record);
                                     biblio.id = Some(id.parse::<i64>().unwrap_or(0));
-                                    all_biblios.push(biblio);
+                                    all_biblios.push(RankedBiblio {
+                                        score: record_score(&biblio, server.trust_weight),
+                                        exact_isbn: matches_isbn(&biblio, &wanted_isbns),
+                                        biblio,
+                                    });
                                 }
                                 Err(e) => {
                                     tracing::warn!("Failed to cache record {}: {}", rec_idx + 1, e);
@@ -125,6 +204,13 @@ impl Z3950Service {
                 }
                 Err(e) => {
                     tracing::warn!("Failed to query server {}: {}", server.name, e);
+                    self.record_failure(server, latency_ms, &e).await;
+                    degraded_sources.push(Z3950DegradedSource {
+                        server_id: server.id,
+                        name: server.name.clone(),
+                        reason: Z3950DegradedReason::Failed,
+                        retry_at: None,
+                    });
                 }
             }
 
@@ -139,6 +225,7 @@ impl Z3950Service {
         tracing::info!("Z39.50 live search completed in {:?}, found {} biblios", search_elapsed, all_biblios.len());
 
         let total = all_biblios.len() as i32;
+        let biblios = rank_results(all_biblios);
         let source = if sources.is_empty() {
             "cache".to_string()
         } else {
@@ -146,7 +233,13 @@ impl Z3950Service {
         };
 
         tracing::info!("Z39.50 search complete: {} results from {}", total, source);
-        Ok((all_biblios, total, source))
+        Ok(Z3950SearchResponse {
+            total,
+            biblios,
+            source,
+            degraded_sources,
+            source_reports,
+        })
     }
 
     /// Load one **active** Z39.50 server by id (same filter as search).
@@ -168,6 +261,7 @@ impl Z3950Service {
             format: None,
             login: row.login,
             password: row.password,
+            trust_weight: row.trust_weight,
         })
     }
 
@@ -201,12 +295,15 @@ impl Z3950Service {
     }
 
     /// CQL search + MARC present on an **existing** connection. Does **not** close the client.
+    ///
+    /// Records are decoded one by one (see [`decode_batch`]); undecodable ones are logged under
+    /// the `z3950` target and returned in [`PresentedRecords::failures`].
     #[tracing::instrument(skip(client, query), fields(server = %server.name))]
     pub async fn query(
         client: &mut Client,
         server: &Z3950Server,
         query: &Z3950SearchQuery,
-    ) -> AppResult<Vec<MarcRecord>> {
+    ) -> AppResult<PresentedRecords> {
         tracing::debug!("Z39.50 query: {:?}", query);
 
         let databases = if server.database.is_empty() {
@@ -233,20 +330,47 @@ impl Z3950Service {
         tracing::debug!("Z39.50 search returned {} hits on {}", hits, server.name);
 
         if hits == 0 {
-            return Ok(Vec::new());
+            return Ok(PresentedRecords::default());
         }
 
         let count = std::cmp::min(hits, query.max_results.unwrap_or(50) as usize);
-        let records = client
-            .present_marc(1, count as i64)
+        let raw = client
+            .present_raw(1, count as i64)
             .await
             .map_err(|e| {
                 tracing::warn!("Z39.50 present failed on {}: {}", server.name, e);
                 AppError::Z3950(format!("Z39.50 present failed: {}", e))
             })?;
 
-        tracing::info!("z3950-rs returned {} MARC records from {}", records.len(), server.name);
-        Ok(records)
+        let presented = decode_batch(&raw);
+        for failure in &presented.failures {
+            tracing::warn!(
+                target: "z3950",
+                server_id = server.id,
+                server = %server.name,
+                position = failure.position,
+                leader = failure.leader.as_deref().unwrap_or_default(),
+                error = %failure.error,
+                "Z39.50 record could not be decoded"
+            );
+        }
+        if presented.recovered > 0 {
+            tracing::info!(
+                target: "z3950",
+                server_id = server.id,
+                server = %server.name,
+                recovered = presented.recovered,
+                "Z39.50 records decoded with the alternative MARC syntax"
+            );
+        }
+
+        tracing::info!(
+            "z3950-rs returned {} MARC records from {} ({} undecodable)",
+            presented.records.len(),
+            server.name,
+            presented.failures.len()
+        );
+        Ok(presented)
     }
 
     /// Connect, search, present, then close — convenience for one-shot calls.
@@ -254,7 +378,7 @@ impl Z3950Service {
         &self,
         server: &Z3950Server,
         query: &Z3950SearchQuery,
-    ) -> AppResult<Vec<MarcRecord>> {
+    ) -> AppResult<PresentedRecords> {
         tracing::info!("Z39.50 search starting on server: {}", server.name);
         let mut client = Self::connect_server(server).await?;
         let out = Self::query(&mut client, server, query).await;
@@ -268,6 +392,101 @@ impl Z3950Service {
         format!("z3950:item:{}", id)
     }
 
+    /// Get Redis key for the health counters of a server
+    fn get_health_key(server_id: i64) -> String {
+        format!("z3950:health:{}", server_id)
+    }
+
+    /// Health of a server. Redis errors are logged and reported as a healthy server, so a Redis
+    /// outage never blocks searches.
+    async fn server_health(&self, server_id: i64) -> Z3950ServerHealth {
+        let fields: AppResult<HashMap<String, String>> = async {
+            let mut conn = self.redis.get_connection().await?;
+            conn.hgetall(Self::get_health_key(server_id))
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to read Z39.50 server health: {}", e)))
+        }
+        .await;
+        match fields {
+            Ok(fields) => health_from_fields(&fields, Utc::now()),
+            Err(e) => {
+                tracing::warn!("{}", e);
+                Z3950ServerHealth::default()
+            }
+        }
+    }
+
+    /// Count a successful search and close the server's circuit
+    async fn record_success(&self, server_id: i64, latency_ms: i64) {
+        let key = Self::get_health_key(server_id);
+        let result: AppResult<()> = async {
+            let mut conn = self.redis.get_connection().await?;
+            redis::pipe()
+                .atomic()
+                .hincr(&key, "successes", 1)
+                .hincr(&key, "latency_ms_total", latency_ms)
+                .hset_multiple(
+                    &key,
+                    &[
+                        ("last_latency_ms", latency_ms),
+                        ("consecutive_failures", 0),
+                        ("open_until", 0),
+                        ("last_success_at", Utc::now().timestamp()),
+                    ],
+                )
+                .expire(&key, HEALTH_TTL.as_secs() as i64)
+                .query_async::<_, ()>(&mut conn)
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to record Z39.50 server health: {}", e)))
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("{}", e);
+        }
+    }
+
+    /// Count a failed search; open the server's circuit after too many consecutive failures
+    async fn record_failure(&self, server: &Z3950Server, latency_ms: i64, error: &AppError) {
+        let key = Self::get_health_key(server.id);
+        let now = Utc::now().timestamp();
+        let result: AppResult<()> = async {
+            let mut conn = self.redis.get_connection().await?;
+            let (consecutive,): (i64,) = redis::pipe()
+                .atomic()
+                .hincr(&key, "failures", 1)
+                .ignore()
+                .hincr(&key, "consecutive_failures", 1)
+                .hset_multiple(
+                    &key,
+                    &[("last_latency_ms", latency_ms), ("last_failure_at", now)],
+                )
+                .ignore()
+                .hset(&key, "last_error", error.to_string())
+                .ignore()
+                .expire(&key, HEALTH_TTL.as_secs() as i64)
+                .ignore()
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to record Z39.50 server health: {}", e)))?;
+
+            if consecutive >= CIRCUIT_FAILURE_THRESHOLD {
+                tracing::warn!(
+                    "Opening circuit for Z39.50 server {} after {} consecutive failures",
+                    server.name,
+                    consecutive
+                );
+                conn.hset::<_, _, _, ()>(&key, "open_until", now + CIRCUIT_COOLDOWN.as_secs() as i64)
+                    .await
+                    .map_err(|e| AppError::Internal(format!("Failed to record Z39.50 server health: {}", e)))?;
+            }
+            Ok(())
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("{}", e);
+        }
+    }
+
    
     /// Upsert a MARC record in Redis cache and return ItemRemoteShort
     async fn upsert_cache_record(
@@ -317,19 +536,7 @@ impl Z3950Service {
         items: Option<Vec<ImportItem>>,
         confirm_replace_existing_id: Option<i64>,
     ) -> AppResult<(Biblio, ImportReport)> {
-        let mut conn = self.redis.get_connection().await?;
-
-        let redis_key = Self::get_redis_key(&biblio_id);
-        let json_str: Option<String> = conn
-            .get(&redis_key)
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to get biblio from Redis: {}", e)))?;
-
-        let marc_record: MarcRecord = serde_json::from_str(
-            &json_str.ok_or_else(|| AppError::NotFound("Remote biblio not found in cache".to_string()))?
-        )
-        .map_err(|e| AppError::Internal(format!("Failed to deserialize biblio from Redis: {}", e)))?;
-
+        let marc_record = self.cached_record(biblio_id).await?;
         let biblio: Biblio = marc_record.into();
         let (mut biblio, report) = self
             .catalog
@@ -354,12 +561,78 @@ impl Z3950Service {
 
     
 
-    /// Staff UI: all Z39.50 server rows.
+    /// Remote record kept in the search cache under `remote_id`
+    async fn cached_record(&self, remote_id: i64) -> AppResult<MarcRecord> {
+        let mut conn = self.redis.get_connection().await?;
+
+        let redis_key = Self::get_redis_key(&remote_id);
+        let json_str: Option<String> = conn
+            .get(&redis_key)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to get biblio from Redis: {}", e)))?;
+
+        serde_json::from_str(
+            &json_str.ok_or_else(|| AppError::NotFound("Remote biblio not found in cache".to_string()))?
+        )
+        .map_err(|e| AppError::Internal(format!("Failed to deserialize biblio from Redis: {}", e)))
+    }
+
+    /// MARC record of a local biblio as compared with remote ones: the stored record when there is
+    /// one, otherwise one built from the catalog data; holdings are left out.
+    async fn local_record(&self, biblio_id: i64) -> AppResult<MarcRecord> {
+        let biblio = self.repository.biblios_get_by_id(biblio_id).await?;
+        let mut record = match self.repository.biblios_get_marc_record_optional(biblio_id).await? {
+            Some(record) => record,
+            None => MarcRecord::from(&biblio),
+        };
+        record.local.items.clear();
+        Ok(record)
+    }
+
+    /// Field-level differences between a local biblio and a cached remote record.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn marc_diff(
+        &self,
+        biblio_id: i64,
+        remote_id: i64,
+        format: MarcDiffFormat,
+    ) -> AppResult<Vec<MarcFieldDiff>> {
+        let local = self.local_record(biblio_id).await?;
+        let mut remote = self.cached_record(remote_id).await?;
+        remote.local.items.clear();
+        diff_records(&local, &remote, format)
+    }
+
+    /// Update a local biblio from a cached remote record, taking only the given tags from the
+    /// remote side so local enrichments survive the re-import. Copies are kept.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn apply_marc_diff(
+        &self,
+        biblio_id: i64,
+        remote_id: i64,
+        tags: &[String],
+        format: MarcDiffFormat,
+    ) -> AppResult<Biblio> {
+        if tags.is_empty() {
+            return Err(AppError::Validation("Select at least one tag to take from the remote record".to_string()));
+        }
+        if let Some(tag) = tags.iter().find(|t| t.len() != 3 || !t.bytes().all(|b| b.is_ascii_alphanumeric())) {
+            return Err(AppError::Validation(format!("Invalid MARC tag: {}", tag)));
+        }
+        let local = self.local_record(biblio_id).await?;
+        let mut remote = self.cached_record(remote_id).await?;
+        remote.local.items.clear();
+        let merged = merge_records(&local, &remote, tags, format)?;
+        self.catalog.refresh_biblio_from_z3950_marc(biblio_id, merged).await
+    }
+
+    /// Staff UI: all Z39.50 server rows, with their health.
     pub async fn get_servers_for_settings(&self) -> AppResult<Vec<Z3950ServerConfig>> {
         let rows = self.repository.z3950_servers_list_all().await?;
-        Ok(rows
-            .into_iter()
-            .map(|r| Z3950ServerConfig {
+        let mut servers = Vec::with_capacity(rows.len());
+        for r in rows {
+            let health = self.server_health(r.id).await;
+            servers.push(Z3950ServerConfig {
                 id: r.id,
                 name: r.name.unwrap_or_default(),
                 address: r.address.unwrap_or_default(),
@@ -370,8 +643,11 @@ impl Z3950Service {
                 password: r.password,
                 encoding: r.encoding.unwrap_or_else(|| "utf-8".to_string()),
                 is_active: r.activated.unwrap_or(false),
-            })
-            .collect())
+                trust_weight: r.trust_weight,
+                health: Some(health),
+            });
+        }
+        Ok(servers)
     }
 
     /// Staff UI: upsert Z39.50 servers (id &gt; 0 update, id == 0 insert).
@@ -379,6 +655,12 @@ impl Z3950Service {
         &self,
         servers: Vec<Z3950ServerConfig>,
     ) -> AppResult<Vec<Z3950ServerConfig>> {
+        if let Some(server) = servers.iter().find(|s| s.trust_weight.is_nan() || s.trust_weight < 0.0) {
+            return Err(AppError::Validation(format!(
+                "Trust weight of Z39.50 server {} must not be negative",
+                server.name
+            )));
+        }
         for server in servers {
             if server.id > 0 {
                 self.repository
@@ -393,6 +675,7 @@ impl Z3950Service {
                         &server.password,
                         &server.encoding,
                         server.is_active,
+                        server.trust_weight,
                     )
                     .await?;
             } else {
@@ -407,6 +690,7 @@ impl Z3950Service {
                         &server.password,
                         &server.encoding,
                         server.is_active,
+                        server.trust_weight,
                     )
                     .await?;
             }
@@ -415,4 +699,228 @@ impl Z3950Service {
     }
 }
 
+/// Decode a present batch record by record, so one unreadable record does not drop the others.
+/// A record failing in its detected syntax is retried once in the other one (MARC21 / UNIMARC).
+fn decode_batch(data: &[u8]) -> PresentedRecords {
+    let mut out = PresentedRecords::default();
+    let chunks = data
+        .split_inclusive(|&b| b == RECORD_TERMINATOR)
+        .filter(|chunk| !chunk.iter().all(u8::is_ascii_whitespace));
+    for (idx, chunk) in chunks.enumerate() {
+        match decode_record(chunk) {
+            Ok((record, retried)) => {
+                out.recovered += usize::from(retried);
+                out.records.push(record);
+            }
+            Err(e) => out.failures.push(Z3950RecordFailure {
+                position: idx as i32 + 1,
+                leader: chunk.get(..24).map(|l| String::from_utf8_lossy(l).into_owned()),
+                error: e.to_string(),
+            }),
+        }
+    }
+    out
+}
+
+/// One ISO 2709 record, and whether it needed the alternative syntax
+fn decode_record(data: &[u8]) -> Result<(MarcRecord, bool), MarcError> {
+    let view = BinaryReader::new(data)
+        .next()
+        .ok_or(MarcError::InvalidRecord("empty record"))??;
+    let raw = view.as_raw();
+    let format = MarcFormat::detect(raw, None)?;
+    match format.to_record(raw) {
+        Ok(record) => Ok((record, false)),
+        Err(first) => {
+            let alternative = match format {
+                MarcFormat::Marc21(encoding) => MarcFormat::Unimarc(encoding),
+                MarcFormat::Unimarc(encoding) => MarcFormat::Marc21(encoding),
+            };
+            alternative.to_record(raw).map(|record| (record, true)).map_err(|_| first)
+        }
+    }
+}
+
+/// Merged search result with its ranking keys
+struct RankedBiblio {
+    biblio: Biblio,
+    /// The record's ISBN is one of those searched for
+    exact_isbn: bool,
+    score: f64,
+}
+
+/// Normalized ISBNs (10 or 13 characters) found in a search query
+fn query_isbns(query: &str) -> Vec<Isbn> {
+    query
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .map(Isbn::new)
+        .filter(|isbn| {
+            let s = isbn.as_str();
+            matches!(s.len(), 10 | 13)
+                && s[..s.len() - 1].bytes().all(|b| b.is_ascii_digit())
+                && s.ends_with(|c: char| c.is_ascii_digit() || c == 'X')
+        })
+        .collect()
+}
+
+fn matches_isbn(biblio: &Biblio, wanted: &[Isbn]) -> bool {
+    biblio.isbn.as_ref().is_some_and(|isbn| wanted.contains(isbn))
+}
+
+/// Richness of a record (one point each for ISBN, collection and subjects, plus one) times the
+/// trust weight of the server it comes from
+fn record_score(biblio: &Biblio, trust_weight: f64) -> f64 {
+    let has_isbn = biblio.isbn.as_ref().is_some_and(|isbn| !isbn.is_empty());
+    let has_collection = !biblio.collections.is_empty() || !biblio.series.is_empty();
+    let has_subjects = biblio.subject.as_deref().is_some_and(|s| !s.trim().is_empty())
+        || biblio.keywords.as_ref().is_some_and(|k| !k.is_empty());
+    let points = 1 + u8::from(has_isbn) + u8::from(has_collection) + u8::from(has_subjects);
+    trust_weight.max(0.0) * f64::from(points)
+}
+
+/// Exact ISBN matches first, then by descending score. The sort is stable, so ties keep the
+/// order servers (by id) and their records came in and pages of a repeated search line up.
+fn rank_results(mut results: Vec<RankedBiblio>) -> Vec<Biblio> {
+    results.sort_by(|a, b| {
+        b.exact_isbn
+            .cmp(&a.exact_isbn)
+            .then_with(|| b.score.total_cmp(&a.score))
+    });
+    results.into_iter().map(|r| r.biblio).collect()
+}
+
+/// Build server health from its Redis hash (missing fields count as zero)
+fn health_from_fields(fields: &HashMap<String, String>, now: DateTime<Utc>) -> Z3950ServerHealth {
+    let int = |name: &str| fields.get(name).and_then(|v| v.parse::<i64>().ok()).unwrap_or(0);
+    let time = |name: &str| Some(int(name)).filter(|&t| t > 0).and_then(|t| Utc.timestamp_opt(t, 0).single());
+
+    let successes = int("successes");
+    let failures = int("failures");
+    let open_until = time("open_until").filter(|&until| until > now);
+    Z3950ServerHealth {
+        successes,
+        failures,
+        success_rate: (successes + failures > 0).then(|| successes as f64 / (successes + failures) as f64),
+        avg_latency_ms: (successes > 0).then(|| int("latency_ms_total") / successes),
+        last_latency_ms: fields.get("last_latency_ms").and_then(|v| v.parse().ok()),
+        consecutive_failures: int("consecutive_failures"),
+        circuit_open: open_until.is_some(),
+        open_until,
+        last_success_at: time("last_success_at"),
+        last_failure_at: time("last_failure_at"),
+        last_error: fields.get("last_error").cloned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn marc21_record(title: &str) -> Vec<u8> {
+        use z3950_rs::marc_rs::{BinaryWriter, Encoding};
+
+        let mut biblio = Biblio::from(MarcRecord::default());
+        biblio.marc_record = None;
+        biblio.title = Some(title.to_string());
+        let mut record = MarcRecord::from(&biblio);
+        let mut data = Vec::new();
+        BinaryWriter::new(&mut data)
+            .write_record(&MarcFormat::Marc21(Encoding::Utf8), &mut record)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn undecodable_record_does_not_drop_the_batch() {
+        let mut batch = marc21_record("First");
+        batch.extend_from_slice(b"99999nam a2200000 i 4500garbage\x1D");
+        batch.extend(marc21_record("Third"));
+
+        let presented = decode_batch(&batch);
+        let titles: Vec<_> = presented.records.into_iter().map(|r| Biblio::from(r).title).collect();
+        assert_eq!(titles, vec![Some("First".to_string()), Some("Third".to_string())]);
+        assert_eq!(presented.recovered, 0);
+        assert_eq!(presented.failures.len(), 1);
+        assert_eq!(presented.failures[0].position, 2);
+        assert_eq!(presented.failures[0].leader.as_deref(), Some("99999nam a2200000 i 4500"));
+    }
+
+    fn ranked(title: &str, isbn: Option<&str>, subject: Option<&str>, trust_weight: f64, wanted: &[Isbn]) -> RankedBiblio {
+        let mut biblio = Biblio::from(MarcRecord::default());
+        biblio.title = Some(title.to_string());
+        biblio.isbn = isbn.map(Isbn::new);
+        biblio.subject = subject.map(str::to_string);
+        RankedBiblio {
+            score: record_score(&biblio, trust_weight),
+            exact_isbn: matches_isbn(&biblio, wanted),
+            biblio,
+        }
+    }
+
+    #[test]
+    fn isbns_are_read_from_the_query() {
+        let isbns = query_isbns(r#"isbn="978-2-01-210137-2" or title="Asterix 1961""#);
+        assert_eq!(isbns.iter().map(Isbn::as_str).collect::<Vec<_>>(), vec!["9782012101372"]);
+        assert_eq!(query_isbns("2-01-210137-X").len(), 1);
+    }
+
+    #[test]
+    fn exact_isbn_then_richness_weighted_by_trust() {
+        let wanted = query_isbns("9782012101372");
+        let results = vec![
+            ranked("bare", None, None, 1.0, &wanted),
+            ranked("rich", Some("9782012101389"), Some("Gaule"), 1.0, &wanted),
+            ranked("exact", Some("978-2-01-210137-2"), None, 0.5, &wanted),
+            ranked("trusted", None, Some("Gaule"), 3.0, &wanted),
+            ranked("bare again", None, None, 1.0, &wanted),
+        ];
+        let titles: Vec<_> = rank_results(results).into_iter().filter_map(|b| b.title).collect();
+        assert_eq!(titles, vec!["exact", "trusted", "rich", "bare", "bare again"]);
+    }
 
+    #[test]
+    fn unknown_server_is_healthy() {
+        let health = health_from_fields(&HashMap::new(), Utc::now());
+        assert_eq!(health, Z3950ServerHealth::default());
+        assert!(!health.circuit_open);
+    }
+
+    #[test]
+    fn circuit_stays_open_until_cooldown_ends() {
+        let now = Utc::now();
+        let until = (now.timestamp() + 60).to_string();
+        let open = fields(&[("failures", "3"), ("consecutive_failures", "3"), ("open_until", &until)]);
+        let health = health_from_fields(&open, now);
+        assert!(health.circuit_open);
+        assert_eq!(health.success_rate, Some(0.0));
+
+        // Past the cooldown the next search probes the server again
+        let later = now + chrono::Duration::seconds(61);
+        let health = health_from_fields(&open, later);
+        assert!(!health.circuit_open);
+        assert_eq!(health.open_until, None);
+        assert_eq!(health.consecutive_failures, 3);
+    }
+
+    #[test]
+    fn rates_and_latency_are_derived_from_counters() {
+        let health = health_from_fields(
+            &fields(&[
+                ("successes", "3"),
+                ("failures", "1"),
+                ("latency_ms_total", "900"),
+                ("last_latency_ms", "120"),
+                ("open_until", "0"),
+            ]),
+            Utc::now(),
+        );
+        assert_eq!(health.success_rate, Some(0.75));
+        assert_eq!(health.avg_latency_ms, Some(300));
+        assert_eq!(health.last_latency_ms, Some(120));
+        assert!(!health.circuit_open);
+    }
+}
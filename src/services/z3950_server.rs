@@ -0,0 +1,671 @@
+//! Z39.50 target: lets partner libraries search the catalog with their Z39.50 clients.
+//!
+//! Spawned at startup when `[z3950_server] enabled = true`, on its own port. Supports Init,
+//! Search (Type-1/RPN queries with BIB-1 use attributes) and Present; each connection keeps its
+//! named result sets (biblio ids). Records are generated from the stored `marc_record` (or the
+//! relational biblio) with active copies as holdings, in UNIMARC, MARC21 or MARCXML.
+
+use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
+
+use rasn::types::{BitString, Integer, ObjectIdentifier, OctetString, VisibleString};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::Semaphore,
+};
+use z3950_rs::marc_rs::{BinaryWriter, Encoding as MarcEncoding, MarcFormat, XmlWriter};
+use z3950_rs::pdu::{
+    AddInfo, Apdu, AttributeValue, Close, CloseReason, DatabaseName, DefaultDiagFormat, DiagRec,
+    External, ExternalEncoding, InitRequest, InitResponse, NamePlusRecord, Operand, Operator,
+    PresentRequest, PresentResponse, PresentStatus, Query, Record, Records, RpnStructure,
+    SearchRequest, SearchResponse, Term,
+};
+
+use crate::{
+    config::Z3950ServerConfig,
+    error::{AppError, AppResult},
+    marc::MarcRecord,
+    models::biblio::{CatalogSearchField, CatalogSearchNode},
+    services::catalog::CatalogService,
+};
+
+/// Requests are small; anything bigger is a broken or hostile client.
+const MAX_FRAME_SIZE: usize = 1024 * 1024;
+/// Nesting limit for RPN trees (AND/OR/AND-NOT).
+const MAX_QUERY_DEPTH: usize = 32;
+/// Largest message we accept to send in one response (negotiated at Init).
+const MAX_MESSAGE_SIZE: i64 = 16 * 1024 * 1024;
+
+const BIB1_DIAGNOSTIC_SET: &[u32] = &[1, 2, 840, 10003, 4, 1];
+
+/// BIB-1 diagnostic conditions returned to clients
+mod diag {
+    pub const TEMPORARY_SYSTEM_ERROR: i64 = 2;
+    pub const PRESENT_OUT_OF_RANGE: i64 = 13;
+    pub const SYSTEM_ERROR_PRESENTING_RECORDS: i64 = 14;
+    pub const RESULT_SET_EXISTS: i64 = 21;
+    pub const RESULT_SET_DOES_NOT_EXIST: i64 = 30;
+    pub const QUERY_TYPE_NOT_SUPPORTED: i64 = 107;
+    pub const MALFORMED_QUERY: i64 = 108;
+    pub const DATABASE_UNAVAILABLE: i64 = 109;
+    pub const UNSUPPORTED_USE_ATTRIBUTE: i64 = 114;
+    pub const RESULT_SET_AS_OPERAND_NOT_SUPPORTED: i64 = 18;
+    pub const UNSUPPORTED_TERM_TYPE: i64 = 229;
+    pub const RECORD_SYNTAX_NOT_SUPPORTED: i64 = 239;
+}
+
+/// Error reported to the client inside a Search/Present response
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Diagnostic {
+    condition: i64,
+    addinfo: String,
+}
+
+impl Diagnostic {
+    fn new(condition: i64, addinfo: impl Into<String>) -> Self {
+        Self { condition, addinfo: addinfo.into() }
+    }
+
+    fn to_pdu(&self) -> DefaultDiagFormat {
+        DefaultDiagFormat {
+            diagnostic_set_id: ObjectIdentifier::new(BIB1_DIAGNOSTIC_SET)
+                .expect("BIB-1 diagnostic set OID is valid"),
+            condition: self.condition.into(),
+            addinfo: AddInfo::V3Addinfo(self.addinfo.clone()),
+        }
+    }
+}
+
+/// Record syntaxes offered to clients
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RecordSyntax {
+    Unimarc,
+    Marc21,
+    MarcXml,
+}
+
+impl RecordSyntax {
+    const UNIMARC_OID: &'static [u32] = &[1, 2, 840, 10003, 5, 1];
+    const MARC21_OID: &'static [u32] = &[1, 2, 840, 10003, 5, 10];
+    const MARCXML_OID: &'static [u32] = &[1, 2, 840, 10003, 5, 109, 10];
+
+    fn oid(self) -> ObjectIdentifier {
+        let arcs = match self {
+            Self::Unimarc => Self::UNIMARC_OID,
+            Self::Marc21 => Self::MARC21_OID,
+            Self::MarcXml => Self::MARCXML_OID,
+        };
+        ObjectIdentifier::new(arcs).expect("record syntax OID is valid")
+    }
+
+    fn from_oid(oid: &ObjectIdentifier) -> Option<Self> {
+        match oid.as_ref() {
+            arcs if arcs == Self::UNIMARC_OID => Some(Self::Unimarc),
+            arcs if arcs == Self::MARC21_OID => Some(Self::Marc21),
+            arcs if arcs == Self::MARCXML_OID => Some(Self::MarcXml),
+            _ => None,
+        }
+    }
+
+    fn from_config(value: &str) -> Self {
+        match value.to_ascii_lowercase().as_str() {
+            "marc21" | "usmarc" => Self::Marc21,
+            "marcxml" => Self::MarcXml,
+            _ => Self::Unimarc,
+        }
+    }
+}
+
+/// Named result set kept for the lifetime of a connection
+struct ResultSet {
+    biblio_ids: Vec<i64>,
+    /// Syntax asked for in the Search request, used when Present does not name one
+    syntax: Option<RecordSyntax>,
+}
+
+/// Start the Z39.50 listener in the background (no-op when disabled).
+pub fn spawn(config: Z3950ServerConfig, catalog: CatalogService) {
+    if !config.enabled {
+        return;
+    }
+    tokio::spawn(async move {
+        if let Err(e) = run(config, catalog).await {
+            tracing::error!("Z39.50 target stopped: {}", e);
+        }
+    });
+}
+
+async fn run(config: Z3950ServerConfig, catalog: CatalogService) -> std::io::Result<()> {
+    let listener = TcpListener::bind((config.host.as_str(), config.port)).await?;
+    tracing::info!(
+        "Z39.50 target listening on {}:{} (database \"{}\", max {} connections)",
+        config.host,
+        config.port,
+        config.database_name,
+        config.max_connections
+    );
+    let config = Arc::new(config);
+    let slots = Arc::new(Semaphore::new(config.max_connections.max(1)));
+
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                tracing::warn!("Z39.50 accept failed: {}", e);
+                continue;
+            }
+        };
+        let Ok(permit) = slots.clone().try_acquire_owned() else {
+            tracing::warn!(
+                "Z39.50 connection from {} refused: {} connections already open",
+                peer,
+                config.max_connections
+            );
+            continue;
+        };
+        let mut session = Session::new(config.clone(), catalog.clone(), peer);
+        tokio::spawn(async move {
+            tracing::debug!("Z39.50 session opened from {}", peer);
+            if let Err(e) = session.serve(stream).await {
+                tracing::debug!("Z39.50 session from {} ended with error: {}", peer, e);
+            }
+            drop(permit);
+        });
+    }
+}
+
+struct Session {
+    config: Arc<Z3950ServerConfig>,
+    catalog: CatalogService,
+    peer: SocketAddr,
+    initialized: bool,
+    result_sets: HashMap<String, ResultSet>,
+}
+
+impl Session {
+    fn new(config: Arc<Z3950ServerConfig>, catalog: CatalogService, peer: SocketAddr) -> Self {
+        Self { config, catalog, peer, initialized: false, result_sets: HashMap::new() }
+    }
+
+    async fn serve(&mut self, mut stream: TcpStream) -> AppResult<()> {
+        let idle = Duration::from_secs(self.config.idle_timeout_seconds.max(1));
+        loop {
+            let frame = match tokio::time::timeout(idle, read_ber_frame(&mut stream)).await {
+                Err(_) => {
+                    let close = close_apdu(None, CloseReason::LackOfActivity, "idle timeout");
+                    return write_apdu(&mut stream, &close).await;
+                }
+                Ok(Ok(None)) => return Ok(()),
+                Ok(Ok(Some(frame))) => frame,
+                Ok(Err(e)) => return Err(e),
+            };
+
+            let apdu = match rasn::ber::decode::<Apdu>(&frame) {
+                Ok(apdu) => apdu,
+                Err(e) => {
+                    let close = close_apdu(None, CloseReason::ProtocolError, "undecodable APDU");
+                    write_apdu(&mut stream, &close).await?;
+                    return Err(AppError::BadRequest(format!("Z39.50 decode: {}", e)));
+                }
+            };
+
+            let reply = match apdu {
+                Apdu::InitRequest(req) => {
+                    self.initialized = true;
+                    Apdu::InitResponse(init_response(&req))
+                }
+                Apdu::Close(close) => {
+                    let reply = close_apdu(close.reference_id, CloseReason::Finished, "");
+                    return write_apdu(&mut stream, &reply).await;
+                }
+                _ if !self.initialized => {
+                    let close = close_apdu(None, CloseReason::ProtocolError, "Init required");
+                    return write_apdu(&mut stream, &close).await;
+                }
+                Apdu::SearchRequest(req) => Apdu::SearchResponse(self.search(req).await),
+                Apdu::PresentRequest(req) => Apdu::PresentResponse(self.present(req).await),
+                other => {
+                    tracing::debug!("Z39.50 {}: unsupported APDU {:?}", self.peer, std::mem::discriminant(&other));
+                    let close = close_apdu(None, CloseReason::ProtocolError, "unsupported service");
+                    return write_apdu(&mut stream, &close).await;
+                }
+            };
+            write_apdu(&mut stream, &reply).await?;
+        }
+    }
+
+    async fn search(&mut self, req: SearchRequest) -> SearchResponse {
+        let reference_id = req.reference_id.clone();
+        match self.run_search(req).await {
+            Ok(count) => SearchResponse {
+                reference_id,
+                result_count: count.into(),
+                number_of_records_returned: 0.into(),
+                next_result_set_position: 1.into(),
+                search_status: true,
+                result_set_status: None,
+                present_status: None,
+                records: None,
+                additional_search_info: None,
+                other_info: None,
+            },
+            Err(d) => SearchResponse {
+                reference_id,
+                result_count: 0.into(),
+                number_of_records_returned: 0.into(),
+                next_result_set_position: 0.into(),
+                search_status: false,
+                // 3 = none (no result set created)
+                result_set_status: Some(3.into()),
+                present_status: None,
+                records: Some(Records::NonSurrogateDiagnostic(d.to_pdu())),
+                additional_search_info: None,
+                other_info: None,
+            },
+        }
+    }
+
+    async fn run_search(&mut self, req: SearchRequest) -> Result<i64, Diagnostic> {
+        let known_database = req.database_names.iter().any(|db| {
+            let DatabaseName::General(name) = db;
+            name.to_string().eq_ignore_ascii_case(&self.config.database_name)
+        });
+        if !known_database {
+            return Err(Diagnostic::new(diag::DATABASE_UNAVAILABLE, self.config.database_name.clone()));
+        }
+        if !req.replace_indicator && self.result_sets.contains_key(&req.result_set_name) {
+            return Err(Diagnostic::new(diag::RESULT_SET_EXISTS, req.result_set_name));
+        }
+        let node = match &req.query {
+            Query::Type1(rpn) | Query::Type101(rpn) => rpn_to_search_node(&rpn.rpn, 0)?,
+            _ => return Err(Diagnostic::new(diag::QUERY_TYPE_NOT_SUPPORTED, "only Type-1 (RPN) queries")),
+        };
+
+        let (biblio_ids, total) = self
+            .catalog
+            .search_tree(&node, self.config.max_result_set.max(1))
+            .await
+            .map_err(|e| {
+                tracing::error!("Z39.50 search failed: {}", e);
+                Diagnostic::new(diag::TEMPORARY_SYSTEM_ERROR, "search failed")
+            })?;
+        tracing::info!(
+            "Z39.50 {} searched {:?}: {} hit(s), {} kept",
+            self.peer,
+            node,
+            total,
+            biblio_ids.len()
+        );
+
+        let count = biblio_ids.len() as i64;
+        let syntax = req.preferred_record_syntax.as_ref().and_then(RecordSyntax::from_oid);
+        self.result_sets.insert(req.result_set_name, ResultSet { biblio_ids, syntax });
+        Ok(count)
+    }
+
+    async fn present(&mut self, req: PresentRequest) -> PresentResponse {
+        let reference_id = req.reference_id.clone();
+        match self.run_present(&req).await {
+            Ok((records, next)) => PresentResponse {
+                reference_id,
+                number_of_records_returned: (records.len() as i64).into(),
+                next_result_set_position: next.into(),
+                present_status: Some(PresentStatus::Success),
+                records: Some(Records::ResponseRecords(records)),
+                other_info: None,
+            },
+            Err(d) => PresentResponse {
+                reference_id,
+                number_of_records_returned: 0.into(),
+                next_result_set_position: 0.into(),
+                present_status: Some(PresentStatus::Failure),
+                records: Some(Records::NonSurrogateDiagnostic(d.to_pdu())),
+                other_info: None,
+            },
+        }
+    }
+
+    async fn run_present(&self, req: &PresentRequest) -> Result<(Vec<NamePlusRecord>, i64), Diagnostic> {
+        let set = self
+            .result_sets
+            .get(&req.result_set_id)
+            .ok_or_else(|| Diagnostic::new(diag::RESULT_SET_DOES_NOT_EXIST, req.result_set_id.clone()))?;
+        let syntax = match &req.preferred_record_syntax {
+            Some(oid) => RecordSyntax::from_oid(oid)
+                .ok_or_else(|| Diagnostic::new(diag::RECORD_SYNTAX_NOT_SUPPORTED, oid.to_string()))?,
+            None => set
+                .syntax
+                .unwrap_or_else(|| RecordSyntax::from_config(&self.config.default_record_syntax)),
+        };
+
+        let (start, end) = present_window(
+            to_i64(&req.result_set_start_point),
+            to_i64(&req.number_of_records_requested),
+            set.biblio_ids.len() as i64,
+            self.config.max_records_per_present.max(1),
+        )?;
+
+        let mut records = Vec::with_capacity((end - start) as usize);
+        for &biblio_id in &set.biblio_ids[start as usize..end as usize] {
+            let record = match self.catalog.get_opac_marc_record_with_holdings(biblio_id).await {
+                Ok(marc) => match encode_record(&marc, syntax) {
+                    Ok(bytes) => Record::RetrievalRecord(External {
+                        direct_reference: Some(syntax.oid()),
+                        indirect_reference: None,
+                        data_value_descriptor: None,
+                        encoding: ExternalEncoding::OctetAligned(OctetString::from(bytes)),
+                    }),
+                    Err(e) => {
+                        tracing::warn!("Z39.50 cannot encode biblio {}: {}", biblio_id, e);
+                        surrogate_diagnostic(biblio_id)
+                    }
+                },
+                // Archived or deleted since the search
+                Err(e) => {
+                    tracing::debug!("Z39.50 cannot load biblio {}: {}", biblio_id, e);
+                    surrogate_diagnostic(biblio_id)
+                }
+            };
+            records.push(NamePlusRecord {
+                name: VisibleString::from_iso646_bytes(self.config.database_name.as_bytes()).ok(),
+                record,
+            });
+        }
+
+        let next = if end < set.biblio_ids.len() as i64 { end + 1 } else { 0 };
+        Ok((records, next))
+    }
+}
+
+/// 0-based `[start, end)` slice of a result set for a 1-based Present request, capped at `max`.
+fn present_window(start_point: i64, requested: i64, len: i64, max: i64) -> Result<(i64, i64), Diagnostic> {
+    if requested < 0 || start_point < 1 || (start_point > len && requested > 0) {
+        return Err(Diagnostic::new(diag::PRESENT_OUT_OF_RANGE, format!("{} records available", len)));
+    }
+    let start = (start_point - 1).min(len);
+    let end = (start + requested.min(max)).min(len);
+    Ok((start, end))
+}
+
+fn surrogate_diagnostic(biblio_id: i64) -> Record {
+    let d = Diagnostic::new(diag::SYSTEM_ERROR_PRESENTING_RECORDS, biblio_id.to_string());
+    Record::SurrogateDiagnostic(DiagRec::DefaultFormat(d.to_pdu()))
+}
+
+/// Translate a Type-1 query into a catalog search tree.
+///
+/// BIB-1 use attributes: 4 title, 7 ISBN, 8 ISSN, 21 subject, 1003/1004 author, 1018 publisher,
+/// 1016/1017 any (also the default when no use attribute is given).
+fn rpn_to_search_node(rpn: &RpnStructure, depth: usize) -> Result<CatalogSearchNode, Diagnostic> {
+    if depth > MAX_QUERY_DEPTH {
+        return Err(Diagnostic::new(diag::MALFORMED_QUERY, "query too deeply nested"));
+    }
+    match rpn {
+        RpnStructure::RpnRpnOperator(op) => {
+            let left = Box::new(rpn_to_search_node(&op.rpn1, depth + 1)?);
+            let right = Box::new(rpn_to_search_node(&op.rpn2, depth + 1)?);
+            Ok(match op.op {
+                Operator::And(()) => CatalogSearchNode::And(left, right),
+                Operator::Or(()) => CatalogSearchNode::Or(left, right),
+                Operator::AndNot(()) => CatalogSearchNode::AndNot(left, right),
+            })
+        }
+        RpnStructure::Op(Operand::AttributesPlusTerm(apt)) => {
+            let mut field = CatalogSearchField::Any;
+            for attr in &apt.attributes {
+                let AttributeValue::Numeric(value) = &attr.attribute_value;
+                if to_i64(&attr.attribute_type) != 1 {
+                    continue;
+                }
+                field = match to_i64(value) {
+                    4 => CatalogSearchField::Title,
+                    7 => CatalogSearchField::Isbn,
+                    8 => CatalogSearchField::Issn,
+                    21 => CatalogSearchField::Subject,
+                    1003 | 1004 => CatalogSearchField::Author,
+                    1018 => CatalogSearchField::Publisher,
+                    1016 | 1017 => CatalogSearchField::Any,
+                    other => {
+                        return Err(Diagnostic::new(diag::UNSUPPORTED_USE_ATTRIBUTE, other.to_string()))
+                    }
+                };
+            }
+            let value = match &apt.term {
+                Term::General(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+                Term::CharacterString(s) => s.clone(),
+                Term::Numeric(n) => to_i64(n).to_string(),
+                _ => return Err(Diagnostic::new(diag::UNSUPPORTED_TERM_TYPE, "")),
+            };
+            let value = value.trim().to_string();
+            if value.is_empty() {
+                return Err(Diagnostic::new(diag::MALFORMED_QUERY, "empty term"));
+            }
+            Ok(CatalogSearchNode::Term { field, value })
+        }
+        RpnStructure::Op(_) => Err(Diagnostic::new(diag::RESULT_SET_AS_OPERAND_NOT_SUPPORTED, "")),
+    }
+}
+
+fn init_response(req: &InitRequest) -> InitResponse {
+    // Z39.50 versions 1-3
+    let mut protocol_version = BitString::repeat(false, 8);
+    for bit in 0..3 {
+        protocol_version.set(bit, true);
+    }
+    // Services: search (bit 0) and present (bit 1) only
+    let mut options = BitString::repeat(false, 16);
+    options.set(0, true);
+    options.set(1, true);
+
+    let message_size = to_i64(&req.preferred_message_size).clamp(1, MAX_MESSAGE_SIZE);
+    InitResponse {
+        reference_id: req.reference_id.clone(),
+        protocol_version: Some(protocol_version),
+        options: Some(options),
+        preferred_message_size: Some(message_size.into()),
+        exceptional_record_size: Some(message_size.into()),
+        result: true,
+        implementation_id: Some("Elidune".to_string()),
+        implementation_name: Some("Elidune Z39.50 target".to_string()),
+        implementation_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        user_information_field: None,
+        other_info: None,
+    }
+}
+
+fn close_apdu(reference_id: Option<OctetString>, reason: CloseReason, info: &str) -> Apdu {
+    Apdu::Close(Close {
+        reference_id,
+        close_reason: reason,
+        diagnostic_information: (!info.is_empty()).then(|| info.to_string()),
+        resource_report_format: None,
+        resource_report: None,
+        other_info: None,
+    })
+}
+
+fn encode_record(record: &MarcRecord, syntax: RecordSyntax) -> AppResult<Vec<u8>> {
+    let mut buf = Vec::new();
+    match syntax {
+        RecordSyntax::MarcXml => {
+            let mut w = XmlWriter::new(&mut buf);
+            w.write_record(&MarcFormat::Marc21(MarcEncoding::Utf8), record)
+                .map_err(|e| AppError::Internal(format!("MARC-XML record: {}", e)))?;
+            w.flush()
+                .map_err(|e| AppError::Internal(format!("MARC-XML flush: {}", e)))?;
+        }
+        RecordSyntax::Marc21 | RecordSyntax::Unimarc => {
+            let fmt = if syntax == RecordSyntax::Marc21 {
+                MarcFormat::Marc21(MarcEncoding::Utf8)
+            } else {
+                MarcFormat::Unimarc(MarcEncoding::Utf8)
+            };
+            let mut w = BinaryWriter::new(&mut buf);
+            let mut rec = record.clone();
+            w.write_record(&fmt, &mut rec)
+                .map_err(|e| AppError::Internal(format!("MARC binary write: {}", e)))?;
+            w.flush()
+                .map_err(|e| AppError::Internal(format!("MARC binary flush: {}", e)))?;
+        }
+    }
+    Ok(buf)
+}
+
+fn to_i64(value: &Integer) -> i64 {
+    i64::try_from(value.clone()).unwrap_or(i64::MAX)
+}
+
+async fn write_apdu(stream: &mut TcpStream, apdu: &Apdu) -> AppResult<()> {
+    let bytes = rasn::ber::encode(apdu)
+        .map_err(|e| AppError::Internal(format!("Z39.50 encode: {}", e)))?;
+    stream
+        .write_all(&bytes)
+        .await
+        .map_err(|e| AppError::Internal(format!("Z39.50 write: {}", e)))?;
+    Ok(())
+}
+
+/// Read one BER-encoded APDU (definite length only). `None` on a clean end of stream.
+async fn read_ber_frame<R: AsyncRead + Unpin>(stream: &mut R) -> AppResult<Option<Vec<u8>>> {
+    let io_err = |e: std::io::Error| AppError::Internal(format!("Z39.50 read: {}", e));
+
+    let mut frame = Vec::with_capacity(64);
+    let first = match stream.read_u8().await {
+        Ok(b) => b,
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(io_err(e)),
+    };
+    frame.push(first);
+    // High-tag-number form: tag continues while bit 8 is set
+    if first & 0x1F == 0x1F {
+        loop {
+            let b = stream.read_u8().await.map_err(io_err)?;
+            frame.push(b);
+            if b & 0x80 == 0 {
+                break;
+            }
+            if frame.len() > 6 {
+                return Err(AppError::BadRequest("Z39.50 tag too long".to_string()));
+            }
+        }
+    }
+
+    let len_byte = stream.read_u8().await.map_err(io_err)?;
+    frame.push(len_byte);
+    let content_len = if len_byte & 0x80 == 0 {
+        len_byte as usize
+    } else {
+        let n = (len_byte & 0x7F) as usize;
+        if n == 0 {
+            return Err(AppError::BadRequest("Z39.50 indefinite length not supported".to_string()));
+        }
+        if n > 4 {
+            return Err(AppError::BadRequest("Z39.50 length too long".to_string()));
+        }
+        let mut len = 0usize;
+        for _ in 0..n {
+            let b = stream.read_u8().await.map_err(io_err)?;
+            frame.push(b);
+            len = (len << 8) | b as usize;
+        }
+        len
+    };
+    if content_len > MAX_FRAME_SIZE {
+        return Err(AppError::BadRequest(format!("Z39.50 APDU too large ({} bytes)", content_len)));
+    }
+
+    let header_len = frame.len();
+    frame.resize(header_len + content_len, 0);
+    stream.read_exact(&mut frame[header_len..]).await.map_err(io_err)?;
+    Ok(Some(frame))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use z3950_rs::pdu::{
+        make_init_request, make_search_request, make_type1_query, AttributeElement,
+        AttributesPlusTerm, RpnRpnOperator,
+    };
+
+    /// Single term with a BIB-1 use attribute (type 1)
+    fn use_term(use_attr: i64, term: &str) -> RpnStructure {
+        RpnStructure::Op(Operand::AttributesPlusTerm(AttributesPlusTerm {
+            attributes: vec![AttributeElement {
+                attribute_set: None,
+                attribute_type: 1.into(),
+                attribute_value: AttributeValue::Numeric(use_attr.into()),
+            }],
+            term: Term::General(OctetString::from(term.as_bytes().to_vec())),
+        }))
+    }
+
+    #[test]
+    fn bib1_use_attributes_map_to_fields() {
+        let title = use_term(4, "Dune");
+        assert_eq!(
+            rpn_to_search_node(&title, 0).unwrap(),
+            CatalogSearchNode::Term { field: CatalogSearchField::Title, value: "Dune".to_string() }
+        );
+
+        let isbn = use_term(7, "978-2-07-036822-8");
+        let author = use_term(1003, "Herbert");
+        let tree = RpnStructure::RpnRpnOperator(RpnRpnOperator {
+            rpn1: Box::new(isbn),
+            rpn2: Box::new(author),
+            op: Operator::Or(()),
+        });
+        assert_eq!(
+            rpn_to_search_node(&tree, 0).unwrap(),
+            CatalogSearchNode::Or(
+                Box::new(CatalogSearchNode::Term {
+                    field: CatalogSearchField::Isbn,
+                    value: "978-2-07-036822-8".to_string()
+                }),
+                Box::new(CatalogSearchNode::Term {
+                    field: CatalogSearchField::Author,
+                    value: "Herbert".to_string()
+                }),
+            )
+        );
+
+        let unsupported = use_term(12, "42");
+        assert_eq!(
+            rpn_to_search_node(&unsupported, 0).unwrap_err().condition,
+            diag::UNSUPPORTED_USE_ATTRIBUTE
+        );
+    }
+
+    #[test]
+    fn present_window_bounds() {
+        assert_eq!(present_window(1, 10, 25, 50).unwrap(), (0, 10));
+        assert_eq!(present_window(21, 10, 25, 50).unwrap(), (20, 25));
+        assert_eq!(present_window(1, 100, 25, 5).unwrap(), (0, 5));
+        assert_eq!(present_window(1, 0, 0, 5).unwrap(), (0, 0));
+        assert!(present_window(26, 1, 25, 50).is_err());
+        assert!(present_window(0, 1, 25, 50).is_err());
+    }
+
+    #[tokio::test]
+    async fn frames_round_trip_client_pdus() {
+        let init = Apdu::InitRequest(make_init_request(None).unwrap());
+        let query = make_type1_query(4, "Dune").unwrap();
+        let search = Apdu::SearchRequest(
+            make_search_request(&["elidune".to_string()], "default", query).unwrap(),
+        );
+        let mut wire = rasn::ber::encode(&init).unwrap();
+        wire.extend(rasn::ber::encode(&search).unwrap());
+
+        let mut reader = wire.as_slice();
+        let first = read_ber_frame(&mut reader).await.unwrap().unwrap();
+        let Apdu::InitRequest(req) = rasn::ber::decode::<Apdu>(&first).unwrap() else {
+            panic!("expected InitRequest");
+        };
+        let response = rasn::ber::encode(&Apdu::InitResponse(init_response(&req))).unwrap();
+        assert!(rasn::ber::decode::<InitResponse>(&response).unwrap().result);
+
+        let second = read_ber_frame(&mut reader).await.unwrap().unwrap();
+        assert!(matches!(rasn::ber::decode::<Apdu>(&second).unwrap(), Apdu::SearchRequest(_)));
+        assert!(read_ber_frame(&mut reader).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,125 @@
+//! Event envelope compatibility: the golden samples under `tests/contract/events` against the
+//! current payload types.
+//!
+//! Every published `(type, version)` keeps its sample (`<type>.v<version>.json`). A sample must
+//! still decode, and decoding then re-encoding it must keep every one of its fields: fields are
+//! only added within a version. Removing or renaming a field means bumping the version in
+//! `models::event_envelope` and adding the sample of the new version next to the old one.
+
+use std::path::{Path, PathBuf};
+
+use elidune_server::{
+    api::sse::SsePayload,
+    models::{
+        audit::AuditLogEntry,
+        event_envelope::{EventEnvelope, AUDIT_ENTRY_VERSION, SSE_EVENT_VERSIONS},
+    },
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+fn samples_dir(channel: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/contract/events").join(channel)
+}
+
+fn read_sample(path: &Path) -> Value {
+    let text = std::fs::read_to_string(path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    serde_json::from_str(&text).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+/// `(type, version)` of a sample file name
+fn sample_key(path: &Path) -> (String, u32) {
+    let stem = path.file_stem().and_then(|s| s.to_str()).expect("UTF-8 file name");
+    let (event_type, version) = stem.rsplit_once(".v").expect("`<type>.v<version>.json`");
+    (event_type.to_string(), version.parse().expect("numeric version"))
+}
+
+/// Fields of `golden` missing from (or of another JSON type in) `actual`, as JSON paths
+fn lost_fields(golden: &Value, actual: &Value, at: &str, lost: &mut Vec<String>) {
+    match (golden, actual) {
+        (Value::Object(expected), Value::Object(found)) => {
+            for (key, value) in expected {
+                let path = format!("{}.{}", at, key);
+                match found.get(key) {
+                    Some(other) => lost_fields(value, other, &path, lost),
+                    None => lost.push(path),
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(found)) => {
+            for (i, (value, other)) in expected.iter().zip(found).enumerate() {
+                lost_fields(value, other, &format!("{}[{}]", at, i), lost);
+            }
+        }
+        (expected, found) if std::mem::discriminant(expected) != std::mem::discriminant(found) => {
+            lost.push(format!("{} ({} became {})", at, expected, found));
+        }
+        _ => {}
+    }
+}
+
+/// Decode every sample of `channel` as an envelope of `P`, re-encode it and report lost fields
+fn check_samples<P: Serialize + DeserializeOwned>(channel: &str) -> Vec<(String, u32)> {
+    let mut keys = Vec::new();
+    for entry in std::fs::read_dir(samples_dir(channel)).expect("samples directory") {
+        let path = entry.unwrap().path();
+        let golden = read_sample(&path);
+        let (event_type, version) = sample_key(&path);
+        let envelope: EventEnvelope<P> = serde_json::from_value(golden.clone())
+            .unwrap_or_else(|e| panic!("{} no longer decodes: {}", path.display(), e));
+        assert_eq!(envelope.version, version, "{}: version differs from the file name", path.display());
+        if channel != "audit" {
+            assert_eq!(envelope.event_type, event_type, "{}: type differs from the file name", path.display());
+        }
+
+        let mut lost = Vec::new();
+        lost_fields(&golden, &serde_json::to_value(&envelope).unwrap(), "$", &mut lost);
+        assert!(lost.is_empty(), "{} lost fields: {:?}", path.display(), lost);
+        keys.push((event_type, version));
+    }
+    keys
+}
+
+#[test]
+fn sse_samples_stay_compatible() {
+    let samples = check_samples::<SsePayload>("sse");
+    for (event_type, version) in SSE_EVENT_VERSIONS {
+        assert!(
+            samples.iter().any(|(t, v)| t == event_type && v == version),
+            "no sample for {} v{} (add tests/contract/events/sse/{}.v{}.json)",
+            event_type,
+            version,
+            event_type,
+            version
+        );
+    }
+    for (event_type, version) in &samples {
+        let current = SSE_EVENT_VERSIONS.iter().find(|(t, _)| t == event_type);
+        assert!(
+            current.is_some_and(|(_, v)| v >= version),
+            "sample {} v{} is ahead of the registered version",
+            event_type,
+            version
+        );
+    }
+}
+
+#[test]
+fn audit_samples_stay_compatible() {
+    let samples = check_samples::<AuditLogEntry>("audit");
+    assert!(
+        samples.iter().any(|(_, v)| *v == AUDIT_ENTRY_VERSION),
+        "no sample for audit entries v{}",
+        AUDIT_ENTRY_VERSION
+    );
+}
+
+#[test]
+fn lost_fields_are_reported() {
+    let golden = serde_json::json!({ "payload": { "loan_id": "1", "counters": { "activeLoans": 2 } } });
+    let renamed = serde_json::json!({ "payload": { "loanId": "1", "counters": { "activeLoans": "2" } } });
+    let mut lost = Vec::new();
+    lost_fields(&golden, &renamed, "$", &mut lost);
+    lost.sort();
+    assert_eq!(lost, ["$.payload.counters.activeLoans (2 became \"2\")", "$.payload.loan_id"]);
+}
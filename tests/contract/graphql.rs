@@ -0,0 +1,71 @@
+//! GraphQL facade (`graphql` feature) on the live router:
+//!
+//! ```sh
+//! cargo test --features graphql --test contract -- --ignored
+//! ```
+
+use serde_json::{json, Value};
+
+use crate::server::{self, BARCODE, ISBN};
+
+const QUERY: &str = r#"
+    query {
+        biblios(isbn: "9782070360024") {
+            total
+            items { id isbn authors { lastname } items { barcode borrowed } availability { totalItems available } }
+        }
+        me { id loans { itemIdentification biblio { isbn availability { availableItems } } } }
+        stats { loans { active } }
+    }
+"#;
+
+async fn post(server: &server::Server, token: Option<&str>, query: &str) -> reqwest::Response {
+    let mut request = reqwest::Client::new()
+        .post(format!("{}/graphql", server.base_url))
+        .json(&json!({ "query": query }));
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    request.send().await.expect("POST /graphql")
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --features graphql --test contract -- --ignored
+async fn nested_graph_resolves_through_the_loaders() {
+    let server = server::boot().await;
+
+    let response = post(&server, Some(&server.token), QUERY).await;
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert!(body.get("errors").is_none(), "{}", body);
+
+    let data = &body["data"];
+    assert_eq!(data["biblios"]["total"], 1);
+    let biblio = &data["biblios"]["items"][0];
+    assert!(biblio["id"].is_string(), "ids are strings: {}", biblio);
+    assert_eq!(biblio["isbn"], ISBN);
+    assert_eq!(biblio["items"], json!([{ "barcode": BARCODE, "borrowed": true }]));
+    assert_eq!(biblio["availability"], json!({ "totalItems": 1, "available": false }));
+
+    let loans = data["me"]["loans"].as_array().unwrap();
+    assert_eq!(loans.len(), 1);
+    assert_eq!(loans[0]["itemIdentification"], BARCODE);
+    assert_eq!(loans[0]["biblio"]["availability"]["availableItems"], 0);
+    assert_eq!(data["stats"]["loans"]["active"], 1);
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --features graphql --test contract -- --ignored
+async fn errors_use_the_rest_codes() {
+    let server = server::boot().await;
+
+    let response = post(&server, None, "{ me { id } }").await;
+    assert_eq!(response.status(), 401);
+
+    let response = post(&server, Some(&server.token), r#"{ user(id: "9223372036854775807") { id } }"#).await;
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["data"], Value::Null);
+    assert_eq!(body["errors"][0]["extensions"]["code"], "not_found");
+    assert_eq!(body["errors"][0]["extensions"]["status"], 404);
+    assert_eq!(body["errors"][0]["path"], json!(["user"]));
+}
@@ -0,0 +1,33 @@
+//! Contract tests: the OpenAPI document against the router, and the event envelope samples.
+//!
+//! The route coverage checks are static and always run. The live walk boots the full router on a
+//! throwaway database (see `tests/repository`) and is ignored by default:
+//!
+//! ```sh
+//! cargo test --test contract -- --ignored
+//! ```
+//!
+//! Add `--features graphql` to also exercise the GraphQL facade.
+
+#[allow(dead_code)]
+#[path = "../repository/fixtures.rs"]
+mod fixtures;
+#[allow(dead_code)]
+#[path = "../repository/harness.rs"]
+mod harness;
+
+#[cfg(feature = "graphql")]
+mod graphql;
+mod events;
+mod live;
+mod routes;
+mod schema;
+mod server;
+
+use elidune_server::api::openapi::ApiDoc;
+use utoipa::OpenApi;
+
+/// The generated OpenAPI document as JSON
+fn openapi() -> serde_json::Value {
+    serde_json::to_value(ApiDoc::openapi()).expect("serialize OpenAPI document")
+}
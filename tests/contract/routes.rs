@@ -0,0 +1,178 @@
+//! Routed vs documented operations.
+//!
+//! Axum cannot list the routes of a built `Router`, so the routed side is read from the
+//! `.route("/path", get(..).post(..))` calls in `src/api`; the documented side from the
+//! generated OpenAPI document.
+
+use std::{collections::BTreeSet, fs, path::Path};
+
+use serde_json::Value;
+
+const METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+/// Routes that are deliberately left out of the OpenAPI document
+const UNDOCUMENTED: &[(&str, &str)] = &[
+    // GraphQL facade (`graphql` feature): described by its own schema, not by OpenAPI
+    ("get", "/graphql"),
+    ("post", "/graphql"),
+    // Postman collection generated from the OpenAPI document, served next to `/api-docs`
+    ("get", "/openapi/collection"),
+];
+
+/// `(method, OpenAPI path)`, e.g. `("get", "/items/{id}")`
+pub type Operation = (String, String);
+
+/// Every operation of the OpenAPI document (`paths` are relative to the `/api/v1` server)
+pub fn documented(doc: &Value) -> BTreeSet<Operation> {
+    let mut operations = BTreeSet::new();
+    for (path, item) in doc["paths"].as_object().expect("OpenAPI paths") {
+        for method in METHODS {
+            if item.get(method).is_some() {
+                operations.insert((method.to_string(), path.clone()));
+            }
+        }
+    }
+    operations
+}
+
+/// Every `.route(..)` registered by the `src/api` modules (test code excluded)
+pub fn routed() -> BTreeSet<Operation> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/api");
+    let mut operations = BTreeSet::new();
+    for entry in fs::read_dir(&dir).expect("read src/api") {
+        let path = entry.expect("src/api entry").path();
+        // The top-level router only nests the module routers under /api/v1
+        if path.extension().is_none_or(|e| e != "rs") || path.ends_with("router.rs") {
+            continue;
+        }
+        let source = fs::read_to_string(&path).expect("read api module");
+        let source = source.split("#[cfg(test)]").next().unwrap_or_default();
+        operations.extend(parse_routes(source));
+    }
+    operations
+}
+
+fn parse_routes(source: &str) -> Vec<Operation> {
+    let mut operations = Vec::new();
+    let mut rest = source;
+    while let Some(start) = rest.find(".route(") {
+        rest = &rest[start + ".route(".len()..];
+        let call = &rest[..closing_paren(rest)];
+        let Some((path, method_router)) = call
+            .trim_start()
+            .strip_prefix('"')
+            .and_then(|s| s.split_once('"'))
+        else {
+            continue;
+        };
+        let path = openapi_path(path);
+        for method in METHODS {
+            if calls(method_router, method) {
+                operations.push((method.to_string(), path.clone()));
+            }
+        }
+    }
+    operations
+}
+
+/// Offset of the parenthesis closing the call whose arguments start `s`
+fn closing_paren(s: &str) -> usize {
+    let mut depth = 1;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    s.len()
+}
+
+/// `get(` / `.get(` as a method-router call, not as part of a handler name
+fn calls(method_router: &str, method: &str) -> bool {
+    let needle = format!("{}(", method);
+    method_router.match_indices(&needle).any(|(i, _)| {
+        method_router[..i]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !(c.is_alphanumeric() || c == '_'))
+    })
+}
+
+/// `/items/:id/*path` → `/items/{id}/{path}`
+fn openapi_path(axum_path: &str) -> String {
+    axum_path
+        .split('/')
+        .map(|segment| match segment.strip_prefix(':').or_else(|| segment.strip_prefix('*')) {
+            Some(name) => format!("{{{}}}", name),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn allowed_undocumented(operation: &Operation) -> bool {
+    UNDOCUMENTED
+        .iter()
+        .any(|(method, path)| operation.0 == *method && operation.1 == *path)
+}
+
+#[test]
+fn every_route_is_documented() {
+    let documented = documented(&crate::openapi());
+    let missing: Vec<_> = routed()
+        .into_iter()
+        .filter(|op| !documented.contains(op) && !allowed_undocumented(op))
+        .map(|(method, path)| format!("{} {}", method.to_uppercase(), path))
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "routes missing from the OpenAPI document (add them to `ApiDoc` or to UNDOCUMENTED):\n  {}",
+        missing.join("\n  ")
+    );
+}
+
+#[test]
+fn every_documented_operation_is_routed() {
+    let routed = routed();
+    let dangling: Vec<_> = documented(&crate::openapi())
+        .into_iter()
+        .filter(|op| !routed.contains(op))
+        .map(|(method, path)| format!("{} {}", method.to_uppercase(), path))
+        .collect();
+    assert!(
+        dangling.is_empty(),
+        "documented operations without a route:\n  {}",
+        dangling.join("\n  ")
+    );
+}
+
+#[test]
+fn parses_multi_line_method_routers() {
+    let source = r#"
+        Router::new()
+            .route("/donations/:id", get(get_donation).put(update_donation).delete(delete_donation))
+            .route(
+                "/stats/saved/:id",
+                get(get_saved_query)
+                    .put(update_saved_query),
+            )
+            .route("/files/*path", post(upload_post))
+    "#;
+    assert_eq!(
+        parse_routes(source),
+        vec![
+            ("get".to_string(), "/donations/{id}".to_string()),
+            ("put".to_string(), "/donations/{id}".to_string()),
+            ("delete".to_string(), "/donations/{id}".to_string()),
+            ("get".to_string(), "/stats/saved/{id}".to_string()),
+            ("put".to_string(), "/stats/saved/{id}".to_string()),
+            ("post".to_string(), "/files/{path}".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,180 @@
+//! Minimal OpenAPI 3.0 schema validation for response bodies.
+//!
+//! Covers what utoipa generates: `$ref`, `allOf` / `oneOf` / `anyOf`, `nullable`, `enum`,
+//! primitive types, objects (`required`, `properties`, `additionalProperties`) and arrays.
+//! Formats and string patterns are not checked.
+
+use serde_json::Value;
+
+const REF_PREFIX: &str = "#/components/schemas/";
+
+/// Mismatches between `value` and `schema`, as `json.path: problem`
+pub fn validate(doc: &Value, schema: &Value, value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    check(doc, schema, value, "$", &mut errors);
+    errors
+}
+
+/// `$ref`s of the document that do not resolve to a component schema
+pub fn dangling_refs(doc: &Value) -> Vec<String> {
+    let mut refs = Vec::new();
+    collect_refs(doc, &mut refs);
+    refs.sort();
+    refs.dedup();
+    refs.into_iter().filter(|r| resolve(doc, r).is_none()).collect()
+}
+
+fn collect_refs(value: &Value, refs: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(r)) = map.get("$ref") {
+                refs.push(r.clone());
+            }
+            map.values().for_each(|v| collect_refs(v, refs));
+        }
+        Value::Array(values) => values.iter().for_each(|v| collect_refs(v, refs)),
+        _ => {}
+    }
+}
+
+fn resolve<'a>(doc: &'a Value, reference: &str) -> Option<&'a Value> {
+    let name = reference.strip_prefix(REF_PREFIX)?;
+    doc["components"]["schemas"].get(name)
+}
+
+fn check(doc: &Value, schema: &Value, value: &Value, at: &str, errors: &mut Vec<String>) {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        match resolve(doc, reference) {
+            Some(target) => check(doc, target, value, at, errors),
+            None => errors.push(format!("{}: unresolved {}", at, reference)),
+        }
+        return;
+    }
+    if value.is_null() && schema.get("nullable").and_then(Value::as_bool) == Some(true) {
+        return;
+    }
+    if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+        for sub in all {
+            check(doc, sub, value, at, errors);
+        }
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(alternatives) = schema.get(key).and_then(Value::as_array) {
+            let matched = alternatives
+                .iter()
+                .any(|sub| validate_at(doc, sub, value, at).is_empty());
+            if !matched {
+                errors.push(format!("{}: matches none of the {} alternatives", at, key));
+            }
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            errors.push(format!("{}: {} is not one of {}", at, value, Value::from(allowed.clone())));
+        }
+    }
+
+    let Some(ty) = schema.get("type").and_then(Value::as_str) else {
+        return;
+    };
+    let type_ok = match ty {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        _ => true,
+    };
+    if !type_ok {
+        errors.push(format!("{}: expected {}, got {}", at, ty, kind(value)));
+        return;
+    }
+
+    match value {
+        Value::Object(map) => {
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !map.contains_key(name) {
+                        errors.push(format!("{}: missing required property `{}`", at, name));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let additional = schema.get("additionalProperties").filter(|s| s.is_object());
+            for (name, field) in map {
+                let field_at = format!("{}.{}", at, name);
+                if let Some(sub) = properties.and_then(|p| p.get(name)).or(additional) {
+                    check(doc, sub, field, &field_at, errors);
+                }
+            }
+        }
+        Value::Array(values) => {
+            if let Some(items) = schema.get("items") {
+                for (i, item) in values.iter().enumerate() {
+                    check(doc, items, item, &format!("{}[{}]", at, i), errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn validate_at(doc: &Value, schema: &Value, value: &Value, at: &str) -> Vec<String> {
+    let mut errors = Vec::new();
+    check(doc, schema, value, at, &mut errors);
+    errors
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[test]
+fn every_ref_resolves() {
+    let dangling = dangling_refs(&crate::openapi());
+    assert!(
+        dangling.is_empty(),
+        "schemas referenced but not registered in `ApiDoc` components:\n  {}",
+        dangling.join("\n  ")
+    );
+}
+
+#[test]
+fn reports_mismatches_with_their_path() {
+    let doc = serde_json::json!({
+        "components": { "schemas": {
+            "Item": {
+                "type": "object",
+                "required": ["id", "state"],
+                "properties": {
+                    "id": { "type": "string" },
+                    "state": { "type": "string", "enum": ["available", "lost"] },
+                    "note": { "type": "string", "nullable": true },
+                    "tags": { "type": "array", "items": { "type": "string" } }
+                }
+            }
+        }}
+    });
+    let schema = serde_json::json!({ "type": "array", "items": { "$ref": "#/components/schemas/Item" } });
+
+    let ok = serde_json::json!([{ "id": "1", "state": "lost", "note": null, "tags": ["a"] }]);
+    assert!(validate(&doc, &schema, &ok).is_empty());
+
+    let bad = serde_json::json!([{ "id": 1, "state": "gone", "tags": [3] }]);
+    assert_eq!(
+        validate(&doc, &schema, &bad),
+        vec![
+            "$[0].id: expected string, got number",
+            "$[0].state: \"gone\" is not one of [\"available\",\"lost\"]",
+            "$[0].tags[0]: expected string, got number",
+        ]
+    );
+}
@@ -0,0 +1,104 @@
+//! The full router (rate limiters, `ConnectInfo`) served on a fresh database with a few fixtures:
+//! an admin account and a copy of [`ISBN`] (barcode [`BARCODE`]) on loan to it.
+
+use std::{net::SocketAddr, path::Path, sync::Arc};
+
+use elidune_server::{
+    api::router::create_router, config::AppConfig, services::Services, AppState, DynamicConfig,
+    EmailService,
+};
+use tokio::sync::{broadcast, Notify};
+
+use crate::{
+    fixtures::{ItemBuilder, LoanBuilder, UserBuilder},
+    harness::{self, TestDb},
+};
+
+pub const ISBN: &str = "9782070360024";
+pub const BARCODE: &str = "C-0001";
+pub const ADMIN_LOGIN: &str = "contract-admin";
+pub const ADMIN_PASSWORD: &str = "contract-password";
+
+pub struct Server {
+    /// API root, e.g. `http://127.0.0.1:41234/api/v1`
+    pub base_url: String,
+    /// Access token of the admin account
+    pub token: String,
+    _db: TestDb,
+}
+
+pub async fn boot() -> Server {
+    let db = TestDb::new().await;
+
+    // Also included by the client crate's tests, one directory below the server
+    let sample = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .ancestors()
+        .map(|dir| dir.join("config/sample.toml"))
+        .find(|path| path.exists())
+        .expect("config/sample.toml");
+    let mut config = AppConfig::load(Some(&sample)).expect("load config/sample.toml");
+    config.redis.url = harness::redis_url().to_string();
+    config.meilisearch = None;
+    config.artifacts.directory = std::env::temp_dir()
+        .join(format!("elidune-contract-{}", uuid::Uuid::new_v4().simple()))
+        .to_string_lossy()
+        .into_owned();
+    // The walk calls every endpoint once, far above the per-IP defaults
+    config.server.auth_rate_burst = Some(1000);
+    config.server.public_rate_burst = Some(1000);
+    config.server.widget_rate_burst = Some(1000);
+    config.server.api_key_rate_burst = Some(1000);
+    config.server.barcode_login_rate_burst = Some(1000);
+
+    let dynamic_config = DynamicConfig::new(config.clone());
+    let email = Arc::new(EmailService::new(dynamic_config.clone(), db.pool.clone()));
+    let services = Services::new(
+        db.repo.clone(),
+        config.users.clone(),
+        dynamic_config.clone(),
+        config.redis.clone(),
+        harness::redis().await,
+        None,
+        email,
+        config.artifacts.clone(),
+    )
+    .await
+    .expect("build services");
+
+    let admin_id = UserBuilder::new(ADMIN_LOGIN).account_type("admin").insert(&db.pool).await;
+    let admin = services.users.get_by_id(admin_id).await.expect("admin user");
+    let token = services.users.issue_access_token(&admin).await.expect("admin token");
+    let password = services.users.hash_password(ADMIN_PASSWORD).expect("hash password");
+    sqlx::query("UPDATE users SET password = $1 WHERE id = $2")
+        .bind(password)
+        .bind(admin_id)
+        .execute(&db.pool)
+        .await
+        .expect("set admin password");
+    let item = ItemBuilder::new(BARCODE).isbn(ISBN).insert(&db.pool).await;
+    LoanBuilder::new(admin_id, item).insert(&db.repo).await;
+
+    let state = AppState {
+        config: Arc::new(config),
+        dynamic_config,
+        services: Arc::new(services),
+        scheduler_notify: Arc::new(Notify::new()),
+        event_bus: broadcast::channel(16).0,
+    };
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let addr = listener.local_addr().expect("local address");
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            create_router(state).into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    Server {
+        base_url: format!("http://{}/api/v1", addr),
+        token,
+        _db: db,
+    }
+}
@@ -0,0 +1,76 @@
+use chrono::Utc;
+use elidune_server::models::{
+    api_key::{CreateApiKey, UpdateApiKey, SCOPE_OPAC_AVAILABILITY},
+    biblio::Isbn,
+};
+
+use crate::{
+    fixtures::{ItemBuilder, LoanBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn usage_is_counted_per_day_and_endpoint() {
+    let db = TestDb::new().await;
+    let admin = UserBuilder::new("keyadmin").insert(&db.pool).await;
+    let data = CreateApiKey {
+        name: " City app ".to_string(),
+        scopes: vec![SCOPE_OPAC_AVAILABILITY.to_string()],
+        expires_at: None,
+    };
+    let key = db.repo.api_keys_create(&data, "hash-1", "apikey_abcdefg", admin).await.unwrap();
+    assert_eq!(key.name, "City app");
+    assert!(key.last_used_at.is_none());
+    assert_eq!(db.repo.api_keys_find_by_token_hash("hash-1").await.unwrap().map(|k| k.id), Some(key.id));
+
+    db.repo.api_keys_record_usage(key.id, SCOPE_OPAC_AVAILABILITY, 3).await.unwrap();
+    db.repo.api_keys_record_usage(key.id, SCOPE_OPAC_AVAILABILITY, 40).await.unwrap();
+    let today = Utc::now().date_naive();
+    let usage = db.repo.api_keys_usage(key.id, today, today).await.unwrap();
+    assert_eq!(usage.len(), 1);
+    assert_eq!((usage[0].request_count, usage[0].record_count), (2, 43));
+    assert!(db.repo.api_keys_get(key.id).await.unwrap().last_used_at.is_some());
+
+    // Revoking keeps the key; a new secret replaces the old hash
+    let update = UpdateApiKey { name: None, scopes: None, is_active: Some(false), expires_at: None, clear_expires_at: false };
+    assert!(!db.repo.api_keys_update(key.id, &update).await.unwrap().is_active);
+    db.repo.api_keys_set_token(key.id, "hash-2", "apikey_hijklmn").await.unwrap();
+    assert!(db.repo.api_keys_find_by_token_hash("hash-1").await.unwrap().is_none());
+
+    // Deleting drops the usage history
+    db.repo.api_keys_delete(key.id).await.unwrap();
+    assert!(db.repo.api_keys_usage(key.id, today, today).await.unwrap().is_empty());
+    assert!(db.repo.api_keys_get(key.id).await.is_err());
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn availability_is_resolved_for_many_isbns() {
+    let db = TestDb::new().await;
+    let user_id = UserBuilder::new("isbnreader").insert(&db.pool).await;
+    let on_loan = ItemBuilder::new("AV-1").isbn("9782070612758").insert(&db.pool).await;
+    LoanBuilder::new(user_id, on_loan).insert(&db.repo).await;
+    ItemBuilder::new("AV-2").isbn("9782253004226").insert(&db.pool).await;
+
+    let isbns = [Isbn::new("978-2-07-061275-8"), Isbn::new("9782253004226"), Isbn::new("9780000000002")];
+    let found = db.repo.biblios_get_availability_by_isbns(&isbns).await.unwrap();
+    assert_eq!(found.len(), 2);
+    let loaned = &found[&isbns[0]];
+    assert_eq!((loaned.total_items, loaned.available_items), (1, 0));
+    assert!(found[&isbns[1]].is_available());
+    assert!(!found.contains_key(&isbns[2]));
+
+    // A floating copy travelling back to its place is not available, as in the widget
+    let travelling = ItemBuilder::new("AV-3").isbn("9782253006329").insert(&db.pool).await;
+    sqlx::query("UPDATE items SET transit_place = 1 WHERE id = $1")
+        .bind(travelling.item_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    let isbn = Isbn::new("9782253006329");
+    let batch = &db.repo.biblios_get_availability_by_isbns(std::slice::from_ref(&isbn)).await.unwrap()[&isbn];
+    let widget = db.repo.biblios_get_availability_by_isbn(&isbn).await.unwrap().unwrap();
+    assert_eq!((batch.total_items, batch.available_items), (1, 0));
+    assert_eq!((widget.total_items, widget.available_items), (1, 0));
+}
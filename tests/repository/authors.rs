@@ -0,0 +1,79 @@
+use crate::{fixtures::ItemBuilder, harness::TestDb};
+
+async fn insert_author(db: &TestDb, lastname: &str, firstname: Option<&str>) -> i64 {
+    sqlx::query_scalar("INSERT INTO authors (lastname, firstname) VALUES ($1, $2) RETURNING id")
+        .bind(lastname)
+        .bind(firstname)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap()
+}
+
+async fn credit(db: &TestDb, biblio_id: i64, author_id: i64) {
+    sqlx::query("INSERT INTO biblio_authors (biblio_id, author_id, function) VALUES ($1, $2, 'author')")
+        .bind(biblio_id)
+        .bind(author_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn normalization_previews_then_renames_and_merges_duplicates() {
+    let db = TestDb::new().await;
+    let hobbit = ItemBuilder::new("AU-1").insert(&db.pool).await;
+    let silmarillion = ItemBuilder::new("AU-2").insert(&db.pool).await;
+    let inverted = insert_author(&db, "TOLKIEN J.R.R.", None).await;
+    let lowercase = insert_author(&db, "tolkien", Some("j.r.r.")).await;
+    let clean = insert_author(&db, "Le Guin", Some("Ursula K.")).await;
+    credit(&db, hobbit.biblio_id, inverted).await;
+    credit(&db, silmarillion.biblio_id, lowercase).await;
+    sqlx::query("UPDATE authors SET bio = 'Philologist' WHERE id = $1")
+        .bind(lowercase)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let preview = db.repo.authors_normalize(false, 10).await.unwrap();
+    assert!(preview.dry_run);
+    assert_eq!((preview.renamed, preview.merged), (1, 1));
+    assert!(preview.changes.iter().all(|c| c.id != clean));
+    let merge = preview.changes.iter().find(|c| c.id == lowercase).expect("merge listed");
+    assert_eq!(merge.merged_into, Some(inverted));
+    assert_eq!(db.repo.authors_get(lowercase).await.unwrap().lastname.as_deref(), Some("tolkien"));
+
+    let report = db.repo.authors_normalize(true, 10).await.unwrap();
+    assert_eq!((report.renamed, report.merged), (1, 1));
+    let mut biblio_ids = vec![hobbit.biblio_id, silmarillion.biblio_id];
+    biblio_ids.sort_unstable();
+    assert_eq!(report.biblio_ids, biblio_ids);
+
+    let kept = db.repo.authors_get(inverted).await.unwrap();
+    assert_eq!((kept.lastname.as_deref(), kept.firstname.as_deref()), (Some("Tolkien"), Some("J. R. R.")));
+    assert_eq!(kept.bio.as_deref(), Some("Philologist"));
+    assert!(db.repo.authors_get(lowercase).await.is_err());
+    let credited: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM biblio_authors WHERE author_id = $1")
+        .bind(inverted)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(credited, 2);
+
+    // Nothing left to do
+    let again = db.repo.authors_normalize(false, 10).await.unwrap();
+    assert_eq!((again.renamed, again.merged), (0, 0));
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn display_name_is_set_trimmed_and_cleared() {
+    let db = TestDb::new().await;
+    let id = insert_author(&db, "Tolkien", Some("John Ronald Reuel")).await;
+
+    let author = db.repo.authors_set_display_name(id, Some("  J. R. R. Tolkien ")).await.unwrap();
+    assert_eq!(author.display_name.as_deref(), Some("J. R. R. Tolkien"));
+    let author = db.repo.authors_set_display_name(id, Some("")).await.unwrap();
+    assert_eq!(author.display_name, None);
+    assert!(db.repo.authors_set_display_name(id + 1_000, None).await.is_err());
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use elidune_server::{
+    config::{ArtifactsConfig, BackupConfig},
+    error::AppError,
+    models::backup::{BackupDestination, BackupStatus},
+    services::{
+        artifacts::{ArtifactsService, FilesystemBlobStore},
+        audit::AuditService,
+        backup::{restore::restore_file, BackupService},
+    },
+};
+use sqlx::postgres::PgPoolOptions;
+
+use crate::{
+    fixtures::{ItemBuilder, LoanBuilder, UserBuilder},
+    harness::{self, TestDb},
+};
+
+async fn count(pool: &sqlx::PgPool, table: &str) -> i64 {
+    sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn backup_restores_into_an_empty_database() {
+    let db = TestDb::new().await;
+    let admin = UserBuilder::new("backup-admin").account_type("admin").insert(&db.pool).await;
+    let reader = UserBuilder::new("backup-reader").insert(&db.pool).await;
+    let item = ItemBuilder::new("BK-0001").title("Tab\tand\nnewline \\ title").insert(&db.pool).await;
+    LoanBuilder::new(reader, item).insert(&db.repo).await;
+
+    let dir = std::env::temp_dir().join(format!("elidune-backups-{}", uuid::Uuid::new_v4().simple()));
+    let artifacts = ArtifactsService::new(
+        Arc::new(db.repo.clone()),
+        Arc::new(FilesystemBlobStore::new(&dir)),
+        ArtifactsConfig::default(),
+        "test-secret",
+    );
+    let service = BackupService::new(
+        Arc::new(db.repo.clone()),
+        db.pool.clone(),
+        artifacts.clone(),
+        harness::redis().await,
+        AuditService::new(db.repo.clone()),
+        BackupConfig::default(),
+    );
+
+    let started = service.start(admin).await.unwrap();
+    assert_eq!(started.status, BackupStatus::Running);
+    assert_eq!(started.destination, BackupDestination::Artifacts);
+    assert!(matches!(service.start(admin).await, Err(AppError::Conflict(_))));
+
+    let backup = service.execute(started).await.unwrap();
+    assert_eq!(backup.status, BackupStatus::Succeeded, "{:?}", backup.error);
+    assert!(backup.size_bytes.unwrap() > 0);
+    assert!(backup.row_count.unwrap() >= 4);
+    let artifact = artifacts.get(backup.artifact_id.unwrap()).await.unwrap();
+    assert!(artifact.expires_at > chrono::Utc::now() + chrono::Duration::days(13));
+    assert_eq!(service.list(None).await.unwrap()[0].id, backup.id);
+
+    // Rows written after the backup are not in it
+    UserBuilder::new("after-backup").insert(&db.pool).await;
+
+    let file = dir.join(&artifact.storage_key);
+    let target_url = harness::empty_database_url().await;
+    let report = restore_file(&target_url, &file, false).await.unwrap();
+    assert_eq!(report.schema_version, backup.schema_version.unwrap());
+    assert_eq!(report.migrations_applied, 0);
+    assert_eq!(report.tables as i32, backup.table_count.unwrap());
+
+    let target = PgPoolOptions::new().max_connections(2).connect(&target_url).await.unwrap();
+    assert_eq!(count(&target, "users").await, count(&db.pool, "users").await - 1);
+    assert_eq!(count(&target, "loans").await, 1);
+    let title: String = sqlx::query_scalar("SELECT title FROM biblios")
+        .fetch_one(&target)
+        .await
+        .unwrap();
+    assert_eq!(title, "Tab\tand\nnewline \\ title");
+    // Sequences follow the restored rows
+    let next = UserBuilder::new("after-restore").insert(&target).await;
+    assert!(next > reader);
+
+    // A database holding data is only replaced on request
+    assert!(matches!(restore_file(&target_url, &file, false).await, Err(AppError::Conflict(_))));
+    restore_file(&target_url, &file, true).await.unwrap();
+    assert_eq!(count(&target, "loans").await, 1);
+
+    let _ = tokio::fs::remove_dir_all(&dir).await;
+}
@@ -0,0 +1,101 @@
+use elidune_server::{
+    error::AppError,
+    models::{bundle::CreateItemBundle, loan::CreateLoan},
+};
+
+use crate::{
+    fixtures::{ItemBuilder, LoanBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+#[tokio::test]
+#[ignore]
+async fn bundle_circulates_as_one_unit_and_reports_missing_parts() {
+    let db = TestDb::new().await;
+    let reader = UserBuilder::new("reader1").insert(&db.pool).await;
+    let volumes = [
+        ItemBuilder::new("ENC-1").insert(&db.pool).await,
+        ItemBuilder::new("ENC-2").insert(&db.pool).await,
+        ItemBuilder::new("ENC-3").insert(&db.pool).await,
+    ];
+
+    let bundle = db
+        .repo
+        .bundles_create(&CreateItemBundle {
+            name: "Encyclopedia".to_string(),
+            barcode: Some("BOX-1".to_string()),
+            notes: None,
+            part_barcodes: vec!["ENC-1".into(), "ENC-2".into(), "ENC-3".into()],
+        })
+        .await
+        .unwrap();
+    assert_eq!(bundle.parts_count, 3);
+    assert!(bundle.is_available());
+
+    // Scanning the box checks out every volume with one due date
+    db.repo
+        .loans_create(&CreateLoan {
+            user_id: reader,
+            item_id: None,
+            equipment_id: None,
+            item_identification: Some("BOX-1".to_string()),
+            force: false,
+            deposit_received: false,
+            override_blocks: false,
+        })
+        .await
+        .unwrap();
+    let (parts_out, due_dates): (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*), COUNT(DISTINCT expiry_at) FROM loans WHERE bundle_id = $1",
+    )
+    .bind(bundle.id)
+    .fetch_one(&db.pool)
+    .await
+    .unwrap();
+    assert_eq!((parts_out, due_dates), (3, 1));
+
+    // Any part is already out with the set
+    assert!(matches!(
+        db.repo.loans_create(&LoanBuilder::new(reader, volumes[1]).request()).await,
+        Err(AppError::BusinessRule(_))
+    ));
+
+    // Returning one volume alone flags the two others
+    let loan_id = db.repo.bundles_get(bundle.id).await.unwrap().parts[0].loan_id.unwrap();
+    let outcome = db.repo.loans_return(loan_id).await.unwrap();
+    assert_eq!(outcome.missing_bundle_parts.len(), 2);
+
+    // The returned volume stays unavailable while the set is incomplete
+    let availability = db
+        .repo
+        .biblios_get_availability_by_ids(&[volumes[0].biblio_id])
+        .await
+        .unwrap();
+    assert_eq!(availability[&volumes[0].biblio_id].available_items, 0);
+
+    let report = db.repo.bundles_return(bundle.id, &["ENC-2".to_string()]).await.unwrap();
+    assert_eq!(report.returned.len(), 1);
+    assert_eq!(report.missing.len(), 1);
+    assert_eq!(report.missing[0].barcode.as_deref(), Some("ENC-3"));
+
+    assert!(matches!(
+        db.repo.bundles_delete(bundle.id).await,
+        Err(AppError::BusinessRule(_))
+    ));
+    let report = db.repo.bundles_return(bundle.id, &["ENC-3".to_string()]).await.unwrap();
+    assert!(report.is_complete());
+
+    let availability = db
+        .repo
+        .biblios_get_availability_by_ids(&[volumes[0].biblio_id])
+        .await
+        .unwrap();
+    assert_eq!(availability[&volumes[0].biblio_id].available_items, 1);
+
+    db.repo.bundles_delete(bundle.id).await.unwrap();
+    let bundled: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items WHERE bundle_id IS NOT NULL")
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(bundled, 0);
+}
@@ -0,0 +1,89 @@
+use elidune_server::models::campaign::{CampaignRecipientStatus, CampaignSegment, CampaignStatus, CreateCampaign};
+
+use crate::{fixtures::UserBuilder, harness::TestDb};
+
+#[tokio::test]
+#[ignore]
+async fn segment_is_queued_drained_and_bounces_counted() {
+    let db = TestDb::new().await;
+    let librarian = UserBuilder::new("librarian1").account_type("librarian").insert(&db.pool).await;
+    let teen = UserBuilder::new("teen").insert(&db.pool).await;
+    let sibling = UserBuilder::new("sibling").insert(&db.pool).await;
+    let adult = UserBuilder::new("adult").insert(&db.pool).await;
+    let no_consent = UserBuilder::new("noconsent").insert(&db.pool).await;
+    for (id, email, age, opt_in) in [
+        (teen, "Family@Example.org", 15, true),
+        (sibling, "family@example.org ", 13, true),
+        (adult, "adult@example.org", 40, true),
+        (no_consent, "quiet@example.org", 14, false),
+    ] {
+        sqlx::query(
+            "UPDATE users SET email = $1, birthdate = CURRENT_DATE - make_interval(years => $2), \
+             campaigns_opt_in = $3 WHERE id = $4",
+        )
+        .bind(email)
+        .bind(age)
+        .bind(opt_in)
+        .bind(id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    }
+
+    // Teenagers who opted in: the siblings share one address
+    let segment = CampaignSegment { min_age: Some(12), max_age: Some(17), ..Default::default() };
+    assert_eq!(db.repo.campaigns_segment_count(&segment).await.unwrap(), 1);
+    let everyone = CampaignSegment { opted_in_only: false, ..segment.clone() };
+    assert_eq!(db.repo.campaigns_segment_count(&everyone).await.unwrap(), 2);
+
+    let campaign = db
+        .repo
+        .campaigns_create(
+            &CreateCampaign {
+                name: "Manga club".to_string(),
+                subject: "Manga club starts".to_string(),
+                body: "See you there".to_string(),
+                segment: everyone,
+            },
+            librarian,
+        )
+        .await
+        .unwrap();
+    assert_eq!(campaign.status, CampaignStatus::Draft);
+    assert_eq!(db.repo.campaigns_enqueue(campaign.id).await.unwrap(), Some(2));
+    // Already sending: nothing queued twice
+    assert_eq!(db.repo.campaigns_enqueue(campaign.id).await.unwrap(), None);
+
+    let first = db.repo.campaigns_claim_next(campaign.id).await.unwrap().unwrap();
+    let second = db.repo.campaigns_claim_next(campaign.id).await.unwrap().unwrap();
+    assert!(db.repo.campaigns_claim_next(campaign.id).await.unwrap().is_none());
+    assert_eq!(first.email, "family@example.org");
+    assert!(!db.repo.campaigns_finish(campaign.id).await.unwrap());
+    db.repo.campaigns_mark_delivery(campaign.id, &first.email, None).await.unwrap();
+    db.repo
+        .campaigns_mark_delivery(campaign.id, &second.email, Some("550 mailbox unavailable"))
+        .await
+        .unwrap();
+    assert!(db.repo.campaigns_finish(campaign.id).await.unwrap());
+
+    let bounced = db
+        .repo
+        .campaigns_record_bounces(campaign.id, &["FAMILY@example.org".to_string(), second.email.clone()])
+        .await
+        .unwrap();
+    assert_eq!(bounced, vec!["family@example.org".to_string()]);
+
+    let campaign = db.repo.campaigns_get(campaign.id).await.unwrap();
+    assert_eq!(campaign.status, CampaignStatus::Sent);
+    assert_eq!(
+        (campaign.recipients_count, campaign.pending_count, campaign.delivered_count, campaign.failed_count, campaign.bounced_count),
+        (2, 0, 0, 1, 1)
+    );
+    let failed = db
+        .repo
+        .campaigns_recipients(campaign.id, Some(CampaignRecipientStatus::Failed))
+        .await
+        .unwrap();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].user_id, Some(no_consent));
+}
@@ -0,0 +1,77 @@
+use elidune_server::{
+    error::AppError,
+    models::dashboard::{DashboardWidgetType, DashboardWidgetWrite},
+};
+use serde_json::json;
+
+use crate::{fixtures::UserBuilder, harness::TestDb};
+
+fn widget(widget_type: DashboardWidgetType, title: &str) -> DashboardWidgetWrite {
+    DashboardWidgetWrite {
+        widget_type,
+        title: Some(title.to_string()),
+        params: json!({}),
+        layout: Some(json!({ "x": 0, "y": 0, "w": 6, "h": 4 })),
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn dashboards_are_personal_and_ordered() {
+    let db = TestDb::new().await;
+    let librarian = UserBuilder::new("librarian1").account_type("librarian").insert(&db.pool).await;
+    let other = UserBuilder::new("librarian2").account_type("librarian").insert(&db.pool).await;
+
+    let loans = db
+        .repo
+        .dashboards_add_widget(librarian, &widget(DashboardWidgetType::LoanStats, "Loans"))
+        .await
+        .unwrap();
+    let catalog = db
+        .repo
+        .dashboards_add_widget(librarian, &widget(DashboardWidgetType::CatalogStats, "Catalog"))
+        .await
+        .unwrap();
+    assert_eq!((loans.position, catalog.position), (0, 1));
+    assert_eq!(catalog.layout, Some(json!({ "x": 0, "y": 0, "w": 6, "h": 4 })));
+
+    // Widgets of another user can be neither changed nor removed
+    assert!(matches!(
+        db.repo.dashboards_update_widget(other, loans.id, &widget(DashboardWidgetType::Stats, "Mine")).await,
+        Err(AppError::NotFound(_))
+    ));
+    assert!(matches!(
+        db.repo.dashboards_delete_widget(other, loans.id).await,
+        Err(AppError::NotFound(_))
+    ));
+    assert!(db.repo.dashboards_list_widgets(other).await.unwrap().is_empty());
+
+    let updated = db
+        .repo
+        .dashboards_update_widget(librarian, loans.id, &widget(DashboardWidgetType::UserStats, "Readers"))
+        .await
+        .unwrap();
+    assert_eq!((updated.widget_type, updated.position), (DashboardWidgetType::UserStats, 0));
+
+    let saved = db
+        .repo
+        .dashboards_replace(
+            librarian,
+            &[
+                widget(DashboardWidgetType::VisitorStats, "Visitors"),
+                widget(DashboardWidgetType::Stats, "Overview"),
+            ],
+        )
+        .await
+        .unwrap();
+    let listed = db.repo.dashboards_list_widgets(librarian).await.unwrap();
+    assert_eq!(
+        listed.iter().map(|w| (w.id, w.title.clone().unwrap())).collect::<Vec<_>>(),
+        saved.iter().map(|w| (w.id, w.title.clone().unwrap())).collect::<Vec<_>>()
+    );
+    assert_eq!(listed[0].title.as_deref(), Some("Visitors"));
+    assert_eq!(db.repo.dashboards_count_widgets(librarian).await.unwrap(), 2);
+
+    db.repo.dashboards_replace(librarian, &[]).await.unwrap();
+    assert_eq!(db.repo.dashboards_count_widgets(librarian).await.unwrap(), 0);
+}
@@ -0,0 +1,81 @@
+use chrono::{Duration, Local};
+use elidune_server::{
+    error::AppError,
+    models::deposit::{CreateDeposit, DepositQuery, DepositStatus},
+};
+
+use crate::{
+    fixtures::{ItemBuilder, LoanBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+#[tokio::test]
+#[ignore]
+async fn deposit_copies_cannot_be_weeded_and_close_keeps_copies_on_loan() {
+    let db = TestDb::new().await;
+    let reader = UserBuilder::new("reader1").insert(&db.pool).await;
+    let today = Local::now().date_naive();
+
+    let deposit = db
+        .repo
+        .deposits_create(
+            &CreateDeposit {
+                owner: "Departmental lending library".to_string(),
+                label: Some("Spring rotation".to_string()),
+                received_at: Some(today),
+                return_due: today + Duration::days(180),
+                notes: None,
+            },
+            reader,
+        )
+        .await
+        .unwrap();
+    assert_eq!(deposit.status, DepositStatus::Active);
+
+    let shelved = ItemBuilder::new("DEP-1").insert(&db.pool).await;
+    let lent = ItemBuilder::new("DEP-2").insert(&db.pool).await;
+    sqlx::query("UPDATE items SET deposit_id = $1 WHERE id = ANY($2)")
+        .bind(deposit.id)
+        .bind(vec![shelved.item_id, lent.item_id])
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    db.repo.loans_create(&LoanBuilder::new(reader, lent).request()).await.unwrap();
+
+    let deposit = db.repo.deposits_get(deposit.id).await.unwrap();
+    assert_eq!((deposit.copies_count, deposit.copies_on_loan), (2, 1));
+
+    // Weeding is blocked while the deposit is active, even when forced
+    assert!(matches!(
+        db.repo.items_delete(shelved.item_id, true).await,
+        Err(AppError::BusinessRule(_))
+    ));
+    assert!(matches!(
+        db.repo.biblios_delete(shelved.biblio_id, true).await,
+        Err(AppError::BusinessRule(_))
+    ));
+    assert!(matches!(db.repo.deposits_delete(deposit.id).await, Err(AppError::BusinessRule(_))));
+
+    // The manifest lists every copy, including the one on loan
+    let copies = db.repo.deposits_copies(deposit.id).await.unwrap();
+    assert_eq!(copies.len(), 2);
+    assert!(copies.iter().any(|c| c.barcode.as_deref() == Some("DEP-2") && c.due_at.is_some()));
+
+    let archived = db.repo.deposits_close(deposit.id, reader).await.unwrap();
+    assert_eq!(archived, 1);
+    let remaining = db.repo.deposits_copies(deposit.id).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].item_id, lent.item_id);
+
+    assert!(matches!(
+        db.repo.deposits_close(deposit.id, reader).await,
+        Err(AppError::BusinessRule(_))
+    ));
+    let closed = db
+        .repo
+        .deposits_list(&DepositQuery { status: Some(DepositStatus::Closed) })
+        .await
+        .unwrap();
+    assert_eq!(closed.len(), 1);
+    assert!(closed[0].closed_at.is_some());
+}
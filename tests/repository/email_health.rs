@@ -0,0 +1,100 @@
+use elidune_server::models::{
+    campaign::CampaignSegment,
+    email_health::{EmailFeedbackEvent, EmailFeedbackKind, EmailHealthStatus},
+};
+
+use crate::{fixtures::UserBuilder, harness::TestDb};
+
+fn event(email: &str, kind: EmailFeedbackKind, event_id: Option<&str>) -> EmailFeedbackEvent {
+    EmailFeedbackEvent {
+        email: email.to_string(),
+        kind,
+        reason: Some("550 5.1.1 user unknown".to_string()),
+        event_id: event_id.map(str::to_string),
+        occurred_at: None,
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn repeated_hard_bounces_suppress_the_address() {
+    let db = TestDb::new().await;
+    let patron = UserBuilder::new("bouncer").insert(&db.pool).await;
+    sqlx::query("UPDATE users SET email = 'Gone@Example.org', campaigns_opt_in = TRUE WHERE id = $1")
+        .bind(patron)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    let segment = CampaignSegment::default();
+    assert_eq!(db.repo.campaigns_segment_count(&segment).await.unwrap(), 1);
+
+    let first = db
+        .repo
+        .email_health_record(&event("gone@example.org ", EmailFeedbackKind::HardBounce, Some("evt-1")), 2)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(first.health.status, EmailHealthStatus::Bouncing);
+    assert!(!first.became_undeliverable);
+    // Webhook retry of the same event
+    assert!(db
+        .repo
+        .email_health_record(&event("gone@example.org", EmailFeedbackKind::HardBounce, Some("evt-1")), 2)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(!db.repo.email_health_is_undeliverable("gone@example.org").await.unwrap());
+
+    let second = db
+        .repo
+        .email_health_record(&event("GONE@example.org", EmailFeedbackKind::HardBounce, Some("evt-2")), 2)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(second.became_undeliverable);
+    assert_eq!(second.health.status, EmailHealthStatus::Undeliverable);
+    assert_eq!(second.health.hard_bounces, 2);
+    assert!(db.repo.email_health_is_undeliverable(" Gone@Example.org").await.unwrap());
+    assert_eq!(db.repo.campaigns_segment_count(&segment).await.unwrap(), 0);
+
+    // A later bounce does not report the address again
+    let third = db
+        .repo
+        .email_health_record(&event("gone@example.org", EmailFeedbackKind::HardBounce, None), 2)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!third.became_undeliverable);
+
+    assert!(db.repo.email_health_reset("Gone@Example.org").await.unwrap());
+    assert!(!db.repo.email_health_is_undeliverable("gone@example.org").await.unwrap());
+    assert!(db.repo.email_health_get("gone@example.org").await.unwrap().is_none());
+    assert!(!db.repo.email_health_reset("gone@example.org").await.unwrap());
+    assert_eq!(db.repo.campaigns_segment_count(&segment).await.unwrap(), 1);
+}
+
+#[tokio::test]
+#[ignore]
+async fn complaint_withdraws_campaign_consent() {
+    let db = TestDb::new().await;
+    let patron = UserBuilder::new("complainer").insert(&db.pool).await;
+    sqlx::query("UPDATE users SET email = 'angry@example.org', campaigns_opt_in = TRUE WHERE id = $1")
+        .bind(patron)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let recorded = db
+        .repo
+        .email_health_record(&event("angry@example.org", EmailFeedbackKind::Complaint, None), 3)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(recorded.health.status, EmailHealthStatus::Complained);
+    // Complaints only stop campaigns, transactional emails still go out
+    assert!(!db.repo.email_health_is_undeliverable("angry@example.org").await.unwrap());
+    let user = db.repo.users_get_by_id(patron).await.unwrap();
+    assert!(!user.campaigns_opt_in);
+    let everyone = CampaignSegment { opted_in_only: false, ..Default::default() };
+    assert_eq!(db.repo.campaigns_segment_count(&everyone).await.unwrap(), 0);
+}
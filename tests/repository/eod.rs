@@ -0,0 +1,61 @@
+use chrono::{Duration, Utc};
+use elidune_server::models::eod::{EodRunStatus, EodStep, EodStepResult, EodStepStatus, EodTrigger};
+
+use crate::{
+    fixtures::{ItemBuilder, LoanBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn end_of_day_snapshots_the_day() {
+    let db = TestDb::new().await;
+    let reader = UserBuilder::new("eod-reader").insert(&db.pool).await;
+    let returned = ItemBuilder::new("EOD-1").insert(&db.pool).await;
+    let kept = ItemBuilder::new("EOD-2").insert(&db.pool).await;
+    let out = ItemBuilder::new("EOD-3").insert(&db.pool).await;
+    let archived = LoanBuilder::new(reader, returned).insert(&db.repo).await;
+    db.repo.loans_return(archived).await.unwrap();
+    // Returned but not archived yet (`circulation.archive_returned_after_days`)
+    let waiting = LoanBuilder::new(reader, kept).insert(&db.repo).await;
+    sqlx::query("UPDATE loans SET returned_at = NOW() WHERE id = $1")
+        .bind(waiting)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    LoanBuilder::new(reader, out).insert(&db.repo).await;
+
+    let day = Utc::now().date_naive();
+    let (from, to) = (Utc::now() - Duration::hours(1), Utc::now() + Duration::hours(1));
+    let stats = db.repo.eod_daily_stats_upsert(day, from, to).await.unwrap();
+    assert_eq!((stats.loans, stats.returns, stats.new_users), (3, 2, 1));
+    assert_eq!((stats.active_loans, stats.overdue_loans), (1, 0));
+    // A second run for the same day rewrites the snapshot
+    let again = db.repo.eod_daily_stats_upsert(day, from, to).await.unwrap();
+    assert_eq!(again.loans, stats.loans);
+    assert_eq!(db.repo.eod_daily_stats_get(day).await.unwrap(), Some(again));
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn one_end_of_day_run_at_a_time() {
+    let db = TestDb::new().await;
+    let day = Utc::now().date_naive();
+
+    let mut run = db.repo.eod_runs_start(day, EodTrigger::Scheduled, None).await.unwrap().unwrap();
+    assert_eq!(run.status, EodRunStatus::Running);
+    assert!(db.repo.eod_runs_start(day, EodTrigger::Manual, None).await.unwrap().is_none());
+
+    run.steps = vec![EodStepResult { step: EodStep::ArchiveLoans, status: EodStepStatus::Succeeded, count: Some(3), message: None }];
+    run.status = EodRunStatus::from_steps(&run.steps);
+    run.archived_loans = Some(3);
+    let finished = db.repo.eod_runs_finish(&run).await.unwrap();
+    assert_eq!(finished.status, EodRunStatus::Succeeded);
+    assert_eq!(finished.steps, run.steps);
+    assert!(finished.finished_at.is_some());
+
+    let next = db.repo.eod_runs_start(day, EodTrigger::Manual, None).await.unwrap().unwrap();
+    let runs = db.repo.eod_runs_list(10).await.unwrap();
+    assert_eq!(runs.iter().map(|r| r.id).collect::<Vec<_>>(), vec![next.id, run.id]);
+    assert_eq!(db.repo.eod_runs_get(run.id).await.unwrap().archived_loans, Some(3));
+}
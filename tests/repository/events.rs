@@ -0,0 +1,96 @@
+use chrono::{NaiveDate, NaiveTime};
+use elidune_server::models::event::{CreateEvent, EventConflictKind, EventQuery, EventSlot, EventStatus};
+
+use crate::{fixtures::UserBuilder, harness::TestDb};
+
+fn event(name: &str, date: &str, start: Option<&str>, end: Option<&str>, room: Option<&str>) -> CreateEvent {
+    serde_json::from_value(serde_json::json!({
+        "name": name, "eventDate": date, "startTime": start, "endTime": end, "room": room
+    }))
+    .expect("create event")
+}
+
+fn slot(date: &str, start: Option<&str>, end: Option<&str>, room: &str) -> EventSlot {
+    let time = |t: Option<&str>| t.map(|t| NaiveTime::parse_from_str(t, "%H:%M").unwrap());
+    EventSlot { date: date.parse().unwrap(), start_time: time(start), end_time: time(end), room: Some(room.to_string()) }
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn room_overlaps_and_closures_are_reported() {
+    let db = TestDb::new().await;
+    let story = db.repo.events_create(&event("Story time", "2026-05-02", Some("10:00"), Some("11:00"), Some(" Auditorium ")), None).await.unwrap();
+    assert_eq!(story.room.as_deref(), Some("Auditorium"));
+    let expo = db.repo.events_create(&event("Exhibition", "2026-05-02", None, None, Some("auditorium")), None).await.unwrap();
+    db.repo.events_create(&event("Workshop", "2026-05-02", Some("11:00"), Some("12:00"), Some("Lab")), None).await.unwrap();
+    let holiday = db.repo.events_create(&event("Concert", "2026-05-01", Some("18:00"), None, None), None).await.unwrap();
+    sqlx::query("INSERT INTO schedule_closures (closure_date, reason) VALUES ('2026-05-01', 'Labour Day')")
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    // Back-to-back is fine; the all-day exhibition overlaps everything in the room
+    let after = db.repo.events_room_overlaps(&slot("2026-05-02", Some("11:00"), Some("12:00"), "AUDITORIUM"), None).await.unwrap();
+    assert_eq!(after.iter().map(|e| e.id).collect::<Vec<_>>(), vec![expo.id]);
+    let during = db.repo.events_room_overlaps(&slot("2026-05-02", Some("10:30"), None, "Auditorium"), Some(expo.id)).await.unwrap();
+    assert_eq!(during.iter().map(|e| e.id).collect::<Vec<_>>(), vec![story.id]);
+
+    let closure = db.repo.events_closure_on(NaiveDate::from_ymd_opt(2026, 5, 1).unwrap()).await.unwrap();
+    assert_eq!(closure.unwrap().reason.as_deref(), Some("Labour Day"));
+
+    let conflicts = db
+        .repo
+        .events_conflicts("2026-05-01".parse().unwrap(), "2026-05-31".parse().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(conflicts.len(), 2);
+    assert_eq!((conflicts[0].kind, conflicts[0].event_id), (EventConflictKind::Closure, holiday.id));
+    assert_eq!(conflicts[1].kind, EventConflictKind::Room);
+    assert_eq!((conflicts[1].event_id, conflicts[1].other_event_id), (story.id, Some(expo.id)));
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn events_move_from_draft_to_cancelled() {
+    let db = TestDb::new().await;
+    let draft = db.repo.events_create(&event("Book club", "2026-06-10", Some("18:00"), Some("19:00"), Some("Lab")), None).await.unwrap();
+    assert_eq!((draft.status, draft.registrations_count), (EventStatus::Draft, 0));
+
+    let published_only = EventQuery {
+        start_date: None,
+        end_date: None,
+        event_type: None,
+        status: Some(EventStatus::Published),
+        page: None,
+        per_page: None,
+    };
+    assert_eq!(db.repo.events_list(&published_only).await.unwrap().1, 0);
+
+    // Only drafts can be published
+    let published = db.repo.events_set_status(draft.id, &[EventStatus::Draft], EventStatus::Published, None).await.unwrap().unwrap();
+    assert!(published.published_at.is_some());
+    assert!(db.repo.events_set_status(draft.id, &[EventStatus::Draft], EventStatus::Published, None).await.unwrap().is_none());
+    assert_eq!(db.repo.events_list(&published_only).await.unwrap().1, 1);
+
+    let reader = UserBuilder::new("attendee").insert(&db.pool).await;
+    sqlx::query("UPDATE users SET email = 'attendee@example.org' WHERE id = $1").bind(reader).execute(&db.pool).await.unwrap();
+    assert!(db.repo.events_register(draft.id, reader).await.unwrap());
+    assert!(!db.repo.events_register(draft.id, reader).await.unwrap());
+    assert_eq!(db.repo.events_get_by_id(draft.id).await.unwrap().registrations_count, 1);
+    let emails = db.repo.events_registrant_emails(draft.id).await.unwrap();
+    assert_eq!(emails.iter().map(|u| u.id).collect::<Vec<_>>(), vec![reader]);
+
+    // A cancelled event frees its room
+    let cancelled = db
+        .repo
+        .events_set_status(draft.id, &[EventStatus::Draft, EventStatus::Published], EventStatus::Cancelled, Some("Speaker ill".to_string()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!((cancelled.status, cancelled.cancellation_reason.as_deref()), (EventStatus::Cancelled, Some("Speaker ill")));
+    assert!(db.repo.events_room_overlaps(&slot("2026-06-10", Some("18:30"), None, "lab"), None).await.unwrap().is_empty());
+
+    db.repo.events_unregister(draft.id, reader).await.unwrap();
+    assert!(db.repo.events_unregister(draft.id, reader).await.is_err());
+    assert!(db.repo.events_registrations(draft.id).await.unwrap().is_empty());
+}
@@ -0,0 +1,83 @@
+use std::str::FromStr;
+
+use chrono::{Duration, Utc};
+use elidune_server::{
+    error::AppError,
+    models::fine::{FineStatus, FineWaiverReason},
+};
+use rust_decimal::Decimal;
+
+use crate::{fixtures::UserBuilder, harness::TestDb};
+
+fn dec(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn fine_waivers_are_recorded_and_summed_by_reason() {
+    let db = TestDb::new().await;
+    let admin = UserBuilder::new("admin").account_type("admin").insert(&db.pool).await;
+    let reader = UserBuilder::new("reader").insert(&db.pool).await;
+    let (from, to) = (Utc::now() - Duration::days(30), Utc::now() + Duration::days(1));
+
+    // No migration creates `fines` yet: an amnesty has nothing to waive
+    assert_eq!(db.repo.fines_amnesty("Spring", from, to, admin).await.unwrap(), (0, Decimal::ZERO));
+
+    sqlx::query(
+        r#"
+        CREATE TABLE fines (
+            id BIGINT PRIMARY KEY, loan_id BIGINT NOT NULL, user_id BIGINT NOT NULL,
+            amount NUMERIC(10,2) NOT NULL, paid_amount NUMERIC(10,2) NOT NULL DEFAULT 0,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(), paid_at TIMESTAMPTZ,
+            status TEXT NOT NULL DEFAULT 'pending', notes TEXT
+        )
+        "#,
+    )
+    .execute(&db.pool)
+    .await
+    .unwrap();
+
+    let manual = db.repo.fines_create(1, reader, dec("2.00"), None).await.unwrap();
+    let waived = db
+        .repo
+        .fines_waive(manual.id, Some("Lost in the mail"), FineWaiverReason::Manual, None, Some(admin))
+        .await
+        .unwrap();
+    assert_eq!(waived.status, FineStatus::Waived);
+    let err = db.repo.fines_waive(manual.id, None, FineWaiverReason::Manual, None, Some(admin)).await.unwrap_err();
+    assert!(matches!(err, AppError::BusinessRule(_)));
+
+    // The campaign writes off what is left of partial fines and leaves older ones alone
+    let partial = db.repo.fines_create(2, reader, dec("3.00"), None).await.unwrap();
+    sqlx::query("UPDATE fines SET paid_amount = 1.00, status = 'partial' WHERE id = $1")
+        .bind(partial.id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    db.repo.fines_create(3, reader, dec("1.50"), None).await.unwrap();
+    let old = db.repo.fines_create(4, reader, dec("4.00"), None).await.unwrap();
+    sqlx::query("UPDATE fines SET created_at = NOW() - INTERVAL '60 days' WHERE id = $1")
+        .bind(old.id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    assert_eq!(db.repo.fines_amnesty("Spring", from, to, admin).await.unwrap(), (2, dec("3.50")));
+    assert_eq!(db.repo.fines_get_by_id(old.id).await.unwrap().status, FineStatus::Pending);
+    assert_eq!(db.repo.fines_total_unpaid(reader).await.unwrap(), dec("4.00"));
+
+    let totals = db.repo.fines_waivers_summary(Some(from), None).await.unwrap();
+    let summary: Vec<_> = totals
+        .iter()
+        .map(|t| (t.reason, t.amnesty.as_deref(), t.fine_count, t.waived_total))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (FineWaiverReason::Amnesty, Some("Spring"), 2, dec("3.50")),
+            (FineWaiverReason::Manual, None, 1, dec("2.00")),
+        ]
+    );
+    assert!(db.repo.fines_waivers_summary(None, Some(from)).await.unwrap().is_empty());
+}
@@ -0,0 +1,194 @@
+//! Builders inserting catalog, patron and loan rows for repository tests.
+//!
+//! Rows are written with plain SQL so fixtures do not depend on the code under test; every
+//! builder has defaults that make a borrowable copy / active reader out of the box.
+
+use elidune_server::{models::loan::CreateLoan, repository::Repository};
+use sqlx::PgPool;
+
+/// Biblio with one copy
+pub struct ItemBuilder {
+    title: String,
+    isbn: Option<String>,
+    media_type: String,
+    accessibility: Vec<String>,
+    reading_level: Option<String>,
+    barcode: String,
+    borrowable: bool,
+    circulation_status: Option<i16>,
+}
+
+/// Ids of an inserted [`ItemBuilder`]
+#[derive(Debug, Clone, Copy)]
+pub struct ItemFixture {
+    pub biblio_id: i64,
+    pub item_id: i64,
+}
+
+impl ItemBuilder {
+    pub fn new(barcode: &str) -> Self {
+        Self {
+            title: format!("Title {}", barcode),
+            isbn: None,
+            media_type: "printedText".to_string(),
+            accessibility: Vec::new(),
+            reading_level: None,
+            barcode: barcode.to_string(),
+            borrowable: true,
+            circulation_status: None,
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    pub fn isbn(mut self, isbn: &str) -> Self {
+        self.isbn = Some(isbn.to_string());
+        self
+    }
+
+    pub fn media_type(mut self, media_type: &str) -> Self {
+        self.media_type = media_type.to_string();
+        self
+    }
+
+    /// Accessible formats (`largePrint`, `braille`, `audiobook`, `dyslexiaFriendly`)
+    pub fn accessibility(mut self, features: &[&str]) -> Self {
+        self.accessibility = features.iter().map(|f| f.to_string()).collect();
+        self
+    }
+
+    /// Reading level (`ages0To3`, `ages4To6`, `ages7To9`, `ages10To12`, `teen`)
+    pub fn reading_level(mut self, level: &str) -> Self {
+        self.reading_level = Some(level.to_string());
+        self
+    }
+
+    pub fn not_borrowable(mut self) -> Self {
+        self.borrowable = false;
+        self
+    }
+
+    /// `item_states` code (0 available … 4 withdrawn)
+    pub fn state(mut self, code: i16) -> Self {
+        self.circulation_status = Some(code);
+        self
+    }
+
+    pub async fn insert(self, pool: &PgPool) -> ItemFixture {
+        let biblio_id: i64 = sqlx::query_scalar(
+            "INSERT INTO biblios (media_type, isbn, title, accessibility, reading_level) VALUES ($1, $2, $3, $4, $5) RETURNING id",
+        )
+        .bind(&self.media_type)
+        .bind(&self.isbn)
+        .bind(&self.title)
+        .bind(&self.accessibility)
+        .bind(&self.reading_level)
+        .fetch_one(pool)
+        .await
+        .expect("insert biblio");
+        let item_id = add_copy(pool, biblio_id, &self.barcode, self.borrowable, self.circulation_status).await;
+        ItemFixture { biblio_id, item_id }
+    }
+}
+
+/// Add another copy to an existing biblio
+pub async fn add_copy(
+    pool: &PgPool,
+    biblio_id: i64,
+    barcode: &str,
+    borrowable: bool,
+    circulation_status: Option<i16>,
+) -> i64 {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO items (biblio_id, barcode, borrowable, circulation_status)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id
+        "#,
+    )
+    .bind(biblio_id)
+    .bind(barcode)
+    .bind(borrowable)
+    .bind(circulation_status)
+    .fetch_one(pool)
+    .await
+    .expect("insert item")
+}
+
+/// Library account
+pub struct UserBuilder {
+    login: String,
+    account_type: String,
+}
+
+impl UserBuilder {
+    pub fn new(login: &str) -> Self {
+        Self {
+            login: login.to_string(),
+            account_type: "reader".to_string(),
+        }
+    }
+
+    /// `account_types.code` (guest, reader, librarian, admin, group)
+    pub fn account_type(mut self, code: &str) -> Self {
+        self.account_type = code.to_string();
+        self
+    }
+
+    pub async fn insert(self, pool: &PgPool) -> i64 {
+        sqlx::query_scalar(
+            r#"
+            INSERT INTO users (login, firstname, lastname, account_type, status)
+            VALUES ($1, 'Test', $1, $2, 'active')
+            RETURNING id
+            "#,
+        )
+        .bind(&self.login)
+        .bind(&self.account_type)
+        .fetch_one(pool)
+        .await
+        .expect("insert user")
+    }
+}
+
+/// Checkout through the repository (due date from the seeded loan settings)
+pub struct LoanBuilder {
+    user_id: i64,
+    item_id: i64,
+    force: bool,
+}
+
+impl LoanBuilder {
+    pub fn new(user_id: i64, item: ItemFixture) -> Self {
+        Self {
+            user_id,
+            item_id: item.item_id,
+            force: false,
+        }
+    }
+
+    pub fn force(mut self) -> Self {
+        self.force = true;
+        self
+    }
+
+    pub fn request(&self) -> CreateLoan {
+        CreateLoan {
+            user_id: self.user_id,
+            item_id: Some(self.item_id),
+            equipment_id: None,
+            item_identification: None,
+            force: self.force,
+            deposit_received: false,
+            override_blocks: self.force,
+        }
+    }
+
+    /// Loan id
+    pub async fn insert(self, repo: &Repository) -> i64 {
+        repo.loans_create(&self.request()).await.expect("create loan").0
+    }
+}
@@ -0,0 +1,190 @@
+//! Throwaway Postgres and Redis for repository tests.
+//!
+//! Containers are started once per test binary through the `docker` CLI (publishing a random
+//! local port) and removed when the process exits. Each test gets its own freshly migrated
+//! database, so tests can run in parallel and never see each other's rows.
+//!
+//! Set `TEST_DATABASE_URL` (a server URL whose user may `CREATE DATABASE`) and/or `TEST_REDIS_URL`
+//! to use existing servers instead of containers.
+
+use std::{
+    process::Command,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use elidune_server::{repository::Repository, services::redis::RedisService};
+use sqlx::{postgres::PgPoolOptions, Connection, PgConnection, PgPool};
+
+const POSTGRES_IMAGE: &str = "postgres:16-alpine";
+const REDIS_IMAGE: &str = "redis:7-alpine";
+const POSTGRES_PASSWORD: &str = "elidune-test";
+
+/// Containers to remove at exit
+static STARTED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static POSTGRES_URL: OnceLock<String> = OnceLock::new();
+static REDIS_URL: OnceLock<String> = OnceLock::new();
+
+extern "C" fn remove_containers() {
+    let ids = STARTED.lock().map(|ids| ids.clone()).unwrap_or_default();
+    if !ids.is_empty() {
+        let _ = Command::new("docker").args(["rm", "-f"]).args(&ids).output();
+    }
+}
+
+fn docker(args: &[&str]) -> String {
+    let output = Command::new("docker")
+        .args(args)
+        .output()
+        .expect("docker CLI not found (or set TEST_DATABASE_URL / TEST_REDIS_URL)");
+    assert!(
+        output.status.success(),
+        "docker {} failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Start `image` detached and return the host port mapped to `container_port`.
+fn start_container(image: &str, container_port: u16, env: &[&str]) -> u16 {
+    let published = format!("127.0.0.1::{}", container_port);
+    let mut args = vec!["run", "-d", "--rm", "-p", published.as_str()];
+    for e in env {
+        args.extend(["-e", e]);
+    }
+    args.push(image);
+    let id = docker(&args);
+
+    let mut started = STARTED.lock().unwrap();
+    if started.is_empty() {
+        // SAFETY: registering a plain `extern "C"` function with no captured state
+        unsafe { libc::atexit(remove_containers) };
+    }
+    started.push(id.clone());
+
+    // "127.0.0.1:49153"
+    let mapping = docker(&["port", &id, &format!("{}/tcp", container_port)]);
+    mapping
+        .lines()
+        .next()
+        .and_then(|line| line.rsplit(':').next())
+        .and_then(|port| port.parse().ok())
+        .unwrap_or_else(|| panic!("unexpected `docker port` output: {}", mapping))
+}
+
+fn postgres_server_url() -> &'static str {
+    POSTGRES_URL.get_or_init(|| {
+        if let Ok(url) = std::env::var("TEST_DATABASE_URL") {
+            return url;
+        }
+        let password = format!("POSTGRES_PASSWORD={}", POSTGRES_PASSWORD);
+        let port = start_container(POSTGRES_IMAGE, 5432, &[&password]);
+        format!("postgres://postgres:{}@127.0.0.1:{}/postgres", POSTGRES_PASSWORD, port)
+    })
+}
+
+/// Redis URL (container started on first use)
+pub fn redis_url() -> &'static str {
+    REDIS_URL.get_or_init(|| {
+        if let Ok(url) = std::env::var("TEST_REDIS_URL") {
+            return url;
+        }
+        let port = start_container(REDIS_IMAGE, 6379, &[]);
+        format!("redis://127.0.0.1:{}", port)
+    })
+}
+
+/// Connect to Redis, waiting for the container to accept connections
+pub async fn redis() -> RedisService {
+    let url = redis_url();
+    for _ in 0..50 {
+        if let Ok(service) = RedisService::new(url).await {
+            if service.get_connection().await.is_ok() {
+                return service;
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    panic!("Redis at {} did not become ready", url);
+}
+
+/// A migrated database of its own, plus a [`Repository`] on it
+pub struct TestDb {
+    pub pool: PgPool,
+    pub repo: Repository,
+}
+
+impl TestDb {
+    pub async fn new() -> Self {
+        let url = empty_database_url().await;
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .connect(&url)
+            .await
+            .expect("connect to test database");
+        elidune_server::MIGRATOR
+            .run(&pool)
+            .await
+            .expect("run migrations");
+
+        Self {
+            repo: Repository::new(pool.clone(), None, None),
+            pool,
+        }
+    }
+}
+
+/// URL of a new database without any migration (restore target)
+pub async fn empty_database_url() -> String {
+    let server_url = postgres_server_url();
+    let mut admin = connect_with_retry(server_url).await;
+    let name = format!("elidune_test_{}", uuid::Uuid::new_v4().simple());
+    sqlx::query(&format!(r#"CREATE DATABASE "{}""#, name))
+        .execute(&mut admin)
+        .await
+        .expect("create test database");
+    let _ = admin.close().await;
+    database_url(server_url, &name)
+}
+
+/// The server URL with its database name replaced
+fn database_url(server_url: &str, database: &str) -> String {
+    let (base, query) = match server_url.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (server_url, None),
+    };
+    let authority_end = base.find("://").map(|i| i + 3).unwrap_or(0);
+    let base = match base[authority_end..].find('/') {
+        Some(slash) => &base[..authority_end + slash],
+        None => base,
+    };
+    match query {
+        Some(query) => format!("{}/{}?{}", base, database, query),
+        None => format!("{}/{}", base, database),
+    }
+}
+
+async fn connect_with_retry(url: &str) -> PgConnection {
+    let mut last_error = None;
+    for _ in 0..100 {
+        match PgConnection::connect(url).await {
+            Ok(conn) => return conn,
+            Err(e) => last_error = Some(e),
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    panic!("Postgres at {} did not become ready: {:?}", url, last_error);
+}
+
+#[test]
+fn database_url_replaces_the_database() {
+    assert_eq!(
+        database_url("postgres://u:p@localhost:5432/postgres", "t1"),
+        "postgres://u:p@localhost:5432/t1"
+    );
+    assert_eq!(
+        database_url("postgres://u:p@localhost:5432?sslmode=disable", "t1"),
+        "postgres://u:p@localhost:5432/t1?sslmode=disable"
+    );
+}
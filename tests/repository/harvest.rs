@@ -0,0 +1,123 @@
+use elidune_server::models::harvest::{CreateHarvestSource, HarvestRunStatus, HarvestTrigger, UpdateHarvestSource};
+
+use crate::{fixtures::ItemBuilder, harness::TestDb};
+
+fn source(name: &str) -> CreateHarvestSource {
+    CreateHarvestSource {
+        name: name.to_string(),
+        url: "sftp://union.example.org/deltas/".to_string(),
+        username: Some("library".to_string()),
+        password: Some("secret".to_string()),
+        host_key_fingerprint: Some("SHA256:abc".to_string()),
+        file_pattern: Some("delta_*.mrc".to_string()),
+        marc_format: None,
+        mapping_profile: None,
+        catalog_source_id: None,
+        schedule_time: None,
+        is_active: None,
+        alert_emails: None,
+    }
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn run_claim_is_taken_once() {
+    let db = TestDb::new().await;
+    let created = db.repo.harvest_sources_create(&source("Regional network")).await.unwrap();
+    assert!(created.has_password);
+    assert_eq!(created.schedule_time, "02:30");
+
+    // Two instances read the same `last_run_at`: only the first claim opens a run
+    let run = db
+        .repo
+        .harvest_runs_start(created.id, created.last_run_at, HarvestTrigger::Schedule, None)
+        .await
+        .unwrap()
+        .expect("first claim");
+    assert_eq!(run.status, HarvestRunStatus::Running);
+    assert!(db
+        .repo
+        .harvest_runs_start(created.id, created.last_run_at, HarvestTrigger::Schedule, None)
+        .await
+        .unwrap()
+        .is_none());
+
+    // A fresh read is still refused while the run is in progress
+    let claimed = db.repo.harvest_sources_get(created.id).await.unwrap();
+    assert!(claimed.last_run_at.is_some());
+    assert!(db
+        .repo
+        .harvest_runs_start(created.id, claimed.last_run_at, HarvestTrigger::Manual, None)
+        .await
+        .unwrap()
+        .is_none());
+
+    let mut finished = run.clone();
+    finished.status = HarvestRunStatus::Partial;
+    finished.files = vec!["delta_01.mrc".to_string()];
+    finished.failed_count = 1;
+    let finished = db.repo.harvest_runs_finish(&finished).await.unwrap();
+    assert!(finished.finished_at.is_some());
+    assert_eq!(db.repo.harvest_runs_list(created.id, 10).await.unwrap().len(), 1);
+
+    assert!(db
+        .repo
+        .harvest_runs_start(created.id, claimed.last_run_at, HarvestTrigger::Manual, None)
+        .await
+        .unwrap()
+        .is_some());
+}
+
+#[tokio::test]
+#[ignore]
+async fn cursor_reset_and_password_removal() {
+    let db = TestDb::new().await;
+    let created = db.repo.harvest_sources_create(&source("Network")).await.unwrap();
+    db.repo.harvest_sources_set_cursor(created.id, "delta_02.mrc").await.unwrap();
+    assert_eq!(
+        db.repo.harvest_sources_get(created.id).await.unwrap().last_cursor.as_deref(),
+        Some("delta_02.mrc")
+    );
+
+    let update = UpdateHarvestSource {
+        name: None,
+        url: None,
+        username: None,
+        password: Some(String::new()),
+        host_key_fingerprint: None,
+        file_pattern: None,
+        marc_format: None,
+        mapping_profile: None,
+        catalog_source_id: None,
+        schedule_time: Some("04:15".to_string()),
+        is_active: None,
+        alert_emails: None,
+        reset_cursor: true,
+    };
+    let updated = db.repo.harvest_sources_update(created.id, &update).await.unwrap();
+    assert!(!updated.has_password && updated.password.is_none());
+    assert_eq!(updated.last_cursor, None);
+    assert_eq!(updated.schedule_time, "04:15");
+    assert_eq!(updated.username.as_deref(), Some("library"));
+}
+
+#[tokio::test]
+#[ignore]
+async fn remote_ids_are_linked_per_source() {
+    let db = TestDb::new().await;
+    let first = db.repo.harvest_sources_create(&source("First")).await.unwrap();
+    let second = db.repo.harvest_sources_create(&source("Second")).await.unwrap();
+    let item = ItemBuilder::new("H-0001").insert(&db.pool).await;
+    let other = ItemBuilder::new("H-0002").insert(&db.pool).await;
+
+    db.repo.harvest_records_link(first.id, "PPN123", item.biblio_id).await.unwrap();
+    assert_eq!(db.repo.harvest_records_get(first.id, "PPN123").await.unwrap(), Some(item.biblio_id));
+    assert_eq!(db.repo.harvest_records_get(second.id, "PPN123").await.unwrap(), None);
+
+    // Re-linking moves the remote id to the new record
+    db.repo.harvest_records_link(first.id, "PPN123", other.biblio_id).await.unwrap();
+    assert_eq!(db.repo.harvest_records_get(first.id, "PPN123").await.unwrap(), Some(other.biblio_id));
+
+    db.repo.harvest_records_unlink(first.id, "PPN123").await.unwrap();
+    assert_eq!(db.repo.harvest_records_get(first.id, "PPN123").await.unwrap(), None);
+}
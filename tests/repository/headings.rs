@@ -0,0 +1,115 @@
+use elidune_server::{
+    error::AppError,
+    models::{
+        biblio::BiblioQuery,
+        heading::{CreateGenreHeading, CreateSubjectHeading, SubjectQuery, UpdateSubjectHeading},
+    },
+};
+use serde_json::json;
+
+use crate::{fixtures::ItemBuilder, harness::TestDb};
+
+fn query(value: serde_json::Value) -> BiblioQuery {
+    serde_json::from_value(value).expect("biblio query")
+}
+
+fn subject(heading: &str, broader_id: Option<i64>) -> CreateSubjectHeading {
+    CreateSubjectHeading { heading: heading.to_string(), broader_id, scope_note: None }
+}
+
+fn move_subject(broader_id: Option<i64>, top_level: bool) -> UpdateSubjectHeading {
+    UpdateSubjectHeading { heading: None, broader_id, top_level, scope_note: None }
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn subject_hierarchy_rejects_loops_and_used_deletes() {
+    let db = TestDb::new().await;
+    let science = db.repo.subjects_create(&subject("Science", None)).await.unwrap();
+    let physics = db.repo.subjects_create(&subject("Physics", Some(science.id))).await.unwrap();
+    let optics = db.repo.subjects_create(&subject("Optics", Some(physics.id))).await.unwrap();
+
+    assert!(matches!(db.repo.subjects_create(&subject("physics", None)).await, Err(AppError::Conflict(_))));
+    assert_eq!(db.repo.subjects_get(science.id).await.unwrap().narrower_count, 1);
+
+    // Science under Optics would loop
+    assert!(matches!(
+        db.repo.subjects_update(science.id, &move_subject(Some(optics.id), false)).await,
+        Err(AppError::BusinessRule(_))
+    ));
+    let moved = db.repo.subjects_update(optics.id, &move_subject(Some(science.id), false)).await.unwrap();
+    assert_eq!(moved.broader_id, Some(science.id));
+    let detached = db.repo.subjects_update(optics.id, &move_subject(None, true)).await.unwrap();
+    assert_eq!(detached.broader_id, None);
+
+    let (top, total) = db
+        .repo
+        .subjects_list(&SubjectQuery { top_level: Some(true), ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(top.iter().map(|s| s.heading.as_str()).collect::<Vec<_>>(), vec!["Optics", "Science"]);
+
+    assert!(matches!(db.repo.subjects_delete(science.id).await, Err(AppError::Conflict(_))));
+    let item = ItemBuilder::new("H-0001").insert(&db.pool).await;
+    db.repo.biblio_subjects_set(item.biblio_id, &[optics.id]).await.unwrap();
+    assert!(matches!(db.repo.subjects_delete(optics.id).await, Err(AppError::Conflict(_))));
+    db.repo.subjects_delete(physics.id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore]
+async fn merging_headings_remaps_records_and_search() {
+    let db = TestDb::new().await;
+    let a = ItemBuilder::new("H-0010").title("Heading A").insert(&db.pool).await;
+    let b = ItemBuilder::new("H-0011").title("Heading B").insert(&db.pool).await;
+    ItemBuilder::new("H-0012").title("Heading C").insert(&db.pool).await;
+
+    let fiction = db.repo.genres_list().await.unwrap().into_iter().find(|g| g.code == "LitteratureFiction").unwrap();
+    let novels = db
+        .repo
+        .genres_create(&CreateGenreHeading { code: "novels".into(), label: "Novels".into(), sort_order: None })
+        .await
+        .unwrap();
+    db.repo.biblio_genres_set(a.biblio_id, &[fiction.id]).await.unwrap();
+    db.repo.biblio_genres_set(b.biblio_id, &[novels.id, fiction.id]).await.unwrap();
+    assert!(matches!(db.repo.biblio_genres_set(a.biblio_id, &[-1]).await, Err(AppError::Validation(_))));
+
+    let history = db.repo.subjects_create(&subject("History", None)).await.unwrap();
+    let wars = db.repo.subjects_create(&subject("Wars", Some(history.id))).await.unwrap();
+    let battles = db.repo.subjects_create(&subject("Battles", None)).await.unwrap();
+    let sieges = db.repo.subjects_create(&subject("Sieges", Some(battles.id))).await.unwrap();
+    db.repo.biblio_subjects_set(a.biblio_id, &[battles.id]).await.unwrap();
+    db.repo.biblio_subjects_set(b.biblio_id, &[wars.id, sieges.id]).await.unwrap();
+
+    // Subject filter includes narrower terms
+    let (found, _) = db.repo.biblios_search(&query(json!({ "subjectId": history.id }))).await.unwrap();
+    assert_eq!(found.iter().map(|r| r.id).collect::<Vec<_>>(), vec![b.biblio_id]);
+    let (found, _) = db.repo.biblios_search(&query(json!({ "genre": "novels,other" }))).await.unwrap();
+    assert_eq!(found.iter().map(|r| r.id).collect::<Vec<_>>(), vec![b.biblio_id]);
+
+    let facets = db.repo.biblios_search_facets(&query(json!({ "title": "heading" }))).await.unwrap();
+    assert_eq!(facets.genres[0].value, "LitteratureFiction");
+    assert_eq!((facets.genres[0].label.as_deref(), facets.genres[0].count), (Some("Fiction"), 2));
+
+    // Merging a broader term into its narrower one would orphan the kept heading
+    assert!(matches!(db.repo.subjects_merge(sieges.id, &[battles.id]).await, Err(AppError::BusinessRule(_))));
+    assert!(matches!(db.repo.subjects_merge(wars.id, &[wars.id]).await, Err(AppError::Validation(_))));
+
+    let report = db.repo.subjects_merge(wars.id, &[battles.id]).await.unwrap();
+    assert_eq!((report.biblios_remapped, report.narrower_moved), (1, 1));
+    assert!(matches!(db.repo.subjects_get(battles.id).await, Err(AppError::NotFound(_))));
+    assert_eq!(db.repo.subjects_get(sieges.id).await.unwrap().broader_id, Some(wars.id));
+    let (found, _) = db.repo.biblios_search(&query(json!({ "subjectId": history.id }))).await.unwrap();
+    assert_eq!(found.len(), 2);
+
+    let report = db.repo.genres_merge(fiction.id, &[novels.id]).await.unwrap();
+    assert_eq!(report.biblio_ids, vec![b.biblio_id]);
+    let genres = db.repo.biblio_genres_get(b.biblio_id).await.unwrap();
+    assert_eq!(genres.iter().map(|g| g.id).collect::<Vec<_>>(), vec![fiction.id]);
+
+    let doc = db.repo.biblios_get_meili_document(b.biblio_id).await.unwrap().unwrap();
+    assert_eq!(doc.genres, vec!["LitteratureFiction"]);
+    assert_eq!(doc.subject_ids, vec![wars.id, sieges.id]);
+    assert_eq!(doc.subject_path_ids, vec![history.id, wars.id, sieges.id]);
+}
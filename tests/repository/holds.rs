@@ -0,0 +1,174 @@
+use chrono::{Duration, Utc};
+use elidune_server::models::hold::{CreateHold, HoldPriority, HoldStatus, LockerReservation};
+
+use crate::{
+    fixtures::{ItemBuilder, LoanBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+fn reservation(compartment: &str) -> LockerReservation {
+    LockerReservation {
+        locker_id: "LOBBY".to_string(),
+        compartment: compartment.to_string(),
+        pickup_code: Some("482913".to_string()),
+    }
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn locker_pickup_closes_hold_on_checkout() {
+    let db = TestDb::new().await;
+    let user_id = UserBuilder::new("locker1").insert(&db.pool).await;
+    let item = ItemBuilder::new("L-0001").insert(&db.pool).await;
+    let hold = db
+        .repo
+        .holds_create(&CreateHold { user_id, item_id: item.item_id, notes: None, pickup_locker: true, priority: HoldPriority::Normal })
+        .await
+        .unwrap();
+    assert!(hold.pickup_locker);
+
+    // Only a ready hold goes into a locker
+    let expires_at = Utc::now() + Duration::days(3);
+    assert!(db.repo.holds_assign_locker(hold.id, &reservation("A12"), expires_at).await.unwrap().is_none());
+    db.repo.holds_mark_ready(hold.id, 7).await.unwrap();
+    let in_locker = db
+        .repo
+        .holds_assign_locker(hold.id, &reservation("A12"), expires_at)
+        .await
+        .unwrap()
+        .expect("ready hold");
+    assert_eq!(in_locker.status, HoldStatus::InLocker);
+    assert_eq!(in_locker.locker_compartment.as_deref(), Some("A12"));
+    assert!(db.repo.holds_has_active_for_user_item(user_id, item.item_id).await.unwrap());
+
+    // Checking the copy out to the patron closes the hold as picked up
+    LoanBuilder::new(user_id, item).insert(&db.repo).await;
+    let closed = db.repo.holds_get_by_id(hold.id).await.unwrap();
+    assert_eq!(closed.status, HoldStatus::PickedUp);
+    assert!(closed.picked_up_at.is_some());
+    assert!(db.repo.holds_mark_picked_up(hold.id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+#[ignore]
+async fn lapsed_locker_holds_expire() {
+    let db = TestDb::new().await;
+    let user_id = UserBuilder::new("locker2").insert(&db.pool).await;
+    let lapsed = ItemBuilder::new("L-0002").insert(&db.pool).await;
+    let waiting = ItemBuilder::new("L-0003").insert(&db.pool).await;
+
+    let mut ids = Vec::new();
+    for (item_id, compartment, expires_at) in [
+        (lapsed.item_id, "B01", Utc::now() - Duration::hours(1)),
+        (waiting.item_id, "B02", Utc::now() + Duration::days(2)),
+    ] {
+        let hold = db
+            .repo
+            .holds_create(&CreateHold { user_id, item_id, notes: None, pickup_locker: true, priority: HoldPriority::Normal })
+            .await
+            .unwrap();
+        db.repo.holds_mark_ready(hold.id, 7).await.unwrap();
+        db.repo.holds_assign_locker(hold.id, &reservation(compartment), expires_at).await.unwrap();
+        ids.push(hold.id);
+    }
+
+    let expired = db.repo.holds_expire_lockers().await.unwrap();
+    assert_eq!(expired.iter().map(|h| h.id).collect::<Vec<_>>(), vec![ids[0]]);
+    assert_eq!(expired[0].status, HoldStatus::Expired);
+    assert_eq!(db.repo.holds_get_by_id(ids[1]).await.unwrap().status, HoldStatus::InLocker);
+
+    // The webhook path expires a single hold once
+    assert!(db.repo.holds_expire_from_locker(ids[1]).await.unwrap().is_some());
+    assert!(db.repo.holds_expire_from_locker(ids[1]).await.unwrap().is_none());
+}
+
+#[tokio::test]
+#[ignore]
+async fn hold_shelf_lists_pull_queue_heads_and_expired_copies() {
+    let db = TestDb::new().await;
+    let first = UserBuilder::new("shelf1").insert(&db.pool).await;
+    let second = UserBuilder::new("shelf2").insert(&db.pool).await;
+    let shelved = ItemBuilder::new("S-0001").insert(&db.pool).await;
+    let lent = ItemBuilder::new("S-0002").insert(&db.pool).await;
+    let waiting = ItemBuilder::new("S-0003").insert(&db.pool).await;
+    let lapsed = ItemBuilder::new("S-0004").insert(&db.pool).await;
+    let requeued = ItemBuilder::new("S-0005").insert(&db.pool).await;
+    sqlx::query("UPDATE items SET place = 4 WHERE id = $1")
+        .bind(shelved.item_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let hold = |user_id, item_id| CreateHold { user_id, item_id, notes: None, pickup_locker: false, priority: HoldPriority::Normal };
+    let queue_head = db.repo.holds_create(&hold(first, shelved.item_id)).await.unwrap();
+    db.repo.holds_create(&hold(second, shelved.item_id)).await.unwrap();
+    LoanBuilder::new(first, lent).insert(&db.repo).await;
+    db.repo.holds_create(&hold(second, lent.item_id)).await.unwrap();
+    let ready = db.repo.holds_create(&hold(first, waiting.item_id)).await.unwrap();
+    db.repo.holds_mark_ready(ready.id, 7).await.unwrap();
+    let lapsed_hold = db.repo.holds_create(&hold(first, lapsed.item_id)).await.unwrap();
+    db.repo.holds_mark_ready(lapsed_hold.id, 7).await.unwrap();
+    let requeued_hold = db.repo.holds_create(&hold(first, requeued.item_id)).await.unwrap();
+    db.repo.holds_mark_ready(requeued_hold.id, 7).await.unwrap();
+    let next = db.repo.holds_create(&hold(second, requeued.item_id)).await.unwrap();
+    sqlx::query("UPDATE holds SET expires_at = NOW() - INTERVAL '1 day' WHERE id = ANY($1)")
+        .bind(vec![lapsed_hold.id, requeued_hold.id])
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    db.repo.holds_expire_overdue().await.unwrap();
+
+    // Only the first hold of a copy on the shelf: not copies on loan or already set aside
+    let pull = db.repo.holds_pull_list().await.unwrap();
+    let pulled: Vec<i64> = pull.iter().map(|e| e.hold_id).collect();
+    assert_eq!(pulled, vec![queue_head.id, next.id]);
+    assert_eq!(pull[0].place, Some(4));
+
+    // The lapsed copy goes back to the shelves; the requeued one waits for the next patron
+    let expired = db.repo.holds_expired_to_reshelve(Utc::now() - Duration::days(7)).await.unwrap();
+    let reshelve: Vec<i64> = expired.iter().map(|e| e.item_id).collect();
+    assert_eq!(reshelve, vec![lapsed.item_id]);
+    assert!(db.repo.holds_expired_to_reshelve(Utc::now()).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn priority_holds_jump_the_regular_queue() {
+    let db = TestDb::new().await;
+    let item = ItemBuilder::new("P-0001").insert(&db.pool).await;
+    let mut users = Vec::new();
+    for login in ["prio1", "prio2", "prio3", "prio4", "prio5"] {
+        users.push(UserBuilder::new(login).insert(&db.pool).await);
+    }
+    let hold = |user_id, priority| CreateHold { user_id, item_id: item.item_id, notes: None, pickup_locker: false, priority };
+
+    let served = db.repo.holds_create(&hold(users[0], HoldPriority::Normal)).await.unwrap();
+    db.repo.holds_mark_ready(served.id, 7).await.unwrap();
+    let regular = db.repo.holds_create(&hold(users[1], HoldPriority::Normal)).await.unwrap();
+    let staff = db.repo.holds_create(&hold(users[2], HoldPriority::Staff)).await.unwrap();
+    let teaching = db.repo.holds_create(&hold(users[3], HoldPriority::Teaching)).await.unwrap();
+    let later = db.repo.holds_create(&hold(users[4], HoldPriority::Normal)).await.unwrap();
+
+    // Default order: teaching, staff, then normal holds; the ready hold keeps its place
+    let queue = db.repo.holds_list_for_item(item.item_id).await.unwrap();
+    let order: Vec<(i64, Option<i32>)> = queue.iter().map(|h| (h.id, h.queue_position)).collect();
+    assert_eq!(
+        order,
+        vec![
+            (served.id, Some(1)),
+            (teaching.id, Some(2)),
+            (staff.id, Some(3)),
+            (regular.id, Some(4)),
+            (later.id, Some(5)),
+        ]
+    );
+    assert_eq!(queue[1].priority, HoldPriority::Teaching);
+
+    // Cancelled holds leave the queue; positions of the others close up
+    db.repo.holds_cancel(teaching.id).await.unwrap();
+    let mine = db.repo.holds_list_for_user(users[1]).await.unwrap();
+    assert_eq!(mine[0].queue_position, Some(3));
+    assert_eq!(db.repo.holds_get_next_pending(item.item_id).await.unwrap().map(|h| h.id), Some(staff.id));
+    let cancelled = db.repo.holds_list_for_user(users[3]).await.unwrap();
+    assert_eq!(cancelled[0].queue_position, None);
+}
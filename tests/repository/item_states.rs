@@ -0,0 +1,81 @@
+use elidune_server::models::{
+    biblio::{BiblioQuery, BiblioShort, Isbn},
+    hold::{CreateHold, HoldPriority},
+    item_state::UpdateItemState,
+};
+
+use crate::{
+    fixtures::{add_copy, ItemBuilder, ItemFixture, LoanBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+/// Seeded states hidden from the OPAC
+const ON_ORDER: i16 = 5;
+const IN_PROCESSING: i16 = 6;
+
+fn ids(found: &[BiblioShort]) -> Vec<i64> {
+    found.iter().map(|b| b.id).collect()
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn copies_in_hidden_states_stay_out_of_the_opac() {
+    let db = TestDb::new().await;
+    let ordered = ItemBuilder::new("VIS-1").isbn("9782070612758").state(ON_ORDER).insert(&db.pool).await;
+    let mixed = ItemBuilder::new("VIS-2").isbn("9782253004226").state(IN_PROCESSING).insert(&db.pool).await;
+    let shelved = add_copy(&db.pool, mixed.biblio_id, "VIS-3", true, Some(0)).await;
+
+    let (staff, _) = db.repo.biblios_search(&BiblioQuery::default()).await.unwrap();
+    assert_eq!(ids(&staff), vec![ordered.biblio_id, mixed.biblio_id]);
+    let opac = BiblioQuery { opac: true, ..Default::default() };
+    let (public, total) = db.repo.biblios_search(&opac).await.unwrap();
+    assert_eq!((ids(&public), total), (vec![mixed.biblio_id], 1));
+
+    let biblio = db.repo.biblios_get_by_id(mixed.biblio_id).await.unwrap();
+    let hidden: Vec<(Option<i64>, bool)> = biblio.items.iter().map(|i| (i.id, i.hidden_from_opac)).collect();
+    assert!(hidden.contains(&(Some(mixed.item_id), true)) && hidden.contains(&(Some(shelved), false)));
+
+    // Public availability counts the visible copy only and does not know the ordered title
+    let isbns = [Isbn::new("9782070612758"), Isbn::new("9782253004226")];
+    let found = db.repo.biblios_get_availability_by_isbns(&isbns).await.unwrap();
+    assert!(!found.contains_key(&isbns[0]));
+    assert_eq!(found[&isbns[1]].total_items, 1);
+    assert!(db.repo.biblios_get_availability_by_isbn(&isbns[0]).await.unwrap().is_none());
+
+    let doc = db.repo.biblios_get_meili_document(ordered.biblio_id).await.unwrap().unwrap();
+    assert!(doc.opac_hidden);
+
+    // Making the state visible publishes the ordered title
+    let visible = UpdateItemState { label: None, blocks_circulation: None, opac_visible: Some(true), color: None, sort_order: None };
+    let state = db.repo.item_states_update(ON_ORDER, &visible).await.unwrap();
+    assert!(state.opac_visible);
+    assert_eq!(db.repo.item_states_biblio_ids(ON_ORDER).await.unwrap(), vec![ordered.biblio_id]);
+    let (public, _) = db.repo.biblios_search(&opac).await.unwrap();
+    assert_eq!(ids(&public), vec![ordered.biblio_id, mixed.biblio_id]);
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn opac_circulation_counts_skip_hidden_copies() {
+    let db = TestDb::new().await;
+    let reader = UserBuilder::new("opac-counts").insert(&db.pool).await;
+    let hold = |item_id| CreateHold { user_id: reader, item_id, notes: None, pickup_locker: false, priority: HoldPriority::Normal };
+
+    // Record whose only copy is in processing: hidden from the OPAC, its loan and hold not counted
+    let hidden = ItemBuilder::new("CNT-1").state(IN_PROCESSING).insert(&db.pool).await;
+    LoanBuilder::new(reader, hidden).force().insert(&db.repo).await;
+    db.repo.holds_create(&hold(hidden.item_id)).await.unwrap();
+    let biblio = db.repo.biblios_get_by_id(hidden.biblio_id).await.unwrap();
+    assert!(biblio.items.iter().all(|i| i.hidden_from_opac));
+    assert_eq!(db.repo.loans_count_active_for_biblio(hidden.biblio_id).await.unwrap(), 0);
+    assert_eq!(db.repo.holds_count_active_for_biblio(hidden.biblio_id).await.unwrap(), 0);
+
+    // Record with a shelved copy and one in processing: only the shelved one counts
+    let mixed = ItemBuilder::new("CNT-2").state(IN_PROCESSING).insert(&db.pool).await;
+    let shelved = add_copy(&db.pool, mixed.biblio_id, "CNT-3", true, Some(0)).await;
+    LoanBuilder::new(reader, mixed).force().insert(&db.repo).await;
+    LoanBuilder::new(reader, ItemFixture { biblio_id: mixed.biblio_id, item_id: shelved }).insert(&db.repo).await;
+    db.repo.holds_create(&hold(mixed.item_id)).await.unwrap();
+    assert_eq!(db.repo.loans_count_active_for_biblio(mixed.biblio_id).await.unwrap(), 1);
+    assert_eq!(db.repo.holds_count_active_for_biblio(mixed.biblio_id).await.unwrap(), 0);
+}
@@ -0,0 +1,38 @@
+use elidune_server::{error::AppError, models::item_template::ItemTemplateFields, repository::ItemTemplatesRepository};
+
+use crate::harness::TestDb;
+
+fn fields(name: &str) -> ItemTemplateFields {
+    ItemTemplateFields { name: name.to_string(), place: Some(4), ..Default::default() }
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn templates_are_seeded_and_editable() {
+    let db = TestDb::new().await;
+    let seeded = db.repo.item_templates_list().await.unwrap();
+    let mut codes: Vec<_> = seeded.iter().map(|t| t.code.as_str()).collect();
+    codes.sort();
+    assert_eq!(codes, vec!["dvd", "magazine", "novel"]);
+    assert!(seeded.iter().find(|t| t.code == "novel").unwrap().genre_id.is_some());
+
+    let created = db.repo.item_templates_create("kids-novel", &fields("Children's novel")).await.unwrap();
+    assert_eq!(created.place, Some(4));
+    assert!(matches!(
+        db.repo.item_templates_create("kids-novel", &fields("Again")).await,
+        Err(AppError::Conflict(_))
+    ));
+
+    // PUT replaces every field
+    let updated = db.repo.item_templates_update("kids-novel", &ItemTemplateFields { borrowable: Some(false), ..fields("Kids") }).await.unwrap();
+    assert_eq!((updated.name.as_str(), updated.borrowable), ("Kids", Some(false)));
+    let cleared = db.repo.item_templates_update("kids-novel", &ItemTemplateFields { place: None, ..fields("Kids") }).await.unwrap();
+    assert_eq!(cleared.place, None);
+
+    assert!(!db.repo.item_templates_genre_exists(-1).await.unwrap());
+    assert!(matches!(db.repo.item_templates_ensure_state(Some(999)).await, Err(AppError::Validation(_))));
+
+    db.repo.item_templates_delete("kids-novel").await.unwrap();
+    assert!(db.repo.item_templates_get_by_code("kids-novel").await.unwrap().is_none());
+    assert!(matches!(db.repo.item_templates_delete("kids-novel").await, Err(AppError::NotFound(_))));
+}
@@ -0,0 +1,306 @@
+use elidune_server::{
+    error::AppError,
+    marc::MarcRecord,
+    models::{
+        biblio::{Biblio, BiblioQuery, ReadingLevel},
+        item::{Item, SpineLabelQuery},
+    },
+};
+use serde_json::json;
+use z3950_rs::marc_rs::record::{Note, NoteType};
+
+use crate::{
+    fixtures::{add_copy, ItemBuilder},
+    harness::TestDb,
+};
+
+fn query(value: serde_json::Value) -> BiblioQuery {
+    serde_json::from_value(value).expect("biblio query")
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn search_matches_title_ignoring_accents() {
+    let db = TestDb::new().await;
+    let wanted = ItemBuilder::new("S-0001").title("Les Misérables").insert(&db.pool).await;
+    ItemBuilder::new("S-0002").title("Notre-Dame de Paris").insert(&db.pool).await;
+
+    let (found, total) = db.repo.biblios_search(&query(json!({ "title": "miserables" }))).await.unwrap();
+
+    assert_eq!(total, 1);
+    assert_eq!(found[0].id, wanted.biblio_id);
+}
+
+#[tokio::test]
+#[ignore]
+async fn search_filters_on_isbn_and_media_type() {
+    let db = TestDb::new().await;
+    let book = ItemBuilder::new("S-0010").isbn("9782070360024").insert(&db.pool).await;
+    let cd = ItemBuilder::new("S-0011").media_type("audioMusic").insert(&db.pool).await;
+
+    let (found, _) = db.repo.biblios_search(&query(json!({ "isbn": "9782070360024" }))).await.unwrap();
+    assert_eq!(found.iter().map(|b| b.id).collect::<Vec<_>>(), vec![book.biblio_id]);
+
+    let (found, _) = db.repo.biblios_search(&query(json!({ "mediaType": "audioMusic" }))).await.unwrap();
+    assert_eq!(found.iter().map(|b| b.id).collect::<Vec<_>>(), vec![cd.biblio_id]);
+}
+
+#[tokio::test]
+#[ignore]
+async fn suggestions_complete_titles_and_authors() {
+    let db = TestDb::new().await;
+    let prefix = ItemBuilder::new("S-0030").title("Les Misérables").insert(&db.pool).await;
+    ItemBuilder::new("S-0031").title("Les Misérables").insert(&db.pool).await;
+    let inner = ItemBuilder::new("S-0032").title("Contes des misérables").insert(&db.pool).await;
+    let archived = ItemBuilder::new("S-0033").title("Misère et noblesse").insert(&db.pool).await;
+    sqlx::query("UPDATE biblios SET archived_at = NOW() WHERE id = $1")
+        .bind(archived.biblio_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let titles = db.repo.biblios_suggest_titles("les mise", 10).await.unwrap();
+    assert_eq!(titles.len(), 1);
+    assert_eq!((titles[0].id, titles[0].label.as_str(), titles[0].count), (prefix.biblio_id, "Les Misérables", 2));
+    // Same similarity: the title with more records comes first
+    let titles = db.repo.biblios_suggest_titles("miserables", 10).await.unwrap();
+    assert_eq!(titles.iter().map(|t| t.label.as_str()).collect::<Vec<_>>(), vec!["Les Misérables", "Contes des misérables"]);
+    assert_eq!(titles[1].id, inner.biblio_id);
+    let titles = db.repo.biblios_suggest_titles("misere", 10).await.unwrap();
+    assert!(titles.iter().all(|t| t.id != archived.biblio_id));
+
+    for (firstname, lastname, biblio_id) in
+        [("Victor", "Hugo", prefix.biblio_id), ("Hugues", "Aufray", inner.biblio_id), ("Lost", "Hugon", archived.biblio_id)]
+    {
+        sqlx::query(
+            "WITH a AS (INSERT INTO authors (firstname, lastname) VALUES ($1, $2) RETURNING id) \
+             INSERT INTO biblio_authors (biblio_id, author_id) SELECT $3, id FROM a",
+        )
+        .bind(firstname)
+        .bind(lastname)
+        .bind(biblio_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    }
+    let authors = db.repo.biblios_suggest_authors("hug", 10).await.unwrap();
+    assert_eq!(authors.iter().map(|a| a.label.as_str()).collect::<Vec<_>>(), vec!["Victor Hugo", "Hugues Aufray"]);
+    let authors = db.repo.biblios_suggest_authors("victor hu", 10).await.unwrap();
+    assert_eq!(authors.iter().map(|a| (a.label.as_str(), a.count)).collect::<Vec<_>>(), vec![("Victor Hugo", 1)]);
+}
+
+#[tokio::test]
+#[ignore]
+async fn search_skips_biblios_without_active_copies_unless_asked() {
+    let db = TestDb::new().await;
+    let item = ItemBuilder::new("S-0020").title("Orphan record").insert(&db.pool).await;
+    db.repo.items_delete(item.item_id, false).await.unwrap();
+
+    let (_, total) = db.repo.biblios_search(&query(json!({ "title": "orphan" }))).await.unwrap();
+    assert_eq!(total, 0);
+
+    let (found, _) = db
+        .repo
+        .biblios_search(&query(json!({ "title": "orphan", "includeWithoutActiveItems": true })))
+        .await
+        .unwrap();
+    assert_eq!(found.iter().map(|b| b.id).collect::<Vec<_>>(), vec![item.biblio_id]);
+}
+
+#[tokio::test]
+#[ignore]
+async fn search_filters_and_counts_accessible_formats() {
+    let db = TestDb::new().await;
+    let large = ItemBuilder::new("S-0050").title("Accessible A").accessibility(&["largePrint"]).insert(&db.pool).await;
+    let both = ItemBuilder::new("S-0051")
+        .title("Accessible B")
+        .accessibility(&["largePrint", "dyslexiaFriendly"])
+        .insert(&db.pool)
+        .await;
+    ItemBuilder::new("S-0052").title("Accessible C").insert(&db.pool).await;
+
+    let (found, _) = db.repo.biblios_search(&query(json!({ "accessibility": "largePrint" }))).await.unwrap();
+    assert_eq!(found.iter().map(|b| b.id).collect::<Vec<_>>(), vec![large.biblio_id, both.biblio_id]);
+
+    let (found, _) = db
+        .repo
+        .biblios_search(&query(json!({ "accessibility": "largePrint,dyslexiaFriendly" })))
+        .await
+        .unwrap();
+    assert_eq!(found.iter().map(|b| b.id).collect::<Vec<_>>(), vec![both.biblio_id]);
+
+    let facets = db.repo.biblios_search_facets(&query(json!({ "title": "accessible" }))).await.unwrap();
+    let counts: Vec<(&str, i64)> = facets.accessibility.iter().map(|f| (f.value.as_str(), f.count)).collect();
+    assert_eq!(counts, vec![("largePrint", 2), ("braille", 0), ("audiobook", 0), ("dyslexiaFriendly", 1)]);
+
+    let biblio = db.repo.biblios_get_by_id(both.biblio_id).await.unwrap();
+    assert_eq!(biblio.accessibility.len(), 2);
+}
+
+#[tokio::test]
+#[ignore]
+async fn search_filters_by_reading_level() {
+    let db = TestDb::new().await;
+    let picture = ItemBuilder::new("S-0060").title("Levels A").reading_level("ages4To6").insert(&db.pool).await;
+    let novel = ItemBuilder::new("S-0061").title("Levels B").reading_level("ages10To12").insert(&db.pool).await;
+    ItemBuilder::new("S-0062").title("Levels C").reading_level("teen").insert(&db.pool).await;
+    ItemBuilder::new("S-0063").title("Levels D").insert(&db.pool).await;
+
+    let (found, _) = db
+        .repo
+        .biblios_search(&query(json!({ "title": "levels", "readingLevel": "ages4To6, ages10To12" })))
+        .await
+        .unwrap();
+    assert_eq!(found.iter().map(|b| b.id).collect::<Vec<_>>(), vec![picture.biblio_id, novel.biblio_id]);
+
+    let biblio = db.repo.biblios_get_by_id(novel.biblio_id).await.unwrap();
+    assert_eq!(biblio.reading_level, Some(ReadingLevel::Ages10To12));
+}
+
+#[tokio::test]
+#[ignore]
+async fn active_item_lookup_by_barcode() {
+    let db = TestDb::new().await;
+    let item = ItemBuilder::new("S-0030").insert(&db.pool).await;
+    let second = add_copy(&db.pool, item.biblio_id, "S-0031", true, None).await;
+
+    let found = db.repo.items_get_active_by_barcode("S-0031").await.unwrap();
+    assert_eq!(found.id, Some(second));
+    assert_eq!(found.biblio_id, Some(item.biblio_id));
+
+    assert!(matches!(
+        db.repo.items_get_active_by_barcode("NOPE").await,
+        Err(AppError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+#[ignore]
+async fn availability_is_batched_by_biblio() {
+    let db = TestDb::new().await;
+    let shelved = ItemBuilder::new("S-0040").insert(&db.pool).await;
+    let reference = ItemBuilder::new("S-0041").not_borrowable().insert(&db.pool).await;
+    add_copy(&db.pool, shelved.biblio_id, "S-0042", true, None).await;
+
+    let availability = db
+        .repo
+        .biblios_get_availability_by_ids(&[shelved.biblio_id, reference.biblio_id, i64::MAX])
+        .await
+        .unwrap();
+
+    assert_eq!(availability.len(), 2, "unknown ids are absent");
+    assert_eq!(availability[&shelved.biblio_id].total_items, 2);
+    assert_eq!(availability[&shelved.biblio_id].available_items, 2);
+    assert!(!availability[&reference.biblio_id].is_available());
+}
+
+#[tokio::test]
+#[ignore]
+async fn spine_labels_filter_by_place_and_creation_day() {
+    let db = TestDb::new().await;
+    let mut ids = Vec::new();
+    for (barcode, call_number, place, created) in [
+        ("L-0001", Some(" R DUM "), 1, "2026-03-01 10:00+00"),
+        ("L-0002", Some("BD GOS"), 1, "2026-03-02 23:00+00"),
+        ("L-0003", None, 1, "2026-03-01 10:00+00"),
+        ("L-0004", Some("R ZOL"), 2, "2026-03-01 10:00+00"),
+    ] {
+        let item = ItemBuilder::new(barcode).insert(&db.pool).await;
+        sqlx::query("UPDATE items SET call_number = $2, place = $3, created_at = $4::timestamptz WHERE id = $1")
+            .bind(item.item_id)
+            .bind(call_number)
+            .bind(place as i16)
+            .bind(created)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        ids.push(item.item_id);
+    }
+
+    let by_place = labels(&db, SpineLabelQuery { place: Some(1), ..Default::default() }).await;
+    assert_eq!(by_place, vec![(ids[1], "BD GOS".to_string()), (ids[0], "R DUM".to_string())]);
+
+    let day = "2026-03-01".parse().ok();
+    let by_day = labels(&db, SpineLabelQuery { created_from: day, created_to: day, ..Default::default() }).await;
+    assert_eq!(by_day.iter().map(|l| l.0).collect::<Vec<_>>(), vec![ids[0], ids[3]]);
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn processing_slips_follow_requested_order() {
+    let db = TestDb::new().await;
+    let first = ItemBuilder::new("PS-0001").title("Germinal").isbn("9782070360420").insert(&db.pool).await;
+    let second = ItemBuilder::new("PS-0002").title("Nana").insert(&db.pool).await;
+    sqlx::query("UPDATE items SET call_number = ' R ZOL ', place = 3 WHERE id = $1")
+        .bind(first.item_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let rows = db.repo.items_processing_slips(&[second.item_id, first.item_id, -1]).await.unwrap();
+    assert_eq!(rows.iter().map(|r| r.item_id).collect::<Vec<_>>(), vec![second.item_id, first.item_id]);
+    let germinal = &rows[1];
+    assert_eq!(germinal.title.as_deref(), Some("Germinal"));
+    assert_eq!((germinal.call_number.as_deref(), germinal.place), (Some("R ZOL"), Some(3)));
+    assert_eq!(germinal.barcode.as_deref(), Some("PS-0001"));
+    assert_eq!(germinal.isbn.as_deref(), Some("9782070360420"));
+}
+
+#[tokio::test]
+#[ignore]
+async fn biblio_edits_keep_unmodeled_marc_data() {
+    let db = TestDb::new().await;
+    let mut record = MarcRecord::default();
+    record.notes.items.push(Note { note_type: Some(NoteType::Awards), text: "Prix des lecteurs 2020".to_string() });
+    let mut biblio = Biblio::from(record);
+    biblio.title = Some("Germinal".to_string());
+    let id = db.repo.biblios_create(&mut biblio).await.unwrap().id.unwrap();
+
+    // Edit payloads never carry the stored record
+    let mut edit = db.repo.biblios_get_by_id(id).await.unwrap();
+    edit.marc_record = None;
+    edit.title = Some("Germinal (édition annotée)".to_string());
+    db.repo.biblios_update(id, &mut edit).await.unwrap();
+
+    let stored = db.repo.biblios_get_marc_record_optional(id).await.unwrap().unwrap();
+    assert_eq!(stored.title_main(), Some("Germinal (édition annotée)"));
+    assert!(stored.notes.items.iter().any(|n| n.text == "Prix des lecteurs 2020"));
+}
+
+#[tokio::test]
+#[ignore]
+async fn inventory_numbers_have_no_gaps_under_concurrency() {
+    let db = TestDb::new().await;
+    let biblio = ItemBuilder::new("INV-0000").insert(&db.pool).await;
+    let copy = |n: i32| -> Item {
+        serde_json::from_value(json!({ "biblioId": null, "sourceId": null, "barcode": format!("INV-{n:04}") }))
+            .unwrap()
+    };
+    let first = db.repo.biblios_create_item(biblio.biblio_id, &copy(1)).await.unwrap().inventory_number.unwrap();
+    let (year, first_seq) = first.split_once('-').unwrap();
+    let first_seq: i32 = first_seq.parse().unwrap();
+
+    let tasks: Vec<_> = (2..=11)
+        .map(|n| {
+            let repo = db.repo.clone();
+            let item = copy(n);
+            tokio::spawn(async move { repo.biblios_create_item(biblio.biblio_id, &item).await.unwrap() })
+        })
+        .collect();
+    let mut seqs = Vec::new();
+    for task in tasks {
+        let number = task.await.unwrap().inventory_number.unwrap();
+        assert!(number.starts_with(&format!("{year}-")));
+        seqs.push(number[year.len() + 1..].parse::<i32>().unwrap());
+    }
+    seqs.sort_unstable();
+    assert_eq!(seqs, (first_seq + 1..=first_seq + 10).collect::<Vec<_>>());
+
+    let (found, _) = db.repo.biblios_search(&query(json!({ "inventoryNumber": first }))).await.unwrap();
+    assert_eq!(found.iter().map(|b| b.id).collect::<Vec<_>>(), vec![biblio.biblio_id]);
+}
+
+async fn labels(db: &TestDb, query: SpineLabelQuery) -> Vec<(i64, String)> {
+    let rows = db.repo.items_spine_labels(&query, 10).await.unwrap();
+    rows.into_iter().map(|r| (r.item_id, r.call_number)).collect()
+}
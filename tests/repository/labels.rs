@@ -0,0 +1,55 @@
+use elidune_server::models::{label::LabelKind, Language};
+
+use crate::{fixtures::UserBuilder, harness::TestDb};
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn labels_are_seeded_and_editable() {
+    let db = TestDb::new().await;
+
+    let languages = db.repo.labels_languages().await.unwrap();
+    assert!(languages.contains(&"en".to_string()) && languages.contains(&"fr".to_string()));
+
+    let media_types = db.repo.labels_list("fr", Some(LabelKind::MediaType)).await.unwrap();
+    assert!(media_types.iter().all(|l| l.kind == LabelKind::MediaType && l.lang == "fr"));
+    let book = media_types.iter().find(|l| l.code == "printedText").expect("seeded label");
+    assert_eq!(book.label, "Livre");
+
+    // Upsert replaces, then a new language shows up
+    let updated = db.repo.labels_upsert(LabelKind::MediaType, "printedText", "fr", "Livre imprimé").await.unwrap();
+    assert_eq!(updated.label, "Livre imprimé");
+    db.repo.labels_upsert(LabelKind::AccountType, "reader", "de", "Leser").await.unwrap();
+    assert!(db.repo.labels_languages().await.unwrap().contains(&"de".to_string()));
+    let german = db.repo.labels_list("de", None).await.unwrap();
+    assert_eq!(german.len(), 1);
+
+    db.repo.labels_delete(LabelKind::AccountType, "reader", "de").await.unwrap();
+    assert!(db.repo.labels_delete(LabelKind::AccountType, "reader", "de").await.is_err());
+    assert!(!db.repo.labels_languages().await.unwrap().contains(&"de".to_string()));
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn user_language_drives_label_language() {
+    let db = TestDb::new().await;
+    let user_id = UserBuilder::new("polyglot").insert(&db.pool).await;
+    // `users.language` defaults to french
+    let language = db.repo.labels_user_language(user_id).await.unwrap();
+    assert_eq!(language, Some(Language::French));
+    assert_eq!(language.and_then(|l| l.iso_639_1()), Some("fr"));
+
+    sqlx::query("UPDATE users SET language = 'english' WHERE id = $1")
+        .bind(user_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(db.repo.labels_user_language(user_id).await.unwrap(), Some(Language::English));
+
+    sqlx::query("UPDATE users SET language = NULL WHERE id = $1")
+        .bind(user_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(db.repo.labels_user_language(user_id).await.unwrap(), None);
+    assert_eq!(db.repo.labels_user_language(-1).await.unwrap(), None);
+}
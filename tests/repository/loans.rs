@@ -0,0 +1,374 @@
+use chrono::{Duration, Utc};
+use elidune_server::{
+    error::AppError,
+    models::{
+        hold::{CreateHold, HoldPriority},
+        loan::{LoanMatchField, RenewalBlockCode, ReturnRouting, ReturnRoutingAction},
+    },
+};
+
+use crate::{
+    fixtures::{ItemBuilder, LoanBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn checkout_and_return_lifecycle() {
+    let db = TestDb::new().await;
+    let user_id = UserBuilder::new("reader1").insert(&db.pool).await;
+    let item = ItemBuilder::new("L-0001").insert(&db.pool).await;
+
+    let loan_id = LoanBuilder::new(user_id, item).insert(&db.repo).await;
+    assert_eq!(db.repo.loans_count_active_for_item(item.item_id).await.unwrap(), 1);
+
+    db.repo.loans_return(loan_id).await.unwrap();
+
+    // Returned loans move to `loans_archives`
+    assert!(matches!(
+        db.repo.loans_get_by_id(loan_id).await,
+        Err(AppError::NotFound(_))
+    ));
+    assert_eq!(db.repo.loans_count_active_for_item(item.item_id).await.unwrap(), 0);
+    let archived: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM loans_archives WHERE item_id = $1")
+        .bind(item.item_id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(archived, 1);
+
+    assert!(matches!(
+        db.repo.loans_return(loan_id).await,
+        Err(AppError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+#[ignore]
+async fn borrowed_item_cannot_be_lent_twice() {
+    let db = TestDb::new().await;
+    let first = UserBuilder::new("reader1").insert(&db.pool).await;
+    let second = UserBuilder::new("staff1").account_type("librarian").insert(&db.pool).await;
+    let item = ItemBuilder::new("L-0010").insert(&db.pool).await;
+
+    LoanBuilder::new(first, item).insert(&db.repo).await;
+
+    let again = LoanBuilder::new(second, item).request();
+    assert!(matches!(
+        db.repo.loans_create(&again).await,
+        Err(AppError::BusinessRule(_))
+    ));
+}
+
+#[tokio::test]
+#[ignore]
+async fn concurrent_checkouts_of_one_copy_lend_it_once() {
+    let db = TestDb::new().await;
+    let item = ItemBuilder::new("L-0011").insert(&db.pool).await;
+    let mut tasks = Vec::new();
+    for n in 0..8 {
+        let user_id = UserBuilder::new(&format!("desk{n}")).insert(&db.pool).await;
+        let repo = db.repo.clone();
+        let request = LoanBuilder::new(user_id, item).request();
+        tasks.push(tokio::spawn(async move { repo.loans_create(&request).await }));
+    }
+
+    let mut lent = 0;
+    for task in tasks {
+        match task.await.unwrap() {
+            Ok(_) => lent += 1,
+            Err(AppError::BusinessRule(msg)) => assert_eq!(msg, "Item is already borrowed"),
+            Err(e) => panic!("unexpected error: {e}"),
+        }
+    }
+    assert_eq!(lent, 1);
+    assert_eq!(db.repo.loans_count_active_for_item(item.item_id).await.unwrap(), 1);
+}
+
+#[tokio::test]
+#[ignore]
+async fn circulation_rules_can_be_forced() {
+    let db = TestDb::new().await;
+    let user_id = UserBuilder::new("reader1").insert(&db.pool).await;
+    let reference = ItemBuilder::new("L-0020").not_borrowable().insert(&db.pool).await;
+    let lost = ItemBuilder::new("L-0021").state(2).insert(&db.pool).await;
+
+    for item in [reference, lost] {
+        let refused = LoanBuilder::new(user_id, item).request();
+        assert!(matches!(
+            db.repo.loans_create(&refused).await,
+            Err(AppError::BusinessRule(_))
+        ));
+        LoanBuilder::new(user_id, item).force().insert(&db.repo).await;
+        assert_eq!(db.repo.loans_count_active_for_item(item.item_id).await.unwrap(), 1);
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn active_loans_are_batched_by_user() {
+    let db = TestDb::new().await;
+    let reader = UserBuilder::new("reader1").insert(&db.pool).await;
+    let idle = UserBuilder::new("reader2").insert(&db.pool).await;
+    let first = ItemBuilder::new("L-0030").insert(&db.pool).await;
+    let second = ItemBuilder::new("L-0031").insert(&db.pool).await;
+    LoanBuilder::new(reader, first).insert(&db.repo).await;
+    let returned = LoanBuilder::new(reader, second).insert(&db.repo).await;
+    db.repo.loans_return(returned).await.unwrap();
+
+    let loans = db.repo.loans_get_active_for_users(&[reader, idle]).await.unwrap();
+
+    assert_eq!(loans.len(), 1, "users without active loans are absent");
+    let active = &loans[&reader];
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].item_id, Some(first.item_id));
+    assert_eq!(active[0].item_identification.as_deref(), Some("L-0030"));
+}
+
+#[tokio::test]
+#[ignore]
+async fn overdue_loans_are_counted_per_user() {
+    let db = TestDb::new().await;
+    let reader = UserBuilder::new("reader1").insert(&db.pool).await;
+    let other = UserBuilder::new("reader2").insert(&db.pool).await;
+    let late = ItemBuilder::new("L-0040").insert(&db.pool).await;
+    let on_time = ItemBuilder::new("L-0041").insert(&db.pool).await;
+    let elsewhere = ItemBuilder::new("L-0042").insert(&db.pool).await;
+    let late_loan = LoanBuilder::new(reader, late).insert(&db.repo).await;
+    LoanBuilder::new(reader, on_time).insert(&db.repo).await;
+    let other_loan = LoanBuilder::new(other, elsewhere).insert(&db.repo).await;
+    sqlx::query("UPDATE loans SET expiry_at = NOW() - INTERVAL '3 days' WHERE id = ANY($1)")
+        .bind(vec![late_loan, other_loan])
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    assert_eq!(db.repo.loans_count_overdue_for_user(reader).await.unwrap(), 1);
+
+    // Returned loans no longer count
+    db.repo.loans_return(late_loan).await.unwrap();
+    assert_eq!(db.repo.loans_count_overdue_for_user(reader).await.unwrap(), 0);
+}
+
+#[tokio::test]
+#[ignore]
+async fn renewability_reports_every_blocking_rule() {
+    let db = TestDb::new().await;
+    let borrower = UserBuilder::new("reader1").insert(&db.pool).await;
+    let other = UserBuilder::new("reader2").insert(&db.pool).await;
+    let item = ItemBuilder::new("L-0030").insert(&db.pool).await;
+    let loan_id = LoanBuilder::new(borrower, item).insert(&db.repo).await;
+
+    let renewability = db.repo.loans_renewability(loan_id).await.unwrap();
+    assert!(renewability.renewable && renewability.blocks.is_empty());
+    assert!(renewability.max_renewals > 0);
+
+    // Renewals until the limit: the check and the renewal agree
+    for _ in 0..renewability.max_renewals {
+        db.repo.loans_renew(loan_id).await.unwrap();
+    }
+    let renewability = db.repo.loans_renewability(loan_id).await.unwrap();
+    assert!(!renewability.renewable);
+    assert_eq!(renewability.renew_count, renewability.max_renewals);
+    assert!(renewability.is_blocked_by(RenewalBlockCode::MaxRenewalsReached));
+    assert!(matches!(db.repo.loans_renew(loan_id).await, Err(AppError::BusinessRule(_))));
+
+    // A hold queued by another patron, and the copy's deposit closed
+    db.repo
+        .holds_create(&CreateHold {
+            user_id: other,
+            item_id: item.item_id,
+            notes: None,
+            pickup_locker: false,
+            priority: HoldPriority::Normal,
+        })
+        .await
+        .unwrap();
+    let deposit_id: i64 = sqlx::query_scalar(
+        "INSERT INTO deposits (owner, return_due, status) VALUES ('County library', CURRENT_DATE, 'closed') RETURNING id",
+    )
+    .fetch_one(&db.pool)
+    .await
+    .unwrap();
+    sqlx::query("UPDATE items SET deposit_id = $1 WHERE id = $2")
+        .bind(deposit_id)
+        .bind(item.item_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    let codes: Vec<_> = db.repo.loans_renewability(loan_id).await.unwrap().blocks.iter().map(|b| b.code).collect();
+    assert_eq!(
+        codes,
+        vec![
+            RenewalBlockCode::MaxRenewalsReached,
+            RenewalBlockCode::HoldQueued,
+            RenewalBlockCode::ItemRequestedBack,
+        ]
+    );
+}
+
+#[tokio::test]
+#[ignore]
+async fn floating_routing_is_recorded_on_the_copy() {
+    let db = TestDb::new().await;
+    let item = ItemBuilder::new("L-0040").media_type("videoDvd").insert(&db.pool).await;
+    sqlx::query("UPDATE items SET place = 1 WHERE id = $1")
+        .bind(item.item_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    let routing = |action, prompt_override| ReturnRouting {
+        action,
+        home_place: 1,
+        return_place: 2,
+        rule: None,
+        overridden: false,
+        prompt_override,
+    };
+
+    let facts = db.repo.loans_item_floating_facts(item.item_id).await.unwrap();
+    assert_eq!((facts.home_place, facts.media_type.as_deref()), (Some(1), Some("videoDvd")));
+    assert!(!facts.on_loan);
+
+    // Waiting for the staff: nothing moves yet
+    db.repo.loans_route_item(item.item_id, &routing(ReturnRoutingAction::Float, true)).await.unwrap();
+    let facts = db.repo.loans_item_floating_facts(item.item_id).await.unwrap();
+    assert_eq!((facts.home_place, facts.routing_place), (Some(1), Some(2)));
+
+    db.repo.loans_route_item(item.item_id, &routing(ReturnRoutingAction::TransitHome, false)).await.unwrap();
+    let facts = db.repo.loans_item_floating_facts(item.item_id).await.unwrap();
+    assert_eq!((facts.transit_place, facts.routing_place), (Some(1), None));
+    db.repo.loans_clear_item_routing(item.item_id).await.unwrap();
+    assert_eq!(db.repo.loans_item_floating_facts(item.item_id).await.unwrap().transit_place, None);
+
+    db.repo.loans_route_item(item.item_id, &routing(ReturnRoutingAction::Float, false)).await.unwrap();
+    let (place, floated_from): (Option<i16>, Option<i16>) =
+        sqlx::query_as("SELECT place, floated_from_place FROM items WHERE id = $1")
+            .bind(item.item_id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+    assert_eq!((place, floated_from), (Some(2), Some(1)));
+}
+
+#[tokio::test]
+#[ignore]
+async fn due_soon_loans_are_listed_once_per_day() {
+    let db = TestDb::new().await;
+    let user_id = UserBuilder::new("reader1").insert(&db.pool).await;
+    sqlx::query("UPDATE users SET email = 'reader1@example.org', receive_reminders = TRUE WHERE id = $1")
+        .bind(user_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    let soon = LoanBuilder::new(user_id, ItemBuilder::new("L-0040").insert(&db.pool).await).insert(&db.repo).await;
+    let later = LoanBuilder::new(user_id, ItemBuilder::new("L-0041").insert(&db.pool).await).insert(&db.repo).await;
+    for (loan_id, days) in [(soon, 2), (later, 7)] {
+        sqlx::query("UPDATE loans SET expiry_at = NOW() + make_interval(days => $1) WHERE id = $2")
+            .bind(days)
+            .bind(loan_id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+    }
+
+    let ids = |rows: Vec<elidune_server::repository::loans::OverdueLoanRow>| rows.iter().map(|r| r.loan_id).collect::<Vec<_>>();
+    assert_eq!(ids(db.repo.loans_get_due_soon(&[2]).await.unwrap()), vec![soon]);
+    assert_eq!(ids(db.repo.loans_get_due_soon(&[7, 2]).await.unwrap()), vec![soon, later]);
+    assert!(db.repo.loans_get_due_soon(&[]).await.unwrap().is_empty());
+
+    db.repo.loans_update_due_soon_notified(&[soon]).await.unwrap();
+    assert_eq!(ids(db.repo.loans_get_due_soon(&[7, 2]).await.unwrap()), vec![later]);
+}
+
+#[tokio::test]
+#[ignore]
+async fn patron_loans_search_covers_active_and_archived_loans() {
+    let db = TestDb::new().await;
+    let user_id = UserBuilder::new("reader1").insert(&db.pool).await;
+    let other = UserBuilder::new("reader2").insert(&db.pool).await;
+    let gaul = ItemBuilder::new("AST-01").title("Astérix le Gaulois").insert(&db.pool).await;
+    let legion = ItemBuilder::new("AST-02").title("Astérix légionnaire").insert(&db.pool).await;
+    let tintin = ItemBuilder::new("TIN-01").title("Tintin au Tibet").insert(&db.pool).await;
+    let others = ItemBuilder::new("AST-03").title("Astérix chez les Belges").insert(&db.pool).await;
+    sqlx::query(
+        "WITH a AS (INSERT INTO authors (firstname, lastname) VALUES ('René', 'Goscinny') RETURNING id) \
+         INSERT INTO biblio_authors (biblio_id, author_id) SELECT $1, id FROM a",
+    )
+    .bind(legion.biblio_id)
+    .execute(&db.pool)
+    .await
+    .unwrap();
+
+    let returned = LoanBuilder::new(user_id, gaul).insert(&db.repo).await;
+    db.repo.loans_return(returned).await.unwrap();
+    LoanBuilder::new(user_id, legion).insert(&db.repo).await;
+    LoanBuilder::new(user_id, tintin).insert(&db.repo).await;
+    LoanBuilder::new(other, others).insert(&db.repo).await;
+
+    // Active loans first, then returned ones; another patron's loans are not searched
+    let (loans, total) = db.repo.loans_search_for_user(user_id, "asterix", 1, 20).await.unwrap();
+    assert_eq!(total, 2);
+    assert!(loans[0].returned_at.is_none() && loans[1].returned_at.is_some());
+    let matched = loans[1].matched.as_ref().unwrap();
+    assert_eq!((matched.field, matched.highlighted.as_str()), (LoanMatchField::Title, "<mark>Astérix</mark> le Gaulois"));
+
+    let (loans, _) = db.repo.loans_search_for_user(user_id, "goscinny", 1, 20).await.unwrap();
+    assert_eq!(loans.len(), 1);
+    let matched = loans[0].matched.as_ref().unwrap();
+    assert_eq!((matched.field, matched.value.as_str()), (LoanMatchField::Author, "René Goscinny"));
+
+    let (loans, _) = db.repo.loans_search_for_user(user_id, "tin-0", 1, 20).await.unwrap();
+    assert_eq!(loans[0].matched.as_ref().unwrap().field, LoanMatchField::Barcode);
+
+    let (loans, total) = db.repo.loans_search_for_user(user_id, "100%", 1, 20).await.unwrap();
+    assert!(loans.is_empty() && total == 0);
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn returned_loans_are_archived_by_the_archival_policy() {
+    let db = TestDb::new().await;
+    let user_id = UserBuilder::new("archival-reader").insert(&db.pool).await;
+    let old = ItemBuilder::new("ARC-1").insert(&db.pool).await;
+    let recent = ItemBuilder::new("ARC-2").insert(&db.pool).await;
+    let out = ItemBuilder::new("ARC-3").insert(&db.pool).await;
+    // Returned loans kept in `loans` (`circulation.archive_returned_after_days` > 0)
+    for (item, days_ago) in [(old, 10), (recent, 1)] {
+        let loan_id = LoanBuilder::new(user_id, item).insert(&db.repo).await;
+        sqlx::query("UPDATE loans SET returned_at = NOW() - make_interval(days => $2) WHERE id = $1")
+            .bind(loan_id)
+            .bind(days_ago)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+    }
+    LoanBuilder::new(user_id, out).insert(&db.repo).await;
+
+    // Both returns show in the patron's history before archival
+    let (history, total) = db.repo.loans_archives_get_for_user(user_id, 1, 20).await.unwrap();
+    assert_eq!((history.len(), total), (2, 2));
+
+    let cutoff = Utc::now() - Duration::days(7);
+    assert_eq!(db.repo.loans_archive_returned(cutoff).await.unwrap(), 1);
+    assert_eq!(db.repo.loans_archive_returned(cutoff).await.unwrap(), 0);
+
+    let (archived, in_loans): (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*) FILTER (WHERE archived), COUNT(*) FILTER (WHERE NOT archived) FROM loans_all WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_one(&db.pool)
+    .await
+    .unwrap();
+    assert_eq!((archived, in_loans), (1, 2));
+    let account_type: Option<String> =
+        sqlx::query_scalar("SELECT account_type FROM loans_archives WHERE item_id = $1")
+            .bind(old.item_id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+    assert!(account_type.is_some());
+    let (_, total) = db.repo.loans_archives_get_for_user(user_id, 1, 20).await.unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(db.repo.loans_count_active_for_user(user_id).await.unwrap(), 1);
+}
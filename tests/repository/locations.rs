@@ -0,0 +1,82 @@
+use elidune_server::models::{
+    biblio::BiblioQuery,
+    location::{CreateLocation, LocationKind, UpdateLocation},
+};
+use serde_json::json;
+
+use crate::{fixtures::ItemBuilder, harness::TestDb};
+
+fn location(kind: LocationKind, parent_id: Option<i64>, code: Option<&str>, name: &str) -> CreateLocation {
+    CreateLocation {
+        kind,
+        parent_id,
+        code: code.map(str::to_string),
+        name: name.to_string(),
+        place: None,
+        sort_order: None,
+    }
+}
+
+async fn shelve(db: &TestDb, item_id: i64, place: i16, call_number: &str) {
+    sqlx::query("UPDATE items SET place = $2, call_number = $3 WHERE id = $1")
+        .bind(item_id)
+        .bind(place)
+        .bind(call_number)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn shelving_map_locates_copies_by_call_number_prefix() {
+    let db = TestDb::new().await;
+    let site = db.repo.locations_create(&location(LocationKind::Site, None, None, "Main library"), 1).await.unwrap();
+    let youth = db
+        .repo
+        .locations_create(&location(LocationKind::Room, Some(site.id), Some("J"), "Youth"), 1)
+        .await
+        .unwrap();
+    let comics = db
+        .repo
+        .locations_create(&location(LocationKind::ShelfRange, Some(youth.id), Some("BD"), "Comics"), 1)
+        .await
+        .unwrap();
+    assert_eq!(comics.path, "Main library › Youth › Comics");
+
+    let listed: Vec<i64> = db.repo.locations_list(Some(1)).await.unwrap().iter().map(|l| l.id).collect();
+    assert_eq!(listed, vec![site.id, youth.id, comics.id]);
+
+    let tintin = ItemBuilder::new("LOC-1").title("Tintin").insert(&db.pool).await;
+    let novel = ItemBuilder::new("LOC-2").title("Novel").insert(&db.pool).await;
+    shelve(&db, tintin.item_id, 1, "BD HER").await;
+    shelve(&db, novel.item_id, 1, "R DOY").await;
+
+    let rows = db.repo.locations_mapping_candidates().await.unwrap();
+    let bd = rows.iter().find(|r| r.prefix == "BD").expect("BD group");
+    assert_eq!((bd.place, bd.item_count, bd.suggested_location_id), (Some(1), 1, Some(comics.id)));
+    assert_eq!(rows.iter().find(|r| r.prefix == "R").unwrap().suggested_location_id, None);
+
+    let located = db.repo.locations_apply_mapping(Some(1), "BD", &comics).await.unwrap();
+    assert_eq!(located, vec![tintin.biblio_id]);
+    assert_eq!(db.repo.locations_count_unlocated().await.unwrap(), 1);
+    assert_eq!(db.repo.locations_count_usage(comics.id).await.unwrap(), (0, 1));
+    assert_eq!(db.repo.locations_count_usage(youth.id).await.unwrap(), (1, 0));
+
+    // The location filter includes the shelf ranges under a room
+    let query: BiblioQuery = serde_json::from_value(json!({ "locationId": youth.id })).unwrap();
+    let (found, _) = db.repo.biblios_search(&query).await.unwrap();
+    assert_eq!(found.iter().map(|r| r.id).collect::<Vec<_>>(), vec![tintin.biblio_id]);
+    let facets = db.repo.biblios_search_facets(&BiblioQuery::default()).await.unwrap();
+    assert_eq!(facets.locations[0].label.as_deref(), Some("Main library › Youth › Comics"));
+
+    let item = db.repo.items_get_active_by_id(tintin.item_id).await.unwrap();
+    assert_eq!(item.location_path.as_deref(), Some("Main library › Youth › Comics"));
+
+    let renamed = db
+        .repo
+        .locations_update(youth.id, &UpdateLocation { code: Some(String::new()), name: Some("Kids".to_string()), sort_order: None })
+        .await
+        .unwrap();
+    assert_eq!((renamed.code, renamed.path.as_str()), (None, "Main library › Kids"));
+}
@@ -0,0 +1,46 @@
+//! Repository integration tests against throwaway Postgres / Redis containers.
+//!
+//! These need Docker (or `TEST_DATABASE_URL` / `TEST_REDIS_URL`), so they are ignored by default:
+//!
+//! ```sh
+//! cargo test --test repository -- --ignored
+//! ```
+
+mod fixtures;
+mod harness;
+
+mod api_keys;
+mod authors;
+mod backups;
+mod bundles;
+mod campaigns;
+mod dashboards;
+mod deposits;
+mod email_health;
+mod eod;
+mod events;
+mod fines;
+mod harvest;
+mod headings;
+mod holds;
+mod item_states;
+mod item_templates;
+mod items;
+mod labels;
+mod loans;
+mod locations;
+mod media_types;
+mod notifications;
+mod payments;
+mod purchase_suggestions;
+mod redis;
+mod reengagement;
+mod relators;
+mod sitemap;
+mod snapshots;
+mod soft_delete;
+mod staffing;
+mod stats;
+mod user_messages;
+mod users;
+mod warehouse;
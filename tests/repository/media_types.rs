@@ -0,0 +1,112 @@
+use chrono::Utc;
+use elidune_server::{
+    error::AppError,
+    marc::{set_marc_leader_codes, MarcRecord},
+    models::{
+        biblio::Biblio,
+        media_type::{CreateMediaType, UpdateMediaType},
+        MediaType,
+    },
+};
+
+use crate::{
+    fixtures::{ItemBuilder, LoanBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+fn board_game() -> CreateMediaType {
+    CreateMediaType {
+        code: "boardGame".to_string(),
+        label: "Board game".to_string(),
+        icon: Some("dice".to_string()),
+        loan_duration: Some(7),
+        loan_nb_max: Some(1),
+        loan_nb_renews: Some(0),
+        marc_record_type: Some("r".to_string()),
+        marc_bibliographic_level: None,
+        vat_rate: Some(rust_decimal::Decimal::new(55, 1)),
+        sort_order: Some(20),
+    }
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn media_types_are_seeded_and_editable() {
+    let db = TestDb::new().await;
+    let seeded = db.repo.media_types_list().await.unwrap();
+    let book = seeded.iter().find(|m| m.code == "printedText").expect("seeded media type");
+    assert_eq!((book.legacy_code.as_deref(), book.marc_record_type.as_str()), (Some("b"), "a"));
+    let periodic = seeded.iter().find(|m| m.code == "periodic").unwrap();
+    assert_eq!(periodic.marc_bibliographic_level, "s");
+
+    let created = db.repo.media_types_create(&board_game()).await.unwrap();
+    assert_eq!((created.marc_record_type.as_str(), created.marc_bibliographic_level.as_str()), ("r", "m"));
+    assert_eq!(created.vat_rate, Some(rust_decimal::Decimal::new(55, 1)));
+    assert!(db.repo.media_types_create(&board_game()).await.is_err());
+
+    let updated = db
+        .repo
+        .media_types_update(
+            "boardGame",
+            &UpdateMediaType {
+                label: Some("Game".to_string()),
+                icon: Some(String::new()),
+                loan_duration: Some(14),
+                loan_nb_max: None,
+                loan_nb_renews: None,
+                clear_loan_rules: true,
+                marc_record_type: None,
+                marc_bibliographic_level: None,
+                vat_rate: None,
+                clear_vat_rate: true,
+                sort_order: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!((updated.label.as_str(), updated.icon.as_deref()), ("Game", None));
+    assert_eq!((updated.loan_duration, updated.loan_nb_max, updated.loan_nb_renews), (Some(14), None, None));
+    assert_eq!(updated.marc_record_type, "r");
+    assert_eq!(updated.vat_rate, None);
+
+    ItemBuilder::new("MT-1").media_type("boardGame").insert(&db.pool).await;
+    assert_eq!(db.repo.media_types_count_biblios("boardGame").await.unwrap(), 1);
+
+    db.repo.media_types_create(&CreateMediaType { code: "puzzle".to_string(), ..board_game() }).await.unwrap();
+    db.repo.media_types_delete("puzzle").await.unwrap();
+    assert!(matches!(db.repo.media_types_get("puzzle").await, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn media_type_default_loan_rules_apply_without_loan_settings() {
+    let db = TestDb::new().await;
+    db.repo.media_types_create(&board_game()).await.unwrap();
+    let user_id = UserBuilder::new("gamer").insert(&db.pool).await;
+    let item = ItemBuilder::new("MT-2").media_type("boardGame").insert(&db.pool).await;
+
+    let (_, expiry_at) = db.repo.loans_create(&LoanBuilder::new(user_id, item).request()).await.unwrap();
+    let days = (expiry_at - Utc::now()).num_hours() as f64 / 24.0;
+    assert!((6.0..=8.0).contains(&days), "expected a 7-day loan, got {days} days");
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn biblios_must_use_a_known_media_type() {
+    let db = TestDb::new().await;
+
+    let mut biblio = Biblio::from(MarcRecord::default());
+    biblio.title = Some("Unregistered".to_string());
+    biblio.marc_record = None;
+    biblio.media_type = MediaType::from("boardGame");
+    assert!(matches!(db.repo.biblios_create(&mut biblio).await, Err(AppError::Validation(_))));
+
+    // Imported records are classified from the leader codes the taxonomy claims
+    db.repo.media_types_create(&board_game()).await.unwrap();
+    let mut record = MarcRecord::default();
+    set_marc_leader_codes(&mut record, 'r', 'm');
+    let mut biblio = Biblio::from(record);
+    biblio.title = Some("Imported game".to_string());
+    let created = db.repo.biblios_create(&mut biblio).await.unwrap();
+    assert_eq!(created.media_type, MediaType::Other("boardGame".to_string()));
+}
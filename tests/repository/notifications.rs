@@ -0,0 +1,57 @@
+use elidune_server::{
+    error::AppError,
+    models::notification::{NewNotification, NotificationKind},
+};
+use serde_json::json;
+
+use crate::{fixtures::UserBuilder, harness::TestDb};
+
+fn task_completed(title: &str) -> NewNotification {
+    NewNotification {
+        kind: NotificationKind::TaskCompleted,
+        title: title.to_string(),
+        body: None,
+        entity_type: Some("task".to_string()),
+        entity_id: Some(42),
+        data: Some(json!({ "taskKind": "marcBatchImport" })),
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn notifications_are_scoped_to_their_recipient() {
+    let db = TestDb::new().await;
+    let librarian = UserBuilder::new("librarian1").account_type("librarian").insert(&db.pool).await;
+    let reader = UserBuilder::new("reader1").account_type("reader").insert(&db.pool).await;
+
+    let first = db.repo.notifications_create(librarian, &task_completed("Import done")).await.unwrap();
+    let second = db.repo.notifications_create(librarian, &task_completed("Export done")).await.unwrap();
+    assert_eq!(first.kind, NotificationKind::TaskCompleted);
+    assert!(first.read_at.is_none());
+
+    let (page, total) = db.repo.notifications_list(librarian, false, 1, 20).await.unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(page[0].id, second.id, "newest first");
+    assert_eq!(db.repo.notifications_unread_count(librarian).await.unwrap(), 2);
+
+    // Another user can neither see nor mark them
+    assert_eq!(db.repo.notifications_list(reader, false, 1, 20).await.unwrap().1, 0);
+    assert!(matches!(
+        db.repo.notifications_mark_read(reader, first.id).await,
+        Err(AppError::NotFound(_))
+    ));
+
+    let read = db.repo.notifications_mark_read(librarian, first.id).await.unwrap();
+    let read_at = read.read_at.expect("marked as read");
+    let again = db.repo.notifications_mark_read(librarian, first.id).await.unwrap();
+    assert_eq!(again.read_at, Some(read_at), "first read time is kept");
+
+    let (unread, total) = db.repo.notifications_list(librarian, true, 1, 20).await.unwrap();
+    assert_eq!((unread.len(), total), (1, 1));
+    assert_eq!(db.repo.notifications_mark_all_read(librarian).await.unwrap(), 1);
+    assert_eq!(db.repo.notifications_unread_count(librarian).await.unwrap(), 0);
+
+    let recipients = db.repo.notifications_staff_recipients().await.unwrap();
+    assert!(recipients.contains(&librarian));
+    assert!(!recipients.contains(&reader));
+}
@@ -0,0 +1,156 @@
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDate, Utc};
+use elidune_server::{
+    error::AppError,
+    models::{
+        fine::FineStatus,
+        payment::{CashTotalsBy, PaymentCategory, PaymentMethod, RecordPayment, RefundPayment},
+    },
+};
+use rust_decimal::Decimal;
+
+use crate::{fixtures::UserBuilder, harness::TestDb};
+
+const YEAR: i32 = 2026;
+
+fn dec(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+fn payment(category: PaymentCategory, method: PaymentMethod, user_id: Option<i64>, fine_id: Option<i64>) -> RecordPayment {
+    RecordPayment { user_id, amount: None, method, category, fine_id, notes: None }
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn payments_settle_fines_renew_memberships_and_refund() {
+    let db = TestDb::new().await;
+    let clerk = UserBuilder::new("clerk").account_type("librarian").insert(&db.pool).await;
+    let reader = UserBuilder::new("reader").insert(&db.pool).await;
+
+    // No migration creates `fines` yet: an unknown fine is reported as such
+    let fine_payment = payment(PaymentCategory::Fine, PaymentMethod::Cash, None, Some(1));
+    let err = db.repo.payments_create(&fine_payment, dec("1.00"), None, clerk, YEAR).await.unwrap_err();
+    assert!(matches!(err, AppError::NotFound(_)));
+
+    sqlx::query(
+        r#"
+        CREATE TABLE fines (
+            id BIGINT PRIMARY KEY, loan_id BIGINT NOT NULL, user_id BIGINT NOT NULL,
+            amount NUMERIC(10,2) NOT NULL, paid_amount NUMERIC(10,2) NOT NULL DEFAULT 0,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(), paid_at TIMESTAMPTZ,
+            status TEXT NOT NULL DEFAULT 'pending', notes TEXT
+        )
+        "#,
+    )
+    .execute(&db.pool)
+    .await
+    .unwrap();
+    let fine = db.repo.fines_create(1, reader, dec("3.00"), None).await.unwrap();
+
+    let fine_payment = payment(PaymentCategory::Fine, PaymentMethod::Cash, None, Some(fine.id));
+    let err = db.repo.payments_create(&fine_payment, dec("3.50"), None, clerk, YEAR).await.unwrap_err();
+    assert!(matches!(err, AppError::Validation(_)));
+    let paid = db.repo.payments_create(&fine_payment, dec("3.00"), None, clerk, YEAR).await.unwrap();
+    // Rejected payments did not consume a receipt number
+    assert_eq!(paid.receipt_number, "2026-000001");
+    assert_eq!((paid.user_id, paid.operator_id), (Some(reader), Some(clerk)));
+    assert_eq!(db.repo.fines_get_by_id(fine.id).await.unwrap().status, FineStatus::Paid);
+    let err = db.repo.payments_create(&fine_payment, dec("1.00"), None, clerk, YEAR).await.unwrap_err();
+    assert!(matches!(err, AppError::BusinessRule(_)));
+
+    // A partial refund reopens the fine, a second one cannot exceed what is left
+    let partial = RefundPayment { amount: Some(dec("1.00")), ..Default::default() };
+    let refund = db.repo.payments_refund(paid.id, &partial, clerk, YEAR).await.unwrap();
+    assert_eq!((refund.amount, refund.refund_of), (dec("-1.00"), Some(paid.id)));
+    let fine = db.repo.fines_get_by_id(fine.id).await.unwrap();
+    assert_eq!((fine.status, fine.paid_amount), (FineStatus::Partial, dec("2.00")));
+    let too_much = RefundPayment { amount: Some(dec("2.50")), ..Default::default() };
+    assert!(matches!(db.repo.payments_refund(paid.id, &too_much, clerk, YEAR).await, Err(AppError::Validation(_))));
+    assert!(matches!(db.repo.payments_refund(refund.id, &partial, clerk, YEAR).await, Err(AppError::BusinessRule(_))));
+    assert_eq!(db.repo.payments_get(paid.id).await.unwrap().refunded_amount, dec("1.00"));
+
+    // A renewal extends an expired membership from today
+    sqlx::query("UPDATE users SET expiry_at = NOW() - INTERVAL '10 days' WHERE id = $1")
+        .bind(reader)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    let renewal = payment(PaymentCategory::Membership, PaymentMethod::Card, Some(reader), None);
+    let renewed = db.repo.payments_create(&renewal, dec("15.00"), Some(365), clerk, YEAR).await.unwrap();
+    let expiry = renewed.membership_expiry_at.unwrap();
+    assert!(expiry > Utc::now() + Duration::days(364) && expiry < Utc::now() + Duration::days(366));
+
+    let from = Utc::now() - Duration::hours(1);
+    let to = Utc::now() + Duration::hours(1);
+    let by_method = db.repo.payments_totals(from, to, CashTotalsBy::Method).await.unwrap();
+    let cash = by_method.iter().find(|t| t.key == "cash").unwrap();
+    assert_eq!((cash.received, cash.refunded, cash.net, cash.count), (dec("3.00"), dec("1.00"), dec("2.00"), 2));
+    let operators = db.repo.payments_operator_totals(from, to).await.unwrap();
+    assert_eq!(operators.len(), 1);
+    assert_eq!((operators[0].operator_id, operators[0].net), (Some(clerk), dec("17.00")));
+}
+
+#[tokio::test]
+#[ignore]
+async fn receipts_are_numbered_per_year_and_exported_periods_are_locked() {
+    let db = TestDb::new().await;
+    let clerk = UserBuilder::new("clerk").account_type("librarian").insert(&db.pool).await;
+    let printing = RecordPayment {
+        user_id: None,
+        amount: None,
+        method: PaymentMethod::Cash,
+        category: PaymentCategory::Printing,
+        fine_id: None,
+        notes: None,
+    };
+
+    let first = db.repo.payments_create(&printing, dec("0.40"), None, clerk, YEAR).await.unwrap();
+    let second = db.repo.payments_create(&printing, dec("0.80"), None, clerk, YEAR).await.unwrap();
+    let refund = db.repo.payments_refund(second.id, &RefundPayment::default(), clerk, YEAR).await.unwrap();
+    let next_year = db.repo.payments_create(&printing, dec("0.20"), None, clerk, YEAR + 1).await.unwrap();
+    assert_eq!(
+        [&first, &second, &refund, &next_year].map(|p| p.receipt_number.as_str()),
+        ["2026-000001", "2026-000002", "2026-000003", "2027-000001"]
+    );
+
+    let report = db.repo.payments_receipt_gaps(YEAR).await.unwrap();
+    assert_eq!((report.issued, report.last_number.as_deref()), (3, Some("2026-000003")));
+    assert!(report.missing.is_empty());
+
+    let from = Utc::now() - Duration::hours(1);
+    let to = Utc::now() + Duration::hours(1);
+    let rows = db.repo.payments_export_rows(from, to).await.unwrap();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[2].refund_of_receipt.as_deref(), Some("2026-000002"));
+
+    // Deleting a payment leaves a gap while the period is open
+    sqlx::query("DELETE FROM payments WHERE id = $1").bind(first.id).execute(&db.pool).await.unwrap();
+    let report = db.repo.payments_receipt_gaps(YEAR).await.unwrap();
+    assert_eq!(report.missing, vec!["2026-000001".to_string()]);
+
+    let period = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+    let lock = db.repo.payments_lock_period(period, from, to, 3, dec("0.20"), clerk).await.unwrap();
+    assert_eq!((lock.month.as_str(), lock.payments_count), ("2026-10", 3));
+    // A second export keeps the first lock
+    let again = db.repo.payments_lock_period(period, from, to, 99, dec("0"), clerk).await.unwrap();
+    assert_eq!(again.payments_count, 3);
+
+    // Accounting fields of a locked period are frozen; the patron link may still change
+    let update = sqlx::query("UPDATE payments SET amount = 9 WHERE id = $1").bind(second.id).execute(&db.pool).await;
+    assert!(update.is_err());
+    let delete = sqlx::query("DELETE FROM payments WHERE id = $1").bind(next_year.id).execute(&db.pool).await;
+    assert!(delete.is_err());
+    sqlx::query("UPDATE payments SET user_id = $2 WHERE id = $1")
+        .bind(second.id)
+        .bind(clerk)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    assert_eq!(db.repo.payments_period_locks().await.unwrap().len(), 1);
+    db.repo.payments_unlock_period(period).await.unwrap();
+    assert!(matches!(db.repo.payments_unlock_period(period).await, Err(AppError::NotFound(_))));
+    sqlx::query("DELETE FROM payments WHERE id = $1").bind(next_year.id).execute(&db.pool).await.unwrap();
+}
@@ -0,0 +1,100 @@
+use elidune_server::models::purchase_suggestion::{
+    CreatePurchaseSuggestion, PurchaseSuggestionQuery, PurchaseSuggestionStatus, TriagePurchaseSuggestion,
+};
+
+use crate::{
+    fixtures::{ItemBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+const ISBN: &str = "9782070368228";
+
+#[tokio::test]
+#[ignore]
+async fn suggestions_are_claimed_once_the_title_has_a_copy() {
+    let db = TestDb::new().await;
+    let reader = UserBuilder::new("reader1").insert(&db.pool).await;
+    let other = UserBuilder::new("reader2").insert(&db.pool).await;
+    let staff = UserBuilder::new("librarian1").account_type("librarian").insert(&db.pool).await;
+
+    let request = CreatePurchaseSuggestion {
+        isbn: Some(ISBN.to_string()),
+        title: "L'Étranger".to_string(),
+        author: Some("Albert Camus".to_string()),
+        notes: None,
+    };
+    let suggestion = db
+        .repo
+        .purchase_suggestions_create(reader, Some(ISBN.to_string()), &request)
+        .await
+        .unwrap();
+    assert_eq!(suggestion.status, PurchaseSuggestionStatus::Pending);
+    assert!(db.repo.purchase_suggestions_open_exists(reader, ISBN).await.unwrap());
+    assert!(!db.repo.purchase_suggestions_open_exists(other, ISBN).await.unwrap());
+
+    let rejected = db
+        .repo
+        .purchase_suggestions_create(other, Some(ISBN.to_string()), &request)
+        .await
+        .unwrap();
+    let rejected = db
+        .repo
+        .purchase_suggestions_triage(
+            rejected.id,
+            &TriagePurchaseSuggestion {
+                status: PurchaseSuggestionStatus::Rejected,
+                rejection_reason: Some("Out of print".to_string()),
+                purchase_order_id: None,
+                biblio_id: None,
+            },
+            staff,
+        )
+        .await
+        .unwrap();
+    assert_eq!(rejected.rejection_reason.as_deref(), Some("Out of print"));
+    assert_eq!(rejected.triaged_by, Some(staff));
+
+    let ordered = db
+        .repo
+        .purchase_suggestions_triage(
+            suggestion.id,
+            &TriagePurchaseSuggestion {
+                status: PurchaseSuggestionStatus::Ordered,
+                rejection_reason: Some("ignored".to_string()),
+                purchase_order_id: None,
+                biblio_id: None,
+            },
+            staff,
+        )
+        .await
+        .unwrap();
+    assert_eq!(ordered.status, PurchaseSuggestionStatus::Ordered);
+    assert_eq!(ordered.rejection_reason, None);
+
+    // Nothing in the catalog yet
+    assert!(db.repo.purchase_suggestions_claim_available(None).await.unwrap().is_empty());
+    assert_eq!(db.repo.purchase_suggestions_owned_biblio(ISBN).await.unwrap(), None);
+
+    // The copy is cataloged with a hyphenated ISBN
+    let copy = ItemBuilder::new("SUG-1").isbn("978-2-07-036822-8").insert(&db.pool).await;
+    assert_eq!(db.repo.purchase_suggestions_owned_biblio(ISBN).await.unwrap(), Some(copy.biblio_id));
+
+    let claimed = db.repo.purchase_suggestions_claim_available(None).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].id, suggestion.id);
+    assert_eq!(claimed[0].status, PurchaseSuggestionStatus::Added);
+    assert_eq!(claimed[0].biblio_id, Some(copy.biblio_id));
+    assert!(claimed[0].notified_at.is_some());
+
+    // Patrons are notified only once; rejected suggestions are left alone
+    assert!(db.repo.purchase_suggestions_claim_available(None).await.unwrap().is_empty());
+    let mine = db
+        .repo
+        .purchase_suggestions_list(&PurchaseSuggestionQuery { status: None, user_id: Some(reader) })
+        .await
+        .unwrap();
+    assert_eq!(mine.len(), 1);
+    let still_rejected = db.repo.purchase_suggestions_get_by_id(rejected.id).await.unwrap();
+    assert_eq!(still_rejected.status, PurchaseSuggestionStatus::Rejected);
+    assert_eq!(still_rejected.notified_at, None);
+}
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use elidune_server::{
+    api::sse::{SseEvent, SsePayload},
+    error::AppError,
+    services::{
+        edit_locks::EditLocksService,
+        redis::{RedisService, TwoFactorCodeCheck},
+    },
+};
+
+use crate::harness;
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn trusted_device_roundtrip() {
+    let redis = harness::redis().await;
+    let device = uuid::Uuid::new_v4().to_string();
+
+    assert!(!redis.is_device_trusted(42, &device).await.unwrap());
+    redis.store_trusted_device(42, &device).await.unwrap();
+    assert!(redis.is_device_trusted(42, &device).await.unwrap());
+    assert!(!redis.is_device_trusted(43, &device).await.unwrap());
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn email_2fa_challenges_limit_attempts() {
+    let redis = harness::redis().await;
+    let user_id = rand_user_id();
+    let first = uuid::Uuid::new_v4().to_string();
+    redis.store_2fa_challenge(&first, user_id, "123456", 60).await.unwrap();
+    // A new login replaces the previous challenge
+    let second = uuid::Uuid::new_v4().to_string();
+    redis.store_2fa_challenge(&second, user_id, "654321", 60).await.unwrap();
+
+    assert_eq!(check(&redis, user_id, Some(&first), "123456").await, TwoFactorCodeCheck::Expired);
+    assert_eq!(check(&redis, user_id, Some(&second), "000000").await, TwoFactorCodeCheck::Invalid);
+    assert_eq!(redis.verify_2fa_challenge(user_id + 1, Some(&second), "654321", 3).await.unwrap(), TwoFactorCodeCheck::Expired);
+    // Without an id, the latest challenge is used; the right code consumes it
+    assert_eq!(check(&redis, user_id, None, "654321").await, TwoFactorCodeCheck::Valid);
+    assert_eq!(check(&redis, user_id, Some(&second), "654321").await, TwoFactorCodeCheck::Expired);
+
+    let third = uuid::Uuid::new_v4().to_string();
+    redis.store_2fa_challenge(&third, user_id, "111111", 60).await.unwrap();
+    assert_eq!(check(&redis, user_id, Some(&third), "000000").await, TwoFactorCodeCheck::Invalid);
+    assert_eq!(check(&redis, user_id, Some(&third), "000000").await, TwoFactorCodeCheck::Invalid);
+    assert_eq!(check(&redis, user_id, Some(&third), "000000").await, TwoFactorCodeCheck::TooManyAttempts);
+    assert_eq!(check(&redis, user_id, Some(&third), "111111").await, TwoFactorCodeCheck::Expired);
+}
+
+async fn check(redis: &RedisService, user_id: i64, challenge: Option<&str>, code: &str) -> TwoFactorCodeCheck {
+    redis.verify_2fa_challenge(user_id, challenge, code, 3).await.unwrap()
+}
+
+fn rand_user_id() -> i64 {
+    (uuid::Uuid::new_v4().as_u128() % 1_000_000_000) as i64 + 1_000_000
+}
+
+#[tokio::test]
+#[ignore]
+async fn published_events_reach_every_relay() {
+    let redis = harness::redis().await;
+    // One relay per server instance; a fresh channel keeps parallel runs apart
+    let channel: &'static str = Box::leak(format!("test:events:{}", uuid::Uuid::new_v4()).into_boxed_str());
+    let (first, mut first_rx) = tokio::sync::broadcast::channel::<SseEvent>(8);
+    let (second, mut second_rx) = tokio::sync::broadcast::channel::<SseEvent>(8);
+    let relays = [redis.relay(channel, first), redis.relay(channel, second)];
+
+    // Subscriptions complete in the background: publish until both relays got the event
+    let event = SsePayload::loan("loan.created", 7, Some(42), None);
+    let mut received = [None, None];
+    for _ in 0..50 {
+        redis.publish(channel, &event).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        for (slot, rx) in received.iter_mut().zip([&mut first_rx, &mut second_rx]) {
+            if let Ok(message) = rx.try_recv() {
+                slot.get_or_insert(message);
+            }
+        }
+        if received.iter().all(Option::is_some) {
+            break;
+        }
+    }
+
+    for message in received {
+        let message = message.expect("event relayed to every instance");
+        assert_eq!(message.event_type, "loan.created");
+        assert_eq!(message.payload.loan_id.as_deref(), Some("7"));
+        assert_eq!(message.payload.user_id.as_deref(), Some("42"));
+    }
+    relays.iter().for_each(|relay| relay.abort());
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn edit_locks_belong_to_one_cataloger() {
+    let locks = EditLocksService::new(harness::redis().await);
+    let biblio_id = rand_user_id();
+    let (alice, bob) = (rand_user_id(), rand_user_id());
+
+    assert!(locks.get(biblio_id).await.unwrap().is_none());
+    let lock = locks.acquire(biblio_id, alice, "alice").await.unwrap();
+    assert_eq!((lock.user_id, lock.login.as_str()), (alice, "alice"));
+    // Heartbeat keeps the acquisition time
+    let renewed = locks.acquire(biblio_id, alice, "alice").await.unwrap();
+    assert_eq!(renewed.acquired_at, lock.acquired_at);
+
+    assert!(matches!(locks.acquire(biblio_id, bob, "bob").await, Err(AppError::Conflict(_))));
+    assert!(matches!(locks.release(biblio_id, bob, false).await, Err(AppError::Conflict(_))));
+    let forced = locks.release(biblio_id, bob, true).await.unwrap();
+    assert_eq!(forced.map(|l| l.user_id), Some(alice));
+
+    assert_eq!(locks.acquire(biblio_id, bob, "bob").await.unwrap().user_id, bob);
+    assert!(locks.release(biblio_id, bob, false).await.unwrap().is_some());
+    assert!(locks.release(biblio_id, bob, false).await.unwrap().is_none());
+}
@@ -0,0 +1,98 @@
+use chrono::{Duration, Utc};
+
+use crate::{
+    fixtures::{ItemBuilder, LoanBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn inactive_members_are_emailed_once_and_reactivations_counted() {
+    let db = TestDb::new().await;
+    let lapsed = UserBuilder::new("lapsed").insert(&db.pool).await;
+    let borrowing = UserBuilder::new("borrowing").insert(&db.pool).await;
+    let no_consent = UserBuilder::new("noconsent").insert(&db.pool).await;
+    for (id, email, opt_in) in [
+        (lapsed, "lapsed@example.org", true),
+        (borrowing, "borrowing@example.org", true),
+        (no_consent, "quiet@example.org", false),
+    ] {
+        sqlx::query("UPDATE users SET email = $1, campaigns_opt_in = $2 WHERE id = $3")
+            .bind(email)
+            .bind(opt_in)
+            .bind(id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+    }
+
+    // Everyone borrowed a novel and returned it 200 days ago; `borrowing` also has a loan out
+    for (i, user_id) in [lapsed, borrowing, no_consent].into_iter().enumerate() {
+        let item = ItemBuilder::new(&format!("RE-OLD-{}", i)).insert(&db.pool).await;
+        let loan_id = LoanBuilder::new(user_id, item).insert(&db.repo).await;
+        db.repo.loans_return(loan_id).await.unwrap();
+    }
+    for table in ["loans", "loans_archives"] {
+        sqlx::query(&format!(
+            "UPDATE {} SET date = NOW() - INTERVAL '200 days', returned_at = NOW() - INTERVAL '190 days'",
+            table
+        ))
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    }
+    let out = ItemBuilder::new("RE-OUT").insert(&db.pool).await;
+    LoanBuilder::new(borrowing, out).insert(&db.repo).await;
+
+    let now = Utc::now();
+    let inactive_before = now - Duration::days(180);
+    let contacted_after = now - Duration::days(180);
+    assert_eq!(db.repo.reengagement_count_candidates(inactive_before, contacted_after).await.unwrap(), 1);
+    let candidates = db.repo.reengagement_candidates(inactive_before, contacted_after, 10).await.unwrap();
+    assert_eq!(candidates.len(), 1);
+    let candidate = &candidates[0];
+    assert_eq!((candidate.user_id, candidate.email.as_str()), (lapsed, "lapsed@example.org"));
+
+    // New novel suggested; the new film and the novel already borrowed are not
+    let novel = ItemBuilder::new("RE-NEW-1").title("New novel").insert(&db.pool).await;
+    ItemBuilder::new("RE-NEW-2").media_type("video").insert(&db.pool).await;
+    let media_types = db.repo.reengagement_preferred_media_types(lapsed, 3).await.unwrap();
+    assert_eq!(media_types, vec!["printedText".to_string()]);
+    let titles = db
+        .repo
+        .reengagement_new_titles(lapsed, &media_types, now - Duration::days(90), 5)
+        .await
+        .unwrap();
+    let biblio_ids: Vec<i64> = titles.iter().map(|t| t.biblio_id).collect();
+    assert!(biblio_ids.contains(&novel.biblio_id));
+    assert!(titles.iter().all(|t| t.title.as_deref() != Some("Title RE-OLD-0")));
+    assert!(titles.iter().all(|t| t.media_type.to_string() == "printedText"));
+
+    let id = db
+        .repo
+        .reengagement_claim(candidate, &media_types, &biblio_ids, contacted_after)
+        .await
+        .unwrap()
+        .expect("claimed");
+    // Claimed: not selected nor claimed again
+    assert!(db
+        .repo
+        .reengagement_claim(candidate, &media_types, &biblio_ids, contacted_after)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(db.repo.reengagement_count_candidates(inactive_before, contacted_after).await.unwrap(), 0);
+    db.repo.reengagement_finish(id, None).await.unwrap();
+
+    let from = now - Duration::days(1);
+    let to = now + Duration::days(1);
+    let (months, failed) = db.repo.reengagement_stats(from, to, 60).await.unwrap();
+    assert_eq!(failed, 0);
+    assert_eq!(months.len(), 1);
+    assert_eq!((months[0].sent, months[0].reactivated, months[0].suggested_loans), (1, 0, 0));
+
+    // The member comes back for the suggested novel
+    LoanBuilder::new(lapsed, novel).insert(&db.repo).await;
+    let (months, _) = db.repo.reengagement_stats(from, to, 60).await.unwrap();
+    assert_eq!((months[0].sent, months[0].reactivated, months[0].suggested_loans), (1, 1, 1));
+}
@@ -0,0 +1,130 @@
+use elidune_server::{
+    error::AppError,
+    models::{
+        biblio::BiblioQuery,
+        relator::{CreateRelator, UpdateRelator},
+    },
+};
+use serde_json::json;
+
+use crate::{fixtures::ItemBuilder, harness::TestDb};
+
+fn query(value: serde_json::Value) -> BiblioQuery {
+    serde_json::from_value(value).expect("biblio query")
+}
+
+async fn resolve(db: &TestDb, term: &str) -> Option<String> {
+    sqlx::query_scalar("SELECT resolve_relator($1)")
+        .bind(term)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap()
+}
+
+async fn credit(db: &TestDb, biblio_id: i64, lastname: &str, function: &str) {
+    let author_id: i64 = sqlx::query_scalar("INSERT INTO authors (lastname) VALUES ($1) RETURNING id")
+        .bind(lastname)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO biblio_authors (biblio_id, author_id, function) VALUES ($1, $2, $3)")
+        .bind(biblio_id)
+        .bind(author_id)
+        .bind(function)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn relator_terms_resolve_to_codes() {
+    let db = TestDb::new().await;
+    assert_eq!(resolve(&db, "ill").await.as_deref(), Some("illustrator"));
+    assert_eq!(resolve(&db, "Illustrateur.").await.as_deref(), Some("illustrator"));
+    assert_eq!(resolve(&db, "Réalisateur").await.as_deref(), Some("director"));
+    assert_eq!(resolve(&db, "300").await.as_deref(), Some("director"));
+    assert_eq!(resolve(&db, "publisher").await.as_deref(), Some("publishingDirector"));
+    assert_eq!(resolve(&db, "scientificadvisor").await.as_deref(), Some("scientificAdvisor"));
+    assert_eq!(resolve(&db, "juggler").await, None);
+
+    let roles = vec!["drt".to_string(), "traducteur".to_string(), "juggler".to_string()];
+    assert_eq!(db.repo.authors_resolve_roles(&roles).await.unwrap(), vec!["director", "translator"]);
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn relators_are_seeded_and_editable() {
+    let db = TestDb::new().await;
+    let seeded = db.repo.relators_list().await.unwrap();
+    let director = seeded.iter().find(|r| r.code == "director").expect("seeded relator");
+    assert_eq!((director.marc_code.as_str(), director.unimarc_code.as_deref()), ("drt", Some("300")));
+
+    let create = CreateRelator {
+        code: "soundEngineer".to_string(),
+        marc_code: "rce".to_string(),
+        unimarc_code: None,
+        label: "Sound engineer".to_string(),
+        aliases: vec![" ingénieur du son ".to_string(), String::new(), "Ingénieur du son".to_string()],
+        sort_order: None,
+    };
+    let created = db.repo.relators_create(&create).await.unwrap();
+    assert_eq!(created.aliases, vec!["ingénieur du son"]);
+    assert_eq!(resolve(&db, "Ingenieur du son").await.as_deref(), Some("soundEngineer"));
+
+    let duplicate_marc = CreateRelator { code: "recorder".to_string(), ..create };
+    assert!(matches!(db.repo.relators_create(&duplicate_marc).await, Err(AppError::Conflict(_))));
+
+    let updated = db
+        .repo
+        .relators_update(
+            "soundEngineer",
+            &UpdateRelator {
+                marc_code: None,
+                unimarc_code: Some("715".to_string()),
+                label: Some("Recording engineer".to_string()),
+                aliases: None,
+                sort_order: Some(30),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!((updated.unimarc_code.as_deref(), updated.label.as_str()), (Some("715"), "Recording engineer"));
+    assert_eq!(updated.aliases, vec!["ingénieur du son"]);
+
+    let album = ItemBuilder::new("RL-0").insert(&db.pool).await;
+    credit(&db, album.biblio_id, "Emerick", "soundEngineer").await;
+    assert_eq!(db.repo.relators_count_credits("soundEngineer").await.unwrap(), 1);
+
+    db.repo.relators_delete("director").await.unwrap();
+    assert!(matches!(db.repo.relators_get("director").await, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn search_filters_by_author_role() {
+    let db = TestDb::new().await;
+    let film = ItemBuilder::new("RL-1").insert(&db.pool).await;
+    let picture_book = ItemBuilder::new("RL-2").insert(&db.pool).await;
+    credit(&db, film.biblio_id, "Miyazaki", "director").await;
+    credit(&db, picture_book.biblio_id, "Miyazaki", "illustrator").await;
+    credit(&db, picture_book.biblio_id, "Sendak", "author").await;
+
+    let (found, _) = db.repo.biblios_search(&query(json!({ "authorRole": "drt" }))).await.unwrap();
+    assert_eq!(found.iter().map(|b| b.id).collect::<Vec<_>>(), vec![film.biblio_id]);
+
+    let (found, _) = db
+        .repo
+        .biblios_search(&query(json!({ "author": "miyazaki", "authorRole": "illustrateur,translator" })))
+        .await
+        .unwrap();
+    assert_eq!(found.iter().map(|b| b.id).collect::<Vec<_>>(), vec![picture_book.biblio_id]);
+
+    // Name and role must match the same contributor
+    let (found, _) = db
+        .repo
+        .biblios_search(&query(json!({ "author": "sendak", "authorRole": "illustrator" })))
+        .await
+        .unwrap();
+    assert!(found.is_empty());
+}
@@ -0,0 +1,56 @@
+use elidune_server::error::AppError;
+
+use crate::{fixtures::ItemBuilder, harness::TestDb};
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn sitemap_lists_records_shown_by_the_opac() {
+    let db = TestDb::new().await;
+    let shown = ItemBuilder::new("SM-1").insert(&db.pool).await;
+    let on_order = ItemBuilder::new("SM-2").insert(&db.pool).await;
+    let withdrawn = ItemBuilder::new("SM-3").insert(&db.pool).await;
+    let later = ItemBuilder::new("SM-4").insert(&db.pool).await;
+    sqlx::query("UPDATE items SET circulation_status = 5 WHERE id = $1")
+        .bind(on_order.item_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE items SET archived_at = NOW() WHERE id = $1")
+        .bind(withdrawn.item_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let entries = db.repo.sitemap_entries(0, 10).await.unwrap();
+    let ids: Vec<i64> = entries.iter().map(|e| e.biblio_id).collect();
+    assert_eq!(ids, vec![shown.biblio_id, later.biblio_id]);
+    assert!(entries.iter().all(|e| e.lastmod.is_some()));
+
+    let page = db.repo.sitemap_entries(shown.biblio_id, 1).await.unwrap();
+    assert_eq!(page.iter().map(|e| e.biblio_id).collect::<Vec<_>>(), vec![later.biblio_id]);
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn sitemap_generations_are_published_at_once() {
+    let db = TestDb::new().await;
+    assert!(matches!(db.repo.sitemap_get_file("sitemap.xml").await, Err(AppError::NotFound(_))));
+
+    let first = db.repo.sitemap_begin().await.unwrap();
+    db.repo.sitemap_store_file(first, "sitemap.xml", "<urlset/>", 0).await.unwrap();
+    assert!(matches!(db.repo.sitemap_get_file("sitemap.xml").await, Err(AppError::NotFound(_))));
+    db.repo.sitemap_publish(first).await.unwrap();
+    assert_eq!(db.repo.sitemap_get_file("sitemap.xml").await.unwrap().content, "<urlset/>");
+
+    // A slower run started earlier does not replace a newer sitemap
+    let stale = db.repo.sitemap_begin().await.unwrap();
+    let second = db.repo.sitemap_begin().await.unwrap();
+    db.repo.sitemap_store_file(second, "sitemap.xml", "<sitemapindex/>", 2).await.unwrap();
+    db.repo.sitemap_store_file(second, "sitemap-1.xml", "<urlset>1</urlset>", 1).await.unwrap();
+    db.repo.sitemap_publish(second).await.unwrap();
+    db.repo.sitemap_store_file(stale, "sitemap.xml", "<urlset>stale</urlset>", 1).await.unwrap();
+    db.repo.sitemap_publish(stale).await.unwrap();
+
+    assert_eq!(db.repo.sitemap_get_file("sitemap.xml").await.unwrap().content, "<sitemapindex/>");
+    assert_eq!(db.repo.sitemap_get_file("sitemap-1.xml").await.unwrap().content, "<urlset>1</urlset>");
+}
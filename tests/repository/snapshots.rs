@@ -0,0 +1,96 @@
+use chrono::{Duration, Utc};
+use elidune_server::{error::AppError, models::snapshot::SnapshotScope};
+
+use crate::{
+    fixtures::{ItemBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+async fn call_number_and_archived(pool: &sqlx::PgPool, item_id: i64) -> (Option<String>, bool) {
+    sqlx::query_as("SELECT call_number, archived_at IS NOT NULL FROM items WHERE id = $1")
+        .bind(item_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn restore_puts_captured_copies_back_once() {
+    let db = TestDb::new().await;
+    let admin = UserBuilder::new("snapshot-admin").account_type("admin").insert(&db.pool).await;
+    let kept = ItemBuilder::new("SN-0001").insert(&db.pool).await;
+    let deleted = ItemBuilder::new("SN-0002").insert(&db.pool).await;
+    let untouched = ItemBuilder::new("SN-0003").insert(&db.pool).await;
+    sqlx::query("UPDATE items SET call_number = 'R DUM' WHERE id = ANY($1)")
+        .bind(vec![kept.item_id, deleted.item_id, untouched.item_id])
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let scope = SnapshotScope::Items(vec![kept.item_id, deleted.item_id]);
+    let snapshot = db
+        .repo
+        .snapshots_capture("call_numbers.recalculate", &scope, Some(admin))
+        .await
+        .unwrap()
+        .expect("snapshot");
+    assert_eq!(snapshot.item_count, 2);
+    assert!(db.repo.snapshots_capture("x", &SnapshotScope::Items(vec![-1]), None).await.unwrap().is_none());
+
+    sqlx::query("UPDATE items SET call_number = 'RP DUM', archived_at = NOW() WHERE id = ANY($1)")
+        .bind(vec![kept.item_id, untouched.item_id])
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM items WHERE id = $1").bind(deleted.item_id).execute(&db.pool).await.unwrap();
+
+    let report = db.repo.snapshots_restore(snapshot.id, admin).await.unwrap();
+    assert_eq!((report.restored, report.missing), (1, 1));
+    assert_eq!(call_number_and_archived(&db.pool, kept.item_id).await, (Some("R DUM".to_string()), false));
+    assert_eq!(call_number_and_archived(&db.pool, untouched.item_id).await, (Some("RP DUM".to_string()), true));
+
+    let snapshot = db.repo.snapshots_get(snapshot.id).await.unwrap();
+    assert_eq!(snapshot.restored_by, Some(admin));
+    assert!(matches!(db.repo.snapshots_restore(snapshot.id, admin).await, Err(AppError::Conflict(_))));
+}
+
+#[tokio::test]
+#[ignore]
+async fn prune_keeps_recent_snapshots_within_count() {
+    let db = TestDb::new().await;
+    let source_id: i64 = sqlx::query_scalar("INSERT INTO sources (name) VALUES ('Snapshot source') RETURNING id")
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    let item = ItemBuilder::new("SN-0010").insert(&db.pool).await;
+    sqlx::query("UPDATE items SET source_id = $1 WHERE id = $2")
+        .bind(source_id)
+        .bind(item.item_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        let snapshot = db
+            .repo
+            .snapshots_capture("sources.merge", &SnapshotScope::Sources(vec![source_id]), None)
+            .await
+            .unwrap()
+            .expect("snapshot");
+        assert_eq!(snapshot.item_count, 1);
+        ids.push(snapshot.id);
+    }
+    sqlx::query("UPDATE catalog_snapshots SET created_at = NOW() - INTERVAL '40 days' WHERE id = $1")
+        .bind(ids[2])
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    // The back-dated snapshot goes with the retention, the older of the two others with the count
+    let deleted = db.repo.snapshots_prune(Utc::now() - Duration::days(30), 1).await.unwrap();
+    assert_eq!(deleted, 2);
+    let left: Vec<i64> = db.repo.snapshots_list().await.unwrap().iter().map(|s| s.id).collect();
+    assert_eq!(left, vec![ids[1]]);
+}
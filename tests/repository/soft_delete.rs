@@ -0,0 +1,139 @@
+use chrono::{Duration, Utc};
+use elidune_server::{error::AppError, models::user::UserStatus};
+
+use crate::{
+    fixtures::{add_copy, ItemBuilder, LoanBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn deleted_item_is_archived_and_frees_its_barcode() {
+    let db = TestDb::new().await;
+    let item = ItemBuilder::new("D-0001").insert(&db.pool).await;
+
+    db.repo.items_delete(item.item_id, false).await.unwrap();
+
+    assert!(matches!(
+        db.repo.items_get_active_by_id(item.item_id).await,
+        Err(AppError::NotFound(_))
+    ));
+    let (barcode, archived): (String, bool) =
+        sqlx::query_as("SELECT barcode, archived_at IS NOT NULL FROM items WHERE id = $1")
+            .bind(item.item_id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+    assert!(archived);
+    assert!(barcode.starts_with("ARCH_") && barcode.ends_with("_D-0001"), "{}", barcode);
+
+    let reused = add_copy(&db.pool, item.biblio_id, "D-0001", true, None).await;
+    assert_eq!(db.repo.items_get_active_by_barcode("D-0001").await.unwrap().id, Some(reused));
+}
+
+#[tokio::test]
+#[ignore]
+async fn borrowed_item_delete_needs_force() {
+    let db = TestDb::new().await;
+    let user_id = UserBuilder::new("reader1").insert(&db.pool).await;
+    let item = ItemBuilder::new("D-0010").insert(&db.pool).await;
+    let loan_id = LoanBuilder::new(user_id, item).insert(&db.repo).await;
+
+    assert!(matches!(
+        db.repo.items_delete(item.item_id, false).await,
+        Err(AppError::Conflict(_))
+    ));
+
+    db.repo.items_delete(item.item_id, true).await.unwrap();
+    assert!(matches!(
+        db.repo.loans_get_by_id(loan_id).await,
+        Err(AppError::NotFound(_))
+    ));
+    assert_eq!(db.repo.loans_count_active_for_item(item.item_id).await.unwrap(), 0);
+}
+
+#[tokio::test]
+#[ignore]
+async fn deleted_biblio_archives_its_copies() {
+    let db = TestDb::new().await;
+    let user_id = UserBuilder::new("reader1").insert(&db.pool).await;
+    let item = ItemBuilder::new("D-0020").insert(&db.pool).await;
+    let other = add_copy(&db.pool, item.biblio_id, "D-0021", true, None).await;
+    LoanBuilder::new(user_id, item).insert(&db.repo).await;
+
+    assert!(matches!(
+        db.repo.biblios_delete(item.biblio_id, false).await,
+        Err(AppError::BusinessRule(_))
+    ));
+
+    db.repo.biblios_delete(item.biblio_id, true).await.unwrap();
+    for id in [item.item_id, other] {
+        assert!(matches!(
+            db.repo.items_get_active_by_id(id).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+    let biblio_archived: bool = sqlx::query_scalar("SELECT archived_at IS NOT NULL FROM biblios WHERE id = $1")
+        .bind(item.biblio_id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert!(biblio_archived);
+}
+
+#[tokio::test]
+#[ignore]
+async fn deleted_user_is_restorable_until_anonymized() {
+    let db = TestDb::new().await;
+    let user_id = UserBuilder::new("reader2").insert(&db.pool).await;
+    sqlx::query("UPDATE users SET status = 'blocked' WHERE id = $1")
+        .bind(user_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let later = Utc::now() + Duration::days(30);
+    let pending = db.repo.users_schedule_deletion(user_id, false, later).await.unwrap();
+    assert_eq!(pending.status, Some(UserStatus::PendingDeletion));
+    assert_eq!(pending.login.as_deref(), Some("reader2"));
+    // Not due yet
+    assert!(db.repo.users_purge_pending_deletions().await.unwrap().is_empty());
+
+    let restored = db.repo.users_restore(user_id).await.unwrap();
+    assert_eq!(restored.status, Some(UserStatus::Blocked));
+    assert_eq!(restored.deletion_scheduled_at, None);
+    assert!(matches!(db.repo.users_restore(user_id).await, Err(AppError::BusinessRule(_))));
+
+    let past = Utc::now() - Duration::minutes(1);
+    db.repo.users_schedule_deletion(user_id, false, past).await.unwrap();
+    assert_eq!(db.repo.users_purge_pending_deletions().await.unwrap(), vec![user_id]);
+    let anonymized = db.repo.users_get_by_id(user_id).await.unwrap();
+    assert_eq!(anonymized.status, Some(UserStatus::Deleted));
+    assert!(anonymized.login.is_none() && anonymized.lastname.is_none());
+    assert!(anonymized.archived_at.is_some());
+    assert!(matches!(db.repo.users_restore(user_id).await, Err(AppError::BusinessRule(_))));
+}
+
+#[tokio::test]
+#[ignore]
+async fn user_deletion_with_loans_needs_force() {
+    let db = TestDb::new().await;
+    let user_id = UserBuilder::new("reader3").insert(&db.pool).await;
+    let item = ItemBuilder::new("D-0030").insert(&db.pool).await;
+    LoanBuilder::new(user_id, item).insert(&db.repo).await;
+
+    let later = Utc::now() + Duration::days(30);
+    assert!(matches!(
+        db.repo.users_schedule_deletion(user_id, false, later).await,
+        Err(AppError::BusinessRule(_))
+    ));
+    db.repo.users_schedule_deletion(user_id, true, later).await.unwrap();
+    assert_eq!(db.repo.loans_count_active_for_item(item.item_id).await.unwrap(), 0);
+    // Archived loans keep pointing at the (not yet anonymized) borrower
+    let archived: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM loans_archives WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(archived, 1);
+}
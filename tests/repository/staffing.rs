@@ -0,0 +1,75 @@
+use chrono::{NaiveDate, NaiveTime};
+use elidune_server::{
+    error::AppError,
+    models::staffing::{CreateShiftAssignment, CreateStaffAbsence, ShiftTemplateFields},
+};
+
+use crate::{fixtures::UserBuilder, harness::TestDb};
+
+fn day(d: &str) -> NaiveDate {
+    d.parse().unwrap()
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn shifts_follow_slots_closures_and_absences() {
+    let db = TestDb::new().await;
+    let period_id: i64 = sqlx::query_scalar(
+        "INSERT INTO schedule_periods (name, start_date, end_date) VALUES ('May', '2026-05-01', '2026-05-31') RETURNING id",
+    )
+    .fetch_one(&db.pool)
+    .await
+    .unwrap();
+    // Saturdays 10:00–12:00
+    let slot_id: i64 = sqlx::query_scalar(
+        "INSERT INTO schedule_slots (period_id, day_of_week, open_time, close_time) VALUES ($1, 5, '10:00', '12:00') RETURNING id",
+    )
+    .bind(period_id)
+    .fetch_one(&db.pool)
+    .await
+    .unwrap();
+    sqlx::query("INSERT INTO schedule_closures (closure_date, reason) VALUES ('2026-05-09', 'Bridge day')")
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let fields = ShiftTemplateFields {
+        slot_id,
+        name: "Front desk".to_string(),
+        start_time: None,
+        end_time: None,
+        staff_needed: Some(2),
+        notes: None,
+    };
+    let template = db.repo.staffing_create_template(&fields, None, None).await.unwrap();
+    assert_eq!((template.period_id, template.day_of_week), (period_id, 5));
+    assert_eq!(template.start_time, NaiveTime::from_hms_opt(10, 0, 0).unwrap());
+    assert_eq!(template.end_time, NaiveTime::from_hms_opt(12, 0, 0).unwrap());
+
+    // The closed Saturday has no shift
+    let occurrences = db.repo.staffing_occurrences(day("2026-05-01"), day("2026-05-16")).await.unwrap();
+    let dates: Vec<_> = occurrences.iter().map(|o| o.shift_date).collect();
+    assert_eq!(dates, vec![day("2026-05-02"), day("2026-05-16")]);
+
+    let volunteer = UserBuilder::new("volunteer").insert(&db.pool).await;
+    let assign = CreateShiftAssignment { template_id: template.id, shift_date: day("2026-05-16"), user_id: volunteer, notes: None };
+    let assignment = db.repo.staffing_create_assignment(&assign, volunteer).await.unwrap();
+    assert!(!assignment.absent);
+    assert!(matches!(db.repo.staffing_create_assignment(&assign, volunteer).await, Err(AppError::Conflict(_))));
+
+    let absence = CreateStaffAbsence {
+        user_id: None,
+        start_date: day("2026-05-15"),
+        end_date: day("2026-05-17"),
+        reason: Some("Holiday".to_string()),
+    };
+    db.repo.staffing_create_absence(volunteer, &absence, volunteer).await.unwrap();
+    let shifts = db.repo.staffing_assignments(day("2026-05-01"), day("2026-05-31"), Some(volunteer)).await.unwrap();
+    assert_eq!(shifts.len(), 1);
+    assert!(shifts[0].absent);
+
+    db.repo.staffing_set_calendar_token(volunteer, "hash-1").await.unwrap();
+    db.repo.staffing_set_calendar_token(volunteer, "hash-2").await.unwrap();
+    assert!(db.repo.staffing_calendar_owner("hash-1").await.unwrap().is_none());
+    assert_eq!(db.repo.staffing_calendar_owner("hash-2").await.unwrap().map(|o| o.0), Some(volunteer));
+}
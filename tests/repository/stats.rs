@@ -0,0 +1,213 @@
+use chrono::{Duration, Utc};
+use elidune_server::{
+    api::stats::{Interval, StatEntry},
+    models::biblio::MediaType,
+    repository::stats::StatsFilter,
+    services::stats::StatsService,
+};
+
+use crate::{
+    fixtures::{ItemBuilder, LoanBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+fn value(entries: &[StatEntry], label: &str) -> i64 {
+    entries.iter().filter(|e| e.label == label).map(|e| e.value).sum()
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn stats_normalize_legacy_codes_and_audience_spelling() {
+    let db = TestDb::new().await;
+    let legacy = ItemBuilder::new("ST-1").media_type("b").insert(&db.pool).await;
+    let current = ItemBuilder::new("ST-2").insert(&db.pool).await;
+    ItemBuilder::new("ST-3").media_type("").insert(&db.pool).await;
+    sqlx::query("UPDATE biblios SET audience_type = 'Adult' WHERE id = $1")
+        .bind(legacy.biblio_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE biblios SET audience_type = 'adult' WHERE id = $1")
+        .bind(current.biblio_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let stats = db.repo.stats_get_stats(None).await.unwrap();
+    assert_eq!(value(&stats.items.by_media_type, "printedText"), 2);
+    assert_eq!(value(&stats.items.by_media_type, "unknown"), 1);
+    assert_eq!(value(&stats.items.by_public_type, "adult"), 2);
+    assert!(stats.items.by_media_type.iter().all(|e| e.label != "b"));
+
+    // Filters accept either spelling and match both records
+    let filter = StatsFilter {
+        reference_date: Some(Utc::now().date_naive().succ_opt().unwrap()),
+        public_type: Some("ADULT".to_string()),
+        media_type: Some("b".to_string()),
+    };
+    let filtered = db.repo.stats_get_stats(Some(filter)).await.unwrap();
+    assert_eq!(filtered.items.total, 2);
+
+    let user_id = UserBuilder::new("reader").insert(&db.pool).await;
+    LoanBuilder::new(user_id, legacy).insert(&db.repo).await;
+    let loans = db
+        .repo
+        .stats_get_loan_stats(None, Some(Utc::now() + Duration::minutes(1)), Interval::Day, Some(&MediaType::PrintedText), Some("adult"), None)
+        .await
+        .unwrap();
+    assert_eq!(loans.total_loans, 1);
+    assert_eq!(value(&loans.by_media_type, "printedText"), 1);
+
+    let catalog = db.repo.stats_get_catalog_stats(None, None, true, true, true, false).await.unwrap();
+    let source = &catalog.by_source.unwrap()[0];
+    let media = source.by_media_type.as_ref().unwrap();
+    let printed = media.iter().find(|m| m.label == "printedText").unwrap();
+    assert_eq!((printed.active_items, printed.loans), (2, 1));
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn stats_break_down_by_shelving_location() {
+    let db = TestDb::new().await;
+    let adult = ItemBuilder::new("LOC-1").insert(&db.pool).await;
+    let comics = ItemBuilder::new("LOC-2").media_type("bc").insert(&db.pool).await;
+    ItemBuilder::new("LOC-3").insert(&db.pool).await;
+    sqlx::query("UPDATE items SET place = 2 WHERE id = ANY($1)")
+        .bind(vec![adult.item_id, comics.item_id])
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let user_id = UserBuilder::new("reader").insert(&db.pool).await;
+    LoanBuilder::new(user_id, adult).insert(&db.repo).await;
+    let returned = LoanBuilder::new(user_id, comics).insert(&db.repo).await;
+    db.repo.loans_return(returned).await.unwrap();
+
+    let end = Some(Utc::now() + Duration::minutes(1));
+    let loans = db.repo.stats_get_loan_location_stats(None, end, None, None, None).await.unwrap();
+    assert_eq!(loans.len(), 1);
+    assert_eq!((loans[0].place, loans[0].loans, loans[0].returns), (Some(2), 2, 1));
+    assert_eq!(value(&loans[0].by_media_type, "comics"), 1);
+
+    let own = db.repo.stats_get_loan_location_stats(None, end, None, None, Some(user_id + 1)).await.unwrap();
+    assert!(own.is_empty());
+
+    let catalog = db.repo.stats_get_catalog_location_stats(None, end, true).await.unwrap();
+    let shelf = catalog.iter().find(|l| l.place == Some(2)).unwrap();
+    assert_eq!((shelf.active_items, shelf.loans), (2, 2));
+    let medias = shelf.by_media_type.as_ref().unwrap();
+    assert!(medias.iter().any(|m| m.label == "comics" && m.loans == 1));
+    let unshelved = catalog.iter().find(|l| l.place.is_none()).unwrap();
+    assert_eq!((unshelved.active_items, unshelved.loans), (1, 0));
+
+    let flat = db.repo.stats_get_catalog_location_stats(None, end, false).await.unwrap();
+    assert!(flat.iter().all(|l| l.by_media_type.is_none()));
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn stats_compare_with_previous_year() {
+    let db = TestDb::new().await;
+    let old = ItemBuilder::new("YOY-1").insert(&db.pool).await;
+    let recent = ItemBuilder::new("YOY-2").insert(&db.pool).await;
+    // Holdings are counted as of the start of the reference day
+    for (item_id, age) in [(old.item_id, 400), (recent.item_id, 2)] {
+        sqlx::query("UPDATE items SET created_at = NOW() - make_interval(days => $2) WHERE id = $1")
+            .bind(item_id)
+            .bind(age)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+    }
+
+    let stats = StatsService::new(db.repo.clone()).get_stats_compared(None).await.unwrap();
+    let previous = stats.previous.as_ref().expect("previous period");
+    assert_eq!((stats.items.total, previous.items.total), (2, 1));
+    let deltas = stats.deltas.as_ref().unwrap();
+    let total = deltas.iter().find(|d| d.metric == "items.total").unwrap();
+    assert_eq!((total.current, total.previous, total.delta, total.percent), (2, 1, 1, Some(100.0)));
+    assert!(deltas.iter().all(|d| d.metric.starts_with("items.")));
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn stats_keep_loan_age_bands_after_anonymization() {
+    let db = TestDb::new().await;
+    let child = UserBuilder::new("young-reader").insert(&db.pool).await;
+    let adult = UserBuilder::new("adult-reader").insert(&db.pool).await;
+    UserBuilder::new("no-birthdate").insert(&db.pool).await;
+    for (user_id, age) in [(child, 10), (adult, 30)] {
+        sqlx::query("UPDATE users SET birthdate = CURRENT_DATE - make_interval(years => $2) WHERE id = $1")
+            .bind(user_id)
+            .bind(age)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+    }
+    let item = ItemBuilder::new("AGE-1").insert(&db.pool).await;
+    let other = ItemBuilder::new("AGE-2").insert(&db.pool).await;
+    let returned = LoanBuilder::new(adult, item).insert(&db.repo).await;
+    db.repo.loans_return(returned).await.unwrap();
+    LoanBuilder::new(child, other).insert(&db.repo).await;
+
+    // The adult asks to be forgotten: the birthdate goes, the band captured on the loan stays
+    db.repo.users_schedule_deletion(adult, false, Utc::now() - Duration::minutes(1)).await.unwrap();
+    assert_eq!(db.repo.users_purge_pending_deletions().await.unwrap(), vec![adult]);
+    let birthdate: Option<chrono::NaiveDate> = sqlx::query_scalar("SELECT birthdate FROM users WHERE id = $1")
+        .bind(adult)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert!(birthdate.is_none());
+
+    let end = Some(Utc::now() + Duration::minutes(1));
+    let loans = db.repo.stats_get_loan_stats(None, end, Interval::Month, None, None, None).await.unwrap();
+    assert_eq!(value(&loans.by_age_band, "0-14"), 1);
+    assert_eq!(value(&loans.by_age_band, "25-44"), 1);
+    assert_eq!(loans.by_age_band.iter().map(|e| e.value).sum::<i64>(), loans.total_loans);
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn stats_catalog_quality_per_source() {
+    let db = TestDb::new().await;
+    let migrated: i64 = sqlx::query_scalar("INSERT INTO sources (name) VALUES ('Migration') RETURNING id")
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    let rich = ItemBuilder::new("CQ-1").isbn("978-2-07-036822-8").insert(&db.pool).await;
+    let skeletal = ItemBuilder::new("CQ-2").isbn("n.c.").insert(&db.pool).await;
+    ItemBuilder::new("CQ-3").insert(&db.pool).await;
+    sqlx::query("UPDATE items SET source_id = $1 WHERE id = ANY($2)")
+        .bind(migrated)
+        .bind(vec![rich.item_id, skeletal.item_id])
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE items SET call_number = 'R CAM' WHERE id = $1")
+        .bind(rich.item_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE biblios SET subject = 'Absurde', marc_record = '{}'::jsonb WHERE id = $1")
+        .bind(rich.biblio_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let (by_source, totals) = db.repo.stats_get_catalog_quality().await.unwrap();
+    let source = by_source.iter().find(|s| s.source_id == Some(migrated)).unwrap();
+    assert_eq!(source.source_name.as_deref(), Some("Migration"));
+    assert_eq!(
+        (source.records, source.with_isbn, source.with_cover, source.with_call_number),
+        (2, 2, 1, 1)
+    );
+    assert_eq!((source.with_subjects, source.with_marc_record, source.with_authors), (1, 1, 0));
+    let unsourced = by_source.iter().find(|s| s.source_id.is_none()).unwrap();
+    assert_eq!((unsourced.records, unsourced.with_isbn), (1, 0));
+    assert_eq!((totals.records, totals.with_isbn, totals.with_cover), (3, 2, 1));
+
+    let report = StatsService::new(db.repo.clone()).get_catalog_quality().await.unwrap();
+    assert_eq!(report.totals.isbn.percent, 66.7);
+    assert_eq!(report.by_source.first().map(|s| s.source_id), Some(None));
+}
@@ -0,0 +1,56 @@
+use chrono::{Duration, Utc};
+use elidune_server::models::user_message::{CreateUserMessage, UserMessageSeverity};
+
+use crate::{fixtures::UserBuilder, harness::TestDb};
+
+#[tokio::test]
+#[ignore]
+async fn pending_messages_exclude_acknowledged_and_expired() {
+    let db = TestDb::new().await;
+    let reader = UserBuilder::new("reader1").insert(&db.pool).await;
+    let librarian = UserBuilder::new("librarian1").account_type("librarian").insert(&db.pool).await;
+    let message = |body: &str, severity, patron_visible| CreateUserMessage {
+        body: body.to_string(),
+        severity,
+        patron_visible,
+        expires_at: None,
+    };
+
+    let info = db
+        .repo
+        .user_messages_create(reader, &message("New card ready", UserMessageSeverity::Info, true), librarian)
+        .await
+        .unwrap();
+    let blocking = db
+        .repo
+        .user_messages_create(reader, &message("Unpaid damage", UserMessageSeverity::Blocking, false), librarian)
+        .await
+        .unwrap();
+    let expired = db
+        .repo
+        .user_messages_create(reader, &message("Old note", UserMessageSeverity::Blocking, false), librarian)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE user_messages SET expires_at = $1 WHERE id = $2")
+        .bind(Utc::now() - Duration::hours(1))
+        .bind(expired.id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    // Blocking messages come first; the expired one is no longer pending
+    let pending = db.repo.user_messages_list(reader, true, false).await.unwrap();
+    assert_eq!(pending.iter().map(|m| m.id).collect::<Vec<_>>(), vec![blocking.id, info.id]);
+    let patron = db.repo.user_messages_list(reader, true, true).await.unwrap();
+    assert_eq!(patron.iter().map(|m| m.id).collect::<Vec<_>>(), vec![info.id]);
+
+    let acked = db.repo.user_messages_acknowledge(reader, blocking.id, librarian).await.unwrap().unwrap();
+    assert_eq!(acked.acknowledged_by, Some(librarian));
+    assert!(db.repo.user_messages_acknowledge(reader, blocking.id, librarian).await.unwrap().is_none());
+    // Messages are scoped to their patron
+    assert!(db.repo.user_messages_get(librarian, info.id).await.is_err());
+
+    let pending = db.repo.user_messages_list(reader, true, false).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(db.repo.user_messages_list(reader, false, false).await.unwrap().len(), 3);
+}
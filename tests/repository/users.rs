@@ -0,0 +1,268 @@
+use chrono::{DateTime, Duration, Utc};
+use elidune_server::models::{
+    hold::{CreateHold, HoldPriority},
+    payment::{PaymentCategory, PaymentMethod, RecordPayment},
+    user::{UserActivityKind, UserContactMerge, UserExpiryCampaign, UserQuery, UserStatus},
+};
+use rust_decimal::Decimal;
+
+use crate::{
+    fixtures::{ItemBuilder, LoanBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+async fn hold(db: &TestDb, user_id: i64, item_id: i64) -> i64 {
+    db.repo
+        .holds_create(&CreateHold { user_id, item_id, notes: None, pickup_locker: false, priority: HoldPriority::Normal })
+        .await
+        .unwrap()
+        .id
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn merge_moves_history_and_archives_duplicate() {
+    let db = TestDb::new().await;
+    let survivor = UserBuilder::new("reader1").insert(&db.pool).await;
+    let duplicate = UserBuilder::new("reader1-bis").insert(&db.pool).await;
+    sqlx::query("UPDATE users SET barcode = 'C-0042' WHERE id = $1")
+        .bind(duplicate)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let borrowed = ItemBuilder::new("M-0001").insert(&db.pool).await;
+    let held = ItemBuilder::new("M-0002").insert(&db.pool).await;
+    let shared = ItemBuilder::new("M-0003").insert(&db.pool).await;
+    let loan_id = LoanBuilder::new(duplicate, borrowed).insert(&db.repo).await;
+    sqlx::query("INSERT INTO loans_archives (user_id, item_id, date, returned_at) VALUES ($1, $2, NOW(), NOW())")
+        .bind(duplicate)
+        .bind(held.item_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    let moved_hold = hold(&db, duplicate, held.item_id).await;
+    let kept_hold = hold(&db, survivor, shared.item_id).await;
+    let dropped_hold = hold(&db, duplicate, shared.item_id).await;
+
+    let contact = UserContactMerge { barcode: Some("C-0042".to_string()), ..Default::default() };
+
+    // Dry run: counts are reported, nothing changes
+    let preview = db.repo.users_merge(survivor, duplicate, &contact, true).await.unwrap();
+    assert_eq!((preview.loans, preview.archived_loans, preview.holds, preview.holds_dropped), (1, 1, 1, 1));
+    assert_eq!(db.repo.loans_get_by_id(loan_id).await.unwrap().user_id, duplicate);
+    assert_eq!(db.repo.users_get_by_id(duplicate).await.unwrap().barcode.as_deref(), Some("C-0042"));
+
+    let moved = db.repo.users_merge(survivor, duplicate, &contact, false).await.unwrap();
+    assert_eq!((moved.loans, moved.archived_loans, moved.holds, moved.holds_dropped), (1, 1, 1, 1));
+    assert_eq!(db.repo.loans_get_by_id(loan_id).await.unwrap().user_id, survivor);
+    assert_eq!(db.repo.holds_get_by_id(moved_hold).await.unwrap().user_id, survivor);
+    assert_eq!(db.repo.holds_get_by_id(kept_hold).await.unwrap().user_id, survivor);
+    assert!(db.repo.holds_get_by_id(dropped_hold).await.is_err());
+    let archived: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM loans_archives WHERE user_id = $1")
+        .bind(survivor)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(archived, 1);
+
+    // The duplicate's card moved to the survivor
+    assert_eq!(db.repo.users_get_by_id(survivor).await.unwrap().barcode.as_deref(), Some("C-0042"));
+    let gone = db.repo.users_get_by_id(duplicate).await.unwrap();
+    assert_eq!(gone.status, Some(UserStatus::Deleted));
+    assert_eq!(gone.merged_into, Some(survivor));
+    assert!(gone.login.is_none() && gone.barcode.is_none());
+
+    // An archived account cannot be merged again
+    assert!(db.repo.users_merge(survivor, duplicate, &contact, false).await.is_err());
+}
+
+#[tokio::test]
+#[ignore]
+async fn name_search_ignores_case_and_accents() {
+    let db = TestDb::new().await;
+    let user = UserBuilder::new("reader-accents").insert(&db.pool).await;
+    sqlx::query("UPDATE users SET firstname = 'Hélène', lastname = 'Lefèvre' WHERE id = $1")
+        .bind(user)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    for name in ["helene", "LEFEVRE", "Lefèv"] {
+        let query = UserQuery { name: Some(name.to_string()), barcode: None, page: None, per_page: None };
+        let (users, total) = db.repo.users_search(&query).await.unwrap();
+        assert_eq!(total, 1, "{}", name);
+        assert_eq!(users[0].id, user);
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn activity_merges_circulation_payments_and_audit() {
+    let db = TestDb::new().await;
+    let clerk = UserBuilder::new("clerk-activity").account_type("librarian").insert(&db.pool).await;
+    let reader = UserBuilder::new("reader-activity").insert(&db.pool).await;
+    let returned = ItemBuilder::new("A-0001").insert(&db.pool).await;
+    let held = ItemBuilder::new("A-0002").insert(&db.pool).await;
+
+    let loan_id = LoanBuilder::new(reader, returned).insert(&db.repo).await;
+    db.repo.loans_return(loan_id).await.unwrap();
+    hold(&db, reader, held.item_id).await;
+    let payment = RecordPayment {
+        user_id: Some(reader),
+        amount: None,
+        method: PaymentMethod::Cash,
+        category: PaymentCategory::Printing,
+        fine_id: None,
+        notes: None,
+    };
+    db.repo.payments_create(&payment, Decimal::new(150, 2), None, clerk, 2026).await.unwrap();
+    for (event, outcome) in [
+        ("email.overdue_reminder_sent", "failure"),
+        ("user.updated", "success"),
+        ("user.impersonated_request", "success"),
+    ] {
+        db.repo
+            .audit_insert(event, Some(clerk), Some("user"), Some(reader), None, None, outcome, None, None, None)
+            .await
+            .unwrap();
+    }
+
+    let (entries, total) = db.repo.users_activity(reader, None, 1, 50).await.unwrap();
+    assert_eq!(total, 6);
+    let mut events: Vec<&str> = entries.iter().map(|e| e.event.as_str()).collect();
+    events.sort();
+    assert_eq!(
+        events,
+        [
+            "email.overdue_reminder_sent",
+            "hold.created",
+            "loan.created",
+            "loan.returned",
+            "payment.recorded",
+            "user.updated"
+        ]
+    );
+    assert!(entries.windows(2).all(|w| w[0].occurred_at >= w[1].occurred_at));
+    let notification = entries.iter().find(|e| e.kind == UserActivityKind::Notification).unwrap();
+    assert!(notification.failed);
+    assert_eq!(notification.actor_id, Some(clerk));
+
+    let (payments, total) = db.repo.users_activity(reader, Some(UserActivityKind::Payment), 1, 50).await.unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(payments[0].amount, Some(Decimal::new(150, 2)));
+
+    let (page, total) = db.repo.users_activity(reader, None, 2, 4).await.unwrap();
+    assert_eq!((page.len(), total), (2, 6));
+}
+
+#[tokio::test]
+#[ignore]
+async fn two_factor_failures_lock_with_back_off() {
+    let db = TestDb::new().await;
+    let user = UserBuilder::new("reader-2fa").insert(&db.pool).await;
+
+    for expected in 1..5 {
+        let (failures, locked_until) = db.repo.users_record_two_factor_failure(user, 5, 1, 1440).await.unwrap();
+        assert_eq!((failures, locked_until), (expected, None));
+    }
+    let (failures, first_lock) = db.repo.users_record_two_factor_failure(user, 5, 1, 1440).await.unwrap();
+    assert_eq!(failures, 5);
+    let first_lock = first_lock.unwrap();
+    assert!(first_lock > Utc::now() && first_lock <= Utc::now() + Duration::minutes(1));
+
+    // Each further failure doubles the lock, up to the maximum
+    let (_, second_lock) = db.repo.users_record_two_factor_failure(user, 5, 1, 1440).await.unwrap();
+    assert!(second_lock.unwrap() > Utc::now() + Duration::minutes(1));
+    for _ in 0..20 {
+        db.repo.users_record_two_factor_failure(user, 5, 1, 1440).await.unwrap();
+    }
+    let locked_until = db.repo.users_two_factor_locked_until(user).await.unwrap().unwrap();
+    assert!(locked_until <= Utc::now() + Duration::minutes(1440));
+
+    db.repo.users_reset_two_factor_failures(user).await.unwrap();
+    assert_eq!(db.repo.users_two_factor_locked_until(user).await.unwrap(), None);
+    let (failures, locked_until) = db.repo.users_record_two_factor_failure(user, 5, 1, 1440).await.unwrap();
+    assert_eq!((failures, locked_until), (1, None));
+}
+
+#[tokio::test]
+#[ignore]
+async fn replacing_recovery_codes_forgets_used_ones() {
+    let db = TestDb::new().await;
+    let user = UserBuilder::new("reader-recovery").insert(&db.pool).await;
+    sqlx::query("UPDATE users SET recovery_codes = '[\"old\"]', recovery_codes_used = '[\"old\"]' WHERE id = $1")
+        .bind(user)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    db.repo.users_replace_recovery_codes(user, "[\"new\"]").await.unwrap();
+    let (codes, used): (Option<String>, Option<String>) =
+        sqlx::query_as("SELECT recovery_codes, recovery_codes_used FROM users WHERE id = $1")
+            .bind(user)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+    assert_eq!(codes.as_deref(), Some("[\"new\"]"));
+    assert_eq!(used, None);
+}
+
+#[tokio::test]
+#[ignore]
+async fn expiry_campaign_targets_match_every_filter() {
+    let db = TestDb::new().await;
+    let june = Utc::now() + Duration::days(30);
+    let group = UserBuilder::new("class-6b").account_type("group").insert(&db.pool).await;
+    let member = UserBuilder::new("pupil-expiring").insert(&db.pool).await;
+    let unlimited = UserBuilder::new("pupil-unlimited").insert(&db.pool).await;
+    let later = UserBuilder::new("pupil-later").insert(&db.pool).await;
+    let other_group = UserBuilder::new("reader-no-group").insert(&db.pool).await;
+    let deleted = UserBuilder::new("pupil-deleted").insert(&db.pool).await;
+    for (user, group_id, expiry) in [
+        (member, Some(group), Some(june)),
+        (unlimited, Some(group), None),
+        (later, Some(group), Some(june + Duration::days(365))),
+        (other_group, None, Some(june)),
+        (deleted, Some(group), Some(june)),
+    ] {
+        sqlx::query("UPDATE users SET group_id = $1, expiry_at = $2 WHERE id = $3")
+            .bind(group_id)
+            .bind(expiry)
+            .bind(user)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+    }
+    sqlx::query("UPDATE users SET status = 'deleted' WHERE id = $1").bind(deleted).execute(&db.pool).await.unwrap();
+
+    let mut campaign = UserExpiryCampaign {
+        group_id: Some(group),
+        account_type: None,
+        public_type: None,
+        expires_from: None,
+        expires_to: Some(june + Duration::days(1)),
+        include_without_expiry: false,
+        expiry_at: june + Duration::days(365),
+        send_invitations: false,
+        renewal_url: None,
+        dry_run: false,
+    };
+    let ids = |targets: Vec<elidune_server::repository::users::UserExpiryTarget>| targets.iter().map(|t| t.id).collect::<Vec<_>>();
+    assert_eq!(ids(db.repo.users_expiry_campaign_targets(&campaign).await.unwrap()), vec![member]);
+    campaign.include_without_expiry = true;
+    let mut targets = ids(db.repo.users_expiry_campaign_targets(&campaign).await.unwrap());
+    targets.sort();
+    assert_eq!(targets, vec![member, unlimited]);
+
+    assert_eq!(db.repo.users_set_expiry(&targets, campaign.expiry_at).await.unwrap(), 2);
+    let expiry: Option<DateTime<Utc>> = sqlx::query_scalar("SELECT expiry_at FROM users WHERE id = $1")
+        .bind(unlimited)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(expiry.map(|e| e.timestamp()), Some(campaign.expiry_at.timestamp()));
+    // Both now end after the window
+    campaign.include_without_expiry = false;
+    assert!(db.repo.users_expiry_campaign_targets(&campaign).await.unwrap().is_empty());
+}
@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+
+use chrono::{Duration, Utc};
+use elidune_server::models::warehouse::{
+    CreateWarehouseTarget, UpdateWarehouseTarget, WarehouseFormat, WarehouseRunStatus, WarehouseTrigger,
+};
+
+use crate::{
+    fixtures::{ItemBuilder, LoanBuilder, UserBuilder},
+    harness::TestDb,
+};
+
+fn target(name: &str) -> CreateWarehouseTarget {
+    CreateWarehouseTarget {
+        name: name.to_string(),
+        url: "s3://warehouse/elidune/".to_string(),
+        username: Some("AKIDEXAMPLE".to_string()),
+        password: Some("secret".to_string()),
+        host_key_fingerprint: None,
+        region: None,
+        endpoint: None,
+        format: None,
+        facts: None,
+        schedule_time: None,
+        is_active: None,
+    }
+}
+
+#[tokio::test]
+#[ignore] // Run with: cargo test --test repository -- --ignored
+async fn run_claim_and_watermarks() {
+    let db = TestDb::new().await;
+    let created = db.repo.warehouse_targets_create(&target("Data lake")).await.unwrap();
+    assert!(created.has_password);
+    assert_eq!(created.format, WarehouseFormat::Parquet);
+    assert_eq!(created.facts, ["loans", "acquisitions", "visits"]);
+    assert_eq!(created.region, "us-east-1");
+    assert!(created.watermarks.is_empty());
+
+    let run = db
+        .repo
+        .warehouse_runs_start(created.id, created.last_run_at, WarehouseTrigger::Manual, None)
+        .await
+        .unwrap()
+        .expect("first claim");
+    assert_eq!(run.status, WarehouseRunStatus::Running);
+    assert!(run.manifest.is_none());
+    assert!(db
+        .repo
+        .warehouse_runs_start(created.id, created.last_run_at, WarehouseTrigger::Schedule, None)
+        .await
+        .unwrap()
+        .is_none());
+
+    let mut finished = run.clone();
+    finished.status = WarehouseRunStatus::Failed;
+    finished.error = Some("S3 upload failed".to_string());
+    let finished = db.repo.warehouse_runs_finish(&finished).await.unwrap();
+    assert!(finished.finished_at.is_some() && finished.manifest.is_none());
+
+    // Watermarks are merged per fact
+    let until = Utc::now();
+    let mut marks = BTreeMap::new();
+    marks.insert("loans".to_string(), until);
+    db.repo.warehouse_targets_set_watermarks(created.id, &marks).await.unwrap();
+    let mut marks = BTreeMap::new();
+    marks.insert("visits".to_string(), until);
+    db.repo.warehouse_targets_set_watermarks(created.id, &marks).await.unwrap();
+    let stored = db.repo.warehouse_targets_get(created.id).await.unwrap();
+    assert_eq!(stored.watermarks.len(), 2);
+
+    let update = UpdateWarehouseTarget {
+        name: None,
+        url: None,
+        username: None,
+        password: None,
+        host_key_fingerprint: None,
+        region: Some("eu-west-3".to_string()),
+        endpoint: Some(String::new()),
+        format: Some(WarehouseFormat::Csv),
+        facts: None,
+        schedule_time: None,
+        is_active: None,
+        reset_watermarks: true,
+    };
+    let updated = db.repo.warehouse_targets_update(created.id, &update).await.unwrap();
+    assert!(updated.watermarks.is_empty());
+    assert_eq!(updated.region, "eu-west-3");
+    assert_eq!(updated.format, WarehouseFormat::Csv);
+    assert!(updated.has_password);
+}
+
+#[tokio::test]
+#[ignore]
+async fn loan_facts_are_anonymized_and_windowed() {
+    let db = TestDb::new().await;
+    let user = UserBuilder::new("wh-reader").insert(&db.pool).await;
+    let item = ItemBuilder::new("WH-0001").insert(&db.pool).await;
+    let loan_id = LoanBuilder::new(user, item).insert(&db.repo).await;
+    let until = Utc::now();
+
+    let facts = db.repo.warehouse_loan_facts(None, until, None, 100).await.unwrap();
+    let fact = facts.iter().find(|f| f.loan_key == format!("loan-{}", loan_id)).expect("loan fact");
+    assert_eq!(fact.item_id, Some(item.item_id));
+    assert_eq!(fact.borrower_account_type.as_deref(), Some("reader"));
+    assert!(fact.returned_at.is_none());
+
+    // Already sent: nothing after the watermark
+    let after = db.repo.warehouse_loan_facts(Some(until), until, None, 100).await.unwrap();
+    assert!(after.is_empty());
+
+    // Returned: the archived row is the new version
+    db.repo.loans_return(loan_id).await.unwrap();
+    let later = Utc::now() + Duration::seconds(5);
+    let facts = db.repo.warehouse_loan_facts(Some(until), later, None, 100).await.unwrap();
+    assert_eq!(facts.len(), 1);
+    assert!(facts[0].loan_key.starts_with("archive-") && facts[0].returned_at.is_some());
+
+    // Keyset pagination resumes after the cursor
+    let cursor = Some((facts[0].changed_at, facts[0].loan_key.clone()));
+    assert!(db.repo.warehouse_loan_facts(Some(until), later, cursor, 100).await.unwrap().is_empty());
+
+    let acquisitions = db.repo.warehouse_acquisition_facts(None, later, None, 100).await.unwrap();
+    assert!(acquisitions.iter().any(|a| a.item_id == item.item_id));
+}